@@ -2,7 +2,8 @@
 // web playground.
 //
 // Exposes `compile_and_run(source) -> JSON` so the browser can drive
-// the language without going through a server. The playground is a
+// the language without going through a server, and `run_program(source)`
+// for other WASM hosts. The playground is a
 // "demo, not a full toolchain" surface — JIT, FFI, Z3, file I/O, and
 // the watcher are intentionally absent (none compile to WASM today,
 // and the playground value is the language semantics, not the
@@ -108,6 +109,38 @@ struct InnerResult {
     exit_code: i32,
 }
 
+/// Result of [`run_program`]: `resilient::RunResult` as JSON.
+#[derive(Serialize, Debug, PartialEq)]
+struct ProgramResult {
+    /// `true` iff the program parsed and ran without error.
+    ok: bool,
+    /// Everything the program printed, including output before a
+    /// runtime error.
+    stdout: String,
+    /// Parse errors, or the runtime error that stopped the run, one
+    /// `line:col: message` string each.
+    errors: Vec<String>,
+}
+
+/// Run a Resilient program and return `{ ok, stdout, errors }`.
+///
+/// The embedding entry point for hosts other than the playground page
+/// (sandboxes, notebooks, other sites): no timing, no exit-code
+/// mapping, just the library's own result.
+#[wasm_bindgen]
+pub fn run_program(source: &str) -> JsValue {
+    serde_wasm_bindgen::to_value(&run_program_inner(source)).unwrap_or(JsValue::NULL)
+}
+
+fn run_program_inner(source: &str) -> ProgramResult {
+    let result = resilient::run_program(source);
+    ProgramResult {
+        ok: result.ok,
+        stdout: result.stdout,
+        errors: result.errors,
+    }
+}
+
 /// Library version string (e.g. `"0.1.0-tree-walker"`) so the page
 /// banner can pin a build to a known version when filing bugs.
 #[wasm_bindgen]
@@ -195,6 +228,24 @@ mod tests {
         assert!(r.stderr.is_some());
     }
 
+    #[test]
+    fn run_program_mirrors_the_library_result() {
+        let ok = run_program_inner("println(6 * 7);");
+        assert_eq!(
+            ok,
+            ProgramResult {
+                ok: true,
+                stdout: "42\n".to_owned(),
+                errors: Vec::new(),
+            }
+        );
+
+        let failed = run_program_inner("println(1);\nlet x = 1 / 0;");
+        assert!(!failed.ok);
+        assert_eq!(failed.stdout, "1\n");
+        assert_eq!(failed.errors.len(), 1);
+    }
+
     #[test]
     fn version_string_is_no_longer_stub() {
         let v = playground_version();
@@ -0,0 +1,108 @@
+//! Derive macros for `resilient::bridge`.
+//!
+//! `#[derive(IntoResilient)]` turns a Rust struct with named fields
+//! into a Resilient struct value of the same name, field by field;
+//! `#[derive(FromResilient)]` reads one back, failing with a
+//! `BridgeError` that names the offending field when the name, a
+//! field or a field's type doesn't match. `#[resilient(name = "...")]`
+//! on the struct sets the Resilient struct name when it differs from
+//! the Rust one. The generated code refers to `::resilient::bridge`,
+//! so these are used through the `resilient` crate's re-exports.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident, LitStr, parse_macro_input};
+
+#[proc_macro_derive(IntoResilient, attributes(resilient))]
+pub fn derive_into_resilient(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, into_resilient).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+#[proc_macro_derive(FromResilient, attributes(resilient))]
+pub fn derive_from_resilient(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, from_resilient).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+fn expand(
+    input: &DeriveInput,
+    body: fn(&DeriveInput, &str, &[&Ident]) -> TokenStream2,
+) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "only structs with named fields convert to and from Resilient values",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "only structs with named fields convert to and from Resilient values",
+        ));
+    };
+    let fields: Vec<&Ident> = named
+        .named
+        .iter()
+        .filter_map(|f| f.ident.as_ref())
+        .collect();
+    let name = struct_name(input)?;
+    Ok(body(input, &name, &fields).into())
+}
+
+/// The Resilient struct name: `#[resilient(name = "...")]`, or the
+/// Rust one.
+fn struct_name(input: &DeriveInput) -> syn::Result<String> {
+    let mut name = input.ident.to_string();
+    for attr in &input.attrs {
+        if !attr.path().is_ident("resilient") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("expected `name = \"...\"`"))
+            }
+        })?;
+    }
+    Ok(name)
+}
+
+fn into_resilient(input: &DeriveInput, name: &str, fields: &[&Ident]) -> TokenStream2 {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let keys = fields.iter().map(|f| f.to_string());
+    quote! {
+        impl #impl_generics ::resilient::bridge::IntoResilient for #ident #ty_generics #where_clause {
+            fn into_resilient(self) -> ::resilient::bridge::ResilientValue {
+                ::resilient::bridge::ResilientValue::new_struct(
+                    #name,
+                    ::std::vec![
+                        #((#keys, ::resilient::bridge::IntoResilient::into_resilient(self.#fields)),)*
+                    ],
+                )
+            }
+        }
+    }
+}
+
+fn from_resilient(input: &DeriveInput, name: &str, fields: &[&Ident]) -> TokenStream2 {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let keys = fields.iter().map(|f| f.to_string());
+    quote! {
+        impl #impl_generics ::resilient::bridge::FromResilient for #ident #ty_generics #where_clause {
+            fn from_resilient(
+                value: ::resilient::bridge::ResilientValue,
+            ) -> ::std::result::Result<Self, ::resilient::bridge::BridgeError> {
+                let mut __fields = value.into_struct_fields(#name)?;
+                ::std::result::Result::Ok(Self {
+                    #(#fields: __fields.take(#keys)?,)*
+                })
+            }
+        }
+    }
+}
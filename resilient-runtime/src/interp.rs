@@ -0,0 +1,772 @@
+//! `#![no_std]` tree-walking interpreter core (`interp` feature).
+//!
+//! [`crate::vm`] runs the scalar bytecode subset with no heap at all.
+//! This module is its allocator-backed counterpart: it evaluates a
+//! [`Program`] tree directly, with as many variables and as deep a
+//! program as the heap allows, using nothing beyond `core` and
+//! `alloc`. Scopes are `BTreeMap`s, where the host interpreter uses
+//! `HashMap`s, which need `std`'s random state. The binary supplies
+//! the `#[global_allocator]`; adding the `alloc` feature also brings
+//! string values, as it does for [`crate::Value`].
+//!
+//! Everything the host interpreter asks the OS for goes through a
+//! [`Host`] the firmware supplies: where `print` output lands, the
+//! clock behind `clock_ms()`, and the entropy behind `random_int` /
+//! `random_float`.
+//!
+//! ```
+//! use resilient_runtime::Value;
+//! use resilient_runtime::interp::{BinaryOp, Expr, Host, Interpreter, Program, Stmt};
+//!
+//! #[derive(Default)]
+//! struct Capture(String);
+//!
+//! impl Host for Capture {
+//!     fn print(&mut self, s: &str) {
+//!         self.0.push_str(s);
+//!     }
+//!     fn clock_ms(&mut self) -> u64 {
+//!         0
+//!     }
+//!     fn random_u64(&mut self) -> u64 {
+//!         4
+//!     }
+//! }
+//!
+//! // println(1 + 2);
+//! let sum = Expr::Binary(
+//!     BinaryOp::Add,
+//!     Box::new(Expr::Const(Value::Int(1))),
+//!     Box::new(Expr::Const(Value::Int(2))),
+//! );
+//! let program = Program {
+//!     functions: vec![],
+//!     body: vec![Stmt::Expr(Expr::Call("println".into(), vec![sum]))],
+//! };
+//! let mut interp = Interpreter::new(Capture::default());
+//! interp.run(&program).unwrap();
+//! assert_eq!(interp.host().0, "3\n");
+//! ```
+//!
+//! The host crate lowers parsed source onto this tree (see
+//! `resilient/src/interp_lower.rs`), so the covered subset is the
+//! one that lowering accepts: `let`, assignment, `if`, `while`,
+//! `return`, top-level functions, and scalar expressions.
+//! Semantics follow the host interpreter: integer arithmetic wraps,
+//! `+` with a string operand concatenates, functions see and update
+//! top-level bindings, and conditions must be `Bool`.
+//!
+//! # No panics
+//!
+//! Every failure is an [`InterpError`]. Calls nest at most
+//! [`MAX_CALL_DEPTH`] deep, so runaway recursion is an error rather
+//! than a blown stack.
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write as _};
+
+use crate::{RuntimeError, Value};
+
+/// How deep calls may nest before [`InterpError::CallDepth`].
+pub const MAX_CALL_DEPTH: usize = 64;
+
+/// The hooks through which a program reaches its surroundings.
+pub trait Host {
+    /// Emit `s`, which `println` ends with `'\n'`.
+    fn print(&mut self, s: &str);
+    /// Milliseconds since an arbitrary epoch. Must not decrease.
+    fn clock_ms(&mut self) -> u64;
+    /// A fresh 64-bit random sample.
+    fn random_u64(&mut self) -> u64;
+}
+
+/// An expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Const(Value),
+    Var(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// A call to a [`Function`] or a builtin, by name.
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+/// `And` and `Or` short-circuit; the rest evaluate both sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+/// A statement. Blocks are plain `Vec<Stmt>`s and open a scope.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Let(String, Expr),
+    Assign(String, Expr),
+    Expr(Expr),
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+    While(Expr, Vec<Stmt>),
+    Return(Option<Expr>),
+}
+
+/// A top-level function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Vec<Stmt>,
+}
+
+/// Functions plus the top-level statements run in order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Program {
+    pub functions: Vec<Function>,
+    pub body: Vec<Stmt>,
+}
+
+/// Why a run stopped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InterpError {
+    /// An operator was applied to the wrong kinds of value, or an
+    /// integer was divided by zero.
+    Runtime(RuntimeError),
+    /// A variable was read or assigned before any `let`.
+    Unbound(String),
+    /// A call named neither a function nor a builtin.
+    UnknownFunction(String),
+    Arity {
+        function: String,
+        expected: usize,
+        got: usize,
+    },
+    /// A function that returned nothing was used as a value.
+    NoValue(String),
+    /// Calls nested deeper than [`MAX_CALL_DEPTH`].
+    CallDepth,
+}
+
+impl From<RuntimeError> for InterpError {
+    fn from(e: RuntimeError) -> Self {
+        InterpError::Runtime(e)
+    }
+}
+
+impl fmt::Display for InterpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpError::Runtime(RuntimeError::TypeMismatch(op)) => {
+                write!(f, "type mismatch in {}", op)
+            }
+            InterpError::Runtime(RuntimeError::DivideByZero) => write!(f, "division by zero"),
+            InterpError::Unbound(name) => write!(f, "unbound variable `{}`", name),
+            InterpError::UnknownFunction(name) => write!(f, "unknown function `{}`", name),
+            InterpError::Arity {
+                function,
+                expected,
+                got,
+            } => write!(
+                f,
+                "`{}` expects {} argument(s), got {}",
+                function, expected, got
+            ),
+            InterpError::NoValue(name) => write!(f, "`{}` returned no value", name),
+            InterpError::CallDepth => {
+                write!(f, "calls nested deeper than {}", MAX_CALL_DEPTH)
+            }
+        }
+    }
+}
+
+/// Variable bindings: the top-level scope, then one scope per
+/// enclosing block of the running call, innermost last.
+#[derive(Debug, Default)]
+pub struct Env {
+    globals: BTreeMap<String, Value>,
+    scopes: Vec<BTreeMap<String, Value>>,
+}
+
+impl Env {
+    pub fn new() -> Env {
+        Env::default()
+    }
+
+    /// The value `name` is bound to, innermost scope first.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .or_else(|| self.globals.get(name))
+    }
+
+    /// Bind `name` in the innermost scope, shadowing any outer one.
+    pub fn define(&mut self, name: String, value: Value) {
+        match self.scopes.last_mut() {
+            Some(scope) => scope.insert(name, value),
+            None => self.globals.insert(name, value),
+        };
+    }
+
+    /// Rebind the innermost existing `name`. False if it is unbound.
+    pub fn assign(&mut self, name: &str, value: Value) -> bool {
+        let slot = match self.scopes.iter_mut().rev().find_map(|s| s.get_mut(name)) {
+            Some(slot) => slot,
+            None => match self.globals.get_mut(name) {
+                Some(slot) => slot,
+                None => return false,
+            },
+        };
+        *slot = value;
+        true
+    }
+}
+
+/// Whether a statement list ran to its end or hit `return`.
+enum Flow {
+    Next,
+    Return(Option<Value>),
+}
+
+/// Runs [`Program`]s against a [`Host`]. Top-level bindings persist
+/// across [`Interpreter::run`] calls.
+pub struct Interpreter<H: Host> {
+    host: H,
+    env: Env,
+    depth: usize,
+}
+
+impl<H: Host> Interpreter<H> {
+    pub fn new(host: H) -> Interpreter<H> {
+        Interpreter {
+            host,
+            env: Env::new(),
+            depth: 0,
+        }
+    }
+
+    pub fn host(&self) -> &H {
+        &self.host
+    }
+
+    pub fn host_mut(&mut self) -> &mut H {
+        &mut self.host
+    }
+
+    pub fn into_host(self) -> H {
+        self.host
+    }
+
+    /// The bindings left by the runs so far.
+    pub fn env(&self) -> &Env {
+        &self.env
+    }
+
+    /// Run `program`'s top-level statements. A top-level `return`
+    /// ends the run early.
+    pub fn run(&mut self, program: &Program) -> Result<(), InterpError> {
+        let functions: BTreeMap<&str, &Function> = program
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f))
+            .collect();
+        for stmt in &program.body {
+            if let Flow::Return(_) = self.exec(stmt, &functions)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn exec_block(
+        &mut self,
+        stmts: &[Stmt],
+        functions: &BTreeMap<&str, &Function>,
+    ) -> Result<Flow, InterpError> {
+        self.env.scopes.push(BTreeMap::new());
+        let mut flow = Ok(Flow::Next);
+        for stmt in stmts {
+            flow = self.exec(stmt, functions);
+            if !matches!(flow, Ok(Flow::Next)) {
+                break;
+            }
+        }
+        self.env.scopes.pop();
+        flow
+    }
+
+    fn exec(
+        &mut self,
+        stmt: &Stmt,
+        functions: &BTreeMap<&str, &Function>,
+    ) -> Result<Flow, InterpError> {
+        match stmt {
+            Stmt::Let(name, value) => {
+                let value = self.eval(value, functions)?;
+                self.env.define(name.clone(), value);
+            }
+            Stmt::Assign(name, value) => {
+                let value = self.eval(value, functions)?;
+                if !self.env.assign(name, value) {
+                    return Err(InterpError::Unbound(name.clone()));
+                }
+            }
+            Stmt::Expr(Expr::Call(name, args)) => {
+                self.call(name, args, functions)?;
+            }
+            Stmt::Expr(expr) => {
+                self.eval(expr, functions)?;
+            }
+            Stmt::If(condition, then, otherwise) => {
+                let branch = if self.condition(condition, functions)? {
+                    then
+                } else {
+                    otherwise
+                };
+                return self.exec_block(branch, functions);
+            }
+            Stmt::While(condition, body) => {
+                while self.condition(condition, functions)? {
+                    if let Flow::Return(value) = self.exec_block(body, functions)? {
+                        return Ok(Flow::Return(value));
+                    }
+                }
+            }
+            Stmt::Return(value) => {
+                let value = match value {
+                    Some(expr) => Some(self.eval(expr, functions)?),
+                    None => None,
+                };
+                return Ok(Flow::Return(value));
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn condition(
+        &mut self,
+        expr: &Expr,
+        functions: &BTreeMap<&str, &Function>,
+    ) -> Result<bool, InterpError> {
+        match self.eval(expr, functions)? {
+            Value::Bool(b) => Ok(b),
+            _ => Err(RuntimeError::TypeMismatch("condition").into()),
+        }
+    }
+
+    fn eval(
+        &mut self,
+        expr: &Expr,
+        functions: &BTreeMap<&str, &Function>,
+    ) -> Result<Value, InterpError> {
+        match expr {
+            Expr::Const(value) => Ok(value.clone()),
+            Expr::Var(name) => self
+                .env
+                .get(name)
+                .cloned()
+                .ok_or_else(|| InterpError::Unbound(name.clone())),
+            Expr::Unary(op, operand) => match (op, self.eval(operand, functions)?) {
+                (UnaryOp::Neg, Value::Int(n)) => Ok(Value::Int(n.wrapping_neg())),
+                (UnaryOp::Neg, Value::Float(x)) => Ok(Value::Float(-x)),
+                (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+                (UnaryOp::Neg, _) => Err(RuntimeError::TypeMismatch("neg").into()),
+                (UnaryOp::Not, _) => Err(RuntimeError::TypeMismatch("not").into()),
+            },
+            Expr::Binary(BinaryOp::And, lhs, rhs) => Ok(Value::Bool(
+                self.condition(lhs, functions)? && self.condition(rhs, functions)?,
+            )),
+            Expr::Binary(BinaryOp::Or, lhs, rhs) => Ok(Value::Bool(
+                self.condition(lhs, functions)? || self.condition(rhs, functions)?,
+            )),
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = self.eval(lhs, functions)?;
+                let rhs = self.eval(rhs, functions)?;
+                binary(*op, lhs, rhs)
+            }
+            Expr::Call(name, args) => self
+                .call(name, args, functions)?
+                .ok_or_else(|| InterpError::NoValue(name.clone())),
+        }
+    }
+
+    fn call(
+        &mut self,
+        name: &str,
+        args: &[Expr],
+        functions: &BTreeMap<&str, &Function>,
+    ) -> Result<Option<Value>, InterpError> {
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args {
+            values.push(self.eval(arg, functions)?);
+        }
+        let Some(function) = functions.get(name) else {
+            return self.builtin(name, values);
+        };
+        if values.len() != function.params.len() {
+            return Err(InterpError::Arity {
+                function: function.name.clone(),
+                expected: function.params.len(),
+                got: values.len(),
+            });
+        }
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(InterpError::CallDepth);
+        }
+        // The callee sees the top-level scope but not the caller's
+        // locals.
+        let caller_scopes = core::mem::take(&mut self.env.scopes);
+        self.env.scopes.push(
+            function
+                .params
+                .iter()
+                .cloned()
+                .zip(values)
+                .collect::<BTreeMap<_, _>>(),
+        );
+        self.depth += 1;
+        let flow = self.exec_block(&function.body, functions);
+        self.depth -= 1;
+        self.env.scopes = caller_scopes;
+        match flow? {
+            Flow::Return(value) => Ok(value),
+            Flow::Next => Ok(None),
+        }
+    }
+
+    fn builtin(&mut self, name: &str, args: Vec<Value>) -> Result<Option<Value>, InterpError> {
+        match (name, args.as_slice()) {
+            ("print" | "println", [value]) => {
+                let mut text = String::new();
+                push_value(&mut text, value);
+                if name == "println" {
+                    text.push('\n');
+                }
+                self.host.print(&text);
+                Ok(None)
+            }
+            ("clock_ms", []) => {
+                let ms = self.host.clock_ms();
+                Ok(Some(Value::Int(i64::try_from(ms).unwrap_or(i64::MAX))))
+            }
+            ("random_int", [Value::Int(lo), Value::Int(hi)]) if hi > lo => {
+                let span = hi.wrapping_sub(*lo) as u64;
+                let r = self.host.random_u64() % span;
+                Ok(Some(Value::Int(lo.wrapping_add(r as i64))))
+            }
+            ("random_int", [_, _]) => Err(RuntimeError::TypeMismatch("random_int").into()),
+            ("random_float", []) => {
+                // Top 53 bits over 2^53: uniform in [0, 1).
+                let mantissa = self.host.random_u64() >> 11;
+                Ok(Some(Value::Float(mantissa as f64 / (1u64 << 53) as f64)))
+            }
+            ("print" | "println" | "clock_ms" | "random_int" | "random_float", _) => {
+                Err(InterpError::Arity {
+                    function: String::from(name),
+                    expected: match name {
+                        "print" | "println" => 1,
+                        "random_int" => 2,
+                        _ => 0,
+                    },
+                    got: args.len(),
+                })
+            }
+            _ => Err(InterpError::UnknownFunction(String::from(name))),
+        }
+    }
+}
+
+fn binary(op: BinaryOp, lhs: Value, rhs: Value) -> Result<Value, InterpError> {
+    use core::cmp::Ordering;
+    let order = |op_name: &'static str| match (&lhs, &rhs) {
+        (Value::Int(a), Value::Int(b)) => Ok(a.cmp(b)),
+        (Value::Float(a), Value::Float(b)) => {
+            a.partial_cmp(b).ok_or(RuntimeError::TypeMismatch(op_name))
+        }
+        #[cfg(feature = "alloc")]
+        (Value::String(a), Value::String(b)) => Ok(a.cmp(b)),
+        _ => Err(RuntimeError::TypeMismatch(op_name)),
+    };
+    let value = match op {
+        // As in the host interpreter, `+` with a string operand
+        // concatenates the other's printed form.
+        #[cfg(feature = "alloc")]
+        BinaryOp::Add if matches!(lhs, Value::String(_)) != matches!(rhs, Value::String(_)) => {
+            let mut text = String::new();
+            push_value(&mut text, &lhs);
+            push_value(&mut text, &rhs);
+            Value::String(text)
+        }
+        BinaryOp::Add => lhs.add(rhs)?,
+        BinaryOp::Sub => lhs.sub(rhs)?,
+        BinaryOp::Mul => lhs.mul(rhs)?,
+        BinaryOp::Div => lhs.div(rhs)?,
+        BinaryOp::Rem => match (lhs, rhs) {
+            (Value::Int(_), Value::Int(0)) => return Err(RuntimeError::DivideByZero.into()),
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_rem(b)),
+            (Value::Float(a), Value::Float(b)) => Value::Float(a % b),
+            _ => return Err(RuntimeError::TypeMismatch("rem").into()),
+        },
+        BinaryOp::Eq => lhs.eq(rhs)?,
+        BinaryOp::Ne => match lhs.eq(rhs)? {
+            Value::Bool(b) => Value::Bool(!b),
+            other => other,
+        },
+        BinaryOp::Lt => Value::Bool(order("lt")? == Ordering::Less),
+        BinaryOp::Le => Value::Bool(order("le")? != Ordering::Greater),
+        BinaryOp::Gt => Value::Bool(order("gt")? == Ordering::Greater),
+        BinaryOp::Ge => Value::Bool(order("ge")? != Ordering::Less),
+        // Short-circuited in `eval`; only reached with both sides
+        // already evaluated.
+        BinaryOp::And | BinaryOp::Or => match (lhs, rhs) {
+            (Value::Bool(a), Value::Bool(b)) if op == BinaryOp::And => Value::Bool(a && b),
+            (Value::Bool(a), Value::Bool(b)) => Value::Bool(a || b),
+            _ => return Err(RuntimeError::TypeMismatch("logic").into()),
+        },
+    };
+    Ok(value)
+}
+
+/// Append `value` as `println` shows it.
+fn push_value(out: &mut String, value: &Value) {
+    // Writing into a `String` cannot fail.
+    let _ = match value {
+        Value::Int(n) => write!(out, "{}", n),
+        Value::Bool(b) => write!(out, "{}", b),
+        Value::Float(x) => write!(out, "{}", x),
+        #[cfg(feature = "alloc")]
+        Value::String(s) => out.write_str(s),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[derive(Default)]
+    struct TestHost {
+        out: String,
+        ms: u64,
+    }
+
+    impl Host for TestHost {
+        fn print(&mut self, s: &str) {
+            self.out.push_str(s);
+        }
+        fn clock_ms(&mut self) -> u64 {
+            self.ms += 5;
+            self.ms
+        }
+        fn random_u64(&mut self) -> u64 {
+            7
+        }
+    }
+
+    fn int(n: i64) -> Expr {
+        Expr::Const(Value::Int(n))
+    }
+
+    fn var(name: &str) -> Expr {
+        Expr::Var(name.to_string())
+    }
+
+    fn bin(op: BinaryOp, lhs: Expr, rhs: Expr) -> Expr {
+        Expr::Binary(op, Box::new(lhs), Box::new(rhs))
+    }
+
+    fn call(name: &str, args: Vec<Expr>) -> Expr {
+        Expr::Call(name.to_string(), args)
+    }
+
+    fn println(expr: Expr) -> Stmt {
+        Stmt::Expr(call("println", vec![expr]))
+    }
+
+    fn run(program: &Program) -> (Result<(), InterpError>, String) {
+        let mut interp = Interpreter::new(TestHost::default());
+        let result = interp.run(program);
+        (result, interp.into_host().out)
+    }
+
+    /// fn fact(n) { if n <= 1 { return 1; } return n * fact(n - 1); }
+    fn fact() -> Function {
+        Function {
+            name: "fact".to_string(),
+            params: vec!["n".to_string()],
+            body: vec![
+                Stmt::If(
+                    bin(BinaryOp::Le, var("n"), int(1)),
+                    vec![Stmt::Return(Some(int(1)))],
+                    vec![],
+                ),
+                Stmt::Return(Some(bin(
+                    BinaryOp::Mul,
+                    var("n"),
+                    call("fact", vec![bin(BinaryOp::Sub, var("n"), int(1))]),
+                ))),
+            ],
+        }
+    }
+
+    #[test]
+    fn recursion_and_loops_run() {
+        let program = Program {
+            functions: vec![fact()],
+            body: vec![
+                Stmt::Let("i".to_string(), int(0)),
+                Stmt::While(
+                    bin(BinaryOp::Lt, var("i"), int(3)),
+                    vec![Stmt::Assign(
+                        "i".to_string(),
+                        bin(BinaryOp::Add, var("i"), int(1)),
+                    )],
+                ),
+                println(call("fact", vec![int(5)])),
+                println(var("i")),
+            ],
+        };
+        let (result, out) = run(&program);
+        assert_eq!(result, Ok(()));
+        assert_eq!(out, "120\n3\n");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn plus_with_a_string_concatenates() {
+        let program = Program {
+            functions: vec![],
+            body: vec![println(bin(
+                BinaryOp::Add,
+                Expr::Const(Value::String("t=".to_string())),
+                Expr::Const(Value::Float(21.5)),
+            ))],
+        };
+        assert_eq!(run(&program).1, "t=21.5\n");
+    }
+
+    #[test]
+    fn functions_update_globals_but_not_caller_locals() {
+        // let g = 1; fn bump() { g = g + 1; } bump(); println(g);
+        // fn peek() { return x; } -- `x` is the caller's local.
+        let program = Program {
+            functions: vec![
+                Function {
+                    name: "bump".to_string(),
+                    params: vec![],
+                    body: vec![Stmt::Assign(
+                        "g".to_string(),
+                        bin(BinaryOp::Add, var("g"), int(1)),
+                    )],
+                },
+                Function {
+                    name: "peek".to_string(),
+                    params: vec![],
+                    body: vec![Stmt::Return(Some(var("x")))],
+                },
+            ],
+            body: vec![
+                Stmt::Let("g".to_string(), int(1)),
+                Stmt::Expr(call("bump", vec![])),
+                println(var("g")),
+                Stmt::If(
+                    Expr::Const(Value::Bool(true)),
+                    vec![
+                        Stmt::Let("x".to_string(), int(9)),
+                        println(call("peek", vec![])),
+                    ],
+                    vec![],
+                ),
+            ],
+        };
+        let (result, out) = run(&program);
+        assert_eq!(out, "2\n");
+        assert_eq!(result, Err(InterpError::Unbound("x".to_string())));
+    }
+
+    #[test]
+    fn clock_and_random_go_through_the_host() {
+        let program = Program {
+            functions: vec![],
+            body: vec![
+                Stmt::Let("t0".to_string(), call("clock_ms", vec![])),
+                println(bin(BinaryOp::Sub, call("clock_ms", vec![]), var("t0"))),
+                println(call("random_int", vec![int(10), int(13)])),
+                println(call("random_float", vec![])),
+            ],
+        };
+        let (result, out) = run(&program);
+        assert_eq!(result, Ok(()));
+        assert_eq!(out, "5\n11\n0\n");
+    }
+
+    #[test]
+    fn failures_are_typed_errors() {
+        let cases = [
+            (
+                println(bin(BinaryOp::Div, int(1), int(0))),
+                InterpError::Runtime(RuntimeError::DivideByZero),
+            ),
+            (
+                println(bin(BinaryOp::Add, int(1), Expr::Const(Value::Bool(true)))),
+                InterpError::Runtime(RuntimeError::TypeMismatch("add")),
+            ),
+            (
+                Stmt::If(int(1), vec![], vec![]),
+                InterpError::Runtime(RuntimeError::TypeMismatch("condition")),
+            ),
+            (
+                Stmt::Assign("nope".to_string(), int(1)),
+                InterpError::Unbound("nope".to_string()),
+            ),
+            (
+                println(call("missing", vec![])),
+                InterpError::UnknownFunction("missing".to_string()),
+            ),
+            (
+                println(call("println", vec![int(1)])),
+                InterpError::NoValue("println".to_string()),
+            ),
+        ];
+        for (stmt, expected) in cases {
+            let program = Program {
+                functions: vec![],
+                body: vec![stmt],
+            };
+            assert_eq!(run(&program).0, Err(expected));
+        }
+    }
+
+    #[test]
+    fn runaway_recursion_stops_at_the_depth_limit() {
+        let program = Program {
+            functions: vec![Function {
+                name: "down".to_string(),
+                params: vec![],
+                body: vec![Stmt::Return(Some(call("down", vec![])))],
+            }],
+            body: vec![Stmt::Expr(call("down", vec![]))],
+        };
+        assert_eq!(run(&program).0, Err(InterpError::CallDepth));
+    }
+}
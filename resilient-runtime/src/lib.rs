@@ -35,6 +35,12 @@ compile_error!(
      `static-only` asserts no-heap posture. Both set = ambiguous build intent."
 );
 
+// `interp` allocates its environments, which `static-only` forbids.
+#[cfg(all(feature = "interp", feature = "static-only"))]
+compile_error!(
+    "`interp` and `static-only` are mutually exclusive: the interpreter core allocates."
+);
+
 // FFI static registry capacity flags are mutually exclusive.
 #[cfg(all(feature = "ffi-static-64", feature = "ffi-static-256"))]
 compile_error!("`ffi-static-64` and `ffi-static-256` are mutually exclusive.");
@@ -44,10 +50,10 @@ compile_error!("`ffi-static-64` and `ffi-static-1024` are mutually exclusive.");
 compile_error!("`ffi-static-256` and `ffi-static-1024` are mutually exclusive.");
 
 // RES-098: pull in the `alloc` crate when the `alloc` feature
-// is on. Needed in both test and production builds — even when
+// (or `interp`, whose environments live on the heap) is on. Needed in both test and production builds — even when
 // std is available, `alloc::string::String` requires the crate
 // to be linked.
-#[cfg(feature = "alloc")]
+#[cfg(any(feature = "alloc", feature = "interp"))]
 extern crate alloc;
 
 // RES-180: `Sink` abstraction + global `print` / `println`
@@ -102,6 +108,12 @@ pub mod interrupt;
 #[cfg(feature = "vm")]
 pub mod vm;
 
+// Allocator-backed tree-walking interpreter core: evaluates a
+// `Program` tree with `BTreeMap` environments and a host-supplied
+// `Host` for print / clock / random. Opt-in like `vm`.
+#[cfg(feature = "interp")]
+pub mod interp;
+
 #[cfg(feature = "alloc")]
 use alloc::string::String;
 
@@ -24,6 +24,7 @@ use std::fmt;
 /// display; offset is the 0-indexed character index into the input
 /// string and exists so we can slice the original source cheaply.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pos {
     pub line: usize,
     pub column: usize,
@@ -50,6 +51,7 @@ impl fmt::Display for Pos {
 /// character of the spanned region, so `end.offset - start.offset` is
 /// the length in chars.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
     pub start: Pos,
     pub end: Pos,
@@ -94,6 +96,15 @@ impl Span {
     pub const fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// The spanned region as a byte range into `src`, the source the
+    /// span was taken from, for slicing (`&src[span.byte_range(src)]`).
+    /// Offsets past the end clamp to `src.len()`.
+    pub fn byte_range(&self, src: &str) -> std::ops::Range<usize> {
+        let byte = |chars: usize| src.char_indices().nth(chars).map_or(src.len(), |(i, _)| i);
+        let start = byte(self.start.offset);
+        start..byte(self.end.offset).max(start)
+    }
 }
 
 impl fmt::Display for Span {
@@ -113,6 +124,7 @@ impl fmt::Display for Span {
 /// Pairs any value with the source span it came from. The intended use
 /// is `Spanned<Node>` once the AST migration lands.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Spanned<T> {
     pub node: T,
     pub span: Span,
@@ -238,6 +250,15 @@ mod tests {
         assert_eq!(mapped.span, s.span);
     }
 
+    #[test]
+    fn byte_range_converts_char_offsets() {
+        let src = "let é = \"ü\";";
+        let span = Span::new(Pos::new(1, 9, 8), Pos::new(1, 12, 11));
+        assert_eq!(&src[span.byte_range(src)], "\"ü\"");
+        let past_end = Span::new(Pos::new(1, 1, 40), Pos::new(1, 1, 50));
+        assert_eq!(past_end.byte_range(src), src.len()..src.len());
+    }
+
     #[test]
     fn build_line_table_empty_source_has_single_bof_entry() {
         assert_eq!(build_line_table(""), vec![0]);
@@ -138,6 +138,26 @@ while i < 200 {
 let n = len(s);
 "#;
 
+/// Benchmark 4: indexed reads from a large named array.
+///
+/// Sums a 5000-element array through `xs[i]`. Each read looks the
+/// array up in the environment, so this tracks the cost of indexing
+/// a binding rather than of copying it.
+const ARRAY_INDEX_SRC: &str = r#"
+let xs = [];
+let i = 0;
+while i < 5000 {
+    xs = push(xs, i);
+    i = i + 1;
+}
+let total = 0;
+i = 0;
+while i < 5000 {
+    total = total + xs[i];
+    i = i + 1;
+}
+"#;
+
 fn bench_fib(c: &mut Criterion) {
     let src_path = write_tmp_source("fib25", FIB_SRC);
     let mut group = c.benchmark_group("tree_walker");
@@ -172,10 +192,22 @@ fn bench_string_processing(c: &mut Criterion) {
     let _ = std::fs::remove_file(&src_path);
 }
 
+fn bench_array_index(c: &mut Criterion) {
+    let src_path = write_tmp_source("array_index5000", ARRAY_INDEX_SRC);
+    let mut group = c.benchmark_group("tree_walker");
+    group
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(10));
+    group.bench_function("array_index_5000", |b| b.iter(|| run_source(&src_path)));
+    group.finish();
+    let _ = std::fs::remove_file(&src_path);
+}
+
 criterion_group!(
     benches,
     bench_fib,
     bench_bubble_sort,
-    bench_string_processing
+    bench_string_processing,
+    bench_array_index
 );
 criterion_main!(benches);
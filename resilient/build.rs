@@ -40,6 +40,7 @@ fn main() {
     println!("cargo:rerun-if-changed=../.git/HEAD");
     println!("cargo:rerun-if-changed=../.git/refs/heads");
     emit_build_metadata();
+    emit_host_cfg();
 
     // Only compile the FFI test helper when the `ffi` feature is active.
     if std::env::var("CARGO_FEATURE_FFI").is_err() {
@@ -131,6 +132,26 @@ fn emit_build_metadata() {
     );
 }
 
+/// Stack for the `rz` binary on WASI. There are no threads to give a
+/// bigger stack to, as `main.rs` does natively, so the linker sets it.
+const WASI_STACK_SIZE: usize = 32 * 1024 * 1024;
+
+/// `cfg(wasm_web)`: `wasm32` without WASI, i.e. the web playground,
+/// where the file, clock and random builtins run on in-memory
+/// stand-ins. On `wasm32-wasi*` std reaches the host through WASI, so
+/// those builtins take their native paths (see `src/host_profile.rs`).
+fn emit_host_cfg() {
+    println!("cargo:rustc-check-cfg=cfg(wasm_web)");
+    let arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+    let os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if arch == "wasm32" && os != "wasi" {
+        println!("cargo:rustc-cfg=wasm_web");
+    }
+    if os == "wasi" {
+        println!("cargo:rustc-link-arg-bins=-zstack-size={}", WASI_STACK_SIZE);
+    }
+}
+
 fn run_capture(argv: &[&str]) -> Option<String> {
     let out = Command::new(argv[0]).args(&argv[1..]).output().ok()?;
     if !out.status.success() {
@@ -195,6 +195,14 @@ impl Scheduler {
         self.runnable.pop_front()
     }
 
+    /// Remove `pid` from the runnable queue wherever it sits, for a
+    /// replayed dispatch order. Returns whether it was runnable.
+    pub fn take_runnable(&mut self, pid: ActorPid) -> bool {
+        let before = self.runnable.len();
+        self.runnable.retain(|p| *p != pid);
+        self.runnable.len() != before
+    }
+
     /// True when there is no runnable actor but at least one blocked
     /// actor. PR 4's deadlock detector fires on this condition.
     pub fn is_deadlocked(&self) -> bool {
@@ -245,6 +253,9 @@ pub fn register_actor() -> ActorPid {
         m.borrow_mut()
             .insert(pid, VecDeque::with_capacity(DEFAULT_MAILBOX_CAPACITY));
     });
+    if crate::metrics::active() {
+        crate::metrics::mailbox_depth(pid.0, Some(0));
+    }
     SCHEDULER.with(|s| s.borrow_mut().mark_runnable(pid));
     pid
 }
@@ -262,6 +273,9 @@ pub fn enqueue(pid: ActorPid, msg: Value) -> Result<(), MailboxError> {
             return Err(MailboxError::WouldBlock(pid));
         }
         mailbox.push_back(msg);
+        if crate::metrics::active() {
+            crate::metrics::mailbox_depth(pid.0, Some(mailbox.len()));
+        }
         Ok(())
     })?;
     // Outside the borrow — wake the actor if it was blocked.
@@ -277,7 +291,11 @@ pub fn dequeue(pid: ActorPid) -> Result<Option<Value>, MailboxError> {
     MAILBOX_REGISTRY.with(|m| {
         let mut reg = m.borrow_mut();
         let mailbox = reg.get_mut(&pid).ok_or(MailboxError::NotLive(pid))?;
-        Ok(mailbox.pop_front())
+        let msg = mailbox.pop_front();
+        if crate::metrics::active() {
+            crate::metrics::mailbox_depth(pid.0, Some(mailbox.len()));
+        }
+        Ok(msg)
     })
 }
 
@@ -299,6 +317,9 @@ pub fn deregister_actor(pid: ActorPid) -> Result<(), MailboxError> {
     if removed.is_none() {
         return Err(MailboxError::NotLive(pid));
     }
+    if crate::metrics::active() {
+        crate::metrics::mailbox_depth(pid.0, None);
+    }
     SCHEDULER.with(|s| {
         let mut sched = s.borrow_mut();
         sched.runnable.retain(|p| *p != pid);
@@ -377,6 +398,14 @@ pub fn next_runnable_actor() -> Option<ActorPid> {
     SCHEDULER.with(|s| s.borrow_mut().pop_runnable())
 }
 
+/// Dispatch `pid` next if it is runnable, out of queue order. Used by
+/// `--replay` to follow the recorded interleaving.
+pub fn take_runnable_actor(pid: ActorPid) -> Option<ActorPid> {
+    SCHEDULER
+        .with(|s| s.borrow_mut().take_runnable(pid))
+        .then_some(pid)
+}
+
 /// True when there are no runnable actors and at least one blocked actor
 /// (deadlock condition). PR 4 surfaces this as a user-visible error.
 pub fn is_deadlocked() -> bool {
@@ -0,0 +1,790 @@
+//! `rz doc [<file|dir>]` — API documentation from the source.
+//!
+//! Each `.rz` file is a module. `rz doc` parses it and writes one page
+//! per module listing its functions, structs, enums, traits, type
+//! aliases, newtypes and constants in source order, with:
+//!
+//! - the signature, built from the AST,
+//! - the `///` comment lines right above the item,
+//! - for functions and methods: the effect (`pure` or `io`), the
+//!   `fails` variants, and the `requires` / `ensures` / `recovers_to`
+//!   contracts.
+//!
+//! `//!` lines at the top of a file document the module itself, and a
+//! `mod name { ... }` block becomes a section of its file's page.
+//! `fn test_*()` functions are left out: they are tests, not API.
+//!
+//! Pages are HTML by default, Markdown with `--format markdown`, and go
+//! to `doc/` (or `--out DIR`) next to an index of the modules. Comments
+//! don't reach the AST, so they are read from the source lines, the
+//! same way `rz fmt` keeps them.
+//!
+//! All logic lives in this file; `lib.rs` contributes only a `mod`
+//! declaration and a dispatch call in `run_cli()`.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{EnumPayload, Node, format_contract_expr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Html,
+    Markdown,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Markdown => "md",
+        }
+    }
+}
+
+/// Entry point called from `run_cli()`. Returns `Some(exit_code)`
+/// when the first CLI arg is `"doc"`, `None` otherwise.
+pub(crate) fn dispatch_doc_subcommand(args: &[String]) -> Option<i32> {
+    if args.get(1).map(String::as_str) != Some("doc") {
+        return None;
+    }
+    let mut target: Option<PathBuf> = None;
+    let mut out = PathBuf::from("doc");
+    let mut format = Format::Html;
+    let mut i = 2;
+    while i < args.len() {
+        let a = args[i].as_str();
+        let (flag, inline) = match a.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (a, None),
+        };
+        match flag {
+            "--help" | "-h" | "help" => {
+                print_doc_help();
+                return Some(0);
+            }
+            "--out" | "--format" => {
+                let value = match inline {
+                    Some(v) => v,
+                    None => {
+                        i += 1;
+                        match args.get(i) {
+                            Some(v) => v.clone(),
+                            None => {
+                                eprintln!("Error: {flag} requires a value");
+                                return Some(2);
+                            }
+                        }
+                    }
+                };
+                if flag == "--out" {
+                    out = PathBuf::from(value);
+                } else {
+                    format = match value.as_str() {
+                        "html" => Format::Html,
+                        "markdown" | "md" => Format::Markdown,
+                        _ => {
+                            eprintln!("Error: --format expects html or markdown, got `{value}`");
+                            return Some(2);
+                        }
+                    };
+                }
+            }
+            _ if a.starts_with('-') => {
+                eprintln!("Error: unknown flag `{a}` to `rz doc`");
+                return Some(2);
+            }
+            _ if target.is_none() => target = Some(PathBuf::from(a)),
+            _ => {
+                eprintln!("Error: unexpected argument `{a}` to `rz doc`");
+                return Some(2);
+            }
+        }
+        i += 1;
+    }
+
+    let root = target.unwrap_or_else(|| PathBuf::from("."));
+    let files = match source_files(&root) {
+        Ok(files) if files.is_empty() => {
+            eprintln!("Error: no .rz files in {}", root.display());
+            return Some(2);
+        }
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return Some(2);
+        }
+    };
+
+    let mut modules = Vec::new();
+    let mut failed = false;
+    for (path, name) in &files {
+        match fs::read_to_string(path) {
+            Ok(src) => match module_docs(name, &src) {
+                Ok(module) => modules.push(module),
+                Err(e) => {
+                    eprintln!("Error: {}: {e}", path.display());
+                    failed = true;
+                }
+            },
+            Err(e) => {
+                eprintln!("Error: could not read {}: {e}", path.display());
+                failed = true;
+            }
+        }
+    }
+    match write_pages(&modules, &out, format) {
+        Ok(written) => {
+            println!(
+                "Documented {} module{} in {}",
+                modules.len(),
+                if modules.len() == 1 { "" } else { "s" },
+                written.display()
+            );
+            Some(if failed { 1 } else { 0 })
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            Some(1)
+        }
+    }
+}
+
+fn print_doc_help() {
+    println!("Usage: rz doc [<file|dir>] [--format html|markdown] [--out DIR]");
+    println!();
+    println!("Write API docs for each .rz file: signatures, `///` doc comments,");
+    println!("effects (pure / io, fails) and requires / ensures contracts.");
+    println!();
+    println!("Options:");
+    println!("  <file|dir>          What to document (default: the current directory)");
+    println!("  --format <fmt>      html (default) or markdown");
+    println!("  --out <dir>         Where to write the pages (default: doc)");
+}
+
+/// The `.rz` files under `root`, each with its module name: the path
+/// below `root` without the extension, or the file stem for a file.
+fn source_files(root: &Path) -> Result<Vec<(PathBuf, String)>, String> {
+    if root.is_file() {
+        let stem = root
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("main")
+            .to_string();
+        return Ok(vec![(root.to_path_buf(), stem)]);
+    }
+    if !root.is_dir() {
+        return Err(format!("{} is not a file or directory", root.display()));
+    }
+    let mut files = Vec::new();
+    collect(root, &mut files)?;
+    files.sort();
+    Ok(files
+        .into_iter()
+        .map(|p| {
+            let name = p
+                .strip_prefix(root)
+                .unwrap_or(&p)
+                .with_extension("")
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+            (p, name)
+        })
+        .collect())
+}
+
+fn collect(dir: &Path, out: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("cannot read directory {}: {e}", dir.display()))?;
+    for entry in entries {
+        let p = entry
+            .map_err(|e| format!("directory entry error: {e}"))?
+            .path();
+        // Hidden directories, build output and earlier docs.
+        let skipped = p
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with('.') || n == "target" || n == "doc");
+        if skipped {
+            continue;
+        }
+        if p.is_dir() {
+            collect(&p, out)?;
+        } else if p.extension().and_then(|s| s.to_str()) == Some("rz") {
+            out.push(p);
+        }
+    }
+    Ok(())
+}
+
+/// A module's page: its doc, its items and its `mod` blocks.
+#[derive(Debug)]
+struct Module {
+    name: String,
+    doc: String,
+    items: Vec<Item>,
+    modules: Vec<Module>,
+}
+
+#[derive(Debug)]
+struct Item {
+    kind: &'static str,
+    name: String,
+    signature: String,
+    doc: String,
+    /// `(label, value)` lines under the signature: effects, contracts,
+    /// fields, variants.
+    details: Vec<(&'static str, String)>,
+}
+
+fn module_docs(name: &str, src: &str) -> Result<Module, String> {
+    let (program, errors) = crate::parse(src);
+    if !errors.is_empty() {
+        // The parser has already printed them.
+        return Err(format!("{} parse error(s)", errors.len()));
+    }
+    let Node::Program(stmts) = &program else {
+        return Err("not a program".to_string());
+    };
+    let lines: Vec<&str> = src.lines().collect();
+    let nodes: Vec<&Node> = stmts.iter().map(|s| &s.node).collect();
+    Ok(module(name.to_string(), module_doc(&lines), &nodes, &lines))
+}
+
+fn module(name: String, doc: String, nodes: &[&Node], lines: &[&str]) -> Module {
+    let mut m = Module {
+        name,
+        doc,
+        items: Vec::new(),
+        modules: Vec::new(),
+    };
+    for node in nodes {
+        match node {
+            Node::Function {
+                name, parameters, ..
+            } if name.starts_with("test_") && parameters.is_empty() => {}
+            Node::Function { .. } => m.items.extend(function_item(node, None, lines)),
+            Node::ImplBlock {
+                struct_name,
+                methods,
+                ..
+            } => {
+                for method in methods {
+                    m.items
+                        .extend(function_item(method, Some(struct_name), lines));
+                }
+            }
+            Node::ModuleDecl { name, body, span } => {
+                let doc = doc_above(lines, decl_line(lines, span.start.line, "mod", name));
+                let nodes: Vec<&Node> = body.iter().collect();
+                m.modules.push(module(name.clone(), doc, &nodes, lines));
+            }
+            other => m.items.extend(type_item(other, lines)),
+        }
+    }
+    m
+}
+
+fn function_item(node: &Node, owner: Option<&str>, lines: &[&str]) -> Option<Item> {
+    let Node::Function {
+        name,
+        parameters,
+        requires,
+        ensures,
+        return_type,
+        span,
+        pure,
+        effects,
+        type_params,
+        type_param_bounds,
+        fails,
+        recovers_to,
+        ..
+    } = node
+    else {
+        return None;
+    };
+    let name = match owner {
+        Some(owner) => {
+            let method = name
+                .strip_prefix(&format!("{owner}$"))
+                .unwrap_or(name.as_str());
+            format!("{owner}::{method}")
+        }
+        None => name.clone(),
+    };
+    let is_pure = *pure || effects.pure;
+    let mut signature = String::new();
+    if is_pure {
+        signature.push_str("pure ");
+    }
+    let _ = write!(signature, "fn {name}");
+    if !type_params.is_empty() {
+        let params: Vec<String> = type_params
+            .iter()
+            .zip(
+                type_param_bounds
+                    .iter()
+                    .chain(std::iter::repeat(&Vec::new())),
+            )
+            .map(|(p, bounds)| {
+                if bounds.is_empty() {
+                    p.clone()
+                } else {
+                    format!("{p}: {}", bounds.join(" + "))
+                }
+            })
+            .collect();
+        let _ = write!(signature, "<{}>", params.join(", "));
+    }
+    let params: Vec<String> = parameters
+        .iter()
+        .map(|(ty, p)| {
+            if ty.is_empty() || p == "self" {
+                p.clone()
+            } else {
+                format!("{ty} {p}")
+            }
+        })
+        .collect();
+    let _ = write!(signature, "({})", params.join(", "));
+    if let Some(ret) = return_type {
+        let _ = write!(signature, " -> {ret}");
+    }
+
+    let mut details = vec![("Effects", if is_pure { "pure" } else { "io" }.to_string())];
+    if !fails.is_empty() {
+        details.push(("Fails", fails.join(", ")));
+    }
+    for clause in requires.iter() {
+        details.push(("Requires", format_contract_expr(clause)));
+    }
+    for clause in ensures.iter() {
+        details.push(("Ensures", format_contract_expr(clause)));
+    }
+    if let Some(expr) = recovers_to {
+        details.push(("Recovers to", format_contract_expr(expr)));
+    }
+    Some(Item {
+        kind: "fn",
+        name,
+        signature,
+        doc: doc_above(lines, span.start.line),
+        details,
+    })
+}
+
+fn type_item(node: &Node, lines: &[&str]) -> Option<Item> {
+    let (kind, name, signature, span, details) = match node {
+        Node::StructDecl {
+            name,
+            type_params,
+            fields,
+            span,
+            ..
+        } => {
+            let generics = if type_params.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", type_params.join(", "))
+            };
+            let details = fields
+                .iter()
+                .map(|(ty, field)| ("Field", format!("{ty} {field}")))
+                .collect();
+            (
+                "struct",
+                name,
+                format!("struct {name}{generics}"),
+                span,
+                details,
+            )
+        }
+        Node::EnumDecl {
+            name,
+            type_params,
+            variants,
+            span,
+        } => {
+            let generics = if type_params.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", type_params.join(", "))
+            };
+            let details = variants
+                .iter()
+                .map(|v| {
+                    let payload = match &v.payload {
+                        EnumPayload::None => String::new(),
+                        EnumPayload::Tuple(types) => format!("({})", types.join(", ")),
+                        EnumPayload::Named(fields) => {
+                            let fields: Vec<String> = fields
+                                .iter()
+                                .map(|f| format!("{}: {}", f.name, f.ty))
+                                .collect();
+                            format!(" {{ {} }}", fields.join(", "))
+                        }
+                    };
+                    ("Variant", format!("{}{payload}", v.name))
+                })
+                .collect();
+            (
+                "enum",
+                name,
+                format!("enum {name}{generics}"),
+                span,
+                details,
+            )
+        }
+        Node::TraitDecl {
+            name,
+            methods,
+            supers,
+            span,
+            ..
+        } => {
+            let supers = if supers.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", supers.join(" + "))
+            };
+            let details = methods
+                .iter()
+                .map(|m| ("Method", format!("fn {}({})", m.name, m.params.join(", "))))
+                .collect();
+            (
+                "trait",
+                name,
+                format!("trait {name}{supers}"),
+                span,
+                details,
+            )
+        }
+        Node::TypeAlias { name, target, span } => (
+            "type",
+            name,
+            format!("type {name} = {target}"),
+            span,
+            Vec::new(),
+        ),
+        Node::NewtypeDecl {
+            name,
+            base_type,
+            span,
+        } => (
+            "newtype",
+            name,
+            format!("newtype {name} = {base_type}"),
+            span,
+            Vec::new(),
+        ),
+        Node::Const {
+            name,
+            value,
+            type_annot,
+            span,
+        } => {
+            let ty = type_annot
+                .as_ref()
+                .map(|t| format!(": {t}"))
+                .unwrap_or_default();
+            (
+                "const",
+                name,
+                format!("const {name}{ty} = {}", format_contract_expr(value)),
+                span,
+                Vec::new(),
+            )
+        }
+        _ => return None,
+    };
+    let keyword = signature.split_whitespace().next().unwrap_or(kind);
+    Some(Item {
+        kind,
+        name: name.clone(),
+        doc: doc_above(lines, decl_line(lines, span.start.line, keyword, name)),
+        signature,
+        details,
+    })
+}
+
+/// The line declaring `keyword name`, at or above `line`: some
+/// declarations record the span of their last token.
+fn decl_line(lines: &[&str], line: usize, keyword: &str, name: &str) -> usize {
+    let declares = |text: &str| {
+        let text = text.trim_start();
+        let text = text.strip_prefix("pub ").unwrap_or(text);
+        text.strip_prefix(keyword)
+            .and_then(|rest| rest.trim_start().strip_prefix(name))
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+    };
+    (1..=line.min(lines.len()))
+        .rev()
+        .find(|l| declares(lines[l - 1]))
+        .unwrap_or(line)
+}
+
+/// The `///` lines right above line `line` (1-based), without the
+/// marker. Attribute lines (`#[...]`, `@name`) in between are skipped.
+fn doc_above(lines: &[&str], line: usize) -> String {
+    let mut doc = Vec::new();
+    let mut idx = line.saturating_sub(1);
+    while idx > 0 {
+        let text = lines.get(idx - 1).map(|l| l.trim()).unwrap_or("");
+        if let Some(rest) = text.strip_prefix("///") {
+            doc.push(rest.strip_prefix(' ').unwrap_or(rest));
+        } else if !(text.starts_with("#[") || text.starts_with('@')) {
+            break;
+        }
+        idx -= 1;
+    }
+    doc.reverse();
+    doc.join("\n").trim().to_string()
+}
+
+/// The `//!` lines at the top of the file.
+fn module_doc(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|l| l.trim())
+        .take_while(|l| l.starts_with("//!") || l.is_empty())
+        .filter_map(|l| l.strip_prefix("//!"))
+        .map(|l| l.strip_prefix(' ').unwrap_or(l))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// The first sentence (or line) of `doc`, for the index.
+fn summary(doc: &str) -> &str {
+    let first = doc.split("\n\n").next().unwrap_or("");
+    let first = first.lines().next().unwrap_or("");
+    match first.find(". ") {
+        Some(i) => &first[..=i],
+        None => first,
+    }
+}
+
+/// Write every page and the index into `out`; returns `out`.
+fn write_pages(modules: &[Module], out: &Path, format: Format) -> Result<PathBuf, String> {
+    let ext = format.extension();
+    let write = |rel: &str, text: String| -> Result<(), String> {
+        let path = out.join(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("could not create {}: {e}", parent.display()))?;
+        }
+        fs::write(&path, text).map_err(|e| format!("could not write {}: {e}", path.display()))
+    };
+    for m in modules {
+        let depth = m.name.matches('/').count();
+        let page = match format {
+            Format::Html => html_page(m, depth),
+            Format::Markdown => markdown_page(m, depth),
+        };
+        write(&format!("{}.{ext}", m.name), page)?;
+    }
+    let index = match format {
+        Format::Html => html_index(modules),
+        Format::Markdown => markdown_index(modules),
+    };
+    write(&format!("index.{ext}"), index)?;
+    Ok(out.to_path_buf())
+}
+
+fn markdown_index(modules: &[Module]) -> String {
+    let mut out = String::from("# API documentation\n\n");
+    for m in modules {
+        let _ = write!(out, "- [`{}`]({}.md)", m.name, m.name);
+        let s = summary(&m.doc);
+        if !s.is_empty() {
+            let _ = write!(out, ": {s}");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn markdown_page(m: &Module, depth: usize) -> String {
+    let mut out = format!("# Module `{}`\n\n", m.name);
+    let _ = writeln!(out, "[Index]({}index.md)\n", "../".repeat(depth));
+    markdown_module(m, 2, &mut out);
+    out
+}
+
+fn markdown_module(m: &Module, level: usize, out: &mut String) {
+    if !m.doc.is_empty() {
+        let _ = writeln!(out, "{}\n", m.doc);
+    }
+    let heading = "#".repeat(level);
+    for item in &m.items {
+        let _ = writeln!(out, "{heading} {} `{}`\n", item.kind, item.name);
+        let _ = writeln!(out, "```rust\n{}\n```\n", item.signature);
+        if !item.doc.is_empty() {
+            let _ = writeln!(out, "{}\n", item.doc);
+        }
+        for (label, value) in &item.details {
+            let _ = writeln!(out, "- **{label}:** `{value}`");
+        }
+        if !item.details.is_empty() {
+            out.push('\n');
+        }
+    }
+    for sub in &m.modules {
+        let _ = writeln!(out, "{heading} mod `{}`\n", sub.name);
+        markdown_module(sub, level + 1, out);
+    }
+}
+
+const HTML_STYLE: &str = "body { font-family: sans-serif; max-width: 60em; margin: auto; }\n\
+     pre { background: #f6f8fa; padding: 0.5em; }\n\
+     dt { font-weight: bold; }\n";
+
+fn html_head(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\n{HTML_STYLE}</style>\n</head>\n<body>\n",
+        escape(title)
+    )
+}
+
+fn html_index(modules: &[Module]) -> String {
+    let mut out = html_head("API documentation");
+    out.push_str("<h1>API documentation</h1>\n<ul>\n");
+    for m in modules {
+        let _ = write!(
+            out,
+            "<li><a href=\"{}.html\"><code>{}</code></a>",
+            escape(&m.name),
+            escape(&m.name)
+        );
+        let s = summary(&m.doc);
+        if !s.is_empty() {
+            let _ = write!(out, ": {}", escape(s));
+        }
+        out.push_str("</li>\n");
+    }
+    out.push_str("</ul>\n</body>\n</html>\n");
+    out
+}
+
+fn html_page(m: &Module, depth: usize) -> String {
+    let mut out = html_head(&m.name);
+    let _ = writeln!(
+        out,
+        "<p><a href=\"{}index.html\">Index</a></p>\n<h1>Module <code>{}</code></h1>",
+        "../".repeat(depth),
+        escape(&m.name)
+    );
+    html_module(m, 2, &mut out);
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn html_module(m: &Module, level: usize, out: &mut String) {
+    html_doc(&m.doc, out);
+    for item in &m.items {
+        let _ = writeln!(
+            out,
+            "<h{level} id=\"{}.{}\">{} <code>{}</code></h{level}>",
+            item.kind,
+            escape(&item.name),
+            item.kind,
+            escape(&item.name)
+        );
+        let _ = writeln!(out, "<pre><code>{}</code></pre>", escape(&item.signature));
+        html_doc(&item.doc, out);
+        if !item.details.is_empty() {
+            out.push_str("<dl>\n");
+            for (label, value) in &item.details {
+                let _ = writeln!(
+                    out,
+                    "<dt>{label}</dt><dd><code>{}</code></dd>",
+                    escape(value)
+                );
+            }
+            out.push_str("</dl>\n");
+        }
+    }
+    for sub in &m.modules {
+        let _ = writeln!(
+            out,
+            "<h{level}>mod <code>{}</code></h{level}>",
+            escape(&sub.name)
+        );
+        html_module(sub, (level + 1).min(6), out);
+    }
+}
+
+/// A doc comment as HTML paragraphs, one per blank-line-separated
+/// block.
+fn html_doc(doc: &str, out: &mut String) {
+    for para in doc.split("\n\n").filter(|p| !p.trim().is_empty()) {
+        let _ = writeln!(out, "<p>{}</p>", escape(para.trim()));
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = "//! Geometry helpers.\n//!\n//! More detail.\n\n\
+/// Clamp `x` into `[lo, hi]`.\n\
+pure fn clamp(int x, int lo, int hi) -> int\n    requires lo <= hi\n    ensures result >= lo\n{\n    if x < lo { return lo; }\n    if x > hi { return hi; }\n    return x;\n}\n\n\
+/// A point.\nstruct Point {\n    int x,\n    int y,\n}\n\n\
+impl Point {\n    /// Manhattan length.\n    fn len(self) -> int {\n        return self.x + self.y;\n    }\n}\n\n\
+fn test_clamp() {\n    assert(clamp(5, 0, 3) == 3);\n}\n";
+
+    #[test]
+    fn items_carry_signatures_docs_effects_and_contracts() {
+        let m = module_docs("geometry", SRC).unwrap();
+        assert_eq!(m.doc, "Geometry helpers.\n\nMore detail.");
+        let names: Vec<&str> = m.items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["clamp", "Point", "Point::len"]);
+
+        let clamp = &m.items[0];
+        assert_eq!(
+            clamp.signature,
+            "pure fn clamp(int x, int lo, int hi) -> int"
+        );
+        assert_eq!(clamp.doc, "Clamp `x` into `[lo, hi]`.");
+        assert_eq!(
+            clamp.details,
+            [
+                ("Effects", "pure".to_string()),
+                ("Requires", "lo <= hi".to_string()),
+                ("Ensures", "result >= lo".to_string()),
+            ]
+        );
+        assert_eq!(m.items[1].doc, "A point.");
+        assert_eq!(
+            m.items[1].details,
+            [
+                ("Field", "int x".to_string()),
+                ("Field", "int y".to_string())
+            ]
+        );
+        assert_eq!(m.items[2].signature, "fn Point::len(self) -> int");
+        assert_eq!(m.items[2].doc, "Manhattan length.");
+
+        let md = markdown_page(&m, 0);
+        assert!(md.contains("## fn `clamp`\n\n```rust\npure fn clamp(int x, int lo, int hi) -> int\n```\n\nClamp `x` into `[lo, hi]`.\n\n- **Effects:** `pure`\n- **Requires:** `lo <= hi`\n"), "{md}");
+        let html = html_page(&m, 0);
+        assert!(
+            html.contains("<dt>Ensures</dt><dd><code>result &gt;= lo</code></dd>"),
+            "{html}"
+        );
+        assert_eq!(summary(&m.doc), "Geometry helpers.");
+    }
+}
@@ -302,15 +302,15 @@ mod tests {
 
     #[test]
     fn rejects_mixed_element_types() {
-        let err =
-            builtin_array_argmax_float(&[Value::Array(vec![Value::Float(1.0), Value::Int(2)])])
-                .unwrap_err();
+        let err = builtin_array_argmax_float(&[Value::Array(
+            vec![Value::Float(1.0), Value::Int(2)].into(),
+        )])
+        .unwrap_err();
         assert!(err.contains("expected all float elements"));
 
-        let err = builtin_array_argmax_string(&[Value::Array(vec![
-            Value::String("a".to_string()),
-            Value::Int(0),
-        ])])
+        let err = builtin_array_argmax_string(&[Value::Array(
+            vec![Value::String("a".to_string()), Value::Int(0)].into(),
+        )])
         .unwrap_err();
         assert!(err.contains("expected all string elements"));
     }
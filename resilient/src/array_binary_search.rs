@@ -250,7 +250,7 @@ mod tests {
     #[test]
     fn binary_search_int_rejects_non_int_elements() {
         let err = builtin_array_binary_search(&[
-            Value::Array(vec![Value::Int(1), Value::Float(2.0)]),
+            Value::Array(vec![Value::Int(1), Value::Float(2.0)].into()),
             Value::Int(1),
         ])
         .unwrap_err();
@@ -358,7 +358,7 @@ mod tests {
             builtin_array_binary_search_float,
             builtin_array_binary_search_string,
         ] {
-            let err = f(&[Value::Array(vec![])]).unwrap_err();
+            let err = f(&[Value::Array(vec![].into())]).unwrap_err();
             assert!(err.contains("expected 2"), "got {}", err);
         }
         let err = builtin_array_binary_search(&[Value::Int(5), Value::Int(1)]).unwrap_err();
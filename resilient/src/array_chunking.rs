@@ -35,9 +35,9 @@ pub(crate) fn builtin_array_chunks(args: &[Value]) -> RResult<Value> {
             let size = *n as usize;
             let chunks: Vec<Value> = items
                 .chunks(size)
-                .map(|c| Value::Array(c.to_vec()))
+                .map(|c| Value::Array(c.to_vec().into()))
                 .collect();
-            Ok(Value::Array(chunks))
+            Ok(Value::Array(chunks.into()))
         }
         [a, b] => Err(format!(
             "array_chunks: expected (array, int), got ({}, {})",
@@ -66,9 +66,9 @@ pub(crate) fn builtin_array_chunks_exact(args: &[Value]) -> RResult<Value> {
             let size = *n as usize;
             let chunks: Vec<Value> = items
                 .chunks_exact(size)
-                .map(|c| Value::Array(c.to_vec()))
+                .map(|c| Value::Array(c.to_vec().into()))
                 .collect();
-            Ok(Value::Array(chunks))
+            Ok(Value::Array(chunks.into()))
         }
         [a, b] => Err(format!(
             "array_chunks_exact: expected (array, int), got ({}, {})",
@@ -92,7 +92,7 @@ pub(crate) fn builtin_array_step(args: &[Value]) -> RResult<Value> {
             }
             let stride = *n as usize;
             let stepped: Vec<Value> = items.iter().step_by(stride).cloned().collect();
-            Ok(Value::Array(stepped))
+            Ok(Value::Array(stepped.into()))
         }
         [a, b] => Err(format!(
             "array_step: expected (array, int), got ({}, {})",
@@ -120,14 +120,14 @@ pub(crate) fn builtin_array_rotate_left(args: &[Value]) -> RResult<Value> {
                 ));
             }
             if items.is_empty() {
-                return Ok(Value::Array(Vec::new()));
+                return Ok(Value::Array(Vec::new().into()));
             }
             let len = items.len();
             let shift = (*n as usize) % len;
             let mut out = Vec::with_capacity(len);
             out.extend_from_slice(&items[shift..]);
             out.extend_from_slice(&items[..shift]);
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_rotate_left: expected (array, int), got ({}, {})",
@@ -153,7 +153,7 @@ pub(crate) fn builtin_array_rotate_right(args: &[Value]) -> RResult<Value> {
                 ));
             }
             if items.is_empty() {
-                return Ok(Value::Array(Vec::new()));
+                return Ok(Value::Array(Vec::new().into()));
             }
             let len = items.len();
             let shift = (*n as usize) % len;
@@ -162,7 +162,7 @@ pub(crate) fn builtin_array_rotate_right(args: &[Value]) -> RResult<Value> {
             let mut out = Vec::with_capacity(len);
             out.extend_from_slice(&items[split..]);
             out.extend_from_slice(&items[..split]);
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_rotate_right: expected (array, int), got ({}, {})",
@@ -1,7 +1,8 @@
 //! RES-2648: Higher-order array combinators with arbitrary callbacks.
 //!
-//! * `array_sort_by(arr, cmp)` — sort using `cmp(a, b) -> int`
-//!   (negative = a before b, 0 = equal, positive = b before a).
+//! * `array_sort_by(arr, cmp)` — stable sort using `cmp(a, b) -> int`
+//!   (negative = a before b, 0 = equal, positive = b before a) or
+//!   `cmp(a, b) -> bool` (true = a before b). Also `sort_by`.
 //! * `array_min_by(arr, fn)` — return the element for which `fn(elem)` is
 //!   smallest (int or float key). Errors on empty array.
 //! * `array_max_by(arr, fn)` — return the element for which `fn(elem)` is
@@ -19,10 +20,12 @@ type RResult<T> = Result<T, String>;
 
 /// `array_sort_by(arr, cmp) -> Array`
 ///
-/// Sorts `arr` using the comparator `cmp(a, b) -> int`. If the return value
-/// is negative, `a` comes before `b`; if positive, `b` comes before `a`; if
-/// zero they are considered equal. Uses a stable sort so elements comparing
-/// equal preserve their original order.
+/// Sorts `arr` using the comparator `cmp(a, b)`. An `int` result orders
+/// the pair like a three-way compare: negative puts `a` first, positive
+/// puts `b` first, zero means equal. A `bool` result is a less-than
+/// test: `true` puts `a` before `b`. Every call must return the same
+/// kind. Uses a stable sort so elements comparing equal preserve their
+/// original order.
 ///
 /// ```text
 /// let sorted = array_sort_by(["banana","apple","cherry"],
@@ -32,6 +35,9 @@ type RResult<T> = Result<T, String>;
 ///         return 0;
 ///     });
 /// // sorted == ["apple", "banana", "cherry"]
+/// let by_len = sort_by(["ccc","a","bb"],
+///     fn(string a, string b) -> bool { return len(a) < len(b); });
+/// // by_len == ["a", "bb", "ccc"]
 /// ```
 pub(crate) fn builtin_array_sort_by(interp: &mut Interpreter, args: &[Value]) -> RResult<Value> {
     // RES-1934: borrow `arr` and `cmp` from `args`. Build the indexed
@@ -58,24 +64,49 @@ pub(crate) fn builtin_array_sort_by(interp: &mut Interpreter, args: &[Value]) ->
     // calling the comparator.
     let mut indexed: Vec<(usize, Value)> = arr.iter().cloned().enumerate().collect();
     let mut error: Option<String> = None;
+    // Whether the comparator answers with bools, fixed by its first call.
+    let mut returns_bool: Option<bool> = None;
 
     indexed.sort_by(|(_, a), (_, b)| {
         if error.is_some() {
             return std::cmp::Ordering::Equal;
         }
-        match interp.apply_function(cmp, vec![a.clone(), b.clone()]) {
-            Ok(Value::Int(n)) => n.cmp(&0),
-            Ok(other) => {
-                error = Some(format!(
-                    "array_sort_by: comparator must return int, got {other}"
+        let mut call = |x: &Value, y: &Value| -> RResult<Value> {
+            let result = interp.apply_function(cmp, vec![x.clone(), y.clone()])?;
+            let is_bool = match result {
+                Value::Int(_) => false,
+                Value::Bool(_) => true,
+                ref other => {
+                    return Err(format!(
+                        "array_sort_by: comparator must return int or bool, got {other}"
+                    ));
+                }
+            };
+            if *returns_bool.get_or_insert(is_bool) != is_bool {
+                return Err(format!(
+                    "array_sort_by: comparator must return the same type every time, got {result} after {}",
+                    if is_bool { "an int" } else { "a bool" }
                 ));
-                std::cmp::Ordering::Equal
-            }
-            Err(e) => {
-                error = Some(e);
-                std::cmp::Ordering::Equal
             }
-        }
+            Ok(result)
+        };
+        // A less-than comparator needs the reverse question to tell
+        // "after" from "equal".
+        let ordering = call(a, b).and_then(|r| match r {
+            Value::Int(n) => Ok(n.cmp(&0)),
+            Value::Bool(true) => Ok(std::cmp::Ordering::Less),
+            _ => call(b, a).map(|r| {
+                if matches!(r, Value::Bool(true)) {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            }),
+        });
+        ordering.unwrap_or_else(|e| {
+            error = Some(e);
+            std::cmp::Ordering::Equal
+        })
     });
 
     if let Some(e) = error {
@@ -260,7 +291,7 @@ pub(crate) fn builtin_array_zip_with(interp: &mut Interpreter, args: &[Value]) -
     for (x, y) in a.into_iter().zip(b) {
         out.push(interp.apply_function(&f, vec![x, y])?);
     }
-    Ok(Value::Array(out))
+    Ok(Value::Array(out.into()))
 }
 
 /// `array_windows(arr, n) -> Array`
@@ -298,13 +329,13 @@ pub(crate) fn builtin_array_windows(args: &[Value]) -> RResult<Value> {
     }
     let n = n as usize;
     if arr.len() < n {
-        return Ok(Value::Array(vec![]));
+        return Ok(Value::Array(vec![].into()));
     }
     let mut out = Vec::with_capacity(arr.len() - n + 1);
     for start in 0..=(arr.len() - n) {
-        out.push(Value::Array(arr[start..start + n].to_vec()));
+        out.push(Value::Array(arr[start..start + n].to_vec().into()));
     }
-    Ok(Value::Array(out))
+    Ok(Value::Array(out.into()))
 }
 
 /// `array_take_while(arr, fn) -> Array`
@@ -343,7 +374,7 @@ pub(crate) fn builtin_array_take_while(interp: &mut Interpreter, args: &[Value])
             }
         }
     }
-    Ok(Value::Array(out))
+    Ok(Value::Array(out.into()))
 }
 
 /// `array_drop_while(arr, fn) -> Array`
@@ -391,7 +422,7 @@ pub(crate) fn builtin_array_drop_while(interp: &mut Interpreter, args: &[Value])
             out.push(elem);
         }
     }
-    Ok(Value::Array(out))
+    Ok(Value::Array(out.into()))
 }
 
 /// Compare two values for ordering (int, float, or string keys).
@@ -544,12 +575,26 @@ println(len(sorted));"#,
     }
 
     #[test]
-    fn sort_by_rejects_non_int_comparator_return() {
+    fn sort_by_takes_a_less_than_comparator_and_is_stable() {
+        let r = run(r#"let words = sort_by(["ccc","b","aa","a","bb"],
+    fn(string a, string b) -> bool { return len(a) < len(b); });
+println(words);"#);
+        assert!(r.ok, "errors: {:?}", r.errors);
+        assert_eq!(r.stdout.trim(), r#"["b", "a", "aa", "bb", "ccc"]"#);
+    }
+
+    #[test]
+    fn sort_by_rejects_other_comparator_returns() {
         let r = run(
-            r#"let sorted = array_sort_by([1,2,3], fn(int a, int b) -> bool { return a < b; });
+            r#"let sorted = sort_by([1,2,3], fn(int a, int b) -> string { return "x"; });
 println(sorted);"#,
         );
-        assert!(!r.ok, "expected error for bool comparator return");
+        assert!(!r.ok, "expected error for string comparator return");
+        assert!(
+            r.errors.iter().any(|e| e.contains("int or bool")),
+            "errors: {:?}",
+            r.errors
+        );
     }
 
     // ── array_min_by / array_max_by ───────────────────────────────────────────
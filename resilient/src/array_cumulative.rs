@@ -40,7 +40,7 @@ pub(crate) fn builtin_array_cumsum(args: &[Value]) -> RResult<Value> {
                 acc = acc.wrapping_add(n);
                 out.push(Value::Int(acc));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_cumsum: expected array, got {}", other)),
         _ => Err(format!(
@@ -62,7 +62,7 @@ pub(crate) fn builtin_array_cumprod(args: &[Value]) -> RResult<Value> {
                 acc = acc.wrapping_mul(n);
                 out.push(Value::Int(acc));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_cumprod: expected array, got {}", other)),
         _ => Err(format!(
@@ -83,7 +83,7 @@ pub(crate) fn builtin_array_diffs(args: &[Value]) -> RResult<Value> {
                 .windows(2)
                 .map(|w| Value::Int(w[1].wrapping_sub(w[0])))
                 .collect();
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_diffs: expected array, got {}", other)),
         _ => Err(format!(
@@ -112,7 +112,7 @@ pub(crate) fn builtin_array_min_max(args: &[Value]) -> RResult<Value> {
                     max = n;
                 }
             }
-            Ok(Value::Array(vec![Value::Int(min), Value::Int(max)]))
+            Ok(Value::Array(vec![Value::Int(min), Value::Int(max)].into()))
         }
         [other] => Err(format!("array_min_max: expected array, got {}", other)),
         _ => Err(format!(
@@ -282,7 +282,7 @@ mod tests {
 
     #[test]
     fn rejects_mixed_element_types() {
-        let arr = Value::Array(vec![Value::Int(1), Value::Float(2.0)]);
+        let arr = Value::Array(vec![Value::Int(1), Value::Float(2.0)].into());
         let err = builtin_array_cumsum(std::slice::from_ref(&arr)).unwrap_err();
         assert!(err.contains("expected all int elements"));
     }
@@ -34,7 +34,7 @@ pub(crate) fn builtin_array_dedup_by(args: &[Value]) -> RResult<Value> {
                     out.push(item.clone());
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_dedup_by: expected (array, string), got ({}, {})",
@@ -80,24 +80,28 @@ pub(crate) fn builtin_array_none(interp: &mut Interpreter, args: &[Value]) -> RR
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::rc::Rc;
 
     fn make_struct(id: i64, name: &str) -> Value {
         Value::Struct {
             name: "Item".to_string(),
-            fields: vec![
+            fields: Rc::new(vec![
                 ("id".to_string(), Value::Int(id)),
                 ("name".to_string(), Value::String(name.to_string())),
-            ],
+            ]),
         }
     }
 
     #[test]
     fn dedup_keeps_first_occurrence() {
-        let arr = Value::Array(vec![
-            make_struct(1, "alpha"),
-            make_struct(2, "beta"),
-            make_struct(1, "alpha-dup"),
-        ]);
+        let arr = Value::Array(
+            vec![
+                make_struct(1, "alpha"),
+                make_struct(2, "beta"),
+                make_struct(1, "alpha-dup"),
+            ]
+            .into(),
+        );
         let result = builtin_array_dedup_by(&[arr, Value::String("id".to_string())]).unwrap();
         if let Value::Array(items) = result {
             assert_eq!(items.len(), 2);
@@ -117,7 +121,7 @@ mod tests {
     #[test]
     fn dedup_empty_array() {
         let result =
-            builtin_array_dedup_by(&[Value::Array(vec![]), Value::String("id".to_string())])
+            builtin_array_dedup_by(&[Value::Array(vec![].into()), Value::String("id".to_string())])
                 .unwrap();
         match result {
             Value::Array(items) => assert!(items.is_empty()),
@@ -127,7 +131,7 @@ mod tests {
 
     #[test]
     fn dedup_no_duplicates() {
-        let arr = Value::Array(vec![make_struct(1, "a"), make_struct(2, "b")]);
+        let arr = Value::Array(vec![make_struct(1, "a"), make_struct(2, "b")].into());
         let result = builtin_array_dedup_by(&[arr, Value::String("id".to_string())]).unwrap();
         if let Value::Array(items) = result {
             assert_eq!(items.len(), 2);
@@ -145,7 +149,7 @@ mod tests {
 
     #[test]
     fn dedup_rejects_wrong_arity() {
-        let err = builtin_array_dedup_by(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_array_dedup_by(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("expected 2 arguments"));
     }
 }
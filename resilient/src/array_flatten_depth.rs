@@ -34,7 +34,7 @@ pub(crate) fn builtin_array_flatten_depth(args: &[Value]) -> RResult<Value> {
             }
             let mut out = Vec::new();
             flatten_rec(items, *depth, &mut out);
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_flatten_depth: expected (array, int), got ({}, {})",
@@ -52,12 +52,12 @@ mod tests {
     use super::*;
 
     fn arr(items: Vec<Value>) -> Value {
-        Value::Array(items)
+        Value::Array(items.into())
     }
 
     fn unwrap_array(v: Value) -> Vec<Value> {
         match v {
-            Value::Array(items) => items,
+            Value::Array(items) => items.into_inner(),
             other => panic!("expected Array, got {:?}", other),
         }
     }
@@ -54,7 +54,7 @@ pub(crate) fn builtin_array_flat_map(interp: &mut Interpreter, args: &[Value]) -
             }
         }
     }
-    Ok(Value::Array(out))
+    Ok(Value::Array(out.into()))
 }
 
 /// `array_group_by(arr, fn) -> Map`
@@ -105,12 +105,12 @@ pub(crate) fn builtin_array_group_by(interp: &mut Interpreter, args: &[Value]) -
     let map: std::collections::HashMap<MapKey, Value> = order
         .into_iter()
         .map(|k| {
-            let v = Value::Array(groups.remove(&k).unwrap());
+            let v = Value::Array(groups.remove(&k).unwrap().into());
             (k, v)
         })
         .collect();
 
-    Ok(Value::Map(map))
+    Ok(Value::Map(map.into()))
 }
 
 /// `array_partition(arr, fn) -> [[passing], [failing]]`
@@ -155,10 +155,9 @@ pub(crate) fn builtin_array_partition(interp: &mut Interpreter, args: &[Value])
             }
         }
     }
-    Ok(Value::Array(vec![
-        Value::Array(passing),
-        Value::Array(failing),
-    ]))
+    Ok(Value::Array(
+        vec![Value::Array(passing.into()), Value::Array(failing.into())].into(),
+    ))
 }
 
 /// `map_from_pairs(pairs) -> Map`
@@ -211,7 +210,7 @@ pub(crate) fn builtin_map_from_pairs(args: &[Value]) -> RResult<Value> {
         }
     }
 
-    Ok(Value::Map(map))
+    Ok(Value::Map(map.into()))
 }
 
 /// `array_scan(arr, init, fn) -> Array`
@@ -247,7 +246,7 @@ pub(crate) fn builtin_array_scan(interp: &mut Interpreter, args: &[Value]) -> RR
         acc = interp.apply_function(&f, vec![acc, elem])?;
         out.push(acc.clone());
     }
-    Ok(Value::Array(out))
+    Ok(Value::Array(out.into()))
 }
 
 #[cfg(test)]
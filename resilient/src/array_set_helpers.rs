@@ -71,7 +71,7 @@ pub(crate) fn builtin_array_difference(args: &[Value]) -> RResult<Value> {
                     out.push(v.clone());
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_difference: expected (array, array), got ({}, {})",
@@ -105,7 +105,7 @@ pub(crate) fn builtin_array_intersection(args: &[Value]) -> RResult<Value> {
                     out.push(v.clone());
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_intersection: expected (array, array), got ({}, {})",
@@ -321,12 +321,15 @@ mod tests {
 
     #[test]
     fn index_of_last_string_elements() {
-        let arr = Value::Array(vec![
-            Value::String("a".to_string()),
-            Value::String("b".to_string()),
-            Value::String("a".to_string()),
-            Value::String("c".to_string()),
-        ]);
+        let arr = Value::Array(
+            vec![
+                Value::String("a".to_string()),
+                Value::String("b".to_string()),
+                Value::String("a".to_string()),
+                Value::String("c".to_string()),
+            ]
+            .into(),
+        );
         let r = builtin_array_index_of_last(&[arr, Value::String("a".to_string())]).unwrap();
         assert_eq!(as_int(r), 2);
     }
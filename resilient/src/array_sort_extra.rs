@@ -321,8 +321,9 @@ mod tests {
 
     #[test]
     fn is_sorted_int_rejects_non_int_elements() {
-        let err = builtin_array_is_sorted(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)])])
-            .unwrap_err();
+        let err =
+            builtin_array_is_sorted(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)].into())])
+                .unwrap_err();
         assert!(err.contains("expected all int elements"));
     }
 
@@ -413,14 +413,16 @@ mod tests {
 
     #[test]
     fn rejects_mixed_element_types() {
-        let err =
-            builtin_array_variance_int(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)])])
-                .unwrap_err();
+        let err = builtin_array_variance_int(&[Value::Array(
+            vec![Value::Int(1), Value::Float(2.0)].into(),
+        )])
+        .unwrap_err();
         assert!(err.contains("expected all int elements"));
 
-        let err =
-            builtin_array_variance_float(&[Value::Array(vec![Value::Float(1.0), Value::Int(2)])])
-                .unwrap_err();
+        let err = builtin_array_variance_float(&[Value::Array(
+            vec![Value::Float(1.0), Value::Int(2)].into(),
+        )])
+        .unwrap_err();
         assert!(err.contains("expected all float elements"));
     }
 }
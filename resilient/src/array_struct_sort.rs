@@ -81,14 +81,15 @@ pub(crate) fn builtin_array_sort_by_field_desc(args: &[Value]) -> RResult<Value>
 mod tests {
     use super::*;
     use std::collections::HashMap;
+    use std::rc::Rc;
 
     fn make_struct(name: &str, age: i64) -> Value {
         Value::Struct {
             name: "Person".to_string(),
-            fields: vec![
+            fields: Rc::new(vec![
                 ("name".to_string(), Value::String(name.to_string())),
                 ("age".to_string(), Value::Int(age)),
-            ],
+            ]),
         }
     }
 
@@ -112,11 +113,14 @@ mod tests {
 
     #[test]
     fn sort_structs_by_int_field_ascending() {
-        let arr = Value::Array(vec![
-            make_struct("Charlie", 30),
-            make_struct("Alice", 20),
-            make_struct("Bob", 25),
-        ]);
+        let arr = Value::Array(
+            vec![
+                make_struct("Charlie", 30),
+                make_struct("Alice", 20),
+                make_struct("Bob", 25),
+            ]
+            .into(),
+        );
         let result = builtin_array_sort_by_field(&[arr, Value::String("age".to_string())]).unwrap();
         if let Value::Array(items) = result {
             assert_eq!(items.len(), 3);
@@ -130,11 +134,14 @@ mod tests {
 
     #[test]
     fn sort_structs_by_string_field_ascending() {
-        let arr = Value::Array(vec![
-            make_struct("Charlie", 30),
-            make_struct("Alice", 20),
-            make_struct("Bob", 25),
-        ]);
+        let arr = Value::Array(
+            vec![
+                make_struct("Charlie", 30),
+                make_struct("Alice", 20),
+                make_struct("Bob", 25),
+            ]
+            .into(),
+        );
         let result =
             builtin_array_sort_by_field(&[arr, Value::String("name".to_string())]).unwrap();
         if let Value::Array(items) = result {
@@ -148,7 +155,7 @@ mod tests {
 
     #[test]
     fn sort_structs_descending() {
-        let arr = Value::Array(vec![make_struct("Alice", 20), make_struct("Bob", 25)]);
+        let arr = Value::Array(vec![make_struct("Alice", 20), make_struct("Bob", 25)].into());
         let result =
             builtin_array_sort_by_field_desc(&[arr, Value::String("age".to_string())]).unwrap();
         if let Value::Array(items) = result {
@@ -164,9 +171,12 @@ mod tests {
         let has_field = make_struct("Alice", 20);
         let no_field = Value::Struct {
             name: "Person".to_string(),
-            fields: vec![("name".to_string(), Value::String("Zara".to_string()))],
+            fields: Rc::new(vec![(
+                "name".to_string(),
+                Value::String("Zara".to_string()),
+            )]),
         };
-        let arr = Value::Array(vec![no_field, has_field]);
+        let arr = Value::Array(vec![no_field, has_field].into());
         let result = builtin_array_sort_by_field(&[arr, Value::String("age".to_string())]).unwrap();
         if let Value::Array(items) = result {
             if let Value::Struct { fields, .. } = &items[1] {
@@ -188,7 +198,7 @@ mod tests {
 
     #[test]
     fn rejects_wrong_arity() {
-        let err = builtin_array_sort_by_field(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_array_sort_by_field(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("expected 2 arguments"));
     }
 
@@ -198,9 +208,9 @@ mod tests {
         let make_map = |age: i64| {
             let mut m = HashMap::new();
             m.insert(MapKey::Str("age".to_string()), Value::Int(age));
-            Value::Map(m)
+            Value::Map(m.into())
         };
-        let arr = Value::Array(vec![make_map(30), make_map(10), make_map(20)]);
+        let arr = Value::Array(vec![make_map(30), make_map(10), make_map(20)].into());
         let result = builtin_array_sort_by_field(&[arr, Value::String("age".to_string())]).unwrap();
         if let Value::Array(items) = result {
             if let Value::Map(m) = &items[0] {
@@ -63,7 +63,7 @@ mod tests {
 
     fn ident(name: &str) -> Node {
         Node::Identifier {
-            name: name.to_string(),
+            name: name.into(),
             span: Span::default(),
         }
     }
@@ -0,0 +1,549 @@
+//! Public AST traversal: [`Visitor`] for read-only walks and [`Folder`]
+//! for rewrites.
+//!
+//! External tools (linters, codemods, instrumentation passes) override
+//! only the hook they need; the default methods descend through every
+//! child node, so a new `Node` variant only has to be taught to
+//! [`for_each_child`] / [`for_each_child_mut`] here.
+//!
+//! ```
+//! use resilient::ast::{self, Folder, Node, Visitor};
+//!
+//! // Count the calls in a program.
+//! struct Calls(usize);
+//! impl Visitor for Calls {
+//!     fn visit_node(&mut self, node: &Node) {
+//!         if let Node::CallExpression { .. } = node {
+//!             self.0 += 1;
+//!         }
+//!         ast::walk_node(self, node);
+//!     }
+//! }
+//!
+//! // Replace every integer literal `1` with `2`.
+//! struct Bump;
+//! impl Folder for Bump {
+//!     fn fold_node(&mut self, node: Node) -> Node {
+//!         match ast::fold_children(self, node) {
+//!             Node::IntegerLiteral { value: 1, span } => Node::IntegerLiteral { value: 2, span },
+//!             other => other,
+//!         }
+//!     }
+//! }
+//!
+//! let program = ast::parse("fn f(int x) -> int { return x + 1; }\nprintln(f(1));\n").unwrap();
+//! let mut calls = Calls(0);
+//! calls.visit_node(&program);
+//! assert_eq!(calls.0, 2);
+//! assert!(ast::to_source(&Bump.fold_node(program)).contains("return x + 2;"));
+//! ```
+
+pub use crate::span::{Pos, Span, Spanned};
+pub use crate::string_interp::StringPart;
+pub use crate::symbol::Symbol;
+pub use crate::{ChainAccess, EnumPatternPayload, Node, Pattern};
+
+/// A child behind an `Arc` (function bodies and contracts). The `&mut`
+/// walk writes through `Arc::make_mut`, which first copies what a
+/// function value still shares.
+macro_rules! shared_child {
+    ($arc:expr,) => {
+        &**$arc
+    };
+    ($arc:expr, mut) => {
+        std::sync::Arc::make_mut($arc)
+    };
+}
+
+/// The child walk shared by the `&` and `&mut` versions; `$m` is empty
+/// or `mut`. Deliberately exhaustive: a new `Node` variant fails to
+/// compile here until its children are listed.
+macro_rules! each_child {
+    ($node:expr, $f:ident, $patterns:ident, $($m:tt)?) => {
+        match $node {
+            Node::Program(stmts) => {
+                for stmt in stmts {
+                    $f(&$($m)? stmt.node);
+                }
+            }
+            Node::Function {
+                defaults,
+                requires,
+                ensures,
+                recovers_to,
+                body,
+                ..
+            } => {
+                for default in defaults {
+                    if let Some(default) = default {
+                        $f(default);
+                    }
+                }
+                for clause in shared_child!(requires, $($m)?) {
+                    $f(clause);
+                }
+                for clause in shared_child!(ensures, $($m)?) {
+                    $f(clause);
+                }
+                if let Some(target) = recovers_to {
+                    $f(target);
+                }
+                $f(shared_child!(body, $($m)?));
+            }
+            Node::FunctionLiteral {
+                requires,
+                ensures,
+                recovers_to,
+                body,
+                ..
+            } => {
+                for clause in shared_child!(requires, $($m)?) {
+                    $f(clause);
+                }
+                for clause in shared_child!(ensures, $($m)?) {
+                    $f(clause);
+                }
+                if let Some(target) = recovers_to {
+                    $f(target);
+                }
+                $f(shared_child!(body, $($m)?));
+            }
+            Node::Extern { decls, .. } => {
+                for decl in decls {
+                    for clause in &$($m)? decl.requires {
+                        $f(clause);
+                    }
+                    for clause in &$($m)? decl.ensures {
+                        $f(clause);
+                    }
+                }
+            }
+            Node::LiveBlock {
+                invariants,
+                timeout,
+                body,
+                ..
+            } => {
+                for invariant in invariants {
+                    $f(invariant);
+                }
+                if let Some(timeout) = timeout {
+                    $f(timeout);
+                }
+                $f(body);
+            }
+            Node::Assert {
+                condition, message, ..
+            }
+            | Node::Assume {
+                condition, message, ..
+            } => {
+                $f(condition);
+                if let Some(message) = message {
+                    $f(message);
+                }
+            }
+            Node::Block { stmts, .. }
+            | Node::ModuleDecl { body: stmts, .. }
+            | Node::ArrayLiteral { items: stmts, .. }
+            | Node::SetLiteral { items: stmts, .. }
+            | Node::TupleLiteral { items: stmts, .. }
+            | Node::ImplBlock { methods: stmts, .. }
+            | Node::BlanketImpl { methods: stmts, .. } => {
+                for stmt in stmts {
+                    $f(stmt);
+                }
+            }
+            Node::LetStatement { value, .. }
+            | Node::StaticLet { value, .. }
+            | Node::Const { value, .. }
+            | Node::Assignment { value, .. }
+            | Node::BreakWith { value, .. }
+            | Node::LetDestructureStruct { value, .. }
+            | Node::LetTupleDestructure { value, .. }
+            | Node::NamedArg { value, .. }
+            | Node::NewtypeConstruct { value, .. }
+            | Node::DeferStatement { expr: value, .. }
+            | Node::ExpressionStatement { expr: value, .. }
+            | Node::TryExpression { expr: value, .. }
+            | Node::InvariantStatement { expr: value, .. }
+            | Node::PrefixExpression { right: value, .. }
+            | Node::FieldAccess { target: value, .. }
+            | Node::TupleIndex { tuple: value, .. }
+            | Node::UnsafeBlock { body: value, .. }
+            | Node::StaticAssert {
+                condition: value, ..
+            }
+            | Node::BenchBlock { body: value, .. }
+            | Node::TestFn {
+                function: value, ..
+            } => $f(value),
+            Node::ReturnStatement { value, .. } => {
+                if let Some(value) = value {
+                    $f(value);
+                }
+            }
+            Node::IfStatement {
+                condition,
+                consequence,
+                alternative,
+                ..
+            } => {
+                $f(condition);
+                $f(consequence);
+                if let Some(alternative) = alternative {
+                    $f(alternative);
+                }
+            }
+            Node::WhileStatement {
+                condition,
+                invariants,
+                body,
+                ..
+            } => {
+                $f(condition);
+                for invariant in invariants {
+                    $f(invariant);
+                }
+                $f(body);
+            }
+            Node::ForInStatement {
+                iterable,
+                invariants,
+                body,
+                ..
+            } => {
+                $f(iterable);
+                for invariant in invariants {
+                    $f(invariant);
+                }
+                $f(body);
+            }
+            Node::InfixExpression { left, right, .. }
+            | Node::Range {
+                lo: left,
+                hi: right,
+                ..
+            }
+            | Node::IndexExpression {
+                target: left,
+                index: right,
+                ..
+            }
+            | Node::FieldAssignment {
+                target: left,
+                value: right,
+                ..
+            } => {
+                $f(left);
+                $f(right);
+            }
+            Node::CallExpression {
+                function,
+                arguments,
+                ..
+            } => {
+                $f(function);
+                for argument in arguments {
+                    $f(argument);
+                }
+            }
+            Node::OptionalChain { object, access, .. } => {
+                $f(object);
+                if let ChainAccess::Method(_, arguments) = access {
+                    for argument in arguments {
+                        $f(argument);
+                    }
+                }
+            }
+            Node::Match {
+                scrutinee, arms, ..
+            } => {
+                $f(scrutinee);
+                for (pattern, guard, body) in arms {
+                    $patterns(pattern, &mut $f);
+                    if let Some(guard) = guard {
+                        $f(guard);
+                    }
+                    $f(body);
+                }
+            }
+            Node::StructLiteral { fields, base, .. } => {
+                for (_, value) in fields {
+                    $f(value);
+                }
+                if let Some(base) = base {
+                    $f(base);
+                }
+            }
+            Node::Slice { target, lo, hi, .. } => {
+                $f(target);
+                if let Some(lo) = lo {
+                    $f(lo);
+                }
+                if let Some(hi) = hi {
+                    $f(hi);
+                }
+            }
+            Node::IndexAssignment {
+                target,
+                index,
+                value,
+                ..
+            } => {
+                $f(target);
+                $f(index);
+                $f(value);
+            }
+            Node::MapLiteral { entries, .. } => {
+                for (key, value) in entries {
+                    $f(key);
+                    $f(value);
+                }
+            }
+            Node::TraitDecl { methods, .. } => {
+                for method in methods {
+                    if let Some(body) = &$($m)? method.default_body {
+                        $f(body);
+                    }
+                }
+            }
+            Node::Actor {
+                state_init,
+                concurrent_ensures,
+                handlers,
+                ..
+            } => {
+                $f(state_init);
+                for clause in concurrent_ensures {
+                    $f(clause);
+                }
+                for handler in handlers {
+                    for clause in &$($m)? handler.ensures {
+                        $f(clause);
+                    }
+                    $f(&$($m)? handler.body);
+                }
+            }
+            Node::ActorDecl {
+                state_fields,
+                always_clauses,
+                eventually_clauses,
+                receive_handlers,
+                handlers,
+                ..
+            } => {
+                for (_, _, init) in state_fields {
+                    $f(init);
+                }
+                for clause in always_clauses {
+                    $f(clause);
+                }
+                for clause in eventually_clauses {
+                    $f(&$($m)? clause.post);
+                }
+                for handler in receive_handlers {
+                    for clause in &$($m)? handler.requires {
+                        $f(clause);
+                    }
+                    for clause in &$($m)? handler.ensures {
+                        $f(clause);
+                    }
+                    $f(&$($m)? handler.body);
+                }
+                for handler in handlers {
+                    for clause in &$($m)? handler.ensures {
+                        $f(clause);
+                    }
+                    $f(&$($m)? handler.body);
+                }
+            }
+            Node::ClusterDecl { invariants, .. } => {
+                for invariant in invariants {
+                    $f(invariant);
+                }
+            }
+            Node::TryCatch { body, handlers, .. } => {
+                for stmt in body {
+                    $f(stmt);
+                }
+                for (_, handler) in handlers {
+                    for stmt in handler {
+                        $f(stmt);
+                    }
+                }
+            }
+            Node::Quantifier { range, body, .. } => {
+                match range {
+                    crate::quantifiers::QuantRange::Range { lo, hi } => {
+                        $f(lo);
+                        $f(hi);
+                    }
+                    crate::quantifiers::QuantRange::Iterable(iterable) => $f(iterable),
+                }
+                $f(body);
+            }
+            Node::InterpolatedString { parts, .. } => {
+                for part in parts {
+                    if let StringPart::Expr(expr) = part {
+                        $f(expr);
+                    }
+                }
+            }
+            Node::EnumDecl { variants, .. } => {
+                for variant in variants {
+                    if let Some(discriminant) = &$($m)? variant.discriminant {
+                        $f(discriminant);
+                    }
+                }
+            }
+            Node::Use { .. }
+            | Node::DurationLiteral { .. }
+            | Node::Break { .. }
+            | Node::Continue { .. }
+            | Node::BreakLabel { .. }
+            | Node::ContinueLabel { .. }
+            | Node::Identifier { .. }
+            | Node::IntegerLiteral { .. }
+            | Node::FloatLiteral { .. }
+            | Node::StringLiteral { .. }
+            | Node::StringInternLiteral { .. }
+            | Node::BytesLiteral { .. }
+            | Node::CharLiteral { .. }
+            | Node::BooleanLiteral { .. }
+            | Node::StructDecl { .. }
+            | Node::TypeAlias { .. }
+            | Node::RegionDecl { .. }
+            | Node::NewtypeDecl { .. }
+            | Node::SupervisorDecl { .. }
+            | Node::RegionParam { .. } => {}
+        }
+    };
+}
+
+/// The literal nodes inside a pattern, for both walk directions.
+macro_rules! each_pattern_node {
+    ($pattern:expr, $f:ident, $recurse:ident, $($m:tt)?) => {
+        match $pattern {
+            Pattern::Literal(node) => $f(node),
+            Pattern::Or(patterns)
+            | Pattern::TupleStruct {
+                fields: patterns, ..
+            }
+            | Pattern::Tuple(patterns) => {
+                for pattern in patterns {
+                    $recurse(pattern, $f);
+                }
+            }
+            Pattern::Bind(_, pattern)
+            | Pattern::Some(pattern)
+            | Pattern::Ok(pattern)
+            | Pattern::Err(pattern) => $recurse(pattern, $f),
+            Pattern::Struct { fields, .. }
+            | Pattern::EnumVariant {
+                payload: EnumPatternPayload::Named(fields),
+                ..
+            } => {
+                for (_, pattern) in fields {
+                    $recurse(pattern, $f);
+                }
+            }
+            Pattern::EnumVariant {
+                payload: EnumPatternPayload::Tuple(patterns),
+                ..
+            } => {
+                for pattern in patterns {
+                    $recurse(pattern, $f);
+                }
+            }
+            Pattern::EnumVariant {
+                payload: EnumPatternPayload::None,
+                ..
+            }
+            | Pattern::Identifier(_)
+            | Pattern::Wildcard
+            | Pattern::Range { .. }
+            | Pattern::None => {}
+        }
+    };
+}
+
+/// Parse `src` into a `Node::Program`, or return the parse errors as
+/// `line:col: message` strings. See [`crate::parse_source`] for the
+/// same parse with structured diagnostics.
+pub fn parse(src: &str) -> Result<Node, Vec<String>> {
+    let (program, errors) = crate::parse_silent(src);
+    if errors.is_empty() {
+        Ok(program)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Print a program back as source, in `rz fmt` style.
+pub fn to_source(program: &Node) -> String {
+    crate::formatter::Formatter::format(program)
+}
+
+/// A read-only walk over the AST.
+pub trait Visitor {
+    /// Called for each node, parents before children. The default
+    /// visits the children; an override that still wants them calls
+    /// [`walk_node`].
+    fn visit_node(&mut self, node: &Node) {
+        walk_node(self, node);
+    }
+}
+
+/// Visit each child of `node` with `visitor`.
+pub fn walk_node<V: Visitor + ?Sized>(visitor: &mut V, node: &Node) {
+    for_each_child(node, |child| visitor.visit_node(child));
+}
+
+/// A rewrite of the AST that takes each node by value and returns its
+/// replacement.
+pub trait Folder {
+    /// Called for each node. The default folds the children and keeps
+    /// the node; an override calls [`fold_children`] first to rewrite
+    /// bottom-up, or last to rewrite top-down.
+    fn fold_node(&mut self, node: Node) -> Node {
+        fold_children(self, node)
+    }
+}
+
+/// Replace each child of `node` with `folder`'s fold of it.
+pub fn fold_children<F: Folder + ?Sized>(folder: &mut F, mut node: Node) -> Node {
+    for_each_child_mut(&mut node, |child| {
+        let taken = std::mem::replace(child, placeholder());
+        *child = folder.fold_node(taken);
+    });
+    node
+}
+
+/// Stands in for a child while the folder owns it.
+fn placeholder() -> Node {
+    Node::BooleanLiteral {
+        value: false,
+        span: Span::default(),
+    }
+}
+
+/// Call `f` on each direct child of `node`, in source order. Children
+/// include contract clauses, default parameter values, actor and trait
+/// method bodies, and the literals inside `match` patterns.
+pub fn for_each_child(node: &Node, mut f: impl FnMut(&Node)) {
+    each_child!(node, f, pattern_nodes,);
+}
+
+/// Call `f` on each direct child of `node`, mutably, in source order.
+pub fn for_each_child_mut(node: &mut Node, mut f: impl FnMut(&mut Node)) {
+    each_child!(node, f, pattern_nodes_mut, mut);
+}
+
+fn pattern_nodes(pattern: &Pattern, f: &mut dyn FnMut(&Node)) {
+    each_pattern_node!(pattern, f, pattern_nodes,);
+}
+
+fn pattern_nodes_mut(pattern: &mut Pattern, f: &mut dyn FnMut(&mut Node)) {
+    each_pattern_node!(pattern, f, pattern_nodes_mut, mut);
+}
@@ -100,7 +100,7 @@ fn walk_async_calls<'a>(node: &'a Node, async_fns: &HashSet<String>, out: &mut V
                     // explicit bridge — skip recursion into args here since they are awaited
                     return;
                 }
-                if async_fns.contains(name) {
+                if async_fns.contains(name.as_str()) {
                     out.push(name.as_str());
                 }
             }
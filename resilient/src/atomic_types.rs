@@ -276,6 +276,7 @@ fn span_of(node: &Node) -> Span {
         | Node::BlanketImpl { span, .. }
         | Node::StaticAssert { span, .. }
         | Node::BenchBlock { span, .. }
+        | Node::TestFn { span, .. }
         | Node::Use { span, .. }
         | Node::Extern { span, .. }
         | Node::Function { span, .. }
@@ -568,7 +569,7 @@ fn validate_atomic_target(
 ) -> Result<(), String> {
     match target {
         Node::Identifier { name, span } => {
-            if atomic_names.contains(name) {
+            if atomic_names.contains(name.as_str()) {
                 Ok(())
             } else {
                 Err(diagnostic(
@@ -740,6 +741,9 @@ fn check_atomic_value_parity(
         | Node::NamedArg { value, .. }
         | Node::NewtypeConstruct { value, .. }
         | Node::BenchBlock { body: value, .. }
+        | Node::TestFn {
+            function: value, ..
+        }
         | Node::UnsafeBlock { body: value, .. } => {
             check_atomic_value_parity(value, source_path, facts)?;
         }
@@ -1054,6 +1058,9 @@ fn check_atomic_call_sites(
         | Node::NamedArg { value, .. }
         | Node::NewtypeConstruct { value, .. }
         | Node::BenchBlock { body: value, .. }
+        | Node::TestFn {
+            function: value, ..
+        }
         | Node::UnsafeBlock { body: value, .. } => {
             check_atomic_call_sites(value, source_path, atomic_names)?;
         }
@@ -1,4 +1,12 @@
 //! RES-2613: `bench "name" { body }` plus `rz bench`.
+//!
+//! `bench fn name() { body }` declares a benchmark as a zero-parameter
+//! function; it is stored as a `BenchBlock` whose body is that
+//! `Node::Function`. Each benchmark runs against one evaluation of the
+//! program: after the warmup runs, the iteration count is doubled until
+//! one sample of that many runs takes at least `CALIBRATION_TARGET`
+//! (or fixed with `--iters`), and each of `--runs` samples reports its
+//! time per run.
 
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -6,16 +14,43 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::Instant;
 
-use crate::{Node, Parser, Token, imports, stdlib, typechecker};
+use std::time::Duration;
 
-const DEFAULT_WARMUP_ITERS: usize = 1;
-const DEFAULT_RUN_ITERS: usize = 3;
+use crate::{Interpreter, Node, Parser, Token, imports, stdlib, typechecker};
 
-/// Parse a `bench "name" { body };` statement.
+const DEFAULT_WARMUP_ITERS: usize = 1;
+const DEFAULT_RUN_ITERS: usize = 10;
+/// Calibration stops once one sample takes at least this long.
+const CALIBRATION_TARGET: Duration = Duration::from_millis(10);
+/// ... or once a sample runs the body this many times.
+const MAX_ITERS_PER_SAMPLE: u64 = 1 << 24;
+
+/// Parse a `bench "name" { body };` statement or a `bench fn name() { body }`
+/// declaration.
 pub(crate) fn parse(parser: &mut Parser) -> Node {
     let start_span = parser.span_at_current();
     parser.next_token();
 
+    if parser.current_token == Token::Function {
+        let function = parser.parse_function();
+        let name = match &function {
+            Node::Function {
+                name, parameters, ..
+            } => {
+                if !parameters.is_empty() {
+                    parser.record_error(format!("bench fn `{name}` must not take parameters"));
+                }
+                name.clone()
+            }
+            _ => String::new(),
+        };
+        return Node::BenchBlock {
+            name,
+            body: Box::new(function),
+            span: start_span,
+        };
+    }
+
     let name = match &parser.current_token {
         Token::StringLiteral(s) => s.clone(),
         other => {
@@ -70,6 +105,7 @@ pub fn dispatch_bench_subcommand(args: &[String]) -> Option<i32> {
     let mut summary_json_path: Option<PathBuf> = None;
     let mut warmup_iters = DEFAULT_WARMUP_ITERS;
     let mut run_iters = DEFAULT_RUN_ITERS;
+    let mut fixed_iters: Option<u64> = None;
 
     let mut i = 2;
     while i < args.len() {
@@ -146,6 +182,24 @@ pub fn dispatch_bench_subcommand(args: &[String]) -> Option<i32> {
                     return Some(2);
                 }
             }
+        } else if arg == "--iters" || arg.starts_with("--iters=") {
+            let value = match arg.strip_prefix("--iters=") {
+                Some(value) => value,
+                None => {
+                    i += 1;
+                    match args.get(i) {
+                        Some(value) => value.as_str(),
+                        None => "",
+                    }
+                }
+            };
+            match value.parse() {
+                Ok(n) if n > 0 => fixed_iters = Some(n),
+                _ => {
+                    eprintln!("Error: --iters requires a positive integer");
+                    return Some(2);
+                }
+            }
         } else if file.is_none() && !arg.starts_with('-') {
             file = Some(PathBuf::from(arg));
         } else {
@@ -184,13 +238,14 @@ pub fn dispatch_bench_subcommand(args: &[String]) -> Option<i32> {
         return Some(1);
     }
 
-    let current_results = match run_benchmarks(&loaded, &benches, warmup_iters, run_iters) {
-        Ok(results) => results,
-        Err(err) => {
-            eprintln!("Error: {err}");
-            return Some(1);
-        }
-    };
+    let current_results =
+        match run_benchmarks(&loaded, &benches, warmup_iters, run_iters, fixed_iters) {
+            Ok(results) => results,
+            Err(err) => {
+                eprintln!("Error: {err}");
+                return Some(1);
+            }
+        };
 
     let baseline_results = if let Some(reference) = baseline_ref.as_deref() {
         match load_program_from_git_ref(&path, reference) {
@@ -204,7 +259,13 @@ pub fn dispatch_bench_subcommand(args: &[String]) -> Option<i32> {
                     );
                     return Some(1);
                 }
-                match run_benchmarks(&loaded_baseline, &baseline_benches, warmup_iters, run_iters) {
+                match run_benchmarks(
+                    &loaded_baseline,
+                    &baseline_benches,
+                    warmup_iters,
+                    run_iters,
+                    fixed_iters,
+                ) {
                     Ok(results) => Some(results),
                     Err(err) => {
                         eprintln!("Error: baseline `{reference}` failed: {err}");
@@ -275,20 +336,25 @@ struct BenchmarkStats {
 struct BenchmarkResult {
     name: String,
     stats: BenchmarkStats,
+    /// Runs of the body per timed sample.
+    iters: u64,
 }
 
 fn print_bench_help() {
     println!(
-        "Usage: rz bench <file> [--baseline <git-ref>] [--summary-json <path>] [--warmup N] [--runs N]"
+        "Usage: rz bench <file> [--baseline <git-ref>] [--summary-json <path>] [--warmup N] [--runs N] [--iters N]"
     );
     println!();
-    println!("Discover and run `bench \"name\" {{ ... }}` blocks.");
+    println!("Discover and run `bench \"name\" {{ ... }}` blocks and `bench fn name() {{ ... }}`");
+    println!("declarations. Times are per run of the body.");
     println!();
     println!("Options:");
     println!("  --baseline <ref>   Compare mean ns/op against a git ref");
     println!("  --summary-json <path>  Write a stable JSON summary artifact");
     println!("  --warmup <N>       Warmup iterations before timing (default: 1)");
-    println!("  --runs <N>         Timed iterations per benchmark (default: 3)");
+    println!("  --runs <N>         Timed samples per benchmark (default: 10)");
+    println!("  --iters <N>        Runs of the body per sample (default: calibrated so a");
+    println!("                     sample takes at least 10ms)");
     println!("  --filter <substr>  Only run benchmarks whose names contain <substr>");
 }
 
@@ -336,6 +402,7 @@ fn load_program_from_source(src: &str, source_path: &Path) -> Result<LoadedProgr
     imports::expand_uses_with_std(&mut program, &base_dir, &mut loaded, &mut std_imports)
         .map_err(|e| format!("import error in {}: {e}", source_path.display()))?;
 
+    crate::overloads::lower_program(&mut program);
     let _ = crate::named_args::lower_program(&mut program);
     crate::default_params::lower_program(&mut program);
     crate::newtypes::lower_program(&mut program);
@@ -400,50 +467,93 @@ fn run_benchmarks(
     benches: &[BenchmarkCase],
     warmup_iters: usize,
     run_iters: usize,
+    fixed_iters: Option<u64>,
 ) -> Result<Vec<BenchmarkResult>, String> {
     let mut results = Vec::with_capacity(benches.len());
     for bench in benches {
-        for _ in 0..warmup_iters {
-            crate::execute_benchmark_body(
-                &loaded.program,
-                &bench.body,
-                &loaded.std_bindings,
-                &loaded.source_label,
-            )
-            .map_err(|e| {
-                format!(
-                    "benchmark `{}` failed during warmup at {}:{}: {}",
-                    bench.name, bench.line, bench.column, e
-                )
-            })?;
-        }
-
-        let mut samples_ns = Vec::with_capacity(run_iters);
-        for _ in 0..run_iters {
-            let started = Instant::now();
-            crate::execute_benchmark_body(
-                &loaded.program,
-                &bench.body,
-                &loaded.std_bindings,
-                &loaded.source_label,
+        let located = |e: String| {
+            format!(
+                "benchmark `{}` failed at {}:{}: {}",
+                bench.name, bench.line, bench.column, e
             )
-            .map_err(|e| {
-                format!(
-                    "benchmark `{}` failed at {}:{}: {}",
-                    bench.name, bench.line, bench.column, e
-                )
-            })?;
-            samples_ns.push(started.elapsed().as_secs_f64() * 1_000_000_000.0);
-        }
+        };
+        let (samples_ns, iters) = crate::with_benchmark_interpreter(
+            &loaded.program,
+            &loaded.std_bindings,
+            &loaded.source_label,
+            |interp| {
+                // A `bench fn` is defined once and then called.
+                if let Node::Function { .. } = &bench.body {
+                    interp.eval(&bench.body)?;
+                }
+                for _ in 0..warmup_iters {
+                    run_body(interp, bench).map_err(|e| format!("during warmup: {e}"))?;
+                }
+                let iters = match fixed_iters {
+                    Some(n) => n,
+                    None => calibrate(interp, bench)?,
+                };
+                let mut samples_ns = Vec::with_capacity(run_iters);
+                for _ in 0..run_iters {
+                    let elapsed = time_sample(interp, bench, iters)?;
+                    samples_ns.push(elapsed.as_secs_f64() * 1_000_000_000.0 / iters as f64);
+                }
+                Ok((samples_ns, iters))
+            },
+        )
+        .map_err(located)?;
 
         results.push(BenchmarkResult {
             name: bench.name.clone(),
             stats: compute_stats(&samples_ns),
+            iters,
         });
     }
     Ok(results)
 }
 
+/// One run of a benchmark body.
+fn run_body(interp: &mut Interpreter, bench: &BenchmarkCase) -> Result<(), String> {
+    match &bench.body {
+        Node::Function { name, .. } => {
+            let function = interp
+                .env
+                .get(name)
+                .ok_or_else(|| format!("bench fn `{name}` is not defined"))?;
+            interp.apply_function(&function, Vec::new())?;
+        }
+        body => {
+            interp.eval(body)?;
+        }
+    }
+    Ok(())
+}
+
+fn time_sample(
+    interp: &mut Interpreter,
+    bench: &BenchmarkCase,
+    iters: u64,
+) -> Result<Duration, String> {
+    let started = Instant::now();
+    for _ in 0..iters {
+        run_body(interp, bench)?;
+    }
+    Ok(started.elapsed())
+}
+
+/// The runs per sample: doubled from 1 until a sample takes at least
+/// `CALIBRATION_TARGET`.
+fn calibrate(interp: &mut Interpreter, bench: &BenchmarkCase) -> Result<u64, String> {
+    let mut iters = 1;
+    while iters < MAX_ITERS_PER_SAMPLE {
+        if time_sample(interp, bench, iters)? >= CALIBRATION_TARGET {
+            break;
+        }
+        iters *= 2;
+    }
+    Ok(iters)
+}
+
 fn compute_stats(samples_ns: &[f64]) -> BenchmarkStats {
     debug_assert!(!samples_ns.is_empty());
 
@@ -497,8 +607,8 @@ fn print_results(
     }
 
     println!(
-        "{:<24} {:>12} {:>12} {:>12} {:>12} {:>12} {:>14} {:>10}",
-        "Benchmark", "mean ns/op", "median", "stddev", "min", "max", "baseline", "delta"
+        "{:<24} {:>12} {:>12} {:>12} {:>12} {:>12} {:>10} {:>14} {:>10}",
+        "Benchmark", "mean ns/op", "median", "stddev", "min", "max", "iters", "baseline", "delta"
     );
 
     for result in current {
@@ -518,13 +628,14 @@ fn print_results(
             None => "-".to_string(),
         };
         println!(
-            "{:<24} {:>12} {:>12} {:>12} {:>12} {:>12} {:>14} {:>10}",
+            "{:<24} {:>12} {:>12} {:>12} {:>12} {:>12} {:>10} {:>14} {:>10}",
             truncate_name(&result.name, 24),
             format_ns(result.stats.mean_ns),
             format_ns(result.stats.median_ns),
             format_ns(result.stats.stddev_ns),
             format_ns(result.stats.min_ns),
             format_ns(result.stats.max_ns),
+            result.iters,
             baseline_mean,
             delta_text,
         );
@@ -580,6 +691,7 @@ fn write_summary_json(
                 "stddev_ns": result.stats.stddev_ns,
                 "min_ns": result.stats.min_ns,
                 "max_ns": result.stats.max_ns,
+                "iters_per_sample": result.iters,
                 "baseline_mean_ns": baseline_mean_ns,
                 "delta_pct": delta_pct,
             })
@@ -716,6 +828,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_bench_fn_keeps_the_function_as_body() {
+        let (program, errors) = parse("bench fn squares() { square(3); }");
+        assert!(errors.is_empty(), "unexpected parse errors: {:?}", errors);
+        let benches = super::discover_benchmarks(&program, None);
+        assert_eq!(benches.len(), 1);
+        assert_eq!(benches[0].name, "squares");
+        assert!(matches!(benches[0].body, crate::Node::Function { .. }));
+
+        let (_, errors) = parse("bench fn takes(int n) { square(n); }");
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("bench fn `takes` must not take parameters")),
+            "{errors:?}"
+        );
+    }
+
     #[test]
     fn discover_bench_finds_multiple() {
         let src = r#"
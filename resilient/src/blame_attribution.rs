@@ -149,7 +149,7 @@ fn walk(node: &Node, caller: &str, map: &mut BlameMap) {
                 let entry = if let Some(e) = map.edges.get_mut(callee.as_str()) {
                     e
                 } else {
-                    map.edges.entry(callee.clone()).or_default()
+                    map.edges.entry(callee.to_string()).or_default()
                 };
                 for (idx, _) in arguments.iter().enumerate() {
                     entry.push((caller.to_string(), idx));
@@ -167,7 +167,7 @@ fn walk_toplevel(node: &Node, source_path: &str, errors: &mut Vec<String>) {
     if let Node::Function { body, requires, .. } = node {
         let mut ctx = BoundsCtx::default();
         // Every `requires` clause is an available axiom inside the body.
-        for r in requires {
+        for r in requires.iter() {
             ctx.axioms.push(r.clone());
         }
         // RES-133b: leading `assume(P)` predicates are also axioms.
@@ -219,7 +219,7 @@ fn walk_node(node: &Node, ctx: &BoundsCtx, source_path: &str, errors: &mut Vec<S
                 && *operator == ".."
             {
                 let ident = |n: &str| Node::Identifier {
-                    name: n.to_string(),
+                    name: n.into(),
                     span: Span::default(),
                 };
                 // Axiom: lower_bound <= i
@@ -320,7 +320,7 @@ fn check_index(
     // the target's literal length — discharge without Z3.
     if let Node::IntegerLiteral { value, .. } = index
         && let Node::Identifier { name, .. } = target
-        && let Some(len) = ctx.literal_len.get(name)
+        && let Some(len) = ctx.literal_len.get(name.as_str())
     {
         if *value >= 0 && *value < *len {
             mark_proven(span);
@@ -344,7 +344,7 @@ fn check_index(
 
     // General path: hand `0 <= index AND index < len(target)` to Z3.
     let target_name = match target {
-        Node::Identifier { name, .. } => name.clone(),
+        Node::Identifier { name, .. } => *name,
         _ => {
             // Non-identifier target (e.g. a nested call result). We
             // can't name its length for the SMT translator — leave
@@ -388,11 +388,11 @@ fn build_bounds_goal(target_name: &str, index: &Node) -> Node {
     };
     let len_call = Node::CallExpression {
         function: Box::new(Node::Identifier {
-            name: "len".to_string(),
+            name: "len".into(),
             span: Span::default(),
         }),
         arguments: vec![Node::Identifier {
-            name: target_name.to_string(),
+            name: target_name.into(),
             span: Span::default(),
         }],
         span: Span::default(),
@@ -0,0 +1,516 @@
+//! Typed values across the Rust ↔ Resilient boundary.
+//!
+//! [`IntoResilient`] turns a Rust value into a Resilient one and
+//! [`FromResilient`] reads one back, so a host calls into a program
+//! with plain Rust types through [`crate::interpreter::Session`]:
+//!
+//! ```
+//! use resilient::bridge::{FromResilient, IntoResilient};
+//! use resilient::interpreter::Session;
+//!
+//! #[derive(Debug, PartialEq, IntoResilient, FromResilient)]
+//! struct Point {
+//!     x: f64,
+//!     y: f64,
+//! }
+//!
+//! let mut interp = Session::load(
+//!     "struct Point { float x, float y }
+//!      fn process(int n, float scale) -> float { return to_float(n) * scale; }
+//!      fn mirror(Point p) -> Point { return new Point { x: p.y, y: p.x }; }",
+//! )
+//! .unwrap();
+//! let total: f64 = interp.call("process", (3, 7.5)).unwrap();
+//! assert_eq!(total, 22.5);
+//! let p: Point = interp.call("mirror", (Point { x: 1.0, y: 2.0 },)).unwrap();
+//! assert_eq!(p, Point { x: 2.0, y: 1.0 });
+//!
+//! let err = interp.call::<i64>("process", (3, 7.5)).unwrap_err();
+//! assert_eq!(err.to_string(), "expected int, found float");
+//! ```
+//!
+//! | Rust | Resilient |
+//! |---|---|
+//! | `i64` (and narrower ints, range-checked on the way back) | `int` |
+//! | `f64`, `f32` | `float` |
+//! | `bool`, `char`, `String` (`&str` into) | `bool`, `char`, `string` |
+//! | `()` | void |
+//! | `Vec<T>` | array |
+//! | `HashMap<String, T>` | map with string keys |
+//! | `Option<T>`, `Result<T, E>` | `Option`, `Result` |
+//! | tuples of up to four | tuple |
+//! | `#[derive(IntoResilient, FromResilient)]` struct | struct of the same name |
+//! | [`ResilientValue`] | any value, unconverted |
+//!
+//! A conversion that doesn't fit fails with a [`BridgeError`] naming
+//! the expected and found types and where in the value they differ
+//! (`expected float, found string at origin.x`).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::type_builtins::value_type_name;
+use crate::{MapKey, Value};
+
+pub use resilient_derive::{FromResilient, IntoResilient};
+
+/// A Resilient value held by the host.
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct ResilientValue(pub(crate) Value);
+
+impl ResilientValue {
+    /// The interpreter's `value`, lent to the host without a copy.
+    pub(crate) fn from_ref(value: &Value) -> &ResilientValue {
+        // SAFETY: `ResilientValue` is a `repr(transparent)` wrapper
+        // around `Value`, so the two have the same layout.
+        unsafe { &*(value as *const Value as *const ResilientValue) }
+    }
+
+    /// [`ResilientValue::from_ref`] for each of `values`.
+    pub(crate) fn from_slice(values: &[Value]) -> &[ResilientValue] {
+        // SAFETY: as for `from_ref`, element by element.
+        unsafe { &*(values as *const [Value] as *const [ResilientValue]) }
+    }
+
+    /// A struct value; what `#[derive(IntoResilient)]` builds.
+    pub fn new_struct(name: &str, fields: Vec<(&str, ResilientValue)>) -> ResilientValue {
+        let fields = fields
+            .into_iter()
+            .map(|(field, value)| (field.to_string(), value.0))
+            .collect();
+        ResilientValue(Value::Struct {
+            name: name.to_string(),
+            fields: Rc::new(fields),
+        })
+    }
+
+    /// The fields of a struct named `name`, for
+    /// `#[derive(FromResilient)]` to take one by one.
+    pub fn into_struct_fields(self, name: &str) -> Result<StructFields, BridgeError> {
+        match self.0 {
+            Value::Struct {
+                name: found,
+                fields,
+            } if found == name => Ok(StructFields {
+                name: found,
+                fields: Rc::unwrap_or_clone(fields),
+            }),
+            other => Err(BridgeError::mismatch(format!("struct {name}"), &other)),
+        }
+    }
+
+    /// The type name `type_of` gives this value.
+    pub fn type_name(&self) -> &'static str {
+        value_type_name(&self.0)
+    }
+}
+
+/// Printed the way `println` prints it.
+impl fmt::Display for ResilientValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Debug for ResilientValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+/// The fields of a struct value being converted to a Rust struct.
+pub struct StructFields {
+    name: String,
+    fields: Vec<(String, Value)>,
+}
+
+impl StructFields {
+    /// Convert and remove field `field`.
+    pub fn take<T: FromResilient>(&mut self, field: &str) -> Result<T, BridgeError> {
+        let Some(i) = self.fields.iter().position(|(name, _)| name == field) else {
+            return Err(BridgeError::MissingField {
+                struct_name: self.name.clone(),
+                field: field.to_string(),
+            });
+        };
+        let (_, value) = self.fields.swap_remove(i);
+        T::from_resilient(ResilientValue(value)).map_err(|e| e.in_field(field))
+    }
+}
+
+/// Why a conversion or a [`crate::interpreter::Session`] call failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BridgeError {
+    /// The value has another type. `path` locates it inside the value
+    /// converted (`origin.x`, `[2]`), empty for the value itself.
+    Mismatch {
+        expected: String,
+        found: String,
+        path: String,
+    },
+    /// A struct value lacks a field the Rust struct has.
+    MissingField { struct_name: String, field: String },
+    /// The program failed to parse or its top level failed to run.
+    Load(Vec<String>),
+    /// The program defines nothing by that name.
+    UnknownFunction(String),
+    /// The name is bound to something other than a function.
+    NotAFunction { name: String, found: String },
+    /// The call passed a different number of arguments.
+    Arity {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    /// The call raised a runtime error.
+    Runtime(String),
+}
+
+impl BridgeError {
+    fn mismatch(expected: impl Into<String>, found: &Value) -> BridgeError {
+        let found = match found {
+            Value::Struct { name, .. } => format!("struct {name}"),
+            other => value_type_name(other).to_string(),
+        };
+        BridgeError::Mismatch {
+            expected: expected.into(),
+            found,
+            path: String::new(),
+        }
+    }
+
+    /// The same error, located one field further out.
+    fn in_field(self, field: &str) -> BridgeError {
+        self.nested(|path| match path.chars().next() {
+            None | Some('[') => format!("{field}{path}"),
+            Some(_) => format!("{field}.{path}"),
+        })
+    }
+
+    /// The same error, located one element further out.
+    fn in_element(self, index: usize) -> BridgeError {
+        self.nested(|path| match path.chars().next() {
+            None | Some('[') => format!("[{index}]{path}"),
+            Some(_) => format!("[{index}].{path}"),
+        })
+    }
+
+    fn nested(self, outer: impl FnOnce(&str) -> String) -> BridgeError {
+        match self {
+            BridgeError::Mismatch {
+                expected,
+                found,
+                path,
+            } => BridgeError::Mismatch {
+                expected,
+                found,
+                path: outer(&path),
+            },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BridgeError::Mismatch {
+                expected,
+                found,
+                path,
+            } => {
+                write!(f, "expected {expected}, found {found}")?;
+                if !path.is_empty() {
+                    write!(f, " at {path}")?;
+                }
+                Ok(())
+            }
+            BridgeError::MissingField { struct_name, field } => {
+                write!(f, "struct {struct_name} has no field `{field}`")
+            }
+            BridgeError::Load(errors) => write!(f, "{}", errors.join("\n")),
+            BridgeError::UnknownFunction(name) => write!(f, "no function named `{name}`"),
+            BridgeError::NotAFunction { name, found } => {
+                write!(f, "`{name}` is a {found}, not a function")
+            }
+            BridgeError::Arity {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{name}` takes {expected} argument(s), {found} were passed"
+            ),
+            BridgeError::Runtime(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for BridgeError {}
+
+/// A Rust value that converts to a Resilient one.
+pub trait IntoResilient {
+    fn into_resilient(self) -> ResilientValue;
+}
+
+/// A Rust value that can be read back from a Resilient one.
+pub trait FromResilient: Sized {
+    fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError>;
+}
+
+/// The argument list of a call: `()` or a tuple of up to six
+/// [`IntoResilient`] values (`(x,)` for a single argument).
+pub trait IntoArgs {
+    fn into_args(self) -> Vec<ResilientValue>;
+}
+
+impl IntoArgs for Vec<ResilientValue> {
+    fn into_args(self) -> Vec<ResilientValue> {
+        self
+    }
+}
+
+macro_rules! args_tuple {
+    ($($t:ident),*) => {
+        impl<$($t: IntoResilient),*> IntoArgs for ($($t,)*) {
+            #[allow(non_snake_case)]
+            fn into_args(self) -> Vec<ResilientValue> {
+                let ($($t,)*) = self;
+                vec![$($t.into_resilient()),*]
+            }
+        }
+    };
+}
+
+args_tuple!();
+args_tuple!(A);
+args_tuple!(A, B);
+args_tuple!(A, B, C);
+args_tuple!(A, B, C, D);
+args_tuple!(A, B, C, D, E);
+args_tuple!(A, B, C, D, E, F);
+
+impl IntoResilient for ResilientValue {
+    fn into_resilient(self) -> ResilientValue {
+        self
+    }
+}
+
+impl FromResilient for ResilientValue {
+    fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError> {
+        Ok(value)
+    }
+}
+
+/// `Into`/`From` pairs between a Rust type and one `Value` variant.
+macro_rules! scalar {
+    ($t:ty, $variant:ident, $expected:literal) => {
+        impl IntoResilient for $t {
+            fn into_resilient(self) -> ResilientValue {
+                ResilientValue(Value::$variant(self.into()))
+            }
+        }
+
+        impl FromResilient for $t {
+            fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError> {
+                match value.0 {
+                    Value::$variant(v) => Ok(v),
+                    other => Err(BridgeError::mismatch($expected, &other)),
+                }
+            }
+        }
+    };
+}
+
+scalar!(i64, Int, "int");
+scalar!(f64, Float, "float");
+scalar!(bool, Bool, "bool");
+scalar!(char, Char, "char");
+scalar!(String, String, "string");
+
+/// Narrower integers: widened on the way in, range-checked on the way
+/// back.
+macro_rules! narrow_int {
+    ($($t:ty),*) => {$(
+        impl IntoResilient for $t {
+            fn into_resilient(self) -> ResilientValue {
+                ResilientValue(Value::Int(self.into()))
+            }
+        }
+
+        impl FromResilient for $t {
+            fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError> {
+                let n = i64::from_resilient(value)?;
+                <$t>::try_from(n).map_err(|_| BridgeError::Mismatch {
+                    expected: concat!("int in ", stringify!($t), " range").to_string(),
+                    found: n.to_string(),
+                    path: String::new(),
+                })
+            }
+        }
+    )*};
+}
+
+narrow_int!(i8, i16, i32, u8, u16, u32);
+
+impl IntoResilient for f32 {
+    fn into_resilient(self) -> ResilientValue {
+        ResilientValue(Value::Float(self.into()))
+    }
+}
+
+impl FromResilient for f32 {
+    fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError> {
+        f64::from_resilient(value).map(|v| v as f32)
+    }
+}
+
+impl IntoResilient for &str {
+    fn into_resilient(self) -> ResilientValue {
+        ResilientValue(Value::String(self.to_string()))
+    }
+}
+
+impl IntoResilient for () {
+    fn into_resilient(self) -> ResilientValue {
+        ResilientValue(Value::Void)
+    }
+}
+
+impl FromResilient for () {
+    fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError> {
+        match value.0 {
+            Value::Void => Ok(()),
+            other => Err(BridgeError::mismatch("void", &other)),
+        }
+    }
+}
+
+impl<T: IntoResilient> IntoResilient for Vec<T> {
+    fn into_resilient(self) -> ResilientValue {
+        let items: Vec<Value> = self.into_iter().map(|v| v.into_resilient().0).collect();
+        ResilientValue(Value::Array(items.into()))
+    }
+}
+
+impl<T: FromResilient> FromResilient for Vec<T> {
+    fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError> {
+        match value.0 {
+            Value::Array(items) => items
+                .into_inner()
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| T::from_resilient(ResilientValue(v)).map_err(|e| e.in_element(i)))
+                .collect(),
+            other => Err(BridgeError::mismatch("array", &other)),
+        }
+    }
+}
+
+impl<T: IntoResilient> IntoResilient for HashMap<String, T> {
+    fn into_resilient(self) -> ResilientValue {
+        let entries: HashMap<MapKey, Value> = self
+            .into_iter()
+            .map(|(k, v)| (MapKey::Str(k), v.into_resilient().0))
+            .collect();
+        ResilientValue(Value::Map(entries.into()))
+    }
+}
+
+impl<T: FromResilient> FromResilient for HashMap<String, T> {
+    fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError> {
+        let Value::Map(entries) = value.0 else {
+            return Err(BridgeError::mismatch("map", &value.0));
+        };
+        entries
+            .into_inner()
+            .into_iter()
+            .map(|(key, v)| {
+                let MapKey::Str(key) = key else {
+                    return Err(BridgeError::Mismatch {
+                        expected: "string key".to_string(),
+                        found: format!("key {key}"),
+                        path: String::new(),
+                    });
+                };
+                let v = T::from_resilient(ResilientValue(v)).map_err(|e| e.in_field(&key))?;
+                Ok((key, v))
+            })
+            .collect()
+    }
+}
+
+impl<T: IntoResilient> IntoResilient for Option<T> {
+    fn into_resilient(self) -> ResilientValue {
+        ResilientValue(Value::Option(self.map(|v| Box::new(v.into_resilient().0))))
+    }
+}
+
+impl<T: FromResilient> FromResilient for Option<T> {
+    fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError> {
+        match value.0 {
+            Value::Option(inner) => inner
+                .map(|v| T::from_resilient(ResilientValue(*v)))
+                .transpose(),
+            other => Err(BridgeError::mismatch("Option", &other)),
+        }
+    }
+}
+
+impl<T: IntoResilient, E: IntoResilient> IntoResilient for Result<T, E> {
+    fn into_resilient(self) -> ResilientValue {
+        let (ok, payload) = match self {
+            Ok(v) => (true, v.into_resilient()),
+            Err(e) => (false, e.into_resilient()),
+        };
+        ResilientValue(Value::Result {
+            ok,
+            payload: Box::new(payload.0),
+        })
+    }
+}
+
+impl<T: FromResilient, E: FromResilient> FromResilient for Result<T, E> {
+    fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError> {
+        match value.0 {
+            Value::Result { ok: true, payload } => {
+                T::from_resilient(ResilientValue(*payload)).map(Ok)
+            }
+            Value::Result { ok: false, payload } => {
+                E::from_resilient(ResilientValue(*payload)).map(Err)
+            }
+            other => Err(BridgeError::mismatch("Result", &other)),
+        }
+    }
+}
+
+macro_rules! value_tuple {
+    ($len:literal; $($t:ident $i:tt),*) => {
+        impl<$($t: IntoResilient),*> IntoResilient for ($($t,)*) {
+            fn into_resilient(self) -> ResilientValue {
+                ResilientValue(Value::Tuple(vec![$(self.$i.into_resilient().0),*]))
+            }
+        }
+
+        impl<$($t: FromResilient),*> FromResilient for ($($t,)*) {
+            fn from_resilient(value: ResilientValue) -> Result<Self, BridgeError> {
+                match value.0 {
+                    Value::Tuple(items) if items.len() == $len => {
+                        let mut items = items.into_iter();
+                        Ok(($(
+                            $t::from_resilient(ResilientValue(items.next().unwrap_or(Value::Void)))
+                                .map_err(|e| e.in_element($i))?,
+                        )*))
+                    }
+                    other => Err(BridgeError::mismatch(concat!("tuple of ", $len), &other)),
+                }
+            }
+        }
+    };
+}
+
+value_tuple!(2; A 0, B 1);
+value_tuple!(3; A 0, B 1, C 2);
+value_tuple!(4; A 0, B 1, C 2, D 3);
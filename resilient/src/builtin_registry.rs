@@ -0,0 +1,4228 @@
+// Shared builtin registry.
+//
+// Builtins historically lived in three places that drifted apart:
+// the runtime `BUILTINS` table in `lib.rs`, the typechecker's
+// `BUILTIN_ENV` seed in `TypeChecker::new`, and the tree-walker's
+// inline dispatch for callback-taking helpers (`array_map`, ...).
+// A builtin added to one table but not the others either crashed at
+// runtime with "Identifier not found" or was rejected by the checker
+// with "Undefined variable" even though the interpreter could run it
+// (`sort`, `join`, `graph_reverse`, ...).
+//
+// `BUILTINS` below is now the only declaration: one row per builtin
+// giving its name, its checker signature and how it is evaluated.
+//
+// - `register_runtime()` binds every `Native` row in the
+//   interpreter's global environment (`Interpreter::new`), and
+//   `crate::lookup_builtin` resolves the same rows for the VM.
+// - `signatures()` seeds the typechecker's `BUILTIN_ENV`.
+// - `Intrinsic` rows have no function pointer: the tree-walker
+//   evaluates them inline because they call back into user closures.
+//
+// Signatures are written in the checker's own `Display` syntax
+// (`fn(array, int) -> array`) and parsed once, when `BUILTIN_ENV` is
+// first built. The unit tests pin that every row parses back to the
+// exact text it was written as.
+
+use crate::typechecker::Type;
+use crate::{BuiltinFn, Value};
+use BuiltinEval::{Intrinsic, Native};
+
+/// How a registered builtin is evaluated.
+#[derive(Clone, Copy)]
+pub(crate) enum BuiltinEval {
+    /// Plain function pointer, shared by the tree-walker and the VM.
+    Native(BuiltinFn),
+    /// Evaluated inline by the tree-walker's call path.
+    Intrinsic,
+}
+
+/// Canonical list of every builtin visible in a fresh Resilient
+/// program: `(name, signature, evaluator)`.
+///
+/// RES-4000: `__`-prefixed entries are VM-internal dispatch targets
+/// and are never bound as identifiers or seen by the checker.
+pub(crate) const BUILTINS: &[(&str, &str, BuiltinEval)] = &[
+    ("println", "fn(any) -> void", Native(crate::builtin_println)),
+    ("print", "fn(any) -> void", Native(crate::builtin_print)),
+    // RES-144: single-line stdin read. std-only.
+    (
+        "input",
+        "fn(string) -> string",
+        Native(crate::builtin_input),
+    ),
+    (
+        "read_line",
+        "fn() -> string",
+        Native(crate::builtin_read_line),
+    ),
+    ("read_int", "fn() -> int", Native(crate::builtin_read_int)),
+    // Structured logging to stderr; see `logging`.
+    (
+        "log_debug",
+        "fn(string, any) -> void",
+        Native(crate::logging::builtin_log_debug),
+    ),
+    (
+        "log_info",
+        "fn(string, any) -> void",
+        Native(crate::logging::builtin_log_info),
+    ),
+    (
+        "log_warn",
+        "fn(string, any) -> void",
+        Native(crate::logging::builtin_log_warn),
+    ),
+    (
+        "log_error",
+        "fn(string, any) -> void",
+        Native(crate::logging::builtin_log_error),
+    ),
+    (
+        "set_log_level",
+        "fn(string) -> string",
+        Native(crate::logging::builtin_set_log_level),
+    ),
+    // RES-1100: expose the compiler version to programs so build
+    // manifests and provenance certificates can pin the toolchain
+    // they were produced by.
+    ("version", "fn() -> string", Native(crate::builtin_version)),
+    // RES-2610: compile-time file embedding.
+    (
+        "include_str",
+        "fn(string) -> string",
+        Native(crate::builtin_include_str),
+    ),
+    (
+        "include_bytes",
+        "fn(string) -> array",
+        Native(crate::builtin_include_bytes),
+    ),
+    ("abs", "fn(any) -> any", Native(crate::builtin_abs)),
+    // RES-410: sign(x) — -1, 0, +1 for negative/zero/positive.
+    ("sign", "fn(any) -> int", Native(crate::builtin_sign)),
+    // RES-411: float predicates per IEEE 754.
+    ("is_nan", "fn(any) -> bool", Native(crate::builtin_is_nan)),
+    ("is_inf", "fn(any) -> bool", Native(crate::builtin_is_inf)),
+    (
+        "is_finite",
+        "fn(any) -> bool",
+        Native(crate::builtin_is_finite),
+    ),
+    ("min", "fn(any, any) -> any", Native(crate::builtin_min)),
+    ("max", "fn(any, any) -> any", Native(crate::builtin_max)),
+    // RES-415: integer gcd/lcm.
+    ("gcd", "fn(int, int) -> int", Native(crate::builtin_gcd)),
+    ("lcm", "fn(int, int) -> int", Native(crate::builtin_lcm)),
+    // RES-536: gcd / lcm reduction over an integer array.
+    (
+        "gcd_array",
+        "fn(any) -> int",
+        Native(crate::builtin_gcd_array),
+    ),
+    (
+        "lcm_array",
+        "fn(any) -> int",
+        Native(crate::builtin_lcm_array),
+    ),
+    // RES-567: factorial with overflow detection.
+    (
+        "factorial",
+        "fn(int) -> int",
+        Native(crate::builtin_factorial),
+    ),
+    // RES-568: binomial coefficient C(n, k).
+    (
+        "binomial",
+        "fn(int, int) -> int",
+        Native(crate::builtin_binomial),
+    ),
+    // RES-569: n-th Fibonacci number with overflow detection.
+    (
+        "fibonacci",
+        "fn(int) -> int",
+        Native(crate::builtin_fibonacci),
+    ),
+    // RES-570: trial-division primality test.
+    (
+        "is_prime",
+        "fn(int) -> bool",
+        Native(crate::builtin_is_prime),
+    ),
+    // RES-571: smallest prime greater than n.
+    (
+        "next_prime",
+        "fn(int) -> int",
+        Native(crate::builtin_next_prime),
+    ),
+    // RES-295: clamp(x, lo, hi) — restrict to [lo, hi]; Err if lo > hi.
+    (
+        "clamp",
+        "fn(any, any, any) -> any",
+        Native(crate::builtin_clamp),
+    ),
+    // RES-130: explicit int ↔ float conversions.
+    (
+        "to_float",
+        "fn(any) -> float",
+        Native(crate::builtin_to_float),
+    ),
+    ("to_int", "fn(any) -> int", Native(crate::builtin_to_int)),
+    // RES-2618: f32/f64 precision casts.
+    (
+        "as_f32",
+        "fn(any) -> f32",
+        Native(crate::float32::builtin_as_f32),
+    ),
+    (
+        "as_f64",
+        "fn(any) -> float",
+        Native(crate::float32::builtin_as_f64),
+    ),
+    // RES-366: pinned-width integer casts. Wrapping truncation.
+    ("as_int8", "fn(any) -> Int8", Native(crate::builtin_as_int8)),
+    (
+        "as_int16",
+        "fn(any) -> Int16",
+        Native(crate::builtin_as_int16),
+    ),
+    (
+        "as_int32",
+        "fn(any) -> Int32",
+        Native(crate::builtin_as_int32),
+    ),
+    (
+        "as_int64",
+        "fn(any) -> int",
+        Native(crate::builtin_as_int64),
+    ),
+    (
+        "as_uint8",
+        "fn(any) -> UInt8",
+        Native(crate::builtin_as_uint8),
+    ),
+    (
+        "as_uint16",
+        "fn(any) -> UInt16",
+        Native(crate::builtin_as_uint16),
+    ),
+    (
+        "as_uint32",
+        "fn(any) -> UInt32",
+        Native(crate::builtin_as_uint32),
+    ),
+    (
+        "as_uint64",
+        "fn(any) -> UInt64",
+        Native(crate::builtin_as_uint64),
+    ),
+    // RES-138: read the current retry count inside a live block.
+    (
+        "live_retries",
+        "fn() -> int",
+        Native(crate::builtin_live_retries),
+    ),
+    // RES-141: process-wide live-block telemetry.
+    (
+        "live_total_retries",
+        "fn() -> int",
+        Native(crate::builtin_live_total_retries),
+    ),
+    (
+        "live_total_exhaustions",
+        "fn() -> int",
+        Native(crate::builtin_live_total_exhaustions),
+    ),
+    ("sqrt", "fn(any) -> float", Native(crate::builtin_sqrt)),
+    ("pow", "fn(any, any) -> any", Native(crate::builtin_pow)),
+    ("floor", "fn(any) -> float", Native(crate::builtin_floor)),
+    ("ceil", "fn(any) -> float", Native(crate::builtin_ceil)),
+    // RES-146: transcendentals. std-only; float-in/float-out per
+    // RES-130's no-implicit-coercion policy.
+    ("sin", "fn(float) -> float", Native(crate::builtin_sin)),
+    ("cos", "fn(float) -> float", Native(crate::builtin_cos)),
+    ("tan", "fn(float) -> float", Native(crate::builtin_tan)),
+    // RES-894.
+    (
+        "to_radians",
+        "fn(float) -> float",
+        Native(crate::builtin_to_radians),
+    ),
+    // RES-895.
+    (
+        "to_degrees",
+        "fn(float) -> float",
+        Native(crate::builtin_to_degrees),
+    ),
+    // RES-295: atan2(y, x) — angle of vector (x, y) in radians.
+    (
+        "atan2",
+        "fn(float, float) -> float",
+        Native(crate::builtin_atan2),
+    ),
+    // RES-892.
+    (
+        "hypot",
+        "fn(float, float) -> float",
+        Native(crate::builtin_hypot),
+    ),
+    // RES-893.
+    (
+        "copysign",
+        "fn(float, float) -> float",
+        Native(crate::builtin_copysign),
+    ),
+    ("ln", "fn(float) -> float", Native(crate::builtin_ln)),
+    // RES-889.
+    ("log10", "fn(float) -> float", Native(crate::builtin_log10)),
+    // RES-890.
+    ("log2", "fn(float) -> float", Native(crate::builtin_log2)),
+    (
+        "log",
+        "fn(float, float) -> float",
+        Native(crate::builtin_log),
+    ),
+    ("exp", "fn(float) -> float", Native(crate::builtin_exp)),
+    // RES-891.
+    ("exp2", "fn(float) -> float", Native(crate::builtin_exp2)),
+    // RES-896.
+    ("sinh", "fn(float) -> float", Native(crate::builtin_sinh)),
+    // RES-897.
+    ("cosh", "fn(float) -> float", Native(crate::builtin_cosh)),
+    // RES-898.
+    ("tanh", "fn(float) -> float", Native(crate::builtin_tanh)),
+    // RES-899.
+    ("asinh", "fn(float) -> float", Native(crate::builtin_asinh)),
+    // RES-900.
+    ("acosh", "fn(float) -> float", Native(crate::builtin_acosh)),
+    // RES-901.
+    ("atanh", "fn(float) -> float", Native(crate::builtin_atanh)),
+    // RES-902.
+    ("asin", "fn(float) -> float", Native(crate::builtin_asin)),
+    // RES-903.
+    ("acos", "fn(float) -> float", Native(crate::builtin_acos)),
+    // RES-904.
+    ("atan", "fn(float) -> float", Native(crate::builtin_atan)),
+    // RES-905.
+    ("cbrt", "fn(float) -> float", Native(crate::builtin_cbrt)),
+    // RES-907: bit-counting integer builtins. Useful for register-bit
+    // decoding, bit-mask iteration, and log₂-of-power-of-two probes.
+    (
+        "count_ones",
+        "fn(int) -> int",
+        Native(crate::builtin_count_ones),
+    ),
+    (
+        "count_zeros",
+        "fn(int) -> int",
+        Native(crate::builtin_count_zeros),
+    ),
+    (
+        "leading_zeros",
+        "fn(int) -> int",
+        Native(crate::builtin_leading_zeros),
+    ),
+    (
+        "trailing_zeros",
+        "fn(int) -> int",
+        Native(crate::builtin_trailing_zeros),
+    ),
+    // RES-147: monotonic ms clock, std-only.
+    ("clock_ms", "fn() -> int", Native(crate::builtin_clock_ms)),
+    // RES-358: monotonic ns clock builtins. @io (non-pure).
+    // Embedded targets stub: a DWT-based implementation is future work.
+    ("clock_now", "fn() -> int", Native(crate::builtin_clock_now)),
+    (
+        "clock_elapsed",
+        "fn(int) -> int",
+        Native(crate::builtin_clock_elapsed),
+    ),
+    // Grand-Implementation Pass 2 — Subsystem A: tick clock + event journal.
+    // Deterministic structural replay as a core-stdlib primitive.
+    (
+        "tick_now",
+        "fn() -> int",
+        Native(crate::event_journal::builtin_tick_now),
+    ),
+    (
+        "tick_advance",
+        "fn(int) -> int",
+        Native(crate::event_journal::builtin_tick_advance),
+    ),
+    (
+        "record_event",
+        "fn(string, int) -> int",
+        Native(crate::event_journal::builtin_record_event),
+    ),
+    (
+        "replay_events",
+        "fn() -> array",
+        Native(crate::event_journal::builtin_replay_events),
+    ),
+    (
+        "clear_events",
+        "fn() -> int",
+        Native(crate::event_journal::builtin_clear_events),
+    ),
+    (
+        "event_count",
+        "fn() -> int",
+        Native(crate::event_journal::builtin_event_count),
+    ),
+    // Grand-Implementation Pass 2 — Subsystem B: runtime provenance.
+    // `tag(value, "source") -> Tagged` wraps an Int with a provenance string.
+    // `untag(t, "expected") -> Result<Int>` enforces source on extract.
+    // `tag_of(t) -> Result<String>` reads the source non-destructively.
+    (
+        "tag",
+        "fn(int, string) -> array",
+        Native(crate::provenance::builtin_tag),
+    ),
+    (
+        "untag",
+        "fn(any, string) -> any",
+        Native(crate::provenance::builtin_untag),
+    ),
+    (
+        "tag_of",
+        "fn(any) -> any",
+        Native(crate::provenance::builtin_tag_of),
+    ),
+    // Grand-Implementation Pass 2 — Subsystem C: named snapshots.
+    // Programmer-addressable in-process checkpoints, bounded to MAX_SNAPSHOTS.
+    (
+        "snapshot_save",
+        "fn(string, int) -> int",
+        Native(crate::snapshot_store::builtin_snapshot_save),
+    ),
+    (
+        "snapshot_load",
+        "fn(string) -> any",
+        Native(crate::snapshot_store::builtin_snapshot_load),
+    ),
+    (
+        "snapshot_keys",
+        "fn() -> array",
+        Native(crate::snapshot_store::builtin_snapshot_keys),
+    ),
+    (
+        "snapshot_clear",
+        "fn(string) -> any",
+        Native(crate::snapshot_store::builtin_snapshot_clear),
+    ),
+    // Grand-Implementation Pass 3 — Subsystem D: named resource quotas.
+    (
+        "quota_set",
+        "fn(string, int) -> int",
+        Native(crate::quotas::builtin_quota_set),
+    ),
+    (
+        "quota_charge",
+        "fn(string, int) -> bool",
+        Native(crate::quotas::builtin_quota_charge),
+    ),
+    (
+        "quota_remaining",
+        "fn(string) -> int",
+        Native(crate::quotas::builtin_quota_remaining),
+    ),
+    (
+        "quota_reset",
+        "fn(string) -> int",
+        Native(crate::quotas::builtin_quota_reset),
+    ),
+    (
+        "quota_used",
+        "fn(string) -> int",
+        Native(crate::quotas::builtin_quota_used),
+    ),
+    (
+        "quotas",
+        "fn() -> array",
+        Native(crate::quotas::builtin_quotas),
+    ),
+    // Grand-Implementation Pass 3 — Subsystem E: capability tokens.
+    (
+        "mint_cap",
+        "fn(string) -> string",
+        Native(crate::capabilities::builtin_mint_cap),
+    ),
+    (
+        "check_cap",
+        "fn(string, string) -> bool",
+        Native(crate::capabilities::builtin_check_cap),
+    ),
+    (
+        "revoke_cap",
+        "fn(string) -> bool",
+        Native(crate::capabilities::builtin_revoke_cap),
+    ),
+    (
+        "caps",
+        "fn() -> array",
+        Native(crate::capabilities::builtin_caps),
+    ),
+    // RES-150: seedable SplitMix64 random builtins. std-only.
+    (
+        "random_int",
+        "fn(int, int) -> int",
+        Native(crate::builtin_random_int),
+    ),
+    (
+        "random_float",
+        "fn() -> float",
+        Native(crate::builtin_random_float),
+    ),
+    ("len", "fn(any) -> int", Native(crate::builtin_len)),
+    (
+        "push",
+        "fn(array, any) -> array",
+        Native(crate::builtin_push),
+    ),
+    ("pop", "fn(array) -> array", Native(crate::builtin_pop)),
+    (
+        "slice",
+        "fn(array, int, int) -> array",
+        Native(crate::builtin_slice),
+    ),
+    (
+        "split",
+        "fn(string, string) -> array",
+        Native(crate::builtin_split),
+    ),
+    // RES-1859: explicit-name alias.
+    (
+        "string_split",
+        "fn(string, string) -> array",
+        Native(crate::builtin_split),
+    ),
+    // RES-535: split with a maximum number-of-splits limit.
+    (
+        "string_split_n",
+        "fn(string, string, int) -> array",
+        Native(crate::builtin_string_split_n),
+    ),
+    // RES-545: split on the last occurrence of the separator.
+    (
+        "string_split_last",
+        "fn(string, string) -> array",
+        Native(crate::builtin_string_split_last),
+    ),
+    ("trim", "fn(string) -> string", Native(crate::builtin_trim)),
+    (
+        "contains",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_contains),
+    ),
+    // RES-2612 Task 6: intern(string) -> string for runtime deduplication.
+    (
+        "intern",
+        "fn(string) -> string",
+        Native(crate::builtin_intern),
+    ),
+    (
+        "to_upper",
+        "fn(string) -> string",
+        Native(crate::builtin_to_upper),
+    ),
+    (
+        "to_lower",
+        "fn(string) -> string",
+        Native(crate::builtin_to_lower),
+    ),
+    // RES-412: reverse a string (Unicode-aware) or an array (clones elements).
+    (
+        "string_reverse",
+        "fn(string) -> string",
+        Native(crate::builtin_string_reverse),
+    ),
+    (
+        "array_reverse",
+        "fn(array) -> array",
+        Native(crate::builtin_array_reverse),
+    ),
+    // RES-416: integer-array reductions (identity 0 / 1 for empty arrays).
+    (
+        "array_sum",
+        "fn(any) -> int",
+        Native(crate::builtin_array_sum),
+    ),
+    (
+        "array_product",
+        "fn(any) -> int",
+        Native(crate::builtin_array_product),
+    ),
+    // RES-417: min/max over an integer array. Empty array errors.
+    (
+        "array_min",
+        "fn(any) -> int",
+        Native(crate::builtin_array_min),
+    ),
+    (
+        "array_max",
+        "fn(any) -> int",
+        Native(crate::builtin_array_max),
+    ),
+    // RES-543: empty-safe min/max with caller-supplied default.
+    (
+        "array_max_or",
+        "fn(any, int) -> int",
+        Native(crate::builtin_array_max_or),
+    ),
+    (
+        "array_min_or",
+        "fn(any, int) -> int",
+        Native(crate::builtin_array_min_or),
+    ),
+    // RES-549: integer mean (truncating toward zero).
+    (
+        "array_mean_int",
+        "fn(any) -> int",
+        Native(crate::builtin_array_mean_int),
+    ),
+    // RES-550: integer median (sorts a clone; truncating mean for even len).
+    (
+        "array_median_int",
+        "fn(any) -> int",
+        Native(crate::builtin_array_median_int),
+    ),
+    // RES-551: integer mode (most-common; smallest on ties).
+    (
+        "array_mode_int",
+        "fn(any) -> int",
+        Native(crate::builtin_array_mode_int),
+    ),
+    // RES-552: peak-to-peak range (max − min) of an int array.
+    (
+        "array_range_int",
+        "fn(any) -> int",
+        Native(crate::builtin_array_range_int),
+    ),
+    // RES-553: consecutive pairwise differences arr[i+1] - arr[i].
+    (
+        "array_diff_consec_int",
+        "fn(any) -> array",
+        Native(crate::builtin_array_diff_consec_int),
+    ),
+    // RES-554: per-element clamp to [lo, hi].
+    (
+        "array_clamp_int",
+        "fn(any, int, int) -> array",
+        Native(crate::builtin_array_clamp_int),
+    ),
+    // RES-555: per-element sign (-1 / 0 / 1).
+    (
+        "array_signum_int",
+        "fn(any) -> array",
+        Native(crate::builtin_array_signum_int),
+    ),
+    // RES-556: per-element absolute value.
+    (
+        "array_abs_int",
+        "fn(any) -> array",
+        Native(crate::builtin_array_abs_int),
+    ),
+    // RES-557: dot product of two equal-length int arrays.
+    (
+        "array_dot_int",
+        "fn(any, any) -> int",
+        Native(crate::builtin_array_dot_int),
+    ),
+    // RES-558: sum of squares (Σ x²) of an int array.
+    (
+        "array_sum_squares_int",
+        "fn(any) -> int",
+        Native(crate::builtin_array_sum_squares_int),
+    ),
+    // RES-559: running prefix sum.
+    (
+        "array_cumsum_int",
+        "fn(any) -> array",
+        Native(crate::builtin_array_cumsum_int),
+    ),
+    // RES-560: running max.
+    (
+        "array_cummax_int",
+        "fn(any) -> array",
+        Native(crate::builtin_array_cummax_int),
+    ),
+    // RES-561: running min.
+    (
+        "array_cummin_int",
+        "fn(any) -> array",
+        Native(crate::builtin_array_cummin_int),
+    ),
+    // RES-562: running product.
+    (
+        "array_cumprod_int",
+        "fn(any) -> array",
+        Native(crate::builtin_array_cumprod_int),
+    ),
+    // RES-563: count elements in inclusive [lo, hi].
+    (
+        "array_count_in_range_int",
+        "fn(any, int, int) -> int",
+        Native(crate::builtin_array_count_in_range_int),
+    ),
+    // RES-503: index of max / min element (first-occurrence on ties).
+    (
+        "array_argmax_int",
+        "fn(any) -> int",
+        Native(crate::builtin_array_argmax_int),
+    ),
+    (
+        "array_argmin_int",
+        "fn(any) -> int",
+        Native(crate::builtin_array_argmin_int),
+    ),
+    // RES-418: element search over an array.
+    (
+        "array_contains",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_array_contains),
+    ),
+    (
+        "array_index_of",
+        "fn(any, any) -> int",
+        Native(crate::builtin_array_index_of),
+    ),
+    // RES-544: every index where element equals x.
+    (
+        "array_index_of_all",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_index_of_all),
+    ),
+    // RES-541: set-like operations on arrays.
+    (
+        "array_intersect",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_intersect),
+    ),
+    (
+        "array_diff",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_diff),
+    ),
+    // RES-542: order-preserving global-dedup union.
+    (
+        "array_union",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_union),
+    ),
+    // RES-419: Unicode-scalar ↔ single-char string conversions.
+    ("chr", "fn(int) -> string", Native(crate::builtin_chr)),
+    ("ord", "fn(string) -> int", Native(crate::builtin_ord)),
+    // RES-505: parse single char to base-36 digit value.
+    (
+        "char_to_digit",
+        "fn(string) -> int",
+        Native(crate::builtin_char_to_digit),
+    ),
+    // RES-513: int 0..=35 → base-36 digit char.
+    (
+        "digit_to_char",
+        "fn(int) -> string",
+        Native(crate::builtin_digit_to_char),
+    ),
+    // RES-420: concatenate two arrays.
+    (
+        "array_concat",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_concat),
+    ),
+    // RES-515: three-way concatenation.
+    (
+        "array_concat3",
+        "fn(any, any, any) -> array",
+        Native(crate::builtin_array_concat3),
+    ),
+    // RES-421: take/drop first n elements.
+    (
+        "array_take",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_take),
+    ),
+    (
+        "array_drop",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_drop),
+    ),
+    // RES-537: take/drop trailing n elements.
+    (
+        "array_take_last",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_take_last),
+    ),
+    (
+        "array_drop_last",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_drop_last),
+    ),
+    // RES-422: ascending sort over an integer array.
+    (
+        "array_sort",
+        "fn(array) -> array",
+        Native(crate::builtin_array_sort),
+    ),
+    // RES-443: descending sort.
+    (
+        "array_sort_desc",
+        "fn(array) -> array",
+        Native(crate::builtin_array_sort_desc),
+    ),
+    // RES-444: Fisher-Yates random permutation.
+    (
+        "array_shuffle",
+        "fn(array) -> array",
+        Native(crate::builtin_array_shuffle),
+    ),
+    // RES-445: array prefix / suffix predicates.
+    (
+        "array_starts_with",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_array_starts_with),
+    ),
+    (
+        "array_ends_with",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_array_ends_with),
+    ),
+    // RES-446: all byte indices of substring occurrences.
+    (
+        "string_find_all",
+        "fn(any, any) -> array",
+        Native(crate::builtin_string_find_all),
+    ),
+    // RES-546: first byte index of substring, -1 if missing.
+    (
+        "string_find",
+        "fn(string, string) -> int",
+        Native(crate::builtin_string_find),
+    ),
+    // RES-547: last byte index of substring, -1 if missing.
+    (
+        "string_rfind",
+        "fn(string, string) -> int",
+        Native(crate::builtin_string_rfind),
+    ),
+    // RES-548: split string at a byte index into [before, after].
+    (
+        "string_split_at",
+        "fn(string, int) -> array",
+        Native(crate::builtin_string_split_at),
+    ),
+    // RES-447: i64 boundary constants as zero-arg functions.
+    ("int_min", "fn() -> int", Native(crate::builtin_int_min)),
+    ("int_max", "fn() -> int", Native(crate::builtin_int_max)),
+    // RES-448: array_index_of starting at a given offset.
+    (
+        "array_position",
+        "fn(any, any, int) -> int",
+        Native(crate::builtin_array_position),
+    ),
+    // RES-449: pad an array to a given length with fill value.
+    (
+        "array_pad_left",
+        "fn(any, int, any) -> any",
+        Native(crate::builtin_array_pad_left),
+    ),
+    (
+        "array_pad_right",
+        "fn(any, int, any) -> any",
+        Native(crate::builtin_array_pad_right),
+    ),
+    // RES-450: exchange two elements; returns a new array.
+    (
+        "array_swap",
+        "fn(any, int, int) -> array",
+        Native(crate::builtin_array_swap),
+    ),
+    // RES-451: insert / remove element at index; returns a new array.
+    (
+        "array_insert_at",
+        "fn(any, int, any) -> array",
+        Native(crate::builtin_array_insert_at),
+    ),
+    (
+        "array_remove_at",
+        "fn(any, int) -> array",
+        Native(crate::builtin_array_remove_at),
+    ),
+    // RES-452: replace element at index; returns a new array.
+    (
+        "array_set_at",
+        "fn(any, int, any) -> array",
+        Native(crate::builtin_array_set_at),
+    ),
+    // RES-453: total Unicode-scalar accessor (errors on bad index).
+    (
+        "string_at",
+        "fn(string, int) -> string",
+        Native(crate::builtin_string_at),
+    ),
+    // RES-454: Unicode-scalar substring [start, end).
+    (
+        "string_substring",
+        "fn(string, int, int) -> string",
+        Native(crate::builtin_string_substring),
+    ),
+    // RES-455: sliding fixed-size windows.
+    (
+        "array_window",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_window),
+    ),
+    // RES-457: ASCII first-char upper, rest lower.
+    (
+        "string_capitalize",
+        "fn(string) -> string",
+        Native(crate::builtin_string_capitalize),
+    ),
+    // RES-458: repeat an array n times.
+    (
+        "array_cycle",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_cycle),
+    ),
+    // RES-459: ASCII-class string predicates.
+    (
+        "is_ascii_alpha",
+        "fn(string) -> bool",
+        Native(crate::builtin_is_ascii_alpha),
+    ),
+    (
+        "is_ascii_digit",
+        "fn(string) -> bool",
+        Native(crate::builtin_is_ascii_digit),
+    ),
+    (
+        "is_ascii_alnum",
+        "fn(string) -> bool",
+        Native(crate::builtin_is_ascii_alnum),
+    ),
+    // RES-460: trim arbitrary char set from both sides.
+    (
+        "trim_chars",
+        "fn(string, string) -> string",
+        Native(crate::builtin_trim_chars),
+    ),
+    // RES-461: indent every line with n ASCII spaces.
+    (
+        "string_indent",
+        "fn(string, int) -> string",
+        Native(crate::builtin_string_indent),
+    ),
+    // RES-462: adjacent pairs as tuples.
+    (
+        "array_pairs",
+        "fn(array) -> array",
+        Native(crate::builtin_array_pairs),
+    ),
+    // RES-463: UTF-8 byte length of a string.
+    (
+        "string_bytes_len",
+        "fn(string) -> int",
+        Native(crate::builtin_string_bytes_len),
+    ),
+    // RES-564: byte at index (-1 if out of range).
+    (
+        "string_byte_at",
+        "fn(string, int) -> int",
+        Native(crate::builtin_string_byte_at),
+    ),
+    // RES-565: string → array of UTF-8 bytes.
+    (
+        "string_to_bytes",
+        "fn(string) -> array",
+        Native(crate::builtin_string_to_bytes),
+    ),
+    // RES-566: array of bytes → Result<String, String> (UTF-8 validated).
+    (
+        "string_from_bytes",
+        "fn(any) -> Result",
+        Native(crate::builtin_string_from_bytes),
+    ),
+    // RES-464: parse int with explicit radix.
+    (
+        "parse_int_base",
+        "fn(string, int) -> Result",
+        Native(crate::builtin_parse_int_base),
+    ),
+    // RES-465: render int with explicit radix.
+    (
+        "int_to_base",
+        "fn(int, int) -> string",
+        Native(crate::builtin_int_to_base),
+    ),
+    // RES-466: remove the first element matching x.
+    (
+        "array_remove",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_remove),
+    ),
+    // RES-467: remove every element matching x.
+    (
+        "array_remove_all",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_remove_all),
+    ),
+    // RES-468: collapse adjacent duplicates.
+    (
+        "array_dedup",
+        "fn(array) -> array",
+        Native(crate::builtin_array_dedup),
+    ),
+    // RES-504: partition into maximal runs of equal elements.
+    (
+        "array_group_by_int",
+        "fn(any) -> array",
+        Native(crate::builtin_array_group_by_int),
+    ),
+    // RES-533: count of maximal runs of equal adjacent elements.
+    (
+        "array_count_runs",
+        "fn(any) -> int",
+        Native(crate::builtin_array_count_runs),
+    ),
+    // RES-469: scalar all/any equality predicates.
+    (
+        "array_all_eq",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_array_all_eq),
+    ),
+    (
+        "array_any_eq",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_array_any_eq),
+    ),
+    // RES-471: conditional prefix/suffix strippers.
+    (
+        "string_strip_prefix",
+        "fn(string, string) -> string",
+        Native(crate::builtin_string_strip_prefix),
+    ),
+    (
+        "string_strip_suffix",
+        "fn(string, string) -> string",
+        Native(crate::builtin_string_strip_suffix),
+    ),
+    // RES-472: element-wise array equality.
+    (
+        "array_eq",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_array_eq),
+    ),
+    // RES-473: ternary numeric min/max.
+    (
+        "min3",
+        "fn(any, any, any) -> any",
+        Native(crate::builtin_min3),
+    ),
+    (
+        "max3",
+        "fn(any, any, any) -> any",
+        Native(crate::builtin_max3),
+    ),
+    // RES-474: element-wise array inequality.
+    (
+        "array_ne",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_array_ne),
+    ),
+    // RES-475: fixed-op integer fold with explicit init.
+    (
+        "array_fold_int",
+        "fn(any, int, string) -> int",
+        Native(crate::builtin_array_fold_int),
+    ),
+    // RES-502: running-fold (intermediate accumulators).
+    (
+        "array_scan_int",
+        "fn(any, int, string) -> array",
+        Native(crate::builtin_array_scan_int),
+    ),
+    // RES-521: element-wise binary op on two int arrays.
+    (
+        "array_zip_with_int",
+        "fn(any, any, string) -> array",
+        Native(crate::builtin_array_zip_with_int),
+    ),
+    // RES-477: one-sided char-set trimmers.
+    (
+        "trim_start_chars",
+        "fn(string, string) -> string",
+        Native(crate::builtin_trim_start_chars),
+    ),
+    (
+        "trim_end_chars",
+        "fn(string, string) -> string",
+        Native(crate::builtin_trim_end_chars),
+    ),
+    // RES-478: explicit-name alias for array_count.
+    (
+        "array_count_eq",
+        "fn(any, any) -> int",
+        Native(crate::builtin_array_count_eq),
+    ),
+    // RES-479: is_empty / is_blank string predicates.
+    (
+        "is_empty",
+        "fn(string) -> bool",
+        Native(crate::builtin_is_empty),
+    ),
+    (
+        "is_blank",
+        "fn(string) -> bool",
+        Native(crate::builtin_is_blank),
+    ),
+    // RES-480: replace only the first occurrence.
+    (
+        "string_replace_first",
+        "fn(string, string, string) -> string",
+        Native(crate::builtin_string_replace_first),
+    ),
+    // RES-481: drop first / drop last; empty stays empty.
+    (
+        "array_rest",
+        "fn(array) -> array",
+        Native(crate::builtin_array_rest),
+    ),
+    (
+        "array_init",
+        "fn(array) -> array",
+        Native(crate::builtin_array_init),
+    ),
+    // RES-482: replace up to n occurrences.
+    (
+        "string_replace_n",
+        "fn(string, string, string, int) -> string",
+        Native(crate::builtin_string_replace_n),
+    ),
+    // RES-483: named-predicate take/drop on int arrays.
+    (
+        "array_take_while_int",
+        "fn(any, string) -> any",
+        Native(crate::builtin_array_take_while_int),
+    ),
+    (
+        "array_drop_while_int",
+        "fn(any, string) -> any",
+        Native(crate::builtin_array_drop_while_int),
+    ),
+    // RES-484: named-predicate filter / partition on int arrays.
+    (
+        "array_filter_int",
+        "fn(any, string) -> any",
+        Native(crate::builtin_array_filter_int),
+    ),
+    (
+        "array_partition_int",
+        "fn(any, string) -> any",
+        Native(crate::builtin_array_partition_int),
+    ),
+    // RES-500: named-predicate any/some on int arrays.
+    (
+        "array_any_int",
+        "fn(any, string) -> bool",
+        Native(crate::builtin_array_any_int),
+    ),
+    // RES-501: named-predicate all/every on int arrays.
+    (
+        "array_all_int",
+        "fn(any, string) -> bool",
+        Native(crate::builtin_array_all_int),
+    ),
+    // RES-530: named-predicate count on int arrays.
+    (
+        "array_count_int",
+        "fn(any, string) -> int",
+        Native(crate::builtin_array_count_int),
+    ),
+    // RES-539: indices of int elements matching named predicate.
+    (
+        "array_indices_where",
+        "fn(any, string) -> array",
+        Native(crate::builtin_array_indices_where),
+    ),
+    // RES-485: |a - b|.
+    (
+        "abs_diff",
+        "fn(int, int) -> int",
+        Native(crate::builtin_abs_diff),
+    ),
+    // RES-406: volatile MMIO intrinsics. Calls outside an `unsafe`
+    // block are rejected by the typechecker.
+    (
+        "volatile_read_u8",
+        "fn(any) -> int",
+        Native(crate::volatile::volatile_read_u8),
+    ),
+    (
+        "volatile_read_u16",
+        "fn(any) -> int",
+        Native(crate::volatile::volatile_read_u16),
+    ),
+    (
+        "volatile_read_u32",
+        "fn(any) -> int",
+        Native(crate::volatile::volatile_read_u32),
+    ),
+    (
+        "volatile_read_u64",
+        "fn(any) -> int",
+        Native(crate::volatile::volatile_read_u64),
+    ),
+    (
+        "volatile_write_u8",
+        "fn(any, any) -> void",
+        Native(crate::volatile::volatile_write_u8),
+    ),
+    (
+        "volatile_write_u16",
+        "fn(any, any) -> void",
+        Native(crate::volatile::volatile_write_u16),
+    ),
+    (
+        "volatile_write_u32",
+        "fn(any, any) -> void",
+        Native(crate::volatile::volatile_write_u32),
+    ),
+    (
+        "volatile_write_u64",
+        "fn(any, any) -> void",
+        Native(crate::volatile::volatile_write_u64),
+    ),
+    // Register I/O through a device file, inside `unsafe` and an
+    // address allowlist (`unsafe-io` feature).
+    (
+        "mmio_read",
+        "fn(int) -> int",
+        Native(crate::mmio::builtin_mmio_read),
+    ),
+    (
+        "mmio_write",
+        "fn(int, int) -> void",
+        Native(crate::mmio::builtin_mmio_write),
+    ),
+    // RES-486: (quotient, remainder) tuple.
+    (
+        "divmod",
+        "fn(int, int) -> array",
+        Native(crate::builtin_divmod),
+    ),
+    // RES-423: flatten one level of nesting.
+    (
+        "array_flatten",
+        "fn(array) -> array",
+        Native(crate::builtin_array_flatten),
+    ),
+    // RES-424: join a string array with a separator.
+    (
+        "array_join",
+        "fn(any, string) -> string",
+        Native(crate::builtin_array_join),
+    ),
+    // RES-2734: short-name aliases so `arr.sort()`, `arr.join(sep)`, etc.
+    // work as dot-call methods via the Array method dispatch allowlist.
+    // These also serve as convenient global function aliases.
+    (
+        "sort",
+        "fn(array) -> array",
+        Native(crate::builtin_array_sort),
+    ),
+    (
+        "sort_desc",
+        "fn(array) -> array",
+        Native(crate::builtin_array_sort_desc),
+    ),
+    (
+        "reverse",
+        "fn(array) -> array",
+        Native(crate::builtin_array_reverse),
+    ),
+    (
+        "join",
+        "fn(any, string) -> string",
+        Native(crate::builtin_array_join),
+    ),
+    (
+        "flatten",
+        "fn(array) -> array",
+        Native(crate::builtin_array_flatten),
+    ),
+    (
+        "dedup",
+        "fn(array) -> array",
+        Native(crate::builtin_array_dedup),
+    ),
+    // "has" avoids collision with string `contains` while providing
+    // element membership testing as a dot-call: `arr.has(x)`.
+    (
+        "has",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_array_contains),
+    ),
+    // RES-425: explicit scalar-to-string conversion.
+    (
+        "to_string",
+        "fn(any) -> string",
+        Native(crate::builtin_to_string),
+    ),
+    // RES-426: first-occurrence dedupe over scalar elements.
+    (
+        "array_unique",
+        "fn(array) -> array",
+        Native(crate::builtin_array_unique),
+    ),
+    // RES-427: count occurrences of a scalar element.
+    (
+        "array_count",
+        "fn(any, any) -> int",
+        Native(crate::builtin_array_count),
+    ),
+    // RES-428: first/last element accessors (empty array errors).
+    (
+        "array_first",
+        "fn(any) -> any",
+        Native(crate::builtin_array_first),
+    ),
+    (
+        "array_last",
+        "fn(any) -> any",
+        Native(crate::builtin_array_last),
+    ),
+    // RES-528: bounded indexing with fallback default.
+    (
+        "array_get_or",
+        "fn(any, int, any) -> any",
+        Native(crate::builtin_array_get_or),
+    ),
+    // RES-429: pad a string to a Unicode-scalar width.
+    (
+        "string_pad_left",
+        "fn(string, int, string) -> string",
+        Native(crate::builtin_string_pad_left),
+    ),
+    (
+        "string_pad_right",
+        "fn(string, int, string) -> string",
+        Native(crate::builtin_string_pad_right),
+    ),
+    // RES-540: center-pad a string to a Unicode-scalar width.
+    (
+        "string_pad_center",
+        "fn(string, int, string) -> string",
+        Native(crate::builtin_string_pad_center),
+    ),
+    // RES-430: pair elements as tuples; truncate to shorter array.
+    (
+        "array_zip",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_zip),
+    ),
+    // RES-531: split an array of 2-tuples into two parallel arrays.
+    (
+        "array_unzip",
+        "fn(array) -> array",
+        Native(crate::builtin_array_unzip),
+    ),
+    // RES-431: generate [start, start+1, ..., end-1].
+    (
+        "array_range",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_range),
+    ),
+    // RES-4000 (VM-internal): construct a Value::Range at runtime.
+    // Not part of the public builtin surface — see doc comment on
+    // `builtin_make_range`.
+    (
+        "__range",
+        "fn(int, int, bool) -> range",
+        Native(crate::builtin_make_range),
+    ),
+    // RES-921: slice(arr, lo, hi, inclusive) — sub-array / sub-string.
+    (
+        "array_slice",
+        "fn(any, any, any, bool) -> array",
+        Native(crate::builtin_array_slice),
+    ),
+    // RES-522: generate [0, 1, ..., len(arr)-1] for the given array.
+    (
+        "array_indices",
+        "fn(array) -> array",
+        Native(crate::builtin_array_indices),
+    ),
+    // RES-432: array of n copies of elem.
+    (
+        "array_repeat",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_repeat),
+    ),
+    // RES-433: split string into single-character strings.
+    (
+        "string_chars",
+        "fn(string) -> array",
+        Native(crate::builtin_string_chars),
+    ),
+    // RES-434: split string into lines (LF, CRLF, no trailing empty).
+    (
+        "string_lines",
+        "fn(string) -> array",
+        Native(crate::builtin_string_lines),
+    ),
+    // RES-496: split string on Unicode whitespace.
+    (
+        "string_words",
+        "fn(string) -> array",
+        Native(crate::builtin_string_words),
+    ),
+    // RES-497: join string array with newline separator.
+    (
+        "string_join_lines",
+        "fn(array) -> string",
+        Native(crate::builtin_string_join_lines),
+    ),
+    // RES-498: join string array with single-space separator.
+    (
+        "string_unwords",
+        "fn(array) -> string",
+        Native(crate::builtin_string_unwords),
+    ),
+    // RES-499: take first n Unicode scalars.
+    (
+        "string_take",
+        "fn(string, int) -> string",
+        Native(crate::builtin_string_take),
+    ),
+    // RES-506: drop first n Unicode scalars.
+    (
+        "string_drop",
+        "fn(string, int) -> string",
+        Native(crate::builtin_string_drop),
+    ),
+    // RES-435: split array into fixed-size chunks (last may be short).
+    (
+        "array_chunk",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_chunk),
+    ),
+    // RES-436: non-overlapping substring count.
+    (
+        "string_count",
+        "fn(string, string) -> int",
+        Native(crate::builtin_string_count),
+    ),
+    // RES-523: count occurrences of a single character.
+    (
+        "string_count_char",
+        "fn(string, string) -> int",
+        Native(crate::builtin_string_count_char),
+    ),
+    // RES-524: char-index of a single character (-1 if absent).
+    (
+        "string_find_char",
+        "fn(string, string) -> int",
+        Native(crate::builtin_string_find_char),
+    ),
+    // RES-525: named-predicate prefix slicing on strings.
+    (
+        "string_take_while_char",
+        "fn(string, string) -> string",
+        Native(crate::builtin_string_take_while_char),
+    ),
+    (
+        "string_drop_while_char",
+        "fn(string, string) -> string",
+        Native(crate::builtin_string_drop_while_char),
+    ),
+    // RES-526: named-predicate global char filter.
+    (
+        "string_filter_char",
+        "fn(string, string) -> string",
+        Native(crate::builtin_string_filter_char),
+    ),
+    // RES-527: ASCII case-insensitive string equality.
+    (
+        "string_eq_ignore_case",
+        "fn(string, string) -> bool",
+        Native(crate::builtin_string_eq_ignore_case),
+    ),
+    // RES-437: insert separator between adjacent array elements.
+    (
+        "array_intersperse",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_intersperse),
+    ),
+    // RES-516: alternate elements from two arrays.
+    (
+        "array_interleave",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_interleave),
+    ),
+    // RES-438: one-sided whitespace trimmers.
+    (
+        "trim_start",
+        "fn(string) -> string",
+        Native(crate::builtin_trim_start),
+    ),
+    (
+        "trim_end",
+        "fn(string) -> string",
+        Native(crate::builtin_trim_end),
+    ),
+    // RES-439: bisect array at index — returns (first n, rest) tuple.
+    (
+        "array_split_at",
+        "fn(any, any) -> array",
+        Native(crate::builtin_array_split_at),
+    ),
+    // RES-440: integer bitwise operations.
+    (
+        "bit_and",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_and),
+    ),
+    (
+        "bit_or",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_or),
+    ),
+    (
+        "bit_xor",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_xor),
+    ),
+    ("bit_not", "fn(int) -> int", Native(crate::builtin_bit_not)),
+    (
+        "bit_shl",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_shl),
+    ),
+    (
+        "bit_shr",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_shr),
+    ),
+    // RES-488: population count (Hamming weight).
+    (
+        "bit_count",
+        "fn(int) -> int",
+        Native(crate::builtin_bit_count),
+    ),
+    // RES-489: count leading zero bits.
+    (
+        "bit_leading_zeros",
+        "fn(int) -> int",
+        Native(crate::builtin_bit_leading_zeros),
+    ),
+    // RES-490: count trailing zero bits.
+    (
+        "bit_trailing_zeros",
+        "fn(int) -> int",
+        Native(crate::builtin_bit_trailing_zeros),
+    ),
+    // RES-511: single-bit test / set / clear / toggle.
+    (
+        "bit_test",
+        "fn(int, int) -> bool",
+        Native(crate::builtin_bit_test),
+    ),
+    (
+        "bit_set",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_set),
+    ),
+    (
+        "bit_clear",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_clear),
+    ),
+    (
+        "bit_toggle",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_toggle),
+    ),
+    // RES-520: circular bit rotation.
+    (
+        "bit_rotate_left",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_rotate_left),
+    ),
+    (
+        "bit_rotate_right",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_rotate_right),
+    ),
+    // RES-534: extract a single byte from an i64 (little-endian).
+    (
+        "bit_byte",
+        "fn(int, int) -> int",
+        Native(crate::builtin_bit_byte),
+    ),
+    // RES-538: set a single byte of an i64 (little-endian).
+    (
+        "bit_set_byte",
+        "fn(int, int, int) -> int",
+        Native(crate::builtin_bit_set_byte),
+    ),
+    // RES-491: integer floor sqrt.
+    (
+        "int_sqrt",
+        "fn(int) -> int",
+        Native(crate::builtin_int_sqrt),
+    ),
+    // RES-517: integer exponentiation.
+    (
+        "pow_int",
+        "fn(int, int) -> int",
+        Native(crate::builtin_pow_int),
+    ),
+    // RES-518: integer division with explicit rounding mode.
+    (
+        "ceil_div",
+        "fn(int, int) -> int",
+        Native(crate::builtin_ceil_div),
+    ),
+    (
+        "floor_div",
+        "fn(int, int) -> int",
+        Native(crate::builtin_floor_div),
+    ),
+    // RES-519: Python-style modulo (sign of divisor).
+    (
+        "modulo",
+        "fn(int, int) -> int",
+        Native(crate::builtin_modulo),
+    ),
+    // RES-492: floor log base 2.
+    (
+        "int_log2",
+        "fn(int) -> int",
+        Native(crate::builtin_int_log2),
+    ),
+    // RES-493: power-of-two predicate.
+    ("is_pow2", "fn(int) -> bool", Native(crate::builtin_is_pow2)),
+    // RES-494: round up to next power of two.
+    (
+        "next_pow2",
+        "fn(int) -> int",
+        Native(crate::builtin_next_pow2),
+    ),
+    // RES-495: lowercase hex string (sign-prefixed for negatives).
+    (
+        "int_to_hex",
+        "fn(int) -> string",
+        Native(crate::builtin_int_to_hex),
+    ),
+    // RES-512: binary string (sign-prefixed for negatives).
+    (
+        "int_to_bin",
+        "fn(int) -> string",
+        Native(crate::builtin_int_to_bin),
+    ),
+    // RES-442: byte index of last substring occurrence, or -1.
+    (
+        "last_index_of",
+        "fn(string, string) -> int",
+        Native(crate::builtin_last_index_of),
+    ),
+    // RES-413: repeat a string n times.
+    (
+        "string_repeat",
+        "fn(string, int) -> string",
+        Native(crate::builtin_string_repeat),
+    ),
+    // RES-414: first byte index of substring, or -1 if not found.
+    (
+        "index_of",
+        "fn(string, string) -> int",
+        Native(crate::builtin_index_of),
+    ),
+    // RES-145: string manipulation expansion.
+    (
+        "replace",
+        "fn(string, string, string) -> string",
+        Native(crate::builtin_replace),
+    ),
+    (
+        "format",
+        "fn(string, array) -> string",
+        Native(crate::builtin_format),
+    ),
+    // RES-213: prefix/suffix/repeat — common stdlib gap.
+    (
+        "starts_with",
+        "fn(string, string) -> bool",
+        Native(crate::builtin_starts_with),
+    ),
+    (
+        "ends_with",
+        "fn(string, string) -> bool",
+        Native(crate::builtin_ends_with),
+    ),
+    (
+        "repeat",
+        "fn(string, int) -> string",
+        Native(crate::builtin_repeat),
+    ),
+    // RES-339: string parsing and formatting.
+    (
+        "parse_int",
+        "fn(string) -> Result",
+        Native(crate::builtin_parse_int),
+    ),
+    // RES-529: non-erroring parse with fallback default.
+    (
+        "parse_int_or",
+        "fn(string, int) -> int",
+        Native(crate::builtin_parse_int_or),
+    ),
+    // RES-532: non-erroring float parse with fallback default.
+    (
+        "parse_float_or",
+        "fn(string, float) -> float",
+        Native(crate::builtin_parse_float_or),
+    ),
+    (
+        "parse_float",
+        "fn(string) -> Result",
+        Native(crate::builtin_parse_float),
+    ),
+    (
+        "char_at",
+        "fn(string, int) -> Result",
+        Native(crate::builtin_char_at),
+    ),
+    (
+        "pad_left",
+        "fn(string, int, string) -> string",
+        Native(crate::builtin_pad_left),
+    ),
+    (
+        "pad_right",
+        "fn(string, int, string) -> string",
+        Native(crate::builtin_pad_right),
+    ),
+    ("Ok", "fn(any) -> Result", Native(crate::builtin_ok)),
+    ("Err", "fn(any) -> Result", Native(crate::builtin_err)),
+    ("is_ok", "fn(Result) -> bool", Native(crate::builtin_is_ok)),
+    (
+        "is_err",
+        "fn(Result) -> bool",
+        Native(crate::builtin_is_err),
+    ),
+    ("unwrap", "fn(Result) -> any", Native(crate::builtin_unwrap)),
+    (
+        "unwrap_err",
+        "fn(Result) -> any",
+        Native(crate::builtin_unwrap_err),
+    ),
+    // RES-363 + RES-375: Option<T> constructors and free-function methods.
+    ("Some", "fn(any) -> Option", Native(crate::builtin_some)),
+    ("None", "Option", Native(crate::builtin_none)),
+    (
+        "is_some",
+        "fn(Option) -> bool",
+        Native(crate::builtin_is_some),
+    ),
+    (
+        "is_none",
+        "fn(Option) -> bool",
+        Native(crate::builtin_is_none),
+    ),
+    (
+        "unwrap_option",
+        "fn(Option) -> any",
+        Native(crate::builtin_unwrap_option),
+    ),
+    (
+        "option_unwrap",
+        "fn(any) -> any",
+        Native(crate::builtin_option_unwrap),
+    ),
+    (
+        "option_unwrap_or",
+        "fn(any, any) -> any",
+        Native(crate::builtin_option_unwrap_or),
+    ),
+    // RES-932: tuple type predicate — distinguishes Value::Tuple from arrays.
+    (
+        "is_tuple",
+        "fn(any) -> bool",
+        Native(crate::builtin_is_tuple),
+    ),
+    // RES-143: file I/O. Std-only; the `resilient-runtime` crate has
+    // no builtins table and stays no_std-clean.
+    (
+        "file_read",
+        "fn(string) -> string",
+        Native(crate::builtin_file_read),
+    ),
+    (
+        "file_write",
+        "fn(string, string) -> void",
+        Native(crate::builtin_file_write),
+    ),
+    (
+        "read_file",
+        "fn(string) -> string",
+        Native(crate::fs_sandbox::builtin_read_file),
+    ),
+    (
+        "write_file",
+        "fn(string, string) -> void",
+        Native(crate::fs_sandbox::builtin_write_file),
+    ),
+    (
+        "append_file",
+        "fn(string, string) -> void",
+        Native(crate::fs_sandbox::builtin_append_file),
+    ),
+    // RES-409: streaming file I/O — open / read_chunk / seek /
+    // write_chunk / close. Memory-bounded reads finally possible.
+    (
+        "file_open",
+        "fn(string, string) -> Result",
+        Native(crate::file_io::builtin_file_open),
+    ),
+    (
+        "file_read_chunk",
+        "fn(any, int) -> Result",
+        Native(crate::file_io::builtin_file_read_chunk),
+    ),
+    (
+        "file_write_chunk",
+        "fn(any, bytes) -> Result",
+        Native(crate::file_io::builtin_file_write_chunk),
+    ),
+    (
+        "file_seek",
+        "fn(any, int, string) -> Result",
+        Native(crate::file_io::builtin_file_seek),
+    ),
+    (
+        "file_close",
+        "fn(any) -> Result",
+        Native(crate::file_io::builtin_file_close),
+    ),
+    // RES-151: read-only env-var accessor, std-only.
+    ("env", "fn(string) -> Result", Native(crate::builtin_env)),
+    ("args", "fn() -> array", Native(crate::builtin_args)),
+    ("exit", "fn(int) -> void", Native(crate::builtin_exit)),
+    // RES-148: Map builtins.
+    ("map_new", "fn() -> any", Native(crate::builtin_map_new)),
+    (
+        "map_insert",
+        "fn(any, any, any) -> any",
+        Native(crate::builtin_map_insert),
+    ),
+    (
+        "map_get",
+        "fn(any, any) -> Result",
+        Native(crate::builtin_map_get),
+    ),
+    (
+        "map_remove",
+        "fn(any, any) -> any",
+        Native(crate::builtin_map_remove),
+    ),
+    (
+        "map_keys",
+        "fn(any) -> array",
+        Native(crate::builtin_map_keys),
+    ),
+    ("map_len", "fn(any) -> int", Native(crate::builtin_map_len)),
+    // RES-883.
+    (
+        "map_values",
+        "fn(any) -> array",
+        Native(crate::builtin_map_values),
+    ),
+    // RES-884.
+    (
+        "map_contains_key",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_map_contains_key),
+    ),
+    // RES-293: HashMap stdlib builtins. Surface the same `Value::Map`
+    // backend under the user-facing `hashmap_*` names called out in
+    // the language guide. `hashmap_contains` is genuinely new — it
+    // had no `map_contains` predecessor.
+    (
+        "hashmap_new",
+        "fn() -> any",
+        Native(crate::builtin_hashmap_new),
+    ),
+    (
+        "hashmap_insert",
+        "fn(any, any, any) -> any",
+        Native(crate::builtin_hashmap_insert),
+    ),
+    (
+        "hashmap_get",
+        "fn(any, any) -> Result",
+        Native(crate::builtin_hashmap_get),
+    ),
+    (
+        "hashmap_remove",
+        "fn(any, any) -> any",
+        Native(crate::builtin_hashmap_remove),
+    ),
+    (
+        "hashmap_contains",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_hashmap_contains),
+    ),
+    (
+        "hashmap_keys",
+        "fn(any) -> array",
+        Native(crate::builtin_hashmap_keys),
+    ),
+    // RES-885.
+    (
+        "hashmap_len",
+        "fn(any) -> int",
+        Native(crate::builtin_hashmap_len),
+    ),
+    // RES-886.
+    (
+        "hashmap_values",
+        "fn(any) -> array",
+        Native(crate::builtin_hashmap_values),
+    ),
+    // RES-149: Set builtins.
+    ("set_new", "fn() -> any", Native(crate::builtin_set_new)),
+    (
+        "set_insert",
+        "fn(any, any) -> any",
+        Native(crate::builtin_set_insert),
+    ),
+    (
+        "set_remove",
+        "fn(any, any) -> any",
+        Native(crate::builtin_set_remove),
+    ),
+    (
+        "set_has",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_set_has),
+    ),
+    ("set_len", "fn(any) -> int", Native(crate::builtin_set_len)),
+    (
+        "set_items",
+        "fn(any) -> array",
+        Native(crate::builtin_set_items),
+    ),
+    // RES-876: set algebra primitives.
+    (
+        "set_union",
+        "fn(any, any) -> any",
+        Native(crate::builtin_set_union),
+    ),
+    // RES-877.
+    (
+        "set_intersection",
+        "fn(any, any) -> any",
+        Native(crate::builtin_set_intersection),
+    ),
+    // RES-878.
+    (
+        "set_difference",
+        "fn(any, any) -> any",
+        Native(crate::builtin_set_difference),
+    ),
+    // RES-879.
+    (
+        "set_is_subset",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_set_is_subset),
+    ),
+    // RES-880.
+    (
+        "set_is_superset",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_set_is_superset),
+    ),
+    // RES-881.
+    (
+        "set_is_disjoint",
+        "fn(any, any) -> bool",
+        Native(crate::builtin_set_is_disjoint),
+    ),
+    // RES-882.
+    (
+        "set_symmetric_difference",
+        "fn(any, any) -> any",
+        Native(crate::builtin_set_symmetric_difference),
+    ),
+    // RES-152: Bytes builtins.
+    (
+        "bytes_len",
+        "fn(bytes) -> int",
+        Native(crate::builtin_bytes_len),
+    ),
+    (
+        "bytes_slice",
+        "fn(bytes, int, int) -> bytes",
+        Native(crate::builtin_bytes_slice),
+    ),
+    (
+        "byte_at",
+        "fn(bytes, int) -> int",
+        Native(crate::builtin_byte_at),
+    ),
+    // RES-887.
+    (
+        "bytes_concat",
+        "fn(bytes, bytes) -> bytes",
+        Native(crate::builtin_bytes_concat),
+    ),
+    // RES-888.
+    (
+        "bytes_eq",
+        "fn(bytes, bytes) -> bool",
+        Native(crate::builtin_bytes_eq),
+    ),
+    // RES-944: byte-search predicates / locator.
+    (
+        "bytes_starts_with",
+        "fn(bytes, bytes) -> bool",
+        Native(crate::builtin_bytes_starts_with),
+    ),
+    (
+        "bytes_ends_with",
+        "fn(bytes, bytes) -> bool",
+        Native(crate::builtin_bytes_ends_with),
+    ),
+    (
+        "bytes_index_of",
+        "fn(bytes, bytes) -> int",
+        Native(crate::builtin_bytes_index_of),
+    ),
+    // RES-943: hex encoding.
+    (
+        "bytes_to_hex",
+        "fn(bytes) -> string",
+        Native(crate::builtin_bytes_to_hex),
+    ),
+    (
+        "bytes_from_hex",
+        "fn(string) -> Result",
+        Native(crate::builtin_bytes_from_hex),
+    ),
+    // RES-936: Result fallback parallel to option_unwrap_or.
+    (
+        "result_unwrap_or",
+        "fn(Result, any) -> any",
+        Native(crate::builtin_result_unwrap_or),
+    ),
+    // RES-937: symmetric Err-side default.
+    (
+        "result_unwrap_or_err",
+        "fn(Result, any) -> any",
+        Native(crate::builtin_result_unwrap_or_err),
+    ),
+    // RES-938: bidirectional Result <-> Option conversion.
+    (
+        "result_to_option",
+        "fn(Result) -> any",
+        Native(crate::builtin_result_to_option),
+    ),
+    (
+        "option_to_result",
+        "fn(any, any) -> Result",
+        Native(crate::builtin_option_to_result),
+    ),
+    // RES-939: chain alternatives without unwrapping.
+    (
+        "option_or",
+        "fn(any, any) -> any",
+        Native(crate::builtin_option_or),
+    ),
+    (
+        "result_or",
+        "fn(Result, Result) -> Result",
+        Native(crate::builtin_result_or),
+    ),
+    // RES-940: power-of-two helpers.
+    (
+        "is_power_of_two",
+        "fn(int) -> bool",
+        Native(crate::builtin_is_power_of_two),
+    ),
+    (
+        "next_power_of_two",
+        "fn(int) -> int",
+        Native(crate::builtin_next_power_of_two),
+    ),
+    // RES-941: int-array statistics.
+    (
+        "array_average",
+        "fn(any) -> float",
+        Native(crate::builtin_array_average),
+    ),
+    (
+        "array_median",
+        "fn(any) -> float",
+        Native(crate::builtin_array_median),
+    ),
+    // RES-942: float-array reductions.
+    (
+        "array_sum_float",
+        "fn(any) -> float",
+        Native(crate::builtin_array_sum_float),
+    ),
+    (
+        "array_product_float",
+        "fn(any) -> float",
+        Native(crate::builtin_array_product_float),
+    ),
+    (
+        "array_min_float",
+        "fn(any) -> float",
+        Native(crate::builtin_array_min_float),
+    ),
+    (
+        "array_max_float",
+        "fn(any) -> float",
+        Native(crate::builtin_array_max_float),
+    ),
+    (
+        "array_average_float",
+        "fn(any) -> float",
+        Native(crate::builtin_array_average_float),
+    ),
+    // RES-945: default-fallback map accessors.
+    (
+        "map_get_or",
+        "fn(any, any, any) -> any",
+        Native(crate::builtin_map_get_or),
+    ),
+    (
+        "hashmap_get_or",
+        "fn(any, any, any) -> any",
+        Native(crate::builtin_hashmap_get_or),
+    ),
+    // RES-385: explicit consumption of a linear value. At runtime
+    // `drop(v)` simply evaluates and discards its argument; the
+    // semantic weight lives in the type checker's linear-use pass,
+    // which treats a `drop` call as the single consumption that
+    // satisfies the single-use obligation.
+    ("drop", "fn(any) -> void", Native(crate::builtin_drop)),
+    // RES-353: StringBuilder — efficient multi-part string construction.
+    // Construction is a top-level function; methods are dispatched via
+    // the special StringBuilder method handler in `CallExpression` eval.
+    (
+        "StringBuilder_new",
+        "fn(int) -> any",
+        Native(crate::builtin_string_builder_new),
+    ),
+    // RES-328: shared mutable cell — explicit shared-state escape hatch
+    // for closures that need to coordinate. Methods (.get / .set) are
+    // dispatched via the special cell handler in `CallExpression` eval.
+    ("cell", "fn(any) -> any", Native(crate::builtin_cell_new)),
+    ("clone", "fn(any) -> any", Native(crate::builtin_clone)),
+    // RES-332 PR 2: actor spawn/send/receive.
+    ("spawn", "fn(any) -> any", Native(crate::builtin_spawn)),
+    ("send", "fn(any, any) -> void", Native(crate::builtin_send)),
+    ("receive", "fn() -> any", Native(crate::builtin_receive)),
+    // RES-1115..1124: appended to the end of BUILTINS so the O(N)
+    // `apply_builtin_by_name` linear scan keeps the hot-path entries
+    // (println, len, push, ...) at their existing positions. Front-loading
+    // the new builtins regressed the fib(25) VM benchmark by ~23%; placing
+    // them last keeps that gate green while leaving the new surface fully
+    // available.
+    // RES-1115..1118: overflow-safe integer arithmetic — safety-critical
+    // embedded code needs an explicit overflow policy, not silent wrap.
+    (
+        "saturating_add",
+        "fn(int, int) -> int",
+        Native(crate::builtin_saturating_add),
+    ),
+    (
+        "saturating_sub",
+        "fn(int, int) -> int",
+        Native(crate::builtin_saturating_sub),
+    ),
+    (
+        "saturating_mul",
+        "fn(int, int) -> int",
+        Native(crate::builtin_saturating_mul),
+    ),
+    (
+        "wrapping_add",
+        "fn(int, int) -> int",
+        Native(crate::builtin_wrapping_add),
+    ),
+    (
+        "wrapping_sub",
+        "fn(int, int) -> int",
+        Native(crate::builtin_wrapping_sub),
+    ),
+    (
+        "wrapping_mul",
+        "fn(int, int) -> int",
+        Native(crate::builtin_wrapping_mul),
+    ),
+    (
+        "checked_add",
+        "fn(int, int) -> Option<int>",
+        Native(crate::builtin_checked_add),
+    ),
+    (
+        "checked_sub",
+        "fn(int, int) -> Option<int>",
+        Native(crate::builtin_checked_sub),
+    ),
+    (
+        "checked_mul",
+        "fn(int, int) -> Option<int>",
+        Native(crate::builtin_checked_mul),
+    ),
+    (
+        "checked_div",
+        "fn(int, int) -> Option<int>",
+        Native(crate::builtin_checked_div),
+    ),
+    // RES-1119..1121: bit manipulation.
+    (
+        "rotate_left_int",
+        "fn(int, int) -> int",
+        Native(crate::builtin_rotate_left_int),
+    ),
+    (
+        "rotate_right_int",
+        "fn(int, int) -> int",
+        Native(crate::builtin_rotate_right_int),
+    ),
+    (
+        "reverse_bits",
+        "fn(int) -> int",
+        Native(crate::builtin_reverse_bits),
+    ),
+    (
+        "swap_bytes",
+        "fn(int) -> int",
+        Native(crate::builtin_swap_bytes),
+    ),
+    // RES-1122..1123: int ↔ bytes endianness conversion.
+    (
+        "to_be_bytes",
+        "fn(int) -> bytes",
+        Native(crate::builtin_to_be_bytes),
+    ),
+    (
+        "to_le_bytes",
+        "fn(int) -> bytes",
+        Native(crate::builtin_to_le_bytes),
+    ),
+    (
+        "from_be_bytes",
+        "fn(bytes) -> int",
+        Native(crate::builtin_from_be_bytes),
+    ),
+    (
+        "from_le_bytes",
+        "fn(bytes) -> int",
+        Native(crate::builtin_from_le_bytes),
+    ),
+    // RES-1124: integer-only math primitives.
+    ("isqrt", "fn(int) -> int", Native(crate::builtin_isqrt)),
+    ("ipow", "fn(int, int) -> int", Native(crate::builtin_ipow)),
+    // RES-1126: direction-rounded division.
+    (
+        "div_ceil",
+        "fn(int, int) -> int",
+        Native(crate::builtin_div_ceil),
+    ),
+    (
+        "div_floor",
+        "fn(int, int) -> int",
+        Native(crate::builtin_div_floor),
+    ),
+    // RES-1127: Euclidean division — non-negative remainder.
+    (
+        "div_euclid",
+        "fn(int, int) -> int",
+        Native(crate::builtin_div_euclid),
+    ),
+    (
+        "rem_euclid",
+        "fn(int, int) -> int",
+        Native(crate::builtin_rem_euclid),
+    ),
+    // RES-1128: overflow-safe arithmetic mean.
+    (
+        "midpoint",
+        "fn(int, int) -> int",
+        Native(crate::builtin_midpoint),
+    ),
+    // RES-1129: integer logarithms (no f64 round-trip).
+    ("ilog2", "fn(int) -> int", Native(crate::builtin_ilog2)),
+    ("ilog10", "fn(int) -> int", Native(crate::builtin_ilog10)),
+    // RES-1130: IEEE 754 bit reinterpret cast.
+    (
+        "float_to_bits",
+        "fn(float) -> int",
+        Native(crate::builtin_float_to_bits),
+    ),
+    (
+        "float_from_bits",
+        "fn(int) -> float",
+        Native(crate::builtin_float_from_bits),
+    ),
+    // RES-1134: bitwise + construction ops on Bytes. Pure leaf
+    // builtins; module-isolated in `bytes_bitwise.rs`. Appended at
+    // the end of BUILTINS per the perf rule established in PR #1125.
+    (
+        "bytes_xor",
+        "fn(bytes, bytes) -> bytes",
+        Native(crate::bytes_bitwise::builtin_bytes_xor),
+    ),
+    (
+        "bytes_and",
+        "fn(bytes, bytes) -> bytes",
+        Native(crate::bytes_bitwise::builtin_bytes_and),
+    ),
+    (
+        "bytes_or",
+        "fn(bytes, bytes) -> bytes",
+        Native(crate::bytes_bitwise::builtin_bytes_or),
+    ),
+    (
+        "bytes_not",
+        "fn(bytes) -> bytes",
+        Native(crate::bytes_bitwise::builtin_bytes_not),
+    ),
+    (
+        "bytes_fill",
+        "fn(int, int) -> bytes",
+        Native(crate::bytes_bitwise::builtin_bytes_fill),
+    ),
+    (
+        "bytes_reverse",
+        "fn(bytes) -> bytes",
+        Native(crate::bytes_bitwise::builtin_bytes_reverse),
+    ),
+    // RES-1136: alignment helpers. Pure leaf builtins; module-isolated
+    // in `alignment_helpers.rs`. Appended at the end of BUILTINS per
+    // the perf rule established in PR #1125.
+    (
+        "next_multiple_of",
+        "fn(int, int) -> int",
+        Native(crate::alignment_helpers::builtin_next_multiple_of),
+    ),
+    (
+        "is_multiple_of",
+        "fn(int, int) -> bool",
+        Native(crate::alignment_helpers::builtin_is_multiple_of),
+    ),
+    // RES-1138: IEEE 754 classification + total order + sign-bit
+    // predicates. Pure leaf builtins; module-isolated in
+    // `float_predicates.rs`. Appended at the end of BUILTINS per
+    // the perf rule established in PR #1125.
+    (
+        "float_classify",
+        "fn(float) -> string",
+        Native(crate::float_predicates::builtin_float_classify),
+    ),
+    (
+        "float_total_cmp",
+        "fn(float, float) -> int",
+        Native(crate::float_predicates::builtin_float_total_cmp),
+    ),
+    (
+        "float_is_normal",
+        "fn(float) -> bool",
+        Native(crate::float_predicates::builtin_float_is_normal),
+    ),
+    (
+        "float_is_subnormal",
+        "fn(float) -> bool",
+        Native(crate::float_predicates::builtin_float_is_subnormal),
+    ),
+    (
+        "float_sign_bit",
+        "fn(float) -> bool",
+        Native(crate::float_predicates::builtin_float_sign_bit),
+    ),
+    // RES-1142: array chunking + striding + rotation primitives.
+    // Pure leaf builtins; module-isolated in `array_chunking.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "array_chunks",
+        "fn(array, int) -> array",
+        Native(crate::array_chunking::builtin_array_chunks),
+    ),
+    (
+        "array_chunks_exact",
+        "fn(array, int) -> array",
+        Native(crate::array_chunking::builtin_array_chunks_exact),
+    ),
+    (
+        "array_step",
+        "fn(any, any) -> array",
+        Native(crate::array_chunking::builtin_array_step),
+    ),
+    (
+        "array_rotate_left",
+        "fn(any, any) -> array",
+        Native(crate::array_chunking::builtin_array_rotate_left),
+    ),
+    (
+        "array_rotate_right",
+        "fn(any, any) -> array",
+        Native(crate::array_chunking::builtin_array_rotate_right),
+    ),
+    // RES-1140: ASCII char-class predicates. Pure leaf builtins;
+    // module-isolated in `ascii_predicates.rs`. Appended at the end
+    // of BUILTINS per the perf rule established in PR #1125.
+    (
+        "is_ascii",
+        "fn(string) -> bool",
+        Native(crate::ascii_predicates::builtin_is_ascii),
+    ),
+    (
+        "is_ascii_whitespace",
+        "fn(string) -> bool",
+        Native(crate::ascii_predicates::builtin_is_ascii_whitespace),
+    ),
+    (
+        "is_ascii_hexdigit",
+        "fn(string) -> bool",
+        Native(crate::ascii_predicates::builtin_is_ascii_hexdigit),
+    ),
+    (
+        "is_ascii_uppercase",
+        "fn(string) -> bool",
+        Native(crate::ascii_predicates::builtin_is_ascii_uppercase),
+    ),
+    (
+        "is_ascii_lowercase",
+        "fn(string) -> bool",
+        Native(crate::ascii_predicates::builtin_is_ascii_lowercase),
+    ),
+    (
+        "is_ascii_punctuation",
+        "fn(string) -> bool",
+        Native(crate::ascii_predicates::builtin_is_ascii_punctuation),
+    ),
+    (
+        "is_ascii_control",
+        "fn(string) -> bool",
+        Native(crate::ascii_predicates::builtin_is_ascii_control),
+    ),
+    // RES-1146: float / string sort + array_is_sorted predicates.
+    // Pure leaf builtins; module-isolated in `array_sort_extra.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "array_sort_float",
+        "fn(array) -> array",
+        Native(crate::array_sort_extra::builtin_array_sort_float),
+    ),
+    (
+        "array_sort_string",
+        "fn(array) -> array",
+        Native(crate::array_sort_extra::builtin_array_sort_string),
+    ),
+    (
+        "array_is_sorted",
+        "fn(array) -> bool",
+        Native(crate::array_sort_extra::builtin_array_is_sorted),
+    ),
+    (
+        "array_is_sorted_float",
+        "fn(array) -> bool",
+        Native(crate::array_sort_extra::builtin_array_is_sorted_float),
+    ),
+    (
+        "array_is_sorted_string",
+        "fn(array) -> bool",
+        Native(crate::array_sort_extra::builtin_array_is_sorted_string),
+    ),
+    // RES-1148: binary search on sorted int / float / string arrays.
+    // Pure leaf builtins; module-isolated in `array_binary_search.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "array_binary_search",
+        "fn(array, int) -> Result",
+        Native(crate::array_binary_search::builtin_array_binary_search),
+    ),
+    (
+        "array_binary_search_float",
+        "fn(array, float) -> Result",
+        Native(crate::array_binary_search::builtin_array_binary_search_float),
+    ),
+    (
+        "array_binary_search_string",
+        "fn(array, string) -> Result",
+        Native(crate::array_binary_search::builtin_array_binary_search_string),
+    ),
+    // RES-1144: map_entries / map_merge / map_is_empty + hashmap aliases.
+    // Pure leaf builtins; module-isolated in `map_entries_merge.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "map_entries",
+        "fn(any) -> array",
+        Native(crate::map_entries_merge::builtin_map_entries),
+    ),
+    (
+        "map_merge",
+        "fn(any, any) -> any",
+        Native(crate::map_entries_merge::builtin_map_merge),
+    ),
+    (
+        "map_is_empty",
+        "fn(any) -> bool",
+        Native(crate::map_entries_merge::builtin_map_is_empty),
+    ),
+    (
+        "hashmap_entries",
+        "fn(any) -> array",
+        Native(crate::map_entries_merge::builtin_hashmap_entries),
+    ),
+    (
+        "hashmap_merge",
+        "fn(any, any) -> any",
+        Native(crate::map_entries_merge::builtin_hashmap_merge),
+    ),
+    (
+        "hashmap_is_empty",
+        "fn(any) -> bool",
+        Native(crate::map_entries_merge::builtin_hashmap_is_empty),
+    ),
+    // RES-1150: statistical reductions — variance, stddev, median_float,
+    // range_float. Pure leaf builtins; module-isolated in
+    // `array_stats.rs`. Appended at the end of BUILTINS per the perf
+    // rule from PR #1125.
+    (
+        "array_variance_int",
+        "fn(array) -> float",
+        Native(crate::array_stats::builtin_array_variance_int),
+    ),
+    (
+        "array_variance_float",
+        "fn(array) -> float",
+        Native(crate::array_stats::builtin_array_variance_float),
+    ),
+    (
+        "array_stddev_int",
+        "fn(array) -> float",
+        Native(crate::array_stats::builtin_array_stddev_int),
+    ),
+    (
+        "array_stddev_float",
+        "fn(array) -> float",
+        Native(crate::array_stats::builtin_array_stddev_float),
+    ),
+    (
+        "array_median_float",
+        "fn(array) -> float",
+        Native(crate::array_stats::builtin_array_median_float),
+    ),
+    (
+        "array_range_float",
+        "fn(array) -> float",
+        Native(crate::array_stats::builtin_array_range_float),
+    ),
+    // RES-1152: per-byte helpers — repeat / count_byte / replace_byte.
+    // Pure leaf builtins; module-isolated in `bytes_helpers.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "bytes_repeat",
+        "fn(bytes, int) -> bytes",
+        Native(crate::bytes_helpers::builtin_bytes_repeat),
+    ),
+    (
+        "bytes_count_byte",
+        "fn(bytes, int) -> int",
+        Native(crate::bytes_helpers::builtin_bytes_count_byte),
+    ),
+    (
+        "bytes_replace_byte",
+        "fn(bytes, int, int) -> bytes",
+        Native(crate::bytes_helpers::builtin_bytes_replace_byte),
+    ),
+    // RES-1154: set_is_empty / set_from_array / result_and / option_and.
+    // Pure leaf builtins; module-isolated in `set_result_option.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "set_is_empty",
+        "fn(any) -> bool",
+        Native(crate::set_result_option::builtin_set_is_empty),
+    ),
+    (
+        "set_from_array",
+        "fn(array) -> any",
+        Native(crate::set_result_option::builtin_set_from_array),
+    ),
+    (
+        "result_and",
+        "fn(any, any) -> any",
+        Native(crate::set_result_option::builtin_result_and),
+    ),
+    (
+        "option_and",
+        "fn(any, any) -> any",
+        Native(crate::set_result_option::builtin_option_and),
+    ),
+    // RES-1156: per-bit accessors on i64 — set / clear / get / flip.
+    // Pure leaf builtins; module-isolated in `bit_manipulation.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "set_bit",
+        "fn(int, int) -> int",
+        Native(crate::bit_manipulation::builtin_set_bit),
+    ),
+    (
+        "clear_bit",
+        "fn(int, int) -> int",
+        Native(crate::bit_manipulation::builtin_clear_bit),
+    ),
+    (
+        "get_bit",
+        "fn(int, int) -> bool",
+        Native(crate::bit_manipulation::builtin_get_bit),
+    ),
+    (
+        "flip_bit",
+        "fn(int, int) -> int",
+        Native(crate::bit_manipulation::builtin_flip_bit),
+    ),
+    // RES-1158: array set-style helpers + fallback-safe first/last +
+    // index_of_last. Pure leaf builtins; module-isolated in
+    // `array_set_helpers.rs`. Appended at the end of BUILTINS per the
+    // perf rule from PR #1125.
+    (
+        "array_difference",
+        "fn(array, array) -> array",
+        Native(crate::array_set_helpers::builtin_array_difference),
+    ),
+    (
+        "array_intersection",
+        "fn(array, array) -> array",
+        Native(crate::array_set_helpers::builtin_array_intersection),
+    ),
+    (
+        "array_index_of_last",
+        "fn(array, any) -> int",
+        Native(crate::array_set_helpers::builtin_array_index_of_last),
+    ),
+    (
+        "array_first_or",
+        "fn(array, any) -> any",
+        Native(crate::array_set_helpers::builtin_array_first_or),
+    ),
+    (
+        "array_last_or",
+        "fn(array, any) -> any",
+        Native(crate::array_set_helpers::builtin_array_last_or),
+    ),
+    // RES-1162: deterministic hash builtins — hash_int / hash_string /
+    // hash_bytes / hash_combine. Pure leaf builtins; module-isolated in
+    // `hash_builtins.rs`. Appended at the end of BUILTINS per the perf
+    // rule from PR #1125.
+    (
+        "hash_int",
+        "fn(int) -> int",
+        Native(crate::hash_builtins::builtin_hash_int),
+    ),
+    (
+        "hash_string",
+        "fn(string) -> int",
+        Native(crate::hash_builtins::builtin_hash_string),
+    ),
+    (
+        "hash_bytes",
+        "fn(bytes) -> int",
+        Native(crate::hash_builtins::builtin_hash_bytes),
+    ),
+    (
+        "hash_combine",
+        "fn(int, int) -> int",
+        Native(crate::hash_builtins::builtin_hash_combine),
+    ),
+    // RES-2560: SHA-256 and SHA-512 cryptographic hash builtins.
+    (
+        "sha256",
+        "fn(bytes) -> string",
+        Native(crate::crypto_hash::builtin_sha256),
+    ),
+    (
+        "sha256_str",
+        "fn(string) -> string",
+        Native(crate::crypto_hash::builtin_sha256_str),
+    ),
+    (
+        "sha512",
+        "fn(bytes) -> string",
+        Native(crate::crypto_hash::builtin_sha512),
+    ),
+    (
+        "sha512_str",
+        "fn(string) -> string",
+        Native(crate::crypto_hash::builtin_sha512_str),
+    ),
+    // RES-2561: CRC-32 and CRC-16 checksum builtins.
+    (
+        "crc32",
+        "fn(bytes) -> int",
+        Native(crate::crypto_hash::builtin_crc32),
+    ),
+    (
+        "crc32_str",
+        "fn(string) -> int",
+        Native(crate::crypto_hash::builtin_crc32_str),
+    ),
+    (
+        "crc16",
+        "fn(bytes) -> int",
+        Native(crate::crypto_hash::builtin_crc16),
+    ),
+    (
+        "crc16_str",
+        "fn(string) -> int",
+        Native(crate::crypto_hash::builtin_crc16_str),
+    ),
+    // RES-1160: argmax / argmin for float and string arrays.
+    // Pure leaf builtins; module-isolated in `array_argminmax.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "array_argmax_float",
+        "fn(array) -> int",
+        Native(crate::array_argminmax::builtin_array_argmax_float),
+    ),
+    (
+        "array_argmin_float",
+        "fn(array) -> int",
+        Native(crate::array_argminmax::builtin_array_argmin_float),
+    ),
+    (
+        "array_argmax_string",
+        "fn(array) -> int",
+        Native(crate::array_argminmax::builtin_array_argmax_string),
+    ),
+    (
+        "array_argmin_string",
+        "fn(array) -> int",
+        Native(crate::array_argminmax::builtin_array_argmin_string),
+    ),
+    // RES-1164: iteration helpers — enumerate, array_zip3, string_truncate.
+    // Pure leaf builtins; module-isolated in `iter_helpers.rs`. Appended
+    // at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "enumerate",
+        "fn(array) -> array",
+        Native(crate::iter_helpers::builtin_enumerate),
+    ),
+    (
+        "array_zip3",
+        "fn(array, array, array) -> array",
+        Native(crate::iter_helpers::builtin_array_zip3),
+    ),
+    (
+        "string_truncate",
+        "fn(string, int) -> string",
+        Native(crate::iter_helpers::builtin_string_truncate),
+    ),
+    // RES-1166: rounding builtins — round / trunc + int variants.
+    // Pure leaf builtins; module-isolated in `rounding.rs`. Appended
+    // at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "round",
+        "fn(float) -> float",
+        Native(crate::rounding::builtin_round),
+    ),
+    (
+        "trunc",
+        "fn(float) -> float",
+        Native(crate::rounding::builtin_trunc),
+    ),
+    (
+        "round_to_int",
+        "fn(float) -> int",
+        Native(crate::rounding::builtin_round_to_int),
+    ),
+    (
+        "trunc_to_int",
+        "fn(float) -> int",
+        Native(crate::rounding::builtin_trunc_to_int),
+    ),
+    // RES-1170: cumulative reductions + combined min/max.
+    // Pure leaf builtins; module-isolated in `array_cumulative.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "array_cumsum",
+        "fn(array) -> array",
+        Native(crate::array_cumulative::builtin_array_cumsum),
+    ),
+    (
+        "array_cumprod",
+        "fn(array) -> array",
+        Native(crate::array_cumulative::builtin_array_cumprod),
+    ),
+    (
+        "array_diffs",
+        "fn(array) -> array",
+        Native(crate::array_cumulative::builtin_array_diffs),
+    ),
+    (
+        "array_min_max",
+        "fn(array) -> array",
+        Native(crate::array_cumulative::builtin_array_min_max),
+    ),
+    // RES-1172: small string + array gaps.
+    // Pure leaf builtins; module-isolated in `string_array_misc.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "string_split_once",
+        "fn(string, string) -> array",
+        Native(crate::string_array_misc::builtin_string_split_once),
+    ),
+    (
+        "string_rsplit_once",
+        "fn(string, string) -> array",
+        Native(crate::string_array_misc::builtin_string_rsplit_once),
+    ),
+    (
+        "string_from_chars",
+        "fn(array) -> string",
+        Native(crate::string_array_misc::builtin_string_from_chars),
+    ),
+    (
+        "array_is_empty",
+        "fn(array) -> bool",
+        Native(crate::string_array_misc::builtin_array_is_empty),
+    ),
+    // RES-1174: wall-clock unix time. @io / impure.
+    // Pure leaf builtins; module-isolated in `unix_time.rs`. Appended
+    // at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "unix_time_s",
+        "fn() -> int",
+        Native(crate::unix_time::builtin_unix_time_s),
+    ),
+    (
+        "unix_time_ms",
+        "fn() -> int",
+        Native(crate::unix_time::builtin_unix_time_ms),
+    ),
+    (
+        "unix_time_ns",
+        "fn() -> int",
+        Native(crate::unix_time::builtin_unix_time_ns),
+    ),
+    // RES-1176: bytes ↔ string conversions — strip_prefix / strip_suffix /
+    // bytes_to_string. Pure leaf builtins; module-isolated in
+    // `bytes_conversions.rs`. Appended at the end of BUILTINS per the
+    // perf rule from PR #1125.
+    (
+        "bytes_strip_prefix",
+        "fn(bytes, bytes) -> bytes",
+        Native(crate::bytes_conversions::builtin_bytes_strip_prefix),
+    ),
+    (
+        "bytes_strip_suffix",
+        "fn(bytes, bytes) -> bytes",
+        Native(crate::bytes_conversions::builtin_bytes_strip_suffix),
+    ),
+    (
+        "bytes_to_string",
+        "fn(bytes) -> string",
+        Native(crate::bytes_conversions::builtin_bytes_to_string),
+    ),
+    // RES-1178: bytes slicing primitives — take / drop / take_last /
+    // drop_last. Pure leaf builtins; module-isolated in `bytes_slicing.rs`.
+    // Appended at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "bytes_take",
+        "fn(bytes, int) -> bytes",
+        Native(crate::bytes_slicing::builtin_bytes_take),
+    ),
+    (
+        "bytes_drop",
+        "fn(bytes, int) -> bytes",
+        Native(crate::bytes_slicing::builtin_bytes_drop),
+    ),
+    (
+        "bytes_take_last",
+        "fn(bytes, int) -> bytes",
+        Native(crate::bytes_slicing::builtin_bytes_take_last),
+    ),
+    (
+        "bytes_drop_last",
+        "fn(bytes, int) -> bytes",
+        Native(crate::bytes_slicing::builtin_bytes_drop_last),
+    ),
+    // RES-1168: precision-sensitive math — expm1 / ln_1p / mul_add / recip.
+    // Pure leaf builtins; module-isolated in `precision_math.rs`. Appended
+    // at the end of BUILTINS per the perf rule from PR #1125.
+    (
+        "expm1",
+        "fn(float) -> float",
+        Native(crate::precision_math::builtin_expm1),
+    ),
+    (
+        "ln_1p",
+        "fn(float) -> float",
+        Native(crate::precision_math::builtin_ln_1p),
+    ),
+    (
+        "mul_add",
+        "fn(float, float, float) -> float",
+        Native(crate::precision_math::builtin_mul_add),
+    ),
+    (
+        "recip",
+        "fn(float) -> float",
+        Native(crate::precision_math::builtin_recip),
+    ),
+    // RES-1182: integer bit rotation + scalar signum. Pure leaf builtins;
+    // module-isolated in `int_rotate.rs`. Appended at the end of BUILTINS
+    // per the perf rule from PR #1125.
+    (
+        "rotate_left",
+        "fn(int, int) -> int",
+        Native(crate::int_rotate::builtin_rotate_left),
+    ),
+    (
+        "rotate_right",
+        "fn(int, int) -> int",
+        Native(crate::int_rotate::builtin_rotate_right),
+    ),
+    (
+        "signum",
+        "fn(int) -> int",
+        Native(crate::int_rotate::builtin_signum),
+    ),
+    // RES-2646: map construction from key-value pairs.
+    (
+        "map_from_pairs",
+        "fn(array) -> any",
+        Native(crate::array_functional::builtin_map_from_pairs),
+    ),
+    // RES-2647: map functional operations (pure — no callback).
+    (
+        "map_to_pairs",
+        "fn(any) -> array",
+        Native(crate::map_functional::builtin_map_to_pairs),
+    ),
+    (
+        "map_invert",
+        "fn(any) -> any",
+        Native(crate::map_functional::builtin_map_invert),
+    ),
+    // RES-2648: array combinators (pure — no callback).
+    (
+        "array_windows",
+        "fn(array, int) -> array",
+        Native(crate::array_combinators::builtin_array_windows),
+    ),
+    // RES-2650: numeric utilities (all pure).
+    (
+        "lerp",
+        "fn(any, any, any) -> float",
+        Native(crate::numeric_utils::builtin_lerp),
+    ),
+    (
+        "remap",
+        "fn(any, any, any, any, any) -> float",
+        Native(crate::numeric_utils::builtin_remap),
+    ),
+    (
+        "float_approx_eq",
+        "fn(any, any, any) -> bool",
+        Native(crate::numeric_utils::builtin_float_approx_eq),
+    ),
+    (
+        "round_to",
+        "fn(any, int) -> float",
+        Native(crate::numeric_utils::builtin_round_to),
+    ),
+    (
+        "int_pow",
+        "fn(int, int) -> int",
+        Native(crate::numeric_utils::builtin_int_pow),
+    ),
+    // RES-2650: collection extras (pure).
+    (
+        "array_frequency_map",
+        "fn(array) -> any",
+        Native(crate::collection_extras::builtin_array_frequency_map),
+    ),
+    // RES-2651: Option/Result HOF (pure).
+    (
+        "option_ok_or",
+        "fn(any, any) -> any",
+        Native(crate::result_option_hof::builtin_option_ok_or),
+    ),
+    // RES-2652: type introspection + result_collect (pure).
+    (
+        "type_of",
+        "fn(any) -> string",
+        Native(crate::type_builtins::builtin_type_of),
+    ),
+    (
+        "struct_name",
+        "fn(any) -> string",
+        Native(crate::type_builtins::builtin_struct_name),
+    ),
+    // Type tests — narrowing points for union-typed values.
+    (
+        "is_int",
+        "fn(any) -> bool",
+        Native(crate::type_builtins::builtin_is_int),
+    ),
+    (
+        "is_float",
+        "fn(any) -> bool",
+        Native(crate::type_builtins::builtin_is_float),
+    ),
+    (
+        "is_string",
+        "fn(any) -> bool",
+        Native(crate::type_builtins::builtin_is_string),
+    ),
+    (
+        "is_bool",
+        "fn(any) -> bool",
+        Native(crate::type_builtins::builtin_is_bool),
+    ),
+    (
+        "result_collect",
+        "fn(array) -> any",
+        Native(crate::type_builtins::builtin_result_collect),
+    ),
+    // RES-2655: number theory builtins (pure).
+    (
+        "prime_factors",
+        "fn(any) -> array",
+        Native(crate::number_theory::builtin_prime_factors),
+    ),
+    (
+        "primes_up_to",
+        "fn(any) -> array",
+        Native(crate::number_theory::builtin_primes_up_to),
+    ),
+    (
+        "euler_totient",
+        "fn(any) -> int",
+        Native(crate::number_theory::builtin_euler_totient),
+    ),
+    (
+        "divisors",
+        "fn(any) -> array",
+        Native(crate::number_theory::builtin_divisors),
+    ),
+    (
+        "is_perfect",
+        "fn(any) -> bool",
+        Native(crate::number_theory::builtin_is_perfect),
+    ),
+    (
+        "digit_sum",
+        "fn(any) -> int",
+        Native(crate::number_theory::builtin_digit_sum),
+    ),
+    (
+        "digital_root",
+        "fn(any) -> int",
+        Native(crate::number_theory::builtin_digital_root),
+    ),
+    (
+        "collatz_length",
+        "fn(int) -> int",
+        Native(crate::number_theory::builtin_collatz_length),
+    ),
+    (
+        "is_fibonacci",
+        "fn(any) -> bool",
+        Native(crate::number_theory::builtin_is_fibonacci),
+    ),
+    (
+        "int_log",
+        "fn(any, any) -> int",
+        Native(crate::number_theory::builtin_int_log),
+    ),
+    (
+        "count_digits",
+        "fn(any) -> int",
+        Native(crate::number_theory::builtin_count_digits),
+    ),
+    (
+        "int_to_digits",
+        "fn(any) -> array",
+        Native(crate::number_theory::builtin_int_to_digits),
+    ),
+    (
+        "int_from_digits",
+        "fn(array) -> int",
+        Native(crate::number_theory::builtin_int_from_digits),
+    ),
+    // RES-2656: functional HOFs — identity is pure; rest need interpreter (inline dispatch).
+    (
+        "identity",
+        "fn(any) -> any",
+        Native(crate::functional_hof::builtin_identity),
+    ),
+    // RES-2657: JSON serialization/deserialization (pure).
+    (
+        "to_json",
+        "fn(any) -> string",
+        Native(crate::json_builtins::builtin_to_json),
+    ),
+    (
+        "from_json",
+        "fn(string) -> any",
+        Native(crate::json_builtins::builtin_from_json),
+    ),
+    // RES-2554: json_encode / json_decode / json_encode_pretty / json_valid.
+    (
+        "json_encode",
+        "fn(any) -> string",
+        Native(crate::json_builtins::builtin_json_encode),
+    ),
+    (
+        "json_decode",
+        "fn(string) -> Result",
+        Native(crate::json_builtins::builtin_json_decode),
+    ),
+    (
+        "json_encode_pretty",
+        "fn(any) -> string",
+        Native(crate::json_builtins::builtin_json_encode_pretty),
+    ),
+    (
+        "json_valid",
+        "fn(string) -> bool",
+        Native(crate::json_builtins::builtin_json_valid),
+    ),
+    (
+        "json_parse",
+        "fn(string) -> any",
+        Native(crate::json_builtins::builtin_json_parse),
+    ),
+    (
+        "json_stringify",
+        "fn(any, bool) -> string",
+        Native(crate::json_builtins::builtin_json_stringify),
+    ),
+    // RES-2585: regular expression matching builtins.
+    (
+        "regex_match",
+        "fn(string, string) -> bool",
+        Native(crate::regex_builtins::builtin_regex_match),
+    ),
+    (
+        "regex_find",
+        "fn(string, string) -> Option<string>",
+        Native(crate::regex_builtins::builtin_regex_find),
+    ),
+    (
+        "regex_find_all",
+        "fn(string, string) -> array",
+        Native(crate::regex_builtins::builtin_regex_find_all),
+    ),
+    (
+        "regex_captures",
+        "fn(string, string) -> Option<array>",
+        Native(crate::regex_builtins::builtin_regex_captures),
+    ),
+    (
+        "regex_replace",
+        "fn(string, string, string) -> string",
+        Native(crate::regex_builtins::builtin_regex_replace),
+    ),
+    (
+        "regex_replace_all",
+        "fn(string, string, string) -> string",
+        Native(crate::regex_builtins::builtin_regex_replace_all),
+    ),
+    // RES-2559: date/time builtins.
+    (
+        "datetime_now",
+        "fn() -> DateTime",
+        Native(crate::datetime_builtins::builtin_datetime_now),
+    ),
+    (
+        "datetime_from_unix",
+        "fn(int) -> DateTime",
+        Native(crate::datetime_builtins::builtin_datetime_from_unix),
+    ),
+    (
+        "datetime_to_unix",
+        "fn(any) -> int",
+        Native(crate::datetime_builtins::builtin_datetime_to_unix),
+    ),
+    (
+        "datetime_format",
+        "fn(any, string) -> string",
+        Native(crate::datetime_builtins::builtin_datetime_format),
+    ),
+    (
+        "datetime_parse",
+        "fn(string, string) -> Result",
+        Native(crate::datetime_builtins::builtin_datetime_parse),
+    ),
+    // RES-2556: HTTP client builtins.
+    (
+        "http_get",
+        "fn(string) -> any",
+        Native(crate::http_client::builtin_http_get),
+    ),
+    (
+        "http_post",
+        "fn(string, string) -> any",
+        Native(crate::http_client::builtin_http_post),
+    ),
+    // RES-2658: linear algebra — vector and matrix operations (pure).
+    (
+        "vec_add",
+        "fn(any, any) -> array",
+        Native(crate::linear_algebra::builtin_vec_add),
+    ),
+    (
+        "vec_sub",
+        "fn(any, any) -> array",
+        Native(crate::linear_algebra::builtin_vec_sub),
+    ),
+    (
+        "vec_scale",
+        "fn(any, any) -> array",
+        Native(crate::linear_algebra::builtin_vec_scale),
+    ),
+    (
+        "vec_dot",
+        "fn(any, any) -> float",
+        Native(crate::linear_algebra::builtin_vec_dot),
+    ),
+    (
+        "vec_norm",
+        "fn(any) -> float",
+        Native(crate::linear_algebra::builtin_vec_norm),
+    ),
+    (
+        "vec_normalize",
+        "fn(any) -> array",
+        Native(crate::linear_algebra::builtin_vec_normalize),
+    ),
+    (
+        "vec_cross",
+        "fn(any, any) -> array",
+        Native(crate::linear_algebra::builtin_vec_cross),
+    ),
+    (
+        "vec_lerp",
+        "fn(any, any, any) -> array",
+        Native(crate::linear_algebra::builtin_vec_lerp),
+    ),
+    (
+        "mat_mul",
+        "fn(any, any) -> array",
+        Native(crate::linear_algebra::builtin_mat_mul),
+    ),
+    (
+        "mat_add",
+        "fn(any, any) -> array",
+        Native(crate::linear_algebra::builtin_mat_add),
+    ),
+    (
+        "mat_scale",
+        "fn(any, any) -> array",
+        Native(crate::linear_algebra::builtin_mat_scale),
+    ),
+    (
+        "mat_transpose",
+        "fn(any) -> array",
+        Native(crate::linear_algebra::builtin_mat_transpose),
+    ),
+    (
+        "mat_identity",
+        "fn(any) -> array",
+        Native(crate::linear_algebra::builtin_mat_identity),
+    ),
+    (
+        "mat_trace",
+        "fn(any) -> float",
+        Native(crate::linear_algebra::builtin_mat_trace),
+    ),
+    // RES-2654: combinatorics and discrete collection operations (pure).
+    (
+        "array_cartesian_product",
+        "fn(array, array) -> array",
+        Native(crate::combinatorics::builtin_array_cartesian_product),
+    ),
+    (
+        "array_combinations",
+        "fn(array, int) -> array",
+        Native(crate::combinatorics::builtin_array_combinations),
+    ),
+    (
+        "array_permutations",
+        "fn(array, int) -> array",
+        Native(crate::combinatorics::builtin_array_permutations),
+    ),
+    (
+        "array_powerset",
+        "fn(array) -> array",
+        Native(crate::combinatorics::builtin_array_powerset),
+    ),
+    (
+        "array_transpose",
+        "fn(array) -> array",
+        Native(crate::combinatorics::builtin_array_transpose),
+    ),
+    (
+        "array_cartesian_product_n",
+        "fn(array) -> array",
+        Native(crate::combinatorics::builtin_array_cartesian_product_n),
+    ),
+    // RES-2659: graph algorithm builtins (pure — no callbacks).
+    (
+        "graph_bfs",
+        "fn(any, any) -> array",
+        Native(crate::graph_algorithms::builtin_graph_bfs),
+    ),
+    (
+        "graph_dfs",
+        "fn(any, any) -> array",
+        Native(crate::graph_algorithms::builtin_graph_dfs),
+    ),
+    (
+        "graph_has_path",
+        "fn(any, any, any) -> bool",
+        Native(crate::graph_algorithms::builtin_graph_has_path),
+    ),
+    (
+        "graph_topological_sort",
+        "fn(any) -> array",
+        Native(crate::graph_algorithms::builtin_graph_topological_sort),
+    ),
+    (
+        "graph_connected_components",
+        "fn(any) -> array",
+        Native(crate::graph_algorithms::builtin_graph_connected_components),
+    ),
+    (
+        "graph_num_components",
+        "fn(any) -> int",
+        Native(crate::graph_algorithms::builtin_graph_num_components),
+    ),
+    (
+        "graph_out_degrees",
+        "fn(any) -> array",
+        Native(crate::graph_algorithms::builtin_graph_out_degrees),
+    ),
+    (
+        "graph_in_degrees",
+        "fn(any) -> array",
+        Native(crate::graph_algorithms::builtin_graph_in_degrees),
+    ),
+    (
+        "graph_reverse",
+        "fn(any) -> array",
+        Native(crate::graph_algorithms::builtin_graph_reverse),
+    ),
+    (
+        "graph_is_dag",
+        "fn(any) -> bool",
+        Native(crate::graph_algorithms::builtin_graph_is_dag),
+    ),
+    (
+        "graph_reachable",
+        "fn(any, any) -> array",
+        Native(crate::graph_algorithms::builtin_graph_reachable),
+    ),
+    (
+        "graph_dijkstra",
+        "fn(any, any) -> array",
+        Native(crate::graph_algorithms::builtin_graph_dijkstra),
+    ),
+    // RES-2660: extended statistics and matrix decomposition builtins.
+    (
+        "stats_covariance",
+        "fn(any, any) -> float",
+        Native(crate::statistics::builtin_stats_covariance),
+    ),
+    (
+        "stats_correlation",
+        "fn(any, any) -> float",
+        Native(crate::statistics::builtin_stats_correlation),
+    ),
+    (
+        "stats_percentile",
+        "fn(any, any) -> float",
+        Native(crate::statistics::builtin_stats_percentile),
+    ),
+    (
+        "stats_zscore",
+        "fn(any) -> array",
+        Native(crate::statistics::builtin_stats_zscore),
+    ),
+    (
+        "stats_normalize",
+        "fn(any) -> array",
+        Native(crate::statistics::builtin_stats_normalize),
+    ),
+    (
+        "stats_histogram",
+        "fn(any, int) -> array",
+        Native(crate::statistics::builtin_stats_histogram),
+    ),
+    (
+        "stats_linear_regression",
+        "fn(any, any) -> array",
+        Native(crate::statistics::builtin_stats_linear_regression),
+    ),
+    (
+        "stats_moving_average",
+        "fn(any, int) -> array",
+        Native(crate::statistics::builtin_stats_moving_average),
+    ),
+    (
+        "stats_weighted_mean",
+        "fn(any, any) -> float",
+        Native(crate::statistics::builtin_stats_weighted_mean),
+    ),
+    (
+        "stats_geometric_mean",
+        "fn(any) -> float",
+        Native(crate::statistics::builtin_stats_geometric_mean),
+    ),
+    (
+        "stats_harmonic_mean",
+        "fn(any) -> float",
+        Native(crate::statistics::builtin_stats_harmonic_mean),
+    ),
+    (
+        "stats_mode_int",
+        "fn(any) -> int",
+        Native(crate::statistics::builtin_stats_mode_int),
+    ),
+    (
+        "stats_iqr",
+        "fn(any) -> float",
+        Native(crate::statistics::builtin_stats_iqr),
+    ),
+    (
+        "mat_det",
+        "fn(any) -> float",
+        Native(crate::statistics::builtin_mat_det),
+    ),
+    (
+        "mat_inv",
+        "fn(any) -> array",
+        Native(crate::statistics::builtin_mat_inv),
+    ),
+    (
+        "mat_solve",
+        "fn(any, any) -> array",
+        Native(crate::statistics::builtin_mat_solve),
+    ),
+    (
+        "mat_norm_frobenius",
+        "fn(any) -> float",
+        Native(crate::statistics::builtin_mat_norm_frobenius),
+    ),
+    (
+        "mat_rank",
+        "fn(any) -> int",
+        Native(crate::statistics::builtin_mat_rank),
+    ),
+    (
+        "mat_lu",
+        "fn(any) -> array",
+        Native(crate::statistics::builtin_mat_lu),
+    ),
+    // RES-2661: complex number builtins (representation: Array<float> [re, im]).
+    (
+        "complex",
+        "fn(any, any) -> array",
+        Native(crate::complex_numbers::builtin_complex),
+    ),
+    (
+        "complex_real",
+        "fn(any) -> float",
+        Native(crate::complex_numbers::builtin_complex_real),
+    ),
+    (
+        "complex_imag",
+        "fn(any) -> float",
+        Native(crate::complex_numbers::builtin_complex_imag),
+    ),
+    (
+        "complex_add",
+        "fn(any, any) -> array",
+        Native(crate::complex_numbers::builtin_complex_add),
+    ),
+    (
+        "complex_sub",
+        "fn(any, any) -> array",
+        Native(crate::complex_numbers::builtin_complex_sub),
+    ),
+    (
+        "complex_mul",
+        "fn(any, any) -> array",
+        Native(crate::complex_numbers::builtin_complex_mul),
+    ),
+    (
+        "complex_div",
+        "fn(any, any) -> array",
+        Native(crate::complex_numbers::builtin_complex_div),
+    ),
+    (
+        "complex_abs",
+        "fn(any) -> float",
+        Native(crate::complex_numbers::builtin_complex_abs),
+    ),
+    (
+        "complex_arg",
+        "fn(any) -> float",
+        Native(crate::complex_numbers::builtin_complex_arg),
+    ),
+    (
+        "complex_conj",
+        "fn(any) -> array",
+        Native(crate::complex_numbers::builtin_complex_conj),
+    ),
+    (
+        "complex_norm_sq",
+        "fn(any) -> float",
+        Native(crate::complex_numbers::builtin_complex_norm_sq),
+    ),
+    (
+        "complex_exp",
+        "fn(any) -> array",
+        Native(crate::complex_numbers::builtin_complex_exp),
+    ),
+    (
+        "complex_ln",
+        "fn(any) -> array",
+        Native(crate::complex_numbers::builtin_complex_ln),
+    ),
+    (
+        "complex_pow_real",
+        "fn(any, any) -> array",
+        Native(crate::complex_numbers::builtin_complex_pow_real),
+    ),
+    (
+        "complex_sqrt",
+        "fn(any) -> array",
+        Native(crate::complex_numbers::builtin_complex_sqrt),
+    ),
+    (
+        "complex_sin",
+        "fn(any) -> array",
+        Native(crate::complex_numbers::builtin_complex_sin),
+    ),
+    (
+        "complex_cos",
+        "fn(any) -> array",
+        Native(crate::complex_numbers::builtin_complex_cos),
+    ),
+    (
+        "complex_from_polar",
+        "fn(any, any) -> array",
+        Native(crate::complex_numbers::builtin_complex_from_polar),
+    ),
+    // RES-2662: data utilities — linspace, CSV, table formatting, RLE.
+    (
+        "linspace",
+        "fn(any, any, any) -> array",
+        Native(crate::data_utils::builtin_linspace),
+    ),
+    (
+        "logspace",
+        "fn(any, any, any) -> array",
+        Native(crate::data_utils::builtin_logspace),
+    ),
+    (
+        "arange",
+        "fn(any, any, any) -> array",
+        Native(crate::data_utils::builtin_arange),
+    ),
+    (
+        "csv_parse",
+        "fn(any) -> array",
+        Native(crate::data_utils::builtin_csv_parse),
+    ),
+    (
+        "csv_parse_tsv",
+        "fn(any) -> array",
+        Native(crate::data_utils::builtin_csv_parse_tsv),
+    ),
+    (
+        "csv_format",
+        "fn(any) -> string",
+        Native(crate::data_utils::builtin_csv_format),
+    ),
+    (
+        "csv_format_tsv",
+        "fn(any) -> string",
+        Native(crate::data_utils::builtin_csv_format_tsv),
+    ),
+    (
+        "table_format",
+        "fn(any) -> string",
+        Native(crate::data_utils::builtin_table_format),
+    ),
+    (
+        "format_float",
+        "fn(any, any) -> string",
+        Native(crate::data_utils::builtin_format_float),
+    ),
+    (
+        "format_int_width",
+        "fn(int, int) -> string",
+        Native(crate::data_utils::builtin_format_int_width),
+    ),
+    (
+        "format_float_sci",
+        "fn(any, int) -> string",
+        Native(crate::data_utils::builtin_format_float_sci),
+    ),
+    (
+        "rle_encode",
+        "fn(any) -> array",
+        Native(crate::data_utils::builtin_rle_encode),
+    ),
+    (
+        "rle_decode",
+        "fn(any) -> array",
+        Native(crate::data_utils::builtin_rle_decode),
+    ),
+    // Pain-points hardening: struct sort, depth-bounded flatten, radix parse/format,
+    // dedup-by, int_to_oct.
+    (
+        "array_sort_by_field",
+        "fn(array, string) -> array",
+        Native(crate::array_struct_sort::builtin_array_sort_by_field),
+    ),
+    (
+        "array_sort_by_field_desc",
+        "fn(array, string) -> array",
+        Native(crate::array_struct_sort::builtin_array_sort_by_field_desc),
+    ),
+    (
+        "array_flatten_depth",
+        "fn(array, int) -> array",
+        Native(crate::array_flatten_depth::builtin_array_flatten_depth),
+    ),
+    (
+        "int_parse_hex",
+        "fn(string) -> int",
+        Native(crate::int_parse_radix::builtin_int_parse_hex),
+    ),
+    (
+        "int_parse_bin",
+        "fn(string) -> int",
+        Native(crate::int_parse_radix::builtin_int_parse_bin),
+    ),
+    (
+        "int_to_oct",
+        "fn(int) -> string",
+        Native(crate::int_parse_radix::builtin_int_to_oct),
+    ),
+    (
+        "array_dedup_by",
+        "fn(array, string) -> array",
+        Native(crate::array_dedup_none::builtin_array_dedup_by),
+    ),
+    // RES-2619: Char type builtins.
+    (
+        "char_is_alpha",
+        "fn(any) -> bool",
+        Native(crate::char_type::builtin_char_is_alpha),
+    ),
+    (
+        "char_is_digit",
+        "fn(any) -> bool",
+        Native(crate::char_type::builtin_char_is_digit),
+    ),
+    (
+        "char_is_whitespace",
+        "fn(any) -> bool",
+        Native(crate::char_type::builtin_char_is_whitespace),
+    ),
+    (
+        "char_is_upper",
+        "fn(any) -> bool",
+        Native(crate::char_type::builtin_char_is_upper),
+    ),
+    (
+        "char_is_lower",
+        "fn(any) -> bool",
+        Native(crate::char_type::builtin_char_is_lower),
+    ),
+    (
+        "char_is_alphanumeric",
+        "fn(any) -> bool",
+        Native(crate::char_type::builtin_char_is_alphanumeric),
+    ),
+    (
+        "char_is_ascii",
+        "fn(any) -> bool",
+        Native(crate::char_type::builtin_char_is_ascii),
+    ),
+    (
+        "char_to_upper",
+        "fn(any) -> char",
+        Native(crate::char_type::builtin_char_to_upper),
+    ),
+    (
+        "char_to_lower",
+        "fn(any) -> char",
+        Native(crate::char_type::builtin_char_to_lower),
+    ),
+    (
+        "char_to_int",
+        "fn(any) -> int",
+        Native(crate::char_type::builtin_char_to_int),
+    ),
+    (
+        "int_to_char",
+        "fn(int) -> char",
+        Native(crate::char_type::builtin_int_to_char),
+    ),
+    (
+        "char_to_string",
+        "fn(any) -> string",
+        Native(crate::char_type::builtin_char_to_string),
+    ),
+    // RES-2586: deque (double-ended queue) collection builtins.
+    (
+        "deque_new",
+        "fn() -> any",
+        Native(crate::deque::builtin_deque_new),
+    ),
+    (
+        "deque_push_front",
+        "fn(any, any) -> any",
+        Native(crate::deque::builtin_deque_push_front),
+    ),
+    (
+        "deque_push_back",
+        "fn(any, any) -> any",
+        Native(crate::deque::builtin_deque_push_back),
+    ),
+    (
+        "deque_pop_front",
+        "fn(any) -> any",
+        Native(crate::deque::builtin_deque_pop_front),
+    ),
+    (
+        "deque_pop_back",
+        "fn(any) -> any",
+        Native(crate::deque::builtin_deque_pop_back),
+    ),
+    (
+        "deque_peek_front",
+        "fn(any) -> any",
+        Native(crate::deque::builtin_deque_peek_front),
+    ),
+    (
+        "deque_peek_back",
+        "fn(any) -> any",
+        Native(crate::deque::builtin_deque_peek_back),
+    ),
+    (
+        "deque_len",
+        "fn(any) -> int",
+        Native(crate::deque::builtin_deque_len),
+    ),
+    (
+        "deque_is_empty",
+        "fn(any) -> bool",
+        Native(crate::deque::builtin_deque_is_empty),
+    ),
+    // RES-2587: priority queue / binary heap collection builtins.
+    (
+        "heap_new",
+        "fn() -> any",
+        Native(crate::heap::builtin_heap_new),
+    ),
+    (
+        "heap_new_max",
+        "fn() -> any",
+        Native(crate::heap::builtin_heap_new_max),
+    ),
+    (
+        "heap_push",
+        "fn(any, any) -> any",
+        Native(crate::heap::builtin_heap_push),
+    ),
+    (
+        "heap_pop",
+        "fn(any) -> any",
+        Native(crate::heap::builtin_heap_pop),
+    ),
+    (
+        "heap_peek",
+        "fn(any) -> any",
+        Native(crate::heap::builtin_heap_peek),
+    ),
+    (
+        "heap_len",
+        "fn(any) -> int",
+        Native(crate::heap::builtin_heap_len),
+    ),
+    (
+        "heap_is_empty",
+        "fn(any) -> bool",
+        Native(crate::heap::builtin_heap_is_empty),
+    ),
+    // RES-2558: process spawning builtins (std-only).
+    (
+        "exec",
+        "fn(string, array) -> Result",
+        Native(crate::process_exec::builtin_exec),
+    ),
+    (
+        "exec_shell",
+        "fn(string) -> Result",
+        Native(crate::process_exec::builtin_exec_shell),
+    ),
+    // RES-2555: TCP/UDP networking builtins (std-only).
+    (
+        "tcp_connect",
+        "fn(any, any) -> Result",
+        Native(crate::tcp_udp::builtin_tcp_connect),
+    ),
+    (
+        "tcp_listen",
+        "fn(any, any) -> Result",
+        Native(crate::tcp_udp::builtin_tcp_listen),
+    ),
+    (
+        "tcp_accept",
+        "fn(any) -> Result",
+        Native(crate::tcp_udp::builtin_tcp_accept),
+    ),
+    (
+        "tcp_read",
+        "fn(any, any) -> Result",
+        Native(crate::tcp_udp::builtin_tcp_read),
+    ),
+    (
+        "tcp_write",
+        "fn(any, any) -> Result",
+        Native(crate::tcp_udp::builtin_tcp_write),
+    ),
+    (
+        "tcp_close",
+        "fn(any) -> bool",
+        Native(crate::tcp_udp::builtin_tcp_close),
+    ),
+    (
+        "tcp_set_timeout",
+        "fn(any, int) -> bool",
+        Native(crate::tcp_udp::builtin_tcp_set_timeout),
+    ),
+    (
+        "udp_bind",
+        "fn(string, int) -> Result",
+        Native(crate::tcp_udp::builtin_udp_bind),
+    ),
+    (
+        "udp_send_to",
+        "fn(any, string, string, int) -> Result",
+        Native(crate::tcp_udp::builtin_udp_send_to),
+    ),
+    (
+        "udp_recv_from",
+        "fn(any, int) -> Result",
+        Native(crate::tcp_udp::builtin_udp_recv_from),
+    ),
+    (
+        "udp_close",
+        "fn(any) -> bool",
+        Native(crate::tcp_udp::builtin_udp_close),
+    ),
+    // MQTT and serial-port telemetry builtins (`telemetry` feature).
+    (
+        "mqtt_publish",
+        "fn(string, string, string, int) -> void",
+        Native(crate::telemetry_io::builtin_mqtt_publish),
+    ),
+    (
+        "mqtt_subscribe",
+        "fn(string, string, int) -> any",
+        Native(crate::telemetry_io::builtin_mqtt_subscribe),
+    ),
+    (
+        "serial_open",
+        "fn(string, int) -> any",
+        Native(crate::telemetry_io::builtin_serial_open),
+    ),
+    (
+        "serial_read",
+        "fn(any, int, int) -> string",
+        Native(crate::telemetry_io::builtin_serial_read),
+    ),
+    (
+        "serial_write",
+        "fn(any, string) -> int",
+        Native(crate::telemetry_io::builtin_serial_write),
+    ),
+    (
+        "serial_close",
+        "fn(any) -> bool",
+        Native(crate::telemetry_io::builtin_serial_close),
+    ),
+    // RES-2583: mutex and rwlock synchronization primitives.
+    (
+        "mutex_new",
+        "fn(any) -> any",
+        Native(crate::mutex_rwlock::builtin_mutex_new),
+    ),
+    (
+        "mutex_lock",
+        "fn(any) -> any",
+        Native(crate::mutex_rwlock::builtin_mutex_lock),
+    ),
+    (
+        "mutex_unlock",
+        "fn(any) -> any",
+        Native(crate::mutex_rwlock::builtin_mutex_unlock),
+    ),
+    (
+        "mutex_try_lock",
+        "fn(any) -> any",
+        Native(crate::mutex_rwlock::builtin_mutex_try_lock),
+    ),
+    (
+        "rwlock_new",
+        "fn(any) -> any",
+        Native(crate::mutex_rwlock::builtin_rwlock_new),
+    ),
+    (
+        "rwlock_read",
+        "fn(any) -> any",
+        Native(crate::mutex_rwlock::builtin_rwlock_read),
+    ),
+    (
+        "rwlock_write",
+        "fn(any) -> any",
+        Native(crate::mutex_rwlock::builtin_rwlock_write),
+    ),
+    (
+        "rwlock_unlock",
+        "fn(any) -> any",
+        Native(crate::mutex_rwlock::builtin_rwlock_unlock),
+    ),
+    // RES-2557: file metadata builtins (std-only).
+    (
+        "file_exists",
+        "fn(string) -> bool",
+        Native(crate::file_meta::builtin_file_exists),
+    ),
+    (
+        "file_is_dir",
+        "fn(string) -> bool",
+        Native(crate::file_meta::builtin_file_is_dir),
+    ),
+    (
+        "file_is_file",
+        "fn(string) -> bool",
+        Native(crate::file_meta::builtin_file_is_file),
+    ),
+    (
+        "file_size",
+        "fn(string) -> Result",
+        Native(crate::file_meta::builtin_file_size),
+    ),
+    (
+        "file_stat",
+        "fn(string) -> Result",
+        Native(crate::file_meta::builtin_file_stat),
+    ),
+    (
+        "dir_list",
+        "fn(string) -> Result",
+        Native(crate::file_meta::builtin_dir_list),
+    ),
+    // Evaluated inline by the tree-walker's call path because they
+    // invoke user-supplied closures; keep in sync with the inline
+    // `match fn_name.as_str()` arms in `Interpreter::eval` (RES-1859
+    // and follow-ups).
+    ("array_all", "fn(array, any) -> bool", Intrinsic),
+    ("array_any", "fn(array, any) -> bool", Intrinsic),
+    ("array_count_if", "fn(array, any) -> int", Intrinsic),
+    ("array_drop_while", "fn(array, any) -> array", Intrinsic),
+    ("array_filter", "fn(array, any) -> array", Intrinsic),
+    ("array_find", "fn(array, any) -> any", Intrinsic),
+    ("array_find_index", "fn(array, any) -> int", Intrinsic),
+    ("array_flat_map", "fn(array, any) -> array", Intrinsic),
+    ("array_from_fn", "fn(int, any) -> array", Intrinsic),
+    ("array_group_by", "fn(array, any) -> any", Intrinsic),
+    ("array_iterate", "fn(any, int, any) -> array", Intrinsic),
+    ("array_key_by", "fn(array, any) -> any", Intrinsic),
+    ("array_map", "fn(array, any) -> array", Intrinsic),
+    ("array_max_by", "fn(array, any) -> any", Intrinsic),
+    ("array_min_by", "fn(array, any) -> any", Intrinsic),
+    ("array_none", "fn(array, any) -> bool", Intrinsic),
+    ("array_partition", "fn(array, any) -> array", Intrinsic),
+    ("array_product_by", "fn(array, any) -> int", Intrinsic),
+    ("array_reduce", "fn(array, any, any) -> any", Intrinsic),
+    ("array_scan", "fn(array, any, any) -> array", Intrinsic),
+    ("array_sort_by", "fn(array, any) -> array", Intrinsic),
+    ("array_sum_by", "fn(array, any) -> int", Intrinsic),
+    ("array_take_while", "fn(array, any) -> array", Intrinsic),
+    (
+        "array_zip_with",
+        "fn(array, array, any) -> array",
+        Intrinsic,
+    ),
+    ("filter", "fn(array, any) -> array", Intrinsic),
+    ("map", "fn(array, any) -> array", Intrinsic),
+    ("map_filter", "fn(any, any) -> any", Intrinsic),
+    ("map_for_each", "fn(any, any) -> void", Intrinsic),
+    ("map_map_values", "fn(any, any) -> any", Intrinsic),
+    ("map_merge_with", "fn(any, any, any) -> any", Intrinsic),
+    (
+        "map_update_with",
+        "fn(any, any, any, any) -> any",
+        Intrinsic,
+    ),
+    ("option_and_then", "fn(any, any) -> any", Intrinsic),
+    ("option_filter", "fn(any, any) -> any", Intrinsic),
+    ("option_map", "fn(any, any) -> any", Intrinsic),
+    ("option_or_else", "fn(any, any) -> any", Intrinsic),
+    ("reduce", "fn(array, any, any) -> any", Intrinsic),
+    ("result_and_then", "fn(any, any) -> any", Intrinsic),
+    ("result_map", "fn(any, any) -> any", Intrinsic),
+    ("result_map_err", "fn(any, any) -> any", Intrinsic),
+    ("result_or_else", "fn(any, any) -> any", Intrinsic),
+    ("sort_by", "fn(array, any) -> array", Intrinsic),
+    ("stacktrace", "fn() -> array", Intrinsic),
+    ("string_filter_by", "fn(string, any) -> string", Intrinsic),
+    ("string_fold", "fn(string, any, any) -> any", Intrinsic),
+    ("string_for_each_char", "fn(string, any) -> void", Intrinsic),
+    ("string_map_chars", "fn(string, any) -> string", Intrinsic),
+];
+
+/// The registry row for `name`, or `None` when it is not a builtin.
+pub(crate) fn lookup(name: &str) -> Option<&'static (&'static str, &'static str, BuiltinEval)> {
+    static INDEX: std::sync::LazyLock<std::collections::HashMap<&'static str, usize>> =
+        std::sync::LazyLock::new(|| {
+            BUILTINS
+                .iter()
+                .enumerate()
+                .filter(|(_, (name, _, _))| !name.starts_with("__"))
+                .map(|(i, (name, _, _))| (*name, i))
+                .collect()
+        });
+    INDEX.get(name).map(|&i| &BUILTINS[i])
+}
+
+/// Every user-callable builtin with a function pointer.
+pub(crate) fn native_builtins() -> impl Iterator<Item = (&'static str, BuiltinFn)> {
+    BUILTINS.iter().filter_map(|(name, _, eval)| match eval {
+        Native(func) if !name.starts_with("__") => Some((*name, *func)),
+        _ => None,
+    })
+}
+
+/// Every user-callable builtin with its parsed checker signature.
+pub(crate) fn signatures() -> impl Iterator<Item = (&'static str, Type)> {
+    BUILTINS
+        .iter()
+        .filter(|(name, _, _)| !name.starts_with("__"))
+        .map(|(name, sig, _)| {
+            let ty = parse_signature(sig)
+                .unwrap_or_else(|| panic!("builtin `{name}` has a malformed signature `{sig}`"));
+            (*name, ty)
+        })
+}
+
+/// Parse a signature written in the checker's `Display` syntax.
+fn parse_signature(sig: &str) -> Option<Type> {
+    match parse_type(sig)? {
+        (ty, "") => Some(ty),
+        _ => None,
+    }
+}
+
+fn parse_type(s: &str) -> Option<(Type, &str)> {
+    if let Some(mut rest) = s.strip_prefix("fn(") {
+        let mut params = Vec::new();
+        if let Some(r) = rest.strip_prefix(')') {
+            rest = r;
+        } else {
+            loop {
+                let (param, r) = parse_type(rest)?;
+                params.push(param);
+                if let Some(r) = r.strip_prefix(", ") {
+                    rest = r;
+                } else {
+                    rest = r.strip_prefix(')')?;
+                    break;
+                }
+            }
+        }
+        let (ret, rest) = parse_type(rest.strip_prefix(" -> ")?)?;
+        let ty = Type::Function {
+            params,
+            return_type: Box::new(ret),
+        };
+        return Some((ty, rest));
+    }
+    if let Some(rest) = s.strip_prefix('[') {
+        let (elem, rest) = parse_type(rest)?;
+        return Some((Type::TypedArray(Box::new(elem)), rest.strip_prefix(']')?));
+    }
+    let end = s
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .unwrap_or(s.len());
+    let (word, rest) = s.split_at(end);
+    let ty = match word {
+        "Option" => {
+            let Some(r) = rest.strip_prefix('<') else {
+                return Some((Type::Option(Box::new(Type::Any)), rest));
+            };
+            let (inner, r) = parse_type(r)?;
+            return Some((Type::Option(Box::new(inner)), r.strip_prefix('>')?));
+        }
+        "int" => Type::Int,
+        "Int8" => Type::Int8,
+        "Int16" => Type::Int16,
+        "Int32" => Type::Int32,
+        "UInt8" => Type::UInt8,
+        "UInt16" => Type::UInt16,
+        "UInt32" => Type::UInt32,
+        "UInt64" => Type::UInt64,
+        "float" => Type::Float,
+        "f32" => Type::Float32,
+        "string" => Type::String,
+        "bool" => Type::Bool,
+        "char" => Type::Char,
+        "bytes" => Type::Bytes,
+        "array" => Type::Array,
+        "range" => Type::Range,
+        "Result" => Type::Result,
+        "void" => Type::Void,
+        "any" => Type::Any,
+        _ if word.starts_with(|c: char| c.is_ascii_uppercase()) => Type::Struct(word.to_string()),
+        _ => return None,
+    };
+    Some((ty, rest))
+}
+
+/// The stdlib reference, searched by [`doc`].
+const STDLIB_REFERENCE: &str = include_str!("../../docs/STDLIB.md");
+
+/// The `docs/STDLIB.md` entry for builtin `name`: its signature and
+/// description, up to its first example. `None` for undocumented
+/// names.
+pub(crate) fn doc(name: &str) -> Option<String> {
+    let tag = format!("`{name}`");
+    let mut lines = STDLIB_REFERENCE.lines();
+    lines.find(|l| l.starts_with("### ") && l.contains(&tag))?;
+    let section: Vec<&str> = lines
+        .take_while(|l| {
+            !l.starts_with('#')
+                && !l.starts_with("---")
+                && !l.starts_with("```")
+                && !l.starts_with("**Example")
+        })
+        .collect();
+    let text = section.join("\n").trim().replace("**", "");
+    (!text.is_empty()).then_some(text)
+}
+
+/// Bind every native builtin into a runtime environment.
+pub(crate) fn register_runtime(env: &crate::Environment) {
+    for (name, func) in native_builtins() {
+        env.set(name.to_string(), Value::Builtin { name, func });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_signature_parses_back_to_its_text() {
+        for (name, sig, _) in BUILTINS {
+            let ty =
+                parse_signature(sig).unwrap_or_else(|| panic!("`{name}`: cannot parse `{sig}`"));
+            assert_eq!(ty.to_string(), *sig, "`{name}` signature is not canonical");
+        }
+    }
+
+    #[test]
+    fn every_builtin_is_declared_once() {
+        let mut seen = std::collections::HashSet::new();
+        for (name, _, _) in BUILTINS {
+            assert!(seen.insert(*name), "`{name}` is declared twice");
+        }
+    }
+
+    #[test]
+    fn every_builtin_has_a_checker_signature() {
+        let untyped: Vec<&str> = signatures()
+            .filter(|(_, ty)| matches!(ty, Type::Any))
+            .map(|(name, _)| name)
+            .collect();
+        assert!(
+            untyped.is_empty(),
+            "builtins typed as bare `any`: {untyped:?}"
+        );
+    }
+
+    #[test]
+    fn every_native_builtin_resolves_for_the_vm() {
+        for (name, _) in native_builtins() {
+            assert!(crate::lookup_builtin(name).is_some(), "`{name}`");
+        }
+    }
+
+    #[test]
+    fn lookup_reports_signature_and_evaluator() {
+        let (name, sig, eval) = lookup("println").expect("println is a builtin");
+        assert_eq!(*name, "println");
+        assert_eq!(*sig, "fn(any) -> void");
+        assert!(matches!(eval, Native(_)));
+
+        let (_, _, Native(abs)) = lookup("abs").expect("abs is a builtin") else {
+            panic!("abs should be a native builtin");
+        };
+        assert!(matches!(abs(&[Value::Int(-3)]), Ok(Value::Int(3))));
+
+        let (_, _, eval) = lookup("array_map").expect("array_map is a builtin");
+        assert!(matches!(eval, Intrinsic));
+
+        assert!(lookup("__range").is_none());
+        assert!(lookup("definitely_not_a_builtin").is_none());
+    }
+
+    #[test]
+    fn doc_is_the_reference_entry_up_to_its_example() {
+        let entry = doc("read_int").expect("read_int is documented");
+        assert!(
+            entry.starts_with("Signatures: `read_line() -> string`"),
+            "{entry}"
+        );
+        assert!(!entry.contains("```"), "{entry}");
+        assert!(doc("array_map").is_none());
+    }
+
+    #[test]
+    fn formerly_untyped_builtins_are_checked() {
+        let (prog, errs) =
+            crate::parse("let xs = sort([3, 1, 2]);\nprintln(join([\"a\"], \",\"));");
+        assert!(errs.is_empty(), "{errs:?}");
+        crate::typechecker::TypeChecker::new()
+            .check_program(&prog)
+            .expect("well-typed builtin calls must check");
+
+        let (prog, errs) = crate::parse("let n = collatz_length(\"seven\");");
+        assert!(errs.is_empty(), "{errs:?}");
+        assert!(
+            crate::typechecker::TypeChecker::new()
+                .check_program(&prog)
+                .is_err(),
+            "collatz_length takes an int"
+        );
+    }
+}
@@ -815,6 +815,8 @@ pub enum CompileError {
     InternalError(&'static str),
     /// FFI v2: foreign library or symbol resolution failed.
     FfiError(String),
+    /// A call to an overloaded fn that arity alone cannot resolve.
+    Overload(String),
 }
 
 impl std::fmt::Display for CompileError {
@@ -856,6 +858,7 @@ impl std::fmt::Display for CompileError {
                 write!(f, "bytecode compile: internal error: {}", msg)
             }
             CompileError::FfiError(msg) => write!(f, "FFI error: {}", msg),
+            CompileError::Overload(msg) => write!(f, "bytecode compile: {}", msg),
         }
     }
 }
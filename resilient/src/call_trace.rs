@@ -0,0 +1,141 @@
+//! `--trace`: a log of function calls and returns.
+//!
+//! While tracing is on, the tree-walking interpreter reports every
+//! call to a user function on stderr with its arguments, and every
+//! return with its value or the error that ended it. Lines are
+//! indented by how many traced calls are open, so the output reads as
+//! a call tree; the `[LIVE BLOCK]` retry lines land in between, which
+//! shows what each attempt of a `live` block called before it failed.
+//!
+//! ```text
+//! [trace] → fetch(attempt = 1)
+//! [trace]   → parse(raw = "x", attempt = 1)
+//! [trace]   ✗ parse: Division by zero
+//! [trace] ✗ fetch: Division by zero
+//! [LIVE BLOCK] Error detected (attempt 1/3): Division by zero
+//! ...
+//! [trace] → fetch(attempt = 2)
+//! [trace]   → parse(raw = "x", attempt = 2)
+//! [trace]   ← parse = 42
+//! [trace] ← fetch = 42
+//! ```
+//!
+//! `--trace=NAME,...` traces only the named functions. Self tail
+//! calls (`return f(...)` inside `f`) run as a loop, so a chain of them
+//! shows as one call.
+//!
+//! The switch is per thread, like `fuel`; interpreters started while
+//! it is on register [`Observer`] (see `eval_observer`).
+
+use std::cell::{Cell, RefCell};
+
+use crate::bridge::ResilientValue;
+use crate::eval_observer::EvalObserver;
+
+/// Which functions to trace; an empty list means all of them.
+struct Trace {
+    only: Vec<String>,
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<Trace>> = const { RefCell::new(None) };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Trace calls on this thread: every function for `Some(vec![])`,
+/// just the named ones otherwise. `None` turns tracing off.
+pub fn set_trace(only: Option<Vec<String>>) {
+    ACTIVE.with(|a| *a.borrow_mut() = only.map(|only| Trace { only }));
+    DEPTH.with(|d| d.set(0));
+}
+
+/// Whether any calls are traced on this thread.
+pub(crate) fn active() -> bool {
+    ACTIVE.with(|a| a.borrow().is_some())
+}
+
+/// Logs the traced calls of the run.
+pub(crate) struct Observer;
+
+impl EvalObserver for Observer {
+    fn call_enter(
+        &mut self,
+        name: &str,
+        parameters: &[(String, String)],
+        args: &[ResilientValue],
+        _: usize,
+    ) {
+        if traces(name) {
+            enter(name, parameters, args);
+        }
+    }
+
+    fn call_exit(&mut self, name: &str, result: Result<&ResilientValue, &str>) {
+        if traces(name) {
+            exit(name, result);
+        }
+    }
+}
+
+/// Whether a call to `name` is traced.
+#[inline]
+pub(crate) fn traces(name: &str) -> bool {
+    ACTIVE.with(|a| {
+        a.borrow()
+            .as_ref()
+            .is_some_and(|t| t.only.is_empty() || t.only.iter().any(|n| n == name))
+    })
+}
+
+/// Log a call to `name`, pairing `args` with the parameter names.
+fn enter(name: &str, parameters: &[(String, String)], args: &[ResilientValue]) {
+    let args: Vec<String> = args
+        .iter()
+        .enumerate()
+        .map(|(i, v)| match parameters.get(i) {
+            Some((_, param)) => format!("{} = {}", param, v),
+            None => v.to_string(),
+        })
+        .collect();
+    let depth = DEPTH.with(|d| d.replace(d.get() + 1));
+    eprintln!("[trace] {}→ {}({})", indent(depth), name, args.join(", "));
+}
+
+/// Log the end of the call to `name` that [`enter`] logged last.
+fn exit(name: &str, result: Result<&ResilientValue, &str>) {
+    let depth = DEPTH.with(|d| {
+        let depth = d.get().saturating_sub(1);
+        d.set(depth);
+        depth
+    });
+    match result {
+        Ok(value) => eprintln!("[trace] {}← {} = {}", indent(depth), name, value),
+        // Only the first line: the rest is the stack trace.
+        Err(e) => eprintln!(
+            "[trace] {}✗ {}: {}",
+            indent(depth),
+            name,
+            e.lines().next().unwrap_or_default()
+        ),
+    }
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_filter_limits_the_traced_functions() {
+        assert!(!traces("f"));
+        set_trace(Some(vec![]));
+        assert!(traces("f") && traces("g"));
+        set_trace(Some(vec!["g".into()]));
+        assert!(!traces("f") && traces("g"));
+        set_trace(None);
+        assert!(!traces("g"));
+    }
+}
@@ -112,6 +112,7 @@ const STD_ONLY_BUILTINS: &[&str] = &[
     "clock_now",
     "clock_elapsed",
     "env",
+    "args",
     "copysign",
     "exec",
     "exec_shell",
@@ -123,6 +124,9 @@ const STD_ONLY_BUILTINS: &[&str] = &[
     "file_is_file",
     "file_open",
     "file_read",
+    "read_file",
+    "write_file",
+    "append_file",
     "file_read_chunk",
     "file_seek",
     "file_size",
@@ -131,6 +135,8 @@ const STD_ONLY_BUILTINS: &[&str] = &[
     "file_write_chunk",
     "dir_list",
     "input",
+    "read_line",
+    "read_int",
     "random_float",
     "random_int",
     "to_degrees",
@@ -155,6 +161,12 @@ const STD_ONLY_BUILTINS: &[&str] = &[
     "udp_close",
     "udp_recv_from",
     "udp_send_to",
+    "mqtt_publish",
+    "mqtt_subscribe",
+    "serial_open",
+    "serial_read",
+    "serial_write",
+    "serial_close",
     "datetime_from_unix",
     "datetime_format",
     "datetime_now",
@@ -335,13 +347,13 @@ pub fn parse_cfg_attribute(parser: &mut Parser) -> Option<crate::Node> {
     // feature-module diagnostic that cites `rec.line` (e.g.
     // `associated_constants`, and now `atomic_types` duplicate
     // detection) report line 0 instead of the real location.
-    let attr_line = parser.current_line;
+    let attr_line = parser.current_span.start.line;
     parser.next_token(); // consume `#[`
 
     // Expect identifier `cfg`. Anything else is a parse error; recover by
     // skipping to `]` and parsing the next item normally (no gating).
     let attr_name = match &parser.current_token {
-        Token::Identifier(n) => n.clone(),
+        Token::Identifier(n) => n.to_string(),
         other => {
             let tok = other.clone();
             parser.record_error(format!(
@@ -560,7 +572,7 @@ fn atomic_node_item_name(node: &crate::Node) -> Option<&str> {
 /// cursor through the surrounding `)` / `]`.
 fn parse_predicate(parser: &mut Parser) -> CfgPredicate {
     let kind = match &parser.current_token {
-        Token::Identifier(n) => n.clone(),
+        Token::Identifier(n) => n.to_string(),
         other => {
             let tok = other.clone();
             parser.record_error(format!(
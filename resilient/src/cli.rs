@@ -0,0 +1,308 @@
+//! The subcommands `rz` defines with clap: `run`, `check`, `verify`,
+//! `repl`, `fmt` and `test`, each with its own flags and `--help`.
+//!
+//! `run_cli` hands its arguments to [`parse`] first; it answers only
+//! when the first one names one of these, so bare `rz [FLAGS] <file>`
+//! and the other subcommands keep their own parsers. `rz run` takes
+//! the same flags as that file mode and forwards them to it as given,
+//! which is why its arguments stay raw strings here.
+
+use crate::AstDumpFormat;
+use crate::coercion::CoercionPolicy;
+use crate::error_format::ErrorFormat;
+use crate::warnings::{self, WarningCategory};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "rz", disable_help_subcommand = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// The names [`Command`] answers to.
+const NAMES: [&str; 6] = ["run", "check", "verify", "repl", "fmt", "test"];
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Run a file; the same as `rz [FLAGS] <file>`
+    #[command(
+        override_usage = "rz run <file> [FLAGS] [-- ARGS...]",
+        after_help = RUN_AFTER_HELP
+    )]
+    Run(RunArgs),
+    /// Type-check files without running them
+    #[command(after_help = CHECK_AFTER_HELP)]
+    Check(CheckArgs),
+    /// Type-check files and verify their contracts; a refuted one
+    /// exits 3
+    #[command(after_help = VERIFY_AFTER_HELP)]
+    Verify(CheckArgs),
+    /// Start the interactive REPL (bare `rz` does the same)
+    #[command(after_help = REPL_AFTER_HELP)]
+    Repl(ReplArgs),
+    /// Format Resilient source files
+    #[command(after_help = FMT_AFTER_HELP)]
+    Fmt(FmtArgs),
+    /// Discover and run `test fn` declarations and `fn test_*()` functions
+    #[command(after_help = TEST_AFTER_HELP)]
+    Test(TestArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct RunArgs {
+    /// The file (`-` reads stdin) and any `rz --help` flags, in any
+    /// order; arguments after `--` go to the program
+    #[arg(required = true, allow_hyphen_values = true, value_name = "FILE|FLAG")]
+    pub(crate) args: Vec<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct CheckArgs {
+    /// Files to check; a directory holding a resilient.toml is checked
+    /// from its entry file
+    #[arg(value_name = "FILE|DIR")]
+    pub(crate) files: Vec<PathBuf>,
+    /// Suppress success output
+    #[arg(short, long)]
+    pub(crate) quiet: bool,
+    /// Emit parse/type diagnostics as JSON
+    #[arg(long)]
+    pub(crate) emit_diagnostics_json: bool,
+    /// Diagnostics on stderr as human, json, or sarif
+    #[arg(long, value_name = "FORMAT", value_parser = parse_error_format)]
+    pub(crate) error_format: Option<ErrorFormat>,
+    /// Promote safety-critical lint failures
+    #[arg(long)]
+    pub(crate) safety_critical: bool,
+    /// Reject `Any` (untyped bindings, `any` annotations)
+    #[arg(long)]
+    pub(crate) strict: bool,
+    /// Implicit conversions: standard, strict, or permissive
+    #[arg(long, value_name = "POLICY", value_parser = parse_coercion)]
+    pub(crate) coercion: Option<CoercionPolicy>,
+    /// Enable a warning category: unused, shadowing, implicit-any,
+    /// implicit-coercion, or all
+    #[arg(long, value_name = "CATEGORY", value_parser = parse_warnings)]
+    warn: Vec<Categories>,
+    /// Disable a warning category
+    #[arg(long, value_name = "CATEGORY", value_parser = parse_warnings)]
+    no_warn: Vec<Categories>,
+    /// `--warn` and `--no-warn` in command-line order, so a later one
+    /// wins over an earlier one
+    #[arg(skip)]
+    pub(crate) warnings: Vec<(WarningCategory, bool)>,
+    /// Report enabled warnings as errors
+    #[arg(long)]
+    pub(crate) deny_warnings: bool,
+    /// Unresolved refinement obligations become errors (requires
+    /// --features z3)
+    #[arg(long)]
+    pub(crate) strict_refinements: bool,
+    /// Per-Z3-query timeout in milliseconds [default: 5000, or the
+    /// project's `[verify] timeout_ms`]
+    #[arg(long, value_name = "N")]
+    pub(crate) verifier_timeout_ms: Option<u32>,
+    /// SMT theory: bv, lia, or auto (requires --features z3)
+    #[arg(long, value_name = "MODE")]
+    pub(crate) z3_theory: Option<String>,
+    /// Print the parsed AST as a tree (or JSON) instead of checking
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "tree",
+        value_parser = parse_dump_ast
+    )]
+    pub(crate) dump_ast: Option<AstDumpFormat>,
+}
+
+#[derive(Args)]
+pub(crate) struct ReplArgs {
+    /// Directory the REPL's `examples` command lists
+    #[arg(long, value_name = "DIR")]
+    pub(crate) examples_dir: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub(crate) struct FmtArgs {
+    /// Files to format; only --check takes more than one
+    #[arg(value_name = "FILE")]
+    pub(crate) files: Vec<PathBuf>,
+    /// Rewrite the file instead of printing formatted source
+    #[arg(short, long)]
+    pub(crate) in_place: bool,
+    /// Exit non-zero if any file is not already formatted
+    #[arg(long)]
+    pub(crate) check: bool,
+    /// Indent N spaces per level [default: the project's `[fmt] indent`
+    /// in resilient.toml, else 4]
+    #[arg(long, value_name = "N")]
+    pub(crate) indent: Option<usize>,
+}
+
+#[derive(Args)]
+pub(crate) struct TestArgs {
+    /// A .rz file, or a directory to search recursively [default: the
+    /// current directory]
+    #[arg(value_name = "FILE|DIR")]
+    pub(crate) target: Option<String>,
+    /// Only run tests whose name contains SUBSTR
+    #[arg(long, value_name = "SUBSTR")]
+    pub(crate) filter: Option<String>,
+    /// Write line, branch and assert coverage of the files the tests
+    /// ran to DIR/lcov.info and DIR/index.html
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "coverage"
+    )]
+    pub(crate) coverage: Option<PathBuf>,
+    /// Run each file and compare its stdout with <name>.snap; --filter
+    /// then matches file paths
+    #[arg(long)]
+    pub(crate) snapshot: bool,
+    /// With --snapshot, write missing or changed snapshots
+    #[arg(long)]
+    pub(crate) update: bool,
+}
+
+/// Parse `args` (program name first) when they start with one of the
+/// clap subcommands. Usage errors and `--help` print and exit here,
+/// with status 2 and 0.
+pub(crate) fn parse(args: &[String]) -> Option<Command> {
+    if !NAMES.contains(&args.get(1)?.as_str()) {
+        return None;
+    }
+    let matches = Cli::command()
+        .try_get_matches_from(args)
+        .unwrap_or_else(|e| e.exit());
+    let mut command = Cli::from_arg_matches(&matches)
+        .unwrap_or_else(|e| e.exit())
+        .command;
+    if let (Command::Check(check) | Command::Verify(check), Some((_, sub))) =
+        (&mut command, matches.subcommand())
+    {
+        let mut flags: Vec<(usize, bool, &Categories)> = Vec::new();
+        for (id, on) in [("warn", true), ("no_warn", false)] {
+            if let (Some(indices), Some(values)) =
+                (sub.indices_of(id), sub.get_many::<Categories>(id))
+            {
+                flags.extend(indices.zip(values).map(|(i, v)| (i, on, v)));
+            }
+        }
+        flags.sort_by_key(|(i, ..)| *i);
+        check.warnings = flags
+            .into_iter()
+            .flat_map(|(_, on, Categories(set))| set.iter().map(move |&c| (c, on)))
+            .collect();
+    }
+    Some(command)
+}
+
+/// The categories one `--warn` / `--no-warn` names.
+#[derive(Clone)]
+struct Categories(Vec<WarningCategory>);
+
+fn parse_warnings(value: &str) -> Result<Categories, String> {
+    warnings::parse_categories(value)
+        .map(Categories)
+        .ok_or_else(|| {
+            format!(
+                "unknown warning category `{}` (expected {})",
+                value,
+                warnings::CATEGORY_LIST
+            )
+        })
+}
+
+fn parse_coercion(value: &str) -> Result<CoercionPolicy, String> {
+    CoercionPolicy::parse(value).ok_or_else(|| {
+        format!(
+            "unknown --coercion policy `{}` (expected standard, strict, or permissive)",
+            value
+        )
+    })
+}
+
+fn parse_error_format(value: &str) -> Result<ErrorFormat, String> {
+    ErrorFormat::parse(value).ok_or_else(|| {
+        format!(
+            "unknown --error-format `{}` (expected human, json, or sarif)",
+            value
+        )
+    })
+}
+
+fn parse_dump_ast(value: &str) -> Result<AstDumpFormat, String> {
+    AstDumpFormat::parse(value)
+        .ok_or_else(|| format!("--dump-ast must be `tree` or `json`; got {:?}", value))
+}
+
+const RUN_AFTER_HELP: &str = "\
+Every flag `rz --help` lists for file mode applies here too.
+
+Examples:
+  rz run examples/hello.rz
+  rz run --vm examples/hello.rz
+  rz run app.rz -- --verbose input.txt
+
+Run `rz --help` for global flags and other subcommands.";
+
+const CHECK_AFTER_HELP: &str = "\
+Each file is checked with everything it `use`s. The exit status is the
+worst of the files'.
+
+Examples:
+  rz check examples/hello.rz
+  rz check --quiet examples/hello.rz
+  rz check my-proj
+  rz check --dump-ast=json examples/hello.rz
+
+Run `rz --help` for global flags and other subcommands.";
+
+const VERIFY_AFTER_HELP: &str = "\
+Runs the checks `rz check` does, with the same flags. The status is 0
+when every contract, invariant and termination obligation holds or is
+left to its runtime check, 2 on a parse or type error, and 3 when the
+verifier refutes one.
+
+Examples:
+  rz verify examples/hello.rz
+  rz verify --verifier-timeout-ms 20000 my-proj
+
+Run `rz --help` for global flags and other subcommands.";
+
+const REPL_AFTER_HELP: &str = "\
+Examples:
+  rz repl                   # start REPL
+  rz repl --examples-dir .  # use the current directory for `examples`
+
+For bare REPL startup, run plain `rz`.";
+
+const FMT_AFTER_HELP: &str = "\
+By default, prints the formatted source to stdout. With --in-place,
+rewrites the file and prints nothing on success. With --check, writes
+nothing; each file that would be reformatted (or fails to parse) is
+reported on stderr and the exit status is non-zero. --check cannot be
+combined with --in-place.
+
+Examples:
+  rz fmt examples/hello.rz
+  rz fmt --in-place examples/hello.rz
+  rz fmt --check examples/*.rz
+
+Run `rz --help` for global flags and other subcommands.";
+
+const TEST_AFTER_HELP: &str = "\
+Examples:
+  rz test
+  rz test tests/ --filter parser
+  rz test --coverage=out/cov
+  rz test --snapshot --update examples/
+
+Run `rz --help` for global flags and other subcommands.";
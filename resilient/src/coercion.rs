@@ -0,0 +1,98 @@
+//! Implicit-coercion policy: which operand conversions `+`, the other
+//! arithmetic operators, and comparisons may perform on their own.
+//!
+//! Three policies, selected with `--coercion <policy>` on `rz` and
+//! `rz check`:
+//!
+//! - `standard` (default) — the language's rules as written: `+` with
+//!   a `string` on either side renders an `int` / `float` / `bool` /
+//!   `char` operand and concatenates; `int` and `float` never mix.
+//! - `strict` — no implicit conversions at all. `"n=" + 3` is an
+//!   error; write `"n=" + to_string(3)`.
+//! - `permissive` — additionally widens an `int` operand to `float`
+//!   when the other operand is a `float` (`1 + 2.5` is `3.5`), and
+//!   warns at every site that converts implicitly, string
+//!   concatenation included, so each one can be audited.
+//!
+//! The typechecker applies the policy statically (and emits the
+//! permissive-mode warnings); the tree-walker and the VM apply it to
+//! the values they actually see, so a program means the same thing on
+//! every path. One process-wide setting, chosen by the driver before
+//! anything runs — the same pattern as `strict_any`.
+
+use crate::Value;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoercionPolicy {
+    Standard,
+    Strict,
+    Permissive,
+}
+
+impl CoercionPolicy {
+    /// Parse a `--coercion` argument.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "standard" => Some(CoercionPolicy::Standard),
+            "strict" => Some(CoercionPolicy::Strict),
+            "permissive" => Some(CoercionPolicy::Permissive),
+            _ => None,
+        }
+    }
+
+    /// May `+` render a non-string scalar to concatenate it?
+    pub fn allows_string_concat(self) -> bool {
+        self != CoercionPolicy::Strict
+    }
+
+    /// May an `int` operand widen to `float` next to a `float`?
+    pub fn widens_int_to_float(self) -> bool {
+        self == CoercionPolicy::Permissive
+    }
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(0);
+
+/// Select the process-wide policy. Called from the CLI dispatchers.
+pub fn set_policy(policy: CoercionPolicy) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// The process-wide policy.
+pub fn policy() -> CoercionPolicy {
+    match POLICY.load(Ordering::Relaxed) {
+        1 => CoercionPolicy::Strict,
+        2 => CoercionPolicy::Permissive,
+        _ => CoercionPolicy::Standard,
+    }
+}
+
+/// Both operands as `float` when exactly one is an `int` and the
+/// other a `float` and the policy widens; `None` otherwise.
+pub(crate) fn widen_pair(a: &Value, b: &Value) -> Option<(f64, f64)> {
+    if !policy().widens_int_to_float() {
+        return None;
+    }
+    match (a, b) {
+        (Value::Int(x), Value::Float(y)) => Some((*x as f64, *y)),
+        (Value::Float(x), Value::Int(y)) => Some((*x, *y as f64)),
+        _ => None,
+    }
+}
+
+/// Runtime error for `+` between a string and `other`, a non-string
+/// scalar, under the strict policy.
+pub(crate) fn strict_concat_error(other: &Value) -> String {
+    let other = match other {
+        Value::Int(_) => "int",
+        Value::Float(_) => "float",
+        Value::Bool(_) => "bool",
+        Value::Char(_) => "char",
+        _ => "a non-string value",
+    };
+    format!(
+        "Cannot apply '+' to string and {} — the strict coercion policy forbids implicit conversion to string. Use `to_string(x)` explicitly.",
+        other
+    )
+}
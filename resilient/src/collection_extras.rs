@@ -34,7 +34,7 @@ pub(crate) fn builtin_array_frequency_map(args: &[Value]) -> RResult<Value> {
                     *n += 1;
                 }
             }
-            Ok(Value::Map(counts))
+            Ok(Value::Map(counts.into()))
         }
         [other] => Err(format!(
             "array_frequency_map: expected an Array, got {other}"
@@ -83,7 +83,7 @@ pub(crate) fn builtin_array_key_by(interp: &mut Interpreter, args: &[Value]) ->
         map.insert(mk, elem);
     }
 
-    Ok(Value::Map(map))
+    Ok(Value::Map(map.into()))
 }
 
 /// `array_iterate(init, n, fn) -> Array`
@@ -123,7 +123,7 @@ pub(crate) fn builtin_array_iterate(interp: &mut Interpreter, args: &[Value]) ->
         current = interp.apply_function(&f, vec![current])?;
         out.push(current.clone());
     }
-    Ok(Value::Array(out))
+    Ok(Value::Array(out.into()))
 }
 
 #[cfg(test)]
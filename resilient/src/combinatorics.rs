@@ -26,10 +26,10 @@ pub(crate) fn builtin_array_cartesian_product(args: &[Value]) -> RResult<Value>
             let mut out = Vec::with_capacity(a.len() * b.len());
             for x in a {
                 for y in b {
-                    out.push(Value::Array(vec![x.clone(), y.clone()]));
+                    out.push(Value::Array(vec![x.clone(), y.clone()].into()));
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, _] if !matches!(a, Value::Array(_)) => Err(format!(
             "array_cartesian_product: first argument must be an Array, got {a}"
@@ -79,8 +79,8 @@ pub(crate) fn builtin_array_combinations(args: &[Value]) -> RResult<Value> {
             };
             let mut indices: Vec<usize> = (0..k).collect();
             if k == 0 {
-                out.push(Value::Array(vec![]));
-                return Ok(Value::Array(out));
+                out.push(Value::Array(vec![].into()));
+                return Ok(Value::Array(out.into()));
             }
             loop {
                 out.push(Value::Array(
@@ -99,7 +99,7 @@ pub(crate) fn builtin_array_combinations(args: &[Value]) -> RResult<Value> {
                     indices[j] = indices[j - 1] + 1;
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [Value::Array(_), n] => Err(format!(
             "array_combinations: second argument must be an int, got {n}"
@@ -155,7 +155,7 @@ pub(crate) fn builtin_array_permutations(args: &[Value]) -> RResult<Value> {
                 out: &mut Vec<Value>,
             ) {
                 if current.len() == k {
-                    out.push(Value::Array(current.clone()));
+                    out.push(Value::Array(current.clone().into()));
                     return;
                 }
                 for i in 0..arr.len() {
@@ -169,7 +169,7 @@ pub(crate) fn builtin_array_permutations(args: &[Value]) -> RResult<Value> {
                 }
             }
             permute(arr, k, &mut used, &mut current, &mut out);
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [Value::Array(_), n] => Err(format!(
             "array_permutations: second argument must be an int, got {n}"
@@ -211,9 +211,9 @@ pub(crate) fn builtin_array_powerset(args: &[Value]) -> RResult<Value> {
                     .filter(|&i| mask & (1 << i) != 0)
                     .map(|i| arr[i].clone())
                     .collect();
-                out.push(Value::Array(subset));
+                out.push(Value::Array(subset.into()));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_powerset: expected an Array, got {other}")),
         _ => Err(format!(
@@ -237,14 +237,14 @@ pub(crate) fn builtin_array_transpose(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::Array(matrix)] => {
             if matrix.is_empty() {
-                return Ok(Value::Array(vec![]));
+                return Ok(Value::Array(vec![].into()));
             }
             // Validate all rows are arrays.
-            let rows: Vec<&Vec<Value>> = matrix
+            let rows: Vec<&[Value]> = matrix
                 .iter()
                 .enumerate()
                 .map(|(i, row)| match row {
-                    Value::Array(r) => Ok(r),
+                    Value::Array(r) => Ok(r.as_slice()),
                     other => Err(format!(
                         "array_transpose: row {i} must be an Array, got {other}"
                     )),
@@ -265,9 +265,9 @@ pub(crate) fn builtin_array_transpose(args: &[Value]) -> RResult<Value> {
             let mut out = Vec::with_capacity(ncols);
             for col in 0..ncols {
                 let new_row: Vec<Value> = rows.iter().map(|row| row[col].clone()).collect();
-                out.push(Value::Array(new_row));
+                out.push(Value::Array(new_row.into()));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!(
             "array_transpose: expected an Array of Arrays, got {other}"
@@ -292,11 +292,11 @@ pub(crate) fn builtin_array_cartesian_product_n(args: &[Value]) -> RResult<Value
     match args {
         [Value::Array(arrays)] => {
             // Validate all elements are arrays.
-            let arrays_inner: Vec<&Vec<Value>> = arrays
+            let arrays_inner: Vec<&[Value]> = arrays
                 .iter()
                 .enumerate()
                 .map(|(i, arr)| match arr {
-                    Value::Array(a) => Ok(a),
+                    Value::Array(a) => Ok(a.as_slice()),
                     other => Err(format!(
                         "array_cartesian_product_n: element {i} must be an Array, got {other}"
                     )),
@@ -316,7 +316,9 @@ pub(crate) fn builtin_array_cartesian_product_n(args: &[Value]) -> RResult<Value
                 }
                 result = new_result;
             }
-            Ok(Value::Array(result.into_iter().map(Value::Array).collect()))
+            Ok(Value::Array(
+                result.into_iter().map(|c| Value::Array(c.into())).collect(),
+            ))
         }
         [other] => Err(format!(
             "array_cartesian_product_n: expected an Array of Arrays, got {other}"
@@ -893,37 +893,27 @@ pub fn compile(program: &Node) -> Result<Program, CompileError> {
 }
 
 /// RES-3992: resolve every top-level `const NAME = expr;` declaration
-/// into its literal `Value`, mirroring `Interpreter::const_eval_program`
-/// (the tree-walker's canonical const-resolution pre-pass — see RES-361).
-/// Reuses `Interpreter::eval_const_expr` directly so the bytecode
-/// compiler can never diverge from the tree-walker's notion of what a
-/// "compile-time constant" is.
+/// into its literal `Value` through `const_eval::ConstEvaluator` — the
+/// same evaluator the typechecker and the tree-walker's
+/// `Interpreter::const_eval_program` use — so the bytecode compiler can
+/// never diverge from the tree-walker's notion of what a "compile-time
+/// constant" is.
 ///
 /// A const whose value expression isn't foldable (circular reference,
 /// non-constant sub-expression) is silently left out of the returned
-/// map rather than erroring here — `static_assert::check` in the shared
+/// map rather than erroring here — `const_eval::check` in the shared
 /// typechecker pass already surfaces that as a diagnostic before either
 /// backend runs, so skipping it just means a later reference to that
 /// name falls through to the pre-existing `UnknownIdentifier` error
 /// instead of this pre-pass raising a second, redundant one.
 fn resolve_top_level_consts(stmts: &[crate::span::Spanned<Node>]) -> HashMap<String, Value> {
-    let mut resolved: HashMap<String, Value> = HashMap::new();
-    for stmt in stmts {
-        let Node::Const { name, value, .. } = &stmt.node else {
-            continue;
-        };
-        let mut evaluating = vec![name.clone()];
-        if let Ok(v) = crate::Interpreter::eval_const_expr(value, &resolved, &mut evaluating) {
-            resolved.insert(name.clone(), v);
-        }
-    }
-    resolved
+    crate::const_eval::ConstEvaluator::resolve_lenient(stmts)
 }
 
 /// RES-3992: convert a resolved const `Value` back into the literal AST
 /// node the bytecode compiler already knows how to emit a `Op::Const`
 /// for. Consts only ever resolve to one of these scalar shapes — see
-/// the allowed-subexpression list on `Interpreter::eval_const_expr` —
+/// the allowed-subexpression list on `const_eval::ConstEvaluator::eval` —
 /// so `None` here is unreachable in practice, but is handled instead of
 /// unwrapped to keep this pass a no-op on any future const value shape
 /// rather than a panic.
@@ -957,7 +947,7 @@ fn const_value_to_literal(value: &Value, span: crate::span::Span) -> Option<Node
 fn inline_consts(node: &Node, resolved: &HashMap<String, Value>) -> Node {
     match node {
         Node::Identifier { name, span } => resolved
-            .get(name)
+            .get(name.as_str())
             .and_then(|v| const_value_to_literal(v, *span))
             .unwrap_or_else(|| node.clone()),
         Node::Program(stmts) => Node::Program(
@@ -986,12 +976,16 @@ fn inline_consts(node: &Node, resolved: &HashMap<String, Value>) -> Node {
             name: name.clone(),
             parameters: parameters.clone(),
             defaults: defaults.clone(),
-            body: Box::new(inline_consts(body, resolved)),
-            requires: requires
-                .iter()
-                .map(|r| inline_consts(r, resolved))
-                .collect(),
-            ensures: ensures.iter().map(|e| inline_consts(e, resolved)).collect(),
+            body: std::sync::Arc::new(inline_consts(body, resolved)),
+            requires: std::sync::Arc::new(
+                requires
+                    .iter()
+                    .map(|r| inline_consts(r, resolved))
+                    .collect(),
+            ),
+            ensures: std::sync::Arc::new(
+                ensures.iter().map(|e| inline_consts(e, resolved)).collect(),
+            ),
             return_type: return_type.clone(),
             span: *span,
             pure: *pure,
@@ -1724,7 +1718,8 @@ fn compile_stmt(
         // and run separately by the `rz bench` subcommand, not by `rz`/
         // `rz --vm`. Mirror that here rather than falling through to the
         // generic `Unsupported` catch-all below.
-        Node::BenchBlock { .. } => Ok(()),
+        // `test fn` likewise belongs to `rz test`.
+        Node::BenchBlock { .. } | Node::TestFn { .. } => Ok(()),
         Node::TryCatch { body, handlers, .. } => compile_try_catch(
             body,
             handlers,
@@ -1904,7 +1899,7 @@ fn compile_live_block(
     });
     let handler_idx = chunk.add_live_handler(LiveHandlerEntry {
         body_start_pc: 0,
-        max_retries: max_retries.unwrap_or(crate::DEFAULT_LIVE_MAX_RETRIES),
+        max_retries: max_retries.unwrap_or_else(crate::default_live_retries),
         backoff: *backoff,
         backoff_kind,
         timeout_ns,
@@ -1994,6 +1989,46 @@ fn zero_arg_enum_variant_const(name: &str, chunk: &mut Chunk) -> Result<Option<u
 }
 
 /// RES-3915: if `name` is a `::`-qualified reference to a **tuple-payload**
+/// The member of `name`'s overload set (see `overloads`) whose arity is
+/// `argc`. `Ok(None)` when `name` is not overloaded; an error when no
+/// member, or more than one, takes `argc` arguments.
+fn overload_by_arity(
+    fn_index: &HashMap<String, u16>,
+    name: &str,
+    argc: usize,
+) -> Result<Option<u16>, CompileError> {
+    let mut members: Vec<(&str, u16)> = fn_index
+        .iter()
+        .filter(|(k, _)| crate::overloads::base_of(k) == Some(name))
+        .map(|(k, &idx)| (k.as_str(), idx))
+        .collect();
+    if members.is_empty() {
+        return Ok(None);
+    }
+    members.sort_by_key(|&(_, idx)| idx);
+    let fits: Vec<(&str, u16)> = members
+        .iter()
+        .copied()
+        .filter(|(k, _)| crate::overloads::arity(k) == argc)
+        .collect();
+    match fits.as_slice() {
+        [(_, idx)] => Ok(Some(*idx)),
+        [] => Err(CompileError::Overload(crate::overloads::no_match_error(
+            name,
+            &format!("{} argument(s)", argc),
+            members.iter().map(|(k, _)| *k),
+        ))),
+        _ => Err(CompileError::Overload(format!(
+            "{} — the VM selects overloads by arity only; run without --vm to dispatch on argument types",
+            crate::overloads::ambiguous_error(
+                name,
+                &format!("{} argument(s)", argc),
+                fits.iter().map(|(k, _)| *k),
+            )
+        ))),
+    }
+}
+
 /// enum variant recorded in `ENUM_INDEX`, intern a `Value::EnumConstructor`
 /// carrying its declared arity and return the pool index. Returns `Ok(None)`
 /// when `name` isn't a qualified tuple variant, so the caller falls through
@@ -3399,7 +3434,7 @@ fn compile_stmt_in_fn(
         | Node::ModuleDecl { .. }
         | Node::Use { .. } => Ok(()),
         // RES-3993: see the matching `Node::BenchBlock` arm in `compile_stmt`.
-        Node::BenchBlock { .. } => Ok(()),
+        Node::BenchBlock { .. } | Node::TestFn { .. } => Ok(()),
         Node::Function {
             name,
             parameters,
@@ -3642,7 +3677,8 @@ fn compile_control_flow_in_fn(
         | Node::Assume { .. }
         | Node::InvariantStatement { .. }
         // RES-3993: see the matching `Node::BenchBlock` arm in `compile_stmt`.
-        | Node::BenchBlock { .. } => Ok(()),
+        | Node::BenchBlock { .. }
+        | Node::TestFn { .. } => Ok(()),
         other => Err(CompileError::Unsupported(node_kind(other))),
     }
 }
@@ -4105,8 +4141,16 @@ fn compile_expr(
             Ok(())
         }
         Node::Identifier { name, .. } => {
-            if let Some(&idx) = locals.get(name) {
+            if let Some(&idx) = locals.get(name.as_str()) {
                 emit_identifier_load(chunk, idx, line)?;
+            } else if fn_index
+                .keys()
+                .any(|k| crate::overloads::base_of(k) == Some(name.as_str()))
+            {
+                // An overload set is only resolvable at a call, by arity.
+                return Err(CompileError::Overload(crate::overloads::as_value_error(
+                    name,
+                )));
             } else if crate::lookup_builtin(name).is_some() {
                 let name_const = chunk.add_string_constant(name)?;
                 chunk.emit(
@@ -4133,7 +4177,7 @@ fn compile_expr(
                 // converts it into the corresponding `EnumVariant`, mirroring
                 // the interpreter's first-class-constructor path.
                 chunk.emit(Op::Const(const_idx), line);
-            } else if let Some(&idx) = fn_index.get(name) {
+            } else if let Some(&idx) = fn_index.get(name.as_str()) {
                 // RES-3993: bare reference to a named top-level (or
                 // impl-block) function used as a first-class value —
                 // `let f = pick(true)` returning `double`, `apply(double,
@@ -4160,7 +4204,7 @@ fn compile_expr(
                     line,
                 );
             } else {
-                return Err(CompileError::UnknownIdentifier(name.clone()));
+                return Err(CompileError::UnknownIdentifier(name.to_string()));
             }
             Ok(())
         }
@@ -4569,8 +4613,14 @@ fn compile_expr(
                 chunk.record_call_col(pc, call_col);
                 return Ok(());
             }
-            // User-defined function next.
-            if let Some(&callee_idx) = fn_index.get(callee_name) {
+            // User-defined function next. An overloaded name resolves
+            // by arity — the compiler has no argument types.
+            let overload = if fn_index.contains_key(callee_name) {
+                None
+            } else {
+                overload_by_arity(fn_index, callee_name, arguments.len())?
+            };
+            if let Some(&callee_idx) = fn_index.get(callee_name).or(overload.as_ref()) {
                 // Push args left-to-right so the VM can pop them in reverse
                 // and assign to locals 0..arity in source order.
                 for arg in arguments {
@@ -6238,12 +6288,12 @@ fn collect_free_vars(
     match node {
         Node::Identifier { name, .. }
             if !param_names.contains(name.as_str())
-                && !seen.contains(name)
-                && outer_locals.contains_key(name) =>
+                && !seen.contains(name.as_str())
+                && outer_locals.contains_key(name.as_str()) =>
         {
-            let slot = outer_locals[name];
-            seen.insert(name.clone());
-            out.push((slot, name.clone()));
+            let slot = outer_locals[name.as_str()];
+            seen.insert(name.to_string());
+            out.push((slot, name.to_string()));
         }
         Node::Identifier { .. } => {}
         // Recurse into all child nodes.
@@ -7542,6 +7592,7 @@ fn node_line(n: &Node) -> Option<u32> {
         Node::DeferStatement { span, .. } => span.start.line as u32,
         // RES-2613: bench block — carries the keyword's span.
         Node::BenchBlock { span, .. } => span.start.line as u32,
+        Node::TestFn { span, .. } => span.start.line as u32,
     };
     if line == 0 { None } else { Some(line) }
 }
@@ -7651,8 +7702,20 @@ fn rewrite_tail_calls(
     // because the new `Op::Return` tombstone at `i+1` cannot itself
     // match a tail-call-eligible `Op::Call` at any later i. Drops the
     // Vec allocation and halves the linear scans.
+    //
+    // A call inside a `try` or `live` body is not in tail position:
+    // the block's handler or retry must still see the callee fail.
+    // Those bodies are emitted contiguously between their Enter/Exit
+    // ops, so a running depth tells whether `i` sits inside one.
+    let mut guarded_depth = 0usize;
     for i in 0..len - 1 {
+        match chunk.code[i] {
+            Op::EnterTry(_) | Op::EnterLive(_) => guarded_depth += 1,
+            Op::ExitTry | Op::ExitLive => guarded_depth = guarded_depth.saturating_sub(1),
+            _ => {}
+        }
         if let Op::Call(target) = chunk.code[i]
+            && guarded_depth == 0
             && chunk.code[i + 1] == Op::ReturnFromCall
             && (target == own_fn_idx || mutual_targets.contains(&target))
         {
@@ -61,7 +61,7 @@ fn unpack(name: &str, v: &Value) -> RResult<(f64, f64)> {
 }
 
 fn pack(re: f64, im: f64) -> Value {
-    Value::Array(vec![Value::Float(re), Value::Float(im)])
+    Value::Array(vec![Value::Float(re), Value::Float(im)].into())
 }
 
 // ── construction ──────────────────────────────────────────────────────────────
@@ -0,0 +1,656 @@
+//! Compile-time constant evaluation.
+//!
+//! One evaluator backs every place the language needs a value before
+//! the program runs: `const` initializers, `static_assert` conditions,
+//! `[T; N]` array lengths, and explicit enum discriminants
+//! (`enum E { A = 1, B = A + 1 }`, where a bare name is a sibling
+//! variant before it is a `const`). The typechecker, the tree-walker,
+//! and the bytecode compiler all resolve through [`ConstEvaluator`], so
+//! they agree on what a constant is and what it evaluates to.
+//!
+//! Constants resolve on demand rather than in source order: `const A =
+//! B * 2; const B = 4;` is fine. Each name is pushed onto an
+//! in-progress stack while its initializer evaluates; meeting a name
+//! that is already on the stack is a cycle, reported with the full
+//! chain (`'A' -> 'B' -> 'A'`).
+//!
+//! Arithmetic matches the interpreter exactly. Integer `+ - *` and
+//! negation go through the configured [`OverflowMode`] (wrap by
+//! default, `RESILIENT_OVERFLOW_MODE=saturate|trap` otherwise), `/`
+//! and `%` by zero are errors, shift amounts outside `0..64` are
+//! errors, floats follow IEEE 754, and `int`/`float` never mix.
+
+use crate::span::{Span, Spanned};
+use crate::vm::OverflowMode;
+use crate::{EnumPayload, EnumVariant, Node, RResult, Value};
+use std::collections::HashMap;
+
+/// Resolves top-level `const` declarations and evaluates constant
+/// expressions against them.
+pub(crate) struct ConstEvaluator<'a> {
+    /// Initializer of every top-level `const`, by name.
+    decls: HashMap<&'a str, &'a Node>,
+    /// Variants of every top-level `enum`, for `E::V as int`.
+    enums: HashMap<&'a str, &'a [EnumVariant]>,
+    resolved: HashMap<String, Value>,
+    in_progress: Vec<String>,
+    /// Enums whose discriminant is being evaluated, innermost last; a
+    /// bare name in one may be a variant of the innermost.
+    discriminant_scopes: Vec<String>,
+    mode: OverflowMode,
+}
+
+impl<'a> ConstEvaluator<'a> {
+    /// An evaluator over the `const` and `enum` declarations in
+    /// `statements`. Nothing is evaluated until asked for.
+    pub(crate) fn new(statements: &'a [Spanned<Node>]) -> Self {
+        let mut decls = HashMap::new();
+        let mut enums = HashMap::new();
+        for stmt in statements {
+            match &stmt.node {
+                Node::Const { name, value, .. } => {
+                    decls.entry(name.as_str()).or_insert(value.as_ref());
+                }
+                Node::EnumDecl { name, variants, .. } => {
+                    enums.insert(name.as_str(), variants.as_slice());
+                }
+                _ => {}
+            }
+        }
+        ConstEvaluator {
+            decls,
+            enums,
+            resolved: HashMap::new(),
+            in_progress: Vec::new(),
+            discriminant_scopes: Vec::new(),
+            mode: OverflowMode::from_env(),
+        }
+    }
+
+    /// An evaluator whose constants are already resolved — the
+    /// interpreter's const table after `const_eval_program` — over the
+    /// enums the interpreter has registered.
+    pub(crate) fn with_resolved(
+        resolved: &HashMap<crate::symbol::Symbol, Value>,
+        enums: &'a HashMap<String, Vec<EnumVariant>>,
+    ) -> Self {
+        ConstEvaluator {
+            decls: HashMap::new(),
+            enums: enums
+                .iter()
+                .map(|(name, variants)| (name.as_str(), variants.as_slice()))
+                .collect(),
+            resolved: resolved
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+            in_progress: Vec::new(),
+            discriminant_scopes: Vec::new(),
+            mode: OverflowMode::from_env(),
+        }
+    }
+
+    /// Resolve every `const` in `statements`, in source order. The
+    /// first failure is returned with the span of its declaration.
+    pub(crate) fn resolve_all(
+        statements: &'a [Spanned<Node>],
+    ) -> Result<HashMap<String, Value>, (String, Span)> {
+        let mut ev = ConstEvaluator::new(statements);
+        for stmt in statements {
+            if let Node::Const { name, .. } = &stmt.node {
+                ev.resolve(name).map_err(|e| (e, stmt.span))?;
+            }
+        }
+        Ok(ev.into_consts())
+    }
+
+    /// Resolve every `const` that can be resolved, leaving failures
+    /// out of the map. For passes that run after the typechecker has
+    /// already reported them.
+    pub(crate) fn resolve_lenient(statements: &'a [Spanned<Node>]) -> HashMap<String, Value> {
+        let mut ev = ConstEvaluator::new(statements);
+        for stmt in statements {
+            if let Node::Const { name, .. } = &stmt.node {
+                let _ = ev.resolve(name);
+            }
+        }
+        ev.into_consts()
+    }
+
+    /// The resolved constants, without the memoized enum
+    /// discriminants.
+    fn into_consts(self) -> HashMap<String, Value> {
+        let mut consts = self.resolved;
+        consts.retain(|name, _| !name.contains("::"));
+        consts
+    }
+
+    /// The value of constant `name`, evaluating its initializer (and
+    /// anything it depends on) on first use.
+    pub(crate) fn resolve(&mut self, name: &str) -> RResult<Value> {
+        if let Some(v) = self.resolved.get(name) {
+            return Ok(v.clone());
+        }
+        let Some(&init) = self.decls.get(name) else {
+            return Err(format!(
+                "error: '{}' is not a compile-time constant (constants may only \
+                 reference other constants)",
+                name
+            ));
+        };
+        self.enter(name)?;
+        let result = self.eval(init);
+        self.in_progress.pop();
+        let v = result?;
+        self.resolved.insert(name.to_string(), v.clone());
+        Ok(v)
+    }
+
+    /// Evaluate a constant expression.
+    ///
+    /// Allowed: literals; prefix `-` / `!`; infix arithmetic,
+    /// comparison, logical, bitwise, and string operators; `if`
+    /// with a constant condition; single-expression blocks; tuples;
+    /// references to other constants; and `E::V as int` on a
+    /// payload-less enum variant, or just `V` inside a discriminant of
+    /// `E`. Anything else — calls, arrays,
+    /// variables — is rejected.
+    pub(crate) fn eval(&mut self, node: &Node) -> RResult<Value> {
+        match node {
+            Node::IntegerLiteral { value, .. } => Ok(Value::Int(*value)),
+            Node::FloatLiteral { value, .. } => Ok(Value::Float(*value)),
+            Node::BooleanLiteral { value, .. } => Ok(Value::Bool(*value)),
+            Node::StringLiteral { value, .. } => Ok(Value::String(value.clone())),
+            // RES-2612: interned strings evaluate to their content at compile time.
+            Node::StringInternLiteral { content, .. } => Ok(Value::String(content.clone())),
+            Node::Identifier { name, .. } => match self.sibling_variant(name) {
+                Some((enum_name, variants)) => self
+                    .discriminant_of(&enum_name, variants, name)
+                    .map(Value::Int),
+                None => self.resolve(name),
+            },
+            Node::PrefixExpression {
+                operator, right, ..
+            } => {
+                let rv = self.eval(right)?;
+                match (*operator, rv) {
+                    ("-", Value::Int(i)) => self.mode.neg_for_eval(i).map(Value::Int),
+                    ("-", Value::Float(f)) => Ok(Value::Float(-f)),
+                    ("!", Value::Bool(b)) => Ok(Value::Bool(!b)),
+                    (op, v) => Err(format!(
+                        "error: operator '{}' cannot be applied to {} in a constant expression",
+                        op, v
+                    )),
+                }
+            }
+            Node::InfixExpression {
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                let lv = self.eval(left)?;
+                let rv = self.eval(right)?;
+                self.infix(operator, lv, rv)
+            }
+            // RES-2580: conditional const expressions: `if C { A } else { B }`.
+            Node::IfStatement {
+                condition,
+                consequence,
+                alternative,
+                ..
+            } => {
+                let Value::Bool(b) = self.eval(condition)? else {
+                    return Err("error: condition in const `if` must evaluate to bool".to_string());
+                };
+                if b {
+                    self.eval(consequence)
+                } else {
+                    match alternative {
+                        Some(alt) => self.eval(alt),
+                        None => Ok(Value::Void),
+                    }
+                }
+            }
+            Node::ExpressionStatement { expr, .. } => self.eval(expr),
+            Node::Block { stmts, .. } => match stmts.as_slice() {
+                [only] => self.eval(only),
+                _ => Err(
+                    "error: only single-expression blocks are valid in const expressions"
+                        .to_string(),
+                ),
+            },
+            Node::TupleLiteral { items, .. } => {
+                let mut vals = Vec::with_capacity(items.len());
+                for item in items {
+                    vals.push(self.eval(item)?);
+                }
+                Ok(Value::Tuple(vals))
+            }
+            // `E::V as int` — the parser desugars the cast to `to_int(E::V)`.
+            Node::CallExpression {
+                function,
+                arguments,
+                ..
+            } if matches!(function.as_ref(), Node::Identifier { name, .. } if name == "to_int")
+                && matches!(arguments.as_slice(), [Node::Identifier { name, .. }] if name.contains("::")) =>
+            {
+                let [Node::Identifier { name, .. }] = arguments.as_slice() else {
+                    unreachable!("guarded above");
+                };
+                let (enum_name, variant) = name.rsplit_once("::").expect("guarded above");
+                let Some(&variants) = self.enums.get(enum_name) else {
+                    return Err(format!(
+                        "error: '{}' is not a compile-time constant (no enum '{}' in scope)",
+                        name, enum_name
+                    ));
+                };
+                self.discriminant_of(enum_name, variants, variant)
+                    .map(Value::Int)
+            }
+            other => Err(format!(
+                "error: '{}' is not a valid constant expression; \
+                 only literals, arithmetic, string ops, bitwise ops, \
+                 conditionals, and constant references are allowed",
+                match other {
+                    Node::CallExpression { .. } => "function call".to_string(),
+                    Node::ArrayLiteral { .. } => "array literal".to_string(),
+                    _ => format!("{:?}", std::mem::discriminant(other)),
+                }
+            )),
+        }
+    }
+
+    /// Evaluate a constant expression that must produce a `bool`.
+    pub(crate) fn eval_bool(&mut self, node: &Node) -> RResult<bool> {
+        match self.eval(node)? {
+            Value::Bool(b) => Ok(b),
+            other => Err(format!("expected boolean, got {}", other)),
+        }
+    }
+
+    fn infix(&self, op: &str, lv: Value, rv: Value) -> RResult<Value> {
+        let mode = self.mode;
+        match (lv, rv) {
+            (Value::Int(a), Value::Int(b)) => match op {
+                "+" => mode.add_for_eval(a, b, "+").map(Value::Int),
+                "-" => mode.sub_for_eval(a, b, "-").map(Value::Int),
+                "*" => mode.mul_for_eval(a, b, "*").map(Value::Int),
+                "/" => mode.div_for_eval(a, b).map(Value::Int),
+                "%" => mode.rem_for_eval(a, b).map(Value::Int),
+                "&" => Ok(Value::Int(a & b)),
+                "|" => Ok(Value::Int(a | b)),
+                "^" => Ok(Value::Int(a ^ b)),
+                "<<" | ">>" if !(0..64).contains(&b) => {
+                    Err(format!("shift amount out of range: {}", b))
+                }
+                "<<" => Ok(Value::Int(a << b)),
+                ">>" => Ok(Value::Int(a >> b)),
+                "==" => Ok(Value::Bool(a == b)),
+                "!=" => Ok(Value::Bool(a != b)),
+                "<" => Ok(Value::Bool(a < b)),
+                "<=" => Ok(Value::Bool(a <= b)),
+                ">" => Ok(Value::Bool(a > b)),
+                ">=" => Ok(Value::Bool(a >= b)),
+                _ => Err(unsupported(op, Value::Int(a), Value::Int(b))),
+            },
+            (Value::Float(a), Value::Float(b)) => match op {
+                "+" => Ok(Value::Float(a + b)),
+                "-" => Ok(Value::Float(a - b)),
+                "*" => Ok(Value::Float(a * b)),
+                "/" => Ok(Value::Float(a / b)),
+                "%" => Ok(Value::Float(a % b)),
+                "==" => Ok(Value::Bool(a == b)),
+                "!=" => Ok(Value::Bool(a != b)),
+                "<" => Ok(Value::Bool(a < b)),
+                "<=" => Ok(Value::Bool(a <= b)),
+                ">" => Ok(Value::Bool(a > b)),
+                ">=" => Ok(Value::Bool(a >= b)),
+                _ => Err(unsupported(op, Value::Float(a), Value::Float(b))),
+            },
+            (Value::Int(_), Value::Float(_)) | (Value::Float(_), Value::Int(_)) => Err(format!(
+                "Cannot apply '{}' to int and float — Resilient does not implicitly coerce between numeric types. Use `to_float(x)` or `to_int(x)` explicitly.",
+                op
+            )),
+            (Value::Bool(a), Value::Bool(b)) => match op {
+                "==" => Ok(Value::Bool(a == b)),
+                "!=" => Ok(Value::Bool(a != b)),
+                "&&" => Ok(Value::Bool(a && b)),
+                "||" => Ok(Value::Bool(a || b)),
+                _ => Err(unsupported(op, Value::Bool(a), Value::Bool(b))),
+            },
+            (Value::String(a), Value::String(b)) => match op {
+                "+" => Ok(Value::String(a + b.as_str())),
+                "==" => Ok(Value::Bool(a == b)),
+                "!=" => Ok(Value::Bool(a != b)),
+                "<" => Ok(Value::Bool(a < b)),
+                "<=" => Ok(Value::Bool(a <= b)),
+                ">" => Ok(Value::Bool(a > b)),
+                ">=" => Ok(Value::Bool(a >= b)),
+                _ => Err(unsupported(op, Value::String(a), Value::String(b))),
+            },
+            (lv, rv) => Err(unsupported(op, lv, rv)),
+        }
+    }
+
+    /// Discriminant of every variant of enum `enum_name`, in
+    /// declaration order. A variant without `= expr` takes the previous
+    /// value plus one (the first defaults to 0). Values must be `int`
+    /// and distinct.
+    pub(crate) fn discriminants(
+        &mut self,
+        enum_name: &str,
+        variants: &[EnumVariant],
+    ) -> RResult<Vec<i64>> {
+        let mut out: Vec<i64> = Vec::with_capacity(variants.len());
+        for (idx, v) in variants.iter().enumerate() {
+            let value = self.discriminant_at(enum_name, variants, idx)?;
+            if let Some(dup) = out.iter().position(|d| *d == value) {
+                return Err(format!(
+                    "error: variants '{}' and '{}' have the same discriminant {}",
+                    variants[dup].name, v.name, value
+                ));
+            }
+            out.push(value);
+        }
+        Ok(out)
+    }
+
+    /// Discriminant of `variant` — the value of `E::V as int`.
+    pub(crate) fn discriminant_of(
+        &mut self,
+        enum_name: &str,
+        variants: &[EnumVariant],
+        variant: &str,
+    ) -> RResult<i64> {
+        let Some(idx) = variants.iter().position(|v| v.name == variant) else {
+            return Err(format!(
+                "error: no variant '{}' in enum '{}'",
+                variant, enum_name
+            ));
+        };
+        if !matches!(variants[idx].payload, EnumPayload::None) {
+            return Err(format!(
+                "error: variant '{}::{}' carries a payload and has no integer discriminant",
+                enum_name, variant
+            ));
+        }
+        self.discriminant_at(enum_name, variants, idx)
+    }
+
+    /// Discriminant of `variants[idx]`, memoized under its qualified
+    /// `E::V` name — which can't collide with a `const` — and guarded
+    /// by the same in-progress stack, so `enum E { A = E::B as int, B }`
+    /// is reported as a cycle.
+    fn discriminant_at(
+        &mut self,
+        enum_name: &str,
+        variants: &[EnumVariant],
+        idx: usize,
+    ) -> RResult<i64> {
+        let key = format!("{}::{}", enum_name, variants[idx].name);
+        if let Some(Value::Int(v)) = self.resolved.get(&key) {
+            return Ok(*v);
+        }
+        self.enter(&key)?;
+        let result = match &variants[idx].discriminant {
+            Some(expr) => match self.eval_in_enum(enum_name, expr) {
+                Ok(Value::Int(i)) => Ok(i),
+                Ok(other) => Err(format!(
+                    "error: discriminant of variant '{}' must be an int, got {}",
+                    variants[idx].name, other
+                )),
+                Err(e) => Err(e),
+            },
+            None if idx == 0 => Ok(0),
+            None => self
+                .discriminant_at(enum_name, variants, idx - 1)
+                .and_then(|prev| self.mode.add_for_eval(prev, 1, "+")),
+        };
+        self.in_progress.pop();
+        let v = result?;
+        self.resolved.insert(key, Value::Int(v));
+        Ok(v)
+    }
+
+    /// Evaluate a discriminant of `enum_name`, where its variants are
+    /// in scope.
+    fn eval_in_enum(&mut self, enum_name: &str, expr: &Node) -> RResult<Value> {
+        self.discriminant_scopes.push(enum_name.to_string());
+        let result = self.eval(expr);
+        self.discriminant_scopes.pop();
+        result
+    }
+
+    /// The enum whose payload-less variant a bare `name` refers to,
+    /// when it is read inside that enum's discriminant.
+    fn sibling_variant(&self, name: &str) -> Option<(String, &'a [EnumVariant])> {
+        let enum_name = self.discriminant_scopes.last()?;
+        let &variants = self.enums.get(enum_name.as_str())?;
+        variants
+            .iter()
+            .any(|v| v.name == name && matches!(v.payload, EnumPayload::None))
+            .then(|| (enum_name.clone(), variants))
+    }
+
+    /// Push `name` onto the in-progress stack, or report the cycle it
+    /// closes.
+    fn enter(&mut self, name: &str) -> RResult<()> {
+        if let Some(start) = self.in_progress.iter().position(|n| n == name) {
+            let chain = self.in_progress[start..]
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(name))
+                .map(|n| format!("'{}'", n))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(format!("error: circular constant definition: {}", chain));
+        }
+        self.in_progress.push(name.to_string());
+        Ok(())
+    }
+}
+
+fn unsupported(op: &str, lv: Value, rv: Value) -> String {
+    format!(
+        "error: operator '{}' on ({}, {}) is not supported in a constant expression",
+        op, lv, rv
+    )
+}
+
+/// The length slot of a `[T; N]` annotation as written — an integer
+/// literal or a constant name. `None` for every other type string.
+pub(crate) fn array_len_slot(ty: &str) -> Option<&str> {
+    let inner = ty.trim().strip_prefix('[')?.strip_suffix(']')?;
+    inner.rsplit_once(';').map(|(_, n)| n.trim())
+}
+
+fn diagnostic(source_path: &str, span: Span, message: &str) -> String {
+    let message = message.strip_prefix("error: ").unwrap_or(message);
+    format!(
+        "{}:{}:{}: error: {}",
+        source_path, span.start.line, span.start.column, message
+    )
+}
+
+/// Typechecker pass: resolve every top-level constant, every explicit
+/// enum discriminant, and every `[T; N]` length written with a
+/// constant name, reporting the first failure.
+pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
+    let Node::Program(statements) = program else {
+        return Ok(());
+    };
+    let mut ev = ConstEvaluator::new(statements);
+    for stmt in statements {
+        match &stmt.node {
+            Node::Const { name, .. } => {
+                ev.resolve(name)
+                    .map_err(|e| diagnostic(source_path, stmt.span, &e))?;
+            }
+            Node::EnumDecl { name, variants, .. }
+                if variants.iter().any(|v| v.discriminant.is_some()) =>
+            {
+                ev.discriminants(name, variants).map_err(|e| {
+                    let e = e.trim_start_matches("error: ");
+                    diagnostic(source_path, stmt.span, &format!("enum {}: {}", name, e))
+                })?;
+            }
+            _ => {}
+        }
+    }
+
+    let mut first_err: Option<String> = None;
+    let mut check_ty = |ty: &str, span: Span, ev: &mut ConstEvaluator| {
+        let Some(slot) = array_len_slot(ty) else {
+            return;
+        };
+        if first_err.is_some() || slot.parse::<usize>().is_ok() {
+            return;
+        }
+        let msg = match ev.resolve(slot) {
+            Ok(Value::Int(n)) if n >= 0 => return,
+            Ok(other) => format!(
+                "array length `{}` in `{}` must be a non-negative int, got {}",
+                slot, ty, other
+            ),
+            Err(e) => format!(
+                "array length in `{}`: {}",
+                ty,
+                e.trim_start_matches("error: ")
+            ),
+        };
+        first_err = Some(diagnostic(source_path, span, &msg));
+    };
+    for stmt in statements {
+        crate::uniqueness_walk::visit(&stmt.node, &mut |n| match n {
+            Node::LetStatement {
+                type_annot: Some(ty),
+                ..
+            }
+            | Node::Const {
+                type_annot: Some(ty),
+                ..
+            } => check_ty(ty, stmt.span, &mut ev),
+            Node::Function {
+                parameters,
+                return_type,
+                ..
+            } => {
+                for (ty, _) in parameters {
+                    check_ty(ty, stmt.span, &mut ev);
+                }
+                if let Some(ty) = return_type {
+                    check_ty(ty, stmt.span, &mut ev);
+                }
+            }
+            Node::StructDecl { fields, .. } => {
+                for (ty, _) in fields {
+                    check_ty(ty, stmt.span, &mut ev);
+                }
+            }
+            _ => {}
+        });
+    }
+    first_err.map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn check_src(src: &str) -> Result<(), String> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {errs:?}");
+        check(&prog, "t.rz")
+    }
+
+    fn resolve(src: &str) -> HashMap<String, Value> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {errs:?}");
+        let Node::Program(stmts) = &prog else {
+            unreachable!()
+        };
+        ConstEvaluator::resolve_all(stmts).expect("consts resolve")
+    }
+
+    #[test]
+    fn constants_resolve_in_any_order() {
+        let c = resolve("const A = B * 2 + C;\nconst B = C << 2;\nconst C = 3;");
+        assert!(matches!(c.get("A"), Some(Value::Int(27))), "{c:?}");
+    }
+
+    #[test]
+    fn cycles_report_the_whole_chain() {
+        let err = check_src("const A = B;\nconst B = C + 1;\nconst C = A;").unwrap_err();
+        assert!(
+            err.contains("t.rz:1:") && err.contains("'A' -> 'B' -> 'C' -> 'A'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn arithmetic_matches_the_interpreter() {
+        // Wrap is the default overflow mode, as at runtime.
+        let c = resolve("const M = 9223372036854775807 + 1;");
+        assert!(matches!(c.get("M"), Some(Value::Int(i64::MIN))), "{c:?}");
+        let err = check_src("const D = 1 / (2 - 2);").unwrap_err();
+        assert!(err.contains("Division by zero"), "{err}");
+        let err = check_src("const S = 1 << 64;").unwrap_err();
+        assert!(err.contains("shift amount out of range: 64"), "{err}");
+        let err = check_src("const F = 1 + 2.0;").unwrap_err();
+        assert!(err.contains("does not implicitly coerce"), "{err}");
+    }
+
+    #[test]
+    fn enum_discriminants_count_up_from_explicit_values() {
+        let c = resolve(
+            "const BASE = 10;\nenum E { A, B = BASE, C, D = E::A as int + 100 }\n\
+             const VC = E::C as int;\nconst VD = E::D as int;",
+        );
+        assert!(matches!(c.get("VC"), Some(Value::Int(11))), "{c:?}");
+        assert!(matches!(c.get("VD"), Some(Value::Int(100))), "{c:?}");
+    }
+
+    #[test]
+    fn enum_discriminants_must_be_distinct_ints() {
+        let err = check_src("enum E { A = 1, B = 0, C }").unwrap_err();
+        assert!(
+            err.contains("variants 'A' and 'C' have the same discriminant 1"),
+            "{err}"
+        );
+        let err = check_src("enum E { A = \"x\" }").unwrap_err();
+        assert!(err.contains("must be an int"), "{err}");
+        let err = check_src("enum E { A = E::B as int, B }").unwrap_err();
+        assert!(err.contains("'E::A' -> 'E::B' -> 'E::A'"), "{err}");
+        let err = check_src("enum E { A = B, B }").unwrap_err();
+        assert!(err.contains("'E::A' -> 'E::B' -> 'E::A'"), "{err}");
+    }
+
+    #[test]
+    fn a_discriminant_may_name_a_sibling_variant() {
+        let c = resolve(
+            "const B = 50;
+enum E { A = 1, B = A + 1, C = B * 10 }
+             enum F { X = E::C as int + B }
+             const VB = E::B as int;
+const VC = E::C as int;
+const VX = F::X as int;",
+        );
+        assert!(matches!(c.get("VB"), Some(Value::Int(2))), "{c:?}");
+        // Inside `E`, `B` is the variant, not the constant.
+        assert!(matches!(c.get("VC"), Some(Value::Int(20))), "{c:?}");
+        // Inside `F`, `B` is the constant again.
+        assert!(matches!(c.get("VX"), Some(Value::Int(70))), "{c:?}");
+    }
+
+    #[test]
+    fn array_lengths_may_name_a_constant() {
+        check_src("const N = 2 * 2;\nfn f([int; N] xs) -> int { return 0; }")
+            .expect("`N` resolves to 4");
+        let err = check_src("let xs: [int; M] = [1];").unwrap_err();
+        assert!(err.contains("'M' is not a compile-time constant"), "{err}");
+        let err = check_src("const N = 0 - 1;\nlet xs: [int; N] = [];").unwrap_err();
+        assert!(err.contains("must be a non-negative int"), "{err}");
+    }
+}
@@ -1,7 +1,8 @@
 //! RES-2580: extended compile-time constant evaluation.
 //!
-//! Extends `Interpreter::eval_const_expr`
-//! previously rejected "not valid constant expression":
+//! Extends the const evaluator (now `const_eval::ConstEvaluator`) to
+//! accept forms it previously rejected as "not a valid constant
+//! expression":
 //!
 //! - **String concatenation**: `const GREETING = "Hello, " + NAME;`
 //! - **String ordering**: `const OK = "alpha" < "beta";`
@@ -10,7 +11,7 @@
 //! - **Single-expression blocks**: `const X = { 1 + 2 };`
 //! - **Tuple literals**: `const PAIR = (1, 2);`
 //!
-//! All new cases live in `Interpreter::eval_const_expr` in `lib.rs`.
+//! All new cases live in `const_eval.rs`.
 //! This module now also validates malformed const declarations so
 //! recovery placeholders do not leak into later phases.
 
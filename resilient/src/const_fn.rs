@@ -57,7 +57,7 @@ fn eval_depth(body: &Node, env: &HashMap<String, ConstValue>, depth: u32) -> Opt
     match body {
         Node::IntegerLiteral { value, .. } => Some(ConstValue::Int(*value)),
         Node::BooleanLiteral { value, .. } => Some(ConstValue::Bool(*value)),
-        Node::Identifier { name, .. } => env.get(name).copied(),
+        Node::Identifier { name, .. } => env.get(name.as_str()).copied(),
 
         Node::PrefixExpression {
             operator, right, ..
@@ -325,7 +325,7 @@ mod tests {
         let mut env = HashMap::new();
         env.insert("LIMIT".to_string(), ConstValue::Int(100));
         let node = Node::Identifier {
-            name: "LIMIT".to_string(),
+            name: "LIMIT".into(),
             span: crate::Span::default(),
         };
         assert_eq!(evaluate(&node, &env), Some(ConstValue::Int(100)));
@@ -30,7 +30,7 @@
 //! type parameter of the enclosing generic function is left alone
 //! (nothing to compare against; conservative-permissive by design).
 
-use crate::Node;
+use crate::{Node, Value};
 use resilient_span::Span;
 use std::collections::HashMap;
 
@@ -52,6 +52,18 @@ fn fixed_len(ty: &str) -> Option<usize> {
     len.trim().parse::<usize>().ok()
 }
 
+/// [`fixed_len`], also accepting a bracket-form length written as a
+/// `const` name (`[int; SIZE]`) by looking it up in the resolved
+/// const table.
+fn declared_len(ty: &str, consts: &HashMap<String, Value>) -> Option<usize> {
+    fixed_len(ty).or_else(
+        || match consts.get(crate::const_eval::array_len_slot(ty)?) {
+            Some(Value::Int(n)) => usize::try_from(*n).ok(),
+            _ => None,
+        },
+    )
+}
+
 /// Length of a direct array-literal expression, `None` for any other
 /// expression shape (which this pass then ignores).
 fn literal_len(value: &Node) -> Option<(usize, Span)> {
@@ -75,6 +87,13 @@ fn format_err(source_path: &str, span: Span, msg: &str) -> String {
 /// Program-level pass invoked from `<EXTENSION_PASSES>` in
 /// `typechecker.rs`.
 pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
+    // `[T; SIZE]` lengths named by a const resolve through the same
+    // evaluator the runtime uses.
+    let consts = match program {
+        Node::Program(stmts) => crate::const_eval::ConstEvaluator::resolve_lenient(stmts),
+        _ => HashMap::new(),
+    };
+
     // Pass 1: collect fn signatures so call sites can pair positional
     // array-literal arguments with `[T; N]`-typed parameters.
     let mut fns: HashMap<&str, &[(String, String)]> = HashMap::new();
@@ -108,7 +127,7 @@ pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
                 ..
             } => {
                 if let (Some(want), Some((got, span))) =
-                    (fixed_len(ty), literal_len(value.as_ref()))
+                    (declared_len(ty, &consts), literal_len(value.as_ref()))
                     && got != want
                 {
                     first_err = Some(format_err(
@@ -133,7 +152,8 @@ pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
                     return;
                 };
                 for (arg, (ptype, pname)) in arguments.iter().zip(params.iter()) {
-                    if let (Some(want), Some((got, span))) = (fixed_len(ptype), literal_len(arg))
+                    if let (Some(want), Some((got, span))) =
+                        (declared_len(ptype, &consts), literal_len(arg))
                         && got != want
                     {
                         first_err = Some(format_err(
@@ -154,7 +174,7 @@ pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
                 return_type: Some(rt),
                 ..
             } => {
-                if let Some(want) = fixed_len(rt)
+                if let Some(want) = declared_len(rt, &consts)
                     && let Some((got, span)) = find_return_literal_mismatch(body.as_ref(), want)
                 {
                     first_err = Some(format_err(
@@ -232,6 +252,15 @@ mod tests {
         assert_eq!(fixed_len("[int; N]"), None);
     }
 
+    #[test]
+    fn const_named_length_mismatch_rejected() {
+        let err = typecheck("const SIZE = 1 + 2;\nlet xs: [int; SIZE] = [1, 2];\n").unwrap_err();
+        assert!(err.contains("array literal has 2 element(s)"), "got: {err}");
+        assert!(err.contains("(expected 3)"), "got: {err}");
+        typecheck("const SIZE = 1 + 2;\nlet xs: [int; SIZE] = [1, 2, 3];\n")
+            .expect("matching const-named length is accepted");
+    }
+
     #[test]
     fn let_literal_mismatch_rejected() {
         let err = typecheck("fn main() { let xs: [int; 3] = [1, 2]; }\nmain();\n").unwrap_err();
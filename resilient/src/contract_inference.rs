@@ -297,7 +297,7 @@ fn single_return_expr(node: &Node) -> Option<String> {
 /// against the sentinel.
 fn format_simple_expr(node: &Node) -> Option<String> {
     match node {
-        Node::Identifier { name, .. } => Some(name.clone()),
+        Node::Identifier { name, .. } => Some(name.to_string()),
         Node::IntegerLiteral { value, .. } => Some(value.to_string()),
         Node::BooleanLiteral { value, .. } => Some(value.to_string()),
         Node::InfixExpression {
@@ -120,8 +120,8 @@ pub(crate) fn collect_contracts(program: &Node) -> std::collections::HashMap<Str
                     name.clone(),
                     ContractInfo {
                         params: param_names,
-                        requires: requires.clone(),
-                        ensures: ensures.clone(),
+                        requires: requires.to_vec(),
+                        ensures: ensures.to_vec(),
                         line: span.start.line,
                     },
                 );
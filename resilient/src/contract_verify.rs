@@ -133,7 +133,7 @@ pub fn verify_program(program: &Node) -> Vec<ClauseVerdict> {
         else {
             continue;
         };
-        for r in requires {
+        for r in requires.iter() {
             out.push(ClauseVerdict {
                 function_name: name.clone(),
                 kind: ClauseKind::Requires,
@@ -142,7 +142,7 @@ pub fn verify_program(program: &Node) -> Vec<ClauseVerdict> {
                 basis: ProofBasis::ClauseOnly,
             });
         }
-        for e in ensures {
+        for e in ensures.iter() {
             let (verdict, basis) = prove_ensures(e, requires, body);
             out.push(ClauseVerdict {
                 function_name: name.clone(),
@@ -392,7 +392,7 @@ fn parse_inferred_clause(clause: &str) -> Option<Node> {
     let sp = crate::span::Span::default();
     Some(Node::InfixExpression {
         left: Box::new(Node::Identifier {
-            name: ident.to_string(),
+            name: ident.into(),
             span: sp,
         }),
         operator,
@@ -406,7 +406,7 @@ fn parse_inferred_clause(clause: &str) -> Option<Node> {
 /// verdict machinery doesn't depend on this string.
 fn render_expr(expr: &Node) -> String {
     match expr {
-        Node::Identifier { name, .. } => name.clone(),
+        Node::Identifier { name, .. } => name.to_string(),
         Node::IntegerLiteral { value, .. } => value.to_string(),
         Node::BooleanLiteral { value, .. } => value.to_string(),
         Node::PrefixExpression {
@@ -222,7 +222,7 @@ mod tests {
         };
         for s in stmts {
             if let Node::Function { body, .. } = s.node {
-                return *body;
+                return std::sync::Arc::unwrap_or_clone(body);
             }
         }
         panic!("no function in source")
@@ -280,7 +280,7 @@ mod tests {
             let mut c = None;
             for s in stmts {
                 if let Node::Function { ensures, .. } = s.node {
-                    c = ensures.into_iter().next();
+                    c = std::sync::Arc::unwrap_or_clone(ensures).into_iter().next();
                 }
             }
             c.expect("ensures clause")
@@ -668,28 +668,33 @@ pub fn run() {
 /// Dispatch the `--dap` CLI flag. Returns `Some(exit_code)` if the flag
 /// was present and handled, `None` to fall through to the normal CLI.
 pub fn dispatch_dap(args: &[String]) -> Option<i32> {
-    // Look for `--dap <file>` or `debug <file>` subcommand.
-    let mut i = 1;
-    while i < args.len() {
-        let arg = &args[i];
-        if arg == "--dap" {
-            // `--dap` starts the DAP server on stdio. The program path
-            // comes from the DAP launch request, not the CLI.
-            run();
-            return Some(0);
+    // `rz debug --dap [<file>]` is a user-friendly alias for `--dap`.
+    // The file argument is printed as guidance but the actual program
+    // path comes from the launch request. Plain `rz debug <file>` is
+    // the console debugger (`debug_console`).
+    if args.get(1).map(String::as_str) == Some("debug") {
+        if !args.iter().any(|a| a == "--dap") {
+            return None;
         }
-        if arg == "debug" && i + 1 < args.len() {
-            // `rz debug <file>` is a user-friendly alias that starts the
-            // DAP server. The file argument is printed as guidance but the
-            // actual program path comes from the launch request.
+        if let Some(file) = args[2..].iter().find(|a| *a != "--dap") {
             eprintln!(
                 "Starting DAP server for {}. Connect a DAP client to stdin/stdout.",
-                args[i + 1]
+                file
             );
-            run();
-            return Some(0);
         }
-        i += 1;
+        run();
+        return Some(0);
+    }
+    // `--dap` starts the DAP server on stdio. The program path comes
+    // from the DAP launch request, not the CLI.
+    if args
+        .iter()
+        .skip(1)
+        .take_while(|a| *a != "--")
+        .any(|a| a == "--dap")
+    {
+        run();
+        return Some(0);
     }
     None
 }
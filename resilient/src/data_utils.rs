@@ -43,16 +43,16 @@ pub(crate) fn builtin_linspace(args: &[Value]) -> RResult<Value> {
             let stop = to_f64(stop, "linspace: stop")?;
             let n = to_usize_pos(n_val, "linspace")?;
             if n == 0 {
-                return Ok(Value::Array(vec![]));
+                return Ok(Value::Array(vec![].into()));
             }
             if n == 1 {
-                return Ok(Value::Array(vec![Value::Float(start)]));
+                return Ok(Value::Array(vec![Value::Float(start)].into()));
             }
             let step = (stop - start) / (n - 1) as f64;
             let v: Vec<Value> = (0..n)
                 .map(|i| Value::Float(start + i as f64 * step))
                 .collect();
-            Ok(Value::Array(v))
+            Ok(Value::Array(v.into()))
         }
         _ => Err(format!(
             "linspace: expected 3 arguments (start, stop, n), got {}",
@@ -71,16 +71,16 @@ pub(crate) fn builtin_logspace(args: &[Value]) -> RResult<Value> {
             let stop = to_f64(stop, "logspace: stop")?;
             let n = to_usize_pos(n_val, "logspace")?;
             if n == 0 {
-                return Ok(Value::Array(vec![]));
+                return Ok(Value::Array(vec![].into()));
             }
             if n == 1 {
-                return Ok(Value::Array(vec![Value::Float(10f64.powf(start))]));
+                return Ok(Value::Array(vec![Value::Float(10f64.powf(start))].into()));
             }
             let step = (stop - start) / (n - 1) as f64;
             let v: Vec<Value> = (0..n)
                 .map(|i| Value::Float(10f64.powf(start + i as f64 * step)))
                 .collect();
-            Ok(Value::Array(v))
+            Ok(Value::Array(v.into()))
         }
         _ => Err(format!(
             "logspace: expected 3 arguments (start, stop, n), got {}",
@@ -126,7 +126,7 @@ pub(crate) fn builtin_arange(args: &[Value]) -> RResult<Value> {
                 }
                 x += step;
             }
-            Ok(Value::Array(v))
+            Ok(Value::Array(v.into()))
         }
         _ => Err(format!(
             "arange: expected 3 arguments (start, stop, step), got {}",
@@ -144,7 +144,7 @@ pub(crate) fn builtin_csv_parse(args: &[Value]) -> RResult<Value> {
     match args {
         [v] => {
             let s = as_string("csv_parse", v)?;
-            Ok(Value::Array(parse_delimited(s, ',')))
+            Ok(Value::Array(parse_delimited(s, ',').into()))
         }
         _ => Err(format!(
             "csv_parse: expected 1 argument, got {}",
@@ -160,7 +160,7 @@ pub(crate) fn builtin_csv_parse_tsv(args: &[Value]) -> RResult<Value> {
     match args {
         [v] => {
             let s = as_string("csv_parse_tsv", v)?;
-            Ok(Value::Array(parse_delimited(s, '\t')))
+            Ok(Value::Array(parse_delimited(s, '\t').into()))
         }
         _ => Err(format!(
             "csv_parse_tsv: expected 1 argument, got {}",
@@ -486,7 +486,7 @@ pub(crate) fn builtin_rle_encode(args: &[Value]) -> RResult<Value> {
                 other => return Err(format!("rle_encode: expected Array, got {other}")),
             };
             if items.is_empty() {
-                return Ok(Value::Array(vec![]));
+                return Ok(Value::Array(vec![].into()));
             }
 
             let mut out: Vec<Value> = Vec::new();
@@ -497,13 +497,13 @@ pub(crate) fn builtin_rle_encode(args: &[Value]) -> RResult<Value> {
                 if values_equal(&current, item) {
                     count += 1;
                 } else {
-                    out.push(Value::Array(vec![Value::Int(count), current]));
+                    out.push(Value::Array(vec![Value::Int(count), current].into()));
                     count = 1;
                     current = item.clone();
                 }
             }
-            out.push(Value::Array(vec![Value::Int(count), current]));
-            Ok(Value::Array(out))
+            out.push(Value::Array(vec![Value::Int(count), current].into()));
+            Ok(Value::Array(out.into()))
         }
         _ => Err(format!(
             "rle_encode: expected 1 argument, got {}",
@@ -557,7 +557,7 @@ pub(crate) fn builtin_rle_decode(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         _ => Err(format!(
             "rle_decode: expected 1 argument, got {}",
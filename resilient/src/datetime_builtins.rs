@@ -9,6 +9,7 @@
 #![allow(clippy::collapsible_if, clippy::doc_lazy_continuation)]
 
 use crate::{Node, Value};
+use std::rc::Rc;
 
 type RResult<T> = Result<T, String>;
 
@@ -23,7 +24,7 @@ fn make_datetime(
 ) -> Value {
     Value::Struct {
         name: "DateTime".to_string(),
-        fields: vec![
+        fields: Rc::new(vec![
             ("year".to_string(), Value::Int(year)),
             ("month".to_string(), Value::Int(month)),
             ("day".to_string(), Value::Int(day)),
@@ -31,7 +32,7 @@ fn make_datetime(
             ("minute".to_string(), Value::Int(minute)),
             ("second".to_string(), Value::Int(second)),
             ("nanos".to_string(), Value::Int(nanos)),
-        ],
+        ]),
     }
 }
 
@@ -49,7 +50,7 @@ fn extract_datetime(v: &Value) -> RResult<(i64, i64, i64, i64, i64, i64, i64)> {
     let mut minute = None;
     let mut second = None;
     let mut nanos = None;
-    for (fname, fval) in fields {
+    for (fname, fval) in fields.iter() {
         if let Value::Int(n) = fval {
             match fname.as_str() {
                 "year" => year = Some(*n),
@@ -13,28 +13,48 @@
 //!    never appears as an `Identifier` reference anywhere in the
 //!    enclosing function body. Names starting with `_` are exempt.
 //!
-//! All diagnostics go to stderr in the form
-//! `warning: <source_path>:<line>:<col>: <message>`.
+//! Unreachable-code diagnostics go to stderr in the form
+//! `warning: <source_path>:<line>:<col>: <message>`; the two unused
+//! checks report under the `unused` warning category (see
+//! `crate::warnings`), so `--no-warn unused` silences them and
+//! `--deny-warnings` makes them errors.
 //!
 //! The pass is called from the `<EXTENSION_PASSES>` block in
 //! `typechecker.rs` after the main type-check completes.
 
 use crate::Node;
 use crate::span::{Span, Spanned};
+use crate::warnings::{WarningCategory, WarningConfig};
 use std::collections::{HashMap, HashSet};
 
 // ---------------------------------------------------------------------------
 // Public entry point
 // ---------------------------------------------------------------------------
 
-/// Run all dead-code lint checks on `program` and print warnings to stderr.
-pub(crate) fn check(program: &Node, source_path: &str) {
-    for msg in collect_warnings(program, source_path) {
+/// Run all dead-code lint checks on `program` and print warnings to
+/// stderr. Returns how many `unused` reports `warnings` denied.
+pub(crate) fn check(program: &Node, source_path: &str, warnings: WarningConfig) -> usize {
+    let stmts = match program {
+        Node::Program(stmts) => stmts,
+        _ => return 0,
+    };
+    let mut unreachable = Vec::new();
+    collect_unreachable_warnings_stmts(stmts, source_path, &mut unreachable);
+    for msg in unreachable {
         crate::typechecker::emit_check_warning_plain(msg, source_path, "dead_code");
     }
+    let mut denied = 0;
+    for (span, msg) in collect_unused(stmts) {
+        let (line, col) = (span.start.line, span.start.column);
+        if warnings.report(WarningCategory::Unused, source_path, line, col, &msg) {
+            denied += 1;
+        }
+    }
+    denied
 }
 
 /// Collect all dead-code warnings as strings (for testing).
+#[cfg(test)]
 pub(crate) fn collect_warnings(program: &Node, source_path: &str) -> Vec<String> {
     let stmts = match program {
         Node::Program(stmts) => stmts,
@@ -42,17 +62,28 @@ pub(crate) fn collect_warnings(program: &Node, source_path: &str) -> Vec<String>
     };
 
     let mut warnings = Vec::new();
-    collect_unused_fn_warnings(stmts, source_path, &mut warnings);
     collect_unreachable_warnings_stmts(stmts, source_path, &mut warnings);
-    collect_unused_var_warnings_stmts(stmts, source_path, &mut warnings);
+    warnings.extend(collect_unused(stmts).into_iter().map(|(span, msg)| {
+        let loc = fmt_loc(source_path, span);
+        format!("warning: {loc}: {msg}")
+    }));
     warnings
 }
 
+/// Unused top-level functions, then unused `let`s, each with the
+/// span of its declaration.
+fn collect_unused(stmts: &[Spanned<Node>]) -> Vec<(Span, String)> {
+    let mut out = Vec::new();
+    collect_unused_fn_warnings(stmts, &mut out);
+    collect_unused_var_warnings_stmts(stmts, &mut out);
+    out
+}
+
 // ---------------------------------------------------------------------------
 // 1. Unused top-level functions
 // ---------------------------------------------------------------------------
 
-fn collect_unused_fn_warnings(stmts: &[Spanned<Node>], source_path: &str, out: &mut Vec<String>) {
+fn collect_unused_fn_warnings(stmts: &[Spanned<Node>], out: &mut Vec<(Span, String)>) {
     let mut declared: HashMap<String, Span> = HashMap::new();
     let mut called: HashSet<String> = HashSet::new();
 
@@ -70,9 +101,7 @@ fn collect_unused_fn_warnings(stmts: &[Spanned<Node>], source_path: &str, out: &
         if name == "main" || name.starts_with('_') || name.contains('$') {
             continue;
         }
-        let span = declared[name];
-        let loc = fmt_loc(source_path, span);
-        out.push(format!("warning: {loc}: function `{name}` is never called"));
+        out.push((declared[name], format!("function `{name}` is never called")));
     }
 }
 
@@ -102,7 +131,7 @@ fn collect_called_names(node: &Node, called: &mut HashSet<String>) {
             ..
         } => {
             if let Node::Identifier { name, .. } = function.as_ref() {
-                called.insert(name.clone());
+                called.insert(name.to_string());
             }
             collect_called_names(function, called);
             for arg in arguments {
@@ -121,10 +150,10 @@ fn collect_called_names(node: &Node, called: &mut HashSet<String>) {
             ..
         } => {
             collect_called_names(body, called);
-            for r in requires {
+            for r in requires.iter() {
                 collect_called_names(r, called);
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 collect_called_names(e, called);
             }
         }
@@ -341,36 +370,32 @@ fn is_terminator(node: &Node) -> bool {
 // 3. Unused let bindings
 // ---------------------------------------------------------------------------
 
-fn collect_unused_var_warnings_stmts(
-    stmts: &[Spanned<Node>],
-    source_path: &str,
-    out: &mut Vec<String>,
-) {
+fn collect_unused_var_warnings_stmts(stmts: &[Spanned<Node>], out: &mut Vec<(Span, String)>) {
     for s in stmts {
-        collect_unused_var_in_node(&s.node, source_path, out);
+        collect_unused_var_in_node(&s.node, out);
     }
 }
 
-fn collect_unused_var_in_node(node: &Node, source_path: &str, out: &mut Vec<String>) {
+fn collect_unused_var_in_node(node: &Node, out: &mut Vec<(Span, String)>) {
     match node {
         Node::Function { body, .. } => {
-            check_fn_body_for_unused_vars(body, source_path, out);
+            check_fn_body_for_unused_vars(body, out);
         }
         Node::ImplBlock { methods, .. } => {
             for m in methods {
-                collect_unused_var_in_node(m, source_path, out);
+                collect_unused_var_in_node(m, out);
             }
         }
         Node::ModuleDecl { body, .. } => {
             for child in body {
-                collect_unused_var_in_node(child, source_path, out);
+                collect_unused_var_in_node(child, out);
             }
         }
         _ => {}
     }
 }
 
-fn check_fn_body_for_unused_vars(body: &Node, source_path: &str, out: &mut Vec<String>) {
+fn check_fn_body_for_unused_vars(body: &Node, out: &mut Vec<(Span, String)>) {
     let stmts = match body {
         Node::Block { stmts, .. } => stmts,
         _ => return,
@@ -394,9 +419,9 @@ fn check_fn_body_for_unused_vars(body: &Node, source_path: &str, out: &mut Vec<S
             continue;
         }
         if span.start.line > 0 {
-            let loc = fmt_loc(source_path, *span);
-            out.push(format!(
-                "warning: {loc}: variable `{name}` is assigned but never read"
+            out.push((
+                *span,
+                format!("variable `{name}` is assigned but never read"),
             ));
         }
     }
@@ -438,7 +463,7 @@ fn collect_let_bindings_in_node(node: &Node, out: &mut Vec<(String, Span)>) {
 fn collect_identifier_reads(node: &Node, reads: &mut HashSet<String>) {
     match node {
         Node::Identifier { name, .. } => {
-            reads.insert(name.clone());
+            reads.insert(name.to_string());
         }
         Node::Assignment { name: _, value, .. } => {
             collect_identifier_reads(value, reads);
@@ -458,10 +483,10 @@ fn collect_identifier_reads(node: &Node, reads: &mut HashSet<String>) {
             ..
         } => {
             collect_identifier_reads(body, reads);
-            for r in requires {
+            for r in requires.iter() {
                 collect_identifier_reads(r, reads);
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 collect_identifier_reads(e, reads);
             }
         }
@@ -77,7 +77,7 @@ fn walk_sends(node: &Node, actors: &HashSet<&str>, out: &mut HashSet<String>) {
                 if name == "send" {
                     if let Some(Node::Identifier { name: tgt, .. }) = arguments.first() {
                         if actors.contains(tgt.as_str()) {
-                            out.insert(tgt.clone());
+                            out.insert(tgt.to_string());
                         }
                     }
                 }
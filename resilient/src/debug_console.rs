@@ -0,0 +1,700 @@
+//! `rz debug <file>`: a console debugger for the tree-walking
+//! interpreter.
+//!
+//! The program runs as under `rz <file>`, and the interpreter reports
+//! each statement it is about to run, each call and return of a user
+//! function, and each failed attempt of a `live` block. The debugger
+//! stops on the first statement, at breakpoints, after a step, and —
+//! with `--live` or the `live on` command — on every `live` retry,
+//! showing the error that triggered it. While stopped it reads
+//! commands from stdin:
+//!
+//! ```text
+//! step, s            run to the next statement, entering calls
+//! next, n            run to the next statement in this function or its caller
+//! continue, c        run to the next breakpoint
+//! break, b [FILE:]LINE   set a breakpoint; `break` alone lists them
+//! delete, d LINE     remove a breakpoint
+//! print, p NAME      show a variable, looked up through the scope chain
+//! vars, v            show every scope of the environment chain
+//! backtrace, bt      show the calls that led here
+//! live on|off        stop on every `live` retry
+//! quit, q            stop the program
+//! ```
+//!
+//! An empty line repeats the last command. At the end of stdin the
+//! program runs on to the end without stopping again.
+//!
+//! Spans don't record which file they came from, so breakpoints and
+//! locations are lines of the debugged file; a statement from a
+//! `use`d file is shown with its own line number.
+//!
+//! `rz run --step` drives the same hooks for teaching: before each
+//! statement it shows the statement, the variables that appeared,
+//! changed or went out of scope since the last step, and the retry
+//! state of the enclosing `live` block, then waits for Enter.
+//!
+//! The state is per thread, like `call_trace`: a `debug` run sets it
+//! up before the interpreter starts, which then registers
+//! [`Observer`] to report to it (see `eval_observer`).
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::BufRead;
+use std::path::Path;
+
+use crate::bridge::ResilientValue;
+use crate::eval_observer::{EvalObserver, Scope};
+use crate::{Environment, Node, Value};
+
+/// Where to stop next, besides breakpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// At the next statement.
+    Step,
+    /// At the next statement at this call depth or shallower.
+    Next(usize),
+    /// Only at breakpoints (and retries with `live on`).
+    Continue,
+}
+
+/// The flags of `rz debug`, taken out of the argument list before
+/// the usual run flags are parsed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct DebugOptions {
+    breakpoints: Vec<String>,
+    break_on_retry: bool,
+}
+
+struct Session {
+    file: String,
+    lines: Vec<String>,
+    breakpoints: BTreeSet<usize>,
+    mode: Mode,
+    break_on_retry: bool,
+    /// The user functions being run, with the line they were called
+    /// from; the innermost last.
+    calls: Vec<(String, usize)>,
+    last_command: String,
+    /// stdin has ended; run to the end.
+    detached: bool,
+    /// Set for `rz run --step` instead of the debugger's prompt.
+    stepper: Option<Stepper>,
+}
+
+/// What `rz run --step` showed last, so each step prints only what
+/// changed.
+#[derive(Debug, Default)]
+struct Stepper {
+    steps: usize,
+    /// Every variable in scope at the last step, as shown.
+    shown: BTreeMap<String, String>,
+    /// The last failed attempt of a `live` block: its line, the
+    /// attempt, the retry budget and the error.
+    last_retry: Option<(usize, usize, usize, String)>,
+}
+
+/// What a command asks the paused program to do.
+#[derive(Debug, PartialEq, Eq)]
+enum Flow {
+    Stay,
+    Resume,
+    Quit,
+}
+
+thread_local! {
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+    static SESSION: RefCell<Option<Session>> = const { RefCell::new(None) };
+}
+
+/// Whether a debug session runs on this thread.
+#[inline]
+pub(crate) fn active() -> bool {
+    ACTIVE.with(Cell::get)
+}
+
+/// Remove `rz debug`'s own flags (`--break [FILE:]LINE`, `--live`)
+/// from `args`.
+pub(crate) fn take_flags(args: &mut Vec<String>) -> Result<DebugOptions, String> {
+    let mut options = DebugOptions::default();
+    let mut i = 1;
+    while i < args.len() && args[i] != "--" {
+        if args[i] == "--live" {
+            options.break_on_retry = true;
+            args.remove(i);
+        } else if args[i] == "--break" {
+            args.remove(i);
+            if i >= args.len() {
+                return Err("--break requires a line: --break [FILE:]LINE".to_string());
+            }
+            options.breakpoints.push(args.remove(i));
+        } else if let Some(spec) = args[i].strip_prefix("--break=") {
+            options.breakpoints.push(spec.to_string());
+            args.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    Ok(options)
+}
+
+/// Start a session for `file`, whose text is `source`. The first
+/// statement stops.
+pub(crate) fn start(file: &str, source: &str, options: &DebugOptions) -> Result<(), String> {
+    let mut session = Session::new(file, source);
+    session.break_on_retry = options.break_on_retry;
+    for spec in &options.breakpoints {
+        let line = session.breakpoint_line(spec)?;
+        session.breakpoints.insert(line);
+    }
+    install(session);
+    Ok(())
+}
+
+/// Start `rz run --step` for `file`, whose text is `source`.
+pub(crate) fn start_stepping(file: &str, source: &str) {
+    let mut session = Session::new(file, source);
+    session.stepper = Some(Stepper::default());
+    install(session);
+    eprintln!("[step] Enter runs the statement shown; `c` runs to the end, `q` quits.");
+}
+
+fn install(session: Session) {
+    SESSION.with(|s| *s.borrow_mut() = Some(session));
+    ACTIVE.with(|a| a.set(true));
+}
+
+/// Reports the run to this thread's session.
+pub(crate) struct Observer;
+
+impl EvalObserver for Observer {
+    fn node_enter(&mut self, node: &Node, scope: &Scope<'_>, _function: &str) {
+        at_statement(node, scope.0);
+    }
+
+    fn call_enter(
+        &mut self,
+        name: &str,
+        _: &[(String, String)],
+        _: &[ResilientValue],
+        line: usize,
+    ) {
+        enter(name, line);
+    }
+
+    fn call_exit(&mut self, _name: &str, _result: Result<&ResilientValue, &str>) {
+        exit();
+    }
+
+    fn live_retry(
+        &mut self,
+        line: usize,
+        attempt: usize,
+        max: usize,
+        error: &str,
+        scope: &Scope<'_>,
+    ) {
+        at_retry(line, attempt, max, error, scope.0);
+    }
+}
+
+/// Called before the interpreter runs `node`, with the environment
+/// it runs in.
+fn at_statement(node: &Node, env: &Environment) {
+    let Some(line) = statement_line(node) else {
+        return;
+    };
+    if with_session(|s| s.stepper.is_some()) == Some(true) {
+        step(line, env);
+        return;
+    }
+    let stop = with_session(|s| {
+        let depth = s.calls.len();
+        !s.detached
+            && (s.breakpoints.contains(&line)
+                || match s.mode {
+                    Mode::Step => true,
+                    Mode::Next(at) => depth <= at,
+                    Mode::Continue => false,
+                })
+    });
+    if stop == Some(true) {
+        let reason = if with_session(|s| s.breakpoints.contains(&line)) == Some(true) {
+            "breakpoint"
+        } else {
+            "step"
+        };
+        pause(line, reason, env);
+    }
+}
+
+/// Called when a `live` block at `line` failed attempt `attempt` of
+/// `max` with `error`.
+fn at_retry(line: usize, attempt: usize, max: usize, error: &str, env: &Environment) {
+    let error = error.lines().next().unwrap_or_default();
+    let stepping = with_session(|s| match s.stepper.as_mut() {
+        Some(stepper) => {
+            stepper.last_retry = Some((line, attempt, max, error.to_string()));
+            true
+        }
+        None => false,
+    });
+    if stepping == Some(true) {
+        return;
+    }
+    if with_session(|s| s.break_on_retry && !s.detached) == Some(true) {
+        pause(
+            line,
+            &format!("live retry {attempt}/{max} after: {error}"),
+            env,
+        );
+    }
+}
+
+/// Called when the user function `name` is called from `line`.
+fn enter(name: &str, line: usize) {
+    with_session(|s| s.calls.push((name.to_string(), line)));
+}
+
+/// Called when the call [`enter`] saw last returns.
+fn exit() {
+    with_session(|s| s.calls.pop());
+}
+
+fn with_session<R>(f: impl FnOnce(&mut Session) -> R) -> Option<R> {
+    SESSION.with(|s| s.borrow_mut().as_mut().map(f))
+}
+
+/// `rz run --step`: show the statement at `line` and what changed
+/// since the last step, then wait for Enter.
+fn step(line: usize, env: &Environment) {
+    let live = crate::live_retry_counts();
+    let Some(report) = with_session(|s| s.step_report(line, env, &live)) else {
+        return;
+    };
+    eprint!("{report}");
+    if with_session(|s| s.detached) != Some(false) {
+        return;
+    }
+    eprint!("(step) ");
+    let mut input = String::new();
+    match std::io::stdin().lock().read_line(&mut input) {
+        Ok(0) | Err(_) => {
+            eprintln!();
+            with_session(|s| s.detached = true);
+        }
+        Ok(_) => match input.trim() {
+            "c" | "continue" => {
+                with_session(|s| s.detached = true);
+            }
+            "q" | "quit" => std::process::exit(0),
+            _ => {}
+        },
+    }
+}
+
+/// Show where the program stopped and run commands until one resumes it.
+fn pause(line: usize, reason: &str, env: &Environment) {
+    let Some(location) = with_session(|s| s.location(line)) else {
+        return;
+    };
+    eprintln!("[debug] stopped ({reason}) at {location}");
+    let stdin = std::io::stdin();
+    loop {
+        eprint!("(rdb) ");
+        let mut input = String::new();
+        match stdin.lock().read_line(&mut input) {
+            Ok(0) | Err(_) => {
+                eprintln!();
+                with_session(|s| s.detached = true);
+                return;
+            }
+            Ok(_) => {}
+        }
+        let flow = with_session(|s| {
+            let (flow, text) = s.command(input.trim(), line, env);
+            if !text.is_empty() {
+                eprintln!("{text}");
+            }
+            flow
+        });
+        match flow {
+            Some(Flow::Stay) => {}
+            Some(Flow::Resume) | None => return,
+            Some(Flow::Quit) => std::process::exit(0),
+        }
+    }
+}
+
+impl Session {
+    fn new(file: &str, source: &str) -> Session {
+        Session {
+            file: file.to_string(),
+            lines: source.lines().map(str::to_string).collect(),
+            breakpoints: BTreeSet::new(),
+            mode: Mode::Step,
+            break_on_retry: false,
+            calls: Vec::new(),
+            last_command: String::new(),
+            detached: false,
+            stepper: None,
+        }
+    }
+
+    /// One step of `rz run --step`: the statement at `line`, each
+    /// variable that appeared (`+`), changed (`~`) or left scope
+    /// (`-`) since the last step, and — inside a `live` block, whose
+    /// retry counters are `live` — how far its retries have got.
+    fn step_report(&mut self, line: usize, env: &Environment, live: &[usize]) -> String {
+        let location = self.location(line);
+        let Some(stepper) = self.stepper.as_mut() else {
+            return String::new();
+        };
+        stepper.steps += 1;
+        let mut out = format!("[step {}] {}\n", stepper.steps, location);
+        let now = visible(env);
+        for (name, value) in &now {
+            match stepper.shown.get(name) {
+                None => out.push_str(&format!("  + {name} = {value}\n")),
+                Some(old) if old != value => {
+                    out.push_str(&format!("  ~ {name} = {old} -> {value}\n"))
+                }
+                Some(_) => {}
+            }
+        }
+        for name in stepper.shown.keys() {
+            if !now.contains_key(name) {
+                out.push_str(&format!("  - {name}\n"));
+            }
+        }
+        stepper.shown = now;
+        match (live.last(), &stepper.last_retry) {
+            (None, _) => stepper.last_retry = None,
+            (Some(&retries), Some((at, attempt, max, error))) if retries > 0 => out.push_str(
+                &format!("  live: block at line {at}, attempt {attempt}/{max} failed: {error}\n"),
+            ),
+            (Some(_), _) => out.push_str("  live: inside a live block, first attempt\n"),
+        }
+        out
+    }
+
+    fn location(&self, line: usize) -> String {
+        match self.lines.get(line.wrapping_sub(1)) {
+            Some(text) => format!("{}:{}: {}", self.file, line, text.trim()),
+            None => format!("{}:{}", self.file, line),
+        }
+    }
+
+    /// The line a `[FILE:]LINE` breakpoint names.
+    fn breakpoint_line(&self, spec: &str) -> Result<usize, String> {
+        let (file, line) = match spec.rsplit_once(':') {
+            Some((file, line)) => (Some(file), line),
+            None => (None, spec),
+        };
+        if let Some(file) = file {
+            let ours = Path::new(&self.file);
+            let same = Path::new(file) == ours || Path::new(file).file_name() == ours.file_name();
+            if !same {
+                return Err(format!(
+                    "breakpoints are lines of {}: statements don't record which file they came from",
+                    self.file
+                ));
+            }
+        }
+        match line.trim().parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!("expected a line number, got `{}`", line.trim())),
+        }
+    }
+
+    /// Run one command typed at the prompt while stopped at `line`;
+    /// returns what to do next and the text to show.
+    fn command(&mut self, input: &str, line: usize, env: &Environment) -> (Flow, String) {
+        let input = if input.is_empty() {
+            self.last_command.clone()
+        } else {
+            self.last_command = input.to_string();
+            input.to_string()
+        };
+        let (name, arg) = match input.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (input.as_str(), ""),
+        };
+        match name {
+            "" => (Flow::Stay, String::new()),
+            "s" | "step" => {
+                self.mode = Mode::Step;
+                (Flow::Resume, String::new())
+            }
+            "n" | "next" => {
+                self.mode = Mode::Next(self.calls.len());
+                (Flow::Resume, String::new())
+            }
+            "c" | "continue" => {
+                self.mode = Mode::Continue;
+                (Flow::Resume, String::new())
+            }
+            "b" | "break" if arg.is_empty() => {
+                if self.breakpoints.is_empty() {
+                    return (Flow::Stay, "no breakpoints".to_string());
+                }
+                let list: Vec<String> = self
+                    .breakpoints
+                    .iter()
+                    .map(|l| format!("breakpoint at {}", self.location(*l)))
+                    .collect();
+                (Flow::Stay, list.join("\n"))
+            }
+            "b" | "break" => match self.breakpoint_line(arg) {
+                Ok(l) => {
+                    self.breakpoints.insert(l);
+                    (Flow::Stay, format!("breakpoint at {}", self.location(l)))
+                }
+                Err(e) => (Flow::Stay, format!("error: {e}")),
+            },
+            "d" | "delete" => match self.breakpoint_line(arg) {
+                Ok(l) if self.breakpoints.remove(&l) => {
+                    (Flow::Stay, format!("deleted the breakpoint at line {l}"))
+                }
+                Ok(l) => (Flow::Stay, format!("no breakpoint at line {l}")),
+                Err(e) => (Flow::Stay, format!("error: {e}")),
+            },
+            "p" | "print" if arg.is_empty() => (Flow::Stay, "usage: print NAME".to_string()),
+            "p" | "print" => match env.get(arg) {
+                Some(value) => (Flow::Stay, format!("{arg} = {}", show(&value))),
+                None => (Flow::Stay, format!("no variable `{arg}` in scope")),
+            },
+            "v" | "vars" => (Flow::Stay, vars(env)),
+            "bt" | "backtrace" => (Flow::Stay, self.backtrace(line)),
+            "live" => match arg {
+                "on" => {
+                    self.break_on_retry = true;
+                    (Flow::Stay, "stopping on every live retry".to_string())
+                }
+                "off" => {
+                    self.break_on_retry = false;
+                    (Flow::Stay, "not stopping on live retries".to_string())
+                }
+                _ => (Flow::Stay, "usage: live on|off".to_string()),
+            },
+            "q" | "quit" => (Flow::Quit, String::new()),
+            "h" | "help" => (Flow::Stay, HELP.trim_end().to_string()),
+            other => (
+                Flow::Stay,
+                format!("unknown command `{other}`; `help` lists them"),
+            ),
+        }
+    }
+
+    fn backtrace(&self, line: usize) -> String {
+        let mut out = Vec::new();
+        let mut at = line;
+        for (i, (name, called_from)) in self.calls.iter().rev().enumerate() {
+            out.push(format!("#{i} {name} at {}:{at}", self.file));
+            at = *called_from;
+        }
+        out.push(format!(
+            "#{} <main> at {}:{at}",
+            self.calls.len(),
+            self.file
+        ));
+        out.join("\n")
+    }
+}
+
+const HELP: &str = "\
+step, s              run to the next statement, entering calls
+next, n              run to the next statement in this function or its caller
+continue, c          run to the next breakpoint
+break, b [FILE:]LINE set a breakpoint; `break` alone lists them
+delete, d LINE       remove a breakpoint
+print, p NAME        show a variable
+vars, v              show every scope, innermost first
+backtrace, bt        show the calls that led here
+live on|off          stop on every `live` retry
+quit, q              stop the program
+";
+
+/// Every scope of `env`, innermost first. Functions and builtins are
+/// left out, and so are scopes with nothing else in them.
+fn vars(env: &Environment) -> String {
+    let frames = user_frames(env);
+    let last = frames.len().saturating_sub(1);
+    let mut out = Vec::new();
+    for (i, mut bindings) in frames.into_iter().enumerate() {
+        if bindings.is_empty() {
+            continue;
+        }
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+        out.push(if i == last {
+            "globals:".to_string()
+        } else {
+            format!("scope {i}:")
+        });
+        for (name, value) in bindings {
+            out.push(format!("  {name} = {}", show(&value)));
+        }
+    }
+    if out.is_empty() {
+        "no variables in scope".to_string()
+    } else {
+        out.join("\n")
+    }
+}
+
+/// The variables visible from `env`, each as shown, with an inner
+/// binding hiding an outer one of the same name.
+fn visible(env: &Environment) -> BTreeMap<String, String> {
+    let mut out = BTreeMap::new();
+    for frame in user_frames(env) {
+        for (name, value) in frame {
+            out.entry(name).or_insert_with(|| show(&value));
+        }
+    }
+    out
+}
+
+/// The program's scopes of `env`, innermost first, without their
+/// functions.
+fn user_frames(env: &Environment) -> Vec<Vec<(String, Value)>> {
+    let mut frames = env.frames();
+    // The outermost frame is the builtins (and `None`) every
+    // interpreter shares, and inside it may sit the prelude's
+    // functions; the next one in holds the program's globals.
+    frames.pop();
+    if frames.len() > 1 && frames.last().is_some_and(|f| is_prelude(f)) {
+        frames.pop();
+    }
+    frames
+        .into_iter()
+        .map(|frame| {
+            frame
+                .into_iter()
+                .filter(|(_, v)| !matches!(v, Value::Function(_) | Value::Builtin { .. }))
+                .collect()
+        })
+        .collect()
+}
+
+/// Whether `frame` is the one holding the prelude: exactly its
+/// functions, nothing else.
+fn is_prelude(frame: &[(String, Value)]) -> bool {
+    let names = crate::prelude::signatures();
+    frame.len() == names.len()
+        && frame
+            .iter()
+            .all(|(name, _)| names.iter().any(|(n, _, _)| n == name))
+}
+
+/// A value as the program would print it, with strings quoted.
+fn show(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("{s:?}"),
+        other => other.to_string(),
+    }
+}
+
+/// The line a statement starts on, when the parser recorded it.
+fn statement_line(node: &Node) -> Option<usize> {
+    let span = match node {
+        Node::LetStatement { span, .. }
+        | Node::StaticLet { span, .. }
+        | Node::Const { span, .. }
+        | Node::Assignment { span, .. }
+        | Node::ReturnStatement { span, .. }
+        | Node::Break { span }
+        | Node::Continue { span }
+        | Node::IfStatement { span, .. }
+        | Node::WhileStatement { span, .. }
+        | Node::ForInStatement { span, .. }
+        | Node::FieldAssignment { span, .. }
+        | Node::IndexAssignment { span, .. }
+        | Node::LiveBlock { span, .. }
+        | Node::Assert { span, .. }
+        | Node::ExpressionStatement { span, .. } => span,
+        _ => return None,
+    };
+    (span.start.line > 0).then_some(span.start.line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        Session::new("dir/prog.rz", "let x = 1;\nf(x);\n")
+    }
+
+    #[test]
+    fn commands_set_breakpoints_steps_and_show_variables() {
+        let mut s = session();
+        // Like `Interpreter::new`: globals sit inside the builtins.
+        let env = Environment::new_enclosed(Environment::new());
+        env.set("x", Value::Int(1));
+        let inner = Environment::new_enclosed(env.clone());
+        inner.set("name", Value::String("ok".into()));
+
+        assert_eq!(s.command("b prog.rz:2", 1, &inner).0, Flow::Stay);
+        assert!(s.breakpoints.contains(&2));
+        assert!(s.command("b other.rz:2", 1, &inner).1.contains("error"));
+        assert_eq!(
+            s.command("p name", 1, &inner).1,
+            "name = \"ok\"".to_string()
+        );
+        assert_eq!(
+            s.command("vars", 1, &inner).1,
+            "scope 0:\n  name = \"ok\"\nglobals:\n  x = 1"
+        );
+
+        s.calls.push(("f".to_string(), 2));
+        assert_eq!(s.command("next", 5, &inner).0, Flow::Resume);
+        assert_eq!(s.mode, Mode::Next(1));
+        assert_eq!(
+            s.backtrace(5),
+            "#0 f at dir/prog.rz:5\n#1 <main> at dir/prog.rz:2"
+        );
+        // An empty line repeats the last command.
+        s.mode = Mode::Continue;
+        assert_eq!(s.command("", 5, &inner).0, Flow::Resume);
+        assert_eq!(s.mode, Mode::Next(1));
+    }
+
+    #[test]
+    fn steps_show_what_changed_and_the_live_retry_state() {
+        let mut s = session();
+        s.stepper = Some(Stepper::default());
+        let env = Environment::new_enclosed(Environment::new());
+        env.set("x", Value::Int(1));
+        assert_eq!(
+            s.step_report(1, &env, &[]),
+            "[step 1] dir/prog.rz:1: let x = 1;\n  + x = 1\n"
+        );
+
+        env.set("x", Value::Int(2));
+        let inner = Environment::new_enclosed(env.clone());
+        inner.set("y", Value::String("a".into()));
+        s.stepper.as_mut().unwrap().last_retry = Some((2, 1, 3, "boom".to_string()));
+        assert_eq!(
+            s.step_report(2, &inner, &[1]),
+            "[step 2] dir/prog.rz:2: f(x);\n  ~ x = 1 -> 2\n  + y = \"a\"\n  live: block at line 2, attempt 1/3 failed: boom\n"
+        );
+
+        assert_eq!(
+            s.step_report(2, &env, &[]),
+            "[step 3] dir/prog.rz:2: f(x);\n  - y\n"
+        );
+        assert!(s.stepper.as_ref().unwrap().last_retry.is_none());
+    }
+
+    #[test]
+    fn debug_flags_are_taken_out_of_the_arguments() {
+        let mut args: Vec<String> = ["rz", "--break", "3", "p.rz", "--live", "--", "--live"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let options = take_flags(&mut args).unwrap();
+        assert_eq!(options.breakpoints, vec!["3".to_string()]);
+        assert!(options.break_on_retry);
+        assert_eq!(args, vec!["rz", "p.rz", "--", "--live"]);
+    }
+}
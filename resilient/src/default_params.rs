@@ -270,11 +270,11 @@ fn rewrite_calls(node: &mut Node, sigs: &HashMap<String, FnDefaults>) {
             recovers_to,
             ..
         } => {
-            rewrite_calls(body, sigs);
-            for r in requires.iter_mut() {
+            rewrite_calls(std::sync::Arc::make_mut(body), sigs);
+            for r in std::sync::Arc::make_mut(requires) {
                 rewrite_calls(r, sigs);
             }
-            for e in ensures.iter_mut() {
+            for e in std::sync::Arc::make_mut(ensures) {
                 rewrite_calls(e, sigs);
             }
             if let Some(rec) = recovers_to {
@@ -288,11 +288,11 @@ fn rewrite_calls(node: &mut Node, sigs: &HashMap<String, FnDefaults>) {
             recovers_to,
             ..
         } => {
-            rewrite_calls(body, sigs);
-            for r in requires.iter_mut() {
+            rewrite_calls(std::sync::Arc::make_mut(body), sigs);
+            for r in std::sync::Arc::make_mut(requires) {
                 rewrite_calls(r, sigs);
             }
-            for e in ensures.iter_mut() {
+            for e in std::sync::Arc::make_mut(ensures) {
                 rewrite_calls(e, sigs);
             }
             if let Some(rec) = recovers_to {
@@ -517,7 +517,7 @@ mod tests {
 
     fn ident(name: &str) -> Node {
         Node::Identifier {
-            name: name.to_string(),
+            name: name.into(),
             span: Span::default(),
         }
     }
@@ -538,12 +538,12 @@ mod tests {
             name: name.to_string(),
             parameters,
             defaults,
-            body: Box::new(Node::Block {
+            body: std::sync::Arc::new(Node::Block {
                 stmts: Vec::new(),
                 span: Span::default(),
             }),
-            requires: Vec::new(),
-            ensures: Vec::new(),
+            requires: std::sync::Arc::default(),
+            ensures: std::sync::Arc::default(),
             recovers_to: None,
             return_type: None,
             span: Span::default(),
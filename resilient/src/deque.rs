@@ -28,7 +28,7 @@ pub(crate) fn builtin_deque_new(args: &[Value]) -> RResult<Value> {
             args.len()
         ));
     }
-    Ok(Value::Array(Vec::new()))
+    Ok(Value::Array(Vec::new().into()))
 }
 
 /// `deque_push_front(dq, val) → Array` — prepend val; O(n).
@@ -38,7 +38,7 @@ pub(crate) fn builtin_deque_push_front(args: &[Value]) -> RResult<Value> {
             let mut out = Vec::with_capacity(dq.len() + 1);
             out.push(val.clone());
             out.extend_from_slice(dq);
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other, _] => Err(format!("deque_push_front: expected Array, got {other}")),
         _ => Err(format!(
@@ -73,11 +73,11 @@ pub(crate) fn builtin_deque_pop_front(args: &[Value]) -> RResult<Value> {
             if dq.is_empty() {
                 Ok(Value::Tuple(vec![
                     Value::Option(None),
-                    Value::Array(Vec::new()),
+                    Value::Array(Vec::new().into()),
                 ]))
             } else {
                 let front = dq[0].clone();
-                let rest = Value::Array(dq[1..].to_vec());
+                let rest = Value::Array(dq[1..].to_vec().into());
                 Ok(Value::Tuple(vec![
                     Value::Option(Some(Box::new(front))),
                     rest,
@@ -99,7 +99,7 @@ pub(crate) fn builtin_deque_pop_back(args: &[Value]) -> RResult<Value> {
             if dq.is_empty() {
                 Ok(Value::Tuple(vec![
                     Value::Option(None),
-                    Value::Array(Vec::new()),
+                    Value::Array(Vec::new().into()),
                 ]))
             } else {
                 let mut rest = dq.clone();
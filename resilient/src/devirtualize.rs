@@ -116,7 +116,7 @@ impl DevirtCtx {
     pub fn resolve_method(&self, target: &Node, method: &str) -> Option<String> {
         let struct_name = match target {
             Node::StructLiteral { name, .. } => name.clone(),
-            Node::Identifier { name, .. } => self.local_struct_types.get(name)?.clone(),
+            Node::Identifier { name, .. } => self.local_struct_types.get(name.as_str())?.clone(),
             _ => return None,
         };
         Some(format!("{}${}", struct_name, method))
@@ -170,15 +170,19 @@ fn rewrite_node(node: &Node, ctx: &mut DevirtCtx) -> Node {
                 name: name.clone(),
                 parameters: parameters.clone(),
                 defaults: defaults.clone(),
-                body: Box::new(rewrite_node(body, &mut fn_ctx)),
-                requires: requires
-                    .iter()
-                    .map(|r| rewrite_node(r, &mut fn_ctx))
-                    .collect(),
-                ensures: ensures
-                    .iter()
-                    .map(|e| rewrite_node(e, &mut fn_ctx))
-                    .collect(),
+                body: std::sync::Arc::new(rewrite_node(body, &mut fn_ctx)),
+                requires: std::sync::Arc::new(
+                    requires
+                        .iter()
+                        .map(|r| rewrite_node(r, &mut fn_ctx))
+                        .collect(),
+                ),
+                ensures: std::sync::Arc::new(
+                    ensures
+                        .iter()
+                        .map(|e| rewrite_node(e, &mut fn_ctx))
+                        .collect(),
+                ),
                 return_type: return_type.clone(),
                 span: *span,
                 pure: *pure,
@@ -241,7 +245,7 @@ fn rewrite_node(node: &Node, ctx: &mut DevirtCtx) -> Node {
                 };
                 return Node::CallExpression {
                     function: Box::new(Node::Identifier {
-                        name: mangled,
+                        name: mangled.into(),
                         span: id_span,
                     }),
                     arguments: new_args,
@@ -415,7 +419,7 @@ mod tests {
         let mut ctx = DevirtCtx::new();
         ctx.record("p", "Point");
         let target = Node::Identifier {
-            name: "p".to_string(),
+            name: "p".into(),
             span: Default::default(),
         };
         let result = ctx.resolve_method(&target, "to_string");
@@ -426,7 +430,7 @@ mod tests {
     fn unknown_identifier_receiver_returns_none() {
         let ctx = DevirtCtx::new();
         let target = Node::Identifier {
-            name: "x".to_string(),
+            name: "x".into(),
             span: Default::default(),
         };
         let result = ctx.resolve_method(&target, "to_string");
@@ -616,7 +620,7 @@ main();
             ctx.record("c", name);
         }
         let target = Node::Identifier {
-            name: "c".to_string(),
+            name: "c".into(),
             span: Default::default(),
         };
         assert_eq!(
@@ -677,7 +681,7 @@ main();
         ctx.record("c", "Circle");
         ctx.local_struct_types.remove("c");
         let target = Node::Identifier {
-            name: "c".to_string(),
+            name: "c".into(),
             span: Default::default(),
         };
         assert_eq!(ctx.resolve_method(&target, "area"), None);
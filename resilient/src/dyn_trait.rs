@@ -557,10 +557,10 @@ fn resolve_alias_chain(
         Node::LetStatement { name, value, .. } if name == var => {
             bindings.push(match value.as_ref() {
                 Node::StructLiteral { name: sname, .. } => Some(AliasTarget::Struct(sname.clone())),
-                Node::Identifier { name: id, .. } => Some(AliasTarget::Ident(id.clone())),
+                Node::Identifier { name: id, .. } => Some(AliasTarget::Ident(id.to_string())),
                 Node::CallExpression { function, .. } => match function.as_ref() {
                     Node::Identifier { name: callee, .. } => {
-                        Some(AliasTarget::Call(callee.clone()))
+                        Some(AliasTarget::Call(callee.to_string()))
                     }
                     _ => None,
                 },
@@ -822,7 +822,7 @@ fn check_method_calls(
         if let Node::CallExpression { function, span, .. } = n
             && let Node::FieldAccess { target, field, .. } = function.as_ref()
             && let Node::Identifier { name: var, .. } = target.as_ref()
-            && let Some(trait_name) = dyn_vars.get(var)
+            && let Some(trait_name) = dyn_vars.get(var.as_str())
         {
             let known = trait_methods
                 .get(trait_name)
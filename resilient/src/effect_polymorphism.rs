@@ -487,8 +487,8 @@ fn find_hof_call_violations(
                         // alias is unambiguous and never reassigned;
                         // see its doc comment) before falling back to
                         // treating it as unresolvable.
-                        let resolved_name = if fn_effects.contains_key(callback_name) {
-                            Some(callback_name.clone())
+                        let resolved_name = if fn_effects.contains_key(callback_name.as_str()) {
+                            Some(callback_name.to_string())
                         } else {
                             resolve_local_alias(callback_name, caller_body)
                         };
@@ -685,8 +685,8 @@ pub(crate) fn resolves_pure_at_call_site(
                 // variable argument back to its initializer (see
                 // [`resolve_local_alias`]) before giving up on it as
                 // unresolvable.
-                let resolved_name = if fn_effects.contains_key(arg_name) {
-                    Some(arg_name.clone())
+                let resolved_name = if fn_effects.contains_key(arg_name.as_str()) {
+                    Some(arg_name.to_string())
                 } else {
                     resolve_local_alias(arg_name, caller_body)
                 };
@@ -767,7 +767,7 @@ fn collect_local_bindings(
         Node::LetStatement { name, value, .. } => {
             if name == var {
                 bindings.push(match value.as_ref() {
-                    Node::Identifier { name: id, .. } => Some(id.clone()),
+                    Node::Identifier { name: id, .. } => Some(id.to_string()),
                     _ => None,
                 });
             }
@@ -1016,8 +1016,8 @@ fn resolve_arg_effect(
 ) -> Option<EffectSet> {
     match arg {
         Node::Identifier { name, .. } => {
-            let resolved_name = if fn_effects.contains_key(name) {
-                Some(name.clone())
+            let resolved_name = if fn_effects.contains_key(name.as_str()) {
+                Some(name.to_string())
             } else {
                 resolve_local_alias(name, caller_body)
             };
@@ -1292,7 +1292,7 @@ fn body_pure_modulo(
                 if deferred.contains(callee.as_str()) {
                     return true;
                 }
-                if let Some(callee_effects) = fn_effects.get(callee) {
+                if let Some(callee_effects) = fn_effects.get(callee.as_str()) {
                     return callee_effects.pure;
                 }
                 if crate::typechecker::IMPURE_BUILTINS.contains(&callee.as_str()) {
@@ -49,7 +49,7 @@ fn result_context(ok: bool, payload: &Value, args: &[Value]) -> RResult<Value> {
     };
     Ok(Value::Result {
         ok: false,
-        payload: Box::new(Value::Array(chain)),
+        payload: Box::new(Value::Array(chain.into())),
     })
 }
 
@@ -69,7 +69,7 @@ fn result_chain(ok: bool, payload: &Value) -> RResult<Value> {
     }
     match payload {
         Value::Array(_) => Ok(payload.clone()),
-        _ => Ok(Value::Array(vec![payload.clone()])),
+        _ => Ok(Value::Array(vec![payload.clone()].into())),
     }
 }
 
@@ -169,11 +169,14 @@ mod tests {
 
     #[test]
     fn root_cause_returns_innermost() {
-        let chain = Value::Array(vec![
-            Value::String("loading config".into()),
-            Value::String("reading file".into()),
-            Value::String("parse error".into()),
-        ]);
+        let chain = Value::Array(
+            vec![
+                Value::String("loading config".into()),
+                Value::String("reading file".into()),
+                Value::String("parse error".into()),
+            ]
+            .into(),
+        );
         let r = result_root_cause(false, &chain).unwrap();
         match r {
             Value::String(s) => assert_eq!(s, "parse error"),
@@ -183,11 +186,14 @@ mod tests {
 
     #[test]
     fn chain_returns_all_segments() {
-        let chain = Value::Array(vec![
-            Value::String("loading config".into()),
-            Value::String("reading file".into()),
-            Value::String("parse error".into()),
-        ]);
+        let chain = Value::Array(
+            vec![
+                Value::String("loading config".into()),
+                Value::String("reading file".into()),
+                Value::String("parse error".into()),
+            ]
+            .into(),
+        );
         let r = result_chain(false, &chain).unwrap();
         match r {
             Value::Array(items) => {
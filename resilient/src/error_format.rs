@@ -0,0 +1,528 @@
+//! `--error-format=json|sarif` — structured diagnostics for tools.
+//!
+//! The driver normally prints each diagnostic as text with a caret
+//! block. With `--error-format=json` every diagnostic from the
+//! parser, the static checks, the typechecker, the verifier and the
+//! runtime is instead written to stderr as one JSON object per line,
+//! as it happens:
+//!
+//! ```text
+//! {"phase":"runtime","severity":"error","code":null,"message":"...","file":"f.rz",
+//!  "span":{"line":5,"column":5,"end_line":5,"end_column":18},"notes":[],"fixes":[...]}
+//! ```
+//!
+//! `--error-format=sarif` collects the same records and prints one
+//! SARIF 2.1.0 log on stderr when the run ends (`finish`), for code
+//! scanning dashboards. The default, `human`, keeps the text output.
+//!
+//! Records are built from the error strings the pipeline already
+//! produces: the `[file:]line:col:` prefix gives the position, the
+//! token there gives the span's end, an `error[E0007]` / `[E0021]`
+//! tag gives the code, extra lines become notes, and a
+//! ` — did you mean `x`?` hint becomes one fix per suggestion.
+
+use std::cell::{Cell, RefCell};
+
+use crate::typechecker::CheckDiagnostic;
+use crate::{Lexer, Token};
+
+/// How diagnostics are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorFormat {
+    Human,
+    Json,
+    Sarif,
+}
+
+impl ErrorFormat {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s {
+            "human" => Some(ErrorFormat::Human),
+            "json" => Some(ErrorFormat::Json),
+            "sarif" => Some(ErrorFormat::Sarif),
+            _ => None,
+        }
+    }
+}
+
+/// The pipeline stage a diagnostic comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Phase {
+    Parse,
+    Borrow,
+    Capability,
+    Termination,
+    Typecheck,
+    Verify,
+    Runtime,
+    /// Anything else that stopped the run (imports, I/O, flags).
+    Driver,
+}
+
+impl Phase {
+    fn as_str(self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Borrow => "borrow",
+            Phase::Capability => "capability",
+            Phase::Termination => "termination",
+            Phase::Typecheck => "typecheck",
+            Phase::Verify => "verify",
+            Phase::Runtime => "runtime",
+            Phase::Driver => "driver",
+        }
+    }
+}
+
+/// A source range; lines and columns are 1-based, `end_column` is
+/// exclusive. Equal start and end mark a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Region {
+    line: usize,
+    column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Fix {
+    message: String,
+    replacement: String,
+    region: Option<Region>,
+}
+
+/// One structured diagnostic.
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    phase: Phase,
+    severity: String,
+    code: Option<String>,
+    message: String,
+    file: String,
+    region: Option<Region>,
+    notes: Vec<String>,
+    fixes: Vec<Fix>,
+}
+
+thread_local! {
+    static FORMAT: Cell<ErrorFormat> = const { Cell::new(ErrorFormat::Human) };
+    /// Records held for the SARIF log.
+    static PENDING: RefCell<Vec<Record>> = const { RefCell::new(Vec::new()) };
+    /// Whether an error was reported since the last `finish`.
+    static REPORTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Apply `--error-format[=]VALUE` at `args[*i]`, advancing `*i` past
+/// a separate value. `None` when `args[*i]` is another flag;
+/// `Some(Err(..))` for a missing or unknown format.
+pub(crate) fn apply_cli_flag(args: &[String], i: &mut usize) -> Option<Result<(), String>> {
+    let arg = args[*i].as_str();
+    let value = match arg.strip_prefix("--error-format") {
+        Some("") => {
+            *i += 1;
+            match args.get(*i) {
+                Some(v) => v.as_str(),
+                None => {
+                    return Some(Err(
+                        "--error-format requires a format: human, json, or sarif".to_string(),
+                    ));
+                }
+            }
+        }
+        Some(rest) => rest.strip_prefix('=')?,
+        None => return None,
+    };
+    match ErrorFormat::parse(value) {
+        Some(format) => {
+            set_format(format);
+            Some(Ok(()))
+        }
+        None => Some(Err(format!(
+            "unknown --error-format `{}` (expected human, json, or sarif)",
+            value
+        ))),
+    }
+}
+
+pub(crate) fn set_format(format: ErrorFormat) {
+    FORMAT.with(|f| f.set(format));
+}
+
+/// True for `json` and `sarif`: callers report instead of printing.
+pub(crate) fn is_structured() -> bool {
+    FORMAT.with(|f| f.get()) != ErrorFormat::Human
+}
+
+/// Report an error string of the form `[file:]line:col: message`
+/// (the position is optional) found in `src`, which belongs to `file`.
+pub(crate) fn report_error(phase: Phase, file: &str, src: &str, err: &str) {
+    let (line, column, message) = crate::parse_error_location(err);
+    let position = (line > 0).then_some((line as usize, column.max(1) as usize));
+    emit(record(phase, "error", file, src, position, &message));
+}
+
+/// Report `message` at `line:column` of `src` (a 0 line means no
+/// position).
+pub(crate) fn report_at(
+    phase: Phase,
+    file: &str,
+    src: &str,
+    line: usize,
+    column: usize,
+    message: &str,
+) {
+    let position = (line > 0).then_some((line, column.max(1)));
+    emit(record(phase, "error", file, src, position, message));
+}
+
+/// Report diagnostics captured with `typechecker::collect_check_diagnostics`.
+/// `src` is the source of `file`; one placed in another file (a `use`d
+/// module, see `project::attribute_warnings`) is read from there.
+pub(crate) fn report_check_diagnostics(diagnostics: &[CheckDiagnostic], file: &str, src: &str) {
+    for d in diagnostics {
+        let position = (d.line > 0).then_some((d.line, d.column.max(1)));
+        let other = (d.file != file)
+            .then(|| std::fs::read_to_string(&d.file).ok())
+            .flatten();
+        let src = other.as_deref().unwrap_or(src);
+        let mut rec = record(
+            Phase::Typecheck,
+            &d.severity,
+            &d.file,
+            src,
+            position,
+            &d.message,
+        );
+        if rec.code.is_none() && !d.code.is_empty() {
+            rec.code = Some(d.code.clone());
+        }
+        emit(rec);
+    }
+}
+
+/// Report the error that ended a run, unless a diagnostic already
+/// explains it (`execute_file` returns a summary after reporting).
+pub(crate) fn report_failure(file: &str, err: &str) {
+    if !REPORTED.with(|r| r.get()) {
+        report_error(Phase::Driver, file, "", err);
+    }
+}
+
+/// End of a run: print the SARIF log, if that is the format.
+pub(crate) fn finish() {
+    REPORTED.with(|r| r.set(false));
+    if FORMAT.with(|f| f.get()) == ErrorFormat::Sarif {
+        let records = PENDING.with(|p| std::mem::take(&mut *p.borrow_mut()));
+        eprintln!(
+            "{}",
+            serde_json::to_string_pretty(&sarif_log(&records)).unwrap_or_default()
+        );
+    }
+}
+
+fn emit(rec: Record) {
+    if rec.severity == "error" {
+        REPORTED.with(|r| r.set(true));
+    }
+    match FORMAT.with(|f| f.get()) {
+        ErrorFormat::Json => eprintln!("{}", json_record(&rec)),
+        ErrorFormat::Sarif => PENDING.with(|p| p.borrow_mut().push(rec)),
+        ErrorFormat::Human => {}
+    }
+}
+
+fn record(
+    phase: Phase,
+    severity: &str,
+    file: &str,
+    src: &str,
+    position: Option<(usize, usize)>,
+    text: &str,
+) -> Record {
+    let mut lines = text.lines().map(str::trim_end);
+    let first = lines.next().unwrap_or_default().trim();
+    let notes = lines
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect();
+    let (code, message) = split_code(first);
+    let tokens = tokens(src);
+    let region = position.map(|(line, column)| {
+        tokens
+            .iter()
+            .find(|(_, r)| r.line == line && r.column == column)
+            .map(|(_, r)| *r)
+            .unwrap_or(Region {
+                line,
+                column,
+                end_line: line,
+                end_column: column,
+            })
+    });
+    let fixes = fixes(&message, position, &tokens);
+    Record {
+        phase,
+        severity: severity.to_string(),
+        code,
+        message,
+        file: file.to_string(),
+        region,
+        notes,
+        fixes,
+    }
+}
+
+/// Split a leading `error[CODE]:` / `warning[CODE]:` or `[CODE]` tag
+/// off `message`.
+fn split_code(message: &str) -> (Option<String>, String) {
+    let tagged = ["error[", "warning[", "["]
+        .iter()
+        .find_map(|p| message.strip_prefix(p));
+    if let Some(rest) = tagged
+        && let Some((code, tail)) = rest.split_once(']')
+        && !code.is_empty()
+        && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        let tail = tail.strip_prefix(':').unwrap_or(tail).trim();
+        return (Some(code.to_string()), tail.to_string());
+    }
+    (None, message.to_string())
+}
+
+/// Every token of `src` with its region; identifiers carry their name.
+fn tokens(src: &str) -> Vec<(Option<String>, Region)> {
+    let mut lexer = Lexer::new(src);
+    let mut out = Vec::new();
+    loop {
+        let (token, span) = lexer.next_token_with_span();
+        let name = match token {
+            Token::Eof => break,
+            Token::Identifier(name) => Some(name.to_string()),
+            _ => None,
+        };
+        let region = Region {
+            line: span.start.line,
+            column: span.start.column,
+            end_line: span.end.line,
+            end_column: span.end.column,
+        };
+        out.push((name, region));
+    }
+    out
+}
+
+/// One fix per suggestion in a ` — did you mean `a`, `b`?` hint. The
+/// fix replaces the misspelled name: the identifier on the reported
+/// line that the message mentions, at or after the reported column.
+fn fixes(
+    message: &str,
+    position: Option<(usize, usize)>,
+    tokens: &[(Option<String>, Region)],
+) -> Vec<Fix> {
+    let Some((head, hint)) = message.split_once(" — did you mean ") else {
+        return Vec::new();
+    };
+    let mentioned: Vec<&str> = head
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let region = position.and_then(|(line, column)| {
+        let mut candidates: Vec<&Region> = tokens
+            .iter()
+            .filter(|(name, r)| {
+                r.line == line && name.as_deref().is_some_and(|n| mentioned.contains(&n))
+            })
+            .map(|(_, r)| r)
+            .collect();
+        candidates.sort_by_key(|r| r.column < column);
+        candidates.first().copied().copied()
+    });
+    hint.trim_end_matches('?')
+        .split(", ")
+        .filter_map(|s| s.strip_prefix('`')?.strip_suffix('`'))
+        .map(|name| Fix {
+            message: format!("replace with `{name}`"),
+            replacement: name.to_string(),
+            region,
+        })
+        .collect()
+}
+
+fn json_region(r: &Region) -> serde_json::Value {
+    serde_json::json!({
+        "line": r.line,
+        "column": r.column,
+        "end_line": r.end_line,
+        "end_column": r.end_column,
+    })
+}
+
+fn json_record(rec: &Record) -> serde_json::Value {
+    let fixes: Vec<serde_json::Value> = rec
+        .fixes
+        .iter()
+        .map(|f| {
+            serde_json::json!({
+                "message": f.message,
+                "replacement": f.replacement,
+                "span": f.region.as_ref().map(json_region),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "phase": rec.phase.as_str(),
+        "severity": rec.severity,
+        "code": rec.code,
+        "message": rec.message,
+        "file": rec.file,
+        "span": rec.region.as_ref().map(json_region),
+        "notes": rec.notes,
+        "fixes": fixes,
+    })
+}
+
+fn sarif_region(r: &Region) -> serde_json::Value {
+    serde_json::json!({
+        "startLine": r.line,
+        "startColumn": r.column,
+        "endLine": r.end_line,
+        "endColumn": r.end_column,
+    })
+}
+
+fn sarif_result(rec: &Record) -> serde_json::Value {
+    let level = match rec.severity.as_str() {
+        "error" => "error",
+        "warning" => "warning",
+        _ => "note",
+    };
+    let mut location = serde_json::json!({
+        "physicalLocation": { "artifactLocation": { "uri": rec.file } }
+    });
+    if let Some(r) = &rec.region {
+        location["physicalLocation"]["region"] = sarif_region(r);
+    }
+    let mut text = rec.message.clone();
+    for note in &rec.notes {
+        text.push('\n');
+        text.push_str(note);
+    }
+    let fixes: Vec<serde_json::Value> = rec
+        .fixes
+        .iter()
+        .filter_map(|f| {
+            let r = f.region.as_ref()?;
+            Some(serde_json::json!({
+                "description": { "text": f.message },
+                "artifactChanges": [{
+                    "artifactLocation": { "uri": rec.file },
+                    "replacements": [{
+                        "deletedRegion": sarif_region(r),
+                        "insertedContent": { "text": f.replacement },
+                    }],
+                }],
+            }))
+        })
+        .collect();
+    serde_json::json!({
+        "ruleId": rec.code.as_deref().unwrap_or(rec.phase.as_str()),
+        "level": level,
+        "message": { "text": text },
+        "locations": [location],
+        "fixes": fixes,
+        "properties": { "phase": rec.phase.as_str() },
+    })
+}
+
+fn sarif_log(records: &[Record]) -> serde_json::Value {
+    let results: Vec<serde_json::Value> = records.iter().map(sarif_result).collect();
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "resilient",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_takes_position_code_notes_and_fixes() {
+        let src = "let total = 1;\nprintln(totl);\n";
+        let rec = record(
+            Phase::Runtime,
+            "error",
+            "f.rz",
+            src,
+            Some((2, 1)),
+            "Identifier not found: totl — did you mean `total`, `tot`?\nnote: from here",
+        );
+        assert_eq!(rec.code, None);
+        assert_eq!(
+            rec.region,
+            Some(Region {
+                line: 2,
+                column: 1,
+                end_line: 2,
+                end_column: 8,
+            })
+        );
+        assert_eq!(rec.notes, ["note: from here"]);
+        let replacements: Vec<&str> = rec.fixes.iter().map(|f| f.replacement.as_str()).collect();
+        assert_eq!(replacements, ["total", "tot"]);
+        assert_eq!(
+            rec.fixes[0].region,
+            Some(Region {
+                line: 2,
+                column: 9,
+                end_line: 2,
+                end_column: 13,
+            })
+        );
+    }
+
+    #[test]
+    fn split_code_reads_rustc_style_and_bare_tags() {
+        assert_eq!(
+            split_code("error[E0009]: index out of bounds"),
+            (Some("E0009".to_string()), "index out of bounds".to_string())
+        );
+        assert_eq!(
+            split_code("[E0021] `dyn T` is not object-safe"),
+            (
+                Some("E0021".to_string()),
+                "`dyn T` is not object-safe".to_string()
+            )
+        );
+        assert_eq!(split_code("[1, 2] is odd").0, None);
+    }
+
+    #[test]
+    fn sarif_result_carries_fix_replacements() {
+        let rec = record(
+            Phase::Typecheck,
+            "warning",
+            "f.rz",
+            "let x = lenn(a);\n",
+            Some((1, 9)),
+            "unknown `lenn` — did you mean `len`?",
+        );
+        let result = sarif_result(&rec);
+        assert_eq!(result["level"], "warning");
+        assert_eq!(result["ruleId"], "typecheck");
+        let replacement = &result["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert_eq!(replacement["insertedContent"]["text"], "len");
+        assert_eq!(replacement["deletedRegion"]["startColumn"], 9);
+        assert_eq!(replacement["deletedRegion"]["endColumn"], 13);
+    }
+}
@@ -0,0 +1,202 @@
+//! Callbacks from the tree-walking interpreter to the tools watching a
+//! run.
+//!
+//! An [`EvalObserver`] hears about:
+//!
+//! - each statement node, before it runs ([`EvalObserver::node_enter`])
+//!   and after, with its result ([`EvalObserver::node_exit`]);
+//! - each variable a `let` binds or an assignment writes;
+//! - each call of a user function and its return or failure;
+//! - which way each `if` went, and each failed attempt of a `live`
+//!   block.
+//!
+//! `rz debug` and `rz run --step` (`debug_console`), `rz test
+//! --coverage` (`test_coverage`), `--trace` (`call_trace`),
+//! `--profile` ([`crate::profiler`]) and the statement counter of
+//! `--metrics-port` ([`crate::metrics`]) are observers, so none of
+//! them has its own code in the evaluator: `Interpreter::new`
+//! registers the ones switched on for the thread. A host registers its
+//! own with [`Session::observe`](crate::interpreter::Session::observe):
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//!
+//! use resilient::bridge::ResilientValue;
+//! use resilient::eval_observer::EvalObserver;
+//! use resilient::interpreter::Session;
+//!
+//! struct Calls(Rc<RefCell<Vec<String>>>);
+//!
+//! impl EvalObserver for Calls {
+//!     fn call_enter(&mut self, name: &str, _: &[(String, String)], args: &[ResilientValue], _: usize) {
+//!         self.0.borrow_mut().push(format!("{name}({})", args[0]));
+//!     }
+//! }
+//!
+//! let mut session = Session::load(
+//!     "fn sq(int x) -> int { return x * x; }
+//!      fn f(int x) -> int { return sq(x) + 1; }",
+//! )
+//! .unwrap();
+//! let calls = Rc::new(RefCell::new(Vec::new()));
+//! session.observe(Box::new(Calls(calls.clone())));
+//! let y: i64 = session.call("f", (3,)).unwrap();
+//! assert_eq!(y, 10);
+//! assert_eq!(*calls.borrow(), ["f(3)", "sq(3)"]);
+//! ```
+//!
+//! The sub-interpreters that run function bodies and contracts share
+//! their parent's list, so one registration sees the whole run. With
+//! nothing registered each hook is a check of an empty list.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::bridge::ResilientValue;
+use crate::{Environment, Node, RResult, Value};
+
+/// What the interpreter reports as it runs. Every method does nothing
+/// by default.
+pub trait EvalObserver {
+    /// The statement `node` is about to run in `scope`, inside the
+    /// user function `function` (`""` at the top level).
+    fn node_enter(&mut self, _node: &Node, _scope: &Scope<'_>, _function: &str) {}
+
+    /// The statement `node` ran and produced `result`.
+    fn node_exit(&mut self, _node: &Node, _result: Result<&ResilientValue, &str>) {}
+
+    /// `let` bound `name`, or an assignment wrote it, to `value`.
+    fn variable_write(&mut self, _name: &str, _value: &ResilientValue) {}
+
+    /// The user function `name`, declared with `parameters` (`(type,
+    /// name)` pairs), is called from `line` with `args`.
+    fn call_enter(
+        &mut self,
+        _name: &str,
+        _parameters: &[(String, String)],
+        _args: &[ResilientValue],
+        _line: usize,
+    ) {
+    }
+
+    /// The call [`EvalObserver::call_enter`] reported last ended.
+    fn call_exit(&mut self, _name: &str, _result: Result<&ResilientValue, &str>) {}
+
+    /// The `if` at `line`:`column` in `function` took its `then` branch
+    /// when `held`, its `else` (or nothing) otherwise.
+    fn branch(&mut self, _line: usize, _column: usize, _function: &str, _held: bool) {}
+
+    /// The `live` block starting on `line` failed attempt `attempt` of
+    /// `max` with `error` and is about to retry or give up.
+    fn live_retry(
+        &mut self,
+        _line: usize,
+        _attempt: usize,
+        _max: usize,
+        _error: &str,
+        _scope: &Scope<'_>,
+    ) {
+    }
+}
+
+/// The variables visible where the interpreter is.
+pub struct Scope<'a>(pub(crate) &'a Environment);
+
+impl Scope<'_> {
+    /// The value `name` is bound to here or in an enclosing scope.
+    pub fn get(&self, name: &str) -> Option<ResilientValue> {
+        self.0.get(name).map(ResilientValue)
+    }
+}
+
+/// `result` as the observers see it.
+#[inline]
+pub(crate) fn outcome(result: &RResult<Value>) -> Result<&ResilientValue, &str> {
+    match result {
+        Ok(value) => Ok(ResilientValue::from_ref(value)),
+        Err(e) => Err(e),
+    }
+}
+
+/// The observers of one run, shared by its interpreters.
+#[derive(Clone, Default)]
+pub(crate) struct Observers(Rc<RefCell<Vec<Box<dyn EvalObserver>>>>);
+
+impl Observers {
+    pub(crate) fn push(&self, observer: Box<dyn EvalObserver>) {
+        self.0.borrow_mut().push(observer);
+    }
+
+    /// Call `f` on each observer in the order they were registered. A
+    /// callback that makes the interpreter report again is not
+    /// re-entered.
+    #[inline]
+    pub(crate) fn notify(&self, mut f: impl FnMut(&mut dyn EvalObserver)) {
+        if let Ok(mut observers) = self.0.try_borrow_mut() {
+            for observer in observers.iter_mut() {
+                f(observer.as_mut());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Log(Rc<RefCell<Vec<String>>>);
+
+    impl EvalObserver for Log {
+        fn node_enter(&mut self, node: &Node, _scope: &Scope<'_>, function: &str) {
+            if let Node::LetStatement { name, .. } = node {
+                self.0
+                    .borrow_mut()
+                    .push(format!("let {name} in `{function}`"));
+            }
+        }
+        fn variable_write(&mut self, name: &str, value: &ResilientValue) {
+            self.0.borrow_mut().push(format!("{name} = {value}"));
+        }
+        fn call_enter(
+            &mut self,
+            name: &str,
+            _: &[(String, String)],
+            args: &[ResilientValue],
+            _: usize,
+        ) {
+            self.0
+                .borrow_mut()
+                .push(format!("call {name}({})", args[0]));
+        }
+        fn call_exit(&mut self, name: &str, result: Result<&ResilientValue, &str>) {
+            let value = result.map(ResilientValue::to_string).unwrap_or_default();
+            self.0.borrow_mut().push(format!("return {name} = {value}"));
+        }
+    }
+
+    #[test]
+    fn reports_statements_writes_and_calls_in_order() {
+        let (program, errors) = crate::parse_silent(
+            "fn double(int n) -> int {\n    let m = n * 2;\n    return m;\n}\nlet x = double(4);\n",
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+        let log = Log::default();
+        let seen = log.0.clone();
+        let mut interp = crate::Interpreter::new();
+        interp.observe(Box::new(log));
+        interp.eval(&program).unwrap();
+        assert_eq!(
+            *seen.borrow(),
+            [
+                "let x in ``",
+                "call double(4)",
+                "let m in `double`",
+                "m = 8",
+                "return double = 8",
+                "x = 8",
+            ]
+        );
+    }
+}
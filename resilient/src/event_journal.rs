@@ -132,7 +132,7 @@ pub(crate) fn builtin_replay_events(args: &[Value]) -> RResult<Value> {
             .map(|e| Value::String(format!("{} [{}] {}={}", e.id, e.tick, e.name, e.payload)))
             .collect()
     });
-    Ok(Value::Array(lines))
+    Ok(Value::Array(lines.into()))
 }
 
 pub(crate) fn builtin_clear_events(args: &[Value]) -> RResult<Value> {
@@ -15,6 +15,60 @@
 
 use crate::ExternDecl;
 use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Library descriptor of `extern "C"` declarations: the symbol is
+/// looked up in the `--ffi-lib` libraries, in order, then in the
+/// running process.
+pub const C_ABI: &str = "C";
+
+/// Whether programs may call native code (`--ffi`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiPolicy {
+    Allow,
+    None,
+}
+
+impl FfiPolicy {
+    /// Parse an `--ffi` argument.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "allow" => Some(FfiPolicy::Allow),
+            "none" => Some(FfiPolicy::None),
+            _ => None,
+        }
+    }
+}
+
+static FFI_DENIED: AtomicBool = AtomicBool::new(false);
+
+/// Select the process-wide FFI policy. Called from the CLI dispatcher.
+pub fn set_policy(policy: FfiPolicy) {
+    FFI_DENIED.store(policy == FfiPolicy::None, Ordering::Relaxed);
+}
+
+fn denied() -> bool {
+    FFI_DENIED.load(Ordering::Relaxed)
+}
+
+static STARTUP_LIBRARIES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Add a library (`--ffi-lib PATH`) for `extern "C"` declarations to
+/// bind against. Libraries are searched in the order they were added.
+pub fn add_startup_library(path: String) {
+    STARTUP_LIBRARIES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(path);
+}
+
+fn startup_libraries() -> Vec<String> {
+    STARTUP_LIBRARIES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FfiType {
@@ -67,6 +121,12 @@ impl FfiType {
             "Void" => Some(FfiType::Void),
             "OpaquePtr" => Some(FfiType::OpaquePtr),
             "Callback" => Some(FfiType::Callback),
+            // The spellings the rest of the language uses.
+            "int" => Some(FfiType::Int),
+            "float" => Some(FfiType::Float),
+            "bool" => Some(FfiType::Bool),
+            "string" => Some(FfiType::Str),
+            "void" => Some(FfiType::Void),
             _ => None,
         }
     }
@@ -276,6 +336,16 @@ pub enum FfiError {
         size: usize,
         max: usize,
     },
+    /// `--ffi=none` (or `[sandbox] ffi = "none"`) forbids native calls.
+    Sandboxed,
+    /// An `--allow` policy without `ffi` forbids native calls.
+    NotAllowed,
+    /// An `extern "C"` symbol is in none of the `--ffi-lib` libraries
+    /// nor the running process.
+    CSymbolNotFound {
+        symbol: String,
+        libraries: Vec<String>,
+    },
 }
 
 impl std::fmt::Display for FfiError {
@@ -331,6 +401,37 @@ impl std::fmt::Display for FfiError {
                     name, size, max
                 )
             }
+            FfiError::Sandboxed => {
+                write!(
+                    f,
+                    "FFI: native calls are disabled by the sandbox (--ffi=none)"
+                )
+            }
+            FfiError::NotAllowed => {
+                write!(
+                    f,
+                    "FFI: PermissionDenied: the sandbox does not allow `ffi` (see --allow)"
+                )
+            }
+            FfiError::CSymbolNotFound { symbol, libraries } if libraries.is_empty() => {
+                write!(
+                    f,
+                    "FFI: symbol `{}` not found in the running process; load the library that defines it with --ffi-lib",
+                    symbol
+                )
+            }
+            FfiError::CSymbolNotFound { symbol, libraries } => {
+                write!(
+                    f,
+                    "FFI: symbol `{}` not found in {} or the running process",
+                    symbol,
+                    libraries
+                        .iter()
+                        .map(|l| format!("`{}`", l))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
         }
     }
 }
@@ -405,50 +506,112 @@ mod dynamic {
             library: &str,
             decls: &[ExternDecl],
         ) -> Result<(), FfiError> {
+            if denied() {
+                return Err(FfiError::Sandboxed);
+            }
+            if !crate::sandbox::allows(crate::sandbox::Capability::Ffi) {
+                return Err(FfiError::NotAllowed);
+            }
             if library == "@static" {
                 return Err(FfiError::StaticOnlyUnavailable {
                     library: library.to_string(),
                 });
             }
-            let lib = match self.libs.entry(library.to_string()) {
-                std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
-                std::collections::hash_map::Entry::Vacant(e) => {
-                    // SAFETY: Loading a dynamic library by path. The library must
-                    // remain loaded for the lifetime of any symbols we extract from
-                    // it; we enforce this by keeping the Library in `self.libs` for
-                    // the lifetime of the ForeignLoader.
-                    let lib = unsafe { libloading::Library::new(library) }.map_err(|err| {
-                        FfiError::LibNotFound {
+            if library == C_ABI {
+                return self.resolve_c_block(decls);
+            }
+            self.open(library)?;
+            for d in decls {
+                let sig = ForeignSignature::from_decl_with_structs(d, &self.structs)?;
+                let ptr =
+                    self.symbol(library, &d.c_name)
+                        .ok_or_else(|| FfiError::SymbolNotFound {
                             library: library.to_string(),
-                            underlying: err.to_string(),
-                        }
-                    })?;
-                    e.insert(lib)
-                }
-            };
+                            symbol: d.c_name.clone(),
+                        })?;
+                self.bind(d, ptr, sig);
+            }
+            Ok(())
+        }
+
+        /// `extern "C"`: bind each symbol from the first `--ffi-lib`
+        /// library that defines it, else from the running process.
+        fn resolve_c_block(&mut self, decls: &[ExternDecl]) -> Result<(), FfiError> {
+            let libraries = startup_libraries();
+            for library in &libraries {
+                self.open(library)?;
+            }
             for d in decls {
                 let sig = ForeignSignature::from_decl_with_structs(d, &self.structs)?;
-                // SAFETY: We look up the symbol by its C name as a byte string.
-                // The returned Symbol borrows from `lib`; we immediately copy
-                // the raw pointer out so the Symbol borrow is released before
-                // we return. The `lib` itself stays alive in `self.libs` so the
-                // pointed-to code is never unmapped while the ForeignLoader lives.
-                let raw: libloading::Symbol<*const ()> = unsafe { lib.get(d.c_name.as_bytes()) }
-                    .map_err(|_| FfiError::SymbolNotFound {
-                        library: library.to_string(),
+                let ptr = libraries
+                    .iter()
+                    .find_map(|library| self.symbol(library, &d.c_name))
+                    .or_else(|| self.process_symbol(&d.c_name))
+                    .ok_or_else(|| FfiError::CSymbolNotFound {
                         symbol: d.c_name.clone(),
+                        libraries: libraries.clone(),
                     })?;
-                let sym = ForeignSymbol {
-                    name: d.resilient_name.clone(),
-                    ptr: *raw,
-                    sig,
-                };
-                self.syms
-                    .insert(d.resilient_name.clone(), std::sync::Arc::new(sym));
+                self.bind(d, ptr, sig);
+            }
+            Ok(())
+        }
+
+        fn open(&mut self, library: &str) -> Result<(), FfiError> {
+            if self.libs.contains_key(library) {
+                return Ok(());
             }
+            // SAFETY: Loading a dynamic library by path. The library must
+            // remain loaded for the lifetime of any symbols we extract from
+            // it; we enforce this by keeping the Library in `self.libs` for
+            // the lifetime of the ForeignLoader.
+            let lib = unsafe { libloading::Library::new(library) }.map_err(|err| {
+                FfiError::LibNotFound {
+                    library: library.to_string(),
+                    underlying: err.to_string(),
+                }
+            })?;
+            self.libs.insert(library.to_string(), lib);
             Ok(())
         }
 
+        fn symbol(&self, library: &str, c_name: &str) -> Option<*const ()> {
+            let lib = self.libs.get(library)?;
+            // SAFETY: We look up the symbol by its C name as a byte string.
+            // The returned Symbol borrows from `lib`; we immediately copy
+            // the raw pointer out so the Symbol borrow is released before
+            // we return. The `lib` itself stays alive in `self.libs` so the
+            // pointed-to code is never unmapped while the ForeignLoader lives.
+            let raw: libloading::Symbol<*const ()> = unsafe { lib.get(c_name.as_bytes()) }.ok()?;
+            Some(*raw)
+        }
+
+        /// A symbol already linked into the running process (libc and
+        /// the libraries the binary was linked against).
+        #[cfg(unix)]
+        fn process_symbol(&mut self, c_name: &str) -> Option<*const ()> {
+            const PROCESS: &str = "<process>";
+            if !self.libs.contains_key(PROCESS) {
+                let this = libloading::os::unix::Library::this();
+                self.libs.insert(PROCESS.to_string(), this.into());
+            }
+            self.symbol(PROCESS, c_name)
+        }
+
+        #[cfg(not(unix))]
+        fn process_symbol(&mut self, _c_name: &str) -> Option<*const ()> {
+            None
+        }
+
+        fn bind(&mut self, d: &ExternDecl, ptr: *const (), sig: ForeignSignature) {
+            let sym = ForeignSymbol {
+                name: d.resilient_name.clone(),
+                ptr,
+                sig,
+            };
+            self.syms
+                .insert(d.resilient_name.clone(), std::sync::Arc::new(sym));
+        }
+
         pub fn lookup(&self, name: &str) -> Option<std::sync::Arc<ForeignSymbol>> {
             self.syms.get(name).cloned()
         }
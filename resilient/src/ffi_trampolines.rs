@@ -2,10 +2,12 @@
 //! C function pointer of the right type.
 //!
 //! Input `Value`s are converted to C ABI scalars here. Output C
-//! scalars are converted back to `Value`. String marshalling uses
-//! `(*const u8, usize)` — the trampoline holds the Resilient String's
-//! UTF-8 bytes alive on the stack for the duration of the call via
-//! the `live_strs` vector.
+//! scalars are converted back to `Value`. A `String` argument is
+//! passed as a NUL-terminated `const char*` copy that lives until the
+//! call returns (a string with an interior NUL is refused); a `String`
+//! return is a `const char*` the C side owns, copied into a new
+//! Resilient string. A variadic call's format string takes the same
+//! shape.
 //!
 //! Coverage: arity 0-2 with the primitive combinations the Phase 1
 //! tests need (libm `cos`, `sqrt`, plus a handful of helper fns).
@@ -25,6 +27,7 @@
 use crate::ffi::{FfiError, FfiType, ForeignSymbol, struct_layout};
 use crate::{RResult, Value};
 use std::ffi::CString;
+use std::rc::Rc;
 
 #[allow(dead_code)]
 #[derive(Copy, Clone)]
@@ -250,7 +253,7 @@ fn unpack_struct_from_u64(raw: u64, ty: &FfiType) -> Result<Value, String> {
     }
     Ok(Value::Struct {
         name: sname.clone(),
-        fields,
+        fields: Rc::new(fields),
     })
 }
 
@@ -329,6 +332,10 @@ fn dispatch_explicit(sym: &ForeignSymbol, args: &[Value], variadic: bool) -> RRe
     let mut struct_words: [u64; 8] = [0; 8];
     // Keep string byte borrows live for the call.
     let mut live_strs: Vec<&[u8]> = Vec::with_capacity(args.len());
+    // Fixed-arity `String` params reach C as NUL-terminated copies;
+    // `c_strings` owns them until the call returns.
+    let mut c_strs: [*const core::ffi::c_char; 8] = [std::ptr::null(); 8];
+    let mut c_strings: Vec<CString> = Vec::new();
     for (i, (arg, want)) in args.iter().zip(params.iter()).enumerate() {
         match (arg, want) {
             (Value::Int(v), FfiType::Int) => ints[i] = *v,
@@ -341,6 +348,16 @@ fn dispatch_explicit(sym: &ForeignSymbol, args: &[Value], variadic: bool) -> RRe
                     ptr: bytes.as_ptr(),
                     len: bytes.len(),
                 };
+                if !variadic {
+                    let c = CString::new(s.as_str()).map_err(|_| {
+                        format!(
+                            "FFI: string arg #{} to `{}` contains an interior NUL byte",
+                            i, sym.name
+                        )
+                    })?;
+                    c_strs[i] = c.as_ptr();
+                    c_strings.push(c);
+                }
             }
             (Value::OpaquePtr(h), FfiType::OpaquePtr) => ptrs[i] = h.0,
             (Value::Struct { .. }, FfiType::Struct { .. }) => {
@@ -1014,6 +1031,61 @@ fn dispatch_explicit(sym: &ForeignSymbol, args: &[Value], variadic: bool) -> RRe
                     ints[0], floats[1], floats[2], floats[3]
                 )),
 
+                // ---- `String` arms: `const char*` in, `const char*` out ----
+                (&[], FfiType::Str) => c_string_result(
+                    sym,
+                    std::mem::transmute::<*const (), extern "C" fn() -> *const core::ffi::c_char>(
+                        sym.ptr,
+                    )(),
+                )?,
+                ([FfiType::Int], FfiType::Str) => c_string_result(
+                    sym,
+                    std::mem::transmute::<*const (), extern "C" fn(i64) -> *const core::ffi::c_char>(
+                        sym.ptr,
+                    )(ints[0]),
+                )?,
+                ([FfiType::Str], FfiType::Str) => c_string_result(
+                    sym,
+                    std::mem::transmute::<
+                        *const (),
+                        extern "C" fn(*const core::ffi::c_char) -> *const core::ffi::c_char,
+                    >(sym.ptr)(c_strs[0]),
+                )?,
+                ([FfiType::Str], FfiType::Int) => Value::Int(std::mem::transmute::<
+                    *const (),
+                    extern "C" fn(*const core::ffi::c_char) -> i64,
+                >(sym.ptr)(c_strs[0])),
+                ([FfiType::Str], FfiType::Float) => {
+                    Value::Float(std::mem::transmute::<
+                        *const (),
+                        extern "C" fn(*const core::ffi::c_char) -> f64,
+                    >(sym.ptr)(c_strs[0]))
+                }
+                ([FfiType::Str], FfiType::Bool) => {
+                    Value::Bool(std::mem::transmute::<
+                        *const (),
+                        extern "C" fn(*const core::ffi::c_char) -> bool,
+                    >(sym.ptr)(c_strs[0]))
+                }
+                ([FfiType::Str], FfiType::Void) => {
+                    std::mem::transmute::<*const (), extern "C" fn(*const core::ffi::c_char)>(
+                        sym.ptr,
+                    )(c_strs[0]);
+                    Value::Void
+                }
+                ([FfiType::Str, FfiType::Str], FfiType::Int) => {
+                    Value::Int(std::mem::transmute::<
+                        *const (),
+                        extern "C" fn(*const core::ffi::c_char, *const core::ffi::c_char) -> i64,
+                    >(sym.ptr)(c_strs[0], c_strs[1]))
+                }
+                ([FfiType::Str, FfiType::Int], FfiType::Int) => {
+                    Value::Int(std::mem::transmute::<
+                        *const (),
+                        extern "C" fn(*const core::ffi::c_char, i64) -> i64,
+                    >(sym.ptr)(c_strs[0], ints[1]))
+                }
+
                 // Fallback.
                 _ => {
                     return Err(format!(
@@ -1029,6 +1101,7 @@ fn dispatch_explicit(sym: &ForeignSymbol, args: &[Value], variadic: bool) -> RRe
     // via normal scope rules. Touch them here so optimizers can't
     // shuffle the drop earlier than the call.
     drop(live_strs);
+    drop(c_strings);
     let _ = strs;
     let _ = ptrs;
     let _ = struct_words;
@@ -1036,6 +1109,23 @@ fn dispatch_explicit(sym: &ForeignSymbol, args: &[Value], variadic: bool) -> RRe
     Ok(out)
 }
 
+/// A `const char*` a C function returned, copied into a `String`
+/// (invalid UTF-8 is replaced). The C side keeps ownership, so the
+/// pointer must stay valid until this returns — a static or a buffer
+/// the library owns, as with `getenv` or `strerror`.
+///
+/// SAFETY: the caller passes a pointer returned by the foreign call it
+/// just made; null is rejected before it is read.
+unsafe fn c_string_result(sym: &ForeignSymbol, ptr: *const core::ffi::c_char) -> RResult<Value> {
+    if ptr.is_null() {
+        return Err(format!("FFI: `{}` returned a null string", sym.name));
+    }
+    // SAFETY: non-null and NUL-terminated per the C string contract
+    // the extern declaration asserts.
+    let text = unsafe { std::ffi::CStr::from_ptr(ptr) };
+    Ok(Value::String(text.to_string_lossy().into_owned()))
+}
+
 /// RES-317: dispatch the small struct signatures we support in
 /// Phase 1. Returns `Ok(Some(value))` on a hit, `Ok(None)` if the
 /// signature contains no struct (caller falls back to the scalar
@@ -1786,7 +1876,7 @@ mod tests {
 
         let v = Value::Struct {
             name: "OneInt".to_string(),
-            fields: vec![("v".to_string(), Value::Int(42))],
+            fields: Rc::new(vec![("v".to_string(), Value::Int(42))]),
         };
         let packed = pack_struct_to_u64(&v, &ty).unwrap();
         assert_eq!(packed, 42_u64);
@@ -1836,7 +1926,7 @@ mod tests {
         };
         let arg = Value::Struct {
             name: "OneInt".to_string(),
-            fields: vec![("v".to_string(), Value::Int(21))],
+            fields: Rc::new(vec![("v".to_string(), Value::Int(21))]),
         };
         let out = call_foreign(&sym, &[arg]).unwrap();
         match out {
@@ -1860,7 +1950,7 @@ mod tests {
         };
         let arg = Value::Struct {
             name: "OneInt".to_string(),
-            fields: vec![("v".to_string(), Value::Int(99))],
+            fields: Rc::new(vec![("v".to_string(), Value::Int(99))]),
         };
         let out = call_foreign(&sym, &[arg]).unwrap();
         assert!(matches!(out, Value::Int(99)));
@@ -1888,11 +1978,11 @@ mod tests {
         };
         let arg = Value::Struct {
             name: "Big".to_string(),
-            fields: vec![
+            fields: Rc::new(vec![
                 ("a".to_string(), Value::Int(1)),
                 ("b".to_string(), Value::Int(2)),
                 ("c".to_string(), Value::Int(3)),
-            ],
+            ]),
         };
         let err = call_foreign(&sym, &[arg]).expect_err("must reject too-large struct");
         assert!(
@@ -1918,7 +2008,7 @@ mod tests {
         };
         let arg = Value::Struct {
             name: "Wrong".to_string(),
-            fields: vec![("v".to_string(), Value::Int(1))],
+            fields: Rc::new(vec![("v".to_string(), Value::Int(1))]),
         };
         let err = call_foreign(&sym, &[arg]).expect_err("must reject mismatched name");
         assert!(err.contains("type mismatch"), "got {}", err);
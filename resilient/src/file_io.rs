@@ -28,21 +28,25 @@
 //! close handlers below are backend-agnostic: they only touch the
 //! `Read + Write + Seek` surface, so a single implementation drives
 //! both. Only `file_open` — which constructs the backing handle —
-//! forks per target.
+//! forks per target. `wasm32-wasi` is not the playground: its files
+//! are the runtime's preopened directories, reached through `std::fs`,
+//! so the VFS is gated on `cfg(wasm_web)` and WASI takes the native
+//! path.
 
 use crate::{RResult, Value};
 use std::cell::RefCell;
 use std::collections::HashMap;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(not(wasm_web))]
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom, Write};
+use std::rc::Rc;
 use std::sync::atomic::{AtomicI64, Ordering};
 
 /// Handle backend. Native builds hold a real OS file; the wasm
 /// playground holds an in-memory VFS handle (RES-3877).
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(not(wasm_web))]
 type Backend = File;
-#[cfg(target_arch = "wasm32")]
+#[cfg(wasm_web)]
 type Backend = wasm_vfs::WasmFile;
 
 /// Process-global handle id counter. Each `file_open` mints the next
@@ -57,7 +61,7 @@ thread_local! {
 /// `file_open(path: String, mode: String) -> Result<File, String>`.
 /// Modes: `"r"` (read-only), `"w"` (write-only, truncate), `"rw"`
 /// (read+write, create if missing, do not truncate).
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(not(wasm_web))]
 pub(crate) fn builtin_file_open(args: &[Value]) -> RResult<Value> {
     let (path, mode) = match args {
         [Value::String(p), Value::String(m)] => (p, m),
@@ -68,6 +72,9 @@ pub(crate) fn builtin_file_open(args: &[Value]) -> RResult<Value> {
             ));
         }
     };
+    if let Err(denied) = crate::fs_sandbox::check("file_open", path, mode != "r") {
+        return Ok(err_result(denied));
+    }
     let result = match mode.as_str() {
         "r" => OpenOptions::new().read(true).open(path),
         "w" => OpenOptions::new()
@@ -112,7 +119,7 @@ pub(crate) fn builtin_file_open(args: &[Value]) -> RResult<Value> {
 /// RES-3877: wasm playground `file_open` — backed by the in-memory
 /// VFS. Same mode contract as the native handler; misses on `"r"`
 /// surface as `Err`, mirroring "no such file" on a real filesystem.
-#[cfg(target_arch = "wasm32")]
+#[cfg(wasm_web)]
 pub(crate) fn builtin_file_open(args: &[Value]) -> RResult<Value> {
     let (path, mode) = match args {
         [Value::String(p), Value::String(m)] => (p, m),
@@ -123,6 +130,9 @@ pub(crate) fn builtin_file_open(args: &[Value]) -> RResult<Value> {
             ));
         }
     };
+    if let Err(denied) = crate::fs_sandbox::check("file_open", path, mode != "r") {
+        return Ok(err_result(denied));
+    }
     match mode.as_str() {
         "r" | "w" | "rw" => match wasm_vfs::open(path, mode) {
             Ok(file) => {
@@ -321,10 +331,18 @@ pub(crate) fn builtin_file_close(args: &[Value]) -> RResult<Value> {
     }
 }
 
+/// An `Err(message)` `Result` value.
+fn err_result(message: String) -> Value {
+    Value::Result {
+        ok: false,
+        payload: Box::new(Value::String(message)),
+    }
+}
+
 fn handle_value(id: i64) -> Value {
     Value::Struct {
         name: "File".to_string(),
-        fields: vec![("id".to_string(), Value::Int(id))],
+        fields: Rc::new(vec![("id".to_string(), Value::Int(id))]),
     }
 }
 
@@ -344,7 +362,7 @@ fn handle_id_from_fields(fields: &[(String, Value)]) -> Result<i64, String> {
 /// A thread-local path→bytes store stands in for the host filesystem;
 /// `WasmFile` is a seekable cursor over a handle's working copy that
 /// mirrors back to the store on every write.
-#[cfg(any(target_arch = "wasm32", test))]
+#[cfg(any(wasm_web, test))]
 mod wasm_vfs {
     use std::cell::RefCell;
     use std::collections::HashMap;
@@ -497,12 +515,12 @@ mod wasm_vfs {
 /// `file_read` / `file_write` builtins in `lib.rs`. They share the
 /// same store as the streaming handlers so `file_write` followed by
 /// `file_open` / `file_read` round-trips inside the sandbox.
-#[cfg(target_arch = "wasm32")]
+#[cfg(wasm_web)]
 pub(crate) fn vfs_write(path: &str, contents: &[u8]) {
     wasm_vfs::store_write(path, contents);
 }
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(wasm_web)]
 pub(crate) fn vfs_read(path: &str) -> std::io::Result<Vec<u8>> {
     wasm_vfs::store_read(path)
 }
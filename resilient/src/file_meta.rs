@@ -9,14 +9,22 @@
 //! wasm host happens to expose, every builtin here short-circuits on
 //! `wasm32` with a clear "unsupported on this target" `Err`, mirroring
 //! the graceful-Err pattern used for `http_client.rs` / `process_exec.rs`.
+//! `wasm32-wasi` builds stat the runtime's preopened directories like
+//! native ones; only `cfg(wasm_web)` short-circuits.
+//!
+//! All of them are reads as far as `fs_sandbox` is concerned: `--fs
+//! none`, or an `--allow` without `fs-read`, denies them with
+//! `PermissionDenied` — raised by the `bool` builtins, the `Err` of
+//! the others.
 
 use crate::Value;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(not(wasm_web))]
 use std::path::Path;
+use std::rc::Rc;
 
 type RResult<T> = Result<T, String>;
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(not(wasm_web))]
 fn ok(v: Value) -> Value {
     Value::Result {
         ok: true,
@@ -31,7 +39,7 @@ fn err(msg: String) -> Value {
     }
 }
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(wasm_web)]
 fn unsupported(builtin: &str) -> String {
     format!(
         "{}: unsupported on this target (no host filesystem in the wasm playground)",
@@ -44,12 +52,19 @@ fn unsupported(builtin: &str) -> String {
 /// Returns true if anything (file, directory, symlink) exists at `path`.
 /// On `wasm32` there is no host filesystem, so this always returns
 /// `false` rather than panicking or hanging on an unsupported syscall.
+/// Under `--fs none` it fails with `PermissionDenied` (`fs_sandbox`).
 pub(crate) fn builtin_file_exists(args: &[Value]) -> RResult<Value> {
     match args {
-        #[cfg(not(target_arch = "wasm32"))]
-        [Value::String(path)] => Ok(Value::Bool(Path::new(path.as_str()).exists())),
-        #[cfg(target_arch = "wasm32")]
-        [Value::String(_path)] => Ok(Value::Bool(false)),
+        #[cfg(not(wasm_web))]
+        [Value::String(path)] => {
+            crate::fs_sandbox::check("file_exists", path, false)?;
+            Ok(Value::Bool(Path::new(path.as_str()).exists()))
+        }
+        #[cfg(wasm_web)]
+        [Value::String(path)] => {
+            crate::fs_sandbox::check("file_exists", path, false)?;
+            Ok(Value::Bool(false))
+        }
         [other] => Err(format!(
             "file_exists: expected string path, got {:?}",
             other
@@ -67,9 +82,12 @@ pub(crate) fn builtin_file_exists(args: &[Value]) -> RResult<Value> {
 /// on `wasm32` (see `file_exists`).
 pub(crate) fn builtin_file_is_dir(args: &[Value]) -> RResult<Value> {
     match args {
-        #[cfg(not(target_arch = "wasm32"))]
-        [Value::String(path)] => Ok(Value::Bool(Path::new(path.as_str()).is_dir())),
-        #[cfg(target_arch = "wasm32")]
+        #[cfg(not(wasm_web))]
+        [Value::String(path)] => {
+            crate::fs_sandbox::check("file_is_dir", path, false)?;
+            Ok(Value::Bool(Path::new(path.as_str()).is_dir()))
+        }
+        #[cfg(wasm_web)]
         [Value::String(_path)] => Ok(Value::Bool(false)),
         [other] => Err(format!(
             "file_is_dir: expected string path, got {:?}",
@@ -88,9 +106,12 @@ pub(crate) fn builtin_file_is_dir(args: &[Value]) -> RResult<Value> {
 /// on `wasm32` (see `file_exists`).
 pub(crate) fn builtin_file_is_file(args: &[Value]) -> RResult<Value> {
     match args {
-        #[cfg(not(target_arch = "wasm32"))]
-        [Value::String(path)] => Ok(Value::Bool(Path::new(path.as_str()).is_file())),
-        #[cfg(target_arch = "wasm32")]
+        #[cfg(not(wasm_web))]
+        [Value::String(path)] => {
+            crate::fs_sandbox::check("file_is_file", path, false)?;
+            Ok(Value::Bool(Path::new(path.as_str()).is_file()))
+        }
+        #[cfg(wasm_web)]
         [Value::String(_path)] => Ok(Value::Bool(false)),
         [other] => Err(format!(
             "file_is_file: expected string path, got {:?}",
@@ -109,12 +130,17 @@ pub(crate) fn builtin_file_is_file(args: &[Value]) -> RResult<Value> {
 /// returns `Err` — there is no host filesystem to stat.
 pub(crate) fn builtin_file_size(args: &[Value]) -> RResult<Value> {
     match args {
-        #[cfg(not(target_arch = "wasm32"))]
-        [Value::String(path)] => match std::fs::metadata(path.as_str()) {
-            Ok(meta) => Ok(ok(Value::Int(meta.len() as i64))),
-            Err(e) => Ok(err(format!("file_size: {}: {}", path, e))),
-        },
-        #[cfg(target_arch = "wasm32")]
+        #[cfg(not(wasm_web))]
+        [Value::String(path)] => {
+            if let Err(denied) = crate::fs_sandbox::check("file_size", path, false) {
+                return Ok(err(denied));
+            }
+            match std::fs::metadata(path.as_str()) {
+                Ok(meta) => Ok(ok(Value::Int(meta.len() as i64))),
+                Err(e) => Ok(err(format!("file_size: {}: {}", path, e))),
+            }
+        }
+        #[cfg(wasm_web)]
         [Value::String(_path)] => Ok(err(unsupported("file_size"))),
         [other] => Err(format!("file_size: expected string path, got {:?}", other)),
         _ => Err(format!(
@@ -135,29 +161,34 @@ pub(crate) fn builtin_file_size(args: &[Value]) -> RResult<Value> {
 /// On `wasm32` always returns `Err` — there is no host filesystem to stat.
 pub(crate) fn builtin_file_stat(args: &[Value]) -> RResult<Value> {
     match args {
-        #[cfg(not(target_arch = "wasm32"))]
-        [Value::String(path)] => match std::fs::metadata(path.as_str()) {
-            Ok(meta) => {
-                let modified = meta
-                    .modified()
-                    .ok()
-                    .and_then(|t| t.duration_since(std::time::SystemTime::UNIX_EPOCH).ok())
-                    .map(|d| d.as_secs() as i64)
-                    .unwrap_or(0);
-                let file_meta = Value::Struct {
-                    name: "FileMeta".to_string(),
-                    fields: vec![
-                        ("size".to_string(), Value::Int(meta.len() as i64)),
-                        ("modified".to_string(), Value::Int(modified)),
-                        ("is_dir".to_string(), Value::Bool(meta.is_dir())),
-                        ("is_file".to_string(), Value::Bool(meta.is_file())),
-                    ],
-                };
-                Ok(ok(file_meta))
+        #[cfg(not(wasm_web))]
+        [Value::String(path)] => {
+            if let Err(denied) = crate::fs_sandbox::check("file_stat", path, false) {
+                return Ok(err(denied));
+            }
+            match std::fs::metadata(path.as_str()) {
+                Ok(meta) => {
+                    let modified = meta
+                        .modified()
+                        .ok()
+                        .and_then(|t| t.duration_since(std::time::SystemTime::UNIX_EPOCH).ok())
+                        .map(|d| d.as_secs() as i64)
+                        .unwrap_or(0);
+                    let file_meta = Value::Struct {
+                        name: "FileMeta".to_string(),
+                        fields: Rc::new(vec![
+                            ("size".to_string(), Value::Int(meta.len() as i64)),
+                            ("modified".to_string(), Value::Int(modified)),
+                            ("is_dir".to_string(), Value::Bool(meta.is_dir())),
+                            ("is_file".to_string(), Value::Bool(meta.is_file())),
+                        ]),
+                    };
+                    Ok(ok(file_meta))
+                }
+                Err(e) => Ok(err(format!("file_stat: {}: {}", path, e))),
             }
-            Err(e) => Ok(err(format!("file_stat: {}: {}", path, e))),
-        },
-        #[cfg(target_arch = "wasm32")]
+        }
+        #[cfg(wasm_web)]
         [Value::String(_path)] => Ok(err(unsupported("file_stat"))),
         [other] => Err(format!("file_stat: expected string path, got {:?}", other)),
         _ => Err(format!(
@@ -173,29 +204,34 @@ pub(crate) fn builtin_file_stat(args: &[Value]) -> RResult<Value> {
 /// On `wasm32` always returns `Err` — there is no host filesystem to list.
 pub(crate) fn builtin_dir_list(args: &[Value]) -> RResult<Value> {
     match args {
-        #[cfg(not(target_arch = "wasm32"))]
-        [Value::String(path)] => match std::fs::read_dir(path.as_str()) {
-            Ok(entries) => {
-                let mut names: Vec<String> = entries
-                    .filter_map(|e| {
-                        e.ok()
-                            .map(|entry| entry.file_name().to_string_lossy().into_owned())
-                    })
-                    .collect();
-                names.sort();
-                let arr = Value::Array(names.into_iter().map(Value::String).collect());
-                Ok(ok(arr))
+        #[cfg(not(wasm_web))]
+        [Value::String(path)] => {
+            if let Err(denied) = crate::fs_sandbox::check("dir_list", path, false) {
+                return Ok(err(denied));
+            }
+            match std::fs::read_dir(path.as_str()) {
+                Ok(entries) => {
+                    let mut names: Vec<String> = entries
+                        .filter_map(|e| {
+                            e.ok()
+                                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                        })
+                        .collect();
+                    names.sort();
+                    let arr = Value::Array(names.into_iter().map(Value::String).collect());
+                    Ok(ok(arr))
+                }
+                Err(e) => Ok(err(format!("dir_list: {}: {}", path, e))),
             }
-            Err(e) => Ok(err(format!("dir_list: {}: {}", path, e))),
-        },
-        #[cfg(target_arch = "wasm32")]
+        }
+        #[cfg(wasm_web)]
         [Value::String(_path)] => Ok(err(unsupported("dir_list"))),
         [other] => Err(format!("dir_list: expected string path, got {:?}", other)),
         _ => Err(format!("dir_list: expected 1 argument, got {}", args.len())),
     }
 }
 
-#[cfg(all(test, target_arch = "wasm32"))]
+#[cfg(all(test, wasm_web))]
 mod wasm_tests {
     use super::*;
 
@@ -0,0 +1,155 @@
+//! Float special values: what `NaN` and infinities do, and how floats
+//! print.
+//!
+//! By default float arithmetic follows IEEE 754 on both runtimes:
+//! `1.0 / 0.0` is `inf`, `0.0 / 0.0` and `inf - inf` are `NaN`, a `NaN`
+//! operand makes every ordering comparison and `==` false and `!=`
+//! true, and the program carries on. Under `--float-strict` an
+//! operator that would create a special value fails instead, with a
+//! typed error `catch` can name and a `live` block retries:
+//!
+//! - `DivisionByZero` — `/` or `%` with a zero divisor;
+//! - `FloatOverflow` — finite operands whose result is too large for
+//!   `f64`;
+//! - `InvalidOperation` — a `NaN` result from operands that were not
+//!   `NaN` (`inf - inf`, `0.0 * inf`), or `<`, `>`, `<=`, `>=` with a
+//!   `NaN` operand.
+//!
+//! Values that are already special (from `parse_float("inf")`, say)
+//! keep flowing where IEEE gives a meaningful answer (`inf + 1.0` is
+//! `inf`, `NaN + 1.0` is `NaN`), and `==` / `!=` keep their IEEE
+//! answers so `x != x` still tests for `NaN`.
+//!
+//! Printing goes through [`display`]: the shortest digits that read
+//! back as the same value, exponent form outside `1e-5 ..= 1e16`
+//! (`1e21`, `1.5e-10`), and `NaN`, `inf`, `-inf` for the special
+//! values. `--float-precision N` prints every finite float with
+//! exactly `N` digits after the point instead.
+//!
+//! Both settings are per thread, like `fuel`: `rz` sets them on the
+//! CLI thread, embedders call [`set_float_strict`] and
+//! [`set_float_precision`] on theirs.
+
+use std::cell::Cell;
+
+thread_local! {
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+    static PRECISION: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+const FAILURES: &[&str] = &["DivisionByZero", "FloatOverflow", "InvalidOperation"];
+
+/// Make special float results fail on this thread (`--float-strict`).
+pub fn set_float_strict(enabled: bool) {
+    STRICT.with(|s| s.set(enabled));
+}
+
+/// Print finite floats with exactly `digits` decimals on this thread
+/// (`None` restores the shortest round-trip form).
+pub fn set_float_precision(digits: Option<usize>) {
+    PRECISION.with(|p| p.set(digits));
+}
+
+/// The result of `left op right`, or the strict-mode failure for it.
+/// Free unless the result is `NaN` or infinite.
+#[inline]
+pub(crate) fn check_arith(op: &str, left: f64, right: f64, result: f64) -> Result<f64, String> {
+    if result.is_finite() || !STRICT.with(Cell::get) {
+        return Ok(result);
+    }
+    let variant = if matches!(op, "/" | "%") && right == 0.0 {
+        "DivisionByZero"
+    } else if left.is_nan() || right.is_nan() {
+        return Ok(result);
+    } else if result.is_nan() {
+        "InvalidOperation"
+    } else if left.is_finite() && right.is_finite() {
+        "FloatOverflow"
+    } else {
+        return Ok(result);
+    };
+    Err(format!(
+        "{variant}: {left:?} {op} {right:?} under --float-strict"
+    ))
+}
+
+/// Fail an ordering comparison with a `NaN` operand under strict mode.
+#[inline]
+pub(crate) fn check_cmp(op: &str, left: f64, right: f64) -> Result<(), String> {
+    if !(left.is_nan() || right.is_nan()) || !STRICT.with(Cell::get) {
+        return Ok(());
+    }
+    Err(format!(
+        "InvalidOperation: {left:?} {op} {right:?} compares NaN under --float-strict"
+    ))
+}
+
+/// The strict-mode variant `err` carries, for `catch`. Looks past the
+/// location prefix the interpreter may add.
+pub(crate) fn failure_variant(err: &str) -> Option<&'static str> {
+    if !err.contains("under --float-strict") {
+        return None;
+    }
+    FAILURES
+        .iter()
+        .copied()
+        .find(|v| err.contains(&format!("{v}: ")))
+}
+
+/// How a float prints everywhere a program can see it: `println`,
+/// `to_string`, concatenation and interpolation.
+pub(crate) fn display(f: f64) -> String {
+    if f.is_nan() {
+        return "NaN".to_string();
+    }
+    if f.is_infinite() {
+        return if f > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    let magnitude = f.abs();
+    let exponent = magnitude != 0.0 && !(1e-5..1e16).contains(&magnitude);
+    match (PRECISION.with(Cell::get), exponent) {
+        (Some(digits), true) => format!("{f:.digits$e}"),
+        (Some(digits), false) => format!("{f:.digits$}"),
+        (None, true) => format!("{f:e}"),
+        (None, false) => f.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_uses_exponents_at_the_extremes_and_honors_precision() {
+        assert_eq!(display(1.0), "1");
+        assert_eq!(display(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(display(1e21), "1e21");
+        assert_eq!(display(-1.5e-10), "-1.5e-10");
+        assert_eq!(display(0.0), "0");
+        assert_eq!(display(f64::NAN), "NaN");
+        assert_eq!(display(f64::NEG_INFINITY), "-inf");
+        set_float_precision(Some(2));
+        assert_eq!(display(2.0 / 3.0), "0.67");
+        assert_eq!(display(1e21), "1.00e21");
+        assert_eq!(display(f64::INFINITY), "inf");
+        set_float_precision(None);
+    }
+
+    #[test]
+    fn strict_mode_types_special_results() {
+        assert_eq!(check_arith("/", 1.0, 0.0, f64::INFINITY), Ok(f64::INFINITY));
+        set_float_strict(true);
+        let err = check_arith("/", 1.0, 0.0, f64::INFINITY).unwrap_err();
+        assert_eq!(failure_variant(&err), Some("DivisionByZero"), "{err}");
+        let err = check_arith("*", 1e300, 1e300, f64::INFINITY).unwrap_err();
+        assert_eq!(failure_variant(&err), Some("FloatOverflow"), "{err}");
+        let err = check_cmp("<", f64::NAN, 1.0).unwrap_err();
+        assert_eq!(failure_variant(&err), Some("InvalidOperation"), "{err}");
+        let err = check_arith("-", f64::INFINITY, f64::INFINITY, f64::NAN).unwrap_err();
+        assert_eq!(failure_variant(&err), Some("InvalidOperation"), "{err}");
+        assert!(check_arith("+", f64::INFINITY, 1.0, f64::INFINITY).is_ok());
+        assert!(check_arith("+", f64::NAN, 1.0, f64::NAN).is_ok());
+        assert!(check_cmp("<", 1.0, 2.0).is_ok());
+        set_float_strict(false);
+    }
+}
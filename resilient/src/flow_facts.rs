@@ -0,0 +1,562 @@
+//! Flow-sensitive facts learned from conditionals.
+//!
+//! While checking the then-branch of `if y >= 0 { ... }` the checker
+//! knows `y >= 0`; in the else-branch, and after an early exit such as
+//! `if y < 0 { return 0; }`, it knows the negation. This module turns
+//! conditions into `Fact`s and answers two questions with them:
+//!
+//! - does a callee's `requires` clause already hold at this call site
+//!   (`implies`)? A discharged clause needs no runtime check, exactly
+//!   like one folded from constant arguments;
+//! - is this `Option` binding known to be `Some` (`known_some`)? Then
+//!   `unwrap_option(x)` is typed as the payload instead of `Any`.
+//!
+//! Conditions understood: `x OP c` / `c OP x` for the six comparison
+//! operators and a constant `c`, `x != None` / `x == None`,
+//! `is_some(x)` / `is_none(x)`, `!`, `&&` (when true), `||` (when
+//! false), and calls to user predicates — a `-> bool` fn whose body is
+//! a single `return <expr>;` — with identifier arguments, so
+//! `if is_valid_reading(x)` refines `x` the way its body says.
+//!
+//! Facts are only as good as the binding they describe, so the
+//! typechecker drops a variable's facts when it is reassigned or
+//! shadowed, when a loop body may reassign it, and on entry to a
+//! nested fn or closure body. A call that may run user code can
+//! reassign any global or captured binding, so it drops every fact
+//! except those about the enclosing fn's own `Locals`.
+
+use crate::Node;
+use std::collections::{HashMap, HashSet};
+
+/// Inlining depth cap for predicates that call other predicates.
+const MAX_PREDICATE_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Cmp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Cmp {
+    fn from_operator(op: &str) -> Option<Cmp> {
+        Some(match op {
+            "<" => Cmp::Lt,
+            "<=" => Cmp::Le,
+            ">" => Cmp::Gt,
+            ">=" => Cmp::Ge,
+            "==" => Cmp::Eq,
+            "!=" => Cmp::Ne,
+            _ => return None,
+        })
+    }
+
+    fn negate(self) -> Cmp {
+        match self {
+            Cmp::Lt => Cmp::Ge,
+            Cmp::Le => Cmp::Gt,
+            Cmp::Gt => Cmp::Le,
+            Cmp::Ge => Cmp::Lt,
+            Cmp::Eq => Cmp::Ne,
+            Cmp::Ne => Cmp::Eq,
+        }
+    }
+
+    /// The operator with its operands swapped: `c < x` is `x > c`.
+    fn flip(self) -> Cmp {
+        match self {
+            Cmp::Lt => Cmp::Gt,
+            Cmp::Le => Cmp::Ge,
+            Cmp::Gt => Cmp::Lt,
+            Cmp::Ge => Cmp::Le,
+            other => other,
+        }
+    }
+}
+
+/// One thing known to be true about a local binding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Fact {
+    /// `var op value`.
+    Cmp { var: String, op: Cmp, value: i64 },
+    /// `var` is an `Option` holding `Some`.
+    IsSome(String),
+}
+
+impl Fact {
+    fn var(&self) -> &str {
+        match self {
+            Fact::Cmp { var, .. } | Fact::IsSome(var) => var,
+        }
+    }
+}
+
+/// A user predicate eligible for inlining: parameter names and the
+/// returned boolean expression.
+#[derive(Debug, Clone)]
+pub(crate) struct Predicate {
+    pub(crate) params: Vec<String>,
+    pub(crate) body: Node,
+}
+
+impl Predicate {
+    /// Recognise `fn name(...) -> bool { return <expr>; }`.
+    pub(crate) fn from_fn(
+        parameters: &[(String, String)],
+        return_type: Option<&str>,
+        body: &Node,
+    ) -> Option<Predicate> {
+        if !matches!(
+            return_type.map(str::trim),
+            Some("bool" | "Bool" | "boolean")
+        ) {
+            return None;
+        }
+        let Node::Block { stmts, .. } = body else {
+            return None;
+        };
+        let [
+            Node::ReturnStatement {
+                value: Some(expr), ..
+            },
+        ] = stmts.as_slice()
+        else {
+            return None;
+        };
+        Some(Predicate {
+            params: parameters.iter().map(|(_, n)| n.clone()).collect(),
+            body: (**expr).clone(),
+        })
+    }
+}
+
+/// Facts that hold when `cond` evaluates to `truth`. `consts` resolves
+/// named integer constants on the constant side of a comparison.
+pub(crate) fn facts_from_condition(
+    cond: &Node,
+    truth: bool,
+    predicates: &HashMap<String, Predicate>,
+    consts: &HashMap<String, i64>,
+) -> Vec<Fact> {
+    let mut out = Vec::new();
+    collect(cond, truth, predicates, consts, None, 0, &mut out);
+    out
+}
+
+/// How identifiers inside an inlined predicate body map back to the
+/// caller: `Some(name)` for an identifier argument, `None` for any
+/// other argument (facts about it are dropped).
+type Renames = HashMap<String, Option<String>>;
+
+fn resolve_var(name: &str, renames: Option<&Renames>) -> Option<String> {
+    match renames {
+        None => Some(name.to_string()),
+        Some(map) => map.get(name).cloned().flatten(),
+    }
+}
+
+fn collect(
+    cond: &Node,
+    truth: bool,
+    predicates: &HashMap<String, Predicate>,
+    consts: &HashMap<String, i64>,
+    renames: Option<&Renames>,
+    depth: usize,
+    out: &mut Vec<Fact>,
+) {
+    match cond {
+        Node::PrefixExpression {
+            operator: "!",
+            right,
+            ..
+        } => collect(right, !truth, predicates, consts, renames, depth, out),
+        Node::InfixExpression {
+            left,
+            operator,
+            right,
+            ..
+        } => match (*operator, truth) {
+            ("&&", true) | ("||", false) => {
+                collect(left, truth, predicates, consts, renames, depth, out);
+                collect(right, truth, predicates, consts, renames, depth, out);
+            }
+            _ => {
+                if let Some(fact) = comparison_fact(left, operator, right, truth, consts, renames) {
+                    out.push(fact);
+                }
+            }
+        },
+        Node::CallExpression {
+            function,
+            arguments,
+            ..
+        } => {
+            let Node::Identifier { name: callee, .. } = function.as_ref() else {
+                return;
+            };
+            match (callee.as_str(), arguments.as_slice()) {
+                ("is_some", [Node::Identifier { name, .. }]) if truth => {
+                    if let Some(var) = resolve_var(name, renames) {
+                        out.push(Fact::IsSome(var));
+                    }
+                }
+                ("is_none", [Node::Identifier { name, .. }]) if !truth => {
+                    if let Some(var) = resolve_var(name, renames) {
+                        out.push(Fact::IsSome(var));
+                    }
+                }
+                _ => {
+                    if depth >= MAX_PREDICATE_DEPTH {
+                        return;
+                    }
+                    let Some(pred) = predicates.get(callee.as_str()) else {
+                        return;
+                    };
+                    if pred.params.len() != arguments.len() {
+                        return;
+                    }
+                    let inner: Renames = pred
+                        .params
+                        .iter()
+                        .zip(arguments)
+                        .map(|(p, arg)| {
+                            let mapped = match arg {
+                                Node::Identifier { name, .. } => resolve_var(name, renames),
+                                _ => None,
+                            };
+                            (p.clone(), mapped)
+                        })
+                        .collect();
+                    collect(
+                        &pred.body,
+                        truth,
+                        predicates,
+                        consts,
+                        Some(&inner),
+                        depth + 1,
+                        out,
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn comparison_fact(
+    left: &Node,
+    operator: &str,
+    right: &Node,
+    truth: bool,
+    consts: &HashMap<String, i64>,
+    renames: Option<&Renames>,
+) -> Option<Fact> {
+    let op = Cmp::from_operator(operator)?;
+    let op = if truth { op } else { op.negate() };
+    if let Some(fact) = none_fact(left, op, right, renames) {
+        return Some(fact);
+    }
+    // Inside an inlined predicate only parameters are meaningful, so
+    // constants must be literals there; the caller's `consts` apply
+    // at the top level only.
+    let no_consts = HashMap::new();
+    let consts = if renames.is_some() {
+        &no_consts
+    } else {
+        consts
+    };
+    match (left, right) {
+        (Node::Identifier { name, .. }, other) if !consts.contains_key(name.as_str()) => {
+            let value = crate::typechecker::fold_const_i64(other, consts)?;
+            Some(Fact::Cmp {
+                var: resolve_var(name, renames)?,
+                op,
+                value,
+            })
+        }
+        (other, Node::Identifier { name, .. }) if !consts.contains_key(name.as_str()) => {
+            let value = crate::typechecker::fold_const_i64(other, consts)?;
+            Some(Fact::Cmp {
+                var: resolve_var(name, renames)?,
+                op: op.flip(),
+                value,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// `x != None` (or `None != x`) when true; `x == None` when false.
+fn none_fact(left: &Node, op: Cmp, right: &Node, renames: Option<&Renames>) -> Option<Fact> {
+    if op != Cmp::Ne {
+        return None;
+    }
+    let is_none = |n: &Node| matches!(n, Node::Identifier { name, .. } if name == "None");
+    match (left, right) {
+        (Node::Identifier { name, .. }, r) if is_none(r) && name != "None" => {
+            Some(Fact::IsSome(resolve_var(name, renames)?))
+        }
+        (l, Node::Identifier { name, .. }) if is_none(l) && name != "None" => {
+            Some(Fact::IsSome(resolve_var(name, renames)?))
+        }
+        _ => None,
+    }
+}
+
+/// Drop every fact about `var` (reassigned, shadowed, or possibly
+/// reassigned by a loop body).
+pub(crate) fn forget(facts: &mut Vec<Fact>, var: &str) {
+    facts.retain(|f| f.var() != var);
+}
+
+/// Drop the facts of every variable `node` may reassign.
+pub(crate) fn forget_assigned_in(facts: &mut Vec<Fact>, node: &Node) {
+    if facts.is_empty() {
+        return;
+    }
+    crate::uniqueness_walk::visit(node, &mut |n| {
+        if let Node::Assignment { name, .. } = n {
+            forget(facts, name);
+        }
+    });
+}
+
+/// The bindings of the fn being checked whose facts survive a call:
+/// its parameters and `let` locals, minus any name a nested fn or
+/// closure in its body assigns (the callee might be that closure).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Locals {
+    bound: HashSet<String>,
+    captured: HashSet<String>,
+}
+
+impl Locals {
+    /// Locals on entry to a fn with `params` and `body`.
+    pub(crate) fn enter<'a>(params: impl IntoIterator<Item = &'a str>, body: &Node) -> Locals {
+        let mut captured = HashSet::new();
+        crate::uniqueness_walk::walk_children(body, &mut |n| {
+            let nested: &Node = match n {
+                Node::Function { body, .. } => body,
+                Node::FunctionLiteral { body, .. } => body,
+                _ => return,
+            };
+            crate::uniqueness_walk::visit(nested, &mut |inner| {
+                if let Node::Assignment { name, .. } = inner {
+                    captured.insert(name.to_string());
+                }
+            });
+        });
+        let mut locals = Locals {
+            bound: HashSet::new(),
+            captured,
+        };
+        for param in params {
+            locals.bind(param);
+        }
+        locals
+    }
+
+    /// A `let` in the fn body introduces `name`.
+    pub(crate) fn bind(&mut self, name: &str) {
+        if !self.captured.contains(name) {
+            self.bound.insert(name.to_string());
+        }
+    }
+}
+
+/// Drop every fact a call into user code could invalidate: all of
+/// them at top level (`locals` is `None`), otherwise those about
+/// names that aren't the enclosing fn's uncaptured locals.
+pub(crate) fn forget_across_call(facts: &mut Vec<Fact>, locals: Option<&Locals>) {
+    match locals {
+        Some(locals) => facts.retain(|f| locals.bound.contains(f.var())),
+        None => facts.clear(),
+    }
+}
+
+/// Does `node` contain an assignment to `var`?
+pub(crate) fn assigns(node: &Node, var: &str) -> bool {
+    let mut found = false;
+    crate::uniqueness_walk::visit(node, &mut |n| {
+        if let Node::Assignment { name, .. } = n {
+            found |= name == var;
+        }
+    });
+    found
+}
+
+/// Is `var` known to hold `Some`?
+pub(crate) fn known_some(facts: &[Fact], var: &str) -> bool {
+    facts
+        .iter()
+        .any(|f| matches!(f, Fact::IsSome(v) if v == var))
+}
+
+/// Does `clause`, a callee `requires` over its parameters, hold given
+/// `facts`? `args` maps each callee parameter to the caller's argument
+/// expression. Conservative: `false` means "not proven", never
+/// "refuted".
+pub(crate) fn implies(
+    facts: &[Fact],
+    clause: &Node,
+    args: &HashMap<&str, &Node>,
+    consts: &HashMap<String, i64>,
+) -> bool {
+    match clause {
+        Node::InfixExpression {
+            left,
+            operator: "&&",
+            right,
+            ..
+        } => implies(facts, left, args, consts) && implies(facts, right, args, consts),
+        Node::InfixExpression {
+            left,
+            operator: "||",
+            right,
+            ..
+        } => implies(facts, left, args, consts) || implies(facts, right, args, consts),
+        Node::InfixExpression {
+            left,
+            operator,
+            right,
+            ..
+        } => {
+            let Some(op) = Cmp::from_operator(operator) else {
+                return false;
+            };
+            // Rewrite the clause in caller terms: `param op c` becomes
+            // `var op c` when the argument for `param` is a bare
+            // caller identifier.
+            let as_var = |n: &Node| -> Option<String> {
+                let Node::Identifier { name, .. } = n else {
+                    return None;
+                };
+                match args.get(name.as_str()) {
+                    Some(Node::Identifier { name: var, .. }) => Some(var.to_string()),
+                    _ => None,
+                }
+            };
+            // A parameter on the constant side folds through its
+            // argument: `x >= lo` with `lo` passed as `0`.
+            let as_const = |n: &Node| -> Option<i64> {
+                if let Node::Identifier { name, .. } = n
+                    && let Some(arg) = args.get(name.as_str())
+                {
+                    return crate::typechecker::fold_const_i64(arg, consts);
+                }
+                crate::typechecker::fold_const_i64(n, consts)
+            };
+            match (as_var(left), as_var(right)) {
+                (Some(var), None) => {
+                    as_const(right).is_some_and(|c| range_implies(facts, &var, op, c))
+                }
+                (None, Some(var)) => {
+                    as_const(left).is_some_and(|c| range_implies(facts, &var, op.flip(), c))
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Does the interval the facts put on `var` satisfy `var op c`?
+fn range_implies(facts: &[Fact], var: &str, op: Cmp, c: i64) -> bool {
+    let mut lo = i64::MIN;
+    let mut hi = i64::MAX;
+    let mut excluded: Vec<i64> = Vec::new();
+    for fact in facts {
+        let Fact::Cmp { var: v, op, value } = fact else {
+            continue;
+        };
+        if v != var {
+            continue;
+        }
+        match op {
+            Cmp::Lt => hi = hi.min(value.saturating_sub(1)),
+            Cmp::Le => hi = hi.min(*value),
+            Cmp::Gt => lo = lo.max(value.saturating_add(1)),
+            Cmp::Ge => lo = lo.max(*value),
+            Cmp::Eq => {
+                lo = lo.max(*value);
+                hi = hi.min(*value);
+            }
+            Cmp::Ne => excluded.push(*value),
+        }
+    }
+    match op {
+        Cmp::Lt => hi < c,
+        Cmp::Le => hi <= c,
+        Cmp::Gt => lo > c,
+        Cmp::Ge => lo >= c,
+        Cmp::Eq => lo == c && hi == c,
+        Cmp::Ne => c < lo || c > hi || excluded.contains(&c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expr(src: &str) -> Node {
+        let (prog, errs) = crate::parse(&format!("let __c = {src};"));
+        assert!(errs.is_empty(), "{errs:?}");
+        let Node::Program(stmts) = prog else {
+            panic!("expected program");
+        };
+        match &stmts[0].node {
+            Node::LetStatement { value, .. } => (**value).clone(),
+            other => panic!("expected let, got {other:?}"),
+        }
+    }
+
+    fn facts(src: &str, truth: bool) -> Vec<Fact> {
+        facts_from_condition(&expr(src), truth, &HashMap::new(), &HashMap::new())
+    }
+
+    #[test]
+    fn comparison_and_negation() {
+        assert_eq!(
+            facts("y >= 0", true),
+            [Fact::Cmp {
+                var: "y".into(),
+                op: Cmp::Ge,
+                value: 0
+            }]
+        );
+        assert_eq!(
+            facts("0 > y", false),
+            [Fact::Cmp {
+                var: "y".into(),
+                op: Cmp::Ge,
+                value: 0
+            }]
+        );
+        assert_eq!(facts("a >= 0 || b >= 0", true), []);
+        assert_eq!(facts("a < 0 || b < 0", false).len(), 2);
+    }
+
+    #[test]
+    fn option_checks_become_is_some() {
+        assert_eq!(facts("x != None", true), [Fact::IsSome("x".into())]);
+        assert_eq!(facts("x == None", false), [Fact::IsSome("x".into())]);
+        assert_eq!(facts("is_none(x)", false), [Fact::IsSome("x".into())]);
+        assert_eq!(facts("x == None", true), []);
+    }
+
+    #[test]
+    fn interval_reasoning() {
+        let f = facts("y > 2 && y != 7", true);
+        let args_src = expr("y");
+        let args: HashMap<&str, &Node> = [("x", &args_src)].into_iter().collect();
+        let consts = HashMap::new();
+        assert!(implies(&f, &expr("x >= 0"), &args, &consts));
+        assert!(implies(&f, &expr("x != 7"), &args, &consts));
+        assert!(implies(&f, &expr("0 < x && x > 1"), &args, &consts));
+        assert!(!implies(&f, &expr("x > 3"), &args, &consts));
+        assert!(!implies(&f, &expr("x < 100"), &args, &consts));
+    }
+}
@@ -100,6 +100,88 @@ pub fn parse_template(s: &str) -> Result<Vec<FormatSegment>, String> {
     Ok(out)
 }
 
+/// Resolve named `format` arguments — `format("{} read {v:.2}", id,
+/// v: reading)` — into positional ones before the program runs. Each
+/// `{name}` / `{name:spec}` placeholder becomes `{}` / `{:spec}` and
+/// takes the `name:` argument's expression at its position, so the
+/// runtime only ever sees positional arguments. A named argument used
+/// by several placeholders is evaluated once per placeholder.
+///
+/// Called by `named_args` for every `format` call that has named
+/// arguments. The template must be a string literal.
+pub(crate) fn lower_named_args(arguments: &[Node]) -> Result<Vec<Node>, String> {
+    let (template, span) = match arguments.first() {
+        Some(Node::StringInternLiteral { content, span, .. }) => (content.as_str(), *span),
+        Some(Node::StringLiteral { value, span }) => (value.as_str(), *span),
+        _ => return Err("Named `format` arguments need a string literal template".to_string()),
+    };
+    let mut positional = Vec::with_capacity(arguments.len());
+    let mut named: Vec<(&str, &Node, bool)> = Vec::new();
+    for arg in &arguments[1..] {
+        match arg {
+            Node::NamedArg { name, value, .. } => named.push((name.as_str(), value, false)),
+            other => positional.push(other),
+        }
+    }
+    let mut positional = positional.into_iter();
+
+    let mut lowered = String::with_capacity(template.len());
+    let mut out = Vec::with_capacity(arguments.len());
+    for seg in parse_template(template)? {
+        match seg {
+            FormatSegment::Literal(text) => {
+                lowered.push_str(&text.replace('{', "{{").replace('}', "}}"));
+            }
+            FormatSegment::Placeholder(inner) => {
+                let (head, spec) = inner.split_at(inner.find(':').unwrap_or(inner.len()));
+                let is_name = head.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                    && head.chars().all(|c| c.is_alphanumeric() || c == '_');
+                if !is_name {
+                    // `{}` / `{:spec}` take the next positional argument;
+                    // anything else is left for the runtime to reject.
+                    if head.is_empty() {
+                        out.extend(positional.next().cloned());
+                    }
+                    lowered.push_str(&format!("{{{}}}", inner));
+                    continue;
+                }
+                let Some(entry) = named.iter_mut().find(|(n, _, _)| *n == head) else {
+                    let names = named.iter().map(|(n, _, _)| *n);
+                    return Err(match crate::did_you_mean::suggest(head, names).first() {
+                        Some(hint) => format!(
+                            "`format` placeholder `{{{}}}` has no `{}:` argument — did you mean `{}`?",
+                            inner, head, hint
+                        ),
+                        None => format!(
+                            "`format` placeholder `{{{}}}` has no `{}:` argument",
+                            inner, head
+                        ),
+                    });
+                };
+                entry.2 = true;
+                out.push(entry.1.clone());
+                lowered.push_str(&format!("{{{}}}", spec));
+            }
+        }
+    }
+    if let Some((name, _, _)) = named.iter().find(|(_, _, used)| !used) {
+        return Err(format!(
+            "Named argument `{}` is not used by the `format` template",
+            name
+        ));
+    }
+    // Surplus positional arguments stay, for the usual count check.
+    out.extend(positional.cloned());
+    out.insert(
+        0,
+        Node::StringLiteral {
+            value: lowered,
+            span,
+        },
+    );
+    Ok(out)
+}
+
 /// Render an integer through the standalone format-spec engine.
 ///
 /// Supports `:Nd` (space-padded width), `:0Nd` (zero-padded width,
@@ -306,7 +388,7 @@ pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
     let has_format_call = crate::uniqueness_walk::any_node(program, |n| {
         if let Node::CallExpression { function, .. } = n {
             if let Node::Identifier { name, .. } = function.as_ref() {
-                return name == "format" || format_builtins.contains_key(name);
+                return name == "format" || format_builtins.contains_key(name.as_str());
             }
         }
         false
@@ -615,7 +697,7 @@ fn check_format_calls(
                         }
                     }
                 }
-            } else if let Some(decl) = format_builtins.get(name) {
+            } else if let Some(decl) = format_builtins.get(name.as_str()) {
                 // RES-3231: validate format_builtin call sites
                 let line = span.start.line;
                 let col = span.start.column;
@@ -702,6 +784,43 @@ mod tests {
         assert!(err.contains("unterminated"), "got: {err}");
     }
 
+    fn call_args(src: &str) -> Vec<Node> {
+        let (program, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "{errs:?}");
+        let Node::Program(stmts) = program else {
+            unreachable!()
+        };
+        match &stmts[0].node {
+            Node::ExpressionStatement { expr, .. } => match expr.as_ref() {
+                Node::CallExpression { arguments, .. } => arguments.clone(),
+                other => panic!("expected a call, got {other:?}"),
+            },
+            other => panic!("expected an expression statement, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn named_placeholders_lower_to_positional_ones() {
+        // `parse` has already run the lowering.
+        let args = call_args(r#"format("{a} {{x}} {} {b:.1} {a}", 9, b: 2.5, a: "s");"#);
+        let Node::StringLiteral { value, .. } = &args[0] else {
+            panic!("expected a lowered template, got {:?}", args[0]);
+        };
+        assert_eq!(value, "{} {{x}} {} {:.1} {}");
+        assert!(matches!(&args[1], Node::StringInternLiteral { content, .. } if content == "s"));
+        assert!(matches!(&args[2], Node::IntegerLiteral { value: 9, .. }));
+        assert!(matches!(&args[3], Node::FloatLiteral { .. }));
+        assert_eq!(args.len(), 5);
+    }
+
+    #[test]
+    fn named_placeholder_mistakes_are_lowering_errors() {
+        let (_, errs) = crate::parse(r#"format("{nme}", name: 1);"#);
+        assert!(errs[0].contains("did you mean `name`"), "{errs:?}");
+        let (_, errs) = crate::parse(r#"format("{}", 1, extra: 2);"#);
+        assert!(errs[0].contains("`extra` is not used"), "{errs:?}");
+    }
+
     #[test]
     fn render_float_with_precision() {
         assert_eq!(render_float(":.2f", 1.2345).unwrap(), "1.23");
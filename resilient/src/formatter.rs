@@ -23,18 +23,25 @@
 // - Match-arm guards (`if <expr>`) and or-patterns (`p1 | p2`) are
 //   emitted as written, but complex nested match bodies aren't
 //   specially re-wrapped.
-// - The formatter is a structural round-trip: comments are dropped
-//   (the parser doesn't retain them). Users should only run fmt on
-//   code they're willing to have their comments reattached by hand.
-//   This is a known deficiency documented in `docs/tooling.md` and
-//   is the top follow-up for the next formatter ticket.
+// - The parser doesn't retain comments, so `format_source` collects
+//   them from the token stream and puts them back by position: a
+//   comment on a line of its own goes above the statement that follows
+//   it, one just before a statement on the same line stays in front of
+//   it, a comment after code goes back at the end of that code's line,
+//   and a comment before a block's `}` stays inside the block.
+//   Comments inside a multi-line expression move to just after the
+//   statement. No comment is ever dropped.
+
+use std::collections::HashMap;
 
 use crate::BackoffConfig;
 use crate::Node;
 use crate::Pattern;
+use crate::Token;
 
-/// Canonical indent width, in spaces.
-const INDENT: &str = "    ";
+/// Canonical indent width, in spaces. A project can pick another
+/// with `[fmt] indent` in `resilient.toml`.
+pub const DEFAULT_INDENT: usize = 4;
 
 /// RES-4032: some parser desugarings (array comprehensions RES-156,
 /// tuple-destructuring for-loops) synthesize hygienic binder names
@@ -52,12 +59,162 @@ fn sanitize_ident(name: &str) -> std::borrow::Cow<'_, str> {
     }
 }
 
+/// A 1-based source line and column.
+type Position = (usize, usize);
+
+/// A comment from the source, held back while the AST is printed.
+struct Comment {
+    /// Where the comment starts.
+    start: Position,
+    /// The comment as written, `//` or `/*` included.
+    text: String,
+    /// Code comes before it on its line.
+    trailing: bool,
+    /// Code follows it on the line it ends on.
+    code_after: bool,
+    /// A blank line follows it.
+    blank_after: bool,
+}
+
+/// The comments in `src`, in order, and the position of each block's
+/// closing `}`, keyed by the block's position.
+///
+/// Comments are read from the gaps between tokens, so comment markers
+/// inside string literals are never mistaken for comments.
+fn collect_comments(src: &str) -> (Vec<Comment>, HashMap<Position, Position>) {
+    let chars: Vec<char> = src.chars().collect();
+    let newlines: Vec<usize> = (0..chars.len()).filter(|&i| chars[i] == '\n').collect();
+    let position_at = |offset: usize| {
+        let line = newlines.partition_point(|&nl| nl < offset);
+        let line_start = if line == 0 { 0 } else { newlines[line - 1] + 1 };
+        (line + 1, offset - line_start + 1)
+    };
+    let mut comments = Vec::new();
+    let mut scan_gap = |from: usize, to: usize| {
+        let mut i = from;
+        while i + 1 < to {
+            if chars[i] != '/' || (chars[i + 1] != '/' && chars[i + 1] != '*') {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            if chars[i + 1] == '/' {
+                while i < to && chars[i] != '\n' {
+                    i += 1;
+                }
+            } else {
+                // Block comments nest, as in the lexer.
+                let mut depth = 0usize;
+                while i < to {
+                    if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                        depth += 1;
+                        i += 2;
+                    } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let trailing = chars[..start]
+                .iter()
+                .rev()
+                .take_while(|c| **c != '\n')
+                .any(|c| !c.is_whitespace());
+            // The rest of the comment's line, then a whole empty line.
+            let mut lines = chars[i..to].split(|c| *c == '\n');
+            let mut blank = || {
+                lines
+                    .next()
+                    .is_some_and(|l| l.iter().all(|c| c.is_whitespace()))
+            };
+            let code_after = chars[i..to]
+                .iter()
+                .take_while(|c| **c != '\n')
+                .any(|c| !c.is_whitespace())
+                || (to < chars.len() && !chars[i..to].contains(&'\n'));
+            let blank_after = blank() && blank() && lines.next().is_some();
+            comments.push(Comment {
+                start: position_at(start),
+                text: text.trim_end().to_string(),
+                trailing,
+                code_after,
+                blank_after,
+            });
+        }
+    };
+
+    // A block's span starts at its `{`, so that is the key.
+    let mut block_ends = HashMap::new();
+    let mut open_braces: Vec<Position> = Vec::new();
+    let mut lexer = crate::Lexer::new(src);
+    let mut gap_start = 0;
+    loop {
+        let (token, span) = lexer.next_token_with_span();
+        if token == Token::Eof {
+            break;
+        }
+        scan_gap(gap_start, span.start.offset.min(chars.len()));
+        gap_start = span.end.offset.min(chars.len()).max(gap_start);
+        match token {
+            Token::LeftBrace => open_braces.push((span.start.line, span.start.column)),
+            Token::RightBrace => {
+                if let Some(key) = open_braces.pop() {
+                    block_ends.insert(key, (span.start.line, span.start.column));
+                }
+            }
+            _ => {}
+        }
+    }
+    scan_gap(gap_start, chars.len());
+    (comments, block_ends)
+}
+
+/// Where a statement starts in the source, when the parser recorded it.
+fn stmt_start(node: &Node) -> Option<Position> {
+    let span = match node {
+        Node::LetStatement { span, .. }
+        | Node::StaticLet { span, .. }
+        | Node::Const { span, .. }
+        | Node::Assignment { span, .. }
+        | Node::ReturnStatement { span, .. }
+        | Node::Break { span }
+        | Node::Continue { span }
+        | Node::BreakLabel { span, .. }
+        | Node::ContinueLabel { span, .. }
+        | Node::IfStatement { span, .. }
+        | Node::WhileStatement { span, .. }
+        | Node::ForInStatement { span, .. }
+        | Node::Block { span, .. }
+        | Node::Function { span, .. }
+        | Node::FieldAssignment { span, .. }
+        | Node::Match { span, .. }
+        | Node::LiveBlock { span, .. }
+        | Node::Assert { span, .. }
+        | Node::ExpressionStatement { span, .. } => span,
+        _ => return None,
+    };
+    (span.start.line > 0).then_some((span.start.line, span.start.column))
+}
+
 pub struct Formatter {
     out: String,
     depth: usize,
+    /// One level of indentation.
+    indent: String,
     /// Tracks whether we just wrote a newline so we can apply the
     /// "no trailing whitespace" rule at line boundaries.
     at_line_start: bool,
+    /// Source comments still to be written, in order.
+    comments: Vec<Comment>,
+    next_comment: usize,
+    /// Position of each block's `}`, keyed by the block's span start.
+    block_ends: HashMap<Position, Position>,
 }
 
 impl Formatter {
@@ -65,23 +222,45 @@ impl Formatter {
         Self {
             out: String::new(),
             depth: 0,
+            indent: " ".repeat(DEFAULT_INDENT),
             at_line_start: true,
+            comments: Vec::new(),
+            next_comment: 0,
+            block_ends: HashMap::new(),
         }
     }
 
     /// Entry point. Formats a `Node::Program` (or any top-level
     /// statement) into a canonical-style string.
     pub fn format(program: &Node) -> String {
+        Self::format_with_indent(program, DEFAULT_INDENT)
+    }
+
+    /// [`Formatter::format`] with `indent` spaces per level.
+    pub fn format_with_indent(program: &Node, indent: usize) -> String {
+        Self::new().run(program, indent)
+    }
+
+    /// Format `program`, parsed from `src`, keeping `src`'s comments.
+    /// This is what `rz fmt` uses.
+    pub fn format_source(src: &str, program: &Node, indent: usize) -> String {
         let mut f = Self::new();
-        f.fmt_program(program);
+        (f.comments, f.block_ends) = collect_comments(src);
+        f.run(program, indent)
+    }
+
+    fn run(mut self, program: &Node, indent: usize) -> String {
+        self.indent = " ".repeat(indent);
+        self.fmt_program(program);
+        self.write_comments_before((usize::MAX, 0), false);
         // Ensure trailing newline; strip any accidental duplicate.
-        while f.out.ends_with("\n\n") {
-            f.out.pop();
+        while self.out.ends_with("\n\n") {
+            self.out.pop();
         }
-        if !f.out.ends_with('\n') {
-            f.out.push('\n');
+        if !self.out.ends_with('\n') {
+            self.out.push('\n');
         }
-        f.out
+        self.out
     }
 
     // ------------------------------------------------------------------
@@ -91,7 +270,7 @@ impl Formatter {
     fn write(&mut self, s: &str) {
         if self.at_line_start && !s.is_empty() {
             for _ in 0..self.depth {
-                self.out.push_str(INDENT);
+                self.out.push_str(&self.indent);
             }
             self.at_line_start = false;
         }
@@ -110,7 +289,7 @@ impl Formatter {
         use std::fmt::Write as _;
         if self.at_line_start {
             for _ in 0..self.depth {
-                self.out.push_str(INDENT);
+                self.out.push_str(&self.indent);
             }
             self.at_line_start = false;
         }
@@ -147,6 +326,52 @@ impl Formatter {
         }
     }
 
+    /// Write the held-back comments that start before source position
+    /// `pos`. The first one goes back at the end of the last line
+    /// written if it followed code in the source, and so do the block
+    /// comments after it on its line; the rest get lines of their own.
+    /// Before a statement (`before_stmt`), one with code after it on
+    /// its line stays in front of the statement, and a blank line after
+    /// one is kept if the source had one.
+    fn write_comments_before(&mut self, pos: Position, before_stmt: bool) {
+        let first = self.next_comment;
+        // The source line of the last comment put at the end of a line,
+        // while another may still follow it there.
+        let mut appending = None;
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.start >= pos {
+                break;
+            }
+            self.next_comment += 1;
+            let (text, line) = (comment.text.clone(), comment.start.0);
+            let (trailing, code_after, blank_after) =
+                (comment.trailing, comment.code_after, comment.blank_after);
+            let last_line = self.out.trim_end_matches('\n').len();
+            let appends = trailing
+                && last_line > 0
+                && (self.next_comment - 1 == first || appending == Some(line));
+            if appends {
+                self.out.insert_str(last_line, &format!(" {}", text));
+                appending = text.starts_with("/*").then_some(line);
+                continue;
+            }
+            appending = None;
+            if before_stmt && code_after {
+                self.write(&text);
+                self.write(" ");
+                continue;
+            }
+            if !self.at_line_start {
+                self.newline();
+            }
+            self.write(&text);
+            self.newline();
+            if before_stmt && blank_after {
+                self.blank_line();
+            }
+        }
+    }
+
     // ------------------------------------------------------------------
     // top-level program
     // ------------------------------------------------------------------
@@ -158,6 +383,9 @@ impl Formatter {
                     if i > 0 {
                         self.blank_line();
                     }
+                    if s.span.start.line > 0 {
+                        self.write_comments_before((s.span.start.line, s.span.start.column), true);
+                    }
                     self.fmt_stmt(&s.node);
                     if !self.out.ends_with('\n') {
                         self.newline();
@@ -209,8 +437,9 @@ impl Formatter {
                 return_type,
                 ..
             } => {
+                // Overloads are stored under their mangled names.
                 self.fmt_function(
-                    Some(name),
+                    Some(crate::overloads::base_name(name)),
                     parameters,
                     return_type.as_deref(),
                     requires,
@@ -366,6 +595,10 @@ impl Formatter {
                 self.indent();
                 for (i, v) in variants.iter().enumerate() {
                     self.write(&v.name);
+                    if let Some(d) = &v.discriminant {
+                        self.write(" = ");
+                        self.fmt_expr(d);
+                    }
                     if i + 1 < variants.len() {
                         self.write(",");
                     }
@@ -564,12 +797,21 @@ impl Formatter {
                 self.write(";");
                 self.newline();
             }
+            // `bench fn name() { ... }`
+            Node::BenchBlock { body, .. } if matches!(body.as_ref(), Node::Function { .. }) => {
+                self.write("bench ");
+                self.fmt_stmt(body);
+            }
             Node::BenchBlock { name, body, .. } => {
                 self.write("bench \"");
                 self.write(name);
                 self.write("\" ");
                 self.fmt_stmt(body);
             }
+            Node::TestFn { function, .. } => {
+                self.write("test ");
+                self.fmt_stmt(function);
+            }
             Node::LetDestructureStruct {
                 struct_name,
                 fields,
@@ -906,13 +1148,19 @@ impl Formatter {
     /// statement in `{ ... }`.
     fn fmt_block_like(&mut self, node: &Node) {
         match node {
-            Node::Block { stmts, .. } => {
+            Node::Block { stmts, span } => {
                 self.write("{");
                 self.newline();
                 self.indent();
                 for s in stmts {
+                    if let Some(start) = stmt_start(s) {
+                        self.write_comments_before(start, true);
+                    }
                     self.fmt_stmt(s);
                 }
+                if let Some(&end) = self.block_ends.get(&(span.start.line, span.start.column)) {
+                    self.write_comments_before(end, false);
+                }
                 self.dedent();
                 self.write("}");
             }
@@ -951,7 +1199,12 @@ impl Formatter {
             if i > 0 {
                 self.write(", ");
             }
-            self.write_args(format_args!("{} {}", ty, pname));
+            // Untyped parameter (`fn id(x)`).
+            if ty.is_empty() {
+                self.write(pname);
+            } else {
+                self.write_args(format_args!("{} {}", ty, pname));
+            }
         }
         self.write(")");
         if let Some(rt) = return_type {
@@ -1446,6 +1699,7 @@ impl Formatter {
             | Node::StaticAssert { .. }
             | Node::DeferStatement { .. }
             | Node::BenchBlock { .. }
+            | Node::TestFn { .. }
             | Node::Program(_) => {
                 self.fmt_stmt(node);
             }
@@ -1908,6 +2162,16 @@ main();
         assert_eq!(out, expected);
     }
 
+    /// Golden: an untyped parameter prints without a type.
+    #[test]
+    fn fmt_untyped_param() {
+        let src = "fn pick(a, int b) { return a; }";
+        let (program, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let out = Formatter::format(&program);
+        assert_eq!(out, "fn pick(a, int b) {\n    return a;\n}\n");
+    }
+
     /// Golden: let binding + if/else + return.
     #[test]
     fn fmt_let_if_return() {
@@ -2375,6 +2639,19 @@ struct Point {
         assert!(out.contains("enum Color"), "enum must appear: {out}");
     }
 
+    #[test]
+    fn fmt_enum_discriminants() {
+        let src = "enum Level { Low = 1, Mid, High = BASE * 4 }";
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let out = Formatter::format(&prog);
+        assert!(out.contains("Low = 1,"), "discriminant must survive: {out}");
+        assert!(
+            out.contains("High = BASE * 4"),
+            "discriminant must survive: {out}"
+        );
+    }
+
     #[test]
     fn fmt_try_catch() {
         let src = "fn f(int x) -> int { try { return x; } catch e { return 0; } }";
@@ -2416,9 +2693,49 @@ struct Point {
         assert!(out.contains("(1"), "tuple must appear: {out}");
     }
 
+    #[test]
+    fn comments_stay_where_they_were_written() {
+        let src = "// About f.\n\nfn f(int n) -> int {\n  // leading\n  \
+                   let s = \"not // a comment\"; // trailing\n  if n > 0 { // why\n    \
+                   return n;\n  } /* after if */\n  // before close\n}\nf(1); // done\n";
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let out = Formatter::format_source(src, &prog, DEFAULT_INDENT);
+        assert_eq!(
+            out,
+            "// About f.\n\nfn f(int n) -> int {\n    // leading\n    \
+             let s = \"not // a comment\"; // trailing\n    if n > 0 { // why\n        \
+             return n;\n    } /* after if */\n    // before close\n}\n\nf(1); // done\n"
+        );
+        // Without the source there are no comments to keep.
+        assert!(!Formatter::format(&prog).contains("// why"));
+    }
+
+    #[test]
+    fn inline_comments_keep_their_line() {
+        let src = "/* top */ let x = 1; /* a */ // b\n\
+                   fn f(int a) -> int {\n  /* block */ return a+1; // end\n}\n\
+                   let y = f(x); /* one */\n/* two */\n";
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let once = Formatter::format_source(src, &prog, DEFAULT_INDENT);
+        assert_eq!(
+            once,
+            "/* top */ let x = 1; /* a */ // b\n\n\
+             fn f(int a) -> int {\n    /* block */ return a + 1; // end\n}\n\n\
+             let y = f(x); /* one */\n/* two */\n"
+        );
+        let (reparsed, errs) = parse(&once);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        assert_eq!(
+            Formatter::format_source(&once, &reparsed, DEFAULT_INDENT),
+            once
+        );
+    }
+
     /// RES-4032 (E-E5): idempotence across the example corpus —
-    /// `fmt(fmt(x)) == fmt(x)` for every `resilient/examples/*.rz` file
-    /// that parses cleanly. This is the TOOLING_QUALITY.md
+    /// `fmt(fmt(x)) == fmt(x)`, comments included, for every
+    /// `resilient/examples/*.rz` file that parses cleanly. This is the TOOLING_QUALITY.md
     /// "Roundtrip Safety" / "Idempotence" acceptance criterion.
     ///
     /// Doubles as a perf sanity check: TOOLING_QUALITY.md sets a
@@ -2450,7 +2767,7 @@ struct Point {
                 continue;
             }
 
-            let once = Formatter::format(&program);
+            let once = Formatter::format_source(&src, &program, DEFAULT_INDENT);
             let (reparsed, reparse_errs) = parse(&once);
             assert!(
                 reparse_errs.is_empty(),
@@ -2458,7 +2775,14 @@ struct Point {
                 path.display(),
                 reparse_errs
             );
-            let twice = Formatter::format(&reparsed);
+            let comments = |text: &str| collect_comments(text).0.len();
+            assert_eq!(
+                comments(&src),
+                comments(&once),
+                "{}: formatting lost or added a comment",
+                path.display()
+            );
+            let twice = Formatter::format_source(&once, &reparsed, DEFAULT_INDENT);
             assert_eq!(
                 once,
                 twice,
@@ -81,8 +81,8 @@ fn walk(node: &Node, bound: &mut BTreeSet<String>, free: &mut BTreeSet<String>)
     match node {
         // ---- Leaves ----
         Node::Identifier { name, .. } => {
-            if !bound.contains(name) {
-                free.insert(name.clone());
+            if !bound.contains(name.as_str()) {
+                free.insert(name.to_string());
             }
         }
         Node::IntegerLiteral { .. }
@@ -185,7 +185,7 @@ fn walk(node: &Node, bound: &mut BTreeSet<String>, free: &mut BTreeSet<String>)
             for (_, param_name) in parameters {
                 bound.insert(param_name.clone());
             }
-            for clause in requires {
+            for clause in requires.iter() {
                 walk(clause, bound, free);
             }
             walk(body, bound, free);
@@ -193,7 +193,7 @@ fn walk(node: &Node, bound: &mut BTreeSet<String>, free: &mut BTreeSet<String>)
             // clauses — treat it as bound for those walks.
             let pre_ensures = bound.len();
             bound.insert("result".into());
-            for clause in ensures {
+            for clause in ensures.iter() {
                 walk(clause, bound, free);
             }
             // RES-392: `recovers_to` binds in the same env as
@@ -216,13 +216,13 @@ fn walk(node: &Node, bound: &mut BTreeSet<String>, free: &mut BTreeSet<String>)
             for (_, param_name) in parameters {
                 bound.insert(param_name.clone());
             }
-            for clause in requires {
+            for clause in requires.iter() {
                 walk(clause, bound, free);
             }
             walk(body, bound, free);
             let pre_ensures = bound.len();
             bound.insert("result".into());
-            for clause in ensures {
+            for clause in ensures.iter() {
                 walk(clause, bound, free);
             }
             if let Some(rec) = recovers_to {
@@ -533,6 +533,7 @@ fn walk(node: &Node, bound: &mut BTreeSet<String>, free: &mut BTreeSet<String>)
         Node::DeferStatement { expr, .. } => walk(expr, bound, free),
         // RES-2613: bench block — walk the body for free vars.
         Node::BenchBlock { body, .. } => walk(body, bound, free),
+        Node::TestFn { function, .. } => walk(function, bound, free),
     }
 }
 
@@ -759,7 +760,7 @@ mod tests {
         // `fn(x) { return x + n; }` — `n` is free.
         let lit = Node::FunctionLiteral {
             parameters: vec![("int".into(), "x".into())],
-            body: Box::new(Node::Block {
+            body: std::sync::Arc::new(Node::Block {
                 stmts: vec![Node::ReturnStatement {
                     value: Some(Box::new(Node::InfixExpression {
                         left: Box::new(ident("x")),
@@ -771,8 +772,8 @@ mod tests {
                 }],
                 span: Span::default(),
             }),
-            requires: Vec::new(),
-            ensures: Vec::new(),
+            requires: std::sync::Arc::default(),
+            ensures: std::sync::Arc::default(),
             recovers_to: None,
             return_type: None,
             span: Span::default(),
@@ -786,15 +787,15 @@ mod tests {
         // `fn(x) { return x; }` — nothing free.
         let lit = Node::FunctionLiteral {
             parameters: vec![("int".into(), "x".into())],
-            body: Box::new(Node::Block {
+            body: std::sync::Arc::new(Node::Block {
                 stmts: vec![Node::ReturnStatement {
                     value: Some(Box::new(ident("x"))),
                     span: Span::default(),
                 }],
                 span: Span::default(),
             }),
-            requires: Vec::new(),
-            ensures: Vec::new(),
+            requires: std::sync::Arc::default(),
+            ensures: std::sync::Arc::default(),
             recovers_to: None,
             return_type: None,
             span: Span::default(),
@@ -808,7 +809,7 @@ mod tests {
         // `fn(x) { return x + a + b; }` — `a`, `b` free.
         let lit = Node::FunctionLiteral {
             parameters: vec![("int".into(), "x".into())],
-            body: Box::new(Node::Block {
+            body: std::sync::Arc::new(Node::Block {
                 stmts: vec![Node::ReturnStatement {
                     value: Some(Box::new(Node::InfixExpression {
                         left: Box::new(Node::InfixExpression {
@@ -825,8 +826,8 @@ mod tests {
                 }],
                 span: Span::default(),
             }),
-            requires: Vec::new(),
-            ensures: Vec::new(),
+            requires: std::sync::Arc::default(),
+            ensures: std::sync::Arc::default(),
             recovers_to: None,
             return_type: None,
             span: Span::default(),
@@ -900,7 +901,7 @@ mod tests {
         // Outer fn binds `a`; inner fn binds `b`; `c` is free.
         let inner = Node::FunctionLiteral {
             parameters: vec![("int".into(), "b".into())],
-            body: Box::new(Node::Block {
+            body: std::sync::Arc::new(Node::Block {
                 stmts: vec![Node::ReturnStatement {
                     value: Some(Box::new(Node::InfixExpression {
                         left: Box::new(Node::InfixExpression {
@@ -917,8 +918,8 @@ mod tests {
                 }],
                 span: Span::default(),
             }),
-            requires: Vec::new(),
-            ensures: Vec::new(),
+            requires: std::sync::Arc::default(),
+            ensures: std::sync::Arc::default(),
             recovers_to: None,
             return_type: None,
             span: Span::default(),
@@ -926,15 +927,15 @@ mod tests {
         };
         let outer = Node::FunctionLiteral {
             parameters: vec![("int".into(), "a".into())],
-            body: Box::new(Node::Block {
+            body: std::sync::Arc::new(Node::Block {
                 stmts: vec![Node::ReturnStatement {
                     value: Some(Box::new(inner)),
                     span: Span::default(),
                 }],
                 span: Span::default(),
             }),
-            requires: Vec::new(),
-            ensures: Vec::new(),
+            requires: std::sync::Arc::default(),
+            ensures: std::sync::Arc::default(),
             recovers_to: None,
             return_type: None,
             span: Span::default(),
@@ -0,0 +1,248 @@
+//! Filesystem sandbox and the whole-file I/O builtins — `read_file`,
+//! `write_file`, `append_file` — built on it.
+//!
+//! `--fs <policy>` on `rz` selects what a program may touch:
+//!
+//! - `read-write` (default) — the CLI's ambient authority, as before;
+//! - `read-only` — reads and existence checks only; every write or
+//!   append is denied;
+//! - `none` — no filesystem access at all.
+//!
+//! The policy also covers `file_read`, `file_write`, `file_open`, and
+//! `file_exists`, so the older builtins are not a way around it.
+//!
+//! Failures are typed. A missing file fails with `NotFound`; a
+//! denial — by the policy or by the OS — with `PermissionDenied`. The
+//! variant is the second field of the message
+//! (`read_file: NotFound: data.txt: ...`), which is what
+//! `try { ... } catch NotFound { ... }` matches (see
+//! `builtin_failure_variant`). Like any runtime
+//! error, both also make a `live` block retry. One process-wide
+//! setting chosen by the driver — the same pattern as `coercion`.
+
+use crate::sandbox::{self, Capability};
+use crate::{RResult, Value};
+use std::io;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsPolicy {
+    ReadWrite,
+    ReadOnly,
+    None,
+}
+
+impl FsPolicy {
+    /// Parse an `--fs` argument.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "read-write" => Some(FsPolicy::ReadWrite),
+            "read-only" => Some(FsPolicy::ReadOnly),
+            "none" => Some(FsPolicy::None),
+            _ => None,
+        }
+    }
+
+    fn allows(self, write: bool) -> bool {
+        match self {
+            FsPolicy::ReadWrite => true,
+            FsPolicy::ReadOnly => !write,
+            FsPolicy::None => false,
+        }
+    }
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(0);
+
+/// Select the process-wide policy. Called from the CLI dispatcher.
+pub fn set_policy(policy: FsPolicy) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// The process-wide policy.
+pub fn policy() -> FsPolicy {
+    match POLICY.load(Ordering::Relaxed) {
+        1 => FsPolicy::ReadOnly,
+        2 => FsPolicy::None,
+        _ => FsPolicy::ReadWrite,
+    }
+}
+
+/// Fail with `PermissionDenied` unless both `--allow` (`sandbox`) and
+/// the policy let `builtin` read (or, with `write`, modify) `path`.
+pub(crate) fn check(builtin: &str, path: &str, write: bool) -> RResult<()> {
+    let capability = if write {
+        Capability::FsWrite
+    } else {
+        Capability::FsRead
+    };
+    sandbox::require(builtin, capability, path)?;
+    check_policy(builtin, path, write)
+}
+
+/// The `--fs` half of [`check`], for builtins that open device files
+/// under a capability of their own (`serial_open`).
+pub(crate) fn check_policy(builtin: &str, path: &str, write: bool) -> RResult<()> {
+    let policy = policy();
+    if policy.allows(write) {
+        return Ok(());
+    }
+    let flag = match policy {
+        FsPolicy::ReadOnly => "read-only",
+        _ => "none",
+    };
+    Err(format!(
+        "{}: PermissionDenied: {}: denied by --fs {}",
+        builtin, path, flag
+    ))
+}
+
+/// The error for an OS failure on `path`, typed when the kind has a
+/// variant.
+fn io_failure(builtin: &str, path: &str, e: &io::Error) -> String {
+    match e.kind() {
+        io::ErrorKind::NotFound => format!("{}: NotFound: {}: {}", builtin, path, e),
+        io::ErrorKind::PermissionDenied => {
+            format!("{}: PermissionDenied: {}: {}", builtin, path, e)
+        }
+        _ if not_capable(e) => format!("{}: PermissionDenied: {}: {}", builtin, path, e),
+        _ => format!("{}: {}: {}", builtin, path, e),
+    }
+}
+
+/// WASI's `ENOTCAPABLE`: the runtime granted the directory but not
+/// the right asked for. std leaves it uncategorized; it is a denial.
+#[cfg(target_os = "wasi")]
+fn not_capable(e: &io::Error) -> bool {
+    const ENOTCAPABLE: i32 = 76;
+    e.raw_os_error() == Some(ENOTCAPABLE)
+}
+
+#[cfg(not(target_os = "wasi"))]
+fn not_capable(_e: &io::Error) -> bool {
+    false
+}
+
+/// `read_file(path: String) -> String` — the whole file as UTF-8.
+pub(crate) fn builtin_read_file(args: &[Value]) -> RResult<Value> {
+    let path = match args {
+        [Value::String(path)] => path,
+        [other] => return Err(format!("read_file: expected String path, got {}", other)),
+        _ => {
+            return Err(format!(
+                "read_file: expected 1 argument, got {}",
+                args.len()
+            ));
+        }
+    };
+    check("read_file", path, false)?;
+    #[cfg(not(wasm_web))]
+    let read = std::fs::read(path);
+    #[cfg(wasm_web)]
+    let read = crate::file_io::vfs_read(path);
+    let bytes = read.map_err(|e| io_failure("read_file", path, &e))?;
+    String::from_utf8(bytes)
+        .map(Value::String)
+        .map_err(|_| format!("read_file: {}: not valid UTF-8", path))
+}
+
+/// `write_file(path: String, contents: String)` — create or truncate.
+pub(crate) fn builtin_write_file(args: &[Value]) -> RResult<Value> {
+    let (path, contents) = path_and_contents("write_file", args)?;
+    check("write_file", path, true)?;
+    #[cfg(not(wasm_web))]
+    std::fs::write(path, contents).map_err(|e| io_failure("write_file", path, &e))?;
+    #[cfg(wasm_web)]
+    crate::file_io::vfs_write(path, contents.as_bytes());
+    Ok(Value::Void)
+}
+
+/// `append_file(path: String, contents: String)` — append, creating
+/// the file when it is missing.
+pub(crate) fn builtin_append_file(args: &[Value]) -> RResult<Value> {
+    let (path, contents) = path_and_contents("append_file", args)?;
+    check("append_file", path, true)?;
+    #[cfg(not(wasm_web))]
+    {
+        use std::io::Write;
+        std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+            .and_then(|mut f| f.write_all(contents.as_bytes()))
+            .map_err(|e| io_failure("append_file", path, &e))?;
+    }
+    #[cfg(wasm_web)]
+    {
+        let mut bytes = crate::file_io::vfs_read(path).unwrap_or_default();
+        bytes.extend_from_slice(contents.as_bytes());
+        crate::file_io::vfs_write(path, &bytes);
+    }
+    Ok(Value::Void)
+}
+
+fn path_and_contents<'a>(builtin: &str, args: &'a [Value]) -> RResult<(&'a str, &'a str)> {
+    match args {
+        [Value::String(path), Value::String(contents)] => Ok((path, contents)),
+        [a, b] => Err(format!(
+            "{}: expected (String, String), got ({}, {})",
+            builtin, a, b
+        )),
+        _ => Err(format!(
+            "{}: expected 2 arguments, got {}",
+            builtin,
+            args.len()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        let mut p = std::env::temp_dir();
+        p.push(format!(
+            "resilient-fs-sandbox-{}-{}",
+            name,
+            std::process::id()
+        ));
+        p.to_string_lossy().into_owned()
+    }
+
+    fn s(v: &str) -> Value {
+        Value::String(v.to_string())
+    }
+
+    #[test]
+    fn policies_parse_and_gate_writes() {
+        assert_eq!(FsPolicy::parse("READ-ONLY"), Some(FsPolicy::ReadOnly));
+        assert_eq!(FsPolicy::parse("ro"), None);
+        assert!(FsPolicy::ReadOnly.allows(false));
+        assert!(!FsPolicy::ReadOnly.allows(true));
+        assert!(!FsPolicy::None.allows(false));
+    }
+
+    #[test]
+    fn write_append_read_round_trip() {
+        let path = temp_path("round-trip");
+        builtin_write_file(&[s(&path), s("ab")]).unwrap();
+        builtin_append_file(&[s(&path), s("cd")]).unwrap();
+        match builtin_read_file(&[s(&path)]).unwrap() {
+            Value::String(text) => assert_eq!(text, "abcd"),
+            other => panic!("expected String, got {:?}", other),
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn missing_file_is_not_found() {
+        let err = builtin_read_file(&[s(&temp_path("missing"))]).unwrap_err();
+        assert_eq!(
+            crate::builtin_failure_variant(&err),
+            Some("NotFound"),
+            "got: {}",
+            err
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Execution fuel: an optional cap on how many steps a run may take.
+//!
+//! The tree-walking interpreter charges one step per evaluated node
+//! and the bytecode VM one per dispatched instruction. Once a run
+//! spends more than its budget, the next charge fails with a
+//! `ResourceExhausted` error that `live` blocks do not retry and
+//! `catch` does not handle, so a runaway loop ends the run instead
+//! of hanging it.
+//!
+//! The budget is per thread, like the other run-wide switches
+//! (`--panic-on-fault`, `--fs`): sub-interpreters created for calls
+//! draw from the same pool without any plumbing. `rz --max-steps N`
+//! sets it for the CLI and the REPL, where every input gets a fresh
+//! budget; embedders call [`set_max_steps`] before [`crate::run_program`].
+
+use std::cell::Cell;
+
+thread_local! {
+    static LIMIT: Cell<Option<u64>> = const { Cell::new(None) };
+    static USED: Cell<u64> = const { Cell::new(0) };
+}
+
+const RESOURCE_EXHAUSTED: &str = "ResourceExhausted:";
+
+/// Cap every later run on this thread at `limit` steps (`None`
+/// removes the cap). Also refills the budget.
+pub fn set_max_steps(limit: Option<u64>) {
+    LIMIT.with(|l| l.set(limit));
+    refill();
+}
+
+/// Whether a limit is set, so every step must be charged.
+#[cfg(feature = "jit")]
+pub(crate) fn is_limited() -> bool {
+    LIMIT.with(Cell::get).is_some()
+}
+
+/// Start a fresh budget, as at the beginning of a run.
+pub(crate) fn refill() {
+    USED.with(|u| u.set(0));
+}
+
+/// Spend one step. Free when no limit is set.
+#[inline]
+pub(crate) fn charge() -> Result<(), String> {
+    let Some(limit) = LIMIT.with(Cell::get) else {
+        return Ok(());
+    };
+    let used = USED.with(|u| {
+        let used = u.get().saturating_add(1);
+        u.set(used);
+        used
+    });
+    if used > limit {
+        return Err(exhausted(limit));
+    }
+    Ok(())
+}
+
+/// Run `f` and count the steps it took, with or without a limit set
+/// (an active limit still applies to `f`). Leaves a fresh budget.
+pub(crate) fn count_steps<T>(f: impl FnOnce() -> T) -> (T, u64) {
+    let limit = LIMIT.with(Cell::get);
+    // Charging only counts while some limit is set.
+    LIMIT.with(|l| l.set(Some(limit.unwrap_or(u64::MAX))));
+    refill();
+    let out = f();
+    let used = USED.with(Cell::get);
+    LIMIT.with(|l| l.set(limit));
+    refill();
+    (out, used)
+}
+
+/// Run `f` without charging its steps to the budget, for work the
+/// runtime does on the program's behalf (loading the prelude).
+pub(crate) fn uncharged<T>(f: impl FnOnce() -> T) -> T {
+    let limit = LIMIT.with(Cell::get);
+    LIMIT.with(|l| l.set(None));
+    let out = f();
+    LIMIT.with(|l| l.set(limit));
+    out
+}
+
+fn exhausted(limit: u64) -> String {
+    format!("{RESOURCE_EXHAUSTED} execution exceeded the limit of {limit} steps")
+}
+
+/// Whether `err` is a spent budget. Looks past the location prefix
+/// and stack trace the runtimes may add.
+pub(crate) fn is_exhausted(err: &str) -> bool {
+    err.contains(RESOURCE_EXHAUSTED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charges_until_the_limit_then_fails() {
+        set_max_steps(Some(3));
+        assert!((0..3).all(|_| charge().is_ok()));
+        let err = charge().unwrap_err();
+        assert!(is_exhausted(&err), "{err}");
+        assert!(err.contains("limit of 3 steps"), "{err}");
+        // Stays spent until refilled.
+        assert!(charge().is_err());
+        refill();
+        assert!(charge().is_ok());
+        set_max_steps(None);
+        assert!((0..10).all(|_| charge().is_ok()));
+    }
+
+    #[test]
+    fn count_steps_counts_without_a_limit() {
+        set_max_steps(None);
+        let ((), used) = count_steps(|| (0..5).for_each(|_| charge().unwrap()));
+        assert_eq!(used, 5);
+        assert_eq!(LIMIT.with(Cell::get), None);
+    }
+}
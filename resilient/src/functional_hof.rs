@@ -60,7 +60,7 @@ pub(crate) fn builtin_array_zip_with_fn(
             for (x, y) in a.iter().zip(b.iter()) {
                 out.push(interp.apply_function(f, vec![x.clone(), y.clone()])?);
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, _, _] if !matches!(a, Value::Array(_)) => Err(format!(
             "array_zip_with_fn: first argument must be Array, got {a}"
@@ -100,7 +100,7 @@ pub(crate) fn builtin_array_scan_fn(interp: &mut Interpreter, args: &[Value]) ->
                 acc = interp.apply_function(f, vec![acc, elem.clone()])?;
                 out.push(acc.clone());
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other, _, _] if !matches!(other, Value::Array(_)) => Err(format!(
             "array_scan_fn: first argument must be Array, got {other}"
@@ -142,7 +142,7 @@ pub(crate) fn builtin_array_flat_map_fn(
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other, _] if !matches!(other, Value::Array(_)) => Err(format!(
             "array_flat_map_fn: first argument must be Array, got {other}"
@@ -182,7 +182,7 @@ pub(crate) fn builtin_array_apply_n(interp: &mut Interpreter, args: &[Value]) ->
                 }
                 out.push(v);
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [Value::Array(_), _, n] => Err(format!(
             "array_apply_n: third argument must be int, got {n}"
@@ -198,6 +198,7 @@ pub fn specialize_variants(variants: &[EnumVariant], subst: &Subst) -> Vec<EnumV
             name: v.name.clone(),
             span: v.span,
             payload: substitute_payload(&v.payload, subst),
+            discriminant: v.discriminant.clone(),
         })
         .collect()
 }
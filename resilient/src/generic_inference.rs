@@ -147,10 +147,10 @@ fn check_node(node: &Node, sigs: &HashMap<String, GenericSig>) -> Result<(), Str
             ..
         } => {
             check_node(body, sigs)?;
-            for r in requires {
+            for r in requires.iter() {
                 check_node(r, sigs)?;
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 check_node(e, sigs)?;
             }
         }
@@ -88,7 +88,7 @@ fn walk_calls<'a>(node: &'a Node, ghosts: &HashSet<String>, out: &mut Vec<&'a st
             ..
         } => {
             if let Node::Identifier { name, .. } = function.as_ref() {
-                if ghosts.contains(name) {
+                if ghosts.contains(name.as_str()) {
                     out.push(name.as_str());
                 }
             }
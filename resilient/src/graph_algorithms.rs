@@ -112,7 +112,7 @@ pub(crate) fn builtin_graph_bfs(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(order))
+            Ok(Value::Array(order.into()))
         }
         _ => Err(format!(
             "graph_bfs: expected 2 arguments (adj, start), got {}",
@@ -142,7 +142,7 @@ pub(crate) fn builtin_graph_dfs(args: &[Value]) -> RResult<Value> {
             let mut visited = vec![false; n];
             let mut order = Vec::with_capacity(n);
             dfs_visit(&adj, start, &mut visited, &mut order);
-            Ok(Value::Array(order))
+            Ok(Value::Array(order.into()))
         }
         _ => Err(format!(
             "graph_dfs: expected 2 arguments (adj, start), got {}",
@@ -254,7 +254,7 @@ pub(crate) fn builtin_graph_topological_sort(args: &[Value]) -> RResult<Value> {
                     order.len()
                 ));
             }
-            Ok(Value::Array(order))
+            Ok(Value::Array(order.into()))
         }
         _ => Err(format!(
             "graph_topological_sort: expected 1 argument (adj), got {}",
@@ -318,7 +318,7 @@ pub(crate) fn builtin_graph_connected_components(args: &[Value]) -> RResult<Valu
             }
 
             let result: Vec<Value> = component.iter().map(|&c| Value::Int(c as i64)).collect();
-            Ok(Value::Array(result))
+            Ok(Value::Array(result.into()))
         }
         _ => Err(format!(
             "graph_connected_components: expected 1 argument (adj), got {}",
@@ -382,7 +382,7 @@ pub(crate) fn builtin_graph_dijkstra(args: &[Value]) -> RResult<Value> {
                     }
                 })
                 .collect();
-            Ok(Value::Array(result))
+            Ok(Value::Array(result.into()))
         }
         _ => Err(format!(
             "graph_dijkstra: expected 2 arguments (adj, start), got {}",
@@ -508,7 +508,7 @@ pub(crate) fn builtin_graph_out_degrees(args: &[Value]) -> RResult<Value> {
                 .iter()
                 .map(|neighbours| Value::Int(neighbours.len() as i64))
                 .collect();
-            Ok(Value::Array(result))
+            Ok(Value::Array(result.into()))
         }
         _ => Err(format!(
             "graph_out_degrees: expected 1 argument (adj), got {}",
@@ -532,7 +532,7 @@ pub(crate) fn builtin_graph_in_degrees(args: &[Value]) -> RResult<Value> {
                 }
             }
             let result: Vec<Value> = in_deg.iter().map(|&d| Value::Int(d)).collect();
-            Ok(Value::Array(result))
+            Ok(Value::Array(result.into()))
         }
         _ => Err(format!(
             "graph_in_degrees: expected 1 argument (adj), got {}",
@@ -572,7 +572,7 @@ pub(crate) fn builtin_graph_reverse(args: &[Value]) -> RResult<Value> {
                 .into_iter()
                 .map(|nbrs| Value::Array(nbrs.into_iter().map(Value::Int).collect()))
                 .collect();
-            Ok(Value::Array(result))
+            Ok(Value::Array(result.into()))
         }
         _ => Err(format!(
             "graph_reverse: expected 1 argument (adj), got {}",
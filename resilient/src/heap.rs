@@ -126,7 +126,7 @@ pub(crate) fn builtin_heap_new(args: &[Value]) -> RResult<Value> {
             args.len()
         ));
     }
-    Ok(Value::Array(pack(false, Vec::new())))
+    Ok(Value::Array(pack(false, Vec::new()).into()))
 }
 
 /// `heap_new_max() → Array` — empty max-heap.
@@ -137,7 +137,7 @@ pub(crate) fn builtin_heap_new_max(args: &[Value]) -> RResult<Value> {
             args.len()
         ));
     }
-    Ok(Value::Array(pack(true, Vec::new())))
+    Ok(Value::Array(pack(true, Vec::new()).into()))
 }
 
 /// `heap_push(h, val) → Array` — insert val; O(log n).
@@ -149,7 +149,7 @@ pub(crate) fn builtin_heap_push(args: &[Value]) -> RResult<Value> {
             new_elems.push(val.clone());
             let last = new_elems.len() - 1;
             sift_up(&mut new_elems, last, is_max)?;
-            Ok(Value::Array(pack(is_max, new_elems)))
+            Ok(Value::Array(pack(is_max, new_elems).into()))
         }
         [other, _] => Err(format!("heap_push: expected Array, got {other}")),
         _ => Err(format!(
@@ -167,7 +167,7 @@ pub(crate) fn builtin_heap_pop(args: &[Value]) -> RResult<Value> {
             if elems.is_empty() {
                 return Ok(Value::Tuple(vec![
                     Value::Option(None),
-                    Value::Array(pack(is_max, Vec::new())),
+                    Value::Array(pack(is_max, Vec::new()).into()),
                 ]));
             }
             let mut new_elems = elems.to_vec();
@@ -180,7 +180,7 @@ pub(crate) fn builtin_heap_pop(args: &[Value]) -> RResult<Value> {
             }
             Ok(Value::Tuple(vec![
                 Value::Option(Some(Box::new(top))),
-                Value::Array(pack(is_max, new_elems)),
+                Value::Array(pack(is_max, new_elems).into()),
             ]))
         }
         [other] => Err(format!("heap_pop: expected Array, got {other}")),
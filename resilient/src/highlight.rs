@@ -0,0 +1,351 @@
+//! Classified source tokens for editor highlighting.
+//!
+//! [`highlight`] lexes a file and tags every token an editor would
+//! color with its kind and source span. Keywords, literals and
+//! operators come straight from the lexer. Identifiers are classified
+//! by their neighbours (`fn f`, `struct S`, `let x`, `f(...)`) and by the
+//! names the parsed program declares, so a struct name is a type and a
+//! `fn` passed as a value is a function wherever it appears. Comments,
+//! which the lexer skips, are recovered from the gaps between tokens.
+//!
+//! The LSP semantic-tokens endpoint encodes this list
+//! (`collect_semantic_tokens` in `lib.rs`); other editors can call it
+//! directly.
+//!
+//! ```
+//! use resilient::highlight::{HighlightKind, highlight};
+//!
+//! let tokens = highlight("fn f(int x) requires x > 0 { return x; } // done\n");
+//! let kinds: Vec<HighlightKind> = tokens.iter().map(|t| t.kind).collect();
+//! assert_eq!(kinds[..3], [HighlightKind::Keyword, HighlightKind::Function, HighlightKind::Type]);
+//! assert!(kinds.contains(&HighlightKind::ContractKeyword));
+//! assert_eq!(kinds.last(), Some(&HighlightKind::Comment));
+//! ```
+
+use std::collections::HashSet;
+
+use crate::span::{Pos, Span};
+use crate::{Lexer, Node, Parser, Token};
+
+/// What a highlighted token is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightKind {
+    Keyword,
+    /// `requires`, `ensures`, `invariant`, `assert`, ... — the
+    /// verification vocabulary.
+    ContractKeyword,
+    Function,
+    Type,
+    Variable,
+    /// String, bytes and char literals.
+    String,
+    Number,
+    Comment,
+    Operator,
+}
+
+/// One classified token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighlightToken {
+    pub kind: HighlightKind,
+    /// Source span; `offset`s count chars and `end` is exclusive.
+    /// Block comments and triple-quoted strings can cover several lines.
+    pub span: Span,
+    /// The token is the name being declared (`fn f`, `struct S`, `let x`).
+    pub declaration: bool,
+}
+
+/// Built-in type names, as the typechecker accepts them.
+const BUILTIN_TYPES: &[&str] = &[
+    "int", "float", "bool", "boolean", "string", "str", "bytes", "byte", "char", "void", "long",
+    "double", "i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "f32", "f64", "int8", "int16",
+    "int32", "uint8", "uint16", "uint32", "uint64", "array", "Int", "Int8", "Int16", "Int32",
+    "Int64", "UInt8", "UInt16", "UInt32", "UInt64", "Float", "Float32", "Float64", "Bool",
+    "String", "Char", "Array", "Map", "Set", "Option", "Result", "Self",
+];
+
+/// Classify every token of `src`, in source order.
+pub fn highlight(src: &str) -> Vec<HighlightToken> {
+    let names = DeclaredNames::collect(src);
+    let tokens = crate::lexer::tokenize(src);
+
+    let chars: Vec<char> = src.chars().collect();
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut gap = Pos::new(1, 1, 0);
+    for (i, (token, span)) in tokens.iter().enumerate() {
+        scan_comments(&chars, gap, span.start.offset, &mut out);
+        gap = span.end;
+        let prev = i.checked_sub(1).map(|j| &tokens[j].0);
+        let next = tokens.get(i + 1).map(|(t, _)| t);
+        if span.end.offset == span.start.offset {
+            continue;
+        }
+        if let Some((kind, declaration)) = classify(token, prev, next, &names) {
+            out.push(HighlightToken {
+                kind,
+                span: *span,
+                declaration,
+            });
+        }
+    }
+    scan_comments(&chars, gap, chars.len(), &mut out);
+    out
+}
+
+/// Function and type names declared anywhere in the program.
+#[derive(Default)]
+struct DeclaredNames {
+    functions: HashSet<String>,
+    types: HashSet<String>,
+}
+
+impl DeclaredNames {
+    /// Parse without lowering, so names are as written (overloads are
+    /// not yet mangled). Parse errors just leave names out.
+    fn collect(src: &str) -> Self {
+        let mut parser = Parser::new_silent(Lexer::new(src));
+        let program = parser.parse_program();
+        let mut names = DeclaredNames::default();
+        names.visit(&program);
+        names
+    }
+
+    fn visit(&mut self, node: &Node) {
+        match node {
+            Node::Function { name, .. } => {
+                self.functions.insert(name.clone());
+            }
+            Node::StructDecl { name, .. }
+            | Node::EnumDecl { name, .. }
+            | Node::TypeAlias { name, .. }
+            | Node::NewtypeDecl { name, .. }
+            | Node::TraitDecl { name, .. }
+            | Node::ActorDecl { name, .. }
+            | Node::Actor { name, .. } => {
+                self.types.insert(name.clone());
+            }
+            _ => {}
+        }
+        crate::ast::for_each_child(node, |child| self.visit(child));
+    }
+}
+
+/// The kind of `token`, and whether it declares a name, or `None` for
+/// punctuation.
+fn classify(
+    token: &Token,
+    prev: Option<&Token>,
+    next: Option<&Token>,
+    names: &DeclaredNames,
+) -> Option<(HighlightKind, bool)> {
+    let kind = match token {
+        Token::Requires
+        | Token::Ensures
+        | Token::RecoversTo
+        | Token::Invariant
+        | Token::Fails
+        | Token::Assert
+        | Token::Assume
+        | Token::ConcurrentEnsures
+        | Token::Always
+        | Token::Eventually
+        | Token::Forall
+        | Token::Exists
+        | Token::StaticAssert => HighlightKind::ContractKeyword,
+
+        Token::Function
+        | Token::Let
+        | Token::Live
+        | Token::If
+        | Token::Else
+        | Token::Return
+        | Token::Static
+        | Token::While
+        | Token::For
+        | Token::In
+        | Token::Break
+        | Token::Continue
+        | Token::Loop
+        | Token::Struct
+        | Token::New
+        | Token::Match
+        | Token::Default
+        | Token::Use
+        | Token::As
+        | Token::Extern
+        | Token::Impl
+        | Token::Type
+        | Token::Linear
+        | Token::Region
+        | Token::Mut
+        | Token::Actor
+        | Token::Receive
+        | Token::Const
+        | Token::Try
+        | Token::Catch
+        | Token::Mod
+        | Token::Newtype
+        | Token::Supervisor
+        | Token::Trait
+        | Token::Enum
+        | Token::Unsafe
+        | Token::Pub
+        | Token::Where
+        | Token::Defer
+        | Token::Bench
+        | Token::BoolLiteral(_) => HighlightKind::Keyword,
+
+        Token::IntLiteral(_) | Token::FloatLiteral(_) => HighlightKind::Number,
+        Token::StringLiteral(_) | Token::BytesLiteral(_) | Token::CharLiteral(_) => {
+            HighlightKind::String
+        }
+
+        Token::Identifier(name) => return Some(classify_identifier(name, prev, next, names)),
+
+        Token::Plus
+        | Token::Minus
+        | Token::Multiply
+        | Token::Divide
+        | Token::Modulo
+        | Token::Assign
+        | Token::PlusAssign
+        | Token::MinusAssign
+        | Token::StarAssign
+        | Token::SlashAssign
+        | Token::PercentAssign
+        | Token::AmpAssign
+        | Token::PipeAssign
+        | Token::CaretAssign
+        | Token::ShlAssign
+        | Token::ShrAssign
+        | Token::Equal
+        | Token::NotEqual
+        | Token::And
+        | Token::Or
+        | Token::PipeArrow
+        | Token::BitAnd
+        | Token::BitOr
+        | Token::BitXor
+        | Token::ShiftLeft
+        | Token::ShiftRight
+        | Token::Greater
+        | Token::Less
+        | Token::GreaterEqual
+        | Token::LessEqual
+        | Token::Bang
+        | Token::Dot
+        | Token::DotDot
+        | Token::DotDotDot
+        | Token::FatArrow
+        | Token::Arrow
+        | Token::Question
+        | Token::DoubleQuestion
+        | Token::QuestionDot
+        | Token::DoubleColon => HighlightKind::Operator,
+
+        _ => return None,
+    };
+    Some((kind, false))
+}
+
+fn classify_identifier(
+    name: &str,
+    prev: Option<&Token>,
+    next: Option<&Token>,
+    names: &DeclaredNames,
+) -> (HighlightKind, bool) {
+    // `pure fn`, `io fn`, `test fn`: soft keywords.
+    if next == Some(&Token::Function) && matches!(name, "pure" | "io" | "test") {
+        return (HighlightKind::Keyword, false);
+    }
+    match prev {
+        Some(Token::Function) => return (HighlightKind::Function, true),
+        Some(
+            Token::Struct
+            | Token::Type
+            | Token::Enum
+            | Token::Trait
+            | Token::Newtype
+            | Token::Actor,
+        ) => return (HighlightKind::Type, true),
+        Some(Token::Let | Token::Static | Token::Const) => {
+            return (HighlightKind::Variable, true);
+        }
+        Some(Token::New | Token::Impl) => return (HighlightKind::Type, false),
+        _ => {}
+    }
+    if names.types.contains(name) || BUILTIN_TYPES.contains(&name) {
+        (HighlightKind::Type, false)
+    } else if next == Some(&Token::LeftParen) || names.functions.contains(name) {
+        (HighlightKind::Function, false)
+    } else {
+        (HighlightKind::Variable, false)
+    }
+}
+
+/// Push a token for each comment in `chars[from.offset..to]`, a gap
+/// between tokens that holds only whitespace and comments. Block
+/// comments nest, as in the lexer.
+fn scan_comments(chars: &[char], from: Pos, to: usize, out: &mut Vec<HighlightToken>) {
+    let mut pos = from;
+    // A leading `#!` line is skipped by the lexer, not a comment.
+    if pos.offset == 0 && chars.starts_with(&['#', '!']) {
+        while pos.offset < to && chars[pos.offset] != '\n' {
+            advance(chars, &mut pos);
+        }
+    }
+    while pos.offset < to {
+        let opener = (chars[pos.offset], chars.get(pos.offset + 1).copied());
+        let start = pos;
+        match opener {
+            ('/', Some('/')) => {
+                while pos.offset < to && chars[pos.offset] != '\n' {
+                    advance(chars, &mut pos);
+                }
+                push_comment(out, start, pos);
+            }
+            ('/', Some('*')) => {
+                let mut depth = 0usize;
+                while pos.offset < to {
+                    match (chars[pos.offset], chars.get(pos.offset + 1)) {
+                        ('/', Some('*')) => {
+                            depth += 1;
+                            advance(chars, &mut pos);
+                            advance(chars, &mut pos);
+                        }
+                        ('*', Some('/')) => {
+                            depth -= 1;
+                            advance(chars, &mut pos);
+                            advance(chars, &mut pos);
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => advance(chars, &mut pos),
+                    }
+                }
+                push_comment(out, start, pos);
+            }
+            _ => advance(chars, &mut pos),
+        }
+    }
+}
+
+/// Step `pos` past one char.
+fn advance(chars: &[char], pos: &mut Pos) {
+    if chars[pos.offset] == '\n' {
+        pos.line += 1;
+        pos.column = 1;
+    } else {
+        pos.column += 1;
+    }
+    pos.offset += 1;
+}
+
+fn push_comment(out: &mut Vec<HighlightToken>, start: Pos, end: Pos) {
+    out.push(HighlightToken {
+        kind: HighlightKind::Comment,
+        span: Span::new(start, end),
+        declaration: false,
+    });
+}
@@ -12,25 +12,32 @@
 //! synthetic-but-monotonic values instead of crashing — the same
 //! "demonstrate the language, not the host toolchain" posture as the
 //! RES-3877 in-memory VFS.
+//!
+//! The stand-ins key on `cfg(wasm_web)` (set by `build.rs`), not on
+//! `wasm32` as such: `wasm32-wasi` has real clocks behind WASI's
+//! `clock_time_get`, and std reaches them, so WASI builds take the
+//! native paths (see `host_profile`).
 
 use std::time::Duration;
 
 /// Nanoseconds since an unspecified, process-lifetime monotonic
 /// epoch. Only meaningful as a delta between two samples — callers
 /// subtract to get an elapsed duration.
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(not(wasm_web))]
 pub(crate) fn monotonic_nanos() -> u128 {
     use std::sync::OnceLock;
     use std::time::Instant;
     static EPOCH: OnceLock<Instant> = OnceLock::new();
     let epoch = EPOCH.get_or_init(Instant::now);
-    Instant::now().duration_since(*epoch).as_nanos()
+    crate::record_replay::number(crate::record_replay::Kind::Monotonic, || {
+        Instant::now().duration_since(*epoch).as_nanos() as u64
+    }) as u128
 }
 
 /// wasm: no host timer, so advance a process-global monotonic counter
 /// by a fixed step per sample. Preserves the "monotonic, deltas-only"
 /// contract (`clock_now`/`clock_ms`/`clock_elapsed`) without trapping.
-#[cfg(target_arch = "wasm32")]
+#[cfg(wasm_web)]
 pub(crate) fn monotonic_nanos() -> u128 {
     use std::sync::atomic::{AtomicU64, Ordering};
     /// One simulated millisecond of forward progress per sample, so
@@ -44,19 +51,25 @@ pub(crate) fn monotonic_nanos() -> u128 {
 /// Duration since the Unix epoch (1970-01-01 UTC). Saturates to zero
 /// for clocks set before the epoch, matching the `unix_time_*`
 /// builtins' documented behavior.
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(not(wasm_web))]
 pub(crate) fn wall_clock_since_epoch() -> Duration {
     use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or(Duration::ZERO)
+    Duration::from_nanos(crate::record_replay::number(
+        crate::record_replay::Kind::Wall,
+        || {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::ZERO)
+                .as_nanos() as u64
+        },
+    ))
 }
 
 /// wasm: anchor at a fixed, recent base epoch and advance with the
 /// monotonic counter so timestamps are plausible, distinct, and
 /// non-decreasing across calls without reading a (panicking) host
 /// wall clock.
-#[cfg(target_arch = "wasm32")]
+#[cfg(wasm_web)]
 pub(crate) fn wall_clock_since_epoch() -> Duration {
     /// 2026-01-01T00:00:00Z, chosen as a recent, legible anchor.
     const BASE_UNIX_SECS: u64 = 1_767_225_600;
@@ -67,12 +80,12 @@ pub(crate) fn wall_clock_since_epoch() -> Duration {
 /// `std::thread::sleep`. wasm: a no-op — `thread::sleep` panics on
 /// `wasm32-unknown-unknown`, and a real sleep would block the
 /// browser's single-threaded event loop anyway.
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(not(wasm_web))]
 pub(crate) fn sleep_ms(ms: u64) {
     std::thread::sleep(Duration::from_millis(ms));
 }
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(wasm_web)]
 pub(crate) fn sleep_ms(_ms: u64) {}
 
 #[cfg(test)]
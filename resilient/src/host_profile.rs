@@ -0,0 +1,192 @@
+//! Which host the file, clock, random and env builtins talk to.
+//!
+//! The profile is fixed by the compilation target:
+//!
+//! | Profile | Target | Files | Clocks | Entropy | `env` |
+//! |---|---|---|---|---|---|
+//! | [`HostProfile::Native`] | everything but `wasm32` | `std::fs` | OS clocks | `/dev/urandom`, else the clock | process environment |
+//! | [`HostProfile::Wasi`] | `wasm32-wasi*` | preopened directories | `clock_time_get` | `random_get` | the runtime's `--env` list |
+//! | [`HostProfile::Web`] | `wasm32-unknown-unknown` | in-memory VFS | software clocks | the clock | always unset |
+//!
+//! Under WASI the builtins run the native code: std routes `std::fs`,
+//! `Instant`, `SystemTime` and `std::env` through WASI, so only the
+//! seed and entropy sources differ. What a program may touch is then
+//! the WASI runtime's call, made with capabilities rather than paths:
+//! `wasmtime run --dir data::/data --env TZ rz.wasm app.rz` grants one
+//! directory and one variable, and nothing else resolves. `--fs` still
+//! narrows that further. Processes, sockets and the FFI stay
+//! unavailable, as on the web.
+//!
+//! `rz` itself builds for WASI as a one-file runner, [`crate::run_cli`]:
+//!
+//! ```text
+//! rz [--seed N] [--fs POLICY] [--max-steps N] [-q] FILE [-- ARGS...]
+//! ```
+
+/// The host a build's builtins run against; see the module docs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostProfile {
+    Native,
+    Wasi,
+    Web,
+}
+
+impl HostProfile {
+    /// The profile this build was compiled for.
+    pub const fn current() -> HostProfile {
+        if cfg!(target_os = "wasi") {
+            HostProfile::Wasi
+        } else if cfg!(wasm_web) {
+            HostProfile::Web
+        } else {
+            HostProfile::Native
+        }
+    }
+
+    /// `native`, `wasi` or `web`, as `rz --version --verbose` prints it.
+    pub fn name(self) -> &'static str {
+        match self {
+            HostProfile::Native => "native",
+            HostProfile::Wasi => "wasi",
+            HostProfile::Web => "web",
+        }
+    }
+}
+
+#[cfg(target_os = "wasi")]
+const USAGE: &str = "usage: rz [--seed N] [--fs POLICY] [--max-steps N] [-q] FILE [-- ARGS...]";
+
+#[cfg(target_os = "wasi")]
+fn usage_error(msg: String) -> ! {
+    eprintln!("Error: {}", msg);
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
+
+/// `rz` on WASI: run one file, with the flags that make sense inside
+/// a WASI runtime. Exit codes match the native CLI.
+#[cfg(target_os = "wasi")]
+pub(crate) fn run_cli() {
+    use crate::fs_sandbox::{self, FsPolicy};
+
+    let args: Vec<String> = std::env::args().collect();
+    let mut seed: Option<u64> = None;
+    let mut max_steps: Option<u64> = None;
+    let mut quiet = false;
+    let mut file: Option<String> = None;
+    let mut script_args = Vec::new();
+    let mut i = 1;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        let mut value = |flag: &str| -> String {
+            if let Some(v) = arg.strip_prefix(flag).and_then(|r| r.strip_prefix('=')) {
+                return v.to_string();
+            }
+            i += 1;
+            args.get(i)
+                .cloned()
+                .unwrap_or_else(|| usage_error(format!("{} requires a value", flag)))
+        };
+        match arg {
+            "--" => {
+                script_args = args[i + 1..].to_vec();
+                break;
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "-q" | "--quiet" => quiet = true,
+            _ if arg == "--seed" || arg.starts_with("--seed=") => {
+                let v = value("--seed");
+                seed =
+                    Some(v.parse().unwrap_or_else(|_| {
+                        usage_error(format!("--seed expects a u64, got {:?}", v))
+                    }));
+            }
+            _ if arg == "--max-steps" || arg.starts_with("--max-steps=") => {
+                let v = value("--max-steps");
+                max_steps = Some(v.parse().unwrap_or_else(|_| {
+                    usage_error(format!("--max-steps expects a u64, got {:?}", v))
+                }));
+            }
+            _ if arg == "--fs" || arg.starts_with("--fs=") => {
+                let v = value("--fs");
+                let policy = FsPolicy::parse(&v).unwrap_or_else(|| {
+                    usage_error(format!(
+                        "unknown --fs policy `{}` (expected read-write, read-only, or none)",
+                        v
+                    ))
+                });
+                fs_sandbox::set_policy(policy);
+            }
+            _ if arg.starts_with('-') => usage_error(format!("unknown flag `{}`", arg)),
+            _ if file.is_none() => file = Some(arg.to_string()),
+            _ => usage_error(format!("unexpected argument `{}`", arg)),
+        }
+        i += 1;
+    }
+    let Some(file) = file else {
+        usage_error("no file to run".to_string());
+    };
+
+    let used_seed = seed.unwrap_or_else(crate::seed_rng_from_clock);
+    crate::seed_rng(used_seed);
+    if seed.is_none() {
+        eprintln!("seed={}", used_seed);
+    }
+    crate::set_script_args(script_args);
+    crate::fuel::set_max_steps(max_steps);
+
+    // The incremental cache would write next to the source, which a
+    // read-only preopen refuses, so WASI runs skip it.
+    let result = crate::execute_file(
+        &file,
+        None,
+        false,
+        false,
+        false,
+        false,
+        None,
+        None,
+        None,
+        false,
+        false,
+        false,
+        5000,
+        false,
+        false,
+        None,
+        #[cfg(feature = "z3")]
+        crate::verifier_z3::Z3Theory::Auto,
+        true,
+        false,
+        quiet,
+    );
+    match result {
+        Ok(0) => {
+            if !quiet {
+                println!("Program executed successfully");
+            }
+        }
+        Ok(code) => crate::exit_flushed(code),
+        Err(e) => {
+            if let Some(code) = crate::exit_request() {
+                crate::exit_flushed(code);
+            }
+            eprintln!("Error: {}", e);
+            std::process::exit(crate::failure_exit_code(&e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_builds_report_the_native_profile() {
+        assert_eq!(HostProfile::current(), HostProfile::Native);
+        assert_eq!(HostProfile::current().name(), "native");
+    }
+}
@@ -4,6 +4,31 @@
 //! Handles chunked transfer encoding, Content-Length, request
 //! headers, response headers, and configurable timeouts. No TLS —
 //! HTTP only.
+//!
+//! A request returns a `Response` struct (`status`, `body`,
+//! `headers`). Anything that stops it from producing one is a typed
+//! failure, spelled like the filesystem builtins'
+//! (`http_get: Timeout: ...`) so `catch Timeout` matches it and a
+//! `live` block retries it:
+//!
+//! - `InvalidUrl` — not an `http://host[:port]/path` URL;
+//! - `PermissionDenied` — network access is off (`--net none`, or
+//!   `--allow` without `net`);
+//! - `ConnectionFailed` — resolving, connecting, or the connection
+//!   dropping mid-request;
+//! - `Timeout` — the request's timeout or the enclosing
+//!   `live ... within` budget ran out;
+//! - `BadResponse` — the server's reply is not HTTP;
+//! - `Unsupported` — no sockets on this target (the wasm playground).
+//!
+//! The timeout is the optional millisecond argument (30 s without
+//! one), capped at whatever is left of the innermost `live ...
+//! within` deadline, so a request never outlives the block that
+//! asked for it.
+//!
+//! `--net <policy>` on `rz` allows (`allow`, the default) or denies
+//! (`none`) every request. One process-wide setting chosen by the
+//! driver, like `--fs`.
 
 #![allow(clippy::collapsible_if, clippy::doc_lazy_continuation)]
 
@@ -14,26 +39,55 @@ use std::collections::HashMap;
 use std::io::{Read, Write};
 #[cfg(not(target_arch = "wasm32"))]
 use std::net::{TcpStream, ToSocketAddrs};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 type RResult<T> = Result<T, String>;
 
-const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+pub(crate) const DEFAULT_TIMEOUT_MS: u64 = 30_000;
 
-fn ok(v: Value) -> Value {
-    Value::Result {
-        ok: true,
-        payload: Box::new(v),
-    }
+/// Whether programs may reach the network (`--net`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetPolicy {
+    Allow,
+    None,
 }
 
-fn err_val(msg: String) -> Value {
-    Value::Result {
-        ok: false,
-        payload: Box::new(Value::String(msg)),
+impl NetPolicy {
+    /// Parse a `--net` argument.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "allow" => Some(NetPolicy::Allow),
+            "none" => Some(NetPolicy::None),
+            _ => None,
+        }
     }
 }
 
+static NET_DENIED: AtomicBool = AtomicBool::new(false);
+
+/// Select the process-wide network policy. Called from the CLI
+/// dispatcher.
+pub fn set_net_policy(policy: NetPolicy) {
+    NET_DENIED.store(policy == NetPolicy::None, Ordering::Relaxed);
+}
+
+/// Every variant `http_get` and `http_post` fail with.
+pub(crate) const FAILURES: &[&str] = &[
+    "InvalidUrl",
+    "PermissionDenied",
+    "ConnectionFailed",
+    "Timeout",
+    "BadResponse",
+    "Unsupported",
+];
+
+/// The `<builtin>: <Variant>: <detail>` error for a typed failure.
+pub(crate) fn failure(builtin: &str, variant: &str, detail: impl std::fmt::Display) -> String {
+    format!("{builtin}: {variant}: {detail}")
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 struct ParsedUrl {
     host: String,
@@ -60,7 +114,7 @@ impl Default for RequestOptions {
 fn parse_url(url: &str) -> Result<ParsedUrl, String> {
     let rest = url
         .strip_prefix("http://")
-        .ok_or_else(|| format!("http_*: only http:// URLs supported, got: {url}"))?;
+        .ok_or_else(|| format!("only http:// URLs supported, got: {url}"))?;
 
     let (host_port, path) = match rest.find('/') {
         Some(i) => (&rest[..i], &rest[i..]),
@@ -72,14 +126,14 @@ fn parse_url(url: &str) -> Result<ParsedUrl, String> {
             let port_str = &host_port[i + 1..];
             let port: u16 = port_str
                 .parse()
-                .map_err(|_| format!("http_*: invalid port: {port_str}"))?;
+                .map_err(|_| format!("invalid port: {port_str}"))?;
             (&host_port[..i], port)
         }
         None => (host_port, 80),
     };
 
     if host.is_empty() {
-        return Err("http_*: empty host".to_string());
+        return Err(format!("empty host: {url}"));
     }
 
     Ok(ParsedUrl {
@@ -95,22 +149,22 @@ fn response_headers_to_map(headers: Vec<(String, String)>) -> Value {
     for (key, value) in headers {
         map.insert(MapKey::Str(key), Value::String(value));
     }
-    Value::Map(map)
+    Value::Map(map.into())
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 fn make_response(status: i64, body: String, headers: Vec<(String, String)>) -> Value {
     Value::Struct {
         name: "Response".to_string(),
-        fields: vec![
+        fields: Rc::new(vec![
             ("status".to_string(), Value::Int(status)),
             ("body".to_string(), Value::String(body)),
             ("headers".to_string(), response_headers_to_map(headers)),
-        ],
+        ]),
     }
 }
 
-fn timeout_from_ms(ms: i64, builtin: &str) -> RResult<Duration> {
+pub(crate) fn timeout_from_ms(ms: i64, builtin: &str) -> RResult<Duration> {
     if ms <= 0 {
         return Err(format!(
             "{builtin}: timeout must be a positive integer number of milliseconds"
@@ -434,10 +488,10 @@ fn walk(node: &Node, source_path: &str) -> Result<(), String> {
             ..
         } => {
             walk(body, source_path)?;
-            for req in requires {
+            for req in requires.iter() {
                 walk(req, source_path)?;
             }
-            for ens in ensures {
+            for ens in ensures.iter() {
                 walk(ens, source_path)?;
             }
             if let Some(recovers_to) = recovers_to {
@@ -556,24 +610,59 @@ fn walk(node: &Node, source_path: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// The request timeout, capped at what is left of the innermost
+/// `live ... within` budget. Fails with `Timeout` once that budget is
+/// already spent.
+pub(crate) fn effective_timeout(builtin: &str, requested: Duration) -> RResult<Duration> {
+    match crate::live_time_left() {
+        Some(left) if left.is_zero() => Err(failure(
+            builtin,
+            "Timeout",
+            "the enclosing live block's deadline has passed",
+        )),
+        Some(left) => Ok(requested.min(left)),
+        None => Ok(requested),
+    }
+}
+
+/// The typed failure for an I/O error while `doing` something.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn io_failure(builtin: &str, doing: &str, e: &std::io::Error) -> String {
+    match e.kind() {
+        std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => {
+            failure(builtin, "Timeout", format!("{doing} timed out"))
+        }
+        _ => failure(builtin, "ConnectionFailed", format!("{doing} failed: {e}")),
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
-fn connect_with_timeout(parsed: &ParsedUrl, timeout: Duration) -> Result<TcpStream, String> {
+fn connect_with_timeout(
+    builtin: &str,
+    parsed: &ParsedUrl,
+    timeout: Duration,
+) -> Result<TcpStream, String> {
     let addr = format!("{}:{}", parsed.host, parsed.port);
-    let mut last_err: Option<String> = None;
-    let addrs = addr
-        .to_socket_addrs()
-        .map_err(|e| format!("connection failed: could not resolve {}: {}", addr, e))?;
+    let mut last_err: Option<std::io::Error> = None;
+    let addrs = addr.to_socket_addrs().map_err(|e| {
+        failure(
+            builtin,
+            "ConnectionFailed",
+            format!("could not resolve {}: {}", addr, e),
+        )
+    })?;
     for socket_addr in addrs {
         match TcpStream::connect_timeout(&socket_addr, timeout) {
             Ok(stream) => return Ok(stream),
-            Err(e) => last_err = Some(e.to_string()),
+            Err(e) => last_err = Some(e),
         }
     }
     Err(match last_err {
-        Some(e) => format!("connection failed: {}", e),
-        None => format!(
-            "connection failed: no socket addresses resolved for {}",
-            addr
+        Some(e) => io_failure(builtin, &format!("connecting to {}", addr), &e),
+        None => failure(
+            builtin,
+            "ConnectionFailed",
+            format!("no socket addresses resolved for {}", addr),
         ),
     })
 }
@@ -588,24 +677,21 @@ fn host_header(parsed: &ParsedUrl) -> String {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn send_request(method: &str, url: &str, body: Option<&str>, options: RequestOptions) -> Value {
-    let parsed = match parse_url(url) {
-        Ok(p) => p,
-        Err(e) => return err_val(e),
-    };
-
-    let mut stream = match connect_with_timeout(&parsed, options.timeout) {
-        Ok(s) => s,
-        Err(e) => return err_val(e),
-    };
-
-    if stream
-        .set_read_timeout(Some(options.timeout))
-        .and_then(|_| stream.set_write_timeout(Some(options.timeout)))
-        .is_err()
-    {
-        return err_val("failed to set socket timeout".to_string());
-    }
+fn send_request(
+    builtin: &str,
+    method: &str,
+    url: &str,
+    body: Option<&str>,
+    options: RequestOptions,
+) -> RResult<Value> {
+    let parsed = parse_url(url).map_err(|e| failure(builtin, "InvalidUrl", e))?;
+    let timeout = effective_timeout(builtin, options.timeout)?;
+
+    let mut stream = connect_with_timeout(builtin, &parsed, timeout)?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| io_failure(builtin, "setting the socket timeout", &e))?;
 
     let mut headers = HashMap::with_capacity(options.headers.len() + 4);
     headers.insert("User-Agent".to_string(), "Resilient/1.0".to_string());
@@ -631,37 +717,45 @@ fn send_request(method: &str, url: &str, body: Option<&str>, options: RequestOpt
         request.push_str(b);
     }
 
-    if let Err(e) = stream.write_all(request.as_bytes()) {
-        return err_val(format!("write failed: {e}"));
-    }
-    if let Err(e) = stream.flush() {
-        return err_val(format!("flush failed: {e}"));
-    }
+    stream
+        .write_all(request.as_bytes())
+        .and_then(|_| stream.flush())
+        .map_err(|e| io_failure(builtin, "sending the request", &e))?;
 
     let mut raw = Vec::new();
-    if let Err(e) = stream.read_to_end(&mut raw) {
-        return err_val(format!("read failed: {e}"));
+    stream
+        .read_to_end(&mut raw)
+        .map_err(|e| io_failure(builtin, "reading the response", &e))?;
+
+    if raw.is_empty() {
+        return Err(failure(
+            builtin,
+            "ConnectionFailed",
+            "the server closed the connection without a response",
+        ));
     }
 
     let raw_str = String::from_utf8_lossy(&raw);
-    parse_http_response(&raw_str)
+    parse_http_response(&raw_str).map_err(|e| failure(builtin, "BadResponse", e))
 }
 
+/// The `Response` struct for a raw HTTP/1.1 reply, or what is wrong
+/// with it.
 #[cfg(not(target_arch = "wasm32"))]
-fn parse_http_response(raw: &str) -> Value {
+fn parse_http_response(raw: &str) -> Result<Value, String> {
     let header_end = match raw.find("\r\n\r\n") {
         Some(i) => i,
-        None => return err_val("malformed HTTP response: no header/body separator".to_string()),
+        None => return Err("malformed HTTP response: no header/body separator".to_string()),
     };
 
     let header_section = &raw[..header_end];
     let body_raw = &raw[header_end + 4..];
 
     let mut lines = header_section.split("\r\n");
-    let status_line = match lines.next() {
-        Some(l) => l,
-        None => return err_val("empty HTTP response".to_string()),
-    };
+    let status_line = lines.next().unwrap_or_default();
+    if !status_line.starts_with("HTTP/") {
+        return Err(format!("malformed HTTP status line: {status_line:?}"));
+    }
 
     let status = parse_status_code(status_line);
 
@@ -684,7 +778,7 @@ fn parse_http_response(raw: &str) -> Value {
         body_raw.to_string()
     };
 
-    ok(make_response(status, body, headers))
+    Ok(make_response(status, body, headers))
 }
 
 fn parse_status_code(status_line: &str) -> i64 {
@@ -727,26 +821,41 @@ fn decode_chunked(data: &str) -> String {
 // Builtins
 // ---------------------------------------------------------------------------
 
+/// Fail with `PermissionDenied` under `--net none` or an `--allow`
+/// without `net`.
+pub(crate) fn check_net(builtin: &str, url: &str) -> RResult<()> {
+    crate::sandbox::require(builtin, crate::sandbox::Capability::Net, url)?;
+    if NET_DENIED.load(Ordering::Relaxed) {
+        return Err(failure(
+            builtin,
+            "PermissionDenied",
+            format!("{url}: denied by --net none"),
+        ));
+    }
+    Ok(())
+}
+
 /// RES-4126: on `wasm32` (the web playground) there is no raw-socket
 /// access — `TcpStream::connect` is unimplemented for that target, so
 /// letting `send_request` run would fail to compile/link, not just to
-/// connect. Both builtins short-circuit here with a graceful `Err`
-/// after validating arguments, so `try`/`match Err(..)` in Resilient
-/// code sees a normal failure instead of the playground refusing to
-/// build.
+/// connect. Both builtins short-circuit here with a typed
+/// `Unsupported` failure after validating arguments, so `try`/`catch`
+/// in Resilient code sees a normal failure instead of the playground
+/// refusing to build.
 #[cfg(target_arch = "wasm32")]
-fn unsupported(builtin: &str) -> Value {
-    err_val(format!(
-        "{}: unsupported on this target (no raw sockets in the wasm playground)",
-        builtin
-    ))
+fn unsupported(builtin: &str) -> String {
+    failure(
+        builtin,
+        "Unsupported",
+        "no raw sockets in the wasm playground",
+    )
 }
 
 pub(crate) fn builtin_http_get(args: &[Value]) -> RResult<Value> {
     if args.is_empty() {
         return Err("http_get: expected at least 1 argument, got 0".to_string());
     }
-    let _url = match &args[0] {
+    let url = match &args[0] {
         Value::String(url) => url,
         other => return Err(format!("http_get: expected string URL, got {}", other)),
     };
@@ -757,13 +866,14 @@ pub(crate) fn builtin_http_get(args: &[Value]) -> RResult<Value> {
         ));
     }
     let _options = parse_request_options(args, 1, "http_get")?;
+    check_net("http_get", url)?;
     #[cfg(target_arch = "wasm32")]
     {
-        Ok(unsupported("http_get"))
+        Err(unsupported("http_get"))
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Ok(send_request("GET", _url, None, _options))
+        send_request("http_get", "GET", url, None, _options)
     }
 }
 
@@ -774,7 +884,7 @@ pub(crate) fn builtin_http_post(args: &[Value]) -> RResult<Value> {
             args.len()
         ));
     }
-    let _url = match &args[0] {
+    let url = match &args[0] {
         Value::String(url) => url,
         other => return Err(format!("http_post: expected string URL, got {}", other)),
     };
@@ -789,13 +899,14 @@ pub(crate) fn builtin_http_post(args: &[Value]) -> RResult<Value> {
         ));
     }
     let _options = parse_request_options(args, 2, "http_post")?;
+    check_net("http_post", url)?;
     #[cfg(target_arch = "wasm32")]
     {
-        Ok(unsupported("http_post"))
+        Err(unsupported("http_post"))
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Ok(send_request("POST", _url, Some(_body), _options))
+        send_request("http_post", "POST", url, Some(_body), _options)
     }
 }
 
@@ -857,7 +968,7 @@ mod tests {
                 Value::String((*v).to_string()),
             );
         }
-        Value::Map(map)
+        Value::Map(map.into())
     }
 
     fn read_request_text(stream: &mut std::net::TcpStream, needle: &str) -> String {
@@ -940,74 +1051,62 @@ mod tests {
     #[test]
     fn parse_response_basic() {
         let raw = "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\nhello world";
-        let result = parse_http_response(raw);
-        match result {
-            Value::Result {
-                ok: true, payload, ..
-            } => match *payload {
-                Value::Struct {
-                    ref name,
-                    ref fields,
-                    ..
-                } => {
-                    assert_eq!(name, "Response");
-                    let status = fields.iter().find(|(k, _)| k == "status");
-                    assert!(matches!(status, Some((_, Value::Int(200)))));
-                    let body = fields.iter().find(|(k, _)| k == "body");
-                    match body {
-                        Some((_, Value::String(s))) => assert_eq!(s, "hello world"),
-                        _ => panic!("expected body string"),
-                    }
-                    let headers = fields.iter().find(|(k, _)| k == "headers");
-                    match headers {
-                        Some((_, Value::Map(headers))) => {
-                            assert!(matches!(
-                                headers.get(&MapKey::Str("content-type".to_string())),
-                                Some(Value::String(s)) if s == "text/plain"
-                            ));
-                        }
-                        _ => panic!("expected headers map"),
+        match parse_http_response(raw).unwrap() {
+            Value::Struct {
+                ref name,
+                ref fields,
+                ..
+            } => {
+                assert_eq!(name, "Response");
+                let status = fields.iter().find(|(k, _)| k == "status");
+                assert!(matches!(status, Some((_, Value::Int(200)))));
+                let body = fields.iter().find(|(k, _)| k == "body");
+                match body {
+                    Some((_, Value::String(s))) => assert_eq!(s, "hello world"),
+                    _ => panic!("expected body string"),
+                }
+                let headers = fields.iter().find(|(k, _)| k == "headers");
+                match headers {
+                    Some((_, Value::Map(headers))) => {
+                        assert!(matches!(
+                            headers.get(&MapKey::Str("content-type".to_string())),
+                            Some(Value::String(s)) if s == "text/plain"
+                        ));
                     }
+                    _ => panic!("expected headers map"),
                 }
-                _ => panic!("expected struct"),
-            },
-            _ => panic!("expected Ok result"),
+            }
+            _ => panic!("expected struct"),
         }
     }
 
     #[test]
     fn parse_response_chunked() {
         let raw = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n";
-        let result = parse_http_response(raw);
-        match result {
-            Value::Result {
-                ok: true, payload, ..
-            } => match *payload {
-                Value::Struct {
-                    ref name,
-                    ref fields,
-                    ..
-                } => {
-                    assert_eq!(name, "Response");
-                    let body = fields.iter().find(|(k, _)| k == "body");
-                    match body {
-                        Some((_, Value::String(s))) => assert_eq!(s, "hello"),
-                        _ => panic!("expected body string"),
-                    }
-                    let headers = fields.iter().find(|(k, _)| k == "headers");
-                    match headers {
-                        Some((_, Value::Map(headers))) => {
-                            assert!(matches!(
-                                headers.get(&MapKey::Str("transfer-encoding".to_string())),
-                                Some(Value::String(s)) if s == "chunked"
-                            ));
-                        }
-                        _ => panic!("expected headers map"),
+        match parse_http_response(raw).unwrap() {
+            Value::Struct {
+                ref name,
+                ref fields,
+                ..
+            } => {
+                assert_eq!(name, "Response");
+                let body = fields.iter().find(|(k, _)| k == "body");
+                match body {
+                    Some((_, Value::String(s))) => assert_eq!(s, "hello"),
+                    _ => panic!("expected body string"),
+                }
+                let headers = fields.iter().find(|(k, _)| k == "headers");
+                match headers {
+                    Some((_, Value::Map(headers))) => {
+                        assert!(matches!(
+                            headers.get(&MapKey::Str("transfer-encoding".to_string())),
+                            Some(Value::String(s)) if s == "chunked"
+                        ));
                     }
+                    _ => panic!("expected headers map"),
                 }
-                _ => panic!("expected struct"),
-            },
-            _ => panic!("expected Ok result"),
+            }
+            _ => panic!("expected struct"),
         }
     }
 
@@ -1024,15 +1123,42 @@ mod tests {
     }
 
     #[test]
-    fn http_get_bad_url_returns_err() {
-        let result = builtin_http_get(&[s("ftp://example.com")]).unwrap();
-        assert!(matches!(result, Value::Result { ok: false, .. }));
+    fn http_get_bad_url_is_invalid_url() {
+        let err = builtin_http_get(&[s("ftp://example.com")]).unwrap_err();
+        assert_eq!(
+            crate::builtin_failure_variant(&err),
+            Some("InvalidUrl"),
+            "{err}"
+        );
     }
 
     #[test]
     fn http_get_connection_refused() {
-        let result = builtin_http_get(&[s("http://127.0.0.1:1/test")]).unwrap();
-        assert!(matches!(result, Value::Result { ok: false, .. }));
+        let err = builtin_http_get(&[s("http://127.0.0.1:1/test")]).unwrap_err();
+        assert_eq!(
+            crate::builtin_failure_variant(&err),
+            Some("ConnectionFailed"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn non_http_reply_is_bad_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _request = read_request_text(&mut stream, "\r\n\r\n");
+            stream.write_all(b"SSH-2.0-OpenSSH\r\n\r\n").unwrap();
+        });
+        let url = format!("http://127.0.0.1:{}/", port);
+        let err = builtin_http_get(&[s(&url)]).unwrap_err();
+        assert_eq!(
+            crate::builtin_failure_variant(&err),
+            Some("BadResponse"),
+            "{err}"
+        );
+        server.join().unwrap();
     }
 
     #[test]
@@ -1053,32 +1179,29 @@ mod tests {
         let url = format!("http://127.0.0.1:{}/data", port);
         let result = builtin_http_get(&[s(&url), headers_map(&[("X-Test", "one")])]).unwrap();
         match result {
-            Value::Result { ok: true, payload } => match *payload {
-                Value::Struct { name, fields } => {
-                    assert_eq!(name, "Response");
-                    let body = fields.iter().find(|(k, _)| k == "body");
-                    match body {
-                        Some((_, Value::String(s))) => assert_eq!(s, "hello"),
-                        _ => panic!("expected body string"),
-                    }
-                    let headers = fields.iter().find(|(k, _)| k == "headers");
-                    match headers {
-                        Some((_, Value::Map(map))) => {
-                            assert!(matches!(
-                                map.get(&MapKey::Str("content-type".to_string())),
-                                Some(Value::String(s)) if s == "text/plain"
-                            ));
-                            assert!(matches!(
-                                map.get(&MapKey::Str("x-trace".to_string())),
-                                Some(Value::String(s)) if s == "abc"
-                            ));
-                        }
-                        _ => panic!("expected response headers map"),
+            Value::Struct { name, fields } => {
+                assert_eq!(name, "Response");
+                let body = fields.iter().find(|(k, _)| k == "body");
+                match body {
+                    Some((_, Value::String(s))) => assert_eq!(s, "hello"),
+                    _ => panic!("expected body string"),
+                }
+                let headers = fields.iter().find(|(k, _)| k == "headers");
+                match headers {
+                    Some((_, Value::Map(map))) => {
+                        assert!(matches!(
+                            map.get(&MapKey::Str("content-type".to_string())),
+                            Some(Value::String(s)) if s == "text/plain"
+                        ));
+                        assert!(matches!(
+                            map.get(&MapKey::Str("x-trace".to_string())),
+                            Some(Value::String(s)) if s == "abc"
+                        ));
                     }
+                    _ => panic!("expected response headers map"),
                 }
-                other => panic!("expected response struct, got {:?}", other),
-            },
-            other => panic!("expected Ok result, got {:?}", other),
+            }
+            other => panic!("expected response struct, got {:?}", other),
         }
         server.join().unwrap();
     }
@@ -1108,25 +1231,22 @@ mod tests {
         ])
         .unwrap();
         match result {
-            Value::Result { ok: true, payload } => match *payload {
-                Value::Struct { name, fields } => {
-                    assert_eq!(name, "Response");
-                    let status = fields.iter().find(|(k, _)| k == "status");
-                    assert!(matches!(status, Some((_, Value::Int(201)))));
-                    let headers = fields.iter().find(|(k, _)| k == "headers");
-                    match headers {
-                        Some((_, Value::Map(map))) => {
-                            assert!(matches!(
-                                map.get(&MapKey::Str("x-reply".to_string())),
-                                Some(Value::String(s)) if s == "yes"
-                            ));
-                        }
-                        _ => panic!("expected response headers map"),
+            Value::Struct { name, fields } => {
+                assert_eq!(name, "Response");
+                let status = fields.iter().find(|(k, _)| k == "status");
+                assert!(matches!(status, Some((_, Value::Int(201)))));
+                let headers = fields.iter().find(|(k, _)| k == "headers");
+                match headers {
+                    Some((_, Value::Map(map))) => {
+                        assert!(matches!(
+                            map.get(&MapKey::Str("x-reply".to_string())),
+                            Some(Value::String(s)) if s == "yes"
+                        ));
                     }
+                    _ => panic!("expected response headers map"),
                 }
-                other => panic!("expected response struct, got {:?}", other),
-            },
-            other => panic!("expected Ok result, got {:?}", other),
+            }
+            other => panic!("expected response struct, got {:?}", other),
         }
         server.join().unwrap();
     }
@@ -1144,9 +1264,13 @@ mod tests {
 
         let url = format!("http://127.0.0.1:{}/slow", port);
         let start = Instant::now();
-        let result = builtin_http_get(&[s(&url), Value::Int(50)]).unwrap();
+        let err = builtin_http_get(&[s(&url), Value::Int(50)]).unwrap_err();
         let elapsed = start.elapsed();
-        assert!(matches!(result, Value::Result { ok: false, .. }));
+        assert_eq!(
+            crate::builtin_failure_variant(&err),
+            Some("Timeout"),
+            "{err}"
+        );
         assert!(
             elapsed < std::time::Duration::from_millis(200),
             "timeout should fire quickly, elapsed: {:?}",
@@ -1169,10 +1293,11 @@ let resp = http_get("http://example.com/data", headers, 50);
     fn end_to_end_http_get_bad_url() {
         let r = crate::run_program(
             r#"
-let result = http_get("ftp://bad")
-match result {
-Ok(r) => println("unexpected ok"),
-Err(e) => println("error: " + e),
+try {
+    let r = http_get("ftp://bad");
+    println("unexpected ok");
+} catch InvalidUrl {
+    println("error: invalid url");
 }
 "#,
         );
@@ -1262,18 +1387,18 @@ mod wasm_tests {
 
     #[test]
     fn http_get_errs_on_wasm32() {
-        let r = builtin_http_get(&[Value::String("http://example.com".to_string())]).unwrap();
-        assert!(matches!(r, Value::Result { ok: false, .. }));
+        let err = builtin_http_get(&[Value::String("http://example.com".to_string())]).unwrap_err();
+        assert_eq!(crate::builtin_failure_variant(&err), Some("Unsupported"));
     }
 
     #[test]
     fn http_post_errs_on_wasm32() {
-        let r = builtin_http_post(&[
+        let err = builtin_http_post(&[
             Value::String("http://example.com".to_string()),
             Value::String("body".to_string()),
         ])
-        .unwrap();
-        assert!(matches!(r, Value::Result { ok: false, .. }));
+        .unwrap_err();
+        assert_eq!(crate::builtin_failure_variant(&err), Some("Unsupported"));
     }
 
     #[test]
@@ -0,0 +1,186 @@
+//! Let-polymorphism for unannotated `fn` parameters.
+//!
+//! `fn id(x) { return x; }` parses with an empty type for `x`, which
+//! the checker used to read as `Any`: every call site then accepted
+//! anything and produced `Any`. This pass gives such a parameter its
+//! own implicit type parameter instead, so the declaration is checked
+//! exactly as if it had been written `fn<T> id(T x) { return x; }`
+//! — `let s: string = id(1);` is rejected and `id(1) + 1` is `int`.
+//!
+//! Only parameters the body treats parametrically are generalized:
+//! every use must pass the value through untouched — `return x`, or an
+//! element of an array or tuple literal. A parameter that is added,
+//! compared, indexed, passed to another function, reassigned, shadowed,
+//! or mentioned in a contract has constraints this pass doesn't infer;
+//! it keeps the permissive `Any` typing.
+//!
+//! The rewrite runs on a clone of the program inside
+//! `check_program_with_source`. The runtime, formatter, and other
+//! consumers keep seeing the parameter as written.
+
+use crate::Node;
+use std::collections::HashSet;
+
+/// The program with every generalizable untyped parameter rewritten
+/// to a fresh type parameter, or `None` when nothing changes.
+/// `taken` holds the type names the program already declares, which
+/// the synthesized names must avoid.
+pub(crate) fn generalize_program(program: &Node, taken: &HashSet<String>) -> Option<Node> {
+    let Node::Program(stmts) = program else {
+        return None;
+    };
+    let has_untyped = stmts.iter().any(|s| {
+        crate::uniqueness_walk::any_node(
+            &s.node,
+            |n| matches!(n, Node::Function { parameters, .. } if parameters.iter().any(|(t, _)| t.is_empty())),
+        )
+    });
+    if !has_untyped {
+        return None;
+    }
+    let mut program = program.clone();
+    let mut changed = false;
+    if let Node::Program(stmts) = &mut program {
+        for stmt in stmts {
+            changed |= rewrite(&mut stmt.node, taken);
+        }
+    }
+    changed.then_some(program)
+}
+
+/// Rewrite `node` and every `fn` nested in it. Returns whether
+/// anything changed.
+fn rewrite(node: &mut Node, taken: &HashSet<String>) -> bool {
+    let mut changed = false;
+    match node {
+        Node::Function {
+            parameters,
+            type_params,
+            return_type,
+            requires,
+            ensures,
+            body,
+            ..
+        } => {
+            for (ty, name) in parameters.iter_mut() {
+                if !ty.is_empty()
+                    || !is_parametric(name, body, return_type.is_some(), requires, ensures)
+                {
+                    continue;
+                }
+                let fresh = fresh_name(taken, type_params);
+                type_params.push(fresh.clone());
+                *ty = fresh;
+                changed = true;
+            }
+            changed |= rewrite(std::sync::Arc::make_mut(body), taken);
+        }
+        Node::Block { stmts, .. } => {
+            for s in stmts {
+                changed |= rewrite(s, taken);
+            }
+        }
+        Node::ImplBlock { methods, .. } | Node::BlanketImpl { methods, .. } => {
+            for m in methods {
+                changed |= rewrite(m, taken);
+            }
+        }
+        Node::ModuleDecl { body, .. } => {
+            for s in body {
+                changed |= rewrite(s, taken);
+            }
+        }
+        _ => {}
+    }
+    changed
+}
+
+/// `T`, `U`, `V`, `W`, then `T1`, `T2`, ... — the first one neither
+/// the program nor this fn already uses.
+fn fresh_name(taken: &HashSet<String>, own: &[String]) -> String {
+    ["T", "U", "V", "W"]
+        .iter()
+        .map(|s| s.to_string())
+        .chain((1..).map(|i| format!("T{i}")))
+        .find(|n| !taken.contains(n) && !own.contains(n))
+        .expect("an unbounded name supply always has a free name")
+}
+
+/// Is every use of `param` one that passes its value through
+/// untouched?
+fn is_parametric(
+    param: &str,
+    body: &Node,
+    has_return_annotation: bool,
+    requires: &[Node],
+    ensures: &[Node],
+) -> bool {
+    let is_param = |n: &Node| matches!(n, Node::Identifier { name, .. } if name == param);
+    if requires
+        .iter()
+        .chain(ensures)
+        .any(|c| crate::uniqueness_walk::any_node(c, is_param))
+    {
+        return false;
+    }
+    let mut uses = 0usize;
+    let mut pass_through = 0usize;
+    let mut rebound = false;
+    crate::uniqueness_walk::visit(body, &mut |n| match n {
+        Node::Identifier { name, .. } if name == param => uses += 1,
+        // A declared return type pins the result; returning `x`
+        // there constrains it.
+        Node::ReturnStatement { value: Some(v), .. } if !has_return_annotation && is_param(v) => {
+            pass_through += 1
+        }
+        Node::ArrayLiteral { items, .. } | Node::TupleLiteral { items, .. } => {
+            pass_through += items.iter().filter(|i| is_param(i)).count()
+        }
+        Node::Assignment { name, .. } | Node::LetStatement { name, .. } if name == param => {
+            rebound = true
+        }
+        Node::FunctionLiteral { parameters, .. } if parameters.iter().any(|(_, p)| p == param) => {
+            rebound = true
+        }
+        _ => {}
+    });
+    !rebound && uses > 0 && uses == pass_through
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn check(src: &str) -> Result<(), String> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {errs:?}");
+        TypeChecker::new().check_program(&prog).map(|_| ())
+    }
+
+    #[test]
+    fn identity_is_generic_at_call_sites() {
+        check("fn id(x) { return x; }\nlet a: int = id(1);\nlet b: string = id(\"s\");")
+            .expect("each call instantiates `T` afresh");
+        let err = check("fn id(x) { return x; }\nlet s: string = id(1);").unwrap_err();
+        assert!(err.contains("let s: string — value has type int"), "{err}");
+    }
+
+    #[test]
+    fn params_generalize_independently() {
+        check("fn first(a, b) { return a; }\nlet n: int = first(1, \"x\");")
+            .expect("`a` and `b` get distinct type parameters");
+    }
+
+    #[test]
+    fn constrained_param_stays_any() {
+        check("fn add1(x) { return x + 1; }\nlet n: int = add1(2);")
+            .expect("`x + 1` keeps `x` permissive");
+    }
+
+    #[test]
+    fn declared_type_names_are_not_reused() {
+        check("struct T { int v, }\nfn id(x) { return x; }\nlet n: int = id(1);")
+            .expect("the synthesized parameter avoids the user's `T`");
+    }
+}
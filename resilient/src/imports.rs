@@ -1,6 +1,6 @@
 //! Module imports for Resilient.
 //!
-//! Supports three forms of import:
+//! Supports these forms of import:
 //!
 //! 1. **File imports**: `use "path/to/other.rz";` — imports `pub` declarations
 //!    from the referenced file. Without `pub`, declarations are private.
@@ -17,6 +17,12 @@
 //! 4. **Standard library imports**: `use std::http;` / `use std::json as j;`
 //!    — imports a built-in standard library module.
 //!
+//! 5. **Dependency imports**: `use mylib::foo;` or `use "mylib/foo";` —
+//!    imports `src/foo.rz` of the `mylib` entry in `resilient.toml`'s
+//!    `[dependencies]` (see `pkg_deps`). The string form only reaches
+//!    the dependency when no file of that path sits next to the
+//!    importing one.
+//!
 //! Cycles are detected via an in-flight stack: before expanding a file,
 //! we check if it's already being expanded higher up the call chain.
 //! If so, a clean diagnostic shows the full cycle path.
@@ -352,6 +358,10 @@ fn render_use_path_not_found_error(path: &str, base_dir: &Path) -> String {
 fn resolve_use_path(base_dir: &Path, path: &str) -> Result<PathBuf, String> {
     let candidate = base_dir.join(path);
     if !candidate.exists() {
+        // `use "dep/module";` — a module of a declared dependency.
+        if let Some(dep_file) = crate::pkg_deps::resolve_dep_import(base_dir, path)? {
+            return Ok(dep_file);
+        }
         return Err(render_use_path_not_found_error(path, base_dir));
     }
     Ok(candidate)
@@ -662,4 +672,35 @@ mod tests {
             "expected `other` filtered out by selective re-export"
         );
     }
+
+    #[test]
+    fn string_use_resolves_into_a_dependency() {
+        // `use "mylib/greet";` finds `<dep>/src/greet.rz` when there is
+        // no `mylib/greet` next to the importing file.
+        let dir = make_temp_dir().join("string_dep_use");
+        let dep = dir.join("vendor/mylib");
+        let _ = fs::create_dir_all(dep.join("src"));
+        fs::write(
+            dir.join("resilient.toml"),
+            "[package]\nname = \"app\"\n\n[dependencies]\nmylib = { path = \"vendor/mylib\" }\n",
+        )
+        .unwrap();
+        fs::write(dep.join("resilient.toml"), "[package]\nname = \"mylib\"\n").unwrap();
+        fs::write(dep.join("src/greet.rz"), "pub fn greet() { return 1; }\n").unwrap();
+
+        let (mut program, _) = crate::parse("use \"mylib/greet\";\nlet x = greet();\n");
+        let mut loaded = HashSet::new();
+        let result = expand_uses(&mut program, &dir, &mut loaded);
+        cleanup_temp_dir(&dir);
+        assert!(result.is_ok(), "expand failed: {:?}", result);
+        let Node::Program(stmts) = &program else {
+            panic!("expected Program");
+        };
+        assert!(
+            stmts
+                .iter()
+                .any(|s| matches!(&s.node, Node::Function { name, .. } if name == "greet")),
+            "expected `greet` spliced in from the dependency"
+        );
+    }
 }
@@ -0,0 +1,560 @@
+//! Incremental reparsing for editor workloads.
+//!
+//! A [`ParsedDocument`] keeps the parse of each top-level item next to
+//! the char offset where the item starts. [`ParsedDocument::edit`]
+//! splices a text edit in and reparses from the item before the edit
+//! until the parser lands on an old item boundary past it again (same
+//! text from there on, same column). Every later item is reused as is,
+//! moved by the edit's line and char delta. Parsing from a top-level
+//! boundary depends only on the text that follows it, so the items
+//! match a full parse of the new text. The one difference is the
+//! numbering of the `$`-named temporaries that comprehensions desugar
+//! to, which stays unique across the document.
+//!
+//! The lowering passes (overloads, named and default arguments, ...)
+//! need the whole program, so [`ParsedDocument::program`] reruns them
+//! over the assembled items.
+
+use crate::span::{Pos, Span, Spanned};
+use crate::{Lexer, Node, Parser, Token, parser_recovery};
+
+/// Replace the chars `start..end` of the document with `text`. Offsets
+/// count `char`s, as the lexer does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// How much of the tree one [`ParsedDocument::edit`] rebuilt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReparseStats {
+    /// Top-level items parsed again.
+    pub reparsed: usize,
+    /// Top-level items carried over from the previous tree.
+    pub reused: usize,
+}
+
+/// A parsed source file that can be edited and reparsed in place.
+#[derive(Debug, Clone)]
+pub struct ParsedDocument {
+    text: String,
+    items: Vec<Item>,
+    /// Next comprehension temporary number, so a reparsed item never
+    /// mints a name a kept item already uses.
+    comprehension_counter: u32,
+}
+
+/// One iteration of the parser's top-level loop.
+#[derive(Debug, Clone)]
+struct Item {
+    /// Char offset, line and column of the item's first token.
+    offset: usize,
+    line: usize,
+    column: usize,
+    /// `None` for items that yield no node (e.g. `#[cfg]`-disabled).
+    node: Option<Spanned<Node>>,
+    /// Parser errors recorded while parsing this item.
+    errors: Vec<String>,
+}
+
+/// Where reparsing stopped: the old item it resynchronised with and
+/// that item's new line.
+struct Resync {
+    index: usize,
+    line: usize,
+}
+
+impl ParsedDocument {
+    /// Parse `text` from scratch.
+    pub fn parse(text: &str) -> Self {
+        let mut doc = ParsedDocument {
+            text: text.to_string(),
+            items: Vec::new(),
+            comprehension_counter: 0,
+        };
+        doc.items = doc.parse_items((0, 1, 1), |_, _| None).0;
+        doc
+    }
+
+    /// The current source text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Apply `edit` and reparse only the top-level items it can have
+    /// changed.
+    ///
+    /// # Panics
+    ///
+    /// If the edit range is out of bounds or reversed.
+    pub fn edit(&mut self, edit: &TextEdit) -> ReparseStats {
+        let start_byte = byte_offset(&self.text, edit.start);
+        let end_byte = byte_offset(&self.text, edit.end);
+        self.text.replace_range(start_byte..end_byte, &edit.text);
+        let new_end = edit.start + edit.text.chars().count();
+        let delta = new_end as isize - edit.end as isize;
+
+        // Restart one item before the item the edit starts in: its
+        // parse peeked at that item's first token, which may be gone.
+        let first = self
+            .items
+            .iter()
+            .rposition(|item| item.offset < edit.start)
+            .unwrap_or(0)
+            .saturating_sub(1);
+        let mut tail = self.items.split_off(first);
+        let restart = match tail.first() {
+            Some(item) if first > 0 => (item.offset, item.line, item.column),
+            _ => (0, 1, 1),
+        };
+
+        let (fresh, resync) = self.parse_items(restart, |offset, column| {
+            if offset < new_end {
+                return None;
+            }
+            let old = (offset as isize - delta) as usize;
+            let index = tail.binary_search_by_key(&old, |item| item.offset).ok()?;
+            (tail[index].column == column).then_some(index)
+        });
+        let stats = ReparseStats {
+            reparsed: fresh.len(),
+            reused: resync.as_ref().map_or(0, |r| tail.len() - r.index),
+        };
+        self.items.extend(fresh);
+        if let Some(resync) = resync {
+            let by = Shift {
+                lines: resync.line as isize - tail[resync.index].line as isize,
+                chars: delta,
+            };
+            for mut item in tail.drain(resync.index..) {
+                if by.lines != 0 || by.chars != 0 {
+                    shift_item(&mut item, by);
+                }
+                self.items.push(item);
+            }
+        }
+
+        // The parser stops recording at its error cap, so error counts
+        // near it depend on where parsing started; reparse everything.
+        let errors: usize = self.items.iter().map(|item| item.errors.len()).sum();
+        if errors >= parser_recovery::MAX_PARSE_ERRORS {
+            *self = ParsedDocument::parse(&self.text);
+            return ReparseStats {
+                reparsed: self.items.len(),
+                reused: 0,
+            };
+        }
+        stats
+    }
+
+    /// The lowered program and its errors, as `parse` returns them for
+    /// the current text.
+    pub fn program(&self) -> (Node, Vec<String>) {
+        let mut program = Node::Program(
+            self.items
+                .iter()
+                .filter_map(|item| item.node.clone())
+                .collect(),
+        );
+        let mut errors: Vec<String> = self
+            .items
+            .iter()
+            .flat_map(|item| item.errors.iter().cloned())
+            .collect();
+        if let Err(err) =
+            crate::catch_parse_panic(|| crate::lower_parsed_program(&mut program, &mut errors))
+        {
+            return crate::parse_panic_result(err, false);
+        }
+        (program, errors)
+    }
+
+    /// Run the parser's top-level loop from `(offset, line, column)`
+    /// until EOF, or until `stop(offset, column)` of the next item's
+    /// first token names an old item to resume with.
+    fn parse_items(
+        &mut self,
+        (offset, line, column): (usize, usize, usize),
+        stop: impl Fn(usize, usize) -> Option<usize>,
+    ) -> (Vec<Item>, Option<Resync>) {
+        let line_starts = line_starts(&self.text);
+        let mut parser = Parser::new_silent(Lexer::new_at(&self.text, offset, line, column));
+        parser.comprehension_counter = self.comprehension_counter;
+        let mut items = Vec::new();
+        let mut resync = None;
+        while parser.current_token != Token::Eof {
+            let line = parser.current_span.start.line;
+            let column = parser.current_span.start.column;
+            let offset = line_starts[line - 1] + column - 1;
+            if let Some(index) = stop(offset, column) {
+                resync = Some(Resync { index, line });
+                break;
+            }
+            let errors_before = parser.errors.len();
+            // A panic leaves the parser mid-item; keep what was parsed
+            // and report the rest of the file as one error.
+            let node = match crate::catch_parse_panic(|| parser.parse_top_level_item()) {
+                Ok(node) => node,
+                Err(err) => {
+                    items.push(Item {
+                        offset,
+                        line,
+                        column,
+                        node: None,
+                        errors: vec![format!("{line}:{column}: {err}")],
+                    });
+                    break;
+                }
+            };
+            items.push(Item {
+                offset,
+                line,
+                column,
+                node,
+                errors: parser.errors.split_off(errors_before),
+            });
+        }
+        self.comprehension_counter = parser.comprehension_counter;
+        (items, resync)
+    }
+}
+
+/// Byte index of char `offset` in `text`.
+fn byte_offset(text: &str, offset: usize) -> usize {
+    text.char_indices().nth(offset).map_or_else(
+        || {
+            assert_eq!(offset, text.chars().count(), "edit offset out of bounds");
+            text.len()
+        },
+        |(i, _)| i,
+    )
+}
+
+/// Char offset at which each line of `text` starts.
+fn line_starts(text: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    for (i, c) in text.chars().enumerate() {
+        if c == '\n' {
+            starts.push(i + 1);
+        }
+    }
+    starts
+}
+
+/// How far a reused item moves: `lines` lines and `chars` chars down
+/// (or up, when negative).
+#[derive(Clone, Copy)]
+struct Shift {
+    lines: isize,
+    chars: isize,
+}
+
+/// Move a reused item by `by`.
+fn shift_item(item: &mut Item, by: Shift) {
+    item.offset = (item.offset as isize + by.chars) as usize;
+    item.line = shift_line(item.line, by);
+    for error in &mut item.errors {
+        *error = shift_error(error, by);
+    }
+    if let Some(spanned) = &mut item.node {
+        shift_span(&mut spanned.span, by);
+        shift_node(&mut spanned.node, by);
+    }
+}
+
+fn shift_line(line: usize, by: Shift) -> usize {
+    (line as isize + by.lines) as usize
+}
+
+/// Line 0 marks a synthesized span with no source position.
+fn shift_span(span: &mut Span, by: Shift) {
+    for pos in [&mut span.start, &mut span.end] {
+        shift_pos(pos, by);
+    }
+}
+
+fn shift_pos(pos: &mut Pos, by: Shift) {
+    if pos.line > 0 {
+        pos.line = shift_line(pos.line, by);
+        pos.offset = (pos.offset as isize + by.chars) as usize;
+    }
+}
+
+/// Parser errors read `line:col: message`.
+fn shift_error(error: &str, by: Shift) -> String {
+    let Some((line, rest)) = error.split_once(':') else {
+        return error.to_string();
+    };
+    match line.parse::<usize>() {
+        Ok(line) if line > 0 => format!("{}:{rest}", shift_line(line, by)),
+        _ => error.to_string(),
+    }
+}
+
+fn shift_node(node: &mut Node, by: Shift) {
+    if let Some(span) = own_span_mut(node) {
+        shift_span(span, by);
+    }
+    match node {
+        Node::Extern { decls, .. } => {
+            for decl in decls {
+                shift_span(&mut decl.span, by);
+            }
+        }
+        Node::TraitDecl {
+            methods,
+            associated_types,
+            ..
+        } => {
+            for method in methods {
+                shift_span(&mut method.span, by);
+            }
+            for assoc in associated_types {
+                shift_span(&mut assoc.span, by);
+            }
+        }
+        Node::Actor { handlers, .. } => {
+            for handler in handlers {
+                shift_span(&mut handler.span, by);
+            }
+        }
+        Node::ActorDecl {
+            handlers,
+            eventually_clauses,
+            receive_handlers,
+            ..
+        } => {
+            for handler in handlers {
+                shift_span(&mut handler.span, by);
+            }
+            for clause in eventually_clauses {
+                shift_span(&mut clause.span, by);
+            }
+            for handler in receive_handlers {
+                shift_span(&mut handler.span, by);
+            }
+        }
+        Node::EnumDecl { variants, .. } => {
+            for variant in variants {
+                shift_span(&mut variant.span, by);
+                if let crate::EnumPayload::Named(fields) = &mut variant.payload {
+                    for field in fields {
+                        shift_span(&mut field.span, by);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    crate::ast::for_each_child_mut(node, |child| shift_node(child, by));
+}
+
+/// The span every `Node` variant but `Program` carries.
+fn own_span_mut(node: &mut Node) -> Option<&mut Span> {
+    match node {
+        Node::Program(_) => None,
+        Node::Use { span, .. }
+        | Node::Extern { span, .. }
+        | Node::Function { span, .. }
+        | Node::LiveBlock { span, .. }
+        | Node::DurationLiteral { span, .. }
+        | Node::Assert { span, .. }
+        | Node::Assume { span, .. }
+        | Node::Block { span, .. }
+        | Node::LetStatement { span, .. }
+        | Node::StaticLet { span, .. }
+        | Node::Const { span, .. }
+        | Node::Assignment { span, .. }
+        | Node::ReturnStatement { span, .. }
+        | Node::Break { span, .. }
+        | Node::BreakWith { span, .. }
+        | Node::Continue { span, .. }
+        | Node::BreakLabel { span, .. }
+        | Node::ContinueLabel { span, .. }
+        | Node::DeferStatement { span, .. }
+        | Node::IfStatement { span, .. }
+        | Node::WhileStatement { span, .. }
+        | Node::ForInStatement { span, .. }
+        | Node::ExpressionStatement { span, .. }
+        | Node::Identifier { span, .. }
+        | Node::IntegerLiteral { span, .. }
+        | Node::FloatLiteral { span, .. }
+        | Node::StringLiteral { span, .. }
+        | Node::StringInternLiteral { span, .. }
+        | Node::BytesLiteral { span, .. }
+        | Node::CharLiteral { span, .. }
+        | Node::BooleanLiteral { span, .. }
+        | Node::PrefixExpression { span, .. }
+        | Node::InfixExpression { span, .. }
+        | Node::CallExpression { span, .. }
+        | Node::TryExpression { span, .. }
+        | Node::OptionalChain { span, .. }
+        | Node::FunctionLiteral { span, .. }
+        | Node::Match { span, .. }
+        | Node::StructDecl { span, .. }
+        | Node::LetDestructureStruct { span, .. }
+        | Node::StructLiteral { span, .. }
+        | Node::FieldAccess { span, .. }
+        | Node::FieldAssignment { span, .. }
+        | Node::ArrayLiteral { span, .. }
+        | Node::IndexExpression { span, .. }
+        | Node::Slice { span, .. }
+        | Node::IndexAssignment { span, .. }
+        | Node::MapLiteral { span, .. }
+        | Node::SetLiteral { span, .. }
+        | Node::ImplBlock { span, .. }
+        | Node::TraitDecl { span, .. }
+        | Node::TypeAlias { span, .. }
+        | Node::RegionDecl { span, .. }
+        | Node::Actor { span, .. }
+        | Node::ActorDecl { span, .. }
+        | Node::ClusterDecl { span, .. }
+        | Node::TryCatch { span, .. }
+        | Node::Quantifier { span, .. }
+        | Node::InvariantStatement { span, .. }
+        | Node::Range { span, .. }
+        | Node::NamedArg { span, .. }
+        | Node::InterpolatedString { span, .. }
+        | Node::ModuleDecl { span, .. }
+        | Node::NewtypeDecl { span, .. }
+        | Node::NewtypeConstruct { span, .. }
+        | Node::SupervisorDecl { span, .. }
+        | Node::TupleLiteral { span, .. }
+        | Node::TupleIndex { span, .. }
+        | Node::LetTupleDestructure { span, .. }
+        | Node::UnsafeBlock { span, .. }
+        | Node::EnumDecl { span, .. }
+        | Node::RegionParam { span, .. }
+        | Node::BlanketImpl { span, .. }
+        | Node::StaticAssert { span, .. }
+        | Node::BenchBlock { span, .. }
+        | Node::TestFn { span, .. } => Some(span),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = "\
+fn add(int a, int b) -> int {
+    return a + b;
+}
+
+struct Point { int x, int y }
+
+fn main() {
+    let p = Point { x: 1, y: 2 };
+    if p.x > 0 {
+        println(add(p.x, p.y));
+    } else {
+        println(\"neg\");
+    }
+}
+
+main();
+";
+
+    /// Apply `edit` incrementally and check the result against a full
+    /// parse of the edited text, spans and errors included.
+    fn check(src: &str, edit: TextEdit) -> ReparseStats {
+        let mut doc = ParsedDocument::parse(src);
+        let stats = doc.edit(&edit);
+        let (program, errors) = doc.program();
+        let (full, full_errors) = crate::parse_silent(doc.text());
+        assert_eq!(format!("{program:?}"), format!("{full:?}"), "edit {edit:?}");
+        assert_eq!(errors, full_errors, "edit {edit:?}");
+        stats
+    }
+
+    fn at(src: &str, needle: &str) -> usize {
+        src[..src.find(needle).unwrap()].chars().count()
+    }
+
+    #[test]
+    fn edit_inside_a_function_reuses_later_items() {
+        let start = at(SRC, "a + b");
+        let stats = check(
+            SRC,
+            TextEdit {
+                start,
+                end: start + 5,
+                text: "a * b\n        + 0".into(),
+            },
+        );
+        assert!(stats.reused >= 2, "{stats:?}");
+        assert!(stats.reparsed <= 2, "{stats:?}");
+    }
+
+    #[test]
+    fn deleting_lines_shifts_reused_items_and_their_errors() {
+        let src = format!("{SRC}fn broken( {{\n");
+        let start = at(&src, "struct");
+        let end = at(&src, "fn main");
+        let stats = check(
+            &src,
+            TextEdit {
+                start,
+                end,
+                text: String::new(),
+            },
+        );
+        assert!(stats.reused >= 2, "{stats:?}");
+    }
+
+    #[test]
+    fn edits_that_join_or_split_items_match_a_full_parse() {
+        // Dropping a closing brace folds the next item into this one.
+        let brace = at(SRC, "}\n\nstruct");
+        check(
+            SRC,
+            TextEdit {
+                start: brace,
+                end: brace + 1,
+                text: String::new(),
+            },
+        );
+        // Splitting `main` in two in front of its `else`.
+        let close = at(SRC, "} else");
+        check(
+            SRC,
+            TextEdit {
+                start: close + 1,
+                end: close + 1,
+                text: "\n}\nfn extra() {".into(),
+            },
+        );
+    }
+
+    #[test]
+    fn every_single_char_deletion_matches_a_full_parse() {
+        for start in 0..SRC.chars().count() {
+            check(
+                SRC,
+                TextEdit {
+                    start,
+                    end: start + 1,
+                    text: String::new(),
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn every_insertion_point_matches_a_full_parse() {
+        for start in 0..=SRC.chars().count() {
+            for text in ["}", "\n", "fn "] {
+                check(
+                    SRC,
+                    TextEdit {
+                        start,
+                        end: start,
+                        text: text.into(),
+                    },
+                );
+            }
+        }
+    }
+}
@@ -85,23 +85,23 @@ struct Classes {
 /// Taint of an expression's *value*, given the current variable env.
 fn expr_taint(node: &Node, env: &HashMap<String, Taint>, cls: &Classes) -> Taint {
     match node {
-        Node::Identifier { name, .. } => env.get(name).cloned().unwrap_or_default(),
+        Node::Identifier { name, .. } => env.get(name.as_str()).cloned().unwrap_or_default(),
         Node::CallExpression {
             function,
             arguments,
             ..
         } => {
             if let Node::Identifier { name, .. } = function.as_ref() {
-                if cls.declassify.contains(name) {
+                if cls.declassify.contains(name.as_str()) {
                     // Laundered at the audited downgrade point: the result
                     // is public no matter how secret the arguments were.
                     return Taint::new();
                 }
-                if cls.secret.contains(name) {
+                if cls.secret.contains(name.as_str()) {
                     // Secret source: its result is classified regardless
                     // of the arguments.
                     let mut t = Taint::new();
-                    t.insert(name.clone());
+                    t.insert(name.to_string());
                     return t;
                 }
             }
@@ -0,0 +1,315 @@
+//! Lowers a parsed program onto [`resilient_runtime::interp`], the
+//! `#![no_std]` interpreter core, so the same source can run on a
+//! target with no `std`.
+//!
+//! The runtime core has no parser, so the host parses, typechecks and
+//! lowers, and the firmware builds or deserializes the resulting
+//! [`Program`] and runs it against its own [`Host`] hooks for print,
+//! clock and random:
+//!
+//! ```
+//! use resilient::interp_lower::lower;
+//! use resilient::parser::parse_source;
+//! use resilient_runtime::interp::{Host, Interpreter};
+//!
+//! struct Uart(String);
+//!
+//! impl Host for Uart {
+//!     fn print(&mut self, s: &str) {
+//!         self.0.push_str(s);
+//!     }
+//!     fn clock_ms(&mut self) -> u64 {
+//!         0
+//!     }
+//!     fn random_u64(&mut self) -> u64 {
+//!         0
+//!     }
+//! }
+//!
+//! let source = "fn sq(int x) -> int { return x * x; }\nprintln(sq(7));";
+//! let program = lower(&parse_source(source).unwrap()).unwrap();
+//! let mut interp = Interpreter::new(Uart(String::new()));
+//! interp.run(&program).unwrap();
+//! assert_eq!(interp.host().0, "49\n");
+//! ```
+//!
+//! The core covers the scalar subset: `let`, assignment, `if` /
+//! `else`, `while`, `return`, top-level functions, `int` / `float` /
+//! `bool` literals, arithmetic, comparison and logic, and calls to
+//! user functions, `print`, `println`, `clock_ms`, `random_int` and
+//! `random_float`. Anything else is a [`LowerError`] naming it —
+//! strings among them, since this crate builds the core without its
+//! `alloc` feature — never a silently different program. Contracts
+//! are rejected rather than dropped: the core does not check them.
+
+use crate::Node;
+use resilient_runtime::Value as RtValue;
+use resilient_runtime::interp::{BinaryOp, Expr, Function, Program, Stmt, UnaryOp};
+
+#[cfg(doc)]
+use resilient_runtime::interp::Host;
+
+/// A construct the interpreter core cannot run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LowerError {
+    pub reason: String,
+}
+
+impl std::fmt::Display for LowerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "not supported by the no_std interpreter core: {}",
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for LowerError {}
+
+fn unsupported(reason: impl Into<String>) -> LowerError {
+    LowerError {
+        reason: reason.into(),
+    }
+}
+
+/// Lower a parsed `Node::Program`.
+pub fn lower(program: &Node) -> Result<Program, LowerError> {
+    let Node::Program(stmts) = program else {
+        return Err(unsupported("expected a whole program"));
+    };
+    let mut out = Program::default();
+    for stmt in stmts {
+        match &stmt.node {
+            Node::Function {
+                name,
+                parameters,
+                body,
+                requires,
+                ensures,
+                ..
+            } => {
+                if !requires.is_empty() || !ensures.is_empty() {
+                    return Err(unsupported(format!(
+                        "`{}` has `requires` / `ensures` contracts",
+                        name
+                    )));
+                }
+                out.functions.push(Function {
+                    name: name.clone(),
+                    params: parameters.iter().map(|(_, name)| name.clone()).collect(),
+                    body: lower_block(body)?,
+                });
+            }
+            node => out.body.push(lower_stmt(node)?),
+        }
+    }
+    Ok(out)
+}
+
+fn lower_block(node: &Node) -> Result<Vec<Stmt>, LowerError> {
+    match node {
+        Node::Block { stmts, .. } => stmts.iter().map(lower_stmt).collect(),
+        other => Ok(vec![lower_stmt(other)?]),
+    }
+}
+
+fn lower_stmt(node: &Node) -> Result<Stmt, LowerError> {
+    Ok(match node {
+        Node::LetStatement { name, value, .. } => Stmt::Let(name.clone(), lower_expr(value)?),
+        Node::Assignment { name, value, .. } => Stmt::Assign(name.clone(), lower_expr(value)?),
+        Node::ExpressionStatement { expr, .. } => Stmt::Expr(lower_expr(expr)?),
+        Node::ReturnStatement { value, .. } => {
+            Stmt::Return(value.as_deref().map(lower_expr).transpose()?)
+        }
+        Node::IfStatement {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => Stmt::If(
+            lower_expr(condition)?,
+            lower_block(consequence)?,
+            match alternative {
+                Some(alt) => lower_block(alt)?,
+                None => Vec::new(),
+            },
+        ),
+        Node::WhileStatement {
+            condition,
+            body,
+            label: None,
+            ..
+        } => Stmt::While(lower_expr(condition)?, lower_block(body)?),
+        Node::Block { .. } => Stmt::If(
+            Expr::Const(RtValue::Bool(true)),
+            lower_block(node)?,
+            Vec::new(),
+        ),
+        Node::Function { name, .. } => {
+            return Err(unsupported(format!(
+                "nested function `{}`; declare it at the top level",
+                name
+            )));
+        }
+        other => return Err(unsupported(describe(other))),
+    })
+}
+
+fn lower_expr(node: &Node) -> Result<Expr, LowerError> {
+    Ok(match node {
+        Node::IntegerLiteral { value, .. } => Expr::Const(RtValue::Int(*value)),
+        Node::FloatLiteral { value, .. } => Expr::Const(RtValue::Float(*value)),
+        Node::BooleanLiteral { value, .. } => Expr::Const(RtValue::Bool(*value)),
+        Node::Identifier { name, .. } => Expr::Var(name.as_str().to_string()),
+        Node::PrefixExpression {
+            operator, right, ..
+        } => {
+            let op = match &**operator {
+                "-" => UnaryOp::Neg,
+                "!" => UnaryOp::Not,
+                other => return Err(unsupported(format!("operator `{}`", other))),
+            };
+            Expr::Unary(op, Box::new(lower_expr(right)?))
+        }
+        Node::InfixExpression {
+            left,
+            operator,
+            right,
+            ..
+        } => {
+            let op = match &**operator {
+                "+" => BinaryOp::Add,
+                "-" => BinaryOp::Sub,
+                "*" => BinaryOp::Mul,
+                "/" => BinaryOp::Div,
+                "%" => BinaryOp::Rem,
+                "==" => BinaryOp::Eq,
+                "!=" => BinaryOp::Ne,
+                "<" => BinaryOp::Lt,
+                "<=" => BinaryOp::Le,
+                ">" => BinaryOp::Gt,
+                ">=" => BinaryOp::Ge,
+                "&&" => BinaryOp::And,
+                "||" => BinaryOp::Or,
+                other => return Err(unsupported(format!("operator `{}`", other))),
+            };
+            Expr::Binary(
+                op,
+                Box::new(lower_expr(left)?),
+                Box::new(lower_expr(right)?),
+            )
+        }
+        Node::CallExpression {
+            function,
+            arguments,
+            ..
+        } => {
+            let Node::Identifier { name, .. } = function.as_ref() else {
+                return Err(unsupported("a call through an expression"));
+            };
+            Expr::Call(
+                name.as_str().to_string(),
+                arguments.iter().map(lower_expr).collect::<Result<_, _>>()?,
+            )
+        }
+        other => return Err(unsupported(describe(other))),
+    })
+}
+
+/// Name an unsupported node for the error.
+fn describe(node: &Node) -> String {
+    match node {
+        Node::StringLiteral { .. } | Node::StringInternLiteral { .. } => {
+            "string values (the core is built without `alloc`)".to_string()
+        }
+        Node::WhileStatement { .. } => "labelled loops".to_string(),
+        other => {
+            // `Debug` leads with the variant name; the fields are noise.
+            let debug = format!("{:?}", other);
+            let variant = debug
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap_or("this construct");
+            format!("`{}`", variant)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use resilient_runtime::interp::{Host, InterpError, Interpreter};
+
+    #[derive(Default)]
+    struct TestHost {
+        out: String,
+    }
+
+    impl Host for TestHost {
+        fn print(&mut self, s: &str) {
+            self.out.push_str(s);
+        }
+        fn clock_ms(&mut self) -> u64 {
+            0
+        }
+        fn random_u64(&mut self) -> u64 {
+            0
+        }
+    }
+
+    fn run_core(src: &str) -> Result<String, InterpError> {
+        let program = lower(&crate::parser::parse_source(src).unwrap()).unwrap();
+        let mut interp = Interpreter::new(TestHost::default());
+        interp.run(&program)?;
+        Ok(interp.into_host().out)
+    }
+
+    #[test]
+    fn the_core_prints_what_the_host_interpreter_prints() {
+        // The debug tree walker recurses deeply on `fib`; give it the
+        // same enlarged stack as `recursive_function_with_params`.
+        let handle = std::thread::Builder::new()
+            .stack_size(8 * 1024 * 1024)
+            .spawn(|| {
+                let src = "\
+fn fib(int n) -> int {
+    if n < 2 { return n; }
+    return fib(n - 1) + fib(n - 2);
+}
+let total = 0;
+let i = 0;
+while i < 10 {
+    total = total + fib(i);
+    i = i + 1;
+}
+println(total);
+println(-7 % 3);
+println(2.5 * 2.0);
+println(total > 80 && !(i == 3));
+";
+                let host = crate::interpreter::run_program(src);
+                assert!(host.ok, "{:?}", host.errors);
+                assert_eq!(run_core(src).unwrap(), host.stdout);
+                assert_eq!(host.stdout, "88\n-1\n5\ntrue\n");
+            })
+            .expect("thread spawn failed");
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn unsupported_constructs_are_named() {
+        let cases = [
+            ("println(\"hi\");", "string values"),
+            ("let a = [1, 2];", "`ArrayLiteral`"),
+            (
+                "fn f(int x) requires x > 0 { return x; }",
+                "`f` has `requires` / `ensures` contracts",
+            ),
+        ];
+        for (src, reason) in cases {
+            let err = lower(&crate::parser::parse_source(src).unwrap()).unwrap_err();
+            assert!(err.reason.contains(reason), "{src}: {err}");
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Running programs in-process.
+//!
+//! [`run_program`] parses and runs source text; [`run`] runs a program
+//! the caller already parsed, and perhaps type-checked, with
+//! [`crate::parser`] and [`crate::typechecker`]. Either way the
+//! program's `println` / `print` output is captured into
+//! [`RunResult::stdout`] instead of reaching the process's stdout.
+//! Step, memory and stack budgets are set through [`crate::runtime`].
+//! [`Session`] keeps a loaded program around and calls its functions
+//! with Rust values, optionally watched by an
+//! [`EvalObserver`](crate::eval_observer::EvalObserver).
+//!
+//! ```
+//! use resilient::{interpreter, parser, typechecker::TypeChecker};
+//!
+//! let program = parser::parse_source("fn sq(int x) -> int { return x * x; }\nprintln(sq(7));").unwrap();
+//! TypeChecker::new().check_program(&program).unwrap();
+//! let result = interpreter::run(&program);
+//! assert!(result.ok);
+//! assert_eq!(result.stdout, "49\n");
+//! ```
+
+pub use crate::{RunResult, run_program};
+
+use crate::bridge::{BridgeError, FromResilient, IntoArgs, ResilientValue};
+use crate::eval_observer::EvalObserver;
+use crate::type_builtins::value_type_name;
+use crate::{Interpreter, Node, Value, fuel, memory_limit, output_sink};
+
+/// Run a parsed program on a fresh interpreter, then drain any actor
+/// messages it left queued. The step budget is refilled and the memory
+/// estimate reset first, as for each `rz` run. A runtime error stops
+/// the run; it is the one entry in `errors`.
+pub fn run(program: &Node) -> RunResult {
+    fuel::refill();
+    memory_limit::reset();
+    crate::clear_exit_request();
+    let (eval_result, captured) = output_sink::with_captured_output(|| {
+        let mut interp = Interpreter::new();
+        interp.source_path = "<input>".to_string();
+        interp.eval(program)?;
+        crate::run_pending_actors(&mut interp)?;
+        Ok(Value::Void)
+    });
+    match eval_result {
+        Ok(_) => RunResult {
+            ok: true,
+            stdout: captured,
+            errors: Vec::new(),
+        },
+        Err(e) => RunResult {
+            ok: false,
+            stdout: captured,
+            errors: vec![e],
+        },
+    }
+}
+
+/// A loaded program whose functions the host calls with Rust values
+/// (see [`crate::bridge`]). Unlike [`run`], output is not captured.
+///
+/// ```
+/// use resilient::interpreter::Session;
+///
+/// let mut session = Session::load("fn add(int a, int b) -> int { return a + b; }").unwrap();
+/// let sum: i64 = session.call("add", (2, 3)).unwrap();
+/// assert_eq!(sum, 5);
+/// ```
+pub struct Session {
+    interp: Interpreter,
+}
+
+impl Session {
+    /// Parse `src` and run its top level, as [`run`] does, keeping the
+    /// interpreter for later [`Session::call`]s.
+    pub fn load(src: &str) -> Result<Session, BridgeError> {
+        let (program, parse_errors) = crate::parse_silent(src);
+        if !parse_errors.is_empty() {
+            return Err(BridgeError::Load(parse_errors));
+        }
+        fuel::refill();
+        memory_limit::reset();
+        crate::clear_exit_request();
+        let mut interp = Interpreter::new();
+        interp.source_path = "<input>".to_string();
+        interp
+            .eval(&program)
+            .and_then(|_| crate::run_pending_actors(&mut interp))
+            .map_err(|e| BridgeError::Load(vec![e]))?;
+        Ok(Session { interp })
+    }
+
+    /// Register `observer` for the calls made from now on, and the
+    /// calls those make; the top level `load` ran is not replayed.
+    /// See [`crate::eval_observer`].
+    pub fn observe(&mut self, observer: Box<dyn EvalObserver>) {
+        self.interp.observe(observer);
+    }
+
+    /// Call the top-level function `name` and convert what it returns.
+    pub fn call<R: FromResilient>(
+        &mut self,
+        name: &str,
+        args: impl IntoArgs,
+    ) -> Result<R, BridgeError> {
+        let func = self
+            .interp
+            .env
+            .get(name)
+            .ok_or_else(|| BridgeError::UnknownFunction(name.to_string()))?;
+        let args: Vec<Value> = args.into_args().into_iter().map(|a| a.0).collect();
+        crate::clear_exit_request();
+        match &func {
+            Value::Function(f) if f.parameters.len() != args.len() => {
+                return Err(BridgeError::Arity {
+                    name: name.to_string(),
+                    expected: f.parameters.len(),
+                    found: args.len(),
+                });
+            }
+            Value::Function(_) | Value::Builtin { .. } => {}
+            other => {
+                return Err(BridgeError::NotAFunction {
+                    name: name.to_string(),
+                    found: value_type_name(other).to_string(),
+                });
+            }
+        }
+        let result = self
+            .interp
+            .apply_function(&func, args)
+            .map_err(BridgeError::Runtime)?;
+        R::from_resilient(ResilientValue(result))
+    }
+}
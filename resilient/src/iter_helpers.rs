@@ -18,9 +18,9 @@ pub(crate) fn builtin_enumerate(args: &[Value]) -> RResult<Value> {
             let out: Vec<Value> = items
                 .iter()
                 .enumerate()
-                .map(|(i, v)| Value::Array(vec![Value::Int(i as i64), v.clone()]))
+                .map(|(i, v)| Value::Array(vec![Value::Int(i as i64), v.clone()].into()))
                 .collect();
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("enumerate: expected array, got {}", other)),
         _ => Err(format!(
@@ -38,9 +38,11 @@ pub(crate) fn builtin_array_zip3(args: &[Value]) -> RResult<Value> {
             let n = a.len().min(b.len()).min(c.len());
             let mut out: Vec<Value> = Vec::with_capacity(n);
             for i in 0..n {
-                out.push(Value::Array(vec![a[i].clone(), b[i].clone(), c[i].clone()]));
+                out.push(Value::Array(
+                    vec![a[i].clone(), b[i].clone(), c[i].clone()].into(),
+                ));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b, c] => Err(format!(
             "array_zip3: expected (array, array, array), got ({}, {}, {})",
@@ -92,7 +94,7 @@ mod tests {
 
     fn as_array(v: Value) -> Vec<Value> {
         match v {
-            Value::Array(items) => items,
+            Value::Array(items) => items.into_inner(),
             other => panic!("expected Array, got {:?}", other),
         }
     }
@@ -144,11 +146,14 @@ mod tests {
 
     #[test]
     fn enumerate_mixed_types() {
-        let arr = Value::Array(vec![
-            Value::Int(1),
-            Value::String("hello".to_string()),
-            Value::Bool(true),
-        ]);
+        let arr = Value::Array(
+            vec![
+                Value::Int(1),
+                Value::String("hello".to_string()),
+                Value::Bool(true),
+            ]
+            .into(),
+        );
         let r = as_array(builtin_enumerate(&[arr]).unwrap());
         assert_eq!(r.len(), 3);
     }
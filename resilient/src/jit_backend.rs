@@ -15,7 +15,7 @@
 
 #![allow(dead_code)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use cranelift::prelude::*;
 use cranelift_jit::{JITBuilder, JITModule};
@@ -2321,6 +2321,340 @@ pub(crate) fn run_with_stats(program: &Node) -> Result<(i64, u32, u32, u32), Jit
     Ok((result, cache.hits, cache.misses, cache.compiles))
 }
 
+// ---------- `--backend=jit`: hot functions for the tree-walker ----------
+//
+// `--jit` compiles the whole program and falls back to the VM when
+// any construct doesn't lower. `--backend=jit` keeps the tree-walker
+// in charge and compiles only the top-level functions that are pure
+// integer code — the numeric, loop-heavy kernels that dominate run
+// time — which the walker then calls natively.
+
+/// Most parameters a hot function may take; `call_hot_function`
+/// dispatches on arity to a fixed `extern "C"` signature.
+const HOT_MAX_ARITY: usize = 4;
+
+/// Infix operators a hot function may use. Shifts are left out: the
+/// walker rejects an out-of-range shift amount, native code doesn't.
+const HOT_OPERATORS: &[&str] = &[
+    "+", "-", "*", "/", "%", "==", "!=", "<", ">", "<=", ">=", "&&", "||", "&", "|", "^",
+];
+
+/// The compiled hot functions of the program being run.
+struct HotFunctions {
+    /// Owns the code the entries point into.
+    _module: JITModule,
+    /// Name → (span of the body block, arity, entry point). The span
+    /// tells the top-level function apart from a nested one of the
+    /// same name.
+    entries: HashMap<String, (crate::span::Span, usize, *const u8)>,
+}
+
+thread_local! {
+    static HOT_FUNCTIONS: std::cell::RefCell<Option<HotFunctions>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Keeps the hot functions callable; dropping it frees them.
+pub(crate) struct HotFunctionsGuard {
+    /// How many functions compiled.
+    pub(crate) compiled: usize,
+}
+
+impl Drop for HotFunctionsGuard {
+    fn drop(&mut self) {
+        HOT_FUNCTIONS.with(|h| h.borrow_mut().take());
+    }
+}
+
+/// Compile `program`'s hot functions for [`call_hot_function`] on
+/// this thread. Compiling never fails the run: a function that
+/// doesn't lower just stays with the walker.
+pub(crate) fn install_hot_functions(program: &Node) -> HotFunctionsGuard {
+    let hot = match program {
+        Node::Program(stmts) => compile_hot_functions(stmts),
+        _ => None,
+    };
+    let compiled = hot.as_ref().map_or(0, |h| h.entries.len());
+    HOT_FUNCTIONS.with(|h| *h.borrow_mut() = hot);
+    HotFunctionsGuard { compiled }
+}
+
+/// Run `name` natively if it is a compiled hot function with body
+/// `body` and every argument is an int. `None` leaves the call to the
+/// walker, including when the native call faults (a division by
+/// zero, say): hot functions have no side effects, so the walker
+/// reruns the call and reports the error its usual way.
+pub(crate) fn call_hot_function(name: &str, body: &Node, args: &[crate::Value]) -> Option<i64> {
+    let Node::Block { span, .. } = body else {
+        return None;
+    };
+    let code = HOT_FUNCTIONS.with(|h| {
+        let hot = h.borrow();
+        let &(hot_span, arity, code) = hot.as_ref()?.entries.get(name)?;
+        (hot_span == *span && arity == args.len()).then_some(code)
+    })?;
+    let mut ints = [0i64; HOT_MAX_ARITY];
+    for (slot, arg) in ints.iter_mut().zip(args) {
+        let crate::Value::Int(n) = arg else {
+            return None;
+        };
+        *slot = *n;
+    }
+    let [a, b, c, d] = ints;
+    // SAFETY: `code` was compiled with `args.len()` i64 parameters
+    // and an i64 return, and the module owning it stays alive until
+    // the `HotFunctionsGuard` drops, which is after the walker's last
+    // call. Aborts from the division shims land in
+    // `jit_invoke_with_abort_catch`, as for `--jit`.
+    let result = match args.len() {
+        0 => {
+            let f: unsafe extern "C" fn() -> i64 = unsafe { std::mem::transmute(code) };
+            jit_invoke_with_abort_catch(|| unsafe { f() })
+        }
+        1 => {
+            let f: unsafe extern "C" fn(i64) -> i64 = unsafe { std::mem::transmute(code) };
+            jit_invoke_with_abort_catch(|| unsafe { f(a) })
+        }
+        2 => {
+            let f: unsafe extern "C" fn(i64, i64) -> i64 = unsafe { std::mem::transmute(code) };
+            jit_invoke_with_abort_catch(|| unsafe { f(a, b) })
+        }
+        3 => {
+            let f: unsafe extern "C" fn(i64, i64, i64) -> i64 =
+                unsafe { std::mem::transmute(code) };
+            jit_invoke_with_abort_catch(|| unsafe { f(a, b, c) })
+        }
+        4 => {
+            let f: unsafe extern "C" fn(i64, i64, i64, i64) -> i64 =
+                unsafe { std::mem::transmute(code) };
+            jit_invoke_with_abort_catch(|| unsafe { f(a, b, c, d) })
+        }
+        _ => return None,
+    };
+    result.ok()
+}
+
+/// Whether `n` keeps to what a hot function may do — int and bool
+/// arithmetic, locals, `if`, `while` and calls — pushing each callee's
+/// name onto `callees`. Nothing here prints, allocates or reads a
+/// global, so a call behaves the same natively or in the walker.
+fn hot_body_ok(n: &Node, callees: &mut Vec<String>) -> bool {
+    match n {
+        Node::IntegerLiteral { .. } | Node::BooleanLiteral { .. } | Node::Identifier { .. } => true,
+        Node::PrefixExpression {
+            operator, right, ..
+        } => matches!(*operator, "-" | "!") && hot_body_ok(right, callees),
+        Node::InfixExpression {
+            left,
+            operator,
+            right,
+            ..
+        } => {
+            HOT_OPERATORS.contains(operator)
+                && hot_body_ok(left, callees)
+                && hot_body_ok(right, callees)
+        }
+        Node::IfStatement {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => {
+            hot_body_ok(condition, callees)
+                && hot_body_ok(consequence, callees)
+                && alternative.as_ref().is_none_or(|a| hot_body_ok(a, callees))
+        }
+        Node::WhileStatement {
+            condition,
+            body,
+            invariants,
+            label: None,
+            ..
+        } => invariants.is_empty() && hot_body_ok(condition, callees) && hot_body_ok(body, callees),
+        Node::Block { stmts, .. } => stmts.iter().all(|s| hot_body_ok(s, callees)),
+        Node::LetStatement { value, .. } | Node::Assignment { value, .. } => {
+            hot_body_ok(value, callees)
+        }
+        Node::ReturnStatement { value, .. } => {
+            value.as_ref().is_some_and(|v| hot_body_ok(v, callees))
+        }
+        Node::ExpressionStatement { expr, .. } => hot_body_ok(expr, callees),
+        Node::CallExpression {
+            function,
+            arguments,
+            ..
+        } => {
+            let Node::Identifier { name, .. } = function.as_ref() else {
+                return false;
+            };
+            callees.push(name.to_string());
+            arguments.iter().all(|a| hot_body_ok(a, callees))
+        }
+        _ => false,
+    }
+}
+
+/// The top-level functions worth compiling, callees before callers:
+/// `int` parameters and result, no generics or contracts, a body
+/// [`hot_body_ok`] accepts, and calls only to other selected
+/// functions. Recursion stays with the walker, whose call-depth limit
+/// turns a runaway recursion into an error instead of a native stack
+/// overflow.
+fn select_hot_functions<'a>(
+    stmts: &'a [crate::Spanned<Node>],
+    excluded: &HashSet<String>,
+) -> Vec<(&'a str, &'a Node)> {
+    let mut defined: HashMap<&str, usize> = HashMap::new();
+    for spanned in stmts {
+        if let Node::Function { name, .. } = &spanned.node {
+            *defined.entry(name.as_str()).or_default() += 1;
+        }
+    }
+    let mut pending: Vec<(&str, &Node, Vec<String>)> = Vec::new();
+    for spanned in stmts {
+        let Node::Function {
+            name,
+            parameters,
+            body,
+            requires,
+            ensures,
+            return_type,
+            type_params,
+            fails,
+            recovers_to,
+            ..
+        } = &spanned.node
+        else {
+            continue;
+        };
+        let mut callees = Vec::new();
+        if defined[name.as_str()] == 1
+            && !excluded.contains(name)
+            && parameters.len() <= HOT_MAX_ARITY
+            && parameters.iter().all(|(ty, _)| ty == "int")
+            && return_type.as_deref() == Some("int")
+            && type_params.is_empty()
+            && requires.is_empty()
+            && ensures.is_empty()
+            && fails.is_empty()
+            && recovers_to.is_none()
+            && matches!(body.as_ref(), Node::Block { .. })
+            && hot_body_ok(body, &mut callees)
+        {
+            pending.push((name, &spanned.node, callees));
+        }
+    }
+    // Accept functions whose callees are all accepted until nothing
+    // changes; whatever is left recurses or calls a rejected function.
+    let mut selected: Vec<(&str, &Node)> = Vec::new();
+    let mut names: HashSet<&str> = HashSet::new();
+    loop {
+        let before = selected.len();
+        pending.retain(|(name, node, callees)| {
+            if !callees.iter().all(|c| names.contains(c.as_str())) {
+                return true;
+            }
+            names.insert(name);
+            selected.push((name, node));
+            false
+        });
+        if selected.len() == before {
+            return selected;
+        }
+    }
+}
+
+/// Compile the selected functions into one module. A function that
+/// doesn't lower is excluded, along with its callers, and the rest
+/// compiled again in a fresh module.
+fn compile_hot_functions(stmts: &[crate::Spanned<Node>]) -> Option<HotFunctions> {
+    let mut excluded: HashSet<String> = HashSet::new();
+    loop {
+        let selected = select_hot_functions(stmts, &excluded);
+        if selected.is_empty() {
+            return None;
+        }
+        match compile_hot_module(&selected) {
+            Ok(hot) => return Some(hot),
+            Err(Some(name)) => {
+                excluded.insert(name);
+            }
+            Err(None) => return None,
+        }
+    }
+}
+
+/// One attempt for [`compile_hot_functions`]. The error names the
+/// function that failed to lower, or is `None` when the module itself
+/// couldn't be set up or linked.
+fn compile_hot_module(selected: &[(&str, &Node)]) -> Result<HotFunctions, Option<String>> {
+    let mut module = make_module().map_err(|_| None)?;
+    let imports = declare_jit_runtime_imports(&mut module).map_err(|_| None)?;
+    let mut functions: HashMap<String, FuncId> = HashMap::new();
+    let mut function_arities: HashMap<String, usize> = HashMap::new();
+    let mut function_kind: HashMap<String, ValueKind> = HashMap::new();
+    let mut fn_asts: FnAstMap = HashMap::new();
+    for &(name, node) in selected {
+        let Node::Function {
+            parameters, body, ..
+        } = node
+        else {
+            continue;
+        };
+        let mut sig = module.make_signature();
+        for _ in parameters {
+            sig.params.push(AbiParam::new(types::I64));
+        }
+        sig.returns.push(AbiParam::new(types::I64));
+        let func_id = module
+            .declare_function(name, Linkage::Local, &sig)
+            .map_err(|_| None)?;
+        functions.insert(name.to_string(), func_id);
+        function_arities.insert(name.to_string(), parameters.len());
+        function_kind.insert(name.to_string(), type_text_to_kind("int"));
+        fn_asts.insert(name.to_string(), (parameters.clone(), (**body).clone()));
+    }
+    for &(name, node) in selected {
+        let Node::Function {
+            parameters, body, ..
+        } = node
+        else {
+            continue;
+        };
+        compile_function(
+            functions[name],
+            name,
+            parameters,
+            body,
+            &functions,
+            &function_arities,
+            &fn_asts,
+            &function_kind,
+            imports,
+            &mut module,
+        )
+        .map_err(|_| Some(name.to_string()))?;
+    }
+    module.finalize_definitions().map_err(|_| None)?;
+    let mut entries = HashMap::new();
+    for &(name, node) in selected {
+        let Node::Function {
+            parameters, body, ..
+        } = node
+        else {
+            continue;
+        };
+        let Node::Block { span, .. } = body.as_ref() else {
+            continue;
+        };
+        let code = module.get_finalized_function(functions[name]);
+        entries.insert(name.to_string(), (*span, parameters.len(), code));
+    }
+    Ok(HotFunctions {
+        _module: module,
+        entries,
+    })
+}
+
 /// FFI v2 (JIT path): compile a `Program` AST to native code using a
 /// pre-built `JITBuilder` (with foreign symbols already registered via
 /// `JITBuilder::symbol`) and a list of `ForeignJitEntry` descriptors.
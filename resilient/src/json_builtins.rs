@@ -2,6 +2,9 @@
 //!
 //! * `to_json(value) -> string` — serialize any Value to JSON.
 //! * `from_json(s) -> value` — parse a JSON string into a Value.
+//! * `json_stringify(value, pretty)` / `json_parse(s)` — the same, with
+//!   optional indentation and typed `InvalidJson` parse errors that
+//!   carry a line and column.
 //!
 //! Mapping:
 //! - `int`    ↔ JSON number (integer)
@@ -37,7 +40,7 @@ type RResult<T> = Result<T, String>;
 /// ```
 pub(crate) fn builtin_to_json(args: &[Value]) -> RResult<Value> {
     match args {
-        [v] => serialize_value(v).map(Value::String),
+        [v] => encoded("to_json", serialize_value(v)),
         _ => Err(format!("to_json: expected 1 argument, got {}", args.len())),
     }
 }
@@ -45,7 +48,7 @@ pub(crate) fn builtin_to_json(args: &[Value]) -> RResult<Value> {
 /// `json_encode(value) -> string` — alias for `to_json`.
 pub(crate) fn builtin_json_encode(args: &[Value]) -> RResult<Value> {
     match args {
-        [v] => serialize_value(v).map(Value::String),
+        [v] => encoded("json_encode", serialize_value(v)),
         _ => Err(format!(
             "json_encode: expected 1 argument, got {}",
             args.len()
@@ -56,7 +59,7 @@ pub(crate) fn builtin_json_encode(args: &[Value]) -> RResult<Value> {
 /// `json_encode_pretty(value) -> string` — indented JSON, 2 spaces per level.
 pub(crate) fn builtin_json_encode_pretty(args: &[Value]) -> RResult<Value> {
     match args {
-        [v] => serialize_value_pretty(v, 0).map(Value::String),
+        [v] => encoded("json_encode_pretty", serialize_value_pretty(v, 0)),
         _ => Err(format!(
             "json_encode_pretty: expected 1 argument, got {}",
             args.len()
@@ -64,6 +67,31 @@ pub(crate) fn builtin_json_encode_pretty(args: &[Value]) -> RResult<Value> {
     }
 }
 
+/// `json_stringify(value) -> string`, `json_stringify(value, pretty) -> string`
+///
+/// Serializes like `to_json`; with `pretty` set (also spelled
+/// `pretty: true`) the output is indented like `json_encode_pretty`.
+pub(crate) fn builtin_json_stringify(args: &[Value]) -> RResult<Value> {
+    match args {
+        [v] | [v, Value::Bool(false)] => encoded("json_stringify", serialize_value(v)),
+        [v, Value::Bool(true)] => encoded("json_stringify", serialize_value_pretty(v, 0)),
+        [_, other] => Err(format!(
+            "json_stringify: pretty must be a bool, got {other}"
+        )),
+        _ => Err(format!(
+            "json_stringify: expected 1 or 2 arguments, got {}",
+            args.len()
+        )),
+    }
+}
+
+/// The string `json` serialized to, or its error under the name of
+/// the builtin that was called.
+fn encoded(builtin: &str, json: RResult<String>) -> RResult<Value> {
+    json.map(Value::String)
+        .map_err(|e| format!("{}: {}", builtin, e))
+}
+
 /// `json_decode(string) -> Result<Value, string>` — safe JSON parse.
 ///
 /// Unlike `from_json` which propagates errors as Resilient runtime errors,
@@ -71,32 +99,16 @@ pub(crate) fn builtin_json_encode_pretty(args: &[Value]) -> RResult<Value> {
 /// handle parse errors without a try/catch.
 pub(crate) fn builtin_json_decode(args: &[Value]) -> RResult<Value> {
     match args {
-        [Value::String(s)] => {
-            let mut parser = JsonParser::new(s);
-            match parser.parse_value() {
-                Err(e) => Ok(Value::Result {
-                    ok: false,
-                    payload: Box::new(Value::String(e)),
-                }),
-                Ok(v) => {
-                    parser.skip_ws();
-                    if parser.pos < parser.src.len() {
-                        Ok(Value::Result {
-                            ok: false,
-                            payload: Box::new(Value::String(format!(
-                                "json_decode: trailing characters at position {}",
-                                parser.pos
-                            ))),
-                        })
-                    } else {
-                        Ok(Value::Result {
-                            ok: true,
-                            payload: Box::new(v),
-                        })
-                    }
-                }
-            }
-        }
+        [Value::String(s)] => Ok(match parse_document(s) {
+            Ok(v) => Value::Result {
+                ok: true,
+                payload: Box::new(v),
+            },
+            Err(e) => Value::Result {
+                ok: false,
+                payload: Box::new(Value::String(e.render("json_decode", s))),
+            },
+        }),
         [other] => Err(format!("json_decode: expected string, got {other}")),
         _ => Err(format!(
             "json_decode: expected 1 argument, got {}",
@@ -108,14 +120,7 @@ pub(crate) fn builtin_json_decode(args: &[Value]) -> RResult<Value> {
 /// `json_valid(string) -> bool` — true if the string is valid JSON.
 pub(crate) fn builtin_json_valid(args: &[Value]) -> RResult<Value> {
     match args {
-        [Value::String(s)] => {
-            let mut parser = JsonParser::new(s);
-            let ok = parser.parse_value().is_ok() && {
-                parser.skip_ws();
-                parser.pos >= parser.src.len()
-            };
-            Ok(Value::Bool(ok))
-        }
+        [Value::String(s)] => Ok(Value::Bool(parse_document(s).is_ok())),
         [other] => Err(format!("json_valid: expected string, got {other}")),
         _ => Err(format!(
             "json_valid: expected 1 argument, got {}",
@@ -182,14 +187,16 @@ fn serialize_value_pretty(v: &Value, indent: usize) -> RResult<String> {
     }
 }
 
-fn serialize_value(v: &Value) -> RResult<String> {
+/// Compact JSON for `v`. Errors name no builtin; [`encoded`] adds the
+/// caller's.
+pub(crate) fn serialize_value(v: &Value) -> RResult<String> {
     match v {
         Value::Int(n) => Ok(n.to_string()),
         Value::Float(f) => {
             if f.is_nan() {
-                Err("to_json: NaN is not a valid JSON value".to_string())
+                Err("NaN is not a valid JSON value".to_string())
             } else if f.is_infinite() {
-                Err("to_json: Infinity is not a valid JSON value".to_string())
+                Err("Infinity is not a valid JSON value".to_string())
             } else if f.fract() == 0.0 && f.abs() < 1e15 {
                 Ok(format!("{:.1}", f))
             } else {
@@ -239,13 +246,13 @@ fn serialize_value(v: &Value) -> RResult<String> {
             Ok(format!("[{}]", parts?.join(", ")))
         }
         other => Err(format!(
-            "to_json: cannot serialize value of type {}",
+            "cannot serialize value of type {}",
             type_name(other)
         )),
     }
 }
 
-fn json_escape_string(s: &str) -> String {
+pub(crate) fn json_escape_string(s: &str) -> String {
     // RES-2260: write the control-char escape directly via `std::fmt::
     // Write` instead of `push_str(&format!(...))`. Each control
     // character previously allocated a 6-char `String` only to be
@@ -311,18 +318,7 @@ fn type_name(v: &Value) -> &'static str {
 /// Returns an error for malformed JSON.
 pub(crate) fn builtin_from_json(args: &[Value]) -> RResult<Value> {
     match args {
-        [Value::String(s)] => {
-            let mut parser = JsonParser::new(s);
-            let v = parser.parse_value()?;
-            parser.skip_ws();
-            if parser.pos < parser.src.len() {
-                return Err(format!(
-                    "from_json: trailing characters after JSON value at position {}",
-                    parser.pos
-                ));
-            }
-            Ok(v)
-        }
+        [Value::String(s)] => parse_document(s).map_err(|e| e.render("from_json", s)),
         [other] => Err(format!("from_json: expected string, got {other}")),
         _ => Err(format!(
             "from_json: expected 1 argument, got {}",
@@ -331,7 +327,82 @@ pub(crate) fn builtin_from_json(args: &[Value]) -> RResult<Value> {
     }
 }
 
+/// `json_parse(s) -> value`
+///
+/// Parses `s` with the same mapping as `from_json`. Malformed input
+/// fails with the typed `InvalidJson` variant, so `try ... catch
+/// InvalidJson` handles it, and the message points at the line and
+/// column where parsing stopped:
+///
+/// ```text
+/// json_parse("\{\"temp\": 21.5}")  // {"temp" -> 21.5}
+/// json_parse("[1, 2,")             // json_parse: InvalidJson: line 1, column 7: unexpected end of input
+/// ```
+pub(crate) fn builtin_json_parse(args: &[Value]) -> RResult<Value> {
+    match args {
+        [Value::String(s)] => parse_document(s).map_err(|e| {
+            let (line, column) = e.line_col(s);
+            format!(
+                "json_parse: InvalidJson: line {}, column {}: {}",
+                line, column, e.msg
+            )
+        }),
+        [other] => Err(format!("json_parse: expected string, got {other}")),
+        _ => Err(format!(
+            "json_parse: expected 1 argument, got {}",
+            args.len()
+        )),
+    }
+}
+
+/// A parse failure: what went wrong, and the byte offset it was
+/// found at.
+struct JsonError {
+    pos: usize,
+    msg: String,
+}
+
+impl JsonError {
+    /// The 1-based line and column (in characters) of the failure.
+    fn line_col(&self, src: &str) -> (usize, usize) {
+        let before = &src.as_bytes()[..self.pos.min(src.len())];
+        let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+        let line_start = before
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        let column = String::from_utf8_lossy(&before[line_start..])
+            .chars()
+            .count()
+            + 1;
+        (line, column)
+    }
+
+    /// `<builtin>: <msg> at line L, column C`.
+    fn render(&self, builtin: &str, src: &str) -> String {
+        let (line, column) = self.line_col(src);
+        format!(
+            "{}: {} at line {}, column {}",
+            builtin, self.msg, line, column
+        )
+    }
+}
+
+type PResult<T> = Result<T, JsonError>;
+
+/// Parse all of `s` as a single JSON value.
+fn parse_document(s: &str) -> PResult<Value> {
+    let mut parser = JsonParser::new(s);
+    let v = parser.parse_value()?;
+    parser.skip_ws();
+    if parser.pos < parser.src.len() {
+        return Err(parser.error("trailing characters after the JSON value"));
+    }
+    Ok(v)
+}
+
 struct JsonParser<'a> {
+    text: &'a str,
     src: &'a [u8],
     pos: usize,
 }
@@ -339,11 +410,31 @@ struct JsonParser<'a> {
 impl<'a> JsonParser<'a> {
     fn new(s: &'a str) -> Self {
         Self {
+            text: s,
             src: s.as_bytes(),
             pos: 0,
         }
     }
 
+    fn error(&self, msg: impl Into<String>) -> JsonError {
+        self.error_at(self.pos, msg)
+    }
+
+    fn error_at(&self, pos: usize, msg: impl Into<String>) -> JsonError {
+        JsonError {
+            pos,
+            msg: msg.into(),
+        }
+    }
+
+    /// The character starting at byte `pos`, for messages.
+    fn char_at(&self, pos: usize) -> char {
+        self.text
+            .get(pos..)
+            .and_then(|rest| rest.chars().next())
+            .unwrap_or(self.src[pos] as char)
+    }
+
     fn skip_ws(&mut self) {
         while self.pos < self.src.len() && self.src[self.pos].is_ascii_whitespace() {
             self.pos += 1;
@@ -354,38 +445,40 @@ impl<'a> JsonParser<'a> {
         self.src.get(self.pos).copied()
     }
 
-    fn consume(&mut self) -> RResult<u8> {
+    fn consume(&mut self) -> PResult<u8> {
         if self.pos < self.src.len() {
             let b = self.src[self.pos];
             self.pos += 1;
             Ok(b)
         } else {
-            Err("from_json: unexpected end of input".to_string())
+            Err(self.error("unexpected end of input"))
         }
     }
 
-    fn expect_byte(&mut self, expected: u8) -> RResult<()> {
+    fn expect_byte(&mut self, expected: u8) -> PResult<()> {
         let b = self.consume()?;
         if b != expected {
-            Err(format!(
-                "from_json: expected '{}' at position {}, got '{}'",
-                expected as char,
+            Err(self.error_at(
                 self.pos - 1,
-                b as char
+                format!(
+                    "expected '{}', got '{}'",
+                    expected as char,
+                    self.char_at(self.pos - 1)
+                ),
             ))
         } else {
             Ok(())
         }
     }
 
-    fn expect_str(&mut self, s: &[u8]) -> RResult<()> {
+    fn expect_str(&mut self, s: &[u8]) -> PResult<()> {
         for &b in s {
             self.expect_byte(b)?;
         }
         Ok(())
     }
 
-    fn parse_value(&mut self) -> RResult<Value> {
+    fn parse_value(&mut self) -> PResult<Value> {
         self.skip_ws();
         match self.peek() {
             Some(b'n') => {
@@ -404,15 +497,14 @@ impl<'a> JsonParser<'a> {
             Some(b'[') => self.parse_array(),
             Some(b'{') => self.parse_object(),
             Some(b'-') | Some(b'0'..=b'9') => self.parse_number(),
-            Some(b) => Err(format!(
-                "from_json: unexpected character '{}' at position {}",
-                b as char, self.pos
-            )),
-            None => Err("from_json: unexpected end of input".to_string()),
+            Some(_) => {
+                Err(self.error(format!("unexpected character '{}'", self.char_at(self.pos))))
+            }
+            None => Err(self.error("unexpected end of input")),
         }
     }
 
-    fn parse_string(&mut self) -> RResult<String> {
+    fn parse_string(&mut self) -> PResult<String> {
         self.expect_byte(b'"')?;
         // RES-1946: scan ahead for the closing unescaped `"` to get an
         // upper bound on the decoded string length. Escape sequences
@@ -440,61 +532,82 @@ impl<'a> JsonParser<'a> {
             }
             bound.unwrap_or(16)
         };
-        let mut out = String::with_capacity(cap);
+        // Raw bytes are copied through as-is: the input is a `&str`,
+        // so multi-byte characters arrive whole and stay valid UTF-8.
+        let mut out: Vec<u8> = Vec::with_capacity(cap);
         loop {
             let b = self.consume()?;
             match b {
-                b'"' => return Ok(out),
+                b'"' => {
+                    return String::from_utf8(out)
+                        .map_err(|_| self.error("invalid UTF-8 in string"));
+                }
                 b'\\' => {
                     let esc = self.consume()?;
-                    match esc {
-                        b'"' => out.push('"'),
-                        b'\\' => out.push('\\'),
-                        b'/' => out.push('/'),
-                        b'n' => out.push('\n'),
-                        b'r' => out.push('\r'),
-                        b't' => out.push('\t'),
-                        b'b' => out.push('\x08'),
-                        b'f' => out.push('\x0C'),
-                        b'u' => {
-                            let hex = self.take_n(4)?;
-                            let n = u32::from_str_radix(&hex, 16)
-                                .map_err(|_| format!("from_json: invalid \\u escape: {hex}"))?;
-                            let c = char::from_u32(n).ok_or_else(|| {
-                                format!("from_json: invalid unicode codepoint U+{n:04X}")
-                            })?;
-                            out.push(c);
-                        }
-                        other => {
-                            return Err(format!(
-                                "from_json: unknown escape \\{} at position {}",
-                                other as char,
-                                self.pos - 1
+                    let c = match esc {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'b' => '\x08',
+                        b'f' => '\x0C',
+                        b'u' => self.parse_unicode_escape()?,
+                        _ => {
+                            return Err(self.error_at(
+                                self.pos - 2,
+                                format!("unknown escape \\{}", self.char_at(self.pos - 1)),
                             ));
                         }
-                    }
+                    };
+                    out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
                 }
                 b if b < 0x20 => {
-                    return Err(format!(
-                        "from_json: unescaped control character 0x{:02x} in string",
-                        b
+                    return Err(self.error_at(
+                        self.pos - 1,
+                        format!("unescaped control character 0x{:02x} in string", b),
                     ));
                 }
-                b => out.push(b as char),
+                b => out.push(b),
             }
         }
     }
 
-    fn take_n(&mut self, n: usize) -> RResult<String> {
-        if self.pos + n > self.src.len() {
-            return Err("from_json: unexpected end of input in escape".to_string());
-        }
-        let slice = &self.src[self.pos..self.pos + n];
-        self.pos += n;
-        Ok(String::from_utf8_lossy(slice).into_owned())
+    /// The character of a `\uXXXX` escape whose `\u` was just
+    /// consumed, joining a UTF-16 surrogate pair into one character.
+    fn parse_unicode_escape(&mut self) -> PResult<char> {
+        let start = self.pos - 2;
+        let high = self.take_hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if self.src.get(self.pos..self.pos + 2) != Some(b"\\u") {
+                return Err(self.error_at(start, "unpaired surrogate in \\u escape"));
+            }
+            self.pos += 2;
+            let low = self.take_hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error_at(start, "unpaired surrogate in \\u escape"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code)
+            .ok_or_else(|| self.error_at(start, format!("invalid unicode codepoint U+{code:04X}")))
     }
 
-    fn parse_number(&mut self) -> RResult<Value> {
+    fn take_hex4(&mut self) -> PResult<u32> {
+        let Some(slice) = self.src.get(self.pos..self.pos + 4) else {
+            return Err(self.error("unexpected end of input in escape"));
+        };
+        let hex = String::from_utf8_lossy(slice).into_owned();
+        let n = u32::from_str_radix(&hex, 16)
+            .map_err(|_| self.error(format!("invalid \\u escape: {hex}")))?;
+        self.pos += 4;
+        Ok(n)
+    }
+
+    fn parse_number(&mut self) -> PResult<Value> {
         let start = self.pos;
         let mut is_float = false;
 
@@ -510,10 +623,7 @@ impl<'a> JsonParser<'a> {
             is_float = true;
             self.pos += 1;
             if !matches!(self.peek(), Some(b'0'..=b'9')) {
-                return Err(format!(
-                    "from_json: expected digits after '.' at position {}",
-                    self.pos
-                ));
+                return Err(self.error("expected digits after '.'"));
             }
             while matches!(self.peek(), Some(b'0'..=b'9')) {
                 self.pos += 1;
@@ -527,23 +637,21 @@ impl<'a> JsonParser<'a> {
                 self.pos += 1;
             }
             if !matches!(self.peek(), Some(b'0'..=b'9')) {
-                return Err(format!(
-                    "from_json: expected digits in exponent at position {}",
-                    self.pos
-                ));
+                return Err(self.error("expected digits in exponent"));
             }
             while matches!(self.peek(), Some(b'0'..=b'9')) {
                 self.pos += 1;
             }
         }
 
-        let s = std::str::from_utf8(&self.src[start..self.pos])
-            .map_err(|_| "from_json: invalid UTF-8 in number".to_string())?;
+        // Only ASCII digits, signs, '.', and exponent markers were
+        // consumed, so the slice is always valid UTF-8.
+        let s = &self.text[start..self.pos];
 
         if is_float {
             let f: f64 = s
                 .parse()
-                .map_err(|_| format!("from_json: invalid float: {s}"))?;
+                .map_err(|_| self.error_at(start, format!("invalid float: {s}")))?;
             Ok(Value::Float(f))
         } else {
             match s.parse::<i64>() {
@@ -552,14 +660,14 @@ impl<'a> JsonParser<'a> {
                     // Oversized integer → fall back to float
                     let f: f64 = s
                         .parse()
-                        .map_err(|_| format!("from_json: integer too large: {s}"))?;
+                        .map_err(|_| self.error_at(start, format!("invalid number: {s}")))?;
                     Ok(Value::Float(f))
                 }
             }
         }
     }
 
-    fn parse_array(&mut self) -> RResult<Value> {
+    fn parse_array(&mut self) -> PResult<Value> {
         self.expect_byte(b'[')?;
         // RES-1946: typical JSON arrays hold 1-10 items; pre-size to
         // 4 to skip the default 0→4 first grow. Empty arrays
@@ -568,7 +676,7 @@ impl<'a> JsonParser<'a> {
         self.skip_ws();
         if self.peek() == Some(b']') {
             self.pos += 1;
-            return Ok(Value::Array(items));
+            return Ok(Value::Array(items.into()));
         }
         loop {
             items.push(self.parse_value()?);
@@ -579,20 +687,20 @@ impl<'a> JsonParser<'a> {
                 }
                 Some(b']') => {
                     self.pos += 1;
-                    return Ok(Value::Array(items));
+                    return Ok(Value::Array(items.into()));
                 }
-                Some(b) => {
-                    return Err(format!(
-                        "from_json: expected ',' or ']' in array at position {}, got '{}'",
-                        self.pos, b as char
-                    ));
+                Some(_) => {
+                    return Err(self.error(format!(
+                        "expected ',' or ']' in array, got '{}'",
+                        self.char_at(self.pos)
+                    )));
                 }
-                None => return Err("from_json: unterminated array".to_string()),
+                None => return Err(self.error("unterminated array")),
             }
         }
     }
 
-    fn parse_object(&mut self) -> RResult<Value> {
+    fn parse_object(&mut self) -> PResult<Value> {
         self.expect_byte(b'{')?;
         // RES-1946: typical JSON objects hold 2-10 entries; pre-size
         // to 4 to skip the default 0-bucket → 4-bucket rehash. Empty
@@ -601,7 +709,7 @@ impl<'a> JsonParser<'a> {
         self.skip_ws();
         if self.peek() == Some(b'}') {
             self.pos += 1;
-            return Ok(Value::Map(map));
+            return Ok(Value::Map(map.into()));
         }
         loop {
             self.skip_ws();
@@ -617,15 +725,15 @@ impl<'a> JsonParser<'a> {
                 }
                 Some(b'}') => {
                     self.pos += 1;
-                    return Ok(Value::Map(map));
+                    return Ok(Value::Map(map.into()));
                 }
-                Some(b) => {
-                    return Err(format!(
-                        "from_json: expected ',' or '}}' in object at position {}, got '{}'",
-                        self.pos, b as char
-                    ));
+                Some(_) => {
+                    return Err(self.error(format!(
+                        "expected ',' or '}}' in object, got '{}'",
+                        self.char_at(self.pos)
+                    )));
                 }
-                None => return Err("from_json: unterminated object".to_string()),
+                None => return Err(self.error("unterminated object")),
             }
         }
     }
@@ -891,4 +999,70 @@ println(type_of(v));"#);
         assert!(r.ok, "errors: {:?}", r.errors);
         assert!(r.stdout.contains("map"), "stdout: {}", r.stdout);
     }
+
+    // ── json_parse / json_stringify ───────────────────────────────────────────
+
+    #[test]
+    fn json_parse_builds_nested_values() {
+        let r = run(
+            r#"let v = json_parse("\{\"id\": \"sensor-é\", \"readings\": [1, 2.5], \"up\": true}");
+println(v["id"]);
+println(v["readings"][1]);
+println(json_parse("\"\\ud83d\\ude00\""));"#,
+        );
+        assert!(r.ok, "errors: {:?}", r.errors);
+        assert_eq!(r.stdout, "sensor-é\n2.5\n😀\n");
+    }
+
+    #[test]
+    fn json_parse_errors_carry_line_and_column() {
+        let r = run(r#"let v = json_parse("\{\n  \"a\": 1,\n  \"b\" 2\n}");"#);
+        assert!(!r.ok);
+        assert!(
+            r.errors
+                .iter()
+                .any(|e| e.contains("json_parse: InvalidJson: line 3, column 7: expected ':'")),
+            "errors: {:?}",
+            r.errors
+        );
+        let r = run(r#"try {
+    let v = json_parse("[1, 2,");
+} catch InvalidJson {
+    println("caught");
+}"#);
+        assert!(r.ok, "errors: {:?}", r.errors);
+        assert_eq!(r.stdout, "caught\n");
+    }
+
+    #[test]
+    fn json_stringify_compact_and_pretty() {
+        let r = run(r#"let v = [1, {"a" -> true}];
+println(json_stringify(v));
+println(json_stringify(v, pretty: true));
+println(json_stringify("x", false));"#);
+        assert!(r.ok, "errors: {:?}", r.errors);
+        assert_eq!(
+            r.stdout,
+            "[1, {\"a\": true}]\n[\n  1,\n  {\n    \"a\": true\n  }\n]\n\"x\"\n"
+        );
+    }
+
+    #[test]
+    fn serialize_errors_name_the_builtin_called() {
+        let nan = r#"unwrap(parse_float("NaN"))"#;
+        for (call, prefix) in [
+            (format!("json_stringify({nan})"), "json_stringify: "),
+            (format!("json_stringify([{nan}], true)"), "json_stringify: "),
+            (format!("to_json({nan})"), "to_json: "),
+            (format!("json_encode_pretty({nan})"), "json_encode_pretty: "),
+        ] {
+            let r = run(&format!("println({call});"));
+            let expected = format!("{prefix}NaN is not a valid JSON value");
+            assert!(
+                r.errors.iter().any(|e| e.contains(&expected)),
+                "{call}: {:?}",
+                r.errors
+            );
+        }
+    }
 }
@@ -135,7 +135,7 @@ fn lower_expr(node: &Node) -> Result<LeanExpr, LowerError> {
     match node {
         Node::IntegerLiteral { value, .. } => Ok(LeanExpr::Int(*value)),
         Node::BooleanLiteral { value, .. } => Ok(LeanExpr::Bool(*value)),
-        Node::Identifier { name, .. } => Ok(LeanExpr::Var(name.clone())),
+        Node::Identifier { name, .. } => Ok(LeanExpr::Var(name.to_string())),
         Node::PrefixExpression {
             operator, right, ..
         } => {
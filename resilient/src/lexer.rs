@@ -0,0 +1,33 @@
+//! Source text to tokens.
+//!
+//! [`Lexer::next_token_with_span`] yields one [`Token`] and the
+//! [`Span`] it covers per call, ending with `Token::Eof`. Lexing never
+//! fails: a character outside the language comes back as
+//! `Token::Error` for the parser to report. [`tokenize`] collects a
+//! whole file.
+//!
+//! ```
+//! use resilient::lexer::{self, Token};
+//!
+//! let tokens = lexer::tokenize("let x = 1;");
+//! assert_eq!(tokens[0].0, Token::Let);
+//! assert_eq!(tokens[1].1.start.column, 5);
+//! assert_eq!(tokens.len(), 5);
+//! ```
+
+pub use crate::span::{Pos, Span};
+pub use crate::{LexError, Lexer, Token};
+
+/// Every token of `src` with its span, in source order, without the
+/// final `Token::Eof`.
+pub fn tokenize(src: &str) -> Vec<(Token, Span)> {
+    let mut lexer = Lexer::new(src);
+    let mut tokens = Vec::new();
+    loop {
+        let (token, span) = lexer.next_token_with_span();
+        if token == Token::Eof {
+            return tokens;
+        }
+        tokens.push((token, span));
+    }
+}
@@ -835,9 +835,9 @@ pub fn tokenize(src: &str) -> Vec<(Token, Span)> {
             Ok(t) => convert(t),
             Err(_) => {
                 // A char that matched no rule — legacy lexer emits
-                // `Token::Unknown(ch)` with the offending char.
+                // `LexError::UnexpectedChar` with the offending char.
                 let first_char = lex.slice().chars().next().unwrap_or('\0');
-                Token::Unknown(first_char)
+                Token::Error(crate::LexError::UnexpectedChar(first_char))
             }
         };
         out.push((tok, span));
@@ -6,6 +6,9 @@ use std::io;
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::shared::Shared;
 
 pub(crate) const ANONYMOUS_STRUCT_NAME: &str = "__anon_struct";
 
@@ -29,6 +32,19 @@ mod numeric_utils;
 mod result_option_hof;
 mod string_hof;
 mod type_builtins;
+// Public `Visitor` / `Folder` traversal of `Node` for external tools.
+pub mod ast;
+// Embedding API: tokens, parsing, evaluation, typed values and
+// runtime limits.
+pub mod bridge;
+pub mod interpreter;
+pub mod lexer;
+pub mod parser;
+pub mod runtime;
+// Incremental reparsing of edited documents for the LSP server.
+pub mod incremental_parse;
+// Classified, span-tagged tokens for editor highlighting.
+pub mod highlight;
 // RES-2612: compile-time string interning for reduced binary size.
 pub mod string_interning;
 // RES-1148: binary search on sorted int / float / string arrays.
@@ -55,6 +71,9 @@ mod array_stats;
 // RES-1140: ASCII char-class predicates — rounds out the RES-459 family.
 // Pure leaf builtins; module-isolated.
 mod ascii_predicates;
+// Shared builtin registry consumed by both `Interpreter::new` and
+// `TypeChecker::new` so the runtime and checker tables can't drift.
+mod builtin_registry;
 mod bytecode;
 // RES-1144: map_entries / map_merge / map_is_empty + hashmap aliases.
 // Pure leaf builtins; module-isolated.
@@ -114,6 +133,12 @@ mod float_predicates;
 // the lib without dragging it in.
 #[cfg(not(target_arch = "wasm32"))]
 mod repl;
+// REPL settings from `~/.resilientrc` and `resilient.toml [repl]`.
+#[cfg(not(target_arch = "wasm32"))]
+mod repl_config;
+// `:transcript` — Markdown record of a REPL session.
+#[cfg(not(target_arch = "wasm32"))]
+mod repl_transcript;
 // RES-510 PR 3: file watcher for `--watch` mode. Same reason — uses
 // platform fs-notification APIs (inotify / FSEvents / ...).
 mod imports;
@@ -182,11 +207,15 @@ mod supervisor_runtime;
 // (capability check at typecheck time) is in the typechecker
 // extension block.
 mod volatile;
+// `mmio_read` / `mmio_write`: allowlisted register I/O through a
+// device file (`unsafe-io` feature).
+mod mmio;
 // RES-406: capability gate for the volatile intrinsics. Walks the
 // program AST after parse and rejects any `volatile_*` call
 // outside an `unsafe { ... }` block.
-mod typechecker;
 mod unsafe_check;
+// Static type checking; public so embedders can check a parsed program.
+pub mod typechecker;
 #[cfg(feature = "z3")]
 mod verifier_z3;
 // RES-390: distributed-invariant verifier — a joint Z3 state
@@ -225,10 +254,19 @@ mod infer;
 // the offending source span). Used by the driver when formatting
 // parser / typechecker / interpreter / VM errors.
 pub mod diag;
+// `--error-format=json|sarif`: the driver's diagnostics as structured
+// records instead of caret text.
+mod error_format;
 // RES-205: `resilient pkg init <name>` — project scaffolding.
 // Standalone from the compiler pipeline; lives here so the single
 // `resilient` binary carries it alongside the runtime.
 mod pkg_init;
+// Multi-file projects: a directory runs its manifest's entry file,
+// and type errors in `use`d modules are reported against the module.
+mod project;
+// Run settings shared through `resilient.toml` ([check], [live],
+// [sandbox], [verify], [fmt]), merged under the command-line flags.
+mod project_config;
 // RES-342: `resilient pkg publish` — manifest read, tarball synth,
 // auth resolution. Companion to pkg_init.
 mod pkg_publish;
@@ -256,6 +294,15 @@ mod formatter;
 // RES-test: `rz test` subcommand — discover and run `fn test_*()`
 // functions. Standalone from the compiler pipeline.
 mod test_runner;
+// `rz run`, `check`, `repl`, `fmt` and `test`: the subcommands
+// declared with clap.
+mod cli;
+// `rz new <name>`: a project skeleton with an entry file, a module, a
+// sample supervisor and a sample test.
+mod new_project;
+// `rz doc [<file|dir>]`: HTML or Markdown API docs from signatures, doc
+// comments, effects and contracts.
+mod api_docs;
 // RES-164a: reusable pure free-variable analysis on the AST.
 // Returns the set of names referenced inside a subtree that aren't
 // bound by a parameter / let / for-in / match pattern within it.
@@ -445,6 +492,30 @@ pub(crate) mod region_inference;
 // default; enabled via `--strict-termination`. Closes a class of
 // expressible-but-invalid state called out in the Reddit critique.
 mod termination;
+// Strict typing mode: `--strict` turns every `Any` that reaches a
+// user binding into a type error.
+mod strict_any;
+// Flow-sensitive facts from `if` conditions: discharge call-site
+// `requires` and type `unwrap_option` under a `!= None` check.
+mod flow_facts;
+// Let-polymorphism for untyped `fn` parameters: `fn id(x)` is checked
+// as `fn<T> id(T x)`.
+mod implicit_generics;
+// Implicit-coercion policy (`--coercion strict|permissive`) shared by
+// the checker, the tree-walker, and the VM.
+mod coercion;
+// Function overloading: top-level `fn`s sharing a name are mangled by
+// signature after parsing and resolved per call site.
+mod overloads;
+// Warning categories (`--warn` / `--no-warn`) and `--deny-warnings`.
+mod warnings;
+// Which top-level fn bodies `check` may verify off the main thread,
+// and the worker pool that does it (`--features parallel-check`).
+mod parallel_check;
+// Interned identifier names: the lexer, AST, and both environments
+// carry a `Symbol` instead of an owned `String`.
+mod symbol;
+use symbol::{Symbol, SymbolKey};
 // RES-796: mutual recursion termination checking via SCC analysis.
 // Detects mutual recursion (cycles) in the function call graph.
 mod mutual_recursion_scc;
@@ -738,6 +809,8 @@ mod defer_stmt;
 mod unused_imports;
 // RES-2577: tuple struct named constructors (`Point(3, 4)` without `new`).
 mod tuple_struct;
+// Compile-time constant evaluation shared by the checker and both backends.
+mod const_eval;
 // RES-2580: extended const eval — string concat, bitwise ops, conditionals.
 mod const_eval_ext;
 // RES-4078 (A-E2): const-generic `[T; N]` length checking.
@@ -762,13 +835,74 @@ mod wcet_contracts;
 mod process_exec;
 // RES-2555: TCP/UDP networking (std-only).
 mod tcp_udp;
+// MQTT and serial-port builtins for telemetry (`telemetry` feature).
+mod telemetry_io;
 // RES-2557: file metadata (std-only).
 mod file_meta;
+// `--fs` sandbox policy and the `read_file` / `write_file` /
+// `append_file` builtins with typed `NotFound` / `PermissionDenied`
+// failures.
+mod fs_sandbox;
+// `--allow <capabilities>`: which kinds of I/O (files, network, exec,
+// serial, MMIO, FFI) the builtins may do at all.
+pub mod sandbox;
+// `--max-steps` execution fuel: a per-run step budget that ends
+// runaway programs with a `ResourceExhausted` error.
+pub mod fuel;
+// `--max-memory`: an approximate cap on bound program data, enforced
+// with an `OutOfMemory` error.
+pub mod memory_limit;
+// `--trace`: a call tree of user-function calls and returns on stderr.
+pub mod call_trace;
+// `--profile`: calls, total and self time per user function.
+pub mod profiler;
+// `rz debug <file>`: breakpoints, stepping and variable inspection on
+// the console.
+mod debug_console;
+// `--metrics-port`: runtime counters served in Prometheus text format.
+pub mod metrics;
+// Callbacks from the tree-walker (statements, variable writes, calls)
+// that the debugger, coverage, `--trace`, `--profile` and metrics are
+// built on, and that hosts can register through `interpreter::Session`.
+pub mod eval_observer;
+// `tracing` spans for lex, parse, typecheck and eval, plus live-retry
+// and actor-dispatch events (`tracing` feature).
+mod phase_trace;
+// `rz test --coverage`: line, branch and assert hit counts, written as
+// lcov and HTML.
+mod test_coverage;
+// `rz test --snapshot`: program stdout checked against `.snap` files.
+mod test_snapshot;
+// Host stack budget for the tree-walker: deep evaluation fails with a
+// catchable `RecursionLimit` error instead of overflowing the thread.
+pub mod stack_guard;
+// Structured logging builtins (`log_info` and friends) with a
+// `--log-level` filter and text or JSON-lines output.
+pub mod logging;
+// IEEE special-value policy (`--float-strict`) and how floats print
+// (`--float-precision`).
+pub mod float_mode;
+// Copy-on-write storage behind array, map and set values.
+mod shared;
+// `--record` / `--replay`: taps on every nondeterministic input so a
+// run can be re-executed from a trace.
+pub mod record_replay;
+// Native, WASI or web playground: which host the file, clock, random
+// and env builtins talk to.
+pub mod host_profile;
+// `--plugin`: builtin packages loaded from cdylibs at startup.
+pub mod plugin;
+// Lowering onto resilient-runtime's no_std interpreter core.
+pub mod interp_lower;
+// Convenience functions written in Resilient, loaded ahead of every
+// program (`--no-prelude` to skip).
+pub mod prelude;
 // RES-2792: error chaining — `.context()`, `.root_cause()`, `.chain()`.
 mod error_chaining;
 // RES-2794: error stack traces with source locations.
 mod error_stack_traces;
 
+use bridge::ResilientValue;
 #[allow(unused_imports)]
 use span::{Pos, Span, Spanned};
 
@@ -780,7 +914,7 @@ use rustyline::{DefaultEditor, Result as RustylineResult};
 
 // Token types for our lexer
 #[derive(Debug, Clone, PartialEq)]
-enum Token {
+pub enum Token {
     // Keywords
     Function,
     Let,
@@ -948,7 +1082,7 @@ enum Token {
     // </EXTENSION_TOKENS>
 
     // Literals
-    Identifier(String),
+    Identifier(Symbol),
     IntLiteral(i64),
     FloatLiteral(f64),
     StringLiteral(String),
@@ -1028,10 +1162,53 @@ enum Token {
 
     // Other
     Eof,
-    /// A character the lexer did not recognize. Emitted instead of
-    /// panicking so the parser can report a graceful diagnostic. The
-    /// `char` payload is the offending character, for the error message.
-    Unknown(char),
+    /// Source text the lexer could not turn into a token. Emitted
+    /// instead of panicking so the parser can report it and keep going.
+    Error(LexError),
+}
+
+/// What made a `Token::Error`. The parser reports it at the token's
+/// position, together with every other lexical and syntax error.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexError {
+    /// A character outside the language.
+    UnexpectedChar(char),
+    /// A `"` or `"""` string with no closing quote before end of input.
+    UnterminatedString,
+    /// An integer literal too large for `i64`, as written.
+    IntOverflow(String),
+    /// A float literal that is not a valid IEEE 754 value, as written.
+    InvalidFloat(String),
+}
+
+impl std::fmt::Display for LexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // RES-114: an alphabetic char the lexer rejected was
+            // turned away by the ASCII-only identifier policy. Say so,
+            // so users grep for "non-ASCII" rather than chasing a
+            // generic "Unexpected character".
+            LexError::UnexpectedChar(ch) if ch.is_alphabetic() && !ch.is_ascii() => write!(
+                f,
+                "identifier contains non-ASCII character '{}' \
+                 — Resilient identifiers are ASCII-only (see SYNTAX.md)",
+                ch
+            ),
+            LexError::UnexpectedChar(ch) => write!(f, "Unexpected character '{}'", ch),
+            LexError::UnterminatedString => write!(f, "unterminated string literal"),
+            LexError::IntOverflow(lit) => {
+                write!(
+                    f,
+                    "integer literal `{}` overflows i64 (max {})",
+                    lit,
+                    i64::MAX
+                )
+            }
+            LexError::InvalidFloat(lit) => {
+                write!(f, "float literal `{}` is not a valid IEEE 754 value", lit)
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for Token {
@@ -1171,7 +1348,13 @@ impl Token {
             Token::CharLiteral(c) => Cow::Owned(format!("char literal `'{}'`", c)),
             Token::BoolLiteral(b) => Cow::Owned(format!("`{}`", b)),
             Token::Eof => Cow::Borrowed("end of input"),
-            Token::Unknown(c) => Cow::Owned(format!("unrecognized character `{}`", c)),
+            Token::Error(LexError::UnexpectedChar(c)) => {
+                Cow::Owned(format!("unrecognized character `{}`", c))
+            }
+            Token::Error(LexError::UnterminatedString) => Cow::Borrowed("unterminated string"),
+            Token::Error(LexError::IntOverflow(_) | LexError::InvalidFloat(_)) => {
+                Cow::Borrowed("malformed number")
+            }
         }
     }
 }
@@ -1227,7 +1410,7 @@ pub fn format_expected(expected: &[&str], got_syntax: &str) -> String {
 }
 
 // Lexer for tokenizing Resilient source code
-struct Lexer {
+pub struct Lexer {
     input: Vec<char>,
     position: usize,
     read_position: usize,
@@ -1265,7 +1448,7 @@ impl Lexer {
     // the main compile path's `Lexer::new(contents.clone())` cloned
     // the entire source file (~hundreds of KB for large inputs) on
     // every compile. Borrowing eliminates that clone.
-    fn new(input: &str) -> Self {
+    pub fn new(input: &str) -> Self {
         #[cfg(feature = "logos-lexer")]
         {
             // RES-108: under the `logos-lexer` feature, pre-tokenize
@@ -1354,6 +1537,29 @@ impl Lexer {
         }
     }
 
+    /// Lex `input` starting at char `offset`, which must be the first
+    /// char of a token at 1-indexed `line` / `column`. Positions stay
+    /// absolute, so the incremental reparser can resume mid-file.
+    fn new_at(input: &str, offset: usize, line: usize, column: usize) -> Self {
+        let mut lexer = Lexer::new(input);
+        if offset == 0 {
+            return lexer;
+        }
+        #[cfg(feature = "logos-lexer")]
+        if let Some(tokens) = lexer.logos_tokens.take() {
+            let rest: Vec<(Token, span::Span)> = tokens
+                .skip_while(|(_, span)| span.start.offset < offset)
+                .collect();
+            lexer.logos_tokens = Some(rest.into_iter());
+        }
+        lexer.ch = ' ';
+        lexer.line = line;
+        lexer.column = column - 1;
+        lexer.read_position = offset;
+        lexer.read_char();
+        lexer
+    }
+
     fn next_token(&mut self) -> Token {
         // RES-108: under the `logos-lexer` feature, drain the pre-
         // scanned stream. Each pop also updates the legacy line/col
@@ -1560,7 +1766,7 @@ impl Lexer {
             ']' => Token::RightBracket,
             // RES-149: set literal opener `#{...}`. A lone `#` (or
             // `#` followed by anything other than `{` / `[`) still falls
-            // through to `Token::Unknown('#')` below — shebangs are
+            // through to `LexError::UnexpectedChar('#')` below — shebangs are
             // consumed at file head before we ever reach here.
             '#' if self.peek_char() == '{' => {
                 self.read_char(); // consume `{` so the outer
@@ -1635,10 +1841,18 @@ impl Lexer {
                     self.read_char(); // consume 2nd `"`
                     self.read_char(); // consume 3rd `"`; self.ch is first content char
                     let content = self.read_triple_string();
-                    Token::StringLiteral(res2599_strip_indent(&content))
+                    if self.ch == '\0' {
+                        Token::Error(LexError::UnterminatedString)
+                    } else {
+                        Token::StringLiteral(res2599_strip_indent(&content))
+                    }
                 } else {
                     let str_value = self.read_string();
-                    Token::StringLiteral(str_value)
+                    if self.ch == '\0' {
+                        Token::Error(LexError::UnterminatedString)
+                    } else {
+                        Token::StringLiteral(str_value)
+                    }
                 }
             }
             // RES-2619: `'...'` char literal.
@@ -1749,7 +1963,7 @@ impl Lexer {
                         "default" => Token::Default,
                         "true" => Token::BoolLiteral(true),
                         "false" => Token::BoolLiteral(false),
-                        _ => Token::Identifier(ident),
+                        _ => Token::Identifier(ident.into()),
                     };
                 } else if self.is_digit(self.ch) {
                     return self.read_number();
@@ -1758,7 +1972,7 @@ impl Lexer {
                     // route through `record_error` and keep going.
                     let unknown = self.ch;
                     self.read_char();
-                    return Token::Unknown(unknown);
+                    return Token::Error(LexError::UnexpectedChar(unknown));
                 }
             }
         };
@@ -1776,12 +1990,12 @@ impl Lexer {
     /// Existing call sites still use `next_token()` and ignore spans —
     /// they will migrate as the AST gains span fields.
     #[allow(dead_code)]
-    // RES-181a: `pub(crate)` so the LSP hover handler can drive
-    // the lexer directly to find the token at a cursor position.
-    // (AST-based span lookup is unreliable for literal positions —
+    // RES-181a: public so the LSP hover handler (and embedders, via
+    // `lexer`) can drive the lexer directly to find the token at a
+    // cursor position. (AST-based span lookup is unreliable for literal positions —
     // `Parser::span_at_current` records the NEXT token's start, not
     // the current one's extent.)
-    pub(crate) fn next_token_with_span(&mut self) -> (Token, span::Span) {
+    pub fn next_token_with_span(&mut self) -> (Token, span::Span) {
         // `next_token` snapshots line / column / char-offset at the
         // first non-whitespace character of the token into
         // `last_token_*`, then advances the cursor. We use those for
@@ -1884,27 +2098,12 @@ impl Lexer {
         if is_float {
             match number_str.parse::<f64>() {
                 Ok(f) => Token::FloatLiteral(f),
-                Err(_) => {
-                    eprintln!(
-                        "<input>:{}:{}: error: float literal `{}` is not a valid IEEE 754 value",
-                        self.last_token_line, self.last_token_column, number_str
-                    );
-                    Token::FloatLiteral(0.0)
-                }
+                Err(_) => Token::Error(LexError::InvalidFloat(number_str)),
             }
         } else {
             match number_str.parse::<i64>() {
                 Ok(n) => Token::IntLiteral(n),
-                Err(_) => {
-                    eprintln!(
-                        "<input>:{}:{}: error: integer literal `{}` overflows i64 (max {})",
-                        self.last_token_line,
-                        self.last_token_column,
-                        number_str,
-                        i64::MAX
-                    );
-                    Token::IntLiteral(0)
-                }
+                Err(_) => Token::Error(LexError::IntOverflow(number_str)),
             }
         }
     }
@@ -1930,17 +2129,7 @@ impl Lexer {
         }
         match i64::from_str_radix(&cleaned, radix) {
             Ok(n) => Token::IntLiteral(n),
-            Err(_) => {
-                eprintln!(
-                    "<input>:{}:{}: error: integer literal `{}{}` overflows i64 (max {})",
-                    self.last_token_line,
-                    self.last_token_column,
-                    prefix,
-                    cleaned,
-                    i64::MAX
-                );
-                Token::IntLiteral(0)
-            }
+            Err(_) => Token::Error(LexError::IntOverflow(format!("{}{}", prefix, cleaned))),
         }
     }
 
@@ -2174,9 +2363,8 @@ impl Lexer {
         // identifier scanning is tightened. The logos lexer's
         // identifier regex is already ASCII-only, so both paths
         // agree. Non-ASCII at an identifier position falls through
-        // to `Token::Unknown(ch)`; the parser's record_error
-        // branch on that arm surfaces the dedicated diagnostic
-        // "identifier contains non-ASCII character".
+        // to `LexError::UnexpectedChar(ch)`, whose message is the
+        // dedicated "identifier contains non-ASCII character".
         ch.is_ascii_alphabetic() || ch == '_'
     }
 
@@ -2201,7 +2389,8 @@ impl Lexer {
 // relative to leaf variants like Wildcard is by design.
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
-pub(crate) enum Pattern {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pattern {
     /// Matches a literal int, float, string, or bool.
     Literal(Node),
     /// Binds the scrutinee to an identifier; always matches.
@@ -2273,7 +2462,8 @@ pub(crate) enum Pattern {
 /// shape of `EnumValuePayload` on the runtime side, but holds
 /// sub-patterns instead of values.
 #[derive(Debug, Clone)]
-pub(crate) enum EnumPatternPayload {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EnumPatternPayload {
     /// No payload — `Color::Red`.
     None,
     /// Named-field — `Shape::Circle { r }` or `Shape::Circle { r: rr }`.
@@ -2295,6 +2485,7 @@ pub(crate) enum EnumPatternPayload {
 /// to prevent an accidental `factor=1e9` runaway that would block
 /// the interpreter thread for hours on the first retry.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BackoffConfig {
     pub base_ms: u64,
     pub factor: u64,
@@ -2338,6 +2529,7 @@ impl BackoffConfig {
 /// rather than inside it — so a `live backoff(...)` clause without
 /// `kind=...` retains the exponential schedule it has always had.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BackoffKind {
     /// Capped exponential growth (`factor^retries`). Historical
     /// default for `live backoff(...)`.
@@ -2382,6 +2574,7 @@ fn live_backoff_delay_ms(cfg: &BackoffConfig, kind: BackoffKind, retries: u32) -
 /// for backward compatibility — every pre-RES-389 test continues
 /// to type-check.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EffectSet {
     /// The fn is declared side-effect-free: no I/O, no
     /// nondeterminism, no mutation of observable state. Enforced
@@ -2438,9 +2631,26 @@ impl EffectSet {
     }
 }
 
+/// `&'static str` under a name the serde derives don't recognise:
+/// they take a plain `&str` field as text to borrow from the input,
+/// which would only let `'static` input deserialize. Operators are
+/// interned when deserialized; the other uses sit on variants serde
+/// skips.
+type StaticStr = &'static str;
+
 // AST nodes for our parser
+//
+// Children are owned (`Box<Node>` / `Vec<Node>`) except the parts a
+// function value keeps alive after evaluation: a function's body and
+// its `requires` / `ensures` sit behind an `Arc`, so making a
+// `Value::Function` or snapshotting an env for a `live` retry bumps a
+// count instead of copying the tree. This stops short of a node arena
+// (`Vec<Node>` plus `NodeId` children): every pass in the crate and
+// the public `ast` walkers match on owned children, and moving them
+// all to ids would break every one of them to gain walk locality.
 #[derive(Debug, Clone)]
-enum Node {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Node {
     /// RES-077 (G6 partial): top-level statements carry source spans
     /// so diagnostics can point at the originating line:col. Sub-
     /// expressions inside each statement still have no spans —
@@ -2495,14 +2705,17 @@ enum Node {
         /// Length always matches `parameters.len()`.
         #[allow(dead_code)]
         defaults: Vec<Option<Box<Node>>>,
-        body: Box<Node>,
+        /// Shared with every `Value::Function` made from this
+        /// declaration, so evaluating it never copies the body.
+        body: Arc<Node>,
         /// RES-035: pre-condition clauses, checked on entry. Each is a
-        /// boolean expression over the parameters.
-        requires: Vec<Node>,
+        /// boolean expression over the parameters. Shared with function
+        /// values like `body`.
+        requires: Arc<Vec<Node>>,
         /// RES-035: post-condition clauses, checked on exit. The
         /// special identifier `result` is bound to the return value
         /// inside each clause's env.
-        ensures: Vec<Node>,
+        ensures: Arc<Vec<Node>>,
         /// RES-052: optional `-> TYPE` return-type annotation. Advisory.
         #[allow(dead_code)]
         return_type: Option<String>,
@@ -2782,7 +2995,7 @@ enum Node {
     },
     /// RES-078: identifiers carry source span so diagnostics can
     /// point at the referenced name.
-    Identifier { name: String, span: span::Span },
+    Identifier { name: Symbol, span: span::Span },
     /// RES-078: literal nodes carry source span so diagnostics
     /// (typechecker, verifier, VM runtime errors) can point at the
     /// offending value. The fields are unused today — RES-079 and
@@ -2836,7 +3049,11 @@ enum Node {
         span: span::Span,
     },
     PrefixExpression {
-        operator: &'static str,
+        #[cfg_attr(
+            feature = "serde",
+            serde(deserialize_with = "symbol::deserialize_static_str")
+        )]
+        operator: StaticStr,
         right: Box<Node>,
         /// RES-084: source span of the operator token. Consumed in
         /// follow-ups (e.g. typechecker arithmetic-mismatch errors).
@@ -2845,7 +3062,11 @@ enum Node {
     },
     InfixExpression {
         left: Box<Node>,
-        operator: &'static str,
+        #[cfg_attr(
+            feature = "serde",
+            serde(deserialize_with = "symbol::deserialize_static_str")
+        )]
+        operator: StaticStr,
         right: Box<Node>,
         /// RES-084: span of the operator token (NOT the full
         /// `lhs op rhs` range — that's a future refinement).
@@ -2895,9 +3116,11 @@ enum Node {
     /// named-fn semantics.
     FunctionLiteral {
         parameters: Vec<(String, String)>,
-        body: Box<Node>,
-        requires: Vec<Node>,
-        ensures: Vec<Node>,
+        /// Shared with each closure the literal evaluates to, so a
+        /// lambda built in a loop does not copy its body per iteration.
+        body: Arc<Node>,
+        requires: Arc<Vec<Node>>,
+        ensures: Arc<Vec<Node>>,
         /// RES-392: optional crash-recovery postcondition. Same
         /// MVP semantics as the named-fn variant: final-state only.
         recovers_to: Option<Box<Node>>,
@@ -3360,6 +3583,13 @@ enum Node {
         body: Box<Node>,
         span: span::Span,
     },
+    /// `test fn name() { body }` — a test. Skipped during normal
+    /// execution; `function` (a zero-parameter `Node::Function`) is
+    /// defined and called by `rz test` in an interpreter of its own.
+    TestFn {
+        function: Box<Node>,
+        span: span::Span,
+    },
 }
 
 /// RES-400 PR 2: a single variant inside an `enum` declaration.
@@ -3371,7 +3601,8 @@ enum Node {
 /// `EnumPayload::None` covering the payload-less case so existing
 /// callers don't need to special-case.
 #[derive(Debug, Clone)]
-pub(crate) struct EnumVariant {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnumVariant {
     pub name: String,
     /// Source span of the variant name. Read by the exhaustiveness
     /// check (PR 4) for "missing variant" diagnostics that point
@@ -3384,6 +3615,10 @@ pub(crate) struct EnumVariant {
     /// expressions and pattern matching land.
     #[allow(dead_code)]
     pub payload: EnumPayload,
+    /// Explicit discriminant — the `expr` in `Red = expr`. `None`
+    /// takes the previous variant's value plus one; see
+    /// `const_eval::ConstEvaluator::discriminants`.
+    pub discriminant: Option<Node>,
 }
 
 /// RES-400 PR 2: payload shape of an enum variant.
@@ -3395,7 +3630,8 @@ pub(crate) struct EnumVariant {
 /// registered.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
-pub(crate) enum EnumPayload {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EnumPayload {
     /// `Red` — no payload.
     None,
     /// `Circle { r: float, label: string }` — named fields.
@@ -3407,7 +3643,8 @@ pub(crate) enum EnumPayload {
 /// One named field inside `EnumPayload::Named`.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
-pub(crate) struct EnumField {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnumField {
     pub name: String,
     pub ty: String,
     pub span: span::Span,
@@ -3415,7 +3652,8 @@ pub(crate) struct EnumField {
 
 /// RES-386/RES-390: one `receive <name>()` handler inside an `actor` block.
 #[derive(Debug, Clone)]
-pub(crate) struct ActorHandler {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActorHandler {
     pub(crate) name: String,
     #[allow(dead_code)]
     pub(crate) ensures: Vec<Node>,
@@ -3431,7 +3669,8 @@ pub(crate) struct ActorHandler {
 /// measure reaching zero iff the post-condition holds.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
-pub(crate) struct EventuallyClause {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventuallyClause {
     pub(crate) target_handler: String,
     pub(crate) post: Node,
     pub(crate) span: span::Span,
@@ -3441,7 +3680,8 @@ pub(crate) struct EventuallyClause {
 /// inside an `ActorDecl`. Handler bodies run atomically for `always` proofs.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
-pub(crate) struct ReceiveHandler {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReceiveHandler {
     pub(crate) name: String,
     pub(crate) parameters: Vec<(String, String)>,
     pub(crate) requires: Vec<Node>,
@@ -3453,7 +3693,8 @@ pub(crate) struct ReceiveHandler {
 /// RES-333: one child specification inside a `supervisor` block.
 /// Defines a child actor and its restart policy.
 #[derive(Debug, Clone)]
-pub(crate) struct SupervisorChild {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SupervisorChild {
     /// Child identifier (must be unique within the supervisor)
     pub(crate) id: String,
     /// Function to call to start the child actor
@@ -3465,7 +3706,8 @@ pub(crate) struct SupervisorChild {
 /// FFI v1: one foreign fn declaration inside an `extern` block.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
-pub(crate) struct ExternDecl {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExternDecl {
     /// The name used in Resilient source (e.g. `sine`).
     pub(crate) resilient_name: String,
     /// The C symbol to look up. Defaults to `resilient_name`; overridden
@@ -3490,7 +3732,8 @@ pub(crate) struct ExternDecl {
 
 /// RES-363: what to access after the `?.` short-circuit check.
 #[derive(Debug, Clone)]
-pub(crate) enum ChainAccess {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChainAccess {
     /// `?.field` — read a named field.
     Field(String),
     /// `?.method(args)` — call a method with arguments.
@@ -3502,12 +3745,13 @@ struct Parser {
     lexer: Lexer,
     current_token: Token,
     peek_token: Token,
-    /// Source position (line, column) of `current_token`. 1-indexed.
-    current_line: usize,
-    current_column: usize,
-    /// Source position of `peek_token`.
-    peek_line: usize,
-    peek_column: usize,
+    /// Where `current_token` starts and ends in the source.
+    current_span: span::Span,
+    /// Where `peek_token` starts and ends.
+    peek_span: span::Span,
+    /// End of the token consumed before `current_token`: where a node
+    /// whose last token that was ends.
+    prev_end: span::Pos,
     errors: Vec<String>,
     emit_errors: bool,
     /// RES-156: fresh-name counter for array-comprehension
@@ -3523,6 +3767,10 @@ struct Parser {
     /// input (e.g. thousands of nested parens) by returning a typed
     /// diagnostic instead of recursing without bound.
     expr_depth: u32,
+    /// Set while parsing the first argument of a `format(...)` call:
+    /// that string literal is a template whose `{name}` placeholders
+    /// belong to `format`, so it must not become an interpolation.
+    format_template_next: bool,
 }
 
 /// RES-4185: maximum recursive-descent expression nesting depth.
@@ -3545,14 +3793,14 @@ impl Parser {
             lexer,
             current_token: Token::Eof,
             peek_token: Token::Eof,
-            current_line: 1,
-            current_column: 1,
-            peek_line: 1,
-            peek_column: 1,
+            current_span: span::Span::default(),
+            peek_span: span::Span::default(),
+            prev_end: span::Pos::default(),
             errors: Vec::new(),
             emit_errors,
             comprehension_counter: 0,
             expr_depth: 0,
+            format_template_next: false,
         };
 
         parser.next_token();
@@ -3569,11 +3817,21 @@ impl Parser {
     /// returns a syntactically-shaped `Program`), but no further
     /// messages are appended — this guards against pathological
     /// input producing unbounded diagnostic memory.
+    ///
+    /// A `Token::Error` was already reported when it became current
+    /// (see `next_token`), so grammar errors at it are dropped.
     fn record_error(&mut self, msg: String) {
+        if matches!(self.current_token, Token::Error(_)) {
+            return;
+        }
+        self.push_error(msg);
+    }
+
+    fn push_error(&mut self, msg: String) {
         if self.errors.len() >= parser_recovery::MAX_PARSE_ERRORS {
             return;
         }
-        let full = format!("{}:{}: {}", self.current_line, self.current_column, msg);
+        let full = format!("{}: {}", self.current_span.start, msg);
         if self.emit_errors {
             eprintln!("\x1B[31mParser error: {}\x1B[0m", full);
         }
@@ -3668,12 +3926,18 @@ impl Parser {
         // produced by `lexer.next_token()` replace `peek_token` in
         // place; the previous `peek_token` moves into
         // `current_token`. No clone, identical state transitions.
-        self.current_line = self.peek_line;
-        self.current_column = self.peek_column;
-        let new_token = self.lexer.next_token();
-        self.peek_line = self.lexer.last_token_line;
-        self.peek_column = self.lexer.last_token_column;
+        self.prev_end = self.current_span.end;
+        self.current_span = self.peek_span;
+        let (new_token, new_span) = self.lexer.next_token_with_span();
+        self.peek_span = new_span;
         self.current_token = std::mem::replace(&mut self.peek_token, new_token);
+        // Every lexical error is reported as its token comes up, in
+        // source order with the syntax errors, wherever the parser
+        // happens to be when it skips over it.
+        if let Token::Error(err) = &self.current_token {
+            let msg = err.to_string();
+            self.push_error(msg);
+        }
     }
 
     fn parse_program(&mut self) -> Node {
@@ -3684,54 +3948,62 @@ impl Parser {
         let mut program: Vec<span::Spanned<Node>> = Vec::with_capacity(16);
 
         while self.current_token != Token::Eof {
-            // RES-077 (G6 partial): capture each statement's source
-            // span by snapshotting the lexer's last_token_line/column
-            // BEFORE parse_statement and AFTER. End-position reflects
-            // the lexer's cursor at the moment the statement-recognizer
-            // returned, which is close enough to the true end-of-stmt
-            // for diagnostics (off by at most one whitespace token).
-            let start = span::Pos::new(self.lexer.last_token_line, self.lexer.last_token_column, 0);
-            // RES-307: snapshot the error count before each statement
-            // parse. If the parse recorded a new diagnostic AND the
-            // cursor isn't already at a clean statement boundary,
-            // scan ahead to one before continuing — this is what
-            // stops a single mistake from cascading into a pile of
-            // derived errors.
-            let errs_before = self.errors.len();
-            if let Some(statement) = self.parse_statement() {
-                let end =
-                    span::Pos::new(self.lexer.last_token_line, self.lexer.last_token_column, 0);
-                program.push(span::Spanned::new(statement, span::Span::new(start, end)));
-            }
-            if self.errors.len() > errs_before
-                && self.current_token != Token::Eof
-                && self.current_token != Token::Semicolon
-                && !parser_recovery::starts_top_level_item(&self.current_token)
-            {
-                self.synchronize_top_level();
-            }
-            // Defensive: at the per-run cap we stop emitting new
-            // errors but the parser keeps walking tokens; drain to
-            // EOF in one shot rather than churning through every
-            // remaining token via `parse_statement`.
-            if self.errors.len() >= parser_recovery::MAX_PARSE_ERRORS {
-                while self.current_token != Token::Eof {
-                    self.next_token();
-                }
-                break;
-            }
-            // RES-1860: do NOT advance if synchronize_top_level already
-            // parked the cursor on the first token of the next declaration
-            // (e.g. `fn`). Advancing here would consume that token and cause
-            // the next iteration to miss the declaration entirely.
-            if !parser_recovery::starts_top_level_item(&self.current_token) {
-                self.next_token();
+            if let Some(statement) = self.parse_top_level_item() {
+                program.push(statement);
             }
         }
 
         Node::Program(program)
     }
 
+    /// Parse one top-level statement, leaving the cursor on the first
+    /// token of the next one. `None` when the statement produced no
+    /// node (a `#[cfg]`-disabled item, or one that failed to parse).
+    /// Shared by `parse_program` and the incremental reparser, which
+    /// restarts this loop part-way through a file.
+    fn parse_top_level_item(&mut self) -> Option<span::Spanned<Node>> {
+        let mut item = None;
+        // RES-077: a statement runs from its first token to the one
+        // the statement parser stops on (its `;` or closing `}`).
+        let start = self.current_span.start;
+        // RES-307: snapshot the error count before each statement
+        // parse. If the parse recorded a new diagnostic AND the
+        // cursor isn't already at a clean statement boundary,
+        // scan ahead to one before continuing — this is what
+        // stops a single mistake from cascading into a pile of
+        // derived errors.
+        let errs_before = self.errors.len();
+        if let Some(statement) = self.parse_statement() {
+            let end = self.current_span.end;
+            item = Some(span::Spanned::new(statement, span::Span::new(start, end)));
+        }
+        if self.errors.len() > errs_before
+            && self.current_token != Token::Eof
+            && self.current_token != Token::Semicolon
+            && !parser_recovery::starts_top_level_item(&self.current_token)
+        {
+            self.synchronize_top_level();
+        }
+        // Defensive: at the per-run cap we stop emitting new
+        // errors but the parser keeps walking tokens; drain to
+        // EOF in one shot rather than churning through every
+        // remaining token via `parse_statement`.
+        if self.errors.len() >= parser_recovery::MAX_PARSE_ERRORS {
+            while self.current_token != Token::Eof {
+                self.next_token();
+            }
+            return item;
+        }
+        // RES-1860: do NOT advance if synchronize_top_level already
+        // parked the cursor on the first token of the next declaration
+        // (e.g. `fn`). Advancing here would consume that token and cause
+        // the next iteration to miss the declaration entirely.
+        if !parser_recovery::starts_top_level_item(&self.current_token) {
+            self.next_token();
+        }
+        item
+    }
+
     fn parse_statement(&mut self) -> Option<Node> {
         // RES-389: soft-keyword dispatch for the `pure` / `io`
         // effect annotations. They're lexed as identifiers so
@@ -3749,6 +4021,14 @@ impl Parser {
             };
             return Some(self.parse_effect_keyword_function(effects));
         }
+        // `test fn name() { ... }` — a test for `rz test`. `test` stays
+        // a soft keyword so it remains usable as a name.
+        if let Token::Identifier(n) = &self.current_token
+            && n == "test"
+            && self.peek_token == Token::Function
+        {
+            return Some(crate::test_runner::parse_test_fn(self));
+        }
         match self.current_token {
             // RES-191: `@pure` (and future attributes) prefix a
             // function declaration. Dispatched here so the
@@ -3796,25 +4076,8 @@ impl Parser {
             // (must be inside a loop body) is enforced by the
             // typechecker pass `crate::loop_invariants::check`.
             Token::Invariant => Some(crate::loop_invariants::parse_invariant_statement(self)),
-            Token::Unknown(ch) => {
-                // RES-114: if the offending char is alphabetic (in
-                // the Unicode sense), the lexer's ASCII-only
-                // policy rejected it specifically as a non-ASCII
-                // identifier candidate. Surface a dedicated
-                // message so users grep for "non-ASCII" rather
-                // than chasing a generic "Unexpected character".
-                let msg = if ch.is_alphabetic() && !ch.is_ascii() {
-                    format!(
-                        "identifier contains non-ASCII character '{}' \
-                         — Resilient identifiers are ASCII-only (see SYNTAX.md)",
-                        ch
-                    )
-                } else {
-                    format!("Unexpected character '{}'", ch)
-                };
-                self.record_error(msg);
-                None
-            }
+            // Already reported by `next_token`.
+            Token::Error(_) => None,
             // RES-1084: orphan closing brackets at statement-start
             // are an unbalanced-delimiter indicator (an extra `}`
             // outside any block, or a stray `)` / `]`). Without an
@@ -3846,7 +4109,7 @@ impl Parser {
                 // Strategy: read the identifier, consume `:`, then parse
                 // the labeled loop.
                 let label = match &self.current_token {
-                    Token::Identifier(n) => n.clone(),
+                    Token::Identifier(n) => n.to_string(),
                     _ => unreachable!(),
                 };
                 // Advance past identifier and `:` to see what follows.
@@ -3922,6 +4185,7 @@ impl Parser {
     /// follows the LHS. Entered with `current_token` = the leading
     /// Identifier.
     fn parse_maybe_index_assignment(&mut self) -> Node {
+        let stmt_span = self.span_at_current();
         // Parse the index/field expression (which consumes IDENT, [, …, ]).
         let lhs = self.parse_expression(0).unwrap_or(Node::IntegerLiteral {
             value: 0,
@@ -3981,7 +4245,7 @@ impl Parser {
                 },
                 _ => Node::ExpressionStatement {
                     expr: Box::new(lhs),
-                    span: span::Span::default(),
+                    span: self.span_from(stmt_span),
                 },
             };
         }
@@ -4003,7 +4267,7 @@ impl Parser {
         }
         Node::ExpressionStatement {
             expr: Box::new(lhs),
-            span: span::Span::default(),
+            span: self.span_from(stmt_span),
         }
     }
 
@@ -4079,7 +4343,7 @@ impl Parser {
     fn parse_assignment(&mut self) -> Node {
         let stmt_span = self.span_at_current();
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             _ => unreachable!("parse_assignment only dispatched for Identifier"),
         };
         self.next_token(); // move onto '='
@@ -4105,7 +4369,7 @@ impl Parser {
     fn parse_compound_assignment(&mut self) -> Node {
         let stmt_span = self.span_at_current();
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             _ => unreachable!("parse_compound_assignment only dispatched for Identifier"),
         };
         let ident_span = self.span_at_current();
@@ -4133,7 +4397,7 @@ impl Parser {
         }
         // Desugar: `name = name OP rhs`.
         let lhs_read = Node::Identifier {
-            name: name.clone(),
+            name: name.as_str().into(),
             span: ident_span,
         };
         let combined = Node::InfixExpression {
@@ -4231,11 +4495,11 @@ impl Parser {
     /// cascade into unrelated parse errors just because of a typo.
     fn parse_attributed_item(&mut self) -> Node {
         debug_assert_eq!(self.current_token, Token::At);
-        let attr_line = self.current_line;
+        let attr_line = self.current_span.start.line;
         self.next_token(); // skip '@'
 
         let attr_name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             other => {
                 let tok = other.clone();
                 self.record_error_expected(format!(
@@ -4274,7 +4538,7 @@ impl Parser {
             if self.current_token == Token::LeftParen {
                 self.next_token(); // skip `(`
                 if let Token::Identifier(arg) = &self.current_token {
-                    args = arg.clone();
+                    args = arg.to_string();
                     self.next_token(); // skip argument
                 } else {
                     let tok = self.current_token.clone();
@@ -4384,7 +4648,7 @@ impl Parser {
         self.next_token(); // skip `(`
 
         let kind = match &self.current_token {
-            Token::Identifier(s) => s.clone(),
+            Token::Identifier(s) => s.to_string(),
             other => {
                 let tok = other.clone();
                 self.record_error(format!(
@@ -4482,7 +4746,7 @@ impl Parser {
         let (pre_name_type_params, pre_name_bounds) = self.parse_optional_type_params();
 
         let name = match &self.current_token {
-            Token::Identifier(name) => name.clone(),
+            Token::Identifier(name) => name.to_string(),
             _ => {
                 let tok = self.current_token.clone();
                 self.record_error_expected(format!(
@@ -4528,12 +4792,12 @@ impl Parser {
                     name,
                     parameters: Vec::new(),
                     defaults: Vec::new(),
-                    body: Box::new(Node::Block {
+                    body: Arc::new(Node::Block {
                         stmts: Vec::new(),
                         span: span::Span::default(),
                     }),
-                    requires: Vec::new(),
-                    ensures: Vec::new(),
+                    requires: Arc::default(),
+                    ensures: Arc::default(),
                     recovers_to: None,
                     return_type: None,
                     span: fn_span,
@@ -4551,9 +4815,9 @@ impl Parser {
                 name,
                 parameters: Vec::new(),
                 defaults: Vec::new(),
-                body: Box::new(body),
-                requires: Vec::new(),
-                ensures: Vec::new(),
+                body: Arc::new(body),
+                requires: Arc::default(),
+                ensures: Arc::default(),
                 recovers_to: None,
                 return_type: None,
                 span: fn_span,
@@ -4622,12 +4886,12 @@ impl Parser {
                     name,
                     parameters,
                     defaults,
-                    body: Box::new(Node::Block {
+                    body: Arc::new(Node::Block {
                         stmts: Vec::new(),
                         span: span::Span::default(),
                     }),
-                    requires,
-                    ensures,
+                    requires: Arc::new(requires),
+                    ensures: Arc::new(ensures),
                     recovers_to,
                     return_type,
                     span: fn_span,
@@ -4649,9 +4913,9 @@ impl Parser {
             name,
             parameters,
             defaults,
-            body: Box::new(body),
-            requires,
-            ensures,
+            body: Arc::new(body),
+            requires: Arc::new(requires),
+            ensures: Arc::new(ensures),
             recovers_to,
             return_type,
             span: fn_span,
@@ -4689,7 +4953,7 @@ impl Parser {
         }
 
         let first_name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             other => {
                 self.record_error(format!(
                     "Expected struct or trait name after 'impl', found {}",
@@ -4705,7 +4969,7 @@ impl Parser {
         let (trait_name, struct_name) = if self.current_token == Token::For {
             self.next_token(); // skip 'for'
             let type_name = match &self.current_token {
-                Token::Identifier(n) => n.clone(),
+                Token::Identifier(n) => n.to_string(),
                 other => {
                     self.record_error(format!(
                         "Expected type name after 'impl {} for', found {}",
@@ -4777,7 +5041,7 @@ impl Parser {
 
         Node::ImplBlock {
             trait_name,
-            struct_name,
+            struct_name: struct_name.to_string(),
             methods,
             associated_type_impls,
             span: impl_span,
@@ -4813,7 +5077,7 @@ impl Parser {
         // Expect: `<TraitName> for <type_param> {`
         let trait_name = match &self.current_token {
             Token::Identifier(n) => {
-                let n = n.clone();
+                let n = n.to_string();
                 self.next_token();
                 n
             }
@@ -4899,7 +5163,7 @@ impl Parser {
         self.next_token(); // skip 'type'
 
         let type_name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             other => {
                 self.record_error(format!(
                     "Expected type name after 'type' in impl block, found {}",
@@ -4941,7 +5205,7 @@ impl Parser {
                         type_expr.push(' ');
                     }
                     let token_text = match &self.current_token {
-                        Token::Identifier(n) => n.clone(),
+                        Token::Identifier(n) => n.to_string(),
                         _ => self.current_token.to_string(),
                     };
                     type_expr.push_str(&token_text);
@@ -4953,7 +5217,7 @@ impl Parser {
                         type_expr.push(' ');
                     }
                     let token_text = match &self.current_token {
-                        Token::Identifier(n) => n.clone(),
+                        Token::Identifier(n) => n.to_string(),
                         _ => self.current_token.to_string(),
                     };
                     type_expr.push_str(&token_text);
@@ -4964,7 +5228,7 @@ impl Parser {
                         type_expr.push(' ');
                     }
                     let token_text = match &self.current_token {
-                        Token::Identifier(n) => n.clone(),
+                        Token::Identifier(n) => n.to_string(),
                         _ => self.current_token.to_string(),
                     };
                     type_expr.push_str(&token_text);
@@ -4991,7 +5255,7 @@ impl Parser {
         self.next_token(); // skip `actor`
 
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             other => {
                 self.record_error(format!(
                     "Expected actor name after `actor`, found {}",
@@ -5146,7 +5410,7 @@ impl Parser {
                     }
                     self.next_token(); // skip `:`
                     let target = match &self.current_token {
-                        Token::Identifier(n) => n.clone(),
+                        Token::Identifier(n) => n.to_string(),
                         other => {
                             self.record_error(format!(
                                 "Expected handler name inside `eventually(after: ...)` in actor `{}`, found {}",
@@ -5192,7 +5456,7 @@ impl Parser {
                         ));
                     }
                     eventually_clauses.push(EventuallyClause {
-                        target_handler: target,
+                        target_handler: target.to_string(),
                         post,
                         span: ev_span,
                     });
@@ -5202,7 +5466,7 @@ impl Parser {
                     let recv_span = self.span_at_current();
                     self.next_token(); // skip `receive`
                     let handler_name = match &self.current_token {
-                        Token::Identifier(n) => n.clone(),
+                        Token::Identifier(n) => n.to_string(),
                         other => {
                             self.record_error(format!(
                                 "Expected handler name after `receive` in actor `{}`, found {}",
@@ -5248,7 +5512,7 @@ impl Parser {
                         self.next_token();
                     }
                     receive_handlers.push(ReceiveHandler {
-                        name: handler_name,
+                        name: handler_name.to_string(),
                         parameters,
                         requires,
                         ensures,
@@ -5322,7 +5586,7 @@ impl Parser {
         self.next_token(); // skip 'fn'
 
         let method_name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             other => {
                 self.record_error(format!(
                     "Expected method name after 'fn' in impl block, found {}",
@@ -5394,9 +5658,9 @@ impl Parser {
             name: mangled,
             parameters,
             defaults,
-            body: Box::new(body),
-            requires,
-            ensures,
+            body: Arc::new(body),
+            requires: Arc::new(requires),
+            ensures: Arc::new(ensures),
             recovers_to,
             return_type,
             span: fn_span,
@@ -5429,7 +5693,7 @@ impl Parser {
         self.next_token(); // skip `type`
 
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             other => {
                 self.record_error_expected(format!(
                     "Expected alias name after 'type', found {}",
@@ -5478,7 +5742,7 @@ impl Parser {
         self.next_token(); // skip `newtype`
 
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             other => {
                 let tok = other.clone();
                 self.record_error(format!(
@@ -5501,7 +5765,7 @@ impl Parser {
         }
 
         let base_type = match &self.current_token {
-            Token::Identifier(t) => t.clone(),
+            Token::Identifier(t) => t.to_string(),
             other => {
                 let tok = other.clone();
                 self.record_error(format!(
@@ -5541,7 +5805,7 @@ impl Parser {
         self.next_token(); // skip `region`
 
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             other => {
                 let tok = other.clone();
                 self.record_error(format!(
@@ -5602,7 +5866,7 @@ impl Parser {
             && let Token::Identifier(eff_name) = &self.peek_token
             && eff_name.chars().count() == 1
         {
-            let eff = eff_name.clone();
+            let eff = *eff_name;
             self.next_token(); // current was Minus -> Identifier
             self.next_token(); // current was Identifier -> next
             if self.current_token == Token::Arrow {
@@ -5691,7 +5955,7 @@ impl Parser {
                 let region = if self.current_token == Token::LeftBracket {
                     self.next_token(); // skip `[`
                     let label = match &self.current_token {
-                        Token::Identifier(n) => n.clone(),
+                        Token::Identifier(n) => n.to_string(),
                         other => {
                             let tok = other.clone();
                             self.record_error(format!(
@@ -5741,7 +6005,7 @@ impl Parser {
             Token::Identifier(t) if t == "dyn" => {
                 self.next_token(); // consume `dyn`
                 let trait_name = match &self.current_token {
-                    Token::Identifier(n) => n.clone(),
+                    Token::Identifier(n) => n.to_string(),
                     other => {
                         let tok = other.clone();
                         self.record_error(format!(
@@ -5760,7 +6024,7 @@ impl Parser {
                 });
             }
             Token::Identifier(t) => {
-                let ty = t.clone();
+                let ty = t.to_string();
                 self.next_token(); // advance past the identifier
                 // A-E3 (RES-3933): associated-type projection —
                 // `Base::AssocName` (e.g. `Self::Width`, `T::Item`).
@@ -5773,7 +6037,7 @@ impl Parser {
                 if self.current_token == Token::DoubleColon {
                     self.next_token(); // consume `::`
                     let assoc = match &self.current_token {
-                        Token::Identifier(a) => a.clone(),
+                        Token::Identifier(a) => a.to_string(),
                         other => {
                             let tok = other.clone();
                             self.record_error(format!(
@@ -5853,7 +6117,7 @@ impl Parser {
                 // `[T; N]` — fixed-size array type.
                 self.next_token(); // skip `[`
                 let elem = match &self.current_token {
-                    Token::Identifier(t) => t.clone(),
+                    Token::Identifier(t) => t.to_string(),
                     _ => {
                         let tok = self.current_token.clone();
                         self.record_error(format!(
@@ -5892,12 +6156,15 @@ impl Parser {
                     return None;
                 }
                 self.next_token(); // skip `;`
+                // The length is a literal or the name of a `const`,
+                // which `const_eval::check` resolves.
                 let len = match &self.current_token {
-                    Token::IntLiteral(n) if *n >= 0 => *n,
+                    Token::IntLiteral(n) if *n >= 0 => n.to_string(),
+                    Token::Identifier(n) => n.to_string(),
                     _ => {
                         let tok = self.current_token.clone();
                         self.record_error(format!(
-                            "Expected non-negative integer length in `[{}; N]` {}, found {}",
+                            "Expected non-negative integer or constant length in `[{}; N]` {}, found {}",
                             elem, ctx, tok
                         ));
                         while self.current_token != Token::RightBracket
@@ -5980,7 +6247,7 @@ impl Parser {
                     && let Token::Identifier(eff_name) = &self.peek_token
                     && eff_name.chars().count() == 1
                 {
-                    let eff = eff_name.clone();
+                    let eff = *eff_name;
                     self.next_token(); // current was Minus -> Identifier
                     self.next_token(); // current was Identifier -> next
                     if self.current_token == Token::Arrow {
@@ -6051,7 +6318,7 @@ impl Parser {
                 let mut fields: Vec<String> = Vec::with_capacity(2);
                 while self.current_token != Token::RightBrace && self.current_token != Token::Eof {
                     let field_name = match &self.current_token {
-                        Token::Identifier(name) => name.clone(),
+                        Token::Identifier(name) => name.to_string(),
                         other => {
                             let tok = other.clone();
                             self.record_error(format!(
@@ -6106,6 +6373,19 @@ impl Parser {
                 None
             }
         };
+        // Union type `T1 | T2 | ...`, encoded as `"T1 | T2"`. The
+        // typechecker splits it back apart on top-level `|`.
+        let base = match base {
+            Some(first) if self.current_token == Token::BitOr => {
+                let mut members = vec![first];
+                while self.current_token == Token::BitOr {
+                    self.next_token(); // skip `|`
+                    members.push(self.parse_type_annotation(ctx)?);
+                }
+                Some(members.join(" | "))
+            }
+            other => other,
+        };
         // RES-385: prefix-encode the linearity bit so every existing
         // caller slot (parameter lists, let-annotations, return types,
         // struct fields) inherits the feature with zero field changes.
@@ -6213,9 +6493,9 @@ impl Parser {
                     loop {
                         match &self.current_token {
                             Token::Identifier(name) => {
-                                let name = name.clone();
+                                let name = name.to_string();
                                 if !fails.contains(&name) {
-                                    fails.push(name);
+                                    fails.push(name.to_string());
                                 }
                                 self.next_token();
                             }
@@ -6291,7 +6571,7 @@ impl Parser {
         while self.current_token != Token::Greater {
             match &self.current_token {
                 Token::Identifier(name) => {
-                    names.push(name.clone());
+                    names.push(name.to_string());
                     self.next_token();
                     // RES-290: optional `: Trait1 + Trait2` bound list.
                     // RES-775: Also supports `: effect` for effect polymorphism.
@@ -6303,7 +6583,7 @@ impl Parser {
                         loop {
                             match &self.current_token {
                                 Token::Identifier(bname) => {
-                                    this_bounds.push(bname.clone());
+                                    this_bounds.push(bname.to_string());
                                     self.next_token();
                                 }
                                 Token::Type => {
@@ -6407,7 +6687,7 @@ impl Parser {
                         break;
                     }
                     let field_name = match &self.current_token {
-                        Token::Identifier(n) => n.clone(),
+                        Token::Identifier(n) => n.to_string(),
                         tok => {
                             let tok = tok.clone();
                             self.record_error(format!(
@@ -6422,7 +6702,7 @@ impl Parser {
                         self.next_token(); // skip ':'
                         match &self.current_token {
                             Token::Identifier(alias) => {
-                                let alias = alias.clone();
+                                let alias = alias.to_string();
                                 self.next_token();
                                 alias
                             }
@@ -6431,7 +6711,7 @@ impl Parser {
                     } else {
                         field_name.clone()
                     };
-                    fields.push((field_name, local_name));
+                    fields.push((field_name.to_string(), local_name.to_string()));
                     if self.current_token == Token::Comma {
                         self.next_token();
                     }
@@ -6448,7 +6728,7 @@ impl Parser {
                     fields,
                     has_rest,
                     value: Box::new(Node::Identifier {
-                        name: synthetic,
+                        name: synthetic.into(),
                         span: pat_span,
                     }),
                     span: pat_span,
@@ -6469,17 +6749,39 @@ impl Parser {
                 continue;
             }
 
-            let param_name = match &self.current_token {
-                Token::Identifier(name) => name.clone(),
-                _ => {
-                    let tok = self.current_token.clone();
-                    self.record_error_expected(format!("Expected parameter name, found {}", tok));
-                    break;
-                }
+            // An untyped parameter (`fn id(x)`): what was read as the
+            // type is the name. The empty type is generalized or
+            // widened to `Any` by the typechecker (`implicit_generics`).
+            // A lone builtin type name (`fn f(int)`) is still a missing
+            // parameter name, not a parameter called `int`.
+            let untyped = matches!(
+                self.current_token,
+                Token::Comma | Token::RightParen | Token::Assign
+            ) && param_type.chars().all(|c| c.is_alphanumeric() || c == '_')
+                && param_type.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                && !matches!(
+                    param_type.as_str(),
+                    "int" | "float" | "string" | "bool" | "char" | "bytes" | "void" | "any"
+                );
+            let (param_type, param_name) = if untyped {
+                (String::new(), param_type)
+            } else {
+                let param_name = match &self.current_token {
+                    Token::Identifier(name) => name.to_string(),
+                    _ => {
+                        let tok = self.current_token.clone();
+                        self.record_error_expected(format!(
+                            "Expected parameter name, found {}",
+                            tok
+                        ));
+                        break;
+                    }
+                };
+                self.next_token(); // Skip name
+                (param_type, param_name)
             };
 
             parameters.push((param_type, param_name));
-            self.next_token(); // Skip name
 
             // RES-326: optional `= EXPR` default value for this parameter.
             if self.current_token == Token::Assign {
@@ -6598,9 +6900,12 @@ impl Parser {
             self.next_token();
         }
 
+        // The span runs to the closing `}` so the typechecker's scope
+        // map knows where each block's bindings stop being visible.
+        let close = self.current_span.end;
         Node::Block {
             stmts: statements,
-            span: brace_span,
+            span: span::Span::new(brace_span.start, close),
         }
     }
 
@@ -6629,7 +6934,7 @@ impl Parser {
         }
 
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             _ => {
                 let tok = self.current_token.clone();
                 self.record_error_expected(format!(
@@ -6742,7 +7047,7 @@ impl Parser {
                 }
             }
             match &self.current_token {
-                Token::Identifier(n) => names.push(n.clone()),
+                Token::Identifier(n) => names.push(n.to_string()),
                 Token::Underscore => names.push("_".to_string()),
                 other => {
                     let tok = other.clone();
@@ -6811,7 +7116,7 @@ impl Parser {
         let destructure = Node::LetTupleDestructure {
             names,
             value: Box::new(Node::Identifier {
-                name: tmp.clone(),
+                name: tmp.as_str().into(),
                 span: stmt_span,
             }),
             span: stmt_span,
@@ -6991,7 +7296,7 @@ impl Parser {
         self.next_token(); // skip `const`
 
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             _ => {
                 let tok = self.current_token.clone();
                 self.record_error_expected(format!(
@@ -7065,7 +7370,7 @@ impl Parser {
         self.next_token(); // skip `mod`
 
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             _ => {
                 let tok = self.current_token.clone();
                 self.record_error_expected(format!(
@@ -7136,7 +7441,7 @@ impl Parser {
         }
 
         let name = match &self.current_token {
-            Token::Identifier(name) => name.clone(),
+            Token::Identifier(name) => name.to_string(),
             _ => {
                 let tok = self.current_token.clone();
                 self.record_error_expected(format!(
@@ -7312,7 +7617,7 @@ impl Parser {
             }
 
             let field_name = match &self.current_token {
-                Token::Identifier(n) => n.clone(),
+                Token::Identifier(n) => n.to_string(),
                 _ => {
                     let tok = self.current_token.clone();
                     self.record_error(format!(
@@ -7328,7 +7633,7 @@ impl Parser {
             let local_name = if self.current_token == Token::Colon {
                 self.next_token(); // skip `:`
                 let local = match &self.current_token {
-                    Token::Identifier(n) => n.clone(),
+                    Token::Identifier(n) => n.to_string(),
                     _ => {
                         let tok = self.current_token.clone();
                         self.record_error(format!(
@@ -7347,7 +7652,7 @@ impl Parser {
                 field_name.clone()
             };
 
-            fields.push((field_name, local_name));
+            fields.push((field_name.to_string(), local_name.to_string()));
 
             if self.current_token == Token::Comma {
                 self.next_token();
@@ -7476,7 +7781,7 @@ impl Parser {
             }
 
             let field_name = match &self.current_token {
-                Token::Identifier(n) => n.clone(),
+                Token::Identifier(n) => n.to_string(),
                 _ => {
                     let tok = self.current_token.clone();
                     self.record_error(format!(
@@ -7494,9 +7799,9 @@ impl Parser {
                 self.next_token();
                 p
             } else {
-                Pattern::Identifier(field_name.clone())
+                Pattern::Identifier(field_name.to_string())
             };
-            fields.push((field_name, Box::new(subpat)));
+            fields.push((field_name.to_string(), Box::new(subpat)));
 
             if self.current_token == Token::Comma {
                 self.next_token();
@@ -7543,14 +7848,14 @@ impl Parser {
     fn parse_use_statement_with_visibility(&mut self, is_pub: bool) -> Option<Node> {
         self.next_token(); // consume 'use'
         let path = match &self.current_token {
-            Token::StringLiteral(s) => s.clone(),
+            Token::StringLiteral(_) => self.take_string_literal(),
             Token::Identifier(name) => {
-                let mut segments = vec![name.clone()];
+                let mut segments = vec![name.to_string()];
                 while self.peek_token == Token::DoubleColon {
                     self.next_token(); // consume identifier
                     self.next_token(); // consume '::'
                     match &self.current_token {
-                        Token::Identifier(seg) => segments.push(seg.clone()),
+                        Token::Identifier(seg) => segments.push(seg.to_string()),
                         _ => {
                             self.record_error(
                                 "Expected identifier after `::` in use path".to_string(),
@@ -7571,7 +7876,7 @@ impl Parser {
             self.next_token();
             match &self.current_token {
                 Token::Identifier(name) => {
-                    let name = name.clone();
+                    let name = name.to_string();
                     Some(name)
                 }
                 _ => {
@@ -7592,7 +7897,7 @@ impl Parser {
             let mut selectors = Vec::new();
             while self.current_token != Token::RightBrace && self.current_token != Token::Eof {
                 match &self.current_token {
-                    Token::Identifier(name) => selectors.push(name.clone()),
+                    Token::Identifier(name) => selectors.push(name.to_string()),
                     _ => {
                         let tok = self.current_token.clone();
                         self.record_error(format!(
@@ -7634,16 +7939,18 @@ impl Parser {
         })
     }
 
-    /// FFI v1: `extern "lib" { decl; decl; ... }`.
-    /// Each decl is parsed by `parse_extern_decl`.
+    /// FFI v1: `extern "lib" { decl; decl; ... }`, or a single
+    /// `extern "lib" decl` without the braces — usually
+    /// `extern "C" fn f(x: int) -> int;`, bound against the `--ffi-lib`
+    /// libraries. Each decl is parsed by `parse_extern_decl`.
     fn parse_extern_block(&mut self) -> Option<Node> {
         let extern_span = self.span_at_current();
         self.next_token(); // consume `extern`
 
         // Library descriptor.
         let library = match &self.current_token {
-            Token::StringLiteral(s) => {
-                let s = s.clone();
+            Token::StringLiteral(_) => {
+                let s = self.take_string_literal();
                 self.next_token();
                 s
             }
@@ -7656,6 +7963,15 @@ impl Parser {
             }
         };
 
+        if matches!(self.current_token, Token::Function | Token::At) {
+            let decl = self.parse_extern_decl()?;
+            return Some(Node::Extern {
+                library,
+                decls: vec![decl],
+                span: extern_span,
+            });
+        }
+
         // `{`
         if !matches!(self.current_token, Token::LeftBrace) {
             self.record_error(format!(
@@ -7672,6 +7988,7 @@ impl Parser {
         while !matches!(self.current_token, Token::RightBrace | Token::Eof) {
             if let Some(d) = self.parse_extern_decl() {
                 decls.push(d);
+                self.next_token(); // skip `;`
             } else {
                 // Recovery: skip to next `;` or `}`.
                 while !matches!(
@@ -7686,10 +8003,8 @@ impl Parser {
             }
         }
 
-        if matches!(self.current_token, Token::RightBrace) {
-            self.next_token();
-        }
-
+        // Like every statement, stop on the last token (`}`); the
+        // statement loop steps past it.
         Some(Node::Extern {
             library,
             decls,
@@ -7738,7 +8053,7 @@ impl Parser {
         // Function name.
         let resilient_name = match &self.current_token {
             Token::Identifier(n) => {
-                let n = n.clone();
+                let n = n.to_string();
                 self.next_token();
                 n
             }
@@ -7779,8 +8094,8 @@ impl Parser {
         let c_name = if matches!(self.current_token, Token::Assign) {
             self.next_token(); // skip `=`
             match &self.current_token {
-                Token::StringLiteral(s) => {
-                    let s = s.clone();
+                Token::StringLiteral(_) => {
+                    let s = self.take_string_literal();
                     self.next_token();
                     s
                 }
@@ -7794,7 +8109,7 @@ impl Parser {
                 }
             }
         } else {
-            resilient_name.clone()
+            resilient_name.to_string()
         };
 
         // Optional `requires EXPR` / `ensures EXPR` clauses (paren-less — Resilient
@@ -7811,7 +8126,7 @@ impl Parser {
             );
         }
 
-        // Terminator `;`.
+        // Terminator `;`, left current for the caller.
         if !matches!(self.current_token, Token::Semicolon) {
             let tok = self.current_token.clone();
             self.record_error(format!(
@@ -7820,7 +8135,6 @@ impl Parser {
             ));
             return None;
         }
-        self.next_token(); // skip `;`
 
         Some(ExternDecl {
             resilient_name,
@@ -7877,7 +8191,7 @@ impl Parser {
             // Param name.
             let param_name = match &self.current_token {
                 Token::Identifier(n) => {
-                    let n = n.clone();
+                    let n = n.to_string();
                     self.next_token();
                     n
                 }
@@ -7908,7 +8222,7 @@ impl Parser {
                 None => break,
             };
 
-            parameters.push((param_type, param_name));
+            parameters.push((param_type, param_name.to_string()));
 
             match &self.current_token {
                 Token::Comma => {
@@ -8019,13 +8333,12 @@ impl Parser {
         // RES-2653: `break label;` — a lone identifier followed by `;`.
         // Must check peek FIRST: `break foo;` is a label, `break foo + 1;` is
         // an expression.  The label form keeps backward compatibility.
-        if let Token::Identifier(label) = &self.current_token.clone()
+        if let Token::Identifier(label) = self.current_token
             && (self.peek_token == Token::Semicolon || self.peek_token == Token::Eof)
         {
-            let label = label.clone();
             self.next_token(); // skip the label identifier
             return Node::BreakLabel {
-                label,
+                label: label.to_string(),
                 span: stmt_span,
             };
         }
@@ -8048,13 +8361,12 @@ impl Parser {
         let stmt_span = self.span_at_current();
         self.next_token(); // skip `continue`
         // RES-2653: `continue label;` — if current token is an identifier.
-        if let Token::Identifier(label) = &self.current_token.clone() {
-            let label = label.clone();
+        if let Token::Identifier(label) = self.current_token {
             if self.peek_token == Token::Semicolon {
                 self.next_token();
             }
             return Node::ContinueLabel {
-                label,
+                label: label.to_string(),
                 span: stmt_span,
             };
         }
@@ -8255,6 +8567,9 @@ impl Parser {
     }
 
     fn parse_live_block(&mut self) -> Node {
+        // The token after `live`, which shares its line; taken before
+        // the body so the span doesn't land on the closing `}`.
+        let live_span = self.span_at_current();
         self.next_token(); // Skip 'live'
 
         // RES-139 + RES-142 + RES-359: optional `backoff(...)`,
@@ -8334,7 +8649,7 @@ impl Parser {
                 backoff_kind,
                 timeout,
                 max_retries,
-                span: self.span_at_current(),
+                span: live_span,
             };
         }
 
@@ -8347,7 +8662,7 @@ impl Parser {
             backoff_kind,
             timeout,
             max_retries,
-            span: self.span_at_current(),
+            span: live_span,
         }
     }
 
@@ -8426,7 +8741,7 @@ impl Parser {
         self.next_token(); // skip integer literal
 
         let unit = match &self.current_token {
-            Token::Identifier(u) => u.clone(),
+            Token::Identifier(u) => u.to_string(),
             other => {
                 self.record_error(format!(
                     "Expected duration unit (`ns`, `us`, `ms`, `s`) after `within {}`, found {}",
@@ -8501,7 +8816,7 @@ impl Parser {
 
             // kwarg name
             let name = match &self.current_token {
-                Token::Identifier(n) => n.clone(),
+                Token::Identifier(n) => n.to_string(),
                 other => {
                     self.record_error(format!(
                         "Expected backoff kwarg name (`base_ms`, `factor`, `max_ms`, `kind`), found {}",
@@ -8531,7 +8846,7 @@ impl Parser {
             if name == "kind" {
                 let kind_str: String = match &self.current_token {
                     Token::Linear => "linear".to_string(),
-                    Token::Identifier(n) => n.clone(),
+                    Token::Identifier(n) => n.to_string(),
                     other => {
                         self.record_error(format!(
                             "Expected `linear` or `exponential` for backoff.`kind`, found {}",
@@ -8902,16 +9217,29 @@ impl Parser {
 
         Some(Node::ExpressionStatement {
             expr: Box::new(expr),
-            span: stmt_span,
+            span: self.span_from(stmt_span),
         })
     }
 
-    /// RES-078: build a single-position `Span` from the lexer's
-    /// current `last_token_*` state — good enough for leaf nodes
-    /// where the "source range" is just wherever the token starts.
+    /// RES-078: the extent of `current_token`, for the node it starts.
     fn span_at_current(&self) -> span::Span {
-        let pos = span::Pos::new(self.lexer.last_token_line, self.lexer.last_token_column, 0);
-        span::Span::new(pos, pos)
+        self.current_span
+    }
+
+    /// From `start` to the end of `current_token`, for a node whose
+    /// last token is the current one.
+    fn span_from(&self, start: span::Span) -> span::Span {
+        span::Span::new(start.start, self.current_span.end)
+    }
+
+    /// The text of the `StringLiteral` the parser is on, moved out of
+    /// the token instead of cloned. Only for the node that consumes
+    /// the token; the empty string left behind is never read.
+    fn take_string_literal(&mut self) -> String {
+        match &mut self.current_token {
+            Token::StringLiteral(s) => std::mem::take(s),
+            _ => String::new(),
+        }
     }
 
     /// RES-4185: depth-guarded entry point. Delegates to
@@ -8958,7 +9286,7 @@ impl Parser {
                 Some(self.parse_function_literal_with_effect(Some(effect)))
             }
             Token::Identifier(name) => {
-                let name = name.clone();
+                let name = name.to_string();
                 // RES-360: `ns::decl` scoped lookup. If the next token is
                 // `::` followed by an identifier, collapse both into a
                 // single flat identifier name `"ns::decl"`. This keeps
@@ -8968,7 +9296,7 @@ impl Parser {
                     self.next_token(); // consume identifier, current = '::'
                     self.next_token(); // consume '::', current = member ident
                     let member = match &self.current_token {
-                        Token::Identifier(m) => m.clone(),
+                        Token::Identifier(m) => m.to_string(),
                         _ => {
                             let tok = self.current_token.clone();
                             self.record_error(format!(
@@ -8979,16 +9307,23 @@ impl Parser {
                         }
                     };
                     Some(Node::Identifier {
-                        name: format!("{}::{}", name, member),
+                        name: format!("{}::{}", name, member).into(),
                         span: tok_span,
                     })
                 } else {
                     Some(Node::Identifier {
-                        name,
+                        name: name.into(),
                         span: tok_span,
                     })
                 }
             }
+            // `_` in expression position reads a binding named `_`.
+            // Programs never declare one (`let _` is rejected), so this
+            // only resolves in the REPL, which binds its last result there.
+            Token::Underscore => Some(Node::Identifier {
+                name: "_".into(),
+                span: tok_span,
+            }),
             // RES-2616: integer literal — optionally followed by a type suffix identifier.
             // Both int suffixes (`42u8`) and float suffixes (`42f32`) are accepted here.
             Token::IntLiteral(value) => {
@@ -9025,11 +9360,12 @@ impl Parser {
                     })
                 }
             }
-            Token::StringLiteral(value) => {
-                let intern_id = crate::string_interning::intern_string(value.clone());
+            Token::StringLiteral(_) => {
+                let content = self.take_string_literal();
+                let intern_id = crate::string_interning::intern_string(content.clone());
                 Some(Node::StringInternLiteral {
                     intern_id,
-                    content: value.clone(),
+                    content,
                     span: tok_span,
                 })
             }
@@ -9109,7 +9445,7 @@ impl Parser {
             // identifier immediately after the keyword. When followed by `(`
             // directly it can only be the builtin call form.
             Token::Receive if self.peek_token == Token::LeftParen => Some(Node::Identifier {
-                name: "receive".to_string(),
+                name: "receive".into(),
                 span: tok_span,
             }),
             _ => None,
@@ -9119,7 +9455,20 @@ impl Parser {
         // an InterpolatedString node. Done here — after the outer match
         // releases the borrow on `self.current_token` — so we can call
         // `self.record_error` on parse failure without borrow conflicts.
+        let format_template = std::mem::take(&mut self.format_template_next);
         left_expr = match left_expr {
+            // A `format` template keeps its braces; `\{` still
+            // unescapes so older `\{}` templates read the same.
+            Some(Node::StringInternLiteral { content, span, .. })
+                if format_template && content.contains('{') =>
+            {
+                let content = content.replace("\\{", "{");
+                Some(Node::StringInternLiteral {
+                    intern_id: crate::string_interning::intern_string(content.clone()),
+                    content,
+                    span,
+                })
+            }
             Some(Node::StringLiteral { ref value, span }) if value.contains('{') => {
                 let raw = value.clone();
                 match crate::string_interp::parse_parts(&raw) {
@@ -9269,7 +9618,7 @@ impl Parser {
                     self.next_token(); // current = `as`
                     self.next_token(); // current = type identifier
                     let type_name = match &self.current_token {
-                        Token::Identifier(n) => n.clone(),
+                        Token::Identifier(n) => n.to_string(),
                         // `as int` / `as float` / etc. lex `int` as a
                         // bare identifier already, but if a future
                         // change introduces a Token::IntKw or similar,
@@ -9311,7 +9660,7 @@ impl Parser {
                     };
                     Some(Node::CallExpression {
                         function: Box::new(Node::Identifier {
-                            name: builtin.to_string(),
+                            name: builtin.into(),
                             span: as_span,
                         }),
                         arguments: vec![current_left],
@@ -9327,7 +9676,7 @@ impl Parser {
                     let qd_span = self.span_at_current();
                     self.next_token(); // current = identifier, peek = next
                     let field_name = match &self.current_token {
-                        Token::Identifier(n) => n.clone(),
+                        Token::Identifier(n) => n.to_string(),
                         _ => {
                             let tok = self.current_token.clone();
                             self.record_error(format!(
@@ -9362,9 +9711,9 @@ impl Parser {
                                 self.next_token(); // consume `)`
                             }
                         }
-                        ChainAccess::Method(field_name, args)
+                        ChainAccess::Method(field_name.to_string(), args)
                     } else {
-                        ChainAccess::Field(field_name)
+                        ChainAccess::Field(field_name.to_string())
                     };
                     Some(Node::OptionalChain {
                         object: Box::new(current_left),
@@ -9441,7 +9790,10 @@ impl Parser {
         // RES-084: capture the call's span (lands on the `(` token)
         // before parsing arguments advances the lexer.
         let call_span = self.span_at_current();
+        self.format_template_next =
+            matches!(&function, Node::Identifier { name, .. } if name == "format");
         let arguments = self.parse_call_arguments();
+        self.format_template_next = false;
 
         Some(Node::CallExpression {
             function: Box::new(function),
@@ -9516,7 +9868,7 @@ impl Parser {
         // type annotations etc., but call-argument position only allows
         // expressions, so the only way `IDENT :` is legal here is as a
         // named-argument label.
-        if let Token::Identifier(name) = self.current_token.clone()
+        if let Token::Identifier(name) = self.current_token
             && self.peek_token == Token::Colon
         {
             let label_span = self.span_at_current();
@@ -9538,10 +9890,10 @@ impl Parser {
             if used_names.iter().any(|n| n == &name) {
                 self.record_error(format!("Duplicate named argument `{}` in call", name));
             }
-            used_names.push(name.clone());
+            used_names.push(name.to_string());
             *seen_named = true;
             return Some(Node::NamedArg {
-                name,
+                name: name.to_string(),
                 value: Box::new(value),
                 span: label_span,
             });
@@ -9576,7 +9928,7 @@ impl Parser {
     fn parse_struct_decl_with_attrs(&mut self, repr_c: bool) -> Node {
         self.next_token(); // skip 'struct'
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             _ => {
                 let tok = self.current_token.clone();
                 self.record_error_expected(format!(
@@ -9623,7 +9975,7 @@ impl Parser {
                 None => break,
             };
             let fname = match &self.current_token {
-                Token::Identifier(n) => n.clone(),
+                Token::Identifier(n) => n.to_string(),
                 _ => {
                     let tok = self.current_token.clone();
                     self.record_error(format!(
@@ -9743,7 +10095,7 @@ impl Parser {
         self.next_token(); // skip 'actor'
 
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             _ => {
                 let tok = self.current_token.clone();
                 self.record_error_expected(format!(
@@ -9850,7 +10202,7 @@ impl Parser {
                     let handler_span = self.span_at_current();
                     self.next_token(); // skip 'receive'
                     let handler_name = match &self.current_token {
-                        Token::Identifier(n) => n.clone(),
+                        Token::Identifier(n) => n.to_string(),
                         _ => {
                             let tok = self.current_token.clone();
                             self.record_error(format!(
@@ -9911,7 +10263,7 @@ impl Parser {
                     }
                     let body = self.parse_block_statement();
                     handlers.push(ActorHandler {
-                        name: handler_name,
+                        name: handler_name.to_string(),
                         ensures,
                         body: Box::new(body),
                         span: handler_span,
@@ -9955,7 +10307,7 @@ impl Parser {
         let stmt_span = self.span_at_current();
         self.next_token(); // skip `cluster`
         let name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             _ => {
                 let tok = self.current_token.clone();
                 self.record_error(format!(
@@ -9984,7 +10336,7 @@ impl Parser {
         let mut invariants: Vec<Node> = Vec::with_capacity(2);
         while self.current_token != Token::RightBrace && self.current_token != Token::Eof {
             let ident = match &self.current_token {
-                Token::Identifier(n) => n.clone(),
+                Token::Identifier(n) => n.to_string(),
                 _ => {
                     let tok = self.current_token.clone();
                     self.record_error(format!(
@@ -10022,7 +10374,7 @@ impl Parser {
                 invariants.push(expr);
             } else {
                 let actor_ty = match &self.current_token {
-                    Token::Identifier(n) => n.clone(),
+                    Token::Identifier(n) => n.to_string(),
                     _ => {
                         let tok = self.current_token.clone();
                         self.record_error(format!(
@@ -10042,7 +10394,7 @@ impl Parser {
                     break;
                 }
                 self.next_token(); // skip `;`
-                members.push((ident, actor_ty));
+                members.push((ident.to_string(), actor_ty.to_string()));
             }
         }
         Node::ClusterDecl {
@@ -10058,7 +10410,7 @@ impl Parser {
     fn parse_struct_literal(&mut self) -> Node {
         self.next_token(); // skip 'new'
         let mut name = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             _ => {
                 let tok = self.current_token.clone();
                 self.record_error_expected(format!(
@@ -10133,7 +10485,7 @@ impl Parser {
         if self.current_token == Token::DotDot {
             self.next_token(); // skip `..`
             let base_expr = self.parse_expression(0).unwrap_or(Node::Identifier {
-                name: String::new(),
+                name: String::new().into(),
                 span: span::Span::default(),
             });
             self.next_token(); // past expression
@@ -10167,7 +10519,7 @@ impl Parser {
             if self.current_token == Token::DotDot {
                 self.next_token(); // skip `..`
                 let base_expr = self.parse_expression(0).unwrap_or(Node::Identifier {
-                    name: String::new(),
+                    name: String::new().into(),
                     span: span::Span::default(),
                 });
                 base = Some(Box::new(base_expr));
@@ -10184,7 +10536,7 @@ impl Parser {
             // location, not some synthetic blank span).
             let fname_span = self.span_at_current();
             let fname = match &self.current_token {
-                Token::Identifier(n) => n.clone(),
+                Token::Identifier(n) => n.to_string(),
                 _ => {
                     let tok = self.current_token.clone();
                     self.record_error(format!(
@@ -10204,10 +10556,10 @@ impl Parser {
             // surface naturally if the name isn't bound in scope.
             if self.current_token == Token::Comma || self.current_token == Token::RightBrace {
                 let value = Node::Identifier {
-                    name: fname.clone(),
+                    name: fname.as_str().into(),
                     span: fname_span,
                 };
-                fields.push((fname, value));
+                fields.push((fname.to_string(), value));
                 if self.current_token == Token::Comma {
                     self.next_token();
                     if self.current_token == Token::RightBrace {
@@ -10231,7 +10583,7 @@ impl Parser {
                 value: 0,
                 span: span::Span::default(),
             });
-            fields.push((fname, value));
+            fields.push((fname.to_string(), value));
             // parse_expression leaves current on the last token of the
             // expression; advance to move past it.
             self.next_token();
@@ -10283,7 +10635,7 @@ impl Parser {
         loop {
             let fname_span = self.span_at_current();
             let fname = match &self.current_token {
-                Token::Identifier(n) => n.clone(),
+                Token::Identifier(n) => n.to_string(),
                 other => {
                     let tok = other.clone();
                     self.record_error(format!(
@@ -10296,10 +10648,10 @@ impl Parser {
             self.next_token(); // skip field name
             if self.current_token == Token::Comma || self.current_token == Token::RightBrace {
                 let value = Node::Identifier {
-                    name: fname.clone(),
+                    name: fname.as_str().into(),
                     span: fname_span,
                 };
-                fields.push((fname, value));
+                fields.push((fname.to_string(), value));
                 if self.current_token == Token::Comma {
                     self.next_token();
                     if self.current_token == Token::RightBrace {
@@ -10322,7 +10674,7 @@ impl Parser {
                 value: 0,
                 span: span::Span::default(),
             });
-            fields.push((fname, value));
+            fields.push((fname.to_string(), value));
             self.next_token();
             if self.current_token == Token::Comma {
                 self.next_token();
@@ -10366,12 +10718,12 @@ impl Parser {
             self.record_error_expected(format!("Expected '(' after anonymous 'fn', found {}", tok));
             return Node::FunctionLiteral {
                 parameters: Vec::new(),
-                body: Box::new(Node::Block {
+                body: Arc::new(Node::Block {
                     stmts: Vec::new(),
                     span: span::Span::default(),
                 }),
-                requires: Vec::new(),
-                ensures: Vec::new(),
+                requires: Arc::default(),
+                ensures: Arc::default(),
                 recovers_to: None,
                 return_type: None,
                 span: self.span_at_current(),
@@ -10389,12 +10741,12 @@ impl Parser {
             self.record_error_expected(format!("Expected '{{' in anonymous fn, found {}", tok));
             return Node::FunctionLiteral {
                 parameters,
-                body: Box::new(Node::Block {
+                body: Arc::new(Node::Block {
                     stmts: Vec::new(),
                     span: span::Span::default(),
                 }),
-                requires,
-                ensures,
+                requires: Arc::new(requires),
+                ensures: Arc::new(ensures),
                 recovers_to,
                 return_type,
                 span: self.span_at_current(),
@@ -10406,9 +10758,9 @@ impl Parser {
         let body = Self::prepend_prefix_bindings(body, fn_lit_prefix);
         Node::FunctionLiteral {
             parameters,
-            body: Box::new(body),
-            requires,
-            ensures,
+            body: Arc::new(body),
+            requires: Arc::new(requires),
+            ensures: Arc::new(ensures),
             recovers_to,
             return_type,
             span: self.span_at_current(),
@@ -10662,11 +11014,12 @@ impl Parser {
                 value: *f,
                 span: tok_span,
             }),
-            Token::StringLiteral(s) => {
-                let intern_id = crate::string_interning::intern_string(s.clone());
+            Token::StringLiteral(_) => {
+                let content = self.take_string_literal();
+                let intern_id = crate::string_interning::intern_string(content.clone());
                 Pattern::Literal(Node::StringInternLiteral {
                     intern_id,
-                    content: s.clone(),
+                    content,
                     span: tok_span,
                 })
             }
@@ -10680,7 +11033,7 @@ impl Parser {
                 span: tok_span,
             }),
             Token::Identifier(name) => {
-                let name = name.clone();
+                let name = name.to_string();
                 // RES-375: `Some(inner_pattern)` — Option presence pattern.
                 if name == "Some" && self.peek_token == Token::LeftParen {
                     self.next_token(); // current = `(`
@@ -10716,7 +11069,7 @@ impl Parser {
                     self.next_token(); // current = `::`
                     self.next_token(); // current = variant ident
                     let variant_name = match &self.current_token {
-                        Token::Identifier(v) => v.clone(),
+                        Token::Identifier(v) => v.to_string(),
                         other => {
                             let tok = other.clone();
                             self.record_error(format!(
@@ -10898,7 +11251,7 @@ impl Parser {
             });
         }
         let field = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             // RES-928: tuple-struct positional access — `p.0`, `p.1`, etc.
             // The lexer emits `IntLiteral(N)`; we coerce to a string field
             // name so the rest of the pipeline (typechecker, interpreter)
@@ -10993,7 +11346,7 @@ impl Parser {
         self.next_token(); // past `for` to the binding identifier
 
         let binding = match &self.current_token {
-            Token::Identifier(n) => n.clone(),
+            Token::Identifier(n) => n.to_string(),
             _ => {
                 let tok = self.current_token.clone();
                 self.record_error(format!(
@@ -11051,12 +11404,12 @@ impl Parser {
         // push(_r, <expr>)
         let push_call = Node::CallExpression {
             function: Box::new(Node::Identifier {
-                name: "push".to_string(),
+                name: "push".into(),
                 span: default(),
             }),
             arguments: vec![
                 Node::Identifier {
-                    name: acc.clone(),
+                    name: acc.as_str().into(),
                     span: default(),
                 },
                 first_expr,
@@ -11120,7 +11473,7 @@ impl Parser {
         // return _r;
         let ret_stmt = Node::ReturnStatement {
             value: Some(Box::new(Node::Identifier {
-                name: acc,
+                name: acc.into(),
                 span: default(),
             })),
             span: default(),
@@ -11135,9 +11488,9 @@ impl Parser {
         // fn() { body_block }
         let fn_lit = Node::FunctionLiteral {
             parameters: Vec::new(),
-            body: Box::new(body_block),
-            requires: Vec::new(),
-            ensures: Vec::new(),
+            body: Arc::new(body_block),
+            requires: Arc::default(),
+            ensures: Arc::default(),
             recovers_to: None,
             return_type: None,
             span: bracket_span,
@@ -11457,19 +11810,23 @@ type BuiltinFn = fn(&[Value]) -> RResult<Value>;
 /// variants (Int, Float, Bool, String).
 #[derive(Clone)]
 struct FunctionValue {
-    parameters: Rc<Vec<(String, String)>>,
-    body: Rc<Node>,
+    parameters: Arc<Vec<(String, String)>>,
+    body: Arc<Node>,
     env: Environment,
-    requires: Vec<Node>,
-    ensures: Vec<Node>,
+    requires: Arc<Vec<Node>>,
+    ensures: Arc<Vec<Node>>,
     recovers_to: Option<Box<Node>>,
     name: String,
     type_params: Vec<String>,
-    fails: Rc<Vec<String>>,
+    fails: Arc<Vec<String>>,
 }
 
-// Value types for our interpreter
+// Value types for our interpreter. With the `serde` feature, data
+// values serialize; functions, handles into per-process stores
+// (cells, actors) and the interpreter's control-flow signals are
+// skipped, so serializing one is an error.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum Value {
     Int(i64),
     Float(f64),
@@ -11477,21 +11834,28 @@ enum Value {
     Bool(bool),
     /// RES-2619: Unicode scalar value.
     Char(char),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Function(Box<FunctionValue>),
     /// Native function. `name` is the identifier it was registered as,
     /// for diagnostics only.
+    #[cfg_attr(feature = "serde", serde(skip))]
     Builtin {
-        name: &'static str,
+        name: StaticStr,
         func: BuiltinFn,
     },
     /// RES-032: dynamic array. Mixed types allowed at runtime until a
-    /// real type system (G7) can enforce a single element type.
-    Array(Vec<Value>),
+    /// real type system (G7) can enforce a single element type. The
+    /// elements are copy-on-write (see `shared`), so copying an array
+    /// is a refcount bump.
+    Array(Shared<Vec<Value>>),
     /// RES-038: user-defined record. Fields are stored in declaration
-    /// order so Display is stable.
+    /// order so Display is stable. They sit behind an `Rc` so copying a
+    /// struct — and every node of a recursive one hanging off it — is
+    /// a refcount bump; writers go through `Rc::make_mut`, which keeps
+    /// value semantics.
     Struct {
         name: String,
-        fields: Vec<(String, Value)>,
+        fields: Rc<Vec<(String, Value)>>,
     },
     /// RES-040: first-class Result type.
     ///
@@ -11509,24 +11873,30 @@ enum Value {
     /// present value; `Some(x)` constructs it and `?.` unwraps it before
     /// performing a chained field/method access.
     Option(Option<Box<Value>>),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Return(Box<Value>),
     /// RES-910: control-flow sentinel for `break`. Propagates through
     /// `eval_block_statement` like `Return`, but is intercepted by the
     /// nearest `WhileStatement` / `ForInStatement` evaluator.
+    #[cfg_attr(feature = "serde", serde(skip))]
     Break,
     /// RES-2551: control-flow sentinel for `break expr;`. Carries the
     /// break value; the enclosing `loop` evaluator returns it as the
     /// loop expression value.
+    #[cfg_attr(feature = "serde", serde(skip))]
     BreakWith(Box<Value>),
     /// RES-910: control-flow sentinel for `continue`. Same propagation
     /// rule as `Break`; the loop evaluator consumes it and starts the
     /// next iteration instead of exiting.
+    #[cfg_attr(feature = "serde", serde(skip))]
     Continue,
     /// RES-2653: labeled `break label;` sentinel — propagates through inner
     /// loops (which only intercept `Value::Break`) until the loop whose
     /// `label` matches consumes it.
+    #[cfg_attr(feature = "serde", serde(skip))]
     BreakLabel(String),
     /// RES-2653: labeled `continue label;` sentinel — same propagation rule.
+    #[cfg_attr(feature = "serde", serde(skip))]
     ContinueLabel(String),
     Void,
     /// RES-148: associative map. Keys are restricted (via `MapKey`) to
@@ -11538,15 +11908,16 @@ enum Value {
     /// Value identity is structural — two maps compare equal when
     /// their (K, V) pair sets match. (Implemented case-by-case in the
     /// few paths that need it; `Value` itself does not derive
-    /// `PartialEq`.)
-    Map(std::collections::HashMap<MapKey, Value>),
+    /// `PartialEq`.) Copy-on-write, like `Array`.
+    #[cfg_attr(feature = "serde", serde(with = "shared::map_entries"))]
+    Map(Shared<std::collections::HashMap<MapKey, Value>>),
     /// RES-149: unordered set of hashable primitives. Element type
     /// is the same `MapKey` that powers `Value::Map` keys — one
     /// policy, one enforcement site. Iteration order is unspecified
     /// on `std` (hash-based); the sibling no_std runtime would back
     /// with `BTreeSet` for sorted iteration (tracked as a follow-up
     /// when the runtime grows a set value type).
-    Set(std::collections::HashSet<MapKey>),
+    Set(Shared<std::collections::HashSet<MapKey>>),
     /// RES-152: raw byte sequence — protocol frames, register maps,
     /// packed on-the-wire layouts. Distinct from `String`: users
     /// bridge via explicit builtins, and the typechecker rejects
@@ -11577,6 +11948,7 @@ enum Value {
     /// compiler doesn't emit `Op::MakeClosure` yet, and the VM's
     /// dispatch for the new opcodes returns `VmError::Unsupported`.
     #[allow(dead_code)]
+    #[cfg_attr(feature = "serde", serde(skip))]
     Closure {
         fn_idx: u16,
         upvalues: Box<[Value]>,
@@ -11597,8 +11969,9 @@ enum Value {
     /// otherwise unbound in the surrounding env).
     #[cfg(feature = "ffi")]
     #[allow(dead_code)]
+    #[cfg_attr(feature = "serde", serde(skip))]
     Foreign {
-        name: &'static str,
+        name: StaticStr,
         symbol: std::sync::Arc<crate::ffi::ForeignSymbol>,
         param_names: Vec<String>,
         requires: Vec<Node>,
@@ -11618,12 +11991,14 @@ enum Value {
     /// always present so the `Value` enum's shape matches across
     /// feature gates.
     #[allow(dead_code)]
+    #[cfg_attr(feature = "serde", serde(skip))]
     OpaquePtr(crate::ffi::OpaquePtrHandle),
     /// RES-328: shared mutable cell handle. `cell(v)` creates an id
     /// into the thread-local cell store; `.get()` reads, `.set(v)`
     /// writes. Cloning the value copies only this small handle, so
     /// ordinary tree-walker values stay cheap while closures can still
     /// share state explicitly.
+    #[cfg_attr(feature = "serde", serde(skip))]
     Cell(i64),
     /// RES-401: tuple. Heterogeneous fixed-length sequence. Empty
     /// vector is the unit value. Distinct from `Array` so type-aware
@@ -11646,6 +12021,7 @@ enum Value {
     /// user code can pass PIDs to `send()` / receive from `spawn()`.
     /// The raw `u64` is exposed because `actor_runtime::ActorPid` is
     /// not visible from the parser or eval arms that construct Values.
+    #[cfg_attr(feature = "serde", serde(skip))]
     ActorPid(u64),
     /// RES-2592: TCO trampoline signal. Emitted by the child interpreter
     /// when it encounters a `CallExpression` whose callee matches
@@ -11657,33 +12033,53 @@ enum Value {
     /// tail-recursive call to the active TCO function is detected.
     /// Consumed immediately by the `'tco` loop in `apply_function`; never
     /// escapes the call frame. The `Vec<Value>` holds the new argument list.
+    #[cfg_attr(feature = "serde", serde(skip))]
     TailCall(Vec<Value>),
     /// RES-2659: mutual tail-call trampoline sentinel. Emitted when a
     /// `#[mutual_tail_call]` function calls another `#[mutual_tail_call]`
     /// function in tail position. The `'tco` loop in `apply_function` catches
     /// this, looks up `callee` in the env, and re-executes that function's
     /// body without growing the host stack.
+    #[cfg_attr(feature = "serde", serde(skip))]
     MutualTailCall {
         callee: String,
         args: Vec<Value>,
     },
+    /// Trampoline sentinel for `return g(args)` in tail position, where
+    /// `g` is any plain user fn (see `Interpreter::tail_call_target`).
+    /// The `'tco` loop in `apply_function` switches to `callee`'s body
+    /// in the same frame, so mutual recursion runs in constant stack.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    TailCallTo {
+        callee: Box<FunctionValue>,
+        args: Vec<Value>,
+        call_span: span::Span,
+    },
     /// RES-2603: first-class enum variant constructor for tuple-payload
     /// variants. Produced when `EnumName::VariantName` is referenced as
     /// a value without being immediately called (e.g. passing
     /// `Option::Some` to a higher-order function). `apply_function`
     /// delegates to `enum_ctors::apply_constructor` to build the
     /// corresponding `Value::EnumVariant` when it is later called.
+    #[cfg_attr(feature = "serde", serde(skip))]
     EnumConstructor {
         type_name: String,
         variant: String,
         arity: usize,
     },
+    /// Overload set: every `fn` declared under one name in one scope,
+    /// in declaration order. `apply_function` picks the candidate
+    /// whose arity and parameter types fit the runtime arguments
+    /// (see `overloads::select`).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Overloads(Rc<Vec<FunctionValue>>),
 }
 
 /// RES-400: payload carried by a `Value::EnumVariant`. Mirrors the
 /// shape of `EnumPayload` on the AST side, but holds runtime values
 /// instead of source-level type names.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum EnumValuePayload {
     /// Payload-less variant — `Color::Red`.
     None,
@@ -11699,6 +12095,7 @@ enum EnumValuePayload {
 /// at a key position surfaces a runtime error via `MapKey::from_value`.
 /// Derives `Hash + Eq` so `HashMap` works without any custom hasher.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum MapKey {
     Int(i64),
     Str(String),
@@ -11840,6 +12237,9 @@ impl std::fmt::Debug for Value {
             Value::MutualTailCall { callee, args } => {
                 write!(f, "<mutual-tail-call {}({} args)>", callee, args.len())
             }
+            Value::TailCallTo { callee, args, .. } => {
+                write!(f, "<tail-call {}({} args)>", callee.name, args.len())
+            }
             // RES-2603: first-class enum variant constructor.
             Value::EnumConstructor {
                 type_name,
@@ -11848,6 +12248,7 @@ impl std::fmt::Debug for Value {
             } => {
                 write!(f, "EnumConstructor({}::{}/{})", type_name, variant, arity)
             }
+            Value::Overloads(set) => write!(f, "Overloads({} fns)", set.len()),
         }
     }
 }
@@ -11856,7 +12257,7 @@ impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Value::Int(i) => write!(f, "{}", i),
-            Value::Float(fl) => write!(f, "{}", fl),
+            Value::Float(fl) => f.write_str(&float_mode::display(*fl)),
             Value::String(s) => write!(f, "\"{}\"", s),
             Value::Bool(b) => write!(f, "{}", b),
             // RES-2619: char displays as the bare character (no quotes),
@@ -12054,12 +12455,16 @@ impl std::fmt::Display for Value {
             Value::MutualTailCall { callee, args } => {
                 write!(f, "<mutual-tail-call {}({} args)>", callee, args.len())
             }
+            Value::TailCallTo { callee, args, .. } => {
+                write!(f, "<tail-call {}({} args)>", callee.name, args.len())
+            }
             // RES-2603: first-class enum variant constructor.
             Value::EnumConstructor {
                 type_name, variant, ..
             } => {
                 write!(f, "<constructor {}::{}>", type_name, variant)
             }
+            Value::Overloads(_) => write!(f, "<function>"),
         }
     }
 }
@@ -12094,8 +12499,27 @@ struct Environment {
 
 #[derive(Debug)]
 struct EnvFrame {
-    store: HashMap<String, Value>,
+    store: HashMap<Symbol, Value>,
     outer: Option<Environment>,
+    /// This frame's share of the `--max-memory` estimate; see
+    /// `memory_limit`.
+    bytes: usize,
+}
+
+impl EnvFrame {
+    fn account(&mut self, added: usize, removed: usize) {
+        self.bytes = self.bytes.saturating_add(added).saturating_sub(removed);
+        memory_limit::adjust(added, removed);
+    }
+}
+
+impl Drop for EnvFrame {
+    fn drop(&mut self) {
+        // A frame that never counted anything has nothing to give back.
+        if self.bytes != 0 {
+            memory_limit::adjust(0, self.bytes);
+        }
+    }
 }
 
 impl Environment {
@@ -12104,6 +12528,7 @@ impl Environment {
             inner: Rc::new(RefCell::new(EnvFrame {
                 store: HashMap::new(),
                 outer: None,
+                bytes: 0,
             })),
         }
     }
@@ -12113,16 +12538,18 @@ impl Environment {
             inner: Rc::new(RefCell::new(EnvFrame {
                 store: HashMap::new(),
                 outer: Some(outer),
+                bytes: 0,
             })),
         }
     }
 
-    fn get(&self, name: &str) -> Option<Value> {
+    fn get(&self, name: impl SymbolKey) -> Option<Value> {
+        let name = name.find_symbol()?;
         // Take the borrow, look up locally, then if absent walk into
         // outer. Cloning the outer Environment for the recursive call
         // is cheap (Rc bump).
         let frame = self.inner.borrow();
-        if let Some(v) = frame.store.get(name) {
+        if let Some(v) = frame.store.get(&name) {
             return Some(v.clone());
         }
         let outer = frame.outer.clone();
@@ -12130,15 +12557,51 @@ impl Environment {
         outer.and_then(|o| o.get(name))
     }
 
-    fn set(&self, name: String, value: Value) {
-        self.inner.borrow_mut().store.insert(name, value);
+    /// Whether `name` is bound in this frame or an outer one.
+    fn contains(&self, name: impl SymbolKey) -> bool {
+        self.with_value(name, |_| ()).is_some()
+    }
+
+    /// Run `f` on `name`'s value in place, without the clone `get`
+    /// pays. For reads that only need part of a value — one element
+    /// of a large array or map — the clone was the whole cost. `f`
+    /// must not touch the environment: the frame holding the value
+    /// stays borrowed while it runs.
+    fn with_value<R>(&self, name: impl SymbolKey, f: impl FnOnce(&Value) -> R) -> Option<R> {
+        let name = name.find_symbol()?;
+        let frame = self.inner.borrow();
+        if let Some(v) = frame.store.get(&name) {
+            return Some(f(v));
+        }
+        let outer = frame.outer.clone();
+        drop(frame);
+        outer.and_then(|o| o.with_value(name, f))
+    }
+
+    fn set(&self, name: impl Into<Symbol>, value: Value) {
+        // Without `--max-memory` or a reader of the estimate, a write
+        // is just the insert.
+        if !memory_limit::measuring() {
+            self.inner.borrow_mut().store.insert(name.into(), value);
+            return;
+        }
+        let added = memory_limit::binding_footprint(&value);
+        let mut frame = self.inner.borrow_mut();
+        let removed = frame
+            .store
+            .insert(name.into(), value)
+            .map_or(0, |old| memory_limit::binding_footprint(&old));
+        frame.account(added, removed);
     }
 
     /// Update `name` in the frame where it was first defined. Returns
     /// `true` if the name was found and updated, `false` if it doesn't
     /// exist anywhere in the chain.
-    fn reassign(&self, name: &str, value: Value) -> bool {
-        // RES-1459: `get_mut(&str)` does a single hashed lookup AND
+    fn reassign(&self, name: impl SymbolKey, value: Value) -> bool {
+        let Some(name) = name.find_symbol() else {
+            return false;
+        };
+        // RES-1459: `get_mut` does a single hashed lookup AND
         // skips the `to_string` allocation in the hit case. The
         // previous shape did `contains_key` (1 hash) followed by
         // `insert(name.to_string(), value)` (1 hash + 1 String
@@ -12148,8 +12611,15 @@ impl Environment {
         // a `String` per iteration; now zero allocations on the
         // hot path.
         let mut frame = self.inner.borrow_mut();
-        if let Some(slot) = frame.store.get_mut(name) {
+        if let Some(slot) = frame.store.get_mut(&name) {
+            if !memory_limit::measuring() {
+                *slot = value;
+                return true;
+            }
+            let added = memory_limit::binding_footprint(&value);
+            let removed = memory_limit::binding_footprint(slot);
             *slot = value;
+            frame.account(added, removed);
             return true;
         }
         // Drop the borrow before recursing so the outer's borrow_mut
@@ -12173,10 +12643,12 @@ impl Environment {
     /// each attempt sees the same initial state.
     fn deep_clone(&self) -> Environment {
         let frame = self.inner.borrow();
+        memory_limit::adjust(frame.bytes, 0);
         Environment {
             inner: Rc::new(RefCell::new(EnvFrame {
                 store: frame.store.clone(),
                 outer: frame.outer.as_ref().map(|o| o.deep_clone()),
+                bytes: frame.bytes,
             })),
         }
     }
@@ -12193,12 +12665,34 @@ impl Environment {
             if let Value::Function(fv) = val
                 && (!fv.requires.is_empty() || !fv.ensures.is_empty())
             {
-                out.insert(name.clone(), (fv.requires.clone(), fv.ensures.clone()));
+                out.insert(
+                    name.to_string(),
+                    (fv.requires.to_vec(), fv.ensures.to_vec()),
+                );
             }
         }
         out
     }
 
+    /// The bindings of each frame of the chain, innermost first, for
+    /// `rz debug`'s `vars`.
+    fn frames(&self) -> Vec<Vec<(String, Value)>> {
+        let mut out = Vec::new();
+        let mut env = Some(self.clone());
+        while let Some(current) = env {
+            let frame = current.inner.borrow();
+            out.push(
+                frame
+                    .store
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.clone()))
+                    .collect(),
+            );
+            env = frame.outer.clone();
+        }
+        out
+    }
+
     /// RES-311: snapshot the names defined in this frame's local store.
     /// Used by the REPL's tab-completer to surface in-scope identifiers
     /// — including builtins, since `register_builtins` deposits them in
@@ -12212,7 +12706,7 @@ impl Environment {
         // innermost frame are listed first, then each outer frame's
         // names appended.
         let frame = self.inner.borrow();
-        let mut out: Vec<String> = frame.store.keys().cloned().collect();
+        let mut out: Vec<String> = frame.store.keys().map(|k| k.to_string()).collect();
         if let Some(outer) = &frame.outer {
             out.extend(outer.local_names());
         }
@@ -12220,6 +12714,72 @@ impl Environment {
     }
 }
 
+/// An index expression whose evaluation cannot change any binding:
+/// literals, identifiers, and arithmetic over them.
+fn is_pure_index(node: &Node) -> bool {
+    match node {
+        Node::IntegerLiteral { .. } | Node::Identifier { .. } => true,
+        Node::PrefixExpression { right, .. } => is_pure_index(right),
+        Node::InfixExpression { left, right, .. } => is_pure_index(left) && is_pure_index(right),
+        _ => false,
+    }
+}
+
+/// `items[i]`. RES-921: negative indices wrap from the end — `-1` is
+/// the last element, `-len` is the first. `i < -len` and `i >= len`
+/// are both out of bounds and produce the same diagnostic.
+fn array_element(items: &[Value], i: i64) -> RResult<Value> {
+    let len = items.len() as i64;
+    let resolved = if i < 0 { i + len } else { i };
+    if resolved < 0 || resolved >= len {
+        Err(format!(
+            "Index {} out of bounds for array of length {}",
+            i,
+            items.len()
+        ))
+    } else {
+        Ok(items[resolved as usize].clone())
+    }
+}
+
+/// `target[index]` for already-evaluated operands. `target` is
+/// borrowed so a named binding can be indexed in place (see
+/// [`Environment::with_value`]); only the element read is cloned.
+fn index_value(target: &Value, index_val: Value) -> RResult<Value> {
+    match (target, index_val) {
+        (Value::Array(items), Value::Int(i)) => array_element(items, i),
+        (Value::Array(_), other) => Err(format!("Array index must be int, got {}", other)),
+        // RES-427: string / int / bool subscript access on
+        // Map literals (`m["key"]`, `m[42]`).
+        (Value::Map(m), key_val) => {
+            let mk = MapKey::from_value(&key_val).map_err(|e| format!("Invalid map key: {e}"))?;
+            m.get(&mk)
+                .cloned()
+                .ok_or_else(|| format!("Key not found in map: {}", key_val))
+        }
+        // RES-427 / RES-2709: string subscript `s[i]` yields
+        // the i-th Unicode scalar as a `Value::Char` so that
+        // char literal patterns in `match` and `==` comparisons
+        // can match the result. String concat via `+` accepts
+        // Char (see `can_stringify_for_concat`).
+        (Value::String(s), Value::Int(i)) => {
+            let chars: Vec<char> = s.chars().collect();
+            let len = chars.len() as i64;
+            let resolved = if i < 0 { i + len } else { i };
+            if resolved < 0 || resolved >= len {
+                Err(format!(
+                    "String index {} out of bounds for length {}",
+                    i,
+                    chars.len()
+                ))
+            } else {
+                Ok(Value::Char(chars[resolved as usize]))
+            }
+        }
+        (other, _) => Err(format!("Cannot index {:?}", other)),
+    }
+}
+
 /// RES-400: split a qualified name like `"Color::Red"` into
 /// `("Color", "Red")`. Returns `None` if the input isn't qualified.
 /// Multiple `::` separators (e.g. `mod::Color::Red`) are not split — the
@@ -12237,7 +12797,7 @@ fn flatten_field_target(target: &Node, last_field: &str) -> (Option<String>, Vec
     loop {
         match node {
             Node::Identifier { name, .. } => {
-                return (Some(name.clone()), {
+                return (Some(name.to_string()), {
                     path.reverse();
                     path
                 });
@@ -12268,9 +12828,9 @@ fn set_nested_field(root: Value, path: &[String], new_val: Value) -> RResult<Val
                 .iter()
                 .position(|(n, _)| n == head)
                 .ok_or_else(|| format!("Struct {} has no field '{}'", name, head))?;
-            let old = std::mem::replace(&mut fields[idx].1, Value::Void);
-            let updated = set_nested_field(old, tail, new_val)?;
-            fields[idx].1 = updated;
+            let slot = &mut Rc::make_mut(&mut fields)[idx].1;
+            let old = std::mem::replace(slot, Value::Void);
+            *slot = set_nested_field(old, tail, new_val)?;
             Ok(Value::Struct { name, fields })
         }
         other => Err(format!(
@@ -12323,7 +12883,7 @@ fn render_ensures_violation_error(name: &str, clause: &Node, return_value: &Valu
 /// fired, not reconstruct the full AST.
 fn format_contract_expr(node: &Node) -> String {
     match node {
-        Node::Identifier { name, .. } => name.clone(),
+        Node::Identifier { name, .. } => name.to_string(),
         Node::IntegerLiteral { value, .. } => value.to_string(),
         Node::FloatLiteral { value, .. } => value.to_string(),
         Node::StringLiteral { value, .. } => format!("{:?}", value),
@@ -12383,7 +12943,7 @@ fn stringify_for_concat_owned(v: Value) -> String {
     match v {
         Value::String(s) => s,
         Value::Int(i) => i.to_string(),
-        Value::Float(f) => f.to_string(),
+        Value::Float(f) => float_mode::display(f),
         Value::Bool(b) => b.to_string(),
         Value::Char(c) => c.to_string(),
         _ => unreachable!(),
@@ -12404,17 +12964,13 @@ fn register_builtins(env: &Environment) {
     // `Rc<RefCell<EnvFrame>>` handles interior mutation. The `&mut`
     // shape was a historical artifact and prevented this from being
     // called from inside the `thread_local!` initializer.
-    for (name, func) in BUILTINS {
-        // RES-4000: `__`-prefixed entries (e.g. `__range`) are
-        // VM-internal `Op::CallBuiltin` dispatch targets, resolved
-        // directly through `lookup_builtin` — never through a bound
-        // identifier. Skip binding them here so they stay uncallable
-        // from Resilient source in the tree-walker too.
-        if name.starts_with("__") {
-            continue;
-        }
-        env.set((*name).to_string(), Value::Builtin { name, func: *func });
-    }
+    //
+    // RES-4000: `__`-prefixed entries (e.g. `__range`) are VM-internal
+    // `Op::CallBuiltin` dispatch targets, resolved directly through
+    // `lookup_builtin` — never through a bound identifier. The shared
+    // registry skips them so they stay uncallable from Resilient
+    // source in the tree-walker too.
+    builtin_registry::register_runtime(env);
 }
 
 /// RES-188a: iterate the canonical builtin names. Used by the
@@ -12430,10 +12986,7 @@ fn register_builtins(env: &Environment) {
 /// keeps the default / `jit` / `z3` builds warning-clean.
 #[allow(dead_code)]
 pub(crate) fn builtin_names() -> impl Iterator<Item = &'static str> {
-    BUILTINS
-        .iter()
-        .map(|(name, _func)| *name)
-        .filter(|name| !name.starts_with("__"))
+    builtin_registry::native_builtins().map(|(name, _func)| name)
 }
 
 /// RES-VM (issue #266): look up a builtin by name. Returns `Some(func)`
@@ -12457,12 +13010,23 @@ pub(crate) fn lookup_builtin(name: &str) -> Option<BuiltinFn> {
     //
     // Same shape as RES-1349 (cached `BUILTIN_ENV`) and RES-1398
     // (cached `BUILTIN_ENUM_DECLS`): `LazyLock` is `Sync`-safe and
-    // each `BUILTINS` entry is `(&'static str, BuiltinFn)` —
+    // each native registry entry is a `BuiltinFn` —
     // `Copy`-only payload, so the HashMap holds plain values, no
     // ref-bumping needed on lookup.
     static BUILTIN_MAP: std::sync::LazyLock<HashMap<&'static str, BuiltinFn>> =
-        std::sync::LazyLock::new(|| BUILTINS.iter().copied().collect());
-    BUILTIN_MAP.get(name).copied()
+        std::sync::LazyLock::new(|| {
+            builtin_registry::BUILTINS
+                .iter()
+                .filter_map(|(name, _, eval)| match eval {
+                    builtin_registry::BuiltinEval::Native(func) => Some((*name, *func)),
+                    builtin_registry::BuiltinEval::Intrinsic => None,
+                })
+                .collect()
+        });
+    BUILTIN_MAP
+        .get(name)
+        .copied()
+        .or_else(|| plugin::lookup(name))
 }
 
 /// RES-487: enumerate every builtin name. Feeds `did_you_mean::suggest`
@@ -12477,10 +13041,7 @@ pub(crate) fn lookup_builtin(name: &str) -> Option<BuiltinFn> {
 /// `__range`?", which leaks a VM-internal dispatch target no user
 /// program should ever reference.
 pub(crate) fn all_builtin_names() -> impl Iterator<Item = &'static str> {
-    BUILTINS
-        .iter()
-        .map(|(n, _)| *n)
-        .filter(|name| !name.starts_with("__"))
+    builtin_registry::native_builtins().map(|(n, _)| n)
 }
 
 /// RES-487: format the "Identifier not found" runtime diagnostic with
@@ -12516,6 +13077,143 @@ fn parse_checked_failure_signal(err: &str) -> Option<&str> {
     Some(variant)
 }
 
+thread_local! {
+    /// The status of the `exit(code)` unwind in flight on this thread.
+    /// Only `exit` and `os::exit` set it, so no error text a program
+    /// builds (an `assert` message, a missing map key) can pose as an
+    /// exit request.
+    static PENDING_EXIT: Cell<Option<i32>> = const { Cell::new(None) };
+}
+
+/// Start an `exit(code)` unwind. It travels as an error, so every
+/// enclosing call runs its `defer`s on the way out, while the status
+/// itself waits in `PENDING_EXIT` for the driver (see `exit_request`).
+pub(crate) fn exit_signal(code: i32) -> String {
+    PENDING_EXIT.with(|p| p.set(Some(code)));
+    format!("exit({code})")
+}
+
+/// The status of the `exit(code)` unwind in flight, if the error the
+/// caller is holding is one.
+pub(crate) fn exit_request() -> Option<i32> {
+    PENDING_EXIT.with(Cell::get)
+}
+
+/// Forget any finished `exit(code)` unwind, as at the beginning of a
+/// run, so it cannot turn a later run's failure into an exit.
+pub(crate) fn clear_exit_request() {
+    PENDING_EXIT.with(|p| p.set(None));
+}
+
+/// `exit` codes are process statuses: `0..=255` and nothing else, so
+/// `exit(256)` cannot wrap around to a success.
+fn checked_exit_code(builtin: &str, code: i64) -> RResult<i32> {
+    match u8::try_from(code) {
+        Ok(code) => Ok(i32::from(code)),
+        Err(_) => Err(format!(
+            "{}: InvalidArgument: exit code {} is outside 0..=255",
+            builtin, code
+        )),
+    }
+}
+
+/// `execute_file` failures that reject the program before it runs.
+const STATIC_FAILURE_PREFIXES: &[&str] = &[
+    "Failed to parse program",
+    "Safety-critical lint failed",
+    "Import error",
+    "Named argument resolution failed",
+    "Borrow check failed",
+    "Capability check failed",
+    "Type check failed",
+];
+
+/// The process status for a failed run: 2 when the program was
+/// rejected before it started (parse and type errors), 3 when a
+/// proof obligation was refuted, and 1 for runtime errors.
+pub(crate) fn failure_exit_code(err: &str) -> i32 {
+    if err.starts_with("Termination check failed") || err.starts_with("Verification failed") {
+        3
+    } else if STATIC_FAILURE_PREFIXES.iter().any(|p| err.starts_with(p)) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Builtins that fail with a named variant, spelled
+/// `<builtin>: <Variant>: <detail>` in the error. `try ... catch
+/// <Variant>` handles these on both runtimes like a `fails` call.
+const BUILTIN_FAILURES: &[(&str, &[&str])] = &[
+    ("read_file", &["NotFound", "PermissionDenied"]),
+    ("write_file", &["NotFound", "PermissionDenied"]),
+    ("append_file", &["NotFound", "PermissionDenied"]),
+    ("file_exists", &["PermissionDenied"]),
+    ("file_is_dir", &["PermissionDenied"]),
+    ("file_is_file", &["PermissionDenied"]),
+    ("file_read", &["PermissionDenied"]),
+    ("file_write", &["PermissionDenied"]),
+    ("fs_read", &["PermissionDenied"]),
+    ("fs_write", &["PermissionDenied"]),
+    ("fs_append", &["PermissionDenied"]),
+    ("fs_exists", &["PermissionDenied"]),
+    ("fs_remove", &["PermissionDenied"]),
+    ("fs_list", &["PermissionDenied"]),
+    ("include_str", &["PermissionDenied"]),
+    ("include_bytes", &["PermissionDenied"]),
+    ("http_status", &["PermissionDenied"]),
+    ("http_headers", &["PermissionDenied"]),
+    ("net_resolve", &["PermissionDenied"]),
+    ("process_exec", &["PermissionDenied"]),
+    ("process_which", &["PermissionDenied"]),
+    ("read_line", &["EndOfInput"]),
+    ("read_int", &["EndOfInput", "InvalidInput"]),
+    ("json_parse", &["InvalidJson"]),
+    ("sqrt", &["DomainError"]),
+    ("exit", &["InvalidArgument"]),
+    ("os::exit", &["InvalidArgument"]),
+    ("array_sort", &["InvalidArgument"]),
+    ("array_sort_desc", &["InvalidArgument"]),
+    ("sort", &["InvalidArgument"]),
+    ("sort_desc", &["InvalidArgument"]),
+    ("http_get", crate::http_client::FAILURES),
+    ("http_post", crate::http_client::FAILURES),
+    ("mqtt_publish", crate::telemetry_io::MQTT_FAILURES),
+    ("mqtt_subscribe", crate::telemetry_io::MQTT_FAILURES),
+    ("serial_open", crate::telemetry_io::SERIAL_FAILURES),
+    ("serial_read", crate::telemetry_io::SERIAL_FAILURES),
+    ("serial_write", crate::telemetry_io::SERIAL_FAILURES),
+    ("mmio_read", crate::mmio::FAILURES),
+    ("mmio_write", crate::mmio::FAILURES),
+    ("volatile_read_u8", crate::volatile::FAILURES),
+    ("volatile_read_u16", crate::volatile::FAILURES),
+    ("volatile_read_u32", crate::volatile::FAILURES),
+    ("volatile_read_u64", crate::volatile::FAILURES),
+    ("volatile_write_u8", crate::volatile::FAILURES),
+    ("volatile_write_u16", crate::volatile::FAILURES),
+    ("volatile_write_u32", crate::volatile::FAILURES),
+    ("volatile_write_u64", crate::volatile::FAILURES),
+];
+
+/// The failure variants builtin `name` can fail with.
+pub(crate) fn builtin_failures(name: &str) -> &'static [&'static str] {
+    BUILTIN_FAILURES
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map_or(&[], |(_, variants)| variants)
+}
+
+/// The failure variant a builtin's error carries, if any. Looks past
+/// a location prefix the interpreter may have added.
+pub(crate) fn builtin_failure_variant(err: &str) -> Option<&'static str> {
+    BUILTIN_FAILURES.iter().find_map(|(builtin, variants)| {
+        variants
+            .iter()
+            .copied()
+            .find(|v| err.contains(&format!("{}: {}: ", builtin, v)))
+    })
+}
+
 /// RES-2612 Task 6: intern(string) -> string.
 /// Interns a dynamically-created or runtime string for deduplication.
 fn builtin_intern(args: &[Value]) -> RResult<Value> {
@@ -12615,1514 +13313,11 @@ fn apply_builtin_by_name(name: &str, args: &[Value]) -> Option<RResult<Value>> {
             name
         )));
     }
-    BUILTINS
-        .iter()
-        .find(|(n, _)| *n == name)
-        .map(|(_, f)| f(args))
-}
-
-/// Canonical list of every native function visible in a fresh
-/// Resilient program.
-const BUILTINS: &[(&str, BuiltinFn)] = &[
-    ("println", builtin_println),
-    ("print", builtin_print),
-    // RES-144: single-line stdin read. std-only.
-    ("input", builtin_input),
-    // RES-1100: expose the compiler version to programs so build
-    // manifests and provenance certificates can pin the toolchain
-    // they were produced by.
-    ("version", builtin_version),
-    // RES-2610: compile-time file embedding.
-    ("include_str", builtin_include_str),
-    ("include_bytes", builtin_include_bytes),
-    ("abs", builtin_abs),
-    // RES-410: sign(x) — -1, 0, +1 for negative/zero/positive.
-    ("sign", builtin_sign),
-    // RES-411: float predicates per IEEE 754.
-    ("is_nan", builtin_is_nan),
-    ("is_inf", builtin_is_inf),
-    ("is_finite", builtin_is_finite),
-    ("min", builtin_min),
-    ("max", builtin_max),
-    // RES-415: integer gcd/lcm.
-    ("gcd", builtin_gcd),
-    ("lcm", builtin_lcm),
-    // RES-536: gcd / lcm reduction over an integer array.
-    ("gcd_array", builtin_gcd_array),
-    ("lcm_array", builtin_lcm_array),
-    // RES-567: factorial with overflow detection.
-    ("factorial", builtin_factorial),
-    // RES-568: binomial coefficient C(n, k).
-    ("binomial", builtin_binomial),
-    // RES-569: n-th Fibonacci number with overflow detection.
-    ("fibonacci", builtin_fibonacci),
-    // RES-570: trial-division primality test.
-    ("is_prime", builtin_is_prime),
-    // RES-571: smallest prime greater than n.
-    ("next_prime", builtin_next_prime),
-    // RES-295: clamp(x, lo, hi) — restrict to [lo, hi]; Err if lo > hi.
-    ("clamp", builtin_clamp),
-    // RES-130: explicit int ↔ float conversions.
-    ("to_float", builtin_to_float),
-    ("to_int", builtin_to_int),
-    // RES-2618: f32/f64 precision casts.
-    ("as_f32", crate::float32::builtin_as_f32),
-    ("as_f64", crate::float32::builtin_as_f64),
-    // RES-366: pinned-width integer casts. Wrapping truncation.
-    ("as_int8", builtin_as_int8),
-    ("as_int16", builtin_as_int16),
-    ("as_int32", builtin_as_int32),
-    ("as_int64", builtin_as_int64),
-    ("as_uint8", builtin_as_uint8),
-    ("as_uint16", builtin_as_uint16),
-    ("as_uint32", builtin_as_uint32),
-    ("as_uint64", builtin_as_uint64),
-    // RES-138: read the current retry count inside a live block.
-    ("live_retries", builtin_live_retries),
-    // RES-141: process-wide live-block telemetry.
-    ("live_total_retries", builtin_live_total_retries),
-    ("live_total_exhaustions", builtin_live_total_exhaustions),
-    ("sqrt", builtin_sqrt),
-    ("pow", builtin_pow),
-    ("floor", builtin_floor),
-    ("ceil", builtin_ceil),
-    // RES-146: transcendentals. std-only; float-in/float-out per
-    // RES-130's no-implicit-coercion policy.
-    ("sin", builtin_sin),
-    ("cos", builtin_cos),
-    ("tan", builtin_tan),
-    // RES-894.
-    ("to_radians", builtin_to_radians),
-    // RES-895.
-    ("to_degrees", builtin_to_degrees),
-    // RES-295: atan2(y, x) — angle of vector (x, y) in radians.
-    ("atan2", builtin_atan2),
-    // RES-892.
-    ("hypot", builtin_hypot),
-    // RES-893.
-    ("copysign", builtin_copysign),
-    ("ln", builtin_ln),
-    // RES-889.
-    ("log10", builtin_log10),
-    // RES-890.
-    ("log2", builtin_log2),
-    ("log", builtin_log),
-    ("exp", builtin_exp),
-    // RES-891.
-    ("exp2", builtin_exp2),
-    // RES-896.
-    ("sinh", builtin_sinh),
-    // RES-897.
-    ("cosh", builtin_cosh),
-    // RES-898.
-    ("tanh", builtin_tanh),
-    // RES-899.
-    ("asinh", builtin_asinh),
-    // RES-900.
-    ("acosh", builtin_acosh),
-    // RES-901.
-    ("atanh", builtin_atanh),
-    // RES-902.
-    ("asin", builtin_asin),
-    // RES-903.
-    ("acos", builtin_acos),
-    // RES-904.
-    ("atan", builtin_atan),
-    // RES-905.
-    ("cbrt", builtin_cbrt),
-    // RES-907: bit-counting integer builtins. Useful for register-bit
-    // decoding, bit-mask iteration, and log₂-of-power-of-two probes.
-    ("count_ones", builtin_count_ones),
-    ("count_zeros", builtin_count_zeros),
-    ("leading_zeros", builtin_leading_zeros),
-    ("trailing_zeros", builtin_trailing_zeros),
-    // RES-147: monotonic ms clock, std-only.
-    ("clock_ms", builtin_clock_ms),
-    // RES-358: monotonic ns clock builtins. @io (non-pure).
-    // Embedded targets stub: a DWT-based implementation is future work.
-    ("clock_now", builtin_clock_now),
-    ("clock_elapsed", builtin_clock_elapsed),
-    // Grand-Implementation Pass 2 — Subsystem A: tick clock + event journal.
-    // Deterministic structural replay as a core-stdlib primitive.
-    ("tick_now", crate::event_journal::builtin_tick_now),
-    ("tick_advance", crate::event_journal::builtin_tick_advance),
-    ("record_event", crate::event_journal::builtin_record_event),
-    ("replay_events", crate::event_journal::builtin_replay_events),
-    ("clear_events", crate::event_journal::builtin_clear_events),
-    ("event_count", crate::event_journal::builtin_event_count),
-    // Grand-Implementation Pass 2 — Subsystem B: runtime provenance.
-    // `tag(value, "source") -> Tagged` wraps an Int with a provenance string.
-    // `untag(t, "expected") -> Result<Int>` enforces source on extract.
-    // `tag_of(t) -> Result<String>` reads the source non-destructively.
-    ("tag", crate::provenance::builtin_tag),
-    ("untag", crate::provenance::builtin_untag),
-    ("tag_of", crate::provenance::builtin_tag_of),
-    // Grand-Implementation Pass 2 — Subsystem C: named snapshots.
-    // Programmer-addressable in-process checkpoints, bounded to MAX_SNAPSHOTS.
-    (
-        "snapshot_save",
-        crate::snapshot_store::builtin_snapshot_save,
-    ),
-    (
-        "snapshot_load",
-        crate::snapshot_store::builtin_snapshot_load,
-    ),
-    (
-        "snapshot_keys",
-        crate::snapshot_store::builtin_snapshot_keys,
-    ),
-    (
-        "snapshot_clear",
-        crate::snapshot_store::builtin_snapshot_clear,
-    ),
-    // Grand-Implementation Pass 3 — Subsystem D: named resource quotas.
-    ("quota_set", crate::quotas::builtin_quota_set),
-    ("quota_charge", crate::quotas::builtin_quota_charge),
-    ("quota_remaining", crate::quotas::builtin_quota_remaining),
-    ("quota_reset", crate::quotas::builtin_quota_reset),
-    ("quota_used", crate::quotas::builtin_quota_used),
-    ("quotas", crate::quotas::builtin_quotas),
-    // Grand-Implementation Pass 3 — Subsystem E: capability tokens.
-    ("mint_cap", crate::capabilities::builtin_mint_cap),
-    ("check_cap", crate::capabilities::builtin_check_cap),
-    ("revoke_cap", crate::capabilities::builtin_revoke_cap),
-    ("caps", crate::capabilities::builtin_caps),
-    // RES-150: seedable SplitMix64 random builtins. std-only.
-    ("random_int", builtin_random_int),
-    ("random_float", builtin_random_float),
-    ("len", builtin_len),
-    ("push", builtin_push),
-    ("pop", builtin_pop),
-    ("slice", builtin_slice),
-    ("split", builtin_split),
-    // RES-1859: explicit-name alias.
-    ("string_split", builtin_split),
-    // RES-535: split with a maximum number-of-splits limit.
-    ("string_split_n", builtin_string_split_n),
-    // RES-545: split on the last occurrence of the separator.
-    ("string_split_last", builtin_string_split_last),
-    ("trim", builtin_trim),
-    ("contains", builtin_contains),
-    // RES-2612 Task 6: intern(string) -> string for runtime deduplication.
-    ("intern", builtin_intern),
-    ("to_upper", builtin_to_upper),
-    ("to_lower", builtin_to_lower),
-    // RES-412: reverse a string (Unicode-aware) or an array (clones elements).
-    ("string_reverse", builtin_string_reverse),
-    ("array_reverse", builtin_array_reverse),
-    // RES-416: integer-array reductions (identity 0 / 1 for empty arrays).
-    ("array_sum", builtin_array_sum),
-    ("array_product", builtin_array_product),
-    // RES-417: min/max over an integer array. Empty array errors.
-    ("array_min", builtin_array_min),
-    ("array_max", builtin_array_max),
-    // RES-543: empty-safe min/max with caller-supplied default.
-    ("array_max_or", builtin_array_max_or),
-    ("array_min_or", builtin_array_min_or),
-    // RES-549: integer mean (truncating toward zero).
-    ("array_mean_int", builtin_array_mean_int),
-    // RES-550: integer median (sorts a clone; truncating mean for even len).
-    ("array_median_int", builtin_array_median_int),
-    // RES-551: integer mode (most-common; smallest on ties).
-    ("array_mode_int", builtin_array_mode_int),
-    // RES-552: peak-to-peak range (max − min) of an int array.
-    ("array_range_int", builtin_array_range_int),
-    // RES-553: consecutive pairwise differences arr[i+1] - arr[i].
-    ("array_diff_consec_int", builtin_array_diff_consec_int),
-    // RES-554: per-element clamp to [lo, hi].
-    ("array_clamp_int", builtin_array_clamp_int),
-    // RES-555: per-element sign (-1 / 0 / 1).
-    ("array_signum_int", builtin_array_signum_int),
-    // RES-556: per-element absolute value.
-    ("array_abs_int", builtin_array_abs_int),
-    // RES-557: dot product of two equal-length int arrays.
-    ("array_dot_int", builtin_array_dot_int),
-    // RES-558: sum of squares (Σ x²) of an int array.
-    ("array_sum_squares_int", builtin_array_sum_squares_int),
-    // RES-559: running prefix sum.
-    ("array_cumsum_int", builtin_array_cumsum_int),
-    // RES-560: running max.
-    ("array_cummax_int", builtin_array_cummax_int),
-    // RES-561: running min.
-    ("array_cummin_int", builtin_array_cummin_int),
-    // RES-562: running product.
-    ("array_cumprod_int", builtin_array_cumprod_int),
-    // RES-563: count elements in inclusive [lo, hi].
-    ("array_count_in_range_int", builtin_array_count_in_range_int),
-    // RES-503: index of max / min element (first-occurrence on ties).
-    ("array_argmax_int", builtin_array_argmax_int),
-    ("array_argmin_int", builtin_array_argmin_int),
-    // RES-418: element search over an array.
-    ("array_contains", builtin_array_contains),
-    ("array_index_of", builtin_array_index_of),
-    // RES-544: every index where element equals x.
-    ("array_index_of_all", builtin_array_index_of_all),
-    // RES-541: set-like operations on arrays.
-    ("array_intersect", builtin_array_intersect),
-    ("array_diff", builtin_array_diff),
-    // RES-542: order-preserving global-dedup union.
-    ("array_union", builtin_array_union),
-    // RES-419: Unicode-scalar ↔ single-char string conversions.
-    ("chr", builtin_chr),
-    ("ord", builtin_ord),
-    // RES-505: parse single char to base-36 digit value.
-    ("char_to_digit", builtin_char_to_digit),
-    // RES-513: int 0..=35 → base-36 digit char.
-    ("digit_to_char", builtin_digit_to_char),
-    // RES-420: concatenate two arrays.
-    ("array_concat", builtin_array_concat),
-    // RES-515: three-way concatenation.
-    ("array_concat3", builtin_array_concat3),
-    // RES-421: take/drop first n elements.
-    ("array_take", builtin_array_take),
-    ("array_drop", builtin_array_drop),
-    // RES-537: take/drop trailing n elements.
-    ("array_take_last", builtin_array_take_last),
-    ("array_drop_last", builtin_array_drop_last),
-    // RES-514: pick every nth element.
-    ("array_step", builtin_array_step),
-    // RES-422: ascending sort over an integer array.
-    ("array_sort", builtin_array_sort),
-    // RES-443: descending sort.
-    ("array_sort_desc", builtin_array_sort_desc),
-    // RES-444: Fisher-Yates random permutation.
-    ("array_shuffle", builtin_array_shuffle),
-    // RES-445: array prefix / suffix predicates.
-    ("array_starts_with", builtin_array_starts_with),
-    ("array_ends_with", builtin_array_ends_with),
-    // RES-446: all byte indices of substring occurrences.
-    ("string_find_all", builtin_string_find_all),
-    // RES-546: first byte index of substring, -1 if missing.
-    ("string_find", builtin_string_find),
-    // RES-547: last byte index of substring, -1 if missing.
-    ("string_rfind", builtin_string_rfind),
-    // RES-548: split string at a byte index into [before, after].
-    ("string_split_at", builtin_string_split_at),
-    // RES-447: i64 boundary constants as zero-arg functions.
-    ("int_min", builtin_int_min),
-    ("int_max", builtin_int_max),
-    // RES-448: array_index_of starting at a given offset.
-    ("array_position", builtin_array_position),
-    // RES-449: pad an array to a given length with fill value.
-    ("array_pad_left", builtin_array_pad_left),
-    ("array_pad_right", builtin_array_pad_right),
-    // RES-450: exchange two elements; returns a new array.
-    ("array_swap", builtin_array_swap),
-    // RES-451: insert / remove element at index; returns a new array.
-    ("array_insert_at", builtin_array_insert_at),
-    ("array_remove_at", builtin_array_remove_at),
-    // RES-452: replace element at index; returns a new array.
-    ("array_set_at", builtin_array_set_at),
-    // RES-453: total Unicode-scalar accessor (errors on bad index).
-    ("string_at", builtin_string_at),
-    // RES-454: Unicode-scalar substring [start, end).
-    ("string_substring", builtin_string_substring),
-    // RES-455: sliding fixed-size windows.
-    ("array_window", builtin_array_window),
-    // RES-456: rotate elements by n positions (wrapping).
-    ("array_rotate_left", builtin_array_rotate_left),
-    ("array_rotate_right", builtin_array_rotate_right),
-    // RES-457: ASCII first-char upper, rest lower.
-    ("string_capitalize", builtin_string_capitalize),
-    // RES-458: repeat an array n times.
-    ("array_cycle", builtin_array_cycle),
-    // RES-459: ASCII-class string predicates.
-    ("is_ascii_alpha", builtin_is_ascii_alpha),
-    ("is_ascii_digit", builtin_is_ascii_digit),
-    ("is_ascii_alnum", builtin_is_ascii_alnum),
-    // RES-460: trim arbitrary char set from both sides.
-    ("trim_chars", builtin_trim_chars),
-    // RES-461: indent every line with n ASCII spaces.
-    ("string_indent", builtin_string_indent),
-    // RES-462: adjacent pairs as tuples.
-    ("array_pairs", builtin_array_pairs),
-    // RES-463: UTF-8 byte length of a string.
-    ("string_bytes_len", builtin_string_bytes_len),
-    // RES-564: byte at index (-1 if out of range).
-    ("string_byte_at", builtin_string_byte_at),
-    // RES-565: string → array of UTF-8 bytes.
-    ("string_to_bytes", builtin_string_to_bytes),
-    // RES-566: array of bytes → Result<String, String> (UTF-8 validated).
-    ("string_from_bytes", builtin_string_from_bytes),
-    // RES-464: parse int with explicit radix.
-    ("parse_int_base", builtin_parse_int_base),
-    // RES-465: render int with explicit radix.
-    ("int_to_base", builtin_int_to_base),
-    // RES-466: remove the first element matching x.
-    ("array_remove", builtin_array_remove),
-    // RES-467: remove every element matching x.
-    ("array_remove_all", builtin_array_remove_all),
-    // RES-468: collapse adjacent duplicates.
-    ("array_dedup", builtin_array_dedup),
-    // RES-504: partition into maximal runs of equal elements.
-    ("array_group_by_int", builtin_array_group_by_int),
-    // RES-533: count of maximal runs of equal adjacent elements.
-    ("array_count_runs", builtin_array_count_runs),
-    // RES-469: scalar all/any equality predicates.
-    ("array_all_eq", builtin_array_all_eq),
-    ("array_any_eq", builtin_array_any_eq),
-    // RES-471: conditional prefix/suffix strippers.
-    ("string_strip_prefix", builtin_string_strip_prefix),
-    ("string_strip_suffix", builtin_string_strip_suffix),
-    // RES-472: element-wise array equality.
-    ("array_eq", builtin_array_eq),
-    // RES-473: ternary numeric min/max.
-    ("min3", builtin_min3),
-    ("max3", builtin_max3),
-    // RES-474: element-wise array inequality.
-    ("array_ne", builtin_array_ne),
-    // RES-475: fixed-op integer fold with explicit init.
-    ("array_fold_int", builtin_array_fold_int),
-    // RES-502: running-fold (intermediate accumulators).
-    ("array_scan_int", builtin_array_scan_int),
-    // RES-521: element-wise binary op on two int arrays.
-    ("array_zip_with_int", builtin_array_zip_with_int),
-    // RES-477: one-sided char-set trimmers.
-    ("trim_start_chars", builtin_trim_start_chars),
-    ("trim_end_chars", builtin_trim_end_chars),
-    // RES-478: explicit-name alias for array_count.
-    ("array_count_eq", builtin_array_count_eq),
-    // RES-479: is_empty / is_blank string predicates.
-    ("is_empty", builtin_is_empty),
-    ("is_blank", builtin_is_blank),
-    // RES-480: replace only the first occurrence.
-    ("string_replace_first", builtin_string_replace_first),
-    // RES-481: drop first / drop last; empty stays empty.
-    ("array_rest", builtin_array_rest),
-    ("array_init", builtin_array_init),
-    // RES-482: replace up to n occurrences.
-    ("string_replace_n", builtin_string_replace_n),
-    // RES-483: named-predicate take/drop on int arrays.
-    ("array_take_while_int", builtin_array_take_while_int),
-    ("array_drop_while_int", builtin_array_drop_while_int),
-    // RES-484: named-predicate filter / partition on int arrays.
-    ("array_filter_int", builtin_array_filter_int),
-    ("array_partition_int", builtin_array_partition_int),
-    // RES-500: named-predicate any/some on int arrays.
-    ("array_any_int", builtin_array_any_int),
-    // RES-501: named-predicate all/every on int arrays.
-    ("array_all_int", builtin_array_all_int),
-    // RES-530: named-predicate count on int arrays.
-    ("array_count_int", builtin_array_count_int),
-    // RES-539: indices of int elements matching named predicate.
-    ("array_indices_where", builtin_array_indices_where),
-    // RES-485: |a - b|.
-    ("abs_diff", builtin_abs_diff),
-    // RES-406: volatile MMIO intrinsics. Calls outside an `unsafe`
-    // block are rejected by the typechecker.
-    ("volatile_read_u8", crate::volatile::volatile_read_u8),
-    ("volatile_read_u16", crate::volatile::volatile_read_u16),
-    ("volatile_read_u32", crate::volatile::volatile_read_u32),
-    ("volatile_read_u64", crate::volatile::volatile_read_u64),
-    ("volatile_write_u8", crate::volatile::volatile_write_u8),
-    ("volatile_write_u16", crate::volatile::volatile_write_u16),
-    ("volatile_write_u32", crate::volatile::volatile_write_u32),
-    ("volatile_write_u64", crate::volatile::volatile_write_u64),
-    // RES-486: (quotient, remainder) tuple.
-    ("divmod", builtin_divmod),
-    // RES-423: flatten one level of nesting.
-    ("array_flatten", builtin_array_flatten),
-    // RES-424: join a string array with a separator.
-    ("array_join", builtin_array_join),
-    // RES-2734: short-name aliases so `arr.sort()`, `arr.join(sep)`, etc.
-    // work as dot-call methods via the Array method dispatch allowlist.
-    // These also serve as convenient global function aliases.
-    ("sort", builtin_array_sort),
-    ("sort_desc", builtin_array_sort_desc),
-    ("reverse", builtin_array_reverse),
-    ("join", builtin_array_join),
-    ("flatten", builtin_array_flatten),
-    ("dedup", builtin_array_dedup),
-    // "has" avoids collision with string `contains` while providing
-    // element membership testing as a dot-call: `arr.has(x)`.
-    ("has", builtin_array_contains),
-    // RES-425: explicit scalar-to-string conversion.
-    ("to_string", builtin_to_string),
-    // RES-426: first-occurrence dedupe over scalar elements.
-    ("array_unique", builtin_array_unique),
-    // RES-427: count occurrences of a scalar element.
-    ("array_count", builtin_array_count),
-    // RES-428: first/last element accessors (empty array errors).
-    ("array_first", builtin_array_first),
-    ("array_last", builtin_array_last),
-    // RES-528: bounded indexing with fallback default.
-    ("array_get_or", builtin_array_get_or),
-    // RES-429: pad a string to a Unicode-scalar width.
-    ("string_pad_left", builtin_string_pad_left),
-    ("string_pad_right", builtin_string_pad_right),
-    // RES-540: center-pad a string to a Unicode-scalar width.
-    ("string_pad_center", builtin_string_pad_center),
-    // RES-430: pair elements as tuples; truncate to shorter array.
-    ("array_zip", builtin_array_zip),
-    // RES-531: split an array of 2-tuples into two parallel arrays.
-    ("array_unzip", builtin_array_unzip),
-    // RES-431: generate [start, start+1, ..., end-1].
-    ("array_range", builtin_array_range),
-    // RES-4000 (VM-internal): construct a Value::Range at runtime.
-    // Not part of the public builtin surface — see doc comment on
-    // `builtin_make_range`.
-    ("__range", builtin_make_range),
-    // RES-921: slice(arr, lo, hi, inclusive) — sub-array / sub-string.
-    ("array_slice", builtin_array_slice),
-    // RES-522: generate [0, 1, ..., len(arr)-1] for the given array.
-    ("array_indices", builtin_array_indices),
-    // RES-432: array of n copies of elem.
-    ("array_repeat", builtin_array_repeat),
-    // RES-433: split string into single-character strings.
-    ("string_chars", builtin_string_chars),
-    // RES-434: split string into lines (LF, CRLF, no trailing empty).
-    ("string_lines", builtin_string_lines),
-    // RES-496: split string on Unicode whitespace.
-    ("string_words", builtin_string_words),
-    // RES-497: join string array with newline separator.
-    ("string_join_lines", builtin_string_join_lines),
-    // RES-498: join string array with single-space separator.
-    ("string_unwords", builtin_string_unwords),
-    // RES-499: take first n Unicode scalars.
-    ("string_take", builtin_string_take),
-    // RES-506: drop first n Unicode scalars.
-    ("string_drop", builtin_string_drop),
-    // RES-435: split array into fixed-size chunks (last may be short).
-    ("array_chunk", builtin_array_chunk),
-    // RES-436: non-overlapping substring count.
-    ("string_count", builtin_string_count),
-    // RES-523: count occurrences of a single character.
-    ("string_count_char", builtin_string_count_char),
-    // RES-524: char-index of a single character (-1 if absent).
-    ("string_find_char", builtin_string_find_char),
-    // RES-525: named-predicate prefix slicing on strings.
-    ("string_take_while_char", builtin_string_take_while_char),
-    ("string_drop_while_char", builtin_string_drop_while_char),
-    // RES-526: named-predicate global char filter.
-    ("string_filter_char", builtin_string_filter_char),
-    // RES-527: ASCII case-insensitive string equality.
-    ("string_eq_ignore_case", builtin_string_eq_ignore_case),
-    // RES-437: insert separator between adjacent array elements.
-    ("array_intersperse", builtin_array_intersperse),
-    // RES-516: alternate elements from two arrays.
-    ("array_interleave", builtin_array_interleave),
-    // RES-438: one-sided whitespace trimmers.
-    ("trim_start", builtin_trim_start),
-    ("trim_end", builtin_trim_end),
-    // RES-439: bisect array at index — returns (first n, rest) tuple.
-    ("array_split_at", builtin_array_split_at),
-    // RES-440: integer bitwise operations.
-    ("bit_and", builtin_bit_and),
-    ("bit_or", builtin_bit_or),
-    ("bit_xor", builtin_bit_xor),
-    ("bit_not", builtin_bit_not),
-    ("bit_shl", builtin_bit_shl),
-    ("bit_shr", builtin_bit_shr),
-    // RES-488: population count (Hamming weight).
-    ("bit_count", builtin_bit_count),
-    // RES-489: count leading zero bits.
-    ("bit_leading_zeros", builtin_bit_leading_zeros),
-    // RES-490: count trailing zero bits.
-    ("bit_trailing_zeros", builtin_bit_trailing_zeros),
-    // RES-511: single-bit test / set / clear / toggle.
-    ("bit_test", builtin_bit_test),
-    ("bit_set", builtin_bit_set),
-    ("bit_clear", builtin_bit_clear),
-    ("bit_toggle", builtin_bit_toggle),
-    // RES-520: circular bit rotation.
-    ("bit_rotate_left", builtin_bit_rotate_left),
-    ("bit_rotate_right", builtin_bit_rotate_right),
-    // RES-534: extract a single byte from an i64 (little-endian).
-    ("bit_byte", builtin_bit_byte),
-    // RES-538: set a single byte of an i64 (little-endian).
-    ("bit_set_byte", builtin_bit_set_byte),
-    // RES-491: integer floor sqrt.
-    ("int_sqrt", builtin_int_sqrt),
-    // RES-517: integer exponentiation.
-    ("pow_int", builtin_pow_int),
-    // RES-518: integer division with explicit rounding mode.
-    ("ceil_div", builtin_ceil_div),
-    ("floor_div", builtin_floor_div),
-    // RES-519: Python-style modulo (sign of divisor).
-    ("modulo", builtin_modulo),
-    // RES-492: floor log base 2.
-    ("int_log2", builtin_int_log2),
-    // RES-493: power-of-two predicate.
-    ("is_pow2", builtin_is_pow2),
-    // RES-494: round up to next power of two.
-    ("next_pow2", builtin_next_pow2),
-    // RES-495: lowercase hex string (sign-prefixed for negatives).
-    ("int_to_hex", builtin_int_to_hex),
-    // RES-512: binary string (sign-prefixed for negatives).
-    ("int_to_bin", builtin_int_to_bin),
-    // RES-442: byte index of last substring occurrence, or -1.
-    ("last_index_of", builtin_last_index_of),
-    // RES-413: repeat a string n times.
-    ("string_repeat", builtin_string_repeat),
-    // RES-414: first byte index of substring, or -1 if not found.
-    ("index_of", builtin_index_of),
-    // RES-145: string manipulation expansion.
-    ("replace", builtin_replace),
-    ("format", builtin_format),
-    // RES-213: prefix/suffix/repeat — common stdlib gap.
-    ("starts_with", builtin_starts_with),
-    ("ends_with", builtin_ends_with),
-    ("repeat", builtin_repeat),
-    // RES-339: string parsing and formatting.
-    ("parse_int", builtin_parse_int),
-    // RES-529: non-erroring parse with fallback default.
-    ("parse_int_or", builtin_parse_int_or),
-    // RES-532: non-erroring float parse with fallback default.
-    ("parse_float_or", builtin_parse_float_or),
-    ("parse_float", builtin_parse_float),
-    ("char_at", builtin_char_at),
-    ("pad_left", builtin_pad_left),
-    ("pad_right", builtin_pad_right),
-    ("Ok", builtin_ok),
-    ("Err", builtin_err),
-    ("is_ok", builtin_is_ok),
-    ("is_err", builtin_is_err),
-    ("unwrap", builtin_unwrap),
-    ("unwrap_err", builtin_unwrap_err),
-    // RES-363 + RES-375: Option<T> constructors and free-function methods.
-    ("Some", builtin_some),
-    ("None", builtin_none),
-    ("is_some", builtin_is_some),
-    ("is_none", builtin_is_none),
-    ("unwrap_option", builtin_unwrap_option),
-    ("option_unwrap", builtin_option_unwrap),
-    ("option_unwrap_or", builtin_option_unwrap_or),
-    // RES-932: tuple type predicate — distinguishes Value::Tuple from arrays.
-    ("is_tuple", builtin_is_tuple),
-    // RES-143: file I/O. Std-only; the `resilient-runtime` crate has
-    // no builtins table and stays no_std-clean.
-    ("file_read", builtin_file_read),
-    ("file_write", builtin_file_write),
-    // RES-409: streaming file I/O — open / read_chunk / seek /
-    // write_chunk / close. Memory-bounded reads finally possible.
-    ("file_open", file_io::builtin_file_open),
-    ("file_read_chunk", file_io::builtin_file_read_chunk),
-    ("file_write_chunk", file_io::builtin_file_write_chunk),
-    ("file_seek", file_io::builtin_file_seek),
-    ("file_close", file_io::builtin_file_close),
-    // RES-151: read-only env-var accessor, std-only.
-    ("env", builtin_env),
-    // RES-148: Map builtins.
-    ("map_new", builtin_map_new),
-    ("map_insert", builtin_map_insert),
-    ("map_get", builtin_map_get),
-    ("map_remove", builtin_map_remove),
-    ("map_keys", builtin_map_keys),
-    ("map_len", builtin_map_len),
-    // RES-883.
-    ("map_values", builtin_map_values),
-    // RES-884.
-    ("map_contains_key", builtin_map_contains_key),
-    // RES-293: HashMap stdlib builtins. Surface the same `Value::Map`
-    // backend under the user-facing `hashmap_*` names called out in
-    // the language guide. `hashmap_contains` is genuinely new — it
-    // had no `map_contains` predecessor.
-    ("hashmap_new", builtin_hashmap_new),
-    ("hashmap_insert", builtin_hashmap_insert),
-    ("hashmap_get", builtin_hashmap_get),
-    ("hashmap_remove", builtin_hashmap_remove),
-    ("hashmap_contains", builtin_hashmap_contains),
-    ("hashmap_keys", builtin_hashmap_keys),
-    // RES-885.
-    ("hashmap_len", builtin_hashmap_len),
-    // RES-886.
-    ("hashmap_values", builtin_hashmap_values),
-    // RES-149: Set builtins.
-    ("set_new", builtin_set_new),
-    ("set_insert", builtin_set_insert),
-    ("set_remove", builtin_set_remove),
-    ("set_has", builtin_set_has),
-    ("set_len", builtin_set_len),
-    ("set_items", builtin_set_items),
-    // RES-876: set algebra primitives.
-    ("set_union", builtin_set_union),
-    // RES-877.
-    ("set_intersection", builtin_set_intersection),
-    // RES-878.
-    ("set_difference", builtin_set_difference),
-    // RES-879.
-    ("set_is_subset", builtin_set_is_subset),
-    // RES-880.
-    ("set_is_superset", builtin_set_is_superset),
-    // RES-881.
-    ("set_is_disjoint", builtin_set_is_disjoint),
-    // RES-882.
-    ("set_symmetric_difference", builtin_set_symmetric_difference),
-    // RES-152: Bytes builtins.
-    ("bytes_len", builtin_bytes_len),
-    ("bytes_slice", builtin_bytes_slice),
-    ("byte_at", builtin_byte_at),
-    // RES-887.
-    ("bytes_concat", builtin_bytes_concat),
-    // RES-888.
-    ("bytes_eq", builtin_bytes_eq),
-    // RES-944: byte-search predicates / locator.
-    ("bytes_starts_with", builtin_bytes_starts_with),
-    ("bytes_ends_with", builtin_bytes_ends_with),
-    ("bytes_index_of", builtin_bytes_index_of),
-    // RES-943: hex encoding.
-    ("bytes_to_hex", builtin_bytes_to_hex),
-    ("bytes_from_hex", builtin_bytes_from_hex),
-    // RES-936: Result fallback parallel to option_unwrap_or.
-    ("result_unwrap_or", builtin_result_unwrap_or),
-    // RES-937: symmetric Err-side default.
-    ("result_unwrap_or_err", builtin_result_unwrap_or_err),
-    // RES-938: bidirectional Result <-> Option conversion.
-    ("result_to_option", builtin_result_to_option),
-    ("option_to_result", builtin_option_to_result),
-    // RES-939: chain alternatives without unwrapping.
-    ("option_or", builtin_option_or),
-    ("result_or", builtin_result_or),
-    // RES-940: power-of-two helpers.
-    ("is_power_of_two", builtin_is_power_of_two),
-    ("next_power_of_two", builtin_next_power_of_two),
-    // RES-941: int-array statistics.
-    ("array_average", builtin_array_average),
-    ("array_median", builtin_array_median),
-    // RES-942: float-array reductions.
-    ("array_sum_float", builtin_array_sum_float),
-    ("array_product_float", builtin_array_product_float),
-    ("array_min_float", builtin_array_min_float),
-    ("array_max_float", builtin_array_max_float),
-    ("array_average_float", builtin_array_average_float),
-    // RES-945: default-fallback map accessors.
-    ("map_get_or", builtin_map_get_or),
-    ("hashmap_get_or", builtin_hashmap_get_or),
-    // RES-385: explicit consumption of a linear value. At runtime
-    // `drop(v)` simply evaluates and discards its argument; the
-    // semantic weight lives in the type checker's linear-use pass,
-    // which treats a `drop` call as the single consumption that
-    // satisfies the single-use obligation.
-    ("drop", builtin_drop),
-    // RES-353: StringBuilder — efficient multi-part string construction.
-    // Construction is a top-level function; methods are dispatched via
-    // the special StringBuilder method handler in `CallExpression` eval.
-    ("StringBuilder_new", builtin_string_builder_new),
-    // RES-328: shared mutable cell — explicit shared-state escape hatch
-    // for closures that need to coordinate. Methods (.get / .set) are
-    // dispatched via the special cell handler in `CallExpression` eval.
-    ("cell", builtin_cell_new),
-    // RES-332 PR 2: actor spawn/send/receive.
-    ("spawn", builtin_spawn),
-    ("send", builtin_send),
-    ("receive", builtin_receive),
-    // RES-1115..1124: appended to the end of BUILTINS so the O(N)
-    // `apply_builtin_by_name` linear scan keeps the hot-path entries
-    // (println, len, push, ...) at their existing positions. Front-loading
-    // the new builtins regressed the fib(25) VM benchmark by ~23%; placing
-    // them last keeps that gate green while leaving the new surface fully
-    // available.
-    // RES-1115..1118: overflow-safe integer arithmetic — safety-critical
-    // embedded code needs an explicit overflow policy, not silent wrap.
-    ("saturating_add", builtin_saturating_add),
-    ("saturating_sub", builtin_saturating_sub),
-    ("saturating_mul", builtin_saturating_mul),
-    ("wrapping_add", builtin_wrapping_add),
-    ("wrapping_sub", builtin_wrapping_sub),
-    ("wrapping_mul", builtin_wrapping_mul),
-    ("checked_add", builtin_checked_add),
-    ("checked_sub", builtin_checked_sub),
-    ("checked_mul", builtin_checked_mul),
-    ("checked_div", builtin_checked_div),
-    // RES-1119..1121: bit manipulation.
-    ("rotate_left_int", builtin_rotate_left_int),
-    ("rotate_right_int", builtin_rotate_right_int),
-    ("reverse_bits", builtin_reverse_bits),
-    ("swap_bytes", builtin_swap_bytes),
-    // RES-1122..1123: int ↔ bytes endianness conversion.
-    ("to_be_bytes", builtin_to_be_bytes),
-    ("to_le_bytes", builtin_to_le_bytes),
-    ("from_be_bytes", builtin_from_be_bytes),
-    ("from_le_bytes", builtin_from_le_bytes),
-    // RES-1124: integer-only math primitives.
-    ("isqrt", builtin_isqrt),
-    ("ipow", builtin_ipow),
-    // RES-1126: direction-rounded division.
-    ("div_ceil", builtin_div_ceil),
-    ("div_floor", builtin_div_floor),
-    // RES-1127: Euclidean division — non-negative remainder.
-    ("div_euclid", builtin_div_euclid),
-    ("rem_euclid", builtin_rem_euclid),
-    // RES-1128: overflow-safe arithmetic mean.
-    ("midpoint", builtin_midpoint),
-    // RES-1129: integer logarithms (no f64 round-trip).
-    ("ilog2", builtin_ilog2),
-    ("ilog10", builtin_ilog10),
-    // RES-1130: IEEE 754 bit reinterpret cast.
-    ("float_to_bits", builtin_float_to_bits),
-    ("float_from_bits", builtin_float_from_bits),
-    // RES-1134: bitwise + construction ops on Bytes. Pure leaf
-    // builtins; module-isolated in `bytes_bitwise.rs`. Appended at
-    // the end of BUILTINS per the perf rule established in PR #1125.
-    ("bytes_xor", crate::bytes_bitwise::builtin_bytes_xor),
-    ("bytes_and", crate::bytes_bitwise::builtin_bytes_and),
-    ("bytes_or", crate::bytes_bitwise::builtin_bytes_or),
-    ("bytes_not", crate::bytes_bitwise::builtin_bytes_not),
-    ("bytes_fill", crate::bytes_bitwise::builtin_bytes_fill),
-    ("bytes_reverse", crate::bytes_bitwise::builtin_bytes_reverse),
-    // RES-1136: alignment helpers. Pure leaf builtins; module-isolated
-    // in `alignment_helpers.rs`. Appended at the end of BUILTINS per
-    // the perf rule established in PR #1125.
-    (
-        "next_multiple_of",
-        crate::alignment_helpers::builtin_next_multiple_of,
-    ),
-    (
-        "is_multiple_of",
-        crate::alignment_helpers::builtin_is_multiple_of,
-    ),
-    // RES-1138: IEEE 754 classification + total order + sign-bit
-    // predicates. Pure leaf builtins; module-isolated in
-    // `float_predicates.rs`. Appended at the end of BUILTINS per
-    // the perf rule established in PR #1125.
-    (
-        "float_classify",
-        crate::float_predicates::builtin_float_classify,
-    ),
-    (
-        "float_total_cmp",
-        crate::float_predicates::builtin_float_total_cmp,
-    ),
-    (
-        "float_is_normal",
-        crate::float_predicates::builtin_float_is_normal,
-    ),
-    (
-        "float_is_subnormal",
-        crate::float_predicates::builtin_float_is_subnormal,
-    ),
-    (
-        "float_sign_bit",
-        crate::float_predicates::builtin_float_sign_bit,
-    ),
-    // RES-1142: array chunking + striding + rotation primitives.
-    // Pure leaf builtins; module-isolated in `array_chunking.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    ("array_chunks", crate::array_chunking::builtin_array_chunks),
-    (
-        "array_chunks_exact",
-        crate::array_chunking::builtin_array_chunks_exact,
-    ),
-    ("array_step", crate::array_chunking::builtin_array_step),
-    (
-        "array_rotate_left",
-        crate::array_chunking::builtin_array_rotate_left,
-    ),
-    (
-        "array_rotate_right",
-        crate::array_chunking::builtin_array_rotate_right,
-    ),
-    // RES-1140: ASCII char-class predicates. Pure leaf builtins;
-    // module-isolated in `ascii_predicates.rs`. Appended at the end
-    // of BUILTINS per the perf rule established in PR #1125.
-    ("is_ascii", crate::ascii_predicates::builtin_is_ascii),
-    (
-        "is_ascii_whitespace",
-        crate::ascii_predicates::builtin_is_ascii_whitespace,
-    ),
-    (
-        "is_ascii_hexdigit",
-        crate::ascii_predicates::builtin_is_ascii_hexdigit,
-    ),
-    (
-        "is_ascii_uppercase",
-        crate::ascii_predicates::builtin_is_ascii_uppercase,
-    ),
-    (
-        "is_ascii_lowercase",
-        crate::ascii_predicates::builtin_is_ascii_lowercase,
-    ),
-    (
-        "is_ascii_punctuation",
-        crate::ascii_predicates::builtin_is_ascii_punctuation,
-    ),
-    (
-        "is_ascii_control",
-        crate::ascii_predicates::builtin_is_ascii_control,
-    ),
-    // RES-1146: float / string sort + array_is_sorted predicates.
-    // Pure leaf builtins; module-isolated in `array_sort_extra.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    (
-        "array_sort_float",
-        crate::array_sort_extra::builtin_array_sort_float,
-    ),
-    (
-        "array_sort_string",
-        crate::array_sort_extra::builtin_array_sort_string,
-    ),
-    (
-        "array_is_sorted",
-        crate::array_sort_extra::builtin_array_is_sorted,
-    ),
-    (
-        "array_is_sorted_float",
-        crate::array_sort_extra::builtin_array_is_sorted_float,
-    ),
-    (
-        "array_is_sorted_string",
-        crate::array_sort_extra::builtin_array_is_sorted_string,
-    ),
-    // RES-1148: binary search on sorted int / float / string arrays.
-    // Pure leaf builtins; module-isolated in `array_binary_search.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    (
-        "array_binary_search",
-        crate::array_binary_search::builtin_array_binary_search,
-    ),
-    (
-        "array_binary_search_float",
-        crate::array_binary_search::builtin_array_binary_search_float,
-    ),
-    (
-        "array_binary_search_string",
-        crate::array_binary_search::builtin_array_binary_search_string,
-    ),
-    // RES-1144: map_entries / map_merge / map_is_empty + hashmap aliases.
-    // Pure leaf builtins; module-isolated in `map_entries_merge.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    ("map_entries", crate::map_entries_merge::builtin_map_entries),
-    ("map_merge", crate::map_entries_merge::builtin_map_merge),
-    (
-        "map_is_empty",
-        crate::map_entries_merge::builtin_map_is_empty,
-    ),
-    (
-        "hashmap_entries",
-        crate::map_entries_merge::builtin_hashmap_entries,
-    ),
-    (
-        "hashmap_merge",
-        crate::map_entries_merge::builtin_hashmap_merge,
-    ),
-    (
-        "hashmap_is_empty",
-        crate::map_entries_merge::builtin_hashmap_is_empty,
-    ),
-    // RES-1150: statistical reductions — variance, stddev, median_float,
-    // range_float. Pure leaf builtins; module-isolated in
-    // `array_stats.rs`. Appended at the end of BUILTINS per the perf
-    // rule from PR #1125.
-    (
-        "array_variance_int",
-        crate::array_stats::builtin_array_variance_int,
-    ),
-    (
-        "array_variance_float",
-        crate::array_stats::builtin_array_variance_float,
-    ),
-    (
-        "array_stddev_int",
-        crate::array_stats::builtin_array_stddev_int,
-    ),
-    (
-        "array_stddev_float",
-        crate::array_stats::builtin_array_stddev_float,
-    ),
-    (
-        "array_median_float",
-        crate::array_stats::builtin_array_median_float,
-    ),
-    (
-        "array_range_float",
-        crate::array_stats::builtin_array_range_float,
-    ),
-    // RES-1152: per-byte helpers — repeat / count_byte / replace_byte.
-    // Pure leaf builtins; module-isolated in `bytes_helpers.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    ("bytes_repeat", crate::bytes_helpers::builtin_bytes_repeat),
-    (
-        "bytes_count_byte",
-        crate::bytes_helpers::builtin_bytes_count_byte,
-    ),
-    (
-        "bytes_replace_byte",
-        crate::bytes_helpers::builtin_bytes_replace_byte,
-    ),
-    // RES-1154: set_is_empty / set_from_array / result_and / option_and.
-    // Pure leaf builtins; module-isolated in `set_result_option.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    (
-        "set_is_empty",
-        crate::set_result_option::builtin_set_is_empty,
-    ),
-    (
-        "set_from_array",
-        crate::set_result_option::builtin_set_from_array,
-    ),
-    ("result_and", crate::set_result_option::builtin_result_and),
-    ("option_and", crate::set_result_option::builtin_option_and),
-    // RES-1156: per-bit accessors on i64 — set / clear / get / flip.
-    // Pure leaf builtins; module-isolated in `bit_manipulation.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    ("set_bit", crate::bit_manipulation::builtin_set_bit),
-    ("clear_bit", crate::bit_manipulation::builtin_clear_bit),
-    ("get_bit", crate::bit_manipulation::builtin_get_bit),
-    ("flip_bit", crate::bit_manipulation::builtin_flip_bit),
-    // RES-1158: array set-style helpers + fallback-safe first/last +
-    // index_of_last. Pure leaf builtins; module-isolated in
-    // `array_set_helpers.rs`. Appended at the end of BUILTINS per the
-    // perf rule from PR #1125.
-    (
-        "array_difference",
-        crate::array_set_helpers::builtin_array_difference,
-    ),
-    (
-        "array_intersection",
-        crate::array_set_helpers::builtin_array_intersection,
-    ),
-    (
-        "array_index_of_last",
-        crate::array_set_helpers::builtin_array_index_of_last,
-    ),
-    (
-        "array_first_or",
-        crate::array_set_helpers::builtin_array_first_or,
-    ),
-    (
-        "array_last_or",
-        crate::array_set_helpers::builtin_array_last_or,
-    ),
-    // RES-1162: deterministic hash builtins — hash_int / hash_string /
-    // hash_bytes / hash_combine. Pure leaf builtins; module-isolated in
-    // `hash_builtins.rs`. Appended at the end of BUILTINS per the perf
-    // rule from PR #1125.
-    ("hash_int", crate::hash_builtins::builtin_hash_int),
-    ("hash_string", crate::hash_builtins::builtin_hash_string),
-    ("hash_bytes", crate::hash_builtins::builtin_hash_bytes),
-    ("hash_combine", crate::hash_builtins::builtin_hash_combine),
-    // RES-2560: SHA-256 and SHA-512 cryptographic hash builtins.
-    ("sha256", crate::crypto_hash::builtin_sha256),
-    ("sha256_str", crate::crypto_hash::builtin_sha256_str),
-    ("sha512", crate::crypto_hash::builtin_sha512),
-    ("sha512_str", crate::crypto_hash::builtin_sha512_str),
-    // RES-2561: CRC-32 and CRC-16 checksum builtins.
-    ("crc32", crate::crypto_hash::builtin_crc32),
-    ("crc32_str", crate::crypto_hash::builtin_crc32_str),
-    ("crc16", crate::crypto_hash::builtin_crc16),
-    ("crc16_str", crate::crypto_hash::builtin_crc16_str),
-    // RES-1160: argmax / argmin for float and string arrays.
-    // Pure leaf builtins; module-isolated in `array_argminmax.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    (
-        "array_argmax_float",
-        crate::array_argminmax::builtin_array_argmax_float,
-    ),
-    (
-        "array_argmin_float",
-        crate::array_argminmax::builtin_array_argmin_float,
-    ),
-    (
-        "array_argmax_string",
-        crate::array_argminmax::builtin_array_argmax_string,
-    ),
-    (
-        "array_argmin_string",
-        crate::array_argminmax::builtin_array_argmin_string,
-    ),
-    // RES-1164: iteration helpers — enumerate, array_zip3, string_truncate.
-    // Pure leaf builtins; module-isolated in `iter_helpers.rs`. Appended
-    // at the end of BUILTINS per the perf rule from PR #1125.
-    ("enumerate", crate::iter_helpers::builtin_enumerate),
-    ("array_zip3", crate::iter_helpers::builtin_array_zip3),
-    (
-        "string_truncate",
-        crate::iter_helpers::builtin_string_truncate,
-    ),
-    // RES-1166: rounding builtins — round / trunc + int variants.
-    // Pure leaf builtins; module-isolated in `rounding.rs`. Appended
-    // at the end of BUILTINS per the perf rule from PR #1125.
-    ("round", crate::rounding::builtin_round),
-    ("trunc", crate::rounding::builtin_trunc),
-    ("round_to_int", crate::rounding::builtin_round_to_int),
-    ("trunc_to_int", crate::rounding::builtin_trunc_to_int),
-    // RES-1170: cumulative reductions + combined min/max.
-    // Pure leaf builtins; module-isolated in `array_cumulative.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    (
-        "array_cumsum",
-        crate::array_cumulative::builtin_array_cumsum,
-    ),
-    (
-        "array_cumprod",
-        crate::array_cumulative::builtin_array_cumprod,
-    ),
-    ("array_diffs", crate::array_cumulative::builtin_array_diffs),
-    (
-        "array_min_max",
-        crate::array_cumulative::builtin_array_min_max,
-    ),
-    // RES-1172: small string + array gaps.
-    // Pure leaf builtins; module-isolated in `string_array_misc.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    (
-        "string_split_once",
-        crate::string_array_misc::builtin_string_split_once,
-    ),
-    (
-        "string_rsplit_once",
-        crate::string_array_misc::builtin_string_rsplit_once,
-    ),
-    (
-        "string_from_chars",
-        crate::string_array_misc::builtin_string_from_chars,
-    ),
-    (
-        "array_is_empty",
-        crate::string_array_misc::builtin_array_is_empty,
-    ),
-    // RES-1174: wall-clock unix time. @io / impure.
-    // Pure leaf builtins; module-isolated in `unix_time.rs`. Appended
-    // at the end of BUILTINS per the perf rule from PR #1125.
-    ("unix_time_s", crate::unix_time::builtin_unix_time_s),
-    ("unix_time_ms", crate::unix_time::builtin_unix_time_ms),
-    ("unix_time_ns", crate::unix_time::builtin_unix_time_ns),
-    // RES-1176: bytes ↔ string conversions — strip_prefix / strip_suffix /
-    // bytes_to_string. Pure leaf builtins; module-isolated in
-    // `bytes_conversions.rs`. Appended at the end of BUILTINS per the
-    // perf rule from PR #1125.
-    (
-        "bytes_strip_prefix",
-        crate::bytes_conversions::builtin_bytes_strip_prefix,
-    ),
-    (
-        "bytes_strip_suffix",
-        crate::bytes_conversions::builtin_bytes_strip_suffix,
-    ),
-    (
-        "bytes_to_string",
-        crate::bytes_conversions::builtin_bytes_to_string,
-    ),
-    // RES-1178: bytes slicing primitives — take / drop / take_last /
-    // drop_last. Pure leaf builtins; module-isolated in `bytes_slicing.rs`.
-    // Appended at the end of BUILTINS per the perf rule from PR #1125.
-    ("bytes_take", crate::bytes_slicing::builtin_bytes_take),
-    ("bytes_drop", crate::bytes_slicing::builtin_bytes_drop),
-    (
-        "bytes_take_last",
-        crate::bytes_slicing::builtin_bytes_take_last,
-    ),
-    (
-        "bytes_drop_last",
-        crate::bytes_slicing::builtin_bytes_drop_last,
-    ),
-    // RES-1168: precision-sensitive math — expm1 / ln_1p / mul_add / recip.
-    // Pure leaf builtins; module-isolated in `precision_math.rs`. Appended
-    // at the end of BUILTINS per the perf rule from PR #1125.
-    ("expm1", crate::precision_math::builtin_expm1),
-    ("ln_1p", crate::precision_math::builtin_ln_1p),
-    ("mul_add", crate::precision_math::builtin_mul_add),
-    ("recip", crate::precision_math::builtin_recip),
-    // RES-1182: integer bit rotation + scalar signum. Pure leaf builtins;
-    // module-isolated in `int_rotate.rs`. Appended at the end of BUILTINS
-    // per the perf rule from PR #1125.
-    ("rotate_left", crate::int_rotate::builtin_rotate_left),
-    ("rotate_right", crate::int_rotate::builtin_rotate_right),
-    ("signum", crate::int_rotate::builtin_signum),
-    // RES-2646: map construction from key-value pairs.
-    (
-        "map_from_pairs",
-        crate::array_functional::builtin_map_from_pairs,
-    ),
-    // RES-2647: map functional operations (pure — no callback).
-    ("map_to_pairs", crate::map_functional::builtin_map_to_pairs),
-    ("map_invert", crate::map_functional::builtin_map_invert),
-    // RES-2648: array combinators (pure — no callback).
-    (
-        "array_windows",
-        crate::array_combinators::builtin_array_windows,
-    ),
-    // RES-2650: numeric utilities (all pure).
-    ("lerp", crate::numeric_utils::builtin_lerp),
-    ("remap", crate::numeric_utils::builtin_remap),
-    (
-        "float_approx_eq",
-        crate::numeric_utils::builtin_float_approx_eq,
-    ),
-    ("round_to", crate::numeric_utils::builtin_round_to),
-    ("int_pow", crate::numeric_utils::builtin_int_pow),
-    // RES-2650: collection extras (pure).
-    (
-        "array_frequency_map",
-        crate::collection_extras::builtin_array_frequency_map,
-    ),
-    // RES-2651: Option/Result HOF (pure).
-    (
-        "option_ok_or",
-        crate::result_option_hof::builtin_option_ok_or,
-    ),
-    // RES-2652: type introspection + result_collect (pure).
-    ("type_of", crate::type_builtins::builtin_type_of),
-    ("struct_name", crate::type_builtins::builtin_struct_name),
-    (
-        "result_collect",
-        crate::type_builtins::builtin_result_collect,
-    ),
-    // RES-2655: number theory builtins (pure).
-    ("prime_factors", crate::number_theory::builtin_prime_factors),
-    ("primes_up_to", crate::number_theory::builtin_primes_up_to),
-    ("euler_totient", crate::number_theory::builtin_euler_totient),
-    ("divisors", crate::number_theory::builtin_divisors),
-    ("is_perfect", crate::number_theory::builtin_is_perfect),
-    ("digit_sum", crate::number_theory::builtin_digit_sum),
-    ("digital_root", crate::number_theory::builtin_digital_root),
-    (
-        "collatz_length",
-        crate::number_theory::builtin_collatz_length,
-    ),
-    ("is_fibonacci", crate::number_theory::builtin_is_fibonacci),
-    ("int_log", crate::number_theory::builtin_int_log),
-    ("count_digits", crate::number_theory::builtin_count_digits),
-    ("int_to_digits", crate::number_theory::builtin_int_to_digits),
-    (
-        "int_from_digits",
-        crate::number_theory::builtin_int_from_digits,
-    ),
-    // RES-2656: functional HOFs — identity is pure; rest need interpreter (inline dispatch).
-    ("identity", crate::functional_hof::builtin_identity),
-    // RES-2657: JSON serialization/deserialization (pure).
-    ("to_json", crate::json_builtins::builtin_to_json),
-    ("from_json", crate::json_builtins::builtin_from_json),
-    // RES-2554: json_encode / json_decode / json_encode_pretty / json_valid.
-    ("json_encode", crate::json_builtins::builtin_json_encode),
-    ("json_decode", crate::json_builtins::builtin_json_decode),
-    (
-        "json_encode_pretty",
-        crate::json_builtins::builtin_json_encode_pretty,
-    ),
-    ("json_valid", crate::json_builtins::builtin_json_valid),
-    // RES-2585: regular expression matching builtins.
-    ("regex_match", crate::regex_builtins::builtin_regex_match),
-    ("regex_find", crate::regex_builtins::builtin_regex_find),
-    (
-        "regex_find_all",
-        crate::regex_builtins::builtin_regex_find_all,
-    ),
-    (
-        "regex_captures",
-        crate::regex_builtins::builtin_regex_captures,
-    ),
-    (
-        "regex_replace",
-        crate::regex_builtins::builtin_regex_replace,
-    ),
-    (
-        "regex_replace_all",
-        crate::regex_builtins::builtin_regex_replace_all,
-    ),
-    // RES-2559: date/time builtins.
-    (
-        "datetime_now",
-        crate::datetime_builtins::builtin_datetime_now,
-    ),
-    (
-        "datetime_from_unix",
-        crate::datetime_builtins::builtin_datetime_from_unix,
-    ),
-    (
-        "datetime_to_unix",
-        crate::datetime_builtins::builtin_datetime_to_unix,
-    ),
-    (
-        "datetime_format",
-        crate::datetime_builtins::builtin_datetime_format,
-    ),
-    (
-        "datetime_parse",
-        crate::datetime_builtins::builtin_datetime_parse,
-    ),
-    // RES-2556: HTTP client builtins.
-    ("http_get", crate::http_client::builtin_http_get),
-    ("http_post", crate::http_client::builtin_http_post),
-    // RES-2658: linear algebra — vector and matrix operations (pure).
-    ("vec_add", crate::linear_algebra::builtin_vec_add),
-    ("vec_sub", crate::linear_algebra::builtin_vec_sub),
-    ("vec_scale", crate::linear_algebra::builtin_vec_scale),
-    ("vec_dot", crate::linear_algebra::builtin_vec_dot),
-    ("vec_norm", crate::linear_algebra::builtin_vec_norm),
-    (
-        "vec_normalize",
-        crate::linear_algebra::builtin_vec_normalize,
-    ),
-    ("vec_cross", crate::linear_algebra::builtin_vec_cross),
-    ("vec_lerp", crate::linear_algebra::builtin_vec_lerp),
-    ("mat_mul", crate::linear_algebra::builtin_mat_mul),
-    ("mat_add", crate::linear_algebra::builtin_mat_add),
-    ("mat_scale", crate::linear_algebra::builtin_mat_scale),
-    (
-        "mat_transpose",
-        crate::linear_algebra::builtin_mat_transpose,
-    ),
-    ("mat_identity", crate::linear_algebra::builtin_mat_identity),
-    ("mat_trace", crate::linear_algebra::builtin_mat_trace),
-    // RES-2654: combinatorics and discrete collection operations (pure).
-    (
-        "array_cartesian_product",
-        crate::combinatorics::builtin_array_cartesian_product,
-    ),
-    (
-        "array_combinations",
-        crate::combinatorics::builtin_array_combinations,
-    ),
-    (
-        "array_permutations",
-        crate::combinatorics::builtin_array_permutations,
-    ),
-    (
-        "array_powerset",
-        crate::combinatorics::builtin_array_powerset,
-    ),
-    (
-        "array_transpose",
-        crate::combinatorics::builtin_array_transpose,
-    ),
-    (
-        "array_cartesian_product_n",
-        crate::combinatorics::builtin_array_cartesian_product_n,
-    ),
-    // RES-2659: graph algorithm builtins (pure — no callbacks).
-    ("graph_bfs", crate::graph_algorithms::builtin_graph_bfs),
-    ("graph_dfs", crate::graph_algorithms::builtin_graph_dfs),
-    (
-        "graph_has_path",
-        crate::graph_algorithms::builtin_graph_has_path,
-    ),
-    (
-        "graph_topological_sort",
-        crate::graph_algorithms::builtin_graph_topological_sort,
-    ),
-    (
-        "graph_connected_components",
-        crate::graph_algorithms::builtin_graph_connected_components,
-    ),
-    (
-        "graph_num_components",
-        crate::graph_algorithms::builtin_graph_num_components,
-    ),
-    (
-        "graph_out_degrees",
-        crate::graph_algorithms::builtin_graph_out_degrees,
-    ),
-    (
-        "graph_in_degrees",
-        crate::graph_algorithms::builtin_graph_in_degrees,
-    ),
-    (
-        "graph_reverse",
-        crate::graph_algorithms::builtin_graph_reverse,
-    ),
-    (
-        "graph_is_dag",
-        crate::graph_algorithms::builtin_graph_is_dag,
-    ),
-    (
-        "graph_reachable",
-        crate::graph_algorithms::builtin_graph_reachable,
-    ),
-    (
-        "graph_dijkstra",
-        crate::graph_algorithms::builtin_graph_dijkstra,
-    ),
-    // RES-2660: extended statistics and matrix decomposition builtins.
-    (
-        "stats_covariance",
-        crate::statistics::builtin_stats_covariance,
-    ),
-    (
-        "stats_correlation",
-        crate::statistics::builtin_stats_correlation,
-    ),
-    (
-        "stats_percentile",
-        crate::statistics::builtin_stats_percentile,
-    ),
-    ("stats_zscore", crate::statistics::builtin_stats_zscore),
-    (
-        "stats_normalize",
-        crate::statistics::builtin_stats_normalize,
-    ),
-    (
-        "stats_histogram",
-        crate::statistics::builtin_stats_histogram,
-    ),
-    (
-        "stats_linear_regression",
-        crate::statistics::builtin_stats_linear_regression,
-    ),
-    (
-        "stats_moving_average",
-        crate::statistics::builtin_stats_moving_average,
-    ),
-    (
-        "stats_weighted_mean",
-        crate::statistics::builtin_stats_weighted_mean,
-    ),
-    (
-        "stats_geometric_mean",
-        crate::statistics::builtin_stats_geometric_mean,
-    ),
-    (
-        "stats_harmonic_mean",
-        crate::statistics::builtin_stats_harmonic_mean,
-    ),
-    ("stats_mode_int", crate::statistics::builtin_stats_mode_int),
-    ("stats_iqr", crate::statistics::builtin_stats_iqr),
-    ("mat_det", crate::statistics::builtin_mat_det),
-    ("mat_inv", crate::statistics::builtin_mat_inv),
-    ("mat_solve", crate::statistics::builtin_mat_solve),
-    (
-        "mat_norm_frobenius",
-        crate::statistics::builtin_mat_norm_frobenius,
-    ),
-    ("mat_rank", crate::statistics::builtin_mat_rank),
-    ("mat_lu", crate::statistics::builtin_mat_lu),
-    // RES-2661: complex number builtins (representation: Array<float> [re, im]).
-    ("complex", crate::complex_numbers::builtin_complex),
-    ("complex_real", crate::complex_numbers::builtin_complex_real),
-    ("complex_imag", crate::complex_numbers::builtin_complex_imag),
-    ("complex_add", crate::complex_numbers::builtin_complex_add),
-    ("complex_sub", crate::complex_numbers::builtin_complex_sub),
-    ("complex_mul", crate::complex_numbers::builtin_complex_mul),
-    ("complex_div", crate::complex_numbers::builtin_complex_div),
-    ("complex_abs", crate::complex_numbers::builtin_complex_abs),
-    ("complex_arg", crate::complex_numbers::builtin_complex_arg),
-    ("complex_conj", crate::complex_numbers::builtin_complex_conj),
-    (
-        "complex_norm_sq",
-        crate::complex_numbers::builtin_complex_norm_sq,
-    ),
-    ("complex_exp", crate::complex_numbers::builtin_complex_exp),
-    ("complex_ln", crate::complex_numbers::builtin_complex_ln),
-    (
-        "complex_pow_real",
-        crate::complex_numbers::builtin_complex_pow_real,
-    ),
-    ("complex_sqrt", crate::complex_numbers::builtin_complex_sqrt),
-    ("complex_sin", crate::complex_numbers::builtin_complex_sin),
-    ("complex_cos", crate::complex_numbers::builtin_complex_cos),
-    (
-        "complex_from_polar",
-        crate::complex_numbers::builtin_complex_from_polar,
-    ),
-    // RES-2662: data utilities — linspace, CSV, table formatting, RLE.
-    ("linspace", crate::data_utils::builtin_linspace),
-    ("logspace", crate::data_utils::builtin_logspace),
-    ("arange", crate::data_utils::builtin_arange),
-    ("csv_parse", crate::data_utils::builtin_csv_parse),
-    ("csv_parse_tsv", crate::data_utils::builtin_csv_parse_tsv),
-    ("csv_format", crate::data_utils::builtin_csv_format),
-    ("csv_format_tsv", crate::data_utils::builtin_csv_format_tsv),
-    ("table_format", crate::data_utils::builtin_table_format),
-    ("format_float", crate::data_utils::builtin_format_float),
-    (
-        "format_int_width",
-        crate::data_utils::builtin_format_int_width,
-    ),
-    (
-        "format_float_sci",
-        crate::data_utils::builtin_format_float_sci,
-    ),
-    ("rle_encode", crate::data_utils::builtin_rle_encode),
-    ("rle_decode", crate::data_utils::builtin_rle_decode),
-    // Pain-points hardening: struct sort, depth-bounded flatten, radix parse/format,
-    // dedup-by, int_to_oct.
-    (
-        "array_sort_by_field",
-        crate::array_struct_sort::builtin_array_sort_by_field,
-    ),
-    (
-        "array_sort_by_field_desc",
-        crate::array_struct_sort::builtin_array_sort_by_field_desc,
-    ),
-    (
-        "array_flatten_depth",
-        crate::array_flatten_depth::builtin_array_flatten_depth,
-    ),
-    (
-        "int_parse_hex",
-        crate::int_parse_radix::builtin_int_parse_hex,
-    ),
-    (
-        "int_parse_bin",
-        crate::int_parse_radix::builtin_int_parse_bin,
-    ),
-    ("int_to_oct", crate::int_parse_radix::builtin_int_to_oct),
-    (
-        "array_dedup_by",
-        crate::array_dedup_none::builtin_array_dedup_by,
-    ),
-    // RES-2619: Char type builtins.
-    ("char_is_alpha", crate::char_type::builtin_char_is_alpha),
-    ("char_is_digit", crate::char_type::builtin_char_is_digit),
-    (
-        "char_is_whitespace",
-        crate::char_type::builtin_char_is_whitespace,
-    ),
-    ("char_is_upper", crate::char_type::builtin_char_is_upper),
-    ("char_is_lower", crate::char_type::builtin_char_is_lower),
-    (
-        "char_is_alphanumeric",
-        crate::char_type::builtin_char_is_alphanumeric,
-    ),
-    ("char_is_ascii", crate::char_type::builtin_char_is_ascii),
-    ("char_to_upper", crate::char_type::builtin_char_to_upper),
-    ("char_to_lower", crate::char_type::builtin_char_to_lower),
-    ("char_to_int", crate::char_type::builtin_char_to_int),
-    ("int_to_char", crate::char_type::builtin_int_to_char),
-    ("char_to_string", crate::char_type::builtin_char_to_string),
-    // RES-2586: deque (double-ended queue) collection builtins.
-    ("deque_new", crate::deque::builtin_deque_new),
-    ("deque_push_front", crate::deque::builtin_deque_push_front),
-    ("deque_push_back", crate::deque::builtin_deque_push_back),
-    ("deque_pop_front", crate::deque::builtin_deque_pop_front),
-    ("deque_pop_back", crate::deque::builtin_deque_pop_back),
-    ("deque_peek_front", crate::deque::builtin_deque_peek_front),
-    ("deque_peek_back", crate::deque::builtin_deque_peek_back),
-    ("deque_len", crate::deque::builtin_deque_len),
-    ("deque_is_empty", crate::deque::builtin_deque_is_empty),
-    // RES-2587: priority queue / binary heap collection builtins.
-    ("heap_new", crate::heap::builtin_heap_new),
-    ("heap_new_max", crate::heap::builtin_heap_new_max),
-    ("heap_push", crate::heap::builtin_heap_push),
-    ("heap_pop", crate::heap::builtin_heap_pop),
-    ("heap_peek", crate::heap::builtin_heap_peek),
-    ("heap_len", crate::heap::builtin_heap_len),
-    ("heap_is_empty", crate::heap::builtin_heap_is_empty),
-    // RES-2558: process spawning builtins (std-only).
-    ("exec", crate::process_exec::builtin_exec),
-    ("exec_shell", crate::process_exec::builtin_exec_shell),
-    // RES-2555: TCP/UDP networking builtins (std-only).
-    ("tcp_connect", crate::tcp_udp::builtin_tcp_connect),
-    ("tcp_listen", crate::tcp_udp::builtin_tcp_listen),
-    ("tcp_accept", crate::tcp_udp::builtin_tcp_accept),
-    ("tcp_read", crate::tcp_udp::builtin_tcp_read),
-    ("tcp_write", crate::tcp_udp::builtin_tcp_write),
-    ("tcp_close", crate::tcp_udp::builtin_tcp_close),
-    ("tcp_set_timeout", crate::tcp_udp::builtin_tcp_set_timeout),
-    ("udp_bind", crate::tcp_udp::builtin_udp_bind),
-    ("udp_send_to", crate::tcp_udp::builtin_udp_send_to),
-    ("udp_recv_from", crate::tcp_udp::builtin_udp_recv_from),
-    ("udp_close", crate::tcp_udp::builtin_udp_close),
-    // RES-2583: mutex and rwlock synchronization primitives.
-    ("mutex_new", crate::mutex_rwlock::builtin_mutex_new),
-    ("mutex_lock", crate::mutex_rwlock::builtin_mutex_lock),
-    ("mutex_unlock", crate::mutex_rwlock::builtin_mutex_unlock),
-    (
-        "mutex_try_lock",
-        crate::mutex_rwlock::builtin_mutex_try_lock,
-    ),
-    ("rwlock_new", crate::mutex_rwlock::builtin_rwlock_new),
-    ("rwlock_read", crate::mutex_rwlock::builtin_rwlock_read),
-    ("rwlock_write", crate::mutex_rwlock::builtin_rwlock_write),
-    ("rwlock_unlock", crate::mutex_rwlock::builtin_rwlock_unlock),
-    // RES-2557: file metadata builtins (std-only).
-    ("file_exists", crate::file_meta::builtin_file_exists),
-    ("file_is_dir", crate::file_meta::builtin_file_is_dir),
-    ("file_is_file", crate::file_meta::builtin_file_is_file),
-    ("file_size", crate::file_meta::builtin_file_size),
-    ("file_stat", crate::file_meta::builtin_file_stat),
-    ("dir_list", crate::file_meta::builtin_dir_list),
-];
+    match builtin_registry::lookup(name)? {
+        (_, _, builtin_registry::BuiltinEval::Native(f)) => Some(f(args)),
+        (_, _, builtin_registry::BuiltinEval::Intrinsic) => None,
+    }
+}
 
 /// Print the single argument followed by a newline and return `Void`.
 ///
@@ -14195,9 +13390,63 @@ fn builtin_input(args: &[Value]) -> RResult<Value> {
             ));
         }
     };
-    let stdin = std::io::stdin();
-    let mut lock = stdin.lock();
-    do_input(&mut lock, &prompt)
+    with_stdin(|r| do_input(r, &prompt))
+}
+
+/// `read_line() -> String` — read one line from stdin without its line
+/// ending. Unlike `input`, end of input is an error — `EndOfInput`,
+/// which `try ... catch EndOfInput` handles — so a closed stdin can't
+/// be mistaken for an empty line.
+fn builtin_read_line(args: &[Value]) -> RResult<Value> {
+    if !args.is_empty() {
+        return Err(format!(
+            "read_line: expected 0 arguments, got {}",
+            args.len()
+        ));
+    }
+    with_stdin(|r| do_read_line(r, "read_line")).map(Value::String)
+}
+
+/// `read_int() -> Int` — read one line from stdin as an integer,
+/// ignoring surrounding whitespace. Fails with `EndOfInput` at end of
+/// input and `InvalidInput` when the line is not an integer.
+fn builtin_read_int(args: &[Value]) -> RResult<Value> {
+    if !args.is_empty() {
+        return Err(format!(
+            "read_int: expected 0 arguments, got {}",
+            args.len()
+        ));
+    }
+    with_stdin(|r| do_read_int(r))
+}
+
+/// Hand the stdin builtins a reader holding the next line. Reading
+/// goes through `record_replay` so `--record` captures each line and
+/// `--replay` serves it back; a live read error reaches the builtin
+/// as an error from the reader.
+fn with_stdin<T>(f: impl FnOnce(&mut dyn std::io::BufRead) -> T) -> T {
+    use std::io::BufRead;
+    let line = record_replay::stdin_line(|| {
+        let mut line = String::new();
+        let n = std::io::stdin().lock().read_line(&mut line)?;
+        Ok((n > 0).then_some(line))
+    });
+    match line {
+        Ok(line) => f(&mut std::io::Cursor::new(line.unwrap_or_default())),
+        Err(e) => f(&mut std::io::BufReader::new(FailingReader(Some(e)))),
+    }
+}
+
+/// A reader whose first read fails with a stored error.
+struct FailingReader(Option<std::io::Error>);
+
+impl std::io::Read for FailingReader {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        match self.0.take() {
+            Some(e) => Err(e),
+            None => Ok(0),
+        }
+    }
 }
 
 /// RES-1100: `version()` — returns the compiler's `CARGO_PKG_VERSION`
@@ -14217,9 +13466,12 @@ fn builtin_version(args: &[Value]) -> RResult<Value> {
 // compile-time phase in the tree-walker).
 fn builtin_include_str(args: &[Value]) -> RResult<Value> {
     match args {
-        [Value::String(path)] => std::fs::read_to_string(path.as_str())
-            .map(Value::String)
-            .map_err(|e| format!("include_str: cannot read '{}': {}", path, e)),
+        [Value::String(path)] => {
+            crate::fs_sandbox::check("include_str", path, false)?;
+            std::fs::read_to_string(path.as_str())
+                .map(Value::String)
+                .map_err(|e| format!("include_str: cannot read '{}': {}", path, e))
+        }
         [other] => Err(format!(
             "include_str: expected a string path, got {:?}",
             other
@@ -14236,6 +13488,7 @@ fn builtin_include_str(args: &[Value]) -> RResult<Value> {
 fn builtin_include_bytes(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(path)] => {
+            crate::fs_sandbox::check("include_bytes", path, false)?;
             let bytes = std::fs::read(path.as_str())
                 .map_err(|e| format!("include_bytes: cannot read '{}': {}", path, e))?;
             Ok(Value::Array(
@@ -14258,7 +13511,7 @@ fn builtin_include_bytes(args: &[Value]) -> RResult<Value> {
 /// blocking on real stdin (per the RES-144 acceptance criterion
 /// "stubbed stdin via `std::io::Cursor` injected through a small
 /// trait"; here `BufRead` is that trait).
-fn do_input<R: std::io::BufRead>(reader: &mut R, prompt: &str) -> RResult<Value> {
+fn do_input<R: std::io::BufRead + ?Sized>(reader: &mut R, prompt: &str) -> RResult<Value> {
     if !prompt.is_empty() {
         crate::output_sink::write_str(prompt);
         // Flush so a prompt without a trailing newline appears
@@ -14269,23 +13522,55 @@ fn do_input<R: std::io::BufRead>(reader: &mut R, prompt: &str) -> RResult<Value>
     match reader.read_line(&mut line) {
         Ok(0) => Ok(Value::String(String::new())), // EOF
         Ok(_) => {
-            // Strip the trailing `\n` (always present on non-EOF)
-            // and an optional `\r` for CRLF line endings.
-            if line.ends_with('\n') {
-                line.pop();
-                if line.ends_with('\r') {
-                    line.pop();
-                }
-            }
+            strip_line_ending(&mut line);
             Ok(Value::String(line))
         }
         Err(e) => Err(format!("input: stdin read failed: {}", e)),
     }
 }
 
+/// Strip the trailing `\n` (always present unless the input ended
+/// mid-line) and an optional `\r` for CRLF line endings.
+fn strip_line_ending(line: &mut String) {
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+}
+
+/// Core of `read_line()` / `read_int()`, generic over `BufRead` for
+/// the same reason as `do_input`.
+fn do_read_line<R: std::io::BufRead + ?Sized>(reader: &mut R, builtin: &str) -> RResult<String> {
+    let mut line = String::new();
+    match reader.read_line(&mut line) {
+        Ok(0) => Err(format!("{}: EndOfInput: stdin is closed", builtin)),
+        Ok(_) => {
+            strip_line_ending(&mut line);
+            Ok(line)
+        }
+        Err(e) => Err(format!("{}: stdin read failed: {}", builtin, e)),
+    }
+}
+
+fn do_read_int<R: std::io::BufRead + ?Sized>(reader: &mut R) -> RResult<Value> {
+    let line = do_read_line(reader, "read_int")?;
+    line.trim().parse::<i64>().map(Value::Int).map_err(|_| {
+        format!(
+            "read_int: InvalidInput: {:?} is not an integer",
+            line.trim()
+        )
+    })
+}
+
 /// `sqrt(x)` — square root, float-returning. Int arg coerced to f64.
+/// A negative argument is a domain error rather than NaN; a NaN
+/// argument still propagates.
 fn builtin_sqrt(args: &[Value]) -> RResult<Value> {
     match args {
+        [Value::Int(i)] if *i < 0 => Err(format!("sqrt: DomainError: negative input {}", i)),
+        [Value::Float(f)] if *f < 0.0 => Err(format!("sqrt: DomainError: negative input {}", f)),
         [Value::Int(i)] => Ok(Value::Float((*i as f64).sqrt())),
         [Value::Float(f)] => Ok(Value::Float(f.sqrt())),
         [other] => Err(format!("sqrt: expected numeric, got {}", other)),
@@ -15398,6 +14683,7 @@ fn builtin_float_from_bits(args: &[Value]) -> RResult<Value> {
 /// pay only the atomic-load cost plus an `Instant::now()` sample.
 /// The epoch is deliberately unspecified and unobservable except
 /// through `clock_ms()`: users get deltas, not absolute times.
+#[cfg(not(target_os = "wasi"))]
 static CLOCK_EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
 
 /// RES-150: SplitMix64 — tiny, deterministic, dependency-free PRNG.
@@ -15432,6 +14718,7 @@ fn seed_rng(seed: u64) {
 /// RES-150: when the CLI didn't pass `--seed`, seed from the
 /// monotonic ms clock so repeat runs differ. The seed is logged
 /// so the user can pin it via `--seed <N>` on the next run.
+#[cfg(not(target_os = "wasi"))]
 fn seed_rng_from_clock() -> u64 {
     let epoch = CLOCK_EPOCH.get_or_init(std::time::Instant::now);
     let ns = std::time::Instant::now().duration_since(*epoch).as_nanos() as u64;
@@ -15450,6 +14737,18 @@ fn seed_rng_from_clock() -> u64 {
     seed
 }
 
+/// WASI has no process ids (`std::process::id` panics there), but it
+/// does have `random_get`, which beats any clock as a seed.
+#[cfg(target_os = "wasi")]
+fn seed_rng_from_clock() -> u64 {
+    let mut bytes = [0u8; 8];
+    let _ = getrandom::getrandom(&mut bytes);
+    let seed =
+        (u64::from_le_bytes(bytes) ^ host_clock::wall_clock_since_epoch().as_nanos() as u64) | 1;
+    seed_rng(seed);
+    seed
+}
+
 /// RES-150: SplitMix64 step. See https://prng.di.unimi.it/splitmix64.c
 /// — the three-constant finalizer is the canonical mix.
 fn splitmix64_next() -> u64 {
@@ -15773,7 +15072,7 @@ fn builtin_split(args: &[Value]) -> RResult<Value> {
                     .map(|p| Value::String(p.to_string()))
                     .collect()
             };
-            Ok(Value::Array(parts))
+            Ok(Value::Array(parts.into()))
         }
         [a, b] => Err(format!(
             "split: expected (string, string), got ({:?}, {:?})",
@@ -15833,11 +15132,14 @@ fn builtin_string_split_last(args: &[Value]) -> RResult<Value> {
                 return Err("string_split_last: separator must not be empty".to_string());
             }
             match s.rfind(sep.as_str()) {
-                Some(idx) => Ok(Value::Array(vec![
-                    Value::String(s[..idx].to_string()),
-                    Value::String(s[idx + sep.len()..].to_string()),
-                ])),
-                None => Ok(Value::Array(vec![Value::String(s.clone())])),
+                Some(idx) => Ok(Value::Array(
+                    vec![
+                        Value::String(s[..idx].to_string()),
+                        Value::String(s[idx + sep.len()..].to_string()),
+                    ]
+                    .into(),
+                )),
+                None => Ok(Value::Array(vec![Value::String(s.clone())].into())),
             }
         }
         [a, b] => Err(format!(
@@ -15860,7 +15162,8 @@ fn builtin_trim(args: &[Value]) -> RResult<Value> {
     }
 }
 
-/// `contains(haystack, needle)` — substring test.
+/// `contains(haystack, needle)` — substring test, or element membership
+/// when `haystack` is an array (see `array_contains`).
 fn builtin_contains(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(h), Value::String(n)] => Ok(Value::Bool(h.contains(n.as_str()))),
@@ -15880,8 +15183,11 @@ fn builtin_contains(args: &[Value]) -> RResult<Value> {
             };
             Ok(Value::Bool(within))
         }
+        [Value::Array(_), _] => {
+            builtin_array_contains(args).map_err(|e| e.replace("array_contains", "contains"))
+        }
         [a, b] => Err(format!(
-            "contains: expected (string, string) or (range, int), got ({:?}, {:?})",
+            "contains: expected (string, string), (array, any) or (range, int), got ({:?}, {:?})",
             a, b
         )),
         _ => Err(format!(
@@ -15929,15 +15235,20 @@ fn builtin_string_reverse(args: &[Value]) -> RResult<Value> {
 }
 
 /// RES-414: `index_of(s, sub)` — first byte index of `sub` in `s`, or
-/// -1 if not found. Empty `sub` returns 0 (matches str::find).
+/// -1 if not found. Empty `sub` returns 0 (matches str::find). On an
+/// array, `index_of(xs, x)` is the first index of an element equal to
+/// `x` (see `array_index_of`).
 fn builtin_index_of(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(s), Value::String(sub)] => match s.find(sub.as_str()) {
             Some(idx) => Ok(Value::Int(idx as i64)),
             None => Ok(Value::Int(-1)),
         },
+        [Value::Array(_), _] => {
+            builtin_array_index_of(args).map_err(|e| e.replace("array_index_of", "index_of"))
+        }
         [a, b] => Err(format!(
-            "index_of: expected (string, string), got ({}, {})",
+            "index_of: expected (string, string) or (array, any), got ({}, {})",
             a, b
         )),
         _ => Err(format!(
@@ -16597,8 +15908,8 @@ fn builtin_array_split_at(args: &[Value]) -> RResult<Value> {
             }
             let split = (*n as usize).min(items.len());
             Ok(Value::Tuple(vec![
-                Value::Array(items[..split].to_vec()),
-                Value::Array(items[split..].to_vec()),
+                Value::Array(items[..split].to_vec().into()),
+                Value::Array(items[split..].to_vec().into()),
             ]))
         }
         [a, b] => Err(format!(
@@ -16662,7 +15973,7 @@ fn builtin_array_interleave(args: &[Value]) -> RResult<Value> {
                     (None, None) => break,
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_interleave: expected (array, array), got ({}, {})",
@@ -16691,7 +16002,7 @@ fn builtin_array_intersperse(args: &[Value]) -> RResult<Value> {
                 }
                 out.push(v.clone());
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, _] => Err(format!("array_intersperse: expected array, got {}", a)),
         _ => Err(format!(
@@ -16916,9 +16227,9 @@ fn builtin_array_chunk(args: &[Value]) -> RResult<Value> {
             let chunk_size = *n as usize;
             let chunks: Vec<Value> = items
                 .chunks(chunk_size)
-                .map(|c| Value::Array(c.to_vec()))
+                .map(|c| Value::Array(c.to_vec().into()))
                 .collect();
-            Ok(Value::Array(chunks))
+            Ok(Value::Array(chunks.into()))
         }
         [a, b] => Err(format!(
             "array_chunk: expected (array, int), got ({}, {})",
@@ -17133,7 +16444,7 @@ fn builtin_array_repeat(args: &[Value]) -> RResult<Value> {
             for _ in 0..len {
                 out.push(elem.clone());
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [_, b] => Err(format!("array_repeat: expected count to be int, got {}", b)),
         _ => Err(format!(
@@ -17166,7 +16477,7 @@ fn builtin_array_range(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::Int(start), Value::Int(end)] => {
             if start >= end {
-                return Ok(Value::Array(Vec::new()));
+                return Ok(Value::Array(Vec::new().into()));
             }
             const MAX_RANGE: i128 = 1_000_000_000;
             let span = (*end as i128) - (*start as i128);
@@ -17180,7 +16491,7 @@ fn builtin_array_range(args: &[Value]) -> RResult<Value> {
             for i in *start..*end {
                 out.push(Value::Int(i));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_range: expected (int, int), got ({}, {})",
@@ -17259,9 +16570,9 @@ fn builtin_array_slice(args: &[Value]) -> RResult<Value> {
             let lo_u = (lo_i.clamp(0, len)) as usize;
             let hi_u = (hi_i.clamp(0, len)) as usize;
             if lo_u >= hi_u {
-                return Ok(Value::Array(Vec::new()));
+                return Ok(Value::Array(Vec::new().into()));
             }
-            Ok(Value::Array(items[lo_u..hi_u].to_vec()))
+            Ok(Value::Array(items[lo_u..hi_u].to_vec().into()))
         }
         (Value::String(s), Value::Int(lo_raw), Value::Bool(inclusive)) => {
             let chars: Vec<char> = s.chars().collect();
@@ -17299,7 +16610,7 @@ fn builtin_array_zip(args: &[Value]) -> RResult<Value> {
             for i in 0..n {
                 out.push(Value::Tuple(vec![a[i].clone(), b[i].clone()]));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_zip: expected (array, array), got ({}, {})",
@@ -17335,8 +16646,8 @@ fn builtin_array_unzip(args: &[Value]) -> RResult<Value> {
                 }
             }
             Ok(Value::Tuple(vec![
-                Value::Array(firsts),
-                Value::Array(seconds),
+                Value::Array(firsts.into()),
+                Value::Array(seconds.into()),
             ]))
         }
         [other] => Err(format!("array_unzip: expected array, got {}", other)),
@@ -17547,7 +16858,7 @@ fn builtin_array_unique(args: &[Value]) -> RResult<Value> {
                 }
                 out.push(v.clone());
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_unique: expected array, got {}", other)),
         _ => Err(format!(
@@ -17564,7 +16875,7 @@ fn builtin_to_string(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(s)] => Ok(Value::String(s.clone())),
         [Value::Int(n)] => Ok(Value::String(n.to_string())),
-        [Value::Float(f)] => Ok(Value::String(f.to_string())),
+        [Value::Float(f)] => Ok(Value::String(float_mode::display(*f))),
         [Value::Bool(b)] => Ok(Value::String(b.to_string())),
         // RES-2709: char is a scalar; convert to its one-character string form.
         [Value::Char(c)] => Ok(Value::String(c.to_string())),
@@ -17646,7 +16957,7 @@ fn builtin_array_flatten(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_flatten: expected array, got {}", other)),
         _ => Err(format!(
@@ -17715,7 +17026,7 @@ fn builtin_array_filter_int(args: &[Value]) -> RResult<Value> {
                     out.push(Value::Int(n));
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_filter_int: expected (array, string), got ({}, {})",
@@ -17856,7 +17167,7 @@ fn builtin_array_indices_where(args: &[Value]) -> RResult<Value> {
                     out.push(Value::Int(i as i64));
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_indices_where: expected (array, string), got ({}, {})",
@@ -17893,7 +17204,10 @@ fn builtin_array_partition_int(args: &[Value]) -> RResult<Value> {
                     no.push(Value::Int(n));
                 }
             }
-            Ok(Value::Tuple(vec![Value::Array(yes), Value::Array(no)]))
+            Ok(Value::Tuple(vec![
+                Value::Array(yes.into()),
+                Value::Array(no.into()),
+            ]))
         }
         [a, b] => Err(format!(
             "array_partition_int: expected (array, string), got ({}, {})",
@@ -17944,7 +17258,7 @@ fn builtin_array_take_while_int(args: &[Value]) -> RResult<Value> {
                 }
                 out.push(Value::Int(n));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_take_while_int: expected (array, string), got ({}, {})",
@@ -17979,7 +17293,7 @@ fn builtin_array_drop_while_int(args: &[Value]) -> RResult<Value> {
                 }
                 idx += 1;
             }
-            Ok(Value::Array(items[idx..].to_vec()))
+            Ok(Value::Array(items[idx..].to_vec().into()))
         }
         [a, b] => Err(format!(
             "array_drop_while_int: expected (array, string), got ({}, {})",
@@ -18034,9 +17348,9 @@ fn builtin_array_rest(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::Array(items)] => {
             if items.is_empty() {
-                Ok(Value::Array(vec![]))
+                Ok(Value::Array(vec![].into()))
             } else {
-                Ok(Value::Array(items[1..].to_vec()))
+                Ok(Value::Array(items[1..].to_vec().into()))
             }
         }
         [other] => Err(format!("array_rest: expected array, got {}", other)),
@@ -18052,9 +17366,9 @@ fn builtin_array_init(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::Array(items)] => {
             if items.is_empty() {
-                Ok(Value::Array(vec![]))
+                Ok(Value::Array(vec![].into()))
             } else {
-                Ok(Value::Array(items[..items.len() - 1].to_vec()))
+                Ok(Value::Array(items[..items.len() - 1].to_vec().into()))
             }
         }
         [other] => Err(format!("array_init: expected array, got {}", other)),
@@ -18187,7 +17501,7 @@ fn builtin_array_scan_int(args: &[Value]) -> RResult<Value> {
                 };
                 out.push(Value::Int(acc));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b, c] => Err(format!(
             "array_scan_int: expected (array, int, string), got ({}, {}, {})",
@@ -18238,7 +17552,7 @@ fn builtin_array_zip_with_int(args: &[Value]) -> RResult<Value> {
                 };
                 out.push(Value::Int(r));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b, c] => Err(format!(
             "array_zip_with_int: expected (array, array, string), got ({}, {}, {})",
@@ -18474,7 +17788,7 @@ fn builtin_array_group_by_int(args: &[Value]) -> RResult<Value> {
                     Some(p) if p == n => current.push(Value::Int(n)),
                     _ => {
                         if !current.is_empty() {
-                            groups.push(Value::Array(std::mem::take(&mut current)));
+                            groups.push(Value::Array(std::mem::take(&mut current).into()));
                         }
                         current.push(Value::Int(n));
                     }
@@ -18482,9 +17796,9 @@ fn builtin_array_group_by_int(args: &[Value]) -> RResult<Value> {
                 prev = Some(n);
             }
             if !current.is_empty() {
-                groups.push(Value::Array(current));
+                groups.push(Value::Array(current.into()));
             }
-            Ok(Value::Array(groups))
+            Ok(Value::Array(groups.into()))
         }
         [other] => Err(format!("array_group_by_int: expected array, got {}", other)),
         _ => Err(format!(
@@ -18552,7 +17866,7 @@ fn builtin_array_dedup(args: &[Value]) -> RResult<Value> {
                     None => out.push(v.clone()),
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_dedup: expected array, got {}", other)),
         _ => Err(format!(
@@ -18580,7 +17894,7 @@ fn builtin_array_remove_all(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, _] => Err(format!("array_remove_all: expected array, got {}", a)),
         _ => Err(format!(
@@ -18615,7 +17929,7 @@ fn builtin_array_remove(args: &[Value]) -> RResult<Value> {
                 }
                 out.push(v.clone());
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, _] => Err(format!("array_remove: expected array, got {}", a)),
         _ => Err(format!(
@@ -18764,7 +18078,7 @@ fn builtin_string_to_bytes(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(s)] => {
             let out: Vec<Value> = s.bytes().map(|b| Value::Int(b as i64)).collect();
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("string_to_bytes: expected string, got {}", other)),
         _ => Err(format!(
@@ -18825,7 +18139,7 @@ fn builtin_array_pairs(args: &[Value]) -> RResult<Value> {
             for w in items.windows(2) {
                 out.push(Value::Tuple(vec![w[0].clone(), w[1].clone()]));
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_pairs: expected array, got {}", other)),
         _ => Err(format!(
@@ -18964,7 +18278,7 @@ fn builtin_array_cycle(args: &[Value]) -> RResult<Value> {
             for _ in 0..count {
                 out.extend_from_slice(items);
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_cycle: expected (array, int), got ({}, {})",
@@ -19003,69 +18317,6 @@ fn builtin_string_capitalize(args: &[Value]) -> RResult<Value> {
     }
 }
 
-/// RES-456: `array_rotate_left(arr, n)` — shift elements n positions
-/// left, wrapping around. n is reduced modulo len. Negative n errors.
-fn builtin_array_rotate_left(args: &[Value]) -> RResult<Value> {
-    match args {
-        [Value::Array(items), Value::Int(n)] => {
-            if *n < 0 {
-                return Err(format!(
-                    "array_rotate_left: count must be non-negative, got {}",
-                    n
-                ));
-            }
-            if items.is_empty() {
-                return Ok(Value::Array(vec![]));
-            }
-            let k = (*n as usize) % items.len();
-            let mut out = Vec::with_capacity(items.len());
-            out.extend_from_slice(&items[k..]);
-            out.extend_from_slice(&items[..k]);
-            Ok(Value::Array(out))
-        }
-        [a, b] => Err(format!(
-            "array_rotate_left: expected (array, int), got ({}, {})",
-            a, b
-        )),
-        _ => Err(format!(
-            "array_rotate_left: expected 2 arguments, got {}",
-            args.len()
-        )),
-    }
-}
-
-/// RES-456: `array_rotate_right(arr, n)` — shift elements n positions
-/// right, wrapping around. n is reduced modulo len. Negative n errors.
-fn builtin_array_rotate_right(args: &[Value]) -> RResult<Value> {
-    match args {
-        [Value::Array(items), Value::Int(n)] => {
-            if *n < 0 {
-                return Err(format!(
-                    "array_rotate_right: count must be non-negative, got {}",
-                    n
-                ));
-            }
-            if items.is_empty() {
-                return Ok(Value::Array(vec![]));
-            }
-            let k = (*n as usize) % items.len();
-            let split = items.len() - k;
-            let mut out = Vec::with_capacity(items.len());
-            out.extend_from_slice(&items[split..]);
-            out.extend_from_slice(&items[..split]);
-            Ok(Value::Array(out))
-        }
-        [a, b] => Err(format!(
-            "array_rotate_right: expected (array, int), got ({}, {})",
-            a, b
-        )),
-        _ => Err(format!(
-            "array_rotate_right: expected 2 arguments, got {}",
-            args.len()
-        )),
-    }
-}
-
 /// RES-455: `array_window(arr, n)` — sliding contiguous windows of
 /// size `n`. `n` must be > 0; `n > len` returns empty.
 fn builtin_array_window(args: &[Value]) -> RResult<Value> {
@@ -19080,9 +18331,9 @@ fn builtin_array_window(args: &[Value]) -> RResult<Value> {
             let size = *n as usize;
             let windows: Vec<Value> = items
                 .windows(size)
-                .map(|w| Value::Array(w.to_vec()))
+                .map(|w| Value::Array(w.to_vec().into()))
                 .collect();
-            Ok(Value::Array(windows))
+            Ok(Value::Array(windows.into()))
         }
         [a, b] => Err(format!(
             "array_window: expected (array, int), got ({}, {})",
@@ -19272,7 +18523,7 @@ fn array_pad_args(name: &str, args: &[Value]) -> RResult<(Vec<Value>, usize, Val
             if *n > MAX_PAD {
                 return Err(format!("{}: width {} too large (max {})", name, n, MAX_PAD));
             }
-            Ok((items.clone(), *n as usize, fill.clone()))
+            Ok((items.to_vec(), *n as usize, fill.clone()))
         }
         [a, b, _] => Err(format!(
             "{}: expected (array, int, _), got ({}, {}, _)",
@@ -19290,7 +18541,7 @@ fn array_pad_args(name: &str, args: &[Value]) -> RResult<(Vec<Value>, usize, Val
 fn builtin_array_pad_left(args: &[Value]) -> RResult<Value> {
     let (mut items, target, fill) = array_pad_args("array_pad_left", args)?;
     if items.len() >= target {
-        return Ok(Value::Array(items));
+        return Ok(Value::Array(items.into()));
     }
     let needed = target - items.len();
     let mut out = Vec::with_capacity(target);
@@ -19298,21 +18549,21 @@ fn builtin_array_pad_left(args: &[Value]) -> RResult<Value> {
         out.push(fill.clone());
     }
     out.append(&mut items);
-    Ok(Value::Array(out))
+    Ok(Value::Array(out.into()))
 }
 
 /// RES-449: `array_pad_right(arr, n, fill)` — pad on the right.
 fn builtin_array_pad_right(args: &[Value]) -> RResult<Value> {
     let (mut items, target, fill) = array_pad_args("array_pad_right", args)?;
     if items.len() >= target {
-        return Ok(Value::Array(items));
+        return Ok(Value::Array(items.into()));
     }
     let needed = target - items.len();
     items.reserve(needed);
     for _ in 0..needed {
         items.push(fill.clone());
     }
-    Ok(Value::Array(items))
+    Ok(Value::Array(items.into()))
 }
 
 /// RES-448: `array_position(arr, x, start)` — first index `i >= start`
@@ -19391,10 +18642,13 @@ fn builtin_string_split_at(args: &[Value]) -> RResult<Value> {
                 ));
             }
             let (left, right) = s.split_at(i);
-            Ok(Value::Array(vec![
-                Value::String(left.to_string()),
-                Value::String(right.to_string()),
-            ]))
+            Ok(Value::Array(
+                vec![
+                    Value::String(left.to_string()),
+                    Value::String(right.to_string()),
+                ]
+                .into(),
+            ))
         }
         [a, b] => Err(format!(
             "string_split_at: expected (string, int), got ({}, {})",
@@ -19473,7 +18727,7 @@ fn builtin_string_find_all(args: &[Value]) -> RResult<Value> {
                 .match_indices(sub.as_str())
                 .map(|(i, _)| Value::Int(i as i64))
                 .collect();
-            Ok(Value::Array(indices))
+            Ok(Value::Array(indices.into()))
         }
         [a, b] => Err(format!(
             "string_find_all: expected (string, string), got ({}, {})",
@@ -19582,57 +18836,62 @@ fn builtin_array_shuffle(args: &[Value]) -> RResult<Value> {
 /// RES-443: `array_sort_desc(arr)` — descending sort companion to
 /// RES-422's `array_sort`. Same input contract.
 fn builtin_array_sort_desc(args: &[Value]) -> RResult<Value> {
-    match args {
-        [Value::Array(items)] => {
-            let mut nums: Vec<i64> = Vec::with_capacity(items.len());
-            for v in items {
-                match v {
-                    Value::Int(n) => nums.push(*n),
-                    other => {
-                        return Err(format!(
-                            "array_sort_desc: expected all int elements, got {}",
-                            other
-                        ));
-                    }
-                }
-            }
-            nums.sort_unstable_by(|a, b| b.cmp(a));
-            Ok(Value::Array(nums.into_iter().map(Value::Int).collect()))
-        }
-        [other] => Err(format!("array_sort_desc: expected array, got {}", other)),
-        _ => Err(format!(
-            "array_sort_desc: expected 1 argument, got {}",
-            args.len()
-        )),
-    }
+    sort_array("array_sort_desc", args, true)
 }
 
-/// RES-422: `array_sort(arr)` — returns a new integer array sorted
-/// ascending. Non-int elements raise a typed error. Empty array → empty.
+/// RES-422: `array_sort(arr)` — returns a new array sorted ascending.
+/// The elements must all be ints, all floats, or all strings (byte
+/// order); anything else raises a typed error. Empty array → empty.
 fn builtin_array_sort(args: &[Value]) -> RResult<Value> {
-    match args {
-        [Value::Array(items)] => {
-            let mut nums: Vec<i64> = Vec::with_capacity(items.len());
-            for v in items {
-                match v {
-                    Value::Int(n) => nums.push(*n),
-                    other => {
-                        return Err(format!(
-                            "array_sort: expected all int elements, got {}",
-                            other
-                        ));
-                    }
-                }
-            }
-            nums.sort();
-            Ok(Value::Array(nums.into_iter().map(Value::Int).collect()))
+    sort_array("array_sort", args, false)
+}
+
+/// Stable sort shared by `array_sort` / `array_sort_desc`. A NaN has
+/// no place in the order, so it is an error rather than a silent
+/// misplacement.
+fn sort_array(name: &str, args: &[Value], descending: bool) -> RResult<Value> {
+    let items = match args {
+        [Value::Array(items)] => items,
+        [other] => return Err(format!("{}: expected array, got {}", name, other)),
+        _ => {
+            return Err(format!("{}: expected 1 argument, got {}", name, args.len()));
         }
-        [other] => Err(format!("array_sort: expected array, got {}", other)),
-        _ => Err(format!(
-            "array_sort: expected 1 argument, got {}",
-            args.len()
-        )),
+    };
+    if let Some(i) = items
+        .iter()
+        .position(|v| matches!(v, Value::Float(f) if f.is_nan()))
+    {
+        return Err(format!(
+            "{}: InvalidArgument: NaN at index {} cannot be ordered",
+            name, i
+        ));
     }
+    let kind = |v: &Value| match v {
+        Value::Int(_) => Some("int"),
+        Value::Float(_) => Some("float"),
+        Value::String(_) => Some("string"),
+        _ => None,
+    };
+    let first = items.first().and_then(kind);
+    for v in items {
+        if kind(v).is_none() || kind(v) != first {
+            return Err(format!(
+                "{}: expected all int elements, all float elements, or all string elements, got {}",
+                name, v
+            ));
+        }
+    }
+    let mut sorted = items.clone();
+    sorted.sort_by(|a, b| {
+        let ord = match (a, b) {
+            (Value::Int(x), Value::Int(y)) => x.cmp(y),
+            (Value::Float(x), Value::Float(y)) => x.total_cmp(y),
+            (Value::String(x), Value::String(y)) => x.cmp(y),
+            _ => std::cmp::Ordering::Equal,
+        };
+        if descending { ord.reverse() } else { ord }
+    });
+    Ok(Value::Array(sorted))
 }
 
 /// RES-421: `array_take(arr, n)` — first `n` elements of `arr`. Returns
@@ -19644,7 +18903,7 @@ fn builtin_array_take(args: &[Value]) -> RResult<Value> {
                 return Err(format!("array_take: count must be non-negative, got {}", n));
             }
             let take = (*n as usize).min(items.len());
-            Ok(Value::Array(items[..take].to_vec()))
+            Ok(Value::Array(items[..take].to_vec().into()))
         }
         [a, b] => Err(format!(
             "array_take: expected (array, int), got ({}, {})",
@@ -19657,29 +18916,6 @@ fn builtin_array_take(args: &[Value]) -> RResult<Value> {
     }
 }
 
-/// RES-514: `array_step(arr, n)` — pick every `n`th element starting
-/// at index 0. Useful for downsampling and decimation. `n` must be
-/// positive.
-fn builtin_array_step(args: &[Value]) -> RResult<Value> {
-    match args {
-        [Value::Array(items), Value::Int(n)] => {
-            if *n <= 0 {
-                return Err(format!("array_step: step must be positive, got {}", n));
-            }
-            let step = *n as usize;
-            Ok(Value::Array(items.iter().step_by(step).cloned().collect()))
-        }
-        [a, b] => Err(format!(
-            "array_step: expected (array, int), got ({}, {})",
-            a, b
-        )),
-        _ => Err(format!(
-            "array_step: expected 2 arguments, got {}",
-            args.len()
-        )),
-    }
-}
-
 /// RES-421: `array_drop(arr, n)` — `arr` with the first `n` elements
 /// removed. Returns empty if `n >= len`. Negative `n` is an error.
 fn builtin_array_drop(args: &[Value]) -> RResult<Value> {
@@ -19689,7 +18925,7 @@ fn builtin_array_drop(args: &[Value]) -> RResult<Value> {
                 return Err(format!("array_drop: count must be non-negative, got {}", n));
             }
             let drop = (*n as usize).min(items.len());
-            Ok(Value::Array(items[drop..].to_vec()))
+            Ok(Value::Array(items[drop..].to_vec().into()))
         }
         [a, b] => Err(format!(
             "array_drop: expected (array, int), got ({}, {})",
@@ -19716,7 +18952,7 @@ fn builtin_array_take_last(args: &[Value]) -> RResult<Value> {
             }
             let take = (*n as usize).min(items.len());
             let start = items.len() - take;
-            Ok(Value::Array(items[start..].to_vec()))
+            Ok(Value::Array(items[start..].to_vec().into()))
         }
         [a, b] => Err(format!(
             "array_take_last: expected (array, int), got ({}, {})",
@@ -19745,7 +18981,7 @@ fn builtin_array_drop_last(args: &[Value]) -> RResult<Value> {
             }
             let drop = (*n as usize).min(items.len());
             let end = items.len() - drop;
-            Ok(Value::Array(items[..end].to_vec()))
+            Ok(Value::Array(items[..end].to_vec().into()))
         }
         [a, b] => Err(format!(
             "array_drop_last: expected (array, int), got ({}, {})",
@@ -19768,7 +19004,7 @@ fn builtin_array_concat3(args: &[Value]) -> RResult<Value> {
             out.extend_from_slice(a);
             out.extend_from_slice(b);
             out.extend_from_slice(c);
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b, c] => Err(format!(
             "array_concat3: expected (array, array, array), got ({}, {}, {})",
@@ -19789,7 +19025,7 @@ fn builtin_array_concat(args: &[Value]) -> RResult<Value> {
             let mut out = Vec::with_capacity(a.len() + b.len());
             out.extend_from_slice(a);
             out.extend_from_slice(b);
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_concat: expected (array, array), got ({}, {})",
@@ -19972,7 +19208,7 @@ fn builtin_array_intersect(args: &[Value]) -> RResult<Value> {
                     out.push(v.clone());
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_intersect: expected (array, array), got ({}, {})",
@@ -19996,7 +19232,7 @@ fn builtin_array_diff(args: &[Value]) -> RResult<Value> {
                     out.push(v.clone());
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_diff: expected (array, array), got ({}, {})",
@@ -20022,7 +19258,7 @@ fn builtin_array_union(args: &[Value]) -> RResult<Value> {
                     out.push(v.clone());
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b] => Err(format!(
             "array_union: expected (array, array), got ({}, {})",
@@ -20082,7 +19318,7 @@ fn builtin_array_index_of_all(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, _] => Err(format!("array_index_of_all: expected array, got {}", a)),
         _ => Err(format!(
@@ -20305,7 +19541,7 @@ fn builtin_array_cumprod_int(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_cumprod_int: expected array, got {}", other)),
         _ => Err(format!(
@@ -20340,7 +19576,7 @@ fn builtin_array_cummin_int(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_cummin_int: expected array, got {}", other)),
         _ => Err(format!(
@@ -20376,7 +19612,7 @@ fn builtin_array_cummax_int(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_cummax_int: expected array, got {}", other)),
         _ => Err(format!(
@@ -20413,7 +19649,7 @@ fn builtin_array_cumsum_int(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_cumsum_int: expected array, got {}", other)),
         _ => Err(format!(
@@ -20537,7 +19773,7 @@ fn builtin_array_abs_int(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_abs_int: expected array, got {}", other)),
         _ => Err(format!(
@@ -20565,7 +19801,7 @@ fn builtin_array_signum_int(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [other] => Err(format!("array_signum_int: expected array, got {}", other)),
         _ => Err(format!(
@@ -20599,7 +19835,7 @@ fn builtin_array_clamp_int(args: &[Value]) -> RResult<Value> {
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a, b, c] => Err(format!(
             "array_clamp_int: expected (array, int, int), got ({}, {}, {})",
@@ -20621,7 +19857,7 @@ fn builtin_array_diff_consec_int(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::Array(items)] => {
             if items.len() < 2 {
-                return Ok(Value::Array(Vec::new()));
+                return Ok(Value::Array(Vec::new().into()));
             }
             let mut nums: Vec<i64> = Vec::with_capacity(items.len());
             for v in items {
@@ -20646,7 +19882,7 @@ fn builtin_array_diff_consec_int(args: &[Value]) -> RResult<Value> {
                 })?;
                 diffs.push(Value::Int(d_i64));
             }
-            Ok(Value::Array(diffs))
+            Ok(Value::Array(diffs.into()))
         }
         [other] => Err(format!(
             "array_diff_consec_int: expected array, got {}",
@@ -21026,27 +20262,27 @@ fn builtin_repeat(args: &[Value]) -> RResult<Value> {
     }
 }
 
-/// RES-339: `parse_int(s)` — parse a base-10 signed integer from `s`,
+/// RES-339: `parse_int(s)` — parse a signed integer from `s`,
 /// returning a `Result<Int, String>`. Leading/trailing ASCII whitespace
-/// is ignored (matches the conventional `s.trim().parse()` shape). On
-/// any parse failure (empty, non-numeric, overflow) returns
-/// `Err(message)` rather than panicking — this is the contract that
-/// makes the builtin safe for untrusted input on embedded targets.
+/// is ignored (matches the conventional `s.trim().parse()` shape).
+/// Accepts the spellings an integer literal may use: an optional sign,
+/// `_` between digits, and `0x` / `0o` / `0b` prefixes. On any parse
+/// failure (empty, non-numeric, overflow) returns `Err(message)`
+/// naming which of the three it was, rather than panicking — this is
+/// the contract that makes the builtin safe for untrusted input on
+/// embedded targets.
 fn builtin_parse_int(args: &[Value]) -> RResult<Value> {
     match args {
-        [Value::String(s)] => match s.trim().parse::<i64>() {
-            Ok(n) => Ok(Value::Result {
+        [Value::String(s)] => Ok(match parse_int_text(s.trim()) {
+            Ok(n) => Value::Result {
                 ok: true,
                 payload: Box::new(Value::Int(n)),
-            }),
-            Err(e) => Ok(Value::Result {
+            },
+            Err(why) => Value::Result {
                 ok: false,
-                payload: Box::new(Value::String(format!(
-                    "parse_int: invalid integer {:?}: {}",
-                    s, e
-                ))),
-            }),
-        },
+                payload: Box::new(Value::String(format!("parse_int: {:?} {}", s, why))),
+            },
+        }),
         [other] => Err(format!("parse_int: expected string, got {}", other)),
         _ => Err(format!(
             "parse_int: expected 1 argument, got {}",
@@ -21055,6 +20291,43 @@ fn builtin_parse_int(args: &[Value]) -> RResult<Value> {
     }
 }
 
+/// Parse `text` as an integer literal would be read; `Err` says why not.
+fn parse_int_text(text: &str) -> Result<i64, &'static str> {
+    if text.is_empty() {
+        return Err("is empty");
+    }
+    let (negative, unsigned) = match text.as_bytes()[0] {
+        b'-' => (true, &text[1..]),
+        b'+' => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let (radix, digits) = match unsigned.get(..2) {
+        Some("0x" | "0X") => (16, &unsigned[2..]),
+        Some("0o" | "0O") => (8, &unsigned[2..]),
+        Some("0b" | "0B") => (2, &unsigned[2..]),
+        _ => (10, unsigned),
+    };
+    // `_` may only separate digits, as in literals.
+    let bytes = digits.as_bytes();
+    let well_formed = !bytes.is_empty()
+        && bytes[0] != b'_'
+        && bytes[bytes.len() - 1] != b'_'
+        && !digits.contains("__")
+        && bytes
+            .iter()
+            .all(|&b| b == b'_' || (b as char).is_digit(radix));
+    if !well_formed {
+        return Err("is not an integer");
+    }
+    let magnitude: String = digits.chars().filter(|&c| c != '_').collect();
+    let signed = if negative {
+        format!("-{}", magnitude)
+    } else {
+        magnitude
+    };
+    i64::from_str_radix(&signed, radix).map_err(|_| "is out of range for int")
+}
+
 /// RES-529: `parse_int_or(s, default)` — non-erroring base-10 parse.
 /// Returns the parsed integer or `default` if `s` is not a valid
 /// base-10 integer (empty, non-numeric, overflow, etc.). Trims
@@ -21099,24 +20372,38 @@ fn builtin_parse_float_or(args: &[Value]) -> RResult<Value> {
 
 /// RES-339: `parse_float(s)` — parse a 64-bit float from `s`, returning
 /// a `Result<Float, String>`. Leading/trailing ASCII whitespace is
-/// ignored. NaN and infinity strings (`"NaN"`, `"inf"`, `"-inf"`) parse
-/// successfully — this matches Rust's `f64::from_str` semantics. Any
-/// other failure returns `Err(message)`; never panics.
+/// ignored, and `_` may separate digits as in literals. NaN and
+/// infinity strings (`"NaN"`, `"inf"`, `"-inf"`) parse successfully —
+/// this matches Rust's `f64::from_str` semantics. Any other failure
+/// returns `Err(message)`; never panics.
 fn builtin_parse_float(args: &[Value]) -> RResult<Value> {
     match args {
-        [Value::String(s)] => match s.trim().parse::<f64>() {
-            Ok(f) => Ok(Value::Result {
-                ok: true,
-                payload: Box::new(Value::Float(f)),
-            }),
-            Err(e) => Ok(Value::Result {
-                ok: false,
-                payload: Box::new(Value::String(format!(
-                    "parse_float: invalid float {:?}: {}",
-                    s, e
-                ))),
-            }),
-        },
+        [Value::String(s)] => {
+            let text = s.trim();
+            let separated = |i: usize| {
+                let b = text.as_bytes();
+                i > 0 && i + 1 < b.len() && b[i - 1].is_ascii_digit() && b[i + 1].is_ascii_digit()
+            };
+            let why = if text.is_empty() {
+                Err("is empty")
+            } else if text.char_indices().any(|(i, c)| c == '_' && !separated(i)) {
+                Err("is not a number")
+            } else {
+                text.replace('_', "")
+                    .parse::<f64>()
+                    .map_err(|_| "is not a number")
+            };
+            Ok(match why {
+                Ok(f) => Value::Result {
+                    ok: true,
+                    payload: Box::new(Value::Float(f)),
+                },
+                Err(why) => Value::Result {
+                    ok: false,
+                    payload: Box::new(Value::String(format!("parse_float: {:?} {}", s, why))),
+                },
+            })
+        }
         [other] => Err(format!("parse_float: expected string, got {}", other)),
         _ => Err(format!(
             "parse_float: expected 1 argument, got {}",
@@ -21254,8 +20541,11 @@ fn builtin_pad_right(args: &[Value]) -> RResult<Value> {
     }
 }
 
-/// RES-145 / RES-404: `format(fmt, args)` — interpolate an array of
-/// values into a format string with optional printf-style specifiers.
+/// RES-145 / RES-404: `format(fmt, args...)` — interpolate values into
+/// a format string with optional printf-style specifiers. The values
+/// follow the template as separate arguments; the older form that
+/// passes them as one array, `format(fmt, [a, b])`, still works, so a
+/// lone array argument is spread (wrap it, `[xs]`, to print it whole).
 ///
 /// Grammar:
 ///
@@ -21271,6 +20561,11 @@ fn builtin_pad_right(args: &[Value]) -> RResult<Value> {
 ///   `.precision` is digit count after the decimal for floats and
 ///   truncation length for strings. `type` is `x` / `X` (hex), `b`
 ///   (binary), `o` (octal) — numbers only.
+/// - `{name}` / `{name:spec}` take the named argument `name: value`.
+///   Named placeholders are resolved before the program runs (see
+///   `format_builtin::lower_named_args`), which rewrites them to `{}`
+///   / `{:spec}` in place, so they only reach this function from a
+///   template built at runtime — and are an error there.
 /// - `{{` / `}}` escape to a literal `{` / `}`.
 /// - Any other use of `{` / `}` (positional indices, unsupported
 ///   specifier characters) is a runtime error.
@@ -21278,19 +20573,15 @@ fn builtin_pad_right(args: &[Value]) -> RResult<Value> {
 ///   args) is a runtime error.
 fn builtin_format(args: &[Value]) -> RResult<Value> {
     let (fmt, pool) = match args {
-        [Value::String(f), Value::Array(a)] => (f, a),
-        [a, b] => {
+        [Value::String(f), Value::Array(a)] => (f, a.as_slice()),
+        [Value::String(f), rest @ ..] => (f, rest),
+        [other, ..] => {
             return Err(format!(
-                "format: expected (string, array), got ({:?}, {:?})",
-                a, b
-            ));
-        }
-        many => {
-            return Err(format!(
-                "format: expected 2 arguments (fmt, args), got {}",
-                many.len()
+                "format: expected a string template, got {:?}",
+                other
             ));
         }
+        [] => return Err("format: expected a template and its arguments, got none".to_string()),
     };
 
     let mut out = String::with_capacity(fmt.len());
@@ -21321,6 +20612,12 @@ fn builtin_format(args: &[Value]) -> RResult<Value> {
                     FormatSpec::default()
                 } else if let Some(spec_src) = inner.strip_prefix(':') {
                     parse_format_spec(spec_src)?
+                } else if inner.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+                    let name = inner.split(':').next().unwrap_or(inner);
+                    return Err(format!(
+                        "format: named placeholder `{{{}}}` needs a literal template and a `{}: value` argument",
+                        inner, name
+                    ));
                 } else {
                     return Err(format!(
                         "format: positional indices are not supported — only `{{}}` and `{{:spec}}` (got `{{{}}}`)",
@@ -21643,7 +20940,7 @@ fn builtin_slice(args: &[Value]) -> RResult<Value> {
             }
             let s = *start as usize;
             let e = *end as usize;
-            Ok(Value::Array(items[s..e].to_vec()))
+            Ok(Value::Array(items[s..e].to_vec().into()))
         }
         [a, b, c] => Err(format!(
             "slice: expected (array, int, int), got ({:?}, {:?}, {:?})",
@@ -21690,25 +20987,58 @@ fn builtin_len(args: &[Value]) -> RResult<Value> {
 /// index targets the leaf cell that gets replaced. Bounds errors
 /// name the depth (1-indexed) where the out-of-range access occurred
 /// so users can tell `m[2][0]` (outer) from `m[0][5]` (inner).
-fn assign_at_path(container: Value, path: &[Value], leaf: Value, dim: usize) -> RResult<Value> {
-    let (key, rest) = match path.split_first() {
-        Some(pair) => pair,
-        None => return Ok(leaf),
+///
+/// Writes in place: each level is checked before it is touched, so
+/// on an error `container` still holds what it held before. While
+/// `memory_limit` is measuring, returns the heap bytes the write added
+/// and removed, and each collection on the path keeps its footprint
+/// up to date by that much instead of being measured again.
+fn assign_at_path(
+    container: &mut Value,
+    path: &[Value],
+    leaf: Value,
+    dim: usize,
+) -> RResult<Option<(usize, usize)>> {
+    let Some((key, rest)) = path.split_first() else {
+        let change = memory_limit::measuring().then(|| {
+            (
+                memory_limit::heap_size(&leaf),
+                memory_limit::heap_size(container),
+            )
+        });
+        *container = leaf;
+        return Ok(change);
     };
     match container {
-        Value::Map(mut m) => {
+        Value::Map(m) => {
             let mk = MapKey::from_value(key)
                 .map_err(|e| format!("Invalid map key at dim {}: {}", dim, e))?;
-            if rest.is_empty() {
+            let known = m.known_footprint();
+            let change = if rest.is_empty() {
+                let change = memory_limit::measuring().then(|| {
+                    let removed = m
+                        .get(&mk)
+                        .map_or(0, |old| memory_limit::entry_size(&mk, old));
+                    (memory_limit::entry_size(&mk, &leaf), removed)
+                });
                 m.insert(mk, leaf);
+                change
             } else {
-                let inner = m.remove(&mk).unwrap_or(Value::Void);
-                let updated = assign_at_path(inner, rest, leaf, dim + 1)?;
-                m.insert(mk, updated);
-            }
-            Ok(Value::Map(m))
+                match m.get_mut(&mk) {
+                    Some(inner) => assign_at_path(inner, rest, leaf, dim + 1)?,
+                    None => {
+                        return Err(format!(
+                            "Cannot index into {} at dim {}",
+                            Value::Void,
+                            dim + 1
+                        ));
+                    }
+                }
+            };
+            m.set_footprint(memory_limit::grown(known, change));
+            Ok(change)
         }
-        Value::Array(mut items) => {
+        Value::Array(items) => {
             let Value::Int(i) = key else {
                 return Err(format!(
                     "Array index must be int at dim {}, got {}",
@@ -21725,15 +21055,10 @@ fn assign_at_path(container: Value, path: &[Value], leaf: Value, dim: usize) ->
                     dim
                 ));
             }
-            let idx = resolved as usize;
-            if rest.is_empty() {
-                items[idx] = leaf;
-            } else {
-                let inner = std::mem::replace(&mut items[idx], Value::Void);
-                let updated = assign_at_path(inner, rest, leaf, dim + 1)?;
-                items[idx] = updated;
-            }
-            Ok(Value::Array(items))
+            let known = items.known_footprint();
+            let change = assign_at_path(&mut items[resolved as usize], rest, leaf, dim + 1)?;
+            items.set_footprint(memory_limit::grown(known, change));
+            Ok(change)
         }
         other => Err(format!("Cannot index into {} at dim {}", other, dim)),
     }
@@ -22315,17 +21640,18 @@ fn builtin_as_uint64(args: &[Value]) -> RResult<Value> {
 /// no_std-clean. Errors surface as runtime diagnostics; the
 /// interpreter wraps them with the call-site span (RES-116).
 ///
-/// Security: the CLI has ambient authority over the filesystem, and
-/// this builtin inherits it with no sandbox. Users running untrusted
-/// Resilient programs should drop them in a chroot / container.
+/// Security: the CLI has ambient authority over the filesystem unless
+/// `--fs` narrows it (see `fs_sandbox`). Users running untrusted
+/// Resilient programs should still drop them in a chroot / container.
 fn builtin_file_read(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(path)] => {
+            fs_sandbox::check("file_read", path, false)?;
             // RES-3877: the wasm playground has no host filesystem;
             // route through the in-memory VFS so file-I/O examples work.
-            #[cfg(not(target_arch = "wasm32"))]
+            #[cfg(not(wasm_web))]
             let read = fs::read(path);
-            #[cfg(target_arch = "wasm32")]
+            #[cfg(wasm_web)]
             let read = file_io::vfs_read(path);
             match read {
                 Ok(bytes) => match String::from_utf8(bytes) {
@@ -22385,16 +21711,53 @@ fn builtin_env(args: &[Value]) -> RResult<Value> {
     }
 }
 
+/// The program's own arguments: whatever follows `--` on the `rz`
+/// command line (`rz monitor.rz -- 75 http://host`). Set once by the
+/// CLI driver before the program runs.
+static SCRIPT_ARGS: std::sync::RwLock<Vec<String>> = std::sync::RwLock::new(Vec::new());
+
+pub(crate) fn set_script_args(args: Vec<String>) {
+    if let Ok(mut guard) = SCRIPT_ARGS.write() {
+        *guard = args;
+    }
+}
+
+/// `args() -> Array<String>` — the arguments passed to the program
+/// after `--`, in order; empty when there are none. The script path
+/// and `rz`'s own flags are not included.
+fn builtin_args(args: &[Value]) -> RResult<Value> {
+    if !args.is_empty() {
+        return Err(format!("args: expected 0 arguments, got {}", args.len()));
+    }
+    let script_args = SCRIPT_ARGS.read().map(|a| a.clone()).unwrap_or_default();
+    Ok(Value::Array(
+        script_args.into_iter().map(Value::String).collect(),
+    ))
+}
+
+/// `exit(code: Int)` — end the program with `code` as its exit status.
+/// A code outside `0..=255` fails with `InvalidArgument`. Unwinds instead of stopping on the spot: every enclosing function
+/// runs its `defer`s first. No `catch` arm matches it and a `live`
+/// block does not retry it.
+fn builtin_exit(args: &[Value]) -> RResult<Value> {
+    match args {
+        [Value::Int(code)] => Err(exit_signal(checked_exit_code("exit", *code)?)),
+        [other] => Err(format!("exit: expected Int code, got {}", other)),
+        _ => Err(format!("exit: expected 1 argument, got {}", args.len())),
+    }
+}
+
 /// RES-143: `file_write(path, contents)` — write-truncate. Returns
 /// `Void`. Errors surface as runtime diagnostics. Same security
 /// posture as `file_read`.
 fn builtin_file_write(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(path), Value::String(contents)] => {
+            fs_sandbox::check("file_write", path, true)?;
             // RES-3877: wasm playground writes land in the in-memory VFS.
-            #[cfg(not(target_arch = "wasm32"))]
+            #[cfg(not(wasm_web))]
             let res = fs::write(path, contents);
-            #[cfg(target_arch = "wasm32")]
+            #[cfg(wasm_web)]
             let res = {
                 file_io::vfs_write(path, contents.as_bytes());
                 Ok::<(), std::io::Error>(())
@@ -22422,7 +21785,7 @@ fn builtin_map_new(args: &[Value]) -> RResult<Value> {
     if !args.is_empty() {
         return Err(format!("map_new: expected 0 arguments, got {}", args.len()));
     }
-    Ok(Value::Map(std::collections::HashMap::new()))
+    Ok(Value::Map(std::collections::HashMap::new().into()))
 }
 
 /// `map_insert(m, k, v)` — insert / overwrite and return the updated
@@ -22513,7 +21876,7 @@ fn builtin_map_keys(args: &[Value]) -> RResult<Value> {
                 (_, MapKey::Str(_)) => std::cmp::Ordering::Greater,
             });
             let out: Vec<Value> = keys.iter().map(|k| k.to_value()).collect();
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a] => Err(format!("map_keys: expected a Map, got {}", a)),
         _ => Err(format!("map_keys: expected 1 argument, got {}", args.len())),
@@ -22565,7 +21928,7 @@ fn builtin_map_values(args: &[Value]) -> RResult<Value> {
                 (_, MapKey::Str(_)) => std::cmp::Ordering::Greater,
             });
             let out: Vec<Value> = entries.iter().map(|(_, v)| (*v).clone()).collect();
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a] => Err(format!("map_values: expected a Map, got {}", a)),
         _ => Err(format!(
@@ -22593,7 +21956,7 @@ fn builtin_hashmap_new(args: &[Value]) -> RResult<Value> {
             args.len()
         ));
     }
-    Ok(Value::Map(std::collections::HashMap::new()))
+    Ok(Value::Map(std::collections::HashMap::new().into()))
 }
 
 /// `hashmap_insert(m, k, v)` — return the map with `k → v` inserted /
@@ -22717,7 +22080,7 @@ fn builtin_hashmap_values(args: &[Value]) -> RResult<Value> {
                 (_, MapKey::Str(_)) => std::cmp::Ordering::Greater,
             });
             let out: Vec<Value> = entries.iter().map(|(_, v)| (*v).clone()).collect();
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a] => Err(format!("hashmap_values: expected a HashMap, got {}", a)),
         _ => Err(format!(
@@ -22744,7 +22107,7 @@ fn builtin_hashmap_keys(args: &[Value]) -> RResult<Value> {
                 (_, MapKey::Str(_)) => std::cmp::Ordering::Greater,
             });
             let out: Vec<Value> = keys.iter().map(|k| k.to_value()).collect();
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a] => Err(format!("hashmap_keys: expected a HashMap, got {}", a)),
         _ => Err(format!(
@@ -23432,7 +22795,7 @@ fn builtin_set_new(args: &[Value]) -> RResult<Value> {
     if !args.is_empty() {
         return Err(format!("set_new: expected 0 arguments, got {}", args.len()));
     }
-    Ok(Value::Set(std::collections::HashSet::new()))
+    Ok(Value::Set(std::collections::HashSet::new().into()))
 }
 
 /// `set_insert(s, x) -> Set` — return the set with `x` added (no-op
@@ -23520,7 +22883,7 @@ fn builtin_set_items(args: &[Value]) -> RResult<Value> {
                 (_, MapKey::Str(_)) => std::cmp::Ordering::Greater,
             });
             let out: Vec<Value> = items.iter().map(|k| k.to_value()).collect();
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a] => Err(format!("set_items: expected a Set, got {}", a)),
         _ => Err(format!(
@@ -23562,7 +22925,7 @@ fn builtin_set_intersection(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::Set(a), Value::Set(b)] => {
             let out: std::collections::HashSet<MapKey> = a.intersection(b).cloned().collect();
-            Ok(Value::Set(out))
+            Ok(Value::Set(out.into()))
         }
         [Value::Set(_), other] => Err(format!(
             "set_intersection: second argument must be a Set, got {}",
@@ -23585,7 +22948,7 @@ fn builtin_set_difference(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::Set(a), Value::Set(b)] => {
             let out: std::collections::HashSet<MapKey> = a.difference(b).cloned().collect();
-            Ok(Value::Set(out))
+            Ok(Value::Set(out.into()))
         }
         [Value::Set(_), other] => Err(format!(
             "set_difference: second argument must be a Set, got {}",
@@ -23670,7 +23033,7 @@ fn builtin_set_symmetric_difference(args: &[Value]) -> RResult<Value> {
         [Value::Set(a), Value::Set(b)] => {
             let out: std::collections::HashSet<MapKey> =
                 a.symmetric_difference(b).cloned().collect();
-            Ok(Value::Set(out))
+            Ok(Value::Set(out.into()))
         }
         [Value::Set(_), other] => Err(format!(
             "set_symmetric_difference: second argument must be a Set, got {}",
@@ -23712,6 +23075,21 @@ thread_local! {
 // don't touch it without flagging a behaviour change.
 pub const DEFAULT_LIVE_MAX_RETRIES: u32 = 3;
 
+// A project can raise or lower that default for its own blocks with
+// `[live] retries = N` in `resilient.toml`; see `project_config`.
+static LIVE_DEFAULT_RETRIES: std::sync::atomic::AtomicU32 =
+    std::sync::atomic::AtomicU32::new(DEFAULT_LIVE_MAX_RETRIES);
+
+/// Retry budget for `live` blocks without a `retries(N)` clause,
+/// process-wide. `DEFAULT_LIVE_MAX_RETRIES` unless a project set one.
+pub fn set_default_live_retries(retries: u32) {
+    LIVE_DEFAULT_RETRIES.store(retries, std::sync::atomic::Ordering::Relaxed);
+}
+
+pub(crate) fn default_live_retries() -> u32 {
+    LIVE_DEFAULT_RETRIES.load(std::sync::atomic::Ordering::Relaxed)
+}
+
 // `LIVE_SLEEP_HOOK` lets tests substitute a deterministic
 // stand-in for `std::thread::sleep` so backoff-schedule tests can
 // assert the *requested* delay sequence without the wall-clock
@@ -23873,6 +23251,7 @@ fn live_retry_block_label(span: span::Span) -> String {
     })
 }
 
+#[cfg(not(wasm_web))]
 fn live_retry_ts_ns() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -23880,6 +23259,13 @@ fn live_retry_ts_ns() -> u64 {
         .unwrap_or(0)
 }
 
+/// wasm: `SystemTime::now()` traps there, so read `host_clock`'s
+/// software wall clock instead.
+#[cfg(wasm_web)]
+fn live_retry_ts_ns() -> u64 {
+    u64::try_from(host_clock::wall_clock_since_epoch().as_nanos()).unwrap_or(u64::MAX)
+}
+
 fn maybe_emit_live_retry_telemetry(block_span: span::Span, retry: usize, error: &str) {
     let need_file = LIVE_LOG_WRITER.with(|w| w.borrow().is_some());
     if !need_file && !resilient_runtime::live_telemetry::has_live_telemetry_backend() {
@@ -23936,6 +23322,47 @@ impl Drop for LiveRetryGuard {
     }
 }
 
+/// The retry counter of every `live` block being run on this thread,
+/// outermost first; empty outside any block.
+pub(crate) fn live_retry_counts() -> Vec<usize> {
+    LIVE_RETRY_STACK.with(|s| s.borrow().clone())
+}
+
+// Deadlines (monotonic nanoseconds) of the enclosing `live ... within`
+// blocks, innermost last. Blocking builtins cap their own timeouts at
+// `live_time_left()` so a request cannot outlive the block's budget.
+thread_local! {
+    static LIVE_DEADLINES: RefCell<Vec<u128>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the deadline `eval_live_block` pushed, on every exit path.
+struct LiveDeadlineGuard;
+
+impl LiveDeadlineGuard {
+    fn enter(deadline: u128) -> Self {
+        LIVE_DEADLINES.with(|d| d.borrow_mut().push(deadline));
+        LiveDeadlineGuard
+    }
+}
+
+impl Drop for LiveDeadlineGuard {
+    fn drop(&mut self) {
+        LIVE_DEADLINES.with(|d| {
+            d.borrow_mut().pop();
+        });
+    }
+}
+
+/// Time left before the nearest enclosing `live ... within` deadline;
+/// `None` outside any timed `live` block.
+pub(crate) fn live_time_left() -> Option<std::time::Duration> {
+    let deadline = LIVE_DEADLINES.with(|d| d.borrow().iter().copied().min())?;
+    let left = deadline.saturating_sub(host_clock::monotonic_nanos());
+    Some(std::time::Duration::from_nanos(
+        u64::try_from(left).unwrap_or(u64::MAX),
+    ))
+}
+
 // --- RES-141: process-wide live-block telemetry counters ---
 //
 // Two `AtomicU32`s that accumulate across the whole `resilient`
@@ -24137,6 +23564,81 @@ fn cell_get(id: i64) -> RResult<Value> {
     })
 }
 
+/// What every cell holds right now. A `live` block takes this at
+/// entry: its bindings are values and roll back with the environment,
+/// but a cell is shared state that outlives the frame, so the block
+/// has to restore its contents separately.
+fn cell_store_snapshot() -> HashMap<i64, Value> {
+    SHARED_CELLS.with(|cells| cells.borrow().clone())
+}
+
+/// Put back the contents of every cell in `snapshot`. Cells created
+/// since stay as they are; nothing from before the snapshot can
+/// reach them once the environment is restored.
+fn cell_store_restore(snapshot: &HashMap<i64, Value>) {
+    SHARED_CELLS.with(|cells| {
+        let mut cells = cells.borrow_mut();
+        for (id, value) in snapshot {
+            cells.insert(*id, value.clone());
+        }
+    })
+}
+
+/// `clone(v)` — an independent copy of `v`. Arrays, maps, structs and
+/// the rest already copy on assignment, so for them this is the same
+/// value; the difference is cells, which `clone` copies into fresh
+/// cells instead of sharing, at any depth.
+fn builtin_clone(args: &[Value]) -> RResult<Value> {
+    match args {
+        [v] => clone_value(v),
+        _ => Err(format!("clone: expected 1 argument, got {}", args.len())),
+    }
+}
+
+fn clone_value(v: &Value) -> RResult<Value> {
+    let all = |items: &[Value]| items.iter().map(clone_value).collect::<RResult<Vec<_>>>();
+    let fields = |fields: &[(String, Value)]| {
+        fields
+            .iter()
+            .map(|(name, v)| Ok((name.clone(), clone_value(v)?)))
+            .collect::<RResult<Vec<_>>>()
+    };
+    Ok(match v {
+        Value::Cell(id) => cell_alloc(clone_value(&cell_get(*id)?)?)?,
+        Value::Array(items) => Value::Array(all(items)?.into()),
+        Value::Tuple(items) => Value::Tuple(all(items)?),
+        Value::Map(map) => Value::Map(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), clone_value(v)?)))
+                .collect::<RResult<HashMap<_, _>>>()?
+                .into(),
+        ),
+        Value::Struct { name, fields: f } => Value::Struct {
+            name: name.clone(),
+            fields: Rc::new(fields(f)?),
+        },
+        Value::Option(Some(inner)) => Value::Option(Some(Box::new(clone_value(inner)?))),
+        Value::Result { ok, payload } => Value::Result {
+            ok: *ok,
+            payload: Box::new(clone_value(payload)?),
+        },
+        Value::EnumVariant {
+            type_name,
+            variant,
+            payload,
+        } => Value::EnumVariant {
+            type_name: type_name.clone(),
+            variant: variant.clone(),
+            payload: match payload {
+                EnumValuePayload::None => EnumValuePayload::None,
+                EnumValuePayload::Tuple(items) => EnumValuePayload::Tuple(all(items)?),
+                EnumValuePayload::Named(f) => EnumValuePayload::Named(fields(f)?),
+            },
+        },
+        other => other.clone(),
+    })
+}
+
 fn cell_set(id: i64, value: Value) -> RResult<Value> {
     SHARED_CELLS.with(|cells| {
         let mut cells = cells.borrow_mut();
@@ -24266,7 +23768,7 @@ fn sb_clear(id: i64) -> RResult<()> {
 fn sb_struct(id: i64) -> Value {
     Value::Struct {
         name: "StringBuilder".to_string(),
-        fields: vec![("_id".to_string(), Value::Int(id))],
+        fields: Rc::new(vec![("_id".to_string(), Value::Int(id))]),
     }
 }
 
@@ -24333,11 +23835,11 @@ struct Interpreter {
     /// created for function calls so the values survive across invocations.
     /// Keyed by the static's identifier (caveat: two functions using the
     /// same static name currently share — good enough for MVP).
-    statics: Rc<RefCell<HashMap<String, Value>>>,
+    statics: Rc<RefCell<HashMap<Symbol, Value>>>,
     /// RES-361: compile-time constants. Evaluated once before the program
     /// runs; inlined at every identifier lookup. Shared (read-only) across
     /// all sub-interpreters so that constants are visible inside functions.
-    consts: Rc<HashMap<String, Value>>,
+    consts: Rc<HashMap<Symbol, Value>>,
     /// RES-068: function names whose `requires` clauses were 100%
     /// statically discharged across every observed call site. The
     /// runtime check for these is provably redundant — when binding a
@@ -24377,6 +23879,13 @@ struct Interpreter {
     /// whose callee is also `#[mutual_tail_call]` emits `Value::MutualTailCall`
     /// instead of recursing; the trampoline in `apply_function` re-dispatches.
     mutual_tco_fn: Option<String>,
+    /// Set while running a function body whose `return g(args)` calls
+    /// may reuse the frame: the `ReturnStatement` arm then emits
+    /// `Value::TailCallTo` for the `'tco` loop in `apply_function`, as
+    /// the bytecode compiler's `rewrite_tail_calls` does for the VM.
+    /// Cleared inside `try` and `live` bodies, whose handlers must
+    /// still see the callee's failures.
+    tail_calls: bool,
     /// RES-2697: default method bodies declared on traits. Keyed by trait
     /// name; value is the list of `(method_name, FunctionValue)` pairs for
     /// every method that has a default body. Shared via Rc so sub-interpreters
@@ -24393,6 +23902,9 @@ struct Interpreter {
     call_stack: Vec<crate::error_stack_traces::StackFrame>,
     /// RES-2794: source path for stack trace formatting.
     source_path: String,
+    /// The tools watching this run; shared with sub-interpreters so a
+    /// registration covers every call. See `eval_observer`.
+    observers: eval_observer::Observers,
 }
 
 /// RES-1108 + RES-1109 + RES-1110: structural equality for compound
@@ -24577,10 +24089,18 @@ fn enum_payload_strict_eq(l: &EnumValuePayload, r: &EnumValuePayload) -> bool {
     }
 }
 
-/// Scalar total-order comparison for primitive `Value` kinds. Returns
-/// `None` for compound or incomparable types.
+/// Order two `Value`s: primitives by value, arrays and tuples
+/// lexicographically by element (a prefix sorts first), options with
+/// `None` before any `Some`. Returns `None` for kinds without an
+/// order or a mismatched pair.
 fn values_cmp(left: &Value, right: &Value) -> Option<std::cmp::Ordering> {
     match (left, right) {
+        (Value::Array(l), Value::Array(r)) => slices_cmp(l, r),
+        (Value::Tuple(l), Value::Tuple(r)) => slices_cmp(l, r),
+        (Value::Option(l), Value::Option(r)) => match (l.as_deref(), r.as_deref()) {
+            (Some(a), Some(b)) => values_cmp(a, b),
+            (l, r) => Some(l.is_some().cmp(&r.is_some())),
+        },
         (Value::Int(l), Value::Int(r)) => Some(l.cmp(r)),
         (Value::Float(l), Value::Float(r)) => l.partial_cmp(r),
         (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
@@ -24592,6 +24112,52 @@ fn values_cmp(left: &Value, right: &Value) -> Option<std::cmp::Ordering> {
     }
 }
 
+/// Collections, records, options and results — the values whose
+/// comparisons get a dedicated error instead of `Type mismatch`.
+fn is_composite_value(v: &Value) -> bool {
+    matches!(
+        v,
+        Value::Array(_)
+            | Value::Tuple(_)
+            | Value::Map(_)
+            | Value::Set(_)
+            | Value::Struct { .. }
+            | Value::EnumVariant { .. }
+            | Value::Option(_)
+            | Value::Result { .. }
+    )
+}
+
+/// Why `left <operator> right` has no answer: the kinds differ, or
+/// (for `<` and friends) the kind has no order.
+fn incomparable_values_error(operator: &str, left: &Value, right: &Value) -> String {
+    let describe = |v: &Value| match v {
+        Value::Struct { name, .. } => format!("struct {name}"),
+        _ => crate::type_builtins::value_type_name(v).to_string(),
+    };
+    let (l, r) = (describe(left), describe(right));
+    if l != r {
+        return format!("cannot compare {l} with {r} using `{operator}`");
+    }
+    match left {
+        Value::Struct { .. } => format!(
+            "cannot order {l} with `{operator}`: derive PartialOrd or implement `{}`",
+            crate::operator_overload::op_method_name(operator).unwrap_or(operator)
+        ),
+        _ => format!("cannot order {l} values with `{operator}`: only `==` and `!=` apply"),
+    }
+}
+
+fn slices_cmp(l: &[Value], r: &[Value]) -> Option<std::cmp::Ordering> {
+    for (a, b) in l.iter().zip(r.iter()) {
+        let cmp = values_cmp(a, b)?;
+        if cmp != std::cmp::Ordering::Equal {
+            return Some(cmp);
+        }
+    }
+    Some(l.len().cmp(&r.len()))
+}
+
 /// Lexicographic comparison of two struct field-value lists, following
 /// declaration order. Used by `#[derive(PartialOrd)]` dispatch.
 fn struct_fields_lexicographic_cmp(
@@ -24635,8 +24201,51 @@ impl Interpreter {
                 env.set("None".to_string(), Value::Option(None));
                 env
             };
+            // The prelude's functions, between the builtins and each
+            // interpreter's globals so a program's own `fn` of the same
+            // name wins. Evaluated once per thread, like the builtins.
+            static PRELUDE_ENV: Environment = {
+                let mut interp = Interpreter::with_env(BUILTIN_ENV.with(|e| {
+                    Environment::new_enclosed(e.clone())
+                }));
+                let loaded = fuel::uncharged(|| {
+                    memory_limit::uncounted(|| interp.eval_program(&prelude::items()))
+                });
+                if let Err(e) = loaded {
+                    panic!("prelude.rz failed to load: {}", e);
+                }
+                interp.env
+            };
         }
-        let env = BUILTIN_ENV.with(|e| Environment::new_enclosed(e.clone()));
+        let outer = if prelude::enabled() {
+            PRELUDE_ENV.with(Environment::clone)
+        } else {
+            BUILTIN_ENV.with(Environment::clone)
+        };
+        let env = Environment::new_enclosed(outer);
+        // Plugins can load after this thread cached its builtins.
+        plugin::bind(&env);
+        let mut interp = Self::with_env(env);
+        // The tools switched on for this thread watch the run.
+        if debug_console::active() {
+            interp.observe(Box::new(debug_console::Observer));
+        }
+        if test_coverage::active() {
+            interp.observe(Box::new(test_coverage::Observer));
+        }
+        if call_trace::active() {
+            interp.observe(Box::new(call_trace::Observer));
+        }
+        if metrics::active() {
+            interp.observe(Box::new(metrics::Observer));
+        }
+        if let Some(profiler) = profiler::active() {
+            interp.observe(Box::new(profiler));
+        }
+        interp
+    }
+
+    fn with_env(env: Environment) -> Self {
         Interpreter {
             env,
             statics: Rc::new(RefCell::new(HashMap::new())),
@@ -24649,13 +24258,21 @@ impl Interpreter {
             overflow_mode: vm::OverflowMode::from_env(),
             tco_fn_name: None,
             mutual_tco_fn: None,
+            tail_calls: false,
             trait_method_defaults: Rc::new(RefCell::new(HashMap::new())),
             defer_stack: Vec::new(),
             call_stack: Vec::new(),
             source_path: String::new(),
+            observers: eval_observer::Observers::default(),
         }
     }
 
+    /// Register `observer` for the rest of this interpreter's run,
+    /// including the calls it makes.
+    fn observe(&mut self, observer: Box<dyn eval_observer::EvalObserver>) {
+        self.observers.push(observer);
+    }
+
     /// RES-068: pass the set of fully-proven function names to the
     /// interpreter. Their `requires` clauses won't fire at runtime.
     fn with_proven_fns(mut self, proven: HashSet<String>) -> Self {
@@ -24698,6 +24315,9 @@ impl Interpreter {
                 Err(err) => {
                     self.inject_checked_failures = saved_injection;
                     if let Some(variant) = parse_checked_failure_signal(&err)
+                        .or_else(|| builtin_failure_variant(&err))
+                        .or_else(|| stack_guard::failure_variant(&err))
+                        .or_else(|| float_mode::failure_variant(&err))
                         && let Some((_, handler_body)) =
                             handlers.iter().find(|(name, _)| name == variant)
                     {
@@ -24717,7 +24337,39 @@ impl Interpreter {
         Ok(Value::Void)
     }
 
+    /// The value `name` is bound to: a const, a binding, a static or
+    /// a `Type::method`. An overload set comes back as is, for the
+    /// caller to reject unless it is about to call it.
+    fn lookup_identifier(&self, name: &Symbol) -> RResult<Value> {
+        if let Some(value) = self.consts.get(name) {
+            Ok(value.clone())
+        } else if let Some(value) = self.env.get(name) {
+            Ok(value)
+        } else if let Some(value) = self.statics.borrow().get(name).cloned() {
+            Ok(value)
+        } else if let Some(idx) = name.find("::")
+            && let Some(value) = self
+                .env
+                .get(&format!("{}${}", &name[..idx], &name[idx + 2..]))
+        {
+            // RES-424: `Struct::method()` syntax bridges to the
+            // `Struct$method` mangled name impl methods are stored
+            // under. This makes static method calls (impl methods
+            // with no `self`) callable without knowing the internal
+            // mangling convention.
+            Ok(value)
+        } else {
+            // RES-487: hint at close builtin matches via the
+            // out-of-line helper to keep the caller's stack frame
+            // small.
+            Err(format_unknown_identifier(name))
+        }
+    }
+
     fn eval(&mut self, node: &Node) -> RResult<Value> {
+        fuel::charge()?;
+        memory_limit::check()?;
+        stack_guard::check()?;
         match node {
             Node::Program(statements) => self.eval_program(statements),
             // RES-073: `use` should have been resolved by expand_uses
@@ -24743,21 +24395,35 @@ impl Interpreter {
                 // statically proven, the runtime requires check is
                 // provably redundant. Strip it.
                 let runtime_requires = if self.proven_fns.contains(name) {
-                    Vec::new()
+                    Arc::default()
                 } else {
-                    requires.clone()
+                    Arc::clone(requires)
                 };
                 let func = Value::Function(Box::new(FunctionValue {
-                    parameters: Rc::new(parameters.clone()),
-                    body: Rc::new(body.as_ref().clone()),
+                    parameters: Arc::new(parameters.clone()),
+                    body: Arc::clone(body),
                     env: self.env.clone(),
                     requires: runtime_requires,
-                    ensures: ensures.clone(),
+                    ensures: Arc::clone(ensures),
                     recovers_to: recovers_to.clone(),
                     name: name.clone(),
                     type_params: type_params.clone(),
-                    fails: Rc::new(fails.clone()),
+                    fails: Arc::new(fails.clone()),
                 }));
+                // A mangled overload also joins its bare name's set.
+                if let Some(base) = crate::overloads::base_of(name) {
+                    let Value::Function(fv) = &func else {
+                        unreachable!("just built a Value::Function")
+                    };
+                    let mut set = match self.env.get(base) {
+                        Some(Value::Overloads(set)) => set.as_ref().clone(),
+                        _ => Vec::new(),
+                    };
+                    set.retain(|other| other.name != *name);
+                    set.push(fv.as_ref().clone());
+                    self.env
+                        .set(base.to_string(), Value::Overloads(Rc::new(set)));
+                }
                 self.env.set(name.clone(), func);
                 Ok(Value::Void)
             }
@@ -24777,7 +24443,8 @@ impl Interpreter {
                     Node::DurationLiteral { nanos, .. } => Some(*nanos),
                     _ => None,
                 });
-                self.eval_live_block(
+                let saved_tail_calls = std::mem::replace(&mut self.tail_calls, false);
+                let result = self.eval_live_block(
                     body,
                     invariants,
                     backoff.as_ref(),
@@ -24785,7 +24452,9 @@ impl Interpreter {
                     timeout_ns,
                     *max_retries,
                     *span,
-                )
+                );
+                self.tail_calls = saved_tail_calls;
+                result
             }
             // RES-142: duration literals are only legal inside a
             // `live ... within <duration> { ... }` clause — the
@@ -24853,6 +24522,8 @@ impl Interpreter {
                         n, annot, min, max
                     ));
                 }
+                self.observers
+                    .notify(|o| o.variable_write(name, ResilientValue::from_ref(&val)));
                 self.env.set(name.clone(), val);
                 Ok(Value::Void)
             }
@@ -24890,6 +24561,9 @@ impl Interpreter {
                             struct_name, field_name
                         ));
                     };
+                    self.observers.notify(|o| {
+                        o.variable_write(local_name, ResilientValue::from_ref(field_val))
+                    });
                     self.env.set(local_name.clone(), field_val.clone());
                 }
                 Ok(Value::Void)
@@ -24898,25 +24572,37 @@ impl Interpreter {
                 // Initialize only once. Subsequent executions of the
                 // same declaration are no-ops (the value persists in
                 // self.statics across function calls).
-                if !self.statics.borrow().contains_key(name) {
+                let name = Symbol::intern(name);
+                if !self.statics.borrow().contains_key(&name) {
                     let val = self.eval(value)?;
-                    self.statics.borrow_mut().insert(name.clone(), val);
+                    self.statics.borrow_mut().insert(name, val);
                 }
                 Ok(Value::Void)
             }
             Node::Assignment { name, value, .. } => {
                 // RES-361: const names are immutable.
-                if self.consts.contains_key(name) {
+                if !self.consts.is_empty()
+                    && name
+                        .find_symbol()
+                        .is_some_and(|sym| self.consts.contains_key(&sym))
+                {
                     return Err(format!("Cannot assign to compile-time constant '{}'", name));
                 }
+                if let Some(pushed) = self.eval_push_assign(name, value) {
+                    return pushed;
+                }
                 let val = self.eval(value)?;
                 if matches!(val, Value::Return(_)) {
                     return Ok(val);
                 }
+                self.observers
+                    .notify(|o| o.variable_write(name, ResilientValue::from_ref(&val)));
                 if self.env.reassign(name, val.clone()) {
                     Ok(Value::Void)
-                } else if let Some(slot) = self.statics.borrow_mut().get_mut(name) {
-                    // RES-1463: `get_mut(&str)` does a single hashed
+                } else if let Some(sym) = name.find_symbol()
+                    && let Some(slot) = self.statics.borrow_mut().get_mut(&sym)
+                {
+                    // RES-1463: `get_mut` does a single hashed
                     // lookup AND skips both the redundant
                     // `contains_key` (which used the shared borrow
                     // before re-borrowing mutably) and the
@@ -24930,6 +24616,20 @@ impl Interpreter {
                 }
             }
             Node::ReturnStatement { value, .. } => {
+                if self.tail_calls
+                    && let Some(Node::CallExpression {
+                        function,
+                        arguments,
+                        span: call_span,
+                    }) = value.as_deref()
+                    && let Some((callee, args)) = self.tail_call_target(function, arguments)?
+                {
+                    return Ok(Value::Return(Box::new(Value::TailCallTo {
+                        callee,
+                        args,
+                        call_span: *call_span,
+                    })));
+                }
                 let val = match value {
                     Some(expr) => self.eval(expr)?,
                     None => Value::Void,
@@ -24946,7 +24646,7 @@ impl Interpreter {
             }
             // RES-2613: bench blocks are silently skipped during normal eval.
             // They are collected and run by the `rz bench` subcommand.
-            Node::BenchBlock { .. } => Ok(Value::Void),
+            Node::BenchBlock { .. } | Node::TestFn { .. } => Ok(Value::Void),
             // RES-910: emit the control-flow sentinel; the enclosing
             // loop evaluator consumes it.
             Node::Break { .. } => Ok(Value::Break),
@@ -25040,10 +24740,19 @@ impl Interpreter {
                 condition,
                 consequence,
                 alternative,
-                ..
+                span,
             } => {
                 let condition_value = self.eval(condition)?;
-                if self.is_truthy(&condition_value) {
+                let held = self.is_truthy(&condition_value);
+                self.observers.notify(|o| {
+                    o.branch(
+                        span.start.line,
+                        span.start.column,
+                        self.coverage_function(),
+                        held,
+                    )
+                });
+                if held {
                     self.eval(consequence)
                 } else if let Some(alt) = alternative {
                     self.eval(alt)
@@ -25058,31 +24767,10 @@ impl Interpreter {
             // RES-2660: static_assert is evaluated at compile time;
             // at runtime it is a no-op.
             Node::StaticAssert { .. } => Ok(Value::Void),
-            Node::Identifier { name, .. } => {
-                if let Some(value) = self.consts.get(name) {
-                    Ok(value.clone())
-                } else if let Some(value) = self.env.get(name) {
-                    Ok(value)
-                } else if let Some(value) = self.statics.borrow().get(name).cloned() {
-                    Ok(value)
-                } else if let Some(idx) = name.find("::")
-                    && let Some(value) =
-                        self.env
-                            .get(&format!("{}${}", &name[..idx], &name[idx + 2..]))
-                {
-                    // RES-424: `Struct::method()` syntax bridges to the
-                    // `Struct$method` mangled name impl methods are stored
-                    // under. This makes static method calls (impl methods
-                    // with no `self`) callable without knowing the internal
-                    // mangling convention.
-                    Ok(value)
-                } else {
-                    // RES-487: hint at close builtin matches via the
-                    // out-of-line helper to keep this match-arm's
-                    // stack frame small.
-                    Err(format_unknown_identifier(name))
-                }
-            }
+            Node::Identifier { name, .. } => match self.lookup_identifier(name)? {
+                Value::Overloads(_) => Err(crate::overloads::as_value_error(name)),
+                value => Ok(value),
+            },
             Node::IntegerLiteral { value, .. } => Ok(Value::Int(*value)),
             Node::FloatLiteral { value, .. } => Ok(Value::Float(*value)),
             Node::StringLiteral { value, .. } => Ok(Value::String(value.clone())),
@@ -25171,7 +24859,7 @@ impl Interpreter {
                 {
                     let new_args = self.eval_expressions(arguments)?;
                     return Ok(Value::MutualTailCall {
-                        callee: callee_name.clone(),
+                        callee: callee_name.to_string(),
                         args: new_args,
                     });
                 }
@@ -25318,7 +25006,7 @@ impl Interpreter {
                                 for item in items {
                                     out.push(self.apply_function(&callback, vec![item])?);
                                 }
-                                return Ok(Value::Array(out));
+                                return Ok(Value::Array(out.into()));
                             }
                             "filter" => {
                                 if extra_args.len() != 1 {
@@ -25341,7 +25029,7 @@ impl Interpreter {
                                         }
                                     }
                                 }
-                                return Ok(Value::Array(out));
+                                return Ok(Value::Array(out.into()));
                             }
                             "reduce" => {
                                 match extra_args.len() {
@@ -25392,7 +25080,7 @@ impl Interpreter {
                                         other => out.push(other),
                                     }
                                 }
-                                return Ok(Value::Array(out));
+                                return Ok(Value::Array(out.into()));
                             }
                             "for_each" => {
                                 if extra_args.len() != 1 {
@@ -25518,7 +25206,7 @@ impl Interpreter {
                             // expression-result only — the caller can still read
                             // the returned struct if needed.
                             let root_name = if let Node::Identifier { name, .. } = target.as_ref() {
-                                Some(name.clone())
+                                Some(*name)
                             } else {
                                 None
                             };
@@ -25551,7 +25239,7 @@ impl Interpreter {
                                         target_val.clone(),
                                         next_method.clone(),
                                     )?;
-                                    return Ok(Value::Array(items));
+                                    return Ok(Value::Array(items.into()));
                                 }
                                 "map" => {
                                     let mut extra_args = self.eval_expressions(arguments)?;
@@ -25570,7 +25258,7 @@ impl Interpreter {
                                     for item in items {
                                         out.push(self.apply_function(&callback, vec![item])?);
                                     }
-                                    return Ok(Value::Array(out));
+                                    return Ok(Value::Array(out.into()));
                                 }
                                 "filter" => {
                                     let mut extra_args = self.eval_expressions(arguments)?;
@@ -25598,7 +25286,7 @@ impl Interpreter {
                                             }
                                         }
                                     }
-                                    return Ok(Value::Array(out));
+                                    return Ok(Value::Array(out.into()));
                                 }
                                 "take" => {
                                     let mut extra_args = self.eval_expressions(arguments)?;
@@ -25692,9 +25380,15 @@ impl Interpreter {
                 // RES-1859: standalone array_map / array_filter / array_reduce
                 // must be handled inline — they accept user callbacks that
                 // require the stateful `apply_function` path.
+                // `map` / `filter` / `reduce` are short names for the same
+                // three (and `sort_by` for `array_sort_by` below); a user
+                // binding of that name takes precedence.
                 if let Node::Identifier { name: fn_name, .. } = function.as_ref() {
+                    let short_hof =
+                        matches!(fn_name.as_str(), "map" | "filter" | "reduce" | "sort_by")
+                            && self.env.get(*fn_name).is_none();
                     match fn_name.as_str() {
-                        "array_map" => {
+                        "array_map" | "map" if short_hof || fn_name == "array_map" => {
                             let mut args = self.eval_expressions(arguments)?;
                             if args.len() != 2 {
                                 return Err(format!(
@@ -25709,7 +25403,7 @@ impl Interpreter {
                                     for item in items {
                                         out.push(self.apply_function(&callback, vec![item])?);
                                     }
-                                    return Ok(Value::Array(out));
+                                    return Ok(Value::Array(out.into()));
                                 }
                                 _ => {
                                     return Err(
@@ -25718,7 +25412,7 @@ impl Interpreter {
                                 }
                             }
                         }
-                        "array_filter" => {
+                        "array_filter" | "filter" if short_hof || fn_name == "array_filter" => {
                             let mut args = self.eval_expressions(arguments)?;
                             if args.len() != 2 {
                                 return Err(format!(
@@ -25742,7 +25436,7 @@ impl Interpreter {
                                             }
                                         }
                                     }
-                                    return Ok(Value::Array(out));
+                                    return Ok(Value::Array(out.into()));
                                 }
                                 _ => {
                                     return Err("array_filter: expected (array, fn), got {} args"
@@ -25750,7 +25444,7 @@ impl Interpreter {
                                 }
                             }
                         }
-                        "array_reduce" => {
+                        "array_reduce" | "reduce" if short_hof || fn_name == "array_reduce" => {
                             let mut args = self.eval_expressions(arguments)?;
                             if args.len() != 3 {
                                 return Err(format!(
@@ -25948,7 +25642,7 @@ impl Interpreter {
                             return crate::array_functional::builtin_array_scan(self, &args);
                         }
                         // RES-2648: array combinators with callbacks.
-                        "array_sort_by" => {
+                        "array_sort_by" | "sort_by" if short_hof || fn_name == "array_sort_by" => {
                             let args = self.eval_expressions(arguments)?;
                             return crate::array_combinators::builtin_array_sort_by(self, &args);
                         }
@@ -26103,7 +25797,15 @@ impl Interpreter {
                         frames.into_iter().map(Value::String).collect(),
                     ));
                 }
-                let func = self.eval(function)?;
+                // A callee may name an overload set, which `eval`
+                // rejects anywhere else.
+                let func = match function.as_ref() {
+                    Node::Identifier { name, .. } => {
+                        fuel::charge()?;
+                        self.lookup_identifier(name)?
+                    }
+                    _ => self.eval(function)?,
+                };
                 let args = self.eval_expressions(arguments)?;
                 self.apply_function_at(&func, args, call_span)
             }
@@ -26112,7 +25814,7 @@ impl Interpreter {
                 for item in items {
                     out.push(self.eval(item)?);
                 }
-                Ok(Value::Array(out))
+                Ok(Value::Array(out.into()))
             }
             // RES-401: tuple literal / element access / let destructure.
             // All three dispatch into helpers in `tuples.rs`; `lib.rs`
@@ -26137,7 +25839,7 @@ impl Interpreter {
                     let k = MapKey::from_value(&k_val)?;
                     m.insert(k, v_val);
                 }
-                Ok(Value::Map(m))
+                Ok(Value::Map(m.into()))
             }
             Node::SetLiteral { items, .. } => {
                 // RES-149: build a HashSet<MapKey> by evaluating
@@ -26156,7 +25858,7 @@ impl Interpreter {
                     })?;
                     set.insert(k);
                 }
-                Ok(Value::Set(set))
+                Ok(Value::Set(set.into()))
             }
             Node::FunctionLiteral {
                 parameters,
@@ -26166,15 +25868,15 @@ impl Interpreter {
                 recovers_to,
                 ..
             } => Ok(Value::Function(Box::new(FunctionValue {
-                parameters: Rc::new(parameters.clone()),
-                body: Rc::new(body.as_ref().clone()),
+                parameters: Arc::new(parameters.clone()),
+                body: Arc::clone(body),
                 env: self.env.clone(),
-                requires: requires.clone(),
-                ensures: ensures.clone(),
+                requires: Arc::clone(requires),
+                ensures: Arc::clone(ensures),
                 recovers_to: recovers_to.clone(),
                 name: "<anon>".to_string(),
                 type_params: vec![],
-                fails: Rc::new(vec![]),
+                fails: Arc::default(),
             }))),
             Node::TryExpression { expr: inner, .. } => {
                 let v = self.eval(inner)?;
@@ -26221,9 +25923,9 @@ impl Interpreter {
                 let result = match access {
                     ChainAccess::Field(field) => match inner {
                         Value::Struct { name, fields } => fields
-                            .into_iter()
+                            .iter()
                             .find(|(n, _)| n == field)
-                            .map(|(_, v)| v)
+                            .map(|(_, v)| v.clone())
                             .ok_or_else(|| format!("Struct {} has no field '{}'", name, field)),
                         other => Err(format!(
                             "Cannot access field '{}' on non-struct {:?}",
@@ -26363,7 +26065,7 @@ impl Interpreter {
                 let inner = self.eval(value)?;
                 Ok(Value::Struct {
                     name: type_name.clone(),
-                    fields: vec![("__value".to_string(), inner)],
+                    fields: Rc::new(vec![("__value".to_string(), inner)]),
                 })
             }
             // RES-386/RES-388/RES-390: actor/cluster declarations are
@@ -26374,7 +26076,12 @@ impl Interpreter {
             | Node::ActorDecl { .. }
             | Node::ClusterDecl { .. }
             | Node::SupervisorDecl { .. } => Ok(Value::Void),
-            Node::TryCatch { body, handlers, .. } => self.eval_try_catch(body, handlers),
+            Node::TryCatch { body, handlers, .. } => {
+                let saved_tail_calls = std::mem::replace(&mut self.tail_calls, false);
+                let result = self.eval_try_catch(body, handlers);
+                self.tail_calls = saved_tail_calls;
+                result
+            }
             // RES-330: short-circuit interpreter evaluation for
             // `forall` / `exists` expressions. All logic lives in the
             // quantifiers module; this dispatch line is the only touch
@@ -26454,7 +26161,7 @@ impl Interpreter {
                         Value::Struct {
                             fields: base_fields,
                             ..
-                        } => base_fields,
+                        } => Rc::unwrap_or_clone(base_fields),
                         other => {
                             return Err(format!(
                                 "struct update `..` base must be a struct, got {:?}",
@@ -26540,16 +26247,16 @@ impl Interpreter {
                 }
                 Ok(Value::Struct {
                     name: name.clone(),
-                    fields: out,
+                    fields: Rc::new(out),
                 })
             }
             Node::FieldAccess { target, field, .. } => {
                 let tval = self.eval(target)?;
                 match tval {
                     Value::Struct { name, fields } => fields
-                        .into_iter()
+                        .iter()
                         .find(|(n, _)| n == field)
-                        .map(|(_, v)| v)
+                        .map(|(_, v)| v.clone())
                         .ok_or_else(|| format!("Struct {} has no field '{}'", name, field)),
                     other => Err(format!(
                         "Cannot access field '{}' on non-struct {:?}",
@@ -26581,73 +26288,25 @@ impl Interpreter {
                 Ok(Value::Void)
             }
             Node::IndexExpression { target, index, .. } => {
-                let target_val = self.eval(target)?;
-                let index_val = self.eval(index)?;
-                match (target_val, index_val) {
-                    (Value::Array(mut items), Value::Int(i)) => {
-                        // RES-921: negative indices wrap from the end —
-                        // `-1` is the last element, `-len` is the first.
-                        // `i < -len` and `i >= len` are both out of
-                        // bounds and produce the same diagnostic.
-                        let len = items.len() as i64;
-                        let resolved = if i < 0 { i + len } else { i };
-                        if resolved < 0 || resolved >= len {
-                            Err(format!(
-                                "Index {} out of bounds for array of length {}",
-                                i,
-                                items.len()
-                            ))
-                        } else {
-                            // RES-1436: `items` is owned (moved out of
-                            // `Value::Array(items)` by the destructure).
-                            // The previous `items[i].clone()` shape
-                            // cloned the element and then dropped the
-                            // entire `items` Vec — for arrays of
-                            // heap-allocated values (strings, nested
-                            // arrays, structs), the clone was the
-                            // expensive part. `swap_remove(i)` returns
-                            // the element by move (O(1) — swaps the
-                            // last element into position `i`); the rest
-                            // of `items` drops at end of scope as
-                            // before. Net effect: one fewer Value clone
-                            // per array index in the tree-walker.
-                            // Element order in `items` is irrelevant
-                            // since `items` is about to be dropped.
-                            Ok(items.swap_remove(resolved as usize))
-                        }
-                    }
-                    (Value::Array(_), other) => {
-                        Err(format!("Array index must be int, got {}", other))
-                    }
-                    // RES-427: string / int / bool subscript access on
-                    // Map literals (`m["key"]`, `m[42]`).
-                    (Value::Map(mut m), key_val) => {
-                        let mk = MapKey::from_value(&key_val)
-                            .map_err(|e| format!("Invalid map key: {e}"))?;
-                        m.remove(&mk)
-                            .ok_or_else(|| format!("Key not found in map: {}", key_val))
-                    }
-                    // RES-427 / RES-2709: string subscript `s[i]` yields
-                    // the i-th Unicode scalar as a `Value::Char` so that
-                    // char literal patterns in `match` and `==` comparisons
-                    // can match the result. String concat via `+` accepts
-                    // Char (see `can_stringify_for_concat`).
-                    (Value::String(s), Value::Int(i)) => {
-                        let chars: Vec<char> = s.chars().collect();
-                        let len = chars.len() as i64;
-                        let resolved = if i < 0 { i + len } else { i };
-                        if resolved < 0 || resolved >= len {
-                            Err(format!(
-                                "String index {} out of bounds for length {}",
-                                i,
-                                chars.len()
-                            ))
-                        } else {
-                            Ok(Value::Char(chars[resolved as usize]))
-                        }
+                // `xs[i]`, `m[k]` or `s[i]` on a named binding reads
+                // the element in place instead of cloning the whole
+                // value. Operands still evaluate left to right: the
+                // target is a bound name, so evaluating it first is a
+                // lookup that cannot fail, and a pure index cannot
+                // rebind it before the read.
+                if let Node::Identifier { name, .. } = target.as_ref()
+                    && !self.consts.contains_key(name)
+                    && self.env.contains(name)
+                    && is_pure_index(index)
+                {
+                    let index_val = self.eval(index)?;
+                    if let Some(result) = self.env.with_value(name, |v| index_value(v, index_val)) {
+                        return result;
                     }
-                    (other, _) => Err(format!("Cannot index {:?}", other)),
                 }
+                let target_val = self.eval(target)?;
+                let index_val = self.eval(index)?;
+                index_value(&target_val, index_val)
             }
             // RES-911 / RES-916 / RES-921: slicing — `target[lo..hi]`
             // (etc.) returns a fresh `Value::Array` for array targets
@@ -26762,7 +26421,7 @@ impl Interpreter {
                 let mut cursor: &Node = target;
                 let root_name = loop {
                     match cursor {
-                        Node::Identifier { name, .. } => break name.clone(),
+                        Node::Identifier { name, .. } => break *name,
                         Node::IndexExpression {
                             target: inner_t,
                             index: inner_i,
@@ -26785,24 +26444,24 @@ impl Interpreter {
                 // happen before we start mutating the collection.
                 let new_val = self.eval(value)?;
 
-                // Read–modify–write. `env.get` returns a clone, so the
-                // mutation is local until we `reassign` the new root
-                // value — that preserves value semantics for sibling
-                // bindings.
-                let root = self
+                let mut path_vals: Vec<Value> = Vec::with_capacity(path_exprs.len());
+                for idx_expr in &path_exprs {
+                    path_vals.push(self.eval(idx_expr)?);
+                }
+
+                // Read–modify–write. The binding gives up its handle on
+                // the collection while we write, so an unshared array
+                // or map is updated in place; one that another value
+                // still holds is copied first (see `shared`), which
+                // preserves value semantics for sibling bindings.
+                let mut root = self
                     .env
-                    .get(&root_name)
+                    .get(root_name)
                     .ok_or_else(|| format!("Identifier not found: {}", root_name))?;
-
-                {
-                    let mut path_vals: Vec<Value> = Vec::with_capacity(path_exprs.len());
-                    for idx_expr in &path_exprs {
-                        path_vals.push(self.eval(idx_expr)?);
-                    }
-                    let updated = assign_at_path(root, &path_vals, new_val, 1)?;
-                    let _ = self.env.reassign(&root_name, updated);
-                    Ok(Value::Void)
-                }
+                let _ = self.env.reassign(root_name, Value::Void);
+                let result = assign_at_path(&mut root, &path_vals, new_val, 1);
+                let _ = self.env.reassign(root_name, root);
+                result.map(|_| Value::Void)
             }
             // RES-325: a `NamedArg` outside an enclosing call site is
             // an internal error — the parser only emits these inside
@@ -26830,15 +26489,15 @@ impl Interpreter {
                         .map(|p| ("".to_string(), p.clone()))
                         .collect();
                     let fv = Value::Function(Box::new(FunctionValue {
-                        parameters: Rc::new(parameters),
-                        body: Rc::new(*body.clone()),
+                        parameters: Arc::new(parameters),
+                        body: Arc::new(body.as_ref().clone()),
                         env: self.env.clone(),
-                        requires: vec![],
-                        ensures: vec![],
+                        requires: Arc::default(),
+                        ensures: Arc::default(),
                         recovers_to: None,
                         name: format!("{}${}", name, method.name),
                         type_params: vec![],
-                        fails: Rc::new(vec![]),
+                        fails: Arc::default(),
                     }));
                     defaults.push((method.name.clone(), fv));
                 }
@@ -26927,226 +26586,26 @@ impl Interpreter {
 
     /// RES-361: evaluate all `const` declarations before program execution.
     ///
-    /// Walks the top-level statements in source order. For each `Node::Const`
-    /// it evaluates the value expression using only previously-evaluated
-    /// constants (stored in a temporary `HashMap`). Cycle detection is done
-    /// with a `HashSet` of names currently being evaluated; if a constant
-    /// reference tries to look up a name that is still on the stack, we
-    /// emit a clear error.
+    /// Resolution is delegated to `const_eval::ConstEvaluator`, the same
+    /// evaluator the typechecker and the bytecode compiler use, so a
+    /// constant means the same thing on every path. Constants may refer
+    /// to ones declared later; a cycle is an error naming the chain.
     ///
-    /// The resulting `HashMap<String, Value>` is installed into
-    /// `self.consts` (behind an `Rc`) so every sub-interpreter created
-    /// for function calls sees the same set of constants.
+    /// The result is installed into `self.consts` (behind an `Rc`),
+    /// keyed by `Symbol` like the environment, so every sub-interpreter
+    /// created for function calls sees the same set of constants.
     fn const_eval_program(&mut self, statements: &[span::Spanned<Node>]) -> RResult<()> {
-        // Pre-size `resolved` to the actual `Node::Const` count — same
-        // shape as `compile::fn_count` (RES-1461), `pure_fns`
-        // (RES-1796), and `collect_fn_effects::out` (RES-1734): one
-        // linear pre-scan to skip the default 0 → 3 → 7 → 14 → 28 ...
-        // bucket-grow cascade. Non-Const statements contribute zero so
-        // a `statements.len()` over-estimate would over-allocate for
-        // programs with many fns and few consts.
-        let const_count = statements
-            .iter()
-            .filter(|s| matches!(&s.node, Node::Const { .. }))
-            .count();
-        let mut resolved: HashMap<String, Value> = HashMap::with_capacity(const_count);
-
-        for stmt in statements {
-            let Node::Const { name, value, .. } = &stmt.node else {
-                continue;
-            };
-            // Push the current const's name onto the evaluation stack
-            // so that any reference to itself (direct self-reference or
-            // mutual recursion that circles back here) is detected.
-            let mut evaluating: Vec<String> = vec![name.clone()];
-            let v = Self::eval_const_expr(value, &resolved, &mut evaluating)
-                .map_err(|e| decorate_runtime_error(e, &stmt.span))?;
-            resolved.insert(name.clone(), v);
-        }
-
-        self.consts = Rc::new(resolved);
+        let resolved = crate::const_eval::ConstEvaluator::resolve_all(statements)
+            .map_err(|(e, span)| decorate_runtime_error(e, &span))?;
+        self.consts = Rc::new(
+            resolved
+                .into_iter()
+                .map(|(name, value)| (Symbol::intern(&name), value))
+                .collect(),
+        );
         Ok(())
     }
 
-    /// Evaluate a constant expression recursively.
-    ///
-    /// Allowed sub-expressions:
-    /// - Integer and float literals
-    /// - Boolean literals
-    /// - String literals
-    /// - Prefix negation/not over a constant sub-expression
-    /// - Infix arithmetic / comparison over constant sub-expressions
-    /// - Identifiers that refer to an already-resolved constant
-    ///
-    /// Any other node (function call, array literal, etc.) is rejected
-    /// with a diagnostic describing what is allowed.
-    pub(crate) fn eval_const_expr(
-        node: &Node,
-        resolved: &HashMap<String, Value>,
-        evaluating: &mut Vec<String>,
-    ) -> RResult<Value> {
-        match node {
-            Node::IntegerLiteral { value, .. } => Ok(Value::Int(*value)),
-            Node::FloatLiteral { value, .. } => Ok(Value::Float(*value)),
-            Node::BooleanLiteral { value, .. } => Ok(Value::Bool(*value)),
-            Node::StringLiteral { value, .. } => Ok(Value::String(value.clone())),
-            // RES-2612: interned strings evaluate to their content at compile time.
-            Node::StringInternLiteral { content, .. } => Ok(Value::String(content.clone())),
-            Node::Identifier { name, .. } => {
-                if evaluating.contains(name) {
-                    return Err(format!("error: circular constant definition: '{}'", name));
-                }
-                resolved.get(name).cloned().ok_or_else(|| {
-                    format!(
-                        "error: '{}' is not a compile-time constant (constants may only \
-                         reference other constants defined before them)",
-                        name
-                    )
-                })
-            }
-            Node::PrefixExpression {
-                operator, right, ..
-            } => {
-                let rv = Self::eval_const_expr(right, resolved, evaluating)?;
-                match (*operator, rv) {
-                    ("-", Value::Int(i)) => Ok(Value::Int(-i)),
-                    ("-", Value::Float(f)) => Ok(Value::Float(-f)),
-                    ("!", Value::Bool(b)) => Ok(Value::Bool(!b)),
-                    (op, v) => Err(format!(
-                        "error: operator '{}' cannot be applied to {} in a constant expression",
-                        op, v
-                    )),
-                }
-            }
-            Node::InfixExpression {
-                left,
-                operator,
-                right,
-                ..
-            } => {
-                let lv = Self::eval_const_expr(left, resolved, evaluating)?;
-                let rv = Self::eval_const_expr(right, resolved, evaluating)?;
-                match (*operator, lv, rv) {
-                    ("+", Value::Int(a), Value::Int(b)) => Ok(Value::Int(a + b)),
-                    ("-", Value::Int(a), Value::Int(b)) => Ok(Value::Int(a - b)),
-                    ("*", Value::Int(a), Value::Int(b)) => Ok(Value::Int(a * b)),
-                    ("/", Value::Int(a), Value::Int(b)) => {
-                        if b == 0 {
-                            Err("error: division by zero in constant expression".to_string())
-                        } else {
-                            Ok(Value::Int(a / b))
-                        }
-                    }
-                    ("%", Value::Int(a), Value::Int(b)) => {
-                        if b == 0 {
-                            Err("error: modulo by zero in constant expression".to_string())
-                        } else {
-                            Ok(Value::Int(a % b))
-                        }
-                    }
-                    ("+", Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
-                    ("-", Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
-                    ("*", Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
-                    ("/", Value::Float(a), Value::Float(b)) => Ok(Value::Float(a / b)),
-                    // RES-2660: comparison operators — required for
-                    // `static_assert` conditions and useful for any
-                    // const expression that evaluates to bool.
-                    ("==", Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a == b)),
-                    ("!=", Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a != b)),
-                    ("<", Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a < b)),
-                    ("<=", Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a <= b)),
-                    (">", Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a > b)),
-                    (">=", Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a >= b)),
-                    ("==", Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a == b)),
-                    ("!=", Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a != b)),
-                    ("==", Value::String(ref a), Value::String(ref b)) => Ok(Value::Bool(a == b)),
-                    ("!=", Value::String(ref a), Value::String(ref b)) => Ok(Value::Bool(a != b)),
-                    // RES-2660: logical operators for compound conditions.
-                    ("&&", Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a && b)),
-                    ("||", Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a || b)),
-                    // RES-2580: string concatenation in const expressions.
-                    ("+", Value::String(a), Value::String(b)) => Ok(Value::String(a + b.as_str())),
-                    // RES-2580: string ordering comparisons.
-                    ("<", Value::String(ref a), Value::String(ref b)) => Ok(Value::Bool(a < b)),
-                    ("<=", Value::String(ref a), Value::String(ref b)) => Ok(Value::Bool(a <= b)),
-                    (">", Value::String(ref a), Value::String(ref b)) => Ok(Value::Bool(a > b)),
-                    (">=", Value::String(ref a), Value::String(ref b)) => Ok(Value::Bool(a >= b)),
-                    // RES-2580: bitwise operators on integers.
-                    ("&", Value::Int(a), Value::Int(b)) => Ok(Value::Int(a & b)),
-                    ("|", Value::Int(a), Value::Int(b)) => Ok(Value::Int(a | b)),
-                    ("^", Value::Int(a), Value::Int(b)) => Ok(Value::Int(a ^ b)),
-                    ("<<", Value::Int(a), Value::Int(b)) => Ok(Value::Int(a << b)),
-                    (">>", Value::Int(a), Value::Int(b)) => Ok(Value::Int(a >> b)),
-                    (op, lv, rv) => Err(format!(
-                        "error: operator '{}' on ({}, {}) is not supported in a constant expression",
-                        op, lv, rv
-                    )),
-                }
-            }
-            // RES-2580: conditional const expressions: `if C { A } else { B }`.
-            // Both branches must be const-evaluable; the condition must be bool.
-            Node::IfStatement {
-                condition,
-                consequence,
-                alternative,
-                ..
-            } => {
-                let cond = Self::eval_const_expr(condition, resolved, evaluating)?;
-                let Value::Bool(b) = cond else {
-                    return Err("error: condition in const `if` must evaluate to bool".to_string());
-                };
-                if b {
-                    Self::eval_const_expr(consequence, resolved, evaluating)
-                } else {
-                    match alternative {
-                        Some(alt) => Self::eval_const_expr(alt, resolved, evaluating),
-                        None => Ok(Value::Void),
-                    }
-                }
-            }
-            // RES-2580: unwrap expression-statement wrapper (parser emits
-            // ExpressionStatement for bare-expression blocks like `{ A }`).
-            Node::ExpressionStatement { expr, .. } => {
-                Self::eval_const_expr(expr, resolved, evaluating)
-            }
-            // RES-2580: block with single expression is allowed as a const value.
-            Node::Block { stmts, .. } => {
-                // Only single-expression blocks are supported in const context.
-                // Strip ExpressionStatement wrappers before recursing.
-                if stmts.len() == 1 {
-                    let inner = match &stmts[0] {
-                        Node::ExpressionStatement { expr, .. } => expr.as_ref(),
-                        other => other,
-                    };
-                    Self::eval_const_expr(inner, resolved, evaluating)
-                } else {
-                    Err(
-                        "error: only single-expression blocks are valid in const expressions"
-                            .to_string(),
-                    )
-                }
-            }
-            // RES-2580: tuple literals with all-const elements.
-            Node::TupleLiteral { items, .. } => {
-                let mut vals = Vec::with_capacity(items.len());
-                for item in items {
-                    vals.push(Self::eval_const_expr(item, resolved, evaluating)?);
-                }
-                Ok(Value::Tuple(vals))
-            }
-            other => Err(format!(
-                "error: '{}' is not a valid constant expression; \
-                 only literals, arithmetic, string ops, bitwise ops, \
-                 conditionals, and constant references are allowed",
-                match other {
-                    Node::CallExpression { .. } => "function call".to_string(),
-                    Node::ArrayLiteral { .. } => "array literal".to_string(),
-                    _ => format!("{:?}", std::mem::discriminant(other)),
-                }
-            )),
-        }
-    }
-
     /// RES-291: helper for `Node::ForInStatement`, factored out of
     /// `eval` so that branch's stack frame stays small. Inlining the
     /// range-fast-path into `eval` directly bloats the (already huge)
@@ -27476,6 +26935,7 @@ impl Interpreter {
     }
 
     fn eval_program(&mut self, statements: &[span::Spanned<Node>]) -> RResult<Value> {
+        let _span = phase_trace::eval();
         // RES-361: evaluate all `const` declarations first, before any
         // function hoisting or statement execution.
         self.const_eval_program(statements)?;
@@ -27521,13 +26981,21 @@ impl Interpreter {
             ) {
                 continue;
             }
+            self.observers.notify(|o| {
+                o.node_enter(
+                    &statement.node,
+                    &eval_observer::Scope(&self.env),
+                    self.coverage_function(),
+                )
+            });
+            let outcome = self.eval(&statement.node);
+            self.observers
+                .notify(|o| o.node_exit(&statement.node, eval_observer::outcome(&outcome)));
             // RES-116: decorate runtime errors with the statement's
             // source span so `execute_file` can reformat them as
             // `filename:line:col: Runtime error: <msg>` — matching the
             // VM's RES-091/092 output shape.
-            result = self
-                .eval(&statement.node)
-                .map_err(|e| decorate_runtime_error(e, &statement.span))?;
+            result = outcome.map_err(|e| decorate_runtime_error(e, &statement.span))?;
             if let Value::Return(value) = result {
                 return Ok(*value);
             }
@@ -27536,6 +27004,53 @@ impl Interpreter {
         Ok(result)
     }
 
+    /// `xs = push(xs, v)`, the loop that grows an array, appends in
+    /// place: the binding lets go of the array while `v` goes on, so
+    /// an array no other value holds is not copied, and `--max-memory`
+    /// counts `v` instead of measuring the array again. `None` when
+    /// `value` isn't that call on `name`'s array, for the general path.
+    fn eval_push_assign(&mut self, name: &str, value: &Node) -> Option<RResult<Value>> {
+        let Node::CallExpression {
+            function,
+            arguments,
+            ..
+        } = value
+        else {
+            return None;
+        };
+        let (Node::Identifier { name: callee, .. }, [Node::Identifier { name: array, .. }, item]) =
+            (function.as_ref(), arguments.as_slice())
+        else {
+            return None;
+        };
+        if *callee != "push" || *array != name {
+            return None;
+        }
+        if !matches!(
+            self.env.get(*callee),
+            Some(Value::Builtin { name: "push", .. })
+        ) {
+            return None;
+        }
+        let Some(Value::Array(mut items)) = self.env.get(name) else {
+            return None;
+        };
+        let item = match self.eval(item) {
+            Ok(item) => item,
+            Err(e) => return Some(Err(e)),
+        };
+        let _ = self.env.reassign(name, Value::Void);
+        let known = items.known_footprint();
+        let change = memory_limit::measuring().then(|| (memory_limit::element_size(&item), 0));
+        items.push(item);
+        items.set_footprint(memory_limit::grown(known, change));
+        let val = Value::Array(items);
+        self.observers
+            .notify(|o| o.variable_write(name, ResilientValue::from_ref(&val)));
+        let _ = self.env.reassign(name, val);
+        Some(Ok(Value::Void))
+    }
+
     fn eval_block_statement(&mut self, statements: &[Node]) -> RResult<Value> {
         // RES-1111: push a fresh enclosed env so `let` declarations
         // inside the block are confined to the block. Without this,
@@ -27554,7 +27069,17 @@ impl Interpreter {
 
         let mut result = Value::Void;
         for statement in statements {
-            match self.eval(statement) {
+            self.observers.notify(|o| {
+                o.node_enter(
+                    statement,
+                    &eval_observer::Scope(&self.env),
+                    self.coverage_function(),
+                )
+            });
+            let outcome = self.eval(statement);
+            self.observers
+                .notify(|o| o.node_exit(statement, eval_observer::outcome(&outcome)));
+            match outcome {
                 Ok(v) => {
                     result = v;
                     // RES-910: Break/Continue propagate through blocks
@@ -27800,18 +27325,21 @@ impl Interpreter {
     ) -> RResult<Value> {
         // RES-359: retry budget is now configurable via the
         // `live retries(N) { ... }` clause. `None` → ticket default
-        // (`DEFAULT_LIVE_MAX_RETRIES`). The total number of body
+        // (`default_live_retries`). The total number of body
         // attempts is `max_retries + 1` (the initial attempt plus up
         // to N retries on failure); `retries(0)` therefore means
         // exactly one attempt.
-        let max_retries: usize = max_retries_override.unwrap_or(DEFAULT_LIVE_MAX_RETRIES) as usize;
+        let max_retries: usize = max_retries_override.unwrap_or_else(default_live_retries) as usize;
         let mut retry_count: usize = 0;
 
         // Create a snapshot of the environment
         // RES-050: now that env clone is shallow (Rc bump), we must
         // explicitly deep-clone here to preserve the live-block's
-        // restore-on-retry semantics.
+        // restore-on-retry semantics. Function values in it share
+        // their bodies and contracts with the AST, so this copies no
+        // syntax.
         let env_snapshot = self.env.deep_clone();
+        let cell_snapshot = cell_store_snapshot();
 
         // Log the start of live block execution
         eprintln!("\x1B[36m[LIVE BLOCK] Starting execution of live block\x1B[0m");
@@ -27828,7 +27356,10 @@ impl Interpreter {
         // budget. Sampled once at block entry so retries and backoff
         // sleeps both count against the same budget. `None` means
         // "no timeout" and the clock is never queried.
-        let live_start = timeout_ns.map(|_| std::time::Instant::now());
+        let live_start = timeout_ns.map(|_| host_clock::monotonic_nanos());
+        let _deadline = live_start
+            .zip(timeout_ns)
+            .map(|(t0, budget)| LiveDeadlineGuard::enter(t0 + u128::from(budget)));
 
         // Try to evaluate the body with multiple retries
         loop {
@@ -27852,9 +27383,17 @@ impl Interpreter {
             match outcome {
                 Ok(value) => {
                     eprintln!("\x1B[32m[LIVE BLOCK] Successfully executed live block\x1B[0m");
+                    if metrics::active() {
+                        metrics::breaker(block_span.start.line, metrics::BreakerState::Closed);
+                    }
                     return Ok(value);
                 }
                 Err(error) => {
+                    // `exit(code)` is a request to stop, not a fault,
+                    // and a spent step budget stays spent.
+                    if exit_request().is_some() || fuel::is_exhausted(&error) {
+                        return Err(error);
+                    }
                     // RES-211: `--panic-on-fault` dev mode —
                     // disable the retry loop entirely so the
                     // first fault surfaces immediately instead of
@@ -27882,11 +27421,29 @@ impl Interpreter {
                     if retry_count < max_retries {
                         LIVE_TOTAL_RETRIES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                     }
+                    if metrics::active() {
+                        metrics::breaker(block_span.start.line, metrics::BreakerState::HalfOpen);
+                    }
+                    phase_trace::live_retry(
+                        block_span.start.line,
+                        retry_count,
+                        max_retries,
+                        &error,
+                    );
 
                     eprintln!(
                         "\x1B[33m[LIVE BLOCK] Error detected (attempt {}/{}): {}\x1B[0m",
                         retry_count, max_retries, error
                     );
+                    self.observers.notify(|o| {
+                        o.live_retry(
+                            block_span.start.line,
+                            retry_count,
+                            max_retries,
+                            &error,
+                            &eval_observer::Scope(&self.env),
+                        )
+                    });
 
                     // RES-142: budget check. If the wall-clock
                     // elapsed since block entry exceeds the
@@ -27899,7 +27456,7 @@ impl Interpreter {
                     // immediately without an extra sleep.
                     let timed_out = match (live_start, timeout_ns) {
                         (Some(t0), Some(budget)) => {
-                            let elapsed = t0.elapsed().as_nanos();
+                            let elapsed = host_clock::monotonic_nanos().saturating_sub(t0);
                             elapsed >= u128::from(budget)
                         }
                         _ => false,
@@ -27917,6 +27474,10 @@ impl Interpreter {
                         // times any live block gave up across the
                         // whole run. Timeout counts as exhaustion.
                         LIVE_TOTAL_EXHAUSTIONS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        if metrics::active() {
+                            metrics::breaker(block_span.start.line, metrics::BreakerState::Open);
+                        }
+                        phase_trace::live_exhausted(block_span.start.line, retry_count, reason);
                         // RES-140: footer note recording the
                         // nesting depth at which exhaustion fired.
                         // `LIVE_RETRY_STACK.len()` at this point
@@ -27986,6 +27547,7 @@ impl Interpreter {
                     // — otherwise the first retry's mutations would
                     // pollute the second.
                     self.env = env_snapshot.deep_clone();
+                    cell_store_restore(&cell_snapshot);
                 }
             }
         }
@@ -28125,6 +27687,15 @@ impl Interpreter {
             && can_stringify_for_concat(&left)
             && can_stringify_for_concat(&right)
         {
+            // The strict coercion policy concatenates only string + string.
+            if !crate::coercion::policy().allows_string_concat() {
+                if !matches!(left, Value::String(_)) {
+                    return Err(crate::coercion::strict_concat_error(&left));
+                }
+                if !matches!(right, Value::String(_)) {
+                    return Err(crate::coercion::strict_concat_error(&right));
+                }
+            }
             let mut s = stringify_for_concat_owned(left);
             s.push_str(&stringify_for_concat_owned(right));
             return Ok(Value::String(s));
@@ -28208,6 +27779,33 @@ impl Interpreter {
             return Ok(Value::Bool(result));
         }
 
+        // Arrays, tuples and options order element by element, the same
+        // order `sort` would give them.
+        if matches!(operator, "<" | ">" | "<=" | ">=")
+            && matches!(
+                (&left, &right),
+                (Value::Array(_), Value::Array(_))
+                    | (Value::Tuple(_), Value::Tuple(_))
+                    | (Value::Option(_), Value::Option(_))
+            )
+        {
+            let cmp = values_cmp(&left, &right).ok_or_else(|| {
+                format!(
+                    "cannot order {} values with `{}`: their elements are not comparable",
+                    crate::type_builtins::value_type_name(&left),
+                    operator
+                )
+            })?;
+            let result = match operator {
+                "<" => cmp == std::cmp::Ordering::Less,
+                ">" => cmp == std::cmp::Ordering::Greater,
+                "<=" => cmp != std::cmp::Ordering::Greater,
+                ">=" => cmp != std::cmp::Ordering::Less,
+                _ => unreachable!(),
+            };
+            return Ok(Value::Bool(result));
+        }
+
         // RES-1899: match by reference to avoid cloning both Values on
         // every infix dispatch. Int, Float, Bool inner values are Copy,
         // so dereferencing costs nothing. String is handled separately
@@ -28217,10 +27815,16 @@ impl Interpreter {
             (Value::Float(l), Value::Float(r)) => {
                 self.eval_float_infix_expression(operator, *l, *r)
             }
-            (Value::Int(_), Value::Float(_)) | (Value::Float(_), Value::Int(_)) => Err(format!(
-                "Cannot apply '{}' to int and float — Resilient does not implicitly coerce between numeric types. Use `to_float(x)` or `to_int(x)` explicitly.",
-                operator
-            )),
+            (Value::Int(_), Value::Float(_)) | (Value::Float(_), Value::Int(_)) => {
+                // The permissive coercion policy widens the int to float.
+                if let Some((l, r)) = crate::coercion::widen_pair(&left, &right) {
+                    return self.eval_float_infix_expression(operator, l, r);
+                }
+                Err(format!(
+                    "Cannot apply '{}' to int and float — Resilient does not implicitly coerce between numeric types. Use `to_float(x)` or `to_int(x)` explicitly.",
+                    operator
+                ))
+            }
             (Value::String(_), Value::String(_)) => match (left, right) {
                 (Value::String(l), Value::String(r)) => {
                     self.eval_string_infix_expression(operator, l, r)
@@ -28249,6 +27853,11 @@ impl Interpreter {
                 {
                     return Ok(v);
                 }
+                if matches!(operator, "==" | "!=" | "<" | ">" | "<=" | ">=")
+                    && (is_composite_value(&left) || is_composite_value(&right))
+                {
+                    return Err(incomparable_values_error(operator, &left, &right));
+                }
                 Err(format!("Type mismatch: {} {} {}", left, operator, right))
             }
         }
@@ -28304,10 +27913,15 @@ impl Interpreter {
         left: f64,
         right: f64,
     ) -> RResult<Value> {
+        let arith =
+            |result: f64| float_mode::check_arith(operator, left, right, result).map(Value::Float);
+        if matches!(operator, "<" | ">" | "<=" | ">=") {
+            float_mode::check_cmp(operator, left, right)?;
+        }
         match operator {
-            "+" => Ok(Value::Float(left + right)),
-            "-" => Ok(Value::Float(left - right)),
-            "*" => Ok(Value::Float(left * right)),
+            "+" => arith(left + right),
+            "-" => arith(left - right),
+            "*" => arith(left * right),
             // RES-1086: float division and modulo follow IEEE 754:
             // `x / 0.0` yields ±Inf (sign of `x`); `0.0 / 0.0` yields
             // NaN; `x % 0.0` yields NaN. The previous explicit
@@ -28315,9 +27929,10 @@ impl Interpreter {
             // (in some paths) returned exit code 0, hiding the fault
             // from CI scripts. Rust's `f64 / f64` and `f64 % f64`
             // already implement the full IEEE 754 semantics — just
-            // forward to them.
-            "/" => Ok(Value::Float(left / right)),
-            "%" => Ok(Value::Float(left % right)),
+            // forward to them. `--float-strict` turns the special
+            // results into typed errors instead (see `float_mode`).
+            "/" => arith(left / right),
+            "%" => arith(left % right),
             "==" => Ok(Value::Bool(left == right)),
             "!=" => Ok(Value::Bool(left != right)),
             "<" => Ok(Value::Bool(left < right)),
@@ -28381,6 +27996,47 @@ impl Interpreter {
         Ok(result)
     }
 
+    /// The callee and arguments of `return g(args)` when the running
+    /// frame can switch to `g` in place: `g` names a plain user fn
+    /// taking exactly these arguments (no defaults to fill in, no
+    /// overload to pick) with no postcondition or type parameters that
+    /// need a frame of their own. `g` may be the running fn itself.
+    fn tail_call_target(
+        &mut self,
+        function: &Node,
+        arguments: &[Node],
+    ) -> RResult<Option<(Box<FunctionValue>, Vec<Value>)>> {
+        let Node::Identifier { name, .. } = function else {
+            return Ok(None);
+        };
+        // Intrinsics are dispatched by name ahead of any binding.
+        if matches!(
+            builtin_registry::lookup(name),
+            Some((_, _, builtin_registry::BuiltinEval::Intrinsic))
+        ) {
+            return Ok(None);
+        }
+        let Some(Value::Function(callee)) = self.env.get(name) else {
+            return Ok(None);
+        };
+        if callee.parameters.len() != arguments.len()
+            || !callee.ensures.is_empty()
+            || callee.recovers_to.is_some()
+            || !callee.type_params.is_empty()
+            || crate::mutual_tco::is_mutual_tail_call(&callee.name)
+        {
+            return Ok(None);
+        }
+        let args = self.eval_expressions(arguments)?;
+        Ok(Some((callee, args)))
+    }
+
+    /// The function this interpreter runs the body of, `""` at the top
+    /// level, for `rz test --coverage`.
+    fn coverage_function(&self) -> &str {
+        self.call_stack.last().map_or("", |f| f.fn_name.as_str())
+    }
+
     fn apply_function(&mut self, func: &Value, args: Vec<Value>) -> RResult<Value> {
         self.apply_function_at(func, args, span::Span::default())
     }
@@ -28390,6 +28046,41 @@ impl Interpreter {
         func: &Value,
         args: Vec<Value>,
         call_span: span::Span,
+    ) -> RResult<Value> {
+        let Value::Function(fv) = func else {
+            return self.call_value(func, args, call_span);
+        };
+        self.observers.notify(|o| {
+            o.call_enter(
+                &fv.name,
+                &fv.parameters,
+                ResilientValue::from_slice(&args),
+                call_span.start.line,
+            )
+        });
+        let result = self.call_value(func, args, call_span);
+        self.observers
+            .notify(|o| o.call_exit(&fv.name, eval_observer::outcome(&result)));
+        result
+    }
+
+    /// `--backend=jit`: run `name` as native code when it is one of
+    /// the program's compiled hot functions. Native code wraps on
+    /// overflow and charges no steps, so calls under another overflow
+    /// mode or a step budget stay in the walker.
+    #[cfg(feature = "jit")]
+    fn call_hot_function(&self, name: &str, body: &Node, args: &[Value]) -> Option<Value> {
+        if self.overflow_mode != vm::OverflowMode::Wrap || fuel::is_limited() {
+            return None;
+        }
+        jit_backend::call_hot_function(name, body, args).map(Value::Int)
+    }
+
+    fn call_value(
+        &mut self,
+        func: &Value,
+        args: Vec<Value>,
+        call_span: span::Span,
     ) -> RResult<Value> {
         match func {
             Value::Function(fv) => {
@@ -28406,10 +28097,11 @@ impl Interpreter {
                 } = fv.as_ref();
                 let max_depth = max_interpreter_call_depth();
                 if self.call_depth >= max_depth {
-                    return Err(format!(
-                        "maximum interpreter call depth exceeded at fn {} (limit {})",
-                        name, max_depth
-                    ));
+                    return Err(stack_guard::call_depth_exceeded(name, max_depth));
+                }
+                #[cfg(feature = "jit")]
+                if let Some(result) = self.call_hot_function(name, body, &args) {
+                    return Ok(result);
                 }
                 // RES-050: env.clone() is now an Rc bump, not a deep
                 // copy. The self-bind hack from c58c4b1 is gone — the
@@ -28472,10 +28164,12 @@ impl Interpreter {
                     overflow_mode: self.overflow_mode,
                     tco_fn_name: None,
                     mutual_tco_fn: None,
+                    tail_calls: false,
                     trait_method_defaults: self.trait_method_defaults.clone(),
                     defer_stack: Vec::new(),
                     call_stack: child_stack,
                     source_path: self.source_path.clone(),
+                    observers: self.observers.clone(),
                 };
 
                 // RES-2592: activate the trampoline loop for #[must_tail_call] fns.
@@ -28488,6 +28182,15 @@ impl Interpreter {
                 if crate::mutual_tco::is_mutual_tail_call(name) {
                     interpreter.mutual_tco_fn = Some(name.clone());
                 }
+                // `return g(args)` switches the frame below to `g`
+                // instead of nesting a host frame per call, whether `g`
+                // is this fn or another. Not when a postcondition would
+                // need the original arguments, or for generic fns,
+                // whose substitution is per call.
+                interpreter.tail_calls = ensures.is_empty()
+                    && recovers_to.is_none()
+                    && type_params.is_empty()
+                    && interpreter.mutual_tco_fn.is_none();
 
                 // RES-405 PR 2: if this is a generic call, infer the substitution
                 // from actual argument values and store it on the child interpreter
@@ -28508,7 +28211,7 @@ impl Interpreter {
                 // the body. Parameters are already in scope; anything
                 // else (e.g. `static` bindings, closed-over vars) is
                 // reachable just like inside the body.
-                for clause in requires {
+                for clause in requires.iter() {
                     let v = interpreter.eval(clause)?;
                     if !interpreter.is_truthy(&v) {
                         return Err(render_requires_violation_error(name, clause));
@@ -28539,10 +28242,17 @@ impl Interpreter {
                 // None means "use the initial body/parameters by reference".
                 let mut mtco_body: Option<Box<Node>> = None;
                 let mut mtco_params: Option<Vec<(String, String)>> = None;
+                // The fn a `return g(args)` tail call switched to, if any.
+                let mut tail_fn: Option<Box<FunctionValue>> = None;
+                // This call's own frame; tail calls stack theirs above it.
+                let tail_base = interpreter.call_stack.len() - 1;
                 // RES-2790: the TCO loop yields Result so that body errors
                 // don't bypass deferred expressions. Defers always fire.
                 let body_outcome: Result<Value, String> = 'tco: loop {
-                    let cur_body: &Node = mtco_body.as_deref().unwrap_or(body.as_ref());
+                    let cur_body: &Node = match &tail_fn {
+                        Some(callee) => callee.body.as_ref(),
+                        None => mtco_body.as_deref().unwrap_or(body.as_ref()),
+                    };
                     let body_result = match interpreter.eval(cur_body) {
                         Ok(v) => v,
                         Err(e) => {
@@ -28556,8 +28266,19 @@ impl Interpreter {
                     };
                     match body_result {
                         Value::TailCall(new_args) => {
-                            let cur_params: &[(String, String)] =
-                                mtco_params.as_deref().unwrap_or(parameters.as_slice());
+                            // A self-call of whichever fn is running: the
+                            // one a tail call switched to, if any.
+                            let (cur_params, cur_env): (&[(String, String)], _) = match &tail_fn {
+                                Some(cur) => (cur.parameters.as_slice(), cur.env.clone()),
+                                None => (
+                                    mtco_params.as_deref().unwrap_or(parameters.as_slice()),
+                                    env.clone(),
+                                ),
+                            };
+                            // A fresh scope per iteration, as a real call
+                            // gets, so a closure an earlier iteration made
+                            // keeps the parameters it saw.
+                            interpreter.env = Environment::new_enclosed(cur_env);
                             for ((_, param_name), arg_value) in cur_params.iter().zip(new_args) {
                                 interpreter.env.set(param_name.clone(), arg_value);
                             }
@@ -28593,13 +28314,106 @@ impl Interpreter {
                             continue 'tco;
                         }
                         Value::Return(v) => match *v {
+                            Value::TailCallTo {
+                                callee,
+                                args: new_args,
+                                call_span,
+                            } => {
+                                // A fresh scope per call, as a real call
+                                // gets, enclosing the callee's own
+                                // captured env.
+                                interpreter.env = Environment::new_enclosed(callee.env.clone());
+                                for ((_, param_name), arg_value) in
+                                    callee.parameters.iter().zip(new_args)
+                                {
+                                    interpreter.env.set(param_name.clone(), arg_value);
+                                }
+                                for clause in callee.requires.iter() {
+                                    match interpreter.eval(clause) {
+                                        Ok(v) if interpreter.is_truthy(&v) => {}
+                                        Ok(_) => {
+                                            break 'tco Err(render_requires_violation_error(
+                                                &callee.name,
+                                                clause,
+                                            ));
+                                        }
+                                        Err(e) => break 'tco Err(e),
+                                    }
+                                }
+                                if self.inject_checked_failures && !callee.fails.is_empty() {
+                                    break 'tco Err(checked_failure_signal(
+                                        &callee.fails[0],
+                                        &callee.name,
+                                    ));
+                                }
+                                // The callee's frame goes on only now, so
+                                // a failed `requires` is reported from the
+                                // caller, as for any call. Stack traces
+                                // keep the chain of tail calls that led
+                                // here, but a fn already in it cuts the
+                                // chain back to that frame, so a recursion
+                                // cycle stays bounded.
+                                let frames = &mut interpreter.call_stack;
+                                match frames[tail_base..]
+                                    .iter()
+                                    .position(|f| f.fn_name == callee.name)
+                                {
+                                    Some(i) => frames.truncate(tail_base + i + 1),
+                                    None => frames.push(crate::error_stack_traces::StackFrame {
+                                        fn_name: callee.name.clone(),
+                                        call_span,
+                                    }),
+                                }
+                                // Self-calls now mean the callee, and only
+                                // if it is itself `#[must_tail_call]`.
+                                interpreter.tco_fn_name =
+                                    crate::tail_calls::is_must_tail_call(&callee.name)
+                                        .then(|| callee.name.clone());
+                                tail_fn = Some(callee);
+                                continue 'tco;
+                            }
                             Value::TailCall(new_args) => {
-                                let cur_params: &[(String, String)] =
-                                    mtco_params.as_deref().unwrap_or(parameters.as_slice());
+                                let (cur_name, cur_params, cur_env, cur_requires): (
+                                    &str,
+                                    &[(String, String)],
+                                    _,
+                                    &[Node],
+                                ) = match &tail_fn {
+                                    Some(cur) => (
+                                        &cur.name,
+                                        cur.parameters.as_slice(),
+                                        cur.env.clone(),
+                                        cur.requires.as_slice(),
+                                    ),
+                                    None => (
+                                        name,
+                                        mtco_params.as_deref().unwrap_or(parameters.as_slice()),
+                                        env.clone(),
+                                        if mtco_params.is_none() {
+                                            requires.as_slice()
+                                        } else {
+                                            &[]
+                                        },
+                                    ),
+                                };
+                                interpreter.env = Environment::new_enclosed(cur_env);
                                 for ((_, param_name), arg_value) in cur_params.iter().zip(new_args)
                                 {
                                     interpreter.env.set(param_name.clone(), arg_value);
                                 }
+                                // Each iteration is a call as far as
+                                // `requires` is concerned.
+                                for clause in cur_requires {
+                                    match interpreter.eval(clause) {
+                                        Ok(v) if interpreter.is_truthy(&v) => {}
+                                        Ok(_) => {
+                                            break 'tco Err(render_requires_violation_error(
+                                                cur_name, clause,
+                                            ));
+                                        }
+                                        Err(e) => break 'tco Err(e),
+                                    }
+                                }
                                 continue 'tco;
                             }
                             Value::MutualTailCall {
@@ -28680,7 +28494,7 @@ impl Interpreter {
                     interpreter
                         .env
                         .set("result".to_string(), return_value.clone());
-                    for clause in ensures {
+                    for clause in ensures.iter() {
                         let v = interpreter.eval(clause)?;
                         if !interpreter.is_truthy(&v) {
                             return Err(render_ensures_violation_error(
@@ -28716,6 +28530,24 @@ impl Interpreter {
                 {
                     return result;
                 }
+                // `E::V as int` — the variant's discriminant.
+                if *name == "to_int"
+                    && let [
+                        Value::EnumVariant {
+                            type_name, variant, ..
+                        },
+                    ] = args.as_slice()
+                {
+                    let enums = self.enum_decls.borrow();
+                    if let Some(variants) = enums.get(type_name) {
+                        return crate::const_eval::ConstEvaluator::with_resolved(
+                            &self.consts,
+                            &enums,
+                        )
+                        .discriminant_of(type_name, variants, variant)
+                        .map(Value::Int);
+                    }
+                }
                 func(&args)
             }
             #[cfg(feature = "ffi")]
@@ -28753,10 +28585,12 @@ impl Interpreter {
                     overflow_mode: self.overflow_mode,
                     tco_fn_name: None,
                     mutual_tco_fn: None,
+                    tail_calls: false,
                     trait_method_defaults: self.trait_method_defaults.clone(),
                     defer_stack: Vec::new(),
                     call_stack: self.call_stack.clone(),
                     source_path: self.source_path.clone(),
+                    observers: self.observers.clone(),
                 };
                 for pre in requires {
                     let ok = match contract_interp.eval(pre)? {
@@ -28790,10 +28624,12 @@ impl Interpreter {
                         overflow_mode: self.overflow_mode,
                         tco_fn_name: None,
                         mutual_tco_fn: None,
+                        tail_calls: false,
                         trait_method_defaults: self.trait_method_defaults.clone(),
                         defer_stack: Vec::new(),
                         call_stack: self.call_stack.clone(),
                         source_path: self.source_path.clone(),
+                        observers: self.observers.clone(),
                     };
                     post_interp.env.set("result".to_string(), result.clone());
                     for post in ensures {
@@ -28819,6 +28655,11 @@ impl Interpreter {
                 variant,
                 arity,
             } => crate::enum_ctors::apply_constructor(type_name, variant, *arity, args),
+            Value::Overloads(set) => {
+                let chosen =
+                    Value::Function(Box::new(crate::overloads::select(set, &args)?.clone()));
+                self.apply_function_at(&chosen, args, call_span)
+            }
             _ => Err(format!("Not a function: {}", func)),
         }
     }
@@ -29292,14 +29133,14 @@ fn start_repl() -> RustylineResult<()> {
                 }
 
                 // Parse the input
-                let lexer = Lexer::new(input);
-                let mut parser = Parser::new(lexer);
-                let mut program = parser.parse_program();
+                let (mut program, parse_errs) = parse_unlowered(input, true);
 
                 // Skip evaluation if any parser errors were recorded
-                if !parser.errors.is_empty() {
+                if !parse_errs.is_empty() {
                     continue;
                 }
+                // Mangle overload sets before any name-keyed pass.
+                crate::overloads::lower_program(&mut program);
                 // RES-325: lower named call arguments to positional
                 // ones for known top-level fns so the interpreter's
                 // hot path stays free of named-arg awareness.
@@ -29560,6 +29401,59 @@ fn dump_tokens_string(src: &str) -> String {
     lines.join("\n")
 }
 
+/// The parsed AST of `src` as an indented tree, for the REPL's `:ast`.
+/// Built from `Node`'s `Debug` output, like `--dump-tokens`, so every
+/// variant prints without a matching change here; source spans are
+/// dropped and statements are shown without their `Spanned` wrapper.
+fn dump_ast_string(src: &str) -> Result<String, Vec<String>> {
+    let (program, errs) = parse_silent(src);
+    if !errs.is_empty() {
+        return Err(errs);
+    }
+    let debug = format!("{program:#?}");
+    let mut out: Vec<String> = Vec::new();
+    // Indent of a `span: Span {` field being skipped, or of a
+    // `Spanned {` wrapper whose own braces are being elided.
+    let mut skip_until: Option<usize> = None;
+    let mut unwrapped: Vec<usize> = Vec::new();
+    for line in debug.lines() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if let Some(level) = skip_until {
+            if indent == level && trimmed.starts_with('}') {
+                skip_until = None;
+            }
+            continue;
+        }
+        if trimmed.ends_with("Span {") && trimmed.contains(": ") {
+            skip_until = Some(indent);
+            continue;
+        }
+        if trimmed == "Spanned {" {
+            unwrapped.push(indent);
+            continue;
+        }
+        if unwrapped.last() == Some(&indent) && trimmed.starts_with('}') {
+            unwrapped.pop();
+            continue;
+        }
+        // Inside a `Spanned`, `node: X {` becomes `X {`, one level out.
+        let depth = unwrapped.len() * 4;
+        let body = match unwrapped.last() {
+            Some(&level) if indent == level + 4 => {
+                trimmed.strip_prefix("node: ").unwrap_or(trimmed)
+            }
+            _ => trimmed,
+        };
+        out.push(format!(
+            "{}{}",
+            " ".repeat(indent.saturating_sub(depth)),
+            body
+        ));
+    }
+    Ok(out.join("\n"))
+}
+
 fn dump_ast_json_value(src: &str) -> Result<serde_json::Value, Vec<String>> {
     fn node_to_json(node: &Node) -> serde_json::Value {
         use serde_json::json;
@@ -29595,7 +29489,7 @@ fn dump_ast_json_value(src: &str) -> Result<serde_json::Value, Vec<String>> {
             }),
             Node::Identifier { name, .. } => json!({
                 "type": "Identifier",
-                "name": name,
+                "name": name.as_str(),
             }),
             Node::IntegerLiteral { value, .. } => json!({
                 "type": "Int",
@@ -29641,7 +29535,7 @@ fn dump_ast_json_value(src: &str) -> Result<serde_json::Value, Vec<String>> {
                 ..
             } => {
                 let callee = match function.as_ref() {
-                    Node::Identifier { name, .. } => serde_json::Value::String(name.clone()),
+                    Node::Identifier { name, .. } => serde_json::Value::String(name.to_string()),
                     other => node_to_json(other),
                 };
                 json!({
@@ -29707,6 +29601,46 @@ fn dump_ast_json_to_stdout(src: &str) -> Result<(), Vec<String>> {
     Ok(())
 }
 
+/// Output shape of `--dump-ast[=json]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum AstDumpFormat {
+    /// The indented tree the REPL's `:ast` prints.
+    Tree,
+    /// The RES-781 JSON view, same as `--dump-ast-json`.
+    Json,
+}
+
+impl AstDumpFormat {
+    /// Parse the `=VALUE` part of `--dump-ast=VALUE`.
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "tree" | "pretty" => Some(AstDumpFormat::Tree),
+            "json" => Some(AstDumpFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// The flag spelling used in usage errors.
+    fn flag(self) -> &'static str {
+        match self {
+            AstDumpFormat::Tree => "--dump-ast",
+            AstDumpFormat::Json => "--dump-ast-json",
+        }
+    }
+}
+
+/// Print the AST of `src` in `format`; parse errors are returned
+/// instead.
+fn dump_ast_to_stdout(src: &str, format: AstDumpFormat) -> Result<(), Vec<String>> {
+    match format {
+        AstDumpFormat::Tree => {
+            println!("{}", dump_ast_string(src)?);
+            Ok(())
+        }
+        AstDumpFormat::Json => dump_ast_json_to_stdout(src),
+    }
+}
+
 /// RES-073: shared parse helper. Returns the parsed program plus any
 /// parser error strings collected along the way. Used by both the
 /// driver and `imports::expand_uses`.
@@ -29719,38 +29653,126 @@ fn parse_silent(src: &str) -> (Node, Vec<String>) {
 }
 
 fn parse_with_emit_errors(src: &str, emit_errors: bool) -> (Node, Vec<String>) {
-    let lexer = Lexer::new(src);
-    let mut parser = if emit_errors {
-        Parser::new(lexer)
-    } else {
-        Parser::new_silent(lexer)
+    let (mut program, mut errs) = parse_unlowered(src, emit_errors);
+    if let Err(err) = catch_parse_panic(|| lower_parsed_program(&mut program, &mut errs)) {
+        return parse_panic_result(err, emit_errors);
+    }
+    (program, errs)
+}
+
+/// Parse `src` without the lowering passes. A panic anywhere in the
+/// lexer or parser comes back as a parse error at `1:1` and an empty
+/// program, so no input can take down the driver, the REPL or a
+/// language server.
+fn parse_unlowered(src: &str, emit_errors: bool) -> (Node, Vec<String>) {
+    let parsed = catch_parse_panic(|| {
+        let _span = phase_trace::parse();
+        let lexer = {
+            let _span = phase_trace::lex();
+            Lexer::new(src)
+        };
+        let mut parser = Parser::new_with_emit_errors(lexer, emit_errors);
+        let program = parser.parse_program();
+        // RES-1343: `parser.errors` is already `Vec<String>`; move it
+        // directly instead of the round-trip
+        // `.into_iter().map(|e| e.to_string()).collect()` shape — every
+        // `String::to_string` allocated a fresh `String` identical to
+        // its source. Saves one heap alloc per recorded parser error
+        // (zero on the clean-parse fast path, since the Vec is empty).
+        (program, parser.errors)
+    });
+    parsed.unwrap_or_else(|err| parse_panic_result(err, emit_errors))
+}
+
+/// Run `f`, turning a panic into the message of an internal parser
+/// error. The panic hook still prints its usual line.
+fn catch_parse_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(|payload| {
+        let what = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown cause");
+        format!("internal parser error: {what}")
+    })
+}
+
+fn parse_panic_result(err: String, emit_errors: bool) -> (Node, Vec<String>) {
+    let full = format!("1:1: {err}");
+    if emit_errors {
+        eprintln!("\x1B[31mParser error: {}\x1B[0m", full);
+    }
+    (Node::Program(Vec::new()), vec![full])
+}
+
+/// Parse and lower `src` into a `Node::Program`, or return every parse
+/// error as a [`diag::Diagnostic`]. Prints nothing and never panics,
+/// whatever the input; this is the entry point for fuzzers, servers
+/// and other embedders.
+///
+/// ```
+/// let program = resilient::parse_source("let x = 1;").unwrap();
+/// assert!(matches!(program, resilient::Node::Program(_)));
+///
+/// let errors = resilient::parse_source("let = ;").unwrap_err();
+/// assert_eq!(errors[0].span.start.line, 1);
+/// ```
+pub fn parse_source(src: &str) -> Result<Node, Vec<diag::Diagnostic>> {
+    let (program, errs) = parse_silent(src);
+    if errs.is_empty() {
+        return Ok(program);
+    }
+    Err(errs
+        .iter()
+        .map(|e| parse_error_diagnostic(src, e))
+        .collect())
+}
+
+/// Convert a `line:col: message` parser error into a diagnostic with a
+/// zero-width span. A `[E0003] ` rich-diagnostics prefix becomes the
+/// code; every other parse error is `E0001`.
+fn parse_error_diagnostic(src: &str, err: &str) -> diag::Diagnostic {
+    let (line, column, msg) = parse_error_location(err);
+    let (line, column) = (line.max(1) as usize, column.max(1) as usize);
+    let (code, msg) = match msg.strip_prefix('[').and_then(|m| m.split_once("] ")) {
+        Some(("E0003", rest)) => (diag::codes::E0003, rest.to_string()),
+        Some(("E0001", rest)) => (diag::codes::E0001, rest.to_string()),
+        _ => (diag::codes::E0001, msg),
     };
-    let mut program = parser.parse_program();
-    // RES-1343: `parser.errors` is already `Vec<String>`; move it
-    // directly instead of the round-trip
-    // `.into_iter().map(|e| e.to_string()).collect()` shape — every
-    // `String::to_string` allocated a fresh `String` identical to
-    // its source. Saves one heap alloc per recorded parser error
-    // (zero on the clean-parse fast path, since the Vec is empty).
-    let mut errs: Vec<String> = parser.errors;
+    let offset: usize = src
+        .split_inclusive('\n')
+        .take(line - 1)
+        .map(|l| l.chars().count())
+        .sum::<usize>()
+        + column
+        - 1;
+    let pos = span::Pos::new(line, column, offset);
+    diag::Diagnostic::new(diag::Severity::Error, span::Span::new(pos, pos), msg).with_code(code)
+}
+
+/// The whole-program rewrites run after parsing, shared by
+/// `parse_with_emit_errors` and `incremental_parse`. Lowering errors
+/// are appended to `errs`.
+fn lower_parsed_program(program: &mut Node, errs: &mut Vec<String>) {
     // RES-325: lower named call arguments to positional ones for
     // every call whose callee is a known top-level fn or impl
     // method. Failures here (unknown name, duplicate target, etc.)
     // surface alongside parse errors so callers see them in one
     // batch via the same `errs` channel.
-    if let Err(e) = crate::named_args::lower_program(&mut program) {
+    // Mangle overload sets before any pass that keys fns by name.
+    crate::overloads::lower_program(program);
+    if let Err(e) = crate::named_args::lower_program(program) {
         errs.push(e);
     }
     // RES-326: fill in omitted trailing arguments with their declared
     // default expressions. Runs after named-arg lowering so positional
     // reordering has already happened before we count arguments.
-    crate::default_params::lower_program(&mut program);
+    crate::default_params::lower_program(program);
     // RES-319: rewrite newtype constructor calls before eval.
-    crate::newtypes::lower_program(&mut program);
-    crate::macros::lower_program(&mut program);
+    crate::newtypes::lower_program(program);
+    crate::macros::lower_program(program);
     // RES-2685: synthesize concrete ImplBlocks from BlanketImpl nodes.
-    crate::blanket_impl::lower_program(&mut program);
-    (program, errs)
+    crate::blanket_impl::lower_program(program);
 }
 
 fn parse_diagnostics_json_values<'a>(
@@ -29802,16 +29824,14 @@ fn check_diagnostics_json_values(
 /// back into AST nodes.
 pub(crate) fn parse_single_expression(src: &str) -> Option<Node> {
     let wrapped = format!("fn __mexp__() {{ {} }}", src);
-    let lexer = Lexer::new(&wrapped);
-    let mut parser = Parser::new(lexer);
-    let program = parser.parse_program();
-    if !parser.errors.is_empty() {
+    let (program, errs) = parse_unlowered(&wrapped, true);
+    if !errs.is_empty() {
         return None;
     }
     if let Node::Program(stmts) = program
         && let Some(spanned) = stmts.into_iter().next()
         && let Node::Function { body, .. } = spanned.node
-        && let Node::Block { stmts, .. } = *body
+        && let Node::Block { stmts, .. } = Arc::unwrap_or_clone(body)
     {
         // Unwrap ExpressionStatement wrapper if present.
         return stmts.into_iter().next().map(|n| match n {
@@ -30080,6 +30100,10 @@ pub(crate) mod sem_tok {
     pub const MOD_DECLARATION: u32 = 1 << 0;
     #[allow(dead_code)]
     pub const MOD_READONLY: u32 = 1 << 1;
+    /// Contract keywords (`requires`, `ensures`, `assert`, ...): a
+    /// `KEYWORD` with this custom modifier, so clients that don't know
+    /// it still color them as keywords.
+    pub const MOD_CONTRACT: u32 = 1 << 2;
 }
 
 /// RES-187: one semantic-token tuple before delta encoding.
@@ -30100,15 +30124,9 @@ pub(crate) struct AbsSemToken {
 /// tokenType, modifiers]*`. The result is what `SemanticTokens
 /// { data, .. }` carries back to the client.
 ///
-/// Approach: walk the lexer's token stream (runs in-process — we
-/// keep the same lexer the compiler uses, so keyword / literal
-/// lists can't drift). A tiny state machine handles the
-/// keyword-context cases where the lexer alone can't distinguish
-/// (identifier after `fn` → FUNCTION + DECLARATION; after
-/// `struct`/`type` → TYPE + DECLARATION; after `let`/`static` →
-/// VARIABLE + DECLARATION; after `new` → TYPE; default identifier
-/// → VARIABLE). A separate source-text sweep picks up line and
-/// block comments which the lexer discards.
+/// Classification comes from `highlight::highlight` (lexer tokens,
+/// identifier context, and the names the parsed program declares);
+/// this layer only maps kinds onto the `sem_tok` legend.
 ///
 /// Each tuple is absolute at collection time, then sorted by
 /// (line, col) and delta-encoded in a final pass per the LSP
@@ -30125,248 +30143,50 @@ pub(crate) fn compute_semantic_tokens(src: &str) -> Vec<u32> {
 /// coordinate tuples without re-decoding the delta array.
 #[allow(dead_code)]
 pub(crate) fn collect_semantic_tokens(src: &str) -> Vec<AbsSemToken> {
+    use crate::highlight::HighlightKind;
+    // Char length of each line, for splitting multi-line tokens.
+    let line_lens: Vec<u32> = src.split('\n').map(|l| l.chars().count() as u32).collect();
     let mut out: Vec<AbsSemToken> = Vec::new();
-    // Lexer-driven pass for keywords / literals / operators /
-    // identifiers.
-    let mut lex = Lexer::new(src);
-    let mut prev_kw: Option<Token> = None;
-    loop {
-        let (tok, span) = lex.next_token_with_span();
-        if matches!(tok, Token::Eof) {
-            break;
-        }
-        if let Some(entry) = classify_lex_token(&tok, prev_kw.as_ref(), span) {
-            out.push(entry);
-        }
-        // Track "previous keyword" for the next identifier's
-        // context classification. We reset to None on tokens
-        // that would break the context (e.g. a `,` between
-        // params clears the `fn`-context so the NEXT identifier
-        // after the open paren is treated as a parameter name,
-        // not the function's own name).
-        prev_kw = match &tok {
-            Token::Function
-            | Token::Struct
-            | Token::Type
-            | Token::New
-            | Token::Let
-            | Token::Static => Some(tok.clone()),
-            _ => None,
+    for token in crate::highlight::highlight(src) {
+        let (ty, mut modifiers) = match token.kind {
+            HighlightKind::Keyword => (sem_tok::KEYWORD, 0),
+            HighlightKind::ContractKeyword => (sem_tok::KEYWORD, sem_tok::MOD_CONTRACT),
+            HighlightKind::Function => (sem_tok::FUNCTION, 0),
+            HighlightKind::Type => (sem_tok::TYPE, 0),
+            HighlightKind::Variable => (sem_tok::VARIABLE, 0),
+            HighlightKind::String => (sem_tok::STRING, 0),
+            HighlightKind::Number => (sem_tok::NUMBER, 0),
+            HighlightKind::Comment => (sem_tok::COMMENT, 0),
+            HighlightKind::Operator => (sem_tok::OPERATOR, 0),
         };
-    }
-    // Comment pass — the lexer discards these so we scan the
-    // source text for `// ... \n` and `/* ... */`.
-    out.extend(scan_comment_tokens(src));
-    out
-}
-
-/// RES-187: map one lexer token to a semantic-token tuple,
-/// given the most-recently-seen keyword context.
-fn classify_lex_token(
-    tok: &Token,
-    prev_kw: Option<&Token>,
-    span: span::Span,
-) -> Option<AbsSemToken> {
-    // LSP uses 0-indexed line/character.
-    let line = span.start.line.saturating_sub(1) as u32;
-    let col = span.start.column.saturating_sub(1) as u32;
-    // Length in chars (RES-115: offsets are char-counts).
-    let length = span.end.offset.saturating_sub(span.start.offset) as u32;
-
-    let (ty, modifiers) = match tok {
-        // Keywords.
-        Token::Function
-        | Token::Let
-        | Token::Live
-        | Token::Assert
-        | Token::Assume
-        | Token::If
-        | Token::Else
-        | Token::Return
-        | Token::Static
-        | Token::While
-        | Token::For
-        | Token::In
-        | Token::Requires
-        | Token::Ensures
-        | Token::RecoversTo
-        | Token::Invariant
-        | Token::Fails
-        | Token::Struct
-        | Token::New
-        | Token::Match
-        | Token::Use
-        | Token::As
-        | Token::Impl
-        | Token::Trait
-        | Token::Type
-        | Token::Default
-        | Token::BoolLiteral(_) => (sem_tok::KEYWORD, 0),
-
-        // Numeric / string / bytes literals.
-        Token::IntLiteral(_) | Token::FloatLiteral(_) => (sem_tok::NUMBER, 0),
-        Token::StringLiteral(_) | Token::BytesLiteral(_) => (sem_tok::STRING, 0),
-
-        // Identifiers: context-dependent.
-        Token::Identifier(_) => match prev_kw {
-            Some(Token::Function) => (sem_tok::FUNCTION, sem_tok::MOD_DECLARATION),
-            Some(Token::Struct) | Some(Token::Type) => (sem_tok::TYPE, sem_tok::MOD_DECLARATION),
-            Some(Token::New) => (sem_tok::TYPE, 0),
-            Some(Token::Let) | Some(Token::Static) => (sem_tok::VARIABLE, sem_tok::MOD_DECLARATION),
-            _ => (sem_tok::VARIABLE, 0),
-        },
-
-        // Operators. Covers arithmetic, comparison, logical,
-        // bitwise, assignment, prefix !, and the match-arrow
-        // forms. Brackets / braces / parens / semicolons /
-        // commas are delimiters not highlighted as operators in
-        // any standard LSP legend, so they get no token.
-        Token::Plus
-        | Token::Minus
-        | Token::Multiply
-        | Token::Divide
-        | Token::Modulo
-        | Token::Assign
-        | Token::Equal
-        | Token::NotEqual
-        | Token::And
-        | Token::Or
-        | Token::BitAnd
-        | Token::BitOr
-        | Token::BitXor
-        | Token::ShiftLeft
-        | Token::ShiftRight
-        | Token::Greater
-        | Token::Less
-        | Token::GreaterEqual
-        | Token::LessEqual
-        | Token::Bang
-        | Token::Dot
-        | Token::FatArrow
-        | Token::Arrow
-        | Token::Question
-        | Token::DoubleColon => (sem_tok::OPERATOR, 0),
-
-        // Delimiters + internal tokens: no semantic highlight.
-        _ => return None,
-    };
-    // Zero-length tokens aren't useful and some clients reject
-    // them; skip.
-    if length == 0 {
-        return None;
-    }
-    Some(AbsSemToken {
-        line,
-        col,
-        length,
-        ty,
-        modifiers,
-    })
-}
-
-/// RES-187: scan `src` for `// ... \n` line comments and
-/// `/* ... */` block comments, emitting one token per comment.
-/// Walks char-by-char; only allocates the output vec. Handles
-/// nested-less block comments — our lexer doesn't support nested
-/// either.
-fn scan_comment_tokens(src: &str) -> Vec<AbsSemToken> {
-    let mut out = Vec::new();
-    let chars: Vec<char> = src.chars().collect();
-    let mut line: u32 = 0;
-    let mut col: u32 = 0;
-    let mut i = 0;
-    while i < chars.len() {
-        // Line comment?
-        if i + 1 < chars.len() && chars[i] == '/' && chars[i + 1] == '/' {
-            let start_line = line;
-            let start_col = col;
-            let mut j = i;
-            while j < chars.len() && chars[j] != '\n' {
-                j += 1;
-            }
-            out.push(AbsSemToken {
-                line: start_line,
-                col: start_col,
-                length: (j - i) as u32,
-                ty: sem_tok::COMMENT,
-                modifiers: 0,
-            });
-            col += (j - i) as u32;
-            i = j;
-            continue;
-        }
-        // Block comment?
-        if i + 1 < chars.len() && chars[i] == '/' && chars[i + 1] == '*' {
-            let start_line = line;
-            let start_col = col;
-            let mut j = i + 2;
-            // Each block comment produces ONE token per line it
-            // spans — most clients render a single token as a
-            // unit, but if a block comment spans multiple lines
-            // we emit per-line tokens so the delta-array stays
-            // legal (tokens must not span line boundaries per
-            // the LSP spec). Walk tracking current line/col.
-            let mut cur_line = start_line;
-            let mut cur_col = start_col;
-            let mut seg_start_col = cur_col;
-            while j + 1 < chars.len() && !(chars[j] == '*' && chars[j + 1] == '/') {
-                if chars[j] == '\n' {
-                    // Flush the segment on this line.
-                    let seg_chars = j - i;
-                    let length = seg_chars as u32 - (seg_start_col - start_col);
-                    // But we want per-line tokens: split into
-                    // [cur_line, seg_start_col .. cur_col].
-                    // cur_col has been tracking; use it as the
-                    // end of segment.
-                    let seg_len = cur_col - seg_start_col;
-                    if seg_len > 0 {
-                        out.push(AbsSemToken {
-                            line: cur_line,
-                            col: seg_start_col,
-                            length: seg_len,
-                            ty: sem_tok::COMMENT,
-                            modifiers: 0,
-                        });
-                    }
-                    cur_line += 1;
-                    cur_col = 0;
-                    seg_start_col = 0;
-                    let _ = length; // suppress unused warning in some configs
-                } else {
-                    cur_col += 1;
-                }
-                j += 1;
-            }
-            // Closing `*/` (if present).
-            if j + 1 < chars.len() && chars[j] == '*' && chars[j + 1] == '/' {
-                cur_col += 2;
-                j += 2;
-            }
-            // Flush the final segment.
-            let seg_len = cur_col.saturating_sub(seg_start_col);
-            if seg_len > 0 {
+        if token.declaration {
+            modifiers |= sem_tok::MOD_DECLARATION;
+        }
+        // LSP uses 0-indexed line/character, and a token may not
+        // cross a line boundary: split block comments and
+        // triple-quoted strings into one token per line.
+        let (start, end) = (token.span.start, token.span.end);
+        for line in start.line..=end.line {
+            let col = if line == start.line {
+                start.column as u32 - 1
+            } else {
+                0
+            };
+            let end_col = if line == end.line {
+                end.column as u32 - 1
+            } else {
+                line_lens.get(line - 1).copied().unwrap_or(0)
+            };
+            if end_col > col {
                 out.push(AbsSemToken {
-                    line: cur_line,
-                    col: seg_start_col,
-                    length: seg_len,
-                    ty: sem_tok::COMMENT,
-                    modifiers: 0,
+                    line: line as u32 - 1,
+                    col,
+                    length: end_col - col,
+                    ty,
+                    modifiers,
                 });
             }
-            // Sync outer line/col/i to where we ended up.
-            line = cur_line;
-            col = cur_col;
-            i = j;
-            continue;
         }
-        // Advance (and track newlines).
-        if chars[i] == '\n' {
-            line += 1;
-            col = 0;
-        } else {
-            col += 1;
-        }
-        i += 1;
     }
     out
 }
@@ -30598,10 +30418,27 @@ fn run_actor_verification(_program: &Node) {}
 /// The safety cap (`MAX_ACTOR_STEPS`) catches runaway programs where
 /// actors keep re-spawning or sending in a tight loop without making
 /// progress toward termination.
+/// The actor the scheduler runs next. Each pick is a `record_replay`
+/// input, so `--replay` dispatches actors in the recorded order.
+pub(crate) fn next_scheduled_actor() -> Option<actor_runtime::ActorPid> {
+    let mut live_pick = None;
+    let pid = record_replay::number(record_replay::Kind::ActorDispatch, || {
+        live_pick = actor_runtime::next_runnable_actor();
+        live_pick.map_or(0, |p| p.0)
+    });
+    if live_pick.is_some() || pid == 0 {
+        return live_pick;
+    }
+    actor_runtime::take_runnable_actor(actor_runtime::ActorPid(pid)).or_else(|| {
+        record_replay::diverge(&format!("recorded actor {pid} is not runnable"));
+        actor_runtime::next_runnable_actor()
+    })
+}
+
 fn run_pending_actors(interpreter: &mut Interpreter) -> RResult<()> {
     const MAX_ACTOR_STEPS: usize = 100_000;
     let mut steps = 0;
-    while let Some(pid) = actor_runtime::next_runnable_actor() {
+    while let Some(pid) = next_scheduled_actor() {
         if steps >= MAX_ACTOR_STEPS {
             return Err(format!(
                 "actor scheduler exceeded step limit ({MAX_ACTOR_STEPS}); \
@@ -30616,9 +30453,11 @@ fn run_pending_actors(interpreter: &mut Interpreter) -> RResult<()> {
                 continue;
             }
         };
+        let dispatch = phase_trace::actor_dispatch(pid.0);
         actor_runtime::set_current_actor(Some(pid));
         let result = interpreter.apply_function(&fn_val, vec![]);
         actor_runtime::set_current_actor(None);
+        drop(dispatch);
         match result {
             Ok(_) => {
                 let _ = actor_runtime::deregister_actor(pid);
@@ -30633,10 +30472,7 @@ fn run_pending_actors(interpreter: &mut Interpreter) -> RResult<()> {
                     actor_pid: pid.0,
                     reason: supervisor_runtime::CrashReason::UnhandledError,
                 };
-                let now_secs = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
+                let now_secs = host_clock::wall_clock_since_epoch().as_secs();
 
                 if supervisor_runtime::handle_crash_event(crash_event, now_secs) {
                     // Supervisor policy says restart: re-register and re-queue.
@@ -30702,6 +30538,19 @@ const DEFAULT_BUILD_VERIFY_STABLE_PATH: &str = "run `rz --typecheck <file>` or `
 const DEFAULT_BUILD_Z3_THEORY_STABLE_PATH: &str =
     "omit --z3-theory; the default build still runs non-SMT type checks.";
 
+/// `--metrics-port`: start serving the counters before the run, and
+/// keep the memory estimate they report even without `--max-memory`.
+fn start_metrics(addr: &str) {
+    match metrics::serve(addr) {
+        Ok(local) => eprintln!("[metrics] serving http://{local}/metrics"),
+        Err(e) => {
+            eprintln!("Error: --metrics-port: can't listen on {addr}: {e}");
+            std::process::exit(2);
+        }
+    }
+    memory_limit::set_tracking(true);
+}
+
 // RES-3840: Check vibe_debt score against a threshold and exit accordingly.
 // Returns exit code (0 if passed, 2 if failed). Emits JSON to stderr.
 fn check_vibe_gate(
@@ -30795,12 +30644,14 @@ fn run_via_vm(
     no_cache: bool,
     cache_dir: &Path,
     source_hash: &str,
-) -> RResult<()> {
+) -> RResult<i32> {
     // RES-076 + RES-081: bytecode VM path. Compile the AST into
     // a Program (main chunk + function table) and run it.
     // RES-405 PR 3: lower generic functions to monomorphic specializations
     // before handing the AST to the bytecode compiler.
-    let program = monomorph::lower(program);
+    // The VM has no prelude frame; compile in what the program calls.
+    let program = prelude::link(program);
+    let program = monomorph::lower(&program);
     // RES-2605: devirtualize statically-known trait method calls after
     // monomorphization so specialized clones get direct-call rewrites too.
     let program = devirtualize::lower(&program);
@@ -30810,7 +30661,8 @@ fn run_via_vm(
     // which never auto-prints `interpreter.eval(&program)`'s Ok value.
     // Printing it here caused a spurious extra output line on every
     // program whose last top-level statement was a non-Void expression.
-    vm::run_with_source(&prog, filename).map_err(|e| {
+    // It only feeds `main_exit_code`.
+    let value = vm::run_with_source(&prog, filename).map_err(|e| {
         // RES-095: mirror the typechecker's `<file>:<line>:` shape
         // so VM runtime errors are editor-clickable when the
         // wrapper carries a source line. Other variants fall back
@@ -30821,6 +30673,20 @@ fn run_via_vm(
         // points at the offending line; precise column info
         // would need RES-091 to upgrade from line-only to a
         // full Span (tracked there, not here).
+        if error_format::is_structured() {
+            let (line, message) = match &e {
+                vm::VmError::AtLine { line, kind } => (*line as usize, kind.to_string()),
+                other => (0, other.to_string()),
+            };
+            error_format::report_at(
+                error_format::Phase::Runtime,
+                filename,
+                contents,
+                line,
+                1,
+                &message,
+            );
+        }
         if let vm::VmError::AtLine { line, kind } = &e {
             let header = format!("{}:{}: VM runtime error: {}", filename, line, kind);
             let caret = diag::format_diagnostic_from_line_col(
@@ -30839,13 +30705,73 @@ fn run_via_vm(
     if !no_cache {
         cache::write_entry(cache_dir, source_hash);
     }
-    Ok(())
+    main_exit_code(&program, &value)
+}
+
+/// `std::process::exit` skips destructors, so flush a partial
+/// `print` line first.
+fn exit_flushed(code: i32) -> ! {
+    use std::io::Write;
+    let _ = std::io::stdout().flush();
+    std::process::exit(code)
+}
+
+/// The exit status a finished program asks for: what a `main`
+/// declared `-> int` returned, when the last top-level statement is
+/// the `main(...)` call; 0 otherwise. Untyped `main`s keep exiting 0,
+/// since their result is often just an incidental last value. A
+/// result outside `0..=255` fails with `InvalidArgument`, as `exit`
+/// does.
+fn main_exit_code(program: &Node, value: &Value) -> RResult<i32> {
+    let Node::Program(statements) = program else {
+        return Ok(0);
+    };
+    let int_main = statements.iter().any(|s| {
+        matches!(
+            &s.node,
+            Node::Function { name, return_type, .. }
+                if name == "main" && return_type.as_deref() == Some("int")
+        )
+    });
+    let last = statements.iter().rev().find(|s| {
+        !matches!(
+            s.node,
+            Node::Function { .. } | Node::ImplBlock { .. } | Node::ModuleDecl { .. }
+        )
+    });
+    let call = match last.map(|s| &s.node) {
+        Some(Node::ExpressionStatement { expr, .. }) => expr.as_ref(),
+        Some(other) => other,
+        None => return Ok(0),
+    };
+    let calls_main = matches!(
+        call,
+        Node::CallExpression { function, .. }
+            if matches!(function.as_ref(), Node::Identifier { name, .. } if name == "main")
+    );
+    match value {
+        Value::Int(code) if int_main && calls_main => checked_exit_code("main", *code),
+        _ => Ok(0),
+    }
+}
+
+thread_local! {
+    /// The type error the last `execute_file` reported in soft mode
+    /// before running the program anyway; `--watch` summarizes it.
+    static SOFT_TYPE_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn take_soft_type_error() -> Option<String> {
+    SOFT_TYPE_ERROR.with(|e| e.borrow_mut().take())
 }
 
 // Execute a Resilient source file
 #[allow(clippy::too_many_arguments)] // CLI glue fn — all args come from flags
 fn execute_file(
     filename: &str,
+    // The program text for `rz -` and `rz -e CODE`; `filename` then
+    // only names it in diagnostics.
+    inline_source: Option<&str>,
     type_check: bool,
     no_typecheck: bool,
     audit: bool,
@@ -30858,16 +30784,19 @@ fn execute_file(
     emit_contract_cert: Option<&Path>,
     use_vm: bool,
     use_jit: bool,
+    hot_jit: bool,
     verifier_timeout_ms: u32,
     warn_unverified: bool,
     verbose_invariants: bool,
     live_log: Option<&Path>,
     #[cfg(feature = "z3")] z3_theory: verifier_z3::Z3Theory,
     no_cache: bool,
-    // RES-2646: `--typecheck-strict` makes any type error fatal (exit 1)
+    // RES-2646: `--typecheck-strict` makes any type error fatal (exit 2)
     // without the verbose status lines that `--typecheck` prints.
     type_strict: bool,
-) -> RResult<()> {
+    // `-q`: leave out the type checker's status lines too.
+    quiet: bool,
+) -> RResult<i32> {
     #[cfg(not(feature = "z3"))]
     if emit_cert_dir.is_some() {
         return Err(backend_limited_feature_message(
@@ -30887,8 +30816,10 @@ fn execute_file(
         ));
     }
 
-    let contents =
-        fs::read_to_string(filename).map_err(|e| format!("Error reading file: {}", e))?;
+    let contents = match inline_source {
+        Some(src) => src.to_string(),
+        None => fs::read_to_string(filename).map_err(|e| format!("Error reading file: {}", e))?,
+    };
 
     // RES-355: incremental cache — compute SHA-256 of the source text,
     // check for a stored entry, and record whether this run hit the
@@ -30926,9 +30857,10 @@ fn execute_file(
         (source_hash, cache_dir, hit)
     };
 
-    let lexer = Lexer::new(&contents);
-    let mut parser = Parser::new(lexer);
-    let mut program = parser.parse_program();
+    // `--error-format=json|sarif` reports every diagnostic as a
+    // record instead of printing it.
+    let structured = error_format::is_structured();
+    let (mut program, parse_errs) = parse_unlowered(&contents, !structured);
 
     // Check for parser errors (already printed at the point they occurred).
     //
@@ -30938,13 +30870,17 @@ fn execute_file(
     // see while the parser is still scanning); this follow-up
     // dump gives them the source-context block. We keep the
     // original emission intact to minimise surgery on the parser.
-    if !parser.errors.is_empty() {
-        for e in &parser.errors {
-            eprintln!("{}", render_with_caret(&contents, e, "Parser error"));
+    if !parse_errs.is_empty() {
+        for e in &parse_errs {
+            if structured {
+                error_format::report_error(error_format::Phase::Parse, filename, &contents, e);
+            } else {
+                eprintln!("{}", render_with_caret(&contents, e, "Parser error"));
+            }
         }
         return Err(format!(
             "Failed to parse program: {} parser error(s)",
-            parser.errors.len()
+            parse_errs.len()
         ));
     }
 
@@ -30968,7 +30904,13 @@ fn execute_file(
     // Must run before `expand_uses_with_std` because that call replaces
     // `Node::Use` nodes with imported content; after expansion there are
     // no `Node::Use` nodes left to inspect.
-    unused_imports::check(&program, filename);
+    if structured {
+        let ((), warnings) =
+            typechecker::collect_check_diagnostics(|| unused_imports::check(&program, filename));
+        error_format::report_check_diagnostics(&warnings, filename, &contents);
+    } else {
+        unused_imports::check(&program, filename);
+    }
 
     // RES-073: resolve `use` imports before typecheck / interpret.
     //
@@ -30984,6 +30926,8 @@ fn execute_file(
         Node::Program(stmts) if stmts.iter().any(|s| matches!(s.node, Node::Use { .. }))
     );
     let mut std_bindings = Vec::new();
+    // The files pulled in by `use`, for placing type errors.
+    let mut modules: Vec<PathBuf> = Vec::new();
     if has_use {
         let base_dir = Path::new(filename)
             .parent()
@@ -30999,6 +30943,7 @@ fn execute_file(
         {
             return Err(format!("Import error: {}", e));
         }
+        modules = project::modules_of(&loaded, filename);
         for si in &std_imports {
             match stdlib::resolve_std_import(&si.module, si.alias.as_deref()) {
                 Ok(bindings) => std_bindings.extend(bindings),
@@ -31007,12 +30952,18 @@ fn execute_file(
         }
     }
 
+    // Mangle overload sets before any name-keyed pass.
+    crate::overloads::lower_program(&mut program);
     // RES-325: lower named call arguments to positional ones for
     // every call whose callee is a now-resolvable top-level fn or
     // impl method. Runs after `expand_uses` so calls into imported
     // modules can also be lowered.
     if let Err(e) = crate::named_args::lower_program(&mut program) {
-        eprintln!("\x1B[31mNamed-argument error: {}\x1B[0m", e);
+        if structured {
+            error_format::report_error(error_format::Phase::Typecheck, filename, &contents, &e);
+        } else {
+            eprintln!("\x1B[31mNamed-argument error: {}\x1B[0m", e);
+        }
         return Err(format!("Named argument resolution failed: {}", e));
     }
     // RES-326: fill in omitted trailing arguments with declared
@@ -31035,6 +30986,10 @@ fn execute_file(
     let region_errors = check_region_aliasing(&program, filename);
     if !region_errors.is_empty() {
         for e in &region_errors {
+            if structured {
+                error_format::report_error(error_format::Phase::Borrow, filename, &contents, e);
+                continue;
+            }
             eprintln!("\x1B[31m{}\x1B[0m", e);
             eprintln!("{}", render_with_caret(&contents, e, "Borrow check"));
         }
@@ -31051,7 +31006,11 @@ fn execute_file(
     let unsafe_errors = unsafe_check::check_program(&program);
     if !unsafe_errors.is_empty() {
         for e in &unsafe_errors {
-            eprintln!("\x1B[31m{}\x1B[0m", e);
+            if structured {
+                error_format::report_error(error_format::Phase::Capability, filename, &contents, e);
+            } else {
+                eprintln!("\x1B[31m{}\x1B[0m", e);
+            }
         }
         return Err(format!(
             "Capability check failed: {} error(s)",
@@ -31065,7 +31024,11 @@ fn execute_file(
     // (forward-reference of the fn's own name in its body), and we want
     // termination diagnostics regardless of `--typecheck`.
     if let Err(e) = termination::check(&program, filename) {
-        eprintln!("\x1B[31m{}\x1B[0m", e);
+        if structured {
+            error_format::report_error(error_format::Phase::Termination, filename, &contents, &e);
+        } else {
+            eprintln!("\x1B[31m{}\x1B[0m", e);
+        }
         return Err(format!("Termination check failed: {}", e));
     }
 
@@ -31085,18 +31048,20 @@ fn execute_file(
     // * **Strict** (`-t / --typecheck / --audit / --explain-effects /
     //   --emit-certificate / --verbose / --deny-unproven-bounds` —
     //   any of the modes that already implied --typecheck): a type
-    //   error aborts the run with exit 1, matching the legacy
-    //   behaviour. The "Running type checker..." / "Type check
+    //   error aborts the run with exit 2, like a parse error.
+    //   The "Running type checker..." / "Type check
     //   passed" status lines are printed.
     // * **Soft (default)**: a type error is rendered to stderr but
     //   execution continues. Exit code follows the program's
     //   runtime exit. Status lines are suppressed so default-mode
     //   stdout matches what users have always seen.
-    // `verbose_typecheck`: print "Running type checker…" / "Type check passed".
-    // `typecheck_strict`:  treat any type error as fatal (exit 1).
+    // `verbose_typecheck`: print "Running type checker…" / "Type check passed"
+    //                     (strict modes only, and never under `-q`).
+    // `typecheck_strict`:  treat any type error as fatal (exit 2).
     // `--typecheck-strict` sets the latter without the former.
-    let verbose_typecheck = type_check || audit || explain_effects || emit_cert_dir.is_some();
-    let typecheck_strict = verbose_typecheck || type_strict;
+    let checks_strictly = type_check || audit || explain_effects || emit_cert_dir.is_some();
+    let verbose_typecheck = checks_strictly && !quiet;
+    let typecheck_strict = checks_strictly || type_strict;
     let want_typecheck = !no_typecheck;
     let mut proven_fns: HashSet<String> = HashSet::new();
     if want_typecheck {
@@ -31134,21 +31099,71 @@ fn execute_file(
         let mut tc = tc_base;
         // RES-080: pass the source filename so per-statement errors
         // are prefixed with `<file>:<line>:<col>:`.
-        match tc.check_program_with_source(&program, filename) {
+        // Warnings (partial proofs, unused bindings, ...) become
+        // records too under `--error-format`, and are held back in a
+        // project so those from a `use`d file are reported there.
+        let (check_result, mut warnings) = if structured || !modules.is_empty() {
+            typechecker::collect_check_diagnostics(|| tc.check_source(&program, filename))
+        } else {
+            (tc.check_source(&program, filename), Vec::new())
+        };
+        project::attribute_warnings(&mut warnings, filename, &modules, verifier_timeout_ms);
+        if structured {
+            error_format::report_check_diagnostics(&warnings, filename, &contents);
+        } else {
+            typechecker::replay_check_diagnostics(warnings);
+        }
+        match check_result {
             Ok(_) => {
                 if verbose_typecheck {
                     println!("\x1B[32mType check passed\x1B[0m");
                 }
             }
             Err(e) => {
-                eprintln!("\x1B[31mType error: {}\x1B[0m", e);
-                // RES-117: add a caret diagnostic beneath the
-                // ANSI-red header so the offending source position
-                // is visually underlined.
-                eprintln!("{}", render_with_caret(&contents, &e, "Type error"));
+                // An error inside a `use`d file is reported there.
+                let (e, error_source, error_file) = match project::attribute_type_error(
+                    &e,
+                    filename,
+                    &modules,
+                    verifier_timeout_ms,
+                ) {
+                    Some(m) => {
+                        let file = m
+                            .error
+                            .message
+                            .split(':')
+                            .next()
+                            .unwrap_or_default()
+                            .to_string();
+                        (m.error, m.source, file)
+                    }
+                    None => (e, contents.clone(), filename.to_string()),
+                };
+                if structured {
+                    let phase = if e.refuted {
+                        error_format::Phase::Verify
+                    } else {
+                        error_format::Phase::Typecheck
+                    };
+                    error_format::report_error(phase, &error_file, &error_source, &e.message);
+                } else {
+                    eprintln!("\x1B[31mType error: {}\x1B[0m", e);
+                    // RES-117: add a caret diagnostic beneath the
+                    // ANSI-red header so the offending source position
+                    // is visually underlined.
+                    eprintln!(
+                        "{}",
+                        render_with_caret(&error_source, &e.message, "Type error")
+                    );
+                }
                 if typecheck_strict {
+                    // `failure_exit_code` tells the two apart by prefix.
+                    if e.refuted {
+                        return Err(format!("Verification failed: {}", e));
+                    }
                     return Err(format!("Type check failed: {}", e));
                 }
+                SOFT_TYPE_ERROR.with(|slot| *slot.borrow_mut() = Some(e.message.clone()));
                 // RES-1088: soft mode — surface the diagnostic but
                 // keep going so legacy programs that have always
                 // run unchecked still execute. The user sees the
@@ -31214,6 +31229,15 @@ fn execute_file(
         );
     }
 
+    #[cfg(not(feature = "jit"))]
+    if hot_jit {
+        return Err(backend_limited_feature_message(
+            "--backend=jit",
+            "jit",
+            None,
+        ));
+    }
+
     if use_jit {
         // RES-072 / RES-096: Cranelift JIT path for the supported
         // tree-walker subset.
@@ -31232,7 +31256,7 @@ fn execute_file(
         #[cfg(feature = "jit")]
         {
             // RES-405 PR 3: monomorphize before JIT compilation.
-            let jit_program = monomorph::lower(&program);
+            let jit_program = monomorph::lower(&prelude::link(&program));
             // RES-2605: devirtualize after monomorphization.
             let jit_program = devirtualize::lower(&jit_program);
             match jit_backend::run_with_explicit_return_flag(&jit_program) {
@@ -31257,7 +31281,7 @@ fn execute_file(
                     if !no_cache {
                         cache::write_entry(&cache_dir, &source_hash);
                     }
-                    return Ok(());
+                    return Ok(0);
                 }
                 Err(e) if e.is_precompile() => {
                     if verbose_invariants {
@@ -31309,6 +31333,20 @@ fn execute_file(
     };
     let _live_telemetry_guard = install_live_run_telemetry(basename, log_writer);
 
+    // `--backend=jit`: compile the program's hot functions; the walker
+    // calls them natively until the guard drops at the end of the run.
+    #[cfg(feature = "jit")]
+    let _hot_functions = hot_jit.then(|| {
+        let guard = jit_backend::install_hot_functions(&program);
+        if verbose_invariants {
+            eprintln!(
+                "note: --backend=jit compiled {} hot function(s) in {}",
+                guard.compiled, filename
+            );
+        }
+        guard
+    });
+
     let mut interpreter = Interpreter::new().with_proven_fns(proven_fns);
     interpreter.source_path = filename.to_string();
 
@@ -31368,9 +31406,12 @@ fn execute_file(
     //
     // RES-117: also attach a caret diagnostic beneath the header
     // so the offending source line is visually underlined.
-    interpreter.eval(&program).map_err(|e| {
+    let value = interpreter.eval(&program).map_err(|e| {
         let header = format_interpreter_error(filename, &e);
-        if has_line_col_prefix(&e) {
+        if structured {
+            error_format::report_error(error_format::Phase::Runtime, filename, &contents, &e);
+            header
+        } else if has_line_col_prefix(&e) {
             render_with_caret(&contents, &header, "Runtime error")
         } else {
             header
@@ -31378,7 +31419,12 @@ fn execute_file(
     })?;
 
     // RES-332 PR 3: drain spawned actors after the main script finishes.
-    run_pending_actors(&mut interpreter).map_err(|e| format_interpreter_error(filename, &e))?;
+    run_pending_actors(&mut interpreter).map_err(|e| {
+        if structured {
+            error_format::report_error(error_format::Phase::Runtime, filename, &contents, &e);
+        }
+        format_interpreter_error(filename, &e)
+    })?;
 
     // RES-355: persist a cache entry so the next run can detect
     // that this source compiled successfully. Errors here are
@@ -31388,7 +31434,7 @@ fn execute_file(
         cache::write_entry(&cache_dir, &source_hash);
     }
 
-    Ok(())
+    main_exit_code(&program, &value)
 }
 
 /// RES-066: print a structured verification report after a successful
@@ -31575,6 +31621,12 @@ fn fail_on_error_lints(
 /// caller should exit); `None` if no `pkg` verb was seen and main
 /// should fall through to its normal flow.
 fn dispatch_pkg_subcommand(args: &[String]) -> Option<i32> {
+    // `rz add ...` is shorthand for `rz pkg add ...`.
+    if args.get(1).map(|s| s.as_str()) == Some("add") {
+        let mut args = args.to_vec();
+        args.insert(1, "pkg".to_string());
+        return dispatch_pkg_subcommand(&args);
+    }
     if args.get(1).map(|s| s.as_str()) != Some("pkg") {
         return None;
     }
@@ -31732,7 +31784,9 @@ fn dispatch_pkg_subcommand(args: &[String]) -> Option<i32> {
             Some(0)
         }
         Some("add") => {
-            // `pkg add <name> <spec> [--rev X] [--tag X] [--branch X]`
+            // `pkg add <name> <spec> [--rev X] [--tag X] [--branch X]`,
+            // where `--git <url>` / `--path <dir>` can stand in for
+            // `<spec>`.
             // RES-4114: `<spec>` is now optional — `pkg add <name>`
             // with no spec resolves `<name>` against a registry index
             // (`--index <path-or-url>`, or a `[registry]` section
@@ -31774,6 +31828,17 @@ fn dispatch_pkg_subcommand(args: &[String]) -> Option<i32> {
                         return Some(2);
                     }
                     opts.branch = Some(args[i].clone());
+                } else if a == "--git" || a == "--path" {
+                    i += 1;
+                    if i >= args.len() {
+                        eprintln!("Error: {} requires an argument", a);
+                        return Some(2);
+                    }
+                    if spec.is_some() {
+                        eprintln!("Error: `pkg add` takes one source; got a second with {}", a);
+                        return Some(2);
+                    }
+                    spec = Some(format!("{}:{}", &a[2..], args[i]));
                 } else if a == "--index" {
                     i += 1;
                     if i >= args.len() {
@@ -31806,6 +31871,7 @@ fn dispatch_pkg_subcommand(args: &[String]) -> Option<i32> {
                     "Error: `rz pkg add` requires at least a name.\n\
                      Usage: rz pkg add <name> path:../libs/mylib\n\
                      Usage: rz pkg add <name> git:https://... --rev abc123\n\
+                     Usage: rz pkg add <name> --git https://... [--branch main]\n\
                      Usage: rz pkg add <name> [--version v] [--index <path-or-url>]"
                 );
                 return Some(2);
@@ -32094,15 +32160,19 @@ fn print_pkg_add_help() {
          USAGE:\n    \
              rz pkg add <name> path:../libs/<name>\n    \
              rz pkg add <name> git:https://github.com/user/repo --rev abc123\n    \
+             rz pkg add <name> --git https://github.com/user/repo [--tag v1.0]\n    \
              rz pkg add <name> [--version <v>] [--index <path-or-url>]\n\
          \n\
          ARGS:\n    \
-             <name>     Dependency name (used in `use <name>::module;` imports)\n    \
+             <name>     Dependency name (used in `use <name>::module;` and\n    \
+                        `use \"<name>/module\";` imports)\n    \
              <spec>     Source specifier: `path:<relative-path>` or `git:<url>`.\n    \
                         Omit `<spec>` entirely to resolve `<name>` against a\n    \
                         registry index (RES-4114) instead.\n\
          \n\
          FLAGS:\n    \
+             --git <url>    Same as the spec `git:<url>`\n    \
+             --path <dir>   Same as the spec `path:<dir>`\n    \
              --rev <r>      Pin to a git revision (git deps only)\n    \
              --tag <t>      Pin to a git tag (git deps only)\n    \
              --branch <b>   Pin to a git branch (git deps only)\n    \
@@ -32707,6 +32777,22 @@ fn dispatch_lint_subcommand(args: &[String]) -> Option<i32> {
         eprintln!("Error: `rz lint <file> [--deny LCODE]* [--allow LCODE]*` requires a file path");
         return Some(2);
     };
+    // `[lint]` in the project's resilient.toml, for the codes the
+    // flags leave alone.
+    for (code, level) in project_config::ProjectConfig::for_path(Some(&path)).lint {
+        if allow.contains(&code) || deny.contains(&code) {
+            continue;
+        }
+        match level {
+            project_config::LintLevel::Allow => {
+                allow.insert(code);
+            }
+            project_config::LintLevel::Deny => {
+                deny.insert(code);
+            }
+            project_config::LintLevel::Warn => {}
+        }
+    }
 
     let src = match fs::read_to_string(&path) {
         Ok(s) => s,
@@ -32809,170 +32895,179 @@ fn dispatch_lint_subcommand(args: &[String]) -> Option<i32> {
 }
 
 /// RES-225: `resilient check <file> [-q]` — parse + type-check without running.
+/// `rz verify` is the same pipeline: checking already runs the verifier.
 ///
 /// Exit codes:
 /// - 0 = file parsed and type-checked cleanly.
 /// - 1 = parse error or type error.
 /// - 2 = usage error (missing path, bad flag).
 ///
-/// Returns `None` when the first arg isn't `check`.
-fn dispatch_check_subcommand(args: &[String]) -> Option<i32> {
-    if args.get(1).map(|s| s.as_str()) != Some("check") {
-        return None;
+/// `args` are the raw driver arguments, which decide whether a flag
+/// overrides the project's resilient.toml.
+fn run_check_subcommand(check: cli::CheckArgs, args: &[String]) -> i32 {
+    if check.strict {
+        strict_any::set_strict_any(true);
     }
-    if is_check_help_request(args) {
-        print_check_help();
-        return Some(0);
+    if let Some(policy) = check.coercion {
+        coercion::set_policy(policy);
+    }
+    if let Some(format) = check.error_format {
+        error_format::set_format(format);
+    }
+    for (category, on) in check.warnings {
+        warnings::set_enabled(category, on);
+    }
+    if check.deny_warnings {
+        warnings::set_deny(true);
+    }
+    // RES-3839 / RES-354: strict refinements and theory selection
+    // need the z3 backend.
+    #[cfg(not(feature = "z3"))]
+    {
+        if check.strict_refinements {
+            eprintln!(
+                "{}",
+                backend_limited_feature_message("--strict-refinements", "z3", None)
+            );
+            return 2;
+        }
+        if check.z3_theory.is_some() {
+            eprintln!(
+                "{}",
+                backend_limited_feature_message(
+                    "--z3-theory",
+                    "z3",
+                    Some(DEFAULT_BUILD_Z3_THEORY_STABLE_PATH),
+                )
+            );
+            return 2;
+        }
     }
-
-    let mut file: Option<PathBuf> = None;
-    let mut quiet = false;
-    let mut safety_critical = false;
-    let mut emit_diagnostics_json = false;
-    let mut verifier_timeout_ms: u32 = 5000;
-    // RES-3839: strict refinement type checking mode (z3-gated).
-    #[cfg(feature = "z3")]
-    let mut strict_refinements = false;
-    // RES-354: theory selection (z3-gated; default Auto).
     #[cfg(feature = "z3")]
-    let mut z3_theory: verifier_z3::Z3Theory = verifier_z3::Z3Theory::Auto;
-    let mut i = 2;
-    while i < args.len() {
-        let a = &args[i];
-        if a == "--quiet" || a == "-q" {
-            quiet = true;
-        } else if a == "--emit-diagnostics-json" {
-            emit_diagnostics_json = true;
-        } else if a == "--safety-critical" {
-            safety_critical = true;
-        } else if a == "--strict-refinements" {
-            // RES-3839: enable strict mode for refinement type checking.
-            #[cfg(feature = "z3")]
-            {
-                strict_refinements = true;
-            }
-            #[cfg(not(feature = "z3"))]
-            {
-                eprintln!(
-                    "{}",
-                    backend_limited_feature_message("--strict-refinements", "z3", None,)
-                );
-                return Some(2);
-            }
-        } else if a == "--verifier-timeout-ms" {
-            i += 1;
-            if i >= args.len() {
-                eprintln!("Error: --verifier-timeout-ms requires a value");
-                return Some(2);
-            }
-            verifier_timeout_ms = match args[i].parse() {
-                Ok(n) => n,
-                Err(_) => {
-                    eprintln!("Error: --verifier-timeout-ms requires an integer");
-                    return Some(2);
-                }
-            };
-        } else if let Some(v) = a.strip_prefix("--verifier-timeout-ms=") {
-            verifier_timeout_ms = match v.parse() {
-                Ok(n) => n,
-                Err(_) => {
-                    eprintln!("Error: --verifier-timeout-ms requires an integer");
-                    return Some(2);
+    let z3_theory = match check.z3_theory.as_deref() {
+        None | Some("auto") => verifier_z3::Z3Theory::Auto,
+        Some("bv") => verifier_z3::Z3Theory::Bv,
+        Some("lia") => verifier_z3::Z3Theory::Lia,
+        Some(other) => {
+            eprintln!(
+                "Error: --z3-theory must be `bv`, `lia`, or `auto`; got {:?}",
+                other
+            );
+            return 2;
+        }
+    };
+
+    if check.files.is_empty() {
+        eprintln!(
+            "Error: `rz {} <file>... [-q]` requires a file path",
+            args[1]
+        );
+        return 2;
+    }
+    if check.emit_diagnostics_json && check.files.len() > 1 {
+        eprintln!("Error: --emit-diagnostics-json checks one file at a time");
+        return 2;
+    }
+    let mut opts = CheckOptions {
+        quiet: check.quiet,
+        safety_critical: check.safety_critical,
+        emit_diagnostics_json: check.emit_diagnostics_json,
+        verifier_timeout_ms: check.verifier_timeout_ms.unwrap_or(5000),
+        #[cfg(feature = "z3")]
+        strict_refinements: check.strict_refinements,
+        #[cfg(feature = "z3")]
+        z3_theory,
+        dump_ast: check.dump_ast,
+    };
+    // A project directory stands for its entry file. The status is
+    // the worst of the files' statuses.
+    let mut status = 0;
+    for file in &check.files {
+        let code = match project::resolve_entry(file) {
+            Ok(entry) => {
+                // Each file is checked with its own project's settings,
+                // under the command-line flags.
+                let config = project_config::ProjectConfig::for_path(Some(&entry));
+                config.apply_checker_settings(args);
+                if check.verifier_timeout_ms.is_none() {
+                    opts.verifier_timeout_ms = config.verifier_timeout_ms.unwrap_or(5000);
                 }
-            };
-        } else if a == "--z3-theory" {
-            // RES-354: SMT theory override for `check` subcommand.
-            i += 1;
-            if i >= args.len() {
-                eprintln!("Error: --z3-theory requires an argument: bv, lia, or auto");
-                return Some(2);
-            }
-            #[cfg(feature = "z3")]
-            {
-                z3_theory = match args[i].as_str() {
-                    "bv" => verifier_z3::Z3Theory::Bv,
-                    "lia" => verifier_z3::Z3Theory::Lia,
-                    "auto" => verifier_z3::Z3Theory::Auto,
-                    other => {
-                        eprintln!(
-                            "Error: --z3-theory must be `bv`, `lia`, or `auto`; got {:?}",
-                            other
-                        );
-                        return Some(2);
-                    }
-                };
-            }
-            #[cfg(not(feature = "z3"))]
-            {
-                eprintln!(
-                    "{}",
-                    backend_limited_feature_message(
-                        "--z3-theory",
-                        "z3",
-                        Some(DEFAULT_BUILD_Z3_THEORY_STABLE_PATH),
-                    )
-                );
-                return Some(2);
-            }
-        } else if let Some(val) = a.strip_prefix("--z3-theory=") {
-            #[cfg(feature = "z3")]
-            {
-                z3_theory = match val {
-                    "bv" => verifier_z3::Z3Theory::Bv,
-                    "lia" => verifier_z3::Z3Theory::Lia,
-                    "auto" => verifier_z3::Z3Theory::Auto,
-                    other => {
-                        eprintln!(
-                            "Error: --z3-theory must be `bv`, `lia`, or `auto`; got {:?}",
-                            other
-                        );
-                        return Some(2);
-                    }
-                };
+                check_file(&entry, &opts)
             }
-            #[cfg(not(feature = "z3"))]
-            {
-                let _ = val;
-                eprintln!(
-                    "{}",
-                    backend_limited_feature_message(
-                        "--z3-theory",
-                        "z3",
-                        Some(DEFAULT_BUILD_Z3_THEORY_STABLE_PATH),
-                    )
-                );
-                return Some(2);
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                2
             }
-        } else if file.is_none() && !a.starts_with('-') {
-            file = Some(PathBuf::from(a));
-        } else {
-            eprintln!("Error: unexpected argument `{}` to check", a);
-            return Some(2);
-        }
-        i += 1;
+        };
+        status = status.max(code);
     }
+    error_format::finish();
+    status
+}
 
-    let Some(path) = file else {
-        eprintln!("Error: `rz check <file> [-q]` requires a file path");
-        return Some(2);
-    };
+/// The flags of one `rz check` run.
+struct CheckOptions {
+    quiet: bool,
+    safety_critical: bool,
+    emit_diagnostics_json: bool,
+    verifier_timeout_ms: u32,
+    #[cfg(feature = "z3")]
+    strict_refinements: bool,
+    #[cfg(feature = "z3")]
+    z3_theory: verifier_z3::Z3Theory,
+    dump_ast: Option<AstDumpFormat>,
+}
 
-    let src = match fs::read_to_string(&path) {
+/// Check one file for `rz check`; returns its exit status.
+fn check_file(path: &Path, opts: &CheckOptions) -> i32 {
+    let CheckOptions {
+        quiet,
+        safety_critical,
+        emit_diagnostics_json,
+        verifier_timeout_ms,
+        #[cfg(feature = "z3")]
+        strict_refinements,
+        #[cfg(feature = "z3")]
+        z3_theory,
+        dump_ast,
+    } = *opts;
+
+    let src = match fs::read_to_string(path) {
         Ok(s) => s,
         Err(e) => {
             eprintln!("Error: could not read {}: {}", path.display(), e);
-            return Some(2);
+            return 2;
         }
     };
 
+    // `--dump-ast` stops after parsing: the tree is printed whether
+    // or not the program would type-check.
+    if let Some(format) = dump_ast {
+        return match dump_ast_to_stdout(&src, format) {
+            Ok(()) => 0,
+            Err(errs) => {
+                for e in &errs {
+                    eprintln!("{}", render_with_caret(&src, e, "parse error"));
+                }
+                2
+            }
+        };
+    }
+
     // Parse.
-    let (mut program, parse_errs) = if emit_diagnostics_json {
+    let structured = error_format::is_structured();
+    let path_str = path.to_string_lossy();
+    let (mut program, parse_errs) = if emit_diagnostics_json || structured {
         parse_silent(&src)
     } else {
         parse(&src)
     };
     if !parse_errs.is_empty() {
-        if emit_diagnostics_json {
+        if structured {
+            for e in &parse_errs {
+                error_format::report_error(error_format::Phase::Parse, &path_str, &src, e);
+            }
+        } else if emit_diagnostics_json {
             let path_str = path.to_string_lossy();
             let json_diags: Vec<serde_json::Value> = parse_errs
                 .iter()
@@ -32999,7 +33094,7 @@ fn dispatch_check_subcommand(args: &[String]) -> Option<i32> {
                 eprintln!("{}", render_with_caret(&src, e, "parse error"));
             }
         }
-        return Some(1);
+        return 2;
     }
 
     lint::set_safety_critical_mode(safety_critical);
@@ -33017,7 +33112,7 @@ fn dispatch_check_subcommand(args: &[String]) -> Option<i32> {
             "safety-critical lint",
         )
     {
-        return Some(1);
+        return 2;
     }
 
     // Resolve imports.
@@ -33030,21 +33125,25 @@ fn dispatch_check_subcommand(args: &[String]) -> Option<i32> {
         &program,
         Node::Program(stmts) if stmts.iter().any(|s| matches!(s.node, Node::Use { .. }))
     );
+    let mut modules: Vec<PathBuf> = Vec::new();
     if has_use {
         let base_dir = path
             .parent()
             .map(Path::to_path_buf)
             .unwrap_or_else(|| PathBuf::from("."));
         let mut loaded: HashSet<PathBuf> = HashSet::new();
-        if let Ok(canon) = fs::canonicalize(&path) {
+        if let Ok(canon) = fs::canonicalize(path) {
             loaded.insert(canon);
         }
         if let Err(e) = imports::expand_uses(&mut program, &base_dir, &mut loaded) {
-            if !quiet {
+            if structured {
+                error_format::report_at(error_format::Phase::Driver, &path_str, &src, 1, 1, &e);
+            } else if !quiet {
                 eprintln!("{}:1:1: error: {}", path.display(), e);
             }
-            return Some(1);
+            return 2;
         }
+        modules = project::modules_of(&loaded, &path.to_string_lossy());
     }
 
     // RES-3839: set the strict refinements flag before typechecking.
@@ -33060,31 +33159,48 @@ fn dispatch_check_subcommand(args: &[String]) -> Option<i32> {
     let mut tc = tc_base.with_z3_theory(z3_theory);
     #[cfg(not(feature = "z3"))]
     let mut tc = tc_base;
-    let path_str = path.to_string_lossy();
-    let (check_result, check_diagnostics) = if emit_diagnostics_json {
-        typechecker::collect_check_diagnostics(|| {
-            tc.check_program_with_source(&program, path_str.as_ref())
-        })
-    } else {
-        (
-            tc.check_program_with_source(&program, path_str.as_ref()),
-            Vec::new(),
-        )
-    };
+    // Warnings are held back in a project so those from a `use`d
+    // file are reported there.
+    let (check_result, mut check_diagnostics) =
+        if emit_diagnostics_json || structured || !modules.is_empty() {
+            typechecker::collect_check_diagnostics(|| tc.check_source(&program, path_str.as_ref()))
+        } else {
+            (tc.check_source(&program, path_str.as_ref()), Vec::new())
+        };
+    project::attribute_warnings(
+        &mut check_diagnostics,
+        &path_str,
+        &modules,
+        verifier_timeout_ms,
+    );
+    if !emit_diagnostics_json && !structured {
+        typechecker::replay_check_diagnostics(std::mem::take(&mut check_diagnostics));
+    }
     match check_result {
         Ok(_) => {
             // RES-390: distributed-invariant verification runs
             // AFTER successful typechecking — we only try to
             // prove clusters that parse cleanly and whose
-            // members resolve. Any diagnostics raise the
-            // subcommand's exit code to 1 so CI catches
-            // broken clusters the same way it catches a
-            // failed `ensures`.
+            // members resolve. Any diagnostics are a
+            // verification failure (exit 3), the same as a
+            // refuted `ensures`.
             #[cfg(feature = "z3")]
             {
                 let diags = cluster_verifier::verify_program(&program, verifier_timeout_ms);
                 if !diags.is_empty() {
-                    if emit_diagnostics_json {
+                    if structured {
+                        error_format::report_check_diagnostics(&check_diagnostics, &path_str, &src);
+                        for d in &diags {
+                            error_format::report_at(
+                                error_format::Phase::Verify,
+                                &path_str,
+                                &src,
+                                d.span.start.line,
+                                d.span.start.column,
+                                &format!("[{}/{}.{}] {}", d.cluster, d.actor, d.handler, d.message),
+                            );
+                        }
+                    } else if emit_diagnostics_json {
                         let json_diags: Vec<serde_json::Value> = diags
                             .iter()
                             .map(|d| {
@@ -33120,10 +33236,13 @@ fn dispatch_check_subcommand(args: &[String]) -> Option<i32> {
                             );
                         }
                     }
-                    return Some(1);
+                    return 3;
                 }
             }
-            if emit_diagnostics_json {
+            if structured {
+                error_format::report_check_diagnostics(&check_diagnostics, &path_str, &src);
+            }
+            if emit_diagnostics_json && !structured {
                 let json_diags = check_diagnostics_json_values(&check_diagnostics);
                 println!(
                     "{}",
@@ -33132,11 +33251,34 @@ fn dispatch_check_subcommand(args: &[String]) -> Option<i32> {
             } else if !quiet {
                 println!("{}: ok", path.display());
             }
-            Some(0)
+            0
         }
         Err(e) => {
-            if emit_diagnostics_json {
-                let (line, col, msg) = parse_error_location(&e);
+            // An error inside a `use`d file is reported there.
+            let (e, error_source, error_file) =
+                match project::attribute_type_error(&e, &path_str, &modules, verifier_timeout_ms) {
+                    Some(m) => {
+                        let file = m
+                            .error
+                            .message
+                            .split(':')
+                            .next()
+                            .unwrap_or_default()
+                            .to_string();
+                        (m.error, m.source, file)
+                    }
+                    None => (e, src.clone(), path_str.to_string()),
+                };
+            if structured {
+                error_format::report_check_diagnostics(&check_diagnostics, &path_str, &src);
+                let phase = if e.refuted {
+                    error_format::Phase::Verify
+                } else {
+                    error_format::Phase::Typecheck
+                };
+                error_format::report_error(phase, &error_file, &error_source, &e.message);
+            } else if emit_diagnostics_json {
+                let (line, col, msg) = parse_error_location(&e.message);
                 let mut json_diags = check_diagnostics_json_values(&check_diagnostics);
                 json_diags.push(serde_json::json!({
                     "severity": "error",
@@ -33144,7 +33286,7 @@ fn dispatch_check_subcommand(args: &[String]) -> Option<i32> {
                     "line": line,
                     "column": col,
                     "message": msg,
-                    "file": path_str.as_ref(),
+                    "file": error_file,
                 }));
                 println!(
                     "{}",
@@ -33152,9 +33294,9 @@ fn dispatch_check_subcommand(args: &[String]) -> Option<i32> {
                 );
             } else if !quiet {
                 eprintln!("{}", e);
-                eprintln!("{}", render_with_caret(&src, &e, "error"));
+                eprintln!("{}", render_with_caret(&error_source, &e.message, "error"));
             }
-            Some(1)
+            if e.refuted { 3 } else { 2 }
         }
     }
 }
@@ -33363,54 +33505,31 @@ fn print_build_help() {
 /// - 1 = parse errors (formatter refuses to touch broken input); or,
 ///   under `--check`, at least one file would be reformatted.
 /// - 2 = usage error (missing path, bad flag).
-///
-/// Returns `None` when the first arg isn't `fmt`.
-fn dispatch_fmt_subcommand(args: &[String]) -> Option<i32> {
-    if args.get(1).map(|s| s.as_str()) != Some("fmt") {
-        return None;
-    }
-    if is_fmt_help_request(args) {
-        print_fmt_help();
-        return Some(0);
-    }
-
-    let mut files: Vec<PathBuf> = Vec::new();
-    let mut in_place = false;
-    let mut check = false;
-    let mut i = 2;
-    while i < args.len() {
-        let a = &args[i];
-        if a == "--in-place" || a == "-i" {
-            in_place = true;
-        } else if a == "--check" {
-            check = true;
-        } else if a.starts_with("--") {
-            eprintln!("Error: unknown flag `{}` to fmt", a);
-            return Some(2);
-        } else {
-            files.push(PathBuf::from(a));
-        }
-        i += 1;
-    }
-
+fn run_fmt_subcommand(fmt: cli::FmtArgs) -> i32 {
+    let cli::FmtArgs {
+        files,
+        in_place,
+        check,
+        indent,
+    } = fmt;
     if check {
-        return dispatch_fmt_check(&files, in_place);
+        return dispatch_fmt_check(&files, in_place, indent);
     }
 
     let Some(path) = files.first().cloned() else {
         eprintln!("Error: `rz fmt <file> [--in-place]` requires a file path");
-        return Some(2);
+        return 2;
     };
     if files.len() > 1 {
         eprintln!("Error: unexpected argument `{}` to fmt", files[1].display());
-        return Some(2);
+        return 2;
     }
 
     let src = match fs::read_to_string(&path) {
         Ok(s) => s,
         Err(e) => {
             eprintln!("Error: could not read {}: {}", path.display(), e);
-            return Some(2);
+            return 2;
         }
     };
 
@@ -33420,22 +33539,29 @@ fn dispatch_fmt_subcommand(args: &[String]) -> Option<i32> {
             eprintln!("{}", e);
         }
         eprintln!("Error: fmt aborted due to parse errors");
-        return Some(1);
+        return 1;
     }
 
-    let formatted = formatter::Formatter::format(&program);
+    let formatted = formatter::Formatter::format_source(&src, &program, fmt_indent(&path, indent));
 
     if in_place {
         if let Err(e) = fs::write(&path, &formatted) {
             eprintln!("Error: could not write {}: {}", path.display(), e);
-            return Some(1);
+            return 1;
         }
     } else {
         // Print to stdout. No extra newline — the formatter already
         // ends with exactly one.
         print!("{}", formatted);
     }
-    Some(0)
+    0
+}
+
+/// Indent width for formatting `path`: `--indent`, else the
+/// project's `[fmt] indent`, else the canonical four spaces.
+fn fmt_indent(path: &Path, flag: Option<usize>) -> usize {
+    flag.or_else(|| project_config::ProjectConfig::for_path(Some(path)).fmt_indent)
+        .unwrap_or(formatter::DEFAULT_INDENT)
 }
 
 /// RES-4032: `rz fmt --check <file>...` — CI/pre-commit mode.
@@ -33445,14 +33571,14 @@ fn dispatch_fmt_subcommand(args: &[String]) -> Option<i32> {
 /// reformatted (or that fails to parse) gets a one-line diagnostic on
 /// stderr. Exits 0 only when every file is already canonically
 /// formatted.
-fn dispatch_fmt_check(files: &[PathBuf], in_place: bool) -> Option<i32> {
+fn dispatch_fmt_check(files: &[PathBuf], in_place: bool, indent: Option<usize>) -> i32 {
     if in_place {
         eprintln!("Error: --check cannot be combined with --in-place");
-        return Some(2);
+        return 2;
     }
     if files.is_empty() {
         eprintln!("Error: `rz fmt --check <file>...` requires at least one file path");
-        return Some(2);
+        return 2;
     }
 
     let mut needs_reformat = false;
@@ -33478,7 +33604,8 @@ fn dispatch_fmt_check(files: &[PathBuf], in_place: bool) -> Option<i32> {
             continue;
         }
 
-        let formatted = formatter::Formatter::format(&program);
+        let formatted =
+            formatter::Formatter::format_source(&src, &program, fmt_indent(path, indent));
         if formatted != src {
             eprintln!("{}: would reformat", path.display());
             needs_reformat = true;
@@ -33486,11 +33613,11 @@ fn dispatch_fmt_check(files: &[PathBuf], in_place: bool) -> Option<i32> {
     }
 
     if had_error {
-        Some(2)
+        2
     } else if needs_reformat {
-        Some(1)
+        1
     } else {
-        Some(0)
+        0
     }
 }
 
@@ -33504,19 +33631,26 @@ USAGE:
     rz                      # start REPL when no file is provided
     rz help                 # show this help
     rz <subcommand> [ARGS]
+    rz [FLAGS] <file> -- ARGS...
+                            # pass ARGS to the program as `args()`
+    rz [FLAGS] -            # read the program from stdin
+    rz [FLAGS] -e CODE      # run CODE, e.g. rz -e 'println("hi");'
+    rz [FLAGS] <dir>        # run a project's entry (see resilient.toml)
 
 COMMON FLAGS:
     -h, --help                   Show this help and exit
     -t, --typecheck              Run the static type checker in strict mode
-                                 (fail with exit 1 on any type error). The
+                                 (fail with exit 2 on any type error). The
                                  type checker also runs by default in soft
                                  mode — diagnostics print to stderr but the
                                  program still executes (RES-1088).
-        --typecheck-strict       Make any type error fatal (exit 1) without
+        --typecheck-strict       Make any type error fatal (exit 2) without
                                  the verbose status lines of --typecheck.
                                  Suitable for CI scripts that want strict
                                  checking without extra output (RES-2646).
         --no-typecheck           Skip the static type checker entirely
+    -q, --quiet                  Don't print "Program executed successfully"
+    -e, --eval CODE              Run CODE instead of a file
         --audit                  Print the verification audit trail
         --verbose                Print one stderr line per loop
                                  invariant statically proven (RES-318)
@@ -33531,6 +33665,42 @@ COMMON FLAGS:
                                  counterexample / unknown). Works on every
                                  build; verdicts need --features z3 (RES-3859)
         --deny-unproven-bounds   Treat any unproven arr[i] as a compile error (RES-351)
+        --strict                 Reject `Any`: untyped bindings and `any`
+                                 annotations become type errors
+        --coercion POLICY        Implicit conversions: standard (default),
+                                 strict (none), or permissive (int widens to
+                                 float; every conversion warns)
+        --warn CATEGORY          Enable a warning category: unused,
+                                 shadowing, implicit-any, implicit-coercion,
+                                 or all (unused and implicit-coercion are on
+                                 by default)
+        --no-warn CATEGORY       Disable a warning category
+        --deny-warnings          Report enabled warnings as errors
+        --error-format FORMAT    Diagnostics on stderr as human (default)
+                                 text, json (one object per line), or
+                                 sarif (one SARIF 2.1.0 log)
+        --allow CAPS             Capabilities the I/O builtins may use, e.g.
+                                 fs-read,net (also fs-write, fs, exec,
+                                 serial, mmio, ffi, or none); the rest fail
+                                 with PermissionDenied (default: all)
+        --fs POLICY              Filesystem access: read-write (default),
+                                 read-only, or none
+        --net POLICY             Network access for the HTTP and MQTT
+                                 builtins: allow (default) or none
+        --ffi POLICY             Native calls through `extern` declarations:
+                                 allow (default) or none
+        --mmio RANGES            Register ranges mmio_read / mmio_write and
+                                 the volatile intrinsics may touch, as
+                                 start..end or start+size, comma-separated
+                                 (default none; mmio_* requires
+                                 --features unsafe-io)
+        --mmio-device PATH       Device file registers are reached through
+                                 (default /dev/mem)
+        --ffi-lib PATH           Shared library `extern \"C\"` declarations
+                                 bind against (repeatable; requires
+                                 --features ffi)
+        --plugin PATH            Load a builtin package built as a cdylib
+                                 (repeatable; requires --features plugins)
         --safety-critical        Promote vacuous proof-discharge constructs
                                  such as `assume(false)` to hard errors
         --sign-cert PATH         Ed25519-sign the emitted certificate
@@ -33538,7 +33708,13 @@ COMMON FLAGS:
         --vm                     Route through the bytecode VM
         --jit                    Route through the Cranelift JIT
                                  (backend-limited; requires --features jit)
+        --backend NAME           Execution engine: interp (default), vm
+                                 (same as --vm), or jit (the interpreter
+                                 with hot int functions compiled natively;
+                                 requires --features jit)
         --dump-tokens            Print the lexer stream and exit
+        --dump-ast[=json]        Print the parsed AST as a tree (or JSON)
+                                 and exit
         --dump-ast-json          Print the parsed AST as JSON and exit
                                  (experimental tooling surface)
         --dump-chunks            Print the VM disassembly and exit
@@ -33547,6 +33723,31 @@ COMMON FLAGS:
         --panic-on-fault         Disable live-block retry healing;
                                  abort with exit 1 on the first fault
         --no-panic-on-fault      Restore default retry behaviour
+        --max-steps N            Abort with ResourceExhausted after N
+                                 evaluation steps (per input in the REPL)
+        --max-memory BYTES       Fail with OutOfMemory once bound program
+                                 data passes ~BYTES (interpreter only)
+        --trace[=NAME,...]       Log function calls, arguments and returns
+                                 to stderr, optionally only the named ones
+                                 (interpreter only)
+        --profile                Print calls, total and self time per
+                                 function to stderr after the run
+                                 (interpreter only)
+        --metrics-port [HOST:]PORT
+                                 Serve runtime counters at /metrics in
+                                 Prometheus text format (interpreter only)
+        --step                   Pause before each statement and show it,
+                                 the variables that changed and the live
+                                 retry state; Enter advances (interpreter only)
+        --record PATH            Write every nondeterministic input (seed,
+                                 clocks, entropy, stdin, actor order) to PATH
+        --replay PATH            Re-run with the inputs recorded in PATH
+        --log-level LEVEL        Minimum level for log_* builtins: debug,
+                                 info (default), warn, error or off
+        --log-format FORMAT      Write log records as text (default) or json
+        --float-strict           Fail with DivisionByZero, FloatOverflow or
+                                 InvalidOperation instead of producing NaN/inf
+        --float-precision N      Print floats with exactly N decimals
         --emit-live-log PATH     NDJSON log of live-block retries (RES-371)
         --examples-dir DIR       REPL examples directory
         --lsp                    Run the LSP server on stdio
@@ -33563,6 +33764,8 @@ COMMON FLAGS:
                                  on stdio for interactive debugging
         --no-cache               Disable the incremental compilation cache
                                  for this run (RES-355)
+        --no-prelude             Leave out the prelude functions (retry,
+                                 in_range, format_bytes, ...)
         --feature NAME           Activate a `#[cfg(feature="NAME")]` flag
                                  (repeatable; RES-343)
         --target TRIPLE          Set the active triple for `#[cfg(target=...)]`
@@ -33580,16 +33783,30 @@ STATUS:
                        unavailable builds print a rebuild hint
     experimental       User-facing, but policy/output may still evolve
 
+EXIT STATUS:
+    0                  The program ran to completion
+    1                  Runtime error (including `--panic-on-fault` aborts)
+    2                  Parse or type error, or a bad command line
+    3                  Verification failure: a contract, invariant or
+                       termination obligation was refuted
+    A program's own `exit(code)` or `main` result is passed through.
+
 SUBCOMMANDS:
     repl                 Start interactive REPL (alias for bare `rz`)
-    check <file>         Type-check without running (RES-225)
+    run <file>           Run a file (same as `rz [FLAGS] <file>`)
+    check <file|dir>...  Type-check without running (RES-225)
+    verify <file|dir>... Type-check and verify contracts (exit 3 if refuted)
+    test [<file|dir>]    Run `test` functions (--filter <substr>)
     bench <file>         Run `bench "name" { ... }` benchmarks
+    mutate [<file|dir>]  Run tests against mutants; report survivors
     self-host-parity-report [DIR]
                         Publish grammar coverage / gap report for the
                         self-hosting parity corpus (RES-2992)
     stack-usage <file>   Print per-function worst-case stack usage (RES-2627)
-    debug <file>         Start the DAP debug server for a file
+    debug <file>         Run a file under the console debugger (--dap: DAP server)
     mcp [--http-port N]  Start the MCP server on stdio or HTTP
+    new <name>           Create a project skeleton in ./<name>
+    doc [<file|dir>]     Write HTML or Markdown API docs (--format, --out)
     pkg <verb>           Package manager operations (RES-205)
     fmt <file>           Canonical source formatter
     lint <file>          Run the starter lints
@@ -33608,24 +33825,6 @@ for focused usage.
 See SYNTAX.md for the language reference.
 "#;
 
-const REPL_HELP_TEXT: &str = r#"rz repl — start the interactive REPL
-
-USAGE:
-    rz repl [--examples-dir DIR]
-    rz repl --help
-    rz repl help
-
-FLAGS:
-    --help, -h            Show this help and exit
-    --examples-dir DIR    REPL examples directory
-
-EXAMPLES:
-    rz repl                   # start REPL
-    rz repl --examples-dir .  # use the current directory for `examples`
-
-For bare REPL startup, run plain `rz`.
-"#;
-
 fn print_help() {
     print!("{}", GLOBAL_HELP_TEXT);
 }
@@ -33638,61 +33837,34 @@ fn normalize_mcp_http_addr(raw: &str) -> String {
     }
 }
 
-fn print_repl_help() {
-    print!("{}", REPL_HELP_TEXT);
-}
-
-const FMT_HELP_TEXT: &str = r#"rz fmt — format a Resilient source file
-
-USAGE:
-    rz fmt <file> [--in-place]
-    rz fmt --check <file>...
-
-OUTPUT:
-    By default, prints the formatted source to stdout.
-    With --in-place, rewrites the file and prints nothing on success.
-    With --check, writes nothing and prints nothing on success; each
-    file that would be reformatted (or fails to parse) is reported on
-    stderr, and the process exits non-zero. Useful in CI and
-    pre-commit hooks. --check cannot be combined with --in-place.
-
-FLAGS:
-    -i, --in-place    Rewrite the file instead of printing formatted source
-        --check       Exit non-zero if any file(s) are not already
-                       formatted; supports one file or a set
-
-EXAMPLES:
-    rz fmt examples/hello.rz
-    rz fmt --in-place examples/hello.rz
-    rz fmt --check examples/hello.rz
-    rz fmt --check examples/*.rz
-
-Run `rz --help` for global flags and other subcommands.
-"#;
-
-fn is_fmt_help_request(args: &[String]) -> bool {
-    args.get(1).map(String::as_str) == Some("fmt")
-        && matches!(
-            args.get(2).map(String::as_str),
-            Some("--help" | "-h" | "help")
-        )
-}
-
-fn print_fmt_help() {
-    print!("{}", FMT_HELP_TEXT);
-}
-
-const DEBUG_HELP_TEXT: &str = r#"rz debug — start the Debug Adapter Protocol server
+const DEBUG_HELP_TEXT: &str = r#"rz debug — run a file under the console debugger
 
 USAGE:
-    rz debug <file>
+    rz debug <file> [--break [FILE:]LINE]... [--live]
+    rz debug --dap [<file>]
 
 BEHAVIOR:
-    Starts a DAP server on stdin/stdout for an editor or debugger client.
-    The file argument labels the session; the DAP launch request supplies the program path.
+    Runs the file with the tree-walking interpreter and stops before its first
+    statement. Commands are read from stdin at the `(rdb)` prompt:
+      step, s              stop at the next statement, entering calls
+      next, n              stop at the next statement in this function
+      continue, c          run to the next breakpoint
+      break, b [FILE:]LINE set a breakpoint; without a line, list them
+      delete, d LINE       remove a breakpoint
+      print, p NAME        show a variable
+      vars, v              show every variable in scope, innermost first
+      backtrace, bt        show the calls in progress
+      live on|off          stop on each retry of a `live` block
+      quit, q              stop the program
+    An empty line repeats the last command; end of input lets the program finish.
+    --live also stops on each retry of a `live` block, with the error that caused it.
+    --dap starts a Debug Adapter Protocol server on stdin/stdout instead, for an
+    editor or debugger client; the DAP launch request supplies the program path.
 
 EXAMPLES:
     rz debug examples/hello.rz
+    rz debug --break 12 --live app.rz
+    rz debug --dap
 
 For direct adapter launches, clients may use `rz --dap`.
 Run `rz --help` for global flags and other subcommands.
@@ -33723,6 +33895,9 @@ FLAGS:
         --emit-diagnostics-json Emit lint diagnostics as JSON
         --safety-critical       Promote safety-critical lint failures
 
+A `[lint]` section in the project's resilient.toml sets a level per
+code (`L0040 = "deny"`: allow, warn, or deny); the flags win over it.
+
 EXAMPLES:
     rz lint examples/hello.rz
     rz lint --deny L0010 examples/hello.rz
@@ -33743,39 +33918,6 @@ fn print_lint_help() {
     print!("{}", LINT_HELP_TEXT);
 }
 
-const CHECK_HELP_TEXT: &str = r#"rz check — type-check a file without running it
-
-USAGE:
-    rz check <file> [FLAGS]
-
-FLAGS:
-    -q, --quiet                 Suppress success output
-        --emit-diagnostics-json Emit parse/type diagnostics as JSON
-        --safety-critical       Promote safety-critical lint failures
-        --strict-refinements    Unresolved refinement obligations become errors (RES-3839)
-        --verifier-timeout-ms N Per-Z3-query timeout in milliseconds
-        --z3-theory MODE        Backend-limited; requires --features z3
-
-EXAMPLES:
-    rz check examples/hello.rz
-    rz check --quiet examples/hello.rz
-    rz check --strict-refinements examples/refinement_compile_time.rz
-
-Run `rz --help` for global flags and other subcommands.
-"#;
-
-fn is_check_help_request(args: &[String]) -> bool {
-    args.get(1).map(String::as_str) == Some("check")
-        && matches!(
-            args.get(2).map(String::as_str),
-            Some("--help" | "-h" | "help")
-        )
-}
-
-fn print_check_help() {
-    print!("{}", CHECK_HELP_TEXT);
-}
-
 const STACK_USAGE_HELP_TEXT: &str = r#"rz stack-usage — estimate per-function worst-case stack use
 
 USAGE:
@@ -33885,19 +34027,22 @@ fn should_report_unknown_command_or_file(filename: &str) -> bool {
         && !Path::new(filename).exists()
 }
 
-pub(crate) fn execute_benchmark_body(
+/// Evaluate `program` in a fresh interpreter, with its output
+/// captured, and hand the interpreter to `measure` — `rz bench` times
+/// benchmark bodies against the program's definitions without timing
+/// the program itself.
+pub(crate) fn with_benchmark_interpreter<T>(
     program: &Node,
-    body: &Node,
     std_bindings: &[(String, crate::stdlib::StdBinding)],
     source_path: &str,
-) -> Result<(), String> {
+    measure: impl FnOnce(&mut Interpreter) -> Result<T, String>,
+) -> Result<T, String> {
     let (result, _captured) = output_sink::with_captured_output(|| {
         let mut interp = Interpreter::new();
         interp.source_path = source_path.to_string();
         crate::stdlib::inject_std_bindings(std_bindings, &interp.env);
         interp.eval(program)?;
-        let _ = interp.eval(body)?;
-        Ok(())
+        measure(&mut interp)
     });
     result
 }
@@ -33945,7 +34090,7 @@ pub struct RunResult {
 /// closure unless you want the inner capture to be the only output
 /// the outer call sees.
 pub fn run_program(src: &str) -> RunResult {
-    let (program, parse_errors) = parse(src);
+    let (program, parse_errors) = parse_silent(src);
     if !parse_errors.is_empty() {
         return RunResult {
             ok: false,
@@ -33953,25 +34098,7 @@ pub fn run_program(src: &str) -> RunResult {
             errors: parse_errors,
         };
     }
-    let (eval_result, captured) = output_sink::with_captured_output(|| {
-        let mut interp = Interpreter::new();
-        interp.source_path = "<input>".to_string();
-        interp.eval(&program)?;
-        run_pending_actors(&mut interp)?;
-        Ok(Value::Void)
-    });
-    match eval_result {
-        Ok(_) => RunResult {
-            ok: true,
-            stdout: captured,
-            errors: Vec::new(),
-        },
-        Err(e) => RunResult {
-            ok: false,
-            stdout: captured,
-            errors: vec![e],
-        },
-    }
+    interpreter::run(&program)
 }
 
 /// RES-2627: `rz stack-usage <file>` — print per-function worst-case
@@ -34088,6 +34215,14 @@ fn dispatch_stack_usage_subcommand(args: &[String]) -> Option<i32> {
     Some(if budget_exceeded { 1 } else { 0 })
 }
 
+/// `rz` on `wasm32-wasi`: the REPL, watch mode and tool servers don't
+/// build there, so WASI gets a one-file runner instead; see
+/// [`host_profile`].
+#[cfg(target_os = "wasi")]
+pub fn run_cli() {
+    host_profile::run_cli();
+}
+
 /// RES-510: CLI entry point.
 ///
 /// Moved out of `fn main()` so the rest of the compiler is reachable
@@ -34109,6 +34244,7 @@ pub fn run_cli() {
             Some(
                 "bench"
                     | "build"
+                    | "run"
                     | "check"
                     | "debug"
                     | "fmt"
@@ -34127,6 +34263,30 @@ pub fn run_cli() {
         args.remove(1);
     }
 
+    // `rz debug <file> [FLAGS]` runs the file under the console
+    // debugger (`debug_console`); `rz debug --dap` is the DAP server
+    // for editors, dispatched below.
+    let mut debug: Option<debug_console::DebugOptions> = None;
+    if args.get(1).map(String::as_str) == Some("debug")
+        && !is_debug_help_request(&args)
+        && !args.iter().any(|a| a == "--dap")
+    {
+        if args.len() == 2 {
+            eprintln!(
+                "Error: `rz debug` needs a file. Usage: rz debug <file> [--break [FILE:]LINE]... [--live]"
+            );
+            std::process::exit(2);
+        }
+        args.remove(1);
+        match debug_console::take_flags(&mut args) {
+            Ok(options) => debug = Some(options),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
     // RES-209: `--version` / `-V` prints the compiler version plus a
     // pre-1.0 stability notice and exits. See STABILITY.md at the
     // repo root for the policy this notice points to.
@@ -34148,10 +34308,27 @@ pub fn run_cli() {
         std::process::exit(0);
     }
 
-    if is_fmt_help_request(&args) {
-        print_fmt_help();
-        std::process::exit(0);
+    // `run`, `check`, `verify`, `repl`, `fmt` and `test` are declared
+    // with clap; usage errors and their `--help` exit inside
+    // `cli::parse`.
+    let mut run_args: Option<Vec<String>> = None;
+    let mut repl_examples_dir: Option<PathBuf> = None;
+    match cli::parse(&args) {
+        Some(cli::Command::Run(run)) => run_args = Some(run.args),
+        Some(cli::Command::Check(check) | cli::Command::Verify(check)) => {
+            std::process::exit(run_check_subcommand(check, &args))
+        }
+        Some(cli::Command::Repl(repl)) => {
+            repl_examples_dir = repl.examples_dir;
+            args.truncate(2);
+        }
+        Some(cli::Command::Fmt(fmt)) => std::process::exit(run_fmt_subcommand(fmt)),
+        Some(cli::Command::Test(test)) => {
+            std::process::exit(test_runner::run_test_subcommand(test))
+        }
+        None => {}
     }
+
     if is_build_help_request(&args) {
         print_build_help();
         std::process::exit(0);
@@ -34160,10 +34337,6 @@ pub fn run_cli() {
         print_debug_help();
         std::process::exit(0);
     }
-    if is_check_help_request(&args) {
-        print_check_help();
-        std::process::exit(0);
-    }
     if is_lint_help_request(&args) {
         print_lint_help();
         std::process::exit(0);
@@ -34220,9 +34393,19 @@ pub fn run_cli() {
         std::process::exit(code);
     }
 
-    // RES-test: `rz test [<file|dir>] [--filter <substr>]` —
-    // discover and run test functions.
-    if let Some(code) = test_runner::dispatch_test_subcommand(&args) {
+    // `rz mutate [<file|dir>]` — run each file's tests against
+    // mutants of its functions.
+    if let Some(code) = mutation_testing::dispatch_mutate_subcommand(&args) {
+        std::process::exit(code);
+    }
+
+    // `rz new <name>` — create a project skeleton in ./<name>.
+    if let Some(code) = new_project::dispatch_new_subcommand(&args) {
+        std::process::exit(code);
+    }
+
+    // `rz doc [<file|dir>]` — write API docs per module.
+    if let Some(code) = api_docs::dispatch_doc_subcommand(&args) {
         std::process::exit(code);
     }
 
@@ -34247,20 +34430,12 @@ pub fn run_cli() {
         && !Path::new("help").is_file();
 
     // RES-211: `--help` / `-h` / top-level `help` prints a short
-    // usage summary listing the most-used flags. Kept hand-rolled
-    // (no clap dep) to match the rest of the driver's arg-parsing style.
-    if (top_level_help_word || args.iter().any(|a| a == "--help" || a == "-h")) && !explicit_repl {
+    // usage summary listing the most-used flags.
+    if top_level_help_word || args.iter().any(|a| a == "--help" || a == "-h") {
         print_help();
         std::process::exit(0);
     }
 
-    // RES-fmt: `fmt <file> [--in-place]` — canonical source
-    // formatter. Short-circuits before any compilation; returns
-    // before the flag-parsing loop touches subcommand args.
-    if let Some(code) = dispatch_fmt_subcommand(&args) {
-        std::process::exit(code);
-    }
-
     // RES-2611: `dump-source-map <file>` — print bytecode → source-line map.
     if let Some(code) = source_map::dispatch_dump_source_map(&args) {
         std::process::exit(code);
@@ -34298,11 +34473,6 @@ pub fn run_cli() {
         std::process::exit(code);
     }
 
-    // RES-225: `check <file>` — parse + type-check without running.
-    if let Some(code) = dispatch_check_subcommand(&args) {
-        std::process::exit(code);
-    }
-
     // RES-3987 (D-E1): `build --target <TRIPLE> <file>` — compile to
     // a `.rzbc` blob for the no_std embedded runtime VM.
     if let Some(code) = dispatch_build_subcommand(&args) {
@@ -34314,7 +34484,7 @@ pub fn run_cli() {
         std::process::exit(code);
     }
 
-    // DAP debug subcommand: `rz debug <file>` starts the DAP server.
+    // DAP debug subcommand: `rz debug --dap` starts the DAP server.
     #[cfg(not(target_arch = "wasm32"))]
     if let Some(code) = dap_server::dispatch_dap(&args) {
         std::process::exit(code);
@@ -34326,7 +34496,7 @@ pub fn run_cli() {
     // invocation, surface diagnostics on stderr, and continue
     // execution. `-t / --typecheck` (and the modes that imply it
     // via `typecheck_strict` below) keep the legacy strict
-    // semantics — fail with exit 1 on any type error.
+    // semantics — fail with exit 2 on any type error.
     let mut no_typecheck = false;
     // RES-2646: `--typecheck-strict` makes type errors fatal without
     // the verbose status lines that `--typecheck` emits.
@@ -34348,9 +34518,10 @@ pub fn run_cli() {
     // RES-194: Ed25519 signing key — when present, the driver
     // writes `cert.sig` alongside the `.smt2` files.
     let mut sign_cert_key: Option<PathBuf> = None;
-    let mut examples_dir: Option<PathBuf> = None;
+    let mut examples_dir = repl_examples_dir;
     let mut use_vm = false;
     let mut use_jit = false;
+    let mut hot_jit = false;
     let mut lsp_mode = false;
     let mut mcp_mode = false;
     let mut mcp_http_addr: Option<String> = None;
@@ -34359,8 +34530,9 @@ pub fn run_cli() {
     // lexer regressions are inspectable without editing source.
     let mut dump_tokens = false;
     // RES-781: --dump-ast-json prints a stable JSON AST view used by
-    // the self-hosting parity harness.
-    let mut dump_ast_json = false;
+    // the self-hosting parity harness; --dump-ast prints the indented
+    // tree instead, and --dump-ast=json is the JSON view again.
+    let mut dump_ast: Option<AstDumpFormat> = None;
     // RES-173: --dump-chunks compiles the program and prints a
     // human-readable VM disassembly. Reflects RES-172 peephole
     // results because the compiler runs peephole before the
@@ -34392,11 +34564,28 @@ pub fn run_cli() {
     // diagnostic instead of being silently healed. Handy during
     // development — `--no-panic-on-fault` restores the default.
     let mut panic_on_fault_flag = false;
+    // `--max-steps N` caps the run at N evaluation steps; see `fuel`.
+    let mut max_steps: Option<u64> = None;
+    // `--max-memory BYTES` caps bound program data; see `memory_limit`.
+    let mut max_memory: Option<usize> = None;
+    // `--trace[=NAME,...]` logs function calls; see `call_trace`.
+    let mut trace: Option<Vec<String>> = None;
+    // `--step` pauses before each statement; see `debug_console`.
+    let mut step = false;
+    // `--profile` times each user function; see `profiler`.
+    let mut profile = false;
+    // `--metrics-port [HOST:]PORT` serves runtime counters; see `metrics`.
+    let mut metrics_addr: Option<String> = None;
+    // `--record PATH` / `--replay PATH`; see `record_replay`.
+    let mut record_path: Option<String> = None;
+    let mut replay_path: Option<String> = None;
     let mut emit_live_log: Option<PathBuf> = None;
     // RES-355: `--no-cache` bypasses the incremental compilation cache
     // for this run. Both cache reads and writes are skipped so the
     // run is fully isolated from the on-disk cache state.
     let mut no_cache = false;
+    // `--quiet` drops the "Program executed successfully" line.
+    let mut quiet = false;
     // RES-1659: `--persistent-proof-cache <path>` enables the
     // cross-build Z3 proof cache (RES-1657). Loaded at startup;
     // saved at exit. Persists `u64` keys for obligations proven
@@ -34415,48 +34604,46 @@ pub fn run_cli() {
     // RES-228: `--watch` re-runs the program on every file save.
     let mut watch_mode = false;
     let mut filename = "";
-    let mut repl_help = false;
+    // `rz -` reads the program from stdin and `rz -e CODE` takes it
+    // from the command line; `filename` is then `<stdin>` / `<eval>`.
+    let mut inline_source: Option<String> = None;
+    // A project directory runs its entry file; see `project`.
+    let project_entry: String;
     // RES-3840: `--vibe-gate <threshold>` gates compilation on vibe_debt score.
     // Threshold is in [0.0, 1.0] range. Exits 0 if vibe_debt <= threshold,
     // exits 2 if > threshold, emitting structured JSON to stderr.
     let mut vibe_gate_threshold: Option<f64> = None;
 
+    // A misspelt subcommand (`rz verfiy prog.rz`) must not run the
+    // file after it as though nothing else was asked.
+    if run_args.is_none()
+        && !explicit_repl
+        && let Some(word) = args.get(1)
+        && !word.starts_with('-')
+        && word != "mcp"
+        && should_report_unknown_command_or_file(word)
+    {
+        eprintln!(
+            "Error: unknown command or file `{}`. Run `rz help` or `rz --help` to list subcommands, or pass an existing file path.",
+            word
+        );
+        std::process::exit(2);
+    }
+
+    // `rz run <file> [FLAGS]` is the default file mode spelled out.
+    if let Some(run) = run_args {
+        args.truncate(1);
+        args.extend(run);
+    }
+
     // Simple argument parsing
     if args.len() > 1 {
         let mut i = 1;
         while i < args.len() {
             let arg = &args[i];
             if explicit_repl {
-                if i == 1 {
-                    i += 1;
-                    continue;
-                }
-                if arg == "help" || arg == "--help" || arg == "-h" {
-                    repl_help = true;
-                    i += 1;
-                    continue;
-                }
-                if arg == "--examples-dir" {
-                    i += 1;
-                    if i >= args.len() {
-                        eprintln!("Error: --examples-dir requires a directory argument");
-                        std::process::exit(2);
-                    }
-                    examples_dir = Some(PathBuf::from(&args[i]));
-                    i += 1;
-                    continue;
-                }
-                if let Some(dir) = arg.strip_prefix("--examples-dir=") {
-                    examples_dir = Some(PathBuf::from(dir));
-                    i += 1;
-                    continue;
-                }
-                if arg.starts_with('-') {
-                    eprintln!("Error: unknown flag `{}` to `rz repl`", arg);
-                    std::process::exit(2);
-                }
-                eprintln!("Error: `rz repl` does not take file arguments");
-                std::process::exit(2);
+                // `cli` parsed the REPL's own flags.
+                break;
             }
             if arg == "--typecheck" || arg == "-t" {
                 type_check = true;
@@ -34485,6 +34672,217 @@ pub fn run_cli() {
                 // the pass actually runs before the program executes.
                 bounds_check::set_deny_unproven_bounds(true);
                 type_check = true;
+            } else if arg == "--strict" {
+                // Strict typing: every `Any` reaching a parameter,
+                // return annotation, or `let` binding is an error.
+                // Implies --typecheck so the diagnostics are fatal.
+                strict_any::set_strict_any(true);
+                type_check = true;
+            } else if arg == "--coercion" || arg.starts_with("--coercion=") {
+                // Implicit-coercion policy for the checker and both
+                // runtimes; see `coercion`.
+                let value = match arg.strip_prefix("--coercion=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!(
+                                "Error: --coercion requires a policy: standard, strict, or permissive"
+                            );
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                let policy = coercion::CoercionPolicy::parse(&value).unwrap_or_else(|| {
+                    eprintln!(
+                        "Error: unknown --coercion policy `{}` (expected standard, strict, or permissive)",
+                        value
+                    );
+                    std::process::exit(2);
+                });
+                coercion::set_policy(policy);
+            } else if arg == "--fs" || arg.starts_with("--fs=") {
+                // Filesystem sandbox for the file builtins; see
+                // `fs_sandbox`.
+                let value = match arg.strip_prefix("--fs=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!(
+                                "Error: --fs requires a policy: read-write, read-only, or none"
+                            );
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                let policy = fs_sandbox::FsPolicy::parse(&value).unwrap_or_else(|| {
+                    eprintln!(
+                        "Error: unknown --fs policy `{}` (expected read-write, read-only, or none)",
+                        value
+                    );
+                    std::process::exit(2);
+                });
+                fs_sandbox::set_policy(policy);
+            } else if arg == "--allow" || arg.starts_with("--allow=") {
+                // Capabilities the I/O builtins may use; see `sandbox`.
+                let value = match arg.strip_prefix("--allow=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!(
+                                "Error: --allow requires capabilities: fs-read, fs-write, fs, net, exec, serial, mmio, ffi, or none"
+                            );
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                match sandbox::Capabilities::parse(&value) {
+                    Ok(allow) => sandbox::set_allowed(Some(allow)),
+                    Err(e) => {
+                        eprintln!("Error: --allow: {}", e);
+                        std::process::exit(2);
+                    }
+                }
+            } else if arg == "--net" || arg.starts_with("--net=") {
+                // Network sandbox for the HTTP and MQTT builtins; see
+                // `http_client`.
+                let value = match arg.strip_prefix("--net=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --net requires a policy: allow or none");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                let policy = http_client::NetPolicy::parse(&value).unwrap_or_else(|| {
+                    eprintln!(
+                        "Error: unknown --net policy `{}` (expected allow or none)",
+                        value
+                    );
+                    std::process::exit(2);
+                });
+                http_client::set_net_policy(policy);
+            } else if arg == "--ffi" || arg.starts_with("--ffi=") {
+                // Native-code sandbox for `extern` declarations; see `ffi`.
+                let value = match arg.strip_prefix("--ffi=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --ffi requires a policy: allow or none");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                let policy = ffi::FfiPolicy::parse(&value).unwrap_or_else(|| {
+                    eprintln!(
+                        "Error: unknown --ffi policy `{}` (expected allow or none)",
+                        value
+                    );
+                    std::process::exit(2);
+                });
+                ffi::set_policy(policy);
+            } else if arg == "--mmio" || arg.starts_with("--mmio=") {
+                // Register blocks `mmio_read` / `mmio_write` may touch;
+                // see `mmio`.
+                let value = match arg.strip_prefix("--mmio=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --mmio requires address ranges or none");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                match mmio::parse_ranges(&value) {
+                    Ok(ranges) => mmio::set_ranges(ranges),
+                    Err(e) => {
+                        eprintln!("Error: --mmio: {}", e);
+                        std::process::exit(2);
+                    }
+                }
+            } else if arg == "--mmio-device" || arg.starts_with("--mmio-device=") {
+                let path = match arg.strip_prefix("--mmio-device=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --mmio-device requires a device path");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                mmio::set_device(path);
+            } else if arg == "--ffi-lib" || arg.starts_with("--ffi-lib=") {
+                // Libraries `extern "C"` declarations bind against.
+                let path = match arg.strip_prefix("--ffi-lib=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --ffi-lib requires a library path");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                ffi::add_startup_library(path);
+            } else if arg == "--plugin" || arg.starts_with("--plugin=") {
+                // Builtin packages; see `plugin`.
+                let path = match arg.strip_prefix("--plugin=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --plugin requires a library path");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                match plugin::load(&path) {
+                    Ok(()) => {}
+                    Err(plugin::PluginError::Unsupported) => {
+                        eprintln!(
+                            "{}",
+                            backend_limited_feature_message("--plugin", "plugins", None)
+                        );
+                        std::process::exit(1);
+                    }
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(2);
+                    }
+                }
+            } else if let Some(applied) = error_format::apply_cli_flag(&args, &mut i) {
+                // `--error-format=json|sarif`; see `error_format`.
+                if let Err(e) = applied {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(2);
+                }
+            } else if let Some(applied) = warnings::apply_cli_flag(&args, &mut i) {
+                // Warning categories; see `warnings`. A denied warning
+                // must stop the run, so `--deny-warnings` implies
+                // --typecheck.
+                if let Err(e) = applied {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(2);
+                }
+                if arg == "--deny-warnings" {
+                    type_check = true;
+                }
             } else if arg == "--strict-termination" {
                 // RES-398: strict mode — directly-recursive fns must
                 // declare `// @decreases <metric>` or `// @may_diverge`
@@ -34564,6 +34962,33 @@ pub fn run_cli() {
                 // RES-072 / RES-096: route through the Cranelift JIT
                 // backend for the supported tree-walker subset.
                 use_jit = true;
+            } else if arg == "--backend" || arg.starts_with("--backend=") {
+                // Execution engine: `interp` (the tree-walker, the
+                // default), `vm` (same as --vm) or `jit`, the walker
+                // with its hot numeric functions compiled by Cranelift.
+                let value = match arg.strip_prefix("--backend=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --backend requires a name: interp, vm, or jit");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                (use_vm, hot_jit) = match value.as_str() {
+                    "interp" => (false, false),
+                    "vm" => (true, false),
+                    "jit" => (false, true),
+                    other => {
+                        eprintln!(
+                            "Error: unknown --backend `{}` (expected interp, vm, or jit)",
+                            other
+                        );
+                        std::process::exit(2);
+                    }
+                };
             } else if arg == "--lsp" {
                 // RES-074: start the Language Server on stdio. Only
                 // functional when built with `--features lsp`; the
@@ -34597,7 +35022,20 @@ pub fn run_cli() {
                 dump_tokens = true;
             } else if arg == "--dump-ast-json" {
                 // RES-781: print the parsed AST as stable JSON and exit.
-                dump_ast_json = true;
+                dump_ast = Some(AstDumpFormat::Json);
+            } else if arg == "--dump-ast" {
+                dump_ast = Some(AstDumpFormat::Tree);
+            } else if let Some(value) = arg.strip_prefix("--dump-ast=") {
+                match AstDumpFormat::parse(value) {
+                    Some(format) => dump_ast = Some(format),
+                    None => {
+                        eprintln!(
+                            "Error: --dump-ast must be `tree` or `json`; got {:?}",
+                            value
+                        );
+                        std::process::exit(2);
+                    }
+                }
             } else if arg == "--dump-chunks" {
                 // RES-173: compile the program to bytecode and print
                 // a human-readable disassembly (RES-172 peephole
@@ -34744,6 +35182,162 @@ pub fn run_cli() {
                 // retry behaviour even if an earlier arg or wrapper
                 // script set `--panic-on-fault`.
                 panic_on_fault_flag = false;
+            } else if arg == "--max-steps" || arg.starts_with("--max-steps=") {
+                let value = match arg.strip_prefix("--max-steps=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --max-steps requires a step count");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                max_steps = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("Error: --max-steps expects a u64, got {:?}", value);
+                    std::process::exit(2);
+                }));
+            } else if arg == "--max-memory" || arg.starts_with("--max-memory=") {
+                let value = match arg.strip_prefix("--max-memory=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --max-memory requires a byte count");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                max_memory = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("Error: --max-memory expects a byte count, got {:?}", value);
+                    std::process::exit(2);
+                }));
+            } else if arg == "--metrics-port" || arg.starts_with("--metrics-port=") {
+                let value = match arg.strip_prefix("--metrics-port=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --metrics-port requires a port");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                // A bare port listens on every interface, like other
+                // Prometheus exporters; `HOST:PORT` picks one.
+                metrics_addr = Some(if value.parse::<u16>().is_ok() {
+                    format!("0.0.0.0:{value}")
+                } else if value
+                    .rsplit_once(':')
+                    .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+                {
+                    value
+                } else {
+                    eprintln!(
+                        "Error: --metrics-port expects a port or HOST:PORT, got {:?}",
+                        value
+                    );
+                    std::process::exit(2);
+                });
+            } else if arg == "--step" {
+                step = true;
+            } else if arg == "--profile" {
+                profile = true;
+            } else if arg == "--trace" {
+                trace = Some(Vec::new());
+            } else if let Some(names) = arg.strip_prefix("--trace=") {
+                let names: Vec<String> = names
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|n| !n.is_empty())
+                    .map(String::from)
+                    .collect();
+                if names.is_empty() {
+                    eprintln!("Error: --trace= expects function names, e.g. --trace=retry,fetch");
+                    std::process::exit(2);
+                }
+                trace = Some(names);
+            } else if arg == "--record" || arg == "--replay" {
+                i += 1;
+                if i >= args.len() {
+                    eprintln!("Error: {} requires a trace file path", arg);
+                    std::process::exit(2);
+                }
+                if arg == "--record" {
+                    record_path = Some(args[i].clone());
+                } else {
+                    replay_path = Some(args[i].clone());
+                }
+                if record_path.is_some() && replay_path.is_some() {
+                    eprintln!("Error: --record and --replay cannot be combined");
+                    std::process::exit(2);
+                }
+            } else if arg == "--log-level" || arg.starts_with("--log-level=") {
+                let value = match arg.strip_prefix("--log-level=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --log-level requires a level");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                let level = match value.as_str() {
+                    "off" => None,
+                    other => Some(logging::Level::parse(other).unwrap_or_else(|| {
+                        eprintln!(
+                            "Error: --log-level expects debug, info, warn, error or off, got {:?}",
+                            value
+                        );
+                        std::process::exit(2);
+                    })),
+                };
+                logging::set_log_level(level);
+            } else if arg == "--log-format" || arg.starts_with("--log-format=") {
+                let value = match arg.strip_prefix("--log-format=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --log-format requires a format");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                logging::set_log_format(logging::LogFormat::parse(&value).unwrap_or_else(|| {
+                    eprintln!("Error: --log-format expects text or json, got {:?}", value);
+                    std::process::exit(2);
+                }));
+            } else if arg == "--float-strict" {
+                float_mode::set_float_strict(true);
+            } else if arg == "--float-precision" || arg.starts_with("--float-precision=") {
+                let value = match arg.strip_prefix("--float-precision=") {
+                    Some(v) => v.to_string(),
+                    None => {
+                        i += 1;
+                        if i >= args.len() {
+                            eprintln!("Error: --float-precision requires a digit count");
+                            std::process::exit(2);
+                        }
+                        args[i].clone()
+                    }
+                };
+                match value.parse::<usize>() {
+                    Ok(digits) if digits <= 17 => float_mode::set_float_precision(Some(digits)),
+                    _ => {
+                        eprintln!(
+                            "Error: --float-precision expects a digit count from 0 to 17, got {:?}",
+                            value
+                        );
+                        std::process::exit(2);
+                    }
+                }
             } else if arg == "--emit-live-log" {
                 i += 1;
                 if i >= args.len() {
@@ -34764,11 +35358,16 @@ pub fn run_cli() {
                 examples_dir = Some(PathBuf::from(&args[i]));
             } else if let Some(dir) = arg.strip_prefix("--examples-dir=") {
                 examples_dir = Some(PathBuf::from(dir));
+            } else if arg == "--no-prelude" {
+                // Leave out the functions `prelude.rz` defines.
+                prelude::set_enabled(false);
             } else if arg == "--no-cache" {
                 // RES-355: bypass the incremental bytecode cache for
                 // this run. Both cache reads and writes are skipped so
                 // the compilation is isolated from any on-disk state.
                 no_cache = true;
+            } else if arg == "--quiet" || arg == "-q" {
+                quiet = true;
             } else if arg == "--feature" {
                 // RES-343: `--feature NAME` activates a cfg feature for
                 // this compilation. Repeatable: each occurrence adds
@@ -34870,19 +35469,222 @@ pub fn run_cli() {
                         std::process::exit(2);
                     }
                 }
+            } else if arg == "-e" || arg == "--eval" {
+                i += 1;
+                let Some(code) = args.get(i) else {
+                    eprintln!(
+                        "Error: {} requires a program, e.g. rz -e 'println(1);'",
+                        arg
+                    );
+                    std::process::exit(2);
+                };
+                inline_source = Some(code.clone());
+                filename = "<eval>";
+            } else if let Some(code) = arg.strip_prefix("--eval=") {
+                inline_source = Some(code.to_string());
+                filename = "<eval>";
+            } else if arg == "--" {
+                // Everything after `--` belongs to the program; see
+                // `args()`.
+                set_script_args(args[i + 1..].to_vec());
+                break;
             } else {
                 filename = arg;
             }
             i += 1;
         }
 
+        if filename == "-" {
+            let mut src = String::new();
+            if let Err(e) = io::Read::read_to_string(&mut io::stdin(), &mut src) {
+                eprintln!("Error: could not read the program from stdin: {}", e);
+                std::process::exit(1);
+            }
+            inline_source = Some(src);
+            filename = "<stdin>";
+        }
+        // Hot functions run natively too, so `--backend=jit` would
+        // hide the calls they make.
+        let other_backend = if use_jit {
+            "--jit"
+        } else if hot_jit {
+            "--backend=jit"
+        } else {
+            "--vm"
+        };
+        if trace.is_some() && (use_vm || use_jit || hot_jit) {
+            eprintln!(
+                "Error: --trace follows the tree-walking interpreter; drop {}",
+                other_backend
+            );
+            std::process::exit(2);
+        }
+        if profile && (use_vm || use_jit || hot_jit) {
+            eprintln!(
+                "Error: --profile follows the tree-walking interpreter; drop {}",
+                other_backend
+            );
+            std::process::exit(2);
+        }
+        if metrics_addr.is_some() && (use_vm || use_jit || hot_jit) {
+            eprintln!(
+                "Error: --metrics-port follows the tree-walking interpreter; drop {}",
+                other_backend
+            );
+            std::process::exit(2);
+        }
+        // The VM and the JIT keep their data outside `Environment`,
+        // where `memory_limit` counts it.
+        if max_memory.is_some() && (use_vm || use_jit) {
+            eprintln!(
+                "Error: --max-memory is counted by the tree-walking interpreter; drop {}",
+                if use_jit { "--jit" } else { "--vm" }
+            );
+            std::process::exit(2);
+        }
+        if step {
+            if debug.is_some() {
+                eprintln!(
+                    "Error: --step can't be combined with `rz debug`; use its `step` command"
+                );
+                std::process::exit(2);
+            }
+            if use_vm || use_jit || hot_jit {
+                eprintln!(
+                    "Error: --step follows the tree-walking interpreter; drop {}",
+                    other_backend
+                );
+                std::process::exit(2);
+            }
+            if watch_mode || filename == "<stdin>" {
+                eprintln!(
+                    "Error: --step reads Enter from stdin; it can't be combined with {}",
+                    if watch_mode { "--watch" } else { "`-`" }
+                );
+                std::process::exit(2);
+            }
+        }
+        if debug.is_some() {
+            if use_vm || use_jit || hot_jit {
+                eprintln!(
+                    "Error: `rz debug` runs the tree-walking interpreter; drop {}",
+                    other_backend
+                );
+                std::process::exit(2);
+            }
+            if watch_mode || filename == "<stdin>" {
+                eprintln!(
+                    "Error: `rz debug` reads its commands from stdin; it can't be combined with {}",
+                    if watch_mode { "--watch" } else { "`-`" }
+                );
+                std::process::exit(2);
+            }
+        }
+        if inline_source.is_some() {
+            if watch_mode {
+                eprintln!("Error: --watch needs a file; it can't watch {}", filename);
+                std::process::exit(2);
+            }
+            // Nothing on disk to key the incremental cache by.
+            no_cache = true;
+        }
+        if inline_source.is_none() && Path::new(filename).is_dir() {
+            match project::resolve_entry(Path::new(filename)) {
+                Ok(entry) => {
+                    project_entry = entry.display().to_string();
+                    filename = &project_entry;
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        }
+
+        // Settings the project shares through its resilient.toml; a
+        // flag on the command line wins. See `project_config`.
+        if !filename.is_empty() {
+            let config = project_config::ProjectConfig::for_path(
+                inline_source.is_none().then(|| Path::new(filename)),
+            );
+            config.apply_checker_settings(&args);
+            let cli = |flags: &[&str]| project_config::cli_sets(&args, flags);
+            if !cli(&[
+                "-t",
+                "--typecheck",
+                "--typecheck-strict",
+                "--strict",
+                "--no-typecheck",
+            ]) {
+                match config.strictness {
+                    Some(project_config::Strictness::Off) => no_typecheck = true,
+                    Some(
+                        project_config::Strictness::Fatal | project_config::Strictness::Strict,
+                    ) => type_strict = true,
+                    Some(project_config::Strictness::Soft) | None => {}
+                }
+            }
+            // As with --deny-warnings, a denied warning has to stop the run.
+            if config.deny_warnings == Some(true) && !cli(&["--deny-warnings"]) {
+                type_strict = true;
+            }
+            if let Some(retries) = config.live_retries {
+                set_default_live_retries(retries);
+            }
+            if let Some(on) = config.panic_on_fault
+                && !cli(&["--panic-on-fault", "--no-panic-on-fault"])
+            {
+                panic_on_fault_flag = on;
+            }
+            if let Some(allow) = config.allow
+                && !cli(&["--allow"])
+            {
+                sandbox::set_allowed(Some(allow));
+            }
+            if let Some(policy) = config.fs
+                && !cli(&["--fs"])
+            {
+                fs_sandbox::set_policy(policy);
+            }
+            if let Some(policy) = config.net
+                && !cli(&["--net"])
+            {
+                http_client::set_net_policy(policy);
+            }
+            if let Some(policy) = config.ffi
+                && !cli(&["--ffi"])
+            {
+                ffi::set_policy(policy);
+            }
+            if let Some(ranges) = config.mmio.clone()
+                && !cli(&["--mmio"])
+            {
+                mmio::set_ranges(ranges);
+            }
+            if let Some(path) = config.mmio_device.clone()
+                && !cli(&["--mmio-device"])
+            {
+                mmio::set_device(path);
+            }
+            if let Some(ms) = config.verifier_timeout_ms
+                && !cli(&["--verifier-timeout-ms"])
+            {
+                verifier_timeout_ms = ms;
+            }
+            if let Some(on) = config.warn_unverified
+                && !cli(&["--warn-unverified", "--no-warn-unverified"])
+            {
+                warn_unverified = on;
+            }
+        }
+
         if filename == "repl" && !std::path::Path::new(filename).exists() {
             eprintln!(
                 "Error: `repl` is not a subcommand. Run `rz` with no arguments to start the REPL."
             );
             std::process::exit(2);
         }
-        if should_report_unknown_command_or_file(filename) {
+        if inline_source.is_none() && should_report_unknown_command_or_file(filename) {
             eprintln!(
                 "Error: unknown command or file `{}`. Run `rz help` or `rz --help` to list subcommands, or pass an existing file path.",
                 filename
@@ -34938,13 +35740,13 @@ pub fn run_cli() {
         // RES-112: --dump-tokens is mutually exclusive with --lsp
         // (both are terminal modes that don't want a file arg the
         // other way). Emit a clean error if the user combined them.
-        if (dump_tokens || dump_ast_json) && lsp_mode {
+        if (dump_tokens || dump_ast.is_some()) && lsp_mode {
             eprintln!("Error: --dump-tokens/--dump-ast-json and --lsp are mutually exclusive");
             std::process::exit(2);
         }
         // RES-173: --dump-chunks mutually exclusive with the other
         // terminal modes for the same reason.
-        if dump_chunks && (lsp_mode || dump_tokens || dump_ast_json) {
+        if dump_chunks && (lsp_mode || dump_tokens || dump_ast.is_some()) {
             eprintln!(
                 "Error: --dump-chunks and --dump-tokens/--dump-ast-json/--lsp are mutually exclusive"
             );
@@ -34962,8 +35764,10 @@ pub fn run_cli() {
             eprintln!("Error: --emit-lean-spec requires a path argument");
             std::process::exit(2);
         }
-        if dump_ast_json && filename.is_empty() {
-            eprintln!("Error: --dump-ast-json requires a path argument");
+        if let Some(format) = dump_ast
+            && filename.is_empty()
+        {
+            eprintln!("Error: {} requires a path argument", format.flag());
             std::process::exit(2);
         }
         if dump_chunks && filename.is_empty() {
@@ -34971,25 +35775,33 @@ pub fn run_cli() {
             std::process::exit(2);
         }
 
-        if explicit_repl && repl_help {
-            print_repl_help();
-            return;
-        }
-
         // RES-150: install the RNG seed before any user program
         // can pull from it. `--seed <N>` pins the sequence
         // (silently, since the user asked for reproducibility);
         // otherwise we derive from the monotonic clock and echo
         // the chosen seed to stderr so a failing run can be
         // replayed verbatim with `--seed <N>`.
-        let used_seed = match seed_override {
-            Some(n) => {
-                seed_rng(n);
-                n
-            }
-            None => seed_rng_from_clock(),
-        };
-        if seed_override.is_none() {
+        if let Some(path) = &record_path
+            && let Err(e) = record_replay::start_recording(path)
+        {
+            eprintln!("Error: --record: {}", e);
+            std::process::exit(2);
+        }
+        if let Some(path) = &replay_path
+            && let Err(e) = record_replay::start_replay(path)
+        {
+            eprintln!("Error: --replay: {}", e);
+            std::process::exit(2);
+        }
+        // The seed is a recorded input too, so a replay reinstalls the
+        // recorded one whatever `--seed` says.
+        let used_seed = record_replay::number(record_replay::Kind::Seed, || {
+            seed_override.unwrap_or_else(seed_rng_from_clock)
+        });
+        seed_rng(used_seed);
+        // Under `--error-format=json|sarif` stderr carries only
+        // diagnostics.
+        if seed_override.is_none() && !error_format::is_structured() {
             eprintln!("seed={}", used_seed);
         }
 
@@ -35073,18 +35885,18 @@ pub fn run_cli() {
             }
         }
 
-        if dump_ast_json {
+        if let Some(format) = dump_ast {
             if filename.is_empty() {
-                eprintln!("Error: --dump-ast-json requires a path argument");
+                eprintln!("Error: {} requires a path argument", format.flag());
                 std::process::exit(2);
             }
             match fs::read_to_string(filename) {
                 Ok(src) => {
-                    if let Err(errs) = dump_ast_json_to_stdout(&src) {
+                    if let Err(errs) = dump_ast_to_stdout(&src, format) {
                         for err in errs {
                             eprintln!("Parser error: {}", err);
                         }
-                        std::process::exit(1);
+                        std::process::exit(2);
                     }
                     return;
                 }
@@ -35118,7 +35930,7 @@ pub fn run_cli() {
                 for e in errs {
                     eprintln!("Parser error: {}", e);
                 }
-                std::process::exit(1);
+                std::process::exit(2);
             }
             // RES-073: resolve `use "..."` before the compiler sees
             // the AST, matching the --vm driver path.
@@ -35166,6 +35978,13 @@ pub fn run_cli() {
         // the exact same execution as the non-watch path.
         if watch_mode && !filename.is_empty() {
             set_panic_on_fault(panic_on_fault_flag);
+            fuel::set_max_steps(max_steps);
+            memory_limit::set_max_memory(max_memory);
+            call_trace::set_trace(trace.clone());
+            profiler::set_profile(profile);
+            if let Some(addr) = &metrics_addr {
+                start_metrics(addr);
+            }
             let file_path = std::path::Path::new(filename);
             // Snapshot flag values into owned/Copy locals for the closure.
             let filename_owned = filename.to_string();
@@ -35176,8 +35995,12 @@ pub fn run_cli() {
             #[cfg(feature = "z3")]
             let z3_theory_snap = z3_theory;
             watch_mode::run_watch(file_path, || {
+                fuel::refill();
+                clear_exit_request();
+                take_soft_type_error();
                 let result = execute_file(
                     &filename_owned,
+                    None,
                     type_check,
                     no_typecheck,
                     audit,
@@ -35187,6 +36010,7 @@ pub fn run_cli() {
                     contract_cert_owned.as_deref(),
                     use_vm,
                     use_jit,
+                    hot_jit,
                     verifier_timeout_ms,
                     warn_unverified,
                     verbose_invariants,
@@ -35195,22 +36019,46 @@ pub fn run_cli() {
                     z3_theory_snap,
                     no_cache,
                     type_strict,
+                    quiet,
                 );
-                match result {
-                    Ok(_) => {
-                        println!("Program executed successfully");
-                        true
+                profiler::finish();
+                if error_format::is_structured() {
+                    if let Err(e) = &result
+                        && exit_request().is_none()
+                    {
+                        error_format::report_failure(&filename_owned, e);
                     }
-                    Err(e) => {
-                        eprintln!("Error: {}", e);
-                        false
+                    error_format::finish();
+                }
+                match result {
+                    Ok(0) => {
+                        if !quiet {
+                            println!("Program executed successfully");
+                        }
+                        match take_soft_type_error() {
+                            Some(e) => watch_mode::CycleOutcome::RanWithTypeError(e),
+                            None => watch_mode::CycleOutcome::Ok,
+                        }
                     }
+                    Ok(code) => watch_mode::CycleOutcome::Exited(code),
+                    Err(e) => match exit_request() {
+                        Some(0) => watch_mode::CycleOutcome::Ok,
+                        Some(code) => watch_mode::CycleOutcome::Exited(code),
+                        None => {
+                            if !error_format::is_structured() {
+                                eprintln!("Error: {}", e);
+                            }
+                            watch_mode::CycleOutcome::Failed(e)
+                        }
+                    },
                 }
             });
             return;
         }
 
         if explicit_repl {
+            fuel::set_max_steps(max_steps);
+            memory_limit::set_max_memory(max_memory);
             let mut enhanced_repl = repl::EnhancedREPL::with_examples_dir(examples_dir);
             if let Err(e) = enhanced_repl.run() {
                 eprintln!("REPL error: {}", e);
@@ -35235,11 +36083,36 @@ pub fn run_cli() {
             // observe it without plumbing an extra parameter through
             // the pipeline.
             set_panic_on_fault(panic_on_fault_flag);
+            fuel::set_max_steps(max_steps);
+            memory_limit::set_max_memory(max_memory);
+            call_trace::set_trace(trace.clone());
+            profiler::set_profile(profile);
+            if let Some(addr) = &metrics_addr {
+                start_metrics(addr);
+            }
+            if let Some(options) = &debug {
+                let source = match &inline_source {
+                    Some(src) => src.clone(),
+                    None => fs::read_to_string(filename).unwrap_or_default(),
+                };
+                if let Err(e) = debug_console::start(filename, &source, options) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(2);
+                }
+            }
+            if step {
+                let source = match &inline_source {
+                    Some(src) => src.clone(),
+                    None => fs::read_to_string(filename).unwrap_or_default(),
+                };
+                debug_console::start_stepping(filename, &source);
+            }
             // Execute a file. RES-027: a failed run exits non-zero so
             // `run_examples.sh` / CI / ops tooling can distinguish
             // success from failure without parsing stdout.
             let run_result = execute_file(
                 filename,
+                inline_source.as_deref(),
                 type_check,
                 no_typecheck,
                 audit,
@@ -35249,6 +36122,7 @@ pub fn run_cli() {
                 emit_contract_cert.as_deref(),
                 use_vm,
                 use_jit,
+                hot_jit,
                 verifier_timeout_ms,
                 warn_unverified,
                 verbose_invariants,
@@ -35257,7 +36131,9 @@ pub fn run_cli() {
                 z3_theory,
                 no_cache,
                 type_strict,
+                quiet,
             );
+            profiler::finish();
             // RES-174: print cache stats on exit whenever the
             // flag is set, regardless of whether the run
             // succeeded. Stats only reflect JIT usage; `--vm` /
@@ -35287,12 +36163,32 @@ pub fn run_cli() {
                     e
                 );
             }
+            // A nonzero `main` result or an `exit(code)` becomes the
+            // process status as-is, without the success line. Other
+            // failures exit 1, 2 or 3; see `failure_exit_code`.
+            if error_format::is_structured() {
+                if let Err(e) = &run_result
+                    && exit_request().is_none()
+                {
+                    error_format::report_failure(filename, e);
+                }
+                error_format::finish();
+            }
             match run_result {
-                Ok(_) => {
-                    println!("Program executed successfully");
+                Ok(0) => {
+                    if !quiet {
+                        println!("Program executed successfully");
+                    }
                     return;
                 }
+                Ok(code) => exit_flushed(code),
                 Err(e) => {
+                    if let Some(code) = exit_request() {
+                        exit_flushed(code);
+                    }
+                    if error_format::is_structured() {
+                        std::process::exit(failure_exit_code(&e));
+                    }
                     // RES-212: when a `resilient.toml` manifest
                     // sits above the source file, prefix the error
                     // with `[<package-name>] ` so multi-project
@@ -35309,7 +36205,7 @@ pub fn run_cli() {
                     } else {
                         eprintln!("Error: {}", e);
                     }
-                    std::process::exit(1);
+                    std::process::exit(failure_exit_code(&e));
                 }
             }
         }
@@ -35372,6 +36268,8 @@ pub fn run_cli() {
     // Start the enhanced REPL if no file was provided. RES-026:
     // pass through --examples-dir so the `examples` command can list
     // real files instead of the hardcoded snippets.
+    fuel::set_max_steps(max_steps);
+    memory_limit::set_max_memory(max_memory);
     let mut enhanced_repl = repl::EnhancedREPL::with_examples_dir(examples_dir);
     if let Err(e) = enhanced_repl.run() {
         eprintln!("REPL error: {}", e);
@@ -35401,6 +36299,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_single_extern_c_declaration() {
+        let (program, errs) = crate::parse(
+            "extern \"C\" fn c_read_register(addr: int) -> int;\nlet x = c_read_register(4);",
+        );
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let crate::Node::Program(stmts) = &program else {
+            unreachable!()
+        };
+        assert_eq!(stmts.len(), 2);
+        match &stmts[0].node {
+            crate::Node::Extern { library, decls, .. } => {
+                assert_eq!(library, crate::ffi::C_ABI);
+                assert_eq!(decls.len(), 1);
+                assert_eq!(decls[0].c_name, "c_read_register");
+                assert_eq!(
+                    decls[0].parameters,
+                    vec![("int".to_string(), "addr".to_string())]
+                );
+                assert_eq!(decls[0].return_type, "int");
+            }
+            other => panic!("expected Node::Extern, got {:?}", other),
+        }
+    }
+
     /// RES-4185: runs `f` on a thread with a production-sized stack
     /// (matching `main.rs`'s 16 MiB `STACK_SIZE`), so these depth-guard
     /// tests reflect real CLI behavior rather than the default test
@@ -35545,6 +36468,81 @@ mod tests {
         });
     }
 
+    #[test]
+    fn parse_source_returns_program_or_located_diagnostics() {
+        let program = crate::parse_source("fn f(int x) { return x; }\nf(1);").unwrap();
+        assert!(matches!(&program, Node::Program(stmts) if stmts.len() == 2));
+
+        let errors = crate::parse_source("let x = 1;\nlet = 2;").unwrap_err();
+        let first = &errors[0];
+        assert_eq!(first.severity, diag::Severity::Error);
+        assert_eq!(first.code, Some(diag::codes::E0001));
+        assert_eq!(first.span.start.line, 2);
+        assert_eq!(
+            first.span.start.offset,
+            "let x = 1;\n".len() + first.span.start.column - 1
+        );
+    }
+
+    #[test]
+    fn parse_source_survives_hostile_input() {
+        for src in [
+            "",
+            "`~@#$",
+            "fn",
+            "fn f(",
+            "let s = \"\\u{ZZZZ}",
+            "let c = '';",
+            "0x; 1e; 1.e; 99999999999999999999999999;",
+            "match x { => }",
+            "struct { } enum ( impl < trait > where",
+            "/* unterminated",
+            "r#\"raw",
+            "actor A { receive",
+            "\u{feff}\u{0}\u{202e}",
+        ] {
+            // Errors or not, each input must come back as a value.
+            let _ = crate::parse_source(src);
+        }
+    }
+
+    #[test]
+    fn parse_panics_become_parse_errors() {
+        let err = crate::catch_parse_panic::<()>(|| panic!("boom")).unwrap_err();
+        assert_eq!(err, "internal parser error: boom");
+        let (program, errs) = crate::parse_panic_result(err, false);
+        assert!(matches!(&program, Node::Program(stmts) if stmts.is_empty()));
+        assert_eq!(errs, ["1:1: internal parser error: boom"]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn data_values_round_trip_through_serde() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(
+            MapKey::Int(7),
+            Value::Array(Shared::new(vec![
+                Value::Float(2.5),
+                Value::Bytes(vec![1, 2]),
+            ])),
+        );
+        let value = Value::Struct {
+            name: "Reading".to_string(),
+            fields: Rc::new(vec![
+                ("samples".to_string(), Value::Map(Shared::new(map))),
+                (
+                    "unit".to_string(),
+                    Value::Option(Some(Box::new(Value::Char('K')))),
+                ),
+            ]),
+        };
+        let json = serde_json::to_string(&value).unwrap();
+        let restored: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.to_string(), value.to_string());
+        // Handles and code don't leave the process.
+        assert!(serde_json::to_string(&Value::Cell(0)).is_err());
+    }
+
     #[test]
     fn apply_builtin_by_name_respects_std_feature() {
         use crate::cfg_attr::CfgConfig;
@@ -35813,6 +36811,7 @@ mod tests {
             last_token_line: 1,
             last_token_column: 1,
             last_token_offset: 0,
+            quiet: false,
             logos_tokens: None,
         };
         lex.read_char();
@@ -36062,6 +37061,7 @@ mod tests {
         let mut parser = Parser::new(lexer);
         let mut program = parser.parse_program();
         let mut errs = parser.errors;
+        crate::overloads::lower_program(&mut program);
         // RES-325: same post-parse named-arg lowering as the
         // production driver (`crate::parse`), so test-mode programs
         // see identical post-parse AST shape.
@@ -37859,6 +38859,161 @@ mod tests {
         }
     }
 
+    #[test]
+    fn short_name_array_builtins_run_and_check() {
+        let r = run_program(
+            "let xs = [1, 2, 3, 4];\n\
+             println(map(xs, fn(int x) { return x * 10; }));\n\
+             println(filter(xs, fn(int x) { return x % 2 == 0; }));\n\
+             println(reduce(xs, 0, fn(int acc, int x) { return acc + x; }));\n\
+             println(contains(xs, 3));\n\
+             println(index_of(xs, 4));\n\
+             println(index_of(xs, 9));\n\
+             println(contains(\"resilient\", \"lien\"));",
+        );
+        assert!(r.ok, "{:?}", r.errors);
+        assert_eq!(
+            r.stdout,
+            "[10, 20, 30, 40]\n[2, 4]\n10\ntrue\n3\n-1\ntrue\n"
+        );
+
+        let err = typecheck_src("let xs = [1, 2];\nlet ys = push(xs, \"a\");").unwrap_err();
+        assert!(err.contains("push: [int] holds int values"), "{}", err);
+        let err = typecheck_src("let xs = [1, 2];\nlet ys = filter(xs, fn(int x) { return x; });")
+            .unwrap_err();
+        assert!(err.contains("must return bool"), "{}", err);
+        // The result keeps the callback's return type.
+        let err = typecheck_src(
+            "let xs = [1, 2];\nlet ys: array<string> = map(xs, fn(int x) { return x + 1; });",
+        )
+        .unwrap_err();
+        assert!(err.contains("[int]"), "{}", err);
+        // A user fn named `map` is not the builtin.
+        assert!(
+            typecheck_src("fn map(int a, int b) -> int { return a + b; }\nlet n = map(1, 2);")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn sort_checks_element_and_comparator_types() {
+        assert!(typecheck_src("let xs = sort([\"b\", \"a\"]);").is_ok());
+        let err = typecheck_src("let xs = sort([true, false]);").unwrap_err();
+        assert!(err.contains("use sort_by"), "{}", err);
+        assert!(
+            typecheck_src(
+                "let xs = sort_by([1, 2], fn(int a, int b) { return a > b; });\n\
+                 let ys = sort_by([1, 2], fn(int a, int b) { return b - a; });"
+            )
+            .is_ok()
+        );
+        let err = typecheck_src("let xs = sort_by([1, 2], fn(int a, int b) { return 1.5; });")
+            .unwrap_err();
+        assert!(err.contains("must return int or bool"), "{}", err);
+        let err = typecheck_src("let xs = sort_by([1, 2], fn(string a, string b) { return 0; });")
+            .unwrap_err();
+        assert!(err.contains("takes string"), "{}", err);
+    }
+
+    #[test]
+    fn composite_values_compare_structurally_and_order_lexicographically() {
+        let result = run_program(
+            r#"
+            println([[1, 2, 3] == [1, 2, 3], [[1], [2]] != [[1], [3]]]);
+            println([(1, "a") == (1, "a"), Some([1]) == Some([1]), Ok(1) == Err(1)]);
+            println([[1, 2] < [1, 3], [1, 2] < [1, 2, 0], [2] > [1, 9], [1, 2] <= [1, 2]]);
+            println([(1, "b") > (1, "a"), None < Some(0), Some(2) >= Some(3)]);
+        "#,
+        );
+        assert!(result.ok, "{:?}", result.errors);
+        assert_eq!(
+            result.stdout,
+            "[true, true]\n[true, true, false]\n[true, true, true, true]\n[true, true, false]\n"
+        );
+
+        let err = |src: &str| {
+            let result = run_program(src);
+            assert!(!result.ok, "{src}");
+            result.errors.join("\n")
+        };
+        let e = err("println([1] == 1);");
+        assert!(
+            e.contains("cannot compare array with int using `==`"),
+            "{e}"
+        );
+        let e = err(r#"let m = {"a" -> 1}; println(m < m);"#);
+        assert!(
+            e.contains("cannot order map values with `<`: only `==` and `!=` apply"),
+            "{e}"
+        );
+        let e = err("struct P { int x, } let p = new P { x: 1 }; println(p < p);");
+        assert!(e.contains("cannot order struct P with `<`"), "{e}");
+        let e = err(r#"println([1, "a"] < [1, 2]);"#);
+        assert!(e.contains("their elements are not comparable"), "{e}");
+
+        assert!(typecheck_src("let b = [1, 2] < [1, 3];").is_ok());
+        let e = typecheck_src("let b = [1] == 1;").unwrap_err();
+        assert!(e.contains("Cannot compare [int] and int"), "{e}");
+        let e = typecheck_src("let b = Ok(1) < Ok(2);").unwrap_err();
+        assert!(e.contains("Cannot order Result values with '<'"), "{e}");
+    }
+
+    #[test]
+    fn collections_copy_on_assignment_and_clone_copies_cells() {
+        let result = run_program(
+            r#"
+            let a = [1, 2, 3];
+            let b = a;
+            b[0] = 9;
+            println(a);
+            println(b);
+            let m = {"k" -> [1]};
+            let n = m;
+            n["k"][0] = 5;
+            println(m["k"][0]);
+            println(n["k"][0]);
+            let c = cell(1);
+            let d = c;
+            d.set(2);
+            println(c.get());
+            let e = clone([c]);
+            e[0].set(3);
+            println(c.get());
+            println(clone(a) == a);
+        "#,
+        );
+        assert!(result.ok, "{:?}", result.errors);
+        assert_eq!(result.stdout, "[1, 2, 3]\n[9, 2, 3]\n1\n5\n2\n2\ntrue\n");
+    }
+
+    #[test]
+    fn failed_index_assignment_leaves_the_binding_intact() {
+        let mut interp = Interpreter::new();
+        let (p, errors) = parse("let xs = [[1, 2], [3]];");
+        assert!(errors.is_empty(), "{errors:?}");
+        interp.eval(&p).unwrap();
+        let (p, _) = parse("xs[1][4] = 0;");
+        let err = interp.eval(&p).unwrap_err();
+        assert!(err.contains("out of bounds"), "{err}");
+        assert_eq!(interp.env.get("xs").unwrap().to_string(), "[[1, 2], [3]]");
+    }
+
+    #[test]
+    fn live_retry_restores_cell_contents() {
+        let result = run_program(
+            r#"
+            let c = cell(0);
+            live {
+                c.set(c.get() + 1);
+                if live_retries() < 2 { assert(false, "again"); }
+            }
+            println(c.get());
+        "#,
+        );
+        assert!(result.ok, "{:?}", result.errors);
+        assert_eq!(result.stdout, "1\n");
+    }
+
     /// RES-1859: `array_map(arr, fn)` standalone builtin returns Array.
     #[test]
     fn res1859_array_map_standalone() {
@@ -38120,6 +39275,57 @@ mod tests {
         assert!(err.contains("out of bounds"), "got: {}", err);
     }
 
+    /// Indexing a named binding reads it in place; the result matches
+    /// the general path for computed, negative, and out-of-range
+    /// indices, and for string and map bindings.
+    #[test]
+    fn named_array_index_matches_general_path() {
+        let src = "\
+            fn main(int _d) -> int {\n\
+                let xs = [10, 20, 30];\n\
+                let i = 1;\n\
+                let s = \"abc\";\n\
+                let c = s[i];\n\
+                if c != 'b' { return -1; }\n\
+                let m = {\"k\" -> 5};\n\
+                return xs[i] + xs[i + 1] + xs[-3] + m[\"k\"];\n\
+            }\n\
+            main(0);\n\
+        ";
+        let (program, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let mut interp = Interpreter::new();
+        match interp.eval(&program).unwrap() {
+            Value::Int(n) => assert_eq!(n, 65),
+            other => panic!("expected Int(65), got {:?}", other),
+        }
+
+        let src = "\
+            fn main(int _d) -> int {\n\
+                let xs = [1, 2, 3];\n\
+                let i = 2;\n\
+                return xs[i + 1];\n\
+            }\n\
+            main(0);\n\
+        ";
+        let (program, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let mut interp = Interpreter::new();
+        let err = interp.eval(&program).unwrap_err();
+        assert!(
+            err.contains("Index 3 out of bounds for array of length 3"),
+            "got: {}",
+            err
+        );
+
+        // The target still evaluates before the index: an unbound
+        // name is reported ahead of a failing index.
+        let (program, errs) = parse("let i = 0;\nys[1 / i];\n");
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let err = Interpreter::new().eval(&program).unwrap_err();
+        assert!(err.contains("Identifier not found: ys"), "got: {}", err);
+    }
+
     /// RES-921: array slicing accepts negative endpoints — `arr[-2..]`
     /// is the last two; `arr[..-1]` drops the last; `arr[-3..-1]` is
     /// the inner two.
@@ -39680,6 +40886,149 @@ mod tests {
         assert!(err.contains("expected 1 argument"), "err was: {}", err);
     }
 
+    #[test]
+    fn args_returns_the_script_arguments_as_strings() {
+        set_script_args(vec!["75".to_string(), "http://host".to_string()]);
+        let got = builtin_args(&[]).unwrap();
+        set_script_args(Vec::new());
+        match got {
+            Value::Array(items) => {
+                assert_eq!(items.len(), 2);
+                assert!(matches!(&items[0], Value::String(s) if s == "75"));
+                assert!(matches!(&items[1], Value::String(s) if s == "http://host"));
+            }
+            other => panic!("expected Array, got {:?}", other),
+        }
+        assert!(builtin_args(&[Value::Int(1)]).is_err());
+    }
+
+    #[test]
+    fn exit_unwinds_with_its_code() {
+        clear_exit_request();
+        assert!(builtin_exit(&[Value::Int(3)]).is_err());
+        assert_eq!(exit_request(), Some(3));
+        clear_exit_request();
+        // Only `exit` itself records a request, whatever the text.
+        let r = run_program("assert(false, \"exit(0)\");");
+        assert!(!r.ok);
+        assert_eq!(exit_request(), None);
+        for code in [256, -1, i64::MAX] {
+            let err = builtin_exit(&[Value::Int(code)]).unwrap_err();
+            assert_eq!(builtin_failure_variant(&err), Some("InvalidArgument"));
+            assert_eq!(exit_request(), None);
+        }
+        assert!(builtin_exit(&[]).is_err());
+    }
+
+    #[test]
+    fn max_steps_ends_a_runaway_embedded_run() {
+        fuel::set_max_steps(Some(10_000));
+        let result = run_program("let n = 0; while true { n = n + 1; }");
+        fuel::set_max_steps(None);
+        assert!(!result.ok);
+        assert!(fuel::is_exhausted(&result.errors[0]), "{:?}", result.errors);
+        assert!(run_program("println(1);").ok);
+    }
+
+    #[test]
+    fn max_memory_stops_a_growing_binding_and_resets_per_run() {
+        memory_limit::set_max_memory(Some(64 * 1024));
+        let result = run_program(
+            "let s = \"\"; while true { s = s + \"0123456789abcdef0123456789abcdef\"; }",
+        );
+        assert!(!result.ok);
+        assert!(
+            result.errors[0].contains("OutOfMemory"),
+            "{:?}",
+            result.errors
+        );
+        // A new run starts from a fresh total.
+        let fits = run_program("let xs = [1, 2, 3]; println(len(xs));");
+        memory_limit::set_max_memory(None);
+        assert!(fits.ok, "{:?}", fits.errors);
+    }
+
+    #[test]
+    fn push_assign_appends_in_place_and_keeps_value_semantics() {
+        let result = run_program(
+            "let xs = [1];\nlet ys = xs;\nxs = push(xs, 2);\nprintln(xs);\nprintln(ys);\n",
+        );
+        assert!(result.ok, "{:?}", result.errors);
+        assert_eq!(result.stdout, "[1, 2]\n[1]\n");
+        // A user `push` is called, not the builtin.
+        let result = run_program(
+            "fn push(array a, int x) -> array { return [x]; }\nlet xs = [1, 2];\nxs = push(xs, 3);\nprintln(xs);\n",
+        );
+        assert_eq!(result.stdout, "[3]\n");
+    }
+
+    #[test]
+    fn collections_written_in_place_keep_an_exact_footprint() {
+        /// `value` with every collection rebuilt, so nothing is cached.
+        fn remeasured(value: &Value) -> Value {
+            match value {
+                Value::Array(items) => Value::Array(items.iter().map(remeasured).collect()),
+                Value::Map(map) => Value::Map(
+                    map.iter()
+                        .map(|(k, v)| (k.clone(), remeasured(v)))
+                        .collect(),
+                ),
+                other => other.clone(),
+            }
+        }
+        memory_limit::set_tracking(true);
+        let (program, errors) = parse_silent(
+            "let xs = [];\nlet i = 0;\nwhile i < 20 { xs = push(xs, [i, \"ab\"]); i = i + 1; }\n\
+             xs[3] = \"abcdef\";\nxs[4][1] = \"a longer string\";\n\
+             let m = {\"k\" -> [1, 2]};\nm[\"k\"][1] = \"xyz\";\nm[\"new\"] = [\"q\"];\n",
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+        let mut interp = Interpreter::new();
+        let result = interp.eval(&program);
+        memory_limit::set_tracking(false);
+        assert!(result.is_ok(), "{result:?}");
+        for name in ["xs", "m"] {
+            let value = interp.env.get(name).unwrap();
+            let known = match &value {
+                Value::Array(items) => items.known_footprint(),
+                Value::Map(map) => map.known_footprint(),
+                _ => None,
+            };
+            assert!(known.is_some(), "{name} was measured again");
+            assert_eq!(
+                memory_limit::heap_size(&value),
+                memory_limit::heap_size(&remeasured(&value)),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn tracking_memory_keeps_the_estimate_without_a_cap() {
+        memory_limit::set_tracking(true);
+        memory_limit::reset();
+        let (program, errors) = parse_silent("let s = \"0123456789abcdef\"; let keep = s + s;");
+        assert!(errors.is_empty(), "{errors:?}");
+        let mut interp = Interpreter::new();
+        let result = interp.eval(&program);
+        let used = memory_limit::in_use();
+        memory_limit::set_tracking(false);
+        assert!(result.is_ok(), "{result:?}");
+        assert!(used >= 48, "{used}");
+    }
+
+    #[test]
+    fn main_result_is_the_exit_code_only_for_a_trailing_int_main_call() {
+        let (program, errors) = parse("fn main(int d) -> int { return d; } main(7);");
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(main_exit_code(&program, &Value::Int(7)), Ok(7));
+        assert!(main_exit_code(&program, &Value::Int(256)).is_err());
+        let (program, _) = parse("fn main(int d) { return d; } main(7);");
+        assert_eq!(main_exit_code(&program, &Value::Int(7)), Ok(0));
+        let (program, _) = parse("fn f(int d) -> int { return d; } f(7);");
+        assert_eq!(main_exit_code(&program, &Value::Int(7)), Ok(0));
+    }
+
     // --- RES-150: seedable SplitMix64 random builtins ---
 
     /// Guard that serializes tests which assert on exact RNG
@@ -41513,7 +42862,7 @@ mod tests {
     fn format_interpolates_placeholders_in_order() {
         let v = builtin_format(&[
             Value::String("hello {}, you are {} years old".into()),
-            Value::Array(vec![Value::String("alice".into()), Value::Int(30)]),
+            Value::Array(vec![Value::String("alice".into()), Value::Int(30)].into()),
         ])
         .unwrap();
         assert_eq!(s145(v), "hello alice, you are 30 years old");
@@ -41525,7 +42874,7 @@ mod tests {
         // `{}` still consumes an arg.
         let v = builtin_format(&[
             Value::String("{{ literal }} then {}".into()),
-            Value::Array(vec![Value::Int(7)]),
+            Value::Array(vec![Value::Int(7)].into()),
         ])
         .unwrap();
         assert_eq!(s145(v), "{ literal } then 7");
@@ -41535,7 +42884,7 @@ mod tests {
     fn format_errors_on_too_few_args() {
         let err = builtin_format(&[
             Value::String("a={} b={}".into()),
-            Value::Array(vec![Value::Int(1)]),
+            Value::Array(vec![Value::Int(1)].into()),
         ])
         .unwrap_err();
         assert!(err.contains("not enough arguments"), "err was: {}", err);
@@ -41545,7 +42894,7 @@ mod tests {
     fn format_errors_on_too_many_args() {
         let err = builtin_format(&[
             Value::String("a={}".into()),
-            Value::Array(vec![Value::Int(1), Value::Int(2)]),
+            Value::Array(vec![Value::Int(1), Value::Int(2)].into()),
         ])
         .unwrap_err();
         assert!(err.contains("too many arguments"), "err was: {}", err);
@@ -41553,21 +42902,52 @@ mod tests {
 
     #[test]
     fn format_errors_on_unmatched_close_brace() {
-        let err = builtin_format(&[Value::String("close }here".into()), Value::Array(vec![])])
-            .unwrap_err();
+        let err = builtin_format(&[
+            Value::String("close }here".into()),
+            Value::Array(vec![].into()),
+        ])
+        .unwrap_err();
         assert!(err.contains("unmatched `}`"), "err was: {}", err);
     }
 
     #[test]
-    fn format_rejects_non_array_second_arg() {
-        let err = builtin_format(&[Value::String("{}".into()), Value::Int(42)]).unwrap_err();
-        assert!(err.contains("expected (string, array)"), "err was: {}", err);
+    fn format_takes_values_as_separate_arguments() {
+        let out = builtin_format(&[
+            Value::String("{} read {:.2}".into()),
+            Value::Int(42),
+            Value::Float(1.5),
+        ])
+        .unwrap();
+        assert!(matches!(out, Value::String(s) if s == "42 read 1.50"));
+        let err = builtin_format(&[Value::Int(42)]).unwrap_err();
+        assert!(
+            err.contains("expected a string template"),
+            "err was: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn format_named_arguments_resolve_before_the_run() {
+        let r = run_program(
+            "let id = 7;\nprintln(format(\"sensor {} read {v:.2}, {v}\", id, v: 2.345));",
+        );
+        assert!(r.ok, "{:?}", r.errors);
+        assert_eq!(r.stdout, "sensor 7 read 2.35, 2.345\n");
+    }
+
+    #[test]
+    fn format_named_placeholder_in_a_runtime_template_errors() {
+        let t = "{v}".to_string();
+        let err = builtin_format(&[Value::String(t), Value::Int(1)]).unwrap_err();
+        assert!(err.contains("`v: value` argument"), "err was: {}", err);
     }
 
     // --- RES-404: format-specifier subset ---
 
     fn fmt(template: &str, args: Vec<Value>) -> String {
-        match builtin_format(&[Value::String(template.into()), Value::Array(args)]).unwrap() {
+        match builtin_format(&[Value::String(template.into()), Value::Array(args.into())]).unwrap()
+        {
             Value::String(s) => s,
             other => panic!("expected Value::String, got {:?}", other),
         }
@@ -41649,7 +43029,7 @@ mod tests {
     fn format_radix_on_string_errors() {
         let err = builtin_format(&[
             Value::String("{:x}".into()),
-            Value::Array(vec![Value::String("abc".into())]),
+            Value::Array(vec![Value::String("abc".into())].into()),
         ])
         .unwrap_err();
         assert!(
@@ -41663,7 +43043,7 @@ mod tests {
     fn format_radix_on_float_errors() {
         let err = builtin_format(&[
             Value::String("{:b}".into()),
-            Value::Array(vec![Value::Float(1.5)]),
+            Value::Array(vec![Value::Float(1.5)].into()),
         ])
         .unwrap_err();
         assert!(
@@ -41677,7 +43057,7 @@ mod tests {
     fn format_unrecognised_spec_errors() {
         let err = builtin_format(&[
             Value::String("{:?}".into()),
-            Value::Array(vec![Value::Int(1)]),
+            Value::Array(vec![Value::Int(1)].into()),
         ])
         .unwrap_err();
         assert!(err.contains("unrecognised format spec"), "err was: {}", err);
@@ -41687,7 +43067,7 @@ mod tests {
     fn format_dot_without_digits_errors() {
         let err = builtin_format(&[
             Value::String("{:.}".into()),
-            Value::Array(vec![Value::Int(1)]),
+            Value::Array(vec![Value::Int(1)].into()),
         ])
         .unwrap_err();
         assert!(
@@ -41701,7 +43081,7 @@ mod tests {
     fn format_positional_index_unsupported() {
         let err = builtin_format(&[
             Value::String("{0}".into()),
-            Value::Array(vec![Value::Int(1)]),
+            Value::Array(vec![Value::Int(1)].into()),
         ])
         .unwrap_err();
         assert!(
@@ -41726,8 +43106,11 @@ mod tests {
     #[test]
     fn format_precision_overflow_is_error_not_panic() {
         let huge = format!("{{:.{}}}", "9".repeat(30));
-        let err = builtin_format(&[Value::String(huge), Value::Array(vec![Value::Float(1.0)])])
-            .unwrap_err();
+        let err = builtin_format(&[
+            Value::String(huge),
+            Value::Array(vec![Value::Float(1.0)].into()),
+        ])
+        .unwrap_err();
         assert!(
             err.contains("invalid precision"),
             "expected precision parse error, got: {}",
@@ -41738,8 +43121,11 @@ mod tests {
     #[test]
     fn format_width_overflow_is_error_not_panic() {
         let huge = format!("{{:{}}}", "9".repeat(30));
-        let err =
-            builtin_format(&[Value::String(huge), Value::Array(vec![Value::Int(1)])]).unwrap_err();
+        let err = builtin_format(&[
+            Value::String(huge),
+            Value::Array(vec![Value::Int(1)].into()),
+        ])
+        .unwrap_err();
         assert!(
             err.contains("invalid width"),
             "expected width parse error, got: {}",
@@ -41751,7 +43137,7 @@ mod tests {
     fn format_width_exceeds_cap_is_error() {
         let err = builtin_format(&[
             Value::String("{:99999}".into()),
-            Value::Array(vec![Value::Int(1)]),
+            Value::Array(vec![Value::Int(1)].into()),
         ])
         .unwrap_err();
         assert!(
@@ -41765,7 +43151,7 @@ mod tests {
     fn format_precision_exceeds_cap_is_error() {
         let err = builtin_format(&[
             Value::String("{:.99999}".into()),
-            Value::Array(vec![Value::Float(1.0)]),
+            Value::Array(vec![Value::Float(1.0)].into()),
         ])
         .unwrap_err();
         assert!(
@@ -41842,6 +43228,35 @@ mod tests {
         assert_eq!(as_string(v), "no-newline");
     }
 
+    #[test]
+    fn do_read_line_fails_with_end_of_input_at_eof() {
+        let mut r = std::io::Cursor::new(b"last\r\n" as &[u8]);
+        assert_eq!(do_read_line(&mut r, "read_line").unwrap(), "last");
+        let err = do_read_line(&mut r, "read_line").unwrap_err();
+        assert_eq!(builtin_failure_variant(&err), Some("EndOfInput"), "{}", err);
+    }
+
+    #[test]
+    fn do_read_int_parses_trimmed_lines_and_types_failures() {
+        let mut r = std::io::Cursor::new(b" -42 \nforty\n" as &[u8]);
+        assert!(matches!(do_read_int(&mut r).unwrap(), Value::Int(-42)));
+        let err = do_read_int(&mut r).unwrap_err();
+        assert_eq!(
+            builtin_failure_variant(&err),
+            Some("InvalidInput"),
+            "{}",
+            err
+        );
+        let err = do_read_int(&mut r).unwrap_err();
+        assert_eq!(builtin_failure_variant(&err), Some("EndOfInput"), "{}", err);
+    }
+
+    #[test]
+    fn read_line_and_read_int_take_no_arguments() {
+        assert!(builtin_read_line(&[Value::Int(1)]).is_err());
+        assert!(builtin_read_int(&[Value::String("x".into())]).is_err());
+    }
+
     #[test]
     fn builtin_input_rejects_non_string_prompt() {
         let err = builtin_input(&[Value::Int(42)]).unwrap_err();
@@ -43144,7 +44559,7 @@ mod tests {
         // Each builtin should surface a clear arity error rather
         // than silently accept the wrong number of arguments.
         let too_many = builtin_hashmap_get(&[
-            Value::Map(std::collections::HashMap::new()),
+            Value::Map(std::collections::HashMap::new().into()),
             Value::Int(1),
             Value::Int(2),
         ])
@@ -43152,7 +44567,8 @@ mod tests {
         assert!(too_many.contains("expected 2 arguments"));
 
         let too_few =
-            builtin_hashmap_insert(&[Value::Map(std::collections::HashMap::new())]).unwrap_err();
+            builtin_hashmap_insert(&[Value::Map(std::collections::HashMap::new().into())])
+                .unwrap_err();
         assert!(too_few.contains("expected 3 arguments"));
 
         let too_few_keys = builtin_hashmap_keys(&[]).unwrap_err();
@@ -43653,22 +45069,20 @@ mod tests {
     }
 
     #[test]
-    fn lexer_int_overflow_produces_zero_with_diagnostic() {
+    fn lexer_int_overflow_produces_error_token() {
         let toks = tokenize("99999999999999999999");
-        assert!(
-            matches!(toks[0], Token::IntLiteral(0)),
-            "overflowed int literal should produce IntLiteral(0), got {:?}",
-            toks[0]
+        assert_eq!(
+            toks[0],
+            Token::Error(LexError::IntOverflow("99999999999999999999".into()))
         );
     }
 
     #[test]
-    fn lexer_hex_overflow_produces_zero_with_diagnostic() {
+    fn lexer_hex_overflow_produces_error_token() {
         let toks = tokenize("0xFFFFFFFFFFFFFFFFFF");
-        assert!(
-            matches!(toks[0], Token::IntLiteral(0)),
-            "overflowed hex literal should produce IntLiteral(0), got {:?}",
-            toks[0]
+        assert_eq!(
+            toks[0],
+            Token::Error(LexError::IntOverflow("0xFFFFFFFFFFFFFFFFFF".into()))
         );
     }
 
@@ -43685,10 +45099,9 @@ mod tests {
     #[test]
     fn lexer_i64_max_plus_one_overflows() {
         let toks = tokenize("9223372036854775808");
-        assert!(
-            matches!(toks[0], Token::IntLiteral(0)),
-            "i64::MAX+1 should overflow to IntLiteral(0), got {:?}",
-            toks[0]
+        assert_eq!(
+            toks[0],
+            Token::Error(LexError::IntOverflow("9223372036854775808".into()))
         );
     }
 
@@ -44003,6 +45416,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn function_value_shares_its_body_with_the_ast() {
+        let (program, _e) = parse("fn f(int x) { return x; }");
+        let Node::Program(stmts) = &program else {
+            panic!("expected Program");
+        };
+        let Node::Function { body, .. } = &stmts[0].node else {
+            panic!("expected Function");
+        };
+        let mut interp = Interpreter::new();
+        interp.eval(&program).unwrap();
+        match interp.env.get("f").unwrap() {
+            Value::Function(fv) => assert!(Arc::ptr_eq(&fv.body, body)),
+            other => panic!("expected Function, got {:?}", other),
+        }
+    }
+
     // ---------- Const let-binding tracking (RES-063) ----------
 
     #[test]
@@ -44552,9 +45982,9 @@ mod tests {
 
     #[test]
     fn res157a_non_integer_length_errors_cleanly() {
-        // `[Int; x]` — non-literal length is not yet supported
-        // (const-generics are deferred per the ticket notes).
-        let (_p, errors) = parse("let a: [Int; x] = [1, 2, 3];");
+        // `[Int; 1.5]` — the length must be an integer literal or a
+        // const name (resolved later by `const_eval::check`).
+        let (_p, errors) = parse("let a: [Int; 1.5] = [1, 2, 3];");
         assert!(
             errors
                 .iter()
@@ -44777,6 +46207,212 @@ mod tests {
         assert!(matches!(interp.env.get("n").unwrap(), Value::Int(2)));
     }
 
+    #[test]
+    fn named_fn_mutates_top_level_counter() {
+        // A top-level fn's body runs in a child of the defining scope,
+        // not a copy of it, so assignments to an outer name land in
+        // the binding the caller sees.
+        let src = r#"
+            let count = 0;
+            fn bump() { count = count + 1; }
+            bump();
+            bump();
+            bump();
+        "#;
+        let (p, errors) = parse(src);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let mut interp = Interpreter::new();
+        interp.eval(&p).unwrap();
+        assert!(matches!(interp.env.get("count").unwrap(), Value::Int(3)));
+    }
+
+    #[test]
+    fn top_level_toggle_flips_across_calls() {
+        // The toggle pattern from examples/self_healing.rz, with the
+        // flag at top level instead of a `static let`.
+        let src = r#"
+            let toggle = false;
+            fn flip() {
+                toggle = !toggle;
+                return toggle;
+            }
+            let a = flip();
+            let b = flip();
+            let c = flip();
+        "#;
+        let (p, errors) = parse(src);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let mut interp = Interpreter::new();
+        interp.eval(&p).unwrap();
+        assert!(matches!(interp.env.get("a").unwrap(), Value::Bool(true)));
+        assert!(matches!(interp.env.get("b").unwrap(), Value::Bool(false)));
+        assert!(matches!(interp.env.get("c").unwrap(), Value::Bool(true)));
+    }
+
+    #[test]
+    fn nested_fn_and_closure_writes_reach_the_defining_scope() {
+        // Writes go through every enclosing frame up to the one that
+        // owns the name; a local `let` of the same name stays local.
+        let src = r#"
+            let g = 0;
+            fn outer() {
+                fn inner() { g = g + 1; }
+                inner();
+                inner();
+            }
+            outer();
+            let add = fn() { g = g + 100; };
+            add();
+            fn shadow() {
+                let g = 5;
+                g = g + 1;
+                return g;
+            }
+            let local = shadow();
+        "#;
+        let (p, errors) = parse(src);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let mut interp = Interpreter::new();
+        interp.eval(&p).unwrap();
+        assert!(matches!(interp.env.get("g").unwrap(), Value::Int(102)));
+        assert!(matches!(interp.env.get("local").unwrap(), Value::Int(6)));
+    }
+
+    #[test]
+    fn self_tail_calls_run_past_the_call_depth_limit() {
+        // Far deeper than `max_interpreter_call_depth`: each
+        // `return count(...)` restarts the body instead of nesting.
+        let src = r#"
+            fn count(int n, int acc) -> int {
+                if n == 0 {
+                    return acc;
+                }
+                return count(n - 1, acc + 1);
+            }
+            count(100000, 0);
+        "#;
+        assert_eq!(eval_to_int(src), 100000);
+    }
+
+    #[test]
+    fn self_tail_calls_recheck_requires_each_iteration() {
+        let src = r#"
+            fn down(int n) -> int requires n >= 0 {
+                if n == 0 {
+                    return 0;
+                }
+                return down(n - 2);
+            }
+            down(5);
+        "#;
+        let (p, errors) = parse(src);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let err = Interpreter::new().eval(&p).unwrap_err();
+        assert!(err.contains("requires"), "got: {}", err);
+    }
+
+    #[test]
+    fn must_tail_and_general_tail_calls_mix_in_one_chain() {
+        // `f` hands off to `g`, which tail-calls back into `f` or on
+        // to another `#[must_tail_call]` fn; each switch must run the
+        // callee's own body with its own parameters.
+        let src = r#"
+            #[must_tail_call]
+            fn f(int n, int acc) -> int {
+                if n <= 0 {
+                    return acc;
+                }
+                return g(n, acc);
+            }
+            fn g(int m, int total) -> int {
+                if m % 2 == 0 {
+                    return f(m - 1, total + m);
+                }
+                return h(m, 3, total);
+            }
+            #[must_tail_call]
+            fn h(int k, int spin, int s) -> int {
+                if spin > 0 {
+                    return h(k, spin - 1, s);
+                } else {
+                    return f(k - 1, s + k);
+                }
+            }
+            f(5, 0) * 1000000000 + f(100000, 0);
+        "#;
+        assert_eq!(eval_to_int(src), 15 * 1000000000 + 5000050000);
+    }
+
+    #[test]
+    fn mutual_tail_calls_run_past_the_call_depth_limit() {
+        // No `#[mutual_tail_call]` needed: any `return g(args)` switches
+        // the frame to `g`.
+        let src = r#"
+            fn is_even(int n) -> bool {
+                if n == 0 {
+                    return true;
+                }
+                return is_odd(n - 1);
+            }
+            fn is_odd(int n) -> bool {
+                if n == 0 {
+                    return false;
+                }
+                return is_even(n - 1);
+            }
+            let even = is_even(100000);
+            let odd = is_odd(100001);
+        "#;
+        let (p, errors) = parse(src);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let mut interp = Interpreter::new();
+        interp.eval(&p).unwrap();
+        assert!(matches!(interp.env.get("even"), Some(Value::Bool(true))));
+        assert!(matches!(interp.env.get("odd"), Some(Value::Bool(true))));
+    }
+
+    #[test]
+    fn tail_call_cycles_keep_a_bounded_stack_trace() {
+        let src = r#"
+            fn ping(int n) -> int {
+                if n == 0 {
+                    return 1 / n;
+                }
+                return pong(n - 1);
+            }
+            fn pong(int n) -> int {
+                return ping(n);
+            }
+            fn start() -> int {
+                return ping(1000) + 0;
+            }
+            start();
+        "#;
+        let (p, errors) = parse(src);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let err = Interpreter::new().eval(&p).unwrap_err();
+        assert_eq!(err.matches("at ping (").count(), 1, "got: {}", err);
+        assert_eq!(err.matches("at start (").count(), 1, "got: {}", err);
+    }
+
+    #[test]
+    fn tail_call_to_another_fn_checks_its_requires() {
+        let src = r#"
+            fn half(int n) -> int requires n % 2 == 0 {
+                return n / 2;
+            }
+            fn halve_after(int n) -> int {
+                return half(n + 1);
+            }
+            halve_after(4);
+        "#;
+        let (p, errors) = parse(src);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let err = Interpreter::new().eval(&p).unwrap_err();
+        assert!(err.contains("half"), "got: {}", err);
+        assert!(err.contains("requires"), "got: {}", err);
+    }
+
     #[test]
     fn distinct_cells_are_independent() {
         // Each `cell(...)` call creates a fresh `Rc<RefCell<_>>`.
@@ -45136,6 +46772,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_int_reads_literal_spellings_and_names_the_failure() {
+        let ok = |src: &str| match builtin_parse_int(&[Value::String(src.into())]).unwrap() {
+            Value::Result {
+                ok: true, payload, ..
+            } => match *payload {
+                Value::Int(n) => n,
+                other => panic!("expected Int for {:?}, got {:?}", src, other),
+            },
+            other => panic!("expected Ok for {:?}, got {:?}", src, other),
+        };
+        assert_eq!(ok("+17"), 17);
+        assert_eq!(ok("1_000_000"), 1_000_000);
+        assert_eq!(ok("0x1F"), 31);
+        assert_eq!(ok("-0b101"), -5);
+        assert_eq!(ok("0o17"), 15);
+        assert_eq!(ok("-9223372036854775808"), i64::MIN);
+        for (src, why) in [
+            ("", "is empty"),
+            ("12a", "is not an integer"),
+            ("1__0", "is not an integer"),
+            ("_1", "is not an integer"),
+            ("0x", "is not an integer"),
+            ("9223372036854775808", "is out of range for int"),
+        ] {
+            assert_result_err_contains(
+                builtin_parse_int(&[Value::String(src.into())]).unwrap(),
+                why,
+            );
+        }
+        let ok = |src: &str| match builtin_parse_float(&[Value::String(src.into())]).unwrap() {
+            Value::Result {
+                ok: true, payload, ..
+            } => payload,
+            other => panic!("expected Ok for {:?}, got {:?}", src, other),
+        };
+        assert!(matches!(*ok("1_000.5"), Value::Float(f) if f == 1000.5));
+        assert_result_err_contains(
+            builtin_parse_float(&[Value::String("1_.5".into())]).unwrap(),
+            "is not a number",
+        );
+    }
+
     #[test]
     fn parse_int_rejects_non_string_arg() {
         let err = builtin_parse_int(&[Value::Int(1)]).unwrap_err();
@@ -47290,6 +48969,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn recursion_limit_is_caught_by_try_catch() {
+        let handle = std::thread::Builder::new()
+            .stack_size(16 * 1024 * 1024)
+            .spawn(|| {
+                run_program(
+                    r#"
+                    fn count(int n) {
+                        if n <= 0 { return 0; }
+                        return 1 + count(n - 1);
+                    }
+                    try {
+                        println(count(1000));
+                    } catch RecursionLimit {
+                        println("too deep");
+                    }
+                    println(count(3));
+                "#,
+                )
+            })
+            .expect("spawn worker thread");
+        let result = handle.join().expect("recursion must not crash the host");
+        assert!(result.ok, "{:?}", result.errors);
+        assert_eq!(result.stdout, "too deep\n3\n");
+    }
+
+    #[test]
+    fn stack_budget_stops_deep_expressions_with_recursion_limit() {
+        // No calls at all, so the call-depth limit never fires: only
+        // the host stack budget stands between this and an overflow.
+        // 1 MiB leaves room for the few frames above the `try` in a
+        // debug build, where one `eval` frame is close to 100 KiB.
+        let handle = std::thread::Builder::new()
+            .stack_size(16 * 1024 * 1024)
+            .spawn(|| {
+                stack_guard::set_stack_budget(Some(1024 * 1024));
+                let expr = format!("{}1{}", "(1 + ".repeat(150), ")".repeat(150));
+                let caught = run_program(&format!(
+                    "try {{ println({expr}); }} catch RecursionLimit {{ println(\"too deep\"); }}"
+                ));
+                let uncaught = run_program(&format!("println({expr});"));
+                stack_guard::set_stack_budget(None);
+                let unguarded = run_program(&format!("println({expr});"));
+                (caught, uncaught, unguarded)
+            })
+            .expect("spawn worker thread");
+        let (caught, uncaught, unguarded) = handle.join().expect("must not crash the host");
+        assert_eq!(caught.stdout, "too deep\n", "{:?}", caught.errors);
+        assert!(!uncaught.ok);
+        assert!(
+            uncaught.errors[0].contains("RecursionLimit: evaluation nested too deep"),
+            "{:?}",
+            uncaught.errors
+        );
+        assert_eq!(unguarded.stdout, "151\n", "{:?}", unguarded.errors);
+    }
+
     #[test]
     fn forward_reference_between_functions() {
         // RES-018: caller is defined before callee, which only works if
@@ -47744,6 +49480,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn sqrt_of_negative_is_a_domain_error() {
+        let err = builtin_sqrt(&[Value::Float(-1.0)]).unwrap_err();
+        assert!(err.starts_with("sqrt: DomainError: "), "got: {}", err);
+        assert!(builtin_sqrt(&[Value::Int(-9)]).is_err());
+        // -0.0 is not negative for the domain check.
+        assert!(matches!(
+            builtin_sqrt(&[Value::Float(-0.0)]),
+            Ok(Value::Float(f)) if f == 0.0
+        ));
+    }
+
     #[test]
     fn abs_min_max_remain_type_preserving() {
         // Sanity check that our changes didn't regress the builtins
@@ -47925,7 +49673,7 @@ mod tests {
         assert!(err.contains("is_nan:"), "got: {}", err);
         let err = builtin_is_inf(&[Value::Bool(true)]).unwrap_err();
         assert!(err.contains("is_inf:"), "got: {}", err);
-        let err = builtin_is_finite(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_is_finite(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("is_finite:"), "got: {}", err);
     }
 
@@ -47989,11 +49737,9 @@ mod tests {
 
     #[test]
     fn array_reverse_reverses_elements() {
-        match builtin_array_reverse(&[Value::Array(vec![
-            Value::Int(1),
-            Value::Int(2),
-            Value::Int(3),
-        ])])
+        match builtin_array_reverse(&[Value::Array(
+            vec![Value::Int(1), Value::Int(2), Value::Int(3)].into(),
+        )])
         .unwrap()
         {
             Value::Array(items) => {
@@ -48009,11 +49755,11 @@ mod tests {
 
     #[test]
     fn array_reverse_handles_empty_and_single() {
-        match builtin_array_reverse(&[Value::Array(vec![])]).unwrap() {
+        match builtin_array_reverse(&[Value::Array(vec![].into())]).unwrap() {
             Value::Array(items) => assert!(items.is_empty(), "expected []"),
             other => panic!("expected Array, got {:?}", other),
         }
-        match builtin_array_reverse(&[Value::Array(vec![Value::Int(7)])]).unwrap() {
+        match builtin_array_reverse(&[Value::Array(vec![Value::Int(7)].into())]).unwrap() {
             Value::Array(items) => match items.as_slice() {
                 [Value::Int(7)] => {}
                 _ => panic!("expected [7], got {:?}", items),
@@ -48351,7 +50097,7 @@ mod tests {
     #[test]
     fn gcd_lcm_array_reject_non_int_and_arity() {
         assert!(
-            builtin_gcd_array(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)])])
+            builtin_gcd_array(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -48388,8 +50134,10 @@ mod tests {
 
     #[test]
     fn gcd_array_i64_min_overflow_errors() {
-        let err = builtin_gcd_array(&[Value::Array(vec![Value::Int(i64::MIN), Value::Int(0)])])
-            .unwrap_err();
+        let err = builtin_gcd_array(&[Value::Array(
+            vec![Value::Int(i64::MIN), Value::Int(0)].into(),
+        )])
+        .unwrap_err();
         assert!(
             err.contains("overflows"),
             "expected overflow error, got: {err}"
@@ -48398,8 +50146,10 @@ mod tests {
 
     #[test]
     fn lcm_array_overflow_errors() {
-        let err = builtin_lcm_array(&[Value::Array(vec![Value::Int(i64::MAX), Value::Int(2)])])
-            .unwrap_err();
+        let err = builtin_lcm_array(&[Value::Array(
+            vec![Value::Int(i64::MAX), Value::Int(2)].into(),
+        )])
+        .unwrap_err();
         assert!(
             err.contains("overflows"),
             "expected overflow error, got: {err}"
@@ -48744,7 +50494,7 @@ mod tests {
     #[test]
     fn array_sum_empty_is_zero() {
         assert_int(
-            builtin_array_sum(&[Value::Array(vec![])]).unwrap(),
+            builtin_array_sum(&[Value::Array(vec![].into())]).unwrap(),
             0,
             "sum []",
         );
@@ -48752,8 +50502,8 @@ mod tests {
 
     #[test]
     fn array_sum_rejects_non_int_element() {
-        let err =
-            builtin_array_sum(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)])]).unwrap_err();
+        let err = builtin_array_sum(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)].into())])
+            .unwrap_err();
         assert!(err.contains("all int elements"), "got: {}", err);
     }
 
@@ -48780,7 +50530,7 @@ mod tests {
     #[test]
     fn array_product_empty_is_one() {
         assert_int(
-            builtin_array_product(&[Value::Array(vec![])]).unwrap(),
+            builtin_array_product(&[Value::Array(vec![].into())]).unwrap(),
             1,
             "product []",
         );
@@ -48789,7 +50539,7 @@ mod tests {
     #[test]
     fn array_product_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_product(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_product(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -48823,14 +50573,14 @@ mod tests {
 
     #[test]
     fn array_min_empty_errors() {
-        let err = builtin_array_min(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_array_min(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("empty array"), "got: {}", err);
     }
 
     #[test]
     fn array_min_rejects_non_int() {
-        let err =
-            builtin_array_min(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)])]).unwrap_err();
+        let err = builtin_array_min(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)].into())])
+            .unwrap_err();
         assert!(err.contains("all int elements"), "got: {}", err);
     }
 
@@ -48869,14 +50619,14 @@ mod tests {
 
     #[test]
     fn array_max_empty_errors() {
-        let err = builtin_array_max(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_array_max(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("empty array"), "got: {}", err);
     }
 
     #[test]
     fn array_max_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_max(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_max(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -48954,14 +50704,14 @@ mod tests {
     fn array_min_max_or_reject_non_int_and_arity() {
         assert!(
             builtin_array_max_or(&[
-                Value::Array(vec![Value::String("a".into())]),
+                Value::Array(vec![Value::String("a".into())].into()),
                 Value::Int(-1)
             ])
             .unwrap_err()
             .contains("all int elements")
         );
         assert!(
-            builtin_array_min_or(&[Value::Array(vec![])])
+            builtin_array_min_or(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -49007,15 +50757,16 @@ mod tests {
 
     #[test]
     fn array_mean_int_empty_errors() {
-        let err = builtin_array_mean_int(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_array_mean_int(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("empty array"), "got: {}", err);
     }
 
     #[test]
     fn array_mean_int_rejects_non_int_elements() {
-        let err =
-            builtin_array_mean_int(&[Value::Array(vec![Value::Int(1), Value::String("x".into())])])
-                .unwrap_err();
+        let err = builtin_array_mean_int(&[Value::Array(
+            vec![Value::Int(1), Value::String("x".into())].into(),
+        )])
+        .unwrap_err();
         assert!(err.contains("all int elements"), "got: {}", err);
     }
 
@@ -49073,7 +50824,7 @@ mod tests {
 
     #[test]
     fn array_median_int_does_not_mutate_input() {
-        let arg = Value::Array(vec![Value::Int(3), Value::Int(1), Value::Int(2)]);
+        let arg = Value::Array(vec![Value::Int(3), Value::Int(1), Value::Int(2)].into());
         let _ = builtin_array_median_int(std::slice::from_ref(&arg)).unwrap();
         // Re-extract and confirm element order unchanged.
         match arg {
@@ -49093,14 +50844,14 @@ mod tests {
 
     #[test]
     fn array_median_int_empty_errors() {
-        let err = builtin_array_median_int(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_array_median_int(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("empty array"), "got: {}", err);
     }
 
     #[test]
     fn array_median_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_median_int(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_median_int(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -49155,12 +50906,8 @@ mod tests {
 
     #[test]
     fn array_mode_int_does_not_mutate_input() {
-        let arg = Value::Array(vec![
-            Value::Int(3),
-            Value::Int(1),
-            Value::Int(3),
-            Value::Int(2),
-        ]);
+        let arg =
+            Value::Array(vec![Value::Int(3), Value::Int(1), Value::Int(3), Value::Int(2)].into());
         let _ = builtin_array_mode_int(std::slice::from_ref(&arg)).unwrap();
         match arg {
             Value::Array(after) => {
@@ -49179,14 +50926,14 @@ mod tests {
 
     #[test]
     fn array_mode_int_empty_errors() {
-        let err = builtin_array_mode_int(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_array_mode_int(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("empty array"), "got: {}", err);
     }
 
     #[test]
     fn array_mode_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_mode_int(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_mode_int(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -49238,14 +50985,14 @@ mod tests {
 
     #[test]
     fn array_range_int_empty_errors() {
-        let err = builtin_array_range_int(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_array_range_int(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("empty array"), "got: {}", err);
     }
 
     #[test]
     fn array_range_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_range_int(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_range_int(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -49307,10 +51054,9 @@ mod tests {
     #[test]
     fn array_diff_consec_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_diff_consec_int(&[Value::Array(vec![
-                Value::Int(1),
-                Value::String("a".into())
-            ])])
+            builtin_array_diff_consec_int(&[Value::Array(
+                vec![Value::Int(1), Value::String("a".into())].into()
+            )])
             .unwrap_err()
             .contains("all int elements")
         );
@@ -49381,7 +51127,7 @@ mod tests {
     fn array_clamp_int_rejects_non_int_and_arity() {
         assert!(
             builtin_array_clamp_int(&[
-                Value::Array(vec![Value::String("a".into())]),
+                Value::Array(vec![Value::String("a".into())].into()),
                 Value::Int(0),
                 Value::Int(10)
             ])
@@ -49439,7 +51185,7 @@ mod tests {
     #[test]
     fn array_signum_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_signum_int(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_signum_int(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -49502,7 +51248,7 @@ mod tests {
     #[test]
     fn array_abs_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_abs_int(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_abs_int(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -49565,7 +51311,7 @@ mod tests {
     #[test]
     fn array_dot_int_rejects_non_int_and_arity() {
         let err = builtin_array_dot_int(&[
-            Value::Array(vec![Value::Int(1), Value::String("x".into())]),
+            Value::Array(vec![Value::Int(1), Value::String("x".into())].into()),
             int_array(&[1, 2]),
         ])
         .unwrap_err();
@@ -49635,7 +51381,7 @@ mod tests {
     #[test]
     fn array_sum_squares_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_sum_squares_int(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_sum_squares_int(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -49704,7 +51450,7 @@ mod tests {
     #[test]
     fn array_cumsum_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_cumsum_int(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_cumsum_int(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -49777,7 +51523,7 @@ mod tests {
     #[test]
     fn array_cummax_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_cummax_int(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_cummax_int(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -49849,7 +51595,7 @@ mod tests {
     #[test]
     fn array_cummin_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_cummin_int(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_cummin_int(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -49922,7 +51668,7 @@ mod tests {
     #[test]
     fn array_cumprod_int_rejects_non_int_and_arity() {
         assert!(
-            builtin_array_cumprod_int(&[Value::Array(vec![Value::String("a".into())])])
+            builtin_array_cumprod_int(&[Value::Array(vec![Value::String("a".into())].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -49983,7 +51729,7 @@ mod tests {
     fn array_count_in_range_int_rejects_non_int_and_arity() {
         assert!(
             builtin_array_count_in_range_int(&[
-                Value::Array(vec![Value::String("a".into())]),
+                Value::Array(vec![Value::String("a".into())].into()),
                 Value::Int(0),
                 Value::Int(10)
             ])
@@ -50063,12 +51809,12 @@ mod tests {
     #[test]
     fn array_argmax_argmin_int_empty_errors() {
         assert!(
-            builtin_array_argmax_int(&[Value::Array(vec![])])
+            builtin_array_argmax_int(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("empty array")
         );
         assert!(
-            builtin_array_argmin_int(&[Value::Array(vec![])])
+            builtin_array_argmin_int(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("empty array")
         );
@@ -50077,9 +51823,11 @@ mod tests {
     #[test]
     fn array_argmax_argmin_int_reject_non_int_and_arity() {
         assert!(
-            builtin_array_argmax_int(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)])])
-                .unwrap_err()
-                .contains("all int elements")
+            builtin_array_argmax_int(&[Value::Array(
+                vec![Value::Int(1), Value::Float(2.0)].into()
+            )])
+            .unwrap_err()
+            .contains("all int elements")
         );
         assert!(
             builtin_array_argmin_int(&[])
@@ -50106,7 +51854,7 @@ mod tests {
     fn array_contains_int_float_coercion() {
         // 1 (Int) should match 1.0 (Float) under scalar coercion.
         match builtin_array_contains(&[
-            Value::Array(vec![Value::Int(1), Value::Int(2)]),
+            Value::Array(vec![Value::Int(1), Value::Int(2)].into()),
             Value::Float(1.0),
         ])
         .unwrap()
@@ -50119,11 +51867,14 @@ mod tests {
     #[test]
     fn array_contains_strings() {
         match builtin_array_contains(&[
-            Value::Array(vec![
-                Value::String("a".into()),
-                Value::String("b".into()),
-                Value::String("c".into()),
-            ]),
+            Value::Array(
+                vec![
+                    Value::String("a".into()),
+                    Value::String("b".into()),
+                    Value::String("c".into()),
+                ]
+                .into(),
+            ),
             Value::String("b".into()),
         ])
         .unwrap()
@@ -50135,7 +51886,7 @@ mod tests {
 
     #[test]
     fn array_contains_empty_is_false() {
-        match builtin_array_contains(&[Value::Array(vec![]), Value::Int(1)]).unwrap() {
+        match builtin_array_contains(&[Value::Array(vec![].into()), Value::Int(1)]).unwrap() {
             Value::Bool(false) => {}
             other => panic!("expected false, got {:?}", other),
         }
@@ -50144,7 +51895,7 @@ mod tests {
     #[test]
     fn array_contains_rejects_non_scalar_element() {
         let err = builtin_array_contains(&[
-            Value::Array(vec![Value::Array(vec![Value::Int(1)])]),
+            Value::Array(vec![Value::Array(vec![Value::Int(1)].into())].into()),
             Value::Int(1),
         ])
         .unwrap_err();
@@ -50159,7 +51910,7 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_array_contains(&[Value::Array(vec![])])
+            builtin_array_contains(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -50178,7 +51929,7 @@ mod tests {
             "not found",
         );
         assert_int(
-            builtin_array_index_of(&[Value::Array(vec![]), Value::Int(1)]).unwrap(),
+            builtin_array_index_of(&[Value::Array(vec![].into()), Value::Int(1)]).unwrap(),
             -1,
             "empty",
         );
@@ -50192,7 +51943,7 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_array_index_of(&[Value::Array(vec![])])
+            builtin_array_index_of(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -50281,12 +52032,12 @@ mod tests {
     #[test]
     fn array_intersect_diff_reject_non_array_and_arity() {
         assert!(
-            builtin_array_intersect(&[Value::Int(1), Value::Array(vec![])])
+            builtin_array_intersect(&[Value::Int(1), Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected (array, array)")
         );
         assert!(
-            builtin_array_diff(&[Value::Array(vec![])])
+            builtin_array_diff(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -50318,11 +52069,14 @@ mod tests {
     #[test]
     fn array_index_of_all_works_on_strings() {
         match builtin_array_index_of_all(&[
-            Value::Array(vec![
-                Value::String("a".into()),
-                Value::String("b".into()),
-                Value::String("a".into()),
-            ]),
+            Value::Array(
+                vec![
+                    Value::String("a".into()),
+                    Value::String("b".into()),
+                    Value::String("a".into()),
+                ]
+                .into(),
+            ),
             Value::String("a".into()),
         ])
         .unwrap()
@@ -50366,8 +52120,8 @@ mod tests {
     #[test]
     fn array_index_of_all_rejects_non_comparable_elements() {
         let err = builtin_array_index_of_all(&[
-            Value::Array(vec![Value::Array(vec![Value::Int(1)])]),
-            Value::Array(vec![Value::Int(1)]),
+            Value::Array(vec![Value::Array(vec![Value::Int(1)].into())].into()),
+            Value::Array(vec![Value::Int(1)].into()),
         ])
         .unwrap_err();
         assert!(err.contains("not comparable"), "got: {}", err);
@@ -50381,7 +52135,7 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_array_index_of_all(&[Value::Array(vec![])])
+            builtin_array_index_of_all(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -50428,12 +52182,12 @@ mod tests {
     #[test]
     fn array_union_rejects_non_array_and_arity() {
         assert!(
-            builtin_array_union(&[Value::Int(1), Value::Array(vec![])])
+            builtin_array_union(&[Value::Int(1), Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected (array, array)")
         );
         assert!(
-            builtin_array_union(&[Value::Array(vec![])])
+            builtin_array_union(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -50681,15 +52435,17 @@ mod tests {
 
     #[test]
     fn array_concat_with_empty_left_or_right() {
-        match builtin_array_concat(&[Value::Array(vec![]), int_array(&[1, 2])]).unwrap() {
+        match builtin_array_concat(&[Value::Array(vec![].into()), int_array(&[1, 2])]).unwrap() {
             Value::Array(items) if items.len() == 2 => {}
             other => panic!("expected len-2 array, got {:?}", other),
         }
-        match builtin_array_concat(&[int_array(&[1, 2]), Value::Array(vec![])]).unwrap() {
+        match builtin_array_concat(&[int_array(&[1, 2]), Value::Array(vec![].into())]).unwrap() {
             Value::Array(items) if items.len() == 2 => {}
             other => panic!("expected len-2 array, got {:?}", other),
         }
-        match builtin_array_concat(&[Value::Array(vec![]), Value::Array(vec![])]).unwrap() {
+        match builtin_array_concat(&[Value::Array(vec![].into()), Value::Array(vec![].into())])
+            .unwrap()
+        {
             Value::Array(items) if items.is_empty() => {}
             other => panic!("expected empty array, got {:?}", other),
         }
@@ -50698,8 +52454,8 @@ mod tests {
     #[test]
     fn array_concat_allows_heterogeneous_elements() {
         match builtin_array_concat(&[
-            Value::Array(vec![Value::Int(1), Value::String("a".into())]),
-            Value::Array(vec![Value::Bool(true)]),
+            Value::Array(vec![Value::Int(1), Value::String("a".into())].into()),
+            Value::Array(vec![Value::Bool(true)].into()),
         ])
         .unwrap()
         {
@@ -50711,12 +52467,12 @@ mod tests {
     #[test]
     fn array_concat_rejects_non_array_and_arity() {
         assert!(
-            builtin_array_concat(&[Value::Int(1), Value::Array(vec![])])
+            builtin_array_concat(&[Value::Int(1), Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected (array, array)")
         );
         assert!(
-            builtin_array_concat(&[Value::Array(vec![])])
+            builtin_array_concat(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -50770,12 +52526,16 @@ mod tests {
     #[test]
     fn array_concat3_rejects_non_array_and_arity() {
         assert!(
-            builtin_array_concat3(&[Value::Int(1), Value::Array(vec![]), Value::Array(vec![])])
-                .unwrap_err()
-                .contains("expected (array, array, array)")
+            builtin_array_concat3(&[
+                Value::Int(1),
+                Value::Array(vec![].into()),
+                Value::Array(vec![].into())
+            ])
+            .unwrap_err()
+            .contains("expected (array, array, array)")
         );
         assert!(
-            builtin_array_concat3(&[Value::Array(vec![])])
+            builtin_array_concat3(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 3 arguments")
         );
@@ -50954,65 +52714,6 @@ mod tests {
         );
     }
 
-    // ---------- RES-514: array_step ----------
-
-    fn step_int(items: &[i64], n: i64) -> Vec<i64> {
-        match builtin_array_step(&[int_array(items), Value::Int(n)]).unwrap() {
-            Value::Array(out) => out
-                .into_iter()
-                .map(|v| match v {
-                    Value::Int(n) => n,
-                    other => panic!("expected Int, got {:?}", other),
-                })
-                .collect(),
-            other => panic!("expected Array, got {:?}", other),
-        }
-    }
-
-    #[test]
-    fn array_step_basic() {
-        assert_eq!(step_int(&[1, 2, 3, 4, 5, 6], 2), vec![1, 3, 5]);
-        assert_eq!(step_int(&[1, 2, 3, 4, 5, 6], 3), vec![1, 4]);
-        assert_eq!(step_int(&[1, 2, 3, 4, 5, 6], 1), vec![1, 2, 3, 4, 5, 6]);
-    }
-
-    #[test]
-    fn array_step_step_larger_than_len_keeps_first() {
-        assert_eq!(step_int(&[1, 2, 3], 7), vec![1]);
-    }
-
-    #[test]
-    fn array_step_empty_input_is_empty() {
-        assert_eq!(step_int(&[], 2), Vec::<i64>::new());
-    }
-
-    #[test]
-    fn array_step_singleton_is_singleton() {
-        assert_eq!(step_int(&[42], 5), vec![42]);
-    }
-
-    #[test]
-    fn array_step_rejects_zero_or_negative() {
-        let err = builtin_array_step(&[int_array(&[1]), Value::Int(0)]).unwrap_err();
-        assert!(err.contains("positive"), "got: {}", err);
-        let err = builtin_array_step(&[int_array(&[1]), Value::Int(-1)]).unwrap_err();
-        assert!(err.contains("positive"), "got: {}", err);
-    }
-
-    #[test]
-    fn array_step_rejects_wrong_types_and_arity() {
-        assert!(
-            builtin_array_step(&[Value::Int(5), Value::Int(2)])
-                .unwrap_err()
-                .contains("expected (array, int)")
-        );
-        assert!(
-            builtin_array_step(&[int_array(&[1])])
-                .unwrap_err()
-                .contains("expected 2 arguments")
-        );
-    }
-
     // ---------- RES-422: array_sort ----------
 
     #[test]
@@ -51034,7 +52735,7 @@ mod tests {
     #[test]
     fn array_sort_empty_returns_empty() {
         assert_eq!(
-            extract_int_array(builtin_array_sort(&[Value::Array(vec![])]).unwrap()),
+            extract_int_array(builtin_array_sort(&[Value::Array(vec![].into())]).unwrap()),
             Vec::<i64>::new()
         );
     }
@@ -51058,7 +52759,7 @@ mod tests {
     #[test]
     fn array_sort_rejects_non_int_and_non_array() {
         assert!(
-            builtin_array_sort(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)])])
+            builtin_array_sort(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -51074,10 +52775,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn array_sort_orders_floats_and_strings() {
+        let floats = builtin_array_sort(&[Value::Array(
+            vec![Value::Float(2.5), Value::Float(-1.0), Value::Float(0.5)].into(),
+        )])
+        .unwrap();
+        assert_eq!(format!("{}", floats), "[-1, 0.5, 2.5]");
+        let words = builtin_array_sort_desc(&[Value::Array(
+            vec![
+                Value::String("b".into()),
+                Value::String("c".into()),
+                Value::String("a".into()),
+            ]
+            .into(),
+        )])
+        .unwrap();
+        assert_eq!(format!("{}", words), r#"["c", "b", "a"]"#);
+        assert_eq!(
+            builtin_array_sort(&[Value::Array(
+                vec![Value::Float(1.0), Value::Float(f64::NAN)].into()
+            )])
+            .unwrap_err(),
+            "array_sort: InvalidArgument: NaN at index 1 cannot be ordered"
+        );
+        let r = run_program("println(sort([\"pear\", \"apple\", \"fig\"]));");
+        assert!(r.ok, "{:?}", r.errors);
+        assert_eq!(r.stdout, "[\"apple\", \"fig\", \"pear\"]\n");
+    }
+
     // ---------- RES-423: array_flatten ----------
 
     fn arr(items: Vec<Value>) -> Value {
-        Value::Array(items)
+        Value::Array(items.into())
     }
 
     #[test]
@@ -51191,7 +52921,8 @@ mod tests {
     fn array_join_empty_array_returns_empty_string() {
         assert_eq!(
             extract_string(
-                builtin_array_join(&[Value::Array(vec![]), Value::String(",".into())]).unwrap()
+                builtin_array_join(&[Value::Array(vec![].into()), Value::String(",".into())])
+                    .unwrap()
             ),
             ""
         );
@@ -51210,7 +52941,7 @@ mod tests {
     #[test]
     fn array_join_rejects_non_string_element() {
         let err = builtin_array_join(&[
-            Value::Array(vec![Value::String("a".into()), Value::Int(2)]),
+            Value::Array(vec![Value::String("a".into()), Value::Int(2)].into()),
             Value::String(",".into()),
         ])
         .unwrap_err();
@@ -51289,7 +53020,7 @@ mod tests {
 
     #[test]
     fn to_string_rejects_non_scalar() {
-        let err = builtin_to_string(&[Value::Array(vec![Value::Int(1)])]).unwrap_err();
+        let err = builtin_to_string(&[Value::Array(vec![Value::Int(1)].into())]).unwrap_err();
         assert!(err.contains("scalar value"), "got: {}", err);
     }
 
@@ -51328,7 +53059,7 @@ mod tests {
     #[test]
     fn array_unique_empty_returns_empty() {
         assert_eq!(
-            extract_int_array(builtin_array_unique(&[Value::Array(vec![])]).unwrap()),
+            extract_int_array(builtin_array_unique(&[Value::Array(vec![].into())]).unwrap()),
             Vec::<i64>::new()
         );
     }
@@ -51337,11 +53068,9 @@ mod tests {
     fn array_unique_int_float_collide() {
         // 1 (Int) and 1.0 (Float) should be treated as equal under
         // scalar coercion — the first occurrence wins.
-        match builtin_array_unique(&[Value::Array(vec![
-            Value::Int(1),
-            Value::Float(1.0),
-            Value::Int(2),
-        ])])
+        match builtin_array_unique(&[Value::Array(
+            vec![Value::Int(1), Value::Float(1.0), Value::Int(2)].into(),
+        )])
         .unwrap()
         {
             Value::Array(items) => assert_eq!(items.len(), 2),
@@ -51351,12 +53080,15 @@ mod tests {
 
     #[test]
     fn array_unique_strings() {
-        match builtin_array_unique(&[Value::Array(vec![
-            Value::String("a".into()),
-            Value::String("b".into()),
-            Value::String("a".into()),
-            Value::String("c".into()),
-        ])])
+        match builtin_array_unique(&[Value::Array(
+            vec![
+                Value::String("a".into()),
+                Value::String("b".into()),
+                Value::String("a".into()),
+                Value::String("c".into()),
+            ]
+            .into(),
+        )])
         .unwrap()
         {
             Value::Array(items) => assert_eq!(items.len(), 3),
@@ -51367,10 +53099,13 @@ mod tests {
     #[test]
     fn array_unique_rejects_non_scalar_element() {
         // Two non-scalar elements force a comparison call that errors.
-        let err = builtin_array_unique(&[Value::Array(vec![
-            Value::Array(vec![Value::Int(1)]),
-            Value::Array(vec![Value::Int(2)]),
-        ])])
+        let err = builtin_array_unique(&[Value::Array(
+            vec![
+                Value::Array(vec![Value::Int(1)].into()),
+                Value::Array(vec![Value::Int(2)].into()),
+            ]
+            .into(),
+        )])
         .unwrap_err();
         assert!(err.contains("not comparable"), "got: {}", err);
     }
@@ -51408,7 +53143,7 @@ mod tests {
     #[test]
     fn array_count_empty_returns_zero() {
         assert_int(
-            builtin_array_count(&[Value::Array(vec![]), Value::Int(1)]).unwrap(),
+            builtin_array_count(&[Value::Array(vec![].into()), Value::Int(1)]).unwrap(),
             0,
             "empty",
         );
@@ -51418,7 +53153,7 @@ mod tests {
     fn array_count_int_float_coercion() {
         assert_int(
             builtin_array_count(&[
-                Value::Array(vec![Value::Int(1), Value::Float(1.0), Value::Int(2)]),
+                Value::Array(vec![Value::Int(1), Value::Float(1.0), Value::Int(2)].into()),
                 Value::Int(1),
             ])
             .unwrap(),
@@ -51431,11 +53166,14 @@ mod tests {
     fn array_count_strings() {
         assert_int(
             builtin_array_count(&[
-                Value::Array(vec![
-                    Value::String("a".into()),
-                    Value::String("b".into()),
-                    Value::String("a".into()),
-                ]),
+                Value::Array(
+                    vec![
+                        Value::String("a".into()),
+                        Value::String("b".into()),
+                        Value::String("a".into()),
+                    ]
+                    .into(),
+                ),
                 Value::String("a".into()),
             ])
             .unwrap(),
@@ -51447,7 +53185,7 @@ mod tests {
     #[test]
     fn array_count_rejects_non_scalar_element() {
         let err = builtin_array_count(&[
-            Value::Array(vec![Value::Array(vec![Value::Int(1)])]),
+            Value::Array(vec![Value::Array(vec![Value::Int(1)].into())].into()),
             Value::Int(1),
         ])
         .unwrap_err();
@@ -51462,7 +53200,7 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_array_count(&[Value::Array(vec![])])
+            builtin_array_count(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -51486,7 +53224,7 @@ mod tests {
 
     #[test]
     fn array_first_empty_errors() {
-        let err = builtin_array_first(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_array_first(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("empty array"), "got: {}", err);
     }
 
@@ -51520,7 +53258,7 @@ mod tests {
 
     #[test]
     fn array_last_empty_errors() {
-        let err = builtin_array_last(&[Value::Array(vec![])]).unwrap_err();
+        let err = builtin_array_last(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(err.contains("empty array"), "got: {}", err);
     }
 
@@ -51540,12 +53278,15 @@ mod tests {
 
     #[test]
     fn array_first_last_preserve_element_type() {
-        match builtin_array_first(&[Value::Array(vec![Value::String("hi".into())])]).unwrap() {
+        match builtin_array_first(&[Value::Array(vec![Value::String("hi".into())].into())]).unwrap()
+        {
             Value::String(s) => assert_eq!(s, "hi"),
             other => panic!("expected String, got {:?}", other),
         }
-        match builtin_array_last(&[Value::Array(vec![Value::Bool(true), Value::Bool(false)])])
-            .unwrap()
+        match builtin_array_last(&[Value::Array(
+            vec![Value::Bool(true), Value::Bool(false)].into(),
+        )])
+        .unwrap()
         {
             Value::Bool(false) => {}
             other => panic!("expected Bool(false), got {:?}", other),
@@ -51605,7 +53346,7 @@ mod tests {
     #[test]
     fn array_get_or_empty_array_returns_default() {
         match builtin_array_get_or(&[
-            Value::Array(vec![]),
+            Value::Array(vec![].into()),
             Value::Int(0),
             Value::String("fallback".into()),
         ])
@@ -51883,14 +53624,16 @@ mod tests {
 
     #[test]
     fn array_zip_empty_inputs_return_empty() {
-        let pairs =
-            extract_tuples(builtin_array_zip(&[Value::Array(vec![]), int_array(&[1, 2])]).unwrap());
+        let pairs = extract_tuples(
+            builtin_array_zip(&[Value::Array(vec![].into()), int_array(&[1, 2])]).unwrap(),
+        );
         assert!(pairs.is_empty());
-        let pairs =
-            extract_tuples(builtin_array_zip(&[int_array(&[1, 2]), Value::Array(vec![])]).unwrap());
+        let pairs = extract_tuples(
+            builtin_array_zip(&[int_array(&[1, 2]), Value::Array(vec![].into())]).unwrap(),
+        );
         assert!(pairs.is_empty());
         let pairs = extract_tuples(
-            builtin_array_zip(&[Value::Array(vec![]), Value::Array(vec![])]).unwrap(),
+            builtin_array_zip(&[Value::Array(vec![].into()), Value::Array(vec![].into())]).unwrap(),
         );
         assert!(pairs.is_empty());
     }
@@ -51899,8 +53642,8 @@ mod tests {
     fn array_zip_heterogeneous_types() {
         // First array: Int. Second: String.
         let result = builtin_array_zip(&[
-            Value::Array(vec![Value::Int(1), Value::Int(2)]),
-            Value::Array(vec![Value::String("a".into()), Value::String("b".into())]),
+            Value::Array(vec![Value::Int(1), Value::Int(2)].into()),
+            Value::Array(vec![Value::String("a".into()), Value::String("b".into())].into()),
         ])
         .unwrap();
         match result {
@@ -51921,12 +53664,12 @@ mod tests {
     #[test]
     fn array_zip_rejects_non_array_and_arity() {
         assert!(
-            builtin_array_zip(&[Value::Int(1), Value::Array(vec![])])
+            builtin_array_zip(&[Value::Int(1), Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected (array, array)")
         );
         assert!(
-            builtin_array_zip(&[Value::Array(vec![])])
+            builtin_array_zip(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -51939,7 +53682,7 @@ mod tests {
             .into_iter()
             .map(|(a, b)| Value::Tuple(vec![a, b]))
             .collect();
-        let result = builtin_array_unzip(&[Value::Array(arr)]).unwrap();
+        let result = builtin_array_unzip(&[Value::Array(arr.into())]).unwrap();
         match result {
             Value::Tuple(parts) if parts.len() == 2 => {
                 let firsts = match parts[0].clone() {
@@ -51950,7 +53693,7 @@ mod tests {
                     Value::Array(items) => items,
                     other => panic!("expected second as Array, got {:?}", other),
                 };
-                (firsts, seconds)
+                (firsts.into_inner(), seconds.into_inner())
             }
             other => panic!("expected 2-tuple, got {:?}", other),
         }
@@ -51978,7 +53721,7 @@ mod tests {
 
     #[test]
     fn array_unzip_empty_returns_empty_pair() {
-        let result = builtin_array_unzip(&[Value::Array(vec![])]).unwrap();
+        let result = builtin_array_unzip(&[Value::Array(vec![].into())]).unwrap();
         match result {
             Value::Tuple(parts) if parts.len() == 2 => {
                 assert!(matches!(&parts[0], Value::Array(v) if v.is_empty()));
@@ -51997,8 +53740,11 @@ mod tests {
             Value::String("y".into()),
             Value::String("z".into()),
         ];
-        let zipped =
-            builtin_array_zip(&[Value::Array(a.clone()), Value::Array(b.clone())]).unwrap();
+        let zipped = builtin_array_zip(&[
+            Value::Array(a.clone().into()),
+            Value::Array(b.clone().into()),
+        ])
+        .unwrap();
         let unzipped = builtin_array_unzip(&[zipped]).unwrap();
         match unzipped {
             Value::Tuple(parts) if parts.len() == 2 => {
@@ -52011,14 +53757,17 @@ mod tests {
 
     #[test]
     fn array_unzip_rejects_non_2tuple_elements() {
-        let err = builtin_array_unzip(&[Value::Array(vec![Value::Int(1)])]).unwrap_err();
+        let err = builtin_array_unzip(&[Value::Array(vec![Value::Int(1)].into())]).unwrap_err();
         assert!(err.contains("2-tuples"), "got: {}", err);
         // 3-tuple is rejected too.
-        let err = builtin_array_unzip(&[Value::Array(vec![Value::Tuple(vec![
-            Value::Int(1),
-            Value::Int(2),
-            Value::Int(3),
-        ])])])
+        let err = builtin_array_unzip(&[Value::Array(
+            vec![Value::Tuple(vec![
+                Value::Int(1),
+                Value::Int(2),
+                Value::Int(3),
+            ])]
+            .into(),
+        )])
         .unwrap_err();
         assert!(err.contains("2-tuples"), "got: {}", err);
     }
@@ -52135,7 +53884,7 @@ mod tests {
     // ---------- RES-522: array_indices ----------
 
     fn indices(items: Vec<Value>) -> Vec<i64> {
-        match builtin_array_indices(&[Value::Array(items)]).unwrap() {
+        match builtin_array_indices(&[Value::Array(items.into())]).unwrap() {
             Value::Array(out) => out
                 .into_iter()
                 .map(|v| match v {
@@ -52476,7 +54225,7 @@ mod tests {
     }
 
     fn join_lines_str(items: Vec<Value>) -> String {
-        match builtin_string_join_lines(&[Value::Array(items)]).unwrap() {
+        match builtin_string_join_lines(&[Value::Array(items.into())]).unwrap() {
             Value::String(s) => s,
             other => panic!("expected String, got {:?}", other),
         }
@@ -52499,7 +54248,7 @@ mod tests {
         let original = "first\nsecond\nthird";
         let parts = builtin_string_lines(&[Value::String(original.into())]).unwrap();
         let Value::Array(items) = parts else { panic!() };
-        let rejoined = join_lines_str(items);
+        let rejoined = join_lines_str(items.into_inner());
         assert_eq!(rejoined, original);
     }
 
@@ -52511,7 +54260,7 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_string_join_lines(&[Value::Array(vec![Value::Int(5)])])
+            builtin_string_join_lines(&[Value::Array(vec![Value::Int(5)].into())])
                 .unwrap_err()
                 .contains("element must be string")
         );
@@ -52525,7 +54274,7 @@ mod tests {
     // ---------- RES-498: string_unwords ----------
 
     fn unwords_str(items: Vec<Value>) -> String {
-        match builtin_string_unwords(&[Value::Array(items)]).unwrap() {
+        match builtin_string_unwords(&[Value::Array(items.into())]).unwrap() {
             Value::String(s) => s,
             other => panic!("expected String, got {:?}", other),
         }
@@ -52545,7 +54294,7 @@ mod tests {
         let messy = "  foo\t bar\n\nbaz   ";
         let words = builtin_string_words(&[Value::String(messy.into())]).unwrap();
         let Value::Array(items) = words else { panic!() };
-        let normalised = unwords_str(items);
+        let normalised = unwords_str(items.into_inner());
         assert_eq!(normalised, "foo bar baz");
     }
 
@@ -52564,7 +54313,7 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_string_unwords(&[Value::Array(vec![Value::Int(7)])])
+            builtin_string_unwords(&[Value::Array(vec![Value::Int(7)].into())])
                 .unwrap_err()
                 .contains("element must be string")
         );
@@ -52747,8 +54496,9 @@ mod tests {
 
     #[test]
     fn array_chunk_empty_input_returns_empty() {
-        let chunks =
-            extract_chunks(builtin_array_chunk(&[Value::Array(vec![]), Value::Int(3)]).unwrap());
+        let chunks = extract_chunks(
+            builtin_array_chunk(&[Value::Array(vec![].into()), Value::Int(3)]).unwrap(),
+        );
         assert!(chunks.is_empty());
     }
 
@@ -53230,7 +54980,7 @@ mod tests {
     fn array_intersperse_empty_unchanged() {
         assert_eq!(
             extract_int_array(
-                builtin_array_intersperse(&[Value::Array(vec![]), Value::Int(0)]).unwrap()
+                builtin_array_intersperse(&[Value::Array(vec![].into()), Value::Int(0)]).unwrap()
             ),
             Vec::<i64>::new()
         );
@@ -53249,11 +54999,14 @@ mod tests {
     #[test]
     fn array_intersperse_string_separator() {
         let result = builtin_array_intersperse(&[
-            Value::Array(vec![
-                Value::String("a".into()),
-                Value::String("b".into()),
-                Value::String("c".into()),
-            ]),
+            Value::Array(
+                vec![
+                    Value::String("a".into()),
+                    Value::String("b".into()),
+                    Value::String("c".into()),
+                ]
+                .into(),
+            ),
             Value::String(",".into()),
         ])
         .unwrap();
@@ -53271,7 +55024,7 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_array_intersperse(&[Value::Array(vec![])])
+            builtin_array_intersperse(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -53322,12 +55075,12 @@ mod tests {
     #[test]
     fn array_interleave_rejects_non_array_and_arity() {
         assert!(
-            builtin_array_interleave(&[Value::Int(1), Value::Array(vec![])])
+            builtin_array_interleave(&[Value::Int(1), Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected (array, array)")
         );
         assert!(
-            builtin_array_interleave(&[Value::Array(vec![])])
+            builtin_array_interleave(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -53480,7 +55233,7 @@ mod tests {
     fn array_split_at_empty_input() {
         assert_eq!(
             extract_split_pair(
-                builtin_array_split_at(&[Value::Array(vec![]), Value::Int(0)]).unwrap()
+                builtin_array_split_at(&[Value::Array(vec![].into()), Value::Int(0)]).unwrap()
             ),
             (vec![], vec![])
         );
@@ -54836,7 +56589,7 @@ mod tests {
     #[test]
     fn array_sort_desc_empty_returns_empty() {
         assert_eq!(
-            extract_int_array(builtin_array_sort_desc(&[Value::Array(vec![])]).unwrap()),
+            extract_int_array(builtin_array_sort_desc(&[Value::Array(vec![].into())]).unwrap()),
             Vec::<i64>::new()
         );
     }
@@ -54851,7 +56604,7 @@ mod tests {
     #[test]
     fn array_sort_desc_rejects_non_int_and_non_array() {
         assert!(
-            builtin_array_sort_desc(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)])])
+            builtin_array_sort_desc(&[Value::Array(vec![Value::Int(1), Value::Float(2.0)].into())])
                 .unwrap_err()
                 .contains("all int elements")
         );
@@ -54880,7 +56633,7 @@ mod tests {
     #[test]
     fn array_shuffle_empty_returns_empty() {
         assert_eq!(
-            extract_int_array(builtin_array_shuffle(&[Value::Array(vec![])]).unwrap()),
+            extract_int_array(builtin_array_shuffle(&[Value::Array(vec![].into())]).unwrap()),
             Vec::<i64>::new()
         );
     }
@@ -54940,12 +56693,14 @@ mod tests {
     #[test]
     fn array_starts_with_empty_prefix_always_true() {
         assert_eq_bool(
-            builtin_array_starts_with(&[int_array(&[1, 2, 3]), Value::Array(vec![])]).unwrap(),
+            builtin_array_starts_with(&[int_array(&[1, 2, 3]), Value::Array(vec![].into())])
+                .unwrap(),
             true,
             "empty prefix",
         );
         assert_eq_bool(
-            builtin_array_starts_with(&[Value::Array(vec![]), Value::Array(vec![])]).unwrap(),
+            builtin_array_starts_with(&[Value::Array(vec![].into()), Value::Array(vec![].into())])
+                .unwrap(),
             true,
             "empty / empty",
         );
@@ -54977,7 +56732,7 @@ mod tests {
     #[test]
     fn array_ends_with_empty_suffix_always_true() {
         assert_eq_bool(
-            builtin_array_ends_with(&[int_array(&[1, 2, 3]), Value::Array(vec![])]).unwrap(),
+            builtin_array_ends_with(&[int_array(&[1, 2, 3]), Value::Array(vec![].into())]).unwrap(),
             true,
             "empty suffix",
         );
@@ -54996,8 +56751,8 @@ mod tests {
     fn array_prefix_suffix_int_float_coerce() {
         assert_eq_bool(
             builtin_array_starts_with(&[
-                Value::Array(vec![Value::Int(1), Value::Int(2)]),
-                Value::Array(vec![Value::Float(1.0)]),
+                Value::Array(vec![Value::Int(1), Value::Int(2)].into()),
+                Value::Array(vec![Value::Float(1.0)].into()),
             ])
             .unwrap(),
             true,
@@ -55432,8 +57187,12 @@ mod tests {
     fn array_pad_empty_input() {
         assert_eq!(
             extract_int_array(
-                builtin_array_pad_right(&[Value::Array(vec![]), Value::Int(3), Value::Int(7)])
-                    .unwrap()
+                builtin_array_pad_right(&[
+                    Value::Array(vec![].into()),
+                    Value::Int(3),
+                    Value::Int(7)
+                ])
+                .unwrap()
             ),
             vec![7, 7, 7]
         );
@@ -55514,8 +57273,8 @@ mod tests {
 
     #[test]
     fn array_swap_empty_array_always_errors() {
-        let err =
-            builtin_array_swap(&[Value::Array(vec![]), Value::Int(0), Value::Int(0)]).unwrap_err();
+        let err = builtin_array_swap(&[Value::Array(vec![].into()), Value::Int(0), Value::Int(0)])
+            .unwrap_err();
         assert!(err.contains("out of bounds"), "got: {}", err);
     }
 
@@ -55573,8 +57332,12 @@ mod tests {
     fn array_insert_at_empty_array() {
         assert_eq!(
             extract_int_array(
-                builtin_array_insert_at(&[Value::Array(vec![]), Value::Int(0), Value::Int(7)])
-                    .unwrap()
+                builtin_array_insert_at(&[
+                    Value::Array(vec![].into()),
+                    Value::Int(0),
+                    Value::Int(7)
+                ])
+                .unwrap()
             ),
             vec![7]
         );
@@ -55622,7 +57385,8 @@ mod tests {
 
     #[test]
     fn array_remove_at_empty_or_out_of_bounds_errors() {
-        let err = builtin_array_remove_at(&[Value::Array(vec![]), Value::Int(0)]).unwrap_err();
+        let err =
+            builtin_array_remove_at(&[Value::Array(vec![].into()), Value::Int(0)]).unwrap_err();
         assert!(err.contains("out of bounds"), "got: {}", err);
         let err = builtin_array_remove_at(&[int_array(&[1, 2]), Value::Int(99)]).unwrap_err();
         assert!(err.contains("out of bounds"), "got: {}", err);
@@ -55706,7 +57470,7 @@ mod tests {
                 .contains("out of bounds")
         );
         assert!(
-            builtin_array_set_at(&[Value::Array(vec![]), Value::Int(0), Value::Int(0)])
+            builtin_array_set_at(&[Value::Array(vec![].into()), Value::Int(0), Value::Int(0)])
                 .unwrap_err()
                 .contains("out of bounds")
         );
@@ -55924,8 +57688,9 @@ mod tests {
 
     #[test]
     fn array_window_empty_input() {
-        let chunks =
-            extract_chunks(builtin_array_window(&[Value::Array(vec![]), Value::Int(2)]).unwrap());
+        let chunks = extract_chunks(
+            builtin_array_window(&[Value::Array(vec![].into()), Value::Int(2)]).unwrap(),
+        );
         assert!(chunks.is_empty());
     }
 
@@ -55957,103 +57722,6 @@ mod tests {
         );
     }
 
-    // ---------- RES-456: array_rotate_left / array_rotate_right ----------
-
-    #[test]
-    fn array_rotate_left_basic() {
-        assert_eq!(
-            extract_int_array(
-                builtin_array_rotate_left(&[int_array(&[1, 2, 3, 4, 5]), Value::Int(2)]).unwrap()
-            ),
-            vec![3, 4, 5, 1, 2]
-        );
-    }
-
-    #[test]
-    fn array_rotate_right_basic() {
-        assert_eq!(
-            extract_int_array(
-                builtin_array_rotate_right(&[int_array(&[1, 2, 3, 4, 5]), Value::Int(2)]).unwrap()
-            ),
-            vec![4, 5, 1, 2, 3]
-        );
-    }
-
-    #[test]
-    fn array_rotate_zero_returns_clone() {
-        assert_eq!(
-            extract_int_array(
-                builtin_array_rotate_left(&[int_array(&[1, 2, 3]), Value::Int(0)]).unwrap()
-            ),
-            vec![1, 2, 3]
-        );
-    }
-
-    #[test]
-    fn array_rotate_full_cycle_is_identity() {
-        assert_eq!(
-            extract_int_array(
-                builtin_array_rotate_left(&[int_array(&[1, 2, 3]), Value::Int(3)]).unwrap()
-            ),
-            vec![1, 2, 3]
-        );
-        assert_eq!(
-            extract_int_array(
-                builtin_array_rotate_right(&[int_array(&[1, 2, 3]), Value::Int(3)]).unwrap()
-            ),
-            vec![1, 2, 3]
-        );
-    }
-
-    #[test]
-    fn array_rotate_n_modulo_len() {
-        // n=7, len=3 → n mod len = 1.
-        assert_eq!(
-            extract_int_array(
-                builtin_array_rotate_left(&[int_array(&[1, 2, 3]), Value::Int(7)]).unwrap()
-            ),
-            vec![2, 3, 1]
-        );
-    }
-
-    #[test]
-    fn array_rotate_empty_array() {
-        assert_eq!(
-            extract_int_array(
-                builtin_array_rotate_left(&[Value::Array(vec![]), Value::Int(5)]).unwrap()
-            ),
-            Vec::<i64>::new()
-        );
-    }
-
-    #[test]
-    fn array_rotate_rejects_negative_count() {
-        assert!(
-            builtin_array_rotate_left(&[int_array(&[1, 2]), Value::Int(-1)])
-                .unwrap_err()
-                .contains("non-negative")
-        );
-        assert!(
-            builtin_array_rotate_right(&[int_array(&[1, 2]), Value::Int(-1)])
-                .unwrap_err()
-                .contains("non-negative")
-        );
-    }
-
-    #[test]
-    fn array_rotate_rejects_wrong_types_and_arity() {
-        assert!(
-            builtin_array_rotate_left(&[Value::Int(1), Value::Int(2)])
-                .unwrap_err()
-                .contains("expected (array, int)")
-        );
-        assert!(
-            builtin_array_rotate_right(&[int_array(&[1])])
-                .unwrap_err()
-                .contains("expected 2 arguments")
-        );
-    }
-
     // ---------- RES-457: string_capitalize ----------
 
     #[test]
@@ -56152,7 +57820,7 @@ mod tests {
     fn array_cycle_empty_array_stays_empty() {
         assert_eq!(
             extract_int_array(
-                builtin_array_cycle(&[Value::Array(vec![]), Value::Int(99)]).unwrap()
+                builtin_array_cycle(&[Value::Array(vec![].into()), Value::Int(99)]).unwrap()
             ),
             Vec::<i64>::new()
         );
@@ -56504,7 +58172,7 @@ mod tests {
 
     #[test]
     fn array_pairs_empty_returns_empty() {
-        let pairs = extract_tuples(builtin_array_pairs(&[Value::Array(vec![])]).unwrap());
+        let pairs = extract_tuples(builtin_array_pairs(&[Value::Array(vec![].into())]).unwrap());
         assert!(pairs.is_empty());
     }
 
@@ -56748,10 +58416,9 @@ mod tests {
 
     #[test]
     fn string_from_bytes_rejects_non_int_and_arity() {
-        let err = builtin_string_from_bytes(&[Value::Array(vec![
-            Value::Int(65),
-            Value::String("x".into()),
-        ])])
+        let err = builtin_string_from_bytes(&[Value::Array(
+            vec![Value::Int(65), Value::String("x".into())].into(),
+        )])
         .unwrap_err();
         assert!(err.contains("all int elements"), "got: {}", err);
         assert!(
@@ -57029,7 +58696,7 @@ mod tests {
     fn array_remove_empty_returns_empty() {
         assert_eq!(
             extract_int_array(
-                builtin_array_remove(&[Value::Array(vec![]), Value::Int(1)]).unwrap()
+                builtin_array_remove(&[Value::Array(vec![].into()), Value::Int(1)]).unwrap()
             ),
             Vec::<i64>::new()
         );
@@ -57039,7 +58706,7 @@ mod tests {
     fn array_remove_int_float_coerce() {
         // Int 1 should match Float 1.0.
         match builtin_array_remove(&[
-            Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]),
+            Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)].into()),
             Value::Float(1.0),
         ])
         .unwrap()
@@ -57058,11 +58725,14 @@ mod tests {
     #[test]
     fn array_remove_strings() {
         match builtin_array_remove(&[
-            Value::Array(vec![
-                Value::String("a".into()),
-                Value::String("b".into()),
-                Value::String("a".into()),
-            ]),
+            Value::Array(
+                vec![
+                    Value::String("a".into()),
+                    Value::String("b".into()),
+                    Value::String("a".into()),
+                ]
+                .into(),
+            ),
             Value::String("a".into()),
         ])
         .unwrap()
@@ -57082,8 +58752,8 @@ mod tests {
     #[test]
     fn array_remove_rejects_non_scalar_element() {
         let err = builtin_array_remove(&[
-            Value::Array(vec![Value::Array(vec![Value::Int(1)])]),
-            Value::Array(vec![Value::Int(1)]),
+            Value::Array(vec![Value::Array(vec![Value::Int(1)].into())].into()),
+            Value::Array(vec![Value::Int(1)].into()),
         ])
         .unwrap_err();
         assert!(err.contains("not comparable"), "got: {}", err);
@@ -57097,7 +58767,7 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_array_remove(&[Value::Array(vec![])])
+            builtin_array_remove(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -57129,7 +58799,7 @@ mod tests {
     fn array_remove_all_empty_returns_empty() {
         assert_eq!(
             extract_int_array(
-                builtin_array_remove_all(&[Value::Array(vec![]), Value::Int(1)]).unwrap()
+                builtin_array_remove_all(&[Value::Array(vec![].into()), Value::Int(1)]).unwrap()
             ),
             Vec::<i64>::new()
         );
@@ -57155,8 +58825,8 @@ mod tests {
     #[test]
     fn array_remove_all_rejects_non_scalar_and_non_array_and_arity() {
         let err = builtin_array_remove_all(&[
-            Value::Array(vec![Value::Array(vec![Value::Int(1)])]),
-            Value::Array(vec![Value::Int(1)]),
+            Value::Array(vec![Value::Array(vec![Value::Int(1)].into())].into()),
+            Value::Array(vec![Value::Int(1)].into()),
         ])
         .unwrap_err();
         assert!(err.contains("not comparable"), "got: {}", err);
@@ -57166,7 +58836,7 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_array_remove_all(&[Value::Array(vec![])])
+            builtin_array_remove_all(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -57195,7 +58865,7 @@ mod tests {
     #[test]
     fn array_dedup_empty_returns_empty() {
         assert_eq!(
-            extract_int_array(builtin_array_dedup(&[Value::Array(vec![])]).unwrap()),
+            extract_int_array(builtin_array_dedup(&[Value::Array(vec![].into())]).unwrap()),
             Vec::<i64>::new()
         );
     }
@@ -57218,13 +58888,16 @@ mod tests {
 
     #[test]
     fn array_dedup_strings() {
-        match builtin_array_dedup(&[Value::Array(vec![
-            Value::String("a".into()),
-            Value::String("a".into()),
-            Value::String("b".into()),
-            Value::String("b".into()),
-            Value::String("a".into()),
-        ])])
+        match builtin_array_dedup(&[Value::Array(
+            vec![
+                Value::String("a".into()),
+                Value::String("a".into()),
+                Value::String("b".into()),
+                Value::String("b".into()),
+                Value::String("a".into()),
+            ]
+            .into(),
+        )])
         .unwrap()
         {
             Value::Array(items) => {
@@ -57245,10 +58918,13 @@ mod tests {
     #[test]
     fn array_dedup_rejects_non_scalar_runs() {
         // Two adjacent non-scalar values force a comparison call that errors.
-        let err = builtin_array_dedup(&[Value::Array(vec![
-            Value::Array(vec![Value::Int(1)]),
-            Value::Array(vec![Value::Int(2)]),
-        ])])
+        let err = builtin_array_dedup(&[Value::Array(
+            vec![
+                Value::Array(vec![Value::Int(1)].into()),
+                Value::Array(vec![Value::Int(2)].into()),
+            ]
+            .into(),
+        )])
         .unwrap_err();
         assert!(err.contains("not comparable"), "got: {}", err);
     }
@@ -57344,10 +59020,9 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_array_group_by_int(&[Value::Array(vec![
-                Value::Int(1),
-                Value::String("oops".into())
-            ])])
+            builtin_array_group_by_int(&[Value::Array(
+                vec![Value::Int(1), Value::String("oops".into())].into()
+            )])
             .unwrap_err()
             .contains("all int elements")
         );
@@ -57361,7 +59036,7 @@ mod tests {
     // ---------- RES-533: array_count_runs ----------
 
     fn count_runs(items: Vec<Value>) -> i64 {
-        match builtin_array_count_runs(&[Value::Array(items)]).unwrap() {
+        match builtin_array_count_runs(&[Value::Array(items.into())]).unwrap() {
             Value::Int(n) => n,
             other => panic!("expected Int, got {:?}", other),
         }
@@ -57441,10 +59116,13 @@ mod tests {
 
     #[test]
     fn array_count_runs_rejects_non_comparable_elements() {
-        let err = builtin_array_count_runs(&[Value::Array(vec![
-            Value::Array(vec![Value::Int(1)]),
-            Value::Array(vec![Value::Int(2)]),
-        ])])
+        let err = builtin_array_count_runs(&[Value::Array(
+            vec![
+                Value::Array(vec![Value::Int(1)].into()),
+                Value::Array(vec![Value::Int(2)].into()),
+            ]
+            .into(),
+        )])
         .unwrap_err();
         assert!(err.contains("not comparable"), "got: {}", err);
     }
@@ -57482,7 +59160,7 @@ mod tests {
     #[test]
     fn array_all_eq_empty_is_vacuously_true() {
         assert_eq_bool(
-            builtin_array_all_eq(&[Value::Array(vec![]), Value::Int(7)]).unwrap(),
+            builtin_array_all_eq(&[Value::Array(vec![].into()), Value::Int(7)]).unwrap(),
             true,
             "empty",
         );
@@ -57493,7 +59171,7 @@ mod tests {
         // [1, 1.0] all_eq 1 should be true.
         assert_eq_bool(
             builtin_array_all_eq(&[
-                Value::Array(vec![Value::Int(1), Value::Float(1.0)]),
+                Value::Array(vec![Value::Int(1), Value::Float(1.0)].into()),
                 Value::Int(1),
             ])
             .unwrap(),
@@ -57519,7 +59197,7 @@ mod tests {
             "any eq 99",
         );
         assert_eq_bool(
-            builtin_array_any_eq(&[Value::Array(vec![]), Value::Int(0)]).unwrap(),
+            builtin_array_any_eq(&[Value::Array(vec![].into()), Value::Int(0)]).unwrap(),
             false,
             "empty",
         );
@@ -57528,7 +59206,7 @@ mod tests {
     #[test]
     fn array_all_eq_rejects_non_scalar_element() {
         let err = builtin_array_all_eq(&[
-            Value::Array(vec![Value::Array(vec![Value::Int(1)])]),
+            Value::Array(vec![Value::Array(vec![Value::Int(1)].into())].into()),
             Value::Int(1),
         ])
         .unwrap_err();
@@ -57543,7 +59221,7 @@ mod tests {
                 .contains("expected array")
         );
         assert!(
-            builtin_array_all_eq(&[Value::Array(vec![])])
+            builtin_array_all_eq(&[Value::Array(vec![].into())])
                 .unwrap_err()
                 .contains("expected 2 arguments")
         );
@@ -57681,7 +59359,7 @@ mod tests {
     #[test]
     fn array_eq_empty_arrays_true() {
         assert_eq_bool(
-            builtin_array_eq(&[Value::Array(vec![]), Value::Array(vec![])]).unwrap(),
+            builtin_array_eq(&[Value::Array(vec![].into()), Value::Array(vec![].into())]).unwrap(),
             true,
             "both empty",
         );
@@ -57692,8 +59370,8 @@ mod tests {
         // [1, 2.0, 3] == [1.0, 2, 3] under scalar coercion.
         assert_eq_bool(
             builtin_array_eq(&[
-                Value::Array(vec![Value::Int(1), Value::Float(2.0), Value::Int(3)]),
-                Value::Array(vec![Value::Float(1.0), Value::Int(2), Value::Int(3)]),
+                Value::Array(vec![Value::Int(1), Value::Float(2.0), Value::Int(3)].into()),
+                Value::Array(vec![Value::Float(1.0), Value::Int(2), Value::Int(3)].into()),
             ])
             .unwrap(),
             true,
@@ -57704,8 +59382,8 @@ mod tests {
     #[test]
     fn array_eq_rejects_non_scalar_element() {
         let err = builtin_array_eq(&[
-            Value::Array(vec![Value::Array(vec![Value::Int(1)])]),
-            Value::Array(vec![Value::Array(vec![Value::Int(1)])]),
+            Value::Array(vec![Value::Array(vec![Value::Int(1)].into())].into()),
+            Value::Array(vec![Value::Array(vec![Value::Int(1)].into())].into()),
         ])
         .unwrap_err();
         assert!(err.contains("not comparable"), "got: {}", err);
@@ -57805,7 +59483,7 @@ mod tests {
             "ne length mismatch",
         );
         assert_eq_bool(
-            builtin_array_ne(&[Value::Array(vec![]), Value::Array(vec![])]).unwrap(),
+            builtin_array_ne(&[Value::Array(vec![].into()), Value::Array(vec![].into())]).unwrap(),
             false,
             "ne empty",
         );
@@ -57893,7 +59571,7 @@ mod tests {
     fn array_fold_int_empty_returns_init() {
         assert_int(
             builtin_array_fold_int(&[
-                Value::Array(vec![]),
+                Value::Array(vec![].into()),
                 Value::Int(42),
                 Value::String("sum".into()),
             ])
@@ -57914,7 +59592,7 @@ mod tests {
     #[test]
     fn array_fold_int_rejects_non_int_element() {
         let err = builtin_array_fold_int(&[
-            Value::Array(vec![Value::Int(1), Value::Float(2.0)]),
+            Value::Array(vec![Value::Int(1), Value::Float(2.0)].into()),
             Value::Int(0),
             Value::String("sum".into()),
         ])
@@ -58004,7 +59682,7 @@ mod tests {
         );
         assert!(
             builtin_array_scan_int(&[
-                Value::Array(vec![Value::Int(1), Value::Float(2.0)]),
+                Value::Array(vec![Value::Int(1), Value::Float(2.0)].into()),
                 Value::Int(0),
                 Value::String("sum".into()),
             ])
@@ -58082,7 +59760,7 @@ mod tests {
         );
         assert!(
             builtin_array_zip_with_int(&[
-                Value::Array(vec![Value::Int(1), Value::Float(2.0)]),
+                Value::Array(vec![Value::Int(1), Value::Float(2.0)].into()),
                 int_array(&[1, 2]),
                 Value::String("sum".into()),
             ])
@@ -58370,11 +60048,11 @@ mod tests {
     #[test]
     fn array_rest_init_empty_returns_empty() {
         assert_eq!(
-            extract_int_array(builtin_array_rest(&[Value::Array(vec![])]).unwrap()),
+            extract_int_array(builtin_array_rest(&[Value::Array(vec![].into())]).unwrap()),
             Vec::<i64>::new()
         );
         assert_eq!(
-            extract_int_array(builtin_array_init(&[Value::Array(vec![])]).unwrap()),
+            extract_int_array(builtin_array_init(&[Value::Array(vec![].into())]).unwrap()),
             Vec::<i64>::new()
         );
     }
@@ -58709,8 +60387,11 @@ mod tests {
     fn array_filter_int_empty_returns_empty() {
         assert_eq!(
             extract_int_array(
-                builtin_array_filter_int(&[Value::Array(vec![]), Value::String("positive".into())])
-                    .unwrap()
+                builtin_array_filter_int(&[
+                    Value::Array(vec![].into()),
+                    Value::String("positive".into())
+                ])
+                .unwrap()
             ),
             Vec::<i64>::new()
         );
@@ -58791,7 +60472,7 @@ mod tests {
         // negative, so iteration continues to the misplaced string.
         assert!(
             builtin_array_any_int(&[
-                Value::Array(vec![Value::Int(1), Value::String("oops".into())]),
+                Value::Array(vec![Value::Int(1), Value::String("oops".into())].into()),
                 Value::String("negative".into())
             ])
             .unwrap_err()
@@ -58835,7 +60516,7 @@ mod tests {
         // first int already fails the predicate, so the error path
         // should NOT fire.
         let result = builtin_array_all_int(&[
-            Value::Array(vec![Value::Int(-1), Value::String("oops".into())]),
+            Value::Array(vec![Value::Int(-1), Value::String("oops".into())].into()),
             Value::String("positive".into()),
         ])
         .unwrap();
@@ -59850,13 +61531,13 @@ mod tests {
             if matches!(tok, Token::Eof) {
                 break;
             }
-            if matches!(tok, Token::Unknown('#')) {
+            if matches!(tok, Token::Error(LexError::UnexpectedChar('#'))) {
                 saw_unknown_hash = true;
             }
         }
         assert!(
             saw_unknown_hash,
-            "expected Token::Unknown('#') somewhere in the stream"
+            "expected UnexpectedChar('#') somewhere in the stream"
         );
     }
 
@@ -59900,7 +61581,7 @@ mod tests {
             if matches!(tok, Token::Eof) {
                 break;
             }
-            if let Token::Unknown(c) = tok
+            if let Token::Error(LexError::UnexpectedChar(c)) = tok
                 && !c.is_ascii()
             {
                 saw_non_ascii = true;
@@ -59908,7 +61589,7 @@ mod tests {
         }
         assert!(
             saw_non_ascii,
-            "expected non-ASCII char to surface as Token::Unknown"
+            "expected non-ASCII char to surface as UnexpectedChar"
         );
     }
 
@@ -59916,9 +61597,8 @@ mod tests {
     fn lexer_rejects_mixed_latin_greek() {
         // `Αlpha` at the statement head — uppercase Greek Alpha
         // (U+0391) then Latin `lpha`. The Greek start char falls
-        // through to parse_statement's `Token::Unknown` arm,
-        // which routes it through the dedicated non-ASCII
-        // diagnostic. (Bare `let Αlpha` would hit the let-parser's
+        // through to `LexError::UnexpectedChar`, which the parser
+        // reports with the dedicated non-ASCII diagnostic. (Bare `let Αlpha` would hit the let-parser's
         // "expected identifier" error first; using a statement-
         // level position exercises the path the policy is actually
         // designed for.)
@@ -60111,7 +61791,7 @@ mod tests {
         match interp
             .statics
             .borrow()
-            .get("inner_calls")
+            .get(&Symbol::intern("inner_calls"))
             .expect("static counter")
         {
             Value::Int(n) => assert_eq!(*n, 9, "expected 9 inner invocations, got {}", n),
@@ -60146,7 +61826,12 @@ mod tests {
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         let mut interp = Interpreter::new();
         let _ = interp.eval(&program); // exhausts; ignore error
-        match interp.statics.borrow().get("seen").expect("static seen") {
+        match interp
+            .statics
+            .borrow()
+            .get(&Symbol::intern("seen"))
+            .expect("static seen")
+        {
             Value::Array(items) => {
                 let ns: Vec<i64> = items
                     .iter()
@@ -60753,7 +62438,7 @@ mod tests {
         match interp
             .statics
             .borrow()
-            .get("calls")
+            .get(&Symbol::intern("calls"))
             .expect("static counter")
         {
             Value::Int(n) => {
@@ -60798,7 +62483,7 @@ mod tests {
         match interp
             .statics
             .borrow()
-            .get("calls")
+            .get(&Symbol::intern("calls"))
             .expect("static counter")
         {
             Value::Int(n) => assert_eq!(*n, 5, "body must run 5 times under retries(5), got {}", n),
@@ -60838,7 +62523,7 @@ mod tests {
         match interp
             .statics
             .borrow()
-            .get("calls")
+            .get(&Symbol::intern("calls"))
             .expect("static counter")
         {
             Value::Int(n) => assert_eq!(*n, 3, "body must run 3 times by default, got {}", n),
@@ -61003,7 +62688,12 @@ mod tests {
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         let mut interp = Interpreter::new();
         interp.eval(&program).unwrap();
-        match interp.statics.borrow().get("seen").expect("static `seen`") {
+        match interp
+            .statics
+            .borrow()
+            .get(&Symbol::intern("seen"))
+            .expect("static `seen`")
+        {
             Value::Array(items) => {
                 let ns: Vec<i64> = items
                     .iter()
@@ -61468,7 +63158,7 @@ mod tests {
         assert_eq!(Token::LeftBrace.display_syntax(), "`{`");
         assert_eq!(Token::Function.display_syntax(), "`fn`");
         assert_eq!(
-            Token::Identifier("x".to_string()).display_syntax(),
+            Token::Identifier("x".into()).display_syntax(),
             "identifier `x`"
         );
         assert_eq!(Token::Eof.display_syntax(), "end of input");
@@ -61866,6 +63556,28 @@ mod tests {
         assert_eq!(wire[1], 0, "first dStart should be 0");
     }
 
+    /// Contract keywords stay KEYWORDs but carry MOD_CONTRACT, and a
+    /// block comment spanning lines becomes one token per line.
+    #[test]
+    fn collect_semantic_tokens_marks_contracts_and_splits_block_comments() {
+        let src = "/* one\ntwo */\nfn f(int x) requires x > 0 { return x; }";
+        let tokens = collect_semantic_tokens(src);
+        let comments: Vec<(u32, u32, u32)> = tokens
+            .iter()
+            .filter(|t| t.ty == sem_tok::COMMENT)
+            .map(|t| (t.line, t.col, t.length))
+            .collect();
+        assert_eq!(comments, vec![(0, 0, 6), (1, 0, 6)]);
+        let requires = tokens
+            .iter()
+            .find(|t| t.line == 2 && t.col == 12)
+            .expect("token for `requires`");
+        assert_eq!(
+            (requires.ty, requires.modifiers),
+            (sem_tok::KEYWORD, sem_tok::MOD_CONTRACT)
+        );
+    }
+
     // ------------------------------------------------------------
     // RES-391: ownership-region parser and borrow-check unit tests.
     // ------------------------------------------------------------
@@ -62166,9 +63878,12 @@ mod tests {
         let mut interp = Interpreter::new();
         interp.eval(&program).expect("should execute");
         assert!(
-            matches!(interp.consts.get("X"), Some(Value::Int(42))),
+            matches!(
+                interp.consts.get(&Symbol::intern("X")),
+                Some(Value::Int(42))
+            ),
             "const X should be 42, got: {:?}",
-            interp.consts.get("X")
+            interp.consts.get(&Symbol::intern("X"))
         );
     }
 
@@ -62184,28 +63899,46 @@ mod tests {
         let mut interp = Interpreter::new();
         interp.eval(&program).expect("should execute");
         assert!(
-            matches!(interp.consts.get("C"), Some(Value::Int(64))),
+            matches!(
+                interp.consts.get(&Symbol::intern("C")),
+                Some(Value::Int(64))
+            ),
             "const C should be 64, got: {:?}",
-            interp.consts.get("C")
+            interp.consts.get(&Symbol::intern("C"))
         );
     }
 
     #[test]
-    fn const_forward_reference_before_definition_is_error() {
-        // B is not yet defined when A tries to reference it.
+    fn const_forward_reference_resolves() {
+        // A refers to B, declared after it — resolved on demand.
         let src = "\
-            const A: Int = B;\n\
+            const A: Int = B * 2;\n\
             const B: Int = 5;\n\
         ";
         let (program, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         let mut interp = Interpreter::new();
-        let err = interp
-            .eval(&program)
-            .expect_err("forward-ref const must fail");
+        interp.eval(&program).expect("forward-ref const resolves");
+        assert!(
+            matches!(
+                interp.consts.get(&Symbol::intern("A")),
+                Some(Value::Int(10))
+            ),
+            "const A should be 10, got: {:?}",
+            interp.consts.get(&Symbol::intern("A"))
+        );
+    }
+
+    #[test]
+    fn const_mutual_reference_is_circular_error() {
+        let src = "const A: Int = B;\nconst B: Int = A;\n";
+        let (program, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let mut interp = Interpreter::new();
+        let err = interp.eval(&program).expect_err("mutual ref must fail");
         assert!(
-            err.contains("'B'"),
-            "expected message about B, got: {}",
+            err.contains("circular constant definition: 'A' -> 'B' -> 'A'"),
+            "expected the full cycle, got: {}",
             err
         );
     }
@@ -63006,12 +64739,8 @@ mod stdlib_batch_tests_res_936_to_945 {
     // RES-941: array_average / array_median
     #[test]
     fn array_average_simple() {
-        let arr = Value::Array(vec![
-            Value::Int(1),
-            Value::Int(2),
-            Value::Int(3),
-            Value::Int(4),
-        ]);
+        let arr =
+            Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3), Value::Int(4)].into());
         let v = builtin_array_average(&[arr]).unwrap();
         match v {
             Value::Float(f) => assert!((f - 2.5).abs() < 1e-12),
@@ -63021,13 +64750,13 @@ mod stdlib_batch_tests_res_936_to_945 {
 
     #[test]
     fn array_average_empty_errors() {
-        let e = builtin_array_average(&[Value::Array(vec![])]).unwrap_err();
+        let e = builtin_array_average(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(e.contains("empty"), "err was: {}", e);
     }
 
     #[test]
     fn array_median_odd_length() {
-        let arr = Value::Array(vec![Value::Int(7), Value::Int(1), Value::Int(3)]);
+        let arr = Value::Array(vec![Value::Int(7), Value::Int(1), Value::Int(3)].into());
         let v = builtin_array_median(&[arr]).unwrap();
         match v {
             Value::Float(f) => assert!((f - 3.0).abs() < 1e-12),
@@ -63037,12 +64766,8 @@ mod stdlib_batch_tests_res_936_to_945 {
 
     #[test]
     fn array_median_even_length() {
-        let arr = Value::Array(vec![
-            Value::Int(1),
-            Value::Int(2),
-            Value::Int(3),
-            Value::Int(4),
-        ]);
+        let arr =
+            Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3), Value::Int(4)].into());
         let v = builtin_array_median(&[arr]).unwrap();
         match v {
             Value::Float(f) => assert!((f - 2.5).abs() < 1e-12),
@@ -63052,18 +64777,15 @@ mod stdlib_batch_tests_res_936_to_945 {
 
     #[test]
     fn array_median_empty_errors() {
-        let e = builtin_array_median(&[Value::Array(vec![])]).unwrap_err();
+        let e = builtin_array_median(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(e.contains("empty"), "err was: {}", e);
     }
 
     // RES-942: float-array reductions
     #[test]
     fn array_sum_float_basic() {
-        let arr = Value::Array(vec![
-            Value::Float(1.5),
-            Value::Float(2.5),
-            Value::Float(0.0),
-        ]);
+        let arr =
+            Value::Array(vec![Value::Float(1.5), Value::Float(2.5), Value::Float(0.0)].into());
         match builtin_array_sum_float(&[arr]).unwrap() {
             Value::Float(f) => assert!((f - 4.0).abs() < 1e-12),
             other => panic!("expected Float, got {:?}", other),
@@ -63072,7 +64794,7 @@ mod stdlib_batch_tests_res_936_to_945 {
 
     #[test]
     fn array_sum_float_empty_is_zero() {
-        match builtin_array_sum_float(&[Value::Array(vec![])]).unwrap() {
+        match builtin_array_sum_float(&[Value::Array(vec![].into())]).unwrap() {
             Value::Float(f) => assert_eq!(f, 0.0),
             other => panic!("expected Float, got {:?}", other),
         }
@@ -63080,7 +64802,7 @@ mod stdlib_batch_tests_res_936_to_945 {
 
     #[test]
     fn array_product_float_empty_is_one() {
-        match builtin_array_product_float(&[Value::Array(vec![])]).unwrap() {
+        match builtin_array_product_float(&[Value::Array(vec![].into())]).unwrap() {
             Value::Float(f) => assert_eq!(f, 1.0),
             other => panic!("expected Float, got {:?}", other),
         }
@@ -63088,11 +64810,8 @@ mod stdlib_batch_tests_res_936_to_945 {
 
     #[test]
     fn array_min_max_float_basic() {
-        let arr = Value::Array(vec![
-            Value::Float(3.0),
-            Value::Float(-1.0),
-            Value::Float(2.0),
-        ]);
+        let arr =
+            Value::Array(vec![Value::Float(3.0), Value::Float(-1.0), Value::Float(2.0)].into());
         match builtin_array_min_float(std::slice::from_ref(&arr)).unwrap() {
             Value::Float(f) => assert!((f - (-1.0)).abs() < 1e-12),
             other => panic!("expected Float, got {:?}", other),
@@ -63105,11 +64824,8 @@ mod stdlib_batch_tests_res_936_to_945 {
 
     #[test]
     fn array_min_float_propagates_nan() {
-        let arr = Value::Array(vec![
-            Value::Float(1.0),
-            Value::Float(f64::NAN),
-            Value::Float(2.0),
-        ]);
+        let arr =
+            Value::Array(vec![Value::Float(1.0), Value::Float(f64::NAN), Value::Float(2.0)].into());
         match builtin_array_min_float(&[arr]).unwrap() {
             Value::Float(f) => assert!(f.is_nan()),
             other => panic!("expected NaN Float, got {:?}", other),
@@ -63118,11 +64834,8 @@ mod stdlib_batch_tests_res_936_to_945 {
 
     #[test]
     fn array_average_float_basic() {
-        let arr = Value::Array(vec![
-            Value::Float(1.0),
-            Value::Float(2.0),
-            Value::Float(3.0),
-        ]);
+        let arr =
+            Value::Array(vec![Value::Float(1.0), Value::Float(2.0), Value::Float(3.0)].into());
         match builtin_array_average_float(&[arr]).unwrap() {
             Value::Float(f) => assert!((f - 2.0).abs() < 1e-12),
             other => panic!("expected Float, got {:?}", other),
@@ -63131,7 +64844,7 @@ mod stdlib_batch_tests_res_936_to_945 {
 
     #[test]
     fn array_min_float_empty_errors() {
-        let e = builtin_array_min_float(&[Value::Array(vec![])]).unwrap_err();
+        let e = builtin_array_min_float(&[Value::Array(vec![].into())]).unwrap_err();
         assert!(e.contains("empty"), "err was: {}", e);
     }
 
@@ -275,7 +275,7 @@ pub(crate) fn builtin_mat_mul(args: &[Value]) -> RResult<Value> {
             let a = extract_matrix("mat_mul", a)?;
             let b = extract_matrix("mat_mul", b)?;
             if a.is_empty() || b.is_empty() {
-                return Ok(Value::Array(vec![]));
+                return Ok(Value::Array(vec![].into()));
             }
             let m = a.len();
             let k = a[0].len();
@@ -391,7 +391,7 @@ pub(crate) fn builtin_mat_transpose(args: &[Value]) -> RResult<Value> {
         [a] => {
             let a = extract_matrix("mat_transpose", a)?;
             if a.is_empty() {
-                return Ok(Value::Array(vec![]));
+                return Ok(Value::Array(vec![].into()));
             }
             let ncols = a[0].len();
             for (i, row) in a.iter().enumerate() {
@@ -155,6 +155,8 @@ pub const KNOWN_CODES: &[&str] = &[
     "L0093", // function parameter named `result` — shadows the postcondition pseudo-variable
     "L0094", // consecutive `break` or `continue` statements — second is unreachable
     "L0095", // `match` with a single wildcard arm (`_ => ...`) — prefer an expression
+    "L0096", // `live` block with nothing in it that can fail — its retries never run
+    "L0097", // `live` retries that can never help (`retries(0)` with backoff, or a body that always fails)
 ];
 
 /// Return a human-readable explanation for a lint code, or `None` if unknown.
@@ -1118,6 +1120,24 @@ pub fn explain(code: &str) -> Option<&'static str> {
              Remove the `match` or add meaningful patterns.\n\
              Suppress: // resilient: allow L0095",
         ),
+        "L0096" => Some(
+            "L0096 — live block with nothing that can fail\n\
+             \n\
+             A `live` block retries its body when the body fails. When the body\n\
+             only binds, compares and returns values (no calls, indexing,\n\
+             arithmetic, asserts or invariants), it cannot fail, so the retry\n\
+             machinery never runs. Drop the `live` wrapper.\n\
+             Suppress: // resilient: allow L0096",
+        ),
+        "L0097" => Some(
+            "L0097 — unreachable live retries\n\
+             \n\
+             The retries of this `live` block can never help: either\n\
+             `retries(0)` allows no retry, so its `backoff(...)` schedule never\n\
+             applies, or the body starts with `assert(false)`, so every attempt\n\
+             fails the same way. Fix the retry settings or the body.\n\
+             Suppress: // resilient: allow L0097",
+        ),
         _ => None,
     }
 }
@@ -1788,6 +1808,10 @@ pub fn check(program: &Node, source: &str) -> Vec<Lint> {
     if t.has_single_wildcard_match {
         run_l0095_single_wildcard_match(program, &mut out);
     }
+    if t.has_live_block {
+        run_l0096_live_block_cannot_fail(program, &mut out);
+        run_l0097_unreachable_retries(program, &mut out);
+    }
     let safety_critical = safety_critical_mode();
     if safety_critical {
         for lint in out.iter_mut() {
@@ -3122,10 +3146,10 @@ fn recurse_children<F: FnMut(&Node)>(node: &Node, f: &mut F) {
             ..
         } => {
             f(body);
-            for r in requires {
+            for r in requires.iter() {
                 f(r);
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 f(e);
             }
         }
@@ -3663,10 +3687,10 @@ fn l0013_walk(node: &Node, out: &mut Vec<Lint>) {
             ensures,
             ..
         } => {
-            for req in requires {
+            for req in requires.iter() {
                 l0013_walk(req, out);
             }
-            for ens in ensures {
+            for ens in ensures.iter() {
                 l0013_walk(ens, out);
             }
             l0013_walk(body, out);
@@ -4311,6 +4335,9 @@ fn l0018_all_paths_return(node: &Node) -> bool {
         Node::IfStatement {
             alternative: None, ..
         } => false,
+        // `loop { ... }` / `while true { ... }` with no `break` never
+        // falls through; the typechecker's termination check decides.
+        Node::WhileStatement { .. } if crate::typechecker::node_terminates(node) => true,
         // A while/for loop body might not execute at all, so it doesn't
         // guarantee a return.
         Node::WhileStatement { .. } | Node::ForInStatement { .. } => false,
@@ -4322,22 +4349,18 @@ fn l0018_all_paths_return(node: &Node) -> bool {
 // L0019: format() argument count mismatch
 // ============================================================
 //
-// `format(template, args_array)` takes exactly two arguments.
+// `format(template, args...)` takes the values after the template,
+// or (older form) as a single array literal.
 // Fires when:
-//   (a) The call has != 2 arguments, OR
-//   (b) The template is a static string (no runtime interpolation)
-//       and args is an array literal, and the placeholder count
-//       doesn't match the array length.
+//   (a) The call has no template at all, OR
+//   (b) The template is a static string and the placeholder count
+//       doesn't match the number of values.
 //
 // Notes on AST shape:
-//   - A Resilient template like `"\{} \{}"` stores `\{` as an
-//     "unknown escape" in the lexer; string_interp's parse_parts
-//     converts `\{` → `{`, so the InterpolatedString's Literal
-//     parts already contain `{}` as the placeholder text.
-//   - A template with no braces (e.g. `"hello"`) is a plain
-//     StringLiteral; parse_template sees no placeholders.
-//   - Templates with runtime interpolation (`"{expr}"`) have
-//     Expr parts — arity is not statically checkable.
+//   - The parser keeps `format`'s template literal raw, so it is a
+//     StringInternLiteral (`\{` already unescaped to `{`), or a
+//     StringLiteral once named arguments have been lowered.
+//   - Templates built at runtime are not statically checkable.
 
 fn run_l0019_format_arity(program: &Node, out: &mut Vec<Lint>) {
     walk_l0019(program, out);
@@ -4348,6 +4371,7 @@ fn run_l0019_format_arity(program: &Node, out: &mut Vec<Lint>) {
 fn l0019_literal_template(node: &Node) -> Option<String> {
     match node {
         Node::StringLiteral { value, .. } => Some(value.clone()),
+        Node::StringInternLiteral { content, .. } => Some(content.clone()),
         Node::InterpolatedString { parts, .. } => {
             if parts
                 .iter()
@@ -4379,39 +4403,39 @@ fn walk_l0019(node: &Node, out: &mut Vec<Lint>) {
         && let Node::Identifier { name, .. } = function.as_ref()
         && name == "format"
     {
-        if arguments.len() != 2 {
+        if arguments.is_empty() {
             out.push(Lint {
                 code: "L0019".into(),
                 severity: Severity::Warning,
-                message: format!(
-                    "format() requires exactly 2 arguments (template, args_array) but {} {} supplied \
-                     — suppress with `// resilient: allow L0019`",
-                    arguments.len(),
-                    if arguments.len() == 1 { "was" } else { "were" },
-                ),
+                message: "format() requires a template argument \
+                     — suppress with `// resilient: allow L0019`"
+                    .to_string(),
                 line: span.start.line as u32,
                 column: span.start.column as u32,
             });
         } else if let Some(tmpl) = l0019_literal_template(&arguments[0])
-            && let Node::ArrayLiteral { items, .. } = &arguments[1]
             && let Ok(segments) = crate::format_builtin::parse_template(&tmpl)
         {
             let placeholders = segments
                 .iter()
                 .filter(|s| matches!(s, crate::format_builtin::FormatSegment::Placeholder(_)))
                 .count();
-            let array_len = items.len();
-            if placeholders != array_len {
+            let values = match &arguments[1..] {
+                [Node::ArrayLiteral { items, .. }] => items.len(),
+                rest => rest.len(),
+            };
+            if placeholders != values {
                 out.push(Lint {
                     code: "L0019".into(),
                     severity: Severity::Warning,
                     message: format!(
-                        "format() template has {} placeholder{} but args array has {} element{} \
+                        "format() template has {} placeholder{} but {} value{} {} passed \
                          — counts must match; suppress with `// resilient: allow L0019`",
                         placeholders,
                         if placeholders == 1 { "" } else { "s" },
-                        array_len,
-                        if array_len == 1 { "" } else { "s" },
+                        values,
+                        if values == 1 { "" } else { "s" },
+                        if values == 1 { "was" } else { "were" },
                     ),
                     line: span.start.line as u32,
                     column: span.start.column as u32,
@@ -7040,7 +7064,7 @@ fn run_l0076_result_in_requires(program: &Node, out: &mut Vec<Lint>) {
             ..
         } = &s.node
         {
-            for req in requires {
+            for req in requires.iter() {
                 if contains_result_identifier(req) {
                     out.push(Lint {
                         code: "L0076".into(),
@@ -7076,7 +7100,7 @@ fn run_l0077_ensures_result_void(program: &Node, out: &mut Vec<Lint>) {
             ..
         } = &s.node
         {
-            for ens in ensures {
+            for ens in ensures.iter() {
                 if contains_result_identifier(ens) {
                     out.push(Lint {
                         code: "L0077".into(),
@@ -7412,7 +7436,7 @@ fn run_l0086_empty_string_comparison(program: &Node, out: &mut Vec<Lint>) {
     });
 }
 
-fn walk_nodes<F: FnMut(&Node)>(node: &Node, f: &mut F) {
+pub(crate) fn walk_nodes<F: FnMut(&Node)>(node: &Node, f: &mut F) {
     f(node);
     recurse_children(node, &mut |child| walk_nodes(child, f));
 }
@@ -7727,6 +7751,120 @@ fn run_l0095_single_wildcard_match(program: &Node, out: &mut Vec<Lint>) {
     });
 }
 
+// ============================================================
+// L0096: live block with nothing that can fail
+// ============================================================
+
+fn run_l0096_live_block_cannot_fail(program: &Node, out: &mut Vec<Lint>) {
+    walk_nodes(program, &mut |node| {
+        if let Node::LiveBlock {
+            body,
+            invariants,
+            span,
+            ..
+        } = node
+            && invariants.is_empty()
+            && !can_fail(body)
+        {
+            out.push(Lint {
+                code: "L0096".into(),
+                message: "nothing in this `live` block can fail, so it never retries; \
+                           drop the `live` wrapper"
+                    .to_string(),
+                line: span.start.line as u32,
+                column: span.start.column as u32,
+                severity: Severity::Warning,
+            });
+        }
+    });
+}
+
+/// Operators that cannot raise a runtime error, whatever their operands.
+const NON_FAILING_OPERATORS: &[&str] = &["==", "!=", "<", ">", "<=", ">=", "&&", "||", "!"];
+
+/// Whether evaluating `node` could fail at runtime. Anything outside
+/// a short list of plainly safe forms counts as fallible, so the
+/// answer errs towards "yes".
+fn can_fail(node: &Node) -> bool {
+    let safe = match node {
+        Node::Block { .. }
+        | Node::LetStatement { .. }
+        | Node::ReturnStatement { .. }
+        | Node::Break { .. }
+        | Node::Continue { .. }
+        | Node::IfStatement { .. }
+        | Node::ExpressionStatement { .. }
+        | Node::Identifier { .. }
+        | Node::IntegerLiteral { .. }
+        | Node::FloatLiteral { .. }
+        | Node::StringLiteral { .. }
+        | Node::StringInternLiteral { .. }
+        | Node::CharLiteral { .. }
+        | Node::BooleanLiteral { .. } => true,
+        Node::Assignment { .. } => true,
+        Node::InfixExpression { operator, .. } | Node::PrefixExpression { operator, .. } => {
+            NON_FAILING_OPERATORS.contains(operator)
+        }
+        _ => false,
+    };
+    if !safe {
+        return true;
+    }
+    let mut found = false;
+    recurse_children(node, &mut |child| {
+        if !found {
+            found = can_fail(child);
+        }
+    });
+    found
+}
+
+// ============================================================
+// L0097: live retries that can never help
+// ============================================================
+
+fn run_l0097_unreachable_retries(program: &Node, out: &mut Vec<Lint>) {
+    walk_nodes(program, &mut |node| {
+        let Node::LiveBlock {
+            body,
+            backoff,
+            max_retries,
+            span,
+            ..
+        } = node
+        else {
+            return;
+        };
+        let message = if *max_retries == Some(0) && backoff.is_some() {
+            "`retries(0)` allows no retry, so this `backoff(...)` schedule never applies"
+        } else if starts_with_assert_false(body) {
+            "the body starts with `assert(false)`, so every retry fails the same way"
+        } else {
+            return;
+        };
+        out.push(Lint {
+            code: "L0097".into(),
+            message: message.to_string(),
+            line: span.start.line as u32,
+            column: span.start.column as u32,
+            severity: Severity::Warning,
+        });
+    });
+}
+
+fn starts_with_assert_false(body: &Node) -> bool {
+    let Node::Block { stmts, .. } = body else {
+        return false;
+    };
+    let first = match stmts.first() {
+        Some(Node::ExpressionStatement { expr, .. }) => expr.as_ref(),
+        Some(other) => other,
+        None => return false,
+    };
+    matches!(first, Node::Assert { condition, .. }
+        if matches!(condition.as_ref(), Node::BooleanLiteral { value: false, .. }))
+}
+
 // ============================================================
 // Tests
 // ============================================================
@@ -8903,15 +9041,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn l0018_silent_for_break_free_loop() {
+        // `loop` only exits through the `return`, so nothing falls off.
+        let src =
+            "fn f(int x) -> int {\n    loop { if x > 3 { return x; } x = x + 1; }\n}\nf(1);\n";
+        assert!(
+            !codes(src).contains(&"L0018".to_string()),
+            "L0018 must not fire for a loop with no break"
+        );
+    }
+
     // ---- L0019: format() arity mismatch ----
 
     #[test]
-    fn l0019_fires_on_missing_args_array() {
-        // format() called with only 1 argument (missing args array).
+    fn l0019_fires_on_missing_values() {
+        // A template alone is fine; one with a placeholder needs a value.
         let src = "fn f() { let _s = format(\"hello\"); }\nf();\n";
+        assert!(!codes(src).contains(&"L0019".to_string()));
+        let src = "fn f() { let _s = format(\"hello {}\"); }\nf();\n";
         assert!(
             codes(src).contains(&"L0019".to_string()),
-            "L0019 must fire when format() has only 1 argument; got {:?}",
+            "L0019 must fire when a placeholder has no value; got {:?}",
             codes(src)
         );
     }
@@ -11148,4 +11299,55 @@ mod tests {
             "L0095 must not fire for match with multiple arms"
         );
     }
+
+    // ── L0096 tests ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn l0096_fires_on_live_block_that_cannot_fail() {
+        let src = "fn f(int x) -> int { live { let y = x; return y; } return x; }\n";
+        assert!(
+            codes(src).contains(&"L0096".to_string()),
+            "L0096 must fire when nothing in the live block can fail; got {:?}",
+            codes(src)
+        );
+    }
+
+    #[test]
+    fn l0096_silent_when_the_body_calls_or_divides() {
+        for body in ["return g(x);", "return 10 / x;", "let y = x + 1; return y;"] {
+            let src = format!(
+                "fn g(int x) -> int {{ return x; }}\n\
+                 fn f(int x) -> int {{ live {{ {body} }} return x; }}\n"
+            );
+            assert!(
+                !codes(&src).contains(&"L0096".to_string()),
+                "L0096 must not fire for `{body}`"
+            );
+        }
+    }
+
+    // ── L0097 tests ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn l0097_fires_on_backoff_without_retries() {
+        let src = "fn f(int x) -> int {\n    live retries(0) backoff(base_ms=1, factor=2, max_ms=8) {\n        return 10 / x;\n    }\n    return x;\n}\n";
+        let hits: Vec<Lint> = lint(src)
+            .into_iter()
+            .filter(|l| l.code == "L0097")
+            .collect();
+        assert_eq!(hits.len(), 1, "{hits:?}");
+        assert!(hits[0].message.contains("backoff"), "{}", hits[0].message);
+    }
+
+    #[test]
+    fn l0097_fires_on_a_body_that_always_fails() {
+        let src = "fn f(int x) -> int { live { assert(false, \"down\"); return x; } return x; }\n";
+        assert!(
+            codes(src).contains(&"L0097".to_string()),
+            "{:?}",
+            codes(src)
+        );
+        let ok = "fn f(int x) -> int { live retries(2) { return 10 / x; } return x; }\n";
+        assert!(!codes(ok).contains(&"L0097".to_string()));
+    }
 }
@@ -95,7 +95,7 @@ fn collect_pairs(fn_name: &str, body: &Node, pairs: &mut HashMap<(String, String
                 let is_unlock = UNLOCK_FNS.contains(&name.as_str()) || name.starts_with("unlock_");
                 if (is_lock || is_unlock) && !arguments.is_empty() {
                     if let Node::Identifier { name: lk, .. } = &arguments[0] {
-                        acts.push((is_lock, lk.clone()));
+                        acts.push((is_lock, lk.to_string()));
                     }
                 }
             }
@@ -82,7 +82,7 @@ fn walk_priorities<'a>(
             ..
         } => {
             if let Node::Identifier { name, .. } = function.as_ref() {
-                if let Some(p) = table.get(name) {
+                if let Some(p) = table.get(name.as_str()) {
                     if p.priority < holding {
                         return Some((name.as_str(), p.priority));
                     }
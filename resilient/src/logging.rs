@@ -0,0 +1,279 @@
+//! Structured logging: `log_debug`, `log_info`, `log_warn`, `log_error`.
+//!
+//! Each builtin takes a message and an optional map of fields and
+//! writes one record to stderr, so logs never interleave with the
+//! program's `println` output:
+//!
+//! ```text
+//! log_info("request served", {"path" -> "/", "ms" -> 12});
+//! ```
+//!
+//! In the default text format that prints
+//! `[INFO] request served ms=12 path="/"`; with `--log-format json`
+//! it prints one JSON object per line,
+//! `{"level": "info", "msg": "request served", "fields": {"ms": 12, "path": "/"}}`.
+//! Fields are written in key order so the same call always produces
+//! the same line.
+//!
+//! Records below the minimum level are dropped before their fields are
+//! rendered. The level starts at `info` (`--log-level` changes it, and
+//! `off` silences every record) and a program can move it at run time
+//! with `set_log_level`, which returns the level it replaced so a
+//! noisy section can restore it afterwards.
+//!
+//! The level and format are per thread, like `fuel` and
+//! `memory_limit`: `rz` sets them on the CLI thread, embedders call
+//! [`set_log_level`] and [`set_log_format`] on theirs.
+
+use crate::{MapKey, Value};
+use std::cell::Cell;
+
+type RResult<T> = Result<T, String>;
+
+/// Severity of a log record, least severe first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    /// The level spelled `name` (`debug`, `info`, `warn` or `error`).
+    pub fn parse(name: &str) -> Option<Level> {
+        Some(match name {
+            "debug" => Level::Debug,
+            "info" => Level::Info,
+            "warn" => Level::Warn,
+            "error" => Level::Error,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+}
+
+/// How records are written to stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `[LEVEL] message key=value ...`
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+impl LogFormat {
+    /// The format spelled `name` (`text` or `json`).
+    pub fn parse(name: &str) -> Option<LogFormat> {
+        match name {
+            "text" => Some(LogFormat::Text),
+            "json" => Some(LogFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+thread_local! {
+    static MIN_LEVEL: Cell<Option<Level>> = const { Cell::new(Some(Level::Info)) };
+    static FORMAT: Cell<LogFormat> = const { Cell::new(LogFormat::Text) };
+}
+
+/// Drop records below `level` on this thread (`None` drops them all).
+pub fn set_log_level(level: Option<Level>) {
+    MIN_LEVEL.with(|l| l.set(level));
+}
+
+/// Write later records on this thread in `format`.
+pub fn set_log_format(format: LogFormat) {
+    FORMAT.with(|f| f.set(format));
+}
+
+fn enabled(level: Level) -> bool {
+    MIN_LEVEL.with(Cell::get).is_some_and(|min| level >= min)
+}
+
+pub(crate) fn builtin_log_debug(args: &[Value]) -> RResult<Value> {
+    log("log_debug", Level::Debug, args)
+}
+
+pub(crate) fn builtin_log_info(args: &[Value]) -> RResult<Value> {
+    log("log_info", Level::Info, args)
+}
+
+pub(crate) fn builtin_log_warn(args: &[Value]) -> RResult<Value> {
+    log("log_warn", Level::Warn, args)
+}
+
+pub(crate) fn builtin_log_error(args: &[Value]) -> RResult<Value> {
+    log("log_error", Level::Error, args)
+}
+
+fn log(builtin: &str, level: Level, args: &[Value]) -> RResult<Value> {
+    let (msg, fields) = match args {
+        [Value::String(msg)] => (msg, None),
+        [Value::String(msg), Value::Map(fields)] => (msg, Some(fields)),
+        [Value::String(_), other] => {
+            return Err(format!(
+                "{builtin}: fields must be a map, got {}",
+                crate::type_builtins::value_type_name(other)
+            ));
+        }
+        [other, ..] if args.len() <= 2 => {
+            return Err(format!(
+                "{builtin}: message must be a string, got {}",
+                crate::type_builtins::value_type_name(other)
+            ));
+        }
+        _ => {
+            return Err(format!(
+                "{builtin}: expected a message and an optional fields map, got {} args",
+                args.len()
+            ));
+        }
+    };
+    if !enabled(level) {
+        return Ok(Value::Void);
+    }
+    let mut fields: Vec<(String, &Value)> = fields
+        .map(|m| m.iter().map(|(k, v)| (key_name(k), v)).collect())
+        .unwrap_or_default();
+    fields.sort_by(|a, b| a.0.cmp(&b.0));
+    eprintln!("{}", render(FORMAT.with(Cell::get), level, msg, &fields));
+    Ok(Value::Void)
+}
+
+/// `set_log_level(level)`: change the minimum level for the rest of
+/// the run; returns the level it replaced.
+pub(crate) fn builtin_set_log_level(args: &[Value]) -> RResult<Value> {
+    let name = match args {
+        [Value::String(name)] => name,
+        [other] => {
+            return Err(format!(
+                "set_log_level: expected a level name, got {}",
+                crate::type_builtins::value_type_name(other)
+            ));
+        }
+        _ => {
+            return Err(format!("set_log_level: expected 1 arg, got {}", args.len()));
+        }
+    };
+    let level = match name.as_str() {
+        "off" => None,
+        other => Some(Level::parse(other).ok_or_else(|| {
+            format!(
+                "set_log_level: unknown level {other:?} (expected debug, info, warn, error or off)"
+            )
+        })?),
+    };
+    let previous = MIN_LEVEL.with(|l| l.replace(level));
+    Ok(Value::String(
+        previous.map_or("off", Level::name).to_string(),
+    ))
+}
+
+fn key_name(key: &MapKey) -> String {
+    match key {
+        MapKey::Str(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn render(format: LogFormat, level: Level, msg: &str, fields: &[(String, &Value)]) -> String {
+    match format {
+        LogFormat::Text => {
+            let mut line = format!("[{}] {}", level.label(), msg);
+            for (key, value) in fields {
+                let value = match value {
+                    Value::String(s) => crate::json_builtins::json_escape_string(s),
+                    other => other.to_string(),
+                };
+                line.push_str(&format!(" {key}={value}"));
+            }
+            line
+        }
+        LogFormat::Json => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(key, value)| {
+                    // Values JSON has no spelling for (NaN, functions,
+                    // structs) are written as their display string.
+                    let value = crate::json_builtins::serialize_value(value).unwrap_or_else(|_| {
+                        crate::json_builtins::json_escape_string(&value.to_string())
+                    });
+                    format!(
+                        "{}: {}",
+                        crate::json_builtins::json_escape_string(key),
+                        value
+                    )
+                })
+                .collect();
+            format!(
+                "{{\"level\": \"{}\", \"msg\": {}, \"fields\": {{{}}}}}",
+                level.name(),
+                crate::json_builtins::json_escape_string(msg),
+                fields.join(", ")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_render_as_text_and_json_lines() {
+        let path = Value::String("/a b".to_string());
+        let ms = Value::Int(12);
+        let fields = vec![("ms".to_string(), &ms), ("path".to_string(), &path)];
+        assert_eq!(
+            render(LogFormat::Text, Level::Warn, "slow request", &fields),
+            r#"[WARN] slow request ms=12 path="/a b""#
+        );
+        assert_eq!(
+            render(LogFormat::Json, Level::Warn, "slow \"request\"", &fields),
+            r#"{"level": "warn", "msg": "slow \"request\"", "fields": {"ms": 12, "path": "/a b"}}"#
+        );
+        let nan = Value::Float(f64::NAN);
+        assert_eq!(
+            render(
+                LogFormat::Json,
+                Level::Info,
+                "x",
+                &[("v".to_string(), &nan)]
+            ),
+            r#"{"level": "info", "msg": "x", "fields": {"v": "NaN"}}"#
+        );
+    }
+
+    #[test]
+    fn set_log_level_filters_and_returns_the_previous_level() {
+        set_log_level(Some(Level::Info));
+        assert!(!enabled(Level::Debug) && enabled(Level::Info));
+        let prev = builtin_set_log_level(&[Value::String("error".to_string())]).unwrap();
+        assert!(matches!(prev, Value::String(ref s) if s == "info"));
+        assert!(!enabled(Level::Warn) && enabled(Level::Error));
+        builtin_set_log_level(&[Value::String("off".to_string())]).unwrap();
+        assert!(!enabled(Level::Error));
+        let err = builtin_set_log_level(&[Value::String("loud".to_string())]).unwrap_err();
+        assert!(err.contains("unknown level \"loud\""), "{err}");
+        set_log_level(Some(Level::Info));
+    }
+}
@@ -22,6 +22,7 @@
 #![allow(clippy::collapsible_if)]
 
 use crate::Node;
+use crate::typechecker::CheckError;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy)]
@@ -77,7 +78,7 @@ fn diagnostic(source_path: &str, line: usize, fn_name: &str, message: &str) -> S
     )
 }
 
-pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
+pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), CheckError> {
     // RES-3857/RES-3858: enrolment comes only from the module-level
     // `@require_contracts` policy — fast-reject files without it.
     if !crate::contract_policy::module_requires_contracts() {
@@ -105,7 +106,8 @@ pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
                             line,
                             name,
                             "contains a while-loop and requires #[loop_bound(N)] (RES-3780 Tier 2)",
-                        ));
+                        )
+                        .into());
                     }
 
                     // If z3 feature is enabled, verify the bound
@@ -129,7 +131,7 @@ fn verify_loop_bounds(
     fn_name: &str,
     declared_bound: u32,
     source_path: &str,
-) -> Result<(), String> {
+) -> Result<(), CheckError> {
     if let Node::Program(stmts) = program {
         for stmt in stmts {
             if let Node::Function {
@@ -162,7 +164,7 @@ fn verify_while_loops_in_node(
     source_path: &str,
     fn_name: &str,
     requires: &[Node],
-) -> Result<(), String> {
+) -> Result<(), CheckError> {
     match node {
         Node::WhileStatement {
             condition,
@@ -217,7 +219,7 @@ fn verify_one_while_loop(
     fn_name: &str,
     span: crate::span::Span,
     requires: &[Node],
-) -> Result<(), String> {
+) -> Result<(), CheckError> {
     const LOOP_BOUND_TIMEOUT_MS: u32 = 1000;
     let line = span.start.line;
 
@@ -261,10 +263,10 @@ fn verify_one_while_loop(
             let cx = counterexample
                 .map(|c| format!(" (counterexample: {})", c))
                 .unwrap_or_default();
-            Err(format!(
+            Err(crate::typechecker::refutation(format!(
                 "{}:{}:0: error[loop_bound]: loop in `{}` may exceed its declared bound of {} iterations{}",
                 source_path, line, fn_name, declared_bound, cx
-            ))
+            )))
         }
         None => {
             eprintln!(
@@ -394,7 +396,9 @@ mod tests {
         let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {errs:?}");
 
-        let err = check(&prog, "<test>").expect_err("missing loop_bound must be rejected");
+        let err = check(&prog, "<test>")
+            .expect_err("missing loop_bound must be rejected")
+            .message;
         assert!(
             err.contains("error[loop_bound]") && err.contains("requires #[loop_bound(N)]"),
             "expected loop_bound missing error, got: {err}"
@@ -492,7 +496,8 @@ mod tests {
         assert!(errs.is_empty(), "parse errors: {errs:?}");
 
         let err = check(&prog, "<test>")
-            .expect_err("untagged while-loop under @require_contracts must need a bound");
+            .expect_err("untagged while-loop under @require_contracts must need a bound")
+            .message;
         assert!(
             err.contains("error[loop_bound]")
                 && err.contains("`hand_written`")
@@ -740,7 +745,9 @@ mod tests {
         assert!(errs.is_empty(), "parse errors: {errs:?}");
 
         let result = check(&prog, "<test>");
-        let err = result.expect_err("Z3-disprovable bound violation must be a hard error");
+        let err = result
+            .expect_err("Z3-disprovable bound violation must be a hard error")
+            .message;
         assert!(
             err.contains("error[loop_bound]") && err.contains("may exceed its declared bound"),
             "expected loop_bound violation error, got: {err}"
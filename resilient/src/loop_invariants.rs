@@ -130,10 +130,10 @@ fn walk(node: &Node, in_loop: bool, source_path: &str) -> Result<(), String> {
 pub(crate) fn typecheck_invariant_statement(
     tc: &mut crate::typechecker::TypeChecker,
     expr: &Node,
-) -> Result<crate::typechecker::Type, String> {
+) -> Result<crate::typechecker::Type, crate::typechecker::CheckError> {
     let t = tc.check_node(expr)?;
     if t != crate::typechecker::Type::Bool && t != crate::typechecker::Type::Any {
-        return Err(format!("Loop invariant must be a boolean, got {}", t));
+        return Err(format!("Loop invariant must be a boolean, got {}", t).into());
     }
     Ok(crate::typechecker::Type::Void)
 }
@@ -270,8 +270,8 @@ mod tests {
                     walk_count(s, out);
                 }
             }
-            Node::Function { body, .. }
-            | Node::WhileStatement { body, .. }
+            Node::Function { body, .. } => walk_count(body, out),
+            Node::WhileStatement { body, .. }
             | Node::ForInStatement { body, .. }
             | Node::LiveBlock { body, .. } => walk_count(body, out),
             Node::IfStatement {
@@ -3,7 +3,9 @@
 //! Implements `textDocument/didOpen` and `textDocument/didChange`:
 //! each time either fires, we parse the buffer, run the typechecker,
 //! and publish diagnostics with source ranges derived from
-//! RES-077's per-statement `Spanned<Node>` wrappers.
+//! RES-077's per-statement `Spanned<Node>` wrappers. Changes arrive
+//! as incremental edits and only reparse the top-level items they
+//! touch (see `incremental_parse`).
 //!
 //! The same server also exposes best-effort hover, completion,
 //! go-to-definition, references, semantic tokens, inlay hints, rename,
@@ -37,6 +39,7 @@ use tower_lsp::lsp_types::{
 };
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
+use crate::incremental_parse::{self, ParsedDocument};
 use crate::{Node, builtin_names, compute_semantic_tokens, parse, typechecker};
 
 /// RES-186: one workspace-level symbol entry. A flat vec of these
@@ -82,6 +85,9 @@ pub struct Backend {
     /// Same mutex discipline as `documents`: synchronous lock,
     /// never held across `.await`.
     documents_text: Mutex<HashMap<Url, String>>,
+    /// URI → per-item parse of the open document, which `did_change`
+    /// edits in place so only the touched top-level items reparse.
+    parsed: Mutex<HashMap<Url, ParsedDocument>>,
     /// RES-186: per-file symbol index. Keyed by `Url` so a
     /// `did_save` can replace just that file's entries instead of
     /// rebuilding the whole thing. The vec-of-entries form inside
@@ -116,6 +122,7 @@ impl Backend {
             client,
             documents: Mutex::new(HashMap::new()),
             documents_text: Mutex::new(HashMap::new()),
+            parsed: Mutex::new(HashMap::new()),
             workspace_index: Mutex::new(HashMap::new()),
             workspace_root: Mutex::new(None),
             workspace_index_built: Mutex::new(false),
@@ -124,17 +131,23 @@ impl Backend {
         }
     }
 
-    /// RES-074: analyze `text` for parser + typechecker errors and
-    /// publish them as LSP diagnostics against `uri`. Called from
-    /// both `did_open` and `did_change`.
-    async fn publish_analysis(&self, uri: Url, text: String) {
+    /// RES-074: analyze the document for parser + typechecker errors
+    /// and publish them as LSP diagnostics against `uri`. Called from
+    /// both `did_open` and `did_change` once `parsed` holds the
+    /// document's current tree.
+    async fn publish_analysis(&self, uri: Url) {
         let mut diagnostics = Vec::new();
 
         // Step 1: parse. Parser::record_error formats errors with a
         // bare `<line>:<col>:` prefix; route them through
         // extract_range_and_message (RES-089) so they land at the
         // right LSP Range instead of the file's first character.
-        let (program, parser_errors) = parse(&text);
+        let Some((text, (program, parser_errors))) = self.parsed.lock().ok().and_then(|map| {
+            map.get(&uri)
+                .map(|doc| (doc.text().to_string(), doc.program()))
+        }) else {
+            return;
+        };
 
         // RES-185: cache the freshly-parsed AST so document-symbol
         // (and future cursor-aware) handlers don't have to re-parse.
@@ -208,6 +221,28 @@ impl Backend {
     }
 }
 
+/// Turn an LSP change `range` into a char-offset edit of `text`.
+/// Columns count chars, as everywhere else in this server; positions
+/// past the end of a line or of the text clamp to it.
+fn text_edit(text: &str, range: Range, new_text: String) -> incremental_parse::TextEdit {
+    let offset = |pos: Position| {
+        let mut offset = 0;
+        for (i, line) in text.split('\n').enumerate() {
+            let len = line.chars().count();
+            if i == pos.line as usize {
+                return offset + len.min(pos.character as usize);
+            }
+            offset += len + 1;
+        }
+        offset - 1
+    };
+    incremental_parse::TextEdit {
+        start: offset(range.start),
+        end: offset(range.end),
+        text: new_text,
+    }
+}
+
 /// RES-074: LSP uses 0-indexed line/column; RES-077's span is
 /// 1-indexed. Subtract 1 (clamped) and build a zero-width range.
 fn point_range(line_0based: u32, col_0based: u32) -> Range {
@@ -365,7 +400,7 @@ pub(crate) fn identifier_at(src: &str, pos: Position) -> Option<(String, Range)>
             continue;
         }
         if let Token::Identifier(name) = tok {
-            return Some((name, span_to_range(span)));
+            return Some((name.to_string(), span_to_range(span)));
         }
         // Non-identifier token at the cursor — no jump.
         return None;
@@ -1011,10 +1046,10 @@ fn walk_variable_refs(
             for (_, name) in parameters {
                 bind_variable(symbols, scopes, name, fn_range);
             }
-            for req in requires {
+            for req in requires.iter() {
                 walk_variable_refs(req, symbols, scopes);
             }
-            for ensure in ensures {
+            for ensure in ensures.iter() {
                 walk_variable_refs(ensure, symbols, scopes);
             }
             walk_variable_refs(body, symbols, scopes);
@@ -1163,10 +1198,10 @@ fn walk_variable_refs(
             for (_, name) in parameters {
                 bind_variable(symbols, scopes, name, fn_range);
             }
-            for req in requires {
+            for req in requires.iter() {
                 walk_variable_refs(req, symbols, scopes);
             }
-            for ensure in ensures {
+            for ensure in ensures.iter() {
                 walk_variable_refs(ensure, symbols, scopes);
             }
             walk_variable_refs(body, symbols, scopes);
@@ -1195,7 +1230,7 @@ fn collect_qualified_identifier_sites(src: &str, target: &str) -> Vec<Range> {
             i += 1;
             continue;
         };
-        let mut full_name = name.clone();
+        let mut full_name = name.to_string();
         let start_span = *span;
         let mut end_span = *span;
         let mut j = i;
@@ -1243,10 +1278,10 @@ fn walk_identifier_refs(node: &Node, target: &str, out: &mut Vec<Range>) {
             ..
         } => {
             walk_identifier_refs(body, target, out);
-            for r in requires {
+            for r in requires.iter() {
                 walk_identifier_refs(r, target, out);
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 walk_identifier_refs(e, target, out);
             }
         }
@@ -1731,10 +1766,10 @@ fn walk_call_sites(node: &Node, target: &str, out: &mut Vec<Range>) {
             ..
         } => {
             walk_call_sites(body, target, out);
-            for r in requires {
+            for r in requires.iter() {
                 walk_call_sites(r, target, out);
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 walk_call_sites(e, target, out);
             }
         }
@@ -1873,10 +1908,10 @@ fn walk_call_sites(node: &Node, target: &str, out: &mut Vec<Range>) {
             ..
         } => {
             walk_call_sites(body, target, out);
-            for r in requires {
+            for r in requires.iter() {
                 walk_call_sites(r, target, out);
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 walk_call_sites(e, target, out);
             }
         }
@@ -2197,7 +2232,8 @@ fn filter_workspace_symbols(
 /// RES-187: the semantic-tokens legend. The order here MUST match
 /// the `sem_tok::*` token-type indices declared in `lib.rs`
 /// (KEYWORD=0 … OPERATOR=8) and the modifier bit positions
-/// (MOD_DECLARATION=bit0, MOD_READONLY=bit1). Any drift between
+/// (MOD_DECLARATION=bit0, MOD_READONLY=bit1, MOD_CONTRACT=bit2).
+/// Any drift between
 /// these two tables yields mis-colored output in every client.
 fn semantic_tokens_legend() -> SemanticTokensLegend {
     // Indices (0..=8): keyword, function, variable, parameter,
@@ -2213,10 +2249,12 @@ fn semantic_tokens_legend() -> SemanticTokensLegend {
         SemanticTokenType::COMMENT,
         SemanticTokenType::OPERATOR,
     ];
-    // Bit positions: declaration=bit0, readonly=bit1.
+    // Bit positions: declaration=bit0, readonly=bit1, contract=bit2
+    // (custom: contract keywords, so themes can set them apart).
     let token_modifiers = vec![
         SemanticTokenModifier::DECLARATION,
         SemanticTokenModifier::READONLY,
+        SemanticTokenModifier::new("contract"),
     ];
     SemanticTokensLegend {
         token_types,
@@ -2346,10 +2384,10 @@ fn walk_call_hints(node: &Node, fns: &HashMap<&str, Vec<&str>>, out: &mut Vec<In
             ..
         } => {
             walk_call_hints(body, fns, out);
-            for r in requires {
+            for r in requires.iter() {
                 walk_call_hints(r, fns, out);
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 walk_call_hints(e, fns, out);
             }
         }
@@ -2660,7 +2698,7 @@ impl LanguageServer for Backend {
             server_info: None,
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                    TextDocumentSyncKind::FULL,
+                    TextDocumentSyncKind::INCREMENTAL,
                 )),
                 // RES-185: advertise the document-symbol handler so
                 // editors' outline views light up. `OneOf::Left(true)`
@@ -2750,22 +2788,32 @@ impl LanguageServer for Backend {
 
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
         let uri = params.text_document.uri.clone();
-        let text = params.text_document.text;
-        self.publish_analysis(uri, text).await;
+        let doc = ParsedDocument::parse(&params.text_document.text);
+        if let Ok(mut map) = self.parsed.lock() {
+            map.insert(uri.clone(), doc);
+        }
+        self.publish_analysis(uri).await;
     }
 
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
-        // We registered as TextDocumentSyncKind::FULL, so each
-        // change message includes the whole buffer in the first
-        // content change.
+        // We registered as TextDocumentSyncKind::INCREMENTAL: each
+        // change carries a range to splice in, applied in order. A
+        // change without a range replaces the whole buffer.
         let uri = params.text_document.uri.clone();
-        let text = params
-            .content_changes
-            .into_iter()
-            .next()
-            .map(|c| c.text)
-            .unwrap_or_default();
-        self.publish_analysis(uri, text).await;
+        if let Ok(mut map) = self.parsed.lock() {
+            for change in params.content_changes {
+                match (change.range, map.get_mut(&uri)) {
+                    (Some(range), Some(doc)) => {
+                        let edit = text_edit(doc.text(), range, change.text);
+                        doc.edit(&edit);
+                    }
+                    _ => {
+                        map.insert(uri.clone(), ParsedDocument::parse(&change.text));
+                    }
+                }
+            }
+        }
+        self.publish_analysis(uri).await;
     }
 
     /// RES-185: clear the cached AST for the closed document so
@@ -2782,6 +2830,9 @@ impl LanguageServer for Backend {
         if let Ok(mut tmap) = self.documents_text.lock() {
             tmap.remove(&params.text_document.uri);
         }
+        if let Ok(mut pmap) = self.parsed.lock() {
+            pmap.remove(&params.text_document.uri);
+        }
     }
 
     /// RES-186: refresh the workspace symbol index entry for the
@@ -4478,12 +4529,19 @@ mod tests {
             legend.token_types[sem_tok::OPERATOR as usize],
             SemanticTokenType::OPERATOR
         );
-        // Modifier bit positions: bit 0 = declaration, bit 1 = readonly.
+        // Modifier bit positions: bit 0 = declaration, bit 1 = readonly,
+        // bit 2 = contract.
         assert_eq!(
             legend.token_modifiers[0],
             SemanticTokenModifier::DECLARATION
         );
         assert_eq!(legend.token_modifiers[1], SemanticTokenModifier::READONLY);
+        assert_eq!(
+            1 << 2,
+            sem_tok::MOD_CONTRACT,
+            "contract modifier must sit at bit 2"
+        );
+        assert_eq!(legend.token_modifiers[2].as_str(), "contract");
     }
 
     /// `semantic_tokens_from_wire` must unpack an n-tuple u32 stream
@@ -104,7 +104,7 @@ fn lower_node(node: &mut Node, macro_names: &std::collections::HashSet<String>)
             }
 
             if let Node::Identifier { name, .. } = function.as_ref() {
-                if macro_names.contains(name) {
+                if macro_names.contains(name.as_str()) {
                     let arg_strs: Vec<String> = arguments.iter().map(node_to_source).collect();
                     if let Some(expanded) = expand(name, &arg_strs) {
                         if let Some(expanded_node) = crate::parse_single_expression(&expanded) {
@@ -119,7 +119,7 @@ fn lower_node(node: &mut Node, macro_names: &std::collections::HashSet<String>)
                 lower_node(&mut item.node, macro_names);
             }
         }
-        Node::Function { body, .. } => lower_node(body, macro_names),
+        Node::Function { body, .. } => lower_node(std::sync::Arc::make_mut(body), macro_names),
         Node::Block { stmts, .. } => {
             for s in stmts.iter_mut() {
                 lower_node(s, macro_names);
@@ -184,7 +184,7 @@ fn node_to_source(node: &Node) -> String {
         Node::FloatLiteral { value, .. } => value.to_string(),
         Node::BooleanLiteral { value, .. } => value.to_string(),
         Node::StringLiteral { value, .. } => format!("\"{}\"", value.replace('"', "\\\"")),
-        Node::Identifier { name, .. } => name.clone(),
+        Node::Identifier { name, .. } => name.to_string(),
         Node::InfixExpression {
             left,
             operator,
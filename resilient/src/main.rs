@@ -7,6 +7,7 @@
 //! as a Rust library. This file's only job is to call into that
 //! library's CLI entry point.
 
+#[cfg(not(target_os = "wasi"))]
 fn main() {
     // RES-4190: 16 MiB was enough headroom for the parser's own
     // recursion, but the typechecker's `check_node` match arm is much
@@ -17,11 +18,27 @@ fn main() {
     // MAX_CHECK_DEPTH in typechecker.rs) parser-accepted AST
     // typechecks cleanly instead of aborting.
     const STACK_SIZE: usize = 96 * 1024 * 1024;
+    // Evaluation stops with a catchable `RecursionLimit` this far short
+    // of the end of the stack, leaving room for the builtins and
+    // error reporting that run past the last guarded step.
+    const STACK_MARGIN: usize = 16 * 1024 * 1024;
     let builder = std::thread::Builder::new().stack_size(STACK_SIZE);
     let handler = builder
-        .spawn(resilient::run_cli)
+        .spawn(|| {
+            resilient::stack_guard::set_stack_budget(Some(STACK_SIZE - STACK_MARGIN));
+            resilient::run_cli()
+        })
         .expect("failed to spawn CLI thread");
     if let Err(e) = handler.join() {
         std::panic::resume_unwind(e);
     }
 }
+
+/// WASI has no threads to grow the stack with; `build.rs` links the
+/// binary with a 32 MiB stack instead, and the guard keeps the same
+/// 16 MiB margin below it.
+#[cfg(target_os = "wasi")]
+fn main() {
+    resilient::stack_guard::set_stack_budget(Some(16 * 1024 * 1024));
+    resilient::run_cli()
+}
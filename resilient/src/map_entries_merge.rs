@@ -43,10 +43,10 @@ fn entries_impl(name: &str, args: &[Value]) -> RResult<Value> {
                 .into_iter()
                 .map(|k| {
                     let v = m.get(k).expect("key must exist — iter over the same map");
-                    Value::Array(vec![k.to_value(), v.clone()])
+                    Value::Array(vec![k.to_value(), v.clone()].into())
                 })
                 .collect();
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         }
         [a] => Err(format!("{}: expected a Map, got {}", name, a)),
         _ => Err(format!("{}: expected 1 argument, got {}", name, args.len())),
@@ -128,7 +128,7 @@ mod tests {
         for (k, v) in pairs {
             m.insert(k.clone(), v.clone());
         }
-        Value::Map(m)
+        Value::Map(m.into())
     }
 
     fn ki(n: i64) -> MapKey {
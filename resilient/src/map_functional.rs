@@ -55,7 +55,7 @@ pub(crate) fn builtin_map_filter(interp: &mut Interpreter, args: &[Value]) -> RR
             }
         }
     }
-    Ok(Value::Map(out))
+    Ok(Value::Map(out.into()))
 }
 
 /// `map_map_values(m, fn) -> Map`
@@ -89,7 +89,7 @@ pub(crate) fn builtin_map_map_values(interp: &mut Interpreter, args: &[Value]) -
         let new_val = interp.apply_function(f, vec![k_val, v.clone()])?;
         out.insert(k.clone(), new_val);
     }
-    Ok(Value::Map(out))
+    Ok(Value::Map(out.into()))
 }
 
 /// `map_for_each(m, fn) -> Void`
@@ -139,9 +139,9 @@ pub(crate) fn builtin_map_to_pairs(args: &[Value]) -> RResult<Value> {
         [Value::Map(m)] => {
             let pairs: Vec<Value> = m
                 .iter()
-                .map(|(k, v)| Value::Array(vec![map_key_to_value(k), v.clone()]))
+                .map(|(k, v)| Value::Array(vec![map_key_to_value(k), v.clone()].into()))
                 .collect();
-            Ok(Value::Array(pairs))
+            Ok(Value::Array(pairs.into()))
         }
         [other] => Err(format!("map_to_pairs: expected a Map, got {other}")),
         _ => Err(format!(
@@ -172,7 +172,7 @@ pub(crate) fn builtin_map_invert(args: &[Value]) -> RResult<Value> {
                 let new_val = map_key_to_value(k);
                 out.insert(new_key, new_val);
             }
-            Ok(Value::Map(out))
+            Ok(Value::Map(out.into()))
         }
         [other] => Err(format!("map_invert: expected a Map, got {other}")),
         _ => Err(format!(
@@ -1519,7 +1519,8 @@ fn tool_format(args: &Value) -> Result<String, String> {
             parse_errors.join("\n")
         ));
     }
-    let formatted = crate::formatter::Formatter::format(&program);
+    let formatted =
+        crate::formatter::Formatter::format_source(src, &program, crate::formatter::DEFAULT_INDENT);
     Ok(formatted)
 }
 
@@ -2177,7 +2178,7 @@ fn collect_call_targets(node: &crate::Node, out: &mut std::collections::HashSet<
             ..
         } => {
             if let crate::Node::Identifier { name, .. } = function.as_ref() {
-                out.insert(name.clone());
+                out.insert(name.to_string());
             }
             for a in arguments {
                 collect_call_targets(a, out);
@@ -0,0 +1,172 @@
+//! `--max-memory`: an approximate cap on the data a program keeps
+//! alive.
+//!
+//! Every environment frame keeps a running estimate of what its
+//! bindings hold — strings and byte buffers by length, collections
+//! element by element, plus a fixed cost per binding — and moves the
+//! difference into a per-thread total as bindings change and frames
+//! drop. A collection is measured once and remembers the result (see
+//! `shared`); index assignments and `xs = push(xs, v)` update it by
+//! what they changed, so a loop that grows or rewrites one binding
+//! pays per element written, not per element held. The interpreter checks the total next to its step budget
+//! (see `fuel`), so the first evaluation step after the total passes
+//! the cap fails with a typed `OutOfMemory` error. Unlike a spent step
+//! budget this is an ordinary fault: a `live` block that retries rolls
+//! its bindings back, which can bring the total under the cap again.
+//!
+//! Struct fields shared behind an `Rc` count once per binding that
+//! holds them, so the estimate errs high. Temporaries that are never
+//! bound are not counted. Nothing is measured while no cap is set,
+//! unless a reader of the estimate such as `--metrics-port` asked for
+//! it with [`set_tracking`].
+
+use crate::{EnumValuePayload, MapKey, Value};
+use std::cell::Cell;
+use std::mem::size_of;
+
+thread_local! {
+    static LIMIT: Cell<Option<usize>> = const { Cell::new(None) };
+    static TRACKING: Cell<bool> = const { Cell::new(false) };
+    static USED: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Cap the bytes bound in environments on this thread at `limit`
+/// (`None` removes the cap). Bindings made while no cap was set are
+/// not counted.
+pub fn set_max_memory(limit: Option<usize>) {
+    LIMIT.with(|l| l.set(limit));
+}
+
+/// Keep the estimate on this thread whether or not a cap is set.
+/// Bindings made while neither was on are not counted.
+pub fn set_tracking(on: bool) {
+    TRACKING.with(|t| t.set(on));
+}
+
+/// Whether bindings are being counted.
+#[inline]
+pub(crate) fn measuring() -> bool {
+    LIMIT.with(Cell::get).is_some() || TRACKING.with(Cell::get)
+}
+
+/// The current estimate, in bytes.
+pub fn in_use() -> usize {
+    USED.with(Cell::get)
+}
+
+/// Start a fresh total, as at the beginning of a run. Frames from an
+/// earlier run can outlive it (a function value keeps its defining
+/// frame alive), so a new run does not inherit their share.
+pub(crate) fn reset() {
+    USED.with(|u| u.set(0));
+}
+
+/// Run `f` with nothing measured, so the bindings it makes are never
+/// counted: the runtime's own data (the prelude), not the program's.
+pub(crate) fn uncounted<T>(f: impl FnOnce() -> T) -> T {
+    let limit = LIMIT.with(|l| l.replace(None));
+    let tracking = TRACKING.with(|t| t.replace(false));
+    let out = f();
+    LIMIT.with(|l| l.set(limit));
+    TRACKING.with(|t| t.set(tracking));
+    out
+}
+
+/// Fail with `OutOfMemory` once the estimate is over the cap.
+#[inline]
+pub(crate) fn check() -> Result<(), String> {
+    let Some(limit) = LIMIT.with(Cell::get) else {
+        return Ok(());
+    };
+    let used = in_use();
+    if used > limit {
+        return Err(format!(
+            "OutOfMemory: program data reached ~{used} bytes, over the limit of {limit} bytes"
+        ));
+    }
+    Ok(())
+}
+
+/// What binding `value` costs: its own heap data plus the binding's
+/// slot. Zero while nothing is measured, so frames stay unaccounted.
+pub(crate) fn binding_footprint(value: &Value) -> usize {
+    if !measuring() {
+        return 0;
+    }
+    size_of::<(crate::Symbol, Value)>() + heap_size(value)
+}
+
+/// Move a frame's change in footprint into the thread total.
+pub(crate) fn adjust(added: usize, removed: usize) {
+    USED.with(|u| u.set(u.get().saturating_add(added).saturating_sub(removed)));
+}
+
+/// Heap bytes owned by `value`, not counting `value` itself.
+pub(crate) fn heap_size(value: &Value) -> usize {
+    match value {
+        Value::String(s) => s.len(),
+        Value::Bytes(b) => b.len(),
+        Value::Array(items) => items.footprint(|items| values_size(items)),
+        Value::Tuple(items) | Value::TailCall(items) => values_size(items),
+        Value::Struct { name, fields } => name.len() + fields_size(fields),
+        Value::Result { payload, .. } => size_of::<Value>() + heap_size(payload),
+        Value::Option(Some(inner)) | Value::Return(inner) | Value::BreakWith(inner) => {
+            size_of::<Value>() + heap_size(inner)
+        }
+        Value::Map(map) => map.footprint(|map| map.iter().map(|(k, v)| entry_size(k, v)).sum()),
+        Value::Set(set) => {
+            set.footprint(|set| set.iter().map(|k| size_of::<MapKey>() + key_size(k)).sum())
+        }
+        Value::EnumVariant {
+            type_name,
+            variant,
+            payload,
+        } => {
+            type_name.len()
+                + variant.len()
+                + match payload {
+                    EnumValuePayload::None => 0,
+                    EnumValuePayload::Named(fields) => fields_size(fields),
+                    EnumValuePayload::Tuple(items) => values_size(items),
+                }
+        }
+        Value::Function(_) => size_of::<crate::FunctionValue>(),
+        Value::Closure { upvalues, .. } => values_size(upvalues),
+        _ => 0,
+    }
+}
+
+fn values_size(items: &[Value]) -> usize {
+    items.iter().map(element_size).sum()
+}
+
+fn fields_size(fields: &[(String, Value)]) -> usize {
+    fields
+        .iter()
+        .map(|(name, v)| size_of::<(String, Value)>() + name.len() + heap_size(v))
+        .sum()
+}
+
+/// A footprint that was `known` before a write that `change`d it by
+/// `(added, removed)` bytes; unknown if either is.
+pub(crate) fn grown(known: Option<usize>, change: Option<(usize, usize)>) -> Option<usize> {
+    let (added, removed) = change?;
+    Some(known?.saturating_add(added).saturating_sub(removed))
+}
+
+/// What one array element costs.
+pub(crate) fn element_size(value: &Value) -> usize {
+    size_of::<Value>() + heap_size(value)
+}
+
+/// What one map entry costs.
+pub(crate) fn entry_size(key: &MapKey, value: &Value) -> usize {
+    size_of::<(MapKey, Value)>() + key_size(key) + heap_size(value)
+}
+
+fn key_size(key: &MapKey) -> usize {
+    match key {
+        MapKey::Str(s) => s.len(),
+        MapKey::Int(_) | MapKey::Bool(_) => 0,
+    }
+}
@@ -0,0 +1,278 @@
+//! `--metrics-port [HOST:]PORT`: runtime counters in Prometheus text
+//! format.
+//!
+//! While the program runs, a background thread answers `GET /metrics`
+//! with what the interpreter has done so far:
+//!
+//! ```text
+//! resilient_statements_total 1204
+//! resilient_live_retries_total 3
+//! resilient_live_exhaustions_total 0
+//! resilient_memory_bytes 5120
+//! resilient_actor_mailbox_depth{pid="1"} 2
+//! resilient_breaker_state{line="12",state="closed"} 0
+//! resilient_breaker_state{line="12",state="half_open"} 1
+//! resilient_breaker_state{line="12",state="open"} 0
+//! ```
+//!
+//! A `live` block is the program's circuit breaker: `closed` after an
+//! attempt succeeds, `half_open` while it retries after a fault, and
+//! `open` once it gave up. Blocks are keyed by the line they start on
+//! and appear after they first run.
+//!
+//! The interpreter's own state is per thread (see `fuel` and
+//! `memory_limit`), so it publishes into the process-wide cells below
+//! as it goes and the server thread only ever reads those. Nothing is
+//! published while no port is set.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write as _};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering::Relaxed};
+
+use crate::Node;
+use crate::eval_observer::{EvalObserver, Scope};
+
+/// Where a `live` block stands, as a breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BreakerState {
+    Closed,
+    HalfOpen,
+    Open,
+}
+
+impl BreakerState {
+    const ALL: [BreakerState; 3] = [Self::Closed, Self::HalfOpen, Self::Open];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::HalfOpen => "half_open",
+            Self::Open => "open",
+        }
+    }
+}
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static STATEMENTS: AtomicU64 = AtomicU64::new(0);
+static MEMORY: AtomicUsize = AtomicUsize::new(0);
+static MAILBOXES: Mutex<BTreeMap<u64, usize>> = Mutex::new(BTreeMap::new());
+static BREAKERS: Mutex<BTreeMap<usize, BreakerState>> = Mutex::new(BTreeMap::new());
+
+/// Whether the interpreter should publish its counters.
+#[inline]
+pub(crate) fn active() -> bool {
+    ACTIVE.load(Relaxed)
+}
+
+/// Counts each evaluated statement and refreshes the memory estimate.
+pub(crate) struct Observer;
+
+impl EvalObserver for Observer {
+    fn node_enter(&mut self, node: &Node, _scope: &Scope<'_>, _function: &str) {
+        if is_statement(node) {
+            STATEMENTS.fetch_add(1, Relaxed);
+        }
+        MEMORY.store(crate::memory_limit::in_use(), Relaxed);
+    }
+}
+
+/// Whether `node` is a statement. Blocks also report the declarations
+/// in them (`fn`, `struct` and the like), which run nothing.
+fn is_statement(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::LetStatement { .. }
+            | Node::StaticLet { .. }
+            | Node::Const { .. }
+            | Node::Assignment { .. }
+            | Node::ReturnStatement { .. }
+            | Node::Break { .. }
+            | Node::Continue { .. }
+            | Node::IfStatement { .. }
+            | Node::WhileStatement { .. }
+            | Node::ForInStatement { .. }
+            | Node::FieldAssignment { .. }
+            | Node::IndexAssignment { .. }
+            | Node::LiveBlock { .. }
+            | Node::Assert { .. }
+            | Node::ExpressionStatement { .. }
+    )
+}
+
+/// Record `pid`'s mailbox depth; `None` once the actor is gone.
+pub(crate) fn mailbox_depth(pid: u64, depth: Option<usize>) {
+    let mut mailboxes = MAILBOXES.lock().unwrap_or_else(|e| e.into_inner());
+    match depth {
+        Some(depth) => mailboxes.insert(pid, depth),
+        None => mailboxes.remove(&pid),
+    };
+}
+
+/// Record the state of the `live` block that starts on `line`.
+pub(crate) fn breaker(line: usize, state: BreakerState) {
+    BREAKERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(line, state);
+}
+
+/// Bind `addr` and serve `/metrics` from a background thread for the
+/// rest of the process. Returns the bound address, which tells the
+/// caller the port when `addr` asked for port 0.
+pub fn serve(addr: &str) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let local = listener.local_addr()?;
+    ACTIVE.store(true, Relaxed);
+    std::thread::Builder::new()
+        .name("rz-metrics".into())
+        .spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = respond(stream);
+            }
+        })?;
+    Ok(local)
+}
+
+/// Answer one request. Only `GET /metrics` has a body; anything else
+/// is a 404 or a 405.
+fn respond(mut stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers so the client sees a clean close.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (status, body) = match (method, path.split('?').next().unwrap_or("")) {
+        ("GET", "/metrics") => ("200 OK", render()),
+        (_, "/metrics") => ("405 Method Not Allowed", String::new()),
+        _ => ("404 Not Found", String::new()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// The exposition text for the counters as they stand.
+pub(crate) fn render() -> String {
+    let mut out = String::new();
+    metric(
+        &mut out,
+        "resilient_statements_total",
+        "counter",
+        "Statements the interpreter has evaluated.",
+        STATEMENTS.load(Relaxed),
+    );
+    metric(
+        &mut out,
+        "resilient_live_retries_total",
+        "counter",
+        "Times a live block retried after a fault.",
+        crate::LIVE_TOTAL_RETRIES.load(Relaxed),
+    );
+    metric(
+        &mut out,
+        "resilient_live_exhaustions_total",
+        "counter",
+        "Times a live block gave up.",
+        crate::LIVE_TOTAL_EXHAUSTIONS.load(Relaxed),
+    );
+    metric(
+        &mut out,
+        "resilient_memory_bytes",
+        "gauge",
+        "Approximate bytes of program data bound in environments.",
+        MEMORY.load(Relaxed),
+    );
+
+    let _ = writeln!(
+        out,
+        "# HELP resilient_actor_mailbox_depth Unread messages in each live actor's mailbox.\n\
+         # TYPE resilient_actor_mailbox_depth gauge"
+    );
+    for (pid, depth) in MAILBOXES.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+        let _ = writeln!(
+            out,
+            "resilient_actor_mailbox_depth{{pid=\"{pid}\"}} {depth}"
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP resilient_breaker_state State of each live block, by the line it starts on.\n\
+         # TYPE resilient_breaker_state gauge"
+    );
+    for (line, current) in BREAKERS.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+        for state in BreakerState::ALL {
+            let _ = writeln!(
+                out,
+                "resilient_breaker_state{{line=\"{line}\",state=\"{}\"}} {}",
+                state.label(),
+                u8::from(state == *current)
+            );
+        }
+    }
+    out
+}
+
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, value: impl std::fmt::Display) {
+    let _ = writeln!(
+        out,
+        "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_every_family_with_its_type() {
+        mailbox_depth(7, Some(2));
+        breaker(3, BreakerState::HalfOpen);
+        let text = render();
+        for (name, kind) in [
+            ("resilient_statements_total", "counter"),
+            ("resilient_live_retries_total", "counter"),
+            ("resilient_live_exhaustions_total", "counter"),
+            ("resilient_memory_bytes", "gauge"),
+            ("resilient_actor_mailbox_depth", "gauge"),
+            ("resilient_breaker_state", "gauge"),
+        ] {
+            assert!(text.contains(&format!("# TYPE {name} {kind}\n")), "{text}");
+        }
+        assert!(
+            text.contains("resilient_actor_mailbox_depth{pid=\"7\"} 2\n"),
+            "{text}"
+        );
+        assert!(text.contains("resilient_breaker_state{line=\"3\",state=\"closed\"} 0\n"));
+        assert!(text.contains("resilient_breaker_state{line=\"3\",state=\"half_open\"} 1\n"));
+        assert!(text.contains("resilient_breaker_state{line=\"3\",state=\"open\"} 0\n"));
+
+        mailbox_depth(7, None);
+        assert!(!render().contains("pid=\"7\""));
+    }
+
+    #[test]
+    fn counts_statements_but_not_declarations() {
+        let (program, errors) = crate::parse_silent(
+            "struct S { int a }\nfn one() -> int { return 1; }\nlet x = one();\nif x > 0 { x = 2; }\n",
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+        let mut interp = crate::Interpreter::new();
+        interp.observe(Box::new(Observer));
+        let before = STATEMENTS.load(Relaxed);
+        interp.eval(&program).unwrap();
+        // `let x`, `return 1`, the `if` and `x = 2`.
+        assert_eq!(STATEMENTS.load(Relaxed) - before, 4);
+    }
+}
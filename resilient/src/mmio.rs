@@ -0,0 +1,275 @@
+//! Memory-mapped register I/O: `mmio_read(addr)` and
+//! `mmio_write(addr, value)`.
+//!
+//! Unlike the `volatile_*` intrinsics (`volatile.rs`), which
+//! dereference `addr` in `rz`'s own address space, these reach real
+//! peripheral registers from a hosted `rz`: each access is a 32-bit
+//! read or write at offset `addr` of a device file, `/dev/mem` (where
+//! offsets are physical addresses) unless the project names another,
+//! such as a UIO device whose offsets start at its register block.
+//! Both builtins must be called inside `unsafe { ... }`, like the
+//! volatile intrinsics (see `unsafe_check`).
+//!
+//! Access is opt-in twice over:
+//!
+//! - at build time, by the `unsafe-io` feature — without it every
+//!   call fails with `Unsupported`;
+//! - at run time, by an address allowlist. Nothing is allowed until a
+//!   project lists the register blocks its program drives:
+//!
+//! ```toml
+//! [sandbox]
+//! mmio = ["0x40021000..0x40021400", "0x48000000+0x400"]
+//! mmio_device = "/dev/uio0"   # default /dev/mem
+//! ```
+//!
+//! `--mmio RANGES` (comma-separated, or `none`) and `--mmio-device
+//! PATH` set the same on the command line and win over the manifest.
+//! An access must be 4-byte aligned and lie wholly inside one range;
+//! anything else fails with `PermissionDenied` before the device is
+//! opened, as does every access under an `--allow` without `mmio`. A
+//! device that is missing fails with `NotFound`, one the OS refuses
+//! with `PermissionDenied`. The volatile intrinsics are held to the
+//! same capability and allowlist, at their own access width.
+//!
+//! `/dev/mem` performs an access with the kernel's copy routines,
+//! which do not promise a single 32-bit bus cycle; registers that
+//! need one are better reached through a UIO or vendor driver file.
+
+use crate::{RResult, Value};
+use std::ops::Range;
+use std::sync::RwLock;
+
+/// Every variant `mmio_read` and `mmio_write` fail with.
+pub(crate) const FAILURES: &[&str] = &["PermissionDenied", "NotFound", "Unsupported"];
+
+/// The builtins `unsafe_check` confines to `unsafe` blocks.
+pub const MMIO_BUILTIN_NAMES: &[&str] = &["mmio_read", "mmio_write"];
+
+/// Bytes in one register access.
+const WIDTH: u64 = 4;
+
+const DEFAULT_DEVICE: &str = "/dev/mem";
+
+static RANGES: RwLock<Vec<Range<u64>>> = RwLock::new(Vec::new());
+static DEVICE: RwLock<Option<String>> = RwLock::new(None);
+
+fn parse_addr(s: &str) -> Option<u64> {
+    let s = s.trim().replace('_', "");
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+/// Parse one `start..end` (end exclusive) or `start+size` range.
+fn parse_range(s: &str) -> Option<Range<u64>> {
+    let range = if let Some((start, end)) = s.split_once("..") {
+        parse_addr(start)?..parse_addr(end)?
+    } else {
+        let (start, size) = s.split_once('+')?;
+        let start = parse_addr(start)?;
+        start..start.checked_add(parse_addr(size)?)?
+    };
+    (range.start < range.end).then_some(range)
+}
+
+/// Parse an allowlist: ranges separated by commas, optionally in
+/// TOML array brackets and quotes, or `none` for no ranges.
+pub fn parse_ranges(s: &str) -> Result<Vec<Range<u64>>, String> {
+    let list = s.trim().trim_start_matches('[').trim_end_matches(']');
+    if list.trim().trim_matches('"') == "none" {
+        return Ok(Vec::new());
+    }
+    list.split(',')
+        .map(|r| r.trim().trim_matches('"'))
+        .filter(|r| !r.is_empty())
+        .map(|r| {
+            parse_range(r)
+                .ok_or_else(|| format!("`{r}` is not an address range (start..end or start+size)"))
+        })
+        .collect()
+}
+
+/// Select the process-wide allowlist. Called from the CLI dispatcher.
+pub fn set_ranges(ranges: Vec<Range<u64>>) {
+    *RANGES.write().unwrap_or_else(|e| e.into_inner()) = ranges;
+}
+
+/// Select the device file registers are reached through.
+pub fn set_device(path: String) {
+    *DEVICE.write().unwrap_or_else(|e| e.into_inner()) = Some(path);
+}
+
+fn device() -> String {
+    DEVICE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| DEFAULT_DEVICE.to_string())
+}
+
+/// The register address in `args[0]`, once it is aligned and inside
+/// an allowed range.
+fn checked_addr(builtin: &str, args: &[Value]) -> RResult<u64> {
+    let addr = match args.first() {
+        Some(Value::Int(addr)) if *addr >= 0 => *addr as u64,
+        Some(other) => {
+            return Err(format!(
+                "{builtin}: expected a non-negative integer address, got {other}"
+            ));
+        }
+        None => return Err(format!("{builtin}: expected an address")),
+    };
+    if addr % WIDTH != 0 {
+        return Err(format!(
+            "{builtin}: address {addr:#x} is not aligned to {WIDTH} bytes"
+        ));
+    }
+    check_access(builtin, addr, WIDTH)?;
+    Ok(addr)
+}
+
+/// Fail with `PermissionDenied` unless the sandbox allows `mmio` and
+/// the `width` bytes at `addr` lie inside one allowed range. The
+/// volatile intrinsics check their accesses here too.
+pub(crate) fn check_access(builtin: &str, addr: u64, width: u64) -> RResult<()> {
+    crate::sandbox::require(
+        builtin,
+        crate::sandbox::Capability::Mmio,
+        &format!("{addr:#x}"),
+    )?;
+    let allowed = RANGES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .any(|r| r.start <= addr && addr.checked_add(width).is_some_and(|end| end <= r.end));
+    if !allowed {
+        return Err(format!(
+            "{builtin}: PermissionDenied: {addr:#x} is outside every allowed range (see [sandbox] mmio)"
+        ));
+    }
+    Ok(())
+}
+
+/// `mmio_read(addr) -> int`: the 32-bit register at `addr`.
+pub(crate) fn builtin_mmio_read(args: &[Value]) -> RResult<Value> {
+    const NAME: &str = "mmio_read";
+    if args.len() != 1 {
+        return Err(format!(
+            "{NAME}: expected 1 argument (address), got {}",
+            args.len()
+        ));
+    }
+    let addr = checked_addr(NAME, args)?;
+    host::read(NAME, &device(), addr).map(|word| Value::Int(word as i64))
+}
+
+/// `mmio_write(addr, value)`: store the 32-bit `value` at `addr`.
+pub(crate) fn builtin_mmio_write(args: &[Value]) -> RResult<Value> {
+    const NAME: &str = "mmio_write";
+    if args.len() != 2 {
+        return Err(format!(
+            "{NAME}: expected 2 arguments (address, value), got {}",
+            args.len()
+        ));
+    }
+    let addr = checked_addr(NAME, args)?;
+    let value = match &args[1] {
+        Value::Int(n) => {
+            u32::try_from(*n).map_err(|_| format!("{NAME}: value {n} does not fit in 32 bits"))?
+        }
+        other => return Err(format!("{NAME}: expected an integer value, got {other}")),
+    };
+    host::write(NAME, &device(), addr, value)?;
+    Ok(Value::Void)
+}
+
+#[cfg(all(feature = "unsafe-io", unix))]
+mod host {
+    use crate::RResult;
+    use std::fs::{File, OpenOptions};
+    use std::io;
+    use std::os::unix::fs::FileExt;
+    use std::sync::Mutex;
+
+    /// The open device, reused while the path stays the same.
+    static OPEN: Mutex<Option<(String, File)>> = Mutex::new(None);
+
+    fn failure(builtin: &str, path: &str, e: &io::Error) -> String {
+        match e.kind() {
+            io::ErrorKind::NotFound => format!("{builtin}: NotFound: {path}: {e}"),
+            io::ErrorKind::PermissionDenied => format!("{builtin}: PermissionDenied: {path}: {e}"),
+            _ => format!("{builtin}: {path}: {e}"),
+        }
+    }
+
+    fn with_device<T>(
+        builtin: &str,
+        path: &str,
+        f: impl FnOnce(&File) -> io::Result<T>,
+    ) -> RResult<T> {
+        let mut open = OPEN.lock().unwrap_or_else(|e| e.into_inner());
+        if open.as_ref().is_none_or(|(p, _)| p != path) {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(path)
+                .map_err(|e| failure(builtin, path, &e))?;
+            *open = Some((path.to_string(), file));
+        }
+        let (_, file) = open.as_ref().expect("device opened above");
+        f(file).map_err(|e| failure(builtin, path, &e))
+    }
+
+    pub(super) fn read(builtin: &str, path: &str, addr: u64) -> RResult<u32> {
+        with_device(builtin, path, |file| {
+            let mut word = [0u8; 4];
+            file.read_exact_at(&mut word, addr)?;
+            Ok(u32::from_ne_bytes(word))
+        })
+    }
+
+    pub(super) fn write(builtin: &str, path: &str, addr: u64, value: u32) -> RResult<()> {
+        with_device(builtin, path, |file| {
+            file.write_all_at(&value.to_ne_bytes(), addr)
+        })
+    }
+}
+
+#[cfg(not(all(feature = "unsafe-io", unix)))]
+mod host {
+    use crate::RResult;
+
+    fn unsupported(builtin: &str) -> String {
+        format!("{builtin}: Unsupported: this rz was built without the `unsafe-io` feature")
+    }
+
+    pub(super) fn read(builtin: &str, _path: &str, _addr: u64) -> RResult<u32> {
+        Err(unsupported(builtin))
+    }
+
+    pub(super) fn write(builtin: &str, _path: &str, _addr: u64, _value: u32) -> RResult<()> {
+        Err(unsupported(builtin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_parse_from_flags_and_manifests() {
+        assert_eq!(
+            parse_ranges("0x4002_1000..0x4002_1400,0x48000000+0x400").unwrap(),
+            vec![0x4002_1000..0x4002_1400, 0x4800_0000..0x4800_0400]
+        );
+        assert_eq!(
+            parse_ranges(r#"["0x1000..0x1010", "4096+16"]"#).unwrap(),
+            vec![0x1000..0x1010, 0x1000..0x1010]
+        );
+        assert_eq!(parse_ranges("none").unwrap(), vec![]);
+        assert!(parse_ranges("0x2000..0x1000").is_err());
+        assert!(parse_ranges("0x1000").is_err());
+    }
+}
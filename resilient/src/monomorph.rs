@@ -154,10 +154,10 @@ fn collect_in_node(
             ..
         } => {
             collect_in_node(body, generic_fns, out);
-            for r in requires {
+            for r in requires.iter() {
                 collect_in_node(r, generic_fns, out);
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 collect_in_node(e, generic_fns, out);
             }
             if let Some(r) = recovers_to {
@@ -183,7 +183,7 @@ fn collect_in_node(
             if let Node::Identifier { name, .. } = function.as_ref()
                 && let Some(type_args) = try_infer_call(name, arguments, generic_fns)
             {
-                out.entry(name.clone()).or_default().push(type_args);
+                out.entry(name.to_string()).or_default().push(type_args);
             }
         }
         Node::LetStatement { value, .. } => collect_in_node(value, generic_fns, out),
@@ -320,15 +320,19 @@ fn rewrite_node(
             name: name.clone(),
             parameters: parameters.clone(),
             defaults: defaults.clone(),
-            body: Box::new(rewrite_node(body, generic_fns, instantiations)),
-            requires: requires
-                .iter()
-                .map(|r| rewrite_node(r, generic_fns, instantiations))
-                .collect(),
-            ensures: ensures
-                .iter()
-                .map(|e| rewrite_node(e, generic_fns, instantiations))
-                .collect(),
+            body: std::sync::Arc::new(rewrite_node(body, generic_fns, instantiations)),
+            requires: std::sync::Arc::new(
+                requires
+                    .iter()
+                    .map(|r| rewrite_node(r, generic_fns, instantiations))
+                    .collect(),
+            ),
+            ensures: std::sync::Arc::new(
+                ensures
+                    .iter()
+                    .map(|e| rewrite_node(e, generic_fns, instantiations))
+                    .collect(),
+            ),
             return_type: return_type.clone(),
             span: *span,
             pure: *pure,
@@ -368,7 +372,7 @@ fn rewrite_node(
                     if let Some(type_args) = try_infer_call(name, arguments, generic_fns) {
                         let mangled = mangle_name(name, &type_args);
                         Box::new(Node::Identifier {
-                            name: mangled,
+                            name: mangled.into(),
                             span: *id_span,
                         })
                     } else {
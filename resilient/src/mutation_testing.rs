@@ -1,9 +1,10 @@
 //! Feature 43/50 — Mutation Testing.
 //!
-//! `rz mutate <file>` walks the AST, generates structured mutations
-//! (operator swaps, constant changes, branch flips), and reports
-//! the program-level mutation site count. A higher count = more spots
-//! tests need to cover to distinguish live from killed mutants.
+//! The typechecker pass (`check`) walks the AST, generates structured
+//! mutations (operator swaps, constant changes, branch flips), and
+//! reports the program-level mutation site count. A higher count =
+//! more spots tests need to cover to distinguish live from killed
+//! mutants.
 //!
 //! Built-in mutators:
 //! * **Arithmetic**: `+`↔`-`, `*`↔`/`, `%`→`*`
@@ -16,13 +17,22 @@
 //! * **Condition negation**: `if c {}` → `if !c {}`
 //! * **Return void**: `return <expr>` → omit value
 //!
-//! The runner does not re-execute tests — it reports the count of
-//! generated mutation sites. The test-run orchestrator is a follow-up.
+//! `rz mutate [<file|dir>]` runs them: for each file with tests it
+//! applies one mutation at a time to the non-test functions —
+//! comparison flips, off-by-one integer literals, and `assert`
+//! conditions replaced by `true` — reruns the file's tests against
+//! the mutant, and reports the mutants no test failed on. A survivor
+//! marks a comparison, constant or assert that no test (or contract
+//! checked while testing) actually constrains. Mutants run under a
+//! step budget (`fuel`), so one that loops forever counts as killed
+//! by timeout.
 
 #![allow(clippy::collapsible_if, clippy::doc_lazy_continuation, dead_code)]
 
 use crate::Node;
 use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
 
 /// RES-2202: `kind` is now `&'static str`. Every call site in
 /// `generate_in` passes a string literal ("arithmetic", "boundary",
@@ -502,6 +512,320 @@ fn contract_coverage(program: &Node) -> std::collections::HashSet<String> {
     out
 }
 
+// ── `rz mutate` ────────────────────────────────────────────────────────
+
+/// Entry point called from `run_cli()`. Returns `Some(exit_code)` when
+/// the first CLI arg is `"mutate"`, `None` otherwise (fall through).
+/// Exits 0 when every mutant was killed, 1 when some survived, and 2
+/// for a bad command line or a file whose tests fail unmutated.
+pub fn dispatch_mutate_subcommand(args: &[String]) -> Option<i32> {
+    if args.get(1).map(|s| s.as_str()) != Some("mutate") {
+        return None;
+    }
+    let mut target: Option<String> = None;
+    for a in &args[2..] {
+        if a == "--help" || a == "-h" || a == "help" {
+            print_mutate_help();
+            return Some(0);
+        } else if a.starts_with('-') {
+            eprintln!("Error: unknown flag `{a}` to mutate");
+            return Some(2);
+        } else if target.is_none() {
+            target = Some(a.clone());
+        } else {
+            eprintln!("Error: unexpected argument `{a}` to mutate");
+            return Some(2);
+        }
+    }
+    let paths = match crate::test_runner::resolve_target(target.as_deref()) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return Some(2);
+        }
+    };
+    if paths.is_empty() {
+        eprintln!("No .rz files found");
+        return Some(1);
+    }
+
+    let started = Instant::now();
+    let mut total = 0usize;
+    let mut killed = 0usize;
+    let mut survivors: Vec<String> = Vec::new();
+    let mut had_error = false;
+    for path in &paths {
+        match mutate_file(path) {
+            Ok(result) => {
+                total += result.total;
+                killed += result.killed;
+                survivors.extend(result.survivors);
+            }
+            Err(e) => {
+                eprintln!("Error: {}: {e}", path.display());
+                had_error = true;
+            }
+        }
+    }
+
+    if !survivors.is_empty() {
+        println!();
+        println!("survived mutants:");
+        for s in &survivors {
+            println!("  {s}");
+        }
+    }
+    println!();
+    let pct = (killed * 100).checked_div(total).unwrap_or(100);
+    println!(
+        "{total} mutant{}: {killed} killed, {} survived ({pct}% killed) ({})",
+        if total == 1 { "" } else { "s" },
+        survivors.len(),
+        crate::test_runner::format_duration(started.elapsed())
+    );
+    if had_error {
+        Some(2)
+    } else if survivors.is_empty() {
+        Some(0)
+    } else {
+        Some(1)
+    }
+}
+
+fn print_mutate_help() {
+    println!("Usage: rz mutate [<file|dir>]");
+    println!();
+    println!("Apply one mutation at a time to the non-test functions of each .rz file");
+    println!("with tests, rerun its tests, and report the mutants no test failed on.");
+    println!();
+    println!("Mutations:");
+    println!("  comparison   `<` -> `<=` / `>=`, `==` -> `!=`, ...");
+    println!("  literal      integer literal n -> n + 1 / n - 1");
+    println!("  assert       `assert` condition -> `true`");
+    println!();
+    println!("Exit status: 0 when every mutant was killed, 1 when some survived,");
+    println!("2 when a file's tests fail before mutation.");
+}
+
+/// Every mutant of one file is run with at most this many times the
+/// steps its unmutated tests took (plus `TIMEOUT_SLACK`).
+const TIMEOUT_FACTOR: u64 = 10;
+const TIMEOUT_SLACK: u64 = 100_000;
+
+struct FileMutationResult {
+    total: usize,
+    killed: usize,
+    /// `file:line: fn: change`, in source order.
+    survivors: Vec<String>,
+}
+
+/// Run every mutant of `path` against its tests. Files without tests
+/// are skipped.
+fn mutate_file(path: &Path) -> Result<FileMutationResult, String> {
+    let mut program = crate::test_runner::parse_file(path)?;
+    let sites = sites(&mut program);
+    let baseline = crate::test_runner::prepare_file(path, program.clone())?;
+    let ((ran, failure), steps) =
+        crate::fuel::count_steps(|| crate::test_runner::first_failure(&baseline));
+    let mut result = FileMutationResult {
+        total: 0,
+        killed: 0,
+        survivors: Vec::new(),
+    };
+    if ran == 0 {
+        return Ok(result);
+    }
+    if let Some((test, e)) = failure {
+        return Err(format!("test {test} fails before mutation: {e}"));
+    }
+
+    let budget = steps
+        .saturating_mul(TIMEOUT_FACTOR)
+        .saturating_add(TIMEOUT_SLACK);
+    let file = path.display();
+    for (index, site) in sites.iter().enumerate() {
+        let label = format!(
+            "{file}:{}: {}: {}",
+            site.line,
+            site.fn_name,
+            site.change.describe()
+        );
+        let mutant = crate::test_runner::prepare_file(path, mutant(&program, index))?;
+        crate::fuel::set_max_steps(Some(budget));
+        let (_, failure) = crate::test_runner::first_failure(&mutant);
+        crate::fuel::set_max_steps(None);
+        result.total += 1;
+        match failure {
+            Some((_, e)) if crate::fuel::is_exhausted(&e) => {
+                println!("mutant {label} ... killed (timeout)");
+                result.killed += 1;
+            }
+            Some((test, _)) => {
+                println!("mutant {label} ... killed by {test}");
+                result.killed += 1;
+            }
+            None => {
+                println!("mutant {label} ... SURVIVED");
+                result.survivors.push(label);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// One mutation `rz mutate` can apply.
+#[derive(Debug, Clone, PartialEq)]
+enum Change {
+    /// Replace a comparison operator.
+    Operator(&'static str, &'static str),
+    /// Replace an integer literal.
+    Literal(i64, i64),
+    /// Replace an `assert` condition with `true`.
+    DropAssert,
+}
+
+impl Change {
+    fn describe(&self) -> String {
+        match self {
+            Change::Operator(from, to) => format!("`{from}` -> `{to}`"),
+            Change::Literal(from, to) => format!("`{from}` -> `{to}`"),
+            Change::DropAssert => "assert condition -> `true`".to_string(),
+        }
+    }
+}
+
+/// Where a mutation applies. Only the line is kept: expression spans
+/// are not precise enough for a column.
+#[derive(Debug, Clone, PartialEq)]
+struct Site {
+    fn_name: String,
+    line: usize,
+    change: Change,
+}
+
+/// The mutations of `node` itself (not of its children).
+fn changes_at(node: &Node) -> Vec<Change> {
+    match node {
+        Node::InfixExpression { operator, .. } => comparison_flips(operator)
+            .iter()
+            .map(|to| Change::Operator(operator, to))
+            .collect(),
+        Node::IntegerLiteral { value, .. } => [value.checked_add(1), value.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .map(|to| Change::Literal(*value, to))
+            .collect(),
+        Node::Assert { condition, .. }
+            if !matches!(**condition, Node::BooleanLiteral { value: true, .. }) =>
+        {
+            vec![Change::DropAssert]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// A comparison's boundary shift first, then its negation.
+fn comparison_flips(op: &str) -> &'static [&'static str] {
+    match op {
+        "<" => &["<=", ">="],
+        "<=" => &["<", ">"],
+        ">" => &[">=", "<="],
+        ">=" => &[">", "<"],
+        "==" => &["!="],
+        "!=" => &["=="],
+        _ => &[],
+    }
+}
+
+fn apply(node: &mut Node, change: &Change) {
+    match (node, change) {
+        (Node::InfixExpression { operator, .. }, Change::Operator(_, to)) => *operator = to,
+        (Node::IntegerLiteral { value, .. }, Change::Literal(_, to)) => *value = *to,
+        (
+            Node::Assert {
+                condition, span, ..
+            },
+            Change::DropAssert,
+        ) => {
+            **condition = Node::BooleanLiteral {
+                value: true,
+                span: *span,
+            };
+        }
+        _ => {}
+    }
+}
+
+/// Every mutation of `program`'s non-test functions, in the order
+/// `mutant` numbers them.
+fn sites(program: &mut Node) -> Vec<Site> {
+    let mut out = Vec::new();
+    visit_bodies(program, &mut |fn_name, node| {
+        let span = match node {
+            Node::InfixExpression { span, .. }
+            | Node::IntegerLiteral { span, .. }
+            | Node::Assert { span, .. } => *span,
+            _ => return,
+        };
+        for change in changes_at(node) {
+            out.push(Site {
+                fn_name: fn_name.to_string(),
+                line: span.start.line,
+                change,
+            });
+        }
+    });
+    out
+}
+
+/// `program` with its `index`-th site mutated.
+fn mutant(program: &Node, index: usize) -> Node {
+    let mut program = program.clone();
+    let mut seen = 0usize;
+    visit_bodies(&mut program, &mut |_, node| {
+        let changes = changes_at(node);
+        if (seen..seen + changes.len()).contains(&index) {
+            apply(node, &changes[index - seen]);
+        }
+        seen += changes.len();
+    });
+    program
+}
+
+/// Call `f` on each node of the bodies of the top-level functions and
+/// impl methods, in source order, skipping tests (`test fn` and
+/// `fn test_*()`).
+fn visit_bodies(program: &mut Node, f: &mut dyn FnMut(&str, &mut Node)) {
+    let Node::Program(stmts) = program else {
+        return;
+    };
+    for stmt in stmts {
+        match &mut stmt.node {
+            Node::Function {
+                name,
+                parameters,
+                body,
+                ..
+            } if !(name.starts_with("test_") && parameters.is_empty()) => {
+                visit(std::sync::Arc::make_mut(body), name, f)
+            }
+            Node::ImplBlock { methods, .. } => {
+                for method in methods {
+                    if let Node::Function { name, body, .. } = method {
+                        visit(std::sync::Arc::make_mut(body), name, f);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn visit(node: &mut Node, fn_name: &str, f: &mut dyn FnMut(&str, &mut Node)) {
+    f(fn_name, node);
+    crate::ast::for_each_child_mut(node, |child| visit(child, fn_name, f));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -913,4 +1237,38 @@ mod tests {
             s.keys().collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn sites_cover_comparisons_literals_and_asserts_outside_tests() {
+        let src = r#"
+            fn f(int x) -> int { assert(x != 0); return x; }
+            fn test_f() { assert(f(1) < 2); }
+            test fn g() { assert(f(3) == 3); }
+        "#;
+        let (mut prog, errs) = parse(src);
+        assert!(errs.is_empty(), "{errs:?}");
+        let changes: Vec<Change> = sites(&mut prog).into_iter().map(|s| s.change).collect();
+        assert_eq!(
+            changes,
+            [
+                Change::DropAssert,
+                Change::Operator("!=", "=="),
+                Change::Literal(0, 1),
+                Change::Literal(0, -1),
+            ]
+        );
+    }
+
+    #[test]
+    fn mutant_applies_only_the_indexed_site() {
+        let src = "fn f(int x) -> bool { return x < 10; }";
+        let (mut prog, _) = parse(src);
+        let all = sites(&mut prog);
+        assert_eq!(all.len(), 4);
+        let mut second = mutant(&prog, 1);
+        let changes: Vec<Change> = sites(&mut second).into_iter().map(|s| s.change).collect();
+        // `<` became `>=`; the literal is untouched.
+        assert_eq!(changes[0], Change::Operator(">=", ">"));
+        assert_eq!(changes[2], Change::Literal(10, 11));
+    }
 }
@@ -32,7 +32,7 @@ type RResult<T> = Result<T, String>;
 /// `mutex_new(v) → Mutex` — wrap `v` in a new mutex.
 pub(crate) fn builtin_mutex_new(args: &[Value]) -> RResult<Value> {
     match args {
-        [v] => Ok(Value::Array(vec![v.clone()])),
+        [v] => Ok(Value::Array(vec![v.clone()].into())),
         _ => Err(format!(
             "mutex_new: expected 1 argument, got {}",
             args.len()
@@ -88,7 +88,7 @@ pub(crate) fn builtin_mutex_try_lock(args: &[Value]) -> RResult<Value> {
 /// `rwlock_new(v) → RwLock` — wrap `v` in a new read-write lock.
 pub(crate) fn builtin_rwlock_new(args: &[Value]) -> RResult<Value> {
     match args {
-        [v] => Ok(Value::Array(vec![v.clone()])),
+        [v] => Ok(Value::Array(vec![v.clone()].into())),
         _ => Err(format!(
             "rwlock_new: expected 1 argument, got {}",
             args.len()
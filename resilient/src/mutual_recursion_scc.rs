@@ -187,7 +187,7 @@ fn collect_called_functions(
                 // only caller is `build_graph_for_node` which always
                 // inserts first.
                 if let Some(set) = graph.get_mut(current_fn) {
-                    set.insert(name.clone());
+                    set.insert(name.to_string());
                 }
             }
 
@@ -227,11 +227,11 @@ fn rewrite_calls(node: &mut Node, sigs: &HashMap<String, Vec<String>>) -> Result
             recovers_to,
             ..
         } => {
-            rewrite_calls(body, sigs)?;
-            for r in requires.iter_mut() {
+            rewrite_calls(std::sync::Arc::make_mut(body), sigs)?;
+            for r in std::sync::Arc::make_mut(requires) {
                 rewrite_calls(r, sigs)?;
             }
-            for e in ensures.iter_mut() {
+            for e in std::sync::Arc::make_mut(ensures) {
                 rewrite_calls(e, sigs)?;
             }
             if let Some(rec) = recovers_to {
@@ -245,11 +245,11 @@ fn rewrite_calls(node: &mut Node, sigs: &HashMap<String, Vec<String>>) -> Result
             recovers_to,
             ..
         } => {
-            rewrite_calls(body, sigs)?;
-            for r in requires.iter_mut() {
+            rewrite_calls(std::sync::Arc::make_mut(body), sigs)?;
+            for r in std::sync::Arc::make_mut(requires) {
                 rewrite_calls(r, sigs)?;
             }
-            for e in ensures.iter_mut() {
+            for e in std::sync::Arc::make_mut(ensures) {
                 rewrite_calls(e, sigs)?;
             }
             if let Some(rec) = recovers_to {
@@ -439,17 +439,31 @@ fn rewrite_calls(node: &mut Node, sigs: &HashMap<String, Vec<String>>) -> Result
             // resolve it eagerly here. Calls on other shapes keep
             // their NamedArg nodes; the runtime path errors out.
             let callee_name = if let Node::Identifier { name, .. } = function.as_ref() {
-                Some(name.clone())
+                Some(*name)
             } else {
                 None
             };
             if let Some(name) = callee_name
-                && let Some(param_names) = sigs.get(&name)
+                && let Some(param_names) = sigs.get(name.as_str())
             {
                 let label = format!("fn `{}`", name);
                 let lowered = resolve(&label, param_names, arguments)?;
                 *arguments = lowered;
             }
+            // The `format` builtin resolves its named arguments against
+            // the template's `{name}` placeholders instead.
+            else if let Some(name) = callee_name
+                && name == "format"
+            {
+                *arguments = crate::format_builtin::lower_named_args(arguments)?;
+            }
+            // `json_stringify(v, pretty: true)`.
+            else if let Some(name) = callee_name
+                && name == "json_stringify"
+            {
+                let params = ["value".to_string(), "pretty".to_string()];
+                *arguments = resolve("builtin `json_stringify`", &params, arguments)?;
+            }
             // Otherwise leave NamedArg nodes in place; the runtime
             // path raises a clean diagnostic if the call ever
             // reaches eval with named args still present.
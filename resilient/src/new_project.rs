@@ -0,0 +1,192 @@
+//! `rz new <name>` — create a project skeleton in `./<name>`.
+//!
+//! Where `rz pkg init` writes the bare minimum (manifest, hello-world
+//! entry, `.gitignore`), `rz new` lays out a project that already
+//! exercises the pieces a real one uses:
+//!
+//! ```text
+//! <name>/
+//!   resilient.toml
+//!   src/main.rz          entry point; `use`s the two modules below
+//!   src/greet.rz         a function the entry and the test share
+//!   src/supervisor.rz    a sample supervisor with one worker
+//!   tests/greet_test.rz  a sample `fn test_*()` for `rz test`
+//!   .gitignore
+//! ```
+//!
+//! Everything is written inside the new directory, never next to it,
+//! and the same guards as `pkg init` apply: the directory must be new
+//! or empty, and an existing manifest is never overwritten. The
+//! generated project runs with `rz .`, checks with `rz check .` and
+//! passes `rz test`.
+//!
+//! All logic lives in this file; `lib.rs` contributes only a `mod`
+//! declaration and a dispatch call in `run_cli()`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::pkg_init::{self, PkgInitError, Scaffold};
+
+const MAIN_RZ: &str = r#"// Entry point. Run the project with `rz .` from its root.
+use "greet.rz";
+use "supervisor.rz";
+
+fn main(int _d) {
+    println(greeting("world"));
+    // Run the supervisor's worker once by hand.
+    greeter();
+    return 0;
+}
+main(0);
+"#;
+
+const GREET_RZ: &str = r#"// Kept apart from main.rz so tests can `use` it without running
+// the program.
+fn greeting(string name) -> string {
+    return "Hello, " + name + "!";
+}
+"#;
+
+const SUPERVISOR_RZ: &str = r#"// A supervisor restarts the workers it owns when they fail.
+// `one_for_one` restarts only the worker that failed; `permanent`
+// restarts it every time, `transient` leaves it down.
+use "greet.rz";
+
+supervisor {
+    strategy: one_for_one,
+    children: [
+        { id: "greeter", fn: greeter, restart: permanent }
+    ]
+}
+
+fn greeter() -> void {
+    println(greeting("supervisor"));
+}
+"#;
+
+const GREET_TEST_RZ: &str = r#"// Run with `rz test` from the project root.
+use "../src/greet.rz";
+
+fn test_greeting() {
+    assert(greeting("world") == "Hello, world!", "unexpected greeting");
+}
+"#;
+
+/// Entry point called from `run_cli()`. Returns `Some(exit_code)`
+/// when the first CLI arg is `"new"`, `None` otherwise.
+pub(crate) fn dispatch_new_subcommand(args: &[String]) -> Option<i32> {
+    if args.get(1).map(String::as_str) != Some("new") {
+        return None;
+    }
+    let mut name: Option<&str> = None;
+    for a in &args[2..] {
+        if a == "--help" || a == "-h" || a == "help" {
+            print_new_help();
+            return Some(0);
+        } else if a.starts_with('-') {
+            eprintln!("Error: unknown flag `{}` to `rz new`", a);
+            return Some(2);
+        } else if name.is_none() {
+            name = Some(a);
+        } else {
+            eprintln!("Error: unexpected extra argument `{}` to `rz new`", a);
+            return Some(2);
+        }
+    }
+    let Some(name) = name else {
+        eprintln!("Error: `rz new` requires a project name: `rz new <name>`");
+        return Some(2);
+    };
+    let cwd = match std::env::current_dir() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Error: could not read current directory: {}", e);
+            return Some(2);
+        }
+    };
+    match scaffold_in(&cwd, name) {
+        Ok(scaffold) => {
+            println!("Created {} at {}", name, scaffold.root.display());
+            for p in &scaffold.wrote {
+                println!("  wrote {}", p.display());
+            }
+            println!("\nNext steps:");
+            println!("  cd {}", name);
+            println!("  rz .");
+            println!("  rz test");
+            Some(0)
+        }
+        Err(PkgInitError::InvalidName(_)) | Err(PkgInitError::MissingName) => {
+            eprintln!(
+                "Error: invalid project name `{}`: use letters, digits, `_`, `-` or `.`",
+                name
+            );
+            Some(2)
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            Some(1)
+        }
+    }
+}
+
+fn print_new_help() {
+    println!("Usage: rz new <name>");
+    println!();
+    println!("Create a project skeleton in ./<name>: resilient.toml, src/main.rz");
+    println!("using src/greet.rz and a sample supervisor in src/supervisor.rz,");
+    println!("a sample test in tests/greet_test.rz, and a .gitignore.");
+    println!();
+    println!("The directory must not exist yet, or be empty.");
+}
+
+/// Write the skeleton into `<parent>/<name>`; returns the files
+/// written, in order.
+pub(crate) fn scaffold_in(parent: &Path, name: &str) -> Result<Scaffold, PkgInitError> {
+    let root = pkg_init::prepare_root(parent, name)?;
+    fs::create_dir_all(root.join("src"))?;
+    fs::create_dir_all(root.join("tests"))?;
+    let files: [(PathBuf, &str); 6] = [
+        (
+            root.join(pkg_init::MANIFEST_FILENAME),
+            &pkg_init::render_manifest(name, pkg_init::DEFAULT_AUTHOR),
+        ),
+        (root.join("src/main.rz"), MAIN_RZ),
+        (root.join("src/greet.rz"), GREET_RZ),
+        (root.join("src/supervisor.rz"), SUPERVISOR_RZ),
+        (root.join("tests/greet_test.rz"), GREET_TEST_RZ),
+        (root.join(".gitignore"), pkg_init::render_gitignore()),
+    ];
+    let mut wrote = Vec::with_capacity(files.len());
+    for (path, contents) in files {
+        fs::write(&path, contents)?;
+        wrote.push(path);
+    }
+    Ok(Scaffold { root, wrote })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_skeleton_lands_inside_the_new_directory_only() {
+        let parent = std::env::temp_dir().join(format!("res_new_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&parent);
+        fs::create_dir_all(&parent).unwrap();
+
+        let scaffold = scaffold_in(&parent, "demo").expect("scaffold");
+        assert_eq!(scaffold.root, parent.join("demo"));
+        assert_eq!(scaffold.wrote.len(), 6);
+        assert!(scaffold.wrote.iter().all(|p| p.starts_with(&scaffold.root)));
+        let entries: Vec<_> = fs::read_dir(&parent).unwrap().collect();
+        assert_eq!(entries.len(), 1, "only the project directory is created");
+
+        assert!(matches!(
+            scaffold_in(&parent, "demo"),
+            Err(PkgInitError::ManifestExists(_))
+        ));
+        let _ = fs::remove_dir_all(&parent);
+    }
+}
@@ -76,13 +76,13 @@ fn lower_node(node: &mut Node, newtypes: &HashMap<String, String>) {
             span,
         } => {
             if let Node::Identifier { name, .. } = function.as_ref()
-                && newtypes.contains_key(name)
+                && newtypes.contains_key(name.as_str())
                 && arguments.len() == 1
             {
                 // Lower the inner argument first so nested constructors work.
                 lower_node(&mut arguments[0], newtypes);
                 *node = Node::NewtypeConstruct {
-                    type_name: name.clone(),
+                    type_name: name.to_string(),
                     value: Box::new(arguments[0].clone()),
                     span: *span,
                 };
@@ -94,7 +94,7 @@ fn lower_node(node: &mut Node, newtypes: &HashMap<String, String>) {
             }
         }
         Node::Function { body, .. } => {
-            lower_node(body, newtypes);
+            lower_node(std::sync::Arc::make_mut(body), newtypes);
         }
         Node::Block { stmts, .. } => {
             for stmt in stmts.iter_mut() {
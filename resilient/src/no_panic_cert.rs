@@ -94,7 +94,7 @@ mod tests {
     use super::*;
     use crate::parse;
 
-    fn extract_body(src: &str) -> Box<Node> {
+    fn extract_body(src: &str) -> std::sync::Arc<Node> {
         let (prog, _) = parse(src);
         if let Node::Program(ss) = &prog {
             for s in ss {
@@ -32,6 +32,7 @@
 #![allow(clippy::collapsible_if, clippy::doc_lazy_continuation, dead_code)]
 
 use crate::Node;
+use crate::typechecker::CheckError;
 use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
@@ -104,7 +105,7 @@ fn single_return(body: &Node) -> Option<&Node> {
     }
 }
 
-pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
+pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), CheckError> {
     let specs = collect();
     if specs.is_empty() {
         return Ok(());
@@ -143,7 +144,12 @@ pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
 }
 
 #[cfg(feature = "z3")]
-fn verify(source_path: &str, fn_name: &str, expr: &Node, highs: &[String]) -> Result<(), String> {
+fn verify(
+    source_path: &str,
+    fn_name: &str,
+    expr: &Node,
+    highs: &[String],
+) -> Result<(), CheckError> {
     use crate::verifier_z3::{NiOutcome, prove_noninterference};
     match prove_noninterference(expr, highs) {
         NiOutcome::Independent => {
@@ -159,9 +165,9 @@ fn verify(source_path: &str, fn_name: &str, expr: &Node, highs: &[String]) -> Re
             hi_in,
             lo_out,
             hi_out,
-        } => Err(format!(
+        } => Err(crate::typechecker::refutation(format!(
             "{source_path}:0:0: error: noninterference: fn `{fn_name}` leaks high input `{high_var}` to its public output — counterexample: with low inputs fixed, `{high_var}`={lo_in} yields {lo_out} but `{high_var}`={hi_in} yields {hi_out}"
-        )),
+        ))),
         NiOutcome::Unknown(why) => {
             advise(fn_name, &why);
             Ok(())
@@ -175,7 +181,7 @@ fn verify(
     fn_name: &str,
     _expr: &Node,
     _highs: &[String],
-) -> Result<(), String> {
+) -> Result<(), CheckError> {
     advise(
         fn_name,
         "non-interference proof requires the `z3` feature; pass --features z3 to discharge it",
@@ -29,7 +29,7 @@ pub(crate) fn builtin_prime_factors(args: &[Value]) -> RResult<Value> {
         [Value::Int(n)] => {
             let mut n = *n;
             if n < 2 {
-                return Ok(Value::Array(vec![]));
+                return Ok(Value::Array(vec![].into()));
             }
             let mut factors = Vec::new();
             let mut d = 2i64;
@@ -43,7 +43,7 @@ pub(crate) fn builtin_prime_factors(args: &[Value]) -> RResult<Value> {
             if n > 1 {
                 factors.push(Value::Int(n));
             }
-            Ok(Value::Array(factors))
+            Ok(Value::Array(factors.into()))
         }
         [other] => Err(format!("prime_factors: expected int, got {other}")),
         _ => Err(format!(
@@ -70,7 +70,7 @@ pub(crate) fn builtin_primes_up_to(args: &[Value]) -> RResult<Value> {
             }
             let n = *n as usize;
             if n < 2 {
-                return Ok(Value::Array(vec![]));
+                return Ok(Value::Array(vec![].into()));
             }
             if n > 10_000_000 {
                 return Err(format!("primes_up_to: n ({n}) exceeds limit of 10,000,000"));
@@ -93,7 +93,7 @@ pub(crate) fn builtin_primes_up_to(args: &[Value]) -> RResult<Value> {
                 .filter(|&k| is_prime[k])
                 .map(|k| Value::Int(k as i64))
                 .collect();
-            Ok(Value::Array(primes))
+            Ok(Value::Array(primes.into()))
         }
         [other] => Err(format!("primes_up_to: expected int, got {other}")),
         _ => Err(format!(
@@ -432,7 +432,7 @@ pub(crate) fn builtin_int_to_digits(args: &[Value]) -> RResult<Value> {
         [Value::Int(n)] => {
             let mut n = n.unsigned_abs();
             if n == 0 {
-                return Ok(Value::Array(vec![Value::Int(0)]));
+                return Ok(Value::Array(vec![Value::Int(0)].into()));
             }
             let mut digits = Vec::new();
             while n > 0 {
@@ -440,7 +440,7 @@ pub(crate) fn builtin_int_to_digits(args: &[Value]) -> RResult<Value> {
                 n /= 10;
             }
             digits.reverse();
-            Ok(Value::Array(digits))
+            Ok(Value::Array(digits.into()))
         }
         [other] => Err(format!("int_to_digits: expected int, got {other}")),
         _ => Err(format!(
@@ -103,7 +103,7 @@ pub(crate) fn desugar_int_with_suffix(value: i64, suffix: NumericSuffix, span: S
     let builtin = suffix.builtin_name();
     Node::CallExpression {
         function: Box::new(Node::Identifier {
-            name: builtin.to_string(),
+            name: builtin.into(),
             span,
         }),
         arguments: vec![Node::IntegerLiteral { value, span }],
@@ -119,7 +119,7 @@ pub(crate) fn desugar_float_with_suffix(value: f64, suffix: NumericSuffix, span:
     let builtin = suffix.builtin_name();
     Node::CallExpression {
         function: Box::new(Node::Identifier {
-            name: builtin.to_string(),
+            name: builtin.into(),
             span,
         }),
         arguments: vec![Node::FloatLiteral { value, span }],
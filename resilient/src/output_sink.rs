@@ -38,6 +38,9 @@ pub enum OutputSink {
     Stdout,
     /// Capture mode. Bytes are appended here.
     Buffer(Vec<u8>),
+    /// Stdout, with every write also handed to the hook (the REPL's
+    /// `:transcript` recorder).
+    Tee(fn(&str)),
 }
 
 thread_local! {
@@ -58,6 +61,10 @@ pub(crate) fn write_str(s: &str) {
         OutputSink::Buffer(buf) => {
             buf.extend_from_slice(s.as_bytes());
         }
+        OutputSink::Tee(hook) => {
+            let _ = std::io::stdout().lock().write_all(s.as_bytes());
+            hook(s);
+        }
     });
 }
 
@@ -66,7 +73,7 @@ pub(crate) fn write_str(s: &str) {
 /// visible before the next read.
 pub(crate) fn flush() {
     SINK.with(|sink| {
-        if let OutputSink::Stdout = &*sink.borrow() {
+        if let OutputSink::Stdout | OutputSink::Tee(_) = &*sink.borrow() {
             let _ = std::io::stdout().flush();
         }
     });
@@ -110,7 +117,7 @@ pub fn with_captured_output<R>(f: impl FnOnce() -> R) -> (R, String) {
             OutputSink::Buffer(buf) => String::from_utf8_lossy(&buf).into_owned(),
             // Concurrent swap by the closure — unexpected but recover
             // gracefully.
-            OutputSink::Stdout => String::new(),
+            OutputSink::Stdout | OutputSink::Tee(_) => String::new(),
         }
     });
     // The temporary `Stdout` we just installed gets immediately
@@ -120,6 +127,24 @@ pub fn with_captured_output<R>(f: impl FnOnce() -> R) -> (R, String) {
     (result, captured)
 }
 
+/// Run `f` with output going to stdout and to `hook` as well; the
+/// previous sink is restored on exit (even on panic).
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn with_tee<R>(hook: fn(&str), f: impl FnOnce() -> R) -> R {
+    struct Guard(Option<OutputSink>);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            if let Some(prev) = self.0.take() {
+                SINK.with(|sink| *sink.borrow_mut() = prev);
+            }
+        }
+    }
+    let _guard = Guard(Some(SINK.with(|sink| {
+        std::mem::replace(&mut *sink.borrow_mut(), OutputSink::Tee(hook))
+    })));
+    f()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
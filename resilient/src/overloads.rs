@@ -0,0 +1,380 @@
+//! Function overloading by arity and parameter types.
+//!
+//! Several top-level `fn`s may share a name as long as their parameter
+//! types differ — `fn log(string msg)` next to
+//! `fn log(string level, string msg)`. Right after parsing,
+//! [`lower_program`] renames every member of such a set to a mangled
+//! name that carries its signature (`log$(string)`,
+//! `log$(string, string)`), so every name-keyed table downstream —
+//! contracts, the verifier, the VM's function index — sees distinct
+//! functions. Call sites keep the bare name and are resolved against
+//! the set:
+//!
+//! - the typechecker picks the best match from the static argument
+//!   types and checks the call as a call to that overload;
+//! - the interpreter binds the bare name to a `Value::Overloads` set
+//!   and picks from the runtime argument values;
+//! - the VM compiler has no argument types, so it resolves by arity
+//!   and rejects a call that only the types could disambiguate.
+//!
+//! Only a call may name a set. `let f = show;` has no arguments to
+//! choose by, so all three reject it with [`as_value_error`].
+//!
+//! Best match: among the candidates whose arity fits, each argument
+//! scores 2 against an exactly matching parameter type and 1 against a
+//! parameter that merely accepts it (`any`, untyped, a type parameter);
+//! the highest total wins and a tie is ambiguous. A set with two
+//! identical parameter lists is left unmangled so the checker's
+//! duplicate-function error still reports it.
+
+use crate::{FunctionValue, Node, Value};
+use std::collections::HashMap;
+
+/// Rename the members of every top-level overload set to their
+/// mangled names. Runs from `parse`, alongside the other lowerings.
+pub(crate) fn lower_program(program: &mut Node) {
+    let Node::Program(stmts) = program else {
+        return;
+    };
+    let mut sets: HashMap<String, Vec<String>> = HashMap::new();
+    for stmt in stmts.iter() {
+        if let Node::Function {
+            name, parameters, ..
+        } = &stmt.node
+        {
+            sets.entry(name.clone())
+                .or_default()
+                .push(mangle(name, parameters));
+        }
+    }
+    sets.retain(|_, mangled| {
+        let mut distinct = mangled.clone();
+        distinct.sort();
+        distinct.dedup();
+        mangled.len() > 1 && distinct.len() == mangled.len()
+    });
+    if sets.is_empty() {
+        return;
+    }
+    for stmt in stmts.iter_mut() {
+        if let Node::Function {
+            name, parameters, ..
+        } = &mut stmt.node
+            && sets.contains_key(name.as_str())
+        {
+            *name = mangle(name, parameters);
+        }
+    }
+}
+
+/// `log` + `(string level, string msg)` → `log$(string, string)`.
+/// An untyped parameter renders as `any`.
+pub(crate) fn mangle(base: &str, parameters: &[(String, String)]) -> String {
+    let types: Vec<&str> = parameters
+        .iter()
+        .map(|(ty, _)| if ty.is_empty() { "any" } else { ty.as_str() })
+        .collect();
+    format!("{}$({})", base, types.join(", "))
+}
+
+/// The bare name of a mangled overload, or `None` for any other name.
+pub(crate) fn base_of(name: &str) -> Option<&str> {
+    let (base, rest) = name.split_once("$(")?;
+    rest.ends_with(')').then_some(base)
+}
+
+/// The bare name of `name` when it is a mangled overload, else `name`.
+pub(crate) fn base_name(name: &str) -> &str {
+    base_of(name).unwrap_or(name)
+}
+
+/// A mangled overload as it would be written at a call site:
+/// `log$(string, string)` → `log(string, string)`.
+pub(crate) fn display(name: &str) -> String {
+    match base_of(name) {
+        Some(base) => format!("{}{}", base, &name[base.len() + 1..]),
+        None => name.to_string(),
+    }
+}
+
+/// Number of parameters in a mangled overload's signature.
+pub(crate) fn arity(name: &str) -> usize {
+    let Some(base) = base_of(name) else {
+        return 0;
+    };
+    let inner = &name[base.len() + 2..name.len() - 1];
+    if inner.is_empty() {
+        return 0;
+    }
+    let mut depth = 0i32;
+    let mut count = 1;
+    for c in inner.chars() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => count += 1,
+            _ => {}
+        }
+    }
+    count
+}
+
+/// Outcome of choosing among overloads.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Pick {
+    One(usize),
+    NoMatch,
+    Ambiguous(Vec<usize>),
+}
+
+/// Choose the best-scoring candidate. `scores[i]` is `None` when
+/// candidate `i` cannot accept the arguments at all.
+pub(crate) fn pick(scores: &[Option<u32>]) -> Pick {
+    let Some(best) = scores.iter().flatten().max().copied() else {
+        return Pick::NoMatch;
+    };
+    let winners: Vec<usize> = scores
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| **s == Some(best))
+        .map(|(i, _)| i)
+        .collect();
+    if winners.len() == 1 {
+        Pick::One(winners[0])
+    } else {
+        Pick::Ambiguous(winners)
+    }
+}
+
+/// Error for a call that no overload accepts.
+pub(crate) fn no_match_error<'a>(
+    base: &str,
+    args: &str,
+    candidates: impl Iterator<Item = &'a str>,
+) -> String {
+    format!(
+        "no overload of `{}` accepts ({}); candidates: {}",
+        base,
+        args,
+        candidates.map(display).collect::<Vec<_>>().join(", ")
+    )
+}
+
+/// Error for a call that two or more overloads accept equally well.
+pub(crate) fn ambiguous_error<'a>(
+    base: &str,
+    args: &str,
+    candidates: impl Iterator<Item = &'a str>,
+) -> String {
+    format!(
+        "ambiguous call to overloaded `{}` with ({}); candidates: {}",
+        base,
+        args,
+        candidates.map(display).collect::<Vec<_>>().join(", ")
+    )
+}
+
+/// Error for an overload set named anywhere but a call's callee. No
+/// backend can pick a member without arguments to pick by.
+pub(crate) fn as_value_error(base: &str) -> String {
+    format!(
+        "overloaded fn `{}` cannot be used as a value; call it, or wrap the overload you want in a closure",
+        base
+    )
+}
+
+/// Interpreter dispatch: the member of `set` that best fits the
+/// runtime `args`.
+pub(crate) fn select<'a>(
+    set: &'a [FunctionValue],
+    args: &[Value],
+) -> Result<&'a FunctionValue, String> {
+    let scores: Vec<Option<u32>> = set
+        .iter()
+        .map(|fv| {
+            if fv.parameters.len() != args.len() {
+                return None;
+            }
+            fv.parameters
+                .iter()
+                .zip(args)
+                .map(|((ty, _), v)| value_score(ty, v, &fv.type_params))
+                .sum()
+        })
+        .collect();
+    let base = set.first().map_or("", |fv| base_name(&fv.name));
+    let names = || set.iter().map(|fv| fv.name.as_str());
+    let described = || args.iter().map(value_type).collect::<Vec<_>>().join(", ");
+    match pick(&scores) {
+        Pick::One(i) => Ok(&set[i]),
+        Pick::NoMatch => Err(no_match_error(base, &described(), names())),
+        Pick::Ambiguous(is) => Err(ambiguous_error(
+            base,
+            &described(),
+            is.into_iter().map(|i| set[i].name.as_str()),
+        )),
+    }
+}
+
+/// How well a runtime value fits a parameter annotation: 2 exact,
+/// 1 accepted without a type test, `None` rejected.
+fn value_score(ty: &str, v: &Value, type_params: &[String]) -> Option<u32> {
+    let ty = crate::linear::strip_linear(ty).trim();
+    if ty.is_empty() || ty == "any" || ty == "Any" || type_params.iter().any(|p| p == ty) {
+        return Some(1);
+    }
+    let exact = match v {
+        Value::Int(_) => matches!(
+            ty,
+            "int" | "Int" | "Int64" | "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64"
+        ),
+        Value::Float(_) => matches!(
+            ty,
+            "float" | "Float" | "f64" | "Float64" | "f32" | "Float32"
+        ),
+        Value::String(_) => matches!(ty, "string" | "String"),
+        Value::Bool(_) => matches!(ty, "bool" | "Bool"),
+        Value::Char(_) => ty == "char",
+        Value::Bytes(_) => matches!(ty, "bytes" | "Bytes"),
+        Value::Array(_) => ty.starts_with('[') || ty.starts_with("Array"),
+        Value::Map(_) => ty.starts_with("Map") || ty == "map",
+        Value::Set(_) => ty.starts_with("Set") || ty == "set",
+        Value::Option(_) => ty.starts_with("Option"),
+        Value::Result { .. } => ty.starts_with("Result"),
+        Value::Tuple(_) => ty.starts_with('('),
+        Value::Function(_)
+        | Value::Closure { .. }
+        | Value::Builtin { .. }
+        | Value::Overloads(_)
+        | Value::EnumConstructor { .. } => ty.starts_with("fn"),
+        Value::Struct { name, .. } => names_type(ty, name),
+        Value::EnumVariant { type_name, .. } => names_type(ty, type_name),
+        _ => false,
+    };
+    if exact {
+        Some(2)
+    } else if is_builtin_type(ty) {
+        None
+    } else {
+        // An alias or trait name: the runtime can't test it, so the
+        // checker's verdict stands.
+        Some(1)
+    }
+}
+
+fn names_type(ty: &str, name: &str) -> bool {
+    ty == name
+        || ty
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.starts_with('<'))
+}
+
+fn is_builtin_type(ty: &str) -> bool {
+    matches!(
+        ty,
+        "int"
+            | "Int"
+            | "Int64"
+            | "i8"
+            | "i16"
+            | "i32"
+            | "i64"
+            | "u8"
+            | "u16"
+            | "u32"
+            | "u64"
+            | "float"
+            | "Float"
+            | "f64"
+            | "Float64"
+            | "f32"
+            | "Float32"
+            | "string"
+            | "String"
+            | "bool"
+            | "Bool"
+            | "char"
+            | "bytes"
+            | "Bytes"
+            | "map"
+            | "set"
+    ) || ["[", "(", "fn", "Array", "Map", "Set", "Option", "Result"]
+        .iter()
+        .any(|p| ty.starts_with(p))
+}
+
+fn value_type(v: &Value) -> String {
+    match v {
+        Value::Struct { name, .. } => name.clone(),
+        Value::EnumVariant { type_name, .. } => type_name.clone(),
+        other => match crate::type_builtins::builtin_type_of(std::slice::from_ref(other)) {
+            Ok(Value::String(s)) => s,
+            _ => "any".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(types: &[&str]) -> Vec<(String, String)> {
+        types
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.to_string(), format!("p{i}")))
+            .collect()
+    }
+
+    #[test]
+    fn mangled_names_round_trip() {
+        let m = mangle("log", &params(&["string", "Map<int, string>"]));
+        assert_eq!(m, "log$(string, Map<int, string>)");
+        assert_eq!(base_of(&m), Some("log"));
+        assert_eq!(display(&m), "log(string, Map<int, string>)");
+        assert_eq!(arity(&m), 2);
+        assert_eq!(arity(&mangle("now", &[])), 0);
+        assert_eq!(mangle("f", &params(&[""])), "f$(any)");
+        assert_eq!(base_of("Point$add"), None);
+    }
+
+    #[test]
+    fn pick_prefers_the_highest_score() {
+        assert_eq!(pick(&[Some(2), Some(4), None]), Pick::One(1));
+        assert_eq!(pick(&[None, None]), Pick::NoMatch);
+        assert_eq!(pick(&[Some(1), Some(1)]), Pick::Ambiguous(vec![0, 1]));
+    }
+
+    #[test]
+    fn lowering_mangles_only_distinct_overload_sets() {
+        let (mut program, errs) = crate::parse(
+            "fn log(string m) {}\nfn log(string l, string m) {}\n\
+             fn dup(int x) {}\nfn dup(int y) {}\nfn solo(int x) {}\n",
+        );
+        assert!(errs.is_empty(), "{errs:?}");
+        lower_program(&mut program);
+        let Node::Program(stmts) = &program else {
+            unreachable!()
+        };
+        let names: Vec<&str> = stmts
+            .iter()
+            .filter_map(|s| match &s.node {
+                Node::Function { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            names,
+            ["log$(string)", "log$(string, string)", "dup", "dup", "solo"]
+        );
+    }
+
+    #[test]
+    fn value_scores_rank_exact_over_accepting() {
+        assert_eq!(value_score("int", &Value::Int(1), &[]), Some(2));
+        assert_eq!(value_score("", &Value::Int(1), &[]), Some(1));
+        assert_eq!(value_score("T", &Value::Int(1), &["T".into()]), Some(1));
+        assert_eq!(value_score("float", &Value::Int(1), &[]), None);
+        assert_eq!(value_score("Meters", &Value::Int(1), &[]), Some(1));
+    }
+}
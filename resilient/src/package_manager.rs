@@ -379,10 +379,10 @@ fn validate_call_sites(node: &Node, source_path: &str) -> Result<(), String> {
             ..
         } => {
             validate_call_sites(body, source_path)?;
-            for req in requires {
+            for req in requires.iter() {
                 validate_call_sites(req, source_path)?;
             }
-            for ens in ensures {
+            for ens in ensures.iter() {
                 validate_call_sites(ens, source_path)?;
             }
             if let Some(rec) = recovers_to {
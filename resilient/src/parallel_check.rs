@@ -0,0 +1,153 @@
+//! Checking top-level fn bodies off the main thread.
+//!
+//! Once the hoisting pass has bound every top-level signature, a fn
+//! whose return type is declared and whose body declares nothing of
+//! its own leaves the checker exactly as its signature says. Its body
+//! can be checked against a snapshot of the checker taken where the
+//! fn appears, on any thread, and the outcome — result, diagnostics,
+//! counters — merged back in source order, so `rz check` reports the
+//! same thing in the same order as a sequential walk.
+//!
+//! `TypeChecker::check_program_with_source` decides where the
+//! snapshots go; this module decides which bodies qualify and runs
+//! the deferred checks: on a Rayon pool with `--features
+//! parallel-check`, one after another on the calling thread without.
+
+use crate::Node;
+use crate::ast::{Visitor, walk_node};
+
+/// Fewer deferrable bodies than this and the program is checked
+/// sequentially: the snapshots would cost more than the threads save.
+pub(crate) const MIN_BODIES: usize = 16;
+
+/// Whether `body` leaves the checker's program-wide tables alone: no
+/// declaration of a type, trait, impl, actor, module or extern, and
+/// no generic fn, anywhere inside it.
+pub(crate) fn declares_nothing(body: &Node) -> bool {
+    struct Finder {
+        found: bool,
+    }
+
+    impl Visitor for Finder {
+        fn visit_node(&mut self, node: &Node) {
+            if self.found {
+                return;
+            }
+            match node {
+                Node::Use { .. }
+                | Node::Extern { .. }
+                | Node::StructDecl { .. }
+                | Node::EnumDecl { .. }
+                | Node::TypeAlias { .. }
+                | Node::NewtypeDecl { .. }
+                | Node::TraitDecl { .. }
+                | Node::ImplBlock { .. }
+                | Node::BlanketImpl { .. }
+                | Node::RegionDecl { .. }
+                | Node::Actor { .. }
+                | Node::ActorDecl { .. }
+                | Node::ClusterDecl { .. }
+                | Node::SupervisorDecl { .. }
+                | Node::ModuleDecl { .. }
+                | Node::BenchBlock { .. }
+                | Node::TestFn { .. } => self.found = true,
+                Node::Function { type_params, .. } if !type_params.is_empty() => self.found = true,
+                _ => walk_node(self, node),
+            }
+        }
+    }
+
+    let mut finder = Finder { found: false };
+    finder.visit_node(body);
+    !finder.found
+}
+
+/// Whether `body` binds or assigns, at any depth, a name for which
+/// `held` is true.
+pub(crate) fn writes_any(body: &Node, held: impl Fn(&str) -> bool) -> bool {
+    struct Finder<F> {
+        held: F,
+        found: bool,
+    }
+
+    impl<F: Fn(&str) -> bool> Visitor for Finder<F> {
+        fn visit_node(&mut self, node: &Node) {
+            if self.found {
+                return;
+            }
+            match node {
+                Node::LetStatement { name, .. }
+                | Node::StaticLet { name, .. }
+                | Node::Const { name, .. }
+                | Node::Assignment { name, .. }
+                    if (self.held)(name) =>
+                {
+                    self.found = true
+                }
+                Node::LetDestructureStruct { fields, .. }
+                    if fields.iter().any(|(_, local)| (self.held)(local)) =>
+                {
+                    self.found = true
+                }
+                _ => walk_node(self, node),
+            }
+        }
+    }
+
+    let mut finder = Finder { held, found: false };
+    finder.visit_node(body);
+    finder.found
+}
+
+/// Run `check` over `jobs` and return the outcomes in job order.
+/// Each worker builds its own state with `init` and reuses it for
+/// every job it picks up.
+#[cfg(feature = "parallel-check")]
+pub(crate) fn run<J, S, R>(
+    jobs: Vec<J>,
+    init: impl Fn() -> S + Sync + Send,
+    check: impl Fn(&mut S, J) -> R + Sync + Send,
+) -> Vec<R>
+where
+    J: Send,
+    R: Send,
+{
+    use rayon::prelude::*;
+
+    match pool() {
+        Some(pool) => pool.install(|| jobs.into_par_iter().map_init(init, check).collect()),
+        None => {
+            let mut state = init();
+            jobs.into_iter().map(|job| check(&mut state, job)).collect()
+        }
+    }
+}
+
+/// Without `--features parallel-check` the deferred bodies are
+/// checked in order on the calling thread.
+#[cfg(not(feature = "parallel-check"))]
+pub(crate) fn run<J, S, R>(
+    jobs: Vec<J>,
+    init: impl Fn() -> S,
+    check: impl Fn(&mut S, J) -> R,
+) -> Vec<R> {
+    let mut state = init();
+    jobs.into_iter().map(|job| check(&mut state, job)).collect()
+}
+
+/// The checker's pool. Workers get the same stack as the CLI thread
+/// (see `main.rs`): they run the same `check_node` recursion. `None`
+/// when the threads can't be spawned; the caller then runs in place.
+#[cfg(feature = "parallel-check")]
+fn pool() -> Option<&'static rayon::ThreadPool> {
+    const WORKER_STACK: usize = 96 * 1024 * 1024;
+    static POOL: std::sync::OnceLock<Option<rayon::ThreadPool>> = std::sync::OnceLock::new();
+    POOL.get_or_init(|| {
+        rayon::ThreadPoolBuilder::new()
+            .stack_size(WORKER_STACK)
+            .thread_name(|i| format!("rz-check-{i}"))
+            .build()
+            .ok()
+    })
+    .as_ref()
+}
@@ -205,7 +205,7 @@ fn check_calls_recursive(
     } = node
     {
         if let Node::Identifier { name, .. } = function.as_ref() {
-            if let Some(&param_idx) = destructuring_funcs.get(name) {
+            if let Some(&param_idx) = destructuring_funcs.get(name.as_str()) {
                 // Function has destructuring at param_idx; calls must provide at least param_idx + 1 args
                 let required_args = param_idx + 1;
                 if arguments.len() < required_args {
@@ -264,12 +264,12 @@ mod tests {
                 name: "destructure".to_string(),
                 parameters: vec![(param_ty.to_string(), param_name.to_string())],
                 defaults: vec![None],
-                body: Box::new(Node::Block {
+                body: std::sync::Arc::new(Node::Block {
                     stmts: Vec::new(),
                     span: Span::default(),
                 }),
-                requires: Vec::new(),
-                ensures: Vec::new(),
+                requires: std::sync::Arc::default(),
+                ensures: std::sync::Arc::default(),
                 recovers_to: None,
                 return_type: None,
                 span: Span::default(),
@@ -872,14 +872,15 @@ fn main() {
 
     #[test]
     fn check_rejects_same_destructuring_function_twice() {
-        // RES-3237: redefining the same destructuring function is an error
+        // RES-3237: redefining the same destructuring function is an error.
+        // Differing parameter types would make these overloads instead.
         let src = r#"
 fn pair((int, int) _x_y) -> int {
     return 0;
 }
 
-fn pair((float, float) _a_b) -> float {
-    return 0.0;
+fn pair((int, int) _a_b) -> int {
+    return 1;
 }
 "#;
         let (prog, _) = crate::parse(src);
@@ -0,0 +1,36 @@
+//! Source text to AST.
+//!
+//! Both entry points lex, parse and run the lowering passes the driver
+//! runs (overload mangling, named and default arguments, newtypes, ...),
+//! so the tree is the one the interpreter and type checker expect.
+//! Neither prints anything or panics, whatever the input.
+//!
+//! - [`parse_source`] returns the `Node::Program` or every error.
+//! - [`parse_recovering`] always returns the tree the parser recovered,
+//!   next to the errors, for editors that work on broken code.
+//!
+//! ```
+//! use resilient::parser;
+//!
+//! assert!(parser::parse_source("fn f(int x) -> int { return x; }").is_ok());
+//!
+//! let (program, errors) = parser::parse_recovering("let x = 1;\nlet = 2;\nlet y = 3;");
+//! assert_eq!(errors[0].span.start.line, 2);
+//! assert!(matches!(program, resilient::Node::Program(stmts) if !stmts.is_empty()));
+//! ```
+
+pub use crate::diag::Diagnostic;
+pub use crate::parse_source;
+
+use crate::Node;
+
+/// Parse `src`, keeping the partial program even when there are
+/// errors. The errors are the ones [`parse_source`] reports.
+pub fn parse_recovering(src: &str) -> (Node, Vec<Diagnostic>) {
+    let (program, errs) = crate::parse_silent(src);
+    let diagnostics = errs
+        .iter()
+        .map(|e| crate::parse_error_diagnostic(src, e))
+        .collect();
+    (program, diagnostics)
+}
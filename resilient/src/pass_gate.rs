@@ -469,12 +469,12 @@ mod tests {
                     .map(|(t, n)| (t.to_string(), n.to_string()))
                     .collect(),
                 defaults: Vec::new(),
-                body: Box::new(Node::Block {
+                body: std::sync::Arc::new(Node::Block {
                     stmts: Vec::new(),
                     span: span::Span::default(),
                 }),
-                requires: Vec::new(),
-                ensures: Vec::new(),
+                requires: std::sync::Arc::default(),
+                ensures: std::sync::Arc::default(),
                 recovers_to: None,
                 return_type: None,
                 span: span::Span::default(),
@@ -585,7 +585,7 @@ mod tests {
 
     fn id(name: &str) -> Box<Node> {
         Box::new(Node::Identifier {
-            name: name.to_string(),
+            name: name.into(),
             span: span::Span::default(),
         })
     }
@@ -640,12 +640,12 @@ mod tests {
                     .map(|(t, n)| (t.to_string(), n.to_string()))
                     .collect(),
                 defaults: Vec::new(),
-                body: Box::new(Node::Block {
+                body: std::sync::Arc::new(Node::Block {
                     stmts: body,
                     span: span::Span::default(),
                 }),
-                requires: Vec::new(),
-                ensures: Vec::new(),
+                requires: std::sync::Arc::default(),
+                ensures: std::sync::Arc::default(),
                 recovers_to: None,
                 return_type: None,
                 span: span::Span::default(),
@@ -907,12 +907,12 @@ mod tests {
                 name: name.to_string(),
                 parameters: Vec::new(),
                 defaults: Vec::new(),
-                body: Box::new(Node::Block {
+                body: std::sync::Arc::new(Node::Block {
                     stmts: Vec::new(),
                     span: span::Span::default(),
                 }),
-                requires: Vec::new(),
-                ensures: Vec::new(),
+                requires: std::sync::Arc::default(),
+                ensures: std::sync::Arc::default(),
                 recovers_to: None,
                 return_type: None,
                 span: span::Span::default(),
@@ -961,12 +961,12 @@ mod tests {
                 name: "kpure".to_string(),
                 parameters: Vec::new(),
                 defaults: Vec::new(),
-                body: Box::new(Node::Block {
+                body: std::sync::Arc::new(Node::Block {
                     stmts: Vec::new(),
                     span: span::Span::default(),
                 }),
-                requires: Vec::new(),
-                ensures: Vec::new(),
+                requires: std::sync::Arc::default(),
+                ensures: std::sync::Arc::default(),
                 recovers_to: None,
                 return_type: None,
                 span: span::Span::default(),
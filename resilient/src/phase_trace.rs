@@ -0,0 +1,154 @@
+//! `tracing` spans and events for the interpreter's phases, behind the
+//! `tracing` feature.
+//!
+//! A host embedding the library that already runs a `tracing`
+//! subscriber sees, under the `resilient` target:
+//!
+//! - an INFO span per phase: `lex`, `parse`, `typecheck` (with the
+//!   `file` being checked) and `eval`;
+//! - an INFO span `actor_dispatch` (with the actor's `pid`) around
+//!   each turn the scheduler gives an actor;
+//! - a WARN event `live_retry` (`line`, `attempt`, `max_retries`,
+//!   `error`) each time a `live` block retries after a fault, and an
+//!   ERROR event `live_exhausted` (`line`, `attempts`, `reason`) when
+//!   one gives up.
+//!
+//! The default lexer scans on demand as the parser asks for tokens, so
+//! most lexing is timed inside `parse`; `lex` covers the up-front scan
+//! the `logos-lexer` feature does.
+//!
+//! Without the feature every function here is an empty inline body
+//! and the guards are zero-sized, so the hooks cost nothing.
+
+#[cfg(feature = "tracing")]
+const TARGET: &str = "resilient";
+
+/// An entered span; the phase ends when it is dropped.
+#[must_use]
+pub(crate) struct Entered {
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! enter_span {
+    ($name:literal $(, $($field:tt)*)?) => {
+        Entered {
+            _span: tracing::info_span!(target: TARGET, $name $(, $($field)*)?).entered(),
+        }
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! enter_span {
+    ($name:literal $(, $($field:tt)*)?) => {
+        Entered {}
+    };
+}
+
+#[inline]
+pub(crate) fn lex() -> Entered {
+    enter_span!("lex")
+}
+
+#[inline]
+pub(crate) fn parse() -> Entered {
+    enter_span!("parse")
+}
+
+#[inline]
+pub(crate) fn typecheck(file: &str) -> Entered {
+    let _ = file;
+    enter_span!("typecheck", file)
+}
+
+#[inline]
+pub(crate) fn eval() -> Entered {
+    enter_span!("eval")
+}
+
+#[inline]
+pub(crate) fn actor_dispatch(pid: u64) -> Entered {
+    let _ = pid;
+    enter_span!("actor_dispatch", pid)
+}
+
+/// A `live` block starting on `line` is about to retry.
+#[inline]
+pub(crate) fn live_retry(line: usize, attempt: usize, max_retries: usize, error: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(target: TARGET, line, attempt, max_retries, error, "live_retry");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (line, attempt, max_retries, error);
+}
+
+/// A `live` block starting on `line` gave up after `attempts`.
+#[inline]
+pub(crate) fn live_exhausted(line: usize, attempts: usize, reason: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::error!(target: TARGET, line, attempts, reason, "live_exhausted");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (line, attempts, reason);
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Records span names and event messages in the order they happen.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    struct Message<'a>(&'a mut String);
+
+    impl Visit for Message<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                *self.0 = format!("{value:?}");
+            }
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == "resilient"
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut names = self.0.lock().unwrap();
+            names.push(format!("span {}", span.metadata().name()));
+            Id::from_u64(names.len() as u64)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            let mut message = String::new();
+            event.record(&mut Message(&mut message));
+            self.0.lock().unwrap().push(format!("event {message}"));
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn phases_and_live_retries_reach_the_subscriber() {
+        let recorder = Recorder::default();
+        let result = tracing::subscriber::with_default(recorder.clone(), || {
+            crate::run_program(
+                "let d = 0;\nlive retries(3) {\n    if live_retries() < 1 { let z = 1 / d; }\n}\n",
+            )
+        });
+        assert!(result.ok, "{:?}", result.errors);
+        let seen = recorder.0.lock().unwrap().clone();
+        let position = |entry: &str| {
+            seen.iter()
+                .position(|s| s == entry)
+                .unwrap_or_else(|| panic!("no `{entry}` in {seen:?}"))
+        };
+        assert!(position("span parse") < position("span lex"));
+        assert!(position("span lex") < position("span eval"));
+        assert!(position("span eval") < position("event live_retry"));
+    }
+}
@@ -1465,6 +1465,29 @@ pub fn resolve_dep_module(
     }
 }
 
+/// Resolve a string import `use "dep/module";` against the
+/// dependencies in the nearest `resilient.toml`.
+///
+/// The first `/`-separated segment names the dependency and the rest
+/// is the module path inside its `src/`, with or without the `.rz`
+/// extension: `"netutil/http"` and `"netutil/http.rz"` both map to
+/// `<dep_root>/src/http.rz`, and `"mylib/sub/leaf"` to
+/// `<dep_root>/src/sub/leaf.rz`. Returns `Ok(None)` when the first
+/// segment isn't a declared dependency or the module file is missing.
+///
+/// Called from `imports.rs` when a string `use` path doesn't name a
+/// file relative to the importing one.
+pub fn resolve_dep_import(start_dir: &Path, path: &str) -> Result<Option<PathBuf>, String> {
+    let Some((dep_name, module)) = path.split_once('/') else {
+        return Ok(None);
+    };
+    let module = module.strip_suffix(".rz").unwrap_or(module);
+    if dep_name.is_empty() || dep_name.starts_with('.') || module.is_empty() {
+        return Ok(None);
+    }
+    resolve_dep_module(start_dir, dep_name, &module.replace('/', "::"))
+}
+
 // ── Tests ────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -2000,6 +2023,45 @@ lib = { git = \"https://ex.com/lib\", branch = \"develop\" }
         let _ = fs::remove_dir_all(&project);
     }
 
+    #[test]
+    fn resolve_dep_import_maps_a_slash_path_into_the_dep() {
+        let project = tmp_dir("dep_import");
+        fs::write(
+            project.join("resilient.toml"),
+            "[package]\nname = \"proj\"\n\n[dependencies]\nmylib = { path = \"mylib\" }\n",
+        )
+        .unwrap();
+        let dep_dir = project.join("mylib");
+        fs::create_dir_all(dep_dir.join("src/sub")).unwrap();
+        fs::write(
+            dep_dir.join("resilient.toml"),
+            "[package]\nname = \"mylib\"\n",
+        )
+        .unwrap();
+        fs::write(dep_dir.join("src/foo.rz"), "pub fn hello() { return 1; }").unwrap();
+        fs::write(
+            dep_dir.join("src/sub/leaf.rz"),
+            "pub fn hello() { return 1; }",
+        )
+        .unwrap();
+
+        for (path, want) in [
+            ("mylib/foo", "foo.rz"),
+            ("mylib/foo.rz", "foo.rz"),
+            ("mylib/sub/leaf", "leaf.rz"),
+        ] {
+            let got = resolve_dep_import(&project, path).unwrap();
+            assert!(
+                got.as_deref().is_some_and(|p| p.ends_with(want)),
+                "{path}: {got:?}"
+            );
+        }
+        for path in ["mylib/missing", "other/foo", "./mylib/foo", "foo.rz"] {
+            assert_eq!(resolve_dep_import(&project, path).unwrap(), None, "{path}");
+        }
+        let _ = fs::remove_dir_all(&project);
+    }
+
     // ── resolve_all integration test ─────────────────────────────
 
     #[test]
@@ -148,24 +148,7 @@ fn validate_name(name: &str) -> Result<(), PkgInitError> {
 ///
 /// Returns the paths of every file we wrote in the order written.
 pub fn scaffold_in(parent: &Path, name: &str) -> Result<Scaffold, PkgInitError> {
-    validate_name(name)?;
-    let root = parent.join(name);
-
-    // Directory-state checks. Manifest-exists wins over the general
-    // non-empty-dir error so callers get a sharper message — the
-    // idempotency guard is the common case ("I already ran init").
-    if root.exists() {
-        let manifest_path = root.join(MANIFEST_FILENAME);
-        if manifest_path.exists() {
-            return Err(PkgInitError::ManifestExists(manifest_path));
-        }
-        let is_non_empty = fs::read_dir(&root)?.next().is_some();
-        if is_non_empty {
-            return Err(PkgInitError::DirectoryNotEmpty(root));
-        }
-    } else {
-        fs::create_dir(&root)?;
-    }
+    let root = prepare_root(parent, name)?;
 
     let src_dir = root.join("src");
     fs::create_dir_all(&src_dir)?;
@@ -195,6 +178,31 @@ pub fn scaffold_in(parent: &Path, name: &str) -> Result<Scaffold, PkgInitError>
     })
 }
 
+/// Check `name` and make `<parent>/<name>` ready to scaffold into:
+/// created if missing, refused if it holds anything (a manifest in
+/// particular). Shared with `rz new`.
+pub(crate) fn prepare_root(parent: &Path, name: &str) -> Result<PathBuf, PkgInitError> {
+    validate_name(name)?;
+    let root = parent.join(name);
+
+    // Directory-state checks. Manifest-exists wins over the general
+    // non-empty-dir error so callers get a sharper message — the
+    // idempotency guard is the common case ("I already ran init").
+    if root.exists() {
+        let manifest_path = root.join(MANIFEST_FILENAME);
+        if manifest_path.exists() {
+            return Err(PkgInitError::ManifestExists(manifest_path));
+        }
+        let is_non_empty = fs::read_dir(&root)?.next().is_some();
+        if is_non_empty {
+            return Err(PkgInitError::DirectoryNotEmpty(root));
+        }
+    } else {
+        fs::create_dir(&root)?;
+    }
+    Ok(root)
+}
+
 /// Render the `resilient.toml` body. Pure — factored out so tests
 /// can assert on the exact bytes without an on-disk round-trip.
 ///
@@ -264,6 +272,18 @@ pub fn render_gitignore() -> &'static str {
 /// The manifests this function reads are the ones we write in
 /// `render_manifest`, so the tight shape is fine in practice.
 pub fn read_package_name(manifest_path: &Path) -> Option<String> {
+    read_package_field(manifest_path, "name")
+}
+
+/// The project's entry file from `[package].entry`, relative to the
+/// manifest. Same reader as [`read_package_name`]; `None` when the
+/// field is absent.
+pub fn read_package_entry(manifest_path: &Path) -> Option<String> {
+    read_package_field(manifest_path, "entry")
+}
+
+/// A `key = "..."` string under `[package]`.
+fn read_package_field(manifest_path: &Path, field: &str) -> Option<String> {
     let contents = fs::read_to_string(manifest_path).ok()?;
     let mut in_package = false;
     for raw in contents.lines() {
@@ -281,11 +301,11 @@ pub fn read_package_name(manifest_path: &Path) -> Option<String> {
         if !in_package {
             continue;
         }
-        // Look for `name = "..."`. Split on the first `=`.
+        // Look for `<field> = "..."`. Split on the first `=`.
         let Some((key, val)) = line.split_once('=') else {
             continue;
         };
-        if key.trim() != "name" {
+        if key.trim() != field {
             continue;
         }
         // Expect `"..."` (double-quoted basic string).
@@ -0,0 +1,293 @@
+//! Builtin packages loaded at startup.
+//!
+//! A plugin is a `cdylib` that depends on this crate and exports a
+//! register function. It adds builtins the way the core adds its own,
+//! so heavyweight libraries (Modbus, SQLite, ...) stay out of the core
+//! build:
+//!
+//! ```
+//! use resilient::bridge::{FromResilient, IntoResilient, ResilientValue};
+//! use resilient::plugin::Registry;
+//!
+//! fn register(registry: &mut Registry) {
+//!     registry.builtin("modbus_crc", |args| {
+//!         let [frame] = args else {
+//!             return Err(format!("modbus_crc: expected 1 argument, got {}", args.len()));
+//!         };
+//!         let frame = String::from_resilient(frame.clone()).map_err(|e| e.to_string())?;
+//!         Ok(crc16(frame.as_bytes()).into_resilient())
+//!     });
+//! }
+//! # fn crc16(bytes: &[u8]) -> i64 { bytes.len() as i64 }
+//!
+//! // In the plugin crate: `resilient::export_plugin!(register);`
+//! // For a host that links the plugin in instead:
+//! resilient::plugin::add(register).unwrap();
+//! let result = resilient::interpreter::run_program("println(modbus_crc(\"abc\"));");
+//! assert_eq!(result.stdout, "3\n");
+//! ```
+//!
+//! `rz --plugin libmodbus.so prog.rz` loads one (repeatable; needs the
+//! `plugins` feature). A plugin builtin is called like any other and
+//! typechecks as `Any`. It reports failure by returning `Err`, which
+//! surfaces as a runtime error; a panic inside a plugin aborts `rz`.
+//!
+//! The register function is a Rust function, and the values it trades
+//! are this crate's, so a plugin must be built by the same `rustc`
+//! against the same version of this crate, with the same `ffi`
+//! setting. [`export_plugin!`](crate::export_plugin) also exports that
+//! build's [`ABI`] string; loading compares it with the host's and
+//! refuses a mismatch instead of calling into it.
+
+use std::sync::RwLock;
+
+use crate::bridge::ResilientValue;
+use crate::{BuiltinFn, RResult, Value};
+
+/// A plugin builtin: arguments in, a value or an error message out.
+pub type PluginFn = fn(&[ResilientValue]) -> Result<ResilientValue, String>;
+
+/// The crate version, `ffi` setting and `rustc` a plugin was built
+/// with, NUL-terminated for the `resilient_plugin_abi` export.
+#[cfg(feature = "ffi")]
+pub const ABI: &str = concat!(
+    "resilient ",
+    env!("CARGO_PKG_VERSION"),
+    " +ffi, ",
+    env!("RESILIENT_BUILD_RUSTC_VERSION"),
+    "\0"
+);
+#[cfg(not(feature = "ffi"))]
+pub const ABI: &str = concat!(
+    "resilient ",
+    env!("CARGO_PKG_VERSION"),
+    ", ",
+    env!("RESILIENT_BUILD_RUSTC_VERSION"),
+    "\0"
+);
+
+/// Export a plugin's register function under the names `--plugin`
+/// looks up: `resilient_plugin_register(&mut Registry)` and
+/// `resilient_plugin_abi`.
+#[macro_export]
+macro_rules! export_plugin {
+    ($register:path) => {
+        #[unsafe(no_mangle)]
+        pub extern "C" fn resilient_plugin_abi() -> *const ::std::os::raw::c_char {
+            $crate::plugin::ABI.as_ptr().cast()
+        }
+
+        #[unsafe(no_mangle)]
+        pub fn resilient_plugin_register(registry: &mut $crate::plugin::Registry) {
+            $register(registry)
+        }
+    };
+}
+
+/// The builtins one plugin adds, filled in by its register function.
+#[derive(Default)]
+pub struct Registry {
+    builtins: Vec<(String, PluginFn)>,
+}
+
+impl Registry {
+    /// Add builtin `name`.
+    pub fn builtin(&mut self, name: &str, func: PluginFn) -> &mut Registry {
+        self.builtins.push((name.to_string(), func));
+        self
+    }
+}
+
+/// Why a plugin was not loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginError {
+    /// The library could not be opened.
+    Open { path: String, underlying: String },
+    /// The library exports no `resilient_plugin_register`.
+    NotAPlugin { path: String },
+    /// The plugin was built with another version of this crate or
+    /// another `rustc`.
+    AbiMismatch {
+        path: String,
+        plugin: String,
+        host: String,
+    },
+    /// A builtin of that name already exists.
+    Duplicate { plugin: String, name: String },
+    /// Every builtin slot is taken.
+    TooManyBuiltins { plugin: String },
+    /// This build has no `plugins` feature.
+    Unsupported,
+}
+
+impl std::fmt::Display for PluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginError::Open { path, underlying } => {
+                write!(f, "plugin `{}`: cannot open library: {}", path, underlying)
+            }
+            PluginError::NotAPlugin { path } => write!(
+                f,
+                "plugin `{}`: not a Resilient plugin (no `resilient_plugin_register`)",
+                path
+            ),
+            PluginError::AbiMismatch { path, plugin, host } => write!(
+                f,
+                "plugin `{}`: built for {}, but this rz is {}; rebuild the plugin against it",
+                path, plugin, host
+            ),
+            PluginError::Duplicate { plugin, name } => {
+                write!(f, "plugin `{}`: builtin `{}` already exists", plugin, name)
+            }
+            PluginError::TooManyBuiltins { plugin } => write!(
+                f,
+                "plugin `{}`: more than {} plugin builtins in total",
+                plugin,
+                SLOTS.len()
+            ),
+            PluginError::Unsupported => {
+                write!(f, "loading plugins requires the `plugins` feature")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+/// Every plugin builtin added so far, by slot.
+static LOADED: RwLock<Vec<(&'static str, PluginFn)>> = RwLock::new(Vec::new());
+
+/// Add the builtins of a plugin linked into the host, as loading it
+/// would. Programs run afterwards can call them.
+pub fn add(register: fn(&mut Registry)) -> Result<(), PluginError> {
+    install("<static>", register)
+}
+
+/// Load the plugin library at `path` and add its builtins. The library
+/// stays loaded for the life of the process.
+#[cfg(feature = "plugins")]
+pub fn load(path: &str) -> Result<(), PluginError> {
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+    use std::sync::Mutex;
+
+    static LIBRARIES: Mutex<Vec<libloading::Library>> = Mutex::new(Vec::new());
+
+    // SAFETY: opening a library runs its initializers; `--plugin`
+    // names code the operator chose to trust, like `--ffi-lib`.
+    let library = unsafe { libloading::Library::new(path) }.map_err(|e| PluginError::Open {
+        path: path.to_string(),
+        underlying: e.to_string(),
+    })?;
+    // SAFETY: both symbols have the types `export_plugin!` gives them;
+    // the register function is only called once the ABI strings match,
+    // so its Rust signature means the same thing on both sides.
+    let register = unsafe {
+        let abi = library
+            .get::<extern "C" fn() -> *const c_char>(b"resilient_plugin_abi\0")
+            .map_err(|_| PluginError::NotAPlugin {
+                path: path.to_string(),
+            })?;
+        let plugin_abi = CStr::from_ptr(abi()).to_string_lossy().into_owned();
+        let host_abi = ABI.trim_end_matches('\0');
+        if plugin_abi != host_abi {
+            return Err(PluginError::AbiMismatch {
+                path: path.to_string(),
+                plugin: plugin_abi,
+                host: host_abi.to_string(),
+            });
+        }
+        *library
+            .get::<fn(&mut Registry)>(b"resilient_plugin_register\0")
+            .map_err(|_| PluginError::NotAPlugin {
+                path: path.to_string(),
+            })?
+    };
+    install(path, register)?;
+    if let Ok(mut libraries) = LIBRARIES.lock() {
+        libraries.push(library);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "plugins"))]
+pub fn load(_path: &str) -> Result<(), PluginError> {
+    Err(PluginError::Unsupported)
+}
+
+fn install(plugin: &str, register: fn(&mut Registry)) -> Result<(), PluginError> {
+    let mut registry = Registry::default();
+    register(&mut registry);
+    let mut loaded = LOADED.write().unwrap_or_else(|e| e.into_inner());
+    for (i, (name, _)) in registry.builtins.iter().enumerate() {
+        let taken = crate::builtin_registry::lookup(name).is_some()
+            || loaded.iter().any(|(n, _)| n == name)
+            || registry.builtins[..i].iter().any(|(n, _)| n == name);
+        if taken {
+            return Err(PluginError::Duplicate {
+                plugin: plugin.to_string(),
+                name: name.clone(),
+            });
+        }
+    }
+    if loaded.len() + registry.builtins.len() > SLOTS.len() {
+        return Err(PluginError::TooManyBuiltins {
+            plugin: plugin.to_string(),
+        });
+    }
+    for (name, func) in registry.builtins {
+        // Leaked once per builtin per process, like `extern` names.
+        loaded.push((Box::leak(name.into_boxed_str()), func));
+    }
+    Ok(())
+}
+
+/// The names of every plugin builtin, for the typechecker.
+pub(crate) fn builtin_names() -> Vec<&'static str> {
+    let loaded = LOADED.read().unwrap_or_else(|e| e.into_inner());
+    loaded.iter().map(|(name, _)| *name).collect()
+}
+
+/// Bind every plugin builtin into an interpreter's environment.
+pub(crate) fn bind(env: &crate::Environment) {
+    let loaded = LOADED.read().unwrap_or_else(|e| e.into_inner());
+    for (slot, &(name, _)) in loaded.iter().enumerate() {
+        env.set(
+            name.to_string(),
+            Value::Builtin {
+                name,
+                func: SLOTS[slot],
+            },
+        );
+    }
+}
+
+/// The evaluator of plugin builtin `name`, for the VM.
+pub(crate) fn lookup(name: &str) -> Option<BuiltinFn> {
+    let loaded = LOADED.read().unwrap_or_else(|e| e.into_inner());
+    let slot = loaded.iter().position(|(n, _)| *n == name)?;
+    Some(SLOTS[slot])
+}
+
+/// A builtin's evaluator is a plain `fn` pointer, so each plugin
+/// builtin is bound to one of these trampolines; the slot number finds
+/// its `PluginFn`.
+fn call<const SLOT: usize>(args: &[Value]) -> RResult<Value> {
+    let func = LOADED.read().unwrap_or_else(|e| e.into_inner())[SLOT].1;
+    let args: Vec<ResilientValue> = args.iter().cloned().map(ResilientValue).collect();
+    func(&args).map(|value| value.0)
+}
+
+macro_rules! slots {
+    ($($slot:literal)*) => {
+        [$(call::<$slot> as BuiltinFn),*]
+    };
+}
+
+const SLOTS: [BuiltinFn; 128] = slots!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+    32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+    64 65 66 67 68 69 70 71 72 73 74 75 76 77 78 79 80 81 82 83 84 85 86 87 88 89 90 91 92 93 94 95
+    96 97 98 99 100 101 102 103 104 105 106 107 108 109 110 111 112 113 114 115 116 117 118 119 120
+    121 122 123 124 125 126 127
+);
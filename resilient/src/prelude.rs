@@ -0,0 +1,159 @@
+//! The prelude: convenience functions written in Resilient itself
+//! (`prelude.rz`, embedded at build time) and available to every
+//! program and REPL session without a `use`.
+//!
+//! The tree-walker evaluates the prelude once per thread into a frame
+//! between the builtins and each interpreter's globals, so a program's
+//! own `fn retry` shadows the prelude's. The typechecker sees only the
+//! signatures. The VM and JIT have no such frame, so [`link`] copies
+//! the prelude functions a program calls into it before compiling.
+//!
+//! `rz --no-prelude` turns it off for the run.
+
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Node;
+use crate::span::Spanned;
+
+/// The prelude's source.
+pub const SOURCE: &str = include_str!("prelude.rz");
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // Parsed per thread: string literals are interned in a
+    // thread-local pool, so the nodes only mean something on the
+    // thread that parsed them.
+    static PROGRAM: Rc<[Spanned<Node>]> = match crate::parse_source(SOURCE) {
+        Ok(Node::Program(stmts)) => stmts.into(),
+        Ok(_) => unreachable!("parse_source returns a Node::Program"),
+        Err(errors) => panic!("prelude.rz does not parse: {:?}", errors),
+    };
+}
+
+/// Whether programs get the prelude. On unless `--no-prelude`.
+pub fn enabled() -> bool {
+    !DISABLED.load(Ordering::Relaxed)
+}
+
+/// `--no-prelude`.
+pub fn set_enabled(on: bool) {
+    DISABLED.store(!on, Ordering::Relaxed);
+}
+
+/// The prelude's top-level declarations.
+pub(crate) fn items() -> Rc<[Spanned<Node>]> {
+    PROGRAM.with(Rc::clone)
+}
+
+/// A function's name, parameters and return type.
+pub(crate) type Signature = (String, Vec<(String, String)>, Option<String>);
+
+/// The signature of each prelude function.
+pub(crate) fn signatures() -> Vec<Signature> {
+    items()
+        .iter()
+        .filter_map(|stmt| match &stmt.node {
+            Node::Function {
+                name,
+                parameters,
+                return_type,
+                ..
+            } => Some((name.clone(), parameters.clone(), return_type.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `program` with the prelude functions it calls, directly or through
+/// other prelude functions, appended. Names the program declares
+/// itself are left alone. Returns `program` unchanged when the prelude
+/// is off or unused.
+pub(crate) fn link(program: &Node) -> std::borrow::Cow<'_, Node> {
+    use std::borrow::Cow;
+    let Node::Program(stmts) = program else {
+        return Cow::Borrowed(program);
+    };
+    if !enabled() {
+        return Cow::Borrowed(program);
+    }
+    let declared: HashSet<&str> = stmts
+        .iter()
+        .filter_map(|stmt| match &stmt.node {
+            Node::Function { name, .. } => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    let prelude = items();
+    let find = |name: &str| {
+        prelude
+            .iter()
+            .find(|stmt| matches!(&stmt.node, Node::Function { name: n, .. } if n == name))
+    };
+    let mut wanted = HashSet::new();
+    let mut pending = vec![program];
+    while let Some(node) = pending.pop() {
+        for name in crate::free_vars::free_vars(node) {
+            if declared.contains(name.as_str()) || wanted.contains(&name) {
+                continue;
+            }
+            if let Some(stmt) = find(&name) {
+                pending.push(&stmt.node);
+                wanted.insert(name);
+            }
+        }
+    }
+    if wanted.is_empty() {
+        return Cow::Borrowed(program);
+    }
+    let mut linked = stmts.clone();
+    linked.extend(
+        prelude
+            .iter()
+            .filter(|stmt| matches!(&stmt.node, Node::Function { name, .. } if wanted.contains(name.as_str())))
+            .cloned(),
+    );
+    Cow::Owned(Node::Program(linked))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_prelude_typechecks() {
+        let program = Node::Program(items().to_vec());
+        crate::typechecker::TypeChecker::new()
+            .check_program(&program)
+            .unwrap();
+    }
+
+    #[test]
+    fn link_brings_in_what_the_program_calls() {
+        let names = |src: &str| -> Vec<String> {
+            let program = crate::parse_source(src).unwrap();
+            let Node::Program(stmts) = link(&program).into_owned() else {
+                unreachable!()
+            };
+            stmts
+                .iter()
+                .filter_map(|stmt| match &stmt.node {
+                    Node::Function { name, .. } => Some(name.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert!(names("println(1);").is_empty());
+        // `retry_or` calls `retry`.
+        let mut linked = names("fn f() -> Result { return Ok(1); }\nprintln(retry_or(f, 2, 0));");
+        linked.sort();
+        assert_eq!(linked, ["f", "retry", "retry_or"]);
+        // A program's own declaration is not duplicated.
+        assert_eq!(
+            names("fn in_range(int x) -> bool { return true; }\nprintln(in_range(1));"),
+            ["in_range"]
+        );
+    }
+}
@@ -12,7 +12,9 @@
 //!
 //! Non-zero exit codes are NOT errors — check `exit_code` yourself.
 //! A `Result::Err` is only returned when the process cannot be spawned
-//! (e.g., command not found, permission denied).
+//! (e.g., command not found, permission denied), or when an `--allow`
+//! policy leaves out `exec` (`exec: PermissionDenied: ...`, see
+//! `sandbox`).
 //!
 //! RES-4126: on `wasm32` (the web playground) there is no host
 //! process to spawn — `std::process::Command` is unimplemented for
@@ -21,17 +23,19 @@
 //! instead of failing to build.
 
 use crate::Value;
+use crate::sandbox::Capability;
+use std::rc::Rc;
 
 type RResult<T> = Result<T, String>;
 
 fn make_process_result(stdout: String, stderr: String, exit_code: i64) -> Value {
     Value::Struct {
         name: "ProcessResult".to_string(),
-        fields: vec![
+        fields: Rc::new(vec![
             ("stdout".to_string(), Value::String(stdout)),
             ("stderr".to_string(), Value::String(stderr)),
             ("exit_code".to_string(), Value::Int(exit_code)),
-        ],
+        ]),
     }
 }
 
@@ -70,6 +74,9 @@ fn unsupported(builtin: &str) -> Value {
 pub(crate) fn builtin_exec(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(cmd), Value::Array(arg_vals)] => {
+            if let Err(denied) = crate::sandbox::require("exec", Capability::Exec, cmd) {
+                return Ok(err(denied));
+            }
             let mut str_args: Vec<String> = Vec::with_capacity(arg_vals.len());
             for (i, v) in arg_vals.iter().enumerate() {
                 match v {
@@ -128,6 +135,9 @@ pub(crate) fn builtin_exec(args: &[Value]) -> RResult<Value> {
 pub(crate) fn builtin_exec_shell(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(cmd)] => {
+            if let Err(denied) = crate::sandbox::require("exec_shell", Capability::Exec, cmd) {
+                return Ok(err(denied));
+            }
             #[cfg(target_arch = "wasm32")]
             {
                 let _ = cmd;
@@ -0,0 +1,197 @@
+//! `--profile`: where a run spends its time, per user function.
+//!
+//! A [`Profiler`] is an [`EvalObserver`] that times each call of a
+//! user function. It uses only the public hooks, so a host can
+//! register one on a [`Session`](crate::interpreter::Session) the
+//! same way `rz` does for a whole run. With `--profile`, `rz` prints
+//! the table on stderr when the program ends, however it ends:
+//!
+//! ```text
+//! [profile] function   calls   total ms    self ms
+//! [profile] fib          177      12.41      12.22
+//! [profile] twice          1      12.60       0.19
+//! ```
+//!
+//! `total` runs from a call to its return, counted once for a
+//! recursive function however deep it goes; `self` leaves out the
+//! user functions it called. Rows are sorted by self time. A tail
+//! call runs in its caller's frame (see `tail_calls`), so its time
+//! belongs to the call that started the chain.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::bridge::ResilientValue;
+use crate::eval_observer::EvalObserver;
+
+/// What a [`Profiler`] measured for one function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FunctionProfile {
+    /// Times it was called.
+    pub calls: u64,
+    /// Time from its outermost calls to their returns.
+    pub total: Duration,
+    /// `total` less the time spent in the user functions it called.
+    pub self_time: Duration,
+}
+
+/// Times user-function calls. Clones share one profile, so keep one
+/// to read it after registering another.
+#[derive(Clone, Default)]
+pub struct Profiler(Rc<RefCell<Profile>>);
+
+#[derive(Default)]
+struct Profile {
+    /// Calls under way, innermost last: when each started and how long
+    /// its callees have taken so far.
+    calls: Vec<(Instant, Duration)>,
+    /// How many calls of each function are under way.
+    depth: HashMap<String, usize>,
+    functions: HashMap<String, FunctionProfile>,
+}
+
+impl Profiler {
+    pub fn new() -> Profiler {
+        Profiler::default()
+    }
+
+    /// Each function called so far, most self time first.
+    pub fn functions(&self) -> Vec<(String, FunctionProfile)> {
+        let mut functions: Vec<(String, FunctionProfile)> = self
+            .0
+            .borrow()
+            .functions
+            .iter()
+            .map(|(name, profile)| (name.clone(), *profile))
+            .collect();
+        functions.sort_by(|a, b| b.1.self_time.cmp(&a.1.self_time).then(a.0.cmp(&b.0)));
+        functions
+    }
+
+    /// The table `--profile` prints, one `[profile]` line per row.
+    pub fn report(&self) -> String {
+        let functions = self.functions();
+        let width = functions
+            .iter()
+            .map(|(name, _)| name.len())
+            .chain(["function".len()])
+            .max()
+            .unwrap_or_default();
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "[profile] {:<width$} {:>7} {:>10} {:>10}",
+            "function", "calls", "total ms", "self ms"
+        );
+        for (name, p) in &functions {
+            let _ = writeln!(
+                out,
+                "[profile] {:<width$} {:>7} {:>10.2} {:>10.2}",
+                name,
+                p.calls,
+                ms(p.total),
+                ms(p.self_time)
+            );
+        }
+        out
+    }
+}
+
+impl EvalObserver for Profiler {
+    fn call_enter(&mut self, name: &str, _: &[(String, String)], _: &[ResilientValue], _: usize) {
+        let mut profile = self.0.borrow_mut();
+        profile.calls.push((Instant::now(), Duration::ZERO));
+        *profile.depth.entry(name.to_string()).or_default() += 1;
+        profile.functions.entry(name.to_string()).or_default().calls += 1;
+    }
+
+    fn call_exit(&mut self, name: &str, _: Result<&ResilientValue, &str>) {
+        let mut profile = self.0.borrow_mut();
+        let Some((start, callees)) = profile.calls.pop() else {
+            return;
+        };
+        let elapsed = start.elapsed();
+        if let Some((_, parent_callees)) = profile.calls.last_mut() {
+            *parent_callees += elapsed;
+        }
+        let outermost = match profile.depth.get_mut(name) {
+            Some(depth) => {
+                *depth -= 1;
+                *depth == 0
+            }
+            None => true,
+        };
+        let function = profile.functions.entry(name.to_string()).or_default();
+        function.self_time += elapsed.saturating_sub(callees);
+        if outermost {
+            function.total += elapsed;
+        }
+    }
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<Profiler>> = const { RefCell::new(None) };
+}
+
+/// Switch `--profile` on or off for runs on this thread.
+pub fn set_profile(on: bool) {
+    ACTIVE.with(|a| *a.borrow_mut() = on.then(Profiler::new));
+}
+
+/// The profiler `--profile` registers on each run, if it is on.
+pub(crate) fn active() -> Option<Profiler> {
+    ACTIVE.with(|a| a.borrow().clone())
+}
+
+/// Print what `--profile` measured and start over, for the next run
+/// under `--watch`.
+pub(crate) fn finish() {
+    if let Some(profiler) = active() {
+        eprint!("{}", profiler.report());
+        set_profile(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_calls_and_splits_self_time_from_callees() {
+        // `fact` recurses through the debug tree walker; give it the same
+        // enlarged stack as the other recursive interpreter tests.
+        let handle = std::thread::Builder::new()
+            .stack_size(8 * 1024 * 1024)
+            .spawn(|| {
+                let (program, errors) = crate::parse_silent(
+                    "fn leaf(int n) -> int { return n + 1; }\n\
+                     fn fact(int n) -> int {\n    if n <= 1 { return leaf(0) * 1; }\n    return n * fact(n - 1);\n}\n\
+                     println(fact(5));\n",
+                );
+                assert!(errors.is_empty(), "{errors:?}");
+                let profiler = Profiler::new();
+                let mut interp = crate::Interpreter::new();
+                interp.observe(Box::new(profiler.clone()));
+                interp.eval(&program).unwrap();
+
+                let functions: HashMap<String, FunctionProfile> =
+                    profiler.functions().into_iter().collect();
+                assert_eq!(functions["fact"].calls, 5);
+                assert_eq!(functions["leaf"].calls, 1);
+                let fact = functions["fact"];
+                assert!(fact.self_time <= fact.total, "{fact:?}");
+                assert!(functions["leaf"].total <= fact.total);
+                assert!(profiler.0.borrow().calls.is_empty());
+
+                let report = profiler.report();
+                assert!(report.starts_with("[profile] function"), "{report}");
+                assert_eq!(report.lines().count(), 3, "{report}");
+            })
+            .unwrap();
+        handle.join().unwrap();
+    }
+}
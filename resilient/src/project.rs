@@ -0,0 +1,272 @@
+//! Multi-file projects.
+//!
+//! A project is a directory holding a `resilient.toml`. Running or
+//! checking the directory runs or checks its entry file: the
+//! manifest's `[package].entry` (relative to the manifest), or
+//! `src/main.rz` as laid out by `rz pkg init`. The entry pulls in the
+//! rest of the project with `use "..."`, and the whole program is
+//! type-checked as one.
+//!
+//! Spans don't record which file they came from, so a type error or
+//! warning in an imported module is first reported at the module's
+//! line and column but under the entry file's name.
+//! [`attribute_type_error`] and [`attribute_warnings`] put it back in
+//! its own file: they check each module on its own and pick the one
+//! that reports the same thing at the same place.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::pkg_init;
+use crate::typechecker::{CheckDiagnostic, CheckError};
+
+/// Entry file used when the manifest doesn't name one.
+const DEFAULT_ENTRY: &str = "src/main.rz";
+
+/// The file to run or check for `path`: `path` itself for a file, or
+/// the entry of the project rooted at a directory.
+pub(crate) fn resolve_entry(path: &Path) -> Result<PathBuf, String> {
+    if !path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+    let manifest = path.join(pkg_init::MANIFEST_FILENAME);
+    if !manifest.is_file() {
+        return Err(format!(
+            "{} is a directory without a {}; pass a file, or run `rz pkg init` to make it a project",
+            path.display(),
+            pkg_init::MANIFEST_FILENAME
+        ));
+    }
+    let entry = pkg_init::read_package_entry(&manifest).unwrap_or_else(|| DEFAULT_ENTRY.into());
+    let entry = path.join(entry);
+    if !entry.is_file() {
+        return Err(format!(
+            "project entry {} does not exist; set `entry` under [package] in {}",
+            entry.display(),
+            manifest.display()
+        ));
+    }
+    Ok(entry)
+}
+
+/// The files `use`d by `entry`, directly or not, from the set
+/// `imports::expand_uses` filled in. Sorted, for a stable search order.
+pub(crate) fn modules_of(loaded: &HashSet<PathBuf>, entry: &str) -> Vec<PathBuf> {
+    let entry = std::fs::canonicalize(entry).ok();
+    let mut modules: Vec<PathBuf> = loaded
+        .iter()
+        .filter(|p| Some(*p) != entry.as_ref())
+        .cloned()
+        .collect();
+    modules.sort();
+    modules
+}
+
+/// A type error placed in the module it came from.
+pub(crate) struct ModuleTypeError {
+    /// The error, prefixed with the module's path.
+    pub(crate) error: CheckError,
+    /// The module's source, for the caret line.
+    pub(crate) source: String,
+}
+
+/// If `error`, reported for the whole program under `entry`, comes
+/// from one of `modules`, the same error under that module's path.
+/// `None` keeps it where it is, including when the entry itself is
+/// at fault.
+pub(crate) fn attribute_type_error(
+    error: &CheckError,
+    entry: &str,
+    modules: &[PathBuf],
+    verifier_timeout_ms: u32,
+) -> Option<ModuleTypeError> {
+    let located = strip_path(&error.message, entry)?;
+    modules.iter().find_map(|module| {
+        let shown = display_path(module);
+        let alone = check_alone(module, &shown, verifier_timeout_ms)?;
+        let own_error = alone.result.err()?;
+        (strip_path(&own_error.message, &shown) == Some(located)).then_some(ModuleTypeError {
+            error: own_error,
+            source: alone.source,
+        })
+    })
+}
+
+/// Move each of `warnings`, reported for the whole program under
+/// `entry`, that comes from one of `modules` under that module's
+/// path. A warning the module doesn't give on its own stays put.
+pub(crate) fn attribute_warnings(
+    warnings: &mut [CheckDiagnostic],
+    entry: &str,
+    modules: &[PathBuf],
+    verifier_timeout_ms: u32,
+) {
+    let placed = |w: &CheckDiagnostic| w.file == entry && w.line > 0;
+    if modules.is_empty() || !warnings.iter().any(placed) {
+        return;
+    }
+    let mut own: Vec<(String, Vec<CheckDiagnostic>)> = modules
+        .iter()
+        .filter_map(|module| {
+            let shown = display_path(module);
+            let alone = check_alone(module, &shown, verifier_timeout_ms)?;
+            Some((shown, alone.warnings))
+        })
+        .collect();
+    for warning in warnings.iter_mut().filter(|w| placed(w)) {
+        let Some(shown) = own.iter_mut().find_map(|(shown, theirs)| {
+            let i = theirs.iter().position(|w| {
+                (w.line, w.column, &w.severity, &w.code, &w.message)
+                    == (
+                        warning.line,
+                        warning.column,
+                        &warning.severity,
+                        &warning.code,
+                        &warning.message,
+                    )
+            })?;
+            theirs.swap_remove(i);
+            Some(shown.clone())
+        }) else {
+            continue;
+        };
+        let at = |file: &str| format!("{file}:{}:{}", warning.line, warning.column);
+        warning.plain = warning.plain.replacen(&at(entry), &at(&shown), 1);
+        warning.file = shown;
+    }
+}
+
+/// `error` without its `path:` prefix.
+fn strip_path<'a>(error: &'a str, path: &str) -> Option<&'a str> {
+    error.strip_prefix(path)?.strip_prefix(':')
+}
+
+/// `path` relative to the working directory when it lies below it.
+fn display_path(path: &Path) -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+/// A module type-checked with its own imports.
+struct Alone {
+    source: String,
+    result: Result<(), CheckError>,
+    warnings: Vec<CheckDiagnostic>,
+}
+
+/// Type-check `module` with its own imports, collecting its warnings
+/// instead of printing them: the whole-program check has already
+/// reported them. `None` if it can't be read, parsed or expanded.
+fn check_alone(module: &Path, shown: &str, verifier_timeout_ms: u32) -> Option<Alone> {
+    let source = std::fs::read_to_string(module).ok()?;
+    let (mut program, errs) = crate::parse_silent(&source);
+    if !errs.is_empty() {
+        return None;
+    }
+    let base_dir = module.parent().unwrap_or_else(|| Path::new("."));
+    let mut loaded = HashSet::from([module.to_path_buf()]);
+    crate::imports::expand_uses(&mut program, base_dir, &mut loaded).ok()?;
+    let (result, warnings) = crate::typechecker::collect_check_diagnostics(|| {
+        crate::typechecker::TypeChecker::new()
+            .with_verifier_timeout_ms(verifier_timeout_ms)
+            .check_source(&program, shown)
+    });
+    Some(Alone {
+        source,
+        result: result.map(|_| ()),
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmp_project(label: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("res_project_{}_{}", label, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        root
+    }
+
+    #[test]
+    fn a_directory_resolves_to_its_manifest_entry() {
+        let root = tmp_project("entry");
+        assert!(
+            resolve_entry(&root)
+                .unwrap_err()
+                .contains("without a resilient.toml")
+        );
+
+        let manifest = root.join("resilient.toml");
+        std::fs::write(&manifest, "[package]\nname = \"demo\"\n").unwrap();
+        assert!(resolve_entry(&root).unwrap_err().contains("does not exist"));
+        std::fs::write(root.join("src/main.rz"), "println(1);\n").unwrap();
+        assert_eq!(resolve_entry(&root).unwrap(), root.join("src/main.rz"));
+
+        std::fs::write(&manifest, "[package]\nentry = \"app.rz\"\n").unwrap();
+        std::fs::write(root.join("app.rz"), "println(2);\n").unwrap();
+        assert_eq!(resolve_entry(&root).unwrap(), root.join("app.rz"));
+        let file = root.join("app.rz");
+        assert_eq!(resolve_entry(&file).unwrap(), file);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn a_module_type_error_is_attributed_to_the_module() {
+        let root = tmp_project("attribute");
+        let util = root.join("src/util.rz");
+        std::fs::write(&util, "fn one() -> int {\n    return \"one\";\n}\n").unwrap();
+        let util = std::fs::canonicalize(util).unwrap();
+        let shown = display_path(&util);
+
+        let own = check_alone(&util, &shown, 0)
+            .and_then(|alone| alone.result.err())
+            .expect("the module fails on its own");
+        let as_reported = CheckError::from(format!("main.rz{}", &own.message[shown.len()..]));
+        let moved = attribute_type_error(&as_reported, "main.rz", std::slice::from_ref(&util), 0)
+            .expect("the error comes from the module");
+        assert!(
+            moved.error.message.starts_with(&format!("{shown}:2:")),
+            "{}",
+            moved.error
+        );
+        assert!(moved.source.contains("return \"one\""));
+
+        // An error the module doesn't produce stays with the entry.
+        let elsewhere = CheckError::from("main.rz:9:1: something else".to_string());
+        let kept = attribute_type_error(&elsewhere, "main.rz", &[util], 0);
+        assert!(kept.is_none());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn a_module_warning_is_attributed_to_the_module() {
+        let root = tmp_project("warning");
+        let util = root.join("src/util.rz");
+        std::fs::write(&util, "fn one() -> int {\n    return 1;\n}\n").unwrap();
+        let util = std::fs::canonicalize(util).unwrap();
+        let shown = display_path(&util);
+
+        let unused = |file: &str, line| CheckDiagnostic {
+            severity: "warning".into(),
+            code: "unused".into(),
+            file: file.into(),
+            line,
+            column: 1,
+            message: "function `one` is never called".into(),
+            plain: format!("{file}:{line}:1: warning[unused]: function `one` is never called"),
+        };
+        let mut warnings = vec![unused("main.rz", 1), unused("main.rz", 7)];
+        attribute_warnings(&mut warnings, "main.rz", std::slice::from_ref(&util), 0);
+        assert_eq!(warnings[0], unused(&shown, 1));
+        // One the module doesn't give on its own stays with the entry.
+        assert_eq!(warnings[1], unused("main.rz", 7));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
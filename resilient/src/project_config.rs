@@ -0,0 +1,382 @@
+//! Run settings a project shares through its `resilient.toml`.
+//!
+//! `rz`, `rz check`, `rz fmt` and `rz lint` read the nearest
+//! `resilient.toml` above the file they are given (above the working
+//! directory for `rz -` and `rz -e`), so everyone working on a project
+//! runs with the same settings. A flag on the command line wins over
+//! the manifest, which wins over the built-in default.
+//!
+//! ```toml
+//! [check]
+//! strictness = "strict"     # off, soft (default), fatal, or strict
+//! coercion = "strict"       # standard (default), strict, or permissive
+//! warn = ["shadowing"]      # extra warning categories, or "all"
+//! deny_warnings = true
+//!
+//! [live]
+//! retries = 5               # for `live` blocks without `retries(N)`
+//! panic_on_fault = false
+//!
+//! [sandbox]
+//! allow = ["fs-read", "net"]  # capabilities the builtins may use; default all
+//! fs = "read-only"          # read-write (default), read-only, or none
+//! net = "none"              # allow (default) or none
+//! ffi = "none"              # allow (default) or none
+//! mmio = ["0x40021000..0x40021400"]  # registers mmio_read/write may touch
+//! mmio_device = "/dev/uio0"  # default /dev/mem
+//!
+//! [verify]
+//! timeout_ms = 2000         # per Z3 query; 0 means no timeout
+//! warn_unverified = false
+//!
+//! [fmt]
+//! indent = 2                # spaces per level; default 4
+//!
+//! [lint]
+//! L0040 = "deny"            # per lint code: allow, warn (default), or deny
+//! ```
+//!
+//! `strictness` maps onto the type-checking flags: `off` is
+//! `--no-typecheck`, `soft` reports type errors and runs anyway,
+//! `fatal` is `--typecheck-strict`, and `strict` adds `--strict`.
+//! `[package].entry` is read by `project`. Like the `[repl]` section
+//! (`repl_config`), a setting that can't be read is reported on
+//! stderr with its file and line, and the default stays in place.
+
+use std::ops::Range;
+use std::path::Path;
+
+use crate::coercion::{self, CoercionPolicy};
+use crate::ffi::FfiPolicy;
+use crate::fs_sandbox::FsPolicy;
+use crate::http_client::NetPolicy;
+use crate::sandbox::Capabilities;
+use crate::warnings::{self, WarningCategory, WarningConfig};
+
+/// How type errors are treated, from `[check].strictness`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Strictness {
+    Off,
+    Soft,
+    Fatal,
+    Strict,
+}
+
+/// What `[lint]` does with one lint code, like `--allow` / `--deny`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// The settings a manifest sets; `None` keeps the built-in default.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct ProjectConfig {
+    pub(crate) strictness: Option<Strictness>,
+    pub(crate) coercion: Option<CoercionPolicy>,
+    pub(crate) warn: Vec<WarningCategory>,
+    pub(crate) deny_warnings: Option<bool>,
+    pub(crate) live_retries: Option<u32>,
+    pub(crate) panic_on_fault: Option<bool>,
+    pub(crate) allow: Option<Capabilities>,
+    pub(crate) fs: Option<FsPolicy>,
+    pub(crate) net: Option<NetPolicy>,
+    pub(crate) ffi: Option<FfiPolicy>,
+    pub(crate) mmio: Option<Vec<Range<u64>>>,
+    pub(crate) mmio_device: Option<String>,
+    pub(crate) verifier_timeout_ms: Option<u32>,
+    pub(crate) warn_unverified: Option<bool>,
+    pub(crate) fmt_indent: Option<usize>,
+    pub(crate) lint: Vec<(String, LintLevel)>,
+}
+
+impl ProjectConfig {
+    /// The settings of the project `path` belongs to, or of the one
+    /// around the working directory when `path` is `None`. No
+    /// manifest means no settings.
+    pub(crate) fn for_path(path: Option<&Path>) -> Self {
+        let start = match path {
+            Some(p) => std::fs::canonicalize(p).ok(),
+            None => std::env::current_dir().ok(),
+        };
+        let mut config = ProjectConfig::default();
+        if let Some(manifest) = start.and_then(|s| crate::pkg_init::find_manifest_upwards(&s)) {
+            config.apply_file(&manifest);
+        }
+        config
+    }
+
+    fn apply_file(&mut self, path: &Path) {
+        let Ok(text) = std::fs::read_to_string(path) else {
+            return;
+        };
+        for warning in self.apply(&text) {
+            eprintln!("warning: {}:{}", path.display(), warning);
+        }
+    }
+
+    /// Apply the settings in `text`; returns a `line: message` warning
+    /// for each one that was not applied.
+    pub(crate) fn apply(&mut self, text: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mut section = String::new();
+        for (idx, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                section = header.trim_end_matches(']').trim().to_string();
+                continue;
+            }
+            if !matches!(
+                section.as_str(),
+                "check" | "live" | "sandbox" | "verify" | "fmt" | "lint"
+            ) {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                warnings.push(format!("{}: expected `key = value`", idx + 1));
+                continue;
+            };
+            if let Err(e) = self.set(&section, key.trim(), value.trim()) {
+                warnings.push(format!("{}: {}", idx + 1, e));
+            }
+        }
+        warnings
+    }
+
+    fn set(&mut self, section: &str, key: &str, value: &str) -> Result<(), String> {
+        let text = value.trim_matches('"');
+        let bool_value = || match text {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(format!("`{key}` expects true or false, got `{value}`")),
+        };
+        let number = |what: &str| {
+            text.parse::<u32>()
+                .map_err(|_| format!("`{key}` expects {what}, got `{value}`"))
+        };
+        match (section, key) {
+            ("check", "strictness") => {
+                self.strictness = Some(match text {
+                    "off" => Strictness::Off,
+                    "soft" => Strictness::Soft,
+                    "fatal" => Strictness::Fatal,
+                    "strict" => Strictness::Strict,
+                    _ => {
+                        return Err(format!(
+                            "`strictness` expects off, soft, fatal, or strict, got `{value}`"
+                        ));
+                    }
+                })
+            }
+            ("check", "coercion") => {
+                self.coercion = Some(CoercionPolicy::parse(text).ok_or_else(|| {
+                    format!("`coercion` expects standard, strict, or permissive, got `{value}`")
+                })?)
+            }
+            ("check", "warn") => {
+                let names = value.trim_start_matches('[').trim_end_matches(']');
+                for name in names.split(',').map(|n| n.trim().trim_matches('"')) {
+                    if name.is_empty() {
+                        continue;
+                    }
+                    let categories = warnings::parse_categories(name)
+                        .ok_or_else(|| format!("unknown warning category `{name}`"))?;
+                    self.warn.extend(categories);
+                }
+            }
+            ("check", "deny_warnings") => self.deny_warnings = Some(bool_value()?),
+            ("live", "retries") => self.live_retries = Some(number("a retry count")?),
+            ("live", "panic_on_fault") => self.panic_on_fault = Some(bool_value()?),
+            ("sandbox", "allow") => {
+                let names: Vec<&str> = value
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .split(',')
+                    .map(|n| n.trim().trim_matches('"'))
+                    .collect();
+                self.allow = Some(
+                    Capabilities::parse(&names.join(",")).map_err(|e| format!("`allow`: {e}"))?,
+                )
+            }
+            ("sandbox", "fs") => {
+                self.fs = Some(FsPolicy::parse(text).ok_or_else(|| {
+                    format!("`fs` expects read-write, read-only, or none, got `{value}`")
+                })?)
+            }
+            ("sandbox", "net") => {
+                self.net = Some(
+                    NetPolicy::parse(text)
+                        .ok_or_else(|| format!("`net` expects allow or none, got `{value}`"))?,
+                )
+            }
+            ("sandbox", "ffi") => {
+                self.ffi = Some(
+                    FfiPolicy::parse(text)
+                        .ok_or_else(|| format!("`ffi` expects allow or none, got `{value}`"))?,
+                )
+            }
+            ("sandbox", "mmio") => {
+                self.mmio =
+                    Some(crate::mmio::parse_ranges(value).map_err(|e| format!("`mmio`: {e}"))?)
+            }
+            ("sandbox", "mmio_device") => self.mmio_device = Some(text.to_string()),
+            ("verify", "timeout_ms") => {
+                self.verifier_timeout_ms = Some(number("a number of milliseconds")?)
+            }
+            ("verify", "warn_unverified") => self.warn_unverified = Some(bool_value()?),
+            ("fmt", "indent") => {
+                self.fmt_indent = Some(number("a number of spaces")? as usize);
+            }
+            ("lint", code) => {
+                if !crate::lint::KNOWN_CODES.contains(&code) {
+                    return Err(format!("unknown lint code `{code}`"));
+                }
+                let level = match text {
+                    "allow" => LintLevel::Allow,
+                    "warn" => LintLevel::Warn,
+                    "deny" => LintLevel::Deny,
+                    _ => {
+                        return Err(format!(
+                            "`{code}` expects allow, warn, or deny, got `{value}`"
+                        ));
+                    }
+                };
+                self.lint.push((code.to_string(), level));
+            }
+            _ => return Err(format!("unknown setting `{key}` in [{section}]")),
+        }
+        Ok(())
+    }
+
+    /// Install the checker settings the command line (`args`) left
+    /// alone: `--strict`, `--coercion`, the warning categories and
+    /// `--deny-warnings`. Settings the manifest doesn't name go back
+    /// to their defaults, so checking several projects in one run
+    /// doesn't carry one's settings into the next.
+    pub(crate) fn apply_checker_settings(&self, args: &[String]) {
+        if !cli_sets(args, &["--strict"]) {
+            crate::strict_any::set_strict_any(self.strictness == Some(Strictness::Strict));
+        }
+        if !cli_sets(args, &["--coercion"]) {
+            coercion::set_policy(self.coercion.unwrap_or(CoercionPolicy::Standard));
+        }
+        if !cli_sets(args, &["--warn", "--no-warn"]) {
+            let defaults = WarningConfig::default();
+            for category in WarningCategory::ALL {
+                warnings::set_enabled(
+                    category,
+                    defaults.enabled(category) || self.warn.contains(&category),
+                );
+            }
+        }
+        if !cli_sets(args, &["--deny-warnings"]) {
+            warnings::set_deny(self.deny_warnings == Some(true));
+        }
+    }
+}
+
+/// Whether the driver arguments `args` hold one of `flags`, either
+/// bare or as `--flag=value`. Arguments after `--` belong to the
+/// program and are not looked at.
+pub(crate) fn cli_sets(args: &[String], flags: &[&str]) -> bool {
+    args.iter().skip(1).take_while(|a| *a != "--").any(|a| {
+        let name = a.split_once('=').map_or(a.as_str(), |(name, _)| name);
+        flags.contains(&name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_fill_in_settings_and_bad_lines_warn() {
+        let manifest = "[package]\nname = \"demo\"\nretries = 9\n\n\
+                        [check]\nstrictness = \"fatal\"\nwarn = [\"shadowing\", \"implicit-any\"]\n\
+                        [live]\nretries = 5 # more patience\nretry = 1\n\
+                        [sandbox]\nfs = \"read-only\"\nnet = \"nowhere\"\n\
+                        [verify]\ntimeout_ms = 250\n\
+                        [fmt]\nindent = 2\n\
+                        [lint]\nL0040 = \"deny\"\nL9999 = \"allow\"\nL0017 = \"loud\"\n";
+        let mut config = ProjectConfig::default();
+        let warnings = config.apply(manifest);
+        assert_eq!(
+            warnings,
+            vec![
+                "10: unknown setting `retry` in [live]",
+                "13: `net` expects allow or none, got `\"nowhere\"`",
+                "20: unknown lint code `L9999`",
+                "21: `L0017` expects allow, warn, or deny, got `\"loud\"`",
+            ]
+        );
+        assert_eq!(
+            config,
+            ProjectConfig {
+                strictness: Some(Strictness::Fatal),
+                warn: vec![WarningCategory::Shadowing, WarningCategory::ImplicitAny],
+                live_retries: Some(5),
+                fs: Some(FsPolicy::ReadOnly),
+                verifier_timeout_ms: Some(250),
+                fmt_indent: Some(2),
+                lint: vec![("L0040".to_string(), LintLevel::Deny)],
+                ..ProjectConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn allow_lists_capabilities_and_an_empty_list_grants_none() {
+        use crate::sandbox::Capability;
+
+        let mut config = ProjectConfig::default();
+        let warnings = config.apply("[sandbox]\nallow = [\"fs-read\", \"net\"]\n");
+        assert!(warnings.is_empty(), "{warnings:?}");
+        let allow = config.allow.unwrap();
+        assert!(allow.contains(Capability::FsRead) && allow.contains(Capability::Net));
+        assert!(!allow.contains(Capability::Exec));
+
+        let mut config = ProjectConfig::default();
+        assert!(config.apply("[sandbox]\nallow = []\n").is_empty());
+        assert_eq!(config.allow, Some(Capabilities::NONE));
+
+        let mut config = ProjectConfig::default();
+        assert_eq!(
+            config.apply("[sandbox]\nallow = [\"disk\"]\n"),
+            vec![
+                "2: `allow`: unknown capability `disk` (expected fs-read, fs-write, fs, net, exec, serial, mmio, ffi, or none)"
+            ]
+        );
+    }
+
+    #[test]
+    fn mmio_ranges_come_from_the_sandbox_section() {
+        let mut config = ProjectConfig::default();
+        let warnings = config.apply(
+            "[sandbox]\nmmio = [\"0x40021000..0x40021400\", \"0x48000000+0x400\"]\n\
+             mmio_device = \"/dev/uio0\"\nmmio = [\"0x40021000\"]\n",
+        );
+        assert_eq!(
+            warnings,
+            vec!["4: `mmio`: `0x40021000` is not an address range (start..end or start+size)"]
+        );
+        assert_eq!(
+            config.mmio,
+            Some(vec![0x4002_1000..0x4002_1400, 0x4800_0000..0x4800_0400])
+        );
+        assert_eq!(config.mmio_device.as_deref(), Some("/dev/uio0"));
+    }
+
+    #[test]
+    fn flags_are_found_before_the_program_arguments() {
+        let args: Vec<String> = ["rz", "--coercion=strict", "p.rz", "--", "--strict"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert!(cli_sets(&args, &["--coercion"]));
+        assert!(!cli_sets(&args, &["--strict"]));
+    }
+}
@@ -303,10 +303,10 @@ fn check_property_test_call_sites(
             ensures,
             ..
         } => {
-            for clause in requires {
+            for clause in requires.iter() {
                 check_property_test_call_sites(clause, source_path, property_test_fns)?;
             }
-            for clause in ensures {
+            for clause in ensures.iter() {
                 check_property_test_call_sites(clause, source_path, property_test_fns)?;
             }
             check_property_test_call_sites(body, source_path, property_test_fns)?;
@@ -332,7 +332,7 @@ fn check_property_test_call_sites(
             }
 
             if let Node::Identifier { name, .. } = function.as_ref()
-                && let Some(param_types) = property_test_fns.get(name)
+                && let Some(param_types) = property_test_fns.get(name.as_str())
             {
                 validate_property_test_call_site(source_path, name, param_types, arguments, span)?;
             }
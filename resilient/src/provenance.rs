@@ -30,10 +30,9 @@ type RResult<T> = Result<T, String>;
 /// provenance string. Returned shape: `Array[String(source), Int(value)]`.
 pub(crate) fn builtin_tag(args: &[Value]) -> RResult<Value> {
     match args {
-        [Value::Int(v), Value::String(src)] => Ok(Value::Array(vec![
-            Value::String(src.clone()),
-            Value::Int(*v),
-        ])),
+        [Value::Int(v), Value::String(src)] => Ok(Value::Array(
+            vec![Value::String(src.clone()), Value::Int(*v)].into(),
+        )),
         [a, b] => Err(format!(
             "tag: expected (Int, String), got ({}, {})",
             type_name(a),
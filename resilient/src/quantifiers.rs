@@ -16,11 +16,12 @@
 //! statically — the caller falls back to the runtime check.
 
 use crate::span;
-use crate::typechecker::{Type, TypeChecker};
+use crate::typechecker::{CheckError, Type, TypeChecker};
 use crate::{Interpreter, Node, Parser, RResult, Token, Value};
 
 /// Universal vs. existential quantifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QuantifierKind {
     /// `forall id in range: body` — body must hold for every witness.
     Forall,
@@ -39,6 +40,7 @@ impl QuantifierKind {
 
 /// Range / iterable an `id` is quantified over.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QuantRange {
     /// `lo..hi` — half-open integer range. The Z3 backend recognizes
     /// this form; everything else falls back to runtime evaluation.
@@ -69,7 +71,7 @@ pub(crate) fn parse_quantifier(parser: &mut Parser) -> Option<Node> {
     parser.next_token(); // skip `forall` / `exists`
 
     let var = match &parser.current_token {
-        Token::Identifier(name) => name.clone(),
+        Token::Identifier(name) => name.to_string(),
         other => {
             let tok = other.clone();
             parser.record_error(format!(
@@ -262,7 +264,7 @@ fn step_quantifier(
 
 fn iterable_witnesses(v: Value) -> RResult<Vec<Value>> {
     match v {
-        Value::Array(items) => Ok(items),
+        Value::Array(items) => Ok(items.into_inner()),
         Value::Set(set) => Ok(set
             .into_iter()
             .map(|k| match k {
@@ -298,7 +300,7 @@ pub(crate) fn typecheck_quantifier(
     var: &str,
     range: &QuantRange,
     body: &Node,
-) -> Result<Type, String> {
+) -> Result<Type, CheckError> {
     let var_ty = match range {
         QuantRange::Range { lo, hi } => {
             let lo_ty = tc.check_node(lo)?;
@@ -318,10 +320,7 @@ pub(crate) fn typecheck_quantifier(
 
     let body_ty = tc.with_quantifier_binding(var, var_ty, body)?;
     if body_ty != Type::Bool && body_ty != Type::Any {
-        return Err(format!(
-            "quantifier body must evaluate to Bool, got {}",
-            body_ty
-        ));
+        return Err(format!("quantifier body must evaluate to Bool, got {}", body_ty).into());
     }
     Ok(Type::Bool)
 }
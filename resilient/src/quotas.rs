@@ -180,7 +180,7 @@ pub(crate) fn builtin_quotas(args: &[Value]) -> RResult<Value> {
             .map(|k| Value::String(k.clone()))
             .collect()
     });
-    Ok(Value::Array(names))
+    Ok(Value::Array(names.into()))
 }
 
 fn type_name(v: &Value) -> &'static str {
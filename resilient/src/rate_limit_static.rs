@@ -70,7 +70,7 @@ pub(crate) fn check(program: &Node, _source_path: &str) -> Result<(), String> {
                         if let Some(c) = counts.get_mut(callee.as_str()) {
                             *c += 1;
                         } else {
-                            counts.insert(callee.clone(), 1);
+                            counts.insert(callee.to_string(), 1);
                         }
                     }
                 }
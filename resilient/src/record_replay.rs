@@ -0,0 +1,336 @@
+//! `--record` / `--replay`: deterministic re-execution of a run.
+//!
+//! Every input a program can observe that is not a function of its
+//! source goes through one of the taps below: the RNG seed, monotonic
+//! and wall clock reads, OS entropy, stdin lines, and the order the
+//! actor scheduler dispatches actors in. Under `--record trace.bin`
+//! each tap reads the live source and appends what it saw to the
+//! trace as it happens, so the file is complete even when the run
+//! dies. Under `--replay trace.bin` the taps hand back the recorded
+//! values in order instead, so a failure seen once in a long-running
+//! program can be re-run and debugged offline.
+//!
+//! A replay only stays faithful while the program asks for the same
+//! inputs in the same order. The first time it asks for something
+//! else — an edited program, or a trace from another one — the run
+//! warns once on stderr and carries on with live inputs.
+//!
+//! The mode is process-wide, like the `--fs` sandbox: actor bodies and
+//! sub-interpreters reach the same trace without any plumbing.
+//!
+//! Trace format: the magic `RZTRACE1`, then one record per input —
+//! a kind byte followed by a little-endian `u64` for numbers, or a
+//! little-endian `u32` length and the raw bytes for byte strings. A
+//! stdin read that hit end of input is its own kind with no payload.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Write;
+use std::sync::Mutex;
+
+const MAGIC: &[u8; 8] = b"RZTRACE1";
+
+/// One class of nondeterministic input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// The RNG seed installed at startup.
+    Seed,
+    /// A monotonic clock read, in nanoseconds.
+    Monotonic,
+    /// A wall clock read, in nanoseconds since the Unix epoch.
+    Wall,
+    /// Bytes drawn from the OS entropy source.
+    Entropy,
+    /// One raw line from stdin, line ending included.
+    StdinLine,
+    /// A stdin read that found end of input.
+    StdinEof,
+    /// The PID the actor scheduler ran next (0 when none was runnable).
+    ActorDispatch,
+}
+
+impl Kind {
+    fn tag(self) -> u8 {
+        match self {
+            Kind::Seed => 1,
+            Kind::Monotonic => 2,
+            Kind::Wall => 3,
+            Kind::Entropy => 4,
+            Kind::StdinLine => 5,
+            Kind::StdinEof => 6,
+            Kind::ActorDispatch => 7,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Kind> {
+        Some(match tag {
+            1 => Kind::Seed,
+            2 => Kind::Monotonic,
+            3 => Kind::Wall,
+            4 => Kind::Entropy,
+            5 => Kind::StdinLine,
+            6 => Kind::StdinEof,
+            7 => Kind::ActorDispatch,
+            _ => return None,
+        })
+    }
+
+    fn has_bytes(self) -> bool {
+        matches!(self, Kind::Entropy | Kind::StdinLine)
+    }
+
+    fn has_number(self) -> bool {
+        !self.has_bytes() && self != Kind::StdinEof
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Event {
+    Number(Kind, u64),
+    Bytes(Kind, Vec<u8>),
+    Eof,
+}
+
+impl Event {
+    fn kind(&self) -> Kind {
+        match self {
+            Event::Number(k, _) | Event::Bytes(k, _) => *k,
+            Event::Eof => Kind::StdinEof,
+        }
+    }
+}
+
+enum Mode {
+    Off,
+    Record(File),
+    Replay {
+        events: VecDeque<Event>,
+        /// Index of the next event, for the divergence warning.
+        next: usize,
+    },
+}
+
+static MODE: Mutex<Mode> = Mutex::new(Mode::Off);
+
+fn mode() -> std::sync::MutexGuard<'static, Mode> {
+    MODE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Record every later nondeterministic input to a new trace at `path`.
+pub fn start_recording(path: &str) -> Result<(), String> {
+    let mut file = File::create(path).map_err(|e| format!("cannot create {path}: {e}"))?;
+    file.write_all(MAGIC)
+        .map_err(|e| format!("cannot write {path}: {e}"))?;
+    *mode() = Mode::Record(file);
+    Ok(())
+}
+
+/// Serve every later nondeterministic input from the trace at `path`.
+pub fn start_replay(path: &str) -> Result<(), String> {
+    let data = std::fs::read(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+    let events = decode(&data).map_err(|e| format!("{path}: {e}"))?;
+    *mode() = Mode::Replay { events, next: 0 };
+    Ok(())
+}
+
+/// Go back to live inputs. Closes any trace being recorded.
+pub fn stop() {
+    *mode() = Mode::Off;
+}
+
+/// Tap a numeric input: `live` reads the real source.
+pub(crate) fn number(kind: Kind, live: impl FnOnce() -> u64) -> u64 {
+    match take(kind) {
+        Taken::Replayed(Event::Number(_, n)) => n,
+        Taken::Replayed(_) => unreachable!("take matches the kind"),
+        Taken::Live => {
+            let n = live();
+            append(&Event::Number(kind, n));
+            n
+        }
+    }
+}
+
+/// Tap entropy: `live` fills the buffer from the real source.
+pub(crate) fn entropy(buf: &mut [u8], live: impl FnOnce(&mut [u8])) {
+    match take(Kind::Entropy) {
+        Taken::Replayed(Event::Bytes(_, bytes)) if bytes.len() == buf.len() => {
+            buf.copy_from_slice(&bytes);
+        }
+        Taken::Replayed(_) => {
+            diverge("entropy of a different length");
+            live(buf);
+        }
+        Taken::Live => {
+            live(buf);
+            append(&Event::Bytes(Kind::Entropy, buf.to_vec()));
+        }
+    }
+}
+
+/// Tap a raw stdin line, line ending included; `None` is end of input.
+/// `live` reads the real stdin. Returns `Err` only for a live read
+/// error, which is not recorded.
+pub(crate) fn stdin_line(
+    live: impl FnOnce() -> std::io::Result<Option<String>>,
+) -> std::io::Result<Option<String>> {
+    let replayed = {
+        let mut guard = mode();
+        match &mut *guard {
+            Mode::Replay { events, next } => match events.front().map(Event::kind) {
+                Some(Kind::StdinLine | Kind::StdinEof) => {
+                    *next += 1;
+                    events.pop_front()
+                }
+                found => {
+                    drop(guard);
+                    diverge_at(Kind::StdinLine, found);
+                    None
+                }
+            },
+            _ => None,
+        }
+    };
+    match replayed {
+        Some(Event::Bytes(_, bytes)) => Ok(Some(String::from_utf8_lossy(&bytes).into_owned())),
+        Some(_) => Ok(None),
+        None => {
+            let line = live()?;
+            append(&match &line {
+                Some(l) => Event::Bytes(Kind::StdinLine, l.as_bytes().to_vec()),
+                None => Event::Eof,
+            });
+            Ok(line)
+        }
+    }
+}
+
+/// Report that the program stopped following the trace, then switch to
+/// live inputs for the rest of the run.
+pub(crate) fn diverge(what: &str) {
+    let mut guard = mode();
+    if let Mode::Replay { next, .. } = &*guard {
+        eprintln!(
+            "rz: warning: replay diverged at event {}: {}; continuing with live inputs",
+            next, what
+        );
+        *guard = Mode::Off;
+    }
+}
+
+enum Taken {
+    Replayed(Event),
+    Live,
+}
+
+/// Pop the next replayed event when it is a `kind`; otherwise warn and
+/// fall back to live inputs. Outside a replay always `Live`.
+fn take(kind: Kind) -> Taken {
+    let mut guard = mode();
+    let Mode::Replay { events, next } = &mut *guard else {
+        return Taken::Live;
+    };
+    let found = events.front().map(Event::kind);
+    if found == Some(kind) {
+        *next += 1;
+        return Taken::Replayed(events.pop_front().expect("front was Some"));
+    }
+    drop(guard);
+    diverge_at(kind, found);
+    Taken::Live
+}
+
+fn diverge_at(wanted: Kind, found: Option<Kind>) {
+    match found {
+        Some(found) => diverge(&format!(
+            "the program read {wanted:?}, the trace has {found:?}"
+        )),
+        None => diverge(&format!(
+            "the program read {wanted:?} past the end of the trace"
+        )),
+    }
+}
+
+/// Write `event` through to the trace when recording. A write failure
+/// stops the recording with a warning rather than failing the run.
+fn append(event: &Event) {
+    let mut guard = mode();
+    let Mode::Record(file) = &mut *guard else {
+        return;
+    };
+    if let Err(e) = file.write_all(&encode(event)) {
+        eprintln!("rz: warning: trace write failed, recording stopped: {e}");
+        *guard = Mode::Off;
+    }
+}
+
+fn encode(event: &Event) -> Vec<u8> {
+    let mut out = vec![event.kind().tag()];
+    match event {
+        Event::Number(_, n) => out.extend_from_slice(&n.to_le_bytes()),
+        Event::Bytes(_, bytes) => {
+            out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            out.extend_from_slice(bytes);
+        }
+        Event::Eof => {}
+    }
+    out
+}
+
+fn decode(data: &[u8]) -> Result<VecDeque<Event>, String> {
+    let rest = data
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or("not a Resilient trace (bad magic)")?;
+    let mut events = VecDeque::new();
+    let mut pos = 0;
+    let truncated = |at: usize| format!("trace truncated at byte {}", MAGIC.len() + at);
+    while pos < rest.len() {
+        let tag = rest[pos];
+        let kind = Kind::from_tag(tag)
+            .ok_or_else(|| format!("unknown record kind {tag} at byte {}", MAGIC.len() + pos))?;
+        pos += 1;
+        let event = if kind.has_number() {
+            let raw = rest.get(pos..pos + 8).ok_or_else(|| truncated(pos))?;
+            pos += 8;
+            Event::Number(kind, u64::from_le_bytes(raw.try_into().expect("8 bytes")))
+        } else if kind.has_bytes() {
+            let raw = rest.get(pos..pos + 4).ok_or_else(|| truncated(pos))?;
+            let len = u32::from_le_bytes(raw.try_into().expect("4 bytes")) as usize;
+            pos += 4;
+            let bytes = rest.get(pos..pos + len).ok_or_else(|| truncated(pos))?;
+            pos += len;
+            Event::Bytes(kind, bytes.to_vec())
+        } else {
+            Event::Eof
+        };
+        events.push_back(event);
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_round_trips_through_the_encoding() {
+        let events = vec![
+            Event::Number(Kind::Seed, 42),
+            Event::Bytes(Kind::StdinLine, b"hello\n".to_vec()),
+            Event::Eof,
+            Event::Bytes(Kind::Entropy, vec![1, 2, 3]),
+            Event::Number(Kind::ActorDispatch, 0),
+        ];
+        let mut data = MAGIC.to_vec();
+        for e in &events {
+            data.extend(encode(e));
+        }
+        assert_eq!(decode(&data).unwrap(), VecDeque::from(events));
+        assert!(decode(b"nope").unwrap_err().contains("bad magic"));
+        assert!(
+            decode(&data[..data.len() - 1])
+                .unwrap_err()
+                .contains("truncated")
+        );
+    }
+}
@@ -391,8 +391,8 @@ fn write_smtlib2(node: &Node, out: &mut String) {
 /// Used to emit `(declare-const …)` lines in the SMT-LIB2 preamble.
 fn collect_identifiers(node: &Node, out: &mut Vec<String>) {
     match node {
-        Node::Identifier { name, .. } if !out.contains(name) => {
-            out.push(name.clone());
+        Node::Identifier { name, .. } if !out.iter().any(|n| n == name) => {
+            out.push(name.to_string());
         }
         Node::InfixExpression { left, right, .. } => {
             collect_identifiers(left, out);
@@ -579,7 +579,7 @@ mod tests {
         let (prog, _) = parse(src);
         match prog {
             Node::Program(stmts) => match &stmts[0].node {
-                Node::Function { body, .. } => *body.clone(),
+                Node::Function { body, .. } => body.as_ref().clone(),
                 other => panic!("expected Function, got {:?}", other),
             },
             other => panic!("expected Program, got {:?}", other),
@@ -664,7 +664,7 @@ mod tests {
     #[test]
     fn smtlib2_identifier() {
         let node = Node::Identifier {
-            name: "reading".to_string(),
+            name: "reading".into(),
             span: Span::default(),
         };
         assert_eq!(node_to_smtlib2(&node), "reading");
@@ -675,7 +675,7 @@ mod tests {
         // `reading > 100` → `(> reading 100)`
         let node = Node::InfixExpression {
             left: Box::new(Node::Identifier {
-                name: "reading".to_string(),
+                name: "reading".into(),
                 span: Span::default(),
             }),
             operator: ">",
@@ -693,12 +693,12 @@ mod tests {
         // `a != b` → `(distinct a b)`
         let node = Node::InfixExpression {
             left: Box::new(Node::Identifier {
-                name: "a".to_string(),
+                name: "a".into(),
                 span: Span::default(),
             }),
             operator: "!=",
             right: Box::new(Node::Identifier {
-                name: "b".to_string(),
+                name: "b".into(),
                 span: Span::default(),
             }),
             span: Span::default(),
@@ -724,7 +724,7 @@ mod tests {
         // recovers_to: reading > 100
         let clause = Node::InfixExpression {
             left: Box::new(Node::Identifier {
-                name: "reading".to_string(),
+                name: "reading".into(),
                 span: Span::default(),
             }),
             operator: ">",
@@ -762,7 +762,7 @@ mod tests {
         // the negated recovers_to postcondition.
         let requires = Node::InfixExpression {
             left: Box::new(Node::Identifier {
-                name: "reading".to_string(),
+                name: "reading".into(),
                 span: Span::default(),
             }),
             operator: ">=",
@@ -774,7 +774,7 @@ mod tests {
         };
         let clause = Node::InfixExpression {
             left: Box::new(Node::Identifier {
-                name: "reading".to_string(),
+                name: "reading".into(),
                 span: Span::default(),
             }),
             operator: ">",
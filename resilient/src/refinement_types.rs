@@ -20,6 +20,7 @@
 #![allow(clippy::collapsible_if, clippy::doc_lazy_continuation, dead_code)]
 
 use crate::Node;
+use crate::typechecker::CheckError;
 use std::sync::OnceLock;
 use std::sync::RwLock;
 
@@ -165,7 +166,7 @@ pub fn refine_int(value: i64, refinement_name: &str) -> Result<i64, String> {
     }
 }
 
-pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
+pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), CheckError> {
     // RES-1302: skip the `install` call when the current program
     // declares no `#[refinement]` attributes. The `install` helper
     // *replaces* the process-global `REFINEMENTS` vector — calling
@@ -214,7 +215,7 @@ fn check_let_obligations(
     program: &Node,
     source_path: &str,
     specs: &std::collections::HashMap<String, RefinementSpec>,
-) -> Result<(), String> {
+) -> Result<(), CheckError> {
     // Walk only the top-level function bodies — LetStatements inside
     // function bodies are the primary refinement obligation sites.
     let Node::Program(stmts) = program else {
@@ -250,7 +251,7 @@ fn check_parameter_refinement_with_z3(
     span: &crate::span::Span,
     binding_name: &str,
     axioms: &[Node],
-) -> Result<(), String> {
+) -> Result<(), CheckError> {
     // Convert the predicate string into an AST node, substituting the parameter name for "self".
     let predicate_expr = match build_predicate_node(param_name, &spec.predicate) {
         Some(expr) => expr,
@@ -282,10 +283,10 @@ fn check_parameter_refinement_with_z3(
             let cx_str = counterexample
                 .map(|c| format!(" (counterexample: {})", c))
                 .unwrap_or_default();
-            Err(format!(
+            Err(crate::typechecker::refutation(format!(
                 "{}:{}: refinement error: let `{}`: {} of type `{}` cannot satisfy the refinement predicate{}",
                 source_path, line, binding_name, param_name, refinement_name, cx_str
-            ))
+            )))
         }
         None => {
             // Unknown: Z3 couldn't decide.
@@ -293,7 +294,8 @@ fn check_parameter_refinement_with_z3(
                 Err(format!(
                     "{}:{}: refinement error: let `{}`: static verification inconclusive for `{}` under `--strict-refinements`",
                     source_path, line, binding_name, refinement_name
-                ))
+                )
+                .into())
             } else {
                 // Issue a warning but allow compilation to proceed.
                 eprintln!(
@@ -342,7 +344,7 @@ fn build_predicate_node(param_name: &str, predicate: &str) -> Option<Node> {
     let sp = crate::span::Span::default();
     Some(Node::InfixExpression {
         left: Box::new(Node::Identifier {
-            name: param_name.to_string(),
+            name: param_name.into(),
             span: sp,
         }),
         operator,
@@ -359,7 +361,7 @@ fn check_node_obligations(
     source_path: &str,
     specs: &std::collections::HashMap<String, RefinementSpec>,
     fn_ctx: Option<FunctionContext>,
-) -> Result<(), String> {
+) -> Result<(), CheckError> {
     // Handle the Program node specially to extract statement nodes.
     if let Node::Program(stmts) = node {
         for stmt in stmts {
@@ -385,7 +387,8 @@ fn check_node_obligations(
                             return Err(format!(
                                 "{}:{}: refinement error: let `{}`: {}",
                                 source_path, line, name, msg
-                            ));
+                            )
+                            .into());
                         }
                     }
                     // RES-3839: with Z3 support, check parameter references using requires clauses.
@@ -424,7 +427,7 @@ fn check_node_obligations(
             // Extract function context for recursive checks in the body.
             let fn_context = FunctionContext {
                 parameters: parameters.clone(),
-                requires: requires.clone(),
+                requires: requires.to_vec(),
             };
             check_node_obligations(body, source_path, specs, Some(fn_context))?;
         }
@@ -562,7 +565,7 @@ mod tests {
         let program = make_let("Positive", -1);
         let result = check_node_obligations(&program, "test.rz", &specs, None);
         assert!(result.is_err(), "expected error, got Ok");
-        let msg = result.unwrap_err();
+        let msg = result.unwrap_err().message;
         assert!(msg.contains("refinement error"), "unexpected msg: {}", msg);
         assert!(msg.contains("Positive"), "missing type name: {}", msg);
     }
@@ -701,7 +704,7 @@ mod tests {
         let program = Node::Program(vec![span::Spanned::new(func, sp)]);
         let result = check_node_obligations(&program, "test.rz", &specs, None);
         assert!(result.is_err(), "expected disproved case to fail");
-        let msg = result.unwrap_err();
+        let msg = result.unwrap_err().message;
         assert!(
             msg.contains("refinement error"),
             "expected 'refinement error' in message: {}",
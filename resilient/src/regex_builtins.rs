@@ -156,7 +156,7 @@ pub(crate) fn builtin_regex_find_all(args: &[Value]) -> RResult<Value> {
                 .find_iter(text)
                 .map(|m| Value::String(m.as_str().to_string()))
                 .collect();
-            Ok(Value::Array(matches))
+            Ok(Value::Array(matches.into()))
         }
         [a, b] => Err(format!(
             "regex_find_all: expected (string, string), got ({}, {})",
@@ -182,7 +182,7 @@ pub(crate) fn builtin_regex_captures(args: &[Value]) -> RResult<Value> {
                             None => Value::Void,
                         })
                         .collect();
-                    Ok(Value::Option(Some(Box::new(Value::Array(groups)))))
+                    Ok(Value::Option(Some(Box::new(Value::Array(groups.into())))))
                 }
                 None => Ok(Value::Option(None)),
             }
@@ -1097,7 +1097,7 @@ pub fn check_call_site_region_aliasing(program: &crate::Node, source_path: &str)
                     .iter()
                     .map(|arg| {
                         if let crate::Node::Identifier { name, .. } = arg
-                            && let Some(ty) = caller_param_types.get(name)
+                            && let Some(ty) = caller_param_types.get(name.as_str())
                         {
                             return region_from_type_str(ty).and_then(|(_, lbl)| lbl);
                         }
@@ -1,13 +1,14 @@
 // Enhanced REPL for Resilient language
 use crate::formatter::Formatter;
 use crate::typechecker;
-use crate::{Lexer, Node, Parser, Value};
+use crate::{LexError, Lexer, Node, Token, Value};
 use rustyline::completion::{Completer, Pair};
 use rustyline::error::ReadlineError;
 use rustyline::highlight::Highlighter;
 use rustyline::hint::Hinter;
 use rustyline::validate::Validator;
 use rustyline::{Context, Editor, Helper, Result as RustylineResult};
+use std::borrow::Cow;
 use std::cell::RefCell;
 use std::env;
 use std::fs;
@@ -16,12 +17,52 @@ use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 // ANSI color codes for syntax highlighting
-const RESET: &str = "\x1B[0m";
-const RED: &str = "\x1B[31m";
-const GREEN: &str = "\x1B[32m";
-const YELLOW: &str = "\x1B[33m";
-const BLUE: &str = "\x1B[34m";
-const CYAN: &str = "\x1B[36m";
+const RESET: Color = Color("\x1B[0m");
+const RED: Color = Color("\x1B[31m");
+const GREEN: Color = Color("\x1B[32m");
+const YELLOW: Color = Color("\x1B[33m");
+const BLUE: Color = Color("\x1B[34m");
+const CYAN: Color = Color("\x1B[36m");
+
+thread_local! {
+    /// `color = false` in the REPL settings turns every color off.
+    static COLOR: std::cell::Cell<bool> = const { std::cell::Cell::new(true) };
+}
+
+/// An ANSI color code that formats as nothing when color is off.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Color(&'static str);
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if COLOR.with(std::cell::Cell::get) {
+            f.write_str(self.0)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// `println!` for the REPL's own output; also records into an
+/// active `:transcript`.
+macro_rules! out {
+    ($($arg:tt)*) => {{
+        let text = format!($($arg)*);
+        println!("{}", text);
+        crate::repl_transcript::record(&text);
+        crate::repl_transcript::record("\n");
+    }};
+}
+
+/// `eprintln!` counterpart of [`out!`], for diagnostics.
+macro_rules! eout {
+    ($($arg:tt)*) => {{
+        let text = format!($($arg)*);
+        eprintln!("{}", text);
+        crate::repl_transcript::record(&text);
+        crate::repl_transcript::record("\n");
+    }};
+}
 
 /// RES-311: language keywords surfaced as tab-completion candidates.
 /// Mirrors the keyword table in `lib.rs::Lexer::next_token`. Hand-curated
@@ -130,6 +171,358 @@ pub(crate) fn collect_suggestions(bindings: &[String], prefix: &str) -> Vec<Stri
     out
 }
 
+/// Type-directed completion for the line being edited: the session's
+/// earlier input followed by `line` is checked, and every identifier
+/// in scope at the end of `line` comes back with its type. This is
+/// what lets a parameter or `let` typed earlier on the same line
+/// complete before the line has ever run.
+pub(crate) fn typed_candidates(session: &str, line: &str) -> Vec<typechecker::Completion> {
+    let src = format!("{}{}", session, line);
+    let (program, _errs) = crate::parse_silent(&src);
+    let last = src.rsplit('\n').next().unwrap_or("");
+    let position = crate::span::Pos::new(src.lines().count().max(1), last.chars().count() + 1, 0);
+    typechecker::query_completions(&program, position)
+}
+
+/// Prompt shown while an input is still open.
+const CONTINUATION_PROMPT: &str = "... ";
+
+/// Whether `src` stops inside an open `{`, `(` or `[`, a string or a
+/// block comment, so the REPL should read another line before
+/// evaluating it. Brackets inside strings, chars and comments do not
+/// count; a stray closer makes the input complete so the parser can
+/// report it.
+pub(crate) fn needs_continuation(src: &str) -> bool {
+    let chars: Vec<char> = src.chars().collect();
+    let at = |i: usize| chars.get(i).copied().unwrap_or('\0');
+    let starts_triple = |i: usize| at(i) == '"' && at(i + 1) == '"' && at(i + 2) == '"';
+    let mut depth: i64 = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '/' if at(i + 1) == '/' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if at(i + 1) == '*' => {
+                let mut nested = 1;
+                i += 2;
+                while nested > 0 {
+                    if i >= chars.len() {
+                        return true;
+                    }
+                    if chars[i] == '*' && at(i + 1) == '/' {
+                        nested -= 1;
+                        i += 2;
+                    } else if chars[i] == '/' && at(i + 1) == '*' {
+                        nested += 1;
+                        i += 2;
+                    } else {
+                        i += 1;
+                    }
+                }
+                continue;
+            }
+            '"' => {
+                // `r"..."` and `r"""..."""` have no escapes.
+                let raw = i > 0 && chars[i - 1] == 'r';
+                if starts_triple(i) {
+                    i += 3;
+                    while !starts_triple(i) {
+                        if i >= chars.len() {
+                            return true;
+                        }
+                        i += if !raw && chars[i] == '\\' { 2 } else { 1 };
+                    }
+                    i += 2;
+                } else {
+                    i += 1;
+                    while at(i) != '"' {
+                        if i >= chars.len() {
+                            return true;
+                        }
+                        i += if !raw && chars[i] == '\\' { 2 } else { 1 };
+                    }
+                }
+            }
+            '\'' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '\'' && chars[i] != '\n' {
+                    i += if chars[i] == '\\' { 2 } else { 1 };
+                }
+            }
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => {
+                depth -= 1;
+                if depth < 0 {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    depth > 0
+}
+
+/// Add one line read at the prompt to the input being built: a
+/// `:paste` block collects raw lines until a lone `:end`, anything else
+/// until [`needs_continuation`] is satisfied. Returns the input once it
+/// is complete.
+fn take_line(line: &str, pending: &mut String, paste: &mut Option<String>) -> Option<String> {
+    if let Some(buffer) = paste.as_mut() {
+        if line.trim() != ":end" {
+            buffer.push_str(line);
+            buffer.push('\n');
+            return None;
+        }
+        return paste.take().map(|buffered| buffered.trim().to_string());
+    }
+    if pending.is_empty() && line.trim() == ":paste" {
+        // Not `out!`: the banner is the REPL's, not the pasted code's
+        // output, so a transcript leaves it out.
+        println!("(paste mode: end with a line holding only :end)");
+        *paste = Some(String::new());
+        return None;
+    }
+    pending.push_str(line);
+    pending.push('\n');
+    if needs_continuation(pending) {
+        return None;
+    }
+    Some(std::mem::take(pending).trim().to_string())
+}
+
+/// Color `line` as it is typed: keywords blue, string and char
+/// literals green, numbers and booleans yellow. The bracket at the
+/// cursor (or just before it) and its partner are cyan; a bracket
+/// with no partner is red. Tokens come from the real lexer, so what
+/// is colored is what the parser will see; comments stay plain.
+/// `pos` is the cursor's byte offset, as rustyline passes it.
+pub(crate) fn highlight_line(line: &str, pos: usize) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut lexer = Lexer::new(line);
+    // (start, end) in chars, and the color for that range.
+    let mut spans: Vec<(usize, usize, Option<Color>)> = Vec::new();
+    let mut brackets: Vec<(usize, char)> = Vec::new();
+    loop {
+        let (token, span) = lexer.next_token_with_span();
+        if matches!(token, Token::Eof) {
+            break;
+        }
+        let start = span.start.offset.min(chars.len());
+        let end = span.end.offset.clamp(start + 1, chars.len().max(start + 1));
+        let text = &chars[start..end.min(chars.len())];
+        let color = match token {
+            // A string still being typed has no closing quote yet.
+            Token::StringLiteral(_)
+            | Token::CharLiteral(_)
+            | Token::BytesLiteral(_)
+            | Token::Error(LexError::UnterminatedString) => Some(GREEN),
+            Token::IntLiteral(_)
+            | Token::FloatLiteral(_)
+            | Token::BoolLiteral(_)
+            | Token::Error(LexError::IntOverflow(_) | LexError::InvalidFloat(_)) => Some(YELLOW),
+            Token::Identifier(_) | Token::Underscore | Token::Error(_) => None,
+            Token::LeftParen
+            | Token::RightParen
+            | Token::LeftBrace
+            | Token::RightBrace
+            | Token::LeftBracket
+            | Token::RightBracket => {
+                brackets.push((spans.len(), text[0]));
+                None
+            }
+            _ if text.first().is_some_and(|c| c.is_alphabetic()) => Some(BLUE),
+            _ => None,
+        };
+        spans.push((start, end, color));
+    }
+
+    // Pair the brackets; whatever is left unpaired is red.
+    let mut partner: Vec<Option<usize>> = vec![None; spans.len()];
+    let mut open: Vec<(usize, char)> = Vec::new();
+    for &(idx, ch) in &brackets {
+        match ch {
+            '(' | '{' | '[' => open.push((idx, ch)),
+            _ => {
+                let want = match ch {
+                    ')' => '(',
+                    '}' => '{',
+                    _ => '[',
+                };
+                if open.last().is_some_and(|&(_, o)| o == want) {
+                    let (o, _) = open.pop().unwrap_or_default();
+                    partner[o] = Some(idx);
+                    partner[idx] = Some(o);
+                } else {
+                    spans[idx].2 = Some(RED);
+                }
+            }
+        }
+    }
+    for (idx, _) in open {
+        spans[idx].2 = Some(RED);
+    }
+    let cursor = line[..pos.min(line.len())].chars().count();
+    let at_cursor = |c: usize| {
+        brackets
+            .iter()
+            .map(|&(idx, _)| idx)
+            .find(|&idx| spans[idx].0 == c)
+    };
+    let current = at_cursor(cursor).or_else(|| cursor.checked_sub(1).and_then(at_cursor));
+    if let Some(idx) = current
+        && let Some(other) = partner[idx]
+    {
+        spans[idx].2 = Some(CYAN);
+        spans[other].2 = Some(CYAN);
+    }
+
+    let mut out = String::with_capacity(line.len() + spans.len() * 8);
+    let mut next = 0;
+    for (start, end, color) in spans {
+        let Some(color) = color else { continue };
+        if start < next || start >= chars.len() {
+            continue;
+        }
+        out.extend(&chars[next..start]);
+        out.push_str(color.0);
+        out.extend(&chars[start..end.min(chars.len())]);
+        out.push_str(RESET.0);
+        next = end.min(chars.len());
+    }
+    out.extend(&chars[next..]);
+    out
+}
+
+/// Render an error in `src` the way the REPL reports it: the line it
+/// points at, the token there underlined, and the message beneath.
+///
+/// ```text
+/// let x = 1 + ;
+///             ^
+/// Parse error: Expected expression after `+`, found `;`
+/// ```
+///
+/// An error without a `line:col:` position is just the message.
+pub(crate) fn caret_diagnostic(src: &str, level: &str, err: &str) -> String {
+    let (line, col, msg) = crate::parse_error_location(err);
+    let text = (line > 0)
+        .then(|| src.lines().nth(line as usize - 1))
+        .flatten();
+    let Some(text) = text else {
+        return format!("{}{}: {}{}", RED, level, msg, RESET);
+    };
+    let col = col.max(1) as usize;
+    // Keep tabs so the carets line up under them.
+    let pad: String = text
+        .chars()
+        .chain(std::iter::repeat(' '))
+        .take(col - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    // Multi-line input says which line it means.
+    let at = if src.lines().count() > 1 {
+        format!(" (line {})", line)
+    } else {
+        String::new()
+    };
+    format!(
+        "{}\n{}{}{}{}\n{}{}{}: {}{}",
+        text,
+        pad,
+        RED,
+        "^".repeat(token_width_at(src, line as usize, col)),
+        RESET,
+        RED,
+        level,
+        at,
+        msg,
+        RESET
+    )
+}
+
+/// Width in columns of the token starting at `line:col` in `src`, or
+/// 1 when none starts there (an error at end of input).
+fn token_width_at(src: &str, line: usize, col: usize) -> usize {
+    let mut lexer = Lexer::new(src);
+    loop {
+        let (token, span) = lexer.next_token_with_span();
+        if matches!(token, Token::Eof) || span.start.line > line {
+            return 1;
+        }
+        if span.start.line == line && span.start.column == col {
+            return if span.end.line == line {
+                span.end.column.saturating_sub(col).max(1)
+            } else {
+                1
+            };
+        }
+    }
+}
+
+/// Runs `:time` makes when `-n` is not given.
+const TIME_DEFAULT_RUNS: usize = 10;
+
+/// Parse `src` as a single expression for a `:` command, returned as
+/// a one-statement program.
+fn parse_expression_input(src: &str, command: &str) -> Result<Node, String> {
+    let (parsed, errs) = crate::parse_silent(src);
+    if let Some(err) = errs.first() {
+        return Err(err.clone());
+    }
+    match &parsed {
+        Node::Program(stmts) => match stmts.as_slice() {
+            [stmt] if matches!(stmt.node, Node::ExpressionStatement { .. }) => Ok(parsed),
+            [_] => Err(format!("{command} takes an expression, not a statement")),
+            _ => Err(format!("{command} takes a single expression")),
+        },
+        _ => Err(format!("{command} takes a single expression")),
+    }
+}
+
+/// `s` without a leading `flag` word, or `None` if it does not start
+/// with one.
+fn strip_flag<'a>(s: &'a str, flag: &str) -> Option<&'a str> {
+    let tail = s.strip_prefix(flag)?;
+    (tail.is_empty() || tail.starts_with(char::is_whitespace)).then(|| tail.trim_start())
+}
+
+/// Run `eval` `runs` times; returns the last value, each run's wall
+/// time, and the evaluation steps of all runs together.
+fn measure_runs(
+    runs: usize,
+    mut eval: impl FnMut() -> Result<Value, String>,
+) -> Result<(Value, Vec<std::time::Duration>, u64), String> {
+    let mut times = Vec::with_capacity(runs);
+    let mut steps = 0;
+    let mut value = Value::Void;
+    for _ in 0..runs {
+        let start = std::time::Instant::now();
+        let (result, used) = crate::fuel::count_steps(&mut eval);
+        times.push(start.elapsed());
+        steps += used;
+        value = result?;
+    }
+    Ok((value, times, steps))
+}
+
+/// A duration with three significant-ish digits in a fitting unit.
+fn format_duration(d: std::time::Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{nanos}ns")
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.3}s", nanos as f64 / 1e9)
+    }
+}
+
 /// RES-311: rustyline `Helper` that drives Tab completion in the REPL.
 /// The candidate list is rebuilt on every `complete()` call from the
 /// shared `bindings` snapshot, so new `let`/`fn` definitions become
@@ -139,11 +532,14 @@ pub(crate) struct RzCompleter {
     /// top-level frame. Refreshed by the REPL after every successful
     /// line via `RefCell::borrow_mut`.
     bindings: Rc<RefCell<Vec<String>>>,
+    /// Every evaluated input so far, one per line; the source
+    /// `typed_candidates` checks ahead of the line being edited.
+    session: Rc<RefCell<String>>,
 }
 
 impl RzCompleter {
-    fn new(bindings: Rc<RefCell<Vec<String>>>) -> Self {
-        RzCompleter { bindings }
+    fn new(bindings: Rc<RefCell<Vec<String>>>, session: Rc<RefCell<String>>) -> Self {
+        RzCompleter { bindings, session }
     }
 }
 
@@ -157,12 +553,20 @@ impl Completer for RzCompleter {
         _ctx: &Context<'_>,
     ) -> RustylineResult<(usize, Vec<Pair>)> {
         let (start, prefix) = extract_prefix(line, pos);
-        let bindings = self.bindings.borrow();
-        let suggestions = collect_suggestions(&bindings, prefix);
+        if prefix.is_empty() {
+            return Ok((start, Vec::new()));
+        }
+        let typed = typed_candidates(&self.session.borrow(), &line[..pos]);
+        let mut names = self.bindings.borrow().clone();
+        names.extend(typed.iter().map(|c| c.name.clone()));
+        let suggestions = collect_suggestions(&names, prefix);
         let pairs = suggestions
             .into_iter()
             .map(|s| Pair {
-                display: s.clone(),
+                display: match typed.iter().find(|c| c.name == s) {
+                    Some(c) => format!("{}: {}", s, c.ty),
+                    None => s.clone(),
+                },
                 replacement: s,
             })
             .collect();
@@ -173,7 +577,21 @@ impl Completer for RzCompleter {
 impl Hinter for RzCompleter {
     type Hint = String;
 }
-impl Highlighter for RzCompleter {}
+impl Highlighter for RzCompleter {
+    fn highlight<'l>(&self, line: &'l str, pos: usize) -> Cow<'l, str> {
+        if COLOR.with(std::cell::Cell::get) {
+            Cow::Owned(highlight_line(line, pos))
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+
+    // Re-color on every edit and cursor move so the bracket pair
+    // follows the cursor.
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        COLOR.with(std::cell::Cell::get)
+    }
+}
 impl Validator for RzCompleter {}
 impl Helper for RzCompleter {}
 
@@ -190,6 +608,16 @@ pub struct EnhancedREPL {
     /// interpreter's top-level frame. Cloned into the rustyline helper
     /// so Tab completion sees fresh state after each REPL line.
     completion_bindings: Rc<RefCell<Vec<String>>>,
+    /// Source of every evaluated input, shared with the completer so
+    /// it can type what the session has defined.
+    session_source: Rc<RefCell<String>>,
+    /// Type environment of the session: every evaluated input has been
+    /// checked into it, so a function defined on one line is known on
+    /// the next, as the interpreter's environment is.
+    type_checker: typechecker::TypeChecker,
+    /// Non-void results so far; the latest is bound to `_` and to
+    /// `_<n>`.
+    results: usize,
 }
 
 impl EnhancedREPL {
@@ -221,6 +649,9 @@ impl EnhancedREPL {
             history_path,
             examples_dir,
             completion_bindings,
+            session_source: Rc::new(RefCell::new(String::new())),
+            type_checker: typechecker::TypeChecker::new(),
+            results: 0,
         }
     }
 
@@ -237,29 +668,51 @@ impl EnhancedREPL {
         // clone of `self.completion_bindings`, so refreshing the Vec
         // through `refresh_completion_bindings` is visible on the next
         // Tab press.
-        let helper = RzCompleter::new(self.completion_bindings.clone());
-        let mut rl: Editor<RzCompleter, rustyline::history::DefaultHistory> = Editor::new()?;
+        let helper = RzCompleter::new(
+            self.completion_bindings.clone(),
+            self.session_source.clone(),
+        );
+        let config = crate::repl_config::ReplConfig::load();
+        COLOR.with(|c| c.set(config.color));
+        self.type_check_enabled |= config.typecheck;
+        let editor_config = rustyline::Config::builder()
+            .edit_mode(config.edit_mode)
+            .max_history_size(config.history_size)?
+            .build();
+        let mut rl: Editor<RzCompleter, rustyline::history::DefaultHistory> =
+            Editor::with_config(editor_config)?;
         rl.set_helper(Some(helper));
 
         // Load command history
         if self.history_path.exists()
             && let Err(err) = rl.load_history(&self.history_path)
         {
-            eprintln!("Error loading history: {}", err);
+            eout!("Error loading history: {}", err);
         }
 
-        println!(
+        out!(
             "{}Resilient Programming Language REPL (v0.1.0){}",
-            CYAN, RESET
+            CYAN,
+            RESET
         );
-        println!(
+        out!(
             "Type '{}help{}' for command list, '{}exit{}' to quit",
-            GREEN, RESET, RED, RESET
+            GREEN,
+            RESET,
+            RED,
+            RESET
         );
 
+        // Lines of an input that is still open (see
+        // `needs_continuation`), evaluated together once it closes.
+        let mut pending = String::new();
+        // `:paste` mode: raw lines collected until a lone `:end`.
+        let mut paste: Option<String> = None;
         loop {
             // Create prompt with type checking indicator
-            let prompt = if self.type_check_enabled {
+            let prompt = if !pending.is_empty() || paste.is_some() {
+                format!("{}{}{}", BLUE, CONTINUATION_PROMPT, RESET)
+            } else if self.type_check_enabled {
                 format!("{}>> [typecheck]{} ", BLUE, RESET)
             } else {
                 format!("{}>> {} ", BLUE, RESET)
@@ -270,7 +723,9 @@ impl EnhancedREPL {
 
             match readline {
                 Ok(line) => {
-                    let input = line.trim();
+                    let Some(input) = take_line(&line, &mut pending, &mut paste) else {
+                        continue;
+                    };
 
                     // Skip empty lines
                     if input.is_empty() {
@@ -278,26 +733,31 @@ impl EnhancedREPL {
                     }
 
                     // Add to history
-                    rl.add_history_entry(input)?;
+                    rl.add_history_entry(input.as_str())?;
 
                     // Process the input
-                    self.process_input(input);
+                    self.process_input(&input);
 
                     // RES-311: refresh the completer's binding snapshot
                     // so any new `let` / `fn` from this turn is
                     // completable on the next prompt.
                     self.refresh_completion_bindings();
                 }
+                // Ctrl-C inside an open input drops just that input.
+                Err(ReadlineError::Interrupted) if !pending.is_empty() || paste.is_some() => {
+                    pending.clear();
+                    paste = None;
+                }
                 Err(ReadlineError::Interrupted) => {
-                    println!("CTRL-C");
+                    out!("CTRL-C");
                     break;
                 }
                 Err(ReadlineError::Eof) => {
-                    println!("CTRL-D");
+                    out!("CTRL-D");
                     break;
                 }
                 Err(err) => {
-                    eprintln!("Error: {}", err);
+                    eout!("Error: {}", err);
                     break;
                 }
             }
@@ -305,17 +765,62 @@ impl EnhancedREPL {
 
         // Save history
         if let Err(err) = rl.save_history(&self.history_path) {
-            eprintln!("Error saving history: {}", err);
+            eout!("Error saving history: {}", err);
         }
 
         Ok(())
     }
 
     fn process_input(&mut self, input: &str) {
+        // `:transcript` commands are not themselves recorded.
+        if let Some(rest) = strip_flag(input, ":transcript") {
+            self.transcript_command(rest);
+            return;
+        }
+        if crate::repl_transcript::path().is_none() {
+            self.handle_input(input);
+            return;
+        }
+        crate::output_sink::with_tee(crate::repl_transcript::record, || self.handle_input(input));
+        if let Err(e) = crate::repl_transcript::write_entry(input) {
+            eout!("{}Transcript stopped: {}{}", RED, e, RESET);
+        }
+    }
+
+    /// `:transcript on <file>` starts a Markdown record of the session,
+    /// `:transcript off` ends it, and a bare `:transcript` says where
+    /// the current one is going.
+    /// Its messages bypass `out!` so they stay out of the transcript.
+    fn transcript_command(&mut self, args: &str) {
+        if let Some(file) = strip_flag(args, "on") {
+            if file.is_empty() {
+                eprintln!("{}usage: :transcript on <file>{}", RED, RESET);
+                return;
+            }
+            match crate::repl_transcript::start(Path::new(file)) {
+                Ok(()) => println!("Recording transcript to {}", file),
+                Err(e) => eprintln!("{}Error: could not write {}: {}{}", RED, file, e, RESET),
+            }
+        } else if args == "off" {
+            match crate::repl_transcript::stop() {
+                Some(path) => println!("Transcript saved to {}", path.display()),
+                None => eprintln!("{}No transcript is being recorded{}", RED, RESET),
+            }
+        } else if args.is_empty() {
+            match crate::repl_transcript::path() {
+                Some(path) => println!("Recording transcript to {}", path.display()),
+                None => println!("No transcript is being recorded"),
+            }
+        } else {
+            eprintln!("{}usage: :transcript [on <file> | off]{}", RED, RESET);
+        }
+    }
+
+    fn handle_input(&mut self, input: &str) {
         // Handle special commands
         match input {
             "exit" | "quit" => {
-                println!("Exiting Resilient REPL");
+                out!("Exiting Resilient REPL");
                 std::process::exit(0);
             }
             "help" => {
@@ -329,7 +834,7 @@ impl EnhancedREPL {
             }
             "typecheck" => {
                 self.type_check_enabled = !self.type_check_enabled;
-                println!(
+                out!(
                     "Type checking {}",
                     if self.type_check_enabled {
                         format!("{}enabled{}", GREEN, RESET)
@@ -339,6 +844,24 @@ impl EnhancedREPL {
                 );
                 return;
             }
+            "strict" => {
+                let on = !crate::strict_any::strict_any();
+                crate::strict_any::set_strict_any(on);
+                // Strict diagnostics come from the checker, so turning
+                // strict mode on also turns type checking on.
+                if on {
+                    self.type_check_enabled = true;
+                }
+                out!(
+                    "Strict typing (no `Any`) {}",
+                    if on {
+                        format!("{}enabled{}", GREEN, RESET)
+                    } else {
+                        format!("{}disabled{}", YELLOW, RESET)
+                    }
+                );
+                return;
+            }
             "examples" => {
                 self.show_examples();
                 return;
@@ -354,79 +877,182 @@ impl EnhancedREPL {
             return;
         }
 
+        // `:tokens <src>` / `:ast <src>` — what the lexer and parser
+        // make of the input, without evaluating it.
+        if let Some(rest) = input.strip_prefix(":tokens")
+            && (rest.is_empty() || rest.starts_with(char::is_whitespace))
+        {
+            out!("{}", crate::dump_tokens_string(rest.trim()));
+            return;
+        }
+        if let Some(rest) = input.strip_prefix(":ast")
+            && (rest.is_empty() || rest.starts_with(char::is_whitespace))
+        {
+            match crate::dump_ast_string(rest.trim()) {
+                Ok(tree) => out!("{}", tree),
+                Err(errs) => {
+                    for e in errs {
+                        eout!("{}Parse error: {}{}", RED, e, RESET);
+                    }
+                }
+            }
+            return;
+        }
+
+        // `:time [-n N] [--vm] <expr>` — micro-benchmark an expression.
+        if let Some(rest) = strip_flag(input, ":time") {
+            match self.time_expr(rest) {
+                Ok((value, report)) => {
+                    if !matches!(value, Value::Void) {
+                        out!("{}{}{}", CYAN, value, RESET);
+                    }
+                    out!("{}", report);
+                }
+                Err(e) => eout!("{}Error: {}{}", RED, e, RESET),
+            }
+            return;
+        }
+
+        // `:doc <name>` — signature and docs of a function or builtin.
+        if let Some(rest) = strip_flag(input, ":doc") {
+            match self.doc(rest) {
+                Ok(doc) => out!("{}", doc),
+                Err(e) => eout!("{}Error: {}{}", RED, e, RESET),
+            }
+            return;
+        }
+
+        // `:type <expr>` — the expression's static type, unevaluated.
+        if let Some(rest) = input.strip_prefix(":type")
+            && (rest.is_empty() || rest.starts_with(char::is_whitespace))
+        {
+            match self.type_of(rest.trim()) {
+                Ok(ty) => out!("{}{}{}", CYAN, ty, RESET),
+                Err(e) => eout!("{}", caret_diagnostic(rest.trim(), "Type error", &e)),
+            }
+            return;
+        }
+
         // RES-356: `.contracts [fn_name]` — list contracts table.
         if input == ".contracts" {
-            print!("{}", self.contracts_output(None));
+            out!("{}", self.contracts_output(None).trim_end());
             return;
         }
         if let Some(rest) = input.strip_prefix(".contracts ") {
             let fn_name = rest.trim();
             if fn_name.is_empty() {
-                print!("{}", self.contracts_output(None));
+                out!("{}", self.contracts_output(None).trim_end());
             } else {
-                print!("{}", self.contracts_output(Some(fn_name)));
+                out!("{}", self.contracts_output(Some(fn_name)).trim_end());
             }
             return;
         }
 
         // Regular code evaluation
-        let lexer = Lexer::new(input);
-        let mut parser = Parser::new(lexer);
-
-        // Parse the program
-        let program = parser.parse_program();
+        let (program, parse_errs) = crate::parse_unlowered(input, false);
 
         // If parser recorded errors, abort before type-checking/execution.
-        // Errors are already printed as they happen inside the parser.
-        if !parser.errors.is_empty() {
+        if !parse_errs.is_empty() {
+            for e in &parse_errs {
+                eout!("{}", caret_diagnostic(input, "Parse error", e));
+            }
             return;
         }
 
-        // Run type checker if enabled
+        // Check the input against a copy of the session's type
+        // environment. With type checking on, a rejected input leaves
+        // the session untouched; with it off the input runs anyway, so
+        // whatever it declared is kept for when checking is turned on.
+        let mut checker = self
+            .type_checker
+            .clone()
+            .with_strict_any(crate::strict_any::strict_any());
         if self.type_check_enabled {
-            match typechecker::TypeChecker::new().check_program(&program) {
-                Ok(_) => println!("{}Type check passed{}", GREEN, RESET),
+            match checker.check_program(&program) {
+                Ok(_) => out!("{}Type check passed{}", GREEN, RESET),
                 Err(e) => {
-                    eprintln!("{}Type error: {}{}", RED, e, RESET);
+                    eout!("{}", caret_diagnostic(input, "Type error", &e));
                     return; // Skip execution if type checking fails
                 }
             }
+        } else {
+            let _ = typechecker::collect_check_diagnostics(|| checker.check_program(&program));
+        }
+        // The static type of a trailing expression, for binding its
+        // result below; `Any` when it did not check.
+        let result_type = match &program {
+            Node::Program(stmts) => match stmts.last().map(|s| &s.node) {
+                Some(Node::ExpressionStatement { expr, .. }) => {
+                    let mut probe = checker.clone();
+                    typechecker::collect_check_diagnostics(|| probe.check_node(expr))
+                        .0
+                        .ok()
+                }
+                _ => None,
+            },
+            _ => None,
         }
+        .unwrap_or(typechecker::Type::Any);
+        self.type_checker = checker;
 
-        // Evaluate the program
+        {
+            let mut session = self.session_source.borrow_mut();
+            session.push_str(input);
+            session.push('\n');
+        }
+
+        // Evaluate the program. Each input gets the full
+        // `--max-steps` budget.
+        crate::fuel::refill();
+        crate::clear_exit_request();
         match self.interpreter.eval(&program) {
             Ok(value) => {
                 if !matches!(value, Value::Void) {
-                    println!("{}{}{}", CYAN, value, RESET);
+                    out!("{}{}{}", CYAN, value, RESET);
+                    self.bind_result(value, result_type);
                 }
             }
             Err(error) => {
-                eprintln!("{}Error: {}{}", RED, error, RESET);
+                eout!("{}Error: {}{}", RED, error, RESET);
             }
         }
     }
 
+    /// Bind a result to `_` and to the next `_<n>`, in both the
+    /// interpreter's and the type checker's environment.
+    fn bind_result(&mut self, value: Value, typ: typechecker::Type) {
+        self.results += 1;
+        let numbered = format!("_{}", self.results);
+        self.interpreter.env.set(numbered.as_str(), value.clone());
+        self.interpreter.env.set("_", value);
+        self.type_checker.bind(&numbered, typ.clone());
+        self.type_checker.bind("_", typ);
+    }
+
     fn show_help(&self) {
-        println!("{}Available commands:{}", CYAN, RESET);
-        println!("  {}help{}       - Show this help message", GREEN, RESET);
-        println!("  {}exit{}       - Exit the REPL", GREEN, RESET);
-        println!("  {}clear{}      - Clear the screen", GREEN, RESET);
+        out!("{}Available commands:{}", CYAN, RESET);
+        out!("  {}help{}       - Show this help message", GREEN, RESET);
+        out!("  {}exit{}       - Exit the REPL", GREEN, RESET);
+        out!("  {}clear{}      - Clear the screen", GREEN, RESET);
         if self.examples_dir.is_some() {
-            println!(
+            out!(
                 "  {}examples{}        - List example files in --examples-dir",
-                GREEN, RESET
+                GREEN,
+                RESET
             );
-            println!(
+            out!(
                 "  {}examples <name>{} - Print the contents of one example file",
-                GREEN, RESET
+                GREEN,
+                RESET
             );
         } else {
-            println!(
+            out!(
                 "  {}examples{}   - Show example code snippets",
-                GREEN, RESET
+                GREEN,
+                RESET
             );
         }
-        println!(
+        out!(
             "  {}typecheck{}  - Toggle type checking (currently {})",
             GREEN,
             RESET,
@@ -436,31 +1062,88 @@ impl EnhancedREPL {
                 format!("{}disabled{}", YELLOW, RESET)
             }
         );
-        println!(
+        out!(
+            "  {}strict{}     - Toggle strict typing, rejecting `Any` (currently {})",
+            GREEN,
+            RESET,
+            if crate::strict_any::strict_any() {
+                format!("{}enabled{}", GREEN, RESET)
+            } else {
+                format!("{}disabled{}", YELLOW, RESET)
+            }
+        );
+        out!(
+            "  {}:type <expr>{}        - Show an expression's type without running it",
+            GREEN,
+            RESET
+        );
+        out!(
+            "  {}:transcript on <file>{} - Record inputs and output as Markdown (:transcript off)",
+            GREEN,
+            RESET
+        );
+        out!(
+            "  {}:paste{}              - Read lines as one input until a lone :end",
+            GREEN,
+            RESET
+        );
+        out!(
+            "  {}:doc <name>{}         - Show a function's or builtin's signature and docs",
+            GREEN,
+            RESET
+        );
+        out!(
+            "  {}:time [-n N] [--vm] <expr>{} - Time N runs (default {}) of an expression",
+            GREEN,
+            RESET,
+            TIME_DEFAULT_RUNS
+        );
+        out!(
+            "  {}:tokens <code>{}      - Show the lexer's token stream for the code",
+            GREEN,
+            RESET
+        );
+        out!(
+            "  {}:ast <code>{}         - Show the parsed syntax tree for the code",
+            GREEN,
+            RESET
+        );
+        out!(
             "  {}.contracts{}          - List all function contracts (requires/ensures)",
-            GREEN, RESET
+            GREEN,
+            RESET
         );
-        println!(
+        out!(
             "  {}.contracts <fn_name>{} - Show contracts for one function",
-            GREEN, RESET
+            GREEN,
+            RESET
+        );
+
+        out!(
+            "\nAn input with an open `{{`, `(`, `[` or string continues on the\nnext line at the `{}` prompt; Ctrl-C drops it.",
+            CONTINUATION_PROMPT.trim_end()
         );
 
-        println!("\n{}Resilient Language Syntax:{}", CYAN, RESET);
-        println!(
+        out!("\n{}Resilient Language Syntax:{}", CYAN, RESET);
+        out!(
             "  {}fn name(type param) {{ ... }}{}  - Define a function",
-            YELLOW, RESET
+            YELLOW,
+            RESET
         );
-        println!(
+        out!(
             "  {}let name = value;{}       - Declare a variable",
-            YELLOW, RESET
+            YELLOW,
+            RESET
         );
-        println!(
+        out!(
             "  {}live {{ ... }}{}             - Define a live block",
-            YELLOW, RESET
+            YELLOW,
+            RESET
         );
-        println!(
+        out!(
             "  {}assert(condition, \"msg\");{}  - Add an assertion",
-            YELLOW, RESET
+            YELLOW,
+            RESET
         );
     }
 
@@ -471,33 +1154,35 @@ impl EnhancedREPL {
         if let Some(dir) = &self.examples_dir {
             match Self::list_examples_in(dir) {
                 Ok(text) => {
-                    println!("{}Example files in {}:{}", CYAN, dir.display(), RESET);
-                    print!("{}", text);
+                    out!("{}Example files in {}:{}", CYAN, dir.display(), RESET);
+                    if let Some(listing) = text.strip_suffix('\n') {
+                        out!("{}", listing);
+                    }
                 }
                 Err(e) => {
-                    eprintln!("{}examples: {}{}", RED, e, RESET);
+                    eout!("{}examples: {}{}", RED, e, RESET);
                 }
             }
             return;
         }
 
-        println!("{}Example code snippets:{}", CYAN, RESET);
+        out!("{}Example code snippets:{}", CYAN, RESET);
 
-        println!("\n{}1. Basic variable and function:{}", GREEN, RESET);
-        println!("{}let x = 42;", YELLOW);
-        println!("fn add(int a, int b) {{ return a + b; }}");
-        println!("add(x, 10);{}", RESET);
+        out!("\n{}1. Basic variable and function:{}", GREEN, RESET);
+        out!("{}let x = 42;", YELLOW);
+        out!("fn add(int a, int b) {{ return a + b; }}");
+        out!("add(x, 10);{}", RESET);
 
-        println!("\n{}2. Live block example:{}", GREEN, RESET);
-        println!("{}live {{", YELLOW);
-        println!("  let result = 100 / 0; // This would normally crash");
-        println!("  println(\"Result: \" + result);");
-        println!("}}{}", RESET);
+        out!("\n{}2. Live block example:{}", GREEN, RESET);
+        out!("{}live {{", YELLOW);
+        out!("  let result = 100 / 0; // This would normally crash");
+        out!("  println(\"Result: \" + result);");
+        out!("}}{}", RESET);
 
-        println!("\n{}3. Assertion example:{}", GREEN, RESET);
-        println!("{}let age = 25;", YELLOW);
-        println!("assert(age >= 18, \"Must be an adult\");");
-        println!("println(\"Access granted\");{}", RESET);
+        out!("\n{}3. Assertion example:{}", GREEN, RESET);
+        out!("{}let age = 25;", YELLOW);
+        out!("assert(age >= 18, \"Must be an adult\");");
+        out!("println(\"Access granted\");{}", RESET);
     }
 
     /// RES-026: handle `examples <name>` — print the contents of a
@@ -505,16 +1190,19 @@ impl EnhancedREPL {
     /// any '/' or '..' is rejected up front.
     fn show_named_example(&self, name: &str) {
         let Some(dir) = &self.examples_dir else {
-            eprintln!(
+            eout!(
                 "{}examples: '{}' subcommand requires --examples-dir{}",
-                RED, name, RESET
+                RED,
+                name,
+                RESET
             );
             return;
         };
         if name.contains('/') || name.contains("..") || name.is_empty() {
-            eprintln!(
+            eout!(
                 "{}examples: name must be a single basename, not a path{}",
-                RED, RESET
+                RED,
+                RESET
             );
             return;
         }
@@ -525,14 +1213,11 @@ impl EnhancedREPL {
         };
         match fs::read_to_string(&candidate) {
             Ok(body) => {
-                println!("{}--- {} ---{}", CYAN, candidate.display(), RESET);
-                print!("{}", body);
-                if !body.ends_with('\n') {
-                    println!();
-                }
+                out!("{}--- {} ---{}", CYAN, candidate.display(), RESET);
+                out!("{}", body.strip_suffix('\n').unwrap_or(&body));
             }
             Err(_) => {
-                eprintln!(
+                eout!(
                     "{}examples: no such file '{}' in {}{}",
                     RED,
                     name,
@@ -567,6 +1252,103 @@ impl EnhancedREPL {
     /// RES-356: Render a contract expression node to a compact single-line
     /// string. Uses the canonical formatter; trims trailing whitespace and
     /// newlines so each clause fits on one table row.
+    /// `:type` — typecheck `expr` in the session's type environment
+    /// and name its type. Nothing is evaluated, so a
+    /// call's side effects do not happen.
+    pub(crate) fn type_of(&self, expr: &str) -> Result<String, String> {
+        if expr.is_empty() {
+            return Err("usage: :type <expr>".to_string());
+        }
+        let parsed = parse_expression_input(expr, ":type")?;
+        let Node::Program(stmts) = &parsed else {
+            unreachable!("parse_expression_input returns a program")
+        };
+        let Node::ExpressionStatement { expr, .. } = &stmts[0].node else {
+            unreachable!("parse_expression_input checked the statement")
+        };
+        let mut checker = self.type_checker.clone();
+        let (ty, _) = typechecker::collect_check_diagnostics(|| checker.check_node(expr));
+        ty.map(|ty| ty.to_string()).map_err(|e| e.message)
+    }
+
+    /// `:time [-n N] [--vm] <expr>` — evaluate `expr` N times (default
+    /// 10) and report the wall time and evaluation steps of a run.
+    /// The interpreter evaluates it in the session, like any input;
+    /// `--vm` compiles it together with the session's definitions and
+    /// runs the bytecode instead, so the two backends can be compared.
+    /// Returns the expression's value and the report line.
+    pub(crate) fn time_expr(&mut self, args: &str) -> Result<(Value, String), String> {
+        let mut runs = TIME_DEFAULT_RUNS;
+        let mut on_vm = false;
+        let mut rest = args.trim_start();
+        loop {
+            if let Some(tail) = strip_flag(rest, "--vm") {
+                on_vm = true;
+                rest = tail;
+            } else if let Some(tail) = strip_flag(rest, "-n") {
+                let end = tail.find(char::is_whitespace).unwrap_or(tail.len());
+                runs = tail[..end]
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| {
+                        format!(
+                            ":time: -n expects a positive run count, got {:?}",
+                            &tail[..end]
+                        )
+                    })?;
+                rest = tail[end..].trim_start();
+            } else {
+                break;
+            }
+        }
+        if rest.is_empty() {
+            return Err("usage: :time [-n N] [--vm] <expr>".to_string());
+        }
+        let parsed = parse_expression_input(rest, ":time")?;
+        let (value, times, steps) = if on_vm {
+            let Node::Program(mut stmts) = crate::parse_silent(&self.session_source.borrow()).0
+            else {
+                unreachable!("the parser returns a program")
+            };
+            // Definitions and bindings only: re-running the session's
+            // bare expressions would repeat their output every run.
+            stmts.retain(|s| !matches!(s.node, Node::ExpressionStatement { .. }));
+            let Node::Program(expr_stmt) = parsed else {
+                unreachable!("parse_expression_input returns a program")
+            };
+            stmts.extend(expr_stmt);
+            let program = crate::monomorph::lower(&Node::Program(stmts));
+            let program = crate::devirtualize::lower(&program);
+            let compiled = crate::compiler::compile(&program)
+                .map_err(|e| format!("VM compile error: {}", e))?;
+            measure_runs(runs, || {
+                crate::vm::run(&compiled).map_err(|e| format!("VM runtime error: {}", e))
+            })?
+        } else {
+            measure_runs(runs, || self.interpreter.eval(&parsed))?
+        };
+        let mut sorted = times.clone();
+        sorted.sort();
+        let median = if runs % 2 == 1 {
+            sorted[runs / 2]
+        } else {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2
+        };
+        let mean = times.iter().sum::<std::time::Duration>() / runs as u32;
+        let report = format!(
+            "{}, {} run{}: min {}  median {}  mean {}  steps {}",
+            if on_vm { "vm" } else { "interpreter" },
+            runs,
+            if runs == 1 { "" } else { "s" },
+            format_duration(sorted[0]),
+            format_duration(median),
+            format_duration(mean),
+            steps / runs as u64
+        );
+        Ok((value, report))
+    }
+
     fn format_contract_node(node: &Node) -> String {
         Formatter::format(node)
             .trim_end_matches('\n')
@@ -582,6 +1364,75 @@ impl EnhancedREPL {
     /// When `filter` is `Some(name)`, only rows for that function are
     /// included. Output is sorted by function name (then by clause order
     /// within each function).
+    /// `:doc <name>` — the signature and doc comment of a function the
+    /// session defined, or the signature and reference entry of a
+    /// builtin. A session function shadows a builtin of the same name.
+    pub(crate) fn doc(&self, name: &str) -> Result<String, String> {
+        use std::fmt::Write;
+        let source = self.session_source.borrow();
+        let (program, _) = crate::parse_silent(&source);
+        let Node::Program(stmts) = &program else {
+            unreachable!("the parser returns a program")
+        };
+        let defined = stmts.iter().rev().find_map(|s| match &s.node {
+            Node::Function {
+                name: fn_name,
+                parameters,
+                return_type,
+                requires,
+                ensures,
+                span,
+                ..
+            } if fn_name == name => Some((parameters, return_type, requires, ensures, span)),
+            _ => None,
+        });
+        let mut out = String::new();
+        if let Some((parameters, return_type, requires, ensures, span)) = defined {
+            let params: Vec<String> = parameters
+                .iter()
+                .map(|(ty, pname)| format!("{ty} {pname}"))
+                .collect();
+            let _ = write!(out, "fn {}({})", name, params.join(", "));
+            if let Some(ret) = return_type.as_deref().filter(|r| !r.is_empty()) {
+                let _ = write!(out, " -> {}", ret);
+            }
+            for clause in requires.iter() {
+                let _ = write!(out, "\n  requires {}", Self::format_contract_node(clause));
+            }
+            for clause in ensures.iter() {
+                let _ = write!(out, "\n  ensures {}", Self::format_contract_node(clause));
+            }
+            // The `//` comment lines right above the definition.
+            let above: Vec<&str> = source
+                .lines()
+                .take(span.start.line.saturating_sub(1))
+                .collect();
+            let comment: Vec<&str> = above
+                .iter()
+                .rev()
+                .map(|l| l.trim())
+                .take_while(|l| l.starts_with("//"))
+                .map(|l| {
+                    let text = l.trim_start_matches('/');
+                    text.strip_prefix(' ').unwrap_or(text)
+                })
+                .collect();
+            if !comment.is_empty() {
+                out.push_str("\n\n");
+                out.push_str(&comment.into_iter().rev().collect::<Vec<_>>().join("\n"));
+            }
+            return Ok(out);
+        }
+        let (_, signature, _) = crate::builtin_registry::lookup(name)
+            .ok_or_else(|| format!("no function or builtin named `{}`", name))?;
+        let _ = write!(out, "builtin {}: {}", name, signature);
+        if let Some(doc) = crate::builtin_registry::doc(name) {
+            out.push_str("\n\n");
+            out.push_str(&doc);
+        }
+        Ok(out)
+    }
+
     pub(crate) fn contracts_output(&self, filter: Option<&str>) -> String {
         let map = self.interpreter.collect_contract_fns();
 
@@ -699,6 +1550,267 @@ mod tests {
     // capture needed) by evaluating Resilient source into the REPL's
     // interpreter and then inspecting the returned string.
 
+    #[test]
+    fn type_command_uses_the_session_without_evaluating() {
+        let mut repl = EnhancedREPL::new();
+        eval_in_repl(
+            &mut repl,
+            "let hits = 0;\nfn ratio(int a, int b) -> float { return to_float(a) / to_float(b); }",
+        );
+        assert_eq!(repl.type_of("ratio(1, 2)").unwrap(), "float");
+        assert_eq!(repl.type_of("ratio").unwrap(), "fn(int, int) -> float");
+        assert_eq!(repl.type_of("[hits, 2]").unwrap(), "[int]");
+        // Typed, not run: evaluating this would end the test process.
+        assert!(repl.type_of("exit(3)").is_ok());
+        assert!(repl.type_of("ratio(\"a\", 2)").is_err());
+        assert!(
+            repl.type_of("let y = 1;")
+                .unwrap_err()
+                .contains("not a statement")
+        );
+        assert!(repl.type_of("").unwrap_err().contains("usage"));
+    }
+
+    #[test]
+    fn highlight_line_colors_tokens_and_the_bracket_pair_at_the_cursor() {
+        let line = r#"let s = "if"; if x > 1.5 { f(true) }"#;
+        let colored = highlight_line(line, 0);
+        assert!(colored.starts_with(&format!(
+            "{BLUE}let{RESET} s = {GREEN}\"if\"{RESET}; {BLUE}if{RESET} x > {YELLOW}1.5{RESET} {{"
+        )));
+        assert!(colored.contains(&format!("f({YELLOW}true{RESET})")));
+        // Cursor just after `(`: it and its `)` light up.
+        let open = line.find('(').unwrap() + 1;
+        let colored = highlight_line(line, open);
+        assert!(
+            colored.contains(&format!("f{CYAN}({RESET}{YELLOW}true{RESET}{CYAN}){RESET}")),
+            "{colored}"
+        );
+        // An unclosed bracket is red; a bracket inside a string is not one.
+        assert_eq!(
+            highlight_line("g(\"(\"", 0),
+            format!("g{RED}({RESET}{GREEN}\"(\"{RESET}")
+        );
+        // Stripping the colors gives the line back.
+        let plain = highlight_line(line, open)
+            .replace(BLUE.0, "")
+            .replace(GREEN.0, "")
+            .replace(YELLOW.0, "")
+            .replace(CYAN.0, "")
+            .replace(RESET.0, "");
+        assert_eq!(plain, line);
+    }
+
+    #[test]
+    fn ast_and_tokens_commands_show_the_front_end_without_spans() {
+        let tree = crate::dump_ast_string("let x = 1 + f(2);").unwrap();
+        assert!(
+            tree.starts_with("Program(\n    [\n        LetStatement {\n            name: \"x\","),
+            "{tree}"
+        );
+        assert!(tree.contains("operator: \"+\""), "{tree}");
+        assert!(
+            !tree.contains("span") && !tree.contains("Spanned"),
+            "{tree}"
+        );
+        assert!(crate::dump_ast_string("let = ;").is_err());
+        let tokens = crate::dump_tokens_string("fn f");
+        assert_eq!(
+            tokens,
+            "1:1  Function(\"fn\")\n1:4  Identifier(\"f\")(\"f\")\n1:5  Eof(\"\")"
+        );
+    }
+
+    #[test]
+    fn type_environment_persists_across_checked_inputs() {
+        let mut repl = EnhancedREPL::new();
+        repl.type_check_enabled = true;
+        eval_in_repl(
+            &mut repl,
+            "fn sq(int x) -> int { return x * x; }\nlet n = sq(3);\nlet bad = sq(\"a\");",
+        );
+        assert_eq!(repl.type_of("n").unwrap(), "int");
+        // The rejected input neither ran nor left a binding behind.
+        assert!(repl.type_of("bad").is_err());
+        assert!(repl.interpreter.binding_names().iter().all(|b| b != "bad"));
+        // Inputs that ran unchecked are known once checking is on.
+        let mut repl = EnhancedREPL::new();
+        eval_in_repl(&mut repl, "fn half(float x) -> float { return x / 2.0; }");
+        repl.type_check_enabled = true;
+        eval_in_repl(&mut repl, "let h = half(3.0);");
+        assert_eq!(repl.type_of("h").unwrap(), "float");
+    }
+
+    #[test]
+    fn transcript_records_inputs_with_their_output() {
+        let path = make_tmp("transcript").join("session.md");
+        let mut repl = EnhancedREPL::new();
+        repl.process_input(&format!(":transcript on {}", path.display()));
+        eval_in_repl(&mut repl, "let n = 6;\nprintln(n);\nn * 7\nlet bad = ;");
+        repl.process_input(":transcript off");
+        eval_in_repl(&mut repl, "n + 1");
+        let text = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+        assert!(
+            text.starts_with(
+                "# Resilient REPL session\n\n```rust\nlet n = 6;\n```\n\n```rust\nprintln(n);\n```\n```text\n6\n```\n\n```rust\nn * 7\n```\n```text\n42\n```\n"
+            ),
+            "{text}"
+        );
+        assert!(text.contains("Parse error: "), "{text}");
+        assert!(!text.contains("n + 1") && !text.contains('\x1B'), "{text}");
+    }
+
+    #[test]
+    fn transcript_records_a_paste_without_its_banner() {
+        let path = make_tmp("transcript_paste").join("session.md");
+        let mut repl = EnhancedREPL::new();
+        repl.process_input(&format!(":transcript on {}", path.display()));
+        let (mut pending, mut paste) = (String::new(), None);
+        let mut inputs = Vec::new();
+        for line in [":paste", "let a = 2;", "", "println(a * 3);", ":end"] {
+            inputs.extend(take_line(line, &mut pending, &mut paste));
+        }
+        assert_eq!(inputs, ["let a = 2;\n\nprintln(a * 3);"]);
+        repl.process_input(&inputs[0]);
+        repl.process_input(":transcript off");
+        let text = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(
+            text,
+            "# Resilient REPL session\n\n```rust\nlet a = 2;\n\nprintln(a * 3);\n```\n```text\n6\n```\n"
+        );
+    }
+
+    #[test]
+    fn doc_shows_session_functions_and_builtins() {
+        let mut repl = EnhancedREPL::new();
+        eval_in_repl(
+            &mut repl,
+            "// Square of x.\nfn sq(int x) -> int requires x > -100 { return x * x; }",
+        );
+        assert_eq!(
+            repl.doc("sq").unwrap(),
+            "fn sq(int x) -> int\n  requires x > -100\n\nSquare of x."
+        );
+        let abs = repl.doc("abs").unwrap();
+        assert!(abs.starts_with("builtin abs: fn("), "{abs}");
+        assert!(abs.contains("Return the absolute value."), "{abs}");
+        assert!(repl.doc("no_such_fn").is_err());
+    }
+
+    #[test]
+    fn type_error_carets_sit_under_the_offending_expression() {
+        let plain = |s: String| s.replace(RED.0, "").replace(RESET.0, "");
+        let caret_line = |input: &str| {
+            let (program, errs) = crate::parse_unlowered(input, false);
+            assert!(errs.is_empty(), "{errs:?}");
+            let err = typechecker::TypeChecker::new()
+                .check_program(&program)
+                .expect_err("input should not check");
+            let rendered = plain(caret_diagnostic(input, "Type error", &err));
+            rendered.lines().nth(1).unwrap_or_default().to_string()
+        };
+        assert_eq!(caret_line("let v: int = \"abc\";"), "             ^^^^^");
+        assert_eq!(
+            caret_line("fn sq(int x) -> int { return x * x; }\nsq(true);"),
+            "   ^^^^"
+        );
+    }
+
+    #[test]
+    fn errors_echo_the_line_with_the_token_underlined() {
+        let plain = |s: String| s.replace(RED.0, "").replace(RESET.0, "");
+        assert_eq!(
+            plain(caret_diagnostic(
+                "let total = count + ;",
+                "Parse error",
+                "1:21: Expected expression after `+`, found `;`"
+            )),
+            "let total = count + ;\n                    ^\nParse error: Expected expression after `+`, found `;`"
+        );
+        assert_eq!(
+            plain(caret_diagnostic(
+                "fn f() {\n\tlet v: int = \"abc\";\n}",
+                "Type error",
+                "<unknown>:2:15: let v: int — value has type string"
+            )),
+            "\tlet v: int = \"abc\";\n\t             ^^^^^\nType error (line 2): let v: int — value has type string"
+        );
+        assert_eq!(
+            plain(caret_diagnostic("x", "Type error", "no position")),
+            "Type error: no position"
+        );
+    }
+
+    #[test]
+    fn results_are_bound_to_underscore_and_numbered_names() {
+        let mut repl = EnhancedREPL::new();
+        repl.type_check_enabled = true;
+        eval_in_repl(&mut repl, "6 * 7\nlet n = 1;\n_ + 1\nlet both = _1 + _2;");
+        assert_eq!(repl.interpreter.env.get("_").unwrap().to_string(), "43");
+        assert_eq!(repl.interpreter.env.get("both").unwrap().to_string(), "85");
+        assert_eq!(repl.type_of("_").unwrap(), "int");
+        // Void results leave the bindings alone.
+        eval_in_repl(&mut repl, "println(\"x\")");
+        assert_eq!(repl.interpreter.env.get("_").unwrap().to_string(), "43");
+        assert!(repl.interpreter.env.get("_3").is_none());
+    }
+
+    #[test]
+    fn time_reports_runs_on_both_backends() {
+        let mut repl = EnhancedREPL::new();
+        eval_in_repl(&mut repl, "fn sq(int x) -> int { return x * x; }");
+        let (value, report) = repl.time_expr("-n 3 sq(4)").unwrap();
+        assert_eq!(value.to_string(), "16");
+        assert!(report.starts_with("interpreter, 3 runs: min "), "{report}");
+        let (value, report) = repl.time_expr("--vm -n 1 sq(5)").unwrap();
+        assert_eq!(value.to_string(), "25");
+        assert!(report.starts_with("vm, 1 run: min "), "{report}");
+        let steps: u64 = report.rsplit(' ').next().unwrap().parse().unwrap();
+        assert!(steps > 0, "{report}");
+        assert!(repl.time_expr("-n 0 sq(1)").is_err());
+        assert!(repl.time_expr("let y = 1;").is_err());
+    }
+
+    #[test]
+    fn needs_continuation_tracks_open_brackets_and_strings() {
+        assert!(needs_continuation("fn add(int a, int b) {\n"));
+        assert!(needs_continuation("let xs = [1,\n"));
+        assert!(needs_continuation("println(\"a\n"));
+        assert!(needs_continuation("let s = \"\"\"first\n"));
+        assert!(needs_continuation("/* note\n"));
+        assert!(!needs_continuation(
+            "fn add(int a, int b) {\n    return a + b;\n}\n"
+        ));
+        assert!(!needs_continuation("println(\"{ ( [\");\n"));
+        assert!(!needs_continuation("let c = '{'; // {\n"));
+        assert!(!needs_continuation("let s = \"\\\"{\";\n"));
+        assert!(!needs_continuation("let p = r\"C:\\\";\n"));
+        assert!(
+            !needs_continuation("}\n{\n"),
+            "a stray closer goes to the parser"
+        );
+    }
+
+    #[test]
+    fn multi_line_function_definition_evaluates_once_closed() {
+        let mut repl = EnhancedREPL::new();
+        let mut pending = String::new();
+        for line in ["fn twice(int x) {", "    return x * 2;", "}"] {
+            pending.push_str(line);
+            pending.push('\n');
+        }
+        assert!(!needs_continuation(&pending));
+        repl.process_input(pending.trim());
+        assert!(
+            repl.interpreter
+                .binding_names()
+                .iter()
+                .any(|n| n == "twice")
+        );
+    }
+
     fn eval_in_repl(repl: &mut EnhancedREPL, src: &str) {
         // Drive process_input line-by-line so multi-statement sources
         // are handled correctly (each top-level statement on one call).
@@ -1005,4 +2117,20 @@ mod tests {
             *snap
         );
     }
+
+    #[test]
+    fn typed_candidates_see_the_session_and_the_current_line() {
+        let mut repl = EnhancedREPL::new();
+        eval_in_repl(&mut repl, "let ratio = 0.5;");
+        let session = repl.session_source.borrow().clone();
+        let found = typed_candidates(&session, "fn f(int count) { return cou");
+        let ty = |name: &str| {
+            found
+                .iter()
+                .find(|c| c.name == name)
+                .map(|c| c.ty.to_string())
+        };
+        assert_eq!(ty("ratio").as_deref(), Some("float"));
+        assert_eq!(ty("count").as_deref(), Some("int"));
+    }
 }
@@ -0,0 +1,162 @@
+//! REPL settings read when the REPL starts.
+//!
+//! Two files are consulted, later ones overriding earlier ones:
+//!
+//! 1. `~/.resilientrc`
+//! 2. the `[repl]` section of the nearest `resilient.toml` above the
+//!    working directory, so a project can pin its own defaults.
+//!
+//! Both use the same `key = value` lines (`#` starts a comment). In
+//! `~/.resilientrc` the keys may sit at the top or under `[repl]`; in
+//! `resilient.toml` only `[repl]` is read. Recognized keys:
+//!
+//! ```toml
+//! [repl]
+//! edit_mode = "vi"       # or "emacs" (default)
+//! color = false          # default true
+//! history_size = 500     # entries kept in ~/.resilient_history; default 1000
+//! typecheck = true       # start with type checking on; default false
+//! ```
+//!
+//! A setting that can't be read is reported on stderr with its file
+//! and line, and the REPL starts with the value it had before.
+
+use std::path::Path;
+
+use rustyline::EditMode;
+
+/// Settings applied when the REPL builds its line editor.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ReplConfig {
+    pub(crate) edit_mode: EditMode,
+    pub(crate) color: bool,
+    pub(crate) history_size: usize,
+    pub(crate) typecheck: bool,
+}
+
+impl Default for ReplConfig {
+    fn default() -> Self {
+        ReplConfig {
+            edit_mode: EditMode::Emacs,
+            color: true,
+            history_size: 1000,
+            typecheck: false,
+        }
+    }
+}
+
+impl ReplConfig {
+    /// Defaults, then `~/.resilientrc`, then the project's `[repl]`
+    /// section. Problems are printed as warnings.
+    pub(crate) fn load() -> Self {
+        let mut config = ReplConfig::default();
+        if let Ok(home) = std::env::var("HOME") {
+            config.apply_file(&Path::new(&home).join(".resilientrc"), true);
+        }
+        if let Some(manifest) = std::env::current_dir()
+            .ok()
+            .and_then(|dir| crate::pkg_init::find_manifest_upwards(&dir))
+        {
+            config.apply_file(&manifest, false);
+        }
+        config
+    }
+
+    fn apply_file(&mut self, path: &Path, top_level_keys: bool) {
+        let Ok(text) = std::fs::read_to_string(path) else {
+            return;
+        };
+        for warning in self.apply(&text, top_level_keys) {
+            eprintln!("warning: {}:{}", path.display(), warning);
+        }
+    }
+
+    /// Apply the settings in `text`; returns a `line: message` warning
+    /// for each one that was not applied. `top_level_keys` also reads
+    /// keys that come before any section header.
+    pub(crate) fn apply(&mut self, text: &str, top_level_keys: bool) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mut in_repl = top_level_keys;
+        for (idx, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                in_repl = header.trim_end_matches(']').trim() == "repl";
+                continue;
+            }
+            if !in_repl {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                warnings.push(format!("{}: expected `key = value`", idx + 1));
+                continue;
+            };
+            if let Err(e) = self.set(key.trim(), value.trim().trim_matches('"')) {
+                warnings.push(format!("{}: {}", idx + 1, e));
+            }
+        }
+        warnings
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let bool_value = || match value {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(format!("`{key}` expects true or false, got `{value}`")),
+        };
+        match key {
+            "edit_mode" => {
+                self.edit_mode = match value {
+                    "emacs" => EditMode::Emacs,
+                    "vi" => EditMode::Vi,
+                    _ => {
+                        return Err(format!(
+                            "`edit_mode` expects \"vi\" or \"emacs\", got `{value}`"
+                        ));
+                    }
+                }
+            }
+            "color" => self.color = bool_value()?,
+            "typecheck" => self.typecheck = bool_value()?,
+            "history_size" => {
+                self.history_size = value.parse().map_err(|_| {
+                    format!("`history_size` expects a number of entries, got `{value}`")
+                })?
+            }
+            _ => return Err(format!("unknown REPL setting `{key}`")),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_settings_override_and_bad_lines_warn() {
+        let mut config = ReplConfig::default();
+        let rc = "edit_mode = \"vi\"\nhistory_size = 50 # short\ncolour = false\n";
+        let warnings = config.apply(rc, true);
+        assert_eq!(warnings, vec!["3: unknown REPL setting `colour`"]);
+        let manifest =
+            "[package]\nname = \"demo\"\ncolor = true\n\n[repl]\ncolor = false\ntypecheck = yes\n";
+        let warnings = config.apply(manifest, false);
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert!(
+            warnings[0].starts_with("7: `typecheck` expects"),
+            "{warnings:?}"
+        );
+        assert_eq!(
+            config,
+            ReplConfig {
+                edit_mode: EditMode::Vi,
+                color: false,
+                history_size: 50,
+                typecheck: false,
+            }
+        );
+    }
+}
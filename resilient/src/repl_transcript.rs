@@ -0,0 +1,138 @@
+//! `:transcript on <file>` — a Markdown record of a REPL session.
+//!
+//! While a transcript is on, every input is appended to the file as a
+//! fenced code block, followed by a `text` block holding what it
+//! printed: program output, the result, and any diagnostics, in the
+//! order they appeared and without colors. Each input is written as
+//! soon as it finishes, so the file is complete up to the last input
+//! even if the session ends abruptly.
+//!
+//! The state is per thread, like the output sink it hooks into.
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+struct Transcript {
+    path: PathBuf,
+    file: File,
+    /// What the current input has printed so far.
+    output: String,
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<Transcript>> = const { RefCell::new(None) };
+}
+
+/// Start recording into `path`, replacing any earlier transcript
+/// there (and ending one already running).
+pub(crate) fn start(path: &Path) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(b"# Resilient REPL session\n")?;
+    ACTIVE.with(|t| {
+        *t.borrow_mut() = Some(Transcript {
+            path: path.to_path_buf(),
+            file,
+            output: String::new(),
+        })
+    });
+    Ok(())
+}
+
+/// Stop recording; returns the file that was being written.
+pub(crate) fn stop() -> Option<PathBuf> {
+    ACTIVE.with(|t| t.borrow_mut().take().map(|t| t.path))
+}
+
+/// The file being recorded into, if any.
+pub(crate) fn path() -> Option<PathBuf> {
+    ACTIVE.with(|t| t.borrow().as_ref().map(|t| t.path.clone()))
+}
+
+/// Note text the current input printed. A no-op with no transcript.
+pub(crate) fn record(text: &str) {
+    ACTIVE.with(|t| {
+        if let Some(t) = t.borrow_mut().as_mut() {
+            strip_colors_into(text, &mut t.output);
+        }
+    });
+}
+
+/// Append `input` and everything recorded since the last entry.
+/// A failed write ends the transcript, and the error is returned.
+pub(crate) fn write_entry(input: &str) -> std::io::Result<()> {
+    ACTIVE.with(|cell| {
+        let mut active = cell.borrow_mut();
+        let Some(t) = active.as_mut() else {
+            return Ok(());
+        };
+        let output = std::mem::take(&mut t.output);
+        let mut entry = format!("\n{}", fenced("rust", input));
+        if !output.trim().is_empty() {
+            entry.push_str(&fenced("text", output.trim_end()));
+        }
+        let written = t.file.write_all(entry.as_bytes());
+        if written.is_err() {
+            *active = None;
+        }
+        written
+    })
+}
+
+/// `body` in a code fence long enough not to be closed by any run of
+/// backticks inside it.
+fn fenced(lang: &str, body: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in body.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{fence}{lang}\n{body}\n{fence}\n")
+}
+
+/// Append `text` to `out` without ANSI color sequences.
+fn strip_colors_into(text: &str, out: &mut String) {
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1B' {
+            // `ESC [ ... <letter>`
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_hold_the_input_and_its_uncolored_output() {
+        let path = std::env::temp_dir().join(format!("res_transcript_{}.md", std::process::id()));
+        start(&path).unwrap();
+        record("\x1B[36m42\x1B[0m\n");
+        write_entry("6 * 7").unwrap();
+        write_entry("let quiet = 1;").unwrap();
+        record("```\n");
+        write_entry("println(\"```\")").unwrap();
+        assert_eq!(stop(), Some(path.clone()));
+        record("after stop\n");
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            text,
+            "# Resilient REPL session\n\
+             \n```rust\n6 * 7\n```\n```text\n42\n```\n\
+             \n```rust\nlet quiet = 1;\n```\n\
+             \n````rust\nprintln(\"```\")\n````\n````text\n```\n````\n"
+        );
+    }
+}
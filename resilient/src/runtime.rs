@@ -0,0 +1,31 @@
+//! The knobs an embedder sets around [`crate::interpreter`] runs.
+//!
+//! Each is a module of its own, re-exported here so the whole runtime
+//! surface sits under one path. All but `record_replay` are per
+//! thread, so a host running programs on several threads sets them on
+//! each.
+//!
+//! | Module | CLI flag | What it controls |
+//! |---|---|---|
+//! | [`fuel`] | `--max-steps` | evaluation step budget |
+//! | [`memory_limit`] | `--max-memory` | cap on data kept in bindings |
+//! | [`stack_guard`] | — | host stack the interpreter may use |
+//! | [`float_mode`] | `--float-strict`, `--float-precision` | float special values and printing |
+//! | [`logging`] | `--log-level`, `--log-format` | `log_*` builtin filtering and format |
+//! | [`record_replay`] | `--record`, `--replay` | nondeterministic inputs |
+//! | [`output_sink`] | — | where `print` / `println` output goes |
+//! | [`host_profile`] | — | native, WASI or web host for files, clocks, entropy, env (fixed by target) |
+//!
+//! ```
+//! use resilient::{interpreter, runtime};
+//!
+//! runtime::fuel::set_max_steps(Some(10_000));
+//! let result = interpreter::run_program("while true { }");
+//! runtime::fuel::set_max_steps(None);
+//! assert!(!result.ok);
+//! assert!(result.errors[0].contains("ResourceExhausted"));
+//! ```
+
+pub use crate::{
+    float_mode, fuel, host_profile, logging, memory_limit, output_sink, record_replay, stack_guard,
+};
@@ -0,0 +1,174 @@
+//! `--allow <capabilities>`: the kinds of I/O a program may do at all.
+//!
+//! With no policy a program has the CLI's ambient authority, narrowed
+//! only by `--fs`, `--net`, `--ffi` and `--mmio`. Once one is set —
+//! `--allow fs-read,net` on `rz`, or `allow = ["fs-read", "net"]` in
+//! the `[sandbox]` section of `resilient.toml` — every capability it
+//! doesn't name is denied:
+//!
+//! - `fs-read` — reading files, checking and listing paths;
+//! - `fs-write` — creating, writing and appending to files (`fs`
+//!   names both);
+//! - `net` — HTTP (`std::http` included), MQTT, TCP, UDP and
+//!   `std::net`'s `net_resolve`;
+//! - `exec` — `exec`, `exec_shell` and `std::process`'s
+//!   `process_exec` / `process_which`;
+//! - `serial` — the serial-port builtins;
+//! - `mmio` — `mmio_read` / `mmio_write` and the `volatile_*`
+//!   intrinsics, still within `--mmio`;
+//! - `ffi` — binding `extern` declarations.
+//!
+//! `--allow none` denies all of them, which is what an untrusted
+//! script in the REPL or an embedding host wants. A denied builtin
+//! fails with the same typed `PermissionDenied` as the other sandbox
+//! flags (`http_get: PermissionDenied: <url>: ...`), or returns it as
+//! the `Err` of the builtins that report failures in a `Result`. The
+//! policy only ever narrows the others: `--allow fs --fs read-only`
+//! still can't write.
+//!
+//! One process-wide setting chosen by the driver, like `fs_sandbox`;
+//! embedders call [`set_allowed`] before [`crate::run_program`].
+
+use crate::RResult;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// One kind of I/O a policy can grant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    FsRead,
+    FsWrite,
+    Net,
+    Exec,
+    Serial,
+    Mmio,
+    Ffi,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 7] = [
+        Capability::FsRead,
+        Capability::FsWrite,
+        Capability::Net,
+        Capability::Exec,
+        Capability::Serial,
+        Capability::Mmio,
+        Capability::Ffi,
+    ];
+
+    /// The name `--allow` spells it with.
+    pub fn name(self) -> &'static str {
+        match self {
+            Capability::FsRead => "fs-read",
+            Capability::FsWrite => "fs-write",
+            Capability::Net => "net",
+            Capability::Exec => "exec",
+            Capability::Serial => "serial",
+            Capability::Mmio => "mmio",
+            Capability::Ffi => "ffi",
+        }
+    }
+
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// The capabilities a policy grants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+    /// Grants nothing, like `--allow none`.
+    pub const NONE: Capabilities = Capabilities(0);
+
+    /// This set plus `capability`.
+    pub fn with(self, capability: Capability) -> Self {
+        Capabilities(self.0 | capability.bit())
+    }
+
+    pub fn contains(self, capability: Capability) -> bool {
+        self.0 & capability.bit() != 0
+    }
+
+    /// Parse an `--allow` list: capability names separated by commas,
+    /// `fs` for both file capabilities, or `none`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut set = Capabilities::NONE;
+        for name in s.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let name = name.to_ascii_lowercase();
+            set = match name.as_str() {
+                "none" => set,
+                "fs" => set.with(Capability::FsRead).with(Capability::FsWrite),
+                _ => match Capability::ALL.iter().find(|c| c.name() == name) {
+                    Some(&capability) => set.with(capability),
+                    None => {
+                        return Err(format!(
+                            "unknown capability `{name}` (expected fs-read, fs-write, fs, net, exec, serial, mmio, ffi, or none)"
+                        ));
+                    }
+                },
+            };
+        }
+        Ok(set)
+    }
+}
+
+/// Every bit set: no policy, ambient authority.
+const UNRESTRICTED: u32 = u32::MAX;
+
+static ALLOWED: AtomicU32 = AtomicU32::new(UNRESTRICTED);
+
+/// Install the process-wide policy; `None` lifts it.
+pub fn set_allowed(allowed: Option<Capabilities>) {
+    ALLOWED.store(allowed.map_or(UNRESTRICTED, |set| set.0), Ordering::Relaxed);
+}
+
+/// The process-wide policy, or `None` when none is set.
+pub fn allowed() -> Option<Capabilities> {
+    match ALLOWED.load(Ordering::Relaxed) {
+        UNRESTRICTED => None,
+        bits => Some(Capabilities(bits)),
+    }
+}
+
+/// Whether the policy grants `capability`.
+pub(crate) fn allows(capability: Capability) -> bool {
+    allowed().is_none_or(|set| set.contains(capability))
+}
+
+/// Fail with `PermissionDenied` unless the policy grants `capability`
+/// to `builtin`, acting on `subject` (a path, URL or command).
+pub(crate) fn require(builtin: &str, capability: Capability, subject: &str) -> RResult<()> {
+    if allows(capability) {
+        return Ok(());
+    }
+    Err(format!(
+        "{}: PermissionDenied: {}: the sandbox does not allow `{}`",
+        builtin,
+        subject,
+        capability.name()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lists_shorthands_and_none() {
+        let set = Capabilities::parse("fs-read, NET").unwrap();
+        assert!(set.contains(Capability::FsRead) && set.contains(Capability::Net));
+        assert!(!set.contains(Capability::FsWrite) && !set.contains(Capability::Exec));
+
+        let fs = Capabilities::parse("fs").unwrap();
+        assert!(fs.contains(Capability::FsRead) && fs.contains(Capability::FsWrite));
+
+        assert_eq!(Capabilities::parse("none"), Ok(Capabilities::NONE));
+        assert_eq!(Capabilities::parse(""), Ok(Capabilities::NONE));
+        assert!(
+            Capabilities::parse("net,disk")
+                .unwrap_err()
+                .contains("unknown capability `disk`")
+        );
+    }
+}
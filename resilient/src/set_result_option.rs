@@ -38,7 +38,7 @@ pub(crate) fn builtin_set_from_array(args: &[Value]) -> RResult<Value> {
                 let k = MapKey::from_value(v)?;
                 s.insert(k);
             }
-            Ok(Value::Set(s))
+            Ok(Value::Set(s.into()))
         }
         [a] => Err(format!("set_from_array: expected an Array, got {}", a)),
         _ => Err(format!(
@@ -101,7 +101,7 @@ mod tests {
         for k in keys {
             s.insert(k.clone());
         }
-        Value::Set(s)
+        Value::Set(s.into())
     }
 
     fn ok(v: Value) -> Value {
@@ -156,7 +156,7 @@ mod tests {
 
     #[test]
     fn set_from_array_basic() {
-        let arr = Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+        let arr = Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)].into());
         let s = builtin_set_from_array(&[arr]).unwrap();
         match s {
             Value::Set(set) => {
@@ -171,12 +171,8 @@ mod tests {
 
     #[test]
     fn set_from_array_deduplicates() {
-        let arr = Value::Array(vec![
-            Value::Int(1),
-            Value::Int(2),
-            Value::Int(1),
-            Value::Int(2),
-        ]);
+        let arr =
+            Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(1), Value::Int(2)].into());
         let s = builtin_set_from_array(&[arr]).unwrap();
         match s {
             Value::Set(set) => assert_eq!(set.len(), 2),
@@ -186,7 +182,7 @@ mod tests {
 
     #[test]
     fn set_from_array_empty() {
-        let s = builtin_set_from_array(&[Value::Array(vec![])]).unwrap();
+        let s = builtin_set_from_array(&[Value::Array(vec![].into())]).unwrap();
         match s {
             Value::Set(set) => assert_eq!(set.len(), 0),
             _ => panic!("expected Set"),
@@ -195,11 +191,14 @@ mod tests {
 
     #[test]
     fn set_from_array_mixed_hashable_types() {
-        let arr = Value::Array(vec![
-            Value::Int(1),
-            Value::String("hello".to_string()),
-            Value::Bool(true),
-        ]);
+        let arr = Value::Array(
+            vec![
+                Value::Int(1),
+                Value::String("hello".to_string()),
+                Value::Bool(true),
+            ]
+            .into(),
+        );
         let s = builtin_set_from_array(&[arr]).unwrap();
         match s {
             Value::Set(set) => {
@@ -214,7 +213,7 @@ mod tests {
 
     #[test]
     fn set_from_array_rejects_non_hashable_element() {
-        let arr = Value::Array(vec![Value::Int(1), Value::Float(2.0)]);
+        let arr = Value::Array(vec![Value::Int(1), Value::Float(2.0)].into());
         let err = builtin_set_from_array(&[arr]).unwrap_err();
         assert!(err.contains("Map key must be Int, String, or Bool"));
     }
@@ -0,0 +1,238 @@
+//! Copy-on-write storage for collection values.
+//!
+//! Arrays, maps and sets have value semantics: `let b = a;` gives `b`
+//! its own collection, and writing through one name never shows up
+//! through the other. Copying the elements on every assignment, call
+//! and `live` snapshot made that expensive, so the interpreter keeps
+//! each collection behind a [`Shared`] — an `Rc` that is cloned by
+//! bumping a count and copied only when someone writes to it while
+//! another value still holds it (`Rc::make_mut`, the same scheme
+//! struct fields use).
+//!
+//! Reads go through `Deref`, writes through `DerefMut`, so code that
+//! handles a `Vec<Value>` or a map works on a `Shared` unchanged. Two
+//! values can only observe each other's writes through an explicit
+//! `cell(v)` handle; `clone(v)` is the way to copy one of those.
+//!
+//! Each collection also remembers its `--max-memory` footprint once
+//! `memory_limit` has worked it out, so binding the same collection
+//! again costs nothing. A write forgets it, unless the writer says by
+//! how much it changed (see [`Shared::set_footprint`]).
+
+use std::cell::Cell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+/// A reference-counted collection that copies itself on first write
+/// while shared.
+pub(crate) struct Shared<T>(Rc<Inner<T>>);
+
+#[derive(Clone)]
+struct Inner<T> {
+    value: T,
+    /// Heap bytes the collection holds, as `memory_limit` counts them;
+    /// `None` until asked for and after a write.
+    footprint: Cell<Option<usize>>,
+}
+
+impl<T> Shared<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Shared(Rc::new(Inner {
+            value,
+            footprint: Cell::new(None),
+        }))
+    }
+
+    /// The collection's footprint, worked out with `measure` only if it
+    /// changed since it was last asked for.
+    pub(crate) fn footprint(&self, measure: impl FnOnce(&T) -> usize) -> usize {
+        match self.0.footprint.get() {
+            Some(bytes) => bytes,
+            None => {
+                let bytes = measure(&self.0.value);
+                self.0.footprint.set(Some(bytes));
+                bytes
+            }
+        }
+    }
+
+    /// The footprint, if it is known.
+    pub(crate) fn known_footprint(&self) -> Option<usize> {
+        self.0.footprint.get()
+    }
+
+    /// Record the footprint after a write whose cost the writer worked
+    /// out, instead of measuring the whole collection again.
+    pub(crate) fn set_footprint(&self, bytes: Option<usize>) {
+        self.0.footprint.set(bytes);
+    }
+}
+
+impl<T: Clone> Shared<T> {
+    /// The collection itself, copied only when another value holds it.
+    pub(crate) fn into_inner(self) -> T {
+        Rc::unwrap_or_clone(self.0).value
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared(Rc::clone(&self.0))
+    }
+}
+
+impl<T> Deref for Shared<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0.value
+    }
+}
+
+impl<T: Clone> DerefMut for Shared<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        let inner = Rc::make_mut(&mut self.0);
+        inner.footprint.set(None);
+        &mut inner.value
+    }
+}
+
+impl<T> From<T> for Shared<T> {
+    fn from(value: T) -> Self {
+        Shared::new(value)
+    }
+}
+
+impl<T: Default> Default for Shared<T> {
+    fn default() -> Self {
+        Shared::new(T::default())
+    }
+}
+
+impl<T: PartialEq> PartialEq for Shared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0) || self.0.value == other.0.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.value.fmt(f)
+    }
+}
+
+impl<A, T: FromIterator<A>> FromIterator<A> for Shared<T> {
+    fn from_iter<I: IntoIterator<Item = A>>(iter: I) -> Self {
+        Shared::new(iter.into_iter().collect())
+    }
+}
+
+impl<T: Clone + IntoIterator> IntoIterator for Shared<T> {
+    type Item = T::Item;
+    type IntoIter = T::IntoIter;
+
+    fn into_iter(self) -> T::IntoIter {
+        self.into_inner().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Shared<T>
+where
+    &'a T: IntoIterator,
+{
+    type Item = <&'a T as IntoIterator>::Item;
+    type IntoIter = <&'a T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        (**self).into_iter()
+    }
+}
+
+impl<'a, T: Clone> IntoIterator for &'a mut Shared<T>
+where
+    &'a mut T: IntoIterator,
+{
+    type Item = <&'a mut T as IntoIterator>::Item;
+    type IntoIter = <&'a mut T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        (**self).into_iter()
+    }
+}
+
+/// Serialized as the collection itself; sharing is not preserved.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Shared<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.value.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Shared<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Shared::new)
+    }
+}
+
+/// `serde(with)` for a shared map: a sequence of `(key, value)`
+/// pairs, since formats such as JSON only take string keys.
+#[cfg(feature = "serde")]
+pub(crate) mod map_entries {
+    use super::Shared;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub(crate) fn serialize<K: Serialize, V: Serialize, S: Serializer>(
+        map: &Shared<HashMap<K, V>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub(crate) fn deserialize<'de, K, V, D>(
+        deserializer: D,
+    ) -> Result<Shared<HashMap<K, V>>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let entries = Vec::<(K, V)>::deserialize(deserializer)?;
+        Ok(Shared::new(entries.into_iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_copy_only_while_shared() {
+        let mut a: Shared<Vec<i64>> = vec![1, 2].into();
+        let b = a.clone();
+        assert!(Rc::ptr_eq(&a.0, &b.0));
+        a.push(3);
+        assert_eq!((*a).clone(), vec![1, 2, 3]);
+        assert_eq!((*b).clone(), vec![1, 2]);
+        let before = Rc::as_ptr(&a.0);
+        a.push(4);
+        assert_eq!(Rc::as_ptr(&a.0), before, "sole owner writes in place");
+        assert_eq!(b.into_inner(), vec![1, 2]);
+    }
+
+    #[test]
+    fn a_write_forgets_the_footprint_unless_it_sets_it() {
+        let mut a: Shared<Vec<i64>> = vec![1, 2].into();
+        assert_eq!(a.footprint(|v| v.len()), 2);
+        assert_eq!(a.footprint(|_| unreachable!()), 2);
+        let b = a.clone();
+        a.push(3);
+        assert_eq!(a.known_footprint(), None);
+        assert_eq!(b.known_footprint(), Some(2), "the copy left behind");
+        a.set_footprint(Some(3));
+        assert_eq!(a.footprint(|_| unreachable!()), 3);
+    }
+}
@@ -109,7 +109,7 @@ pub(crate) fn builtin_snapshot_keys(args: &[Value]) -> RResult<Value> {
             .map(|k| Value::String(k.clone()))
             .collect()
     });
-    Ok(Value::Array(keys))
+    Ok(Value::Array(keys.into()))
 }
 
 pub(crate) fn builtin_snapshot_clear(args: &[Value]) -> RResult<Value> {
@@ -96,7 +96,7 @@ fn direct_callees(node: &Node, out: &mut HashSet<String>) {
             ..
         } => {
             if let Node::Identifier { name, .. } = function.as_ref() {
-                out.insert(name.clone());
+                out.insert(name.to_string());
             }
             for a in arguments {
                 direct_callees(a, out);
@@ -0,0 +1,97 @@
+//! A cap on how much host stack the tree-walking interpreter may use.
+//!
+//! `Interpreter::eval` recurses on the Rust stack, one or more frames
+//! per nested expression, block, and call. The call-depth limit
+//! (`RZ_MAX_CALL_DEPTH`) bounds user recursion, but a deep enough
+//! expression inside a deep enough call chain can still run the host
+//! thread out of stack, and a Rust stack overflow aborts the whole
+//! process — no `catch`, no `live` block, no error message.
+//!
+//! The guard measures instead of counting: [`set_stack_budget`]
+//! records the address of a local as the base of the run, and every
+//! evaluation step compares the address of one of its own locals
+//! against it. Once the distance passes the budget the step fails
+//! with a typed `RecursionLimit` error, which unwinds normally and
+//! which `try ... catch RecursionLimit` handles like any other typed
+//! failure. The call-depth limit reports the same variant.
+//!
+//! The budget is per thread, like `fuel` and `memory_limit`. `rz` sets
+//! it on the CLI thread from that thread's stack size; embedders that
+//! run programs on their own threads call [`set_stack_budget`] there.
+//! Nothing is checked while no budget is set.
+
+use std::cell::Cell;
+
+thread_local! {
+    static BUDGET: Cell<Option<usize>> = const { Cell::new(None) };
+    static BASE: Cell<usize> = const { Cell::new(0) };
+}
+
+const RECURSION_LIMIT: &str = "RecursionLimit:";
+
+/// Let evaluation on this thread use up to `bytes` of stack below the
+/// caller's frame (`None` removes the guard). Call it near the top of
+/// the thread, with `bytes` comfortably under the thread's stack size
+/// so the guard fires before the host does.
+#[inline(never)]
+pub fn set_stack_budget(bytes: Option<usize>) {
+    let marker = 0u8;
+    BASE.with(|b| b.set(std::ptr::addr_of!(marker) as usize));
+    BUDGET.with(|b| b.set(bytes));
+}
+
+/// Fail with `RecursionLimit` once the stack in use passes the budget.
+#[inline]
+pub(crate) fn check() -> Result<(), String> {
+    let Some(budget) = BUDGET.with(Cell::get) else {
+        return Ok(());
+    };
+    let marker = 0u8;
+    let used = (std::ptr::addr_of!(marker) as usize).abs_diff(BASE.with(Cell::get));
+    if used > budget {
+        return Err(format!(
+            "{RECURSION_LIMIT} evaluation nested too deep (~{used} bytes of stack, limit {budget})"
+        ));
+    }
+    Ok(())
+}
+
+/// The `RecursionLimit` error for a call chain deeper than `limit`.
+pub(crate) fn call_depth_exceeded(name: &str, limit: usize) -> String {
+    format!(
+        "{RECURSION_LIMIT} maximum interpreter call depth exceeded at fn {name} (limit {limit})"
+    )
+}
+
+/// `"RecursionLimit"` when `err` is a recursion limit, for `catch`.
+/// Looks past the location prefix the interpreter may add.
+pub(crate) fn failure_variant(err: &str) -> Option<&'static str> {
+    err.contains(RECURSION_LIMIT).then_some("RecursionLimit")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[inline(never)]
+    fn descend(n: usize) -> Result<usize, String> {
+        check()?;
+        let pad = [n as u8; 256];
+        if n == 0 {
+            return Ok(pad[0] as usize);
+        }
+        descend(n - 1).map(|v| v + std::hint::black_box(pad)[1] as usize)
+    }
+
+    #[test]
+    fn fails_once_the_stack_passes_the_budget() {
+        set_stack_budget(None);
+        assert!(descend(200).is_ok());
+        set_stack_budget(Some(16 * 1024));
+        assert!(descend(4).is_ok());
+        let err = descend(200).unwrap_err();
+        assert_eq!(failure_variant(&err), Some("RecursionLimit"), "{err}");
+        set_stack_budget(None);
+        assert!(descend(200).is_ok());
+    }
+}
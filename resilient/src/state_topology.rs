@@ -115,7 +115,7 @@ pub fn collect_specs() -> Vec<StateMachineSpec> {
 fn collect_identifiers(node: &Node, out: &mut HashSet<String>) {
     match node {
         Node::Identifier { name, .. } => {
-            out.insert(name.clone());
+            out.insert(name.to_string());
         }
         Node::InfixExpression { left, right, .. } => {
             collect_identifiers(left, out);
@@ -183,7 +183,7 @@ fn fn_bodies(program: &Node) -> HashMap<String, Node> {
     if let Node::Program(stmts) = program {
         for s in stmts {
             if let Node::Function { name, body, .. } = &s.node {
-                map.insert(name.clone(), *body.clone());
+                map.insert(name.clone(), body.as_ref().clone());
             }
         }
     }
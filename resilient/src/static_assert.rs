@@ -20,7 +20,7 @@
 //! Both arguments are required. The condition must be a
 //! const-evaluable boolean expression (same rules as `const`
 //! declarations: literals, arithmetic, comparisons, and references
-//! to previously declared constants). The message must be a string
+//! to other constants). The message must be a string
 //! literal.
 //!
 //! ## Feature isolation
@@ -111,37 +111,19 @@ pub(crate) fn parse(parser: &mut Parser) -> Node {
 /// Evaluate all `static_assert` statements in the program.
 ///
 /// Called from the `<EXTENSION_PASSES>` block in `typechecker.rs`.
-/// Resolves all `const` declarations first (same logic as
-/// `Interpreter::const_eval_program`), then evaluates each
-/// `static_assert` condition. A `false` result is a hard compile
-/// error; `true` is silently accepted.
+/// Evaluates each condition through `const_eval::ConstEvaluator`, the
+/// evaluator `Interpreter::const_eval_program` also uses. A `false`
+/// result is a hard compile error; `true` is silently accepted.
 pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
     let statements = match program {
         Node::Program(stmts) => stmts,
         _ => return Ok(()),
     };
 
-    // First pass: resolve all top-level `const` declarations so
-    // static_assert conditions can reference them. This mirrors
-    // `Interpreter::const_eval_program` but is self-contained —
-    // the typechecker pass doesn't have access to the interpreter's
-    // const table.
-    let mut consts: HashMap<String, Value> = HashMap::new();
-    for stmt in statements {
-        if let Node::Const { name, value, .. } = &stmt.node {
-            let mut evaluating: Vec<String> = vec![name.clone()];
-            match crate::Interpreter::eval_const_expr(value, &consts, &mut evaluating) {
-                Ok(v) => {
-                    consts.insert(name.clone(), v);
-                }
-                Err(_) => {
-                    // Const eval failure is reported elsewhere; skip.
-                }
-            }
-        }
-    }
+    // Conditions may reference any top-level `const`; the evaluator
+    // resolves them on demand, exactly as the interpreter does.
+    let mut consts = crate::const_eval::ConstEvaluator::new(statements);
 
-    // Second pass: evaluate each static_assert.
     let mut errors: Vec<String> = Vec::new();
 
     for stmt in statements {
@@ -154,8 +136,7 @@ pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
             continue;
         };
 
-        let mut evaluating: Vec<String> = Vec::new();
-        match eval_const_bool(condition, &consts, &mut evaluating) {
+        match consts.eval_bool(condition) {
             Ok(true) => {
                 // Assertion passed — nothing to do.
             }
@@ -186,12 +167,20 @@ pub(crate) fn check(program: &Node, source_path: &str) -> Result<(), String> {
 ///
 /// Called from `Interpreter::eval_program` after `const_eval_program`
 /// has populated the const table. This is the interpreter-path entry
-/// point; the typechecker path uses `check()` which resolves consts
-/// itself.
+/// point; the typechecker path uses `check()`. Enums are read from
+/// `statements` because none are registered before execution starts.
 pub(crate) fn check_with_consts(
     statements: &[crate::span::Spanned<Node>],
-    consts: &std::rc::Rc<HashMap<String, Value>>,
+    consts: &HashMap<crate::symbol::Symbol, Value>,
 ) -> Result<(), String> {
+    let enums: HashMap<String, Vec<crate::EnumVariant>> = statements
+        .iter()
+        .filter_map(|s| match &s.node {
+            Node::EnumDecl { name, variants, .. } => Some((name.clone(), variants.clone())),
+            _ => None,
+        })
+        .collect();
+    let mut consts = crate::const_eval::ConstEvaluator::with_resolved(consts, &enums);
     let mut errors: Vec<String> = Vec::new();
 
     for stmt in statements {
@@ -204,8 +193,7 @@ pub(crate) fn check_with_consts(
             continue;
         };
 
-        let mut evaluating: Vec<String> = Vec::new();
-        match eval_const_bool(condition, consts, &mut evaluating) {
+        match consts.eval_bool(condition) {
             Ok(true) => {}
             Ok(false) => {
                 errors.push(format!(
@@ -230,22 +218,6 @@ pub(crate) fn check_with_consts(
     }
 }
 
-/// Evaluate a const expression expected to produce a boolean.
-///
-/// Delegates to `Interpreter::eval_const_expr` for the heavy lifting,
-/// then checks that the result is actually `Value::Bool`.
-fn eval_const_bool(
-    node: &Node,
-    consts: &HashMap<String, Value>,
-    evaluating: &mut Vec<String>,
-) -> Result<bool, String> {
-    let value = crate::Interpreter::eval_const_expr(node, consts, evaluating)?;
-    match value {
-        Value::Bool(b) => Ok(b),
-        other => Err(format!("expected boolean, got {}", other)),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use crate::parse;
@@ -874,7 +874,7 @@ pub(crate) fn builtin_mat_lu(args: &[Value]) -> RResult<Value> {
             let l_val = float_matrix(l);
             let u_val = float_matrix(u);
             let p_val = Value::Array(perm.into_iter().map(|i| Value::Int(i as i64)).collect());
-            Ok(Value::Array(vec![l_val, u_val, p_val]))
+            Ok(Value::Array(vec![l_val, u_val, p_val].into()))
         }
         _ => Err(format!(
             "mat_lu: expected 1 argument (m), got {}",
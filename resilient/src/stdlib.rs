@@ -186,6 +186,7 @@ fn http_get(args: &[Value]) -> RResult<Value> {
         Value::String(s) => s.clone(),
         other => return Err(format!("http::get: expected string URL, got {:?}", other)),
     };
+    crate::http_client::check_net("http_get", &url)?;
 
     // Synchronous HTTP GET using std::net::TcpStream
     match simple_http_get(&url) {
@@ -206,6 +207,7 @@ fn http_post(args: &[Value]) -> RResult<Value> {
         Value::String(s) => s.clone(),
         other => return Err(format!("http::post: expected string body, got {:?}", other)),
     };
+    crate::http_client::check_net("http_post", &url)?;
 
     match simple_http_post(&url, &body) {
         Ok(response) => Ok(Value::String(response)),
@@ -226,6 +228,7 @@ fn http_status(args: &[Value]) -> RResult<Value> {
             ));
         }
     };
+    crate::http_client::check_net("http_status", &url)?;
     match simple_http_head(&url) {
         Ok(status) => Ok(Value::Int(status as i64)),
         Err(e) => Err(format!("http::status failed: {}", e)),
@@ -245,13 +248,14 @@ fn http_headers(args: &[Value]) -> RResult<Value> {
             ));
         }
     };
+    crate::http_client::check_net("http_headers", &url)?;
     match simple_http_head_headers(&url) {
         Ok(headers) => {
             let mut map = HashMap::new();
             for (k, v) in headers {
                 map.insert(MapKey::Str(k), Value::String(v));
             }
-            Ok(Value::Map(map))
+            Ok(Value::Map(map.into()))
         }
         Err(e) => Err(format!("http::headers failed: {}", e)),
     }
@@ -511,11 +515,7 @@ fn math_max(args: &[Value]) -> RResult<Value> {
 
 fn math_random(_args: &[Value]) -> RResult<Value> {
     // Simple LCG for determinism in tests; sufficient for non-crypto use.
-    use std::time::SystemTime;
-    let seed = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map(|d| d.subsec_nanos() as u64)
-        .unwrap_or(42);
+    let seed = crate::host_clock::wall_clock_since_epoch().subsec_nanos() as u64;
     let val = (seed
         .wrapping_mul(6364136223846793005)
         .wrapping_add(1442695040888963407)) as f64
@@ -660,6 +660,7 @@ fn fs_read(args: &[Value]) -> RResult<Value> {
         Value::String(s) => s.clone(),
         other => return Err(format!("fs::read: expected string path, got {:?}", other)),
     };
+    crate::fs_sandbox::check("fs_read", &path, false)?;
     match std::fs::read_to_string(&path) {
         Ok(contents) => Ok(Value::String(contents)),
         Err(e) => Err(format!("fs::read '{}': {}", path, e)),
@@ -683,6 +684,7 @@ fn fs_write(args: &[Value]) -> RResult<Value> {
             ));
         }
     };
+    crate::fs_sandbox::check("fs_write", &path, true)?;
     match std::fs::write(&path, &contents) {
         Ok(()) => Ok(Value::Void),
         Err(e) => Err(format!("fs::write '{}': {}", path, e)),
@@ -697,6 +699,7 @@ fn fs_exists(args: &[Value]) -> RResult<Value> {
         Value::String(s) => s.clone(),
         other => return Err(format!("fs::exists: expected string path, got {:?}", other)),
     };
+    crate::fs_sandbox::check("fs_exists", &path, false)?;
     Ok(Value::Bool(std::path::Path::new(&path).exists()))
 }
 
@@ -708,6 +711,7 @@ fn fs_remove(args: &[Value]) -> RResult<Value> {
         Value::String(s) => s.clone(),
         other => return Err(format!("fs::remove: expected string path, got {:?}", other)),
     };
+    crate::fs_sandbox::check("fs_remove", &path, true)?;
     match std::fs::remove_file(&path) {
         Ok(()) => Ok(Value::Void),
         Err(e) => Err(format!("fs::remove '{}': {}", path, e)),
@@ -722,6 +726,7 @@ fn fs_list(args: &[Value]) -> RResult<Value> {
         Value::String(s) => s.clone(),
         other => return Err(format!("fs::list: expected string path, got {:?}", other)),
     };
+    crate::fs_sandbox::check("fs_list", &path, false)?;
     match std::fs::read_dir(&path) {
         Ok(entries) => {
             let mut files = Vec::new();
@@ -730,7 +735,7 @@ fn fs_list(args: &[Value]) -> RResult<Value> {
                     files.push(Value::String(name.to_string()));
                 }
             }
-            Ok(Value::Array(files))
+            Ok(Value::Array(files.into()))
         }
         Err(e) => Err(format!("fs::list '{}': {}", path, e)),
     }
@@ -753,6 +758,7 @@ fn fs_append(args: &[Value]) -> RResult<Value> {
             ));
         }
     };
+    crate::fs_sandbox::check("fs_append", &path, true)?;
     use std::io::Write;
     let mut file = std::fs::OpenOptions::new()
         .create(true)
@@ -825,7 +831,7 @@ fn os_env(args: &[Value]) -> RResult<Value> {
 
 fn os_args(_args: &[Value]) -> RResult<Value> {
     let args: Vec<Value> = std::env::args().map(Value::String).collect();
-    Ok(Value::Array(args))
+    Ok(Value::Array(args.into()))
 }
 
 fn os_exit(args: &[Value]) -> RResult<Value> {
@@ -833,11 +839,12 @@ fn os_exit(args: &[Value]) -> RResult<Value> {
         0
     } else {
         match &args[0] {
-            Value::Int(n) => *n as i32,
+            Value::Int(n) => crate::checked_exit_code("os::exit", *n)?,
             _ => 1,
         }
     };
-    std::process::exit(code);
+    // Unwind like the `exit` builtin so `defer`s still run.
+    Err(crate::exit_signal(code))
 }
 
 fn os_platform(_args: &[Value]) -> RResult<Value> {
@@ -917,6 +924,33 @@ fn crypto_sha256(args: &[Value]) -> RResult<Value> {
     Ok(Value::String(sha256_hex(&input)))
 }
 
+/// Fill `bytes` from /dev/urandom on unix, WASI's `random_get` on
+/// `wasm32-wasi`, or a time-seeded fallback elsewhere. A
+/// `record_replay` input, so `--replay` gets the same bytes.
+fn fill_random(bytes: &mut [u8]) {
+    crate::record_replay::entropy(bytes, |bytes| {
+        #[cfg(unix)]
+        {
+            use std::io::Read;
+            if let Ok(mut f) = std::fs::File::open("/dev/urandom") {
+                let _ = f.read_exact(bytes);
+            }
+        }
+        #[cfg(target_os = "wasi")]
+        {
+            let _ = getrandom::getrandom(bytes);
+        }
+        #[cfg(not(any(unix, target_os = "wasi")))]
+        {
+            let mut seed = random_seed();
+            for b in bytes.iter_mut() {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                *b = (seed >> 33) as u8;
+            }
+        }
+    });
+}
+
 fn crypto_random_bytes(args: &[Value]) -> RResult<Value> {
     if args.is_empty() {
         return Err("crypto::random_bytes requires a length argument".to_string());
@@ -934,51 +968,14 @@ fn crypto_random_bytes(args: &[Value]) -> RResult<Value> {
         return Err("crypto::random_bytes: max 1MB".to_string());
     }
     let mut bytes = vec![0u8; n];
-    // Use /dev/urandom on unix, or time-based fallback
-    #[cfg(unix)]
-    {
-        use std::io::Read;
-        if let Ok(mut f) = std::fs::File::open("/dev/urandom") {
-            let _ = f.read_exact(&mut bytes);
-        }
-    }
-    #[cfg(not(unix))]
-    {
-        use std::time::SystemTime;
-        let mut seed = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|d| d.as_nanos() as u64)
-            .unwrap_or(42);
-        for b in bytes.iter_mut() {
-            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
-            *b = (seed >> 33) as u8;
-        }
-    }
+    fill_random(&mut bytes);
     Ok(Value::Bytes(bytes))
 }
 
 fn crypto_uuid(_args: &[Value]) -> RResult<Value> {
     // Generate a v4-style UUID using random bytes
     let mut bytes = [0u8; 16];
-    #[cfg(unix)]
-    {
-        use std::io::Read;
-        if let Ok(mut f) = std::fs::File::open("/dev/urandom") {
-            let _ = f.read_exact(&mut bytes);
-        }
-    }
-    #[cfg(not(unix))]
-    {
-        use std::time::SystemTime;
-        let mut seed = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|d| d.as_nanos() as u64)
-            .unwrap_or(42);
-        for b in bytes.iter_mut() {
-            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
-            *b = (seed >> 33) as u8;
-        }
-    }
+    fill_random(&mut bytes);
     bytes[6] = (bytes[6] & 0x0f) | 0x40; // version 4
     bytes[8] = (bytes[8] & 0x3f) | 0x80; // variant 1
     let uuid = format!(
@@ -1240,20 +1237,12 @@ static STD_REGEX: StdModule = StdModule {
 // ─── Standard Library: Time ──��──────────────────────────────────────
 
 fn time_now(_args: &[Value]) -> RResult<Value> {
-    use std::time::SystemTime;
-    let ms = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map(|d| d.as_millis() as i64)
-        .unwrap_or(0);
+    let ms = crate::host_clock::wall_clock_since_epoch().as_millis() as i64;
     Ok(Value::Int(ms))
 }
 
 fn time_seconds(_args: &[Value]) -> RResult<Value> {
-    use std::time::SystemTime;
-    let secs = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0);
+    let secs = crate::host_clock::wall_clock_since_epoch().as_secs() as i64;
     Ok(Value::Int(secs))
 }
 
@@ -1270,12 +1259,8 @@ fn time_sleep(args: &[Value]) -> RResult<Value> {
 }
 
 fn time_format(args: &[Value]) -> RResult<Value> {
-    use std::time::SystemTime;
     let ms = if args.is_empty() {
-        SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|d| d.as_millis() as i64)
-            .unwrap_or(0)
+        crate::host_clock::wall_clock_since_epoch().as_millis() as i64
     } else {
         match &args[0] {
             Value::Int(n) => *n,
@@ -1340,6 +1325,7 @@ fn net_resolve(args: &[Value]) -> RResult<Value> {
             ));
         }
     };
+    crate::http_client::check_net("net_resolve", &host)?;
     use std::net::ToSocketAddrs;
     let addr_str = format!("{}:80", host);
     match addr_str.to_socket_addrs() {
@@ -1404,11 +1390,11 @@ static STD_NET: StdModule = StdModule {
 // ─── Standard Library: Collections ────────��─────────────────────────
 
 fn collections_deque_new(_args: &[Value]) -> RResult<Value> {
-    Ok(Value::Array(Vec::new()))
+    Ok(Value::Array(Vec::new().into()))
 }
 
 fn collections_stack_new(_args: &[Value]) -> RResult<Value> {
-    Ok(Value::Array(Vec::new()))
+    Ok(Value::Array(Vec::new().into()))
 }
 
 fn collections_sorted(args: &[Value]) -> RResult<Value> {
@@ -1474,7 +1460,7 @@ fn collections_unique(args: &[Value]) -> RResult<Value> {
             result.push(item);
         }
     }
-    Ok(Value::Array(result))
+    Ok(Value::Array(result.into()))
 }
 
 static STD_COLLECTIONS: StdModule = StdModule {
@@ -2583,16 +2569,15 @@ fn csv_parse(args: &[Value]) -> RResult<Value> {
     Ok(Value::Array(
         s.lines()
             .filter(|l| !l.is_empty())
-            .map(|l| Value::Array(csv_parse_row_str(l)))
+            .map(|l| Value::Array(csv_parse_row_str(l).into()))
             .collect(),
     ))
 }
 fn csv_parse_row(args: &[Value]) -> RResult<Value> {
     require_args("csv::parse_row", args, 1)?;
-    Ok(Value::Array(csv_parse_row_str(&extract_string(
-        "csv::parse_row",
-        &args[0],
-    )?)))
+    Ok(Value::Array(
+        csv_parse_row_str(&extract_string("csv::parse_row", &args[0])?).into(),
+    ))
 }
 fn csv_stringify(args: &[Value]) -> RResult<Value> {
     require_args("csv::stringify", args, 1)?;
@@ -2618,11 +2603,12 @@ fn csv_stringify(args: &[Value]) -> RResult<Value> {
 fn csv_headers(args: &[Value]) -> RResult<Value> {
     require_args("csv::headers", args, 1)?;
     Ok(Value::Array(
-        extract_string("csv::headers", &args[0])?
+        (extract_string("csv::headers", &args[0])?
             .lines()
             .next()
             .map(csv_parse_row_str)
-            .unwrap_or_default(),
+            .unwrap_or_default())
+        .into(),
     ))
 }
 fn csv_parse_row_str(line: &str) -> Vec<Value> {
@@ -2797,11 +2783,7 @@ static STD_HEX: StdModule = StdModule {
 // ─── Standard Library: Random ──────────────────────────────────────
 
 fn random_seed() -> u64 {
-    use std::time::SystemTime;
-    SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map(|d| d.as_nanos() as u64)
-        .unwrap_or(42)
+    (crate::host_clock::wall_clock_since_epoch().as_nanos() as u64)
         .wrapping_mul(6364136223846793005)
         .wrapping_add(1442695040888963407)
 }
@@ -2833,14 +2815,14 @@ fn random_shuffle(args: &[Value]) -> RResult<Value> {
     let mut arr = extract_array("random::shuffle", &args[0])?;
     let len = arr.len();
     if len <= 1 {
-        return Ok(Value::Array(arr));
+        return Ok(Value::Array(arr.into()));
     }
     let mut seed = random_seed();
     for i in (1..len).rev() {
         seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
         arr.swap(i, (seed as usize) % (i + 1));
     }
-    Ok(Value::Array(arr))
+    Ok(Value::Array(arr.into()))
 }
 
 static STD_RANDOM: StdModule = StdModule {
@@ -3051,9 +3033,11 @@ static STD_COLOR: StdModule = StdModule {
 
 fn process_exec(args: &[Value]) -> RResult<Value> {
     require_args("process::exec", args, 1)?;
+    let command = extract_string("process::exec", &args[0])?;
+    crate::sandbox::require("process_exec", crate::sandbox::Capability::Exec, &command)?;
     let output = std::process::Command::new("sh")
         .arg("-c")
-        .arg(&extract_string("process::exec", &args[0])?)
+        .arg(&command)
         .output()
         .map_err(|e| format!("process::exec: {}", e))?;
     let mut map = HashMap::new();
@@ -3073,7 +3057,7 @@ fn process_exec(args: &[Value]) -> RResult<Value> {
         MapKey::Str("success".to_string()),
         Value::Bool(output.status.success()),
     );
-    Ok(Value::Map(map))
+    Ok(Value::Map(map.into()))
 }
 fn process_pid(_args: &[Value]) -> RResult<Value> {
     Ok(Value::Int(std::process::id() as i64))
@@ -3083,7 +3067,7 @@ fn process_env_vars(_args: &[Value]) -> RResult<Value> {
     for (k, v) in std::env::vars() {
         map.insert(MapKey::Str(k), Value::String(v));
     }
-    Ok(Value::Map(map))
+    Ok(Value::Map(map.into()))
 }
 fn process_set_env(args: &[Value]) -> RResult<Value> {
     require_args("process::set_env", args, 2)?;
@@ -3097,8 +3081,10 @@ fn process_set_env(args: &[Value]) -> RResult<Value> {
 }
 fn process_which(args: &[Value]) -> RResult<Value> {
     require_args("process::which", args, 1)?;
+    let program = extract_string("process::which", &args[0])?;
+    crate::sandbox::require("process_which", crate::sandbox::Capability::Exec, &program)?;
     let output = std::process::Command::new("which")
-        .arg(&extract_string("process::which", &args[0])?)
+        .arg(&program)
         .output()
         .map_err(|e| format!("process::which: {}", e))?;
     Ok(Value::String(if output.status.success() {
@@ -3162,7 +3148,7 @@ fn ini_parse(args: &[Value]) -> RResult<Value> {
             if !section.is_empty() || !section_map.is_empty() {
                 result.insert(
                     MapKey::Str(section.clone()),
-                    Value::Map(std::mem::take(&mut section_map)),
+                    Value::Map(std::mem::take(&mut section_map).into()),
                 );
             }
             section = line[1..line.len() - 1].trim().to_string();
@@ -3176,9 +3162,9 @@ fn ini_parse(args: &[Value]) -> RResult<Value> {
         }
     }
     if !section.is_empty() || !section_map.is_empty() {
-        result.insert(MapKey::Str(section), Value::Map(section_map));
+        result.insert(MapKey::Str(section), Value::Map(section_map.into()));
     }
-    Ok(Value::Map(result))
+    Ok(Value::Map(result.into()))
 }
 fn ini_stringify(args: &[Value]) -> RResult<Value> {
     require_args("ini::stringify", args, 1)?;
@@ -3278,15 +3264,13 @@ fn iter_range(args: &[Value]) -> RResult<Value> {
             i += step;
         }
     }
-    Ok(Value::Array(result))
+    Ok(Value::Array(result.into()))
 }
 fn iter_repeat(args: &[Value]) -> RResult<Value> {
     require_args("iter::repeat", args, 2)?;
-    Ok(Value::Array(vec![
-        args[0].clone();
-        extract_int("iter::repeat", &args[1])?
-            as usize
-    ]))
+    Ok(Value::Array(
+        vec![args[0].clone(); extract_int("iter::repeat", &args[1])? as usize].into(),
+    ))
 }
 fn iter_zip(args: &[Value]) -> RResult<Value> {
     require_args("iter::zip", args, 2)?;
@@ -3294,7 +3278,7 @@ fn iter_zip(args: &[Value]) -> RResult<Value> {
         extract_array("iter::zip", &args[0])?
             .into_iter()
             .zip(extract_array("iter::zip", &args[1])?)
-            .map(|(a, b)| Value::Array(vec![a, b]))
+            .map(|(a, b)| Value::Array(vec![a, b].into()))
             .collect(),
     ))
 }
@@ -3304,7 +3288,7 @@ fn iter_enumerate(args: &[Value]) -> RResult<Value> {
         extract_array("iter::enumerate", &args[0])?
             .into_iter()
             .enumerate()
-            .map(|(i, v)| Value::Array(vec![Value::Int(i as i64), v]))
+            .map(|(i, v)| Value::Array(vec![Value::Int(i as i64), v].into()))
             .collect(),
     ))
 }
@@ -3317,7 +3301,7 @@ fn iter_flatten(args: &[Value]) -> RResult<Value> {
             other => r.push(other),
         }
     }
-    Ok(Value::Array(r))
+    Ok(Value::Array(r.into()))
 }
 fn iter_take(args: &[Value]) -> RResult<Value> {
     require_args("iter::take", args, 2)?;
@@ -3345,14 +3329,16 @@ fn iter_chunks(args: &[Value]) -> RResult<Value> {
         return Err("iter::chunks: size must be > 0".to_string());
     }
     Ok(Value::Array(
-        arr.chunks(size).map(|c| Value::Array(c.to_vec())).collect(),
+        arr.chunks(size)
+            .map(|c| Value::Array(c.to_vec().into()))
+            .collect(),
     ))
 }
 fn iter_chain(args: &[Value]) -> RResult<Value> {
     require_args("iter::chain", args, 2)?;
     let mut a = extract_array("iter::chain", &args[0])?;
     a.extend(extract_array("iter::chain", &args[1])?);
-    Ok(Value::Array(a))
+    Ok(Value::Array(a.into()))
 }
 
 static STD_ITER: StdModule = StdModule {
@@ -3671,13 +3657,13 @@ fn sort_asc(args: &[Value]) -> RResult<Value> {
     require_args("sort::asc", args, 1)?;
     let mut a = extract_array("sort::asc", &args[0])?;
     a.sort_by(value_cmp);
-    Ok(Value::Array(a))
+    Ok(Value::Array(a.into()))
 }
 fn sort_desc(args: &[Value]) -> RResult<Value> {
     require_args("sort::desc", args, 1)?;
     let mut a = extract_array("sort::desc", &args[0])?;
     a.sort_by(|x, y| value_cmp(y, x));
-    Ok(Value::Array(a))
+    Ok(Value::Array(a.into()))
 }
 fn sort_is_sorted(args: &[Value]) -> RResult<Value> {
     require_args("sort::is_sorted", args, 1)?;
@@ -3723,7 +3709,7 @@ fn sort_by_length(args: &[Value]) -> RResult<Value> {
         Value::Bytes(b) => b.len(),
         _ => 0,
     });
-    Ok(Value::Array(a))
+    Ok(Value::Array(a.into()))
 }
 fn value_cmp(a: &Value, b: &Value) -> std::cmp::Ordering {
     match (a, b) {
@@ -3788,21 +3774,7 @@ static STD_SORT: StdModule = StdModule {
 
 fn uuid_v4(_args: &[Value]) -> RResult<Value> {
     let mut bytes = [0u8; 16];
-    #[cfg(unix)]
-    {
-        use std::io::Read;
-        if let Ok(mut f) = std::fs::File::open("/dev/urandom") {
-            let _ = f.read_exact(&mut bytes);
-        }
-    }
-    #[cfg(not(unix))]
-    {
-        let mut s = random_seed();
-        for b in bytes.iter_mut() {
-            s = s.wrapping_mul(6364136223846793005).wrapping_add(1);
-            *b = (s >> 33) as u8;
-        }
-    }
+    fill_random(&mut bytes);
     bytes[6] = (bytes[6] & 0x0f) | 0x40;
     bytes[8] = (bytes[8] & 0x3f) | 0x80;
     Ok(Value::String(format_uuid_bytes(&bytes)))
@@ -4045,7 +4017,7 @@ fn url_parse_fn(args: &[Value]) -> RResult<Value> {
     };
     map.insert(MapKey::Str("query".to_string()), Value::String(query));
     map.insert(MapKey::Str("fragment".to_string()), Value::String(fragment));
-    Ok(Value::Map(map))
+    Ok(Value::Map(map.into()))
 }
 fn url_build(args: &[Value]) -> RResult<Value> {
     require_args("url::build", args, 1)?;
@@ -4113,7 +4085,7 @@ fn url_query_params(args: &[Value]) -> RResult<Value> {
         };
         map.insert(MapKey::Str(k), Value::String(v));
     }
-    Ok(Value::Map(map))
+    Ok(Value::Map(map.into()))
 }
 fn url_build_query(args: &[Value]) -> RResult<Value> {
     require_args("url::build_query", args, 1)?;
@@ -4204,7 +4176,7 @@ fn extract_int(name: &str, v: &Value) -> RResult<i64> {
 }
 fn extract_array(name: &str, v: &Value) -> RResult<Vec<Value>> {
     match v {
-        Value::Array(a) => Ok(a.clone()),
+        Value::Array(a) => Ok(a.to_vec()),
         other => Err(format!("{}: expected array, got {:?}", name, other)),
     }
 }
@@ -4217,7 +4189,7 @@ fn extract_bytes(name: &str, v: &Value) -> RResult<Vec<u8>> {
 }
 fn extract_map(name: &str, v: &Value) -> RResult<HashMap<MapKey, Value>> {
     match v {
-        Value::Map(m) => Ok(m.clone()),
+        Value::Map(m) => Ok((**m).clone()),
         other => Err(format!("{}: expected map, got {:?}", name, other)),
     }
 }
@@ -4225,7 +4197,7 @@ fn value_display(v: &Value) -> String {
     match v {
         Value::String(s) => s.clone(),
         Value::Int(n) => n.to_string(),
-        Value::Float(f) => format!("{}", f),
+        Value::Float(f) => crate::float_mode::display(*f),
         Value::Bool(b) => b.to_string(),
         Value::Void => "void".to_string(),
         Value::Array(a) => format!(
@@ -4615,14 +4587,14 @@ fn parse_json_array(s: &str) -> Result<(Value, &str), String> {
     let mut items = Vec::new();
     s = s.trim();
     if let Some(rest) = s.strip_prefix(']') {
-        return Ok((Value::Array(items), rest));
+        return Ok((Value::Array(items.into()), rest));
     }
     loop {
         let (val, rest) = parse_json_inner(s)?;
         items.push(val);
         s = rest.trim();
         if let Some(rest) = s.strip_prefix(']') {
-            return Ok((Value::Array(items), rest));
+            return Ok((Value::Array(items.into()), rest));
         }
         if s.starts_with(',') {
             s = &s[1..];
@@ -4637,7 +4609,7 @@ fn parse_json_object(s: &str) -> Result<(Value, &str), String> {
     let mut map = HashMap::new();
     s = s.trim();
     if let Some(rest) = s.strip_prefix('}') {
-        return Ok((Value::Map(map), rest));
+        return Ok((Value::Map(map.into()), rest));
     }
     loop {
         s = s.trim();
@@ -4655,7 +4627,7 @@ fn parse_json_object(s: &str) -> Result<(Value, &str), String> {
         map.insert(MapKey::Str(key), val);
         s = rest.trim();
         if let Some(rest) = s.strip_prefix('}') {
-            return Ok((Value::Map(map), rest));
+            return Ok((Value::Map(map.into()), rest));
         }
         if s.starts_with(',') {
             s = &s[1..];
@@ -5005,7 +4977,7 @@ mod tests {
             Value::Array(a) => assert_eq!(a.len(), 3),
             other => panic!("expected Array, got {:?}", other),
         }
-        let arr = Value::Array(vec![Value::String("x".into()), Value::String("y".into())]);
+        let arr = Value::Array(vec![Value::String("x".into()), Value::String("y".into())].into());
         assert_str(
             string_join(&[arr, Value::String("-".into())]).unwrap(),
             "x-y",
@@ -5180,7 +5152,7 @@ mod tests {
     #[test]
     fn sort_asc_desc() {
         let arr = vec![Value::Int(3), Value::Int(1), Value::Int(2)];
-        match sort_asc(&[Value::Array(arr.clone())]).unwrap() {
+        match sort_asc(&[Value::Array(arr.clone().into())]).unwrap() {
             Value::Array(a) => {
                 assert_int(a[0].clone(), 1);
                 assert_int(a[1].clone(), 2);
@@ -5188,7 +5160,7 @@ mod tests {
             }
             other => panic!("expected Array, got {:?}", other),
         }
-        match sort_desc(&[Value::Array(arr)]).unwrap() {
+        match sort_desc(&[Value::Array(arr.into())]).unwrap() {
             Value::Array(a) => {
                 assert_int(a[0].clone(), 3);
                 assert_int(a[1].clone(), 2);
@@ -5201,8 +5173,8 @@ mod tests {
     #[test]
     fn sort_min_max() {
         let arr = vec![Value::Int(5), Value::Int(1), Value::Int(3)];
-        assert_int(sort_min(&[Value::Array(arr.clone())]).unwrap(), 1);
-        assert_int(sort_max(&[Value::Array(arr)]).unwrap(), 5);
+        assert_int(sort_min(&[Value::Array(arr.clone().into())]).unwrap(), 1);
+        assert_int(sort_max(&[Value::Array(arr.into())]).unwrap(), 5);
     }
 
     #[test]
@@ -72,6 +72,21 @@ const TIER_BUILTINS: &[TierBuiltin] = &[
         tier: 2,
         resource: "file I/O",
     },
+    TierBuiltin {
+        name: "read_file",
+        tier: 2,
+        resource: "file I/O",
+    },
+    TierBuiltin {
+        name: "write_file",
+        tier: 2,
+        resource: "file I/O",
+    },
+    TierBuiltin {
+        name: "append_file",
+        tier: 2,
+        resource: "file I/O",
+    },
     TierBuiltin {
         name: "file_exists",
         tier: 2,
@@ -182,6 +197,36 @@ const TIER_BUILTINS: &[TierBuiltin] = &[
         tier: 2,
         resource: "networking",
     },
+    TierBuiltin {
+        name: "mqtt_publish",
+        tier: 2,
+        resource: "networking",
+    },
+    TierBuiltin {
+        name: "mqtt_subscribe",
+        tier: 2,
+        resource: "networking",
+    },
+    TierBuiltin {
+        name: "serial_open",
+        tier: 2,
+        resource: "serial I/O",
+    },
+    TierBuiltin {
+        name: "serial_read",
+        tier: 2,
+        resource: "serial I/O",
+    },
+    TierBuiltin {
+        name: "serial_write",
+        tier: 2,
+        resource: "serial I/O",
+    },
+    TierBuiltin {
+        name: "serial_close",
+        tier: 2,
+        resource: "serial I/O",
+    },
 ];
 
 fn find_tier_builtin(name: &str) -> Option<&'static TierBuiltin> {
@@ -0,0 +1,187 @@
+//! Strict typing mode: `Any` is an error, not an escape hatch.
+//!
+//! The checker leans on `Type::Any` to stay permissive — builtins with
+//! heterogeneous inputs return it, untyped collections index to it,
+//! and a parameter annotated `any` accepts everything. That is the
+//! right default for scripts, but in safety-critical code an `Any`
+//! is a hole in the proof story: the verifier and the backends can no
+//! longer say what flows through that binding.
+//!
+//! `--strict` (CLI) or `strict` (REPL toggle) turns every site where
+//! an `Any` *enters* user-visible bindings into a diagnostic that
+//! names the binding and explains where the `Any` was introduced:
+//!
+//! - a parameter or return annotation spelled `any` / `Any`;
+//! - an unannotated parameter the body constrains (`x + 1`), which
+//!   let-polymorphism cannot generalize and so types as `Any`;
+//! - a `let` annotated `any` / `Any`;
+//! - an unannotated `let` whose initializer the checker types as
+//!   `Any` (a call returning `Any`, indexing an untyped collection,
+//!   a field read off an `Any` value, ...).
+//!
+//! An explicit concrete annotation (`let v: int = identity(raw);`)
+//! is the fix in the last case: the binding is typed even though the
+//! initializer isn't, and the checker enforces compatibility there.
+//!
+//! Mirrors the `termination::STRICT_TERMINATION` pattern — one
+//! process-wide flag set by the driver before the typechecker runs.
+
+use crate::Node;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static STRICT_ANY: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable strict mode. Called from the CLI dispatcher and
+/// the REPL's `strict` toggle.
+pub fn set_strict_any(on: bool) {
+    STRICT_ANY.store(on, Ordering::Relaxed);
+}
+
+/// Whether strict mode is currently on.
+pub fn strict_any() -> bool {
+    STRICT_ANY.load(Ordering::Relaxed)
+}
+
+/// `true` when a source-level type annotation spells `Any`.
+pub(crate) fn is_any_annotation(annot: &str) -> bool {
+    matches!(annot.trim(), "any" | "Any")
+}
+
+/// Diagnostic for a parameter annotated `Any`.
+pub(crate) fn param_error(fn_name: &str, param: &str) -> String {
+    format!(
+        "strict: parameter `{}` of fn `{}` is declared `Any` — give it a concrete type",
+        param, fn_name
+    )
+}
+
+/// Diagnostic for a parameter with no annotation at all.
+pub(crate) fn untyped_param_error(fn_name: &str, param: &str) -> String {
+    format!(
+        "strict: parameter `{}` of fn `{}` has no type annotation, so it is `Any` — give it a concrete type",
+        param, fn_name
+    )
+}
+
+/// Diagnostic for a return annotation spelled `Any`.
+pub(crate) fn return_error(fn_name: &str) -> String {
+    format!(
+        "strict: fn `{}` declares return type `Any` — give it a concrete type",
+        fn_name
+    )
+}
+
+/// Diagnostic for a `let` whose binding ends up `Any`. `annotated`
+/// distinguishes `let x: any = ...` from an inferred `Any`.
+pub(crate) fn let_error(name: &str, value: &Node, annotated: bool) -> String {
+    if annotated {
+        return format!(
+            "strict: `let {}` is annotated `Any` — give it a concrete type",
+            name
+        );
+    }
+    format!(
+        "strict: `let {}` has type `Any`, introduced by {} — add a type annotation",
+        name,
+        describe_origin(value)
+    )
+}
+
+/// Human-readable description of why `value` typed as `Any`.
+pub(crate) fn describe_origin(value: &Node) -> String {
+    match value {
+        Node::CallExpression { function, .. } => match function.as_ref() {
+            Node::Identifier { name, .. } => {
+                format!("a call to `{}`, whose return type is `Any`", name)
+            }
+            Node::FieldAccess { field, .. } => {
+                format!("a call to method `{}`, whose return type is `Any`", field)
+            }
+            _ => "a call whose callee has no known signature".to_string(),
+        },
+        Node::IndexExpression { .. } => "indexing into an untyped collection".to_string(),
+        Node::FieldAccess { field, .. } => {
+            format!("reading field `{}` from a value of type `Any`", field)
+        }
+        Node::Identifier { name, .. } => format!("`{}`, which is itself `Any`", name),
+        Node::Match { .. } => "a `match` whose arms disagree on type".to_string(),
+        Node::IfStatement { .. } => "an `if` whose branches disagree on type".to_string(),
+        _ => "an expression the checker cannot type".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn check_strict(src: &str) -> Result<(), String> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {errs:?}");
+        TypeChecker::new()
+            .with_strict_any(true)
+            .check_program(&prog)
+            .map(|_| ())
+    }
+
+    #[test]
+    fn any_parameter_is_rejected() {
+        let err = check_strict("fn f(any x) -> int { return 1; }").unwrap_err();
+        assert!(
+            err.contains("parameter `x` of fn `f` is declared `Any`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn untyped_parameter_is_rejected() {
+        let err = check_strict("fn f(x) -> int { return x + 1; }").unwrap_err();
+        assert!(
+            err.contains("parameter `x` of fn `f` has no type annotation"),
+            "{err}"
+        );
+        // A parameter the body only passes through is generalized to
+        // a type parameter, so it is not `Any`.
+        check_strict("fn id(x) { return x; }\nlet n: int = id(1);")
+            .expect("generalized parameter is typed");
+    }
+
+    #[test]
+    fn any_return_is_rejected() {
+        let err = check_strict("fn f(int x) -> any { return x; }").unwrap_err();
+        assert!(err.contains("fn `f` declares return type `Any`"), "{err}");
+    }
+
+    #[test]
+    fn inferred_any_let_names_the_call() {
+        let err = check_strict("let v = identity(1);").unwrap_err();
+        assert!(err.contains("`let v` has type `Any`"), "{err}");
+        assert!(err.contains("call to `identity`"), "{err}");
+    }
+
+    #[test]
+    fn annotated_let_is_the_fix() {
+        check_strict("let v: int = identity(1);").expect("concrete let is fine");
+    }
+
+    #[test]
+    fn annotated_any_let_is_rejected() {
+        let err = check_strict("let v: any = 1;").unwrap_err();
+        assert!(err.contains("`let v` is annotated `Any`"), "{err}");
+    }
+
+    #[test]
+    fn concrete_program_passes() {
+        check_strict("fn add(int a, int b) -> int { return a + b; }\nlet s = add(1, 2);")
+            .expect("no Any anywhere");
+    }
+
+    #[test]
+    fn non_strict_keeps_any_permissive() {
+        let (prog, _) = parse("fn f(any x) -> int { return 1; }");
+        TypeChecker::new()
+            .with_strict_any(false)
+            .check_program(&prog)
+            .expect("non-strict mode keeps Any permissive");
+    }
+}
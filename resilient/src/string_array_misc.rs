@@ -12,11 +12,11 @@
 use crate::{RResult, Value};
 
 fn split_array_2(before: String, after: String) -> Value {
-    Value::Array(vec![Value::String(before), Value::String(after)])
+    Value::Array(vec![Value::String(before), Value::String(after)].into())
 }
 
 fn single_array(s: String) -> Value {
-    Value::Array(vec![Value::String(s)])
+    Value::Array(vec![Value::String(s)].into())
 }
 
 /// `string_split_once(s, sep) -> Array` — split on the first
@@ -228,14 +228,14 @@ mod tests {
 
     #[test]
     fn from_chars_basic() {
-        let arr = Value::Array(vec![s("h"), s("e"), s("l"), s("l"), s("o")]);
+        let arr = Value::Array(vec![s("h"), s("e"), s("l"), s("l"), s("o")].into());
         let r = builtin_string_from_chars(&[arr]).unwrap();
         assert_eq!(as_string(r), "hello");
     }
 
     #[test]
     fn from_chars_empty_array_is_empty_string() {
-        let arr = Value::Array(vec![]);
+        let arr = Value::Array(vec![].into());
         let r = builtin_string_from_chars(&[arr]).unwrap();
         assert_eq!(as_string(r), "");
     }
@@ -243,28 +243,28 @@ mod tests {
     #[test]
     fn from_chars_multi_byte_codepoints() {
         // 🌟 is a 4-byte UTF-8 codepoint, but a single Unicode scalar.
-        let arr = Value::Array(vec![s("🌟"), s("h"), s("i")]);
+        let arr = Value::Array(vec![s("🌟"), s("h"), s("i")].into());
         let r = builtin_string_from_chars(&[arr]).unwrap();
         assert_eq!(as_string(r), "🌟hi");
     }
 
     #[test]
     fn from_chars_rejects_multi_char_element() {
-        let arr = Value::Array(vec![s("h"), s("el"), s("lo")]);
+        let arr = Value::Array(vec![s("h"), s("el"), s("lo")].into());
         let err = builtin_string_from_chars(&[arr]).unwrap_err();
         assert!(err.contains("single char"));
     }
 
     #[test]
     fn from_chars_rejects_empty_element() {
-        let arr = Value::Array(vec![s("h"), s("")]);
+        let arr = Value::Array(vec![s("h"), s("")].into());
         let err = builtin_string_from_chars(&[arr]).unwrap_err();
         assert!(err.contains("empty string"));
     }
 
     #[test]
     fn from_chars_rejects_non_string_element() {
-        let arr = Value::Array(vec![s("h"), Value::Int(105)]);
+        let arr = Value::Array(vec![s("h"), Value::Int(105)].into());
         let err = builtin_string_from_chars(&[arr]).unwrap_err();
         assert!(
             err.contains("must be a char or single-char string"),
@@ -277,13 +277,13 @@ mod tests {
 
     #[test]
     fn array_is_empty_true_for_empty() {
-        let r = builtin_array_is_empty(&[Value::Array(vec![])]).unwrap();
+        let r = builtin_array_is_empty(&[Value::Array(vec![].into())]).unwrap();
         assert!(as_bool(r));
     }
 
     #[test]
     fn array_is_empty_false_for_non_empty() {
-        let r = builtin_array_is_empty(&[Value::Array(vec![Value::Int(1)])]).unwrap();
+        let r = builtin_array_is_empty(&[Value::Array(vec![Value::Int(1)].into())]).unwrap();
         assert!(!as_bool(r));
     }
 
@@ -312,7 +312,7 @@ mod tests {
         // For an ASCII string: chars(s) = arr; from_chars(arr) = s.
         let original = "abcdef";
         let chars: Vec<Value> = original.chars().map(|c| s(&c.to_string())).collect();
-        let r = builtin_string_from_chars(&[Value::Array(chars)]).unwrap();
+        let r = builtin_string_from_chars(&[Value::Array(chars.into())]).unwrap();
         assert_eq!(as_string(r), original);
     }
 }
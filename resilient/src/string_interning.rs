@@ -204,10 +204,10 @@ fn check_node(node: &crate::Node) -> Result<(), String> {
             for default_expr in defaults.iter().flatten() {
                 check_node(default_expr)?;
             }
-            for req in requires {
+            for req in requires.iter() {
                 check_node(req)?;
             }
-            for ens in ensures {
+            for ens in ensures.iter() {
                 check_node(ens)?;
             }
             Ok(())
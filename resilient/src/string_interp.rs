@@ -27,6 +27,7 @@ use crate::{Interpreter, Lexer, Node, Parser, RResult, Value};
 
 /// One segment of an interpolated string.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StringPart {
     /// A run of literal characters (no interpolation needed).
     Literal(String),
@@ -178,7 +179,7 @@ fn value_to_string(v: Value) -> String {
     match v {
         Value::String(s) => s,
         Value::Int(i) => i.to_string(),
-        Value::Float(f) => f.to_string(),
+        Value::Float(f) => crate::float_mode::display(f),
         Value::Bool(b) => b.to_string(),
         other => format!("{}", other),
     }
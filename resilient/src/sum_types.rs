@@ -81,7 +81,7 @@ pub(crate) fn parse_enum_decl(parser: &mut Parser) -> Node {
     parser.next_token(); // consume 'enum'
 
     let name = match &parser.current_token {
-        Token::Identifier(n) => n.clone(),
+        Token::Identifier(n) => n.to_string(),
         other => {
             let tok = other.clone();
             parser.record_error(format!("Expected identifier after 'enum', found {}", tok));
@@ -102,7 +102,7 @@ pub(crate) fn parse_enum_decl(parser: &mut Parser) -> Node {
         // Best-effort recovery: return an empty enum so the rest of
         // the program still parses.
         return Node::EnumDecl {
-            name,
+            name: name.to_string(),
             type_params,
             variants: Vec::new(),
             span: enum_span,
@@ -132,7 +132,7 @@ pub(crate) fn parse_enum_decl(parser: &mut Parser) -> Node {
                 break;
             }
             Token::Identifier(variant_name) => {
-                let v_name = variant_name.clone();
+                let v_name = variant_name.to_string();
                 let v_span = parser.span_at_current();
                 parser.next_token(); // consume variant name
 
@@ -145,14 +145,32 @@ pub(crate) fn parse_enum_decl(parser: &mut Parser) -> Node {
                     _ => EnumPayload::None,
                 };
 
-                if !seen.insert(v_name.clone()) {
+                // `Red = expr` — an explicit discriminant, evaluated by
+                // `const_eval`. Only payload-less variants have one.
+                let discriminant = if parser.current_token == Token::Assign {
+                    if !matches!(payload, EnumPayload::None) {
+                        parser.record_error(format!(
+                            "Variant '{}' of 'enum {}' has a payload and cannot have a discriminant",
+                            v_name, name
+                        ));
+                    }
+                    parser.next_token(); // consume '='
+                    let expr = parser.parse_expression(0);
+                    parser.next_token(); // step past the expression's last token
+                    expr
+                } else {
+                    None
+                };
+
+                if !seen.insert(v_name.to_string()) {
                     parser
                         .record_error(format!("Duplicate variant '{}' in 'enum {}'", v_name, name));
                 } else {
                     variants.push(EnumVariant {
-                        name: v_name,
+                        name: v_name.to_string(),
                         span: v_span,
                         payload,
+                        discriminant,
                     });
                 }
 
@@ -183,7 +201,7 @@ pub(crate) fn parse_enum_decl(parser: &mut Parser) -> Node {
     }
 
     Node::EnumDecl {
-        name,
+        name: name.to_string(),
         type_params,
         variants,
         span: enum_span,
@@ -234,7 +252,7 @@ fn parse_named_payload(parser: &mut Parser, enum_name: &str, variant_name: &str)
                 return EnumPayload::Named(fields);
             }
             Token::Identifier(field_name) => {
-                let f_name = field_name.clone();
+                let f_name = field_name.to_string();
                 let f_span = parser.span_at_current();
                 parser.next_token(); // consume field name
                 if parser.current_token != Token::Colon {
@@ -258,14 +276,14 @@ fn parse_named_payload(parser: &mut Parser, enum_name: &str, variant_name: &str)
                         continue;
                     }
                 };
-                if !seen.insert(f_name.clone()) {
+                if !seen.insert(f_name.to_string()) {
                     parser.record_error(format!(
                         "Duplicate field '{}' in variant '{}::{}'",
                         f_name, enum_name, variant_name
                     ));
                 } else {
                     fields.push(EnumField {
-                        name: f_name,
+                        name: f_name.to_string(),
                         ty,
                         span: f_span,
                     });
@@ -352,7 +370,7 @@ fn skip_to_payload_separator(parser: &mut Parser, close: Token) {
 fn parse_payload_type(parser: &mut Parser) -> Option<String> {
     match &parser.current_token {
         Token::Identifier(n) => {
-            let name = n.clone();
+            let name = n.to_string();
             parser.next_token();
             Some(name)
         }
@@ -383,7 +401,7 @@ pub(crate) fn parse_named_pattern_payload(parser: &mut Parser) -> EnumPatternPay
                 return EnumPatternPayload::Named(fields);
             }
             Token::Identifier(field_name) => {
-                let f_name = field_name.clone();
+                let f_name = field_name.to_string();
                 let sub = if parser.peek_token == Token::Colon {
                     parser.next_token(); // past field name
                     parser.next_token(); // past `:` to start of sub-pattern
@@ -392,9 +410,9 @@ pub(crate) fn parse_named_pattern_payload(parser: &mut Parser) -> EnumPatternPay
                     p
                 } else {
                     parser.next_token(); // past field name
-                    Pattern::Identifier(f_name.clone())
+                    Pattern::Identifier(f_name.to_string())
                 };
-                fields.push((f_name, Box::new(sub)));
+                fields.push((f_name.to_string(), Box::new(sub)));
                 if parser.current_token == Token::Comma {
                     parser.next_token();
                 }
@@ -58,7 +58,7 @@ pub(crate) fn parse(parser: &mut crate::Parser) -> Node {
     parser.next_token();
 
     let strategy = match &parser.current_token {
-        Token::Identifier(s) => s.clone(),
+        Token::Identifier(s) => s.to_string(),
         _ => {
             parser.record_error(format!(
                 "expected strategy identifier, found {}",
@@ -208,8 +208,8 @@ fn parse_child(parser: &mut crate::Parser) -> Option<SupervisorChild> {
     }
     parser.next_token();
 
-    // Parse fn field
-    if !matches!(parser.current_token, Token::Identifier(ref n) if n == "fn") {
+    // Parse fn field. `fn` lexes as the keyword.
+    if parser.current_token != Token::Function {
         parser.record_error("expected `fn` field in child spec".to_string());
         return None;
     }
@@ -222,7 +222,7 @@ fn parse_child(parser: &mut crate::Parser) -> Option<SupervisorChild> {
     parser.next_token();
 
     let fn_name = match &parser.current_token {
-        Token::Identifier(s) => s.clone(),
+        Token::Identifier(s) => s.to_string(),
         _ => {
             parser.record_error(format!(
                 "expected identifier for fn, found {}",
@@ -254,7 +254,7 @@ fn parse_child(parser: &mut crate::Parser) -> Option<SupervisorChild> {
     parser.next_token();
 
     let restart = match &parser.current_token {
-        Token::Identifier(s) => s.clone(),
+        Token::Identifier(s) => s.to_string(),
         _ => {
             parser.record_error(format!(
                 "expected restart type identifier, found {}",
@@ -0,0 +1,289 @@
+//! Interned identifier names.
+//!
+//! A `Symbol` is a `u32` handle for one distinct name. The lexer
+//! interns every identifier once; the AST, the interpreter's
+//! `Environment`, and the typechecker's `TypeEnvironment` then carry
+//! and compare the handle instead of an owned `String`, so copying a
+//! name is free and an environment lookup hashes an integer.
+//!
+//! The table is process-wide — type environments are shared across
+//! threads — and append-only: each distinct name is leaked once, which
+//! bounds its size by the program's vocabulary rather than by how many
+//! times a name is mentioned.
+//!
+//! Nothing is ever freed. `as_str` hands out `&'static str`, and a
+//! `Symbol` can outlive any session that created it (it sits in ASTs,
+//! type environments and values), so there is no point at which a name
+//! is known to be dead. Each distinct name costs its own bytes plus
+//! roughly 60 bytes of bookkeeping (the boxed text, a hash-map entry,
+//! a slot in the shared list and one in each reading thread's copy).
+//! A one-shot `rz run` or `rz check` never notices. Long-lived
+//! processes do grow with every name they ever see: the REPL interns
+//! a fresh `_N` per result, and the LSP server re-lexes each edit, so
+//! every identifier prefix typed along the way (`c`, `co`, `cou`, ...)
+//! becomes an entry. A hundred thousand distinct names is on the order
+//! of 7 MB.
+//!
+//! `Symbol` derefs to `str` and compares equal to string types, so
+//! code that only reads a name (`name == "main"`, `name.starts_with`,
+//! `format!("{}", name)`) does not need to know it is interned.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+#[derive(Default)]
+struct Interner {
+    ids: HashMap<&'static str, Symbol>,
+    names: Vec<&'static str>,
+}
+
+fn interner() -> &'static RwLock<Interner> {
+    static INTERNER: OnceLock<RwLock<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Default::default)
+}
+
+impl Symbol {
+    /// The symbol for `name`, adding it to the table on first use. A
+    /// new name is leaked and stays for the life of the process.
+    pub fn intern(name: &str) -> Symbol {
+        if let Some(sym) = Symbol::lookup(name) {
+            return sym;
+        }
+        let mut table = interner().write().unwrap_or_else(|e| e.into_inner());
+        if let Some(&sym) = table.ids.get(name) {
+            return sym;
+        }
+        let text: &'static str = Box::leak(name.to_owned().into_boxed_str());
+        let sym = Symbol(table.names.len() as u32);
+        table.names.push(text);
+        table.ids.insert(text, sym);
+        sym
+    }
+
+    /// The symbol for `name` if it has been interned. A name nothing
+    /// interned cannot be bound in any environment, so lookups use
+    /// this rather than growing the table.
+    pub fn lookup(name: &str) -> Option<Symbol> {
+        let table = interner().read().unwrap_or_else(|e| e.into_inner());
+        table.ids.get(name).copied()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        // Names never move or go away, so each thread keeps its own
+        // copy of the table and only takes the lock to catch up with
+        // names interned since it last looked.
+        thread_local! {
+            static NAMES: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+        }
+        let i = self.0 as usize;
+        NAMES.with(|names| {
+            let mut names = names.borrow_mut();
+            if i >= names.len() {
+                let table = interner().read().unwrap_or_else(|e| e.into_inner());
+                let seen = names.len();
+                names.extend_from_slice(&table.names[seen..]);
+            }
+            names[i]
+        })
+    }
+}
+
+impl std::ops::Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// A name that can be looked up in a `Symbol`-keyed table. Lookups
+/// by string go through [`Symbol::lookup`], so asking for a name that
+/// was never interned misses without adding it to the table.
+pub trait SymbolKey {
+    fn find_symbol(&self) -> Option<Symbol>;
+}
+
+impl SymbolKey for Symbol {
+    fn find_symbol(&self) -> Option<Symbol> {
+        Some(*self)
+    }
+}
+
+impl SymbolKey for &Symbol {
+    fn find_symbol(&self) -> Option<Symbol> {
+        Some(**self)
+    }
+}
+
+impl SymbolKey for &str {
+    fn find_symbol(&self) -> Option<Symbol> {
+        Symbol::lookup(self)
+    }
+}
+
+impl SymbolKey for &String {
+    fn find_symbol(&self) -> Option<Symbol> {
+        Symbol::lookup(self)
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Symbol {
+        Symbol::intern(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Symbol {
+        Symbol::intern(&name)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(name: &String) -> Symbol {
+        Symbol::intern(name)
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(sym: Symbol) -> String {
+        sym.as_str().to_owned()
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Symbol {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<Symbol> for str {
+    fn eq(&self, other: &Symbol) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<Symbol> for &str {
+    fn eq(&self, other: &Symbol) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<Symbol> for String {
+    fn eq(&self, other: &Symbol) -> bool {
+        self == other.as_str()
+    }
+}
+
+/// Ordered by name, not by interning order, so sorted output does not
+/// depend on which name happened to be seen first.
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        if self.0 == other.0 {
+            return std::cmp::Ordering::Equal;
+        }
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Same shape as a `String`'s, so AST dumps read as before.
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Serialized as the name itself: handles are only meaningful within
+/// one process.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Symbol {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Symbol {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Symbol, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Symbol::intern(&name))
+    }
+}
+
+/// `deserialize_with` for the AST's `&'static str` fields (operators):
+/// the text is interned, which gives it the `'static` lifetime.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_static_str<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<&'static str, D::Error> {
+    let text = <Symbol as serde::Deserialize>::deserialize(deserializer)?;
+    Ok(text.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interning_is_idempotent_and_round_trips() {
+        let a = Symbol::intern("symbol_test_alpha");
+        let b = Symbol::intern(&String::from("symbol_test_alpha"));
+        assert_eq!(a, b);
+        assert_eq!(a.as_str(), "symbol_test_alpha");
+        assert_ne!(a, Symbol::intern("symbol_test_beta"));
+        assert!(a == "symbol_test_alpha");
+        assert_eq!(
+            format!("{} {:?}", a, a),
+            "symbol_test_alpha \"symbol_test_alpha\""
+        );
+    }
+
+    #[test]
+    fn lookup_does_not_intern() {
+        assert_eq!(Symbol::lookup("symbol_test_never_interned"), None);
+        let sym = Symbol::intern("symbol_test_gamma");
+        assert_eq!(Symbol::lookup("symbol_test_gamma"), Some(sym));
+    }
+
+    #[test]
+    fn ordering_follows_the_names() {
+        let z = Symbol::intern("symbol_test_z");
+        let a = Symbol::intern("symbol_test_a");
+        assert!(a < z);
+    }
+}
@@ -55,6 +55,19 @@
 //! `#[must_tail_call]` is statically verified to allow only tail self-calls,
 //! this signal is always consumed by the trampoline before propagating.
 //!
+//! Without the attribute the same trampoline still serves any plain
+//! `return g(args)` call, to the running fn or another: the
+//! interpreter's `tail_calls` flag makes the `ReturnStatement` arm emit
+//! `Value::TailCallTo { callee, args }` for exactly that shape, which is
+//! always a tail position, and the loop switches the frame to `callee`.
+//! Stack traces still list the chain of tail calls, cut back at the
+//! first repeat so a recursion cycle keeps a bounded trace.
+//! It is off inside `try` / `live` bodies and when either side has
+//! `ensures` / `recovers_to` or type parameters, and each switch checks
+//! the callee's `requires`. The VM gets the self-call case from the
+//! compiler's `rewrite_tail_calls`; across fns it still needs a
+//! `#[mutual_tail_call]` group.
+//!
 //! ## Feature isolation
 //!
 //! All TCO logic lives here. The core files touch only:
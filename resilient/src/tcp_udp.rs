@@ -7,12 +7,17 @@
 //! Handle ids are minted by monotonic counters and stored in thread-local registries
 //! keyed by id. Closing a handle removes it from the registry so stale handles
 //! surface `unknown handle` errors.
+//!
+//! Making a handle (`tcp_connect`, `tcp_listen`, `udp_bind`) is network
+//! access: under `--net none`, or an `--allow` without `net`, it
+//! returns `Err` with a `PermissionDenied` message instead.
 
 use crate::Value;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::rc::Rc;
 use std::sync::atomic::{AtomicI64, Ordering};
 use std::time::Duration;
 
@@ -49,28 +54,28 @@ fn err(msg: String) -> Value {
 fn tcp_conn_handle(id: i64) -> Value {
     Value::Struct {
         name: "TcpConn".to_string(),
-        fields: vec![("id".to_string(), Value::Int(id))],
+        fields: Rc::new(vec![("id".to_string(), Value::Int(id))]),
     }
 }
 
 fn tcp_listener_handle(id: i64) -> Value {
     Value::Struct {
         name: "TcpListener".to_string(),
-        fields: vec![("id".to_string(), Value::Int(id))],
+        fields: Rc::new(vec![("id".to_string(), Value::Int(id))]),
     }
 }
 
 fn udp_socket_handle(id: i64) -> Value {
     Value::Struct {
         name: "UdpSocket".to_string(),
-        fields: vec![("id".to_string(), Value::Int(id))],
+        fields: Rc::new(vec![("id".to_string(), Value::Int(id))]),
     }
 }
 
 fn extract_handle_id(v: &Value, kind: &str) -> RResult<i64> {
     match v {
         Value::Struct { name, fields } => {
-            for (k, val) in fields {
+            for (k, val) in fields.iter() {
                 if k == "id" {
                     return match val {
                         Value::Int(i) => Ok(*i),
@@ -98,6 +103,9 @@ pub(crate) fn builtin_tcp_connect(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(host), Value::Int(port)] => {
             let addr = format!("{}:{}", host, port);
+            if let Err(denied) = crate::http_client::check_net("tcp_connect", &addr) {
+                return Ok(err(denied));
+            }
             match TcpStream::connect(addr.as_str()) {
                 Ok(stream) => {
                     let id = NEXT_TCP_CONN.fetch_add(1, Ordering::Relaxed);
@@ -121,6 +129,9 @@ pub(crate) fn builtin_tcp_listen(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(host), Value::Int(port)] => {
             let addr = format!("{}:{}", host, port);
+            if let Err(denied) = crate::http_client::check_net("tcp_listen", &addr) {
+                return Ok(err(denied));
+            }
             match TcpListener::bind(addr.as_str()) {
                 Ok(listener) => {
                     let id = NEXT_TCP_LISTENER.fetch_add(1, Ordering::Relaxed);
@@ -296,6 +307,9 @@ pub(crate) fn builtin_udp_bind(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::String(host), Value::Int(port)] => {
             let addr = format!("{}:{}", host, port);
+            if let Err(denied) = crate::http_client::check_net("udp_bind", &addr) {
+                return Ok(err(denied));
+            }
             match UdpSocket::bind(addr.as_str()) {
                 Ok(socket) => {
                     let id = NEXT_UDP.fetch_add(1, Ordering::Relaxed);
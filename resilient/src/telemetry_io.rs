@@ -0,0 +1,842 @@
+//! MQTT and serial-port builtins for telemetry (std-only, behind the
+//! `telemetry` feature).
+//!
+//! - `mqtt_publish(broker, topic, payload[, timeout_ms])` sends one
+//!   QoS 0 message;
+//! - `mqtt_subscribe(broker, topic[, timeout_ms])` waits for the next
+//!   message on `topic` and returns it as an `MqttMessage` struct
+//!   (`topic`, `payload`);
+//! - `serial_open(path[, baud])` opens a serial device as a
+//!   `SerialPort` handle, setting the line to raw mode at `baud` with
+//!   `stty` when one is given;
+//! - `serial_read(port, max_bytes[, timeout_ms])` returns what the
+//!   device has sent, as text, once at least one byte arrived;
+//! - `serial_write(port, data)` returns the number of bytes written;
+//! - `serial_close(port)` releases the handle.
+//!
+//! Each MQTT call is a whole MQTT 3.1.1 session (connect, do the one
+//! thing, disconnect) over plain TCP, so there is no connection to
+//! keep alive between calls. Brokers are `host[:port]` or
+//! `mqtt://host[:port]`, port 1883 by default.
+//!
+//! Failures are typed like the HTTP builtins' (`mqtt_publish:
+//! Timeout: ...`), so `catch Timeout` matches them and a `live` block
+//! retries them:
+//!
+//! - `InvalidUrl` — a broker that is not `host[:port]`;
+//! - `NotFound` — no serial device at the path;
+//! - `PermissionDenied` — `--net none` (MQTT), `--fs` or the OS
+//!   (serial), or a capability `--allow` leaves out (`sandbox`);
+//! - `ConnectionFailed` — the broker is unreachable, refuses the
+//!   session or drops it; the serial line errors or is closed;
+//! - `Timeout` — the call's timeout or the enclosing `live ...
+//!   within` budget ran out;
+//! - `BadResponse` — the broker's reply is not MQTT;
+//! - `Unsupported` — a build without the `telemetry` feature, or a
+//!   target with no sockets or devices (wasm).
+//!
+//! Timeouts default to 30 s and are capped at what is left of the
+//! innermost `live ... within` deadline. A serial write has no timeout
+//! of its own, but fails with `Timeout` once that deadline has passed.
+
+use crate::Value;
+use crate::http_client::{DEFAULT_TIMEOUT_MS, failure, timeout_from_ms};
+use std::rc::Rc;
+use std::time::Duration;
+
+type RResult<T> = Result<T, String>;
+
+const DEFAULT_MQTT_PORT: u16 = 1883;
+
+/// Every variant the MQTT builtins fail with.
+pub(crate) const MQTT_FAILURES: &[&str] = &[
+    "InvalidUrl",
+    "PermissionDenied",
+    "ConnectionFailed",
+    "Timeout",
+    "BadResponse",
+    "Unsupported",
+];
+
+/// Every variant the serial builtins fail with.
+pub(crate) const SERIAL_FAILURES: &[&str] = &[
+    "NotFound",
+    "PermissionDenied",
+    "ConnectionFailed",
+    "Timeout",
+    "Unsupported",
+];
+
+fn string_arg<'a>(builtin: &str, what: &str, value: &'a Value) -> RResult<&'a str> {
+    match value {
+        Value::String(s) => Ok(s),
+        other => Err(format!("{builtin}: expected string {what}, got {other}")),
+    }
+}
+
+/// The optional trailing timeout argument, or the default.
+fn timeout_arg(builtin: &str, value: Option<&Value>) -> RResult<Duration> {
+    match value {
+        None => Ok(Duration::from_millis(DEFAULT_TIMEOUT_MS)),
+        Some(Value::Int(ms)) => timeout_from_ms(*ms, builtin),
+        Some(other) => Err(format!(
+            "{builtin}: expected integer timeout in milliseconds, got {other}"
+        )),
+    }
+}
+
+fn arity(builtin: &str, args: &[Value], min: usize, max: usize) -> RResult<()> {
+    if args.len() < min || args.len() > max {
+        return Err(format!(
+            "{builtin}: expected {min} to {max} arguments, got {}",
+            args.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Split `host[:port]` or `mqtt://host[:port]`.
+fn parse_broker(builtin: &str, broker: &str) -> RResult<(String, u16)> {
+    let invalid = || {
+        failure(
+            builtin,
+            "InvalidUrl",
+            format!("{broker:?} is not an MQTT broker address (host[:port])"),
+        )
+    };
+    let rest = broker.strip_prefix("mqtt://").unwrap_or(broker);
+    let rest = rest.strip_suffix('/').unwrap_or(rest);
+    if rest.is_empty() || rest.contains('/') || rest.contains("://") {
+        return Err(invalid());
+    }
+    match rest.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && !host.ends_with(':') => {
+            let port = port.parse::<u16>().map_err(|_| invalid())?;
+            Ok((host.to_string(), port))
+        }
+        Some(_) => Err(invalid()),
+        None => Ok((rest.to_string(), DEFAULT_MQTT_PORT)),
+    }
+}
+
+fn check_topic(builtin: &str, topic: &str, filter: bool) -> RResult<()> {
+    if topic.is_empty() {
+        return Err(format!("{builtin}: topic must not be empty"));
+    }
+    if !filter && topic.contains(['+', '#']) {
+        return Err(format!(
+            "{builtin}: cannot publish to wildcard topic {topic:?}"
+        ));
+    }
+    Ok(())
+}
+
+fn port_handle(id: i64) -> Value {
+    Value::Struct {
+        name: "SerialPort".to_string(),
+        fields: Rc::new(vec![("id".to_string(), Value::Int(id))]),
+    }
+}
+
+fn port_id(builtin: &str, value: &Value) -> RResult<i64> {
+    match value {
+        Value::Struct { name, fields } if name == "SerialPort" => fields
+            .iter()
+            .find_map(|(k, v)| match (k.as_str(), v) {
+                ("id", Value::Int(id)) => Some(*id),
+                _ => None,
+            })
+            .ok_or_else(|| format!("{builtin}: invalid SerialPort handle")),
+        other => Err(format!("{builtin}: expected SerialPort, got {other}")),
+    }
+}
+
+/// `mqtt_publish(broker, topic, payload[, timeout_ms])`
+pub(crate) fn builtin_mqtt_publish(args: &[Value]) -> RResult<Value> {
+    const NAME: &str = "mqtt_publish";
+    arity(NAME, args, 3, 4)?;
+    let broker = string_arg(NAME, "broker", &args[0])?;
+    let topic = string_arg(NAME, "topic", &args[1])?;
+    let payload = string_arg(NAME, "payload", &args[2])?;
+    let timeout = timeout_arg(NAME, args.get(3))?;
+    check_topic(NAME, topic, false)?;
+    let (host, port) = parse_broker(NAME, broker)?;
+    crate::http_client::check_net(NAME, broker)?;
+    host::mqtt_publish(NAME, &host, port, topic, payload.as_bytes(), timeout)?;
+    Ok(Value::Void)
+}
+
+/// `mqtt_subscribe(broker, topic[, timeout_ms]) -> MqttMessage`
+pub(crate) fn builtin_mqtt_subscribe(args: &[Value]) -> RResult<Value> {
+    const NAME: &str = "mqtt_subscribe";
+    arity(NAME, args, 2, 3)?;
+    let broker = string_arg(NAME, "broker", &args[0])?;
+    let topic = string_arg(NAME, "topic", &args[1])?;
+    let timeout = timeout_arg(NAME, args.get(2))?;
+    check_topic(NAME, topic, true)?;
+    let (host, port) = parse_broker(NAME, broker)?;
+    crate::http_client::check_net(NAME, broker)?;
+    let (topic, payload) = host::mqtt_subscribe(NAME, &host, port, topic, timeout)?;
+    Ok(Value::Struct {
+        name: "MqttMessage".to_string(),
+        fields: Rc::new(vec![
+            ("topic".to_string(), Value::String(topic)),
+            (
+                "payload".to_string(),
+                Value::String(String::from_utf8_lossy(&payload).into_owned()),
+            ),
+        ]),
+    })
+}
+
+/// `serial_open(path[, baud]) -> SerialPort`
+pub(crate) fn builtin_serial_open(args: &[Value]) -> RResult<Value> {
+    const NAME: &str = "serial_open";
+    arity(NAME, args, 1, 2)?;
+    let path = string_arg(NAME, "device path", &args[0])?;
+    let baud = match args.get(1) {
+        None => None,
+        Some(Value::Int(baud)) if *baud > 0 => Some(*baud),
+        Some(other) => {
+            return Err(format!(
+                "{NAME}: expected a positive integer baud rate, got {other}"
+            ));
+        }
+    };
+    crate::sandbox::require(NAME, crate::sandbox::Capability::Serial, path)?;
+    crate::fs_sandbox::check_policy(NAME, path, true)?;
+    host::serial_open(NAME, path, baud).map(port_handle)
+}
+
+/// `serial_read(port, max_bytes[, timeout_ms]) -> string`
+pub(crate) fn builtin_serial_read(args: &[Value]) -> RResult<Value> {
+    const NAME: &str = "serial_read";
+    arity(NAME, args, 2, 3)?;
+    let id = port_id(NAME, &args[0])?;
+    let max = match &args[1] {
+        Value::Int(n) if *n > 0 => *n as usize,
+        other => {
+            return Err(format!(
+                "{NAME}: expected a positive integer max_bytes, got {other}"
+            ));
+        }
+    };
+    let timeout = timeout_arg(NAME, args.get(2))?;
+    let bytes = host::serial_read(NAME, id, max, timeout)?;
+    Ok(Value::String(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+/// `serial_write(port, data) -> int`
+pub(crate) fn builtin_serial_write(args: &[Value]) -> RResult<Value> {
+    const NAME: &str = "serial_write";
+    arity(NAME, args, 2, 2)?;
+    let id = port_id(NAME, &args[0])?;
+    let data = string_arg(NAME, "data", &args[1])?;
+    host::serial_write(NAME, id, data.as_bytes())?;
+    Ok(Value::Int(data.len() as i64))
+}
+
+/// `serial_close(port) -> bool`: `false` when it was already closed.
+pub(crate) fn builtin_serial_close(args: &[Value]) -> RResult<Value> {
+    const NAME: &str = "serial_close";
+    arity(NAME, args, 1, 1)?;
+    let id = port_id(NAME, &args[0])?;
+    Ok(Value::Bool(host::serial_close(id)))
+}
+
+/// MQTT 3.1.1 packet encoding, kept apart from the sockets so it can
+/// be tested without a broker.
+#[cfg_attr(
+    not(all(feature = "telemetry", not(target_arch = "wasm32"))),
+    allow(dead_code)
+)]
+mod packet {
+    pub(super) const CONNACK: u8 = 0x20;
+    pub(super) const PUBLISH: u8 = 0x30;
+    pub(super) const SUBACK: u8 = 0x90;
+    pub(super) const DISCONNECT: [u8; 2] = [0xE0, 0x00];
+
+    /// The packet identifier of our one `SUBSCRIBE`.
+    const SUBSCRIBE_ID: u16 = 1;
+
+    fn push_str(out: &mut Vec<u8>, s: &[u8]) {
+        out.extend_from_slice(&(s.len() as u16).to_be_bytes());
+        out.extend_from_slice(s);
+    }
+
+    /// `first` plus the remaining-length varint, then `body`.
+    fn frame(first: u8, body: &[u8]) -> Vec<u8> {
+        let mut out = vec![first];
+        let mut len = body.len();
+        loop {
+            let mut byte = (len % 128) as u8;
+            len /= 128;
+            if len > 0 {
+                byte |= 0x80;
+            }
+            out.push(byte);
+            if len == 0 {
+                break;
+            }
+        }
+        out.extend_from_slice(body);
+        out
+    }
+
+    /// A clean-session `CONNECT` with keep-alive off: a session lasts
+    /// one call, and the call has its own timeout.
+    pub(super) fn connect(client_id: &str) -> Vec<u8> {
+        let mut body = Vec::new();
+        push_str(&mut body, b"MQTT");
+        body.extend_from_slice(&[4, 0x02, 0, 0]);
+        push_str(&mut body, client_id.as_bytes());
+        frame(0x10, &body)
+    }
+
+    /// A QoS 0 `PUBLISH`.
+    pub(super) fn publish(topic: &str, payload: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        push_str(&mut body, topic.as_bytes());
+        body.extend_from_slice(payload);
+        frame(PUBLISH, &body)
+    }
+
+    /// A `SUBSCRIBE` to one filter at QoS 0.
+    pub(super) fn subscribe(filter: &str) -> Vec<u8> {
+        let mut body = SUBSCRIBE_ID.to_be_bytes().to_vec();
+        push_str(&mut body, filter.as_bytes());
+        body.push(0);
+        frame(0x82, &body)
+    }
+
+    /// Decode a remaining length from its varint bytes, one at a time.
+    /// `None` once a fifth byte is asked for.
+    pub(super) struct Length {
+        value: usize,
+        shift: u32,
+    }
+
+    impl Length {
+        pub(super) fn new() -> Length {
+            Length { value: 0, shift: 0 }
+        }
+
+        /// Feed one byte: `Some(Some(len))` when complete, `Some(None)`
+        /// when more follow, `None` when malformed.
+        pub(super) fn push(&mut self, byte: u8) -> Option<Option<usize>> {
+            if self.shift >= 28 {
+                return None;
+            }
+            self.value |= ((byte & 0x7F) as usize) << self.shift;
+            self.shift += 7;
+            Some((byte & 0x80 == 0).then_some(self.value))
+        }
+    }
+
+    /// The topic and payload of a `PUBLISH` body with header flags
+    /// `flags`, or `None` if it is malformed.
+    pub(super) fn parse_publish(flags: u8, body: &[u8]) -> Option<(String, Vec<u8>)> {
+        let len = u16::from_be_bytes([*body.first()?, *body.get(1)?]) as usize;
+        let topic = body.get(2..2 + len)?;
+        let mut rest = 2 + len;
+        // QoS 1 and 2 messages carry a packet identifier.
+        if flags & 0x06 != 0 {
+            rest += 2;
+        }
+        let payload = body.get(rest..)?;
+        Some((
+            String::from_utf8_lossy(topic).into_owned(),
+            payload.to_vec(),
+        ))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn decode(bytes: &[u8]) -> Option<usize> {
+            let mut length = Length::new();
+            for &b in bytes {
+                if let Some(len) = length.push(b)? {
+                    return Some(len);
+                }
+            }
+            None
+        }
+
+        #[test]
+        fn remaining_length_round_trips_across_varint_widths() {
+            for len in [0, 127, 128, 16_383, 16_384, 2_097_152] {
+                let framed = frame(0x30, &vec![0; len]);
+                assert_eq!(decode(&framed[1..]), Some(len), "len {len}");
+            }
+            assert_eq!(decode(&[0x80, 0x80, 0x80, 0x80, 0x01]), None);
+        }
+
+        #[test]
+        fn publish_packet_layout() {
+            assert_eq!(
+                publish("a/b", b"hi"),
+                [0x30, 7, 0, 3, b'a', b'/', b'b', b'h', b'i']
+            );
+            assert_eq!(
+                parse_publish(0x00, &publish("a/b", b"hi")[2..]),
+                Some(("a/b".to_string(), b"hi".to_vec()))
+            );
+            // QoS 1: the packet identifier is not part of the payload.
+            assert_eq!(
+                parse_publish(0x02, &[0, 1, b't', 0, 9, b'x']),
+                Some(("t".to_string(), b"x".to_vec()))
+            );
+            assert_eq!(parse_publish(0x00, &[0, 5, b't']), None);
+        }
+
+        #[test]
+        fn connect_packet_is_a_clean_mqtt_311_session() {
+            let packet = connect("rz");
+            assert_eq!(
+                packet,
+                [
+                    0x10, 14, 0, 4, b'M', b'Q', b'T', b'T', 4, 0x02, 0, 0, 0, 2, b'r', b'z'
+                ]
+            );
+        }
+    }
+}
+
+#[cfg(all(feature = "telemetry", not(target_arch = "wasm32")))]
+mod host {
+    use super::packet;
+    use crate::http_client::{effective_timeout, failure, io_failure};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::io::{Read, Write};
+    use std::net::{TcpStream, ToSocketAddrs};
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+    use std::time::{Duration, Instant};
+
+    type RResult<T> = Result<T, String>;
+
+    /// One MQTT session, every step bounded by one deadline.
+    struct Session<'a> {
+        builtin: &'a str,
+        stream: TcpStream,
+        deadline: Instant,
+    }
+
+    impl<'a> Session<'a> {
+        fn open(builtin: &'a str, host: &str, port: u16, timeout: Duration) -> RResult<Self> {
+            let timeout = effective_timeout(builtin, timeout)?;
+            let deadline = Instant::now() + timeout;
+            let addr = format!("{host}:{port}");
+            let addrs = addr.to_socket_addrs().map_err(|e| {
+                failure(
+                    builtin,
+                    "ConnectionFailed",
+                    format!("could not resolve {addr}: {e}"),
+                )
+            })?;
+            let mut last_err = None;
+            let mut stream = None;
+            for sock in addrs {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() {
+                    break;
+                }
+                match TcpStream::connect_timeout(&sock, left) {
+                    Ok(s) => {
+                        stream = Some(s);
+                        break;
+                    }
+                    Err(e) => last_err = Some(e),
+                }
+            }
+            let stream = match (stream, last_err) {
+                (Some(s), _) => s,
+                (None, Some(e)) => {
+                    return Err(io_failure(builtin, &format!("connecting to {addr}"), &e));
+                }
+                (None, None) => {
+                    return Err(failure(
+                        builtin,
+                        "Timeout",
+                        format!("connecting to {addr} timed out"),
+                    ));
+                }
+            };
+            let mut session = Session {
+                builtin,
+                stream,
+                deadline,
+            };
+            session.send(&packet::connect(&client_id()))?;
+            let (first, body) = session.recv()?;
+            match (first, body.as_slice()) {
+                (packet::CONNACK, [_, 0]) => Ok(session),
+                (packet::CONNACK, [_, code]) => Err(failure(
+                    builtin,
+                    "ConnectionFailed",
+                    format!("{addr} refused the session (return code {code})"),
+                )),
+                _ => Err(failure(
+                    builtin,
+                    "BadResponse",
+                    format!("{addr} did not answer CONNECT with CONNACK"),
+                )),
+            }
+        }
+
+        /// Arm the socket's timeouts with what is left of the deadline.
+        fn arm(&self, doing: &str) -> RResult<()> {
+            let left = self.deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Err(failure(
+                    self.builtin,
+                    "Timeout",
+                    format!("{doing} timed out"),
+                ));
+            }
+            let set = self
+                .stream
+                .set_read_timeout(Some(left))
+                .and_then(|_| self.stream.set_write_timeout(Some(left)));
+            set.map_err(|e| io_failure(self.builtin, doing, &e))
+        }
+
+        fn send(&mut self, bytes: &[u8]) -> RResult<()> {
+            const DOING: &str = "writing to the broker";
+            self.arm(DOING)?;
+            self.stream
+                .write_all(bytes)
+                .map_err(|e| io_failure(self.builtin, DOING, &e))
+        }
+
+        fn read_exact(&mut self, buf: &mut [u8]) -> RResult<()> {
+            const DOING: &str = "waiting for the broker";
+            self.arm(DOING)?;
+            self.stream
+                .read_exact(buf)
+                .map_err(|e| io_failure(self.builtin, DOING, &e))
+        }
+
+        /// The next packet: its first byte and its body.
+        fn recv(&mut self) -> RResult<(u8, Vec<u8>)> {
+            let mut first = [0u8];
+            self.read_exact(&mut first)?;
+            let mut length = packet::Length::new();
+            let len = loop {
+                let mut byte = [0u8];
+                self.read_exact(&mut byte)?;
+                match length.push(byte[0]) {
+                    Some(Some(len)) => break len,
+                    Some(None) => {}
+                    None => {
+                        return Err(failure(
+                            self.builtin,
+                            "BadResponse",
+                            "malformed packet length from the broker",
+                        ));
+                    }
+                }
+            };
+            let mut body = vec![0u8; len];
+            self.read_exact(&mut body)?;
+            Ok((first[0], body))
+        }
+
+        /// Say goodbye; the call already did its work, so a broker
+        /// that hung up first is not an error.
+        fn close(mut self) {
+            let _ = self.stream.write_all(&packet::DISCONNECT);
+        }
+    }
+
+    /// A client id no other `rz` call shares, since a broker drops
+    /// the older of two sessions with the same id.
+    fn client_id() -> String {
+        static NEXT: AtomicI64 = AtomicI64::new(1);
+        format!(
+            "rz-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    pub(super) fn mqtt_publish(
+        builtin: &str,
+        host: &str,
+        port: u16,
+        topic: &str,
+        payload: &[u8],
+        timeout: Duration,
+    ) -> RResult<()> {
+        let mut session = Session::open(builtin, host, port, timeout)?;
+        session.send(&packet::publish(topic, payload))?;
+        session.close();
+        Ok(())
+    }
+
+    pub(super) fn mqtt_subscribe(
+        builtin: &str,
+        host: &str,
+        port: u16,
+        filter: &str,
+        timeout: Duration,
+    ) -> RResult<(String, Vec<u8>)> {
+        let mut session = Session::open(builtin, host, port, timeout)?;
+        session.send(&packet::subscribe(filter))?;
+        loop {
+            let (first, body) = session.recv()?;
+            match first & 0xF0 {
+                packet::SUBACK if body.get(2) == Some(&0x80) => {
+                    return Err(failure(
+                        builtin,
+                        "ConnectionFailed",
+                        format!("the broker refused the subscription to {filter:?}"),
+                    ));
+                }
+                packet::PUBLISH => {
+                    let message = packet::parse_publish(first & 0x0F, &body).ok_or_else(|| {
+                        failure(builtin, "BadResponse", "malformed PUBLISH from the broker")
+                    })?;
+                    session.close();
+                    return Ok(message);
+                }
+                // SUBACK granting the filter, or anything else the
+                // broker sends before the first message.
+                _ => {}
+            }
+        }
+    }
+
+    /// An open serial port. A reader thread owns a second handle to
+    /// the device and forwards what it reads, so a read can wait with a
+    /// timeout, which `File` cannot.
+    struct Port {
+        device: File,
+        incoming: Receiver<std::io::Result<Vec<u8>>>,
+        pending: Vec<u8>,
+    }
+
+    static NEXT_PORT: AtomicI64 = AtomicI64::new(1);
+
+    thread_local! {
+        static PORTS: RefCell<HashMap<i64, Port>> = RefCell::new(HashMap::new());
+    }
+
+    fn open_failure(builtin: &str, path: &str, e: &std::io::Error) -> String {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => failure(builtin, "NotFound", format!("{path}: {e}")),
+            std::io::ErrorKind::PermissionDenied => {
+                failure(builtin, "PermissionDenied", format!("{path}: {e}"))
+            }
+            _ => failure(builtin, "ConnectionFailed", format!("{path}: {e}")),
+        }
+    }
+
+    /// Put the line in raw mode at `baud`. std has no termios, so this
+    /// goes through `stty` like a shell script would.
+    fn set_baud(builtin: &str, path: &str, baud: i64) -> RResult<()> {
+        let device_flag = if cfg!(target_os = "linux") {
+            "-F"
+        } else {
+            "-f"
+        };
+        let output = std::process::Command::new("stty")
+            .args([device_flag, path, &baud.to_string(), "raw", "-echo"])
+            .output();
+        let detail = match output {
+            Ok(out) if out.status.success() => return Ok(()),
+            Ok(out) => String::from_utf8_lossy(&out.stderr).trim().to_string(),
+            Err(e) => format!("cannot run stty: {e}"),
+        };
+        Err(failure(
+            builtin,
+            "ConnectionFailed",
+            format!("{path}: cannot set {baud} baud: {detail}"),
+        ))
+    }
+
+    pub(super) fn serial_open(builtin: &str, path: &str, baud: Option<i64>) -> RResult<i64> {
+        let device = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(|e| open_failure(builtin, path, &e))?;
+        if let Some(baud) = baud {
+            set_baud(builtin, path, baud)?;
+        }
+        let mut reader = device
+            .try_clone()
+            .map_err(|e| open_failure(builtin, path, &e))?;
+        let (tx, incoming) = mpsc::channel();
+        // The thread ends when the device reports end of input or an
+        // error, or on the first read after the port is closed.
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                let chunk = match reader.read(&mut buf) {
+                    Ok(0) => return,
+                    Ok(n) => Ok(buf[..n].to_vec()),
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(e) => Err(e),
+                };
+                let failed = chunk.is_err();
+                if tx.send(chunk).is_err() || failed {
+                    return;
+                }
+            }
+        });
+        let id = NEXT_PORT.fetch_add(1, Ordering::Relaxed);
+        PORTS.with(|ports| {
+            ports.borrow_mut().insert(
+                id,
+                Port {
+                    device,
+                    incoming,
+                    pending: Vec::new(),
+                },
+            )
+        });
+        Ok(id)
+    }
+
+    fn with_port<T>(builtin: &str, id: i64, f: impl FnOnce(&mut Port) -> RResult<T>) -> RResult<T> {
+        PORTS.with(|ports| match ports.borrow_mut().get_mut(&id) {
+            Some(port) => f(port),
+            None => Err(format!("{builtin}: unknown or closed serial port {id}")),
+        })
+    }
+
+    pub(super) fn serial_read(
+        builtin: &str,
+        id: i64,
+        max: usize,
+        timeout: Duration,
+    ) -> RResult<Vec<u8>> {
+        let timeout = effective_timeout(builtin, timeout)?;
+        with_port(builtin, id, |port| {
+            let closed = || failure(builtin, "ConnectionFailed", "the device closed the line");
+            if port.pending.is_empty() {
+                match port.incoming.recv_timeout(timeout) {
+                    Ok(Ok(chunk)) => port.pending = chunk,
+                    Ok(Err(e)) => return Err(io_failure(builtin, "reading the port", &e)),
+                    Err(RecvTimeoutError::Timeout) => {
+                        return Err(failure(
+                            builtin,
+                            "Timeout",
+                            format!("no data within {} ms", timeout.as_millis()),
+                        ));
+                    }
+                    Err(RecvTimeoutError::Disconnected) => return Err(closed()),
+                }
+            }
+            // Take whatever else has already arrived, up to `max`.
+            while port.pending.len() < max {
+                match port.incoming.try_recv() {
+                    Ok(Ok(chunk)) => port.pending.extend_from_slice(&chunk),
+                    _ => break,
+                }
+            }
+            let take = max.min(port.pending.len());
+            Ok(port.pending.drain(..take).collect())
+        })
+    }
+
+    pub(super) fn serial_write(builtin: &str, id: i64, data: &[u8]) -> RResult<()> {
+        effective_timeout(builtin, Duration::MAX)?;
+        with_port(builtin, id, |port| {
+            port.device
+                .write_all(data)
+                .and_then(|_| port.device.flush())
+                .map_err(|e| io_failure(builtin, "writing the port", &e))
+        })
+    }
+
+    pub(super) fn serial_close(id: i64) -> bool {
+        PORTS.with(|ports| ports.borrow_mut().remove(&id).is_some())
+    }
+}
+
+#[cfg(not(all(feature = "telemetry", not(target_arch = "wasm32"))))]
+mod host {
+    use crate::http_client::failure;
+    use std::time::Duration;
+
+    type RResult<T> = Result<T, String>;
+
+    fn unsupported(builtin: &str) -> String {
+        let why = if cfg!(target_arch = "wasm32") {
+            "no sockets or serial devices on this target"
+        } else {
+            "this rz was built without the `telemetry` feature"
+        };
+        failure(builtin, "Unsupported", why)
+    }
+
+    pub(super) fn mqtt_publish(
+        builtin: &str,
+        _host: &str,
+        _port: u16,
+        _topic: &str,
+        _payload: &[u8],
+        _timeout: Duration,
+    ) -> RResult<()> {
+        Err(unsupported(builtin))
+    }
+
+    pub(super) fn mqtt_subscribe(
+        builtin: &str,
+        _host: &str,
+        _port: u16,
+        _filter: &str,
+        _timeout: Duration,
+    ) -> RResult<(String, Vec<u8>)> {
+        Err(unsupported(builtin))
+    }
+
+    pub(super) fn serial_open(builtin: &str, _path: &str, _baud: Option<i64>) -> RResult<i64> {
+        Err(unsupported(builtin))
+    }
+
+    pub(super) fn serial_read(
+        builtin: &str,
+        _id: i64,
+        _max: usize,
+        _timeout: Duration,
+    ) -> RResult<Vec<u8>> {
+        Err(unsupported(builtin))
+    }
+
+    pub(super) fn serial_write(builtin: &str, _id: i64, _data: &[u8]) -> RResult<()> {
+        Err(unsupported(builtin))
+    }
+
+    pub(super) fn serial_close(_id: i64) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn broker_addresses() {
+        let b = "mqtt_publish";
+        assert_eq!(
+            parse_broker(b, "localhost").unwrap(),
+            ("localhost".to_string(), 1883)
+        );
+        assert_eq!(
+            parse_broker(b, "mqtt://10.0.0.2:1884/").unwrap(),
+            ("10.0.0.2".to_string(), 1884)
+        );
+        for bad in ["", "http://host", "host:port", "host/x", ":1883"] {
+            let err = parse_broker(b, bad).unwrap_err();
+            assert!(err.starts_with("mqtt_publish: InvalidUrl: "), "{err}");
+        }
+    }
+}
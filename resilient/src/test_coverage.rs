@@ -0,0 +1,496 @@
+//! `rz test --coverage`: which lines, branches and asserts the tests
+//! ran.
+//!
+//! While coverage is on, the tree-walking interpreter counts every
+//! statement it runs and, for each `if`, which way it went. After the
+//! suite, `rz test` parses each file the tests ran — the test files
+//! and the files they `use` — for its sites:
+//!
+//! - a **line** for every statement,
+//! - two **branches** for every `if`: the condition held, or it didn't
+//!   (whether or not there is an `else`),
+//! - an **assert site** for every `assert(...)` statement,
+//!
+//! and writes two reports into the coverage directory: `lcov.info`
+//! (`DA` lines and `BRDA` branches, for editors and CI tools) and
+//! `index.html`, which shows each file with its hit counts and marks
+//! the lines, branches and asserts that never ran.
+//!
+//! Spans don't record which file they came from, so a site is matched
+//! by the function it is in and its line and column. Function names
+//! are unique across a program and the files it `use`s; top-level
+//! statements of two files at the same line and column share a count.
+//!
+//! The counters are per thread, like `call_trace`: tests run on the
+//! thread that called [`start`], whose interpreters register
+//! [`Observer`] to count (see `eval_observer`).
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::Node;
+use crate::eval_observer::{EvalObserver, Scope};
+use crate::span::Span;
+
+/// Where a statement is: its function (`""` at the top level), line
+/// and column.
+type Site = (String, usize, usize);
+
+/// Counts per function, keyed by `(line, column)` and, for branches,
+/// whether the condition held.
+#[derive(Default)]
+struct Hits {
+    statements: HashMap<String, HashMap<(usize, usize), u64>>,
+    branches: HashMap<String, HashMap<(usize, usize, bool), u64>>,
+}
+
+impl Hits {
+    fn statement(&self, (function, line, column): &Site) -> u64 {
+        self.statements
+            .get(function)
+            .and_then(|m| m.get(&(*line, *column)))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn branch(&self, (function, line, column): &Site, held: bool) -> u64 {
+        self.branches
+            .get(function)
+            .and_then(|m| m.get(&(*line, *column, held)))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Bump `key` in `function`'s counts, allocating the name only the
+/// first time it is seen.
+fn bump<K: std::hash::Hash + Eq>(
+    map: &mut HashMap<String, HashMap<K, u64>>,
+    function: &str,
+    key: K,
+) {
+    if !map.contains_key(function) {
+        map.insert(function.to_string(), HashMap::new());
+    }
+    if let Some(counts) = map.get_mut(function) {
+        *counts.entry(key).or_default() += 1;
+    }
+}
+
+thread_local! {
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+    static HITS: RefCell<Hits> = RefCell::new(Hits::default());
+}
+
+/// Start counting on this thread, from zero.
+pub(crate) fn start() {
+    HITS.with(|h| *h.borrow_mut() = Hits::default());
+    ACTIVE.with(|a| a.set(true));
+}
+
+/// Stop counting; the counts stay for [`report`].
+pub(crate) fn stop() {
+    ACTIVE.with(|a| a.set(false));
+}
+
+/// Whether coverage is being counted on this thread.
+#[inline]
+pub(crate) fn active() -> bool {
+    ACTIVE.with(|a| a.get())
+}
+
+/// Counts the statements and branches of the run.
+pub(crate) struct Observer;
+
+impl EvalObserver for Observer {
+    fn node_enter(&mut self, node: &Node, _scope: &Scope<'_>, function: &str) {
+        at_statement(node, function);
+    }
+
+    fn branch(&mut self, line: usize, column: usize, function: &str, held: bool) {
+        at_branch(line, column, function, held);
+    }
+}
+
+/// Called before the interpreter runs the statement `node` in
+/// `function` (`""` at the top level).
+fn at_statement(node: &Node, function: &str) {
+    if let Some(span) = statement_span(node) {
+        let key = (span.start.line, span.start.column);
+        HITS.with(|h| bump(&mut h.borrow_mut().statements, function, key));
+    }
+}
+
+/// Called when the `if` at `line`:`column` in `function` took the
+/// branch `held`.
+fn at_branch(line: usize, column: usize, function: &str, held: bool) {
+    let key = (line, column, held);
+    HITS.with(|h| bump(&mut h.borrow_mut().branches, function, key));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SiteKind {
+    Statement,
+    Assert,
+    If,
+}
+
+/// One file's sites with the counts of the run.
+struct FileCoverage {
+    path: PathBuf,
+    source: String,
+    /// Hits per line; a line with several statements counts the most
+    /// run of them.
+    lines: BTreeMap<usize, u64>,
+    /// `(line, then count, else count)` per `if`, `None` when the `if`
+    /// never ran.
+    branches: Vec<(usize, Option<(u64, u64)>)>,
+    /// `(line, hits)` per assert.
+    asserts: Vec<(usize, u64)>,
+}
+
+/// Totals over a set of files: `(hit, found)` for lines, branches and
+/// asserts.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Summary {
+    pub(crate) lines: (usize, usize),
+    pub(crate) branches: (usize, usize),
+    pub(crate) asserts: (usize, usize),
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let part = |(hit, found): (usize, usize)| {
+            if found == 0 {
+                format!("{hit}/{found}")
+            } else {
+                format!("{hit}/{found} ({:.1}%)", hit as f64 * 100.0 / found as f64)
+            }
+        };
+        write!(
+            f,
+            "lines {}, branches {}, asserts {}",
+            part(self.lines),
+            part(self.branches),
+            part(self.asserts)
+        )
+    }
+}
+
+/// Write `lcov.info` and `index.html` for `files` (absolute paths, as
+/// lcov wants them) into `dir`, with the counts gathered since
+/// [`start`]. Files that can't be read or parsed are left out.
+pub(crate) fn report(files: &[PathBuf], dir: &Path) -> Result<Summary, String> {
+    let files: Vec<FileCoverage> = HITS.with(|h| {
+        let hits = h.borrow();
+        files
+            .iter()
+            .filter_map(|path| {
+                let source = std::fs::read_to_string(path).ok()?;
+                let (program, errors) = crate::parse(&source);
+                errors
+                    .is_empty()
+                    .then(|| file_coverage(path, source, &program, &hits))
+            })
+            .collect()
+    });
+    std::fs::create_dir_all(dir).map_err(|e| format!("could not create {}: {e}", dir.display()))?;
+    for (name, text) in [("lcov.info", lcov(&files)), ("index.html", html(&files))] {
+        let path = dir.join(name);
+        std::fs::write(&path, text)
+            .map_err(|e| format!("could not write {}: {e}", path.display()))?;
+    }
+    Ok(summary(&files))
+}
+
+fn file_coverage(path: &Path, source: String, program: &Node, hits: &Hits) -> FileCoverage {
+    let mut lines = BTreeMap::new();
+    let mut branches = Vec::new();
+    let mut asserts = Vec::new();
+    for (site, kind) in sites(program) {
+        let line = site.1;
+        let count = hits.statement(&site);
+        let entry = lines.entry(line).or_insert(0);
+        *entry = (*entry).max(count);
+        match kind {
+            SiteKind::Statement => {}
+            SiteKind::Assert => asserts.push((line, count)),
+            SiteKind::If => {
+                let taken = (hits.branch(&site, true), hits.branch(&site, false));
+                branches.push((line, (count > 0).then_some(taken)));
+            }
+        }
+    }
+    FileCoverage {
+        path: path.to_path_buf(),
+        source,
+        lines,
+        branches,
+        asserts,
+    }
+}
+
+/// Every statement the interpreter reports, in source order.
+fn sites(program: &Node) -> Vec<(Site, SiteKind)> {
+    let mut out = Vec::new();
+    let Node::Program(stmts) = program else {
+        return out;
+    };
+    for stmt in stmts {
+        match &stmt.node {
+            Node::Function { name, body, .. } => add_sites_in(body, name, &mut out),
+            Node::TestFn { function, .. } => {
+                if let Node::Function { name, body, .. } = function.as_ref() {
+                    add_sites_in(body, name, &mut out);
+                }
+            }
+            Node::ImplBlock { methods, .. } => {
+                for method in methods {
+                    if let Node::Function { name, body, .. } = method {
+                        add_sites_in(body, name, &mut out);
+                    }
+                }
+            }
+            other => {
+                add_site(other, "", &mut out);
+                add_sites_in(other, "", &mut out);
+            }
+        }
+    }
+    out.sort_by_key(|(site, _)| (site.1, site.2));
+    out
+}
+
+/// The statements of every block in `node`, which runs in `function`.
+fn add_sites_in(node: &Node, function: &str, out: &mut Vec<(Site, SiteKind)>) {
+    crate::lint::walk_nodes(node, &mut |node| {
+        if let Node::Block { stmts, .. } = node {
+            for stmt in stmts {
+                add_site(stmt, function, out);
+            }
+        }
+    });
+}
+
+fn add_site(stmt: &Node, function: &str, out: &mut Vec<(Site, SiteKind)>) {
+    let Some(span) = statement_span(stmt) else {
+        return;
+    };
+    let kind = match stmt {
+        Node::Assert { .. } => SiteKind::Assert,
+        Node::ExpressionStatement { expr, .. } if matches!(**expr, Node::Assert { .. }) => {
+            SiteKind::Assert
+        }
+        Node::IfStatement { .. } => SiteKind::If,
+        _ => SiteKind::Statement,
+    };
+    let site = (function.to_string(), span.start.line, span.start.column);
+    out.push((site, kind));
+}
+
+/// The span of a statement the interpreter counts, when the parser
+/// recorded one.
+fn statement_span(node: &Node) -> Option<&Span> {
+    let span = match node {
+        Node::LetStatement { span, .. }
+        | Node::StaticLet { span, .. }
+        | Node::Const { span, .. }
+        | Node::Assignment { span, .. }
+        | Node::ReturnStatement { span, .. }
+        | Node::Break { span }
+        | Node::Continue { span }
+        | Node::IfStatement { span, .. }
+        | Node::WhileStatement { span, .. }
+        | Node::ForInStatement { span, .. }
+        | Node::FieldAssignment { span, .. }
+        | Node::IndexAssignment { span, .. }
+        | Node::LiveBlock { span, .. }
+        | Node::Assert { span, .. }
+        | Node::ExpressionStatement { span, .. } => span,
+        _ => return None,
+    };
+    (span.start.line > 0).then_some(span)
+}
+
+fn summary(files: &[FileCoverage]) -> Summary {
+    let mut s = Summary::default();
+    for f in files {
+        let (lines, branches, asserts) = file_summary(f);
+        s.lines.0 += lines.0;
+        s.lines.1 += lines.1;
+        s.branches.0 += branches.0;
+        s.branches.1 += branches.1;
+        s.asserts.0 += asserts.0;
+        s.asserts.1 += asserts.1;
+    }
+    s
+}
+
+type Counts = (usize, usize);
+
+fn file_summary(f: &FileCoverage) -> (Counts, Counts, Counts) {
+    let lines = (f.lines.values().filter(|c| **c > 0).count(), f.lines.len());
+    let branches_hit = f
+        .branches
+        .iter()
+        .map(|(_, taken)| taken.map_or(0, |(t, e)| (t > 0) as usize + (e > 0) as usize))
+        .sum();
+    let branches = (branches_hit, f.branches.len() * 2);
+    let asserts = (
+        f.asserts.iter().filter(|(_, c)| *c > 0).count(),
+        f.asserts.len(),
+    );
+    (lines, branches, asserts)
+}
+
+fn lcov(files: &[FileCoverage]) -> String {
+    let mut out = String::new();
+    for f in files {
+        let _ = writeln!(out, "TN:\nSF:{}", f.path.display());
+        let mut block_on_line: HashMap<usize, usize> = HashMap::new();
+        for (line, taken) in &f.branches {
+            let block = block_on_line.entry(*line).or_insert(0);
+            for (branch, count) in [(0, taken.map(|t| t.0)), (1, taken.map(|t| t.1))] {
+                let count = count.map_or("-".to_string(), |c| c.to_string());
+                let _ = writeln!(out, "BRDA:{line},{block},{branch},{count}");
+            }
+            *block += 1;
+        }
+        let (lines, branches, _) = file_summary(f);
+        let _ = writeln!(out, "BRF:{}\nBRH:{}", branches.1, branches.0);
+        for (line, count) in &f.lines {
+            let _ = writeln!(out, "DA:{line},{count}");
+        }
+        let _ = writeln!(out, "LF:{}\nLH:{}\nend_of_record", lines.1, lines.0);
+    }
+    out
+}
+
+fn html(files: &[FileCoverage]) -> String {
+    let total = summary(files);
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Resilient test coverage</title>\n<style>\n\
+         body { font-family: sans-serif; }\n\
+         table.src { border-collapse: collapse; font-family: monospace; }\n\
+         table.src td { padding: 0 0.5em; white-space: pre; }\n\
+         td.n, td.c { text-align: right; color: #666; }\n\
+         tr.hit { background: #e6ffed; }\n\
+         tr.miss { background: #ffeef0; }\n\
+         span.note { color: #a00; }\n\
+         </style>\n</head>\n<body>\n<h1>Test coverage</h1>\n",
+    );
+    let _ = writeln!(out, "<p>{}</p>\n<ul>", escape(&total.to_string()));
+    for (i, f) in files.iter().enumerate() {
+        let s = summary(std::slice::from_ref(f));
+        let _ = writeln!(
+            out,
+            "<li><a href=\"#f{i}\">{}</a>: {}</li>",
+            escape(&display(&f.path)),
+            escape(&s.to_string())
+        );
+    }
+    out.push_str("</ul>\n");
+    for (i, f) in files.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "<h2 id=\"f{i}\">{}</h2>\n<table class=\"src\">",
+            escape(&display(&f.path))
+        );
+        for (idx, text) in f.source.lines().enumerate() {
+            let line = idx + 1;
+            let (class, count) = match f.lines.get(&line) {
+                Some(0) => ("miss", "0".to_string()),
+                Some(c) => ("hit", c.to_string()),
+                None => ("", String::new()),
+            };
+            let mut notes = Vec::new();
+            for (_, taken) in f.branches.iter().filter(|(l, _)| *l == line) {
+                match taken {
+                    Some((t, e)) if *t == 0 || *e == 0 => {
+                        let missed = if *t == 0 { "true" } else { "false" };
+                        notes.push(format!("condition never {missed}"));
+                    }
+                    _ => {}
+                }
+            }
+            if f.asserts.iter().any(|(l, c)| *l == line && *c == 0) {
+                notes.push("assert never checked".to_string());
+            }
+            let note = if notes.is_empty() {
+                String::new()
+            } else {
+                format!(" <span class=\"note\">← {}</span>", notes.join("; "))
+            };
+            let class = if !notes.is_empty() { "miss" } else { class };
+            let _ = writeln!(
+                out,
+                "<tr class=\"{class}\"><td class=\"n\">{line}</td><td class=\"c\">{count}</td>\
+                 <td>{}{note}</td></tr>",
+                escape(text)
+            );
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// `path` relative to the working directory when it is below it.
+fn display(path: &Path) -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sites_are_counted_per_line_branch_and_assert() {
+        let src = "fn f(int x) -> int {\n    if x > 0 {\n        return 1;\n    }\n    assert(x == 0, \"zero\");\n    return 0;\n}\nf(1);\n";
+        let (program, errors) = crate::parse(src);
+        assert!(errors.is_empty(), "{errors:?}");
+        start();
+        crate::Interpreter::new().eval(&program).unwrap();
+        stop();
+        let f = HITS.with(|h| file_coverage(Path::new("t.rz"), src.into(), &program, &h.borrow()));
+
+        assert_eq!(
+            f.lines.iter().map(|(l, c)| (*l, *c)).collect::<Vec<_>>(),
+            [(2, 1), (3, 1), (5, 0), (6, 0), (8, 1)]
+        );
+        assert_eq!(f.branches, [(2, Some((1, 0)))]);
+        assert_eq!(f.asserts, [(5, 0)]);
+        assert_eq!(
+            summary(std::slice::from_ref(&f)),
+            Summary {
+                lines: (3, 5),
+                branches: (1, 2),
+                asserts: (0, 1),
+            }
+        );
+        let lcov = lcov(std::slice::from_ref(&f));
+        assert!(lcov.contains("BRDA:2,0,0,1\nBRDA:2,0,1,0\n"), "{lcov}");
+        assert!(
+            lcov.contains("DA:5,0\n") && lcov.contains("LF:5\nLH:3\n"),
+            "{lcov}"
+        );
+        let html = html(std::slice::from_ref(&f));
+        assert!(html.contains("condition never false"), "{html}");
+        assert!(html.contains("assert never checked"), "{html}");
+    }
+}
@@ -1,71 +1,103 @@
-//! `rz test` subcommand — discover and run `fn test_*()` functions.
+//! `rz test` subcommand — discover and run `test fn` declarations and
+//! `fn test_*()` functions.
 //!
-//! Test discovery walks the parsed AST for top-level `Function` nodes
-//! whose name starts with `test_` and that take zero parameters.  Each
-//! test runs in an isolated `Interpreter` scope with stdlib bindings
-//! injected (so `use std::testing; testing::assert_eq(...)` works).
-//! A runtime error or assertion failure counts as a test failure.
+//! Test discovery walks the parsed AST for top-level `test fn name()`
+//! declarations (`Node::TestFn`, parsed by [`parse_test_fn`]) and for
+//! `Function` nodes whose name starts with `test_` and that take zero
+//! parameters.  Each test runs in an isolated `Interpreter` scope with
+//! stdlib bindings injected (so `use std::testing; testing::assert_eq(...)`
+//! works) and with the `std::testing` assertions also bound unqualified
+//! (`assert_eq`, `assert_ne`, `assert_true`, `assert_false`, `fail`).
+//! A runtime error or assertion failure counts as a test failure; each
+//! result line reports how long the test took.
+//!
+//! `--coverage[=DIR]` counts which lines, branches and asserts the
+//! tests ran and writes `lcov.info` and `index.html` into `DIR`
+//! (`coverage` by default); see `test_coverage`.
+//!
+//! `--snapshot` compares each file's stdout with its `.snap` file
+//! instead of running tests (`--update` rewrites them); see
+//! `test_snapshot`.
 //!
 //! All logic lives in this file; `lib.rs` contributes only a `mod`
-//! declaration and a dispatch call in `run_cli()`.
+//! declaration and a dispatch call in `run_cli()`; the flags are
+//! declared with the other subcommands' in `cli`.
 
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
-
-use crate::{Interpreter, Node, imports, output_sink, stdlib};
-
-/// Entry point called from `run_cli()`.  Returns `Some(exit_code)` when
-/// the first CLI arg is `"test"`, `None` otherwise (fall through).
-pub fn dispatch_test_subcommand(args: &[String]) -> Option<i32> {
-    if args.get(1).map(|s| s.as_str()) != Some("test") {
-        return None;
+use std::time::{Duration, Instant};
+
+use crate::cli::TestArgs;
+use crate::{
+    Interpreter, Node, Parser, imports, output_sink, stdlib, test_coverage, test_snapshot,
+};
+
+/// Parse a `test fn name() { body }` declaration. Entered with
+/// `current_token` on the `test` soft keyword and `fn` peeked.
+pub(crate) fn parse_test_fn(parser: &mut Parser) -> Node {
+    let span = parser.span_at_current();
+    parser.next_token();
+    let function = parser.parse_function();
+    if let Node::Function {
+        name, parameters, ..
+    } = &function
+        && !parameters.is_empty()
+    {
+        parser.record_error(format!("test fn `{name}` must not take parameters"));
+    }
+    Node::TestFn {
+        function: Box::new(function),
+        span,
     }
+}
 
-    let mut target: Option<String> = None;
-    let mut filter: Option<String> = None;
-    let mut i = 2;
-    while i < args.len() {
-        let a = &args[i];
-        if a == "--filter" {
-            i += 1;
-            if i >= args.len() {
-                eprintln!("Error: --filter requires a substring argument");
-                return Some(2);
-            }
-            filter = Some(args[i].clone());
-        } else if let Some(f) = a.strip_prefix("--filter=") {
-            filter = Some(f.to_string());
-        } else if a == "--help" || a == "-h" || a == "help" {
-            print_test_help();
-            return Some(0);
-        } else if target.is_none() {
-            target = Some(a.clone());
-        } else {
-            eprintln!("Error: unexpected argument `{a}` to test");
-            return Some(2);
-        }
-        i += 1;
+/// Entry point called from `run_cli()` with the parsed `rz test`
+/// arguments; returns the exit code.
+pub(crate) fn run_test_subcommand(test: TestArgs) -> i32 {
+    let TestArgs {
+        target,
+        filter,
+        coverage,
+        snapshot,
+        update,
+    } = test;
+    if update && !snapshot {
+        eprintln!("Error: --update only applies to --snapshot");
+        return 2;
+    }
+    if snapshot && coverage.is_some() {
+        eprintln!("Error: --coverage cannot be combined with --snapshot");
+        return 2;
     }
 
     let paths = match resolve_target(target.as_deref()) {
         Ok(p) => p,
         Err(e) => {
             eprintln!("Error: {e}");
-            return Some(2);
+            return 2;
         }
     };
 
     if paths.is_empty() {
         eprintln!("No .rz files found");
-        return Some(1);
+        return 1;
+    }
+    if snapshot {
+        return test_snapshot::run(&paths, filter.as_deref(), update);
     }
 
     let mut total = 0usize;
     let mut passed = 0usize;
     let mut failed = 0usize;
     let mut failure_details: Vec<String> = Vec::new();
+    let started = Instant::now();
+    // The files the tests ran, for the coverage report.
+    let mut sources: Vec<PathBuf> = Vec::new();
 
+    if coverage.is_some() {
+        test_coverage::start();
+    }
     for path in &paths {
         match run_tests_in_file(path, filter.as_deref()) {
             Ok(result) => {
@@ -73,6 +105,17 @@ pub fn dispatch_test_subcommand(args: &[String]) -> Option<i32> {
                 passed += result.passed;
                 failed += result.failed;
                 failure_details.extend(result.failure_details);
+                // A file without tests runs nothing to cover.
+                let ran = if result.total > 0 {
+                    result.sources
+                } else {
+                    Vec::new()
+                };
+                for source in ran {
+                    if !sources.contains(&source) {
+                        sources.push(source);
+                    }
+                }
             }
             Err(e) => {
                 eprintln!("Error processing {}: {e}", path.display());
@@ -93,29 +136,36 @@ pub fn dispatch_test_subcommand(args: &[String]) -> Option<i32> {
 
     println!();
     println!(
-        "{total} test{}: {passed} passed, {failed} failed",
-        if total == 1 { "" } else { "s" }
+        "{total} test{}: {passed} passed, {failed} failed ({})",
+        if total == 1 { "" } else { "s" },
+        format_duration(started.elapsed())
     );
 
-    if failed > 0 { Some(1) } else { Some(0) }
+    if let Some(dir) = coverage {
+        test_coverage::stop();
+        match test_coverage::report(&sources, &dir) {
+            Ok(summary) => {
+                println!("coverage: {summary}");
+                println!(
+                    "wrote {} and {}",
+                    dir.join("lcov.info").display(),
+                    dir.join("index.html").display()
+                );
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                return 1;
+            }
+        }
+    }
+
+    if failed > 0 { 1 } else { 0 }
 }
 
 // ── helpers ────────────────────────────────────────────────────────────
 
-fn print_test_help() {
-    println!("Usage: rz test [<file|dir>] [--filter <substring>]");
-    println!();
-    println!("Discover and run fn test_*() functions in .rz files.");
-    println!();
-    println!("Options:");
-    println!("  <file>              Run tests in a single .rz file");
-    println!("  <dir>               Discover all .rz files recursively");
-    println!("  (no argument)       Discover from the current directory");
-    println!("  --filter <substr>   Only run tests whose name contains <substr>");
-}
-
 /// Resolve the CLI target into a list of `.rz` file paths.
-fn resolve_target(target: Option<&str>) -> Result<Vec<PathBuf>, String> {
+pub(crate) fn resolve_target(target: Option<&str>) -> Result<Vec<PathBuf>, String> {
     let path = match target {
         Some(t) => PathBuf::from(t),
         None => {
@@ -157,20 +207,80 @@ struct FileTestResult {
     passed: usize,
     failed: usize,
     failure_details: Vec<String>,
+    /// The file and the files it `use`s, canonicalized.
+    sources: Vec<PathBuf>,
 }
 
-/// Parse one `.rz` file, discover `fn test_*()` functions, and run each
-/// in its own `Interpreter`.
+/// Parse one `.rz` file, discover its tests, and run each in its own
+/// `Interpreter`.
 fn run_tests_in_file(path: &Path, filter: Option<&str>) -> Result<FileTestResult, String> {
+    let prepared = prepare_file(path, parse_file(path)?)?;
+    let program = &prepared.program;
+    let std_bindings = &prepared.std_bindings;
+
+    // Discover test functions.
+    let tests = discover_tests(program, filter);
+    let prelude = testing_prelude();
+
+    let file_display = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_else(|| path.to_str().unwrap_or("?"));
+
+    let mut result = FileTestResult {
+        total: 0,
+        passed: 0,
+        failed: 0,
+        failure_details: Vec::new(),
+        sources: prepared.sources.clone(),
+    };
+
+    for test in &tests {
+        let test_name = &test.name;
+        result.total += 1;
+        let started = Instant::now();
+        let outcome = run_single_test(program, std_bindings, &prelude, test);
+        let took = format_duration(started.elapsed());
+        match outcome {
+            Ok(()) => {
+                println!("test {test_name} ... ok ({took})");
+                result.passed += 1;
+            }
+            Err(e) => {
+                println!("test {test_name} ... FAIL ({took})");
+                let detail = format!("  {file_display}: {test_name}: {e}");
+                result.failure_details.push(detail);
+                result.failed += 1;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Read and parse `path`.
+pub(crate) fn parse_file(path: &Path) -> Result<Node, String> {
     let src =
         fs::read_to_string(path).map_err(|e| format!("could not read {}: {e}", path.display()))?;
-    let (mut program, parse_errs) = crate::parse(&src);
-
+    let (program, parse_errs) = crate::parse(&src);
     if !parse_errs.is_empty() {
         let joined = parse_errs.join("\n");
         return Err(format!("parse errors in {}:\n{joined}", path.display()));
     }
+    Ok(program)
+}
+
+/// A test file's program with its imports resolved, ready to run.
+pub(crate) struct PreparedFile {
+    program: Node,
+    std_bindings: Vec<(String, stdlib::StdBinding)>,
+    /// The file and the files it `use`s, canonicalized.
+    sources: Vec<PathBuf>,
+}
 
+/// Resolve the imports of `program`, parsed from `path`, and lower it
+/// for running.
+pub(crate) fn prepare_file(path: &Path, mut program: Node) -> Result<PreparedFile, String> {
     // Resolve `use` imports (especially `use std::testing;`).
     let base_dir = path
         .parent()
@@ -198,87 +308,120 @@ fn run_tests_in_file(path: &Path, filter: Option<&str>) -> Result<FileTestResult
     // Re-run lowering passes that `parse()` already ran on the original
     // source but that need a second pass after `expand_uses` spliced in
     // imported definitions.
+    crate::overloads::lower_program(&mut program);
     let _ = crate::named_args::lower_program(&mut program);
     crate::default_params::lower_program(&mut program);
     crate::newtypes::lower_program(&mut program);
     crate::macros::lower_program(&mut program);
 
-    // Discover test functions.
-    let test_names = discover_tests(&program, filter);
-
-    let file_display = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or_else(|| path.to_str().unwrap_or("?"));
-
-    let mut result = FileTestResult {
-        total: 0,
-        passed: 0,
-        failed: 0,
-        failure_details: Vec::new(),
-    };
+    // `loaded` holds the canonical paths of the file and its imports.
+    let canon = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut imported: Vec<PathBuf> = loaded.into_iter().filter(|p| *p != canon).collect();
+    imported.sort();
+    let mut sources = vec![canon];
+    sources.extend(imported);
+    Ok(PreparedFile {
+        program,
+        std_bindings,
+        sources,
+    })
+}
 
-    for test_name in &test_names {
-        result.total += 1;
-        match run_single_test(&program, &std_bindings, test_name) {
-            Ok(()) => {
-                println!("test {test_name} ... ok");
-                result.passed += 1;
-            }
-            Err(e) => {
-                println!("test {test_name} ... FAIL");
-                let detail = format!("  {file_display}: {test_name}: {e}");
-                result.failure_details.push(detail);
-                result.failed += 1;
-            }
+/// Run every test of `file`, quietly, stopping at the first failure.
+/// Returns how many tests ran and the failing test's name and error.
+pub(crate) fn first_failure(file: &PreparedFile) -> (usize, Option<(String, String)>) {
+    let prelude = testing_prelude();
+    let tests = discover_tests(&file.program, None);
+    for (i, test) in tests.iter().enumerate() {
+        if let Err(e) = run_single_test(&file.program, &file.std_bindings, &prelude, test) {
+            return (i + 1, Some((test.name.clone(), e)));
         }
     }
+    (tests.len(), None)
+}
 
-    Ok(result)
+/// A discovered test.
+struct TestCase<'a> {
+    name: String,
+    /// The `Node::Function` of a `test fn`, defined only when the test
+    /// runs; `None` for a `fn test_*()` the program itself defines.
+    function: Option<&'a Node>,
 }
 
-/// Walk the top-level AST and collect names of `fn test_*()`
-/// (zero-parameter functions whose name starts with `test_`).
-fn discover_tests(program: &Node, filter: Option<&str>) -> Vec<String> {
+/// Walk the top-level AST and collect `test fn` declarations and
+/// `fn test_*()` functions (zero parameters, name starting with
+/// `test_`), in source order.
+fn discover_tests<'a>(program: &'a Node, filter: Option<&str>) -> Vec<TestCase<'a>> {
     let stmts = match program {
         Node::Program(stmts) => stmts,
         _ => return Vec::new(),
     };
 
-    let mut names = Vec::new();
+    let mut tests = Vec::new();
     for stmt in stmts {
-        if let Node::Function {
-            name, parameters, ..
-        } = &stmt.node
-            && name.starts_with("test_")
-            && parameters.is_empty()
+        let test = match &stmt.node {
+            Node::Function {
+                name, parameters, ..
+            } if name.starts_with("test_") && parameters.is_empty() => TestCase {
+                name: name.clone(),
+                function: None,
+            },
+            Node::TestFn { function, .. } => match function.as_ref() {
+                Node::Function { name, .. } => TestCase {
+                    name: name.clone(),
+                    function: Some(function.as_ref()),
+                },
+                _ => continue,
+            },
+            _ => continue,
+        };
+        if let Some(f) = filter
+            && !test.name.contains(f)
         {
-            if let Some(f) = filter
-                && !name.contains(f)
-            {
-                continue;
-            }
-            names.push(name.clone());
+            continue;
         }
+        tests.push(test);
     }
-    names
+    tests
 }
 
-/// Run a single test function by name.  Sets up a fresh `Interpreter`,
-/// evaluates the entire program (which hoists all `fn` defs), then
-/// calls the named test function with zero arguments.
+/// The `std::testing` functions under their bare names, so a test can
+/// call `assert_eq(a, b)` without `use std::testing;`.
+fn testing_prelude() -> Vec<(String, stdlib::StdBinding)> {
+    stdlib::resolve_std_import("testing", None)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, binding)| match name.strip_prefix("testing_") {
+            Some(bare) => (bare.to_string(), binding),
+            None => (name, binding),
+        })
+        .collect()
+}
+
+/// Run a single test.  Sets up a fresh `Interpreter` with the testing
+/// prelude, evaluates the entire program (which hoists all `fn` defs),
+/// defines the `test fn` if there is one, then calls the test function
+/// with zero arguments.
 fn run_single_test(
     program: &Node,
     std_bindings: &[(String, stdlib::StdBinding)],
-    test_name: &str,
+    prelude: &[(String, stdlib::StdBinding)],
+    test: &TestCase,
 ) -> Result<(), String> {
+    let test_name = &test.name;
     // Capture stdout so test println!s don't leak into the harness output.
+    crate::clear_exit_request();
     let (eval_result, _captured) = output_sink::with_captured_output(|| {
         let mut interp = Interpreter::new();
+        // The program's own definitions shadow the prelude.
+        stdlib::inject_std_bindings(prelude, &interp.env);
         stdlib::inject_std_bindings(std_bindings, &interp.env);
 
         // Evaluate the program to hoist all function definitions.
         interp.eval(program)?;
+        if let Some(function) = test.function {
+            interp.eval(function)?;
+        }
 
         // Look up the test function and call it.
         let func = interp
@@ -290,3 +433,13 @@ fn run_single_test(
     });
     eval_result
 }
+
+/// `0.4ms`, `12.0ms`, `1.25s`.
+pub(crate) fn format_duration(d: Duration) -> String {
+    let ms = d.as_secs_f64() * 1000.0;
+    if ms < 1000.0 {
+        format!("{ms:.1}ms")
+    } else {
+        format!("{:.2}s", ms / 1000.0)
+    }
+}
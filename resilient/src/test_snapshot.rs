@@ -0,0 +1,187 @@
+//! `rz test --snapshot` — golden-output tests for whole programs.
+//!
+//! Each `.rz` file is run in a child `rz` process (stdin closed) and
+//! its stdout is compared with the sibling `<stem>.snap` file. A
+//! missing or different snapshot fails with a line diff; `--update`
+//! writes the current output instead. Files with a sibling
+//! `<stem>.interactive` marker are skipped, as in the examples golden
+//! test.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Check (or with `update`, rewrite) the snapshot of every file in
+/// `paths` whose path contains `filter`. Returns the exit code.
+pub(crate) fn run(paths: &[PathBuf], filter: Option<&str>, update: bool) -> i32 {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("Error: resolve current executable: {e}");
+            return 2;
+        }
+    };
+
+    let mut total = 0usize;
+    let mut passed = 0usize;
+    let mut written = 0usize;
+    let mut failures: Vec<String> = Vec::new();
+    for path in paths {
+        if is_interactive(path) {
+            continue;
+        }
+        if let Some(f) = filter
+            && !path.to_string_lossy().contains(f)
+        {
+            continue;
+        }
+        total += 1;
+        let name = path.display();
+        let actual = match program_stdout(&exe, path) {
+            Ok(out) => out,
+            Err(e) => {
+                println!("snapshot {name} ... FAIL");
+                failures.push(format!("  {name}: {e}"));
+                continue;
+            }
+        };
+        let snap = snapshot_path(path);
+        let expected = fs::read_to_string(&snap).ok();
+        if expected.as_deref() == Some(actual.as_str()) {
+            println!("snapshot {name} ... ok");
+            passed += 1;
+            continue;
+        }
+        if update {
+            if let Err(e) = fs::write(&snap, &actual) {
+                println!("snapshot {name} ... FAIL");
+                failures.push(format!("  {}: cannot write: {e}", snap.display()));
+                continue;
+            }
+            let what = if expected.is_some() {
+                "updated"
+            } else {
+                "written"
+            };
+            println!("snapshot {name} ... {what}");
+            written += 1;
+            continue;
+        }
+        match expected {
+            Some(expected) => {
+                println!("snapshot {name} ... changed");
+                failures.push(format!(
+                    "  {name}: stdout differs from {} (- snapshot, + actual)\n{}",
+                    snap.display(),
+                    line_diff(&expected, &actual)
+                ));
+            }
+            None => {
+                println!("snapshot {name} ... missing");
+                failures.push(format!(
+                    "  {name}: no snapshot at {}; rerun with --update to write it",
+                    snap.display()
+                ));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        eprintln!();
+        eprintln!("failures:");
+        for detail in &failures {
+            eprintln!("{detail}");
+        }
+    }
+    println!();
+    let failed = failures.len();
+    if update {
+        println!(
+            "{total} snapshot{}: {passed} unchanged, {written} written, {failed} failed",
+            if total == 1 { "" } else { "s" }
+        );
+    } else {
+        println!(
+            "{total} snapshot{}: {passed} passed, {failed} failed",
+            if total == 1 { "" } else { "s" }
+        );
+    }
+    if failed > 0 { 1 } else { 0 }
+}
+
+/// `dir/name.rz` → `dir/name.snap`.
+fn snapshot_path(path: &Path) -> PathBuf {
+    path.with_extension("snap")
+}
+
+fn is_interactive(path: &Path) -> bool {
+    path.with_extension("interactive").exists()
+}
+
+/// Run `path` with this `rz` binary and return what it printed to
+/// stdout, whatever its exit status.
+fn program_stdout(exe: &Path, path: &Path) -> Result<String, String> {
+    let output = Command::new(exe)
+        .arg(path)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("cannot run {}: {e}", exe.display()))?;
+    String::from_utf8(output.stdout).map_err(|e| format!("stdout was not UTF-8: {e}"))
+}
+
+/// The lines that differ between `old` and `new`, as `-`/`+` lines
+/// under an `@@ line N` header per hunk (line numbers are `old`'s).
+fn line_diff(old: &str, new: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    // lcs[i][j]: length of the longest common subsequence of a[i..], b[j..].
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    let mut in_hunk = false;
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            in_hunk = false;
+            i += 1;
+            j += 1;
+            continue;
+        }
+        if !in_hunk {
+            out.push_str(&format!("    @@ line {}\n", i + 1));
+            in_hunk = true;
+        }
+        if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("    -{}\n", a[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("    +{}\n", b[j]));
+            j += 1;
+        }
+    }
+    if old.ends_with('\n') != new.ends_with('\n') {
+        out.push_str("    (trailing newline differs)\n");
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_diff_marks_changed_lines_per_hunk() {
+        let diff = line_diff("a\nb\nc\nd\n", "a\nB\nc\nd\ne\n");
+        assert_eq!(diff, "    @@ line 2\n    -b\n    +B\n    @@ line 5\n    +e");
+        assert_eq!(line_diff("a\n", "a"), "    (trailing newline differs)");
+    }
+}
@@ -43,16 +43,16 @@ pub(crate) fn check(program: &Node, _source_path: &str) -> Result<(), String> {
             {
                 if let Node::Identifier { name, .. } = function.as_ref() {
                     let arg = arguments.first().and_then(|a| match a {
-                        Node::Identifier { name, .. } => Some(name.clone()),
+                        Node::Identifier { name, .. } => Some(name.to_string()),
                         Node::StringLiteral { value, .. } => Some(value.clone()),
                         _ => None,
                     });
                     if CHECK_SUFFIXES.iter().any(|s| name.ends_with(*s)) {
-                        events.push((true, name.clone(), arg));
+                        events.push((true, name.to_string(), arg));
                     } else if USE_SUFFIXES.iter().any(|s| name.ends_with(*s))
                         && !name.starts_with("atomic_")
                     {
-                        events.push((false, name.clone(), arg));
+                        events.push((false, name.to_string(), arg));
                     }
                 }
             }
@@ -74,7 +74,8 @@ use std::collections::{HashMap, HashSet};
 
 /// Method signature on a trait declaration.
 #[derive(Debug, Clone)]
-pub(crate) struct TraitMethodSig {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraitMethodSig {
     pub name: String,
     /// Number of parameters declared, including `self`.
     pub param_arity: usize,
@@ -103,7 +104,8 @@ pub(crate) struct TraitMethodSig {
 /// RES-779: `type Name;` inside a trait declares a type member that each
 /// impl must define.
 #[derive(Debug, Clone)]
-pub(crate) struct AssociatedTypeDecl {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AssociatedTypeDecl {
     #[allow(dead_code)]
     pub name: String,
     #[allow(dead_code)]
@@ -119,7 +121,7 @@ pub(crate) fn parse(parser: &mut Parser) -> Node {
     parser.next_token(); // skip 'trait'
 
     let name = match &parser.current_token {
-        Token::Identifier(n) => n.clone(),
+        Token::Identifier(n) => n.to_string(),
         other => {
             let tok = other.clone();
             parser.record_error(format!("Expected identifier after 'trait', found {}", tok));
@@ -137,7 +139,7 @@ pub(crate) fn parse(parser: &mut Parser) -> Node {
         loop {
             match &parser.current_token {
                 Token::Identifier(super_name) => {
-                    supers.push(super_name.clone());
+                    supers.push(super_name.to_string());
                     parser.next_token();
                     if parser.current_token == Token::Plus {
                         parser.next_token(); // skip '+'
@@ -220,7 +222,7 @@ fn parse_method_sig(parser: &mut Parser) -> Option<TraitMethodSig> {
     parser.next_token(); // skip 'fn'
 
     let method_name = match &parser.current_token {
-        Token::Identifier(n) => n.clone(),
+        Token::Identifier(n) => n.to_string(),
         other => {
             let tok = other.clone();
             parser.record_error(format!("Expected method name in trait body, found {}", tok));
@@ -289,7 +291,7 @@ fn parse_method_sig(parser: &mut Parser) -> Option<TraitMethodSig> {
                 parser.next_token();
             }
             Token::Identifier(ident) if at_param_start && depth == 0 => {
-                params.push(ident.clone());
+                params.push(ident.to_string());
                 saw_any_token_in_param = true;
                 at_param_start = false;
                 parser.next_token();
@@ -369,7 +371,7 @@ fn parse_assoc_type_decl(parser: &mut Parser) -> Option<AssociatedTypeDecl> {
     parser.next_token(); // skip 'type'
 
     let type_name = match &parser.current_token {
-        Token::Identifier(n) => n.clone(),
+        Token::Identifier(n) => n.to_string(),
         other => {
             let tok = other.clone();
             parser.record_error(format!(
@@ -942,7 +944,7 @@ fn walk_call_sites_with_bindings(
                             && let Some((caller_tp, caller_bounds)) = bindings
                                 .iter()
                                 .rev()
-                                .find_map(|scope| scope.generic.get(arg_name))
+                                .find_map(|scope| scope.generic.get(arg_name.as_str()))
                         {
                             for bound in bounds {
                                 if bound.contains("::") {
@@ -1064,7 +1066,7 @@ fn resolve_concrete_type(
         Node::Identifier { name, .. } => bindings
             .iter()
             .rev()
-            .find_map(|scope| scope.concrete.get(name).cloned()),
+            .find_map(|scope| scope.concrete.get(name.as_str()).cloned()),
         // RES-4048: an argument that is itself a call to a fn with a
         // known, concrete (non-generic) return-type annotation — e.g.
         // `needs_drawable(make_bad())` where `fn make_bad() -> Bad`.
@@ -75,7 +75,7 @@ pub(crate) fn parse(parser: &mut crate::Parser) -> Node {
         parser.next_token(); // consume `catch`
         let variant = match &parser.current_token {
             crate::Token::Identifier(n) => {
-                let name = n.clone();
+                let name = n.to_string();
                 parser.next_token();
                 name
             }
@@ -89,7 +89,7 @@ pub(crate) fn parse(parser: &mut crate::Parser) -> Node {
             }
         };
         let handler_body = parse_block(parser, "catch");
-        handlers.push((variant, handler_body));
+        handlers.push((variant.to_string(), handler_body));
         // Peek past this handler's `}` to see if another `catch`
         // follows. If the next non-trivial token is NOT `catch` we
         // must NOT advance — leaving the cursor on `}` matches the
@@ -295,13 +295,21 @@ fn collect_from_node<'a>(
             arguments,
             ..
         } => {
-            if let Node::Identifier { name, .. } = function.as_ref()
-                && let Some(variants) = fn_fails.get(name.as_str()).copied()
-            {
-                for v in variants {
-                    let v_str = v.as_str();
-                    if !out.contains(&v_str) {
-                        out.push(v_str);
+            if let Node::Identifier { name, .. } = function.as_ref() {
+                if let Some(variants) = fn_fails.get(name.as_str()).copied() {
+                    for v in variants {
+                        let v_str = v.as_str();
+                        if !out.contains(&v_str) {
+                            out.push(v_str);
+                        }
+                    }
+                } else {
+                    // A builtin with typed failures (`read_file`,
+                    // `http_get`, ...) emits those.
+                    for &v in crate::builtin_failures(name) {
+                        if !out.contains(&v) {
+                            out.push(v);
+                        }
                     }
                 }
             }
@@ -346,6 +354,7 @@ fn collect_from_node<'a>(
             collect_from_node(iterable, fn_fails, out);
             collect_from_node(body, fn_fails, out);
         }
+        Node::UnsafeBlock { body, .. } => collect_from_node(body, fn_fails, out),
         Node::TryCatch { body, handlers, .. } => {
             // Variants caught by inner try/catch do NOT propagate out
             // — the outer try should not see them unless some other
@@ -423,6 +432,15 @@ mod tests {
         assert!(found, "expected a TryCatch node in the parsed program");
     }
 
+    #[test]
+    fn builtin_failures_can_be_caught_inside_a_fn() {
+        let src = "\
+            fn blink(int addr) {\n\
+                try { unsafe { mmio_write(addr, 1); } } catch PermissionDenied { return; }\n\
+            }\n";
+        check_src(src).expect("mmio_write fails with PermissionDenied");
+    }
+
     #[test]
     fn exhaustive_handler_accepted_with_empty_caller_fails() {
         // Caller declares no `fails` — the try block fully handles
@@ -31,7 +31,7 @@
 //! and field count; it validates the argument count at construction time
 //! and builds the `Value::Struct` result.
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::{Environment, FunctionValue, Node, Value, span::Span};
 
@@ -65,7 +65,7 @@ pub(crate) fn make_constructor(struct_name: String, field_count: usize, env: Env
             (
                 i.to_string(),
                 Node::Identifier {
-                    name: format!("__arg{i}"),
+                    name: format!("__arg{i}").into(),
                     span: Span::default(),
                 },
             )
@@ -83,15 +83,15 @@ pub(crate) fn make_constructor(struct_name: String, field_count: usize, env: Env
     };
 
     Value::Function(Box::new(FunctionValue {
-        parameters: Rc::new(params),
-        body: Rc::new(body),
+        parameters: Arc::new(params),
+        body: Arc::new(body),
         env,
-        requires: Vec::new(),
-        ensures: Vec::new(),
+        requires: Arc::default(),
+        ensures: Arc::default(),
         recovers_to: None,
         name: struct_name,
         type_params: Vec::new(),
-        fails: Rc::new(Vec::new()),
+        fails: Arc::default(),
     }))
 }
 
@@ -117,7 +117,7 @@ pub(crate) fn parse_let_tuple_destructure(parser: &mut Parser, stmt_span: Span)
             }
         }
         match &parser.current_token {
-            Token::Identifier(n) => names.push(n.clone()),
+            Token::Identifier(n) => names.push(n.to_string()),
             // RES-4032: accept `_` alongside plain identifiers, matching
             // `parse_for_tuple_in_statement`'s `for (a, _) in ...` grammar.
             // Needed so the formatter can round-trip the `LetTupleDestructure`
@@ -220,9 +220,9 @@ pub(crate) fn eval_tuple_index(
         Value::Struct { name, fields } => {
             let key = index.to_string();
             fields
-                .into_iter()
+                .iter()
                 .find(|(n, _)| n == &key)
-                .map(|(_, v)| v)
+                .map(|(_, v)| v.clone())
                 .ok_or_else(|| {
                     format!(
                         "{}:{}: struct {} has no positional field `.{}`",
@@ -41,8 +41,12 @@
 //! Self-loops (`type A = A;`) fall out as the degenerate single-node
 //! case.
 //!
-//! Aliases whose target is *not* another alias (e.g. `type M = int;`)
-//! act as terminals — DFS bottoms out without ever visiting them.
+//! Edges come from an occurs check on the target: every alias *named
+//! anywhere* in it, so `type L = Option<L>;` is a self-loop too — an
+//! alias can't be recursive, because expanding it never terminates.
+//! Recursive data needs a nominal `struct`. Aliases whose target names
+//! no other alias (e.g. `type M = int;`) act as terminals — DFS
+//! bottoms out without ever visiting them.
 
 use crate::Node;
 
@@ -147,20 +151,29 @@ fn dfs<'a>(
     color.insert(node, Color::OnStack);
     path.push(node);
 
+    // Occurs check: every alias named anywhere in the target is an
+    // edge, so `type L = Option<L>;` and `type P = (int, P);` are
+    // self-loops just like `type A = A;`.
     let target = aliases[node].0;
-    if let Some(c) = color.get(target).copied() {
-        match c {
+    for referenced in target
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+    {
+        let Some((referenced, _)) = aliases.get_key_value(referenced) else {
+            continue;
+        };
+        match color[referenced] {
             Color::OnStack => {
                 // Cycle. Reconstruct the chain from the first
-                // occurrence of `target` in `path` to the end, then
-                // append `target` again to close the loop visually.
-                let cut = path.iter().position(|n| *n == target).unwrap_or(0);
+                // occurrence of `referenced` in `path` to the end,
+                // then append it again to close the loop visually.
+                let cut = path.iter().position(|n| n == referenced).unwrap_or(0);
                 let mut chain: Vec<&'a str> = path[cut..].to_vec();
-                chain.push(target);
+                chain.push(referenced);
                 return Some(chain);
             }
             Color::Unvisited => {
-                if let Some(chain) = dfs(target, aliases, color, path) {
+                if let Some(chain) = dfs(referenced, aliases, color, path) {
                     return Some(chain);
                 }
             }
@@ -169,7 +182,7 @@ fn dfs<'a>(
             }
         }
     }
-    // Else: target is not an alias — it's a terminal type name. No edge.
+    // Names that aren't aliases are terminal type names. No edge.
 
     path.pop();
     color.insert(node, Color::Done);
@@ -256,6 +269,27 @@ mod tests {
         assert!(err.contains("type alias cycle"), "got: {}", err);
     }
 
+    #[test]
+    fn alias_occurring_inside_its_own_target_is_rejected() {
+        let err = run_check("type L = Option<L>;\n").expect_err("occurs check");
+        assert!(err.contains("type alias cycle: L -> L"), "got: {}", err);
+        let err = run_check("type P = (int, Q);\ntype Q = Option<P>;\n").expect_err("occurs check");
+        assert!(
+            err.contains("type alias cycle: P -> Q -> P"),
+            "got: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn alias_naming_a_recursive_struct_is_accepted() {
+        let src = "\
+            struct Link { int v, Option<Link> next, }\n\
+            type List = Option<Link>;\n\
+        ";
+        run_check(src).expect("the struct, not the alias, is recursive");
+    }
+
     #[test]
     fn cycle_diagnostic_carries_source_position() {
         let src = "\
@@ -26,50 +26,83 @@ type RResult<T> = Result<T, String>;
 /// ```
 pub(crate) fn builtin_type_of(args: &[Value]) -> RResult<Value> {
     match args {
-        [v] => {
-            let name = match v {
-                Value::Int(_) => "int",
-                Value::Float(_) => "float",
-                Value::String(_) => "string",
-                Value::Bool(_) => "bool",
-                Value::Char(_) => "char",
-                Value::Array(_) => "array",
-                Value::Map(_) => "map",
-                Value::Set(_) => "set",
-                Value::Void => "void",
-                Value::Function(_) | Value::Closure { .. } | Value::Builtin { .. } => "function",
-                #[cfg(feature = "ffi")]
-                Value::Foreign { .. } => "function",
-                Value::Bytes(_) => "bytes",
-                Value::Struct { .. } => "struct",
-                Value::Tuple(_) => "tuple",
-                Value::EnumVariant { .. } => "enum",
-                Value::Result { .. } => "result",
-                Value::Option(_) => "option",
-                Value::ActorPid(_) => "actor_pid",
-                // Control-flow sentinels — not user-visible.
-                Value::Return(_)
-                | Value::Break
-                | Value::BreakWith(_)
-                | Value::Continue
-                | Value::BreakLabel(_)
-                | Value::ContinueLabel(_) => "void",
-                // RES-2548: Range is a first-class type.
-                Value::Range { .. } => "range",
-                Value::OpaquePtr(_) | Value::Cell(_) => "opaque",
-                // RES-2592: internal trampoline sentinel — never user-visible.
-                Value::TailCall(_) => "void",
-                // RES-2603: enum variant constructor is callable — report as "function".
-                Value::EnumConstructor { .. } => "function",
-                // RES-2659: internal trampoline sentinel — never user-visible.
-                Value::MutualTailCall { .. } => "void",
-            };
-            Ok(Value::String(name.to_string()))
-        }
+        [v] => Ok(Value::String(value_type_name(v).to_string())),
         _ => Err(format!("type_of: expected 1 argument, got {}", args.len())),
     }
 }
 
+/// The name `type_of` reports for `v`; also used to name operands in
+/// runtime type errors.
+pub(crate) fn value_type_name(v: &Value) -> &'static str {
+    match v {
+        Value::Int(_) => "int",
+        Value::Float(_) => "float",
+        Value::String(_) => "string",
+        Value::Bool(_) => "bool",
+        Value::Char(_) => "char",
+        Value::Array(_) => "array",
+        Value::Map(_) => "map",
+        Value::Set(_) => "set",
+        Value::Void => "void",
+        Value::Function(_) | Value::Closure { .. } | Value::Builtin { .. } => "function",
+        #[cfg(feature = "ffi")]
+        Value::Foreign { .. } => "function",
+        Value::Bytes(_) => "bytes",
+        Value::Struct { .. } => "struct",
+        Value::Tuple(_) => "tuple",
+        Value::EnumVariant { .. } => "enum",
+        Value::Result { .. } => "result",
+        Value::Option(_) => "option",
+        Value::ActorPid(_) => "actor_pid",
+        // Control-flow sentinels — not user-visible.
+        Value::Return(_)
+        | Value::Break
+        | Value::BreakWith(_)
+        | Value::Continue
+        | Value::BreakLabel(_)
+        | Value::ContinueLabel(_) => "void",
+        // RES-2548: Range is a first-class type.
+        Value::Range { .. } => "range",
+        Value::OpaquePtr(_) | Value::Cell(_) => "opaque",
+        // RES-2592: internal trampoline sentinel — never user-visible.
+        Value::TailCall(_) => "void",
+        // RES-2603: enum variant constructor is callable — report as "function".
+        Value::EnumConstructor { .. } => "function",
+        Value::Overloads(_) => "function",
+        // RES-2659: internal trampoline sentinel — never user-visible.
+        Value::MutualTailCall { .. } | Value::TailCallTo { .. } => "void",
+    }
+}
+
+/// Type-test builtins `is_int` / `is_float` / `is_string` / `is_bool`.
+///
+/// Each returns `true` when its single argument has that runtime type.
+/// Beyond the runtime answer, the typechecker treats `if is_int(x)` as
+/// a narrowing point for a union-typed `x` (`int | string` becomes
+/// `int` in the then-branch and `string` in the else-branch).
+fn type_test(name: &str, args: &[Value], pred: fn(&Value) -> bool) -> RResult<Value> {
+    match args {
+        [v] => Ok(Value::Bool(pred(v))),
+        _ => Err(format!("{name}: expected 1 argument, got {}", args.len())),
+    }
+}
+
+pub(crate) fn builtin_is_int(args: &[Value]) -> RResult<Value> {
+    type_test("is_int", args, |v| matches!(v, Value::Int(_)))
+}
+
+pub(crate) fn builtin_is_float(args: &[Value]) -> RResult<Value> {
+    type_test("is_float", args, |v| matches!(v, Value::Float(_)))
+}
+
+pub(crate) fn builtin_is_string(args: &[Value]) -> RResult<Value> {
+    type_test("is_string", args, |v| matches!(v, Value::String(_)))
+}
+
+pub(crate) fn builtin_is_bool(args: &[Value]) -> RResult<Value> {
+    type_test("is_bool", args, |v| matches!(v, Value::Bool(_)))
+}
+
 pub(crate) fn builtin_struct_name(args: &[Value]) -> RResult<Value> {
     match args {
         [Value::Struct { name, .. }] => Ok(Value::String(name.clone())),
@@ -114,7 +147,7 @@ pub(crate) fn builtin_result_collect(args: &[Value]) -> RResult<Value> {
             }
             Ok(Value::Result {
                 ok: true,
-                payload: Box::new(Value::Array(out)),
+                payload: Box::new(Value::Array(out.into())),
             })
         }
         [other] => Err(format!(
@@ -160,7 +193,7 @@ pub(crate) fn builtin_array_from_fn(interp: &mut Interpreter, args: &[Value]) ->
     for i in 0..n {
         out.push(interp.apply_function(&f, vec![Value::Int(i)])?);
     }
-    Ok(Value::Array(out))
+    Ok(Value::Array(out.into()))
 }
 
 #[cfg(test)]
@@ -219,6 +252,21 @@ println(type_of(f));"#);
         assert!(r.stdout.contains("function"), "stdout: {}", r.stdout);
     }
 
+    // ── is_int / is_float / is_string / is_bool ───────────────────────────────
+
+    #[test]
+    fn type_tests_match_runtime_type() {
+        let r = run(r#"println(is_int(1));
+println(is_int("1"));
+println(is_float(1.5));
+println(is_string("s"));
+println(is_bool(false));
+println(is_bool(0));"#);
+        assert!(r.ok, "errors: {:?}", r.errors);
+        let lines: Vec<&str> = r.stdout.trim().lines().collect();
+        assert_eq!(lines, ["true", "false", "true", "true", "true", "false"]);
+    }
+
     // ── result_collect ────────────────────────────────────────────────────────
 
     #[test]
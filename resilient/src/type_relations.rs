@@ -94,6 +94,12 @@ pub(crate) fn substitute_type_params(ty: &Type, type_params: &[String]) -> Type
                 .collect(),
         ),
         Type::Option(inner) => Type::Option(Box::new(substitute_type_params(inner, type_params))),
+        Type::Union(members) => Type::Union(
+            members
+                .iter()
+                .map(|m| substitute_type_params(m, type_params))
+                .collect(),
+        ),
         other => other.clone(),
     }
 }
@@ -145,6 +151,12 @@ pub(crate) fn substitute_with_bindings(
             type_params,
             bindings,
         ))),
+        Type::Union(members) => Type::Union(
+            members
+                .iter()
+                .map(|m| substitute_with_bindings(m, type_params, bindings))
+                .collect(),
+        ),
         other => other.clone(),
     }
 }
@@ -161,6 +173,31 @@ pub(crate) fn infer_generic_return_type(
     substitute_with_bindings(return_type, tp, tp_bindings)
 }
 
+/// Build a union from `members`: nested unions are flattened, duplicates
+/// dropped (first occurrence wins, so source order is kept), and a
+/// single surviving member is returned bare. Any `Any` member swallows
+/// the union — `int | any` carries no more information than `any`.
+pub(crate) fn union_of(members: Vec<Type>) -> Type {
+    let mut flat: Vec<Type> = Vec::with_capacity(members.len());
+    for m in members {
+        let parts = match m {
+            Type::Union(inner) => inner,
+            Type::Any => return Type::Any,
+            other => vec![other],
+        };
+        for p in parts {
+            if !flat.contains(&p) {
+                flat.push(p);
+            }
+        }
+    }
+    if flat.len() == 1 {
+        flat.pop().unwrap_or(Type::Any)
+    } else {
+        Type::Union(flat)
+    }
+}
+
 /// Conservative subtype relation used by the variance checker and any
 /// future call-site relation logic.
 ///
@@ -210,6 +247,8 @@ pub(crate) fn is_subtype(sub: &Type, sup: &Type) -> bool {
                     .zip(b_elems.iter())
                     .all(|(a, b)| is_subtype(a, b))
         }
+        (Type::Union(a_members), _) => a_members.iter().all(|m| is_subtype(m, sup)),
+        (_, Type::Union(b_members)) => b_members.iter().any(|m| is_subtype(sub, m)),
         _ => false,
     }
 }
@@ -249,6 +288,15 @@ pub(crate) fn compatible(a: &Type, b: &Type) -> bool {
                 .zip(b_elems.iter())
                 .all(|(x, y)| compatible(x, y));
     }
+    // Unions are the one directional case: `a` flows into `b`. A member
+    // widens into its union, but a union only fits a target that every
+    // member fits — `int | string` into `int` needs narrowing first.
+    if let Type::Union(a_members) = a {
+        return a_members.iter().all(|m| compatible(m, b));
+    }
+    if let Type::Union(b_members) = b {
+        return b_members.iter().any(|m| compatible(a, m));
+    }
     if *a == Type::Int && is_pinned_int(b) {
         return true;
     }
@@ -265,6 +313,29 @@ pub(crate) fn compatible(a: &Type, b: &Type) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn union_flattens_dedups_and_collapses() {
+        let u = union_of(vec![Type::Int, Type::Union(vec![Type::String, Type::Int])]);
+        assert_eq!(u, Type::Union(vec![Type::Int, Type::String]));
+        assert_eq!(union_of(vec![Type::Int, Type::Int]), Type::Int);
+        assert_eq!(union_of(vec![Type::Int, Type::Any]), Type::Any);
+    }
+
+    #[test]
+    fn union_compatibility_is_directional() {
+        let u = Type::Union(vec![Type::Int, Type::String]);
+        assert!(compatible(&Type::Int, &u));
+        assert!(compatible(&Type::String, &u));
+        assert!(!compatible(&Type::Bool, &u));
+        assert!(!compatible(&u, &Type::Int));
+        assert!(compatible(&u, &u));
+        let wider = Type::Union(vec![Type::String, Type::Bool, Type::Int]);
+        assert!(compatible(&u, &wider));
+        assert!(!compatible(&wider, &u));
+        assert!(is_subtype(&Type::Int, &u));
+        assert!(!is_subtype(&u, &Type::Int));
+    }
+
     #[test]
     fn subtype_is_covariant_for_option_and_tuple() {
         let sup = Type::Option(Box::new(Type::Any));
@@ -1,5 +1,18 @@
 //! Type checker module for Resilient language.
 //!
+//! [`TypeChecker::check_program`] checks a program from
+//! [`crate::parser`] and returns the first type error as a
+//! `line:col: message` string. Warnings and unproven contracts go to
+//! stderr, as under `rz --typecheck`.
+//!
+//! ```
+//! use resilient::{parser, typechecker::TypeChecker};
+//!
+//! let program = parser::parse_source("let x: int = \"one\";").unwrap();
+//! let err = TypeChecker::new().check_program(&program).unwrap_err();
+//! assert!(err.contains("int"), "{err}");
+//! ```
+//!
 //! ## RES-776 / RES-780: Supervisor-Actor Integration Design
 //!
 //! When fully implemented, the typechecker will validate that supervisors
@@ -19,6 +32,7 @@
 //! scheduler/crash machinery, and `supervisor.rs` handles parsing.
 
 use crate::span::{Pos, Span};
+use crate::symbol::{Symbol, SymbolKey};
 use crate::{Node, Pattern};
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
@@ -38,6 +52,48 @@ thread_local! {
     static CHECK_DIAGNOSTIC_COLLECTOR: RefCell<Option<Vec<CheckDiagnostic>>> = const { RefCell::new(None) };
 }
 
+/// A failed check. `refuted` marks the verifier disproving a
+/// contract, invariant or bound, as opposed to an ill-typed program;
+/// the driver exits 3 for the first and 2 for the second.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckError {
+    pub message: String,
+    pub refuted: bool,
+}
+
+impl CheckError {
+    /// The same failure with `f` applied to its message.
+    pub(crate) fn map_message(self, f: impl FnOnce(String) -> String) -> Self {
+        CheckError {
+            message: f(self.message),
+            refuted: self.refuted,
+        }
+    }
+}
+
+impl From<String> for CheckError {
+    fn from(message: String) -> Self {
+        CheckError {
+            message,
+            refuted: false,
+        }
+    }
+}
+
+impl std::fmt::Display for CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// `message` as the verifier refuting a contract, invariant or bound.
+pub(crate) fn refutation(message: String) -> CheckError {
+    CheckError {
+        message,
+        refuted: true,
+    }
+}
+
 pub(crate) fn collect_check_diagnostics<T>(f: impl FnOnce() -> T) -> (T, Vec<CheckDiagnostic>) {
     let previous = CHECK_DIAGNOSTIC_COLLECTOR.with(|slot| slot.replace(Some(Vec::new())));
     let result = f();
@@ -46,6 +102,19 @@ pub(crate) fn collect_check_diagnostics<T>(f: impl FnOnce() -> T) -> (T, Vec<Che
     (result, diagnostics)
 }
 
+/// Emit diagnostics captured by [`collect_check_diagnostics`] as if
+/// they were being emitted now.
+pub(crate) fn replay_check_diagnostics(diagnostics: Vec<CheckDiagnostic>) {
+    CHECK_DIAGNOSTIC_COLLECTOR.with(|slot| match slot.borrow_mut().as_mut() {
+        Some(collected) => collected.extend(diagnostics),
+        None => {
+            for diagnostic in diagnostics {
+                eprintln!("{}", diagnostic.plain);
+            }
+        }
+    });
+}
+
 pub(crate) fn emit_check_warning_plain(
     plain: impl Into<String>,
     fallback_file: &str,
@@ -83,7 +152,7 @@ fn check_diagnostic_from_plain(
     fallback_file: &str,
     fallback_code: &str,
 ) -> CheckDiagnostic {
-    for severity in ["warning", "note", "hint"] {
+    for severity in ["warning", "error", "note", "hint"] {
         let needle = format!(": {severity}[");
         if let Some((loc, rest)) = plain.split_once(&needle)
             && let Some((code, message)) = rest.split_once("]: ")
@@ -211,7 +280,130 @@ pub struct FnReturnTypeHint {
     pub ty: Type,
 }
 
+/// One lexical scope the checker walked, retained for completion
+/// queries. `bindings` holds every user binding visible at the end of
+/// the scope — its own and those of the enclosing frames, innermost
+/// first — each with the position it becomes visible from.
+#[derive(Debug, Clone)]
+pub struct ScopeRecord {
+    pub start: Pos,
+    pub end: Pos,
+    pub bindings: Vec<(String, Type, Pos)>,
+}
+
+/// An identifier in scope at a queried position, as returned by
+/// [`query_completions`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub name: String,
+    pub ty: Type,
+}
+
+/// Identifiers in scope at `position` with their types: the bindings
+/// of the innermost block enclosing it (locals declared before the
+/// position, parameters, loop variables, enclosing blocks' locals),
+/// then top-level declarations, then builtins. Overload members are
+/// listed under their bare name, once per signature. A program that
+/// fails to check still yields whatever the walk reached.
+pub fn query_completions(program: &Node, position: Pos) -> Vec<Completion> {
+    let mut checker = TypeChecker::new().with_capture_scopes(true);
+    // Diagnostics are the check's business, not the query's.
+    let _ = collect_check_diagnostics(|| checker.check_program(program));
+
+    let mut visible: Vec<(String, Type, Pos)> = Vec::new();
+    if let Some(scope) = checker
+        .scopes
+        .iter()
+        .filter(|sc| pos_le(sc.start, position) && pos_le(position, sc.end))
+        .max_by_key(|sc| (sc.start.line, sc.start.column))
+    {
+        visible.extend(scope.bindings.iter().cloned());
+    }
+    // The top-level frame: the one just inside the shared builtin root.
+    let mut global = &checker.env;
+    while let Some(outer) = &global.outer {
+        if outer.outer.is_none() {
+            break;
+        }
+        global = outer;
+    }
+    let top_level_lets = match program {
+        Node::Program(stmts) => let_visibility(stmts.iter().map(|s| &s.node)),
+        _ => HashMap::new(),
+    };
+    for (name, ty) in &global.store {
+        let from = top_level_lets
+            .get(name.as_str())
+            .copied()
+            .unwrap_or_default();
+        visible.push((name.to_string(), ty.clone(), from));
+    }
+
+    // Per name, the latest declaration already visible at `position`;
+    // on a tie the innermost frame (listed first) wins.
+    let mut chosen: HashMap<String, (Type, Pos)> = HashMap::new();
+    for (name, ty, from) in visible {
+        if name.contains('$') && crate::overloads::base_of(&name).is_none() {
+            continue;
+        }
+        if !pos_le(from, position) {
+            continue;
+        }
+        match chosen.get(&name) {
+            Some((_, prev)) if !pos_lt(*prev, from) => {}
+            _ => {
+                chosen.insert(name, (ty, from));
+            }
+        }
+    }
+    let mut out: Vec<Completion> = chosen
+        .into_iter()
+        .map(|(name, (ty, _))| Completion {
+            name: crate::overloads::base_name(&name).to_string(),
+            ty,
+        })
+        .collect();
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut builtins: Vec<Completion> = global
+        .outer
+        .iter()
+        .flat_map(|root| root.store.iter())
+        .filter(|(name, _)| !out.iter().any(|c| c.name == **name))
+        .map(|(name, ty)| Completion {
+            name: name.to_string(),
+            ty: ty.clone(),
+        })
+        .collect();
+    builtins.sort_by(|a, b| a.name.cmp(&b.name));
+    out.extend(builtins);
+    out
+}
+
+fn pos_le(a: Pos, b: Pos) -> bool {
+    (a.line, a.column) <= (b.line, b.column)
+}
+
+fn pos_lt(a: Pos, b: Pos) -> bool {
+    (a.line, a.column) < (b.line, b.column)
+}
+
+/// Where each `let` among `stmts` becomes visible: its `let` keyword.
+/// Statement spans don't record where a statement ends, so a binding
+/// is also offered inside its own initializer. A name bound twice
+/// keeps its first position.
+fn let_visibility<'a>(stmts: impl Iterator<Item = &'a Node>) -> HashMap<String, Pos> {
+    let mut out = HashMap::new();
+    for stmt in stmts {
+        if let Node::LetStatement { name, span, .. } = stmt {
+            out.entry(name.clone()).or_insert(span.start);
+        }
+    }
+    out
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Type {
     /// The default integer type — also the type of integer literals
     /// and the canonical name for `Int64` (`Int` and `Int64` alias
@@ -287,6 +479,11 @@ pub enum Type {
     /// index and `LetTupleDestructure` can bind each name to its
     /// precise element type. An empty Vec represents `()` (unit tuple).
     Tuple(Vec<Type>),
+    /// Untagged union `int | string`. Members are flattened, deduplicated
+    /// and kept in source order (see `type_relations::union_of`). A union value
+    /// satisfies no single member type — it has to be narrowed first,
+    /// with a type test such as `is_int(x)` in an `if` condition.
+    Union(Vec<Type>),
     /// RES-121: fresh inference variable (Hindley-Milner). Produced
     /// by the inference walker (RES-120, when it lands) and
     /// eliminated by `unify::Substitution::apply`. The `u32` is a
@@ -368,6 +565,15 @@ impl std::fmt::Display for Type {
                 }
                 write!(f, ")")
             }
+            Type::Union(members) => {
+                for (i, m) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{}", m)?;
+                }
+                Ok(())
+            }
             Type::Var(_, Some(span)) => {
                 write!(f, "type hole at {}:{}", span.start.line, span.start.column)
             }
@@ -418,6 +624,20 @@ pub(crate) fn node_terminates(node: &Node) -> bool {
                     .iter()
                     .all(|(_, hstmts)| hstmts.iter().any(node_terminates))
         }
+        // `while true { ... }` (and `loop { ... }`, which desugars to
+        // it) only ends through a `break`. Any break anywhere in the
+        // body — even one that targets a nested loop — counts, to stay
+        // conservative.
+        Node::WhileStatement {
+            condition, body, ..
+        } if matches!(condition.as_ref(), Node::BooleanLiteral { value: true, .. }) => {
+            !crate::uniqueness_walk::any_node(body, |n| {
+                matches!(
+                    n,
+                    Node::Break { .. } | Node::BreakWith { .. } | Node::BreakLabel { .. }
+                )
+            })
+        }
         _ => false,
     }
 }
@@ -472,6 +692,20 @@ fn substitute_type_params(ty: &Type, type_params: &[String]) -> Type {
 /// RES-2805: produce a concrete return type for a generic function call
 /// by substituting inferred type-parameter bindings into the declared
 /// return type. Falls back to `Type::Any` only when no binding exists.
+/// The checker's view of an `extern` signature's type name. FFI
+/// names (`Int`, `OpaquePtr`, ...) are not language types, so anything
+/// past the scalars is `Any`.
+fn ffi_type(name: &str) -> Type {
+    match name {
+        "Int" | "int" => Type::Int,
+        "Float" | "float" => Type::Float,
+        "Bool" | "bool" => Type::Bool,
+        "String" | "string" => Type::String,
+        "Void" | "void" => Type::Void,
+        _ => Type::Any,
+    }
+}
+
 fn infer_generic_return_type(
     return_type: &Type,
     callee_type_params: &Option<Vec<String>>,
@@ -690,7 +924,7 @@ fn struct_pattern_matches_nominal_type(sname: &str, decl: &[(String, Type)], p:
 fn pattern_is_exhaustive_wrt_scrutinee(
     scrut: &Type,
     p: &Pattern,
-    struct_fields: &HashMap<String, std::rc::Rc<Vec<(String, Type)>>>,
+    struct_fields: &HashMap<String, std::sync::Arc<Vec<(String, Type)>>>,
 ) -> bool {
     match scrut {
         Type::Struct(sname) => {
@@ -792,6 +1026,25 @@ fn check_numeric_same_type(op: &str, left: &Type, right: &Type) -> Result<Type,
     }
 }
 
+/// The part of `ty` that `<` / `>` / `<=` / `>=` cannot order, if any.
+/// Arrays, tuples and options order element by element, so they are
+/// orderable when their elements are; structs order through
+/// `#[derive(PartialOrd)]` or an operator impl, which the runtime
+/// resolves.
+fn unorderable_part(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Bytes
+        | Type::Void
+        | Type::Function { .. }
+        | Type::Range
+        | Type::Result
+        | Type::AnonymousStruct(_) => Some(ty),
+        Type::TypedArray(elem) | Type::Option(elem) => unorderable_part(elem),
+        Type::Tuple(items) => items.iter().find_map(unorderable_part),
+        _ => None,
+    }
+}
+
 /// RES-060/061: fold a contract expression down to a concrete boolean.
 /// `bindings` maps identifier names to known integer values — used at
 /// call sites where the typechecker has constant arguments to
@@ -890,10 +1143,10 @@ fn extract_eq_assumption(cond: &Node) -> Option<(String, i64)> {
         let no_b: HashMap<String, i64> = HashMap::new();
         match (left.as_ref(), right.as_ref()) {
             (Node::Identifier { name, .. }, other) => {
-                fold_const_i64(other, &no_b).map(|v| (name.clone(), v))
+                fold_const_i64(other, &no_b).map(|v| (name.to_string(), v))
             }
             (other, Node::Identifier { name, .. }) => {
-                fold_const_i64(other, &no_b).map(|v| (name.clone(), v))
+                fold_const_i64(other, &no_b).map(|v| (name.to_string(), v))
             }
             _ => None,
         }
@@ -902,11 +1155,47 @@ fn extract_eq_assumption(cond: &Node) -> Option<(String, i64)> {
     }
 }
 
+/// Type-test builtins and the union member each one selects.
+const UNION_TYPE_TESTS: &[(&str, Type)] = &[
+    ("is_int", Type::Int),
+    ("is_float", Type::Float),
+    ("is_string", Type::String),
+    ("is_bool", Type::Bool),
+];
+
+/// Recognise `is_T(x)` / `!is_T(x)` on a bare identifier. Returns the
+/// identifier, the tested member type, and whether the test is
+/// negated.
+fn extract_type_test(cond: &Node) -> Option<(&str, &Type, bool)> {
+    match cond {
+        Node::PrefixExpression {
+            operator: "!",
+            right,
+            ..
+        } => extract_type_test(right).map(|(name, ty, neg)| (name, ty, !neg)),
+        Node::CallExpression {
+            function,
+            arguments,
+            ..
+        } => {
+            let Node::Identifier { name: callee, .. } = function.as_ref() else {
+                return None;
+            };
+            let [Node::Identifier { name, .. }] = arguments.as_slice() else {
+                return None;
+            };
+            let (_, ty) = UNION_TYPE_TESTS.iter().find(|(n, _)| n == callee)?;
+            Some((name.as_str(), ty, false))
+        }
+        _ => None,
+    }
+}
+
 /// Fold an integer-typed expression to a concrete i64 under bindings.
-fn fold_const_i64(n: &Node, bindings: &HashMap<String, i64>) -> Option<i64> {
+pub(crate) fn fold_const_i64(n: &Node, bindings: &HashMap<String, i64>) -> Option<i64> {
     match n {
         Node::IntegerLiteral { value: v, .. } => Some(*v),
-        Node::Identifier { name, .. } => bindings.get(name).copied(),
+        Node::Identifier { name, .. } => bindings.get(name.as_str()).copied(),
         Node::PrefixExpression {
             operator, right, ..
         } if *operator == "-" => fold_const_i64(right, bindings).map(|v| -v),
@@ -986,10 +1275,16 @@ fn check_literal_fits(declared: &Type, literal_val: i64) -> Result<(), String> {
 // through the indirection, so the Arc share is sound.
 #[derive(Debug, Clone)]
 pub struct TypeEnvironment {
-    store: HashMap<String, Type>,
+    store: HashMap<Symbol, Type>,
     outer: Option<std::sync::Arc<TypeEnvironment>>,
 }
 
+impl Default for TypeEnvironment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TypeEnvironment {
     /// RES-1698: kept `pub` so the supervisor test module
     /// (`supervisor.rs:362`) can still build a default-capacity env;
@@ -1032,8 +1327,9 @@ impl TypeEnvironment {
         }
     }
 
-    pub fn get(&self, name: &str) -> Option<Type> {
-        match self.store.get(name) {
+    pub fn get(&self, name: impl SymbolKey) -> Option<Type> {
+        let name = name.find_symbol()?;
+        match self.store.get(&name) {
             Some(typ) => Some(typ.clone()),
             None => {
                 if let Some(outer) = &self.outer {
@@ -1045,8 +1341,11 @@ impl TypeEnvironment {
         }
     }
 
-    pub fn has_user_binding(&self, name: &str) -> bool {
-        if self.store.contains_key(name) {
+    pub fn has_user_binding(&self, name: impl SymbolKey) -> bool {
+        let Some(name) = name.find_symbol() else {
+            return false;
+        };
+        if self.store.contains_key(&name) {
             return true;
         }
         if let Some(outer) = &self.outer {
@@ -1063,8 +1362,8 @@ impl TypeEnvironment {
         }
     }
 
-    pub fn set(&mut self, name: String, typ: Type) {
-        self.store.insert(name, typ);
+    pub fn set(&mut self, name: impl Into<Symbol>, typ: Type) {
+        self.store.insert(name.into(), typ);
     }
 
     /// RES-159: remove a binding from the **current** scope only.
@@ -1072,8 +1371,24 @@ impl TypeEnvironment {
     /// match arm's body is type-checked so the identifier doesn't
     /// leak out. Outer-scope bindings with the same name are left
     /// untouched; this only clears what this scope owns.
-    pub fn remove(&mut self, name: &str) {
-        self.store.remove(name);
+    pub fn remove(&mut self, name: impl SymbolKey) {
+        if let Some(name) = name.find_symbol() {
+            self.store.remove(&name);
+        }
+    }
+
+    /// Every binding below the shared builtin root, innermost frame
+    /// first, tagged with its frame's depth (0 = this scope).
+    fn user_bindings(&self) -> Vec<(usize, Symbol, &Type)> {
+        let mut out = Vec::new();
+        let mut frame = self;
+        let mut depth = 0;
+        while let Some(outer) = &frame.outer {
+            out.extend(frame.store.iter().map(|(name, ty)| (depth, *name, ty)));
+            frame = outer;
+            depth += 1;
+        }
+        out
     }
 
     /// RES-306: collect every name visible in this scope chain
@@ -1082,7 +1397,7 @@ impl TypeEnvironment {
     /// Inner shadowing is intentionally preserved — duplicate names
     /// appear once per scope; the consumer is expected to dedup.
     pub fn all_names(&self) -> Vec<String> {
-        let mut out: Vec<String> = self.store.keys().cloned().collect();
+        let mut out: Vec<String> = self.store.keys().map(|k| k.to_string()).collect();
         if let Some(outer) = &self.outer {
             out.extend(outer.all_names());
         }
@@ -1163,6 +1478,25 @@ impl VerificationStats {
         }
         out
     }
+
+    /// Add the counters from a checker that ran part of the same
+    /// program, such as a fn body checked on a worker thread.
+    pub(crate) fn absorb(&mut self, other: VerificationStats) {
+        self.requires_discharged_at_compile += other.requires_discharged_at_compile;
+        self.requires_left_for_runtime += other.requires_left_for_runtime;
+        self.requires_tautology += other.requires_tautology;
+        self.contracted_call_sites += other.contracted_call_sites;
+        self.requires_discharged_by_z3 += other.requires_discharged_by_z3;
+        self.verifier_timeouts += other.verifier_timeouts;
+        for (name, n) in other.per_fn_discharged {
+            *self.per_fn_discharged.entry(name).or_default() += n;
+        }
+        for (name, n) in other.per_fn_runtime {
+            *self.per_fn_runtime.entry(name).or_default() += n;
+        }
+        self.fn_effects.extend(other.fn_effects);
+        self.loop_invariants_proven += other.loop_invariants_proven;
+    }
 }
 
 /// RES-067: shim that forwards to the Z3 module when built --features z3,
@@ -1401,11 +1735,46 @@ fn clause_span(node: &Node) -> Span {
         | Node::PrefixExpression { span, .. }
         | Node::CallExpression { span, .. }
         | Node::TryExpression { span, .. }
-        | Node::OptionalChain { span, .. } => *span,
+        | Node::OptionalChain { span, .. }
+        | Node::ArrayLiteral { span, .. }
+        | Node::IndexExpression { span, .. }
+        | Node::FieldAccess { span, .. }
+        | Node::StructLiteral { span, .. } => *span,
         _ => Span::default(),
     }
 }
 
+/// Span of a statement inside a block, so an error raised by an arm
+/// that records no position of its own is reported at its statement
+/// rather than wherever the previous statement left `current_span`.
+/// Expression statements defer to [`clause_span`].
+fn statement_span(node: &Node) -> Span {
+    match node {
+        Node::LetStatement { span, .. }
+        | Node::StaticLet { span, .. }
+        | Node::Const { span, .. }
+        | Node::Assignment { span, .. }
+        | Node::FieldAssignment { span, .. }
+        | Node::IndexAssignment { span, .. }
+        | Node::LetDestructureStruct { span, .. }
+        | Node::LetTupleDestructure { span, .. }
+        | Node::ReturnStatement { span, .. }
+        | Node::Break { span }
+        | Node::Continue { span }
+        | Node::IfStatement { span, .. }
+        | Node::WhileStatement { span, .. }
+        | Node::ForInStatement { span, .. }
+        | Node::ExpressionStatement { span, .. }
+        | Node::Match { span, .. }
+        | Node::Block { span, .. }
+        | Node::Assert { span, .. }
+        | Node::Assume { span, .. }
+        | Node::DeferStatement { span, .. }
+        | Node::StaticAssert { span, .. } => *span,
+        other => clause_span(other),
+    }
+}
+
 /// RES-340: gate for the rich type-mismatch diagnostic format.
 /// The legacy short message (`Type mismatch in argument N: expected
 /// X, got Y`) is emitted by default so existing callers — including
@@ -1636,21 +2005,28 @@ pub struct CapturedCertificate {
     pub smt2: String,
 }
 
-// Type checker for verifying type correctness
+// Type checker for verifying type correctness. `Clone` lets the REPL
+// check an input against a copy of its session checker and keep the
+// copy only if the input is accepted.
+#[derive(Clone)]
 pub struct TypeChecker {
     env: TypeEnvironment,
     /// RES-061: top-level function name → its parameters + contract clauses.
     /// Populated by check_program's first pass; consulted by CallExpression.
-    /// RES-1363: stored behind `Rc` so the per-CallExpression lookup
+    /// RES-1363: stored behind `Arc` so the per-CallExpression lookup
     /// at typechecker.rs:5877 ticks a single refcount instead of
     /// deep-cloning four `Vec`s on every call to a user-defined fn.
-    contract_table: HashMap<String, std::rc::Rc<ContractInfo>>,
+    contract_table: HashMap<String, std::sync::Arc<ContractInfo>>,
     /// RES-340: top-level function name → span of its `fn` keyword.
     /// Populated by the same pre-pass that fills `contract_table`. Read
     /// by the rich-diagnostic path in `CallExpression` to attach a
     /// secondary "expected `T` because of this parameter" label
     /// pointing at the function declaration.
     fn_decl_spans: HashMap<String, Span>,
+    /// Overload sets: bare fn name → each member's mangled name and
+    /// parameter types (type parameters widened to `Any`). Filled by
+    /// the same pre-pass; call sites resolve through it.
+    overload_sets: HashMap<String, Vec<(String, Vec<Type>)>>,
     /// RES-063: identifier → known constant integer value.
     const_bindings: HashMap<String, i64>,
     /// RES-066: verification audit counters.
@@ -1664,18 +2040,37 @@ pub struct TypeChecker {
     /// the declared field's type instead of `Type::Any`, and by
     /// `FieldAssignment` to reject writes to non-existent fields
     /// statically.
-    /// RES-1365: stored behind `Rc` so the per-FieldAccess /
+    /// RES-1365: stored behind `Arc` so the per-FieldAccess /
     /// FieldAssignment / Match-pattern reads at 4152 / 4831 etc. tick
     /// a single refcount instead of deep-cloning the
     /// `Vec<(String, Type)>` (which can be large for structs with
     /// many fields, especially when entries carry `Type::Function`
     /// with their own nested Vecs).
-    struct_fields: HashMap<String, std::rc::Rc<Vec<(String, Type)>>>,
+    struct_fields: HashMap<String, std::sync::Arc<Vec<(String, Type)>>>,
     /// RES-2801: for generic structs, store type parameter names and raw
     /// field types so construction sites can infer and validate concrete
     /// type-parameter bindings.
     #[allow(clippy::type_complexity)]
     generic_struct_info: HashMap<String, (Vec<String>, Vec<(String, String)>)>,
+    /// Struct literals whose name had no `StructDecl` in scope when the
+    /// literal was checked. Declarations aren't hoisted, so the verdict
+    /// is deferred to the end of `check_program_with_source`: a name
+    /// still missing from `struct_fields` there is an unknown struct.
+    unresolved_struct_literals: Vec<(String, Span)>,
+    /// Bare type names `parse_type_name` fell back to treating as a
+    /// user struct (RES-053). Checked against every declared type name
+    /// at the end of `check_program_with_source`; interior mutability
+    /// because type resolution only borrows the checker.
+    unresolved_type_names: std::cell::RefCell<Vec<(String, Span)>>,
+    /// Facts the enclosing conditionals establish at the current point
+    /// (`y >= 0` inside `if y >= 0 { ... }`). See `crate::flow_facts`.
+    path_facts: Vec<crate::flow_facts::Fact>,
+    /// Bindings of the fn being checked whose facts survive a call into
+    /// user code; `None` at top level, where every name is a global.
+    fact_locals: Option<crate::flow_facts::Locals>,
+    /// Top-level `-> bool` fns simple enough to inline into
+    /// `path_facts` when used as an `if` condition.
+    predicates: HashMap<String, crate::flow_facts::Predicate>,
     /// RES-400: enum name → variant list. Populated when we visit each
     /// `EnumDecl`. Used by the `Match` exhaustiveness check to ensure
     /// every declared variant is covered, and by `match_pattern_binding_types`
@@ -1715,6 +2110,23 @@ pub struct TypeChecker {
     /// timed out ...` line, which is the per-fn diagnostic about
     /// the `--verifier-timeout-ms` budget.
     warn_unverified: bool,
+    /// Strict mode (`--strict`): every `Any` that reaches a parameter,
+    /// return annotation, or `let` binding is an error. Seeded from
+    /// the process-wide `strict_any` flag; tests use the builder.
+    strict_any: bool,
+    /// Implicit-coercion policy (`--coercion`). Seeded from the
+    /// process-wide setting; tests use the builder.
+    coercion: crate::coercion::CoercionPolicy,
+    /// Which warning categories report, and whether they are denied
+    /// (`--warn` / `--no-warn` / `--deny-warnings`). Seeded from the
+    /// process-wide setting; tests use the builder.
+    warnings: crate::warnings::WarningConfig,
+    /// Sites already warned about, so a body that is checked twice
+    /// reports each warning once.
+    warned: HashSet<(crate::warnings::WarningCategory, usize, usize)>,
+    /// Warnings reported as errors under `--deny-warnings`; the check
+    /// fails after the walk when this is non-zero.
+    denied_warnings: usize,
     /// RES-217: source path threaded from
     /// `check_program_with_source` so the partial-proof warning
     /// can print `<file>:<line>:<col>`. Empty when the caller
@@ -1792,6 +2204,12 @@ pub struct TypeChecker {
     /// `let` only to drop the Vec on TypeChecker drop. Default
     /// `false`; the LSP path flips it via `with_capture_inlay_hints(true)`.
     capture_inlay_hints: bool,
+    /// Opt-in flag for recording `scopes`. Only `query_completions`
+    /// sets it; every other check discards environments as it leaves
+    /// them.
+    capture_scopes: bool,
+    /// Every block scope walked while `capture_scopes` is on.
+    pub scopes: Vec<ScopeRecord>,
     /// RES-1357: opt-in flag for pushing `CapturedCertificate`
     /// entries onto `self.certificates`. The only consumer is the
     /// `--emit-certificate <DIR>` CLI driver (lib.rs:26008) — every
@@ -1835,6 +2253,10 @@ pub struct TypeChecker {
     /// mirrors the parser's RES-4185 `expr_depth` guard so the same
     /// input instead gets a typed diagnostic.
     check_depth: u32,
+    /// Check independent top-level fn bodies against snapshots and
+    /// merge them back in source order (see `crate::parallel_check`).
+    /// On by default with `--features parallel-check`.
+    parallel_bodies: bool,
 }
 
 /// RES-4190: `check_node` counts every recursive descent (statements
@@ -1847,6 +2269,12 @@ pub struct TypeChecker {
 /// to also typecheck instead of hitting this guard.
 const MAX_CHECK_DEPTH: u32 = 550;
 
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TypeChecker {
     pub fn new() -> Self {
         // RES-1349 + RES-2444: cache the built-in env once per
@@ -1861,3964 +2289,555 @@ impl TypeChecker {
         // loop) likewise skips re-cloning the builtin entries.
         static BUILTIN_ENV: std::sync::LazyLock<std::sync::Arc<TypeEnvironment>> =
             std::sync::LazyLock::new(|| {
-                // RES-1698: pre-size to fit the ~490 builtin entries.
-                // Saves 9 rehash rounds on the one-time LazyLock init.
-                let mut env = TypeEnvironment::with_capacity(512);
-
-                // Built-in function signatures. Any-typed parameters keep the
-                // type checker permissive for heterogeneous inputs until real
-                // generics arrive (RES-055).
-                let fn_any_to_void = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Void),
-                };
-                let fn_any_any_to_any = || Type::Function {
-                    params: vec![Type::Any, Type::Any],
-                    return_type: Box::new(Type::Any),
-                };
-                let fn_any_to_any = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Any),
-                };
-                let fn_any_to_int = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Int),
-                };
-                let fn_any_any_to_bool = || Type::Function {
-                    params: vec![Type::Any, Type::Any],
-                    return_type: Box::new(Type::Bool),
-                };
-                let fn_any_any_to_int = || Type::Function {
-                    params: vec![Type::Any, Type::Any],
-                    return_type: Box::new(Type::Int),
-                };
-                let fn_any_any_to_array = || Type::Function {
-                    params: vec![Type::Any, Type::Any],
-                    return_type: Box::new(Type::Array),
-                };
-                let fn_any_to_result = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Result),
-                };
-                let fn_result_to_bool = || Type::Function {
-                    params: vec![Type::Result],
-                    return_type: Box::new(Type::Bool),
-                };
-                let fn_result_to_any = || Type::Function {
-                    params: vec![Type::Result],
-                    return_type: Box::new(Type::Any),
-                };
-
-                // I/O
-                env.set("println".to_string(), fn_any_to_void());
-                env.set("print".to_string(), fn_any_to_void());
+                // Every builtin's signature is declared next to its
+                // evaluator in `builtin_registry::BUILTINS`.
+                let mut env =
+                    TypeEnvironment::with_capacity(crate::builtin_registry::BUILTINS.len());
+                for (name, ty) in crate::builtin_registry::signatures() {
+                    env.set(name.to_string(), ty);
+                }
 
-                // RES-385: `drop(v)` — explicit single-use consumption
-                // of a linear value. Accepts any type; the linearity pass
-                // (see `crate::linear`) is what enforces the single-use
-                // rule on the argument.
-                env.set("drop".to_string(), fn_any_to_void());
+                std::sync::Arc::new(env)
+            });
 
-                // Math (single-arg — int/float passed as Any)
-                env.set("abs".to_string(), fn_any_to_any());
+        // RES-1692: pre-size the per-typecheck HashMaps that grow
+        // once per top-level fn / struct during the hoist pass.
+        // Same shape as RES-1686 / RES-1688 / RES-1690 — perf-only,
+        // saves 2-3 rehash rounds per typecheck on medium / large
+        // programs. `contract_table` and `fn_decl_spans` see one
+        // entry per top-level fn (50+ on `large.rz`); the rest grow
+        // slower but cost a fixed small allocation each anyway.
+        const PRESIZE: usize = 32;
+        let mut checker = TypeChecker {
+            env: {
+                let mut env =
+                    TypeEnvironment::new_with_outer_arc(std::sync::Arc::clone(&BUILTIN_ENV));
+                // Plugin builtins can load after `BUILTIN_ENV` is cached.
+                for name in crate::plugin::builtin_names() {
+                    env.set(name.to_string(), Type::Any);
+                }
+                env
+            },
+            contract_table: HashMap::with_capacity(PRESIZE),
+            fn_decl_spans: HashMap::with_capacity(PRESIZE),
+            overload_sets: HashMap::new(),
+            const_bindings: HashMap::with_capacity(PRESIZE),
+            stats: VerificationStats::default(),
+            certificates: Vec::new(),
+            struct_fields: HashMap::with_capacity(PRESIZE),
+            generic_struct_info: HashMap::new(),
+            unresolved_struct_literals: Vec::new(),
+            unresolved_type_names: std::cell::RefCell::new(Vec::new()),
+            path_facts: Vec::new(),
+            fact_locals: None,
+            predicates: HashMap::new(),
+            // RES-1398: clone the cached builtin enum_decls (Option /
+            // Result) HashMap instead of rebuilding it from scratch.
+            // Pattern mirrors RES-1349's BUILTIN_ENV cache — the data
+            // is invariant, so populate once per process via LazyLock
+            // and pay an Arc bump per `TypeChecker::new()` instead of
+            // 2 Vec allocations + 4 EnumVariant struct constructions
+            // + 4 `String::to_string()`s per call. `Arc` (not `Rc`)
+            // so the static can be `Sync`; atomic refcount ops are
+            // still trivially cheaper than the allocations they
+            // replace.
+            enum_decls: {
+                static BUILTIN_ENUM_DECLS: std::sync::LazyLock<
+                    std::collections::HashMap<String, std::sync::Arc<Vec<crate::EnumVariant>>>,
+                > = std::sync::LazyLock::new(|| {
+                    let mut m = std::collections::HashMap::new();
+                    let s = crate::span::Span::default();
+                    m.insert(
+                        "Option".to_string(),
+                        std::sync::Arc::new(vec![
+                            crate::EnumVariant {
+                                name: "Some".to_string(),
+                                span: s,
+                                payload: crate::EnumPayload::None,
+                                discriminant: None,
+                            },
+                            crate::EnumVariant {
+                                name: "None".to_string(),
+                                span: s,
+                                payload: crate::EnumPayload::None,
+                                discriminant: None,
+                            },
+                        ]),
+                    );
+                    m.insert(
+                        "Result".to_string(),
+                        std::sync::Arc::new(vec![
+                            crate::EnumVariant {
+                                name: "Ok".to_string(),
+                                span: s,
+                                payload: crate::EnumPayload::None,
+                                discriminant: None,
+                            },
+                            crate::EnumVariant {
+                                name: "Err".to_string(),
+                                span: s,
+                                payload: crate::EnumPayload::None,
+                                discriminant: None,
+                            },
+                        ]),
+                    );
+                    m
+                });
+                BUILTIN_ENUM_DECLS.clone()
+            },
+            enum_type_params: HashMap::new(),
+            type_aliases: HashMap::with_capacity(PRESIZE),
+            // RES-137: ticket's default is 5 seconds per query.
+            verifier_timeout_ms: 5000,
+            // RES-217: partial-proof warnings on by default.
+            warn_unverified: true,
+            strict_any: crate::strict_any::strict_any(),
+            coercion: crate::coercion::policy(),
+            warnings: crate::warnings::config(),
+            warned: HashSet::new(),
+            denied_warnings: 0,
+            // RES-217: populated by `check_program_with_source`.
+            source_path: String::new(),
+            // RES-189: populated during LetStatement handling.
+            let_type_hints: Vec::new(),
+            // RES-2569: populated during function / closure checking.
+            fn_return_type_hints: Vec::new(),
+            // RES-387: no enclosing fn at program start.
+            current_fn_fails: None,
+            // RES-403: no enclosing fn return type at program start.
+            current_fn_return_type: None,
+            // A-E3 (RES-3933): no enclosing impl block at program start.
+            current_self_assoc_types: HashMap::new(),
+            // RES-910: loop depth starts at 0 (top-level is not a loop).
+            loop_depth: 0,
+            // RES-2653: no enclosing labeled loops at the top level.
+            loop_label_stack: Vec::new(),
+            // RES-354: auto-detect theory by default.
+            #[cfg(feature = "z3")]
+            z3_theory: crate::verifier_z3::Z3Theory::Auto,
+            // RES-318: per-loop-invariant verbose stderr line is OFF
+            // by default. The driver flips it on via `--verbose`.
+            verbose_loop_invariants: false,
+            // RES-1322: opt-in. Audit + explain-effects drivers set
+            // this; default-mode runs (every non-audit CLI invocation,
+            // the LSP/REPL, every `cargo test` typecheck) skip the
+            // `infer_fn_effects` fixpoint.
+            audit_stats: false,
+            // RES-1353: opt-in. LSP sets this; default-mode runs skip
+            // populating `let_type_hints` (it's only consumed by the
+            // inlay-hint provider).
+            capture_inlay_hints: false,
+            capture_scopes: false,
+            scopes: Vec::new(),
+            // RES-1357: opt-in. The `--emit-certificate` driver flips
+            // this; every other invocation skips pushing
+            // `CapturedCertificate` onto the Vec it'd drop on
+            // TypeChecker drop.
+            emit_certificates: false,
+            // RES-1862: default to zero span (synthetic / unknown).
+            current_span: Span::default(),
 
-                // RES-422: sign(x) always returns -1, 0, or +1 — that's Int.
-                env.set(
-                    "sign".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
+            fn_type_params: HashMap::new(),
+            trait_impls: HashMap::new(),
+            trait_supers: HashMap::new(),
+            trait_default_methods: HashMap::new(),
+            // RES-4190: no recursion depth at construction.
+            check_depth: 0,
+            parallel_bodies: cfg!(feature = "parallel-check"),
+        };
+        // Prelude functions are checked like the program's own hoisted
+        // fns, by signature; a program's own declaration replaces one.
+        if crate::prelude::enabled() {
+            for (name, parameters, return_type) in crate::prelude::signatures() {
+                let ty = checker.hoisted_fn_type(&parameters, return_type.as_deref());
+                checker.env.set(name, ty);
+            }
+        }
+        checker
+    }
 
-                // RES-411: float predicates — return Bool; math functions return Float.
-                // (Parameter is kept as Any so both Int and Float are accepted.)
-                let fn_any_to_bool = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Bool),
-                };
-                let fn_any_to_float = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Float),
-                };
-                env.set("is_nan".to_string(), fn_any_to_bool());
-                env.set("is_inf".to_string(), fn_any_to_bool());
-                env.set("is_finite".to_string(), fn_any_to_bool());
-                env.set("sqrt".to_string(), fn_any_to_float());
-                env.set("floor".to_string(), fn_any_to_float());
-                env.set("ceil".to_string(), fn_any_to_float());
-                env.set("min".to_string(), fn_any_any_to_any());
-                env.set("max".to_string(), fn_any_any_to_any());
-                // RES-415: gcd/lcm — strict (Int, Int) -> Int.
-                env.set(
-                    "gcd".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "lcm".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-536: gcd / lcm reduction over an integer array.
-                let arr_to_int = Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Int),
-                };
-                env.set("gcd_array".to_string(), arr_to_int.clone());
-                env.set("lcm_array".to_string(), arr_to_int);
-                // RES-567: factorial with overflow detection.
-                env.set(
-                    "factorial".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-568: binomial coefficient C(n, k).
-                env.set(
-                    "binomial".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-569: n-th Fibonacci with overflow detection.
-                env.set(
-                    "fibonacci".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-570: trial-division primality test.
-                env.set(
-                    "is_prime".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-571: smallest prime greater than n.
-                env.set(
-                    "next_prime".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set("pow".to_string(), fn_any_any_to_any());
-                // RES-295: clamp(x, lo, hi) — type-preserving for Int triples,
-                // promoted to Float if any arg is Float. Signed as
-                // (Any, Any, Any) -> Any to match abs/min/max precedent.
-                env.set(
-                    "clamp".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
+    /// RES-137: override the per-query Z3 solver timeout in ms.
+    /// Called by the driver from the `--verifier-timeout-ms` CLI
+    /// flag. Pass `0` to disable the timeout entirely (NIA proofs
+    /// that would otherwise hit the default budget run to
+    /// completion — use at your own risk).
+    pub fn with_verifier_timeout_ms(mut self, ms: u32) -> Self {
+        self.verifier_timeout_ms = ms;
+        self
+    }
 
-                // RES-146: transcendentals. Float-in / Float-out per
-                // RES-130 (no implicit int↔float coercion).
-                let fn_float_to_float = || Type::Function {
-                    params: vec![Type::Float],
-                    return_type: Box::new(Type::Float),
-                };
-                env.set("sin".to_string(), fn_float_to_float());
-                env.set("cos".to_string(), fn_float_to_float());
-                env.set("tan".to_string(), fn_float_to_float());
-                // RES-894.
-                env.set("to_radians".to_string(), fn_float_to_float());
-                // RES-895.
-                env.set("to_degrees".to_string(), fn_float_to_float());
-                env.set("ln".to_string(), fn_float_to_float());
-                // RES-889.
-                env.set("log10".to_string(), fn_float_to_float());
-                // RES-890.
-                env.set("log2".to_string(), fn_float_to_float());
-                env.set("exp".to_string(), fn_float_to_float());
-                // RES-891.
-                env.set("exp2".to_string(), fn_float_to_float());
-                // RES-896.
-                env.set("sinh".to_string(), fn_float_to_float());
-                // RES-897.
-                env.set("cosh".to_string(), fn_float_to_float());
-                // RES-898.
-                env.set("tanh".to_string(), fn_float_to_float());
-                // RES-899.
-                env.set("asinh".to_string(), fn_float_to_float());
-                // RES-900.
-                env.set("acosh".to_string(), fn_float_to_float());
-                // RES-901.
-                env.set("atanh".to_string(), fn_float_to_float());
-                // RES-902.
-                env.set("asin".to_string(), fn_float_to_float());
-                // RES-903.
-                env.set("acos".to_string(), fn_float_to_float());
-                // RES-904.
-                env.set("atan".to_string(), fn_float_to_float());
-                // RES-905.
-                env.set("cbrt".to_string(), fn_float_to_float());
-                // RES-907: bit-counting integer builtins (Int -> Int).
-                let fn_int_to_int = || Type::Function {
-                    params: vec![Type::Int],
-                    return_type: Box::new(Type::Int),
-                };
-                env.set("count_ones".to_string(), fn_int_to_int());
-                env.set("count_zeros".to_string(), fn_int_to_int());
-                env.set("leading_zeros".to_string(), fn_int_to_int());
-                env.set("trailing_zeros".to_string(), fn_int_to_int());
-                // RES-1115..1118: overflow-safe integer arithmetic. The
-                // checked_* family returns Option<int>.
-                // RES-2651: use Type::Option(Int) so pattern matching
-                // on `Some(x)` binds `x` to `Int`, not `Any`.
-                let fn_int_int_to_int = || Type::Function {
-                    params: vec![Type::Int, Type::Int],
-                    return_type: Box::new(Type::Int),
-                };
-                let fn_int_int_to_option_int = || Type::Function {
-                    params: vec![Type::Int, Type::Int],
-                    return_type: Box::new(Type::Option(Box::new(Type::Int))),
-                };
-                env.set("saturating_add".to_string(), fn_int_int_to_int());
-                env.set("saturating_sub".to_string(), fn_int_int_to_int());
-                env.set("saturating_mul".to_string(), fn_int_int_to_int());
-                env.set("wrapping_add".to_string(), fn_int_int_to_int());
-                env.set("wrapping_sub".to_string(), fn_int_int_to_int());
-                env.set("wrapping_mul".to_string(), fn_int_int_to_int());
-                env.set("checked_add".to_string(), fn_int_int_to_option_int());
-                env.set("checked_sub".to_string(), fn_int_int_to_option_int());
-                env.set("checked_mul".to_string(), fn_int_int_to_option_int());
-                env.set("checked_div".to_string(), fn_int_int_to_option_int());
-                // RES-1119..1121: bit manipulation.
-                env.set("rotate_left_int".to_string(), fn_int_int_to_int());
-                env.set("rotate_right_int".to_string(), fn_int_int_to_int());
-                env.set("reverse_bits".to_string(), fn_int_to_int());
-                env.set("swap_bytes".to_string(), fn_int_to_int());
-                // RES-1122..1123: int ↔ bytes endianness conversion.
-                env.set(
-                    "to_be_bytes".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Bytes),
-                    },
-                );
-                env.set(
-                    "to_le_bytes".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Bytes),
-                    },
-                );
-                env.set(
-                    "from_be_bytes".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "from_le_bytes".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-1124: integer-only math primitives.
-                env.set("isqrt".to_string(), fn_int_to_int());
-                env.set("ipow".to_string(), fn_int_int_to_int());
-                // RES-1126..1128: direction-rounded + Euclidean + midpoint.
-                env.set("div_ceil".to_string(), fn_int_int_to_int());
-                env.set("div_floor".to_string(), fn_int_int_to_int());
-                env.set("div_euclid".to_string(), fn_int_int_to_int());
-                env.set("rem_euclid".to_string(), fn_int_int_to_int());
-                env.set("midpoint".to_string(), fn_int_int_to_int());
-                // RES-1129: integer logarithms.
-                env.set("ilog2".to_string(), fn_int_to_int());
-                env.set("ilog10".to_string(), fn_int_to_int());
-                // RES-1130: IEEE 754 bit reinterpret cast.
-                env.set(
-                    "float_to_bits".to_string(),
-                    Type::Function {
-                        params: vec![Type::Float],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "float_from_bits".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
-                // RES-1134: bitwise + construction ops on Bytes.
-                let fn_bytes_bytes_to_bytes = || Type::Function {
-                    params: vec![Type::Bytes, Type::Bytes],
-                    return_type: Box::new(Type::Bytes),
-                };
-                let fn_bytes_to_bytes = || Type::Function {
-                    params: vec![Type::Bytes],
-                    return_type: Box::new(Type::Bytes),
-                };
-                env.set("bytes_xor".to_string(), fn_bytes_bytes_to_bytes());
-                env.set("bytes_and".to_string(), fn_bytes_bytes_to_bytes());
-                env.set("bytes_or".to_string(), fn_bytes_bytes_to_bytes());
-                env.set("bytes_not".to_string(), fn_bytes_to_bytes());
-                env.set(
-                    "bytes_fill".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Bytes),
-                    },
-                );
-                env.set("bytes_reverse".to_string(), fn_bytes_to_bytes());
-                // RES-1136: alignment helpers.
-                env.set("next_multiple_of".to_string(), fn_int_int_to_int());
-                env.set(
-                    "is_multiple_of".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-1138: IEEE 754 classification + total order + sign-bit
-                // predicates.
-                let fn_float_to_bool = || Type::Function {
-                    params: vec![Type::Float],
-                    return_type: Box::new(Type::Bool),
-                };
-                env.set(
-                    "float_classify".to_string(),
-                    Type::Function {
-                        params: vec![Type::Float],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "float_total_cmp".to_string(),
-                    Type::Function {
-                        params: vec![Type::Float, Type::Float],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set("float_is_normal".to_string(), fn_float_to_bool());
-                env.set("float_is_subnormal".to_string(), fn_float_to_bool());
-                env.set("float_sign_bit".to_string(), fn_float_to_bool());
-                // RES-1142: array chunking + striding + rotation primitives.
-                let fn_array_int_to_array = || Type::Function {
-                    params: vec![Type::Array, Type::Int],
-                    return_type: Box::new(Type::Array),
-                };
-                env.set("array_chunks".to_string(), fn_array_int_to_array());
-                env.set("array_chunks_exact".to_string(), fn_array_int_to_array());
-                env.set("array_step".to_string(), fn_array_int_to_array());
-                env.set("array_rotate_left".to_string(), fn_array_int_to_array());
-                env.set("array_rotate_right".to_string(), fn_array_int_to_array());
-                // RES-1140: ASCII char-class predicates.
-                let fn_string_to_bool = || Type::Function {
-                    params: vec![Type::String],
-                    return_type: Box::new(Type::Bool),
-                };
-                env.set("is_ascii".to_string(), fn_string_to_bool());
-                env.set("is_ascii_whitespace".to_string(), fn_string_to_bool());
-                env.set("is_ascii_hexdigit".to_string(), fn_string_to_bool());
-                env.set("is_ascii_uppercase".to_string(), fn_string_to_bool());
-                env.set("is_ascii_lowercase".to_string(), fn_string_to_bool());
-                env.set("is_ascii_punctuation".to_string(), fn_string_to_bool());
-                env.set("is_ascii_control".to_string(), fn_string_to_bool());
-                // RES-1146: float / string sort + array_is_sorted predicates.
-                let fn_array_to_array = || Type::Function {
-                    params: vec![Type::Array],
-                    return_type: Box::new(Type::Array),
-                };
-                let fn_array_to_bool = || Type::Function {
-                    params: vec![Type::Array],
-                    return_type: Box::new(Type::Bool),
-                };
-                env.set("array_sort_float".to_string(), fn_array_to_array());
-                env.set("array_sort_string".to_string(), fn_array_to_array());
-                env.set("array_is_sorted".to_string(), fn_array_to_bool());
-                env.set("array_is_sorted_float".to_string(), fn_array_to_bool());
-                env.set("array_is_sorted_string".to_string(), fn_array_to_bool());
-                // RES-1148: binary search on sorted int / float / string arrays.
-                // Return type is Value::Result (ok/err) — now typed as Type::Result
-                // since the runtime confirmed returns Value::Result { ok, payload }.
-                env.set(
-                    "array_binary_search".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Int],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "array_binary_search_float".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Float],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "array_binary_search_string".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-1150: statistical reductions — variance, stddev,
-                // median_float, range_float. All return Float.
-                let fn_array_to_float = || Type::Function {
-                    params: vec![Type::Array],
-                    return_type: Box::new(Type::Float),
-                };
-                env.set("array_variance_int".to_string(), fn_array_to_float());
-                env.set("array_variance_float".to_string(), fn_array_to_float());
-                env.set("array_stddev_int".to_string(), fn_array_to_float());
-                env.set("array_stddev_float".to_string(), fn_array_to_float());
-                env.set("array_median_float".to_string(), fn_array_to_float());
-                env.set("array_range_float".to_string(), fn_array_to_float());
-                // RES-1152: per-byte helpers — repeat / count_byte / replace_byte.
-                env.set(
-                    "bytes_repeat".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes, Type::Int],
-                        return_type: Box::new(Type::Bytes),
-                    },
-                );
-                env.set(
-                    "bytes_count_byte".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "bytes_replace_byte".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes, Type::Int, Type::Int],
-                        return_type: Box::new(Type::Bytes),
-                    },
-                );
-                // RES-1156: per-bit accessors on i64.
-                env.set("set_bit".to_string(), fn_int_int_to_int());
-                env.set("clear_bit".to_string(), fn_int_int_to_int());
-                env.set(
-                    "get_bit".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set("flip_bit".to_string(), fn_int_int_to_int());
-                // RES-1158: array set-style helpers + fallback-safe first/last
-                // + index_of_last.
-                let fn_array_array_to_array = || Type::Function {
-                    params: vec![Type::Array, Type::Array],
-                    return_type: Box::new(Type::Array),
-                };
-                env.set("array_difference".to_string(), fn_array_array_to_array());
-                env.set("array_intersection".to_string(), fn_array_array_to_array());
-                env.set(
-                    "array_index_of_last".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "array_first_or".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "array_last_or".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-1162: deterministic hash builtins.
-                env.set(
-                    "hash_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "hash_string".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "hash_bytes".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set("hash_combine".to_string(), fn_int_int_to_int());
-                // RES-2560: SHA-256 and SHA-512 cryptographic hash builtins.
-                env.set(
-                    "sha256".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "sha256_str".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "sha512".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "sha512_str".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-2561: CRC-32 and CRC-16 checksum builtins.
-                env.set(
-                    "crc32".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "crc32_str".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "crc16".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "crc16_str".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-2555: TCP/UDP networking builtins (std-only).
-                for name in &["tcp_connect", "tcp_listen", "tcp_read", "tcp_write"] {
-                    env.set(
-                        (*name).to_string(),
-                        Type::Function {
-                            params: vec![Type::Any, Type::Any],
-                            return_type: Box::new(Type::Result),
-                        },
-                    );
-                }
-                env.set(
-                    "tcp_accept".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "tcp_close".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "tcp_set_timeout".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "udp_bind".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "udp_send_to".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::String, Type::String, Type::Int],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "udp_recv_from".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "udp_close".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-2558: process execution builtins (std-only). Return Result<Struct>.
-                env.set(
-                    "exec".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Array],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "exec_shell".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-2557: file metadata builtins (std-only).
-                env.set(
-                    "file_exists".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "file_is_dir".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "file_is_file".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "file_size".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "file_stat".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "dir_list".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-2554: JSON serialization builtins.
-                env.set(
-                    "to_json".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "from_json".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "json_encode".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "json_decode".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "json_encode_pretty".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "json_valid".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-2585: regex builtins.
-                env.set(
-                    "regex_match".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "regex_find".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Option(Box::new(Type::String))),
-                    },
-                );
-                env.set(
-                    "regex_find_all".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "regex_captures".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Option(Box::new(Type::Array))),
-                    },
-                );
-                env.set(
-                    "regex_replace".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "regex_replace_all".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-2559: date/time builtins.
-                env.set(
-                    "datetime_now".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Struct("DateTime".to_string())),
-                    },
-                );
-                env.set(
-                    "datetime_from_unix".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Struct("DateTime".to_string())),
-                    },
-                );
-                env.set(
-                    "datetime_to_unix".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "datetime_format".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "datetime_parse".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-2556: HTTP client builtins.
-                env.set(
-                    "http_get".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "http_post".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-1164: iteration helpers.
-                env.set(
-                    "enumerate".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "array_zip3".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Array, Type::Array],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "string_truncate".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-1166: rounding builtins.
-                let fn_float_to_float = || Type::Function {
-                    params: vec![Type::Float],
-                    return_type: Box::new(Type::Float),
-                };
-                let fn_float_to_int = || Type::Function {
-                    params: vec![Type::Float],
-                    return_type: Box::new(Type::Int),
-                };
-                env.set("round".to_string(), fn_float_to_float());
-                env.set("trunc".to_string(), fn_float_to_float());
-                env.set("round_to_int".to_string(), fn_float_to_int());
-                env.set("trunc_to_int".to_string(), fn_float_to_int());
-                // RES-1170: cumulative reductions + combined min/max.
-                let fn_array_to_array_int = || Type::Function {
-                    params: vec![Type::Array],
-                    return_type: Box::new(Type::Array),
-                };
-                env.set("array_cumsum".to_string(), fn_array_to_array_int());
-                env.set("array_cumprod".to_string(), fn_array_to_array_int());
-                env.set("array_diffs".to_string(), fn_array_to_array_int());
-                env.set("array_min_max".to_string(), fn_array_to_array_int());
-                // RES-1172: small string + array gaps.
-                let fn_string_to_array = || Type::Function {
-                    params: vec![Type::String],
-                    return_type: Box::new(Type::Array),
-                };
-                let fn_string_string_to_array = || Type::Function {
-                    params: vec![Type::String, Type::String],
-                    return_type: Box::new(Type::Array),
-                };
-                env.set("string_split_once".to_string(), fn_string_string_to_array());
-                env.set(
-                    "string_rsplit_once".to_string(),
-                    fn_string_string_to_array(),
-                );
-                env.set(
-                    "string_from_chars".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "array_is_empty".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-1174: wall-clock unix time. @io / impure.
-                let fn_zero_to_int = || Type::Function {
-                    params: vec![],
-                    return_type: Box::new(Type::Int),
-                };
-                env.set("unix_time_s".to_string(), fn_zero_to_int());
-                env.set("unix_time_ms".to_string(), fn_zero_to_int());
-                env.set("unix_time_ns".to_string(), fn_zero_to_int());
-                // RES-1176: bytes ↔ string conversions.
-                let fn_bytes_bytes_to_bytes_strip = || Type::Function {
-                    params: vec![Type::Bytes, Type::Bytes],
-                    return_type: Box::new(Type::Bytes),
-                };
-                env.set(
-                    "bytes_strip_prefix".to_string(),
-                    fn_bytes_bytes_to_bytes_strip(),
-                );
-                env.set(
-                    "bytes_strip_suffix".to_string(),
-                    fn_bytes_bytes_to_bytes_strip(),
-                );
-                // RES-1178: bytes_to_string always produces a String value on
-                // the success path (invalid UTF-8 returns a lossy string, not a
-                // different type). Promote from Any → String so the typechecker
-                // can verify that callers don't treat the result as an int/bool.
-                env.set(
-                    "bytes_to_string".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-1178: bytes slicing primitives — (Bytes, Int) -> Bytes.
-                let fn_bytes_int_to_bytes = || Type::Function {
-                    params: vec![Type::Bytes, Type::Int],
-                    return_type: Box::new(Type::Bytes),
-                };
-                env.set("bytes_take".to_string(), fn_bytes_int_to_bytes());
-                env.set("bytes_drop".to_string(), fn_bytes_int_to_bytes());
-                env.set("bytes_take_last".to_string(), fn_bytes_int_to_bytes());
-                env.set("bytes_drop_last".to_string(), fn_bytes_int_to_bytes());
-                // RES-1182: integer bit rotation + scalar signum.
-                let fn_int_int_to_int = || Type::Function {
-                    params: vec![Type::Int, Type::Int],
-                    return_type: Box::new(Type::Int),
-                };
-                env.set("rotate_left".to_string(), fn_int_int_to_int());
-                env.set("rotate_right".to_string(), fn_int_int_to_int());
-                env.set(
-                    "signum".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "log".to_string(),
-                    Type::Function {
-                        params: vec![Type::Float, Type::Float],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
-                // RES-295: atan2(y, x) — Float-only per RES-130.
-                env.set(
-                    "atan2".to_string(),
-                    Type::Function {
-                        params: vec![Type::Float, Type::Float],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
-                // RES-892.
-                env.set(
-                    "hypot".to_string(),
-                    Type::Function {
-                        params: vec![Type::Float, Type::Float],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
-                // RES-893.
-                env.set(
-                    "copysign".to_string(),
-                    Type::Function {
-                        params: vec![Type::Float, Type::Float],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
+    /// RES-217: toggle partial-proof warnings. When `true`
+    /// (default), Z3 `Unknown` verdicts surface as
+    /// `warning[partial-proof]: Z3 returned Unknown for
+    /// assertion at <file>:<line>:<col> — proof is incomplete`.
+    /// The driver flips this to `false` on `--no-warn-unverified`
+    /// for CI runs that want a quieter stderr.
+    pub fn with_warn_unverified(mut self, on: bool) -> Self {
+        self.warn_unverified = on;
+        self
+    }
 
-                // RES-147: monotonic ms-clock builtin. std-only.
-                env.set(
-                    "clock_ms".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
+    /// Toggle strict mode, overriding the process-wide `--strict`
+    /// flag for this checker. See `crate::strict_any`.
+    pub fn with_strict_any(mut self, on: bool) -> Self {
+        self.strict_any = on;
+        self
+    }
 
-                // RES-150: seedable random builtins. std-only.
-                env.set(
-                    "random_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "random_float".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
+    /// Bind `name` to `typ` in the top-level scope, for values that
+    /// reach the environment without a declaration (REPL results).
+    pub(crate) fn bind(&mut self, name: &str, typ: Type) {
+        self.env.set(name, typ);
+    }
 
-                // len: any -> int
-                env.set(
-                    "len".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
+    /// Override the process-wide warning settings for this checker.
+    /// See `crate::warnings`.
+    #[allow(dead_code)]
+    pub fn with_warnings(mut self, warnings: crate::warnings::WarningConfig) -> Self {
+        self.warnings = warnings;
+        self
+    }
 
-                // Array builtins: any -> array / (array,int,int) -> array
-                env.set(
-                    "push".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "pop".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "slice".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Int, Type::Int],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
+    /// Override the process-wide `--coercion` policy for this
+    /// checker. See `crate::coercion`.
+    #[allow(dead_code)]
+    pub fn with_coercion(mut self, policy: crate::coercion::CoercionPolicy) -> Self {
+        self.coercion = policy;
+        self
+    }
 
-                // String builtins
-                env.set(
-                    "split".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-1859: `string_split` — explicit-name alias for `split`.
-                env.set(
-                    "string_split".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-535: split with a maximum number-of-splits limit.
-                env.set(
-                    "string_split_n".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String, Type::Int],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-545: split on the last occurrence of the separator.
-                env.set(
-                    "string_split_last".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "trim".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-2548: contains is overloaded: (string, string), (array, any), (range, int).
-                env.set(
-                    "contains".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-2612 Task 6: intern(string) -> string for runtime deduplication.
-                env.set(
-                    "intern".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "to_upper".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "to_lower".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-412: reverse a string (chars) or an array (clones).
-                env.set(
-                    "string_reverse".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set("array_reverse".to_string(), fn_array_to_array());
-                // RES-1859: higher-order array builtins — callback is typed
-                // as Any because we have no generic function type yet.
-                env.set(
-                    "array_map".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "array_filter".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "array_reduce".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-507: generic callback-based search/predicate builtins.
-                env.set(
-                    "array_find".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "array_find_index".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "array_any".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "array_all".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
+    /// Permissive-policy warning for an implicit conversion at the
+    /// current span. Each source position is reported once.
+    fn warn_implicit_coercion(&mut self, message: String) {
+        let span = self.current_span;
+        self.warn_at(
+            crate::warnings::WarningCategory::ImplicitCoercion,
+            span,
+            &message,
+        );
+    }
 
-                // RES-2647: map functional operations (callback-taking).
-                // Return types use the same permissive-Any convention as map_keys/map_values.
-                env.set(
-                    "map_filter".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "map_map_values".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "map_for_each".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Void),
-                    },
-                );
-                env.set(
-                    "map_to_pairs".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "map_invert".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-2646: higher-order functional array operations.
-                env.set(
-                    "array_flat_map".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // array_group_by returns a Map (unparameterised, same convention
-                // as other map builtins — Type::Any until Map<K,V> lands).
-                env.set(
-                    "array_group_by".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // array_partition returns [[passing], [failing]] — two-element array.
-                env.set(
-                    "array_partition".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // map_from_pairs(pairs) -> Map (Any until Map<K,V> lands).
-                env.set(
-                    "map_from_pairs".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-2646: array_scan(arr, init, fn) -> Array of all prefix accumulator values.
-                env.set(
-                    "array_scan".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-2648: array combinators with arbitrary callbacks.
-                let arr_fn_to_arr = Type::Function {
-                    params: vec![Type::Array, Type::Any],
-                    return_type: Box::new(Type::Array),
-                };
-                env.set("array_sort_by".to_string(), arr_fn_to_arr.clone());
-                env.set("array_take_while".to_string(), arr_fn_to_arr.clone());
-                env.set("array_drop_while".to_string(), arr_fn_to_arr);
-                env.set(
-                    "array_min_by".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "array_max_by".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "array_count_if".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "array_zip_with".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Array, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "array_windows".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Int],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "array_sum_by".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "array_product_by".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-2649: map higher-order operations.
-                env.set(
-                    "map_merge_with".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "map_update_with".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-2649: string higher-order operations.
-                env.set(
-                    "string_map_chars".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Any],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "string_filter_by".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Any],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "string_fold".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "string_for_each_char".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Any],
-                        return_type: Box::new(Type::Void),
-                    },
-                );
-                // RES-2650: numeric utilities.
-                env.set(
-                    "lerp".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
-                env.set(
-                    "remap".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Any, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
-                env.set(
-                    "float_approx_eq".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "round_to".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
-                env.set(
-                    "int_pow".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-2650: collection extras.
-                env.set(
-                    "array_frequency_map".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "array_key_by".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "array_iterate".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-2651: Result/Option HOF.
-                let result_fn_2 = Type::Function {
-                    params: vec![Type::Any, Type::Any],
-                    return_type: Box::new(Type::Any),
-                };
-                env.set("result_map".to_string(), result_fn_2.clone());
-                env.set("result_and_then".to_string(), result_fn_2.clone());
-                env.set("result_map_err".to_string(), result_fn_2.clone());
-                env.set("result_or_else".to_string(), result_fn_2);
-                let option_fn_2 = Type::Function {
-                    params: vec![Type::Any, Type::Any],
-                    return_type: Box::new(Type::Any),
-                };
-                env.set("option_map".to_string(), option_fn_2.clone());
-                env.set("option_and_then".to_string(), option_fn_2.clone());
-                env.set("option_filter".to_string(), option_fn_2.clone());
-                env.set("option_or_else".to_string(), option_fn_2);
-                env.set(
-                    "option_ok_or".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-2652: type introspection + collection ergonomics.
-                env.set(
-                    "type_of".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "result_collect".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "array_from_fn".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-
-                // RES-2619 / RES-2711: char type builtins. Classification
-                // functions take one char-or-any and return Bool; conversion
-                // functions return the precise target type.
-                let fn_char_to_bool = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Bool),
-                };
-                let fn_char_to_char = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Char),
-                };
-                env.set("char_is_alpha".to_string(), fn_char_to_bool());
-                env.set("char_is_digit".to_string(), fn_char_to_bool());
-                env.set("char_is_whitespace".to_string(), fn_char_to_bool());
-                env.set("char_is_upper".to_string(), fn_char_to_bool());
-                env.set("char_is_lower".to_string(), fn_char_to_bool());
-                env.set("char_is_alphanumeric".to_string(), fn_char_to_bool());
-                env.set("char_is_ascii".to_string(), fn_char_to_bool());
-                env.set("char_to_upper".to_string(), fn_char_to_char());
-                env.set("char_to_lower".to_string(), fn_char_to_char());
-                env.set(
-                    "char_to_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "char_to_string".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "int_to_char".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Char),
-                    },
-                );
-
-                // RES-416: integer-array reductions.
-                env.set("array_sum".to_string(), fn_any_to_int());
-                env.set("array_product".to_string(), fn_any_to_int());
-                // RES-417: array min/max.
-                env.set("array_min".to_string(), fn_any_to_int());
-                env.set("array_max".to_string(), fn_any_to_int());
-                // RES-543: empty-safe min/max with fallback default.
-                let arr_int_to_int = Type::Function {
-                    params: vec![Type::Any, Type::Int],
-                    return_type: Box::new(Type::Int),
-                };
-                env.set("array_max_or".to_string(), arr_int_to_int.clone());
-                env.set("array_min_or".to_string(), arr_int_to_int);
-                // RES-549: integer mean (truncating toward zero).
-                env.set(
-                    "array_mean_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-550: integer median (truncating mean for even len).
-                env.set(
-                    "array_median_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-551: integer mode (most-common; smallest on ties).
-                env.set(
-                    "array_mode_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-552: peak-to-peak range (max − min).
-                env.set(
-                    "array_range_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-553: consecutive pairwise differences.
-                env.set(
-                    "array_diff_consec_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-554: per-element clamp to [lo, hi].
-                env.set(
-                    "array_clamp_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int, Type::Int],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-555: per-element sign (-1/0/1).
-                env.set(
-                    "array_signum_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-556: per-element absolute value.
-                env.set(
-                    "array_abs_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-557: dot product of two equal-length int arrays.
-                env.set(
-                    "array_dot_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-558: sum of squares (Σ x²).
-                env.set(
-                    "array_sum_squares_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-559: running prefix sum.
-                env.set(
-                    "array_cumsum_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-560: running max.
-                env.set(
-                    "array_cummax_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-561: running min.
-                env.set(
-                    "array_cummin_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-562: running product.
-                env.set(
-                    "array_cumprod_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-563: count elements in inclusive [lo, hi].
-                env.set(
-                    "array_count_in_range_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-503: index of max/min element.
-                env.set(
-                    "array_argmax_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "array_argmin_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-418: element search.
-                env.set("array_contains".to_string(), fn_any_any_to_bool());
-                env.set("array_index_of".to_string(), fn_any_any_to_int());
-                // RES-544: every index where element equals x.
-                env.set("array_index_of_all".to_string(), fn_any_any_to_array());
-                // RES-541: set-like operations on arrays.
-                env.set("array_intersect".to_string(), fn_any_any_to_array());
-                env.set("array_diff".to_string(), fn_any_any_to_array());
-                // RES-542: order-preserving global-dedup union.
-                env.set("array_union".to_string(), fn_any_any_to_array());
-                // RES-419: Unicode-scalar ↔ char conversions.
-                env.set(
-                    "chr".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "ord".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-505: parse single char to base-36 digit.
-                env.set(
-                    "char_to_digit".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-513: int 0..=35 → base-36 digit char.
-                env.set(
-                    "digit_to_char".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-420: concatenate two arrays.
-                env.set("array_concat".to_string(), fn_any_any_to_array());
-                // RES-515: three-way concatenation.
-                env.set(
-                    "array_concat3".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-421: take/drop first n.
-                env.set("array_take".to_string(), fn_any_any_to_array());
-                env.set("array_drop".to_string(), fn_any_any_to_array());
-                // RES-537: take/drop trailing n elements.
-                env.set("array_take_last".to_string(), fn_any_any_to_array());
-                env.set("array_drop_last".to_string(), fn_any_any_to_array());
-                // RES-514: pick every nth element.
-                env.set("array_step".to_string(), fn_any_any_to_array());
-                // RES-422: integer sort ascending.
-                env.set("array_sort".to_string(), fn_array_to_array());
-                // RES-443: integer sort descending.
-                env.set("array_sort_desc".to_string(), fn_array_to_array());
-                // RES-444: Fisher-Yates shuffle (impure: uses RNG).
-                env.set("array_shuffle".to_string(), fn_array_to_array());
-                // RES-445: array prefix/suffix predicates.
-                env.set("array_starts_with".to_string(), fn_any_any_to_bool());
-                env.set("array_ends_with".to_string(), fn_any_any_to_bool());
-                // RES-446: all match indices.
-                env.set("string_find_all".to_string(), fn_any_any_to_array());
-                // RES-546: first byte index of substring, -1 if missing.
-                env.set(
-                    "string_find".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-547: last byte index of substring, -1 if missing.
-                env.set(
-                    "string_rfind".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-548: split string at byte index → [before, after].
-                env.set(
-                    "string_split_at".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-447: i64 boundary constants — zero-arg → Int.
-                env.set(
-                    "int_min".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "int_max".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-448: array_position(arr, x, start).
-                env.set(
-                    "array_position".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-449: array padding (3-arg: arr, n, fill).
-                let fn_any_int_any_to_any = Type::Function {
-                    params: vec![Type::Any, Type::Int, Type::Any],
-                    return_type: Box::new(Type::Any),
-                };
-                env.set("array_pad_left".to_string(), fn_any_int_any_to_any.clone());
-                env.set("array_pad_right".to_string(), fn_any_int_any_to_any);
-                // RES-450: array_swap(arr, i, j) — 3-arg.
-                // RES-1859: return_type was Type::Any; swapping elements
-                // produces an array of the same type, so Array is correct.
-                env.set(
-                    "array_swap".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int, Type::Int],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-451: insert/remove at index.
-                // RES-1859: both produce a new array of the same element type.
-                env.set(
-                    "array_insert_at".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "array_remove_at".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-452: replace element at index.
-                // RES-1859: produces a new array — return_type was Type::Any.
-                env.set(
-                    "array_set_at".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-453: total Unicode-scalar at index.
-                env.set(
-                    "string_at".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-454: Unicode-scalar substring.
-                env.set(
-                    "string_substring".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int, Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-455: sliding windows.
-                env.set("array_window".to_string(), fn_any_any_to_array());
-                // RES-456: rotation.
-                env.set("array_rotate_left".to_string(), fn_any_any_to_array());
-                env.set("array_rotate_right".to_string(), fn_any_any_to_array());
-                // RES-457: capitalize.
-                env.set(
-                    "string_capitalize".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-458: array_cycle.
-                env.set("array_cycle".to_string(), fn_any_any_to_array());
-                // RES-459: ASCII-class string predicates.
-                let str_to_bool = Type::Function {
-                    params: vec![Type::String],
-                    return_type: Box::new(Type::Bool),
-                };
-                env.set("is_ascii_alpha".to_string(), str_to_bool.clone());
-                env.set("is_ascii_digit".to_string(), str_to_bool.clone());
-                env.set("is_ascii_alnum".to_string(), str_to_bool);
-                // RES-460: trim arbitrary char set.
-                env.set(
-                    "trim_chars".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-461: indent every line with n spaces.
-                env.set(
-                    "string_indent".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-462: adjacent pairs as tuples.
-                env.set("array_pairs".to_string(), fn_array_to_array());
-                // RES-463: UTF-8 byte length.
-                env.set(
-                    "string_bytes_len".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-564: byte at index, -1 if out of range.
-                env.set(
-                    "string_byte_at".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-565: string → array of UTF-8 bytes.
-                env.set(
-                    "string_to_bytes".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-566: array of bytes → Result<String, String>.
-                // RES-1859: return_type was Type::Any; the builtin returns a
-                // Result (Ok(string) on success, Err(string) on invalid UTF-8).
-                env.set(
-                    "string_from_bytes".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-464: parse int with explicit radix → Result<Int, String>.
-                env.set(
-                    "parse_int_base".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-465: render int in given radix → String.
-                env.set(
-                    "int_to_base".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-466: remove first matching element.
-                env.set("array_remove".to_string(), fn_any_any_to_array());
-                // RES-467: remove all matching elements.
-                env.set("array_remove_all".to_string(), fn_any_any_to_array());
-                // RES-468: collapse adjacent duplicates.
-                env.set("array_dedup".to_string(), fn_array_to_array());
-                // RES-2742: pain-points hardening builtins — sort by struct
-                // field, bounded-depth flatten, dedup by field, complement-any,
-                // int radix parse/format.
-                let arr_str_to_arr = Type::Function {
-                    params: vec![Type::Array, Type::String],
-                    return_type: Box::new(Type::Array),
-                };
-                env.set("array_sort_by_field".to_string(), arr_str_to_arr.clone());
-                env.set(
-                    "array_sort_by_field_desc".to_string(),
-                    arr_str_to_arr.clone(),
-                );
-                env.set("array_dedup_by".to_string(), arr_str_to_arr);
-                env.set(
-                    "array_flatten_depth".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Int],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "array_none".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "int_parse_hex".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "int_parse_bin".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "int_to_oct".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-504: partition into maximal runs of equal int elements.
-                // RES-2645: returns an array of groups (array of arrays).
-                env.set(
-                    "array_group_by_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-533: count of maximal runs.
-                env.set(
-                    "array_count_runs".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-469: scalar all/any equality predicates.
-                env.set("array_all_eq".to_string(), fn_any_any_to_bool());
-                env.set("array_any_eq".to_string(), fn_any_any_to_bool());
-                // RES-471: prefix/suffix strippers.
-                let str_str_to_str = Type::Function {
-                    params: vec![Type::String, Type::String],
-                    return_type: Box::new(Type::String),
-                };
-                env.set("string_strip_prefix".to_string(), str_str_to_str.clone());
-                env.set("string_strip_suffix".to_string(), str_str_to_str);
-                // RES-472: element-wise array equality.
-                env.set("array_eq".to_string(), fn_any_any_to_bool());
-                // RES-473: ternary numeric min/max.
-                let any3_to_any = Type::Function {
-                    params: vec![Type::Any, Type::Any, Type::Any],
-                    return_type: Box::new(Type::Any),
-                };
-                env.set("min3".to_string(), any3_to_any.clone());
-                env.set("max3".to_string(), any3_to_any);
-                // RES-474: array_ne.
-                env.set("array_ne".to_string(), fn_any_any_to_bool());
-                // RES-475: fixed-op integer fold.
-                env.set(
-                    "array_fold_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int, Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-502: running-fold (intermediate accumulators).
-                env.set(
-                    "array_scan_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int, Type::String],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-521: element-wise binary op on two int arrays.
-                env.set(
-                    "array_zip_with_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::String],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-477: one-sided char-set trimmers.
-                let str_str_to_str_b = Type::Function {
-                    params: vec![Type::String, Type::String],
-                    return_type: Box::new(Type::String),
-                };
-                env.set("trim_start_chars".to_string(), str_str_to_str_b.clone());
-                env.set("trim_end_chars".to_string(), str_str_to_str_b);
-                // RES-478: array_count_eq alias. RES-2645: count is always Int.
-                env.set("array_count_eq".to_string(), fn_any_any_to_int());
-                // RES-479: string predicates.
-                let str_to_bool_b = Type::Function {
-                    params: vec![Type::String],
-                    return_type: Box::new(Type::Bool),
-                };
-                env.set("is_empty".to_string(), str_to_bool_b.clone());
-                env.set("is_blank".to_string(), str_to_bool_b);
-                // RES-480: replace only first occurrence.
-                env.set(
-                    "string_replace_first".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-481: drop first / drop last.
-                env.set("array_rest".to_string(), fn_array_to_array());
-                env.set("array_init".to_string(), fn_array_to_array());
-                // RES-482: replace up to n occurrences.
-                env.set(
-                    "string_replace_n".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String, Type::String, Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-483: named-predicate take/drop on int arrays.
-                let arr_str_to_arr = Type::Function {
-                    params: vec![Type::Any, Type::String],
-                    return_type: Box::new(Type::Any),
-                };
-                env.set("array_take_while_int".to_string(), arr_str_to_arr.clone());
-                env.set("array_drop_while_int".to_string(), arr_str_to_arr.clone());
-                // RES-484: named-predicate filter / partition on int arrays.
-                env.set("array_filter_int".to_string(), arr_str_to_arr.clone());
-                env.set("array_partition_int".to_string(), arr_str_to_arr);
-                // RES-500: named-predicate any-element on int arrays.
-                env.set(
-                    "array_any_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-501: named-predicate every-element on int arrays.
-                env.set(
-                    "array_all_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-530: named-predicate count on int arrays.
-                env.set(
-                    "array_count_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-539: indices of int elements matching named predicate.
-                env.set(
-                    "array_indices_where".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::String],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-485: |a - b|.
-                env.set(
-                    "abs_diff".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-486: (quotient, remainder) tuple — returns an Array of two Ints.
-                env.set(
-                    "divmod".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-423: flatten one level.
-                env.set("array_flatten".to_string(), fn_array_to_array());
-                // RES-424: join string array with separator.
-                env.set(
-                    "array_join".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-425: scalar-to-string conversion.
-                env.set(
-                    "to_string".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-426: first-occurrence dedupe.
-                env.set("array_unique".to_string(), fn_array_to_array());
-                // RES-427: count element occurrences.
-                env.set("array_count".to_string(), fn_any_any_to_int());
-                // RES-428: array first/last accessors.
-                env.set("array_first".to_string(), fn_any_to_any());
-                env.set("array_last".to_string(), fn_any_to_any());
-                // RES-528: bounded indexing with fallback default.
-                env.set(
-                    "array_get_or".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-429: string padding to Unicode-scalar width.
-                env.set(
-                    "string_pad_left".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "string_pad_right".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-540: center-pad a string to a Unicode-scalar width.
-                env.set(
-                    "string_pad_center".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-430: pair elements as tuples; truncate to shorter array.
-                env.set("array_zip".to_string(), fn_any_any_to_array());
-                // RES-531: split an array of 2-tuples into two parallel arrays — returns Array.
-                env.set(
-                    "array_unzip".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-431: integer range [start, end).
-                env.set(
-                    "array_range".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-921: array_slice(arr, lo, hi, inclusive) — sub-array.
+    /// Report a categorized warning at `span`, once per site.
+    fn warn_at(&mut self, category: crate::warnings::WarningCategory, span: Span, message: &str) {
+        let (line, col) = (span.start.line, span.start.column);
+        if !self.warnings.enabled(category) || !self.warned.insert((category, line, col)) {
+            return;
+        }
+        if self
+            .warnings
+            .report(category, &self.source_path, line, col, message)
+        {
+            self.denied_warnings += 1;
+        }
+    }
 
-                // RES-1859: a slice is still an array — return_type was Type::Any.
+    /// Under the permissive policy, an `int` / `float` operand pair
+    /// widens to `float`: warn and return `Float`. `None` when the
+    /// operands are not that pair or the policy does not widen.
+    fn widen_int_float(&mut self, op: &str, left: &Type, right: &Type) -> Option<Type> {
+        if !self.coercion.widens_int_to_float() {
+            return None;
+        }
+        if !matches!(
+            (left, right),
+            (Type::Int, Type::Float) | (Type::Float, Type::Int)
+        ) {
+            return None;
+        }
+        self.warn_implicit_coercion(format!(
+            "implicit conversion: int operand of `{}` widened to float",
+            op
+        ));
+        Some(Type::Float)
+    }
 
-                env.set(
-                    "array_slice".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Any, Type::Bool],
+    /// RES-354: override the SMT theory used for Z3 encoding.
+    /// `Z3Theory::Auto` (default) picks BV32 when bitwise ops are
+    /// detected; `Bv` forces BV32; `Lia` forces LIA (bails on
+    /// bitwise ops). Driver calls this from `--z3-theory`.
+    #[cfg(feature = "z3")]
+    pub fn with_z3_theory(mut self, theory: crate::verifier_z3::Z3Theory) -> Self {
+        self.z3_theory = theory;
+        self
+    }
 
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-522: indices of an array as a new array.
-                env.set("array_indices".to_string(), fn_array_to_array());
-                // RES-432: array of n copies.
-                env.set("array_repeat".to_string(), fn_any_any_to_array());
-                // RES-433: split string into single-char strings.
-                env.set("string_chars".to_string(), fn_string_to_array());
-                // RES-434: split string into lines (LF, CRLF).
-                env.set("string_lines".to_string(), fn_string_to_array());
-                // RES-496: split on Unicode whitespace.
-                env.set("string_words".to_string(), fn_string_to_array());
-                // RES-497: join string array with newline.
-                env.set(
-                    "string_join_lines".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-498: join string array with single space.
-                env.set(
-                    "string_unwords".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-499: take first n Unicode scalars.
-                env.set(
-                    "string_take".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-506: drop first n Unicode scalars.
-                env.set(
-                    "string_drop".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-435: split array into fixed-size chunks.
-                env.set("array_chunk".to_string(), fn_any_any_to_array());
-                // RES-436: non-overlapping substring count.
-                env.set(
-                    "string_count".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-523: count occurrences of a single character.
-                env.set(
-                    "string_count_char".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-524: char-index of a single character (-1 if absent).
-                env.set(
-                    "string_find_char".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-525: named-predicate prefix slicing on strings.
-                let str_str_to_str = Type::Function {
-                    params: vec![Type::String, Type::String],
-                    return_type: Box::new(Type::String),
-                };
-                env.set("string_take_while_char".to_string(), str_str_to_str.clone());
-                env.set("string_drop_while_char".to_string(), str_str_to_str.clone());
-                // RES-526: named-predicate global char filter.
-                env.set("string_filter_char".to_string(), str_str_to_str);
-                // RES-527: ASCII case-insensitive string equality.
-                env.set(
-                    "string_eq_ignore_case".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-437: insert separator between adjacent elements.
-                env.set("array_intersperse".to_string(), fn_any_any_to_array());
-                // RES-516: alternate elements from two arrays.
-                env.set("array_interleave".to_string(), fn_any_any_to_array());
-                // RES-438: one-sided trimmers.
-                env.set(
-                    "trim_start".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "trim_end".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-439: bisect array at index → tuple.
-                env.set("array_split_at".to_string(), fn_any_any_to_array());
-                // RES-440: integer bitwise ops — strict (Int) -> Int / (Int, Int) -> Int.
-                let int_int_to_int = Type::Function {
-                    params: vec![Type::Int, Type::Int],
-                    return_type: Box::new(Type::Int),
-                };
-                env.set("bit_and".to_string(), int_int_to_int.clone());
-                env.set("bit_or".to_string(), int_int_to_int.clone());
-                env.set("bit_xor".to_string(), int_int_to_int.clone());
-                env.set("bit_shl".to_string(), int_int_to_int.clone());
-                env.set("bit_shr".to_string(), int_int_to_int);
-                env.set(
-                    "bit_not".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-488: popcount.
-                env.set(
-                    "bit_count".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-489: count leading zero bits.
-                env.set(
-                    "bit_leading_zeros".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-490: count trailing zero bits.
-                env.set(
-                    "bit_trailing_zeros".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-511: single-bit test / set / clear / toggle.
-                let int_int_to_int = Type::Function {
-                    params: vec![Type::Int, Type::Int],
-                    return_type: Box::new(Type::Int),
-                };
-                env.set(
-                    "bit_test".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set("bit_set".to_string(), int_int_to_int.clone());
-                env.set("bit_clear".to_string(), int_int_to_int.clone());
-                env.set("bit_toggle".to_string(), int_int_to_int.clone());
-                // RES-520: circular bit rotation.
-                env.set("bit_rotate_left".to_string(), int_int_to_int.clone());
-                env.set("bit_rotate_right".to_string(), int_int_to_int.clone());
-                // RES-534: extract a single byte from an i64.
-                env.set("bit_byte".to_string(), int_int_to_int);
-                // RES-538: set a single byte of an i64.
-                env.set(
-                    "bit_set_byte".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-491: integer floor sqrt.
-                env.set(
-                    "int_sqrt".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-517: integer exponentiation.
-                env.set(
-                    "pow_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-518: integer division with explicit rounding.
-                let int_int_to_int = Type::Function {
-                    params: vec![Type::Int, Type::Int],
-                    return_type: Box::new(Type::Int),
-                };
-                env.set("ceil_div".to_string(), int_int_to_int.clone());
-                env.set("floor_div".to_string(), int_int_to_int.clone());
-                // RES-519: Python-style modulo (sign of divisor).
-                env.set("modulo".to_string(), int_int_to_int);
-                // RES-492: floor log base 2.
-                env.set(
-                    "int_log2".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-493: power-of-two predicate.
-                env.set(
-                    "is_pow2".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-494: round up to next power of two.
-                env.set(
-                    "next_pow2".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-495: int → lowercase hex string.
-                env.set(
-                    "int_to_hex".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-512: int → binary string.
-                env.set(
-                    "int_to_bin".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-442: last byte index of substring.
-                env.set(
-                    "last_index_of".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-413: repeat a string n times.
-                env.set(
-                    "string_repeat".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-414: first byte index of sub in s, or -1.
-                env.set(
-                    "index_of".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-145: replace + format.
-                env.set(
-                    "replace".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // `format`'s second argument is `Array<?>` — the prelude
-                // `Type::Array` is untyped (no element-type parameter yet),
-                // which fits the ticket's `Array<?>` signature.
-                env.set(
-                    "format".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Array],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-213: prefix/suffix tests + string repetition.
-                env.set(
-                    "starts_with".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "ends_with".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "repeat".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-339: string parsing and formatting.
-                env.set(
-                    "parse_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-529: non-erroring parse with fallback default.
-                env.set(
-                    "parse_int_or".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-532: non-erroring float parse with fallback default.
-                env.set(
-                    "parse_float_or".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Float],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
-                env.set(
-                    "parse_float".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "char_at".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "pad_left".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "pad_right".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int, Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-
-                // RES-130: explicit int ↔ float conversions. These are the
-                // only supported bridge between the two numeric types —
-                // arithmetic and literal-match pattern equality both reject
-                // implicit coercion (see `check_numeric_same_type`).
-                env.set(
-                    "to_float".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
-                env.set(
-                    "to_int".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-
-                // RES-366: pinned-width integer cast builtins. All accept
-                // Any (the call site holds whatever the source width is) and
-                // return the target type so the typechecker propagates the
-                // narrowed type into the surrounding expression.
-                let fn_any_to_int8 = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Int8),
-                };
-                let fn_any_to_int16 = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Int16),
-                };
-                let fn_any_to_int32 = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Int32),
-                };
-                let fn_any_to_int = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Int),
-                };
-                let fn_any_to_uint8 = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::UInt8),
-                };
-                let fn_any_to_uint16 = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::UInt16),
-                };
-                let fn_any_to_uint32 = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::UInt32),
-                };
-                let fn_any_to_uint64 = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::UInt64),
-                };
-                env.set("as_int8".to_string(), fn_any_to_int8());
-                env.set("as_int16".to_string(), fn_any_to_int16());
-                env.set("as_int32".to_string(), fn_any_to_int32());
-                env.set("as_int64".to_string(), fn_any_to_int());
-                env.set("as_uint8".to_string(), fn_any_to_uint8());
-                env.set("as_uint16".to_string(), fn_any_to_uint16());
-                env.set("as_uint32".to_string(), fn_any_to_uint32());
-                env.set("as_uint64".to_string(), fn_any_to_uint64());
-                // RES-2618: f32/f64 precision casts.
-                env.set(
-                    "as_f32".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Float32),
-                    },
-                );
-                env.set(
-                    "as_f64".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
+    /// Defer independent top-level fn bodies and check them together
+    /// once the top-level walk is done — on worker threads with
+    /// `--features parallel-check`, where this is the default.
+    /// Diagnostics and the first error come out in source order
+    /// either way.
+    pub fn with_parallel_bodies(mut self, on: bool) -> Self {
+        self.parallel_bodies = on;
+        self
+    }
 
-                // RES-138: current retry counter of the enclosing live block.
-                env.set(
-                    "live_retries".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
+    /// RES-318: enable verbose stderr output from the loop-invariant
+    /// verifier. The driver flips this on via the `--verbose` flag.
+    pub fn with_verbose_loop_invariants(mut self, on: bool) -> Self {
+        self.verbose_loop_invariants = on;
+        self
+    }
 
-                // RES-141: process-wide live-block telemetry.
-                env.set(
-                    "live_total_retries".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "live_total_exhaustions".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
+    /// RES-1322: opt into the post-typecheck `infer_fn_effects`
+    /// fixpoint that populates `self.stats.fn_effects`. The only
+    /// consumers of `fn_effects` are the `--audit` /
+    /// `--explain-effects` CLI drivers; for every other invocation
+    /// (the default `rz prog.rz` path, the LSP/REPL paths, every
+    /// `cargo test` typecheck call) the fixpoint is wasted work
+    /// because the result is never read. Default `false`; the driver
+    /// flips this on for the audit/explain-effects paths.
+    pub fn with_audit_stats(mut self, on: bool) -> Self {
+        self.audit_stats = on;
+        self
+    }
 
-                // RES-144: one-line stdin reader (std-only).
-                env.set(
-                    "input".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
+    /// RES-1353: opt into populating `self.let_type_hints` with an
+    /// entry per inferred `let` binding. The LSP backend reads
+    /// these to produce inlay hints; every other entry point
+    /// (default `rz prog.rz` path, REPL, every `cargo test`
+    /// typecheck call) discards them on TypeChecker drop. Default
+    /// `false`; LSP flips this on before running typecheck.
+    #[allow(dead_code)] // only called behind the `lsp` feature
+    pub fn with_capture_inlay_hints(mut self, on: bool) -> Self {
+        self.capture_inlay_hints = on;
+        self
+    }
 
-                // RES-1100: `version()` returns the compiler's
-                // CARGO_PKG_VERSION so programs can embed the toolchain
-                // version in build manifests / provenance certificates.
-                env.set(
-                    "version".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                // RES-2610: `include_str("path")` → string, `include_bytes("path")` → array.
-                env.set(
-                    "include_str".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "include_bytes".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
+    /// Opt into recording a `ScopeRecord` per block, for
+    /// `query_completions`.
+    pub fn with_capture_scopes(mut self, on: bool) -> Self {
+        self.capture_scopes = on;
+        self
+    }
 
-                // RES-2794: stacktrace() returns the current call stack as an array of strings.
-                env.set(
-                    "stacktrace".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
+    /// RES-1357: opt into pushing `CapturedCertificate` entries onto
+    /// `self.certificates`. The only consumer is the
+    /// `--emit-certificate <DIR>` driver in lib.rs; every other
+    /// path leaves the flag off so the per-push `fn_name.clone()` +
+    /// Vec growth doesn't fire on hot paths that never read the
+    /// certs.
+    pub fn with_emit_certificates(mut self, on: bool) -> Self {
+        self.emit_certificates = on;
+        self
+    }
 
-                // RES-143: file I/O builtins (std-only; the resilient-runtime
-                // sibling crate has no builtins table so its no_std posture is
-                // unaffected).
-                env.set(
-                    "file_read".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "file_write".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Void),
-                    },
-                );
-
-                // RES-409: streaming file I/O — `file_open`, `file_read_chunk`,
-                // `file_write_chunk`, `file_seek`, `file_close`. The `File`
-                // handle is a `Type::Any` (a struct in disguise) until the type
-                // system grows a dedicated linear-resource form. Each builtin
-                // returns `Type::Result` so the user is forced to handle errors.
-                env.set(
-                    "file_open".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "file_read_chunk".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "file_write_chunk".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Bytes],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "file_seek".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Int, Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "file_close".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-
-                // RES-151: read-only env-var accessor. `Result<String, String>`
-                // — absence is a first-class outcome, not a runtime halt.
-                env.set(
-                    "env".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-
-                // RES-148: Map builtins. The typechecker doesn't (yet) carry
-                // a dedicated `Type::Map<K, V>` constructor — following the
-                // same permissive-Any convention as the Array / Result
-                // builtins until G7 inference lands.
-                env.set(
-                    "map_new".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "map_insert".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "map_get".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "map_remove".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "map_keys".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "map_len".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-883.
-                env.set(
-                    "map_values".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-884.
-                env.set(
-                    "map_contains_key".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-1144: map_entries / map_merge / map_is_empty.
-                env.set(
-                    "map_entries".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "map_merge".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "map_is_empty".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
+    /// RES-318: read accessor for the verifier pass.
+    #[allow(dead_code)]
+    pub(crate) fn verifier_timeout_ms(&self) -> u32 {
+        self.verifier_timeout_ms
+    }
 
-                // RES-293: HashMap stdlib builtins. Same permissive-Any
-                // shape as the Map builtins above — once G7 inference lands
-                // these tighten to `HashMap<K, V>`.
-                env.set(
-                    "hashmap_new".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "hashmap_insert".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "hashmap_get".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                env.set(
-                    "hashmap_remove".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "hashmap_contains".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "hashmap_keys".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-885.
-                env.set(
-                    "hashmap_len".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-886.
-                env.set(
-                    "hashmap_values".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-1144: hashmap_entries / hashmap_merge / hashmap_is_empty.
-                env.set(
-                    "hashmap_entries".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "hashmap_merge".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "hashmap_is_empty".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-1154: set_is_empty / set_from_array / result_and / option_and.
-                env.set(
-                    "set_is_empty".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "set_from_array".to_string(),
-                    Type::Function {
-                        params: vec![Type::Array],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "result_and".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "option_and".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-1160: argmax / argmin for float and string arrays.
-                let fn_array_to_int = || Type::Function {
-                    params: vec![Type::Array],
-                    return_type: Box::new(Type::Int),
-                };
-                env.set("array_argmax_float".to_string(), fn_array_to_int());
-                env.set("array_argmin_float".to_string(), fn_array_to_int());
-                env.set("array_argmax_string".to_string(), fn_array_to_int());
-                env.set("array_argmin_string".to_string(), fn_array_to_int());
-                // RES-1168: precision-sensitive math — expm1 / ln_1p / mul_add / recip.
-                let fn_float_to_float_p = || Type::Function {
-                    params: vec![Type::Float],
-                    return_type: Box::new(Type::Float),
-                };
-                env.set("expm1".to_string(), fn_float_to_float_p());
-                env.set("ln_1p".to_string(), fn_float_to_float_p());
-                env.set(
-                    "mul_add".to_string(),
-                    Type::Function {
-                        params: vec![Type::Float, Type::Float, Type::Float],
-                        return_type: Box::new(Type::Float),
-                    },
-                );
-                env.set("recip".to_string(), fn_float_to_float_p());
+    /// RES-318: read accessor for the verifier pass.
+    #[allow(dead_code)]
+    pub(crate) fn verbose_loop_invariants(&self) -> bool {
+        self.verbose_loop_invariants
+    }
 
-                // RES-149: Set builtins. Same permissive-Any convention as
-                // Map — no dedicated `Type::Set<T>` until inference lands.
-                env.set(
-                    "set_new".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "set_insert".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "set_remove".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "set_has".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "set_len".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "set_items".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                // RES-876: set algebra primitives.
-                env.set(
-                    "set_union".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-877.
-                env.set(
-                    "set_intersection".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-878.
-                env.set(
-                    "set_difference".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                // RES-879.
-                env.set(
-                    "set_is_subset".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-880.
-                env.set(
-                    "set_is_superset".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-881.
-                env.set(
-                    "set_is_disjoint".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-882.
-                env.set(
-                    "set_symmetric_difference".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
+    /// RES-318: push a loop-invariant proof certificate so it
+    /// participates in the regular `--emit-certificate <DIR>` dump.
+    ///
+    /// RES-1357: only stores the cert when `emit_certificates` is
+    /// on. The `loop_invariants_proven` stat counter increments
+    /// unconditionally so the audit summary still reports the
+    /// total — only the SMT-LIB2 body Vec push is gated.
+    #[allow(dead_code)]
+    pub(crate) fn push_loop_invariant_certificate(&mut self, idx: usize, smt2: String) {
+        if self.emit_certificates {
+            self.certificates.push(CapturedCertificate {
+                fn_name: "<loop>".to_string(),
+                kind: "loop_invariant",
+                idx,
+                smt2,
+            });
+        }
+        self.stats.loop_invariants_proven += 1;
+    }
 
-                // RES-152: Bytes builtins. `bytes_slice` returns new Bytes;
-                // `byte_at` returns Int — the language has no `u8` yet.
-                env.set(
-                    "bytes_len".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "bytes_slice".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes, Type::Int, Type::Int],
-                        return_type: Box::new(Type::Bytes),
-                    },
-                );
-                env.set(
-                    "byte_at".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-887.
-                env.set(
-                    "bytes_concat".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes, Type::Bytes],
-                        return_type: Box::new(Type::Bytes),
-                    },
-                );
-                // RES-888.
-                env.set(
-                    "bytes_eq".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes, Type::Bytes],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-
-                // Result builtins
-                env.set("Ok".to_string(), fn_any_to_result());
-                env.set("Err".to_string(), fn_any_to_result());
-                env.set("is_ok".to_string(), fn_result_to_bool());
-                env.set("is_err".to_string(), fn_result_to_bool());
-                env.set("unwrap".to_string(), fn_result_to_any());
-                env.set("unwrap_err".to_string(), fn_result_to_any());
-
-                // RES-2651: Option constructors and predicates.
-                // `Some` is registered with return type Option<Any>; call
-                // sites override this with the concrete argument type (see
-                // the `Some(expr)` special case in CallExpression).
-                env.set(
-                    "Some".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Option(Box::new(Type::Any))),
-                    },
-                );
-                env.set("None".to_string(), Type::Option(Box::new(Type::Any)));
-                env.set(
-                    "is_some".to_string(),
-                    Type::Function {
-                        params: vec![Type::Option(Box::new(Type::Any))],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "is_none".to_string(),
-                    Type::Function {
-                        params: vec![Type::Option(Box::new(Type::Any))],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "unwrap_option".to_string(),
-                    Type::Function {
-                        params: vec![Type::Option(Box::new(Type::Any))],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
+    /// RES-318: helper for the verifier's unit tests — count proven
+    /// invariants by counting `loop_invariant`-kinded certificates.
+    #[cfg(feature = "z3")]
+    #[allow(dead_code)]
+    pub(crate) fn loop_invariant_certificate_count(&self) -> usize {
+        self.certificates
+            .iter()
+            .filter(|c| c.kind == "loop_invariant")
+            .count()
+    }
 
-                // RES-936/937: Result fallback variants — Result + default → Any.
-                let fn_result_any_to_any = || Type::Function {
-                    params: vec![Type::Result, Type::Any],
-                    return_type: Box::new(Type::Any),
-                };
-                env.set("result_unwrap_or".to_string(), fn_result_any_to_any());
-                env.set("result_unwrap_or_err".to_string(), fn_result_any_to_any());
-                // RES-938: Result <-> Option conversion.
-                env.set(
-                    "result_to_option".to_string(),
-                    Type::Function {
-                        params: vec![Type::Result],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "option_to_result".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-939: chain alternatives.
-                env.set(
-                    "option_or".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "result_or".to_string(),
-                    Type::Function {
-                        params: vec![Type::Result, Type::Result],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-940: power-of-two helpers.
-                env.set(
-                    "is_power_of_two".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "next_power_of_two".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-941: int-array statistics → Float.
-                let fn_array_to_float = || Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(Type::Float),
-                };
-                env.set("array_average".to_string(), fn_array_to_float());
-                env.set("array_median".to_string(), fn_array_to_float());
-                // RES-942: float-array reductions.
-                env.set("array_sum_float".to_string(), fn_array_to_float());
-                env.set("array_product_float".to_string(), fn_array_to_float());
-                env.set("array_min_float".to_string(), fn_array_to_float());
-                env.set("array_max_float".to_string(), fn_array_to_float());
-                env.set("array_average_float".to_string(), fn_array_to_float());
-                // RES-943: hex encoding.
-                env.set(
-                    "bytes_to_hex".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "bytes_from_hex".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Result),
-                    },
-                );
-                // RES-944: bytes search.
-                let fn_bytes_bytes_to_bool = || Type::Function {
-                    params: vec![Type::Bytes, Type::Bytes],
-                    return_type: Box::new(Type::Bool),
-                };
-                env.set("bytes_starts_with".to_string(), fn_bytes_bytes_to_bool());
-                env.set("bytes_ends_with".to_string(), fn_bytes_bytes_to_bool());
-                env.set(
-                    "bytes_index_of".to_string(),
-                    Type::Function {
-                        params: vec![Type::Bytes, Type::Bytes],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                // RES-945: default-fallback map accessors.
-                let fn_map_key_default = || Type::Function {
-                    params: vec![Type::Any, Type::Any, Type::Any],
-                    return_type: Box::new(Type::Any),
-                };
-                env.set("map_get_or".to_string(), fn_map_key_default());
-                env.set("hashmap_get_or".to_string(), fn_map_key_default());
-
-                // RES-328: `cell(initial)` — shared mutable container.
-                // Element type isn't tracked at the type-system layer (the
-                // generic story lands with G7); the runtime enforces that
-                // `.set` rebinds the inner value, and the inner value's
-                // dynamic type flows through `Type::Any`.
-                env.set("cell".to_string(), fn_any_to_any());
-
-                // RES-2586: deque builtins.
-                env.set(
-                    "deque_new".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set("deque_push_front".to_string(), fn_any_any_to_any());
-                env.set("deque_push_back".to_string(), fn_any_any_to_any());
-                env.set("deque_pop_front".to_string(), fn_any_to_any());
-                env.set("deque_pop_back".to_string(), fn_any_to_any());
-                env.set("deque_peek_front".to_string(), fn_any_to_any());
-                env.set("deque_peek_back".to_string(), fn_any_to_any());
-                env.set("deque_len".to_string(), fn_any_to_int());
-                env.set(
-                    "deque_is_empty".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
+    /// RES-2693: true when one of `a`/`b` is a concrete struct that
+    /// implements the other as a trait.  Checks both orderings so the
+    /// caller doesn't need to know which of the two types is the trait
+    /// and which is the struct.
+    ///
+    /// Only `Type::Struct(name)` pairs are examined; all other combinations
+    /// return `false`, so the method never fires for primitive types.
+    fn satisfies_trait_param(&self, a: &Type, b: &Type) -> bool {
+        let (Type::Struct(a_name), Type::Struct(b_name)) = (a, b) else {
+            return false;
+        };
+        // a is the implementing struct, b is the trait:
+        if self
+            .trait_impls
+            .get(a_name.as_str())
+            .is_some_and(|ts| ts.contains(b_name.as_str()))
+        {
+            return true;
+        }
+        // b is the implementing struct, a is the trait:
+        self.trait_impls
+            .get(b_name.as_str())
+            .is_some_and(|ts| ts.contains(a_name.as_str()))
+    }
 
-                // RES-2587: priority queue / binary heap builtins.
-                env.set(
-                    "heap_new".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "heap_new_max".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set("heap_push".to_string(), fn_any_any_to_any());
-                env.set("heap_pop".to_string(), fn_any_to_any());
-                env.set("heap_peek".to_string(), fn_any_to_any());
-                env.set("heap_len".to_string(), fn_any_to_int());
-                env.set(
-                    "heap_is_empty".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                // RES-2583: mutex and rwlock synchronization primitives.
-                env.set("mutex_new".to_string(), fn_any_to_any());
-                env.set("mutex_lock".to_string(), fn_any_to_any());
-                env.set("mutex_unlock".to_string(), fn_any_to_any());
-                env.set("mutex_try_lock".to_string(), fn_any_to_any());
-                env.set("rwlock_new".to_string(), fn_any_to_any());
-                env.set("rwlock_read".to_string(), fn_any_to_any());
-                env.set("rwlock_write".to_string(), fn_any_to_any());
-                env.set("rwlock_unlock".to_string(), fn_any_to_any());
-
-                // RES-2810: clock builtins.
-                env.set(
-                    "clock_now".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "clock_elapsed".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
+    fn named_struct_fields(&self, name: &str) -> Option<Vec<(String, Type)>> {
+        self.struct_fields
+            .get(name)
+            .map(|fields| fields.iter().map(|(n, t)| (n.clone(), t.clone())).collect())
+    }
 
-                // RES-2810: event journal builtins.
-                env.set(
-                    "tick_now".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "tick_advance".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "record_event".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "replay_events".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "clear_events".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "event_count".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
+    fn structural_fields(&self, ty: &Type) -> Option<Vec<(String, Type)>> {
+        match ty {
+            Type::AnonymousStruct(fields) => Some(fields.clone()),
+            Type::Struct(name) => self.named_struct_fields(name),
+            _ => None,
+        }
+    }
 
-                // RES-2810: provenance builtins.
-                env.set(
-                    "tag".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int, Type::String],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "untag".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::String],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "tag_of".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
+    /// For an `if` condition that type-tests a union-typed identifier,
+    /// return `(name, then_type, else_type)`.
+    fn union_narrowing(&self, condition: &Node) -> Option<(String, Type, Type)> {
+        let (name, tested, negated) = extract_type_test(condition)?;
+        let Type::Union(members) = self.env.get(name)? else {
+            return None;
+        };
+        let (hit, rest): (Vec<Type>, Vec<Type>) =
+            members.into_iter().partition(|m| compatible(m, tested));
+        if hit.is_empty() {
+            return None;
+        }
+        let (then_ty, else_ty) = (
+            crate::type_relations::union_of(hit),
+            crate::type_relations::union_of(rest),
+        );
+        Some(if negated {
+            (name.to_string(), else_ty, then_ty)
+        } else {
+            (name.to_string(), then_ty, else_ty)
+        })
+    }
 
-                // RES-2810: snapshot store builtins.
-                env.set(
-                    "snapshot_save".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "snapshot_load".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "snapshot_keys".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-                env.set(
-                    "snapshot_clear".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-
-                // RES-2810: quota builtins.
-                env.set(
-                    "quota_set".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "quota_charge".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::Int],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "quota_remaining".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "quota_reset".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "quota_used".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Int),
-                    },
-                );
-                env.set(
-                    "quotas".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-
-                // RES-2810: capability builtins.
-                env.set(
-                    "mint_cap".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::String),
-                    },
-                );
-                env.set(
-                    "check_cap".to_string(),
-                    Type::Function {
-                        params: vec![Type::String, Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "revoke_cap".to_string(),
-                    Type::Function {
-                        params: vec![Type::String],
-                        return_type: Box::new(Type::Bool),
-                    },
-                );
-                env.set(
-                    "caps".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Array),
-                    },
-                );
-
-                // RES-2810: actor builtins.
-                env.set(
-                    "spawn".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-                env.set(
-                    "send".to_string(),
-                    Type::Function {
-                        params: vec![Type::Any, Type::Any],
-                        return_type: Box::new(Type::Void),
-                    },
-                );
-                env.set(
-                    "receive".to_string(),
-                    Type::Function {
-                        params: vec![],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-
-                // RES-2810: string builder.
-                env.set(
-                    "StringBuilder_new".to_string(),
-                    Type::Function {
-                        params: vec![Type::Int],
-                        return_type: Box::new(Type::Any),
-                    },
-                );
-
-                // RES-2810: math / data / reflection builtins that are
-                // registered in the interpreter's `BUILTINS` dispatch
-                // table but were missing from the type environment, so
-                // every call site failed with "Undefined variable".
-                // Params are `Any` (matching the abs/min/max precedent —
-                // these accept int- or float-valued arguments and the
-                // runtime coerces); arity is exact (the checker enforces
-                // it) and the return type is precise.
-                let any1 = |ret: Type| Type::Function {
-                    params: vec![Type::Any],
-                    return_type: Box::new(ret),
-                };
-                let any2 = |ret: Type| Type::Function {
-                    params: vec![Type::Any; 2],
-                    return_type: Box::new(ret),
-                };
-                let any3 = |ret: Type| Type::Function {
-                    params: vec![Type::Any; 3],
-                    return_type: Box::new(ret),
-                };
-
-                // Vectors (resilient/src/linear_algebra.rs).
-                env.set("vec_add".to_string(), any2(Type::Array));
-                env.set("vec_sub".to_string(), any2(Type::Array));
-                env.set("vec_scale".to_string(), any2(Type::Array));
-                env.set("vec_dot".to_string(), any2(Type::Float));
-                env.set("vec_norm".to_string(), any1(Type::Float));
-                env.set("vec_cross".to_string(), any2(Type::Array));
-                env.set("vec_lerp".to_string(), any3(Type::Array));
-
-                // Matrices (linear_algebra.rs + statistics.rs).
-                env.set("mat_add".to_string(), any2(Type::Array));
-                env.set("mat_mul".to_string(), any2(Type::Array));
-                env.set("mat_scale".to_string(), any2(Type::Array));
-                env.set("mat_identity".to_string(), any1(Type::Array));
-                env.set("mat_trace".to_string(), any1(Type::Float));
-                env.set("mat_det".to_string(), any1(Type::Float));
-                env.set("mat_inv".to_string(), any1(Type::Array));
-                env.set("mat_lu".to_string(), any1(Type::Array));
-                env.set("mat_rank".to_string(), any1(Type::Int));
-                env.set("mat_solve".to_string(), any2(Type::Array));
-
-                // Complex numbers (complex_numbers.rs) — a complex value
-                // is represented as a `[real, imag]` float array.
-                env.set("complex".to_string(), any2(Type::Array));
-                env.set("complex_real".to_string(), any1(Type::Float));
-                env.set("complex_imag".to_string(), any1(Type::Float));
-                env.set("complex_add".to_string(), any2(Type::Array));
-                env.set("complex_sub".to_string(), any2(Type::Array));
-                env.set("complex_mul".to_string(), any2(Type::Array));
-                env.set("complex_div".to_string(), any2(Type::Array));
-                env.set("complex_abs".to_string(), any1(Type::Float));
-                env.set("complex_arg".to_string(), any1(Type::Float));
-                env.set("complex_conj".to_string(), any1(Type::Array));
-                env.set("complex_exp".to_string(), any1(Type::Array));
-                env.set("complex_ln".to_string(), any1(Type::Array));
-                env.set("complex_sqrt".to_string(), any1(Type::Array));
-                env.set("complex_sin".to_string(), any1(Type::Array));
-                env.set("complex_cos".to_string(), any1(Type::Array));
-
-                // Ranges + data utilities (data_utils.rs).
-                env.set("arange".to_string(), any3(Type::Array));
-                env.set("linspace".to_string(), any3(Type::Array));
-                env.set("logspace".to_string(), any3(Type::Array));
-                env.set("csv_parse".to_string(), any1(Type::Array));
-                env.set("csv_parse_tsv".to_string(), any1(Type::Array));
-                env.set("csv_format".to_string(), any1(Type::String));
-                env.set("csv_format_tsv".to_string(), any1(Type::String));
-                env.set("table_format".to_string(), any1(Type::String));
-                env.set("format_float".to_string(), any2(Type::String));
-                env.set("rle_encode".to_string(), any1(Type::Array));
-                env.set("rle_decode".to_string(), any1(Type::Array));
-
-                // Number theory (number_theory.rs).
-                env.set("count_digits".to_string(), any1(Type::Int));
-                env.set("digit_sum".to_string(), any1(Type::Int));
-                env.set("digital_root".to_string(), any1(Type::Int));
-                env.set("divisors".to_string(), any1(Type::Array));
-                env.set("euler_totient".to_string(), any1(Type::Int));
-                env.set("int_log".to_string(), any2(Type::Int));
-                env.set("int_to_digits".to_string(), any1(Type::Array));
-                env.set("is_fibonacci".to_string(), any1(Type::Bool));
-                env.set("is_perfect".to_string(), any1(Type::Bool));
-                env.set("prime_factors".to_string(), any1(Type::Array));
-                env.set("primes_up_to".to_string(), any1(Type::Array));
-
-                // Statistics (statistics.rs).
-                env.set("stats_iqr".to_string(), any1(Type::Float));
-                env.set("stats_mode_int".to_string(), any1(Type::Int));
-                env.set("stats_zscore".to_string(), any1(Type::Array));
-
-                // Graph algorithms (graph_algorithms.rs).
-                env.set("graph_bfs".to_string(), any2(Type::Array));
-                env.set("graph_dfs".to_string(), any2(Type::Array));
-
-                // Reflection / option helpers.
-                env.set("struct_name".to_string(), any1(Type::String));
-                env.set("identity".to_string(), any1(Type::Any));
-                env.set("is_tuple".to_string(), any1(Type::Bool));
-                env.set("option_unwrap".to_string(), any1(Type::Any));
-                env.set("option_unwrap_or".to_string(), any2(Type::Any));
-
-                // Volatile MMIO intrinsics (volatile.rs). Reads return the
-                // loaded integer; writes return Void. RES-406's capability
-                // pass additionally restricts where these may be called, and
-                // they are classified impure below.
-                env.set("volatile_read_u8".to_string(), any1(Type::Int));
-                env.set("volatile_read_u16".to_string(), any1(Type::Int));
-                env.set("volatile_read_u32".to_string(), any1(Type::Int));
-                env.set("volatile_read_u64".to_string(), any1(Type::Int));
-                env.set("volatile_write_u8".to_string(), any2(Type::Void));
-                env.set("volatile_write_u16".to_string(), any2(Type::Void));
-                env.set("volatile_write_u32".to_string(), any2(Type::Void));
-                env.set("volatile_write_u64".to_string(), any2(Type::Void));
-
-                std::sync::Arc::new(env)
-            });
-
-        // RES-1692: pre-size the per-typecheck HashMaps that grow
-        // once per top-level fn / struct during the hoist pass.
-        // Same shape as RES-1686 / RES-1688 / RES-1690 — perf-only,
-        // saves 2-3 rehash rounds per typecheck on medium / large
-        // programs. `contract_table` and `fn_decl_spans` see one
-        // entry per top-level fn (50+ on `large.rz`); the rest grow
-        // slower but cost a fixed small allocation each anyway.
-        const PRESIZE: usize = 32;
-        TypeChecker {
-            env: TypeEnvironment::new_with_outer_arc(std::sync::Arc::clone(&BUILTIN_ENV)),
-            contract_table: HashMap::with_capacity(PRESIZE),
-            fn_decl_spans: HashMap::with_capacity(PRESIZE),
-            const_bindings: HashMap::with_capacity(PRESIZE),
-            stats: VerificationStats::default(),
-            certificates: Vec::new(),
-            struct_fields: HashMap::with_capacity(PRESIZE),
-            generic_struct_info: HashMap::new(),
-            // RES-1398: clone the cached builtin enum_decls (Option /
-            // Result) HashMap instead of rebuilding it from scratch.
-            // Pattern mirrors RES-1349's BUILTIN_ENV cache — the data
-            // is invariant, so populate once per process via LazyLock
-            // and pay an Arc bump per `TypeChecker::new()` instead of
-            // 2 Vec allocations + 4 EnumVariant struct constructions
-            // + 4 `String::to_string()`s per call. `Arc` (not `Rc`)
-            // so the static can be `Sync`; atomic refcount ops are
-            // still trivially cheaper than the allocations they
-            // replace.
-            enum_decls: {
-                static BUILTIN_ENUM_DECLS: std::sync::LazyLock<
-                    std::collections::HashMap<String, std::sync::Arc<Vec<crate::EnumVariant>>>,
-                > = std::sync::LazyLock::new(|| {
-                    let mut m = std::collections::HashMap::new();
-                    let s = crate::span::Span::default();
-                    m.insert(
-                        "Option".to_string(),
-                        std::sync::Arc::new(vec![
-                            crate::EnumVariant {
-                                name: "Some".to_string(),
-                                span: s,
-                                payload: crate::EnumPayload::None,
-                            },
-                            crate::EnumVariant {
-                                name: "None".to_string(),
-                                span: s,
-                                payload: crate::EnumPayload::None,
-                            },
-                        ]),
-                    );
-                    m.insert(
-                        "Result".to_string(),
-                        std::sync::Arc::new(vec![
-                            crate::EnumVariant {
-                                name: "Ok".to_string(),
-                                span: s,
-                                payload: crate::EnumPayload::None,
-                            },
-                            crate::EnumVariant {
-                                name: "Err".to_string(),
-                                span: s,
-                                payload: crate::EnumPayload::None,
-                            },
-                        ]),
-                    );
-                    m
-                });
-                BUILTIN_ENUM_DECLS.clone()
-            },
-            enum_type_params: HashMap::new(),
-            type_aliases: HashMap::with_capacity(PRESIZE),
-            // RES-137: ticket's default is 5 seconds per query.
-            verifier_timeout_ms: 5000,
-            // RES-217: partial-proof warnings on by default.
-            warn_unverified: true,
-            // RES-217: populated by `check_program_with_source`.
-            source_path: String::new(),
-            // RES-189: populated during LetStatement handling.
-            let_type_hints: Vec::new(),
-            // RES-2569: populated during function / closure checking.
-            fn_return_type_hints: Vec::new(),
-            // RES-387: no enclosing fn at program start.
-            current_fn_fails: None,
-            // RES-403: no enclosing fn return type at program start.
-            current_fn_return_type: None,
-            // A-E3 (RES-3933): no enclosing impl block at program start.
-            current_self_assoc_types: HashMap::new(),
-            // RES-910: loop depth starts at 0 (top-level is not a loop).
-            loop_depth: 0,
-            // RES-2653: no enclosing labeled loops at the top level.
-            loop_label_stack: Vec::new(),
-            // RES-354: auto-detect theory by default.
-            #[cfg(feature = "z3")]
-            z3_theory: crate::verifier_z3::Z3Theory::Auto,
-            // RES-318: per-loop-invariant verbose stderr line is OFF
-            // by default. The driver flips it on via `--verbose`.
-            verbose_loop_invariants: false,
-            // RES-1322: opt-in. Audit + explain-effects drivers set
-            // this; default-mode runs (every non-audit CLI invocation,
-            // the LSP/REPL, every `cargo test` typecheck) skip the
-            // `infer_fn_effects` fixpoint.
-            audit_stats: false,
-            // RES-1353: opt-in. LSP sets this; default-mode runs skip
-            // populating `let_type_hints` (it's only consumed by the
-            // inlay-hint provider).
-            capture_inlay_hints: false,
-            // RES-1357: opt-in. The `--emit-certificate` driver flips
-            // this; every other invocation skips pushing
-            // `CapturedCertificate` onto the Vec it'd drop on
-            // TypeChecker drop.
-            emit_certificates: false,
-            // RES-1862: default to zero span (synthetic / unknown).
-            current_span: Span::default(),
-
-            fn_type_params: HashMap::new(),
-            trait_impls: HashMap::new(),
-            trait_supers: HashMap::new(),
-            trait_default_methods: HashMap::new(),
-            // RES-4190: no recursion depth at construction.
-            check_depth: 0,
-        }
-    }
-
-    /// RES-137: override the per-query Z3 solver timeout in ms.
-    /// Called by the driver from the `--verifier-timeout-ms` CLI
-    /// flag. Pass `0` to disable the timeout entirely (NIA proofs
-    /// that would otherwise hit the default budget run to
-    /// completion — use at your own risk).
-    pub fn with_verifier_timeout_ms(mut self, ms: u32) -> Self {
-        self.verifier_timeout_ms = ms;
-        self
+    /// Check `node` in a child scope where `name` is rebound to `ty`.
+    fn check_with_binding(
+        &mut self,
+        node: &Node,
+        name: &str,
+        ty: Type,
+    ) -> Result<Type, CheckError> {
+        let mut scoped = TypeEnvironment::new_enclosed(self.env.clone());
+        scoped.set(name.to_string(), ty);
+        std::mem::swap(&mut self.env, &mut scoped);
+        let result = self.check_node(node);
+        std::mem::swap(&mut self.env, &mut scoped);
+        result
     }
 
-    /// RES-217: toggle partial-proof warnings. When `true`
-    /// (default), Z3 `Unknown` verdicts surface as
-    /// `warning[partial-proof]: Z3 returned Unknown for
-    /// assertion at <file>:<line>:<col> — proof is incomplete`.
-    /// The driver flips this to `false` on `--no-warn-unverified`
-    /// for CI runs that want a quieter stderr.
-    pub fn with_warn_unverified(mut self, on: bool) -> Self {
-        self.warn_unverified = on;
-        self
+    /// After an `if` where exactly one arm always exits, the rest of
+    /// the block only runs on the other arm's path: assume that arm's
+    /// condition facts and union narrowing from here on.
+    fn assume_after_early_exit(&mut self, stmt: &Node) {
+        let Node::IfStatement {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } = stmt
+        else {
+            return;
+        };
+        let (truth, survivor) = if node_terminates(consequence) {
+            (false, alternative.as_deref())
+        } else if alternative.as_deref().is_some_and(node_terminates) {
+            (true, Some(consequence.as_ref()))
+        } else {
+            return;
+        };
+        let mut facts = self.condition_facts(condition, truth);
+        let mut narrowed = self
+            .union_narrowing(condition)
+            .map(|(name, then_ty, else_ty)| (name, if truth { then_ty } else { else_ty }));
+        if let Some(arm) = survivor {
+            crate::flow_facts::forget_assigned_in(&mut facts, arm);
+            if narrowed
+                .as_ref()
+                .is_some_and(|(name, _)| crate::flow_facts::assigns(arm, name))
+            {
+                narrowed = None;
+            }
+        }
+        self.path_facts.extend(facts);
+        if let Some((name, ty)) = narrowed {
+            self.env.set(name, ty);
+        }
     }
 
-    /// RES-354: override the SMT theory used for Z3 encoding.
-    /// `Z3Theory::Auto` (default) picks BV32 when bitwise ops are
-    /// detected; `Bv` forces BV32; `Lia` forces LIA (bails on
-    /// bitwise ops). Driver calls this from `--z3-theory`.
-    #[cfg(feature = "z3")]
-    pub fn with_z3_theory(mut self, theory: crate::verifier_z3::Z3Theory) -> Self {
-        self.z3_theory = theory;
-        self
+    /// Did-you-mean suffix for a `Type::Struct` that names no declared
+    /// struct, enum, or alias — the annotation was probably a typo of
+    /// a builtin (`Strng`) or of a declared type. Empty otherwise.
+    fn unknown_type_hint(&self, ty: &Type) -> String {
+        let Type::Struct(name) = ty else {
+            return String::new();
+        };
+        if !is_bare_type_name(name)
+            || self.struct_fields.contains_key(name)
+            || self.enum_decls.contains_key(name)
+            || self.type_aliases.contains_key(name)
+        {
+            return String::new();
+        }
+        crate::did_you_mean::hint_from(
+            name,
+            BUILTIN_TYPE_NAMES
+                .iter()
+                .copied()
+                .chain(self.struct_fields.keys().map(String::as_str))
+                .chain(self.enum_decls.keys().map(String::as_str))
+                .chain(self.type_aliases.keys().map(String::as_str)),
+        )
     }
 
-    /// RES-318: enable verbose stderr output from the loop-invariant
-    /// verifier. The driver flips this on via the `--verbose` flag.
-    pub fn with_verbose_loop_invariants(mut self, on: bool) -> Self {
-        self.verbose_loop_invariants = on;
-        self
+    /// `flow_facts::facts_from_condition` against this checker's
+    /// predicates and constants.
+    fn condition_facts(&self, condition: &Node, truth: bool) -> Vec<crate::flow_facts::Fact> {
+        crate::flow_facts::facts_from_condition(
+            condition,
+            truth,
+            &self.predicates,
+            &self.const_bindings,
+        )
     }
 
-    /// RES-1322: opt into the post-typecheck `infer_fn_effects`
-    /// fixpoint that populates `self.stats.fn_effects`. The only
-    /// consumers of `fn_effects` are the `--audit` /
-    /// `--explain-effects` CLI drivers; for every other invocation
-    /// (the default `rz prog.rz` path, the LSP/REPL paths, every
-    /// `cargo test` typecheck call) the fixpoint is wasted work
-    /// because the result is never read. Default `false`; the driver
-    /// flips this on for the audit/explain-effects paths.
-    pub fn with_audit_stats(mut self, on: bool) -> Self {
-        self.audit_stats = on;
-        self
-    }
-
-    /// RES-1353: opt into populating `self.let_type_hints` with an
-    /// entry per inferred `let` binding. The LSP backend reads
-    /// these to produce inlay hints; every other entry point
-    /// (default `rz prog.rz` path, REPL, every `cargo test`
-    /// typecheck call) discards them on TypeChecker drop. Default
-    /// `false`; LSP flips this on before running typecheck.
-    #[allow(dead_code)] // only called behind the `lsp` feature
-    pub fn with_capture_inlay_hints(mut self, on: bool) -> Self {
-        self.capture_inlay_hints = on;
-        self
-    }
-
-    /// RES-1357: opt into pushing `CapturedCertificate` entries onto
-    /// `self.certificates`. The only consumer is the
-    /// `--emit-certificate <DIR>` driver in lib.rs; every other
-    /// path leaves the flag off so the per-push `fn_name.clone()` +
-    /// Vec growth doesn't fire on hot paths that never read the
-    /// certs.
-    pub fn with_emit_certificates(mut self, on: bool) -> Self {
-        self.emit_certificates = on;
-        self
-    }
-
-    /// RES-318: read accessor for the verifier pass.
-    #[allow(dead_code)]
-    pub(crate) fn verifier_timeout_ms(&self) -> u32 {
-        self.verifier_timeout_ms
-    }
-
-    /// RES-318: read accessor for the verifier pass.
-    #[allow(dead_code)]
-    pub(crate) fn verbose_loop_invariants(&self) -> bool {
-        self.verbose_loop_invariants
-    }
-
-    /// RES-318: push a loop-invariant proof certificate so it
-    /// participates in the regular `--emit-certificate <DIR>` dump.
-    ///
-    /// RES-1357: only stores the cert when `emit_certificates` is
-    /// on. The `loop_invariants_proven` stat counter increments
-    /// unconditionally so the audit summary still reports the
-    /// total — only the SMT-LIB2 body Vec push is gated.
-    #[allow(dead_code)]
-    pub(crate) fn push_loop_invariant_certificate(&mut self, idx: usize, smt2: String) {
-        if self.emit_certificates {
-            self.certificates.push(CapturedCertificate {
-                fn_name: "<loop>".to_string(),
-                kind: "loop_invariant",
-                idx,
-                smt2,
-            });
-        }
-        self.stats.loop_invariants_proven += 1;
-    }
-
-    /// RES-318: helper for the verifier's unit tests — count proven
-    /// invariants by counting `loop_invariant`-kinded certificates.
-    #[cfg(feature = "z3")]
-    #[allow(dead_code)]
-    pub(crate) fn loop_invariant_certificate_count(&self) -> usize {
-        self.certificates
-            .iter()
-            .filter(|c| c.kind == "loop_invariant")
-            .count()
-    }
-
-    /// RES-2693: true when one of `a`/`b` is a concrete struct that
-    /// implements the other as a trait.  Checks both orderings so the
-    /// caller doesn't need to know which of the two types is the trait
-    /// and which is the struct.
-    ///
-    /// Only `Type::Struct(name)` pairs are examined; all other combinations
-    /// return `false`, so the method never fires for primitive types.
-    fn satisfies_trait_param(&self, a: &Type, b: &Type) -> bool {
-        let (Type::Struct(a_name), Type::Struct(b_name)) = (a, b) else {
-            return false;
-        };
-        // a is the implementing struct, b is the trait:
-        if self
-            .trait_impls
-            .get(a_name.as_str())
-            .is_some_and(|ts| ts.contains(b_name.as_str()))
-        {
-            return true;
-        }
-        // b is the implementing struct, a is the trait:
-        self.trait_impls
-            .get(b_name.as_str())
-            .is_some_and(|ts| ts.contains(a_name.as_str()))
-    }
-
-    fn named_struct_fields(&self, name: &str) -> Option<Vec<(String, Type)>> {
-        self.struct_fields
-            .get(name)
-            .map(|fields| fields.iter().map(|(n, t)| (n.clone(), t.clone())).collect())
-    }
-
-    fn structural_fields(&self, ty: &Type) -> Option<Vec<(String, Type)>> {
-        match ty {
-            Type::AnonymousStruct(fields) => Some(fields.clone()),
-            Type::Struct(name) => self.named_struct_fields(name),
-            _ => None,
-        }
+    /// Check one arm of an `if` with `facts` assumed and an optional
+    /// union narrowing applied. The arm's facts don't outlive it, and
+    /// any outer fact about a variable the arm reassigns is dropped.
+    fn check_branch(
+        &mut self,
+        branch: &Node,
+        facts: Vec<crate::flow_facts::Fact>,
+        narrowed: Option<(&str, Type)>,
+    ) -> Result<Type, CheckError> {
+        let saved = self.path_facts.clone();
+        let saved_locals = self.fact_locals.clone();
+        self.path_facts.extend(facts);
+        let result = match narrowed {
+            Some((name, ty)) => self.check_with_binding(branch, name, ty),
+            None => self.check_node(branch),
+        };
+        self.path_facts = saved;
+        // A `let` in the arm is gone with it; the name means the outer
+        // binding again.
+        self.fact_locals = saved_locals;
+        crate::flow_facts::forget_assigned_in(&mut self.path_facts, branch);
+        result
     }
 
     fn type_satisfies(&self, actual: &Type, expected: &Type) -> bool {
@@ -6124,6 +3143,190 @@ impl TypeChecker {
         self.check_program_with_source(program, "<unknown>")
     }
 
+    /// Report the error about to be returned at `node`, the offending
+    /// expression, instead of the statement or call that contains it.
+    /// Nodes without a position leave the current span alone.
+    fn point_at(&mut self, node: &Node) {
+        let span = clause_span(node);
+        if span.start.line > 0 {
+            self.current_span = span;
+        }
+    }
+
+    /// Element-generic checking for the array builtins `push`, `pop`,
+    /// `slice`, `contains`, `index_of`, `map`, `filter`, `reduce`,
+    /// `sort` and `sort_by`.
+    /// Their declared signatures take an untyped `Array`; when the
+    /// first argument is an array this checks the other arguments
+    /// against its element type and keeps that type in the result, so
+    /// `map(xs, fn(int x) { return x * 2; })` is an `[int]` and
+    /// `push(xs, "a")` on an `[int]` is an error. Returns `None` when
+    /// the first argument is not an array, leaving the call to the
+    /// declared signature (`contains` and `index_of` also take strings).
+    fn check_array_builtin_call(
+        &mut self,
+        name: &str,
+        arguments: &[Node],
+    ) -> Result<Option<Type>, CheckError> {
+        let Some(first) = arguments.first() else {
+            return Ok(None);
+        };
+        let array_ty = self.check_node(first)?;
+        if !is_array_ty(&array_ty) {
+            return Ok(None);
+        }
+        let expected = match name {
+            "pop" | "sort" | "array_sort" => 1,
+            "slice" | "reduce" => 3,
+            _ => 2,
+        };
+        if arguments.len() != expected {
+            return Err(render_arity_mismatch_error(expected, arguments.len()).into());
+        }
+        let mut rest = Vec::with_capacity(arguments.len() - 1);
+        for arg in &arguments[1..] {
+            rest.push(self.check_node(arg)?);
+        }
+        // `None` when nothing is known about the elements.
+        let elem = match &array_ty {
+            Type::TypedArray(e) if !matches!(**e, Type::Any | Type::Var(..)) => Some((**e).clone()),
+            _ => None,
+        };
+        let check_elem = |value: &Type| match &elem {
+            Some(e) if !compatible(e, value) => Err(format!(
+                "{}: {} holds {} values, got {}",
+                name, array_ty, e, value
+            )),
+            _ => Ok(()),
+        };
+        // A callback's declared return type; `None` when it is not
+        // known statically.
+        let known_return = |t: &Type| match t {
+            Type::Any | Type::Var(..) | Type::Void => None,
+            t => Some(t.clone()),
+        };
+        let callback = |callback: &Type, arity: usize| match callback {
+            Type::Function {
+                params,
+                return_type,
+            } => {
+                if params.len() != arity {
+                    return Err(format!(
+                        "{}: the callback must take {} parameter{}, got {}",
+                        name,
+                        arity,
+                        if arity == 1 { "" } else { "s" },
+                        params.len()
+                    ));
+                }
+                if let Some(e) = &elem
+                    && !compatible(&params[arity - 1], e)
+                {
+                    return Err(format!(
+                        "{}: the callback takes {}, but {} holds {} values",
+                        name,
+                        params[arity - 1],
+                        array_ty,
+                        e
+                    ));
+                }
+                Ok(Some((params.clone(), known_return(return_type))))
+            }
+            Type::Any | Type::Var(..) => Ok(None),
+            other => Err(format!("{}: expected a function, got {}", name, other)),
+        };
+        let ty = match name {
+            "push" => {
+                check_elem(&rest[0])?;
+                array_ty.clone()
+            }
+            "contains" => {
+                check_elem(&rest[0])?;
+                Type::Bool
+            }
+            "index_of" => {
+                check_elem(&rest[0])?;
+                Type::Int
+            }
+            "pop" => array_ty.clone(),
+            "sort" | "array_sort" => {
+                if let Some(e) = &elem
+                    && !matches!(e, Type::Int | Type::Float | Type::String)
+                {
+                    return Err(format!(
+                        "{}: only int, float and string elements have an order, got {}; use sort_by",
+                        name, array_ty
+                    ).into());
+                }
+                array_ty.clone()
+            }
+            // The comparator is a three-way `int` compare or a
+            // less-than `bool`, taking two elements.
+            "sort_by" | "array_sort_by" => {
+                if let Some((params, ret)) = callback(&rest[0], 2)? {
+                    if let Some(e) = &elem
+                        && !compatible(&params[0], e)
+                    {
+                        return Err(format!(
+                            "{}: the comparator takes {}, but {} holds {} values",
+                            name, params[0], array_ty, e
+                        )
+                        .into());
+                    }
+                    if let Some(ret) = ret
+                        && !matches!(ret, Type::Int | Type::Bool)
+                    {
+                        return Err(format!(
+                            "{}: the comparator must return int or bool, got {}",
+                            name, ret
+                        )
+                        .into());
+                    }
+                }
+                array_ty.clone()
+            }
+            "slice" => {
+                for bound in &rest {
+                    if !compatible(bound, &Type::Int) {
+                        return Err(format!("slice: bounds must be int, got {}", bound).into());
+                    }
+                }
+                array_ty.clone()
+            }
+            "map" => match callback(&rest[0], 1)? {
+                Some((_, Some(ret))) => Type::TypedArray(Box::new(ret)),
+                _ => Type::Array,
+            },
+            "filter" => {
+                if let Some((_, Some(ret))) = callback(&rest[0], 1)?
+                    && !compatible(&ret, &Type::Bool)
+                {
+                    return Err(
+                        format!("filter: the callback must return bool, got {}", ret).into(),
+                    );
+                }
+                array_ty.clone()
+            }
+            "reduce" => {
+                let init = &rest[0];
+                match callback(&rest[1], 2)? {
+                    Some((params, ret)) => {
+                        if !compatible(&params[0], init) {
+                            return Err(format!(
+                                "reduce: the callback's accumulator is {}, but the initial value is {}",
+                                params[0], init
+                            ).into());
+                        }
+                        ret.or_else(|| known_return(init)).unwrap_or(Type::Any)
+                    }
+                    None => known_return(init).unwrap_or(Type::Any),
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(ty))
+    }
+
     /// RES-080: like `check_program`, but errors thrown by per-statement
     /// type checking are prefixed with `<source_path>:<line>:<col>: `
     /// (using the statement's `Spanned` start position from RES-077).
@@ -6136,6 +3339,18 @@ impl TypeChecker {
         program: &Node,
         source_path: &str,
     ) -> Result<Type, String> {
+        self.check_source(program, source_path)
+            .map_err(|e| e.message)
+    }
+
+    /// [`Self::check_program_with_source`], keeping whether the
+    /// verifier refuted an obligation.
+    pub(crate) fn check_source(
+        &mut self,
+        program: &Node,
+        source_path: &str,
+    ) -> Result<Type, CheckError> {
+        let _span = crate::phase_trace::typecheck(source_path);
         // RES-217: stash the source path for partial-proof
         // warnings that want to print `<file>:<line>:<col>`.
         self.source_path = source_path.to_string();
@@ -6144,6 +3359,19 @@ impl TypeChecker {
         // cache is thread-local and per-`check_program_with_source`
         // call; entries accumulate during one typecheck only.
         reset_z3_prove_cache();
+        // A REPL session reuses the checker; no `if` encloses a new input.
+        self.path_facts.clear();
+        self.fact_locals = None;
+        // `fn id(x)`: give pass-through untyped parameters an implicit
+        // type parameter so call sites are checked generically.
+        let generalized = match program {
+            Node::Program(statements) => crate::implicit_generics::generalize_program(
+                program,
+                &declared_type_names(statements),
+            ),
+            _ => None,
+        };
+        let program = generalized.as_ref().unwrap_or(program);
         match program {
             Node::Program(statements) => {
                 let direct_trait_supers: HashMap<String, Vec<String>> = statements
@@ -6195,19 +3423,44 @@ impl TypeChecker {
                             ensures,
                             fails,
                             return_type,
+                            body,
                             span,
+                            type_params,
                             ..
                         } => {
-                            // RES-1363: wrap in `Rc` so the call-site
+                            if let Some(base) = crate::overloads::base_of(name) {
+                                let params = parameters
+                                    .iter()
+                                    .map(|(ty, _)| {
+                                        if type_params.contains(ty) {
+                                            Type::Any
+                                        } else {
+                                            self.parse_type_name(ty).unwrap_or(Type::Any)
+                                        }
+                                    })
+                                    .collect();
+                                self.overload_sets
+                                    .entry(base.to_string())
+                                    .or_default()
+                                    .push((name.clone(), params));
+                            }
+                            if let Some(pred) = crate::flow_facts::Predicate::from_fn(
+                                parameters,
+                                return_type.as_deref(),
+                                body,
+                            ) {
+                                self.predicates.insert(name.clone(), pred);
+                            }
+                            // RES-1363: wrap in `Arc` so the call-site
                             // reader at typechecker.rs:5877 clones a
                             // single refcount instead of deep-cloning
                             // four `Vec`s.
                             self.contract_table.insert(
                                 name.clone(),
-                                std::rc::Rc::new(ContractInfo {
+                                std::sync::Arc::new(ContractInfo {
                                     parameters: parameters.clone(),
-                                    requires: requires.clone(),
-                                    ensures: ensures.clone(),
+                                    requires: requires.to_vec(),
+                                    ensures: ensures.to_vec(),
                                     fails: fails.clone(),
                                 }),
                             );
@@ -6223,7 +3476,8 @@ impl TypeChecker {
                                     *span,
                                     name,
                                     *prev,
-                                ));
+                                )
+                                .into());
                             }
                             self.fn_decl_spans.insert(name.clone(), *span);
                             // RES-1105 + RES-1106: also register the
@@ -6244,27 +3498,33 @@ impl TypeChecker {
                             // alias), fall back to Type::Any so the
                             // body still gets to run and surface the
                             // real diagnostic at its definition site.
-                            let param_types: Vec<Type> = parameters
-                                .iter()
-                                .map(|(ty_name, _)| {
-                                    self.parse_type_name(ty_name).unwrap_or(Type::Any)
-                                })
-                                .collect();
-                            let ret_type = match return_type {
-                                Some(ty_name) => self.parse_type_name(ty_name).unwrap_or(Type::Any),
-                                None => Type::Any,
-                            };
-                            self.env.set(
-                                name.clone(),
-                                Type::Function {
-                                    params: param_types,
-                                    return_type: Box::new(ret_type),
-                                },
-                            );
+                            let fn_type = self.hoisted_fn_type(parameters, return_type.as_deref());
+                            self.env.set(name.clone(), fn_type);
                         }
                         Node::TypeAlias { name, target, .. } => {
                             self.type_aliases.insert(name.clone(), target.clone());
                         }
+                        // `extern` declarations bind their names the way
+                        // `fn` does, so top-level code can call them. A
+                        // variadic one takes any arguments past its fixed
+                        // prefix, which a `Function` type can't express.
+                        Node::Extern { decls, .. } => {
+                            for d in decls {
+                                let fn_type = if d.is_variadic {
+                                    Type::Any
+                                } else {
+                                    Type::Function {
+                                        params: d
+                                            .parameters
+                                            .iter()
+                                            .map(|(ty, _)| ffi_type(ty))
+                                            .collect(),
+                                        return_type: Box::new(ffi_type(&d.return_type)),
+                                    }
+                                };
+                                self.env.set(d.resilient_name.clone(), fn_type);
+                            }
+                        }
                         // RES-417: hoist const declarations so functions
                         // that textually precede a const declaration can
                         // still reference it. Without this, `fn f() -> int
@@ -6315,39 +3575,116 @@ impl TypeChecker {
                     }
                 }
 
-                let mut result_type = Type::Void;
-                for stmt in statements {
-                    // RES-1862: reset current_span to the statement's
-                    // own span before descending. check_node arms for
-                    // InfixExpression / CallExpression / LetStatement
-                    // overwrite this with the innermost node's span so
-                    // that error messages point at the exact expression
-                    // rather than the whole containing statement.
-                    self.current_span = stmt.span;
-                    let check_result = self.check_node(&stmt.node);
-                    // Capture the best span after check_node returns
-                    // (self is no longer mutably borrowed at this point).
-                    // Use current_span when it's more specific (non-zero
-                    // line within the statement range); fall back to
-                    // stmt.span otherwise.
-                    let diag_span = if self.current_span.start.line > 0 {
-                        self.current_span
+                let deferrable = statements
+                    .iter()
+                    .filter(|stmt| deferrable_shape(&stmt.node))
+                    .count();
+                let result_type =
+                    if self.parallel_bodies && deferrable >= crate::parallel_check::MIN_BODIES {
+                        self.check_statements_deferred(statements, source_path)?
                     } else {
-                        stmt.span
+                        let mut result_type = Type::Void;
+                        for stmt in statements {
+                            result_type = self.check_top_level(stmt, source_path)?;
+                        }
+                        result_type
                     };
-                    result_type = check_result.map_err(|e| {
-                        // RES-080 / RES-1862: prepend file:line:col.
-                        // Skip the prefix when the span looks
-                        // default/empty (line 0 means "synthetic").
-                        if diag_span.start.line == 0 {
-                            e
+
+                // Every `StructDecl` has been visited by now, so a
+                // literal whose name is still unregistered names a
+                // struct that doesn't exist anywhere in the program.
+                for (lit_name, lit_span) in std::mem::take(&mut self.unresolved_struct_literals) {
+                    if self.struct_fields.contains_key(&lit_name) {
+                        continue;
+                    }
+                    let hint = crate::did_you_mean::hint_from(
+                        &lit_name,
+                        self.struct_fields.keys().map(String::as_str),
+                    );
+                    let msg = format!("unknown struct `{}`{}", lit_name, hint);
+                    return Err(if lit_span.start.line == 0 {
+                        msg.into()
+                    } else {
+                        format!(
+                            "{}:{}:{}: {}",
+                            source_path, lit_span.start.line, lit_span.start.column, msg
+                        )
+                        .into()
+                    });
+                }
+
+                // Same for annotations: a name no declaration in the
+                // program introduces, but that is one typo away from a
+                // builtin or declared type, is a misspelling. A name
+                // with no close match stays permissive — it may be a
+                // generic parameter or an opaque runtime type.
+                let unresolved = std::mem::take(&mut *self.unresolved_type_names.borrow_mut());
+                if !unresolved.is_empty() {
+                    let declared = declared_type_names(statements);
+                    for (ty_name, first_span) in &unresolved {
+                        if declared.contains(ty_name) {
+                            continue;
+                        }
+                        // Signature pre-passes resolve types before any
+                        // span is current; prefer a located occurrence.
+                        let ty_span = unresolved
+                            .iter()
+                            .find(|(n, sp)| n == ty_name && sp.start.line > 0)
+                            .map_or(*first_span, |(_, sp)| *sp);
+                        let hint = crate::did_you_mean::hint_from(
+                            ty_name,
+                            BUILTIN_TYPE_NAMES
+                                .iter()
+                                .copied()
+                                .chain(declared.iter().map(String::as_str)),
+                        );
+                        if hint.is_empty() {
+                            continue;
+                        }
+                        let msg = format!("Unknown type: {}{}", ty_name, hint);
+                        return Err(if ty_span.start.line == 0 {
+                            msg.into()
                         } else {
                             format!(
                                 "{}:{}:{}: {}",
-                                source_path, diag_span.start.line, diag_span.start.column, e
+                                source_path, ty_span.start.line, ty_span.start.column, msg
                             )
-                        }
-                    })?;
+                            .into()
+                        });
+                    }
+                }
+
+                // A struct that reaches itself through plain fields —
+                // with no `Option`, array, or other indirection on the
+                // way back — has no finite value to construct.
+                let decl_order: Vec<(&str, Span)> = statements
+                    .iter()
+                    .filter_map(|s| match &s.node {
+                        Node::StructDecl { name, .. } => Some((name.as_str(), s.span)),
+                        _ => None,
+                    })
+                    .collect();
+                if let Some(cycle) = infinite_struct_cycle(&decl_order, &self.struct_fields) {
+                    let decl_span = decl_order
+                        .iter()
+                        .find(|(n, _)| *n == cycle[0])
+                        .map_or_else(Span::default, |(_, sp)| *sp);
+                    let msg = format!(
+                        "recursive struct `{}` has infinite size: {} — wrap the \
+                         recursive field in `Option<{}>` or an array",
+                        cycle[0],
+                        cycle.join(" -> "),
+                        cycle[1],
+                    );
+                    return Err(if decl_span.start.line == 0 {
+                        msg.into()
+                    } else {
+                        format!(
+                            "{}:{}:{}: {}",
+                            source_path, decl_span.start.line, decl_span.start.column, msg
+                        )
+                        .into()
+                    });
                 }
 
                 // RES-1627: one shared whole-AST marker pre-scan
@@ -6430,7 +3767,7 @@ impl TypeChecker {
                     }
                 }
                 if !refuted.is_empty() {
-                    return Err(refuted.join("\n"));
+                    return Err(refutation(refuted.join("\n")));
                 }
 
                 // RES-191: after regular type-checking, enforce the
@@ -7018,13 +4355,18 @@ impl TypeChecker {
                 // RES-2578: validate `-> !` (never type) function declarations.
                 crate::never_type::check(program, source_path)?;
                 // RES-2589: dead-code warnings — unused fns, unreachable stmts,
-                // unused let bindings. Warning-only, never returns Err.
-                crate::dead_code_lint::check(program, source_path);
+                // unused let bindings. Warning-only; the unused reports
+                // count toward `--deny-warnings` below.
+                self.denied_warnings +=
+                    crate::dead_code_lint::check(program, source_path, self.warnings);
                 // RES-2579: reject `defer` at the top level (outside any fn).
                 crate::defer_stmt::check(program, source_path)?;
-                // RES-2580: extended const eval registration (no-op check;
-                // the actual extension is in eval_const_expr in lib.rs).
+                // RES-2580: reject malformed const declarations before
+                // they reach the evaluator.
                 crate::const_eval_ext::check(program, source_path)?;
+                // Resolve consts, enum discriminants, and `[T; N]`
+                // lengths named by a const.
+                crate::const_eval::check(program, source_path)?;
                 // RES-2601: exhaustive struct field checking in match patterns.
                 crate::struct_field_check::check(program, source_path)?;
                 // RES-2614: validate [target.X] sections in rz.toml.
@@ -7077,9 +4419,12 @@ impl TypeChecker {
                     self.stats.fn_effects = infer_fn_effects(statements);
                 }
 
+                if self.denied_warnings > 0 {
+                    return Err(crate::warnings::denied_error(self.denied_warnings).into());
+                }
                 Ok(result_type)
             }
-            _ => Err("Expected program node".to_string()),
+            _ => Err("Expected program node".to_string().into()),
         }
     }
 
@@ -7377,7 +4722,7 @@ impl TypeChecker {
         var: &str,
         ty: Type,
         body: &Node,
-    ) -> Result<Type, String> {
+    ) -> Result<Type, CheckError> {
         // RES-1320: build the enclosed inner env from a single clone of
         // the current env, then use `mem::replace` to swap it into
         // `self.env` while capturing the original outer for restore.
@@ -7402,7 +4747,7 @@ impl TypeChecker {
     /// prefix operators) hits a typed diagnostic instead of
     /// overflowing the native stack. Mirrors the parser's RES-4185
     /// `parse_expression`/`parse_expression_inner` split.
-    pub fn check_node(&mut self, node: &Node) -> Result<Type, String> {
+    pub fn check_node(&mut self, node: &Node) -> Result<Type, CheckError> {
         self.check_depth += 1;
         if self.check_depth > MAX_CHECK_DEPTH {
             self.check_depth -= 1;
@@ -7412,16 +4757,67 @@ impl TypeChecker {
                 self.current_span.start.line,
                 self.current_span.start.column,
                 MAX_CHECK_DEPTH
-            ));
+            )
+            .into());
         }
         let result = self.check_node_inner(node);
         self.check_depth -= 1;
+        if let Node::CallExpression {
+            function,
+            arguments,
+            ..
+        } = node
+            && !self.path_facts.is_empty()
+            && self.may_run_user_code(function, arguments)
+        {
+            crate::flow_facts::forget_across_call(&mut self.path_facts, self.fact_locals.as_ref());
+        }
         result
     }
 
-    fn check_node_inner(&mut self, node: &Node) -> Result<Type, String> {
+    /// Could calling `function` with `arguments` run user code, which
+    /// may reassign a global or captured binding? Only a builtin that
+    /// isn't handed a fn value is known not to.
+    fn may_run_user_code(&self, function: &Node, arguments: &[Node]) -> bool {
+        let Node::Identifier { name, .. } = function else {
+            return true;
+        };
+        if self.env.has_user_binding(name) || crate::builtin_registry::lookup(name).is_none() {
+            return true;
+        }
+        // `map(xs, f)` and friends call back into `f`.
+        arguments.iter().any(|arg| match arg {
+            Node::FunctionLiteral { .. } => true,
+            Node::Identifier { name, .. } => {
+                self.env.has_user_binding(name)
+                    && matches!(
+                        self.env.get(name),
+                        Some(Type::Function { .. } | Type::Any) | None
+                    )
+            }
+            _ => false,
+        })
+    }
+
+    /// Does `node` contain a call that may run user code?
+    fn contains_user_call(&self, node: &Node) -> bool {
+        let mut found = false;
+        crate::uniqueness_walk::visit(node, &mut |n| {
+            if let Node::CallExpression {
+                function,
+                arguments,
+                ..
+            } = n
+            {
+                found = found || self.may_run_user_code(function, arguments);
+            }
+        });
+        found
+    }
+
+    fn check_node_inner(&mut self, node: &Node) -> Result<Type, CheckError> {
         match node {
-            Node::Program(_statements) => self.check_program(node),
+            Node::Program(_statements) => self.check_source(node, "<unknown>"),
             // RES-073: `use` is resolved away before typecheck. Treat
             // leftovers as void (no-op) for safety.
             Node::Use { .. } => Ok(Type::Void),
@@ -7433,13 +4829,24 @@ impl TypeChecker {
                     &["Int", "Float", "Bool", "String", "OpaquePtr", "Callback"];
                 const SUPPORTED_RETURNS: &[&str] =
                     &["Int", "Float", "Bool", "String", "Void", "OpaquePtr"];
+                // `int`, `float`, ... are accepted for the FFI scalars.
+                let ffi_name = |ty: &str| -> String {
+                    match ty {
+                        "int" | "float" | "bool" | "string" | "void" => {
+                            let mut name = ty.to_string();
+                            name[..1].make_ascii_uppercase();
+                            name
+                        }
+                        other => other.to_string(),
+                    }
+                };
 
                 for d in decls {
                     let fn_name = &d.resilient_name;
 
                     // Validate parameters
                     for (ty, param_name) in &d.parameters {
-                        if !SUPPORTED_PARAMS.contains(&ty.as_str()) {
+                        if !SUPPORTED_PARAMS.contains(&ffi_name(ty).as_str()) {
                             return Err(format!(
                                 "FFI: extern fn `{}` parameter `{}` has unsupported type `{}`; \
                                  supported types are: {}",
@@ -7447,7 +4854,8 @@ impl TypeChecker {
                                 param_name,
                                 ty,
                                 SUPPORTED_PARAMS.join(", ")
-                            ));
+                            )
+                            .into());
                         }
                         // Callbacks as parameters require extra validation
                         if ty == "Callback" {
@@ -7455,19 +4863,20 @@ impl TypeChecker {
                                 "FFI: extern fn `{}` parameter `{}` uses Callback type; \
                                  function pointers as extern parameters are not yet supported (RES-216)",
                                 fn_name, param_name
-                            ));
+                            ).into());
                         }
                     }
 
                     // Validate return type
-                    if !SUPPORTED_RETURNS.contains(&d.return_type.as_str()) {
+                    if !SUPPORTED_RETURNS.contains(&ffi_name(&d.return_type).as_str()) {
                         return Err(format!(
                             "FFI: extern fn `{}` has unsupported return type `{}`; \
                              supported types are: {}",
                             fn_name,
                             d.return_type,
                             SUPPORTED_RETURNS.join(", ")
-                        ));
+                        )
+                        .into());
                     }
 
                     // Reject Callback return types
@@ -7476,7 +4885,8 @@ impl TypeChecker {
                             "FFI: extern fn `{}` returns Callback type; \
                              function pointers as return values are not yet supported (RES-216)",
                             fn_name
-                        ));
+                        )
+                        .into());
                     }
                 }
                 Ok(Type::Void)
@@ -7511,11 +4921,41 @@ impl TypeChecker {
 
                 // Add parameter types to environment
                 for (param_type_name, param_name) in parameters {
-                    let param_type = self.parse_type_name(param_type_name)?;
-                    param_types.push(param_type.clone());
-                    function_env.set(param_name.clone(), param_type);
-                }
-
+                    if self.strict_any && crate::strict_any::is_any_annotation(param_type_name) {
+                        return Err(crate::strict_any::param_error(name, param_name).into());
+                    }
+                    // Untyped parameters the implicit-generics pass
+                    // couldn't generalize are `Any`.
+                    if param_type_name.trim().is_empty() {
+                        if self.strict_any {
+                            return Err(crate::strict_any::untyped_param_error(
+                                crate::overloads::base_name(name),
+                                param_name,
+                            )
+                            .into());
+                        }
+                        self.warn_at(
+                            crate::warnings::WarningCategory::ImplicitAny,
+                            *fn_span,
+                            &format!(
+                                "parameter `{}` of fn `{}` has no type annotation and is treated as `Any`",
+                                param_name,
+                                crate::overloads::base_name(name)
+                            ),
+                        );
+                    }
+                    let param_type = self.parse_type_name(param_type_name)?;
+                    param_types.push(param_type.clone());
+                    function_env.set(param_name.clone(), param_type);
+                }
+                if self.strict_any
+                    && declared_rt
+                        .as_deref()
+                        .is_some_and(crate::strict_any::is_any_annotation)
+                {
+                    return Err(crate::strict_any::return_error(name).into());
+                }
+
                 // Temporarily swap environments
                 std::mem::swap(&mut self.env, &mut function_env);
 
@@ -7669,10 +5109,10 @@ impl TypeChecker {
                                 "fn {}: contract can never hold (statically false clause)",
                                 name
                             );
-                            return Err(match decl_counterexample {
+                            return Err(refutation(match decl_counterexample {
                                 Some(cx) => format!("{} — counterexample: {}", base, cx),
                                 None => base,
-                            });
+                            }));
                         }
                         Some(true) => {
                             self.stats.requires_tautology += 1;
@@ -7744,10 +5184,10 @@ impl TypeChecker {
                                         "fn {}: contract proven safe under unbounded (LIA) arithmetic, but BV64 (wraparound-aware, matching Int64/OverflowMode::Wrap) arithmetic finds an overflow counterexample — LIA said safe, BV64 disproved it",
                                         name
                                     );
-                                    return Err(match bv_cx {
+                                    return Err(refutation(match bv_cx {
                                         Some(cx) => format!("{} — counterexample: {}", base, cx),
                                         None => base,
-                                    });
+                                    }));
                                 }
                                 if bv_timed_out {
                                     self.stats.verifier_timeouts += 1;
@@ -7812,7 +5252,7 @@ impl TypeChecker {
                     // from the function body. They are runtime-checked
                     // before any control flow, so by the time recovers_to
                     // is evaluated they hold.
-                    let mut axioms: Vec<Node> = requires.clone();
+                    let mut axioms: Vec<Node> = requires.to_vec();
                     axioms.extend(crate::assume_axioms::collect_leading_assume_axioms(body));
 
                     let mut verdict = fold_const_bool(clause, &no_bindings);
@@ -7862,10 +5302,10 @@ impl TypeChecker {
                                 pos_prefix, name
                             )
                         };
-                        return Err(match cx {
+                        return Err(refutation(match cx {
                             Some(m) => format!("{} — counterexample (final state): {}", base, m),
                             None => base,
-                        });
+                        }));
                     }
 
                     // RES-222: account for successful discharge and
@@ -7917,12 +5357,12 @@ impl TypeChecker {
                                 "{}fn {}: `recovers_to` invariant cannot be proven — fn declares `fails` {:?} but no handler catches the fault, and Z3 could not show the recovery invariant holds under the declared `requires`",
                                 pos_prefix, name, fails
                             );
-                            return Err(match cx {
+                            return Err(refutation(match cx {
                                 Some(m) => {
                                     format!("{} — counterexample (final state): {}", base, m)
                                 }
                                 None => base,
-                            });
+                            }));
                         }
                     }
 
@@ -7938,10 +5378,10 @@ impl TypeChecker {
                              hold for all inputs (no `requires` constraint to limit them)",
                             pos_prefix, name
                         );
-                        return Err(match cx {
+                        return Err(refutation(match cx {
                             Some(ref m) => format!("{} — counterexample: {}", base, m),
                             None => base,
-                        });
+                        }));
                     }
 
                     // RES-392b: per-prefix bounded model checking.
@@ -7964,19 +5404,16 @@ impl TypeChecker {
                 // RES-065: push each requires clause's extractable
                 // assumption into const_bindings so interior call
                 // sites can use them. This is the inter-procedural
-                // chaining step.
-                // RES-1766: pre-size to requires.len() — at most one
-                // push per requires clause (only when it extracts an
-                // `eq` assumption). Hot: every checked function.
-                let mut pushed_assumptions: Vec<(String, Option<i64>)> =
-                    Vec::with_capacity(requires.len());
-                for clause in requires {
+                // chaining step. The body's own `let`s fold into the
+                // same table; both are undone after the body (see the
+                // restore below).
+                let saved_const_bindings = self.const_bindings.clone();
+                for clause in requires.iter() {
                     if let Some((aname, av)) = extract_eq_assumption(clause) {
-                        let prev = self.const_bindings.get(&aname).copied();
-                        self.const_bindings.insert(aname.clone(), av);
-                        pushed_assumptions.push((aname, prev));
+                        self.const_bindings.insert(aname, av);
                     }
                 }
+                let body_start_const_bindings = self.const_bindings.clone();
 
                 // RES-387: enter the fn's fault scope. Call sites in
                 // the body may invoke fns with `fails` variants only
@@ -7993,8 +5430,22 @@ impl TypeChecker {
                     self.current_fn_return_type = Some(rt);
                 }
 
+                // The body starts from its own `requires`, not from
+                // whatever `if` encloses the declaration.
+                let saved_facts = std::mem::take(&mut self.path_facts);
+                let saved_locals = self.fact_locals.replace(crate::flow_facts::Locals::enter(
+                    parameters.iter().map(|(_, pname)| pname.as_str()),
+                    body,
+                ));
+                for clause in requires.iter() {
+                    let facts = self.condition_facts(clause, true);
+                    self.path_facts.extend(facts);
+                }
+
                 // Check function body
                 let body_result = self.check_node(body);
+                self.path_facts = saved_facts;
+                self.fact_locals = saved_locals;
 
                 // RES-387: leave the fault scope before propagating any
                 // error, so a nested fn declared inside this body does
@@ -8005,17 +5456,16 @@ impl TypeChecker {
 
                 let body_type = body_result?;
 
-                // Restore const_bindings to its pre-body state.
-                for (aname, prev) in pushed_assumptions.into_iter().rev() {
-                    match prev {
-                        Some(v) => {
-                            self.const_bindings.insert(aname, v);
-                        }
-                        None => {
-                            self.const_bindings.remove(&aname);
-                        }
-                    }
-                }
+                // Restore const_bindings to its pre-body state, less
+                // every name the body rebound or assigned: a local
+                // constant must not leak into the next fn, but an
+                // outer variable the body writes is no longer constant
+                // once the fn may have been called.
+                let body_end_const_bindings =
+                    std::mem::replace(&mut self.const_bindings, saved_const_bindings);
+                self.const_bindings.retain(|name, _| {
+                    body_end_const_bindings.get(name) == body_start_const_bindings.get(name)
+                });
 
                 // Restore original environment
                 std::mem::swap(&mut self.env, &mut function_env);
@@ -8051,7 +5501,13 @@ impl TypeChecker {
                         Type::Void
                     } else {
                         let declared = self.parse_type_name(rt_name)?;
-                        if !self.type_satisfies(&body_type, &declared)
+                        // A body that ends in `return` on every path has
+                        // had each `return` checked against `declared`
+                        // (RES-403); its block type is just whatever
+                        // the last statement evaluates to, e.g. `void`
+                        // for a `loop` that only exits by returning.
+                        if !node_terminates(body)
+                            && !self.type_satisfies(&body_type, &declared)
                             // RES-2693: body that returns a concrete struct satisfies
                             // a trait-typed return annotation when the struct impls it.
                             && !self.satisfies_trait_param(&declared, &body_type)
@@ -8059,7 +5515,8 @@ impl TypeChecker {
                             return Err(format!(
                                 "fn {}: return type mismatch — declared {}, body produces {}",
                                 name, declared, body_type
-                            ));
+                            )
+                            .into());
                         }
                         // RES-1112: a non-`void` return type requires every
                         // control-flow path to yield a value — either an
@@ -8074,7 +5531,7 @@ impl TypeChecker {
                             && declared != Type::Any
                             && !body_yields_value(body)
                         {
-                            return Err(render_missing_return_error(name, &declared));
+                            return Err(render_missing_return_error(name, &declared).into());
                         }
                         declared
                     }
@@ -8099,7 +5556,7 @@ impl TypeChecker {
                 for invariant in invariants {
                     let ty = self.check_node(invariant)?;
                     if ty != Type::Bool && ty != Type::Any {
-                        return Err(format!("live block invariant must be Bool, got {}", ty));
+                        return Err(format!("live block invariant must be Bool, got {}", ty).into());
                     }
                 }
                 // Live blocks preserve the type of their body
@@ -8125,10 +5582,10 @@ impl TypeChecker {
                 let hi_t = self.check_node(hi)?;
                 let ok = |t: &Type| matches!(t, Type::Int | Type::Any);
                 if !ok(&lo_t) {
-                    return Err(format!("range lower bound must be Int, got {}", lo_t));
+                    return Err(format!("range lower bound must be Int, got {}", lo_t).into());
                 }
                 if !ok(&hi_t) {
-                    return Err(format!("range upper bound must be Int, got {}", hi_t));
+                    return Err(format!("range upper bound must be Int, got {}", hi_t).into());
                 }
                 Ok(Type::Range)
             }
@@ -8146,14 +5603,17 @@ impl TypeChecker {
                     return Err(format!(
                         "Assert condition must be a boolean, got {}",
                         condition_type
-                    ));
+                    )
+                    .into());
                 }
 
                 // Message, if present, should be a string
                 if let Some(msg) = message {
                     let msg_type = self.check_node(msg)?;
                     if msg_type != Type::String && msg_type != Type::Any {
-                        return Err(format!("Assert message must be a string, got {}", msg_type));
+                        return Err(
+                            format!("Assert message must be a string, got {}", msg_type).into()
+                        );
                     }
                 }
 
@@ -8180,25 +5640,48 @@ impl TypeChecker {
                     return Err(format!(
                         "Assume condition must be a boolean, got {}",
                         condition_type
-                    ));
+                    )
+                    .into());
                 }
                 if let Some(msg) = message {
                     let msg_type = self.check_node(msg)?;
                     if msg_type != Type::String && msg_type != Type::Any {
-                        return Err(format!("Assume message must be a string, got {}", msg_type));
+                        return Err(
+                            format!("Assume message must be a string, got {}", msg_type).into()
+                        );
                     }
                 }
                 Ok(Type::Void)
             }
 
             Node::Block {
-                stmts: statements, ..
+                stmts: statements,
+                span: block_span,
             } => {
                 let mut result_type = Type::Void;
 
                 // Create a new enclosed environment for block
                 let mut block_env = TypeEnvironment::new_enclosed(self.env.clone());
                 std::mem::swap(&mut self.env, &mut block_env);
+                let saved_locals = self.fact_locals.clone();
+
+                // Hoist nested `fn` declarations the same way the
+                // top-level pre-pass does, so sibling helpers declared
+                // inside one block can call each other regardless of
+                // textual order. The interpreter already binds every
+                // block-level fn before running the block's statements.
+                for stmt in statements {
+                    if let Node::Function {
+                        name,
+                        parameters,
+                        return_type,
+                        ..
+                    } = stmt
+                    {
+                        let fn_type = self.hoisted_fn_type(parameters, return_type.as_deref());
+                        self.env.set(name.clone(), fn_type);
+                    }
+                }
 
                 // RES-1113: track reachability. Once a statement
                 // unconditionally terminates (return / break /
@@ -8206,7 +5689,7 @@ impl TypeChecker {
                 // any subsequent statement in the same block is
                 // unreachable and rejected with a clean diagnostic.
                 let mut reachable = true;
-                let mut block_err: Option<String> = None;
+                let mut block_err: Option<CheckError> = None;
                 for stmt in statements {
                     if !reachable {
                         let (kind, sp) = match stmt {
@@ -8224,20 +5707,46 @@ impl TypeChecker {
                         if sp.start.line > 0 {
                             self.current_span = sp;
                         }
-                        block_err = Some(format!(
-                            "unreachable code: {} after an earlier return/break/continue",
-                            kind
-                        ));
+                        block_err = Some(
+                            format!(
+                                "unreachable code: {} after an earlier return/break/continue",
+                                kind
+                            )
+                            .into(),
+                        );
                         break;
                     }
-                    result_type = self.check_node(stmt)?;
+                    let sp = statement_span(stmt);
+                    if sp.start.line > 0 {
+                        self.current_span = sp;
+                    }
+                    match self.check_node(stmt) {
+                        Ok(ty) => result_type = ty,
+                        Err(e) => {
+                            block_err = Some(e);
+                            break;
+                        }
+                    }
                     if node_terminates(stmt) {
                         reachable = false;
+                    } else {
+                        self.assume_after_early_exit(stmt);
                     }
                 }
 
+                if self.capture_scopes {
+                    self.record_scope(*block_span, statements);
+                }
+
                 // Restore original environment
                 std::mem::swap(&mut self.env, &mut block_env);
+                // Past the block its `let`s name the outer bindings again.
+                self.fact_locals = saved_locals;
+                for stmt in statements {
+                    if let Node::LetStatement { name, .. } = stmt {
+                        crate::flow_facts::forget(&mut self.path_facts, name);
+                    }
+                }
 
                 if let Some(e) = block_err {
                     return Err(e);
@@ -8254,6 +5763,17 @@ impl TypeChecker {
             } => {
                 // RES-1862: track innermost span for better diagnostics.
                 self.current_span = *span;
+                if !name.starts_with('_') && !name.contains('$') && self.env.has_user_binding(name)
+                {
+                    self.warn_at(
+                        crate::warnings::WarningCategory::Shadowing,
+                        *span,
+                        &format!(
+                            "`let {}` shadows an existing binding of the same name",
+                            name
+                        ),
+                    );
+                }
                 // RES-4095 increment 4: `let x: Array<dyn Trait> = [lit,
                 // lit, ...];` — a heterogeneous array of trait objects
                 // is the entire point of `Array<dyn Trait>`, but the
@@ -8290,12 +5810,31 @@ impl TypeChecker {
                 // The typechecker conservatively types WhileStatement as Void; skip the
                 // void-binding check here so `let x = loop { break 42; }` is accepted.
                 let rhs_is_loop = matches!(value.as_ref(), Node::WhileStatement { .. });
+                if self.strict_any {
+                    let annotated_any = type_annot
+                        .as_deref()
+                        .is_some_and(crate::strict_any::is_any_annotation);
+                    if annotated_any || (type_annot.is_none() && value_type == Type::Any) {
+                        return Err(crate::strict_any::let_error(name, value, annotated_any).into());
+                    }
+                } else if type_annot.is_none() && value_type == Type::Any && !name.starts_with('_')
+                {
+                    self.warn_at(
+                        crate::warnings::WarningCategory::ImplicitAny,
+                        *span,
+                        &format!(
+                            "`let {}` has type `Any`, introduced by {}",
+                            name,
+                            crate::strict_any::describe_origin(value)
+                        ),
+                    );
+                }
                 if value_type == Type::Void && !name.starts_with('_') && !rhs_is_loop {
                     return Err(format!(
                         "cannot bind void value to `{}` — the right-hand side expression has type void; \
                          use `let _ = expr;` to explicitly discard it",
                         name
-                    ));
+                    ).into());
                 }
                 // RES-053: enforce `let x: T = value` — reject if value's
                 // type isn't compatible with the declared annotation.
@@ -8306,10 +5845,15 @@ impl TypeChecker {
                         // when the struct implements the trait.
                         && !self.satisfies_trait_param(&declared, &value_type)
                     {
+                        self.point_at(value);
                         return Err(format!(
-                            "let {}: {} — value has type {}",
-                            name, declared, value_type
-                        ));
+                            "let {}: {} — value has type {}{}",
+                            name,
+                            declared,
+                            value_type,
+                            self.unknown_type_hint(&declared)
+                        )
+                        .into());
                     }
                     // RES-411 / RES-3880: reject integer literals that overflow
                     // the declared pinned-int type. Shared with call args,
@@ -8372,6 +5916,12 @@ impl TypeChecker {
                 } else {
                     self.const_bindings.remove(name);
                 }
+                // A shadowing `let` is a new variable; facts about the
+                // old one no longer describe it.
+                crate::flow_facts::forget(&mut self.path_facts, name);
+                if let Some(locals) = &mut self.fact_locals {
+                    locals.bind(name);
+                }
                 Ok(Type::Void)
             }
 
@@ -8408,7 +5958,8 @@ impl TypeChecker {
                             return Err(format!(
                                 "Struct {} has no field `{}`{}",
                                 struct_name, pf, hint
-                            ));
+                            )
+                            .into());
                         }
                     }
                     // Exhaustiveness check when `..` is not used.
@@ -8424,7 +5975,7 @@ impl TypeChecker {
                                 "Non-exhaustive destructure of {}: missing field(s) {} — add `..` to ignore them",
                                 struct_name,
                                 missing.join(", ")
-                            ));
+                            ).into());
                         }
                     }
                 }
@@ -8471,7 +6022,7 @@ impl TypeChecker {
                         return Err(format!(
                             "Array literal contains mixed element types: {} and {}. Resilient does not implicitly coerce between types — pick one and convert the others explicitly.",
                             first_ty, other_ty
-                        ));
+                        ).into());
                     }
                 }
                 // RES-3923: track the homogeneous element type so
@@ -8509,7 +6060,7 @@ impl TypeChecker {
                         return Err(format!(
                             "map literal contains mixed key types: {} and {} — all keys must have the same type",
                             first, other
-                        ));
+                        ).into());
                     }
                 }
                 if val_types.len() > 1 {
@@ -8518,7 +6069,7 @@ impl TypeChecker {
                         return Err(format!(
                             "map literal contains mixed value types: {} and {} — all values must have the same type",
                             first, other
-                        ));
+                        ).into());
                     }
                 }
                 Ok(Type::Any)
@@ -8543,7 +6094,8 @@ impl TypeChecker {
                         return Err(format!(
                             "Set literal contains mixed element types: {} and {}",
                             first, other
-                        ));
+                        )
+                        .into());
                     }
                 }
                 Ok(Type::Any)
@@ -8562,10 +6114,7 @@ impl TypeChecker {
                 if compatible(&inner_type, &Type::Result) {
                     return Ok(Type::Any);
                 }
-                Err(format!(
-                    "? operator expects a Result or Option, got {}",
-                    inner_type
-                ))
+                Err(format!("? operator expects a Result or Option, got {}", inner_type).into())
             }
 
             // RES-363: `expr?.field` / `expr?.method(args)` — optional
@@ -8610,7 +6159,17 @@ impl TypeChecker {
                     self.current_fn_return_type = Some(rt);
                 }
                 std::mem::swap(&mut self.env, &mut fn_env);
-                let body_type = self.check_node(body)?;
+                // A closure may run anywhere; the enclosing `if`
+                // conditions say nothing about its parameters.
+                let saved_facts = std::mem::take(&mut self.path_facts);
+                let saved_locals = self.fact_locals.replace(crate::flow_facts::Locals::enter(
+                    parameters.iter().map(|(_, pname)| pname.as_str()),
+                    body,
+                ));
+                let body_type = self.check_node(body);
+                self.path_facts = saved_facts;
+                self.fact_locals = saved_locals;
+                let body_type = body_type?;
                 std::mem::swap(&mut self.env, &mut fn_env);
                 self.current_fn_return_type = saved_lit_return_type;
                 if lit_return_type.is_none()
@@ -8657,7 +6216,8 @@ impl TypeChecker {
                                 return Err(format!(
                                     "or-pattern branches bind different names: {:?} vs {:?}",
                                     first, other
-                                ));
+                                )
+                                .into());
                             }
                         }
                     }
@@ -8691,7 +6251,9 @@ impl TypeChecker {
                                     }
                                 }
                             }
-                            return Err(format!("Match arm guard must be a boolean, got {}", gt));
+                            return Err(
+                                format!("Match arm guard must be a boolean, got {}", gt).into()
+                            );
                         }
                     }
                     let body_res = self.check_node(body);
@@ -8752,7 +6314,8 @@ impl TypeChecker {
                                     if has_true { "" } else { "missing `true` arm" },
                                     if !has_true && !has_false { "; " } else { "" },
                                     if has_false { "" } else { "missing `false` arm" },
-                                ));
+                                )
+                                .into());
                             }
                         }
                         // For any other scrutinee type — int, float,
@@ -8809,7 +6372,8 @@ impl TypeChecker {
                                     return Err(format!(
                                         "Non-exhaustive match on enum `{}`: missing variants: {}",
                                         sname, list
-                                    ));
+                                    )
+                                    .into());
                                 }
                             } else {
                                 // RES-4012: before falling back to the
@@ -8837,7 +6401,7 @@ impl TypeChecker {
                                     return Err(format!(
                                         "Non-exhaustive match on struct `{}`: add `{} {{ .. }}`, `_`, or an identifier arm that covers every field",
                                         sname, sname
-                                    ));
+                                    ).into());
                                 }
                             }
                         }
@@ -8869,7 +6433,8 @@ impl TypeChecker {
                                 return Err(format!(
                                     "Non-exhaustive match on enum `Result`: missing variants: {}",
                                     missing.join(", ")
-                                ));
+                                )
+                                .into());
                             }
                         }
                         // RES-2651: Option<T> is exhaustive when both
@@ -8912,14 +6477,15 @@ impl TypeChecker {
                                     "Non-exhaustive match on {}: missing variants: {}",
                                     scrutinee_type,
                                     missing.join(", ")
-                                ));
+                                )
+                                .into());
                             }
                         }
                         other => {
                             return Err(format!(
                                 "Non-exhaustive match on {}: add a wildcard `_` or identifier arm to handle unmatched values",
                                 other
-                            ));
+                            ).into());
                         }
                     }
                 }
@@ -8942,7 +6508,8 @@ impl TypeChecker {
                             return Err(format!(
                                 "match arms have incompatible types: {} and {}",
                                 e, t
-                            ));
+                            )
+                            .into());
                         }
                     }
                 }
@@ -9019,7 +6586,7 @@ impl TypeChecker {
                         name,
                         prev_target,
                         target,
-                    ));
+                    ).into());
                 }
                 self.type_aliases.insert(name.clone(), target.clone());
                 Ok(Type::Void)
@@ -9112,7 +6679,8 @@ impl TypeChecker {
                         self.current_span.start.column,
                         index,
                         elems.len()
-                    ));
+                    )
+                    .into());
                 }
                 Ok(Type::Any)
             }
@@ -9176,7 +6744,7 @@ impl TypeChecker {
                         return Err(format!(
                             "actor `{}` state field `{}` has reference type {}; actor boundaries require ownership-by-value to preserve race safety",
                             name, field, ty
-                        ));
+                        ).into());
                     }
                     let resolved = self.parse_type_name(ty)?;
                     let init_ty = self.check_node(init)?;
@@ -9184,7 +6752,8 @@ impl TypeChecker {
                         return Err(format!(
                             "actor `{}` state field `{}` initializer has type {}, expected {}",
                             name, field, init_ty, resolved
-                        ));
+                        )
+                        .into());
                     }
                     self.env.set(field.clone(), resolved.clone());
                     resolved_fields.push((field.clone(), resolved));
@@ -9194,17 +6763,18 @@ impl TypeChecker {
                 // pure dead allocation (one extra `Vec` + one extra
                 // `String` per state field).
                 //
-                // RES-1365: wrap in `Rc` so per-FieldAccess reads on
+                // RES-1365: wrap in `Arc` so per-FieldAccess reads on
                 // the actor state can clone a single refcount.
                 self.struct_fields
-                    .insert(name.clone(), std::rc::Rc::new(resolved_fields));
+                    .insert(name.clone(), std::sync::Arc::new(resolved_fields));
                 for clause in always_clauses {
                     let ty = self.check_node(clause)?;
                     if ty != Type::Bool && ty != Type::Any {
                         return Err(format!(
                             "actor `{}` `always` invariant must be Bool, got {}",
                             name, ty
-                        ));
+                        )
+                        .into());
                     }
                 }
                 // RES-388 follow-up: `eventually(after: h): P;` — `P`
@@ -9216,13 +6786,15 @@ impl TypeChecker {
                         return Err(format!(
                             "actor `{}` `eventually` post-condition must be Bool, got {}",
                             name, ty
-                        ));
+                        )
+                        .into());
                     }
                     if !receive_handlers.iter().any(|h| h.name == ev.target_handler) {
                         return Err(format!(
                             "actor `{}` `eventually(after: {})` references unknown handler",
                             name, ev.target_handler
-                        ));
+                        )
+                        .into());
                     }
                 }
                 for handler in receive_handlers {
@@ -9239,7 +6811,7 @@ impl TypeChecker {
                             return Err(format!(
                                 "actor `{}` handler `{}` parameter `{}` has reference type {}; actor boundaries require ownership-by-value to preserve race safety",
                                 name, handler.name, pname, pty
-                            ));
+                            ).into());
                         }
                         let resolved = self.parse_type_name(pty)?;
                         self.env.set(pname.clone(), resolved);
@@ -9276,7 +6848,8 @@ impl TypeChecker {
                     return Err(format!(
                         "{}:{}:{}: error: duplicate struct declaration `{}`",
                         self.source_path, span.start.line, span.start.column, name,
-                    ));
+                    )
+                    .into());
                 }
 
                 let tp_set: std::collections::HashSet<&str> =
@@ -9290,7 +6863,8 @@ impl TypeChecker {
                         return Err(format!(
                             "{}:{}:{}: error: duplicate field `{}` in struct `{}`",
                             self.source_path, span.start.line, span.start.column, field_name, name,
-                        ));
+                        )
+                        .into());
                     }
                     // RES-2574: type parameters resolve as Any; concrete
                     // types are checked at construction sites.
@@ -9301,10 +6875,10 @@ impl TypeChecker {
                     };
                     resolved.push((field_name.clone(), ty));
                 }
-                // RES-1365: wrap in `Rc` so per-FieldAccess reads
+                // RES-1365: wrap in `Arc` so per-FieldAccess reads
                 // clone a single refcount.
                 self.struct_fields
-                    .insert(name.clone(), std::rc::Rc::new(resolved));
+                    .insert(name.clone(), std::sync::Arc::new(resolved));
                 // RES-2801: store generic struct metadata for
                 // construction-site type-parameter validation.
                 if !type_params.is_empty() {
@@ -9349,16 +6923,24 @@ impl TypeChecker {
                                 "struct update base has type `{}`, \
                                  but the literal constructs `{}`",
                                 base_name, effective_struct_name
-                            ));
+                            )
+                            .into());
                         }
                     } else if base_ty != Type::Any {
                         return Err(format!(
                             "struct update base must be a struct, found {}",
                             base_ty
-                        ));
+                        )
+                        .into());
                     }
                 }
                 let declared_opt = self.struct_fields.get(&effective_struct_name).cloned();
+                if declared_opt.is_none()
+                    && name != crate::ANONYMOUS_STRUCT_NAME
+                    && !name.contains("::")
+                {
+                    self.unresolved_struct_literals.push((name.clone(), *span));
+                }
                 // RES-2801: collect field value types for generic
                 // type-parameter consistency checking below.
                 let generic_info = self
@@ -9385,7 +6967,8 @@ impl TypeChecker {
                                 field_name,
                                 &hint,
                                 &avail_str,
-                            ));
+                            )
+                            .into());
                         }
                         // Type mismatch on a known field.
                         if let Some((_, field_ty)) = declared.iter().find(|(n, _)| n == field_name)
@@ -9395,7 +6978,8 @@ impl TypeChecker {
                                 "struct `{}` field `{}` has type {}, \
                                  but the initializer has type {}",
                                 effective_struct_name, field_name, field_ty, val_ty
-                            ));
+                            )
+                            .into());
                         }
                         // RES-3880: an in-type literal must still fit the
                         // pinned-width field type it initializes.
@@ -9432,7 +7016,8 @@ impl TypeChecker {
                                          bound to `{}` by an earlier field, \
                                          but field `{}` has type `{}`",
                                         effective_struct_name, raw_type, bound, field_name, val_ty
-                                    ));
+                                    )
+                                    .into());
                                 }
                             } else {
                                 bindings.insert(raw_type.as_str(), val_ty.clone());
@@ -9480,7 +7065,8 @@ impl TypeChecker {
                         } else {
                             avail.join(", ")
                         }
-                    ));
+                    )
+                    .into());
                 }
                 // RES-153: if the target is a known struct, return the
                 // declared field's type. When the struct IS declared but
@@ -9556,7 +7142,8 @@ impl TypeChecker {
                         } else {
                             avail.join(", ")
                         }
-                    ));
+                    )
+                    .into());
                     // Struct name unknown (forward reference, generic container,
                     // etc.) — fall through to permissive Any.
                 }
@@ -9784,7 +7371,8 @@ impl TypeChecker {
                                 return Err(format!(
                                     "struct `{}` field `{}` has type {}, cannot assign {}",
                                     sname, field, field_ty, val_ty
-                                ));
+                                )
+                                .into());
                             }
                         }
                         None => {
@@ -9797,7 +7385,8 @@ impl TypeChecker {
                                 field,
                                 hint,
                                 avail.join(", ")
-                            ));
+                            )
+                            .into());
                         }
                     }
                 }
@@ -9815,7 +7404,7 @@ impl TypeChecker {
                     return Err(format!(
                         "cannot index a value of type {} — only arrays, strings, and maps support `[]` indexing",
                         tgt_ty
-                    ));
+                    ).into());
                 }
                 // RES-405: array/string indexing must use an integer
                 // index. Reject obvious type errors like `arr["key"]`
@@ -9827,7 +7416,8 @@ impl TypeChecker {
                     return Err(format!(
                         "index expression requires an integer index, got {}",
                         idx_ty
-                    ));
+                    )
+                    .into());
                 }
                 // RES-921 added Python-style negative indexing to the runtime: arr[-1]
                 // is the last element, arr[-2] is second-to-last, etc. The RES-415
@@ -9855,19 +7445,17 @@ impl TypeChecker {
                 if let Some(lo_expr) = lo {
                     let lo_ty = self.check_node(lo_expr)?;
                     if !is_int_like(&lo_ty) {
-                        return Err(format!(
-                            "slice lower bound must be an integer, got {}",
-                            lo_ty
-                        ));
+                        return Err(
+                            format!("slice lower bound must be an integer, got {}", lo_ty).into(),
+                        );
                     }
                 }
                 if let Some(hi_expr) = hi {
                     let hi_ty = self.check_node(hi_expr)?;
                     if !is_int_like(&hi_ty) {
-                        return Err(format!(
-                            "slice upper bound must be an integer, got {}",
-                            hi_ty
-                        ));
+                        return Err(
+                            format!("slice upper bound must be an integer, got {}", hi_ty).into(),
+                        );
                     }
                 }
                 match target_ty {
@@ -9893,7 +7481,7 @@ impl TypeChecker {
                     return Err(format!(
                         "cannot index a value of type {} — only arrays, strings, and maps support `[]` indexing",
                         tgt_ty
-                    ));
+                    ).into());
                 }
                 if matches!(tgt_ty, Type::Array | Type::TypedArray(_) | Type::String)
                     && !matches!(idx_ty, Type::Int | Type::Any)
@@ -9902,7 +7490,8 @@ impl TypeChecker {
                     return Err(format!(
                         "index assignment requires an integer index, got {}",
                         idx_ty
-                    ));
+                    )
+                    .into());
                 }
                 let _ = self.check_node(value)?;
                 Ok(Type::Void)
@@ -9933,7 +7522,7 @@ impl TypeChecker {
                     return Err(format!(
                         "cannot iterate over type {} — for-in requires an array, range, string, or iterator",
                         iter_ty
-                    ));
+                    ).into());
                 }
                 // RES-910: track loop depth so nested `break`/`continue`
                 // are accepted only inside the body.
@@ -9977,7 +7566,15 @@ impl TypeChecker {
                 let mut loop_env = TypeEnvironment::new_enclosed(self.env.clone());
                 loop_env.set(name.clone(), elem_ty);
                 std::mem::swap(&mut self.env, &mut loop_env);
+                // The loop variable shadows any outer `name`, and what
+                // the body reassigns may differ on a later iteration.
+                crate::flow_facts::forget_assigned_in(&mut self.path_facts, body);
+                let saved_facts = self.path_facts.clone();
+                let saved_locals = self.fact_locals.clone();
+                crate::flow_facts::forget(&mut self.path_facts, name);
                 let body_result = self.check_node(body);
+                self.path_facts = saved_facts;
+                self.fact_locals = saved_locals;
                 std::mem::swap(&mut self.env, &mut loop_env);
 
                 self.loop_depth -= 1;
@@ -9997,14 +7594,17 @@ impl TypeChecker {
                 // RES-406: while condition must be boolean.
                 let cond_ty = self.check_node(condition)?;
                 if cond_ty != Type::Bool && cond_ty != Type::Any {
-                    return Err(format!(
-                        "while condition must be a boolean, got {}",
-                        cond_ty
-                    ));
+                    return Err(
+                        format!("while condition must be a boolean, got {}", cond_ty).into(),
+                    );
                 }
                 self.loop_depth += 1;
                 self.loop_label_stack.push(label.clone()); // RES-2653
-                let body_result = self.check_node(body);
+                // Anything the body reassigns may differ on a later
+                // iteration; the condition holds at the top of each.
+                crate::flow_facts::forget_assigned_in(&mut self.path_facts, body);
+                let body_facts = self.condition_facts(condition, true);
+                let body_result = self.check_branch(body, body_facts, None);
                 self.loop_depth -= 1;
                 self.loop_label_stack.pop(); // RES-2653
                 let _ = body_result?;
@@ -10017,7 +7617,8 @@ impl TypeChecker {
                 if self.loop_depth == 0 {
                     return Err("'break' outside of a loop — `break` is only valid \
                          inside a `while` or `for-in` body"
-                        .to_string());
+                        .to_string()
+                        .into());
                 }
                 Ok(Type::Void)
             }
@@ -10027,7 +7628,8 @@ impl TypeChecker {
                 if self.loop_depth == 0 {
                     return Err("'break' outside of a loop — `break` is only valid \
                          inside a `while` or `for-in` body"
-                        .to_string());
+                        .to_string()
+                        .into());
                 }
                 self.check_node(value)?;
                 Ok(Type::Void)
@@ -10036,7 +7638,8 @@ impl TypeChecker {
                 if self.loop_depth == 0 {
                     return Err("'continue' outside of a loop — `continue` is only \
                          valid inside a `while` or `for-in` body"
-                        .to_string());
+                        .to_string()
+                        .into());
                 }
                 Ok(Type::Void)
             }
@@ -10044,7 +7647,7 @@ impl TypeChecker {
             // refers to an enclosing labeled loop.
             Node::BreakLabel { label, .. } => {
                 if self.loop_depth == 0 {
-                    return Err(format!("'break {label}' outside of any loop"));
+                    return Err(format!("'break {label}' outside of any loop").into());
                 }
                 if !self
                     .loop_label_stack
@@ -10053,13 +7656,14 @@ impl TypeChecker {
                 {
                     return Err(format!(
                         "label '{label}' not found — no enclosing loop is labeled '{label}'"
-                    ));
+                    )
+                    .into());
                 }
                 Ok(Type::Void)
             }
             Node::ContinueLabel { label, .. } => {
                 if self.loop_depth == 0 {
-                    return Err(format!("'continue {label}' outside of any loop"));
+                    return Err(format!("'continue {label}' outside of any loop").into());
                 }
                 if !self
                     .loop_label_stack
@@ -10068,7 +7672,8 @@ impl TypeChecker {
                 {
                     return Err(format!(
                         "label '{label}' not found — no enclosing loop is labeled '{label}'"
-                    ));
+                    )
+                    .into());
                 }
                 Ok(Type::Void)
             }
@@ -10097,10 +7702,12 @@ impl TypeChecker {
                     if !self.type_satisfies(&value_type, &declared)
                         && !self.satisfies_trait_param(&declared, &value_type)
                     {
+                        self.point_at(value);
                         return Err(format!(
                             "const {}: {} — value has type {}",
                             name, declared, value_type
-                        ));
+                        )
+                        .into());
                     }
                     // RES-416 / RES-3880: apply the same pinned-int overflow
                     // check to const declarations that LetStatement already has.
@@ -10129,7 +7736,7 @@ impl TypeChecker {
                 // while staying permissive for Any-typed variables
                 // (unresolved generics, dynamic containers).
                 if let Some(var_ty) = self.env.get(name)
-                    && !compatible(&var_ty, &val_ty)
+                    && !compatible(&val_ty, &var_ty)
                     // RES-2693: reassigning a struct to a trait-typed variable is
                     // valid when the struct implements that trait.
                     && !self.satisfies_trait_param(&var_ty, &val_ty)
@@ -10150,13 +7757,15 @@ impl TypeChecker {
                     return Err(format!(
                         "cannot assign {} to variable `{}` of type {}",
                         val_ty, name, var_ty
-                    ));
+                    )
+                    .into());
                 }
                 // RES-063: any reassignment kills const-tracking. We
                 // could try to re-track if RHS is foldable, but
                 // mid-function mutation is rare and the conservative
                 // choice keeps the verifier sound.
                 self.const_bindings.remove(name);
+                crate::flow_facts::forget(&mut self.path_facts, name);
                 Ok(Type::Void)
             }
 
@@ -10183,10 +7792,14 @@ impl TypeChecker {
                     // is valid when the struct implements that trait.
                     && !self.satisfies_trait_param(declared, &ret_type)
                 {
-                    return Err(format!(
+                    let message = format!(
                         "return type mismatch — declared {}, returning {}",
                         declared, ret_type
-                    ));
+                    );
+                    if let Some(expr) = value {
+                        self.point_at(expr);
+                    }
+                    return Err(message.into());
                 }
                 // RES-3880: a literal that satisfies the Int↔pinned-width bridge
                 // must still fit the declared pinned-width return type.
@@ -10212,12 +7825,17 @@ impl TypeChecker {
                 }
                 let condition_type = self.check_node(condition)?;
                 if condition_type != Type::Bool && condition_type != Type::Any {
-                    return Err(format!(
-                        "If condition must be a boolean, got {}",
-                        condition_type
-                    ));
+                    return Err(
+                        format!("If condition must be a boolean, got {}", condition_type).into(),
+                    );
                 }
 
+                // Facts each branch may assume (`y >= 0`, `x != None`,
+                // an inlined `is_valid_reading(y)`). Computed before the
+                // RES-064 binding below turns `x` into a constant.
+                let then_facts = self.condition_facts(condition, true);
+                let else_facts = self.condition_facts(condition, false);
+
                 // RES-064: if the condition is `IDENT == LITERAL` (or
                 // `LITERAL == IDENT`), assume that equality inside the
                 // consequence by pushing the binding. Restore on exit
@@ -10236,7 +7854,16 @@ impl TypeChecker {
                     None
                 };
 
-                let consequence_type = self.check_node(consequence)?;
+                // A type test on a union-typed binding narrows it in
+                // each branch: `if is_int(x)` sees `x: int` in the
+                // then-branch and the remaining members in the else.
+                let narrowed = self.union_narrowing(condition);
+
+                let consequence_type = self.check_branch(
+                    consequence,
+                    then_facts,
+                    narrowed.as_ref().map(|(n, t, _)| (n.as_str(), t.clone())),
+                )?;
 
                 // Restore.
                 if let Some((name, prev)) = saved {
@@ -10251,7 +7878,11 @@ impl TypeChecker {
                 }
 
                 if let Some(alt) = alternative {
-                    let alternative_type = self.check_node(alt)?;
+                    let alternative_type = self.check_branch(
+                        alt,
+                        else_facts,
+                        narrowed.as_ref().map(|(n, _, t)| (n.as_str(), t.clone())),
+                    )?;
 
                     // RES-421: when one branch unconditionally diverges
                     // (return / break / continue), its type doesn't
@@ -10274,7 +7905,8 @@ impl TypeChecker {
                         return Err(format!(
                             "If branches have incompatible types: {} and {}",
                             consequence_type, alternative_type
-                        ));
+                        )
+                        .into());
                     }
                     // RES-402: if one branch is Any and the other is a
                     // concrete type, propagate the concrete type so callers
@@ -10337,6 +7969,13 @@ impl TypeChecker {
                         crate::EnumPayload::Named(_) => {}
                     }
                 }
+                // A call resolves an overloaded name before it gets
+                // here, so this is a use as a value.
+                if self.overload_sets.contains_key(name.as_str())
+                    && !self.env.has_user_binding(name)
+                {
+                    return Err(crate::overloads::as_value_error(name).into());
+                }
                 match self.env.get(name) {
                     Some(typ) => Ok(typ),
                     None => {
@@ -10365,7 +8004,8 @@ impl TypeChecker {
                             span.start.line,
                             span.start.column,
                             &hint,
-                        ))
+                        )
+                        .into())
                     }
                 }
             }
@@ -10428,7 +8068,7 @@ impl TypeChecker {
                 match *operator {
                     "!" => {
                         if right_type != Type::Bool && right_type != Type::Any {
-                            return Err(format!("Cannot apply '!' to {}", right_type));
+                            return Err(format!("Cannot apply '!' to {}", right_type).into());
                         }
                         Ok(Type::Bool)
                     }
@@ -10439,11 +8079,11 @@ impl TypeChecker {
                             && right_type != Type::Any
                             && !is_pinned_int(&right_type)
                         {
-                            return Err(format!("Cannot apply '-' to {}", right_type));
+                            return Err(format!("Cannot apply '-' to {}", right_type).into());
                         }
                         Ok(right_type)
                     }
-                    _ => Err(format!("Unknown prefix operator: {}", operator)),
+                    _ => Err(format!("Unknown prefix operator: {}", operator).into()),
                 }
             }
 
@@ -10487,6 +8127,29 @@ impl TypeChecker {
                         if left_type == Type::String || right_type == Type::String {
                             if can_coerce_to_string(&left_type) && can_coerce_to_string(&right_type)
                             {
+                                let other = if left_type == Type::String {
+                                    &right_type
+                                } else {
+                                    &left_type
+                                };
+                                if !matches!(other, Type::String | Type::Any) {
+                                    match self.coercion {
+                                        crate::coercion::CoercionPolicy::Strict => {
+                                            return Err(format!(
+                                                "implicit conversion of {} to string is not allowed under the strict coercion policy — use to_string() for explicit conversion",
+                                                other
+                                            ).into());
+                                        }
+                                        crate::coercion::CoercionPolicy::Permissive => {
+                                            let message = format!(
+                                                "implicit conversion: {} converted to string for `+`",
+                                                other
+                                            );
+                                            self.warn_implicit_coercion(message);
+                                        }
+                                        crate::coercion::CoercionPolicy::Standard => {}
+                                    }
+                                }
                                 return Ok(Type::String);
                             }
                             let bad = if left_type == Type::String {
@@ -10497,7 +8160,7 @@ impl TypeChecker {
                             return Err(format!(
                                 "cannot concatenate string with {} — use to_string() for explicit conversion",
                                 bad
-                            ));
+                            ).into());
                         }
                         // Array concat. RES-4087: `compatible(_, Type::Array)`
                         // is trivially true when either side is `Any` (the
@@ -10522,7 +8185,11 @@ impl TypeChecker {
                         // mixed is a type error. Users route through
                         // the explicit `to_float(x)` / `to_int(x)`
                         // builtins when they really need the conversion.
+                        if let Some(ty) = self.widen_int_float(operator, &left_type, &right_type) {
+                            return Ok(ty);
+                        }
                         check_numeric_same_type(operator, &left_type, &right_type)
+                            .map_err(CheckError::from)
                     }
                     "-" | "*" | "/" | "%" => {
                         // RES-2816: string repetition — `"x" * 3` or `3 * "x"`.
@@ -10546,9 +8213,14 @@ impl TypeChecker {
                                 "division"
                             } else {
                                 "modulo"
-                            }));
+                            })
+                            .into());
+                        }
+                        if let Some(ty) = self.widen_int_float(operator, &left_type, &right_type) {
+                            return Ok(ty);
                         }
                         check_numeric_same_type(operator, &left_type, &right_type)
+                            .map_err(CheckError::from)
                     }
                     "&" | "|" | "^" | "<<" | ">>" => {
                         // Bitwise operators are int-only. Same-width
@@ -10563,11 +8235,13 @@ impl TypeChecker {
                             // Delegate to check_numeric_same_type for
                             // width-matching on pinned types.
                             check_numeric_same_type(operator, &left_type, &right_type)
+                                .map_err(CheckError::from)
                         } else {
                             Err(format!(
                                 "Bitwise '{}' requires int operands, got {} and {}",
                                 operator, left_type, right_type
-                            ))
+                            )
+                            .into())
                         }
                     }
                     "&&" | "||" => {
@@ -10577,15 +8251,31 @@ impl TypeChecker {
                             Err(format!(
                                 "Logical '{}' requires bool operands, got {} and {}",
                                 operator, left_type, right_type
-                            ))
+                            )
+                            .into())
                         }
                     }
                     "==" | "!=" | "<" | ">" | "<=" | ">=" => {
-                        if compatible(&left_type, &right_type) {
-                            Ok(Type::Bool)
-                        } else {
-                            Err(format!("Cannot compare {} and {}", left_type, right_type))
+                        if !compatible(&left_type, &right_type)
+                            && self
+                                .widen_int_float(operator, &left_type, &right_type)
+                                .is_none()
+                        {
+                            return Err(
+                                format!("Cannot compare {} and {}", left_type, right_type).into()
+                            );
+                        }
+                        if !matches!(*operator, "==" | "!=")
+                            && let Some(bad) =
+                                unorderable_part(&left_type).or(unorderable_part(&right_type))
+                        {
+                            return Err(format!(
+                                "Cannot order {} values with '{}' — only ==/!= apply",
+                                bad, operator
+                            )
+                            .into());
                         }
+                        Ok(Type::Bool)
                     }
                     // RES-2717: `??` null-coalescing operator. Left must be
                     // Option<T>; result is T (or the right-hand type when T is
@@ -10606,14 +8296,16 @@ impl TypeChecker {
                             return Err(format!(
                                 "`??` default has type {} but Option inner type is {}",
                                 right_type, inner_ty
-                            ));
+                            )
+                            .into());
                         }
                         Err(format!(
                             "`??` operator requires an Option on the left, got {}",
                             left_type
-                        ))
+                        )
+                        .into())
                     }
-                    _ => Err(format!("Unknown infix operator: {}", operator)),
+                    _ => Err(format!("Unknown infix operator: {}", operator).into()),
                 }
             }
 
@@ -10624,6 +8316,70 @@ impl TypeChecker {
             } => {
                 // RES-1862: track innermost span for better diagnostics.
                 self.current_span = *call_span;
+                // A call to an overloaded fn checks as a call to the
+                // member the argument types select.
+                if let Node::Identifier { name, span } = function.as_ref()
+                    && !self.env.has_user_binding(name)
+                    && let Some(members) = self.overload_sets.get(name.as_str()).cloned()
+                {
+                    let arg_types = arguments
+                        .iter()
+                        .map(|arg| self.check_node(arg))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    self.current_span = *call_span;
+                    let scores: Vec<Option<u32>> = members
+                        .iter()
+                        .map(|(_, params)| {
+                            if params.len() != arg_types.len() {
+                                return None;
+                            }
+                            params
+                                .iter()
+                                .zip(&arg_types)
+                                .map(|(p, a)| self.overload_arg_score(p, a))
+                                .sum()
+                        })
+                        .collect();
+                    let described = || {
+                        arg_types
+                            .iter()
+                            .map(|t| t.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    return match crate::overloads::pick(&scores) {
+                        crate::overloads::Pick::One(i) => {
+                            let resolved = Node::CallExpression {
+                                function: Box::new(Node::Identifier {
+                                    name: members[i].0.as_str().into(),
+                                    span: *span,
+                                }),
+                                arguments: arguments.clone(),
+                                span: *call_span,
+                            };
+                            self.check_node(&resolved)
+                        }
+                        crate::overloads::Pick::NoMatch => Err(crate::overloads::no_match_error(
+                            name,
+                            &described(),
+                            members.iter().map(|(m, _)| m.as_str()),
+                        )
+                        .into()),
+                        // An `Any` argument leaves the choice to the
+                        // runtime dispatch.
+                        crate::overloads::Pick::Ambiguous(_) if arg_types.contains(&Type::Any) => {
+                            Ok(Type::Any)
+                        }
+                        crate::overloads::Pick::Ambiguous(tied) => {
+                            Err(crate::overloads::ambiguous_error(
+                                name,
+                                &described(),
+                                tied.into_iter().map(|i| members[i].0.as_str()),
+                            )
+                            .into())
+                        }
+                    };
+                }
                 // RES-400: tuple-payload enum-variant constructor —
                 // `Either::Just(7)` parses as a CallExpression with
                 // the callee `Identifier("Either::Just")`. Resolve it
@@ -10646,7 +8402,8 @@ impl TypeChecker {
                                 variant_name,
                                 declared.len(),
                                 arguments.len()
-                            ));
+                            )
+                            .into());
                         }
                         // RES-416: validate argument types against the
                         // declared payload types.
@@ -10670,7 +8427,8 @@ impl TypeChecker {
                                 return Err(format!(
                                     "Constructor {}::{}: argument has type {}, expected {}",
                                     type_name, variant_name, arg_ty, expected_ty
-                                ));
+                                )
+                                .into());
                             }
                         }
                         return Ok(Type::Struct(type_name.to_string()));
@@ -10687,7 +8445,57 @@ impl TypeChecker {
                     return Err(format!(
                         "builtin `{}` is unavailable without `feature = \"std\"`",
                         callee_name
-                    ));
+                    )
+                    .into());
+                }
+
+                // `format(fmt, args...)` is variadic: the template must be
+                // a string, and each argument only has to check on its own.
+                if let Node::Identifier {
+                    name: callee_name, ..
+                } = function.as_ref()
+                    && callee_name == "format"
+                    && !self.env.has_user_binding(callee_name)
+                    && let Some((template, rest)) = arguments.split_first()
+                {
+                    let template_type = self.check_node(template)?;
+                    if !matches!(template_type, Type::String | Type::Any) {
+                        return Err(format!(
+                            "format: the template must be a string, got {}",
+                            template_type
+                        )
+                        .into());
+                    }
+                    for arg in rest {
+                        self.check_node(arg)?;
+                    }
+                    return Ok(Type::String);
+                }
+
+                // Array builtins check element types generically.
+                if let Node::Identifier {
+                    name: callee_name, ..
+                } = function.as_ref()
+                    && matches!(
+                        callee_name.as_str(),
+                        "push"
+                            | "pop"
+                            | "sort"
+                            | "array_sort"
+                            | "sort_by"
+                            | "array_sort_by"
+                            | "slice"
+                            | "contains"
+                            | "index_of"
+                            | "map"
+                            | "filter"
+                            | "reduce"
+                    )
+                    && !self.env.has_user_binding(callee_name)
+                    && let Some(ty) =
+                        self.check_array_builtin_call(callee_name.as_str(), arguments)?
+                {
+                    return Ok(ty);
                 }
 
                 // RES-061 + RES-063: if the callee is a known top-level
@@ -10698,7 +8506,7 @@ impl TypeChecker {
                 if let Node::Identifier {
                     name: callee_name, ..
                 } = function.as_ref()
-                    && let Some(info) = self.contract_table.get(callee_name).cloned()
+                    && let Some(info) = self.contract_table.get(callee_name.as_str()).cloned()
                 {
                     // RES-387: every failure variant the callee declares
                     // must be propagated by the enclosing fn's `fails`
@@ -10713,7 +8521,7 @@ impl TypeChecker {
                             return Err(format!(
                                 "unhandled failure variant {} — declare `fails {}` on the caller or wrap the call in `try {{ ... }} catch {} {{ ... }}` (from call to `{}`)",
                                 variant, variant, variant, callee_name
-                            ));
+                            ).into());
                         }
                     }
                     // RES-1399: skip the `bindings` HashMap allocation
@@ -10748,6 +8556,29 @@ impl TypeChecker {
                         for (clause_idx, clause) in info.requires.iter().enumerate() {
                             // Try the cheap hand-rolled folder first.
                             let mut verdict = fold_const_bool(clause, &bindings);
+                            // Not constant: see whether the enclosing
+                            // `if` conditions already establish it.
+                            // An argument that calls into user code runs
+                            // before the callee and may falsify them.
+                            if verdict.is_none()
+                                && !self.path_facts.is_empty()
+                                && !arguments.iter().any(|arg| self.contains_user_call(arg))
+                            {
+                                let args: HashMap<&str, &Node> = info
+                                    .parameters
+                                    .iter()
+                                    .zip(arguments.iter())
+                                    .map(|((_ty, pname), arg)| (pname.as_str(), arg))
+                                    .collect();
+                                if crate::flow_facts::implies(
+                                    &self.path_facts,
+                                    clause,
+                                    &args,
+                                    &self.const_bindings,
+                                ) {
+                                    verdict = Some(true);
+                                }
+                            }
                             // RES-136: slot for Z3's counterexample; only
                             // populated if Z3 runs (folder came back None).
                             let mut call_counterexample: Option<String> = None;
@@ -10810,7 +8641,7 @@ impl TypeChecker {
                                     && let Some(smt2) = cert
                                 {
                                     self.certificates.push(CapturedCertificate {
-                                        fn_name: callee_name.clone(),
+                                        fn_name: callee_name.to_string(),
                                         kind: "callsite_requires",
                                         idx: clause_idx,
                                         smt2,
@@ -10826,10 +8657,10 @@ impl TypeChecker {
                                         "Contract violation: call to fn {} would fail `requires` clause at compile time",
                                         callee_name
                                     );
-                                    return Err(match call_counterexample {
+                                    return Err(refutation(match call_counterexample {
                                         Some(cx) => format!("{} — counterexample: {}", base, cx),
                                         None => base,
-                                    });
+                                    }));
                                 }
                                 Some(true) => {
                                     self.stats.requires_discharged_at_compile += 1;
@@ -10844,7 +8675,9 @@ impl TypeChecker {
                                     {
                                         *c += 1;
                                     } else {
-                                        self.stats.per_fn_discharged.insert(callee_name.clone(), 1);
+                                        self.stats
+                                            .per_fn_discharged
+                                            .insert(callee_name.to_string(), 1);
                                     }
                                 }
                                 None => {
@@ -10854,7 +8687,9 @@ impl TypeChecker {
                                     {
                                         *c += 1;
                                     } else {
-                                        self.stats.per_fn_runtime.insert(callee_name.clone(), 1);
+                                        self.stats
+                                            .per_fn_runtime
+                                            .insert(callee_name.to_string(), 1);
                                     }
                                 }
                             }
@@ -10877,37 +8712,144 @@ impl TypeChecker {
                     return Ok(Type::Option(Box::new(arg_type)));
                 }
 
-                // RES-2556: HTTP builtins accept optional request
-                // headers and timeout arguments. The type system only
-                // tracks the required string parameters; the optional
-                // request options are validated at runtime because the
-                // language does not yet model `map<string, string>` as
-                // a first-class type.
+                // Under an `x != None` / `is_some(x)` guard, unwrapping
+                // `x: Option<T>` cannot fail and yields a `T` rather
+                // than the signature's `Any`.
                 if let Node::Identifier {
                     name: callee_name, ..
                 } = function.as_ref()
-                    && matches!(callee_name.as_str(), "http_get" | "http_post")
+                    && matches!(callee_name.as_str(), "unwrap_option" | "option_unwrap")
+                    && let [Node::Identifier { name: arg, .. }] = arguments.as_slice()
+                    && crate::flow_facts::known_some(&self.path_facts, arg)
+                    && let Some(Type::Option(inner)) = self.env.get(arg)
+                    && *inner != Type::Any
                 {
-                    let (required_count, max_count) = if callee_name == "http_get" {
-                        (1, 3)
-                    } else {
-                        (2, 4)
-                    };
-                    if arguments.len() < required_count || arguments.len() > max_count {
-                        return Err(format!(
-                            "{} expects between {} and {} argument(s), got {}",
-                            callee_name,
-                            required_count,
-                            max_count,
-                            arguments.len()
-                        ));
+                    return Ok(*inner);
+                }
+
+                // `json_stringify(value)` leaves `pretty` off.
+                if let Node::Identifier {
+                    name: callee_name, ..
+                } = function.as_ref()
+                    && callee_name == "json_stringify"
+                    && !self.env.has_user_binding(callee_name)
+                    && let [value] = arguments.as_slice()
+                {
+                    self.check_node(value)?;
+                    return Ok(Type::String);
+                }
+
+                // The telemetry builtins' optional last argument left off.
+                if let Node::Identifier {
+                    name: callee_name, ..
+                } = function.as_ref()
+                    && matches!(
+                        callee_name.as_str(),
+                        "mqtt_publish" | "mqtt_subscribe" | "serial_open" | "serial_read"
+                    )
+                    && !self.env.has_user_binding(callee_name)
+                    && let Some(Type::Function {
+                        params,
+                        return_type,
+                    }) = self.env.get(callee_name)
+                    && arguments.len() + 1 == params.len()
+                {
+                    for (i, (arg, param)) in arguments.iter().zip(&params).enumerate() {
+                        let arg_ty = self.check_node(arg)?;
+                        if !compatible(&arg_ty, param) {
+                            self.point_at(arg);
+                            return Err(format!(
+                                "Type mismatch in argument {}: expected {}, got {}",
+                                i + 1,
+                                param,
+                                arg_ty
+                            )
+                            .into());
+                        }
+                    }
+                    return Ok(*return_type);
+                }
+
+                // `log_info(msg)` and friends leave the fields off. A
+                // fields map written in place may mix value types —
+                // `{"user" -> name, "attempts" -> n}` is the usual shape.
+                if let Node::Identifier {
+                    name: callee_name, ..
+                } = function.as_ref()
+                    && matches!(
+                        callee_name.as_str(),
+                        "log_debug" | "log_info" | "log_warn" | "log_error"
+                    )
+                    && !self.env.has_user_binding(callee_name)
+                    && let [msg, fields @ ..] = arguments.as_slice()
+                    && fields.len() <= 1
+                {
+                    let msg_ty = self.check_node(msg)?;
+                    if !compatible(&msg_ty, &Type::String) {
+                        return Err(format!(
+                            "{} message must be a string, got {}",
+                            callee_name, msg_ty
+                        )
+                        .into());
+                    }
+                    match fields {
+                        [Node::MapLiteral { entries, .. }] => {
+                            for (k, v) in entries {
+                                self.check_node(k)?;
+                                self.check_node(v)?;
+                            }
+                        }
+                        [other] => {
+                            self.check_node(other)?;
+                        }
+                        _ => {}
+                    }
+                    return Ok(Type::Void);
+                }
+
+                if let Node::Identifier {
+                    name: callee_name, ..
+                } = function.as_ref()
+                    && callee_name == "clone"
+                    && !self.env.has_user_binding(callee_name)
+                    && let [value] = arguments.as_slice()
+                {
+                    return self.check_node(value);
+                }
+
+                // RES-2556: HTTP builtins accept optional request
+                // headers and timeout arguments. The type system only
+                // tracks the required string parameters; the optional
+                // request options are validated at runtime because the
+                // language does not yet model `map<string, string>` as
+                // a first-class type.
+                if let Node::Identifier {
+                    name: callee_name, ..
+                } = function.as_ref()
+                    && matches!(callee_name.as_str(), "http_get" | "http_post")
+                {
+                    let (required_count, max_count) = if callee_name == "http_get" {
+                        (1, 3)
+                    } else {
+                        (2, 4)
+                    };
+                    if arguments.len() < required_count || arguments.len() > max_count {
+                        return Err(format!(
+                            "{} expects between {} and {} argument(s), got {}",
+                            callee_name,
+                            required_count,
+                            max_count,
+                            arguments.len()
+                        )
+                        .into());
                     }
                     let url_ty = self.check_node(&arguments[0])?;
                     if !compatible(&url_ty, &Type::String) {
                         return Err(format!(
                             "{} URL must be a string, got {}",
                             callee_name, url_ty
-                        ));
+                        )
+                        .into());
                     }
                     if callee_name == "http_post" {
                         let body_ty = self.check_node(&arguments[1])?;
@@ -10915,7 +8857,8 @@ impl TypeChecker {
                             return Err(format!(
                                 "{} body must be a string, got {}",
                                 callee_name, body_ty
-                            ));
+                            )
+                            .into());
                         }
                         for arg in arguments.iter().skip(2) {
                             self.check_node(arg)?;
@@ -10925,7 +8868,7 @@ impl TypeChecker {
                             self.check_node(arg)?;
                         }
                     }
-                    return Ok(Type::Result);
+                    return Ok(Type::Any);
                 }
 
                 if let Node::Identifier {
@@ -10941,7 +8884,8 @@ impl TypeChecker {
                                 return Err(format!(
                                     "StringBuilder_new capacity must be an integer, got {}",
                                     arg_ty
-                                ));
+                                )
+                                .into());
                             }
                             return Ok(Type::Any);
                         }
@@ -10949,7 +8893,7 @@ impl TypeChecker {
                             return Err(format!(
                                 "StringBuilder_new expects 0 arguments or 1 integer capacity, got {}",
                                 arguments.len()
-                            ));
+                            ).into());
                         }
                     }
                 }
@@ -10990,12 +8934,22 @@ impl TypeChecker {
                         // the interpreter prepends. Only applies for struct
                         // targets (not Array / String builtins whose method
                         // types were registered WITHOUT a self param slot).
+                        // A field whose declared type is `fn(...)` is called
+                        // directly (`op.f(x)`), with no receiver prepended.
                         let is_struct_method_call = if let Node::FieldAccess {
                             target: fa_target,
+                            field: fa_field,
                             ..
                         } = function.as_ref()
                         {
-                            matches!(self.check_node(fa_target), Ok(Type::Struct(_)))
+                            match self.check_node(fa_target) {
+                                Ok(Type::Struct(sn)) => {
+                                    !self.struct_fields.get(&sn).is_some_and(|fields| {
+                                        fields.iter().any(|(f, _)| f == fa_field)
+                                    })
+                                }
+                                _ => false,
+                            }
                         } else {
                             false
                         };
@@ -11011,7 +8965,8 @@ impl TypeChecker {
                             return Err(render_arity_mismatch_error(
                                 explicit_params,
                                 arguments.len(),
-                            ));
+                            )
+                            .into());
                         }
 
                         // RES-425: if the callee is a named generic function,
@@ -11080,7 +9035,7 @@ impl TypeChecker {
                                         ..
                                     } = function.as_ref()
                                     {
-                                        self.fn_decl_spans.get(callee_name).copied()
+                                        self.fn_decl_spans.get(callee_name.as_str()).copied()
                                     } else {
                                         None
                                     };
@@ -11091,14 +9046,18 @@ impl TypeChecker {
                                         i + 1,
                                         &effective_param.to_string(),
                                         &arg_type.to_string(),
-                                    ));
+                                    )
+                                    .into());
                                 }
+                                self.point_at(arg);
                                 return Err(format!(
-                                    "Type mismatch in argument {}: expected {}, got {}",
+                                    "Type mismatch in argument {}: expected {}, got {}{}",
                                     i + 1,
                                     effective_param,
-                                    arg_type
-                                ));
+                                    arg_type,
+                                    self.unknown_type_hint(effective_param)
+                                )
+                                .into());
                             }
 
                             // RES-3880: a literal that satisfies the abstract
@@ -11127,7 +9086,7 @@ impl TypeChecker {
                         Ok(effective_return)
                     }
                     Type::Any => Ok(Type::Any),
-                    _ => Err(format!("Cannot call non-function type: {}", func_type)),
+                    _ => Err(format!("Cannot call non-function type: {}", func_type).into()),
                 }
             }
             // RES-325: a `NamedArg` can only appear inside a call's
@@ -11167,7 +9126,8 @@ impl TypeChecker {
                         return Err(format!(
                             "{}:{}:{}: error: duplicate variant `{}` in enum `{}`",
                             self.source_path, span.start.line, span.start.column, v.name, name,
-                        ));
+                        )
+                        .into());
                     }
                 }
                 // RES-1368: store variants behind a refcounted handle
@@ -11209,6 +9169,277 @@ impl TypeChecker {
             // RES-2613: bench block — silently skipped during normal typecheck.
             // Will be processed by `rz bench` subcommand.
             Node::BenchBlock { .. } => Ok(Type::Void),
+            // `test fn` — run by `rz test`, which brings the testing
+            // assertions (`assert_eq`, ...) into scope.
+            Node::TestFn { .. } => Ok(Type::Void),
+        }
+    }
+
+    /// How well an argument of type `arg` fits an overload parameter
+    /// of type `param`: 2 exact, 1 accepted, `None` rejected.
+    fn overload_arg_score(&self, param: &Type, arg: &Type) -> Option<u32> {
+        if param == arg {
+            Some(2)
+        } else if *param == Type::Any || *arg == Type::Any || self.type_satisfies(arg, param) {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// Retain the block scope being left. The block's own `let`s
+    /// become visible at their `let`; everything else in the
+    /// chain (hoisted fns, parameters, loop variables, enclosing
+    /// locals) is visible from the block's start.
+    fn record_scope(&mut self, span: Span, stmts: &[Node]) {
+        let lets = let_visibility(stmts.iter());
+        let bindings = self
+            .env
+            .user_bindings()
+            .into_iter()
+            .map(|(depth, name, ty)| {
+                let from = match lets.get(name.as_str()) {
+                    Some(p) if depth == 0 => *p,
+                    _ => span.start,
+                };
+                (name.to_string(), ty.clone(), from)
+            })
+            .collect();
+        self.scopes.push(ScopeRecord {
+            start: span.start,
+            end: span.end,
+            bindings,
+        });
+    }
+
+    /// Best-effort signature for a forward-referenced function.
+    /// Parameter or return annotations that don't resolve yet (e.g.
+    /// an alias hoisted later) fall back to `Type::Any` so the body
+    /// still reaches its own definition-site diagnostic.
+    /// One statement of the top-level walk in
+    /// `check_program_with_source`, with an error prefixed by the
+    /// best `file:line:col` known for it.
+    fn check_top_level(
+        &mut self,
+        stmt: &crate::span::Spanned<Node>,
+        source_path: &str,
+    ) -> Result<Type, CheckError> {
+        // RES-1862: reset current_span to the statement's
+        // own span before descending. check_node arms for
+        // InfixExpression / CallExpression / LetStatement
+        // overwrite this with the innermost node's span so
+        // that error messages point at the exact expression
+        // rather than the whole containing statement.
+        self.current_span = stmt.span;
+        let check_result = self.check_node(&stmt.node);
+        // Capture the best span after check_node returns
+        // (self is no longer mutably borrowed at this point).
+        // Use current_span when it's more specific (non-zero
+        // line within the statement range); fall back to
+        // stmt.span otherwise.
+        let diag_span = if self.current_span.start.line > 0 {
+            self.current_span
+        } else {
+            stmt.span
+        };
+        check_result.map_err(|e| {
+            // RES-080 / RES-1862: prepend file:line:col.
+            // Skip the prefix when the span looks
+            // default/empty (line 0 means "synthetic").
+            if diag_span.start.line == 0 {
+                e
+            } else {
+                e.map_message(|m| {
+                    format!(
+                        "{}:{}:{}: {}",
+                        source_path, diag_span.start.line, diag_span.start.column, m
+                    )
+                })
+            }
+        })
+    }
+
+    /// The top-level walk with independent fn bodies set aside (see
+    /// `crate::parallel_check`). Each deferred body is checked
+    /// against a snapshot of the checker taken where the fn appears;
+    /// a run of consecutive deferred fns shares one snapshot. Every
+    /// statement's diagnostics and accumulator additions are held
+    /// back and replayed in source order, up to the first statement
+    /// that fails — the same output a sequential walk produces.
+    fn check_statements_deferred(
+        &mut self,
+        statements: &[crate::span::Spanned<Node>],
+        source_path: &str,
+    ) -> Result<Type, CheckError> {
+        let prelude = self.take_effects();
+        let mut outcomes: Vec<Option<StatementOutcome>> = statements.iter().map(|_| None).collect();
+        let mut snapshots: Vec<std::sync::Mutex<TypeChecker>> = Vec::new();
+        let mut jobs: Vec<(usize, usize)> = Vec::new();
+        let mut snapshot_current = false;
+        for (idx, stmt) in statements.iter().enumerate() {
+            if let Some((name, fn_type)) = self.deferred_fn_type(&stmt.node) {
+                if !snapshot_current {
+                    snapshots.push(std::sync::Mutex::new(self.clone()));
+                    snapshot_current = true;
+                }
+                jobs.push((snapshots.len() - 1, idx));
+                // What checking the body leaves behind.
+                self.env.set(name, fn_type);
+                continue;
+            }
+            snapshot_current = false;
+            let (result, diagnostics) =
+                collect_check_diagnostics(|| self.check_top_level(stmt, source_path));
+            let failed = result.is_err();
+            outcomes[idx] = Some(StatementOutcome {
+                result,
+                diagnostics,
+                effects: self.take_effects(),
+            });
+            if failed {
+                break;
+            }
+        }
+
+        let variance = crate::variance::registry();
+        let checked = crate::parallel_check::run(
+            jobs,
+            || {
+                crate::variance::install_registry(variance.clone());
+                reset_z3_prove_cache();
+                None::<(usize, TypeChecker)>
+            },
+            |worker, (snapshot, idx)| {
+                if worker.as_ref().is_none_or(|(from, _)| *from != snapshot) {
+                    let checker = snapshots[snapshot]
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .clone();
+                    *worker = Some((snapshot, checker));
+                }
+                let (_, checker) = worker.as_mut().expect("worker checker was just set");
+                let (result, diagnostics) = collect_check_diagnostics(|| {
+                    checker.check_top_level(&statements[idx], source_path)
+                });
+                let mut effects = checker.take_effects();
+                effects.warned = checker.warned.clone();
+                // A check that bails out early can leave the body's
+                // environment in place; start the next one afresh.
+                if result.is_err() {
+                    *worker = None;
+                }
+                (
+                    idx,
+                    StatementOutcome {
+                        result,
+                        diagnostics,
+                        effects,
+                    },
+                )
+            },
+        );
+        for (idx, outcome) in checked {
+            outcomes[idx] = Some(outcome);
+        }
+
+        self.absorb_effects(prelude);
+        let mut result_type = Type::Void;
+        for outcome in outcomes.into_iter().flatten() {
+            replay_check_diagnostics(outcome.diagnostics);
+            self.absorb_effects(outcome.effects);
+            result_type = outcome.result?;
+        }
+        Ok(result_type)
+    }
+
+    /// The binding checking a top-level fn leaves in `env`, when the
+    /// signature alone determines it and the body can be checked on
+    /// its own; `None` sends the fn down the sequential path.
+    fn deferred_fn_type(&self, node: &Node) -> Option<(String, Type)> {
+        let Node::Function {
+            name,
+            parameters,
+            return_type: Some(rt),
+            ..
+        } = node
+        else {
+            return None;
+        };
+        if !deferrable_shape(node) {
+            return None;
+        }
+        // Writing a name the enclosing code holds as a constant takes
+        // it out of `const_bindings` for the fns checked after this
+        // one, which their snapshots would miss.
+        if let Node::Function { body, .. } = node
+            && crate::parallel_check::writes_any(body, |name| {
+                self.const_bindings.contains_key(name)
+            })
+        {
+            return None;
+        }
+        // The body's own check resolves these names again, with the
+        // right span current; don't record them from here.
+        let recorded = self.unresolved_type_names.borrow().len();
+        let params = parameters
+            .iter()
+            .map(|(ty_name, _)| self.parse_type_name(ty_name).ok())
+            .collect::<Option<Vec<_>>>();
+        let return_type = self.parse_type_name(rt).ok();
+        self.unresolved_type_names.borrow_mut().truncate(recorded);
+        Some((
+            name.clone(),
+            Type::Function {
+                params: params?,
+                return_type: Box::new(return_type?),
+            },
+        ))
+    }
+
+    /// Move out what checking a statement added to the accumulators.
+    fn take_effects(&mut self) -> CheckEffects {
+        CheckEffects {
+            stats: std::mem::take(&mut self.stats),
+            certificates: std::mem::take(&mut self.certificates),
+            let_type_hints: std::mem::take(&mut self.let_type_hints),
+            fn_return_type_hints: std::mem::take(&mut self.fn_return_type_hints),
+            scopes: std::mem::take(&mut self.scopes),
+            unresolved_struct_literals: std::mem::take(&mut self.unresolved_struct_literals),
+            unresolved_type_names: self.unresolved_type_names.take(),
+            warned: HashSet::new(),
+            denied_warnings: std::mem::take(&mut self.denied_warnings),
+        }
+    }
+
+    /// Put back what [`Self::take_effects`] moved out.
+    fn absorb_effects(&mut self, effects: CheckEffects) {
+        self.stats.absorb(effects.stats);
+        self.certificates.extend(effects.certificates);
+        self.let_type_hints.extend(effects.let_type_hints);
+        self.fn_return_type_hints
+            .extend(effects.fn_return_type_hints);
+        self.scopes.extend(effects.scopes);
+        self.unresolved_struct_literals
+            .extend(effects.unresolved_struct_literals);
+        self.unresolved_type_names
+            .get_mut()
+            .extend(effects.unresolved_type_names);
+        self.warned.extend(effects.warned);
+        self.denied_warnings += effects.denied_warnings;
+    }
+
+    fn hoisted_fn_type(&self, parameters: &[(String, String)], return_type: Option<&str>) -> Type {
+        let params = parameters
+            .iter()
+            .map(|(ty_name, _)| self.parse_type_name(ty_name).unwrap_or(Type::Any))
+            .collect();
+        let ret_type = match return_type {
+            Some(ty_name) => self.parse_type_name(ty_name).unwrap_or(Type::Any),
+            None => Type::Any,
+        };
+        Type::Function {
+            params,
+            return_type: Box::new(ret_type),
         }
     }
 
@@ -11253,6 +9484,30 @@ impl TypeChecker {
             };
             return self.parse_type_name_inner(rest, seen);
         }
+        // Union `T1 | T2 | ...`. Split on top-level `|` only, so a
+        // bar nested inside `Option<...>` / `[...]` / `(...)` belongs
+        // to the member. `fn(...) -> R` is left to its own arm below:
+        // the parser folds a trailing `| T` into the return type.
+        if !name.starts_with("fn(") && name.contains('|') {
+            let mut members: Vec<Type> = Vec::with_capacity(2);
+            let mut depth = 0usize;
+            let mut start = 0usize;
+            for (i, ch) in name.char_indices() {
+                match ch {
+                    '<' | '(' | '[' | '{' => depth += 1,
+                    '>' | ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                    '|' if depth == 0 => {
+                        members.push(self.parse_type_name_inner(name[start..i].trim(), seen)?);
+                        start = i + 1;
+                    }
+                    _ => {}
+                }
+            }
+            if !members.is_empty() {
+                members.push(self.parse_type_name_inner(name[start..].trim(), seen)?);
+                return Ok(crate::type_relations::union_of(members));
+            }
+        }
         match name {
             // RES-366: `Int64` is the long-form alias for `Int`.
             // RES-2719: `long` (Java/C) maps to the 64-bit signed int.
@@ -11510,6 +9765,11 @@ impl TypeChecker {
                 } else {
                     // RES-053: any other identifier is assumed to be a
                     // user-defined struct.
+                    if is_bare_type_name(other) {
+                        self.unresolved_type_names
+                            .borrow_mut()
+                            .push((other.to_string(), self.current_span));
+                    }
                     Ok(Type::Struct(other.to_string()))
                 }
             }
@@ -11517,6 +9777,176 @@ impl TypeChecker {
     }
 }
 
+/// Spellings of the builtin types offered as did-you-mean candidates
+/// for an unknown type name. Width aliases (`i32`, `u8`, ...) are left
+/// out: they are too short to be suggested for anything useful.
+const BUILTIN_TYPE_NAMES: &[&str] = &[
+    "int", "float", "string", "bool", "char", "void", "array", "any", "Int", "Float", "String",
+    "Bool", "Char", "Array", "Any", "Option", "Result",
+];
+
+/// A type annotation that is a single identifier — no generics,
+/// paths, references, or tuple / array / fn syntax.
+fn is_bare_type_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// The first cycle of structs that contain each other *directly* —
+/// as a field type, or inside a tuple or anonymous-struct field —
+/// rendered `A -> B -> A`. `Option`, arrays, and every other wrapper
+/// can be empty, so an edge through one of them ends the chain.
+fn infinite_struct_cycle(
+    decl_order: &[(&str, Span)],
+    struct_fields: &HashMap<String, std::sync::Arc<Vec<(String, Type)>>>,
+) -> Option<Vec<String>> {
+    fn direct_structs<'t>(ty: &'t Type, out: &mut Vec<&'t str>) {
+        match ty {
+            Type::Struct(name) => out.push(name),
+            Type::Tuple(items) => items.iter().for_each(|t| direct_structs(t, out)),
+            Type::AnonymousStruct(fields) => {
+                fields.iter().for_each(|(_, t)| direct_structs(t, out))
+            }
+            _ => {}
+        }
+    }
+    fn dfs<'a>(
+        name: &'a str,
+        struct_fields: &'a HashMap<String, std::sync::Arc<Vec<(String, Type)>>>,
+        path: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(at) = path.iter().position(|n| *n == name) {
+            let mut chain: Vec<String> = path[at..].iter().map(|n| n.to_string()).collect();
+            chain.push(name.to_string());
+            return Some(chain);
+        }
+        let (key, fields) = struct_fields.get_key_value(name)?;
+        if done.contains(name) {
+            return None;
+        }
+        path.push(key);
+        let mut next = Vec::new();
+        for (_, ty) in fields.iter() {
+            direct_structs(ty, &mut next);
+        }
+        for n in next {
+            if let Some(chain) = dfs(n, struct_fields, path, done) {
+                return Some(chain);
+            }
+        }
+        path.pop();
+        done.insert(key);
+        None
+    }
+    let mut done = HashSet::new();
+    decl_order
+        .iter()
+        .find_map(|(name, _)| dfs(name, struct_fields, &mut Vec::new(), &mut done))
+}
+
+/// Every type name the program declares anywhere: structs, enums,
+/// aliases, traits and their associated types, newtypes, actors,
+/// regions, impl targets, and generic parameters.
+/// What checking one top-level statement added to the checker's
+/// accumulators, set aside so statements checked out of order merge
+/// back in source order.
+#[derive(Default)]
+struct CheckEffects {
+    stats: VerificationStats,
+    certificates: Vec<CapturedCertificate>,
+    let_type_hints: Vec<LetTypeHint>,
+    fn_return_type_hints: Vec<FnReturnTypeHint>,
+    scopes: Vec<ScopeRecord>,
+    unresolved_struct_literals: Vec<(String, Span)>,
+    unresolved_type_names: Vec<(String, Span)>,
+    /// Only filled in by workers, whose dedup set started from a
+    /// snapshot.
+    warned: HashSet<(crate::warnings::WarningCategory, usize, usize)>,
+    denied_warnings: usize,
+}
+
+/// A statement checked by `check_statements_deferred`, held until
+/// every statement before it has been replayed.
+struct StatementOutcome {
+    result: Result<Type, CheckError>,
+    diagnostics: Vec<CheckDiagnostic>,
+    effects: CheckEffects,
+}
+
+/// A top-level fn `crate::parallel_check` may check on its own: a
+/// declared return type other than `!`, no type parameters, and a
+/// body that declares nothing.
+fn deferrable_shape(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Function {
+            return_type: Some(rt),
+            type_params,
+            body,
+            ..
+        } if rt != "!" && type_params.is_empty() && crate::parallel_check::declares_nothing(body)
+    )
+}
+
+fn declared_type_names(statements: &[crate::span::Spanned<Node>]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut pending: Vec<&Node> = statements.iter().map(|s| &s.node).collect();
+    while let Some(node) = pending.pop() {
+        crate::uniqueness_walk::visit(node, &mut |n| match n {
+            Node::StructDecl {
+                name, type_params, ..
+            }
+            | Node::EnumDecl {
+                name, type_params, ..
+            } => {
+                names.insert(name.clone());
+                names.extend(type_params.iter().cloned());
+            }
+            Node::Function { type_params, .. } => {
+                names.extend(type_params.iter().cloned());
+            }
+            Node::TypeAlias { name, .. }
+            | Node::NewtypeDecl { name, .. }
+            | Node::RegionDecl { name, .. }
+            | Node::Actor { name, .. }
+            | Node::ActorDecl { name, .. }
+            | Node::ClusterDecl { name, .. } => {
+                names.insert(name.clone());
+            }
+            Node::TraitDecl {
+                name,
+                associated_types,
+                ..
+            } => {
+                names.insert(name.clone());
+                names.extend(associated_types.iter().map(|a| a.name.clone()));
+            }
+            // The walker doesn't descend into impl or module bodies.
+            Node::ImplBlock {
+                struct_name,
+                methods,
+                associated_type_impls,
+                ..
+            } => {
+                names.insert(struct_name.clone());
+                names.extend(associated_type_impls.iter().map(|(a, _)| a.clone()));
+                pending.extend(methods.iter());
+            }
+            Node::BlanketImpl {
+                type_param,
+                methods,
+                ..
+            } => {
+                names.insert(type_param.clone());
+                pending.extend(methods.iter());
+            }
+            Node::ModuleDecl { body, .. } => pending.extend(body.iter()),
+            _ => {}
+        });
+    }
+    names
+}
+
 // ============================================================
 // RES-191: `@pure` purity checker.
 // ============================================================
@@ -11557,6 +9987,14 @@ pub(crate) const IMPURE_BUILTINS: &[&str] = &[
     "println",
     "print",
     "input",
+    "read_line",
+    "read_int",
+    // Structured logging writes to stderr and reads the run's level.
+    "log_debug",
+    "log_info",
+    "log_warn",
+    "log_error",
+    "set_log_level",
     // RES-2610: compile-time file embedding.
     "include_str",
     "include_bytes",
@@ -11577,6 +10015,9 @@ pub(crate) const IMPURE_BUILTINS: &[&str] = &[
     // RES-143: disk I/O.
     "file_read",
     "file_write",
+    "read_file",
+    "write_file",
+    "append_file",
     // RES-409: streaming file I/O. All five reach the disk and so
     // count as impure for the effect inferrer.
     "file_open",
@@ -11587,6 +10028,8 @@ pub(crate) const IMPURE_BUILTINS: &[&str] = &[
     // RES-151: env-var reads depend on process state outside
     // the fn.
     "env",
+    "args",
+    "exit",
     // RES-138 / RES-141: retry-counter readers — observe runtime
     // state that isn't the fn's parameters.
     "live_retries",
@@ -11640,6 +10083,8 @@ pub(crate) const IMPURE_BUILTINS: &[&str] = &[
     "volatile_write_u16",
     "volatile_write_u32",
     "volatile_write_u64",
+    "mmio_read",
+    "mmio_write",
 ];
 
 /// RES-191: top-level entry for the purity pass. Walks the
@@ -12196,6 +10641,9 @@ pub(crate) fn is_known_pure_builtin(name: &str) -> bool {
         "array_map",
         "array_filter",
         "array_reduce",
+        "map",
+        "filter",
+        "reduce",
         // RES-507: generic callback-based search/predicate builtins.
         "array_find",
         "array_find_index",
@@ -12214,6 +10662,7 @@ pub(crate) fn is_known_pure_builtin(name: &str) -> bool {
         "map_to_pairs",
         // RES-2648: array combinators.
         "array_sort_by",
+        "sort_by",
         "array_min_by",
         "array_max_by",
         "array_count_if",
@@ -12263,6 +10712,10 @@ pub(crate) fn is_known_pure_builtin(name: &str) -> bool {
         "option_ok_or",
         // RES-2652: type introspection + collection ergonomics.
         "type_of",
+        "is_int",
+        "is_float",
+        "is_string",
+        "is_bool",
         "result_collect",
         "array_from_fn",
         "map_invert",
@@ -12711,9 +11164,11 @@ pub(crate) fn is_known_pure_builtin(name: &str) -> bool {
         // RES-2554: JSON builtins (pure — no IO).
         "to_json",
         "from_json",
+        "json_parse",
         "json_encode",
         "json_decode",
         "json_encode_pretty",
+        "json_stringify",
         "json_valid",
         // RES-2810: math / data / reflection builtins — all deterministic
         // and side-effect-free, so callable from `@pure` functions. Mirrors
@@ -12795,2295 +11250,2935 @@ pub(crate) fn is_known_pure_builtin(name: &str) -> bool {
     PURE_BUILTINS_SET.contains(name)
 }
 
-// ============================================================
-// RES-192: IO-effect inference (fixpoint over the call graph).
-// ============================================================
-//
-// Lattice: `{}` (pure) or `{IO}`, represented as a single `bool`
-// because the MVP only tracks one effect. Union = logical OR.
-//
-// Rules:
-// - A builtin in `IMPURE_BUILTINS` has `IO`.
-// - A call to a builtin name that's neither impure nor known-pure
-//   is conservatively `IO` (unknown-callee = assume worst).
-// - A user fn has `IO` iff any call site in its body calls
-//   something with `IO`.
-// - A `LiveBlock` is NOT inherently IO (the ticket specifically
-//   calls out "reach println or file_*"; a retry loop over pure
-//   work is still pure).
-//
-// Fixpoint: initialize every user fn to pure; iterate body-walks
-// until no effect flips. Terminates in O(|fns|²) iterations since
-// each pass can only flip pure→IO once per fn.
-//
-// RES-775 Phase 2: Higher-Order Effect Polymorphism
-// ==================================================
-// Current MVP limitation: Higher-order function combinators (map, filter,
-// with_retry, etc.) default to `@io` even when passed a `@pure` callback,
-// because the inference system does not thread effect variables through
-// generic signatures.
-//
-// Phase 2 will extend this to:
-// 1. Accept effect-variable constraints in generics (`E: effect`)
-// 2. Infer concrete effects at HOF call sites based on callback effects
-// 3. Carry effect constraints through monomorphization
-// 4. Compose effects soundly in nested HOF chains
-//
-// This allows reusable combinators to remain provably `@pure` when
-// their callbacks are pure, improving expressiveness for functional
-// programming patterns in safety-critical contexts.
+// ============================================================
+// RES-192: IO-effect inference (fixpoint over the call graph).
+// ============================================================
+//
+// Lattice: `{}` (pure) or `{IO}`, represented as a single `bool`
+// because the MVP only tracks one effect. Union = logical OR.
+//
+// Rules:
+// - A builtin in `IMPURE_BUILTINS` has `IO`.
+// - A call to a builtin name that's neither impure nor known-pure
+//   is conservatively `IO` (unknown-callee = assume worst).
+// - A user fn has `IO` iff any call site in its body calls
+//   something with `IO`.
+// - A `LiveBlock` is NOT inherently IO (the ticket specifically
+//   calls out "reach println or file_*"; a retry loop over pure
+//   work is still pure).
+//
+// Fixpoint: initialize every user fn to pure; iterate body-walks
+// until no effect flips. Terminates in O(|fns|²) iterations since
+// each pass can only flip pure→IO once per fn.
+//
+// RES-775 Phase 2: Higher-Order Effect Polymorphism
+// ==================================================
+// Current MVP limitation: Higher-order function combinators (map, filter,
+// with_retry, etc.) default to `@io` even when passed a `@pure` callback,
+// because the inference system does not thread effect variables through
+// generic signatures.
+//
+// Phase 2 will extend this to:
+// 1. Accept effect-variable constraints in generics (`E: effect`)
+// 2. Infer concrete effects at HOF call sites based on callback effects
+// 3. Carry effect constraints through monomorphization
+// 4. Compose effects soundly in nested HOF chains
+//
+// This allows reusable combinators to remain provably `@pure` when
+// their callbacks are pure, improving expressiveness for functional
+// programming patterns in safety-critical contexts.
+
+/// RES-192: build the call-graph edge set for `statements`, then
+/// run the fixpoint. Returns `name → has_io` for every top-level
+/// user fn. Non-function statements contribute nothing.
+pub fn infer_fn_effects(
+    statements: &[crate::span::Spanned<Node>],
+) -> std::collections::HashMap<String, bool> {
+    // RES-1503: build the intermediate fn-name maps keyed by `&str`,
+    // borrowing from each `Node::Function::name`. The previous shape
+    // allocated owned `String`s for every fn name three times: once
+    // for `fn_bodies`, once for the `effects` init clone, and once
+    // per fixpoint flip. The return type stays `HashMap<String, bool>`
+    // because `self.stats.fn_effects` is an owned field — the
+    // conversion happens once at the end (one `to_string()` per fn,
+    // matching what the return type already costs).
+    //
+    // Pre-size `fn_bodies` to `statements.len()` — upper bound, since
+    // every Function statement contributes one entry. Same shape as
+    // `check_program_purity::pure_fns` (RES-1796) and
+    // `collect_fn_effects::out` (RES-1734) — the two sibling passes
+    // that walk the same statement list to populate the same shape of
+    // map.
+    let mut fn_bodies: std::collections::HashMap<&str, &Node> =
+        std::collections::HashMap::with_capacity(statements.len());
+    for stmt in statements {
+        if let Node::Function { name, body, .. } = &stmt.node {
+            fn_bodies.insert(name.as_str(), body.as_ref());
+        }
+    }
+
+    // Step 2: initialize every fn as pure.
+    let mut effects: std::collections::HashMap<&str, bool> =
+        fn_bodies.keys().map(|n| (*n, false)).collect();
+
+    // Step 3: fixpoint — iterate body-walks until no effect flips.
+    // Upper bound: one flip per fn, so at most |fns| passes.
+    let max_passes = fn_bodies.len().saturating_add(1);
+    for _ in 0..max_passes {
+        let mut changed = false;
+        for (name, body) in &fn_bodies {
+            if *effects.get(*name).unwrap_or(&false) {
+                continue; // already IO — nothing to update
+            }
+            if body_reaches_io(body, &effects) {
+                effects.insert(*name, true);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    effects
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+}
+
+/// RES-192: body-level check for IO reachability under the
+/// current `effects` snapshot. Used inside the fixpoint loop —
+/// each iteration treats `effects` as a frozen best-estimate and
+/// asks "does this body reach anything marked IO today?".
+fn body_reaches_io(node: &Node, effects: &std::collections::HashMap<&str, bool>) -> bool {
+    match node {
+        Node::Block { stmts, .. } => stmts.iter().any(|s| body_reaches_io(s, effects)),
+        Node::LetStatement { value, .. } | Node::StaticLet { value, .. } => {
+            body_reaches_io(value, effects)
+        }
+        Node::ReturnStatement { value: Some(v), .. } => body_reaches_io(v, effects),
+        Node::ReturnStatement { value: None, .. } => false,
+        Node::IfStatement {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => {
+            body_reaches_io(condition, effects)
+                || body_reaches_io(consequence, effects)
+                || alternative
+                    .as_ref()
+                    .is_some_and(|a| body_reaches_io(a, effects))
+        }
+        Node::WhileStatement {
+            condition, body, ..
+        } => body_reaches_io(condition, effects) || body_reaches_io(body, effects),
+        Node::ForInStatement { iterable, body, .. } => {
+            body_reaches_io(iterable, effects) || body_reaches_io(body, effects)
+        }
+        Node::Assert { condition, .. } => body_reaches_io(condition, effects),
+        Node::Assume { condition, .. } => body_reaches_io(condition, effects),
+        Node::LiveBlock {
+            body, invariants, ..
+        } => {
+            // A `live` block is NOT intrinsically IO (retries on
+            // failure observe error state, but not IO per the
+            // ticket's definition). If the body reaches IO, the
+            // outer fn still does.
+            body_reaches_io(body, effects)
+                || invariants.iter().any(|inv| body_reaches_io(inv, effects))
+        }
+        Node::InfixExpression { left, right, .. } => {
+            body_reaches_io(left, effects) || body_reaches_io(right, effects)
+        }
+        Node::PrefixExpression { right, .. } => body_reaches_io(right, effects),
+        Node::CallExpression {
+            function,
+            arguments,
+            ..
+        } => {
+            // Any arg side-effecting → IO.
+            if arguments.iter().any(|a| body_reaches_io(a, effects)) {
+                return true;
+            }
+            // The callee itself.
+            if body_reaches_io(function, effects) {
+                return true;
+            }
+            if let Node::Identifier { name: callee, .. } = function.as_ref() {
+                if IMPURE_BUILTINS.contains(&callee.as_str()) {
+                    return true;
+                }
+                if is_known_pure_builtin(callee) {
+                    return false;
+                }
+                // A user-fn call: inherit that fn's current
+                // best-estimate effect. Unknown user fn (rare —
+                // typechecker would have rejected earlier) →
+                // conservatively IO.
+                match effects.get(callee.as_str()) {
+                    Some(&true) => return true,
+                    Some(&false) => return false,
+                    None => return true, // unknown = IO (conservative)
+                }
+            }
+            // Indirect / method callee — can't resolve statically;
+            // conservatively mark IO.
+            true
+        }
+        Node::FieldAccess { target, .. } => body_reaches_io(target, effects),
+        Node::FieldAssignment { target, value, .. } => {
+            body_reaches_io(target, effects) || body_reaches_io(value, effects)
+        }
+        Node::Assignment { value, .. } => body_reaches_io(value, effects),
+        Node::IndexExpression { target, index, .. } => {
+            body_reaches_io(target, effects) || body_reaches_io(index, effects)
+        }
+        Node::IndexAssignment {
+            target,
+            index,
+            value,
+            ..
+        } => {
+            body_reaches_io(target, effects)
+                || body_reaches_io(index, effects)
+                || body_reaches_io(value, effects)
+        }
+        Node::ArrayLiteral { items, .. } => items.iter().any(|i| body_reaches_io(i, effects)),
+        Node::StructLiteral { fields, base, .. } => {
+            base.as_ref().is_some_and(|b| body_reaches_io(b, effects))
+                || fields.iter().any(|(_, v)| body_reaches_io(v, effects))
+        }
+        Node::Match {
+            scrutinee, arms, ..
+        } => {
+            if body_reaches_io(scrutinee, effects) {
+                return true;
+            }
+            arms.iter().any(|(_pat, guard, arm_body)| {
+                guard.as_ref().is_some_and(|g| body_reaches_io(g, effects))
+                    || body_reaches_io(arm_body, effects)
+            })
+        }
+        Node::ExpressionStatement { expr, .. } => body_reaches_io(expr, effects),
+        Node::TryExpression { expr, .. } => body_reaches_io(expr, effects),
+        Node::OptionalChain { object, access, .. } => {
+            if body_reaches_io(object, effects) {
+                return true;
+            }
+            if let crate::ChainAccess::Method(_, args) = access {
+                args.iter().any(|a| body_reaches_io(a, effects))
+            } else {
+                false
+            }
+        }
+        // Nested fn decls are rare but handled — recurse into
+        // their body too. Today the parser doesn't emit these;
+        // future closures will.
+        Node::Function { body, .. } => body_reaches_io(body, effects),
+        // Pure literals / identifier reads / etc.
+        _ => false,
+    }
+}
+
+// ============================================================
+// RES-389: effect-annotation enforcement.
+// ============================================================
+//
+// Syntax (soft keywords dispatched at statement-start):
+//   pure fn f(int x) { ... }   // EffectSet::pure()
+//   io   fn g(int x) { ... }   // EffectSet::io()
+//   fn h(int x) { ... }        // EffectSet::io() (backward compat)
+//
+// Call rules:
+//   - A `pure` fn may call other `pure` fns or known-pure
+//     builtins (see `is_known_pure_builtin`).
+//   - An `io` fn (the permissive default) may call `pure` or
+//     `io` fns, plus any builtin.
+//   - Calling an `io` callee from a `pure` caller is a compile
+//     error:
+//        E: cannot call io function `X` from pure context
+//
+// The pass is deliberately coarse — the `@pure` checker (RES-191)
+// already rejects impure-builtin calls and unannotated-user-fn
+// calls from a `@pure` body. This pass layers on top so the new
+// `pure fn` / `io fn` keyword form gets its own diagnostic
+// surface separately from the `@pure` attribute.
+
+use crate::EffectSet;
+
+/// RES-389: collect each top-level fn's declared `EffectSet` by
+/// name. Unannotated fns default to `EffectSet::io()`, matching
+/// the parser. Duplicate names (rare — the typechecker elsewhere
+/// diagnoses redeclarations) keep the last one seen.
+fn collect_fn_effects(
+    statements: &[crate::span::Spanned<Node>],
+) -> std::collections::HashMap<String, EffectSet> {
+    // RES-1734: pre-size to `statements.len()` — upper bound, since
+    // every Function statement contributes one entry. Same shape as
+    // RES-1716 / RES-1718 / RES-1724 etc.
+    let mut out = std::collections::HashMap::with_capacity(statements.len());
+    for stmt in statements {
+        if let Node::Function { name, effects, .. } = &stmt.node {
+            out.insert(name.clone(), *effects);
+        }
+    }
+    out
+}
+
+/// RES-389: top-level entry for the effect-annotation pass.
+/// Walks each `pure fn` body and reports the first call site that
+/// reaches an `io` callee or an indeterminate callee (method /
+/// computed).
+fn check_program_effects(
+    statements: &[crate::span::Spanned<Node>],
+    source_path: &str,
+) -> Result<(), String> {
+    // RES-1296: short-circuit. `collect_fn_effects` builds a
+    // name→effects HashMap by iterating every top-level statement,
+    // and the per-stmt loop below only enters its body for `Function`
+    // nodes with `effects.pure == true`. Programs that don't declare
+    // any pure fn — the overwhelming majority — pay for both passes
+    // and produce nothing useful. Pre-scan once before doing either
+    // and bail when no pure fn exists.
+    let has_pure_fn = statements
+        .iter()
+        .any(|s| matches!(&s.node, Node::Function { effects, .. } if effects.pure));
+    if !has_pure_fn {
+        return Ok(());
+    }
+    let fn_effects = collect_fn_effects(statements);
+    for stmt in statements {
+        if let Node::Function {
+            name,
+            body,
+            effects,
+            parameters,
+            ..
+        } = &stmt.node
+            && effects.pure
+            && let Err(reason) = check_body_effects(body, &fn_effects, parameters, statements, body)
+        {
+            let (line, col) = (stmt.span.start.line, stmt.span.start.column);
+            return Err(if line == 0 {
+                format!("pure fn `{}`: {}", name, reason)
+            } else {
+                format!(
+                    "{}:{}:{}: pure fn `{}`: {}",
+                    source_path, line, col, name, reason
+                )
+            });
+        }
+    }
+    // RES-3933 A-E7: call-site-based effect propagation for calls
+    // through a function-typed parameter. `check_body_effects` above
+    // defers those calls (see the `is_function_type` check inside its
+    // `CallExpression` arm) instead of blanket-rejecting them; this
+    // pass makes the real, sound-but-monomorphic call using
+    // whole-program call-site information the per-body walk doesn't
+    // have. See `effect_polymorphism` module docs for what's enforced
+    // vs deferred.
+    crate::effect_polymorphism::check(statements, &fn_effects, source_path)?;
+    Ok(())
+}
+
+/// RES-389/RES-385c: recursive walk of a `pure` fn body. Returns
+/// `Err(<reason>)` at the first call to a non-`pure` callee, or if a
+/// linear parameter is consumed (RES-385c), with the diagnostic text
+/// the caller will prefix with the fn span.
+fn check_body_effects(
+    node: &Node,
+    fn_effects: &std::collections::HashMap<String, EffectSet>,
+    linear_params: &[(String, String)],
+    statements: &[crate::span::Spanned<Node>],
+    caller_body: &Node,
+) -> Result<(), String> {
+    match node {
+        Node::Block { stmts, .. } => {
+            for s in stmts {
+                check_body_effects(s, fn_effects, linear_params, statements, caller_body)?;
+            }
+        }
+        Node::LetStatement { value, .. } | Node::StaticLet { value, .. } => {
+            check_body_effects(value, fn_effects, linear_params, statements, caller_body)?;
+        }
+        Node::ReturnStatement { value: Some(v), .. } => {
+            check_body_effects(v, fn_effects, linear_params, statements, caller_body)?
+        }
+        Node::ReturnStatement { value: None, .. } => {}
+        Node::IfStatement {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => {
+            check_body_effects(
+                condition,
+                fn_effects,
+                linear_params,
+                statements,
+                caller_body,
+            )?;
+            check_body_effects(
+                consequence,
+                fn_effects,
+                linear_params,
+                statements,
+                caller_body,
+            )?;
+            if let Some(a) = alternative {
+                check_body_effects(a, fn_effects, linear_params, statements, caller_body)?;
+            }
+        }
+        Node::WhileStatement {
+            condition, body, ..
+        } => {
+            check_body_effects(
+                condition,
+                fn_effects,
+                linear_params,
+                statements,
+                caller_body,
+            )?;
+            check_body_effects(body, fn_effects, linear_params, statements, caller_body)?;
+        }
+        Node::ForInStatement { iterable, body, .. } => {
+            check_body_effects(iterable, fn_effects, linear_params, statements, caller_body)?;
+            check_body_effects(body, fn_effects, linear_params, statements, caller_body)?;
+        }
+        Node::Assert { condition, .. } | Node::Assume { condition, .. } => {
+            check_body_effects(
+                condition,
+                fn_effects,
+                linear_params,
+                statements,
+                caller_body,
+            )?;
+        }
+        Node::LiveBlock { body, .. } => {
+            check_body_effects(body, fn_effects, linear_params, statements, caller_body)?
+        }
+        Node::InfixExpression { left, right, .. } => {
+            check_body_effects(left, fn_effects, linear_params, statements, caller_body)?;
+            check_body_effects(right, fn_effects, linear_params, statements, caller_body)?;
+        }
+        Node::PrefixExpression { right, .. } => {
+            check_body_effects(right, fn_effects, linear_params, statements, caller_body)?
+        }
+        Node::CallExpression {
+            function,
+            arguments,
+            ..
+        } => {
+            for a in arguments {
+                check_body_effects(a, fn_effects, linear_params, statements, caller_body)?;
+                // RES-385c: detect if a linear parameter is being
+                // consumed (passed to a function). Consuming a linear
+                // parameter is observable IO — an operation on a
+                // resource — so a pure fn cannot do it.
+                if let Node::Identifier { name: arg_name, .. } = a
+                    && linear_params.iter().any(|(param_ty, param_name)| {
+                        arg_name == param_name && crate::linear::is_linear(param_ty)
+                    })
+                {
+                    return Err(format!(
+                        "cannot consume linear parameter `{}` in pure context",
+                        arg_name
+                    ));
+                }
+            }
+            if let Node::Identifier { name: callee, .. } = function.as_ref() {
+                // User fn with a recorded effect set — `pure`
+                // propagates cleanly; any other effect (today just
+                // `io`) is a violation.
+                if let Some(callee_effects) = fn_effects.get(callee.as_str()) {
+                    if callee_effects.pure {
+                        return Ok(());
+                    }
+                    // RES-3933 A-E7 follow-up (#4072): true
+                    // effect-variable polymorphism. `callee`'s own
+                    // declared effect isn't `pure`, but it may still
+                    // be provably pure *at this call site* if it
+                    // declares an effect-variable parameter (RES-193
+                    // `-e->`) that this call binds to a provably-pure
+                    // callback, and the rest of its body is
+                    // independently pure. See
+                    // `effect_polymorphism::resolves_pure_at_call_site`
+                    // for the full soundness argument — this can only
+                    // ever turn a previously-rejected call into an
+                    // accepted one, never the reverse.
+                    if crate::effect_polymorphism::resolves_pure_at_call_site(
+                        callee,
+                        arguments,
+                        statements,
+                        fn_effects,
+                        caller_body,
+                    ) {
+                        return Ok(());
+                    }
+                    return Err(render_io_from_pure_context_error(callee));
+                }
+                // Builtins: pure-by-default list passes; anything
+                // flagged impure by RES-191 is also implicitly io.
+                if IMPURE_BUILTINS.contains(&callee.as_str()) {
+                    return Err(render_io_from_pure_context_error(callee));
+                }
+                if is_known_pure_builtin(callee) {
+                    return Ok(());
+                }
+                // RES-3933 A-E7: a call through one of the enclosing
+                // fn's own function-typed parameters. We can't
+                // resolve its effect here — the same parameter name
+                // could be bound to a pure or an io value depending
+                // on the call site — so defer to
+                // `effect_polymorphism::check`, which has
+                // whole-program call-site visibility and only
+                // rejects a *proven* io-callback violation. Falling
+                // through to the blanket "unknown callee" rejection
+                // below would make every higher-order `pure` fn
+                // uncompilable, even when every actual callback
+                // passed to it is pure.
+                if linear_params
+                    .iter()
+                    .any(|(ty, n)| n == callee && crate::effect_polymorphism::is_function_type(ty))
+                {
+                    return Ok(());
+                }
+                // Unknown callee (not in BUILTINS and not a
+                // declared user fn) — conservatively reject so a
+                // `pure` annotation remains meaningful.
+                return Err(render_io_from_pure_context_error(callee));
+            }
+            // Method / computed callee — can't resolve statically;
+            // same conservative rejection as the purity pass.
+            check_body_effects(function, fn_effects, linear_params, statements, caller_body)?;
+            return Err(
+                "cannot call indirect/method callee from pure context (effect unknown)".to_string(),
+            );
+        }
+        Node::FieldAccess { target, .. } => {
+            check_body_effects(target, fn_effects, linear_params, statements, caller_body)?
+        }
+        Node::FieldAssignment { target, value, .. } => {
+            check_body_effects(target, fn_effects, linear_params, statements, caller_body)?;
+            check_body_effects(value, fn_effects, linear_params, statements, caller_body)?;
+        }
+        Node::Assignment { value, .. } => {
+            check_body_effects(value, fn_effects, linear_params, statements, caller_body)?
+        }
+        Node::IndexExpression { target, index, .. } => {
+            check_body_effects(target, fn_effects, linear_params, statements, caller_body)?;
+            check_body_effects(index, fn_effects, linear_params, statements, caller_body)?;
+        }
+        Node::IndexAssignment {
+            target,
+            index,
+            value,
+            ..
+        } => {
+            check_body_effects(target, fn_effects, linear_params, statements, caller_body)?;
+            check_body_effects(index, fn_effects, linear_params, statements, caller_body)?;
+            check_body_effects(value, fn_effects, linear_params, statements, caller_body)?;
+        }
+        Node::ArrayLiteral { items, .. } => {
+            for i in items {
+                check_body_effects(i, fn_effects, linear_params, statements, caller_body)?;
+            }
+        }
+        Node::StructLiteral { fields, base, .. } => {
+            if let Some(b) = base {
+                check_body_effects(b, fn_effects, linear_params, statements, caller_body)?;
+            }
+            for (_, v) in fields {
+                check_body_effects(v, fn_effects, linear_params, statements, caller_body)?;
+            }
+        }
+        Node::Match {
+            scrutinee, arms, ..
+        } => {
+            check_body_effects(
+                scrutinee,
+                fn_effects,
+                linear_params,
+                statements,
+                caller_body,
+            )?;
+            for (_pat, guard, arm_body) in arms {
+                if let Some(g) = guard {
+                    check_body_effects(g, fn_effects, linear_params, statements, caller_body)?;
+                }
+                check_body_effects(arm_body, fn_effects, linear_params, statements, caller_body)?;
+            }
+        }
+        Node::ExpressionStatement { expr, .. } => {
+            check_body_effects(expr, fn_effects, linear_params, statements, caller_body)?
+        }
+        Node::TryExpression { expr, .. } => {
+            check_body_effects(expr, fn_effects, linear_params, statements, caller_body)?
+        }
+        Node::OptionalChain { object, access, .. } => {
+            check_body_effects(object, fn_effects, linear_params, statements, caller_body)?;
+            if let crate::ChainAccess::Method(_, args) = access {
+                for a in args {
+                    check_body_effects(a, fn_effects, linear_params, statements, caller_body)?;
+                }
+            }
+        }
+        Node::Function { body, .. } => {
+            check_body_effects(body, fn_effects, linear_params, statements, caller_body)?
+        }
+        // Literals, identifier reads, durations — nothing to do.
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod effect_tests {
+    use super::*;
+    use crate::parse;
+
+    fn stmts(src: &str) -> Vec<crate::span::Spanned<Node>> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        match prog {
+            Node::Program(s) => s,
+            other => panic!("expected Program, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn pure_to_pure_passes() {
+        let src = "pure fn inner(int x) { return x + 1; }\n\
+                   pure fn outer(int x) { return inner(x); }\n";
+        let s = stmts(src);
+        check_program_effects(&s, "<t>").expect("pure→pure should pass");
+    }
+
+    #[test]
+    fn pure_to_io_is_rejected() {
+        let src = "io   fn noisy(int x) { return x; }\n\
+                   pure fn caller(int x) { return noisy(x); }\n";
+        let s = stmts(src);
+        let err = check_program_effects(&s, "<t>").expect_err("pure→io should fail");
+        assert!(
+            err.contains("cannot call io function `noisy` from pure context"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn pure_to_unannotated_is_rejected() {
+        // Unannotated fns default to `EffectSet::io()` per the
+        // RES-389 backward-compat rule.
+        let src = "fn      helper(int x) { return x + 1; }\n\
+                   pure fn caller(int x) { return helper(x); }\n";
+        let s = stmts(src);
+        let err = check_program_effects(&s, "<t>").expect_err("pure→unannotated should fail");
+        assert!(
+            err.contains("cannot call io function `helper` from pure context"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn io_to_pure_passes() {
+        let src = "pure fn add1(int x) { return x + 1; }\n\
+                   io   fn caller(int x) { return add1(x); }\n";
+        let s = stmts(src);
+        check_program_effects(&s, "<t>").expect("io→pure should pass");
+    }
+
+    #[test]
+    fn io_to_io_passes() {
+        let src = "io fn a(int x) { return x; }\n\
+                   io fn b(int x) { return a(x); }\n";
+        let s = stmts(src);
+        check_program_effects(&s, "<t>").expect("io→io should pass");
+    }
+
+    #[test]
+    fn pure_using_pure_builtin_passes() {
+        let src = "pure fn f(int x) { return abs(x); }\n";
+        let s = stmts(src);
+        check_program_effects(&s, "<t>").expect("abs is pure");
+    }
+
+    #[test]
+    fn pure_using_impure_builtin_is_rejected() {
+        let src = "pure fn f(int x) { println(x); return x; }\n";
+        let s = stmts(src);
+        let err = check_program_effects(&s, "<t>").expect_err("println is io");
+        assert!(
+            err.contains("cannot call io function `println` from pure context"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn diagnostic_includes_source_location() {
+        // When the `pure` fn has a real span, the error prefix is
+        // `<path>:<line>:<col>: …` — matching RES-080 / RES-191
+        // conventions so IDEs can anchor the message.
+        let src = "io   fn noisy(int x) { return x; }\n\
+                   pure fn caller(int x) { return noisy(x); }\n";
+        let s = stmts(src);
+        let err = check_program_effects(&s, "<t>").unwrap_err();
+        assert!(err.contains("<t>:"), "missing source path: {err}");
+        assert!(err.contains(":2:"), "missing line number: {err}");
+    }
+
+    // RES-385c: linear parameter effect-system interaction tests.
+
+    #[test]
+    fn pure_with_linear_parameter_rejected_on_consumption() {
+        let src = "fn consume(linear int x) { return 0; }\n\
+                   pure fn bad(linear int x) { return consume(x); }\n";
+        let s = stmts(src);
+        let err = check_program_effects(&s, "<t>").expect_err("pure+linear consumed should fail");
+        assert!(
+            err.contains("cannot consume linear parameter `x` in pure context"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn pure_with_linear_parameter_accepted_on_no_consumption() {
+        let src = "pure fn ok(linear int x) { return 0; }\n";
+        let s = stmts(src);
+        check_program_effects(&s, "<t>").expect("pure+linear not consumed should pass");
+    }
+
+    #[test]
+    fn io_with_linear_parameter_accepted_on_consumption() {
+        let src = "fn consume(linear int x) { return 0; }\n\
+                   io fn ok(linear int x) { return consume(x); }\n";
+        let s = stmts(src);
+        check_program_effects(&s, "<t>").expect("io+linear consumed should pass");
+    }
+}
+
+#[cfg(test)]
+mod purity_tests {
+    use super::*;
+    use crate::parse;
+
+    /// Pull the statement list out of a parsed program.
+    fn stmts(src: &str) -> Vec<crate::span::Spanned<Node>> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        match prog {
+            Node::Program(s) => s,
+            other => panic!("expected Program, got {:?}", other),
+        }
+    }
+
+    // ---------- AC: success ----------
+
+    #[test]
+    fn pure_fn_calling_only_arithmetic_passes() {
+        let src = "@pure fn double(int x) { return x * 2; }\n";
+        let s = stmts(src);
+        check_program_purity(&s, "<t>").expect("should pass");
+    }
+
+    #[test]
+    fn pure_fn_calling_pure_builtin_passes() {
+        let src = "@pure fn f(int x) { return abs(x); }\n";
+        let s = stmts(src);
+        check_program_purity(&s, "<t>").expect("abs is pure");
+    }
+
+    #[test]
+    fn pure_fn_with_struct_construction_passes() {
+        let src = "\
+            struct Point { int x, int y }\n\
+            @pure fn make(int a, int b) { return new Point { x: a, y: b }; }\n";
+        let s = stmts(src);
+        check_program_purity(&s, "<t>").expect("struct construction is pure");
+    }
+
+    // ---------- AC: impure builtin ----------
+
+    #[test]
+    fn pure_fn_calling_println_is_rejected() {
+        let src = "@pure fn f(int x) { println(\"hi\"); return x; }\n";
+        let s = stmts(src);
+        let err = check_program_purity(&s, "<t>").expect_err("println is impure");
+        assert!(
+            err.contains("calls impure builtin `println`"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn pure_fn_calling_clock_ms_is_rejected() {
+        let src = "@pure fn f() { return clock_ms(); }\n";
+        let s = stmts(src);
+        let err = check_program_purity(&s, "<t>").expect_err("clock_ms is impure");
+        assert!(err.contains("clock_ms"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn pure_fn_calling_file_read_is_rejected() {
+        let src = "@pure fn f() { return file_read(\"x\"); }\n";
+        let s = stmts(src);
+        let err = check_program_purity(&s, "<t>").expect_err("file_read is impure");
+        assert!(err.contains("file_read"), "unexpected error: {err}");
+    }
+
+    // ---------- AC: impure user-fn call ----------
+
+    #[test]
+    fn pure_fn_calling_unannotated_user_fn_is_rejected() {
+        let src = "\
+            fn helper(int x) { return x + 1; }\n\
+            @pure fn f(int x) { return helper(x); }\n";
+        let s = stmts(src);
+        let err = check_program_purity(&s, "<t>").expect_err("unannotated user fn is rejected");
+        assert!(
+            err.contains("calls unannotated fn `helper`"),
+            "unexpected error: {err}"
+        );
+    }
+
+    // ---------- AC: mutual recursion between two @pure fns ----------
+
+    #[test]
+    fn two_mutually_recursive_pure_fns_pass() {
+        let src = "\
+            @pure fn a(int n) { return b(n); }\n\
+            @pure fn b(int n) { return a(n); }\n";
+        let s = stmts(src);
+        // Only the purity pass — the main typechecker rejects
+        // forward refs today (orthogonal limitation, see
+        // typechecker.rs `pre-pass` comment at line ~800). The
+        // purity pass itself correctly handles mutual recursion
+        // because the optimistic first pass populates `pure_fns`
+        // with both names.
+        check_program_purity(&s, "<t>").expect("mutual recursion between two @pure fns is fine");
+    }
+
+    #[test]
+    fn pure_fn_calling_live_block_is_rejected() {
+        // `live` blocks retry on failure — observable from outside.
+        let src = "@pure fn f(int x) { live { return x; } return 0; }\n";
+        let s = stmts(src);
+        let err = check_program_purity(&s, "<t>").expect_err("live blocks are impure");
+        assert!(err.contains("live"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn unannotated_fn_is_not_checked_for_purity() {
+        // Non-@pure fns are free to do anything; the checker must
+        // leave them alone even if they'd violate purity.
+        let src = "fn noisy() { println(\"hi\"); return 0; }\n";
+        let s = stmts(src);
+        check_program_purity(&s, "<t>").expect("non-@pure fns bypass the purity checker");
+    }
+
+    // ---------- error message shape ----------
+
+    #[test]
+    fn error_mentions_fn_name_and_violating_site() {
+        let src = "@pure fn noisy(int x) { println(\"hi\"); return x; }\n";
+        let s = stmts(src);
+        let err = check_program_purity(&s, "<t>").unwrap_err();
+        assert!(err.contains("noisy"), "expected fn name in error: {err}");
+        assert!(
+            err.contains("println"),
+            "expected callee name in error: {err}"
+        );
+    }
+
+    #[test]
+    fn error_carries_file_path_and_position() {
+        let src = "@pure fn noisy() { println(\"hi\"); return 0; }\n";
+        let s = stmts(src);
+        let err = check_program_purity(&s, "src/thing.rs").unwrap_err();
+        assert!(
+            err.starts_with("src/thing.rs:"),
+            "expected RES-080 prefix `<path>:<line>:<col>:`, got: {err}"
+        );
+    }
+
+    // ---------- RES-192: IO-effect inference ----------
+
+    /// AC: a chain `caller -> helper -> println` has IO at every
+    /// level.
+    #[test]
+    fn effect_chain_propagates_io_transitively() {
+        let src = "\
+            fn helper() { println(\"hi\"); return 0; }\n\
+            fn caller() { return helper(); }\n\
+            fn top() { return caller(); }\n";
+        let s = stmts(src);
+        let eff = infer_fn_effects(&s);
+        assert_eq!(eff.get("helper"), Some(&true), "helper should be IO");
+        assert_eq!(eff.get("caller"), Some(&true), "caller should be IO");
+        assert_eq!(eff.get("top"), Some(&true), "top should be IO");
+    }
 
-/// RES-192: build the call-graph edge set for `statements`, then
-/// run the fixpoint. Returns `name → has_io` for every top-level
-/// user fn. Non-function statements contribute nothing.
-pub fn infer_fn_effects(
-    statements: &[crate::span::Spanned<Node>],
-) -> std::collections::HashMap<String, bool> {
-    // RES-1503: build the intermediate fn-name maps keyed by `&str`,
-    // borrowing from each `Node::Function::name`. The previous shape
-    // allocated owned `String`s for every fn name three times: once
-    // for `fn_bodies`, once for the `effects` init clone, and once
-    // per fixpoint flip. The return type stays `HashMap<String, bool>`
-    // because `self.stats.fn_effects` is an owned field — the
-    // conversion happens once at the end (one `to_string()` per fn,
-    // matching what the return type already costs).
-    //
-    // Pre-size `fn_bodies` to `statements.len()` — upper bound, since
-    // every Function statement contributes one entry. Same shape as
-    // `check_program_purity::pure_fns` (RES-1796) and
-    // `collect_fn_effects::out` (RES-1734) — the two sibling passes
-    // that walk the same statement list to populate the same shape of
-    // map.
-    let mut fn_bodies: std::collections::HashMap<&str, &Node> =
-        std::collections::HashMap::with_capacity(statements.len());
-    for stmt in statements {
-        if let Node::Function { name, body, .. } = &stmt.node {
-            fn_bodies.insert(name.as_str(), body.as_ref());
-        }
+    /// AC: a leaf fn that only does arithmetic is tagged pure.
+    #[test]
+    fn arithmetic_only_leaf_is_pure() {
+        let src = "fn double(int x) { return x * 2; }\n";
+        let s = stmts(src);
+        let eff = infer_fn_effects(&s);
+        assert_eq!(eff.get("double"), Some(&false), "pure arithmetic");
     }
 
-    // Step 2: initialize every fn as pure.
-    let mut effects: std::collections::HashMap<&str, bool> =
-        fn_bodies.keys().map(|n| (*n, false)).collect();
+    #[test]
+    fn fixpoint_handles_mutual_recursion() {
+        // Two fns that call each other but neither does IO.
+        let src = "\
+            fn a(int n) { return b(n); }\n\
+            fn b(int n) { return a(n); }\n";
+        let s = stmts(src);
+        let eff = infer_fn_effects(&s);
+        assert_eq!(eff.get("a"), Some(&false), "pure mutual recursion");
+        assert_eq!(eff.get("b"), Some(&false));
+    }
 
-    // Step 3: fixpoint — iterate body-walks until no effect flips.
-    // Upper bound: one flip per fn, so at most |fns| passes.
-    let max_passes = fn_bodies.len().saturating_add(1);
-    for _ in 0..max_passes {
-        let mut changed = false;
-        for (name, body) in &fn_bodies {
-            if *effects.get(*name).unwrap_or(&false) {
-                continue; // already IO — nothing to update
-            }
-            if body_reaches_io(body, &effects) {
-                effects.insert(*name, true);
-                changed = true;
-            }
-        }
-        if !changed {
-            break;
-        }
+    #[test]
+    fn io_reaches_through_mutual_recursion() {
+        let src = "\
+            fn a(int n) { return b(n); }\n\
+            fn b(int n) { println(\"x\"); return a(n); }\n";
+        let s = stmts(src);
+        let eff = infer_fn_effects(&s);
+        assert_eq!(eff.get("a"), Some(&true), "a reaches IO via b");
+        assert_eq!(eff.get("b"), Some(&true));
     }
-    effects
-        .into_iter()
-        .map(|(k, v)| (k.to_string(), v))
-        .collect()
-}
 
-/// RES-192: body-level check for IO reachability under the
-/// current `effects` snapshot. Used inside the fixpoint loop —
-/// each iteration treats `effects` as a frozen best-estimate and
-/// asks "does this body reach anything marked IO today?".
-fn body_reaches_io(node: &Node, effects: &std::collections::HashMap<&str, bool>) -> bool {
-    match node {
-        Node::Block { stmts, .. } => stmts.iter().any(|s| body_reaches_io(s, effects)),
-        Node::LetStatement { value, .. } | Node::StaticLet { value, .. } => {
-            body_reaches_io(value, effects)
-        }
-        Node::ReturnStatement { value: Some(v), .. } => body_reaches_io(v, effects),
-        Node::ReturnStatement { value: None, .. } => false,
-        Node::IfStatement {
-            condition,
-            consequence,
-            alternative,
-            ..
-        } => {
-            body_reaches_io(condition, effects)
-                || body_reaches_io(consequence, effects)
-                || alternative
-                    .as_ref()
-                    .is_some_and(|a| body_reaches_io(a, effects))
-        }
-        Node::WhileStatement {
-            condition, body, ..
-        } => body_reaches_io(condition, effects) || body_reaches_io(body, effects),
-        Node::ForInStatement { iterable, body, .. } => {
-            body_reaches_io(iterable, effects) || body_reaches_io(body, effects)
-        }
-        Node::Assert { condition, .. } => body_reaches_io(condition, effects),
-        Node::Assume { condition, .. } => body_reaches_io(condition, effects),
-        Node::LiveBlock {
-            body, invariants, ..
-        } => {
-            // A `live` block is NOT intrinsically IO (retries on
-            // failure observe error state, but not IO per the
-            // ticket's definition). If the body reaches IO, the
-            // outer fn still does.
-            body_reaches_io(body, effects)
-                || invariants.iter().any(|inv| body_reaches_io(inv, effects))
-        }
-        Node::InfixExpression { left, right, .. } => {
-            body_reaches_io(left, effects) || body_reaches_io(right, effects)
-        }
-        Node::PrefixExpression { right, .. } => body_reaches_io(right, effects),
-        Node::CallExpression {
-            function,
-            arguments,
-            ..
-        } => {
-            // Any arg side-effecting → IO.
-            if arguments.iter().any(|a| body_reaches_io(a, effects)) {
-                return true;
-            }
-            // The callee itself.
-            if body_reaches_io(function, effects) {
-                return true;
-            }
-            if let Node::Identifier { name: callee, .. } = function.as_ref() {
-                if IMPURE_BUILTINS.contains(&callee.as_str()) {
-                    return true;
-                }
-                if is_known_pure_builtin(callee) {
-                    return false;
-                }
-                // A user-fn call: inherit that fn's current
-                // best-estimate effect. Unknown user fn (rare —
-                // typechecker would have rejected earlier) →
-                // conservatively IO.
-                match effects.get(callee.as_str()) {
-                    Some(&true) => return true,
-                    Some(&false) => return false,
-                    None => return true, // unknown = IO (conservative)
-                }
-            }
-            // Indirect / method callee — can't resolve statically;
-            // conservatively mark IO.
-            true
-        }
-        Node::FieldAccess { target, .. } => body_reaches_io(target, effects),
-        Node::FieldAssignment { target, value, .. } => {
-            body_reaches_io(target, effects) || body_reaches_io(value, effects)
-        }
-        Node::Assignment { value, .. } => body_reaches_io(value, effects),
-        Node::IndexExpression { target, index, .. } => {
-            body_reaches_io(target, effects) || body_reaches_io(index, effects)
-        }
-        Node::IndexAssignment {
-            target,
-            index,
-            value,
-            ..
-        } => {
-            body_reaches_io(target, effects)
-                || body_reaches_io(index, effects)
-                || body_reaches_io(value, effects)
-        }
-        Node::ArrayLiteral { items, .. } => items.iter().any(|i| body_reaches_io(i, effects)),
-        Node::StructLiteral { fields, base, .. } => {
-            base.as_ref().is_some_and(|b| body_reaches_io(b, effects))
-                || fields.iter().any(|(_, v)| body_reaches_io(v, effects))
-        }
-        Node::Match {
-            scrutinee, arms, ..
-        } => {
-            if body_reaches_io(scrutinee, effects) {
-                return true;
-            }
-            arms.iter().any(|(_pat, guard, arm_body)| {
-                guard.as_ref().is_some_and(|g| body_reaches_io(g, effects))
-                    || body_reaches_io(arm_body, effects)
-            })
-        }
-        Node::ExpressionStatement { expr, .. } => body_reaches_io(expr, effects),
-        Node::TryExpression { expr, .. } => body_reaches_io(expr, effects),
-        Node::OptionalChain { object, access, .. } => {
-            if body_reaches_io(object, effects) {
-                return true;
-            }
-            if let crate::ChainAccess::Method(_, args) = access {
-                args.iter().any(|a| body_reaches_io(a, effects))
-            } else {
-                false
-            }
-        }
-        // Nested fn decls are rare but handled — recurse into
-        // their body too. Today the parser doesn't emit these;
-        // future closures will.
-        Node::Function { body, .. } => body_reaches_io(body, effects),
-        // Pure literals / identifier reads / etc.
-        _ => false,
+    #[test]
+    fn file_io_builtins_flag_io() {
+        let src = "\
+            fn writer() { file_write(\"f\", \"data\"); return 0; }\n\
+            fn reader() { return file_read(\"f\"); }\n";
+        let s = stmts(src);
+        let eff = infer_fn_effects(&s);
+        assert_eq!(eff.get("writer"), Some(&true));
+        assert_eq!(eff.get("reader"), Some(&true));
+    }
+
+    #[test]
+    fn clock_and_random_flag_io() {
+        // Nondeterminism counts as IO per the broader "impure
+        // builtin" policy inherited from RES-191.
+        let src = "\
+            fn now() { return clock_ms(); }\n\
+            fn rand() { return random_int(0, 10); }\n";
+        let s = stmts(src);
+        let eff = infer_fn_effects(&s);
+        assert_eq!(eff.get("now"), Some(&true));
+        assert_eq!(eff.get("rand"), Some(&true));
+    }
+
+    #[test]
+    fn pure_builtin_calls_stay_pure() {
+        let src = "\
+            fn compute(int x) { return abs(x); }\n\
+            fn compose(int x) { let y = compute(x); return min(y, 10); }\n";
+        let s = stmts(src);
+        let eff = infer_fn_effects(&s);
+        assert_eq!(eff.get("compute"), Some(&false), "abs is pure");
+        assert_eq!(eff.get("compose"), Some(&false));
+    }
+
+    #[test]
+    fn live_block_alone_is_not_io() {
+        // A live block with a pure body is still pure. The ticket
+        // tracks "reach println or file_*"; retries alone don't
+        // qualify.
+        let src = "fn f(int x) { live { return x; } return 0; }\n";
+        let s = stmts(src);
+        let eff = infer_fn_effects(&s);
+        assert_eq!(eff.get("f"), Some(&false), "pure live body");
     }
-}
-
-// ============================================================
-// RES-389: effect-annotation enforcement.
-// ============================================================
-//
-// Syntax (soft keywords dispatched at statement-start):
-//   pure fn f(int x) { ... }   // EffectSet::pure()
-//   io   fn g(int x) { ... }   // EffectSet::io()
-//   fn h(int x) { ... }        // EffectSet::io() (backward compat)
-//
-// Call rules:
-//   - A `pure` fn may call other `pure` fns or known-pure
-//     builtins (see `is_known_pure_builtin`).
-//   - An `io` fn (the permissive default) may call `pure` or
-//     `io` fns, plus any builtin.
-//   - Calling an `io` callee from a `pure` caller is a compile
-//     error:
-//        E: cannot call io function `X` from pure context
-//
-// The pass is deliberately coarse — the `@pure` checker (RES-191)
-// already rejects impure-builtin calls and unannotated-user-fn
-// calls from a `@pure` body. This pass layers on top so the new
-// `pure fn` / `io fn` keyword form gets its own diagnostic
-// surface separately from the `@pure` attribute.
 
-use crate::EffectSet;
+    #[test]
+    fn live_block_with_io_body_is_io() {
+        let src = "fn f(int x) { live { println(\"r\"); } return x; }\n";
+        let s = stmts(src);
+        let eff = infer_fn_effects(&s);
+        assert_eq!(eff.get("f"), Some(&true));
+    }
 
-/// RES-389: collect each top-level fn's declared `EffectSet` by
-/// name. Unannotated fns default to `EffectSet::io()`, matching
-/// the parser. Duplicate names (rare — the typechecker elsewhere
-/// diagnoses redeclarations) keep the last one seen.
-fn collect_fn_effects(
-    statements: &[crate::span::Spanned<Node>],
-) -> std::collections::HashMap<String, EffectSet> {
-    // RES-1734: pre-size to `statements.len()` — upper bound, since
-    // every Function statement contributes one entry. Same shape as
-    // RES-1716 / RES-1718 / RES-1724 etc.
-    let mut out = std::collections::HashMap::with_capacity(statements.len());
-    for stmt in statements {
-        if let Node::Function { name, effects, .. } = &stmt.node {
-            out.insert(name.clone(), *effects);
-        }
+    #[test]
+    fn empty_program_produces_empty_effects() {
+        let s = stmts("");
+        assert!(infer_fn_effects(&s).is_empty());
     }
-    out
-}
 
-/// RES-389: top-level entry for the effect-annotation pass.
-/// Walks each `pure fn` body and reports the first call site that
-/// reaches an `io` callee or an indeterminate callee (method /
-/// computed).
-fn check_program_effects(
-    statements: &[crate::span::Spanned<Node>],
-    source_path: &str,
-) -> Result<(), String> {
-    // RES-1296: short-circuit. `collect_fn_effects` builds a
-    // name→effects HashMap by iterating every top-level statement,
-    // and the per-stmt loop below only enters its body for `Function`
-    // nodes with `effects.pure == true`. Programs that don't declare
-    // any pure fn — the overwhelming majority — pay for both passes
-    // and produce nothing useful. Pre-scan once before doing either
-    // and bail when no pure fn exists.
-    let has_pure_fn = statements
-        .iter()
-        .any(|s| matches!(&s.node, Node::Function { effects, .. } if effects.pure));
-    if !has_pure_fn {
-        return Ok(());
+    // ---------- RES-385: linear-type single-use enforcement ----------
+
+    /// AC: passing a `linear` parameter to one consumer is fine.
+    /// Passing it to a second consumer is a single-use violation.
+    #[test]
+    fn linear_value_used_twice_is_rejected() {
+        let src = "\
+            fn consume(linear FileHandle fh) { return 0; }\n\
+            fn bad(linear FileHandle fh) {\n\
+                consume(fh);\n\
+                consume(fh);\n\
+                return 0;\n\
+            }\n";
+        let (program, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {errs:?}");
+        let mut tc = TypeChecker::new();
+        let err = tc
+            .check_program_with_source(&program, "<t>")
+            .expect_err("second use of linear fh must be rejected");
+        assert!(
+            err.contains("linear-use"),
+            "expected `linear-use` diagnostic tag, got: {err}"
+        );
+        assert!(
+            err.contains("used after move"),
+            "expected `used after move` in message, got: {err}"
+        );
+        assert!(
+            err.contains("fh"),
+            "expected binding name in message, got: {err}"
+        );
     }
-    let fn_effects = collect_fn_effects(statements);
-    for stmt in statements {
-        if let Node::Function {
-            name,
-            body,
-            effects,
-            parameters,
-            ..
-        } = &stmt.node
-            && effects.pure
-            && let Err(reason) = check_body_effects(body, &fn_effects, parameters, statements, body)
-        {
-            let (line, col) = (stmt.span.start.line, stmt.span.start.column);
-            return Err(if line == 0 {
-                format!("pure fn `{}`: {}", name, reason)
-            } else {
-                format!(
-                    "{}:{}:{}: pure fn `{}`: {}",
-                    source_path, line, col, name, reason
-                )
-            });
-        }
+
+    /// AC: consuming exactly once (via a call or `drop`) is fine.
+    #[test]
+    fn linear_value_used_once_is_accepted() {
+        let src = "\
+            fn consume(linear FileHandle fh) { return 0; }\n\
+            fn good(linear FileHandle fh) {\n\
+                consume(fh);\n\
+                return 0;\n\
+            }\n\
+            fn dropper(linear FileHandle fh) {\n\
+                drop(fh);\n\
+                return 0;\n\
+            }\n";
+        let (program, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {errs:?}");
+        let mut tc = TypeChecker::new();
+        tc.check_program_with_source(&program, "<t>")
+            .expect("one consumption (direct call or drop) must typecheck");
     }
-    // RES-3933 A-E7: call-site-based effect propagation for calls
-    // through a function-typed parameter. `check_body_effects` above
-    // defers those calls (see the `is_function_type` check inside its
-    // `CallExpression` arm) instead of blanket-rejecting them; this
-    // pass makes the real, sound-but-monomorphic call using
-    // whole-program call-site information the per-body walk doesn't
-    // have. See `effect_polymorphism` module docs for what's enforced
-    // vs deferred.
-    crate::effect_polymorphism::check(statements, &fn_effects, source_path)?;
-    Ok(())
-}
 
-/// RES-389/RES-385c: recursive walk of a `pure` fn body. Returns
-/// `Err(<reason>)` at the first call to a non-`pure` callee, or if a
-/// linear parameter is consumed (RES-385c), with the diagnostic text
-/// the caller will prefix with the fn span.
-fn check_body_effects(
-    node: &Node,
-    fn_effects: &std::collections::HashMap<String, EffectSet>,
-    linear_params: &[(String, String)],
-    statements: &[crate::span::Spanned<Node>],
-    caller_body: &Node,
-) -> Result<(), String> {
-    match node {
-        Node::Block { stmts, .. } => {
-            for s in stmts {
-                check_body_effects(s, fn_effects, linear_params, statements, caller_body)?;
-            }
-        }
-        Node::LetStatement { value, .. } | Node::StaticLet { value, .. } => {
-            check_body_effects(value, fn_effects, linear_params, statements, caller_body)?;
-        }
-        Node::ReturnStatement { value: Some(v), .. } => {
-            check_body_effects(v, fn_effects, linear_params, statements, caller_body)?
-        }
-        Node::ReturnStatement { value: None, .. } => {}
-        Node::IfStatement {
-            condition,
-            consequence,
-            alternative,
-            ..
-        } => {
-            check_body_effects(
-                condition,
-                fn_effects,
-                linear_params,
-                statements,
-                caller_body,
-            )?;
-            check_body_effects(
-                consequence,
-                fn_effects,
-                linear_params,
-                statements,
-                caller_body,
-            )?;
-            if let Some(a) = alternative {
-                check_body_effects(a, fn_effects, linear_params, statements, caller_body)?;
-            }
-        }
-        Node::WhileStatement {
-            condition, body, ..
-        } => {
-            check_body_effects(
-                condition,
-                fn_effects,
-                linear_params,
-                statements,
-                caller_body,
-            )?;
-            check_body_effects(body, fn_effects, linear_params, statements, caller_body)?;
-        }
-        Node::ForInStatement { iterable, body, .. } => {
-            check_body_effects(iterable, fn_effects, linear_params, statements, caller_body)?;
-            check_body_effects(body, fn_effects, linear_params, statements, caller_body)?;
-        }
-        Node::Assert { condition, .. } | Node::Assume { condition, .. } => {
-            check_body_effects(
-                condition,
-                fn_effects,
-                linear_params,
-                statements,
-                caller_body,
-            )?;
-        }
-        Node::LiveBlock { body, .. } => {
-            check_body_effects(body, fn_effects, linear_params, statements, caller_body)?
-        }
-        Node::InfixExpression { left, right, .. } => {
-            check_body_effects(left, fn_effects, linear_params, statements, caller_body)?;
-            check_body_effects(right, fn_effects, linear_params, statements, caller_body)?;
+    // ---------- RES-4112: `#[overflow_checked]` BV64 wiring ----------
+    //
+    // `verifier_z3::prove_overflow_safe` (RES-4014 / C-E3) proves a
+    // requires/ensures clause under width-respecting BV64 arithmetic
+    // instead of unbounded LIA — sound for Resilient's `Int64` +
+    // `OverflowMode::Wrap` runtime semantics. These tests exercise the
+    // wiring into the per-fn requires/ensures static pass in
+    // `Node::Function` handling: `#[overflow_checked]` opts a fn in;
+    // without it, only the (unsound-for-overflow) LIA tautology check
+    // runs, exactly as before this ticket.
+    //
+    // Only compiled with `--features z3` — without Z3 the whole static
+    // fold/Z3 pass is a no-op and none of this applies.
+    #[cfg(feature = "z3")]
+    mod overflow_checked_tests {
+        use super::*;
+
+        /// `#[overflow_checked]` records into the process-wide
+        /// `feature_attrs` registry during parsing and is read back
+        /// during `check_program_with_source`. Serialize against the
+        /// binary-wide test lock and reset before/after so this
+        /// module's tests can't race other `feature_attrs`-mutating
+        /// tests running in parallel (see `feature_attrs::lock_for_test`).
+        fn typecheck(src: &str) -> Result<(), String> {
+            let _g = crate::feature_attrs::lock_for_test();
+            crate::feature_attrs::reset();
+            let (program, errs) = crate::parse(src);
+            assert!(errs.is_empty(), "parse errors: {errs:?}");
+            let mut tc = TypeChecker::new();
+            let result = tc.check_program_with_source(&program, "<t>").map(|_| ());
+            crate::feature_attrs::reset();
+            result
         }
-        Node::PrefixExpression { right, .. } => {
-            check_body_effects(right, fn_effects, linear_params, statements, caller_body)?
+
+        // NOTE ON SHAPE: the RES-060 static-fold loop in
+        // `Node::Function` handling proves each `requires`/`ensures`
+        // clause *standalone* — it does not thread sibling clauses in
+        // as axioms (that threading only happens for the separate
+        // `recovers_to` obligation). So every fixture below needs a
+        // clause that is a LIA tautology *on its own*, not one that
+        // only holds given other requires. `x + K > x` for a fixed
+        // positive `K` is exactly that: true for every mathematical
+        // integer `x`, but false once `x` is close enough to
+        // `i64::MAX` for `x + K` to wrap.
+
+        /// Baseline (no attribute): `x + 1 > x` is a LIA tautology, so
+        /// the existing (opt-out) path discharges it and compilation
+        /// succeeds even though it wraps at `x == i64::MAX`. Confirms
+        /// `#[overflow_checked]` is genuinely opt-in.
+        #[test]
+        fn without_attribute_wraparound_prone_add_still_compiles() {
+            let src = "fn f(int x) ensures x + 1 > x { return x; }\n";
+            typecheck(src).expect("LIA tautology discharges without the attribute");
         }
-        Node::CallExpression {
-            function,
-            arguments,
-            ..
-        } => {
-            for a in arguments {
-                check_body_effects(a, fn_effects, linear_params, statements, caller_body)?;
-                // RES-385c: detect if a linear parameter is being
-                // consumed (passed to a function). Consuming a linear
-                // parameter is observable IO — an operation on a
-                // resource — so a pure fn cannot do it.
-                if let Node::Identifier { name: arg_name, .. } = a
-                    && linear_params.iter().any(|(param_ty, param_name)| {
-                        arg_name == param_name && crate::linear::is_linear(param_ty)
-                    })
-                {
-                    return Err(format!(
-                        "cannot consume linear parameter `{}` in pure context",
-                        arg_name
-                    ));
-                }
-            }
-            if let Node::Identifier { name: callee, .. } = function.as_ref() {
-                // User fn with a recorded effect set — `pure`
-                // propagates cleanly; any other effect (today just
-                // `io`) is a violation.
-                if let Some(callee_effects) = fn_effects.get(callee) {
-                    if callee_effects.pure {
-                        return Ok(());
-                    }
-                    // RES-3933 A-E7 follow-up (#4072): true
-                    // effect-variable polymorphism. `callee`'s own
-                    // declared effect isn't `pure`, but it may still
-                    // be provably pure *at this call site* if it
-                    // declares an effect-variable parameter (RES-193
-                    // `-e->`) that this call binds to a provably-pure
-                    // callback, and the rest of its body is
-                    // independently pure. See
-                    // `effect_polymorphism::resolves_pure_at_call_site`
-                    // for the full soundness argument — this can only
-                    // ever turn a previously-rejected call into an
-                    // accepted one, never the reverse.
-                    if crate::effect_polymorphism::resolves_pure_at_call_site(
-                        callee,
-                        arguments,
-                        statements,
-                        fn_effects,
-                        caller_body,
-                    ) {
-                        return Ok(());
-                    }
-                    return Err(render_io_from_pure_context_error(callee));
-                }
-                // Builtins: pure-by-default list passes; anything
-                // flagged impure by RES-191 is also implicitly io.
-                if IMPURE_BUILTINS.contains(&callee.as_str()) {
-                    return Err(render_io_from_pure_context_error(callee));
-                }
-                if is_known_pure_builtin(callee) {
-                    return Ok(());
-                }
-                // RES-3933 A-E7: a call through one of the enclosing
-                // fn's own function-typed parameters. We can't
-                // resolve its effect here — the same parameter name
-                // could be bound to a pure or an io value depending
-                // on the call site — so defer to
-                // `effect_polymorphism::check`, which has
-                // whole-program call-site visibility and only
-                // rejects a *proven* io-callback violation. Falling
-                // through to the blanket "unknown callee" rejection
-                // below would make every higher-order `pure` fn
-                // uncompilable, even when every actual callback
-                // passed to it is pure.
-                if linear_params
-                    .iter()
-                    .any(|(ty, n)| n == callee && crate::effect_polymorphism::is_function_type(ty))
-                {
-                    return Ok(());
-                }
-                // Unknown callee (not in BUILTINS and not a
-                // declared user fn) — conservatively reject so a
-                // `pure` annotation remains meaningful.
-                return Err(render_io_from_pure_context_error(callee));
-            }
-            // Method / computed callee — can't resolve statically;
-            // same conservative rejection as the purity pass.
-            check_body_effects(function, fn_effects, linear_params, statements, caller_body)?;
-            return Err(
-                "cannot call indirect/method callee from pure context (effect unknown)".to_string(),
+
+        /// Same clause, `#[overflow_checked]`: LIA proves `x + 1 > x`
+        /// a tautology, but BV64 finds `x == i64::MAX` as a
+        /// counterexample (wraps to `i64::MIN`, which is not `> x`) —
+        /// must be a hard compile error distinguishing the two
+        /// verdicts.
+        #[test]
+        fn overflow_checked_rejects_wraparound_prone_add() {
+            let src = "#[overflow_checked]\nfn f(int x) ensures x + 1 > x { return x; }\n";
+            let err = typecheck(src).expect_err("BV64 must reject the wraparound-prone add");
+            assert!(
+                err.contains("LIA said safe, BV64 disproved it"),
+                "diagnostic must distinguish LIA-vs-BV64 verdicts, got: {err}"
+            );
+            assert!(
+                err.contains("counterexample"),
+                "diagnostic must carry a BV64 counterexample, got: {err}"
             );
         }
-        Node::FieldAccess { target, .. } => {
-            check_body_effects(target, fn_effects, linear_params, statements, caller_body)?
-        }
-        Node::FieldAssignment { target, value, .. } => {
-            check_body_effects(target, fn_effects, linear_params, statements, caller_body)?;
-            check_body_effects(value, fn_effects, linear_params, statements, caller_body)?;
-        }
-        Node::Assignment { value, .. } => {
-            check_body_effects(value, fn_effects, linear_params, statements, caller_body)?
-        }
-        Node::IndexExpression { target, index, .. } => {
-            check_body_effects(target, fn_effects, linear_params, statements, caller_body)?;
-            check_body_effects(index, fn_effects, linear_params, statements, caller_body)?;
-        }
-        Node::IndexAssignment {
-            target,
-            index,
-            value,
-            ..
-        } => {
-            check_body_effects(target, fn_effects, linear_params, statements, caller_body)?;
-            check_body_effects(index, fn_effects, linear_params, statements, caller_body)?;
-            check_body_effects(value, fn_effects, linear_params, statements, caller_body)?;
+
+        /// Subtraction variant: `x - 1 < x` is a LIA tautology but
+        /// wraps at `x == i64::MIN` (`x - 1` wraps to `i64::MAX`,
+        /// which is not `< x`).
+        #[test]
+        fn overflow_checked_rejects_wraparound_prone_subtraction() {
+            let src = "#[overflow_checked]\nfn f(int x) ensures x - 1 < x { return x; }\n";
+            let err = typecheck(src).expect_err("BV64 must reject the wraparound-prone subtract");
+            assert!(
+                err.contains("LIA said safe, BV64 disproved it"),
+                "got: {err}"
+            );
         }
-        Node::ArrayLiteral { items, .. } => {
-            for i in items {
-                check_body_effects(i, fn_effects, linear_params, statements, caller_body)?;
-            }
+
+        /// `requires` clauses (not just `ensures`) go through the same
+        /// per-clause loop, so the attribute must catch a
+        /// wraparound-prone `requires` too.
+        #[test]
+        fn overflow_checked_rejects_wraparound_prone_requires() {
+            let src = "#[overflow_checked]\nfn f(int x) requires x + 1 > x { return x; }\n";
+            let err = typecheck(src).expect_err("BV64 must reject the wraparound-prone requires");
+            assert!(
+                err.contains("LIA said safe, BV64 disproved it"),
+                "got: {err}"
+            );
         }
-        Node::StructLiteral { fields, base, .. } => {
-            if let Some(b) = base {
-                check_body_effects(b, fn_effects, linear_params, statements, caller_body)?;
-            }
-            for (_, v) in fields {
-                check_body_effects(v, fn_effects, linear_params, statements, caller_body)?;
-            }
+
+        /// Multiple wraparound-prone clauses on the same fn: the loop
+        /// walks `requires` then `ensures` in order and must reject at
+        /// the first one it reaches (`requires`), not silently skip
+        /// past it to `ensures`.
+        #[test]
+        fn overflow_checked_rejects_first_of_several_bad_clauses() {
+            let src = "#[overflow_checked]\n\
+                       fn f(int x) requires x + 1 > x ensures x - 1 < x { return x; }\n";
+            let err = typecheck(src).expect_err("first wraparound-prone clause must be rejected");
+            assert!(
+                err.contains("LIA said safe, BV64 disproved it"),
+                "got: {err}"
+            );
         }
-        Node::Match {
-            scrutinee, arms, ..
-        } => {
-            check_body_effects(
-                scrutinee,
-                fn_effects,
-                linear_params,
-                statements,
-                caller_body,
-            )?;
-            for (_pat, guard, arm_body) in arms {
-                if let Some(g) = guard {
-                    check_body_effects(g, fn_effects, linear_params, statements, caller_body)?;
-                }
-                check_body_effects(arm_body, fn_effects, linear_params, statements, caller_body)?;
-            }
+
+        /// Adding zero can never overflow — the attribute must not
+        /// blanket-reject every arithmetic contract, only ones that
+        /// actually wrap.
+        #[test]
+        fn overflow_checked_accepts_safe_additive_identity() {
+            let src = "#[overflow_checked]\nfn f(int x) ensures x + 0 == x { return x; }\n";
+            typecheck(src).expect("x + 0 == x must verify under BV64 (never wraps)");
         }
-        Node::ExpressionStatement { expr, .. } => {
-            check_body_effects(expr, fn_effects, linear_params, statements, caller_body)?
+
+        /// A goal with no arithmetic at all is trivially safe under
+        /// both theories.
+        #[test]
+        fn overflow_checked_accepts_non_arithmetic_contract() {
+            let src =
+                "#[overflow_checked]\nfn f(int x) requires x >= 0 ensures x >= 0 { return x; }\n";
+            typecheck(src).expect("non-arithmetic contract must verify under BV64");
         }
-        Node::TryExpression { expr, .. } => {
-            check_body_effects(expr, fn_effects, linear_params, statements, caller_body)?
+
+        /// Unknown-tolerant: a `forall`/`exists` quantifier clause is
+        /// handled by the LIA path (`quantifiers::z3_encode`) but has
+        /// no BV64 encoding (`translate_bool_bv_width` has no
+        /// `Node::Quantifier` arm) — `prove_overflow_safe` returns
+        /// `None` (undecided), which must be silently tolerated
+        /// (matches the existing timeout/Unknown soft-failure policy)
+        /// rather than treated as a rejection.
+        #[test]
+        fn overflow_checked_tolerates_unsupported_quantifier_clause() {
+            let src = "#[overflow_checked]\n\
+                       fn f(int x) ensures forall i in 0..1: true { return x; }\n";
+            typecheck(src)
+                .expect("BV64 Unknown/unsupported-construct verdict must not fail compilation");
         }
-        Node::OptionalChain { object, access, .. } => {
-            check_body_effects(object, fn_effects, linear_params, statements, caller_body)?;
-            if let crate::ChainAccess::Method(_, args) = access {
-                for a in args {
-                    check_body_effects(a, fn_effects, linear_params, statements, caller_body)?;
-                }
-            }
+
+        /// Two `#[overflow_checked]` fns in one module: the attribute
+        /// is per-fn (keyed by name in the `feature_attrs` registry),
+        /// so the unchecked sibling keeps the old (opt-out) behavior
+        /// while the annotated one is held to the stricter standard.
+        #[test]
+        fn overflow_checked_is_per_function_not_module_wide() {
+            let src = "fn unchecked(int x) ensures x + 1 > x { return x; }\n\
+                       #[overflow_checked]\n\
+                       fn checked(int x) ensures x + 1 > x { return x; }\n";
+            let err = typecheck(src).expect_err("the annotated sibling must still be rejected");
+            assert!(
+                err.contains("fn checked"),
+                "error must name the annotated fn, got: {err}"
+            );
         }
-        Node::Function { body, .. } => {
-            check_body_effects(body, fn_effects, linear_params, statements, caller_body)?
+
+        /// A genuinely false clause (independent of overflow) must
+        /// keep reporting the pre-existing "statically false clause"
+        /// diagnostic, not the new BV64-specific one — the BV64 pass
+        /// only runs after the LIA path returns `Some(true)`.
+        #[test]
+        fn overflow_checked_does_not_change_plain_contradiction_diagnostic() {
+            let src = "#[overflow_checked]\n\
+                       fn f(int x) requires x > 0 && x < 0 { return x; }\n";
+            let err = typecheck(src).expect_err("plain contradiction must still be rejected");
+            assert!(err.contains("contract can never hold"), "got: {err}");
+            assert!(
+                !err.contains("LIA said safe"),
+                "plain LIA contradiction must not be mislabeled as a BV64 divergence, got: {err}"
+            );
         }
-        // Literals, identifier reads, durations — nothing to do.
-        _ => {}
     }
-    Ok(())
+
+    /// AC: `let fh: linear T = …;` binds a linear local whose double-
+    /// use is rejected just like a linear parameter.
+    #[test]
+    fn linear_let_binding_rejects_double_use() {
+        // Construct the handle via a struct literal so the RHS type
+        // matches the let annotation without needing fancy
+        // inference. The linearity bit flows through `parse_type_name`
+        // via the shared `linear` prefix-stripping helper.
+        let src = "\
+            struct FileHandle { int fd }\n\
+            fn consume(linear FileHandle fh) { return 0; }\n\
+            fn main() {\n\
+                let fh: linear FileHandle = new FileHandle { fd: 3 };\n\
+                consume(fh);\n\
+                consume(fh);\n\
+                return 0;\n\
+            }\n";
+        let (program, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {errs:?}");
+        let mut tc = TypeChecker::new();
+        let err = tc
+            .check_program_with_source(&program, "<t>")
+            .expect_err("second use of let-bound linear must be rejected");
+        assert!(
+            err.contains("linear-use"),
+            "expected `linear-use` diagnostic tag, got: {err}"
+        );
+    }
+
+    /// AC: the diagnostic is prefixed with `<file>:<line>:<col>:`
+    /// so editors can jump straight to the offending second use.
+    #[test]
+    fn linear_use_diagnostic_carries_source_position() {
+        let src = "\
+            fn consume(linear FileHandle fh) { return 0; }\n\
+            fn bad(linear FileHandle fh) {\n\
+                consume(fh);\n\
+                consume(fh);\n\
+                return 0;\n\
+            }\n";
+        let (program, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {errs:?}");
+        let mut tc = TypeChecker::new();
+        let err = tc
+            .check_program_with_source(&program, "src/bad.rz")
+            .expect_err("should fail");
+        assert!(
+            err.starts_with("src/bad.rz:"),
+            "expected <path>:<line>:<col>: prefix, got: {err}"
+        );
+    }
+
+    #[test]
+    fn stats_field_populated_by_full_check() {
+        // End-to-end: running the typechecker with `audit_stats`
+        // opt-in populates `stats.fn_effects`. Confirms the gated
+        // call-site inside `check_program_with_source` is wired.
+        //
+        // RES-1322: `with_audit_stats(true)` is required — the
+        // fixpoint is off by default so non-audit callers don't pay
+        // for a result they never read.
+        let src = "\
+            fn noisy() { println(\"h\"); return 0; }\n\
+            fn quiet() { return 0; }\n\
+            fn main(int _d) { noisy(); return quiet(); }\n\
+            main(0);\n";
+        let (program, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {errs:?}");
+        let mut tc = TypeChecker::new().with_audit_stats(true);
+        tc.check_program_with_source(&program, "<t>")
+            .expect("typecheck should succeed");
+        assert_eq!(tc.stats.fn_effects.get("noisy"), Some(&true));
+        assert_eq!(tc.stats.fn_effects.get("quiet"), Some(&false));
+        assert_eq!(tc.stats.fn_effects.get("main"), Some(&true));
+    }
+
+    #[test]
+    fn stats_field_empty_by_default() {
+        // RES-1322: without `with_audit_stats(true)` the fixpoint
+        // is skipped and `stats.fn_effects` stays empty. Locks in
+        // the opt-in default so a future regression that always-
+        // populates the map fails this test.
+        let src = "fn noisy() { println(\"h\"); return 0; }\n";
+        let (program, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {errs:?}");
+        let mut tc = TypeChecker::new();
+        tc.check_program_with_source(&program, "<t>")
+            .expect("typecheck should succeed");
+        assert!(
+            tc.stats.fn_effects.is_empty(),
+            "fn_effects should be empty when audit_stats is off, got {:?}",
+            tc.stats.fn_effects
+        );
+    }
 }
 
 #[cfg(test)]
-mod effect_tests {
+mod type_hole_display_tests {
+    use super::*;
+    use crate::span::{Pos, Span};
+
+    #[test]
+    fn var_with_span_displays_as_type_hole() {
+        let pos = Pos::new(3, 7, 0);
+        let span = Span::point(pos);
+        let ty = Type::Var(0, Some(span));
+        assert_eq!(ty.to_string(), "type hole at 3:7");
+    }
+
+    #[test]
+    fn var_without_span_displays_as_qt() {
+        let ty = Type::Var(0, None);
+        assert_eq!(ty.to_string(), "?t0");
+    }
+}
+
+/// RES-387: fault model — parser + typechecker slice for the `fails`
+/// annotation and `recovers_to` postcondition. Structured handlers
+/// and the Z3 proof obligation for `recovers_to` are separate tickets.
+#[cfg(test)]
+mod fault_model_tests {
     use super::*;
     use crate::parse;
 
-    fn stmts(src: &str) -> Vec<crate::span::Spanned<Node>> {
+    // ---------- Parser: `fails` / `recovers_to` ----------
+
+    #[test]
+    fn parser_accepts_single_fails_variant() {
+        let src = "fn read_sensor(int addr) fails HardwareFault { return addr; }\n";
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         match prog {
-            Node::Program(s) => s,
+            Node::Program(stmts) => match &stmts[0].node {
+                Node::Function { name, fails, .. } => {
+                    assert_eq!(name, "read_sensor");
+                    assert_eq!(fails, &vec!["HardwareFault".to_string()]);
+                }
+                other => panic!("expected Function, got {:?}", other),
+            },
             other => panic!("expected Program, got {:?}", other),
         }
     }
 
     #[test]
-    fn pure_to_pure_passes() {
-        let src = "pure fn inner(int x) { return x + 1; }\n\
-                   pure fn outer(int x) { return inner(x); }\n";
-        let s = stmts(src);
-        check_program_effects(&s, "<t>").expect("pure→pure should pass");
+    fn parser_accepts_multiple_fails_variants() {
+        let src = "fn write_sensor(int v) fails HardwareFault, Timeout { return v; }\n";
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        match prog {
+            Node::Program(stmts) => match &stmts[0].node {
+                Node::Function { fails, .. } => {
+                    assert_eq!(
+                        fails,
+                        &vec!["HardwareFault".to_string(), "Timeout".to_string()]
+                    );
+                }
+                other => panic!("expected Function, got {:?}", other),
+            },
+            other => panic!("expected Program, got {:?}", other),
+        }
     }
 
     #[test]
-    fn pure_to_io_is_rejected() {
-        let src = "io   fn noisy(int x) { return x; }\n\
-                   pure fn caller(int x) { return noisy(x); }\n";
-        let s = stmts(src);
-        let err = check_program_effects(&s, "<t>").expect_err("pure→io should fail");
-        assert!(
-            err.contains("cannot call io function `noisy` from pure context"),
-            "unexpected error: {err}"
-        );
+    fn parser_accepts_recovers_to_postcondition() {
+        let src = "fn write_sensor(int v) fails Timeout recovers_to: v >= 0; { return v; }\n";
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        match prog {
+            Node::Program(stmts) => match &stmts[0].node {
+                Node::Function {
+                    fails, recovers_to, ..
+                } => {
+                    assert_eq!(fails, &vec!["Timeout".to_string()]);
+                    assert!(
+                        recovers_to.is_some(),
+                        "expected recovers_to to be populated"
+                    );
+                }
+                other => panic!("expected Function, got {:?}", other),
+            },
+            other => panic!("expected Program, got {:?}", other),
+        }
     }
 
     #[test]
-    fn pure_to_unannotated_is_rejected() {
-        // Unannotated fns default to `EffectSet::io()` per the
-        // RES-389 backward-compat rule.
-        let src = "fn      helper(int x) { return x + 1; }\n\
-                   pure fn caller(int x) { return helper(x); }\n";
-        let s = stmts(src);
-        let err = check_program_effects(&s, "<t>").expect_err("pure→unannotated should fail");
-        assert!(
-            err.contains("cannot call io function `helper` from pure context"),
-            "unexpected error: {err}"
-        );
+    fn parser_accepts_fails_after_requires() {
+        // requires, then fails — mirrors the ticket's example.
+        let src = "fn op(int x) requires x > 0 fails Bad { return x; }\n";
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        match prog {
+            Node::Program(stmts) => match &stmts[0].node {
+                Node::Function {
+                    fails, requires, ..
+                } => {
+                    assert_eq!(fails, &vec!["Bad".to_string()]);
+                    assert_eq!(requires.len(), 1);
+                }
+                other => panic!("expected Function, got {:?}", other),
+            },
+            other => panic!("expected Program, got {:?}", other),
+        }
     }
 
     #[test]
-    fn io_to_pure_passes() {
-        let src = "pure fn add1(int x) { return x + 1; }\n\
-                   io   fn caller(int x) { return add1(x); }\n";
-        let s = stmts(src);
-        check_program_effects(&s, "<t>").expect("io→pure should pass");
+    fn parser_fn_without_fails_has_empty_list() {
+        let src = "fn add(int a, int b) { return a + b; }\n";
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        match prog {
+            Node::Program(stmts) => match &stmts[0].node {
+                Node::Function {
+                    fails, recovers_to, ..
+                } => {
+                    assert!(fails.is_empty());
+                    assert!(recovers_to.is_none());
+                }
+                other => panic!("expected Function, got {:?}", other),
+            },
+            other => panic!("expected Program, got {:?}", other),
+        }
     }
 
-    #[test]
-    fn io_to_io_passes() {
-        let src = "io fn a(int x) { return x; }\n\
-                   io fn b(int x) { return a(x); }\n";
-        let s = stmts(src);
-        check_program_effects(&s, "<t>").expect("io→io should pass");
+    // ---------- Typechecker: propagation ----------
+
+    fn check(src: &str) -> Result<(), String> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let mut tc = TypeChecker::new();
+        tc.check_program_with_source(&prog, "<t>").map(|_| ())
     }
 
     #[test]
-    fn pure_using_pure_builtin_passes() {
-        let src = "pure fn f(int x) { return abs(x); }\n";
-        let s = stmts(src);
-        check_program_effects(&s, "<t>").expect("abs is pure");
+    fn propagated_fails_is_accepted() {
+        // `caller` propagates `HardwareFault`, matching `inner`'s fails.
+        let src = "\
+            fn inner(int x) fails HardwareFault { return x; }\n\
+            fn caller(int y) fails HardwareFault { return inner(y); }\n";
+        check(src).expect("propagation should typecheck");
     }
 
     #[test]
-    fn pure_using_impure_builtin_is_rejected() {
-        let src = "pure fn f(int x) { println(x); return x; }\n";
-        let s = stmts(src);
-        let err = check_program_effects(&s, "<t>").expect_err("println is io");
+    fn unhandled_fails_is_rejected() {
+        // `caller` does not declare `HardwareFault`; the call must
+        // be rejected with the MVP diagnostic.
+        let src = "\
+            fn inner(int x) fails HardwareFault { return x; }\n\
+            fn caller(int y) { return inner(y); }\n";
+        let err = check(src).expect_err("unhandled fails must be rejected");
         assert!(
-            err.contains("cannot call io function `println` from pure context"),
+            err.contains("unhandled failure variant HardwareFault"),
             "unexpected error: {err}"
         );
+        assert!(
+            err.contains("declare `fails HardwareFault`"),
+            "diagnostic should mention how to fix it: {err}"
+        );
     }
 
     #[test]
-    fn diagnostic_includes_source_location() {
-        // When the `pure` fn has a real span, the error prefix is
-        // `<path>:<line>:<col>: …` — matching RES-080 / RES-191
-        // conventions so IDEs can anchor the message.
-        let src = "io   fn noisy(int x) { return x; }\n\
-                   pure fn caller(int x) { return noisy(x); }\n";
-        let s = stmts(src);
-        let err = check_program_effects(&s, "<t>").unwrap_err();
-        assert!(err.contains("<t>:"), "missing source path: {err}");
-        assert!(err.contains(":2:"), "missing line number: {err}");
-    }
-
-    // RES-385c: linear parameter effect-system interaction tests.
-
-    #[test]
-    fn pure_with_linear_parameter_rejected_on_consumption() {
-        let src = "fn consume(linear int x) { return 0; }\n\
-                   pure fn bad(linear int x) { return consume(x); }\n";
-        let s = stmts(src);
-        let err = check_program_effects(&s, "<t>").expect_err("pure+linear consumed should fail");
+    fn missing_one_of_many_fails_is_rejected() {
+        // Caller propagates one variant but not the other — must
+        // still be rejected for the missing one.
+        let src = "\
+            fn inner(int x) fails A, B { return x; }\n\
+            fn caller(int y) fails A { return inner(y); }\n";
+        let err = check(src).expect_err("partial propagation must be rejected");
         assert!(
-            err.contains("cannot consume linear parameter `x` in pure context"),
+            err.contains("unhandled failure variant B"),
             "unexpected error: {err}"
         );
     }
 
     #[test]
-    fn pure_with_linear_parameter_accepted_on_no_consumption() {
-        let src = "pure fn ok(linear int x) { return 0; }\n";
-        let s = stmts(src);
-        check_program_effects(&s, "<t>").expect("pure+linear not consumed should pass");
+    fn fails_free_call_is_still_accepted() {
+        // Regression: a call to a fn with no `fails` set must remain
+        // allowed from anywhere, with or without enclosing fault scope.
+        let src = "\
+            fn inner(int x) { return x; }\n\
+            fn caller(int y) { return inner(y); }\n";
+        check(src).expect("fails-free propagation path must stay green");
     }
 
     #[test]
-    fn io_with_linear_parameter_accepted_on_consumption() {
-        let src = "fn consume(linear int x) { return 0; }\n\
-                   io fn ok(linear int x) { return consume(x); }\n";
-        let s = stmts(src);
-        check_program_effects(&s, "<t>").expect("io+linear consumed should pass");
+    fn top_level_call_to_failing_fn_is_rejected() {
+        // No enclosing fn — cannot propagate, must be rejected.
+        let src = "\
+            fn inner(int x) fails Timeout { return x; }\n\
+            let x = inner(1);\n";
+        let err = check(src).expect_err("top-level must reject failing call");
+        assert!(
+            err.contains("unhandled failure variant Timeout"),
+            "unexpected error: {err}"
+        );
     }
 }
 
+// ============================================================
+// RES-340: rich type-mismatch diagnostics
+// ============================================================
+//
+// Default behaviour is byte-identical to before the ticket — the
+// short `Type mismatch in argument N: expected X, got Y` form is
+// what the typechecker still emits unless the user opts in via
+// `RESILIENT_RICH_DIAG=1`. The opt-in produces a rustc-style
+// multi-block diagnostic with a primary span on the offending
+// argument and a secondary span on the function's declaration.
+//
+// These tests exercise the renderer helper directly so they don't
+// depend on (and cannot perturb) the global environment variable
+// other tests might be reading. End-to-end coverage of the gate
+// itself lives in the `legacy_default_remains` test below: it
+// scrubs the env var locally, then asserts the legacy text comes
+// back through the full pipeline.
 #[cfg(test)]
-mod purity_tests {
+mod res340_rich_type_mismatch_tests {
     use super::*;
     use crate::parse;
+    use crate::span::Pos;
 
-    /// Pull the statement list out of a parsed program.
-    fn stmts(src: &str) -> Vec<crate::span::Spanned<Node>> {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        match prog {
-            Node::Program(s) => s,
-            other => panic!("expected Program, got {:?}", other),
-        }
-    }
-
-    // ---------- AC: success ----------
-
-    #[test]
-    fn pure_fn_calling_only_arithmetic_passes() {
-        let src = "@pure fn double(int x) { return x * 2; }\n";
-        let s = stmts(src);
-        check_program_purity(&s, "<t>").expect("should pass");
+    fn span(start_line: usize, start_col: usize, end_line: usize, end_col: usize) -> Span {
+        Span::new(
+            Pos::new(start_line, start_col, 0),
+            Pos::new(end_line, end_col, 0),
+        )
     }
 
-    #[test]
-    fn pure_fn_calling_pure_builtin_passes() {
-        let src = "@pure fn f(int x) { return abs(x); }\n";
-        let s = stmts(src);
-        check_program_purity(&s, "<t>").expect("abs is pure");
+    fn check(src: &str) -> Result<(), String> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let mut tc = TypeChecker::new();
+        tc.check_program_with_source(&prog, "<t>").map(|_| ())
     }
 
     #[test]
-    fn pure_fn_with_struct_construction_passes() {
+    fn legacy_default_remains_byte_identical() {
+        // Without the env var, the short form is what the
+        // typechecker emits. Goldens depend on this — the test
+        // pins the exact substring so a regression here would
+        // also be a regression in any `.expected.txt` that
+        // captured the line.
+        //
+        // We deliberately do NOT touch `RESILIENT_RICH_DIAG`
+        // here — both removing and setting it would race against
+        // any concurrent test that reads it. Instead we trust
+        // that `cargo test` runs in a clean process where the
+        // var is unset by default; if a contributor exports it
+        // in their shell, the assertion below trips and tells
+        // them to unset it before running tests, which is the
+        // correct invariant.
         let src = "\
-            struct Point { int x, int y }\n\
-            @pure fn make(int a, int b) { return new Point { x: a, y: b }; }\n";
-        let s = stmts(src);
-        check_program_purity(&s, "<t>").expect("struct construction is pure");
+            fn drive(int dist) { return dist; }\n\
+            fn caller() { return drive(\"oops\"); }\n";
+        let err = check(src).expect_err("call with wrong arg type must be rejected");
+        if std::env::var("RESILIENT_RICH_DIAG").as_deref() == Ok("1") {
+            // Contributor has the rich-diag flag exported in
+            // their shell. Verify the rich path instead — both
+            // formats are correct, just different.
+            assert!(
+                err.contains("error[E0007]: type mismatch"),
+                "rich path must produce the rustc-style header: {err}"
+            );
+            return;
+        }
+        assert!(
+            err.contains("Type mismatch in argument 1: expected int, got string"),
+            "default format must remain the legacy short message; got: {err}"
+        );
+        // The rich block markers must NOT appear by default.
+        assert!(
+            !err.contains("error[E0007]"),
+            "rich format leaked into default output: {err}"
+        );
     }
 
-    // ---------- AC: impure builtin ----------
-
     #[test]
-    fn pure_fn_calling_println_is_rejected() {
-        let src = "@pure fn f(int x) { println(\"hi\"); return x; }\n";
-        let s = stmts(src);
-        let err = check_program_purity(&s, "<t>").expect_err("println is impure");
+    fn rich_helper_includes_primary_and_secondary_spans() {
+        // Construct a synthetic source whose layout we control,
+        // then ask the renderer for the rich block. We use a
+        // tempfile so the helper can read it back.
+        let src = "fn drive(int dist) { return dist; }\nlet r = drive(\"oops\");\n";
+        let dir = std::env::temp_dir().join("res340_rich_helper");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("rich.rz");
+        std::fs::write(&path, src).expect("write tempfile");
+        // Primary on the bad argument, secondary on the fn decl.
+        let arg_span = span(2, 15, 2, 21); // "oops" string literal
+        let decl_span = Some(span(1, 1, 1, 3)); // "fn"
+        let out = render_rich_arg_type_mismatch(
+            path.to_str().unwrap(),
+            arg_span,
+            decl_span,
+            1,
+            "int",
+            "string",
+        );
+        assert!(
+            out.contains(
+                "error[E0007]: type mismatch in argument 1: expected `int`, found `string`"
+            ),
+            "rich header missing or wrong: {out}"
+        );
+        assert!(
+            out.contains("note: expected `int` because of this declaration"),
+            "secondary label missing: {out}"
+        );
+        // Both source lines should appear (primary + note snippet).
+        assert!(
+            out.contains("let r = drive(\"oops\")"),
+            "primary snippet missing: {out}"
+        );
         assert!(
-            err.contains("calls impure builtin `println`"),
-            "unexpected error: {err}"
+            out.contains("fn drive(int dist)"),
+            "declaration snippet missing: {out}"
         );
+        // Carets must underline both spans.
+        assert!(
+            out.matches('^').count() >= 2,
+            "expected both spans to be underlined: {out}"
+        );
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn pure_fn_calling_clock_ms_is_rejected() {
-        let src = "@pure fn f() { return clock_ms(); }\n";
-        let s = stmts(src);
-        let err = check_program_purity(&s, "<t>").expect_err("clock_ms is impure");
-        assert!(err.contains("clock_ms"), "unexpected error: {err}");
-    }
-
-    #[test]
-    fn pure_fn_calling_file_read_is_rejected() {
-        let src = "@pure fn f() { return file_read(\"x\"); }\n";
-        let s = stmts(src);
-        let err = check_program_purity(&s, "<t>").expect_err("file_read is impure");
-        assert!(err.contains("file_read"), "unexpected error: {err}");
+    fn rich_helper_drops_secondary_when_decl_unknown() {
+        // Calls through a function value (no Identifier callee) —
+        // we have no fn declaration span, so the secondary block
+        // is omitted but the primary is still rich.
+        let src = "let f = drive;\nf(\"oops\");\n";
+        let dir = std::env::temp_dir().join("res340_no_decl");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("nodecl.rz");
+        std::fs::write(&path, src).expect("write tempfile");
+        let out = render_rich_arg_type_mismatch(
+            path.to_str().unwrap(),
+            span(2, 3, 2, 9),
+            None,
+            1,
+            "int",
+            "string",
+        );
+        assert!(
+            out.contains("error[E0007]: type mismatch in argument 1"),
+            "primary header missing: {out}"
+        );
+        assert!(
+            !out.contains("note: expected"),
+            "secondary block leaked when no decl span was passed: {out}"
+        );
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
-    // ---------- AC: impure user-fn call ----------
-
     #[test]
-    fn pure_fn_calling_unannotated_user_fn_is_rejected() {
-        let src = "\
-            fn helper(int x) { return x + 1; }\n\
-            @pure fn f(int x) { return helper(x); }\n";
-        let s = stmts(src);
-        let err = check_program_purity(&s, "<t>").expect_err("unannotated user fn is rejected");
+    fn rich_helper_falls_back_safely_when_source_missing() {
+        // Empty source path → renderer falls back to a no-snippet
+        // diagnostic. Must still include the header so a user
+        // reading the LSP / REPL output sees something useful.
+        let out = render_rich_arg_type_mismatch(
+            "",
+            span(1, 1, 1, 5),
+            Some(span(2, 1, 2, 3)),
+            2,
+            "Meters",
+            "Seconds",
+        );
         assert!(
-            err.contains("calls unannotated fn `helper`"),
-            "unexpected error: {err}"
+            out.contains(
+                "error[E0007]: type mismatch in argument 2: expected `Meters`, found `Seconds`"
+            ),
+            "header missing on no-source path: {out}"
+        );
+        assert!(
+            out.contains("note: expected `Meters` because of this declaration"),
+            "note missing on no-source path: {out}"
         );
     }
 
-    // ---------- AC: mutual recursion between two @pure fns ----------
-
-    #[test]
-    fn two_mutually_recursive_pure_fns_pass() {
-        let src = "\
-            @pure fn a(int n) { return b(n); }\n\
-            @pure fn b(int n) { return a(n); }\n";
-        let s = stmts(src);
-        // Only the purity pass — the main typechecker rejects
-        // forward refs today (orthogonal limitation, see
-        // typechecker.rs `pre-pass` comment at line ~800). The
-        // purity pass itself correctly handles mutual recursion
-        // because the optimistic first pass populates `pure_fns`
-        // with both names.
-        check_program_purity(&s, "<t>").expect("mutual recursion between two @pure fns is fine");
+    /// RES-4115: shared assertion for the four new gated helpers —
+    /// default output stays byte-identical to the legacy message
+    /// (no bracketed code), and `RESILIENT_RICH_DIAG=1` attaches the
+    /// code. Mirrors `legacy_default_remains_byte_identical`'s
+    /// don't-touch-the-env-var-mid-test discipline: check whichever
+    /// branch the ambient environment is actually in.
+    fn assert_gated_code(out: &str, legacy_substr: &str, code: &str) {
+        if std::env::var("RESILIENT_RICH_DIAG").as_deref() == Ok("1") {
+            assert!(
+                out.contains(code),
+                "rich path must include code {code}: {out}"
+            );
+        } else {
+            assert!(
+                out.contains(legacy_substr),
+                "default format must remain the legacy message; got: {out}"
+            );
+            assert!(
+                !out.contains(code),
+                "code {code} leaked into default output: {out}"
+            );
+        }
     }
 
     #[test]
-    fn pure_fn_calling_live_block_is_rejected() {
-        // `live` blocks retry on failure — observable from outside.
-        let src = "@pure fn f(int x) { live { return x; } return 0; }\n";
-        let s = stmts(src);
-        let err = check_program_purity(&s, "<t>").expect_err("live blocks are impure");
-        assert!(err.contains("live"), "unexpected error: {err}");
+    fn duplicate_fn_error_carries_e0011_when_gated() {
+        let out = render_duplicate_fn_error("<t>", span(2, 1, 2, 3), "foo", span(1, 1, 1, 3));
+        assert_gated_code(&out, "duplicate function name `foo`", "E0011");
     }
 
     #[test]
-    fn unannotated_fn_is_not_checked_for_purity() {
-        // Non-@pure fns are free to do anything; the checker must
-        // leave them alone even if they'd violate purity.
-        let src = "fn noisy() { println(\"hi\"); return 0; }\n";
-        let s = stmts(src);
-        check_program_purity(&s, "<t>").expect("non-@pure fns bypass the purity checker");
+    fn missing_return_error_carries_e0013_when_gated() {
+        let out = render_missing_return_error("f", &Type::Int);
+        assert_gated_code(&out, "missing return on at least one path", "E0013");
     }
 
-    // ---------- error message shape ----------
-
     #[test]
-    fn error_mentions_fn_name_and_violating_site() {
-        let src = "@pure fn noisy(int x) { println(\"hi\"); return x; }\n";
-        let s = stmts(src);
-        let err = check_program_purity(&s, "<t>").unwrap_err();
-        assert!(err.contains("noisy"), "expected fn name in error: {err}");
-        assert!(
-            err.contains("println"),
-            "expected callee name in error: {err}"
-        );
+    fn unknown_struct_field_error_carries_e0017_when_gated() {
+        let out = render_unknown_struct_field_error("Point", "z", "", "x, y");
+        assert_gated_code(&out, "struct `Point` has no field `z`", "E0017");
     }
 
     #[test]
-    fn error_carries_file_path_and_position() {
-        let src = "@pure fn noisy() { println(\"hi\"); return 0; }\n";
-        let s = stmts(src);
-        let err = check_program_purity(&s, "src/thing.rs").unwrap_err();
-        assert!(
-            err.starts_with("src/thing.rs:"),
-            "expected RES-080 prefix `<path>:<line>:<col>:`, got: {err}"
-        );
+    fn impure_builtin_error_carries_e0020_when_gated() {
+        let out = render_impure_builtin_error("println");
+        assert_gated_code(&out, "calls impure builtin `println`", "E0020");
     }
 
-    // ---------- RES-192: IO-effect inference ----------
-
-    /// AC: a chain `caller -> helper -> println` has IO at every
-    /// level.
     #[test]
-    fn effect_chain_propagates_io_transitively() {
-        let src = "\
-            fn helper() { println(\"hi\"); return 0; }\n\
-            fn caller() { return helper(); }\n\
-            fn top() { return caller(); }\n";
-        let s = stmts(src);
-        let eff = infer_fn_effects(&s);
-        assert_eq!(eff.get("helper"), Some(&true), "helper should be IO");
-        assert_eq!(eff.get("caller"), Some(&true), "caller should be IO");
-        assert_eq!(eff.get("top"), Some(&true), "top should be IO");
+    fn io_from_pure_context_error_carries_e0020_when_gated() {
+        let out = render_io_from_pure_context_error("println");
+        assert_gated_code(
+            &out,
+            "cannot call io function `println` from pure context",
+            "E0020",
+        );
     }
 
-    /// AC: a leaf fn that only does arithmetic is tagged pure.
     #[test]
-    fn arithmetic_only_leaf_is_pure() {
-        let src = "fn double(int x) { return x * 2; }\n";
-        let s = stmts(src);
-        let eff = infer_fn_effects(&s);
-        assert_eq!(eff.get("double"), Some(&false), "pure arithmetic");
+    fn arity_mismatch_error_carries_e0006_when_gated() {
+        let out = render_arity_mismatch_error(2, 1);
+        assert_gated_code(&out, "Expected 2 arguments, got 1", "E0006");
     }
 
     #[test]
-    fn fixpoint_handles_mutual_recursion() {
-        // Two fns that call each other but neither does IO.
-        let src = "\
-            fn a(int n) { return b(n); }\n\
-            fn b(int n) { return a(n); }\n";
-        let s = stmts(src);
-        let eff = infer_fn_effects(&s);
-        assert_eq!(eff.get("a"), Some(&false), "pure mutual recursion");
-        assert_eq!(eff.get("b"), Some(&false));
+    fn div_by_zero_error_carries_e0008_when_gated() {
+        let out = render_div_by_zero_error("division");
+        assert_gated_code(
+            &out,
+            "integer division by zero — denominator is a compile-time constant 0",
+            "E0008",
+        );
     }
 
     #[test]
-    fn io_reaches_through_mutual_recursion() {
-        let src = "\
-            fn a(int n) { return b(n); }\n\
-            fn b(int n) { println(\"x\"); return a(n); }\n";
-        let s = stmts(src);
-        let eff = infer_fn_effects(&s);
-        assert_eq!(eff.get("a"), Some(&true), "a reaches IO via b");
-        assert_eq!(eff.get("b"), Some(&true));
+    fn undefined_variable_error_carries_e0004_when_gated_with_span() {
+        let out = render_undefined_variable_error("foo", 3, 5, "");
+        assert_gated_code(&out, "Undefined variable 'foo' at 3:5", "E0004");
     }
 
     #[test]
-    fn file_io_builtins_flag_io() {
-        let src = "\
-            fn writer() { file_write(\"f\", \"data\"); return 0; }\n\
-            fn reader() { return file_read(\"f\"); }\n";
-        let s = stmts(src);
-        let eff = infer_fn_effects(&s);
-        assert_eq!(eff.get("writer"), Some(&true));
-        assert_eq!(eff.get("reader"), Some(&true));
+    fn undefined_variable_error_carries_e0004_when_gated_without_span() {
+        let out = render_undefined_variable_error("foo", 0, 0, " (did you mean `bar`?)");
+        assert_gated_code(
+            &out,
+            "Undefined variable: foo (did you mean `bar`?)",
+            "E0004",
+        );
     }
 
     #[test]
-    fn clock_and_random_flag_io() {
-        // Nondeterminism counts as IO per the broader "impure
-        // builtin" policy inherited from RES-191.
-        let src = "\
-            fn now() { return clock_ms(); }\n\
-            fn rand() { return random_int(0, 10); }\n";
-        let s = stmts(src);
-        let eff = infer_fn_effects(&s);
-        assert_eq!(eff.get("now"), Some(&true));
-        assert_eq!(eff.get("rand"), Some(&true));
+    fn fn_decl_span_table_is_populated_for_top_level_fns() {
+        // Internal invariant: the pre-pass that fills
+        // `contract_table` also fills `fn_decl_spans` keyed by fn
+        // name. The rich-diag path depends on this; failing here
+        // would silently degrade error quality.
+        let src = "fn drive(int dist) { return dist; }\nfn caller() { return 0; }\n";
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let mut tc = TypeChecker::new();
+        let _ = tc.check_program_with_source(&prog, "<t>");
+        assert!(
+            tc.fn_decl_spans.contains_key("drive"),
+            "fn_decl_spans missing entry for drive: {:?}",
+            tc.fn_decl_spans.keys().collect::<Vec<_>>()
+        );
+        assert!(
+            tc.fn_decl_spans.contains_key("caller"),
+            "fn_decl_spans missing entry for caller: {:?}",
+            tc.fn_decl_spans.keys().collect::<Vec<_>>()
+        );
     }
+}
 
-    #[test]
-    fn pure_builtin_calls_stay_pure() {
-        let src = "\
-            fn compute(int x) { return abs(x); }\n\
-            fn compose(int x) { let y = compute(x); return min(y, 10); }\n";
-        let s = stmts(src);
-        let eff = infer_fn_effects(&s);
-        assert_eq!(eff.get("compute"), Some(&false), "abs is pure");
-        assert_eq!(eff.get("compose"), Some(&false));
+// RES-402: tests for the array-literal mixed-element-type rejection.
+#[cfg(test)]
+mod res402_polymorphic_array_tests {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn check(src: &str) -> Result<(), String> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new().check_program(&prog).map(|_| ())
     }
 
     #[test]
-    fn live_block_alone_is_not_io() {
-        // A live block with a pure body is still pure. The ticket
-        // tracks "reach println or file_*"; retries alone don't
-        // qualify.
-        let src = "fn f(int x) { live { return x; } return 0; }\n";
-        let s = stmts(src);
-        let eff = infer_fn_effects(&s);
-        assert_eq!(eff.get("f"), Some(&false), "pure live body");
+    fn homogeneous_int_array_passes() {
+        check("let xs = [1, 2, 3];").expect("homogeneous Int literal should typecheck");
     }
 
     #[test]
-    fn live_block_with_io_body_is_io() {
-        let src = "fn f(int x) { live { println(\"r\"); } return x; }\n";
-        let s = stmts(src);
-        let eff = infer_fn_effects(&s);
-        assert_eq!(eff.get("f"), Some(&true));
+    fn homogeneous_string_array_passes() {
+        check("let xs = [\"a\", \"b\", \"c\"];")
+            .expect("homogeneous String literal should typecheck");
     }
 
     #[test]
-    fn empty_program_produces_empty_effects() {
-        let s = stmts("");
-        assert!(infer_fn_effects(&s).is_empty());
+    fn empty_array_passes() {
+        check("let xs = [];").expect("empty array literal should typecheck");
     }
 
-    // ---------- RES-385: linear-type single-use enforcement ----------
-
-    /// AC: passing a `linear` parameter to one consumer is fine.
-    /// Passing it to a second consumer is a single-use violation.
     #[test]
-    fn linear_value_used_twice_is_rejected() {
-        let src = "\
-            fn consume(linear FileHandle fh) { return 0; }\n\
-            fn bad(linear FileHandle fh) {\n\
-                consume(fh);\n\
-                consume(fh);\n\
-                return 0;\n\
-            }\n";
-        let (program, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {errs:?}");
-        let mut tc = TypeChecker::new();
-        let err = tc
-            .check_program_with_source(&program, "<t>")
-            .expect_err("second use of linear fh must be rejected");
+    fn mixed_int_and_string_is_rejected() {
+        let err =
+            check("let xs = [1, \"two\"];").expect_err("mixed Int / String should be rejected");
         assert!(
-            err.contains("linear-use"),
-            "expected `linear-use` diagnostic tag, got: {err}"
+            err.contains("mixed element types"),
+            "diagnostic missing 'mixed element types': {}",
+            err
         );
+        assert!(err.contains("int"), "diagnostic missing int type: {}", err);
         assert!(
-            err.contains("used after move"),
-            "expected `used after move` in message, got: {err}"
+            err.contains("string"),
+            "diagnostic missing string type: {}",
+            err
         );
+    }
+
+    #[test]
+    fn mixed_int_and_float_is_rejected() {
+        // Resilient already rejects Int + Float arithmetic — array
+        // literals follow the same no-implicit-coercion rule.
+        let err = check("let xs = [1, 2.0];").expect_err("mixed Int / Float should be rejected");
         assert!(
-            err.contains("fh"),
-            "expected binding name in message, got: {err}"
+            err.contains("mixed element types"),
+            "diagnostic missing 'mixed element types': {}",
+            err
         );
     }
 
-    /// AC: consuming exactly once (via a call or `drop`) is fine.
     #[test]
-    fn linear_value_used_once_is_accepted() {
-        let src = "\
-            fn consume(linear FileHandle fh) { return 0; }\n\
-            fn good(linear FileHandle fh) {\n\
-                consume(fh);\n\
-                return 0;\n\
-            }\n\
-            fn dropper(linear FileHandle fh) {\n\
-                drop(fh);\n\
-                return 0;\n\
-            }\n";
-        let (program, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {errs:?}");
-        let mut tc = TypeChecker::new();
-        tc.check_program_with_source(&program, "<t>")
-            .expect("one consumption (direct call or drop) must typecheck");
+    fn actor_rejects_reference_state_type() {
+        // RES-777: actor state fields cannot have reference types.
+        // References would create aliases across actor boundaries,
+        // enabling data races despite the actor model's isolation.
+        let err = check(
+            "
+            actor TestActor {
+                state: &int = 0;
+                receive handle() {}
+            }
+            ",
+        )
+        .expect_err("reference-typed actor state should be rejected");
+        assert!(
+            err.contains("has reference type"),
+            "diagnostic missing 'has reference type': {}",
+            err
+        );
+        assert!(
+            err.contains("actor boundaries require ownership-by-value"),
+            "diagnostic missing 'actor boundaries require ownership-by-value': {}",
+            err
+        );
     }
 
-    // ---------- RES-4112: `#[overflow_checked]` BV64 wiring ----------
-    //
-    // `verifier_z3::prove_overflow_safe` (RES-4014 / C-E3) proves a
-    // requires/ensures clause under width-respecting BV64 arithmetic
-    // instead of unbounded LIA — sound for Resilient's `Int64` +
-    // `OverflowMode::Wrap` runtime semantics. These tests exercise the
-    // wiring into the per-fn requires/ensures static pass in
-    // `Node::Function` handling: `#[overflow_checked]` opts a fn in;
-    // without it, only the (unsound-for-overflow) LIA tautology check
-    // runs, exactly as before this ticket.
-    //
-    // Only compiled with `--features z3` — without Z3 the whole static
-    // fold/Z3 pass is a no-op and none of this applies.
-    #[cfg(feature = "z3")]
-    mod overflow_checked_tests {
-        use super::*;
-
-        /// `#[overflow_checked]` records into the process-wide
-        /// `feature_attrs` registry during parsing and is read back
-        /// during `check_program_with_source`. Serialize against the
-        /// binary-wide test lock and reset before/after so this
-        /// module's tests can't race other `feature_attrs`-mutating
-        /// tests running in parallel (see `feature_attrs::lock_for_test`).
-        fn typecheck(src: &str) -> Result<(), String> {
-            let _g = crate::feature_attrs::lock_for_test();
-            crate::feature_attrs::reset();
-            let (program, errs) = crate::parse(src);
-            assert!(errs.is_empty(), "parse errors: {errs:?}");
-            let mut tc = TypeChecker::new();
-            let result = tc.check_program_with_source(&program, "<t>").map(|_| ());
-            crate::feature_attrs::reset();
-            result
-        }
-
-        // NOTE ON SHAPE: the RES-060 static-fold loop in
-        // `Node::Function` handling proves each `requires`/`ensures`
-        // clause *standalone* — it does not thread sibling clauses in
-        // as axioms (that threading only happens for the separate
-        // `recovers_to` obligation). So every fixture below needs a
-        // clause that is a LIA tautology *on its own*, not one that
-        // only holds given other requires. `x + K > x` for a fixed
-        // positive `K` is exactly that: true for every mathematical
-        // integer `x`, but false once `x` is close enough to
-        // `i64::MAX` for `x + K` to wrap.
-
-        /// Baseline (no attribute): `x + 1 > x` is a LIA tautology, so
-        /// the existing (opt-out) path discharges it and compilation
-        /// succeeds even though it wraps at `x == i64::MAX`. Confirms
-        /// `#[overflow_checked]` is genuinely opt-in.
-        #[test]
-        fn without_attribute_wraparound_prone_add_still_compiles() {
-            let src = "fn f(int x) ensures x + 1 > x { return x; }\n";
-            typecheck(src).expect("LIA tautology discharges without the attribute");
-        }
-
-        /// Same clause, `#[overflow_checked]`: LIA proves `x + 1 > x`
-        /// a tautology, but BV64 finds `x == i64::MAX` as a
-        /// counterexample (wraps to `i64::MIN`, which is not `> x`) —
-        /// must be a hard compile error distinguishing the two
-        /// verdicts.
-        #[test]
-        fn overflow_checked_rejects_wraparound_prone_add() {
-            let src = "#[overflow_checked]\nfn f(int x) ensures x + 1 > x { return x; }\n";
-            let err = typecheck(src).expect_err("BV64 must reject the wraparound-prone add");
-            assert!(
-                err.contains("LIA said safe, BV64 disproved it"),
-                "diagnostic must distinguish LIA-vs-BV64 verdicts, got: {err}"
-            );
-            assert!(
-                err.contains("counterexample"),
-                "diagnostic must carry a BV64 counterexample, got: {err}"
-            );
-        }
-
-        /// Subtraction variant: `x - 1 < x` is a LIA tautology but
-        /// wraps at `x == i64::MIN` (`x - 1` wraps to `i64::MAX`,
-        /// which is not `< x`).
-        #[test]
-        fn overflow_checked_rejects_wraparound_prone_subtraction() {
-            let src = "#[overflow_checked]\nfn f(int x) ensures x - 1 < x { return x; }\n";
-            let err = typecheck(src).expect_err("BV64 must reject the wraparound-prone subtract");
-            assert!(
-                err.contains("LIA said safe, BV64 disproved it"),
-                "got: {err}"
-            );
-        }
-
-        /// `requires` clauses (not just `ensures`) go through the same
-        /// per-clause loop, so the attribute must catch a
-        /// wraparound-prone `requires` too.
-        #[test]
-        fn overflow_checked_rejects_wraparound_prone_requires() {
-            let src = "#[overflow_checked]\nfn f(int x) requires x + 1 > x { return x; }\n";
-            let err = typecheck(src).expect_err("BV64 must reject the wraparound-prone requires");
-            assert!(
-                err.contains("LIA said safe, BV64 disproved it"),
-                "got: {err}"
-            );
-        }
-
-        /// Multiple wraparound-prone clauses on the same fn: the loop
-        /// walks `requires` then `ensures` in order and must reject at
-        /// the first one it reaches (`requires`), not silently skip
-        /// past it to `ensures`.
-        #[test]
-        fn overflow_checked_rejects_first_of_several_bad_clauses() {
-            let src = "#[overflow_checked]\n\
-                       fn f(int x) requires x + 1 > x ensures x - 1 < x { return x; }\n";
-            let err = typecheck(src).expect_err("first wraparound-prone clause must be rejected");
-            assert!(
-                err.contains("LIA said safe, BV64 disproved it"),
-                "got: {err}"
-            );
-        }
+    #[test]
+    fn actor_rejects_reference_handler_parameter() {
+        // RES-777: actor handler parameters cannot have reference types
+        // for the same reason as state fields.
+        let err = check(
+            "
+            actor TestActor {
+                state: int = 0;
+                receive handle(&int x) {}
+            }
+            ",
+        )
+        .expect_err("reference-typed handler parameter should be rejected");
+        assert!(
+            err.contains("has reference type"),
+            "diagnostic missing 'has reference type': {}",
+            err
+        );
+        assert!(
+            err.contains("actor boundaries require ownership-by-value"),
+            "diagnostic missing 'actor boundaries require ownership-by-value': {}",
+            err
+        );
+    }
+}
 
-        /// Adding zero can never overflow — the attribute must not
-        /// blanket-reject every arithmetic contract, only ones that
-        /// actually wrap.
-        #[test]
-        fn overflow_checked_accepts_safe_additive_identity() {
-            let src = "#[overflow_checked]\nfn f(int x) ensures x + 0 == x { return x; }\n";
-            typecheck(src).expect("x + 0 == x must verify under BV64 (never wraps)");
-        }
+// RES-1104..RES-1106 + RES-1112 + RES-1113: regression suite for the
+// typechecker scope / reachability fixes shipped together. Each test
+// uses `parse` + `TypeChecker::check_program` so it exercises the
+// real driver path. A bug introduced into any of these fixes must
+// surface here before reaching CI.
+#[cfg(test)]
+mod scope_and_reachability_tests {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
 
-        /// A goal with no arithmetic at all is trivially safe under
-        /// both theories.
-        #[test]
-        fn overflow_checked_accepts_non_arithmetic_contract() {
-            let src =
-                "#[overflow_checked]\nfn f(int x) requires x >= 0 ensures x >= 0 { return x; }\n";
-            typecheck(src).expect("non-arithmetic contract must verify under BV64");
-        }
+    fn check(src: &str) -> Result<(), String> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new().check_program(&prog).map(|_| ())
+    }
 
-        /// Unknown-tolerant: a `forall`/`exists` quantifier clause is
-        /// handled by the LIA path (`quantifiers::z3_encode`) but has
-        /// no BV64 encoding (`translate_bool_bv_width` has no
-        /// `Node::Quantifier` arm) — `prove_overflow_safe` returns
-        /// `None` (undecided), which must be silently tolerated
-        /// (matches the existing timeout/Unknown soft-failure policy)
-        /// rather than treated as a rejection.
-        #[test]
-        fn overflow_checked_tolerates_unsupported_quantifier_clause() {
-            let src = "#[overflow_checked]\n\
-                       fn f(int x) ensures forall i in 0..1: true { return x; }\n";
-            typecheck(src)
-                .expect("BV64 Unknown/unsupported-construct verdict must not fail compilation");
-        }
+    // --- RES-1104: for-in loop variable binding ------------------------------
 
-        /// Two `#[overflow_checked]` fns in one module: the attribute
-        /// is per-fn (keyed by name in the `feature_attrs` registry),
-        /// so the unchecked sibling keeps the old (opt-out) behavior
-        /// while the annotated one is held to the stricter standard.
-        #[test]
-        fn overflow_checked_is_per_function_not_module_wide() {
-            let src = "fn unchecked(int x) ensures x + 1 > x { return x; }\n\
-                       #[overflow_checked]\n\
-                       fn checked(int x) ensures x + 1 > x { return x; }\n";
-            let err = typecheck(src).expect_err("the annotated sibling must still be rejected");
-            assert!(
-                err.contains("fn checked"),
-                "error must name the annotated fn, got: {err}"
-            );
-        }
+    #[test]
+    fn res1104_for_in_array_binds_loop_var() {
+        check(
+            "fn main() { \
+                let xs = [1, 2, 3]; \
+                let total = 0; \
+                for x in xs { total = total + x; } \
+            }",
+        )
+        .expect("for x in xs should bind x for the body");
+    }
 
-        /// A genuinely false clause (independent of overflow) must
-        /// keep reporting the pre-existing "statically false clause"
-        /// diagnostic, not the new BV64-specific one — the BV64 pass
-        /// only runs after the LIA path returns `Some(true)`.
-        #[test]
-        fn overflow_checked_does_not_change_plain_contradiction_diagnostic() {
-            let src = "#[overflow_checked]\n\
-                       fn f(int x) requires x > 0 && x < 0 { return x; }\n";
-            let err = typecheck(src).expect_err("plain contradiction must still be rejected");
-            assert!(err.contains("contract can never hold"), "got: {err}");
-            assert!(
-                !err.contains("LIA said safe"),
-                "plain LIA contradiction must not be mislabeled as a BV64 divergence, got: {err}"
-            );
-        }
+    #[test]
+    fn res1104_for_in_range_binds_loop_var() {
+        check(
+            "fn main() { \
+                let total = 0; \
+                for i in 0..5 { total = total + i; } \
+            }",
+        )
+        .expect("for i in 0..5 should bind i for the body");
     }
 
-    /// AC: `let fh: linear T = …;` binds a linear local whose double-
-    /// use is rejected just like a linear parameter.
     #[test]
-    fn linear_let_binding_rejects_double_use() {
-        // Construct the handle via a struct literal so the RHS type
-        // matches the let annotation without needing fancy
-        // inference. The linearity bit flows through `parse_type_name`
-        // via the shared `linear` prefix-stripping helper.
-        let src = "\
-            struct FileHandle { int fd }\n\
-            fn consume(linear FileHandle fh) { return 0; }\n\
-            fn main() {\n\
-                let fh: linear FileHandle = new FileHandle { fd: 3 };\n\
-                consume(fh);\n\
-                consume(fh);\n\
-                return 0;\n\
-            }\n";
-        let (program, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {errs:?}");
-        let mut tc = TypeChecker::new();
-        let err = tc
-            .check_program_with_source(&program, "<t>")
-            .expect_err("second use of let-bound linear must be rejected");
+    fn res1104_loop_var_does_not_leak_past_block() {
+        // After RES-1104 + RES-1111 the loop variable is confined to
+        // the for body's enclosed env, so referring to it after the
+        // loop is a clean "Undefined variable" diagnostic rather than
+        // accidentally typechecking against the leaked binding.
+        let err = check(
+            "fn main() { \
+                for x in [1, 2, 3] { } \
+                let y = x; \
+            }",
+        )
+        .expect_err("loop var should be undefined after the for body");
         assert!(
-            err.contains("linear-use"),
-            "expected `linear-use` diagnostic tag, got: {err}"
+            err.contains("Undefined variable 'x'"),
+            "expected undefined-x diagnostic, got: {err}"
         );
     }
 
-    /// AC: the diagnostic is prefixed with `<file>:<line>:<col>:`
-    /// so editors can jump straight to the offending second use.
+    // --- RES-1105: direct self-recursion -------------------------------------
+
     #[test]
-    fn linear_use_diagnostic_carries_source_position() {
-        let src = "\
-            fn consume(linear FileHandle fh) { return 0; }\n\
-            fn bad(linear FileHandle fh) {\n\
-                consume(fh);\n\
-                consume(fh);\n\
-                return 0;\n\
-            }\n";
-        let (program, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {errs:?}");
-        let mut tc = TypeChecker::new();
-        let err = tc
-            .check_program_with_source(&program, "src/bad.rz")
-            .expect_err("should fail");
-        assert!(
-            err.starts_with("src/bad.rz:"),
-            "expected <path>:<line>:<col>: prefix, got: {err}"
-        );
+    fn res1105_self_recursion_resolves_in_typechecker() {
+        check(
+            "fn fact(int n) -> int { \
+                if n <= 1 { return 1; } \
+                return n * fact(n - 1); \
+            }",
+        )
+        .expect("self-recursive fact should typecheck");
     }
 
+    // --- RES-1106: forward / mutual recursion --------------------------------
+
     #[test]
-    fn stats_field_populated_by_full_check() {
-        // End-to-end: running the typechecker with `audit_stats`
-        // opt-in populates `stats.fn_effects`. Confirms the gated
-        // call-site inside `check_program_with_source` is wired.
-        //
-        // RES-1322: `with_audit_stats(true)` is required — the
-        // fixpoint is off by default so non-audit callers don't pay
-        // for a result they never read.
-        let src = "\
-            fn noisy() { println(\"h\"); return 0; }\n\
-            fn quiet() { return 0; }\n\
-            fn main(int _d) { noisy(); return quiet(); }\n\
-            main(0);\n";
-        let (program, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {errs:?}");
-        let mut tc = TypeChecker::new().with_audit_stats(true);
-        tc.check_program_with_source(&program, "<t>")
-            .expect("typecheck should succeed");
-        assert_eq!(tc.stats.fn_effects.get("noisy"), Some(&true));
-        assert_eq!(tc.stats.fn_effects.get("quiet"), Some(&false));
-        assert_eq!(tc.stats.fn_effects.get("main"), Some(&true));
+    fn res1106_forward_call_resolves_in_typechecker() {
+        check(
+            "fn caller() -> int { return helper(); } \
+             fn helper() -> int { return 7; }",
+        )
+        .expect("forward fn references should typecheck");
     }
 
     #[test]
-    fn stats_field_empty_by_default() {
-        // RES-1322: without `with_audit_stats(true)` the fixpoint
-        // is skipped and `stats.fn_effects` stays empty. Locks in
-        // the opt-in default so a future regression that always-
-        // populates the map fails this test.
-        let src = "fn noisy() { println(\"h\"); return 0; }\n";
-        let (program, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {errs:?}");
-        let mut tc = TypeChecker::new();
-        tc.check_program_with_source(&program, "<t>")
-            .expect("typecheck should succeed");
+    fn res1106_mutual_recursion_resolves_in_typechecker() {
+        check(
+            "fn even(int n) -> bool { \
+                if n == 0 { return true; } \
+                return odd(n - 1); \
+            } \
+            fn odd(int n) -> bool { \
+                if n == 0 { return false; } \
+                return even(n - 1); \
+            }",
+        )
+        .expect("mutual recursion (even/odd) should typecheck");
+    }
+
+    #[test]
+    fn nested_fn_mutual_recursion_resolves_in_typechecker() {
+        check(
+            "fn main() -> int { \
+                fn ping(int n) -> int { \
+                    if n <= 0 { return 0; } \
+                    return pong(n - 1); \
+                } \
+                fn pong(int n) -> int { \
+                    if n <= 0 { return 1; } \
+                    return ping(n - 1); \
+                } \
+                return ping(5); \
+            }",
+        )
+        .expect("block-level fns should be hoisted like top-level ones");
+    }
+
+    #[test]
+    fn nested_fn_hoisting_keeps_declared_signature() {
+        let err = check(
+            "fn main() -> int { \
+                let s = later(1); \
+                fn later(int n) -> string { return \"x\"; } \
+                return s + 1; \
+            }",
+        )
+        .expect_err("hoisted return type should flow into the caller");
+        assert!(
+            err.contains("string") || err.contains("String"),
+            "expected string/int mismatch, got: {err}"
+        );
+    }
+
+    // --- RES-1112: missing return on non-void fn -----------------------------
+
+    #[test]
+    fn res1112_missing_return_rejects_if_without_else() {
+        let err = check(
+            "fn maybe(int n) -> int { \
+                if n > 0 { return 1; } \
+            }",
+        )
+        .expect_err("missing return on else path should be rejected");
         assert!(
-            tc.stats.fn_effects.is_empty(),
-            "fn_effects should be empty when audit_stats is off, got {:?}",
-            tc.stats.fn_effects
+            err.contains("missing return"),
+            "expected missing-return diagnostic, got: {err}"
         );
     }
-}
-
-#[cfg(test)]
-mod type_hole_display_tests {
-    use super::*;
-    use crate::span::{Pos, Span};
 
     #[test]
-    fn var_with_span_displays_as_type_hole() {
-        let pos = Pos::new(3, 7, 0);
-        let span = Span::point(pos);
-        let ty = Type::Var(0, Some(span));
-        assert_eq!(ty.to_string(), "type hole at 3:7");
+    fn res1112_both_branches_return_passes() {
+        check(
+            "fn pick(int n) -> int { \
+                if n > 0 { return 1; } else { return 0; } \
+            }",
+        )
+        .expect("both branches returning should typecheck");
     }
 
     #[test]
-    fn var_without_span_displays_as_qt() {
-        let ty = Type::Var(0, None);
-        assert_eq!(ty.to_string(), "?t0");
+    fn res1112_implicit_return_expression_passes() {
+        check("fn id(int x) -> int { x }").expect("implicit-return form should typecheck");
     }
-}
 
-/// RES-387: fault model — parser + typechecker slice for the `fails`
-/// annotation and `recovers_to` postcondition. Structured handlers
-/// and the Z3 proof obligation for `recovers_to` are separate tickets.
-#[cfg(test)]
-mod fault_model_tests {
-    use super::*;
-    use crate::parse;
+    #[test]
+    fn res1112_void_fn_can_fall_off_end() {
+        check("fn main() { let x = 1; }").expect("void fn need not return");
+    }
 
-    // ---------- Parser: `fails` / `recovers_to` ----------
+    #[test]
+    fn res1112_live_block_with_return_passes() {
+        // RES-1112: `live { ... return X; ... }` is recognised as a
+        // terminating construct so safety-critical examples using
+        // live-block retry still typecheck without spurious errors.
+        check(
+            "fn safe() -> int { \
+                live { \
+                    let x = 5; \
+                    return x; \
+                } \
+            }",
+        )
+        .expect("live { return X } should count as a terminating body");
+    }
 
     #[test]
-    fn parser_accepts_single_fails_variant() {
-        let src = "fn read_sensor(int addr) fails HardwareFault { return addr; }\n";
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        match prog {
-            Node::Program(stmts) => match &stmts[0].node {
-                Node::Function { name, fails, .. } => {
-                    assert_eq!(name, "read_sensor");
-                    assert_eq!(fails, &vec!["HardwareFault".to_string()]);
-                }
-                other => panic!("expected Function, got {:?}", other),
-            },
-            other => panic!("expected Program, got {:?}", other),
-        }
+    fn res3828_live_block_bool_invariant_passes() {
+        check(
+            "fn safe(int fuel) -> int { \
+                live invariant fuel >= 0 { \
+                    return fuel; \
+                } \
+            }",
+        )
+        .expect("boolean live invariant should typecheck");
     }
 
     #[test]
-    fn parser_accepts_multiple_fails_variants() {
-        let src = "fn write_sensor(int v) fails HardwareFault, Timeout { return v; }\n";
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        match prog {
-            Node::Program(stmts) => match &stmts[0].node {
-                Node::Function { fails, .. } => {
-                    assert_eq!(
-                        fails,
-                        &vec!["HardwareFault".to_string(), "Timeout".to_string()]
-                    );
-                }
-                other => panic!("expected Function, got {:?}", other),
-            },
-            other => panic!("expected Program, got {:?}", other),
-        }
+    fn res3828_live_block_non_bool_invariant_is_rejected() {
+        let err = check(
+            "fn unsafe_live(int fuel) -> int { \
+                live invariant 1 { \
+                    return fuel; \
+                } \
+            }",
+        )
+        .expect_err("non-bool live invariant should be rejected");
+        assert!(
+            err.contains("live block invariant must be Bool"),
+            "expected live invariant Bool diagnostic, got: {err}"
+        );
     }
 
     #[test]
-    fn parser_accepts_recovers_to_postcondition() {
-        let src = "fn write_sensor(int v) fails Timeout recovers_to: v >= 0; { return v; }\n";
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        match prog {
-            Node::Program(stmts) => match &stmts[0].node {
-                Node::Function {
-                    fails, recovers_to, ..
-                } => {
-                    assert_eq!(fails, &vec!["Timeout".to_string()]);
-                    assert!(
-                        recovers_to.is_some(),
-                        "expected recovers_to to be populated"
-                    );
-                }
-                other => panic!("expected Function, got {:?}", other),
-            },
-            other => panic!("expected Program, got {:?}", other),
-        }
+    fn infinite_loop_that_only_returns_passes() {
+        check(
+            "fn first_over(int n) -> int { \
+                loop { if n > 3 { return n; } n = n + 1; } \
+            }",
+        )
+        .expect("a loop with no break never falls off the end");
     }
 
     #[test]
-    fn parser_accepts_fails_after_requires() {
-        // requires, then fails — mirrors the ticket's example.
-        let src = "fn op(int x) requires x > 0 fails Bad { return x; }\n";
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        match prog {
-            Node::Program(stmts) => match &stmts[0].node {
-                Node::Function {
-                    fails, requires, ..
-                } => {
-                    assert_eq!(fails, &vec!["Bad".to_string()]);
-                    assert_eq!(requires.len(), 1);
-                }
-                other => panic!("expected Function, got {:?}", other),
-            },
-            other => panic!("expected Program, got {:?}", other),
-        }
+    fn infinite_loop_with_break_can_fall_off() {
+        check("fn f(int n) -> int { while true { if n > 3 { break; } return n; } }")
+            .expect_err("a break leaves the loop without a value");
     }
 
     #[test]
-    fn parser_fn_without_fails_has_empty_list() {
-        let src = "fn add(int a, int b) { return a + b; }\n";
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        match prog {
-            Node::Program(stmts) => match &stmts[0].node {
-                Node::Function {
-                    fails, recovers_to, ..
-                } => {
-                    assert!(fails.is_empty());
-                    assert!(recovers_to.is_none());
-                }
-                other => panic!("expected Function, got {:?}", other),
-            },
-            other => panic!("expected Program, got {:?}", other),
-        }
+    fn statement_after_infinite_loop_is_unreachable() {
+        let err = check("fn f() -> int { loop { return 1; } let x = 2; }")
+            .expect_err("nothing after a break-free loop runs");
+        assert!(err.contains("unreachable code"), "got: {err}");
     }
 
-    // ---------- Typechecker: propagation ----------
+    // --- RES-1113: unreachable code after return -----------------------------
 
-    fn check(src: &str) -> Result<(), String> {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let mut tc = TypeChecker::new();
-        tc.check_program_with_source(&prog, "<t>").map(|_| ())
+    #[test]
+    fn res1113_statement_after_return_rejected() {
+        let err = check(
+            "fn classify(int n) -> string { \
+                return \"a\"; \
+                return \"b\"; \
+            }",
+        )
+        .expect_err("unreachable return should be rejected");
+        assert!(
+            err.contains("unreachable code"),
+            "expected unreachable-code diagnostic, got: {err}"
+        );
     }
 
     #[test]
-    fn propagated_fails_is_accepted() {
-        // `caller` propagates `HardwareFault`, matching `inner`'s fails.
-        let src = "\
-            fn inner(int x) fails HardwareFault { return x; }\n\
-            fn caller(int y) fails HardwareFault { return inner(y); }\n";
-        check(src).expect("propagation should typecheck");
+    fn res1113_statement_after_return_in_main_rejected() {
+        let err = check(
+            "fn main() { \
+                return; \
+                let x = 1; \
+            }",
+        )
+        .expect_err("statement after bare return should be rejected");
+        assert!(
+            err.contains("unreachable code"),
+            "expected unreachable-code diagnostic, got: {err}"
+        );
     }
 
     #[test]
-    fn unhandled_fails_is_rejected() {
-        // `caller` does not declare `HardwareFault`; the call must
-        // be rejected with the MVP diagnostic.
-        let src = "\
-            fn inner(int x) fails HardwareFault { return x; }\n\
-            fn caller(int y) { return inner(y); }\n";
-        let err = check(src).expect_err("unhandled fails must be rejected");
+    fn res1113_conditional_return_does_not_make_rest_unreachable() {
+        // `if cond { return X; }` is NOT unconditional — the code
+        // following the if is reachable when cond is false.
+        check(
+            "fn main() { \
+                if true { return; } \
+                let x = 1; \
+            }",
+        )
+        .expect("conditional return must not flag subsequent code");
+    }
+
+    #[test]
+    fn res3887_unreachable_diagnostic_points_at_dead_code_not_terminator() {
+        // Real newlines so the two returns are on distinct lines. The
+        // diagnostic must point at line 3 (the dead code), not line 2
+        // (the live return whose span check_node last recorded).
+        let err = check("fn f() -> int {\n    return 1;\n    return 2;\n}")
+            .expect_err("unreachable return should be rejected");
         assert!(
-            err.contains("unhandled failure variant HardwareFault"),
-            "unexpected error: {err}"
+            err.contains("unreachable code"),
+            "expected unreachable-code diagnostic, got: {err}"
         );
         assert!(
-            err.contains("declare `fails HardwareFault`"),
-            "diagnostic should mention how to fix it: {err}"
+            err.contains(":3:"),
+            "diagnostic must point at line 3 (the dead code), got: {err}"
+        );
+        assert!(
+            !err.contains(":2:"),
+            "diagnostic must NOT point at line 2 (the live return), got: {err}"
         );
     }
+}
+
+#[cfg(test)]
+mod duplicate_detection_tests {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected typechecker error")
+    }
+
+    fn check_ok(src: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program(&prog)
+            .expect("unexpected typechecker error");
+    }
 
     #[test]
-    fn missing_one_of_many_fails_is_rejected() {
-        // Caller propagates one variant but not the other — must
-        // still be rejected for the missing one.
-        let src = "\
-            fn inner(int x) fails A, B { return x; }\n\
-            fn caller(int y) fails A { return inner(y); }\n";
-        let err = check(src).expect_err("partial propagation must be rejected");
+    fn duplicate_fn_name_is_rejected() {
+        let err = check_err("fn foo() {} fn foo() {}");
         assert!(
-            err.contains("unhandled failure variant B"),
-            "unexpected error: {err}"
+            err.contains("duplicate function name") && err.contains("`foo`"),
+            "unexpected error: {}",
+            err
         );
     }
 
     #[test]
-    fn fails_free_call_is_still_accepted() {
-        // Regression: a call to a fn with no `fails` set must remain
-        // allowed from anywhere, with or without enclosing fault scope.
-        let src = "\
-            fn inner(int x) { return x; }\n\
-            fn caller(int y) { return inner(y); }\n";
-        check(src).expect("fails-free propagation path must stay green");
+    fn unique_fn_names_pass() {
+        check_ok("fn foo(int x) -> int { return x; } fn bar(int x) -> int { return x; }");
     }
 
     #[test]
-    fn top_level_call_to_failing_fn_is_rejected() {
-        // No enclosing fn — cannot propagate, must be rejected.
-        let src = "\
-            fn inner(int x) fails Timeout { return x; }\n\
-            let x = inner(1);\n";
-        let err = check(src).expect_err("top-level must reject failing call");
+    fn duplicate_struct_field_is_rejected() {
+        let err = check_err("struct Point { int x, int x }");
         assert!(
-            err.contains("unhandled failure variant Timeout"),
-            "unexpected error: {err}"
+            err.contains("duplicate field") && err.contains("`x`"),
+            "unexpected error: {}",
+            err
         );
     }
+
+    #[test]
+    fn unique_struct_fields_pass() {
+        check_ok("struct Point { int x, int y }");
+    }
 }
 
-// ============================================================
-// RES-340: rich type-mismatch diagnostics
-// ============================================================
-//
-// Default behaviour is byte-identical to before the ticket — the
-// short `Type mismatch in argument N: expected X, got Y` form is
-// what the typechecker still emits unless the user opts in via
-// `RESILIENT_RICH_DIAG=1`. The opt-in produces a rustc-style
-// multi-block diagnostic with a primary span on the offending
-// argument and a secondary span on the function's declaration.
-//
-// These tests exercise the renderer helper directly so they don't
-// depend on (and cannot perturb) the global environment variable
-// other tests might be reading. End-to-end coverage of the gate
-// itself lives in the `legacy_default_remains` test below: it
-// scrubs the env var locally, then asserts the legacy text comes
-// back through the full pipeline.
 #[cfg(test)]
-mod res340_rich_type_mismatch_tests {
-    use super::*;
+mod span_diagnostic_tests {
     use crate::parse;
-    use crate::span::Pos;
-
-    fn span(start_line: usize, start_col: usize, end_line: usize, end_col: usize) -> Span {
-        Span::new(
-            Pos::new(start_line, start_col, 0),
-            Pos::new(end_line, end_col, 0),
-        )
-    }
+    use crate::typechecker::TypeChecker;
 
-    fn check(src: &str) -> Result<(), String> {
+    fn check_with_source(src: &str, path: &str) -> Result<(), String> {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let mut tc = TypeChecker::new();
-        tc.check_program_with_source(&prog, "<t>").map(|_| ())
+        TypeChecker::new()
+            .check_program_with_source(&prog, path)
+            .map(|_| ())
     }
 
     #[test]
-    fn legacy_default_remains_byte_identical() {
-        // Without the env var, the short form is what the
-        // typechecker emits. Goldens depend on this — the test
-        // pins the exact substring so a regression here would
-        // also be a regression in any `.expected.txt` that
-        // captured the line.
-        //
-        // We deliberately do NOT touch `RESILIENT_RICH_DIAG`
-        // here — both removing and setting it would race against
-        // any concurrent test that reads it. Instead we trust
-        // that `cargo test` runs in a clean process where the
-        // var is unset by default; if a contributor exports it
-        // in their shell, the assertion below trips and tells
-        // them to unset it before running tests, which is the
-        // correct invariant.
-        let src = "\
-            fn drive(int dist) { return dist; }\n\
-            fn caller() { return drive(\"oops\"); }\n";
-        let err = check(src).expect_err("call with wrong arg type must be rejected");
-        if std::env::var("RESILIENT_RICH_DIAG").as_deref() == Ok("1") {
-            // Contributor has the rich-diag flag exported in
-            // their shell. Verify the rich path instead — both
-            // formats are correct, just different.
-            assert!(
-                err.contains("error[E0007]: type mismatch"),
-                "rich path must produce the rustc-style header: {err}"
-            );
-            return;
-        }
-        assert!(
-            err.contains("Type mismatch in argument 1: expected int, got string"),
-            "default format must remain the legacy short message; got: {err}"
-        );
-        // The rich block markers must NOT appear by default.
+    fn error_includes_source_path() {
+        // A type annotation mismatch on a let statement should include
+        // the source path in the error message.
+        let err = check_with_source(
+            "fn f(int x) -> int { let y: bool = x; return y; }",
+            "myfile.rz",
+        )
+        .expect_err("expected type error");
         assert!(
-            !err.contains("error[E0007]"),
-            "rich format leaked into default output: {err}"
+            err.contains("myfile.rz"),
+            "error should include source path, got: {}",
+            err
         );
     }
 
     #[test]
-    fn rich_helper_includes_primary_and_secondary_spans() {
-        // Construct a synthetic source whose layout we control,
-        // then ask the renderer for the rich block. We use a
-        // tempfile so the helper can read it back.
-        let src = "fn drive(int dist) { return dist; }\nlet r = drive(\"oops\");\n";
-        let dir = std::env::temp_dir().join("res340_rich_helper");
-        let _ = std::fs::create_dir_all(&dir);
-        let path = dir.join("rich.rz");
-        std::fs::write(&path, src).expect("write tempfile");
-        // Primary on the bad argument, secondary on the fn decl.
-        let arg_span = span(2, 15, 2, 21); // "oops" string literal
-        let decl_span = Some(span(1, 1, 1, 3)); // "fn"
-        let out = render_rich_arg_type_mismatch(
-            path.to_str().unwrap(),
-            arg_span,
-            decl_span,
-            1,
-            "int",
-            "string",
-        );
-        assert!(
-            out.contains(
-                "error[E0007]: type mismatch in argument 1: expected `int`, found `string`"
-            ),
-            "rich header missing or wrong: {out}"
-        );
-        assert!(
-            out.contains("note: expected `int` because of this declaration"),
-            "secondary label missing: {out}"
-        );
-        // Both source lines should appear (primary + note snippet).
-        assert!(
-            out.contains("let r = drive(\"oops\")"),
-            "primary snippet missing: {out}"
-        );
-        assert!(
-            out.contains("fn drive(int dist)"),
-            "declaration snippet missing: {out}"
-        );
-        // Carets must underline both spans.
+    fn error_includes_line_number() {
+        // Error on the second line should include line 2 in the message.
+        let src = "fn f(int x) -> int { return x; }\nfn f(int y) -> int { return y; }";
+        let err = check_with_source(src, "test.rz").expect_err("expected duplicate fn error");
         assert!(
-            out.matches('^').count() >= 2,
-            "expected both spans to be underlined: {out}"
+            err.contains("test.rz"),
+            "error should contain file path, got: {}",
+            err
         );
-        let _ = std::fs::remove_file(&path);
-        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn rich_helper_drops_secondary_when_decl_unknown() {
-        // Calls through a function value (no Identifier callee) —
-        // we have no fn declaration span, so the secondary block
-        // is omitted but the primary is still rich.
-        let src = "let f = drive;\nf(\"oops\");\n";
-        let dir = std::env::temp_dir().join("res340_no_decl");
-        let _ = std::fs::create_dir_all(&dir);
-        let path = dir.join("nodecl.rz");
-        std::fs::write(&path, src).expect("write tempfile");
-        let out = render_rich_arg_type_mismatch(
-            path.to_str().unwrap(),
-            span(2, 3, 2, 9),
-            None,
-            1,
-            "int",
-            "string",
-        );
+    fn ok_program_has_no_span_error() {
+        let src = "fn add(int x, int y) -> int { return x + y; }";
+        assert!(check_with_source(src, "test.rz").is_ok());
+    }
+
+    /// RES-1862: an undefined-variable error inside a function body should
+    /// report the identifier's position, not the function declaration's start.
+    #[test]
+    fn undefined_var_span_is_identifier_position() {
+        // `unknown_var` is on line 2 col 12 (1-indexed)
+        let src = "fn f(int x) -> int {\n    return unknown_var;\n}";
+        let err = check_with_source(src, "src.rz").expect_err("expected undefined var error");
         assert!(
-            out.contains("error[E0007]: type mismatch in argument 1"),
-            "primary header missing: {out}"
+            err.contains("src.rz"),
+            "error should contain file path: {}",
+            err
         );
+        // Must point at line 2, not line 1 (the function declaration).
         assert!(
-            !out.contains("note: expected"),
-            "secondary block leaked when no decl span was passed: {out}"
+            err.contains("src.rz:2:"),
+            "error should point to line 2 where the identifier is: {}",
+            err
         );
-        let _ = std::fs::remove_file(&path);
-        let _ = std::fs::remove_dir_all(&dir);
     }
 
+    /// RES-1862: a type error inside an infix expression should report the
+    /// infix span.
     #[test]
-    fn rich_helper_falls_back_safely_when_source_missing() {
-        // Empty source path → renderer falls back to a no-snippet
-        // diagnostic. Must still include the header so a user
-        // reading the LSP / REPL output sees something useful.
-        let out = render_rich_arg_type_mismatch(
-            "",
-            span(1, 1, 1, 5),
-            Some(span(2, 1, 2, 3)),
-            2,
-            "Meters",
-            "Seconds",
-        );
+    fn infix_type_error_uses_infix_span() {
+        // The `+` is on line 2.
+        let src = "fn f(int x) -> int {\n    return x + \"hello\";\n}";
+        let err = check_with_source(src, "expr.rz").expect_err("expected infix type error");
         assert!(
-            out.contains(
-                "error[E0007]: type mismatch in argument 2: expected `Meters`, found `Seconds`"
-            ),
-            "header missing on no-source path: {out}"
+            err.contains("expr.rz:2:"),
+            "error should point to line 2 (the infix expression): {}",
+            err
         );
+    }
+
+    #[test]
+    fn bitwise_type_error_has_path() {
+        let src = r#"fn f(float x) -> int { return x & 1; }"#;
+        let err = check_with_source(src, "ops.rz").expect_err("expected bitwise type error");
         assert!(
-            out.contains("note: expected `Meters` because of this declaration"),
-            "note missing on no-source path: {out}"
+            err.contains("ops.rz"),
+            "error should contain file path, got: {}",
+            err
         );
     }
+}
 
-    /// RES-4115: shared assertion for the four new gated helpers —
-    /// default output stays byte-identical to the legacy message
-    /// (no bracketed code), and `RESILIENT_RICH_DIAG=1` attaches the
-    /// code. Mirrors `legacy_default_remains_byte_identical`'s
-    /// don't-touch-the-env-var-mid-test discipline: check whichever
-    /// branch the ambient environment is actually in.
-    fn assert_gated_code(out: &str, legacy_substr: &str, code: &str) {
-        if std::env::var("RESILIENT_RICH_DIAG").as_deref() == Ok("1") {
-            assert!(
-                out.contains(code),
-                "rich path must include code {code}: {out}"
-            );
-        } else {
-            assert!(
-                out.contains(legacy_substr),
-                "default format must remain the legacy message; got: {out}"
-            );
-            assert!(
-                !out.contains(code),
-                "code {code} leaked into default output: {out}"
-            );
-        }
+#[cfg(test)]
+mod res1859_builtin_return_types {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn check_ok(src: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program(&prog)
+            .expect("should type-check without error");
     }
 
     #[test]
-    fn duplicate_fn_error_carries_e0011_when_gated() {
-        let out = render_duplicate_fn_error("<t>", span(2, 1, 2, 3), "foo", span(1, 1, 1, 3));
-        assert_gated_code(&out, "duplicate function name `foo`", "E0011");
+    fn array_map_return_type_is_array() {
+        // Result of array_map used as an array argument to len() — would
+        // fail type-check if the return type were inferred as Any when
+        // the checker is configured to be strict on function signatures.
+        check_ok(
+            "fn f(array a) -> int { let m = array_map(a, fn(int x) -> int { return x * 2; }); return len(m); }",
+        );
     }
 
     #[test]
-    fn missing_return_error_carries_e0013_when_gated() {
-        let out = render_missing_return_error("f", &Type::Int);
-        assert_gated_code(&out, "missing return on at least one path", "E0013");
+    fn array_filter_return_type_is_array() {
+        check_ok(
+            "fn f(array a) -> int { let filtered = array_filter(a, fn(int x) -> bool { return x > 0; }); return len(filtered); }",
+        );
     }
 
     #[test]
-    fn unknown_struct_field_error_carries_e0017_when_gated() {
-        let out = render_unknown_struct_field_error("Point", "z", "", "x, y");
-        assert_gated_code(&out, "struct `Point` has no field `z`", "E0017");
+    fn string_split_returns_array() {
+        check_ok(
+            "fn f(string s) -> int { let parts = string_split(s, \",\"); return len(parts); }",
+        );
     }
 
     #[test]
-    fn impure_builtin_error_carries_e0020_when_gated() {
-        let out = render_impure_builtin_error("println");
-        assert_gated_code(&out, "calls impure builtin `println`", "E0020");
+    fn method_map_return_type_is_array() {
+        check_ok(
+            "fn f(array a) -> int { let mapped = a.map(fn(int x) -> int { return x + 1; }); return len(mapped); }",
+        );
     }
 
     #[test]
-    fn io_from_pure_context_error_carries_e0020_when_gated() {
-        let out = render_io_from_pure_context_error("println");
-        assert_gated_code(
-            &out,
-            "cannot call io function `println` from pure context",
-            "E0020",
+    fn method_filter_return_type_is_array() {
+        check_ok(
+            "fn f(array a) -> int { let filtered = a.filter(fn(int x) -> bool { return x > 0; }); return len(filtered); }",
         );
     }
 
     #[test]
-    fn arity_mismatch_error_carries_e0006_when_gated() {
-        let out = render_arity_mismatch_error(2, 1);
-        assert_gated_code(&out, "Expected 2 arguments, got 1", "E0006");
+    fn string_split_method_return_type_is_array() {
+        check_ok("fn f(string s) -> int { let parts = s.split(\",\"); return len(parts); }");
     }
 
+    // RES-1859: array mutation builtins — verify return type is Array
+    // so callers can pass the result to array-typed parameters.
+
     #[test]
-    fn div_by_zero_error_carries_e0008_when_gated() {
-        let out = render_div_by_zero_error("division");
-        assert_gated_code(
-            &out,
-            "integer division by zero — denominator is a compile-time constant 0",
-            "E0008",
-        );
+    fn array_swap_return_type_is_array() {
+        check_ok("fn f(array a) -> int { let b = array_swap(a, 0, 1); return len(b); }");
     }
 
     #[test]
-    fn undefined_variable_error_carries_e0004_when_gated_with_span() {
-        let out = render_undefined_variable_error("foo", 3, 5, "");
-        assert_gated_code(&out, "Undefined variable 'foo' at 3:5", "E0004");
+    fn array_insert_at_return_type_is_array() {
+        check_ok("fn f(array a) -> int { let b = array_insert_at(a, 0, 42); return len(b); }");
     }
 
     #[test]
-    fn undefined_variable_error_carries_e0004_when_gated_without_span() {
-        let out = render_undefined_variable_error("foo", 0, 0, " (did you mean `bar`?)");
-        assert_gated_code(
-            &out,
-            "Undefined variable: foo (did you mean `bar`?)",
-            "E0004",
-        );
+    fn array_remove_at_return_type_is_array() {
+        check_ok("fn f(array a) -> int { let b = array_remove_at(a, 0); return len(b); }");
     }
 
     #[test]
-    fn fn_decl_span_table_is_populated_for_top_level_fns() {
-        // Internal invariant: the pre-pass that fills
-        // `contract_table` also fills `fn_decl_spans` keyed by fn
-        // name. The rich-diag path depends on this; failing here
-        // would silently degrade error quality.
-        let src = "fn drive(int dist) { return dist; }\nfn caller() { return 0; }\n";
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let mut tc = TypeChecker::new();
-        let _ = tc.check_program_with_source(&prog, "<t>");
-        assert!(
-            tc.fn_decl_spans.contains_key("drive"),
-            "fn_decl_spans missing entry for drive: {:?}",
-            tc.fn_decl_spans.keys().collect::<Vec<_>>()
-        );
-        assert!(
-            tc.fn_decl_spans.contains_key("caller"),
-            "fn_decl_spans missing entry for caller: {:?}",
-            tc.fn_decl_spans.keys().collect::<Vec<_>>()
-        );
+    fn array_set_at_return_type_is_array() {
+        check_ok("fn f(array a) -> int { let b = array_set_at(a, 0, 99); return len(b); }");
+    }
+
+    #[test]
+    fn array_slice_return_type_is_array() {
+        check_ok("fn f(array a) -> int { let b = array_slice(a, 0, 3, false); return len(b); }");
     }
 }
 
-// RES-402: tests for the array-literal mixed-element-type rejection.
+// ── RES-1862: span attachment for node types that previously lacked it ────────
+
 #[cfg(test)]
-mod res402_polymorphic_array_tests {
+mod res1862_span_attachment {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
-    fn check(src: &str) -> Result<(), String> {
+    fn check_err_with_source(src: &str, path: &str) -> String {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new().check_program(&prog).map(|_| ())
-    }
-
-    #[test]
-    fn homogeneous_int_array_passes() {
-        check("let xs = [1, 2, 3];").expect("homogeneous Int literal should typecheck");
+        TypeChecker::new()
+            .check_program_with_source(&prog, path)
+            .expect_err("expected type error")
     }
 
     #[test]
-    fn homogeneous_string_array_passes() {
-        check("let xs = [\"a\", \"b\", \"c\"];")
-            .expect("homogeneous String literal should typecheck");
+    fn match_non_exhaustive_error_includes_span() {
+        // Bool scrutinee missing `false` arm — error must name the file.
+        let src = "fn f(bool b) -> int {\n    return match b {\n        true => 1,\n    };\n}";
+        let err = check_err_with_source(src, "match.rz");
+        assert!(
+            err.contains("match.rz"),
+            "non-exhaustive match error must include file path; got: {err}"
+        );
     }
 
     #[test]
-    fn empty_array_passes() {
-        check("let xs = [];").expect("empty array literal should typecheck");
+    fn assert_bad_condition_error_includes_span() {
+        let src = "fn f(int x) {\n    assert(x + 1);\n}";
+        let err = check_err_with_source(src, "assert.rz");
+        assert!(
+            err.contains("assert.rz"),
+            "assert type error must include file path; got: {err}"
+        );
     }
 
     #[test]
-    fn mixed_int_and_string_is_rejected() {
-        let err =
-            check("let xs = [1, \"two\"];").expect_err("mixed Int / String should be rejected");
-        assert!(
-            err.contains("mixed element types"),
-            "diagnostic missing 'mixed element types': {}",
-            err
-        );
-        assert!(err.contains("int"), "diagnostic missing int type: {}", err);
+    fn assume_bad_condition_error_includes_span() {
+        let src = "fn f(int x) {\n    assume(x + 1);\n}";
+        let err = check_err_with_source(src, "assume.rz");
         assert!(
-            err.contains("string"),
-            "diagnostic missing string type: {}",
-            err
+            err.contains("assume.rz"),
+            "assume type error must include file path; got: {err}"
         );
     }
 
     #[test]
-    fn mixed_int_and_float_is_rejected() {
-        // Resilient already rejects Int + Float arithmetic — array
-        // literals follow the same no-implicit-coercion rule.
-        let err = check("let xs = [1, 2.0];").expect_err("mixed Int / Float should be rejected");
+    fn range_bad_bound_error_includes_span() {
+        // Range lower bound must be Int; passing a string should error.
+        let src = "fn f(string s) {\n    for x in s..5 { }\n}";
+        let err = check_err_with_source(src, "range.rz");
         assert!(
-            err.contains("mixed element types"),
-            "diagnostic missing 'mixed element types': {}",
-            err
+            err.contains("range.rz"),
+            "range bound error must include file path; got: {err}"
         );
     }
 
     #[test]
-    fn actor_rejects_reference_state_type() {
-        // RES-777: actor state fields cannot have reference types.
-        // References would create aliases across actor boundaries,
-        // enabling data races despite the actor model's isolation.
-        let err = check(
-            "
-            actor TestActor {
-                state: &int = 0;
-                receive handle() {}
-            }
-            ",
-        )
-        .expect_err("reference-typed actor state should be rejected");
-        assert!(
-            err.contains("has reference type"),
-            "diagnostic missing 'has reference type': {}",
-            err
-        );
+    fn for_loop_range_error_includes_span() {
+        // Range lower bound is a string — must error with file path.
+        let src = "fn f(string s) {\n    for i in s..5 { }\n}";
+        let err = check_err_with_source(src, "for.rz");
         assert!(
-            err.contains("actor boundaries require ownership-by-value"),
-            "diagnostic missing 'actor boundaries require ownership-by-value': {}",
-            err
+            err.contains("for.rz"),
+            "for-loop range error must include file path; got: {err}"
         );
     }
 
     #[test]
-    fn actor_rejects_reference_handler_parameter() {
-        // RES-777: actor handler parameters cannot have reference types
-        // for the same reason as state fields.
-        let err = check(
-            "
-            actor TestActor {
-                state: int = 0;
-                receive handle(&int x) {}
-            }
-            ",
-        )
-        .expect_err("reference-typed handler parameter should be rejected");
-        assert!(
-            err.contains("has reference type"),
-            "diagnostic missing 'has reference type': {}",
-            err
-        );
-        assert!(
-            err.contains("actor boundaries require ownership-by-value"),
-            "diagnostic missing 'actor boundaries require ownership-by-value': {}",
-            err
+    fn while_range_in_body_error_includes_span() {
+        // For-range with a string lower bound in a while loop body — the
+        // range error should carry the while loop's file path.
+        let src = "fn f(string s) {\n    while true {\n        for i in s..5 { }\n        break;\n    }\n}";
+        let err = check_err_with_source(src, "while.rz");
+        assert!(
+            err.contains("while.rz"),
+            "error inside while loop must include file path; got: {err}"
         );
     }
+
+    #[test]
+    fn nested_statement_error_points_at_its_own_line() {
+        // `Assignment` records no span of its own; the error must land on
+        // line 3, not on the `let` that last set the position.
+        let src = "fn f() -> int {\n    let x: int = 1;\n    x = \"s\";\n    return x;\n}";
+        let err = check_err_with_source(src, "assign.rz");
+        assert!(err.starts_with("assign.rz:3:"), "got: {err}");
+    }
+
+    #[test]
+    fn field_assignment_error_points_at_its_own_line() {
+        let src = "struct P { int x, }\nfn f() {\n    let p = new P { x: 1 };\n    p.x = \"s\";\n}";
+        let err = check_err_with_source(src, "field.rz");
+        assert!(err.starts_with("field.rz:4:"), "got: {err}");
+    }
 }
 
-// RES-1104..RES-1106 + RES-1112 + RES-1113: regression suite for the
-// typechecker scope / reachability fixes shipped together. Each test
-// uses `parse` + `TypeChecker::check_program` so it exercises the
-// real driver path. A bug introduced into any of these fixes must
-// surface here before reaching CI.
 #[cfg(test)]
-mod scope_and_reachability_tests {
+mod res401_tuple_type {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
-    fn check(src: &str) -> Result<(), String> {
+    fn check_ok(src: &str) {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new().check_program(&prog).map(|_| ())
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect("expected no type error");
     }
 
-    // --- RES-1104: for-in loop variable binding ------------------------------
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected type error")
+    }
 
     #[test]
-    fn res1104_for_in_array_binds_loop_var() {
-        check(
-            "fn main() { \
-                let xs = [1, 2, 3]; \
-                let total = 0; \
-                for x in xs { total = total + x; } \
-            }",
-        )
-        .expect("for x in xs should bind x for the body");
+    fn tuple_literal_type_checks() {
+        // A heterogeneous tuple literal type-checks without error.
+        check_ok(r#"let _t = (1, "hello", true);"#);
     }
 
     #[test]
-    fn res1104_for_in_range_binds_loop_var() {
-        check(
-            "fn main() { \
-                let total = 0; \
-                for i in 0..5 { total = total + i; } \
-            }",
-        )
-        .expect("for i in 0..5 should bind i for the body");
+    fn empty_tuple_type_checks() {
+        check_ok("let _t = ();");
     }
 
     #[test]
-    fn res1104_loop_var_does_not_leak_past_block() {
-        // After RES-1104 + RES-1111 the loop variable is confined to
-        // the for body's enclosed env, so referring to it after the
-        // loop is a clean "Undefined variable" diagnostic rather than
-        // accidentally typechecking against the leaked binding.
-        let err = check(
-            "fn main() { \
-                for x in [1, 2, 3] { } \
-                let y = x; \
-            }",
-        )
-        .expect_err("loop var should be undefined after the for body");
+    fn tuple_index_out_of_range_errors() {
+        // Accessing index 5 of a 2-element tuple should fail.
+        let err = check_err("let t = (1, 2); let _x = t.5;");
         assert!(
-            err.contains("Undefined variable 'x'"),
-            "expected undefined-x diagnostic, got: {err}"
+            err.contains("out of range") || err.contains("index"),
+            "expected out-of-range error; got: {err}"
         );
     }
 
-    // --- RES-1105: direct self-recursion -------------------------------------
-
-    #[test]
-    fn res1105_self_recursion_resolves_in_typechecker() {
-        check(
-            "fn fact(int n) -> int { \
-                if n <= 1 { return 1; } \
-                return n * fact(n - 1); \
-            }",
-        )
-        .expect("self-recursive fact should typecheck");
-    }
-
-    // --- RES-1106: forward / mutual recursion --------------------------------
-
     #[test]
-    fn res1106_forward_call_resolves_in_typechecker() {
-        check(
-            "fn caller() -> int { return helper(); } \
-             fn helper() -> int { return 7; }",
-        )
-        .expect("forward fn references should typecheck");
+    fn tuple_index_in_range_ok() {
+        // Accessing a valid index should succeed.
+        check_ok(r#"let t = (1, "x"); let _s = t.1;"#);
     }
 
     #[test]
-    fn res1106_mutual_recursion_resolves_in_typechecker() {
-        check(
-            "fn even(int n) -> bool { \
-                if n == 0 { return true; } \
-                return odd(n - 1); \
-            } \
-            fn odd(int n) -> bool { \
-                if n == 0 { return false; } \
-                return even(n - 1); \
-            }",
-        )
-        .expect("mutual recursion (even/odd) should typecheck");
+    fn tuple_destructure_ok() {
+        // Destructuring into correct names should pass.
+        check_ok(r#"let (a, b) = (1, "x");"#);
     }
+}
 
-    // --- RES-1112: missing return on non-void fn -----------------------------
+// ── RES-402: match and if expression type inference ───────────────────────────
 
-    #[test]
-    fn res1112_missing_return_rejects_if_without_else() {
-        let err = check(
-            "fn maybe(int n) -> int { \
-                if n > 0 { return 1; } \
-            }",
-        )
-        .expect_err("missing return on else path should be rejected");
-        assert!(
-            err.contains("missing return"),
-            "expected missing-return diagnostic, got: {err}"
-        );
-    }
+#[cfg(test)]
+mod res402_arm_type_inference {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
 
-    #[test]
-    fn res1112_both_branches_return_passes() {
-        check(
-            "fn pick(int n) -> int { \
-                if n > 0 { return 1; } else { return 0; } \
-            }",
-        )
-        .expect("both branches returning should typecheck");
+    fn check_ok(src: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect("expected no type error");
     }
 
-    #[test]
-    fn res1112_implicit_return_expression_passes() {
-        check("fn id(int x) -> int { x }").expect("implicit-return form should typecheck");
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected type error")
     }
 
     #[test]
-    fn res1112_void_fn_can_fall_off_end() {
-        check("fn main() { let x = 1; }").expect("void fn need not return");
+    fn match_result_used_as_int() {
+        // When all match arms return int, the result should be usable
+        // as an int (e.g., added to another int).
+        check_ok(
+            r#"
+fn f(int x) -> int {
+    let v = match x {
+        0 => 10,
+        1 => 20,
+        _ => 30,
+    };
+    return v + 1;
+}
+"#,
+        );
     }
 
     #[test]
-    fn res1112_live_block_with_return_passes() {
-        // RES-1112: `live { ... return X; ... }` is recognised as a
-        // terminating construct so safety-critical examples using
-        // live-block retry still typecheck without spurious errors.
-        check(
-            "fn safe() -> int { \
-                live { \
-                    let x = 5; \
-                    return x; \
-                } \
-            }",
-        )
-        .expect("live { return X } should count as a terminating body");
+    fn match_result_used_as_string() {
+        // When all match arms return a string, the result is a string.
+        check_ok(
+            r#"
+fn describe(int x) -> string {
+    return match x {
+        0 => "zero",
+        1 => "one",
+        _ => "other",
+    };
+}
+"#,
+        );
     }
 
     #[test]
-    fn res3828_live_block_bool_invariant_passes() {
-        check(
-            "fn safe(int fuel) -> int { \
-                live invariant fuel >= 0 { \
-                    return fuel; \
-                } \
-            }",
-        )
-        .expect("boolean live invariant should typecheck");
+    fn match_result_used_as_bool() {
+        // Arms returning bool — inferred type is bool.
+        check_ok(
+            r#"
+fn is_zero(int x) -> bool {
+    return match x {
+        0 => true,
+        _ => false,
+    };
+}
+"#,
+        );
     }
 
     #[test]
-    fn res3828_live_block_non_bool_invariant_is_rejected() {
-        let err = check(
-            "fn unsafe_live(int fuel) -> int { \
-                live invariant 1 { \
-                    return fuel; \
-                } \
-            }",
-        )
-        .expect_err("non-bool live invariant should be rejected");
-        assert!(
-            err.contains("live block invariant must be Bool"),
-            "expected live invariant Bool diagnostic, got: {err}"
+    fn if_with_both_branches_propagates_int() {
+        // if/else where both branches return int — result is int.
+        check_ok(
+            r#"
+fn abs_val(int x) -> int {
+    let v = if x >= 0 { x } else { 0 - x };
+    return v + 1;
+}
+"#,
         );
     }
 
-    // --- RES-1113: unreachable code after return -----------------------------
-
     #[test]
-    fn res1113_statement_after_return_rejected() {
-        let err = check(
-            "fn classify(int n) -> string { \
-                return \"a\"; \
-                return \"b\"; \
-            }",
-        )
-        .expect_err("unreachable return should be rejected");
+    fn if_else_type_mismatch_errors() {
+        // if branch returns int, else branch returns string — type error.
+        let err = check_err(
+            r#"
+fn f(bool c) -> int {
+    let _v = if c { 1 } else { "x" };
+    return 0;
+}
+"#,
+        );
         assert!(
-            err.contains("unreachable code"),
-            "expected unreachable-code diagnostic, got: {err}"
+            err.contains("incompatible") || err.contains("type"),
+            "expected incompatible-types error; got: {err}"
         );
     }
 
     #[test]
-    fn res1113_statement_after_return_in_main_rejected() {
-        let err = check(
-            "fn main() { \
-                return; \
-                let x = 1; \
-            }",
-        )
-        .expect_err("statement after bare return should be rejected");
-        assert!(
-            err.contains("unreachable code"),
-            "expected unreachable-code diagnostic, got: {err}"
+    fn match_arms_used_in_len_context() {
+        // match on bool with both arms returning array — can call len().
+        check_ok(
+            r#"
+fn f(bool b) -> int {
+    let arr = match b {
+        true  => [1, 2, 3],
+        false => [4, 5],
+    };
+    return len(arr);
+}
+"#,
         );
     }
 
     #[test]
-    fn res1113_conditional_return_does_not_make_rest_unreachable() {
-        // `if cond { return X; }` is NOT unconditional — the code
-        // following the if is reachable when cond is false.
-        check(
-            "fn main() { \
-                if true { return; } \
-                let x = 1; \
-            }",
-        )
-        .expect("conditional return must not flag subsequent code");
+    fn match_arms_type_mismatch_errors() {
+        // RES-2664: match arms with different types must error.
+        let err = check_err(
+            r#"
+fn f(int x) -> int {
+    let _v = match x {
+        1 => "hello",
+        _ => 42,
+    };
+    return 0;
+}
+"#,
+        );
+        assert!(
+            err.contains("incompatible"),
+            "expected incompatible-types error; got: {err}"
+        );
     }
 
     #[test]
-    fn res3887_unreachable_diagnostic_points_at_dead_code_not_terminator() {
-        // Real newlines so the two returns are on distinct lines. The
-        // diagnostic must point at line 3 (the dead code), not line 2
-        // (the live return whose span check_node last recorded).
-        let err = check("fn f() -> int {\n    return 1;\n    return 2;\n}")
-            .expect_err("unreachable return should be rejected");
-        assert!(
-            err.contains("unreachable code"),
-            "expected unreachable-code diagnostic, got: {err}"
+    fn match_arms_three_way_mismatch_errors() {
+        // RES-2664: three arms with three different types.
+        let err = check_err(
+            r#"
+fn f(int x) -> int {
+    return match x {
+        1 => "hello",
+        2 => true,
+        _ => 42,
+    };
+}
+"#,
         );
         assert!(
-            err.contains(":3:"),
-            "diagnostic must point at line 3 (the dead code), got: {err}"
+            err.contains("incompatible"),
+            "expected incompatible-types error; got: {err}"
         );
-        assert!(
-            !err.contains(":2:"),
-            "diagnostic must NOT point at line 2 (the live return), got: {err}"
+    }
+
+    #[test]
+    fn match_arms_same_type_accepted() {
+        // Same type in all arms — no error.
+        check_ok(
+            r#"
+fn f(int x) -> int {
+    return match x {
+        1 => 10,
+        2 => 20,
+        _ => 30,
+    };
+}
+"#,
         );
     }
 }
 
+// ── RES-403: return statement type validation against declared return type ────
+
 #[cfg(test)]
-mod duplicate_detection_tests {
+mod res403_return_type_validation {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
-    fn check_err(src: &str) -> String {
+    fn check_ok(src: &str) {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected typechecker error")
+            .check_program_with_source(&prog, "test.rz")
+            .expect("expected no type error");
     }
 
-    fn check_ok(src: &str) {
+    fn check_err(src: &str) -> String {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program(&prog)
-            .expect("unexpected typechecker error");
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected type error")
     }
 
     #[test]
-    fn duplicate_fn_name_is_rejected() {
-        let err = check_err("fn foo() {} fn foo() {}");
+    fn early_return_wrong_type_is_error() {
+        // An early `return 42` in a function declared `-> string` should fail.
+        let err = check_err(
+            r#"
+fn f(bool b) -> string {
+    if b { return 42; }
+    return "hello";
+}
+"#,
+        );
         assert!(
-            err.contains("duplicate function name") && err.contains("`foo`"),
-            "unexpected error: {}",
-            err
+            err.contains("return type mismatch") || err.contains("type mismatch"),
+            "expected return-type-mismatch error; got: {err}"
         );
     }
 
     #[test]
-    fn unique_fn_names_pass() {
-        check_ok("fn foo(int x) -> int { return x; } fn bar(int x) -> int { return x; }");
+    fn early_return_correct_type_ok() {
+        // Early return with the correct type should pass.
+        check_ok(
+            r#"
+fn f(bool b) -> int {
+    if b { return 1; }
+    return 0;
+}
+"#,
+        );
     }
 
     #[test]
-    fn duplicate_struct_field_is_rejected() {
-        let err = check_err("struct Point { int x, int x }");
-        assert!(
-            err.contains("duplicate field") && err.contains("`x`"),
-            "unexpected error: {}",
-            err
+    fn void_function_bare_return_ok() {
+        // Bare `return;` in a void function is always valid.
+        check_ok(
+            r#"
+fn f(bool b) -> void {
+    if b { return; }
+    println("done");
+}
+"#,
         );
     }
 
     #[test]
-    fn unique_struct_fields_pass() {
-        check_ok("struct Point { int x, int y }");
+    fn lambda_return_validated_against_lambda_type() {
+        // The lambda's own declared return type governs its return statements,
+        // not the enclosing function's return type.
+        check_ok(
+            r#"
+fn outer(array a) -> int {
+    let mapped = array_map(a, fn(int x) -> int { return x * 2; });
+    return len(mapped);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn lambda_return_type_mismatch_errors() {
+        // A lambda returning the wrong type should fail.
+        let err = check_err(
+            r#"
+fn f(array a) -> int {
+    let mapped = array_map(a, fn(int x) -> int { return "oops"; });
+    return len(mapped);
+}
+"#,
+        );
+        assert!(
+            err.contains("return type mismatch") || err.contains("type mismatch"),
+            "expected return-type error in lambda; got: {err}"
+        );
     }
 }
 
+// ── RES-404: field assignment type validation ─────────────────────────────────
+
 #[cfg(test)]
-mod span_diagnostic_tests {
+mod res404_field_assignment_type_check {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
-    fn check_with_source(src: &str, path: &str) -> Result<(), String> {
+    fn check_ok(src: &str) {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program_with_source(&prog, path)
-            .map(|_| ())
-    }
-
-    #[test]
-    fn error_includes_source_path() {
-        // A type annotation mismatch on a let statement should include
-        // the source path in the error message.
-        let err = check_with_source(
-            "fn f(int x) -> int { let y: bool = x; return y; }",
-            "myfile.rz",
-        )
-        .expect_err("expected type error");
-        assert!(
-            err.contains("myfile.rz"),
-            "error should include source path, got: {}",
-            err
-        );
-    }
-
-    #[test]
-    fn error_includes_line_number() {
-        // Error on the second line should include line 2 in the message.
-        let src = "fn f(int x) -> int { return x; }\nfn f(int y) -> int { return y; }";
-        let err = check_with_source(src, "test.rz").expect_err("expected duplicate fn error");
-        assert!(
-            err.contains("test.rz"),
-            "error should contain file path, got: {}",
-            err
-        );
+            .check_program_with_source(&prog, "test.rz")
+            .expect("expected no type error");
     }
 
-    #[test]
-    fn ok_program_has_no_span_error() {
-        let src = "fn add(int x, int y) -> int { return x + y; }";
-        assert!(check_with_source(src, "test.rz").is_ok());
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected type error")
     }
-
-    /// RES-1862: an undefined-variable error inside a function body should
-    /// report the identifier's position, not the function declaration's start.
-    #[test]
-    fn undefined_var_span_is_identifier_position() {
-        // `unknown_var` is on line 2 col 12 (1-indexed)
-        let src = "fn f(int x) -> int {\n    return unknown_var;\n}";
-        let err = check_with_source(src, "src.rz").expect_err("expected undefined var error");
-        assert!(
-            err.contains("src.rz"),
-            "error should contain file path: {}",
-            err
-        );
-        // Must point at line 2, not line 1 (the function declaration).
-        assert!(
-            err.contains("src.rz:2:"),
-            "error should point to line 2 where the identifier is: {}",
-            err
+
+    #[test]
+    fn assign_correct_field_type_ok() {
+        check_ok(
+            r#"
+struct Point { int x, int y }
+fn f() -> void {
+    let p = new Point { x: 1, y: 2 };
+    p.x = 10;
+}
+"#,
         );
     }
 
-    /// RES-1862: a type error inside an infix expression should report the
-    /// infix span.
     #[test]
-    fn infix_type_error_uses_infix_span() {
-        // The `+` is on line 2.
-        let src = "fn f(int x) -> int {\n    return x + \"hello\";\n}";
-        let err = check_with_source(src, "expr.rz").expect_err("expected infix type error");
+    fn assign_wrong_field_type_errors() {
+        let err = check_err(
+            r#"
+struct Point { int x, int y }
+fn f() -> void {
+    let p = new Point { x: 1, y: 2 };
+    p.x = "oops";
+}
+"#,
+        );
         assert!(
-            err.contains("expr.rz:2:"),
-            "error should point to line 2 (the infix expression): {}",
-            err
+            err.contains("cannot assign") || err.contains("type"),
+            "expected type mismatch for field assignment; got: {err}"
         );
     }
 
     #[test]
-    fn bitwise_type_error_has_path() {
-        let src = r#"fn f(float x) -> int { return x & 1; }"#;
-        let err = check_with_source(src, "ops.rz").expect_err("expected bitwise type error");
+    fn assign_nonexistent_field_errors() {
+        let err = check_err(
+            r#"
+struct Point { int x, int y }
+fn f() -> void {
+    let p = new Point { x: 1, y: 2 };
+    p.z = 99;
+}
+"#,
+        );
         assert!(
-            err.contains("ops.rz"),
-            "error should contain file path, got: {}",
-            err
+            err.contains("no field") || err.contains("available"),
+            "expected field-not-found error; got: {err}"
         );
     }
 }
 
+// ── RES-405: assignment type validation ───────────────────────────────────────
+
 #[cfg(test)]
-mod res1859_builtin_return_types {
+mod res405_assignment_type_check {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -15091,222 +14186,266 @@ mod res1859_builtin_return_types {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program(&prog)
-            .expect("should type-check without error");
+            .check_program_with_source(&prog, "test.rz")
+            .expect("expected no type error");
     }
 
-    #[test]
-    fn array_map_return_type_is_array() {
-        // Result of array_map used as an array argument to len() — would
-        // fail type-check if the return type were inferred as Any when
-        // the checker is configured to be strict on function signatures.
-        check_ok(
-            "fn f(array a) -> int { let m = array_map(a, fn(int x) -> int { return x * 2; }); return len(m); }",
-        );
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected type error")
     }
 
     #[test]
-    fn array_filter_return_type_is_array() {
+    fn assign_same_type_ok() {
         check_ok(
-            "fn f(array a) -> int { let filtered = array_filter(a, fn(int x) -> bool { return x > 0; }); return len(filtered); }",
+            r#"
+fn f() -> void {
+    let x = 5;
+    x = 10;
+}
+"#,
         );
     }
 
     #[test]
-    fn string_split_returns_array() {
-        check_ok(
-            "fn f(string s) -> int { let parts = string_split(s, \",\"); return len(parts); }",
+    fn assign_wrong_type_errors() {
+        let err = check_err(
+            r#"
+fn f() -> void {
+    let x = 5;
+    x = "hello";
+}
+"#,
+        );
+        assert!(
+            err.contains("cannot assign") || err.contains("type"),
+            "expected type mismatch on assignment; got: {err}"
         );
     }
 
     #[test]
-    fn method_map_return_type_is_array() {
+    fn assign_to_string_var_ok() {
         check_ok(
-            "fn f(array a) -> int { let mapped = a.map(fn(int x) -> int { return x + 1; }); return len(mapped); }",
+            r#"
+fn f() -> void {
+    let s = "hello";
+    s = "world";
+}
+"#,
         );
     }
 
     #[test]
-    fn method_filter_return_type_is_array() {
-        check_ok(
-            "fn f(array a) -> int { let filtered = a.filter(fn(int x) -> bool { return x > 0; }); return len(filtered); }",
+    fn assign_int_to_string_var_errors() {
+        let err = check_err(
+            r#"
+fn f() -> void {
+    let s = "hello";
+    s = 42;
+}
+"#,
+        );
+        assert!(
+            err.contains("cannot assign") || err.contains("type"),
+            "expected type mismatch on string assignment; got: {err}"
         );
     }
+}
 
-    #[test]
-    fn string_split_method_return_type_is_array() {
-        check_ok("fn f(string s) -> int { let parts = s.split(\",\"); return len(parts); }");
-    }
+// ── RES-405 follow-up: index expression integer validation ────────────────────
 
-    // RES-1859: array mutation builtins — verify return type is Array
-    // so callers can pass the result to array-typed parameters.
+#[cfg(test)]
+mod res405_index_type_check {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
 
-    #[test]
-    fn array_swap_return_type_is_array() {
-        check_ok("fn f(array a) -> int { let b = array_swap(a, 0, 1); return len(b); }");
+    fn check_ok(src: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect("expected no type error");
+    }
+
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected type error")
     }
 
     #[test]
-    fn array_insert_at_return_type_is_array() {
-        check_ok("fn f(array a) -> int { let b = array_insert_at(a, 0, 42); return len(b); }");
+    fn int_index_ok() {
+        check_ok(r#"fn f(array a) -> void { let _x = a[0]; }"#);
     }
 
     #[test]
-    fn array_remove_at_return_type_is_array() {
-        check_ok("fn f(array a) -> int { let b = array_remove_at(a, 0); return len(b); }");
+    fn float_index_errors() {
+        let err = check_err(r#"fn f(array a) -> void { let _x = a[1.0]; }"#);
+        assert!(
+            err.contains("integer index") || err.contains("index"),
+            "expected integer-index error; got: {err}"
+        );
     }
 
     #[test]
-    fn array_set_at_return_type_is_array() {
-        check_ok("fn f(array a) -> int { let b = array_set_at(a, 0, 99); return len(b); }");
+    fn string_index_errors() {
+        let err = check_err(r#"fn f(array a) -> void { let _x = a["key"]; }"#);
+        assert!(
+            err.contains("integer index") || err.contains("index"),
+            "expected integer-index error for string index; got: {err}"
+        );
     }
 
     #[test]
-    fn array_slice_return_type_is_array() {
-        check_ok("fn f(array a) -> int { let b = array_slice(a, 0, 3, false); return len(b); }");
+    fn string_char_int_index_ok() {
+        check_ok(r#"fn f(string s) -> void { let _x = s[0]; }"#);
     }
 }
 
-// ── RES-1862: span attachment for node types that previously lacked it ────────
+// ── RES-406: while/for-in/index-assignment/enum-dedup checks ─────────────────
 
 #[cfg(test)]
-mod res1862_span_attachment {
+mod res406_loop_and_collection_checks {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
-    fn check_err_with_source(src: &str, path: &str) -> String {
+    fn check_ok(src: &str) {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program_with_source(&prog, path)
-            .expect_err("expected type error")
+            .check_program_with_source(&prog, "test.rz")
+            .expect("expected no type error");
     }
 
-    #[test]
-    fn match_non_exhaustive_error_includes_span() {
-        // Bool scrutinee missing `false` arm — error must name the file.
-        let src = "fn f(bool b) -> int {\n    return match b {\n        true => 1,\n    };\n}";
-        let err = check_err_with_source(src, "match.rz");
-        assert!(
-            err.contains("match.rz"),
-            "non-exhaustive match error must include file path; got: {err}"
-        );
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected type error")
     }
 
-    #[test]
-    fn assert_bad_condition_error_includes_span() {
-        let src = "fn f(int x) {\n    assert(x + 1);\n}";
-        let err = check_err_with_source(src, "assert.rz");
-        assert!(
-            err.contains("assert.rz"),
-            "assert type error must include file path; got: {err}"
-        );
-    }
+    // ── while condition ───────────────────────────────────────────────────────
 
     #[test]
-    fn assume_bad_condition_error_includes_span() {
-        let src = "fn f(int x) {\n    assume(x + 1);\n}";
-        let err = check_err_with_source(src, "assume.rz");
-        assert!(
-            err.contains("assume.rz"),
-            "assume type error must include file path; got: {err}"
-        );
+    fn while_bool_condition_ok() {
+        check_ok(r#"fn f() -> void { while true { } }"#);
     }
 
     #[test]
-    fn range_bad_bound_error_includes_span() {
-        // Range lower bound must be Int; passing a string should error.
-        let src = "fn f(string s) {\n    for x in s..5 { }\n}";
-        let err = check_err_with_source(src, "range.rz");
+    fn while_int_condition_errors() {
+        let err = check_err(r#"fn f() -> void { while 1 { } }"#);
         assert!(
-            err.contains("range.rz"),
-            "range bound error must include file path; got: {err}"
+            err.contains("while condition") || err.contains("boolean"),
+            "expected bool condition error; got: {err}"
         );
     }
 
     #[test]
-    fn for_loop_range_error_includes_span() {
-        // Range lower bound is a string — must error with file path.
-        let src = "fn f(string s) {\n    for i in s..5 { }\n}";
-        let err = check_err_with_source(src, "for.rz");
+    fn while_string_condition_errors() {
+        let err = check_err(r#"fn f() -> void { while "yes" { } }"#);
         assert!(
-            err.contains("for.rz"),
-            "for-loop range error must include file path; got: {err}"
+            err.contains("while condition") || err.contains("boolean"),
+            "expected bool condition error for string; got: {err}"
         );
     }
 
-    #[test]
-    fn while_range_in_body_error_includes_span() {
-        // For-range with a string lower bound in a while loop body — the
-        // range error should carry the while loop's file path.
-        let src = "fn f(string s) {\n    while true {\n        for i in s..5 { }\n        break;\n    }\n}";
-        let err = check_err_with_source(src, "while.rz");
-        assert!(
-            err.contains("while.rz"),
-            "error inside while loop must include file path; got: {err}"
-        );
-    }
-}
+    // ── for-in iterable type ──────────────────────────────────────────────────
 
-#[cfg(test)]
-mod res401_tuple_type {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
+    #[test]
+    fn for_in_array_ok() {
+        check_ok(r#"fn f(array xs) -> void { for x in xs { } }"#);
+    }
 
-    fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect("expected no type error");
+    #[test]
+    fn for_in_string_ok() {
+        check_ok(r#"fn f(string s) -> void { for c in s { } }"#);
     }
 
-    fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect_err("expected type error")
+    #[test]
+    fn for_in_int_errors() {
+        let err = check_err(r#"fn f(int n) -> void { for x in n { } }"#);
+        assert!(
+            err.contains("cannot iterate") || err.contains("for-in"),
+            "expected cannot-iterate error; got: {err}"
+        );
     }
 
     #[test]
-    fn tuple_literal_type_checks() {
-        // A heterogeneous tuple literal type-checks without error.
-        check_ok(r#"let _t = (1, "hello", true);"#);
+    fn for_in_bool_errors() {
+        let err = check_err(r#"fn f(bool b) -> void { for x in b { } }"#);
+        assert!(
+            err.contains("cannot iterate") || err.contains("for-in"),
+            "expected cannot-iterate error for bool; got: {err}"
+        );
     }
 
+    // ── index assignment integer index ────────────────────────────────────────
+
     #[test]
-    fn empty_tuple_type_checks() {
-        check_ok("let _t = ();");
+    fn index_assign_int_index_ok() {
+        check_ok(r#"fn f(array a) -> void { a[0] = 1; }"#);
     }
 
     #[test]
-    fn tuple_index_out_of_range_errors() {
-        // Accessing index 5 of a 2-element tuple should fail.
-        let err = check_err("let t = (1, 2); let _x = t.5;");
+    fn index_assign_float_index_errors() {
+        let err = check_err(r#"fn f(array a) -> void { a[1.5] = 1; }"#);
         assert!(
-            err.contains("out of range") || err.contains("index"),
-            "expected out-of-range error; got: {err}"
+            err.contains("integer index") || err.contains("index assignment"),
+            "expected integer-index error on index assignment; got: {err}"
         );
     }
 
+    // ── enum duplicate variant ────────────────────────────────────────────────
+
     #[test]
-    fn tuple_index_in_range_ok() {
-        // Accessing a valid index should succeed.
-        check_ok(r#"let t = (1, "x"); let _s = t.1;"#);
+    fn enum_unique_variants_ok() {
+        check_ok(
+            r#"
+enum Color { Red, Green, Blue }
+fn f() -> void { }
+"#,
+        );
     }
 
     #[test]
-    fn tuple_destructure_ok() {
-        // Destructuring into correct names should pass.
-        check_ok(r#"let (a, b) = (1, "x");"#);
+    fn enum_duplicate_variant_errors() {
+        // The parser catches duplicate variants first; either a parse error
+        // or a typecheck error is acceptable — both indicate the duplication
+        // was detected before evaluation.
+        let src = "enum Color { Red, Green, Red }\nfn f() -> void { }\n";
+        let (prog, parse_errs) = crate::parse(src);
+        if !parse_errs.is_empty() {
+            // Parser already caught it — verify the message is meaningful.
+            let combined = parse_errs.join("; ");
+            assert!(
+                combined.to_lowercase().contains("red")
+                    || combined.to_lowercase().contains("duplicate")
+                    || combined.to_lowercase().contains("variant"),
+                "parse error should mention duplicate variant; got: {combined}"
+            );
+            return;
+        }
+        // Typechecker should catch it if parser didn't.
+        let err = TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected duplicate-variant error from typechecker");
+        assert!(
+            err.contains("duplicate variant") || err.contains("Red"),
+            "expected duplicate-variant error; got: {err}"
+        );
     }
 }
 
-// ── RES-402: match and if expression type inference ───────────────────────────
+// ── RES-407: field access on known struct + slice endpoint types ──────────────
 
 #[cfg(test)]
-mod res402_arm_type_inference {
+mod res407_field_access_and_slice {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -15326,260 +14465,156 @@ mod res402_arm_type_inference {
             .expect_err("expected type error")
     }
 
-    #[test]
-    fn match_result_used_as_int() {
-        // When all match arms return int, the result should be usable
-        // as an int (e.g., added to another int).
-        check_ok(
-            r#"
-fn f(int x) -> int {
-    let v = match x {
-        0 => 10,
-        1 => 20,
-        _ => 30,
-    };
-    return v + 1;
-}
-"#,
-        );
-    }
-
-    #[test]
-    fn match_result_used_as_string() {
-        // When all match arms return a string, the result is a string.
-        check_ok(
-            r#"
-fn describe(int x) -> string {
-    return match x {
-        0 => "zero",
-        1 => "one",
-        _ => "other",
-    };
-}
-"#,
-        );
-    }
-
-    #[test]
-    fn match_result_used_as_bool() {
-        // Arms returning bool — inferred type is bool.
-        check_ok(
-            r#"
-fn is_zero(int x) -> bool {
-    return match x {
-        0 => true,
-        _ => false,
-    };
-}
-"#,
-        );
-    }
+    // ── FieldAccess: unknown field on known struct ─────────────────────────────
 
     #[test]
-    fn if_with_both_branches_propagates_int() {
-        // if/else where both branches return int — result is int.
+    fn field_access_known_field_ok() {
         check_ok(
             r#"
-fn abs_val(int x) -> int {
-    let v = if x >= 0 { x } else { 0 - x };
-    return v + 1;
-}
+struct Point { int x, int y }
+fn f(Point p) -> int { return p.x; }
 "#,
         );
     }
 
     #[test]
-    fn if_else_type_mismatch_errors() {
-        // if branch returns int, else branch returns string — type error.
+    fn field_access_unknown_field_errors() {
         let err = check_err(
             r#"
-fn f(bool c) -> int {
-    let _v = if c { 1 } else { "x" };
-    return 0;
-}
+struct Point { int x, int y }
+fn f(Point p) -> int { return p.z; }
 "#,
         );
         assert!(
-            err.contains("incompatible") || err.contains("type"),
-            "expected incompatible-types error; got: {err}"
+            err.contains("no field") || err.contains("z"),
+            "expected no-field error; got: {err}"
         );
     }
 
     #[test]
-    fn match_arms_used_in_len_context() {
-        // match on bool with both arms returning array — can call len().
-        check_ok(
-            r#"
-fn f(bool b) -> int {
-    let arr = match b {
-        true  => [1, 2, 3],
-        false => [4, 5],
-    };
-    return len(arr);
-}
-"#,
-        );
+    fn field_access_unknown_struct_is_permissive() {
+        // When the struct name isn't declared, fall through to Any.
+        check_ok(r#"fn f(UnknownStruct s) -> int { return s.x; }"#);
     }
 
+    // RES-2622: did-you-mean hints on struct field typos so users don't
+    // have to scan the available-fields list to spot a one-letter slip.
+
     #[test]
-    fn match_arms_type_mismatch_errors() {
-        // RES-2664: match arms with different types must error.
+    fn field_access_typo_suggests_close_field() {
         let err = check_err(
             r#"
-fn f(int x) -> int {
-    let _v = match x {
-        1 => "hello",
-        _ => 42,
-    };
-    return 0;
-}
+struct Rectangle { int width, int height }
+fn f(Rectangle r) -> int { return r.heigth; }
 "#,
         );
         assert!(
-            err.contains("incompatible"),
-            "expected incompatible-types error; got: {err}"
+            err.contains("did you mean `height`?"),
+            "expected did-you-mean hint; got: {err}"
         );
     }
 
     #[test]
-    fn match_arms_three_way_mismatch_errors() {
-        // RES-2664: three arms with three different types.
+    fn struct_literal_typo_suggests_close_field() {
         let err = check_err(
             r#"
-fn f(int x) -> int {
-    return match x {
-        1 => "hello",
-        2 => true,
-        _ => 42,
-    };
-}
+struct Rectangle { int width, int height }
+fn f() -> Rectangle { return new Rectangle { width: 1, heigth: 2 }; }
 "#,
         );
         assert!(
-            err.contains("incompatible"),
-            "expected incompatible-types error; got: {err}"
-        );
-    }
-
-    #[test]
-    fn match_arms_same_type_accepted() {
-        // Same type in all arms — no error.
-        check_ok(
-            r#"
-fn f(int x) -> int {
-    return match x {
-        1 => 10,
-        2 => 20,
-        _ => 30,
-    };
-}
-"#,
+            err.contains("did you mean `height`?"),
+            "expected did-you-mean hint; got: {err}"
         );
     }
-}
-
-// ── RES-403: return statement type validation against declared return type ────
-
-#[cfg(test)]
-mod res403_return_type_validation {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
-
-    fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect("expected no type error");
-    }
-
-    fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect_err("expected type error")
-    }
 
     #[test]
-    fn early_return_wrong_type_is_error() {
-        // An early `return 42` in a function declared `-> string` should fail.
+    fn field_assignment_typo_suggests_close_field() {
         let err = check_err(
             r#"
-fn f(bool b) -> string {
-    if b { return 42; }
-    return "hello";
-}
+struct Counter { int count }
+fn f(Counter c) -> void { c.cont = 1; }
 "#,
         );
         assert!(
-            err.contains("return type mismatch") || err.contains("type mismatch"),
-            "expected return-type-mismatch error; got: {err}"
+            err.contains("did you mean `count`?"),
+            "expected did-you-mean hint; got: {err}"
         );
     }
 
     #[test]
-    fn early_return_correct_type_ok() {
-        // Early return with the correct type should pass.
-        check_ok(
+    fn match_pattern_typo_suggests_close_field() {
+        let err = check_err(
             r#"
-fn f(bool b) -> int {
-    if b { return 1; }
-    return 0;
+struct Vec2 { int width, int height }
+fn f(Vec2 v) -> int {
+    return match v {
+        Vec2 { widht, height } => width + height,
+        _ => 0,
+    };
 }
 "#,
         );
+        assert!(
+            err.contains("did you mean `width`?"),
+            "expected did-you-mean hint; got: {err}"
+        );
     }
 
     #[test]
-    fn void_function_bare_return_ok() {
-        // Bare `return;` in a void function is always valid.
-        check_ok(
+    fn field_access_far_typo_omits_hint() {
+        // Edit distance 4 — too far to suggest. Error stands without hint.
+        let err = check_err(
             r#"
-fn f(bool b) -> void {
-    if b { return; }
-    println("done");
-}
+struct Tiny { int n }
+fn f(Tiny t) -> int { return t.completely_different; }
 "#,
         );
+        assert!(
+            err.contains("has no field"),
+            "expected no-field error; got: {err}"
+        );
+        assert!(
+            !err.contains("did you mean"),
+            "did not expect did-you-mean hint at distance > 2; got: {err}"
+        );
     }
 
+    // ── Slice endpoint types ───────────────────────────────────────────────────
+
     #[test]
-    fn lambda_return_validated_against_lambda_type() {
-        // The lambda's own declared return type governs its return statements,
-        // not the enclosing function's return type.
-        check_ok(
-            r#"
-fn outer(array a) -> int {
-    let mapped = array_map(a, fn(int x) -> int { return x * 2; });
-    return len(mapped);
-}
-"#,
-        );
+    fn slice_int_bounds_ok() {
+        check_ok(r#"fn f(array a) -> array { return a[1..3]; }"#);
     }
 
     #[test]
-    fn lambda_return_type_mismatch_errors() {
-        // A lambda returning the wrong type should fail.
-        let err = check_err(
-            r#"
-fn f(array a) -> int {
-    let mapped = array_map(a, fn(int x) -> int { return "oops"; });
-    return len(mapped);
-}
-"#,
+    fn slice_float_lower_bound_errors() {
+        let err = check_err(r#"fn f(array a) -> array { return a[1.0..3]; }"#);
+        assert!(
+            err.contains("lower bound") || err.contains("integer"),
+            "expected integer-bound error; got: {err}"
         );
+    }
+
+    #[test]
+    fn slice_float_upper_bound_errors() {
+        let err = check_err(r#"fn f(array a) -> array { return a[0..3.5]; }"#);
         assert!(
-            err.contains("return type mismatch") || err.contains("type mismatch"),
-            "expected return-type error in lambda; got: {err}"
+            err.contains("upper bound") || err.contains("integer"),
+            "expected integer-bound error; got: {err}"
         );
     }
+
+    #[test]
+    fn slice_string_int_bounds_ok() {
+        check_ok(r#"fn f(string s) -> string { return s[0..5]; }"#);
+    }
 }
 
-// ── RES-404: field assignment type validation ─────────────────────────────────
+// ── RES-408: `any` type annotation → Type::Any + struct pattern permissiveness ─
 
 #[cfg(test)]
-mod res404_field_assignment_type_check {
+mod res408_any_type_annotation {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -15600,57 +14635,75 @@ mod res404_field_assignment_type_check {
     }
 
     #[test]
-    fn assign_correct_field_type_ok() {
+    fn any_param_accepts_int_arg() {
         check_ok(
             r#"
-struct Point { int x, int y }
-fn f() -> void {
-    let p = new Point { x: 1, y: 2 };
-    p.x = 10;
-}
+fn f(any x) -> int { return 0; }
+fn g() -> int { return f(42); }
 "#,
         );
     }
 
     #[test]
-    fn assign_wrong_field_type_errors() {
-        let err = check_err(
+    fn any_param_accepts_string_arg() {
+        check_ok(
+            r#"
+fn f(any x) -> int { return 0; }
+fn g() -> int { return f("hello"); }
+"#,
+        );
+    }
+
+    #[test]
+    fn any_return_ok() {
+        check_ok(r#"fn f() -> any { return 42; }"#);
+    }
+
+    #[test]
+    fn struct_pattern_on_any_scrutinee_ok() {
+        check_ok(
             r#"
 struct Point { int x, int y }
-fn f() -> void {
-    let p = new Point { x: 1, y: 2 };
-    p.x = "oops";
+fn f(any p) -> int {
+    return match p {
+        Point { x, y } => x,
+        _ => 0,
+    };
 }
 "#,
         );
-        assert!(
-            err.contains("cannot assign") || err.contains("type"),
-            "expected type mismatch for field assignment; got: {err}"
-        );
     }
 
     #[test]
-    fn assign_nonexistent_field_errors() {
+    fn struct_pattern_wrong_struct_errors() {
         let err = check_err(
             r#"
 struct Point { int x, int y }
-fn f() -> void {
-    let p = new Point { x: 1, y: 2 };
-    p.z = 99;
+struct Rect { int w, int h }
+fn f(Rect r) -> int {
+    return match r {
+        Point { x, y } => x,
+        _ => 0,
+    };
 }
 "#,
         );
         assert!(
-            err.contains("no field") || err.contains("available"),
-            "expected field-not-found error; got: {err}"
+            err.contains("Point") || err.contains("Rect") || err.contains("pattern"),
+            "expected struct mismatch error; got: {err}"
         );
     }
+
+    #[test]
+    fn any_let_binding_ok() {
+        check_ok(r#"fn f() -> void { let x: any = 5; let _y: any = "hello"; }"#);
+    }
 }
 
-// ── RES-405: assignment type validation ───────────────────────────────────────
+// ── RES-409: for-in element type inference + duplicate struct check ──────────
 
 #[cfg(test)]
-mod res405_assignment_type_check {
+mod res409_forin_element_type_and_duplicate_struct {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -15670,67 +14723,88 @@ mod res405_assignment_type_check {
             .expect_err("expected type error")
     }
 
+    // ── for-in element type inference ─────────────────────────────────────────
+
     #[test]
-    fn assign_same_type_ok() {
+    fn for_in_range_elem_is_int() {
+        // The loop variable `i` should be Int, so `i + 1` is valid.
         check_ok(
             r#"
 fn f() -> void {
-    let x = 5;
-    x = 10;
+    for i in 0..10 {
+        let _x = i + 1;
+    }
 }
 "#,
         );
     }
 
     #[test]
-    fn assign_wrong_type_errors() {
+    fn for_in_range_elem_int_used_in_string_concat_errors() {
+        // `i` is Int from a range; `"hello" + i` is String (coercion),
+        // but `i - "hello"` is an error (not a valid arithmetic op).
+        // This validates that the range-elem binding really is Int.
         let err = check_err(
             r#"
 fn f() -> void {
-    let x = 5;
-    x = "hello";
+    for i in 0..5 {
+        let _x = i - "hello";
+    }
 }
 "#,
         );
         assert!(
-            err.contains("cannot assign") || err.contains("type"),
-            "expected type mismatch on assignment; got: {err}"
+            err.contains("Cannot apply") || err.contains("int") || err.contains("string"),
+            "expected type error for int - string in range loop; got: {err}"
         );
     }
 
     #[test]
-    fn assign_to_string_var_ok() {
+    fn for_in_string_elem_is_string() {
+        // Each character from a string iteration is a String.
         check_ok(
             r#"
-fn f() -> void {
-    let s = "hello";
-    s = "world";
+fn f(string s) -> void {
+    for c in s {
+        let _x: string = c;
+    }
 }
 "#,
         );
     }
 
+    // ── duplicate struct declaration ──────────────────────────────────────────
+
     #[test]
-    fn assign_int_to_string_var_errors() {
+    fn unique_struct_ok() {
+        check_ok(
+            r#"
+struct Point { int x, int y }
+fn f() -> void { }
+"#,
+        );
+    }
+
+    #[test]
+    fn duplicate_struct_errors() {
         let err = check_err(
             r#"
-fn f() -> void {
-    let s = "hello";
-    s = 42;
-}
+struct Point { int x, int y }
+struct Point { int a, int b }
+fn f() -> void { }
 "#,
         );
         assert!(
-            err.contains("cannot assign") || err.contains("type"),
-            "expected type mismatch on string assignment; got: {err}"
+            err.contains("duplicate") || err.contains("Point"),
+            "expected duplicate struct error; got: {err}"
         );
     }
 }
 
-// ── RES-405 follow-up: index expression integer validation ────────────────────
+// ── RES-410: min/max/pow type inference + duplicate TypeAlias ──────────────
 
 #[cfg(test)]
-mod res405_index_type_check {
+mod res410_numeric_poly_and_type_alias {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -15750,39 +14824,69 @@ mod res405_index_type_check {
             .expect_err("expected type error")
     }
 
+    // ── min/max/pow type inference ────────────────────────────────────────────
+
+    #[test]
+    fn min_int_args_returns_int() {
+        // min(1, 2) should infer Int; `x + 1` must succeed.
+        check_ok(r#"fn f() -> int { let x = min(1, 2); return x + 1; }"#);
+    }
+
     #[test]
-    fn int_index_ok() {
-        check_ok(r#"fn f(array a) -> void { let _x = a[0]; }"#);
+    fn max_float_args_returns_float() {
+        check_ok(r#"fn f() -> float { let x = max(1.0, 2.0); return x; }"#);
     }
 
     #[test]
-    fn float_index_errors() {
-        let err = check_err(r#"fn f(array a) -> void { let _x = a[1.0]; }"#);
-        assert!(
-            err.contains("integer index") || err.contains("index"),
-            "expected integer-index error; got: {err}"
+    fn pow_int_args_returns_int() {
+        check_ok(r#"fn f() -> int { let x = pow(2, 10); return x; }"#);
+    }
+
+    #[test]
+    fn min_mixed_types_returns_any() {
+        // Mixed types → Any; the function call itself still succeeds.
+        check_ok(r#"fn f() -> void { let _x = min(1, 2.0); }"#);
+    }
+
+    // ── duplicate type alias ──────────────────────────────────────────────────
+
+    #[test]
+    fn unique_type_alias_ok() {
+        check_ok(
+            r#"
+type Meters = float;
+fn f(Meters m) -> float { return m; }
+"#,
         );
     }
 
     #[test]
-    fn string_index_errors() {
-        let err = check_err(r#"fn f(array a) -> void { let _x = a["key"]; }"#);
-        assert!(
-            err.contains("integer index") || err.contains("index"),
-            "expected integer-index error for string index; got: {err}"
+    fn duplicate_type_alias_same_target_ok() {
+        // Re-declaring to the same target is silently accepted (the
+        // pre-pass may have already registered it).
+        check_ok(
+            r#"
+type Meters = float;
+fn f() -> void { }
+"#,
         );
     }
 
     #[test]
-    fn string_char_int_index_ok() {
-        check_ok(r#"fn f(string s) -> void { let _x = s[0]; }"#);
+    fn duplicate_type_alias_different_target_errors() {
+        // Re-declaring with a different target is a compile error.
+        let err = check_err("type M = int;\ntype M = float;\nfn f() -> void { }\n");
+        assert!(
+            err.contains("duplicate type alias") || err.contains("M"),
+            "expected duplicate-alias error; got: {err}"
+        );
     }
 }
 
-// ── RES-406: while/for-in/index-assignment/enum-dedup checks ─────────────────
+// ── RES-411: integer literal overflow for pinned int types ───────────────────
 
 #[cfg(test)]
-mod res406_loop_and_collection_checks {
+mod res411_pinned_int_overflow {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -15802,291 +14906,163 @@ mod res406_loop_and_collection_checks {
             .expect_err("expected type error")
     }
 
-    // ── while condition ───────────────────────────────────────────────────────
-
-    #[test]
-    fn while_bool_condition_ok() {
-        check_ok(r#"fn f() -> void { while true { } }"#);
-    }
-
     #[test]
-    fn while_int_condition_errors() {
-        let err = check_err(r#"fn f() -> void { while 1 { } }"#);
-        assert!(
-            err.contains("while condition") || err.contains("boolean"),
-            "expected bool condition error; got: {err}"
-        );
+    fn int8_in_range_ok() {
+        check_ok("fn f() -> void { let x: Int8 = 100; }");
     }
 
     #[test]
-    fn while_string_condition_errors() {
-        let err = check_err(r#"fn f() -> void { while "yes" { } }"#);
-        assert!(
-            err.contains("while condition") || err.contains("boolean"),
-            "expected bool condition error for string; got: {err}"
-        );
+    fn int8_min_boundary_ok() {
+        check_ok("fn f() -> void { let x: Int8 = -128; }");
     }
 
-    // ── for-in iterable type ──────────────────────────────────────────────────
-
     #[test]
-    fn for_in_array_ok() {
-        check_ok(r#"fn f(array xs) -> void { for x in xs { } }"#);
+    fn int8_max_boundary_ok() {
+        check_ok("fn f() -> void { let x: Int8 = 127; }");
     }
 
     #[test]
-    fn for_in_string_ok() {
-        check_ok(r#"fn f(string s) -> void { for c in s { } }"#);
+    fn int8_overflow_errors() {
+        let e = check_err("fn f() -> void { let x: Int8 = 300; }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
+        assert!(e.contains("Int8"), "error must name the type; got: {e}");
     }
 
     #[test]
-    fn for_in_int_errors() {
-        let err = check_err(r#"fn f(int n) -> void { for x in n { } }"#);
-        assert!(
-            err.contains("cannot iterate") || err.contains("for-in"),
-            "expected cannot-iterate error; got: {err}"
-        );
+    fn int8_underflow_errors() {
+        let e = check_err("fn f() -> void { let x: Int8 = -200; }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
     }
 
     #[test]
-    fn for_in_bool_errors() {
-        let err = check_err(r#"fn f(bool b) -> void { for x in b { } }"#);
-        assert!(
-            err.contains("cannot iterate") || err.contains("for-in"),
-            "expected cannot-iterate error for bool; got: {err}"
-        );
+    fn uint8_in_range_ok() {
+        check_ok("fn f() -> void { let x: UInt8 = 255; }");
     }
 
-    // ── index assignment integer index ────────────────────────────────────────
-
     #[test]
-    fn index_assign_int_index_ok() {
-        check_ok(r#"fn f(array a) -> void { a[0] = 1; }"#);
+    fn uint8_overflow_errors() {
+        let e = check_err("fn f() -> void { let x: UInt8 = 256; }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
+        assert!(e.contains("UInt8"), "error must name the type; got: {e}");
     }
 
     #[test]
-    fn index_assign_float_index_errors() {
-        let err = check_err(r#"fn f(array a) -> void { a[1.5] = 1; }"#);
+    fn uint8_negative_errors() {
+        let e = check_err("fn f() -> void { let x: UInt8 = -1; }");
         assert!(
-            err.contains("integer index") || err.contains("index assignment"),
-            "expected integer-index error on index assignment; got: {err}"
+            e.contains("overflows"),
+            "expected overflow error for negative UInt8; got: {e}"
         );
     }
 
-    // ── enum duplicate variant ────────────────────────────────────────────────
-
     #[test]
-    fn enum_unique_variants_ok() {
-        check_ok(
-            r#"
-enum Color { Red, Green, Blue }
-fn f() -> void { }
-"#,
-        );
+    fn int16_overflow_errors() {
+        let e = check_err("fn f() -> void { let x: Int16 = 40000; }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
     }
 
     #[test]
-    fn enum_duplicate_variant_errors() {
-        // The parser catches duplicate variants first; either a parse error
-        // or a typecheck error is acceptable — both indicate the duplication
-        // was detected before evaluation.
-        let src = "enum Color { Red, Green, Red }\nfn f() -> void { }\n";
-        let (prog, parse_errs) = crate::parse(src);
-        if !parse_errs.is_empty() {
-            // Parser already caught it — verify the message is meaningful.
-            let combined = parse_errs.join("; ");
-            assert!(
-                combined.to_lowercase().contains("red")
-                    || combined.to_lowercase().contains("duplicate")
-                    || combined.to_lowercase().contains("variant"),
-                "parse error should mention duplicate variant; got: {combined}"
-            );
-            return;
-        }
-        // Typechecker should catch it if parser didn't.
-        let err = TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect_err("expected duplicate-variant error from typechecker");
-        assert!(
-            err.contains("duplicate variant") || err.contains("Red"),
-            "expected duplicate-variant error; got: {err}"
-        );
-    }
-}
-
-// ── RES-407: field access on known struct + slice endpoint types ──────────────
-
-#[cfg(test)]
-mod res407_field_access_and_slice {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
-
-    fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect("expected no type error");
-    }
-
-    fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect_err("expected type error")
+    fn uint16_overflow_errors() {
+        let e = check_err("fn f() -> void { let x: UInt16 = 70000; }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
     }
 
-    // ── FieldAccess: unknown field on known struct ─────────────────────────────
-
     #[test]
-    fn field_access_known_field_ok() {
-        check_ok(
-            r#"
-struct Point { int x, int y }
-fn f(Point p) -> int { return p.x; }
-"#,
-        );
+    fn int32_max_boundary_ok() {
+        check_ok("fn f() -> void { let x: Int32 = 2147483647; }");
     }
 
     #[test]
-    fn field_access_unknown_field_errors() {
-        let err = check_err(
-            r#"
-struct Point { int x, int y }
-fn f(Point p) -> int { return p.z; }
-"#,
-        );
-        assert!(
-            err.contains("no field") || err.contains("z"),
-            "expected no-field error; got: {err}"
-        );
+    fn uint32_overflow_errors() {
+        let e = check_err("fn f() -> void { let x: UInt32 = 5000000000; }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
     }
 
     #[test]
-    fn field_access_unknown_struct_is_permissive() {
-        // When the struct name isn't declared, fall through to Any.
-        check_ok(r#"fn f(UnknownStruct s) -> int { return s.x; }"#);
+    fn plain_int_no_overflow_check() {
+        // Unbounded `int` never overflows.
+        check_ok("fn f() -> void { let x: int = 9999999999; }");
     }
 
-    // RES-2622: did-you-mean hints on struct field typos so users don't
-    // have to scan the available-fields list to spot a one-letter slip.
-
     #[test]
-    fn field_access_typo_suggests_close_field() {
-        let err = check_err(
-            r#"
-struct Rectangle { int width, int height }
-fn f(Rectangle r) -> int { return r.heigth; }
-"#,
-        );
-        assert!(
-            err.contains("did you mean `height`?"),
-            "expected did-you-mean hint; got: {err}"
-        );
+    fn unannotated_let_no_overflow_check() {
+        // Without a type annotation there's no declared range to check.
+        check_ok("fn f() -> void { let x = 300; }");
     }
 
+    // RES-3880: UInt64 was missing from the range check, so negative literals
+    // (folded to a negative i64) slipped into an unsigned type.
     #[test]
-    fn struct_literal_typo_suggests_close_field() {
-        let err = check_err(
-            r#"
-struct Rectangle { int width, int height }
-fn f() -> Rectangle { return new Rectangle { width: 1, heigth: 2 }; }
-"#,
-        );
-        assert!(
-            err.contains("did you mean `height`?"),
-            "expected did-you-mean hint; got: {err}"
-        );
+    fn uint64_negative_literal_errors() {
+        let e = check_err("fn f() -> void { let x: UInt64 = -1; }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
+        assert!(e.contains("UInt64"), "error must name the type; got: {e}");
     }
 
     #[test]
-    fn field_assignment_typo_suggests_close_field() {
-        let err = check_err(
-            r#"
-struct Counter { int count }
-fn f(Counter c) -> void { c.cont = 1; }
-"#,
-        );
-        assert!(
-            err.contains("did you mean `count`?"),
-            "expected did-you-mean hint; got: {err}"
-        );
+    fn uint64_large_positive_ok() {
+        check_ok("fn f() -> void { let x: UInt64 = 9223372036854775807; }");
     }
 
     #[test]
-    fn match_pattern_typo_suggests_close_field() {
-        let err = check_err(
-            r#"
-struct Vec2 { int width, int height }
-fn f(Vec2 v) -> int {
-    return match v {
-        Vec2 { widht, height } => width + height,
-        _ => 0,
-    };
-}
-"#,
-        );
-        assert!(
-            err.contains("did you mean `width`?"),
-            "expected did-you-mean hint; got: {err}"
-        );
+    fn const_uint64_negative_errors() {
+        let e = check_err("const N: UInt64 = -5;\nfn f() -> void { }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
     }
 
+    // RES-3880: the range check now also fires at call arguments, returns,
+    // and struct fields — not just let/const.
     #[test]
-    fn field_access_far_typo_omits_hint() {
-        // Edit distance 4 — too far to suggest. Error stands without hint.
-        let err = check_err(
-            r#"
-struct Tiny { int n }
-fn f(Tiny t) -> int { return t.completely_different; }
-"#,
-        );
-        assert!(
-            err.contains("has no field"),
-            "expected no-field error; got: {err}"
-        );
+    fn call_argument_overflow_errors() {
+        let e =
+            check_err("fn takes_byte(uint8 b) -> void { }\nfn f() -> void { takes_byte(999); }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
         assert!(
-            !err.contains("did you mean"),
-            "did not expect did-you-mean hint at distance > 2; got: {err}"
+            e.contains("argument 1"),
+            "error must name the argument; got: {e}"
         );
     }
 
-    // ── Slice endpoint types ───────────────────────────────────────────────────
-
     #[test]
-    fn slice_int_bounds_ok() {
-        check_ok(r#"fn f(array a) -> array { return a[1..3]; }"#);
+    fn call_argument_in_range_ok() {
+        check_ok("fn takes_byte(uint8 b) -> void { }\nfn f() -> void { takes_byte(200); }");
     }
 
     #[test]
-    fn slice_float_lower_bound_errors() {
-        let err = check_err(r#"fn f(array a) -> array { return a[1.0..3]; }"#);
+    fn return_value_overflow_errors() {
+        let e = check_err("fn g() -> uint8 { return 999; }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
         assert!(
-            err.contains("lower bound") || err.contains("integer"),
-            "expected integer-bound error; got: {err}"
+            e.contains("return value"),
+            "error must name the site; got: {e}"
         );
     }
 
     #[test]
-    fn slice_float_upper_bound_errors() {
-        let err = check_err(r#"fn f(array a) -> array { return a[0..3.5]; }"#);
+    fn return_value_in_range_ok() {
+        check_ok("fn g() -> uint8 { return 200; }");
+    }
+
+    #[test]
+    fn struct_field_overflow_errors() {
+        let e = check_err("struct P { uint8 x }\nfn f() -> void { let _p = new P { x: 999 }; }");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
         assert!(
-            err.contains("upper bound") || err.contains("integer"),
-            "expected integer-bound error; got: {err}"
+            e.contains("field `x`"),
+            "error must name the field; got: {e}"
         );
     }
 
     #[test]
-    fn slice_string_int_bounds_ok() {
-        check_ok(r#"fn f(string s) -> string { return s[0..5]; }"#);
+    fn struct_field_in_range_ok() {
+        check_ok("struct P { uint8 x }\nfn f() -> void { let _p = new P { x: 200 }; }");
     }
 }
 
-// ── RES-408: `any` type annotation → Type::Any + struct pattern permissiveness ─
+// ── RES-412: string/array method return type precision ───────────────────────
 
 #[cfg(test)]
-mod res408_any_type_annotation {
+mod res412_method_return_types {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -16098,84 +15074,108 @@ mod res408_any_type_annotation {
             .expect("expected no type error");
     }
 
-    fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect_err("expected type error")
+    #[test]
+    fn array_len_returns_int() {
+        // arr.len() should be usable where an int is expected.
+        check_ok(r#"fn f() -> int { let arr = [1, 2, 3]; return arr.len(); }"#);
     }
 
     #[test]
-    fn any_param_accepts_int_arg() {
-        check_ok(
-            r#"
-fn f(any x) -> int { return 0; }
-fn g() -> int { return f(42); }
-"#,
-        );
+    fn string_len_returns_int() {
+        check_ok(r#"fn f() -> int { let s = "hello"; return s.len(); }"#);
     }
 
     #[test]
-    fn any_param_accepts_string_arg() {
-        check_ok(
-            r#"
-fn f(any x) -> int { return 0; }
-fn g() -> int { return f("hello"); }
-"#,
-        );
+    fn string_trim_returns_string() {
+        check_ok(r#"fn f() -> string { let s = "  hi  "; return s.trim(); }"#);
     }
 
     #[test]
-    fn any_return_ok() {
-        check_ok(r#"fn f() -> any { return 42; }"#);
+    fn string_to_upper_returns_string() {
+        check_ok(r#"fn f() -> string { let s = "hello"; return s.to_upper(); }"#);
     }
 
     #[test]
-    fn struct_pattern_on_any_scrutinee_ok() {
-        check_ok(
-            r#"
-struct Point { int x, int y }
-fn f(any p) -> int {
-    return match p {
-        Point { x, y } => x,
-        _ => 0,
-    };
-}
-"#,
-        );
+    fn string_to_lower_returns_string() {
+        check_ok(r#"fn f() -> string { let s = "HELLO"; return s.to_lower(); }"#);
     }
 
     #[test]
-    fn struct_pattern_wrong_struct_errors() {
-        let err = check_err(
-            r#"
-struct Point { int x, int y }
-struct Rect { int w, int h }
-fn f(Rect r) -> int {
-    return match r {
-        Point { x, y } => x,
-        _ => 0,
-    };
-}
-"#,
-        );
-        assert!(
-            err.contains("Point") || err.contains("Rect") || err.contains("pattern"),
-            "expected struct mismatch error; got: {err}"
-        );
+    fn string_contains_returns_bool() {
+        check_ok(r#"fn f() -> bool { let s = "hello world"; return s.contains("world"); }"#);
     }
 
     #[test]
-    fn any_let_binding_ok() {
-        check_ok(r#"fn f() -> void { let x: any = 5; let _y: any = "hello"; }"#);
+    fn string_starts_with_returns_bool() {
+        check_ok(r#"fn f() -> bool { let s = "hello"; return s.starts_with("he"); }"#);
+    }
+
+    #[test]
+    fn string_ends_with_returns_bool() {
+        check_ok(r#"fn f() -> bool { let s = "hello"; return s.ends_with("lo"); }"#);
+    }
+
+    #[test]
+    fn string_replace_returns_string() {
+        check_ok(r#"fn f() -> string { let s = "hello"; return s.replace("l", "r"); }"#);
+    }
+
+    #[test]
+    fn array_contains_returns_bool() {
+        check_ok(r#"fn f() -> bool { let arr = [1, 2, 3]; return arr.contains(2); }"#);
+    }
+
+    #[test]
+    fn array_join_returns_string() {
+        check_ok(r#"fn f() -> string { let arr = ["a", "b"]; return arr.join(", "); }"#);
+    }
+
+    // RES-2734: zero-extra-arg methods were incorrectly registered with 1 param.
+    #[test]
+    fn array_sort_accepts_no_args() {
+        check_ok(r#"fn f() -> array { let arr = [3, 1, 2]; return arr.sort(); }"#);
+    }
+
+    #[test]
+    fn array_sort_desc_accepts_no_args() {
+        check_ok(r#"fn f() -> array { let arr = [1, 3, 2]; return arr.sort_desc(); }"#);
+    }
+
+    #[test]
+    fn array_reverse_accepts_no_args() {
+        check_ok(r#"fn f() -> array { let arr = [1, 2, 3]; return arr.reverse(); }"#);
+    }
+
+    #[test]
+    fn array_pop_accepts_no_args() {
+        check_ok(r#"fn f() -> array { let arr = [1, 2, 3]; return arr.pop(); }"#);
+    }
+
+    #[test]
+    fn array_flatten_accepts_no_args() {
+        check_ok(r#"fn f() -> array { let arr = [[1, 2], [3]]; return arr.flatten(); }"#);
+    }
+
+    #[test]
+    fn array_dedup_accepts_no_args() {
+        check_ok(r#"fn f() -> array { let arr = [1, 1, 2]; return arr.dedup(); }"#);
+    }
+
+    #[test]
+    fn array_has_returns_bool() {
+        check_ok(r#"fn f() -> bool { let arr = [1, 2, 3]; return arr.has(2); }"#);
+    }
+
+    #[test]
+    fn array_slice_returns_array() {
+        check_ok(r#"fn f() -> array { let arr = [1, 2, 3, 4]; return arr.slice(1, 3); }"#);
     }
 }
 
-// ── RES-409: for-in element type inference + duplicate struct check ──────────
+// ── RES-413: static division by zero detection ───────────────────────────────
 
 #[cfg(test)]
-mod res409_forin_element_type_and_duplicate_struct {
+mod res413_div_by_zero {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -16195,88 +15195,49 @@ mod res409_forin_element_type_and_duplicate_struct {
             .expect_err("expected type error")
     }
 
-    // ── for-in element type inference ─────────────────────────────────────────
-
     #[test]
-    fn for_in_range_elem_is_int() {
-        // The loop variable `i` should be Int, so `i + 1` is valid.
-        check_ok(
-            r#"
-fn f() -> void {
-    for i in 0..10 {
-        let _x = i + 1;
-    }
-}
-"#,
+    fn div_by_zero_literal_errors() {
+        let e = check_err("fn f() -> int { return 10 / 0; }");
+        assert!(
+            e.contains("division by zero") || e.contains("by zero"),
+            "got: {e}"
         );
     }
 
     #[test]
-    fn for_in_range_elem_int_used_in_string_concat_errors() {
-        // `i` is Int from a range; `"hello" + i` is String (coercion),
-        // but `i - "hello"` is an error (not a valid arithmetic op).
-        // This validates that the range-elem binding really is Int.
-        let err = check_err(
-            r#"
-fn f() -> void {
-    for i in 0..5 {
-        let _x = i - "hello";
-    }
-}
-"#,
-        );
+    fn mod_by_zero_literal_errors() {
+        let e = check_err("fn f() -> int { return 10 % 0; }");
         assert!(
-            err.contains("Cannot apply") || err.contains("int") || err.contains("string"),
-            "expected type error for int - string in range loop; got: {err}"
+            e.contains("modulo by zero") || e.contains("by zero"),
+            "got: {e}"
         );
     }
 
     #[test]
-    fn for_in_string_elem_is_string() {
-        // Each character from a string iteration is a String.
-        check_ok(
-            r#"
-fn f(string s) -> void {
-    for c in s {
-        let _x: string = c;
-    }
-}
-"#,
+    fn div_by_const_zero_errors() {
+        let e = check_err("fn f() -> int { let d = 0; return 10 / d; }");
+        assert!(
+            e.contains("by zero"),
+            "expected division-by-zero error; got: {e}"
         );
     }
 
-    // ── duplicate struct declaration ──────────────────────────────────────────
-
     #[test]
-    fn unique_struct_ok() {
-        check_ok(
-            r#"
-struct Point { int x, int y }
-fn f() -> void { }
-"#,
-        );
+    fn div_by_nonzero_ok() {
+        check_ok("fn f() -> int { return 10 / 2; }");
     }
 
     #[test]
-    fn duplicate_struct_errors() {
-        let err = check_err(
-            r#"
-struct Point { int x, int y }
-struct Point { int a, int b }
-fn f() -> void { }
-"#,
-        );
-        assert!(
-            err.contains("duplicate") || err.contains("Point"),
-            "expected duplicate struct error; got: {err}"
-        );
+    fn div_by_variable_ok() {
+        // Non-constant divisor — can't statically check, so it's ok.
+        check_ok("fn f(int n) -> int { return 10 / n; }");
     }
 }
 
-// ── RES-410: min/max/pow type inference + duplicate TypeAlias ──────────────
+// ── RES-414: void-in-let binding detection ───────────────────────────────────
 
 #[cfg(test)]
-mod res410_numeric_poly_and_type_alias {
+mod res414_void_in_let {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -16296,69 +15257,37 @@ mod res410_numeric_poly_and_type_alias {
             .expect_err("expected type error")
     }
 
-    // ── min/max/pow type inference ────────────────────────────────────────────
-
-    #[test]
-    fn min_int_args_returns_int() {
-        // min(1, 2) should infer Int; `x + 1` must succeed.
-        check_ok(r#"fn f() -> int { let x = min(1, 2); return x + 1; }"#);
-    }
-
-    #[test]
-    fn max_float_args_returns_float() {
-        check_ok(r#"fn f() -> float { let x = max(1.0, 2.0); return x; }"#);
-    }
-
-    #[test]
-    fn pow_int_args_returns_int() {
-        check_ok(r#"fn f() -> int { let x = pow(2, 10); return x; }"#);
-    }
-
     #[test]
-    fn min_mixed_types_returns_any() {
-        // Mixed types → Any; the function call itself still succeeds.
-        check_ok(r#"fn f() -> void { let _x = min(1, 2.0); }"#);
+    fn let_void_fn_result_errors() {
+        let e = check_err("fn f() -> void { let x = print(\"hi\"); }");
+        assert!(
+            e.contains("void") || e.contains("Void"),
+            "expected void-binding error; got: {e}"
+        );
     }
 
-    // ── duplicate type alias ──────────────────────────────────────────────────
-
     #[test]
-    fn unique_type_alias_ok() {
-        check_ok(
-            r#"
-type Meters = float;
-fn f(Meters m) -> float { return m; }
-"#,
-        );
+    fn let_underscore_void_ok() {
+        // _ prefix is the discard convention — allowed for void values.
+        check_ok("fn f() -> void { let _x = print(\"hi\"); }");
     }
 
     #[test]
-    fn duplicate_type_alias_same_target_ok() {
-        // Re-declaring to the same target is silently accepted (the
-        // pre-pass may have already registered it).
-        check_ok(
-            r#"
-type Meters = float;
-fn f() -> void { }
-"#,
-        );
+    fn let_int_fn_ok() {
+        check_ok("fn f() -> int { let x = len(\"hi\"); return x; }");
     }
 
     #[test]
-    fn duplicate_type_alias_different_target_errors() {
-        // Re-declaring with a different target is a compile error.
-        let err = check_err("type M = int;\ntype M = float;\nfn f() -> void { }\n");
-        assert!(
-            err.contains("duplicate type alias") || err.contains("M"),
-            "expected duplicate-alias error; got: {err}"
-        );
+    fn void_fn_as_expression_statement_ok() {
+        // Using a void-returning fn as a plain expression statement is fine.
+        check_ok("fn f() -> void { print(\"hi\"); }");
     }
 }
 
-// ── RES-411: integer literal overflow for pinned int types ───────────────────
+// ── RES-415: map/set literal type consistency + negative index detection ──────
 
 #[cfg(test)]
-mod res411_pinned_int_overflow {
+mod res415_collection_type_checks {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -16379,162 +15308,208 @@ mod res411_pinned_int_overflow {
     }
 
     #[test]
-    fn int8_in_range_ok() {
-        check_ok("fn f() -> void { let x: Int8 = 100; }");
+    fn map_uniform_keys_ok() {
+        check_ok(r#"fn f() -> void { let _m = {"a" -> 1, "b" -> 2}; }"#);
     }
 
     #[test]
-    fn int8_min_boundary_ok() {
-        check_ok("fn f() -> void { let x: Int8 = -128; }");
+    fn map_mixed_key_types_errors() {
+        let e = check_err(r#"fn f() -> void { let _m = {"a" -> 1, 2 -> 3}; }"#);
+        assert!(
+            e.contains("mixed key types") || e.contains("key"),
+            "got: {e}"
+        );
     }
 
     #[test]
-    fn int8_max_boundary_ok() {
-        check_ok("fn f() -> void { let x: Int8 = 127; }");
+    fn map_mixed_value_types_errors() {
+        let e = check_err(r#"fn f() -> void { let _m = {"a" -> 1, "b" -> "two"}; }"#);
+        assert!(
+            e.contains("mixed value types") || e.contains("value"),
+            "got: {e}"
+        );
     }
 
     #[test]
-    fn int8_overflow_errors() {
-        let e = check_err("fn f() -> void { let x: Int8 = 300; }");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
-        assert!(e.contains("Int8"), "error must name the type; got: {e}");
+    fn set_uniform_elements_ok() {
+        check_ok(r#"fn f() -> void { let _s = #{1, 2, 3}; }"#);
     }
 
     #[test]
-    fn int8_underflow_errors() {
-        let e = check_err("fn f() -> void { let x: Int8 = -200; }");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
+    fn set_mixed_elements_errors() {
+        let e = check_err(r#"fn f() -> void { let _s = #{1, "two", 3}; }"#);
+        assert!(
+            e.contains("mixed element types") || e.contains("element"),
+            "got: {e}"
+        );
     }
 
     #[test]
-    fn uint8_in_range_ok() {
-        check_ok("fn f() -> void { let x: UInt8 = 255; }");
+    fn negative_constant_index_is_valid() {
+        // RES-921 added Python-style negative indexing (arr[-1] = last element).
+        // RES-2731 removed the false-positive compile-time rejection of negative
+        // constant indices — the runtime handles them correctly.
+        check_ok(r#"fn f() -> void { let arr = [1, 2, 3]; let x = arr[-1]; }"#);
     }
 
     #[test]
-    fn uint8_overflow_errors() {
-        let e = check_err("fn f() -> void { let x: UInt8 = 256; }");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
-        assert!(e.contains("UInt8"), "error must name the type; got: {e}");
+    fn string_index_returns_char() {
+        // RES-2709 + RES-2711: s[i] now produces a char, not a single-char string.
+        check_ok(r#"fn f() -> char { let s = "hello"; return s[0]; }"#);
     }
+}
 
-    #[test]
-    fn uint8_negative_errors() {
-        let e = check_err("fn f() -> void { let x: UInt8 = -1; }");
-        assert!(
-            e.contains("overflows"),
-            "expected overflow error for negative UInt8; got: {e}"
-        );
-    }
+// ── RES-416: const pinned-int overflow + enum constructor type checking ────────
 
-    #[test]
-    fn int16_overflow_errors() {
-        let e = check_err("fn f() -> void { let x: Int16 = 40000; }");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
+#[cfg(test)]
+mod res416_const_and_enum_checks {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn check_ok(src: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect("expected no type error");
     }
 
-    #[test]
-    fn uint16_overflow_errors() {
-        let e = check_err("fn f() -> void { let x: UInt16 = 70000; }");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected type error")
     }
 
     #[test]
-    fn int32_max_boundary_ok() {
-        check_ok("fn f() -> void { let x: Int32 = 2147483647; }");
+    fn const_int8_in_range_ok() {
+        check_ok("const N: Int8 = 100;");
     }
 
     #[test]
-    fn uint32_overflow_errors() {
-        let e = check_err("fn f() -> void { let x: UInt32 = 5000000000; }");
+    fn const_int8_overflow_errors() {
+        let e = check_err("const N: Int8 = 300;");
         assert!(e.contains("overflows"), "expected overflow error; got: {e}");
     }
 
     #[test]
-    fn plain_int_no_overflow_check() {
-        // Unbounded `int` never overflows.
-        check_ok("fn f() -> void { let x: int = 9999999999; }");
+    fn const_uint8_overflow_errors() {
+        let e = check_err("const N: UInt8 = 256;");
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
     }
 
     #[test]
-    fn unannotated_let_no_overflow_check() {
-        // Without a type annotation there's no declared range to check.
-        check_ok("fn f() -> void { let x = 300; }");
+    fn enum_constructor_correct_types_ok() {
+        check_ok(
+            r#"
+enum Shape { Circle(int) }
+fn f() -> void { let _s = Shape::Circle(5); }
+"#,
+        );
     }
 
-    // RES-3880: UInt64 was missing from the range check, so negative literals
-    // (folded to a negative i64) slipped into an unsigned type.
     #[test]
-    fn uint64_negative_literal_errors() {
-        let e = check_err("fn f() -> void { let x: UInt64 = -1; }");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
-        assert!(e.contains("UInt64"), "error must name the type; got: {e}");
+    fn enum_constructor_wrong_arg_type_errors() {
+        let e = check_err(
+            r#"
+enum Shape { Circle(int) }
+fn f() -> void { let _s = Shape::Circle("not_an_int"); }
+"#,
+        );
+        assert!(
+            e.contains("argument has type") || e.contains("expected"),
+            "expected constructor type error; got: {e}"
+        );
     }
 
     #[test]
-    fn uint64_large_positive_ok() {
-        check_ok("fn f() -> void { let x: UInt64 = 9223372036854775807; }");
+    fn enum_constructor_wrong_arg_count_errors() {
+        let e = check_err(
+            r#"
+enum Shape { Circle(int) }
+fn f() -> void { let _s = Shape::Circle(1, 2); }
+"#,
+        );
+        assert!(
+            e.contains("expected") && (e.contains("arg") || e.contains("argument")),
+            "expected arity error; got: {e}"
+        );
     }
+}
 
-    #[test]
-    fn const_uint64_negative_errors() {
-        let e = check_err("const N: UInt64 = -5;\nfn f() -> void { }");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
+// ── RES-417: const declaration forward reference ──────────────────────────────
+
+#[cfg(test)]
+mod res417_const_forward_ref {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn check_ok(src: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect("expected no type error");
     }
 
-    // RES-3880: the range check now also fires at call arguments, returns,
-    // and struct fields — not just let/const.
-    #[test]
-    fn call_argument_overflow_errors() {
-        let e =
-            check_err("fn takes_byte(uint8 b) -> void { }\nfn f() -> void { takes_byte(999); }");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
-        assert!(
-            e.contains("argument 1"),
-            "error must name the argument; got: {e}"
-        );
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected type error")
     }
 
     #[test]
-    fn call_argument_in_range_ok() {
-        check_ok("fn takes_byte(uint8 b) -> void { }\nfn f() -> void { takes_byte(200); }");
+    fn fn_forward_refs_const_ok() {
+        // Function defined before the const it uses — must succeed.
+        check_ok(
+            r#"
+fn f() -> int { return N; }
+const N: int = 5;
+"#,
+        );
     }
 
     #[test]
-    fn return_value_overflow_errors() {
-        let e = check_err("fn g() -> uint8 { return 999; }");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
-        assert!(
-            e.contains("return value"),
-            "error must name the site; got: {e}"
+    fn const_used_in_expression_ok() {
+        check_ok(
+            r#"
+fn f() -> int { return MAX + 1; }
+const MAX: int = 100;
+"#,
         );
     }
 
     #[test]
-    fn return_value_in_range_ok() {
-        check_ok("fn g() -> uint8 { return 200; }");
+    fn const_after_fn_type_mismatch_errors() {
+        // N is declared as Int8 = 300 — overflow should still be caught
+        // even when the const appears after its use site.
+        let e = check_err(
+            r#"
+const N: Int8 = 300;
+fn f() -> void { }
+"#,
+        );
+        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
     }
 
     #[test]
-    fn struct_field_overflow_errors() {
-        let e = check_err("struct P { uint8 x }\nfn f() -> void { let _p = new P { x: 999 }; }");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
-        assert!(
-            e.contains("field `x`"),
-            "error must name the field; got: {e}"
-        );
+    fn const_normal_declaration_ok() {
+        check_ok("const N: int = 42;");
     }
 
     #[test]
-    fn struct_field_in_range_ok() {
-        check_ok("struct P { uint8 x }\nfn f() -> void { let _p = new P { x: 200 }; }");
+    fn const_float_ok() {
+        check_ok("const PI: float = 3.14;");
     }
 }
 
-// ── RES-412: string/array method return type precision ───────────────────────
+// ── RES-418: struct literal unknown-field detection ───────────────────────────
 
 #[cfg(test)]
-mod res412_method_return_types {
+mod res418_struct_literal_unknown_field {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -16546,516 +15521,734 @@ mod res412_method_return_types {
             .expect("expected no type error");
     }
 
-    #[test]
-    fn array_len_returns_int() {
-        // arr.len() should be usable where an int is expected.
-        check_ok(r#"fn f() -> int { let arr = [1, 2, 3]; return arr.len(); }"#);
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program_with_source(&prog, "test.rz")
+            .expect_err("expected type error")
     }
 
     #[test]
-    fn string_len_returns_int() {
-        check_ok(r#"fn f() -> int { let s = "hello"; return s.len(); }"#);
+    fn known_fields_ok() {
+        check_ok(
+            r#"
+struct Point { int x, int y }
+fn f() -> void { let _p = new Point { x: 1, y: 2 }; }
+"#,
+        );
     }
 
     #[test]
-    fn string_trim_returns_string() {
-        check_ok(r#"fn f() -> string { let s = "  hi  "; return s.trim(); }"#);
+    fn unknown_field_errors() {
+        let e = check_err(
+            r#"
+struct Point { int x, int y }
+fn f() -> void { let _p = new Point { x: 1, z: 2 }; }
+"#,
+        );
+        assert!(
+            e.contains("has no field") || e.contains("no field"),
+            "expected unknown-field error; got: {e}"
+        );
+        assert!(
+            e.contains("z"),
+            "error must name the unknown field; got: {e}"
+        );
     }
 
     #[test]
-    fn string_to_upper_returns_string() {
-        check_ok(r#"fn f() -> string { let s = "hello"; return s.to_upper(); }"#);
+    fn field_type_mismatch_errors() {
+        let e = check_err(
+            r#"
+struct Point { int x, int y }
+fn f() -> void { let _p = new Point { x: "not_an_int", y: 2 }; }
+"#,
+        );
+        assert!(
+            e.contains("type") || e.contains("int"),
+            "expected type mismatch error; got: {e}"
+        );
     }
 
     #[test]
-    fn string_to_lower_returns_string() {
-        check_ok(r#"fn f() -> string { let s = "HELLO"; return s.to_lower(); }"#);
+    fn struct_on_unknown_struct_errors() {
+        // Nominal typing: a literal must name a declared struct.
+        let e = check_err("fn f() -> void { let _p = new UnknownStruct { foo: 1 }; }");
+        assert!(e.contains("unknown struct `UnknownStruct`"), "got: {e}");
+        assert!(
+            e.starts_with("test.rz:1:"),
+            "expected a located error; got: {e}"
+        );
     }
 
     #[test]
-    fn string_contains_returns_bool() {
-        check_ok(r#"fn f() -> bool { let s = "hello world"; return s.contains("world"); }"#);
+    fn struct_literal_typo_suggests_declared_struct() {
+        let e = check_err(
+            "struct Point { int x, int y, }\nfn f() -> void { let _p = new Pont { x: 1, y: 2 }; }",
+        );
+        assert!(e.contains("unknown struct `Pont`"), "got: {e}");
+        assert!(e.contains("did you mean `Point`?"), "got: {e}");
     }
 
     #[test]
-    fn string_starts_with_returns_bool() {
-        check_ok(r#"fn f() -> bool { let s = "hello"; return s.starts_with("he"); }"#);
+    fn struct_declared_after_use_ok() {
+        // Declarations aren't hoisted, but the unknown-struct verdict is
+        // deferred until every `StructDecl` has been seen.
+        check_ok("fn f() -> void { let _p = new Later { v: 1 }; }\nstruct Later { int v, }");
     }
 
     #[test]
-    fn string_ends_with_returns_bool() {
-        check_ok(r#"fn f() -> bool { let s = "hello"; return s.ends_with("lo"); }"#);
+    fn misspelled_param_type_suggests_declared_struct() {
+        let e = check_err("struct Point { int x, }\nfn g(Pointt p) -> int { return 1; }");
+        assert!(e.contains("Unknown type: Pointt"), "got: {e}");
+        assert!(e.contains("did you mean `Point`?"), "got: {e}");
     }
 
     #[test]
-    fn string_replace_returns_string() {
-        check_ok(r#"fn f() -> string { let s = "hello"; return s.replace("l", "r"); }"#);
+    fn misspelled_let_type_suggests_builtin() {
+        let e = check_err("let s: Strng = \"a\";");
+        assert!(e.contains("did you mean `String`, `string`?"), "got: {e}");
     }
 
     #[test]
-    fn array_contains_returns_bool() {
-        check_ok(r#"fn f() -> bool { let arr = [1, 2, 3]; return arr.contains(2); }"#);
+    fn unknown_type_without_close_match_stays_permissive() {
+        check_ok("fn g(Quux q) -> int { return 1; }");
     }
 
     #[test]
-    fn array_join_returns_string() {
-        check_ok(r#"fn f() -> string { let arr = ["a", "b"]; return arr.join(", "); }"#);
+    fn generic_param_is_not_an_unknown_type() {
+        // `Strg` is one edit from `String` but declared as a type parameter.
+        check_ok("fn<Strg> first(Strg x) -> Strg { return x; }");
     }
 
-    // RES-2734: zero-extra-arg methods were incorrectly registered with 1 param.
     #[test]
-    fn array_sort_accepts_no_args() {
-        check_ok(r#"fn f() -> array { let arr = [3, 1, 2]; return arr.sort(); }"#);
+    fn self_referential_struct_through_option_ok() {
+        check_ok(
+            "struct Link { int v, Option<Link> next, }\n\
+             fn len(Option<Link> l) -> int {\n\
+                 match l { Some(n) => { return 1 + len(n.next); }, None => { return 0; }, }\n\
+             }\n\
+             let n: int = len(Some(new Link { v: 1, next: None }));",
+        );
     }
 
     #[test]
-    fn array_sort_desc_accepts_no_args() {
-        check_ok(r#"fn f() -> array { let arr = [1, 3, 2]; return arr.sort_desc(); }"#);
+    fn mutually_recursive_structs_through_indirection_ok() {
+        check_ok(
+            "struct Tree { int v, Forest kids, }\n\
+             struct Forest { Array<Tree> items, Option<Tree> first, }",
+        );
     }
 
     #[test]
-    fn array_reverse_accepts_no_args() {
-        check_ok(r#"fn f() -> array { let arr = [1, 2, 3]; return arr.reverse(); }"#);
+    fn struct_containing_itself_directly_is_rejected() {
+        let e = check_err("struct Bad { int v, Bad inner, }");
+        assert!(
+            e.contains("recursive struct `Bad` has infinite size: Bad -> Bad"),
+            "got: {e}"
+        );
+        assert!(e.contains("`Option<Bad>`"), "got: {e}");
+        assert!(e.starts_with("test.rz:1:"), "got: {e}");
     }
 
     #[test]
-    fn array_pop_accepts_no_args() {
-        check_ok(r#"fn f() -> array { let arr = [1, 2, 3]; return arr.pop(); }"#);
+    fn mutual_recursion_without_indirection_is_rejected() {
+        let e = check_err("struct A { int v, B b, }\nstruct B { A a, }");
+        assert!(e.contains("A -> B -> A"), "got: {e}");
+    }
+}
+
+// =====================================================
+// RES-419: fn(T)->R type annotation parsing
+// ============================================================
+#[cfg(test)]
+mod res419_fn_type_annotation {
+    use super::*;
+
+    fn check_ok(src: &str) {
+        let (prog, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program(&prog)
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
     #[test]
-    fn array_flatten_accepts_no_args() {
-        check_ok(r#"fn f() -> array { let arr = [[1, 2], [3]]; return arr.flatten(); }"#);
+    fn fn_param_callable_as_function() {
+        // A higher-order function with a fn(int) -> int parameter must
+        // be callable inside the body without a type error.
+        check_ok(
+            r#"
+fn apply(int x, fn(int) -> int f) -> int { return f(x); }
+fn double(int x) -> int { return x * 2; }
+fn main(int _d) -> int { return apply(5, double); }
+"#,
+        );
     }
 
     #[test]
-    fn array_dedup_accepts_no_args() {
-        check_ok(r#"fn f() -> array { let arr = [1, 1, 2]; return arr.dedup(); }"#);
+    fn fn_param_zero_args_callable() {
+        check_ok(
+            r#"
+fn call_zero(fn() -> int f) -> int { return f(); }
+fn get_one() -> int { return 1; }
+fn main(int _d) -> int { return call_zero(get_one); }
+"#,
+        );
     }
 
     #[test]
-    fn array_has_returns_bool() {
-        check_ok(r#"fn f() -> bool { let arr = [1, 2, 3]; return arr.has(2); }"#);
+    fn fn_param_multi_arg_callable() {
+        check_ok(
+            r#"
+fn apply2(int x, int y, fn(int, int) -> int f) -> int { return f(x, y); }
+fn add(int a, int b) -> int { return a + b; }
+fn main(int _d) -> int { return apply2(3, 4, add); }
+"#,
+        );
     }
 
     #[test]
-    fn array_slice_returns_array() {
-        check_ok(r#"fn f() -> array { let arr = [1, 2, 3, 4]; return arr.slice(1, 3); }"#);
+    fn fn_return_type_inferred_from_annotation() {
+        // When calling a fn-typed parameter the return type is the
+        // declared return type of the function annotation.
+        check_ok(
+            r#"
+fn transform(string s, fn(string) -> string f) -> string { return f(s); }
+fn shout(string x) -> string { return to_upper(x); }
+fn main(int _d) -> int {
+    let _r = transform("hello", shout);
+    return 0;
+}
+"#,
+        );
     }
+
+    #[test]
+    fn fn_type_in_let_binding() {
+        // A let binding with a fn(...) -> ... annotation should work.
+        check_ok(
+            r#"
+fn id(int x) -> int { return x; }
+fn main(int _d) -> int {
+    let f: fn(int) -> int = id;
+    return f(3);
 }
+"#,
+        );
+    }
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected a type error but got Ok")
+    }
 
-// ── RES-413: static division by zero detection ───────────────────────────────
+    #[test]
+    fn mismatched_fn_argument_is_rejected() {
+        let e = check_err(
+            "fn use1(fn(int) -> int g) -> int { return g(1); }\n\
+             fn neg(string s) -> int { return 1; }\n\
+             let _r = use1(neg);",
+        );
+        assert!(
+            e.contains("expected fn(int) -> int, got fn(string) -> int"),
+            "{e}"
+        );
+    }
 
-#[cfg(test)]
-mod res413_div_by_zero {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
+    #[test]
+    fn call_through_param_checks_arguments() {
+        let e = check_err("fn bad(fn(int) -> int g) -> int { return g(\"s\"); }");
+        assert!(e.contains("expected int, got string"), "{e}");
+    }
 
-    fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect("expected no type error");
+    #[test]
+    fn let_annotation_checks_fn_signature() {
+        let e = check_err(
+            "fn add(int x, int y) -> int { return x + y; }\nlet h: fn(int) -> int = add;",
+        );
+        assert!(e.contains("value has type fn(int, int) -> int"), "{e}");
     }
 
-    fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
+    #[test]
+    fn fn_typed_struct_field_is_called_without_receiver() {
+        check_ok(
+            "struct Op { fn(int) -> int step, }\n\
+             let o = new Op { step: fn(int x) -> int { return x + 1; } };\n\
+             let _n: int = o.step(41);",
+        );
+        let e = check_err(
+            "struct Op { fn(int) -> int step, }\n\
+             let o = new Op { step: fn(int x) -> int { return x + 1; } };\n\
+             let _s: string = o.step(1);",
+        );
+        assert!(e.contains("value has type int"), "{e}");
+    }
+}
+
+// ============================================================
+// RES-420: for-in element type from builtin call
+// ============================================================
+#[cfg(test)]
+mod res420_forin_element_type {
+    use super::*;
+
+    fn check_ok(src: &str) {
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect_err("expected type error")
+            .check_program(&prog)
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
     #[test]
-    fn div_by_zero_literal_errors() {
-        let e = check_err("fn f() -> int { return 10 / 0; }");
-        assert!(
-            e.contains("division by zero") || e.contains("by zero"),
-            "got: {e}"
-        );
+    fn forin_array_range_element_is_int() {
+        // `array_range(lo, hi)` returns `Array`; element type is Int.
+        // The arithmetic `x + 1` must not produce a type error.
+        check_ok(
+            r#"
+fn main(int _d) -> int {
+    let s = 0;
+    for x in array_range(0, 5) {
+        let s = s + x;
     }
-
-    #[test]
-    fn mod_by_zero_literal_errors() {
-        let e = check_err("fn f() -> int { return 10 % 0; }");
-        assert!(
-            e.contains("modulo by zero") || e.contains("by zero"),
-            "got: {e}"
+    return s;
+}
+"#,
         );
     }
 
     #[test]
-    fn div_by_const_zero_errors() {
-        let e = check_err("fn f() -> int { let d = 0; return 10 / d; }");
-        assert!(
-            e.contains("by zero"),
-            "expected division-by-zero error; got: {e}"
+    fn forin_split_element_is_string() {
+        // `split(str, sep)` → Array of strings; element type is String.
+        check_ok(
+            r#"
+fn main(int _d) -> int {
+    for word in split("hello world", " ") {
+        let _len = len(word);
+    }
+    return 0;
+}
+"#,
         );
     }
 
     #[test]
-    fn div_by_nonzero_ok() {
-        check_ok("fn f() -> int { return 10 / 2; }");
+    fn forin_string_split_element_is_string() {
+        check_ok(
+            r#"
+fn main(int _d) -> int {
+    for tok in string_split("a,b,c", ",") {
+        let _u = to_upper(tok);
+    }
+    return 0;
+}
+"#,
+        );
     }
 
     #[test]
-    fn div_by_variable_ok() {
-        // Non-constant divisor — can't statically check, so it's ok.
-        check_ok("fn f(int n) -> int { return 10 / n; }");
+    fn forin_range_syntax_still_int() {
+        // The original Node::Range { .. } case must still work.
+        check_ok(
+            r#"
+fn main(int _d) -> int {
+    let acc = 0;
+    for i in 0..5 { let acc = acc + i; }
+    return acc;
+}
+"#,
+        );
     }
 }
 
-// ── RES-414: void-in-let binding detection ───────────────────────────────────
-
+// ============================================================
+// RES-421: IfStatement type with diverging branch
+// ============================================================
 #[cfg(test)]
-mod res414_void_in_let {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
+mod res421_if_diverging_branch {
+    use super::*;
 
     fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect("expected no type error");
+            .check_program(&prog)
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
     fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
+            .check_program(&prog)
             .expect_err("expected type error")
     }
 
     #[test]
-    fn let_void_fn_result_errors() {
-        let e = check_err("fn f() -> void { let x = print(\"hi\"); }");
-        assert!(
-            e.contains("void") || e.contains("Void"),
-            "expected void-binding error; got: {e}"
+    fn if_else_consequence_diverges_uses_alt_type() {
+        // consequence returns early; else branch is Int — whole expr is Int.
+        check_ok(
+            r#"
+fn guard(bool bad) -> int {
+    let x = if bad { return 0; } else { 42 };
+    return x;
+}
+fn main(int _d) -> int { return guard(false); }
+"#,
         );
     }
 
     #[test]
-    fn let_underscore_void_ok() {
-        // _ prefix is the discard convention — allowed for void values.
-        check_ok("fn f() -> void { let _x = print(\"hi\"); }");
+    fn if_else_alternative_diverges_uses_cons_type() {
+        // alternative returns early; consequence is Int — whole expr is Int.
+        check_ok(
+            r#"
+fn guard2(bool bad) -> int {
+    let x = if bad { 42 } else { return 0; };
+    return x;
+}
+fn main(int _d) -> int { return guard2(false); }
+"#,
+        );
     }
 
     #[test]
-    fn let_int_fn_ok() {
-        check_ok("fn f() -> int { let x = len(\"hi\"); return x; }");
+    fn both_branches_non_diverging_must_match() {
+        // Neither diverges and types differ → type error (pre-existing behaviour).
+        let e = check_err(
+            r#"
+fn f(bool b) -> int {
+    let x = if b { 1 } else { "two" };
+    return x;
+}
+"#,
+        );
+        assert!(
+            e.contains("incompatible") || e.contains("mismatch") || e.contains("type"),
+            "expected incompatible-types error; got: {e}"
+        );
     }
 
     #[test]
-    fn void_fn_as_expression_statement_ok() {
-        // Using a void-returning fn as a plain expression statement is fine.
-        check_ok("fn f() -> void { print(\"hi\"); }");
+    fn guard_clause_pattern_compiles() {
+        // Classic guard: early return in if-without-else is fine.
+        check_ok(
+            r#"
+fn safe_sqrt(int n) -> float {
+    if n < 0 { return 0.0; }
+    return sqrt(n);
+}
+fn main(int _d) -> int { return 0; }
+"#,
+        );
     }
 }
 
-// ── RES-415: map/set literal type consistency + negative index detection ──────
+// ── RES-425: generic type parameter call-site fix ────────────────────────────
 
 #[cfg(test)]
-mod res415_collection_type_checks {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
+mod res425_generic_typecheck {
+    use super::*;
 
     fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect("expected no type error");
+            .check_program(&prog)
+            .unwrap_or_else(|e| panic!("type error: {e}"));
     }
 
     fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
+            .check_program(&prog)
             .expect_err("expected type error")
     }
 
     #[test]
-    fn map_uniform_keys_ok() {
-        check_ok(r#"fn f() -> void { let _m = {"a" -> 1, "b" -> 2}; }"#);
+    fn generic_id_with_int_no_false_positive() {
+        check_ok("fn id<T>(T x) -> T { return x; }\nfn f(int a) -> int { return id(a); }");
     }
 
     #[test]
-    fn map_mixed_key_types_errors() {
-        let e = check_err(r#"fn f() -> void { let _m = {"a" -> 1, 2 -> 3}; }"#);
-        assert!(
-            e.contains("mixed key types") || e.contains("key"),
-            "got: {e}"
-        );
+    fn generic_id_with_string_no_false_positive() {
+        check_ok("fn id<T>(T x) -> T { return x; }\nfn f(string s) -> string { return id(s); }");
     }
 
     #[test]
-    fn map_mixed_value_types_errors() {
-        let e = check_err(r#"fn f() -> void { let _m = {"a" -> 1, "b" -> "two"}; }"#);
-        assert!(
-            e.contains("mixed value types") || e.contains("value"),
-            "got: {e}"
+    fn generic_two_params_no_false_positive() {
+        check_ok(
+            "fn first<A, B>(A a, B b) -> A { return a; }\n\
+             fn f(int x, string y) -> int { return first(x, y); }",
         );
     }
 
     #[test]
-    fn set_uniform_elements_ok() {
-        check_ok(r#"fn f() -> void { let _s = #{1, 2, 3}; }"#);
+    fn generic_accepts_different_types_at_different_sites() {
+        check_ok(
+            "fn wrap<T>(T x) -> T { return x; }\n\
+             fn f(int a) -> int { return wrap(a); }\n\
+             fn g(string s) -> string { return wrap(s); }",
+        );
     }
 
     #[test]
-    fn set_mixed_elements_errors() {
-        let e = check_err(r#"fn f() -> void { let _s = #{1, "two", 3}; }"#);
+    fn non_generic_type_mismatch_still_caught() {
+        let e = check_err(
+            "fn takes_int(int x) -> int { return x; }\n\
+             fn f(string s) -> int { return takes_int(s); }",
+        );
         assert!(
-            e.contains("mixed element types") || e.contains("element"),
-            "got: {e}"
+            e.contains("mismatch") || e.contains("expected int"),
+            "expected type mismatch; got: {e}"
         );
     }
-
-    #[test]
-    fn negative_constant_index_is_valid() {
-        // RES-921 added Python-style negative indexing (arr[-1] = last element).
-        // RES-2731 removed the false-positive compile-time rejection of negative
-        // constant indices — the runtime handles them correctly.
-        check_ok(r#"fn f() -> void { let arr = [1, 2, 3]; let x = arr[-1]; }"#);
-    }
-
-    #[test]
-    fn string_index_returns_char() {
-        // RES-2709 + RES-2711: s[i] now produces a char, not a single-char string.
-        check_ok(r#"fn f() -> char { let s = "hello"; return s[0]; }"#);
-    }
 }
 
-// ── RES-416: const pinned-int overflow + enum constructor type checking ────────
+// ── RES-426: tuple return type in function signatures ────────────────────────
 
 #[cfg(test)]
-mod res416_const_and_enum_checks {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
+mod res426_tuple_return_type {
+    use super::*;
 
     fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect("expected no type error");
-    }
-
-    fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect_err("expected type error")
-    }
-
-    #[test]
-    fn const_int8_in_range_ok() {
-        check_ok("const N: Int8 = 100;");
-    }
-
-    #[test]
-    fn const_int8_overflow_errors() {
-        let e = check_err("const N: Int8 = 300;");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
+        TypeChecker::new()
+            .check_program(&prog)
+            .unwrap_or_else(|e| panic!("type error: {e}"));
     }
 
     #[test]
-    fn const_uint8_overflow_errors() {
-        let e = check_err("const N: UInt8 = 256;");
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
+    fn tuple_return_type_parses_and_typechecks() {
+        check_ok(
+            "fn divmod(int a, int b) -> (int, int) { return (a / b, a % b); }\n\
+             fn f(int a) -> int { let r = divmod(a, 3); return 0; }",
+        );
     }
 
     #[test]
-    fn enum_constructor_correct_types_ok() {
-        check_ok(
-            r#"
-enum Shape { Circle(int) }
-fn f() -> void { let _s = Shape::Circle(5); }
-"#,
-        );
+    fn tuple_return_single_element() {
+        check_ok("fn wrap(int x) -> (int) { return (x); }\nfn f(int a) -> int { return 0; }");
     }
 
     #[test]
-    fn enum_constructor_wrong_arg_type_errors() {
-        let e = check_err(
-            r#"
-enum Shape { Circle(int) }
-fn f() -> void { let _s = Shape::Circle("not_an_int"); }
-"#,
-        );
-        assert!(
-            e.contains("argument has type") || e.contains("expected"),
-            "expected constructor type error; got: {e}"
+    fn tuple_return_three_elements() {
+        check_ok(
+            "fn triple(int x) -> (int, int, int) { return (x, x, x); }\n\
+             fn f(int a) -> int { return 0; }",
         );
     }
 
     #[test]
-    fn enum_constructor_wrong_arg_count_errors() {
-        let e = check_err(
-            r#"
-enum Shape { Circle(int) }
-fn f() -> void { let _s = Shape::Circle(1, 2); }
-"#,
-        );
-        assert!(
-            e.contains("expected") && (e.contains("arg") || e.contains("argument")),
-            "expected arity error; got: {e}"
+    fn tuple_destructure_from_function() {
+        // let (a, b) = divmod(...) should parse and type-check
+        check_ok(
+            "fn divmod(int a, int b) -> (int, int) { return (a / b, a % b); }\n\
+             fn f(int a) -> int {\n\
+                 let (q, r) = divmod(a, 3);\n\
+                 return q;\n\
+             }",
         );
     }
 }
 
-// ── RES-417: const declaration forward reference ──────────────────────────────
-
+// RES-2693: struct implementing a trait must be accepted wherever the trait
+// type is expected — function parameters, let annotations, return statements.
 #[cfg(test)]
-mod res417_const_forward_ref {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
+mod res2693_trait_param_compat {
+    use super::*;
 
     fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect("expected no type error");
+            .check_program(&prog)
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
+    fn check_err(src: &str, expected_fragment: &str) {
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect_err("expected type error")
+        let e = TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected a type error but got Ok");
+        // Case-insensitive: under RESILIENT_RICH_DIAG=1 the E0007
+        // rich format emits a lowercase "type mismatch" header
+        // instead of the legacy "Type mismatch" string.
+        assert!(
+            e.to_lowercase().contains(&expected_fragment.to_lowercase()),
+            "expected fragment {:?} not found in error: {e}",
+            expected_fragment
+        );
     }
 
     #[test]
-    fn fn_forward_refs_const_ok() {
-        // Function defined before the const it uses — must succeed.
+    fn struct_accepted_as_trait_fn_param() {
         check_ok(
-            r#"
-fn f() -> int { return N; }
-const N: int = 5;
-"#,
+            "trait Greet { fn greet(self) -> string; }\n\
+             struct Alice { int x }\n\
+             impl Greet for Alice {\n\
+               fn greet(self) -> string { return \"hi\"; }\n\
+             }\n\
+             fn say(Greet g) -> void { println(g.greet()); }\n\
+             let a = new Alice { x: 1 };\n\
+             say(a);\n",
         );
     }
 
     #[test]
-    fn const_used_in_expression_ok() {
+    fn struct_accepted_for_trait_let_annotation() {
         check_ok(
-            r#"
-fn f() -> int { return MAX + 1; }
-const MAX: int = 100;
-"#,
+            "trait Greet { fn greet(self) -> string; }\n\
+             struct Bob { int y }\n\
+             impl Greet for Bob {\n\
+               fn greet(self) -> string { return \"hello\"; }\n\
+             }\n\
+             let b: Greet = new Bob { y: 2 };\n\
+             println(b.greet());\n",
         );
     }
 
     #[test]
-    fn const_after_fn_type_mismatch_errors() {
-        // N is declared as Int8 = 300 — overflow should still be caught
-        // even when the const appears after its use site.
-        let e = check_err(
-            r#"
-const N: Int8 = 300;
-fn f() -> void { }
-"#,
+    fn struct_accepted_as_trait_return_type() {
+        check_ok(
+            "trait Greet { fn greet(self) -> string; }\n\
+             struct Carol { int z }\n\
+             impl Greet for Carol {\n\
+               fn greet(self) -> string { return \"hey\"; }\n\
+             }\n\
+             fn make() -> Greet { return new Carol { z: 3 }; }\n",
         );
-        assert!(e.contains("overflows"), "expected overflow error; got: {e}");
     }
 
     #[test]
-    fn const_normal_declaration_ok() {
-        check_ok("const N: int = 42;");
+    fn non_implementing_struct_still_errors() {
+        check_err(
+            "trait Greet { fn greet(self) -> string; }\n\
+             struct Dave { int w }\n\
+             fn say(Greet g) -> void { println(g.greet()); }\n\
+             let d = new Dave { w: 4 };\n\
+             say(d);\n",
+            "Type mismatch",
+        );
     }
 
     #[test]
-    fn const_float_ok() {
-        check_ok("const PI: float = 3.14;");
+    fn multiple_impls_different_traits_independent() {
+        // struct implementing two traits can satisfy either
+        check_ok(
+            "trait Greet { fn greet(self) -> string; }\n\
+             trait Farewell { fn bye(self) -> string; }\n\
+             struct Eve { int n }\n\
+             impl Greet for Eve { fn greet(self) -> string { return \"hi\"; } }\n\
+             impl Farewell for Eve { fn bye(self) -> string { return \"bye\"; } }\n\
+             fn say_hi(Greet g) -> void { println(g.greet()); }\n\
+             fn say_bye(Farewell f) -> void { println(f.bye()); }\n\
+             let e = new Eve { n: 0 };\n\
+             say_hi(e);\n\
+             say_bye(e);\n",
+        );
     }
 }
 
-// ── RES-418: struct literal unknown-field detection ───────────────────────────
-
 #[cfg(test)]
-mod res418_struct_literal_unknown_field {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
+mod res2701_generic_fn_type_params {
+    use super::*;
 
     fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect("expected no type error");
+            .check_program(&prog)
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
+    fn check_err(src: &str, fragment: &str) {
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program_with_source(&prog, "test.rz")
-            .expect_err("expected type error")
+        let e = TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected a type error but got Ok");
+        // Case-insensitive: under RESILIENT_RICH_DIAG=1 the E0007
+        // rich format emits a lowercase "type mismatch" header
+        // instead of the legacy "Type mismatch" string.
+        assert!(
+            e.to_lowercase().contains(&fragment.to_lowercase()),
+            "expected {:?} in error: {e}",
+            fragment
+        );
     }
 
     #[test]
-    fn known_fields_ok() {
+    fn lambda_accepted_for_fn_t_to_t_param() {
         check_ok(
-            r#"
-struct Point { int x, int y }
-fn f() -> void { let _p = new Point { x: 1, y: 2 }; }
-"#,
+            "fn apply_fn<T>(T x, fn(T) -> T f) -> T { return f(x); }\n\
+             let r = apply_fn(5, fn(int n) -> int { return n * 2; });\n",
         );
     }
 
     #[test]
-    fn unknown_field_errors() {
-        let e = check_err(
-            r#"
-struct Point { int x, int y }
-fn f() -> void { let _p = new Point { x: 1, z: 2 }; }
-"#,
-        );
-        assert!(
-            e.contains("has no field") || e.contains("no field"),
-            "expected unknown-field error; got: {e}"
-        );
-        assert!(
-            e.contains("z"),
-            "error must name the unknown field; got: {e}"
+    fn named_fn_accepted_for_fn_t_to_t_param() {
+        check_ok(
+            "fn double(int x) -> int { return x * 2; }\n\
+             fn apply_fn<T>(T x, fn(T) -> T f) -> T { return f(x); }\n\
+             let r = apply_fn(5, double);\n",
         );
     }
 
     #[test]
-    fn field_type_mismatch_errors() {
-        let e = check_err(
-            r#"
-struct Point { int x, int y }
-fn f() -> void { let _p = new Point { x: "not_an_int", y: 2 }; }
-"#,
+    fn two_type_params_fn_arg_accepted() {
+        // A and B both appear in non-fn-type positions so inference can bind
+        // them; the fn-type param fn(A) -> A still uses a type variable.
+        check_ok(
+            "fn transform<A, B>(A x, B y, fn(A) -> A f) -> A { return f(x); }\n\
+             let r = transform(3, \"unused\", fn(int n) -> int { return n * 2; });\n",
         );
-        assert!(
-            e.contains("type") || e.contains("int"),
-            "expected type mismatch error; got: {e}"
+    }
+
+    #[test]
+    fn wrong_concrete_fn_type_still_errors() {
+        check_err(
+            "fn apply_int(int x, fn(int) -> int f) -> int { return f(x); }\n\
+             let r = apply_int(5, fn(string s) -> int { return 0; });\n",
+            "Type mismatch",
         );
     }
 
     #[test]
-    fn struct_on_unknown_struct_ok() {
-        // If the struct isn't declared, we skip unknown-field checking
-        // to be permissive about partial programs.
-        check_ok("fn f() -> void { let _p = new UnknownStruct { foo: 1 }; }");
+    fn substitute_type_params_helper_recurses() {
+        let tp = vec!["T".to_string()];
+        let fn_type = Type::Function {
+            params: vec![Type::Struct("T".to_string())],
+            return_type: Box::new(Type::Struct("T".to_string())),
+        };
+        let subst = substitute_type_params(&fn_type, &tp);
+        assert_eq!(
+            subst,
+            Type::Function {
+                params: vec![Type::Any],
+                return_type: Box::new(Type::Any),
+            }
+        );
     }
 }
 
-// =====================================================
-// RES-419: fn(T)->R type annotation parsing
-// ============================================================
+/// RES-4087: `+` on a generic-`T` receiver's method-call results was
+/// misinferred as array concat. `shape.draw()` on `T: Drawable` types
+/// as `Any` (the checker can't look through the trait bound to see the
+/// method returns `int`), and the array-concat branch of `+` used to
+/// accept `Any` on both sides via the `compatible(_, Type::Array)`
+/// wildcard rule — so `Any + Any` was classified as array concat even
+/// though both operands are ints at runtime. `rz check` rejected the
+/// program while `rz run` executed it correctly (printing 12).
 #[cfg(test)]
-mod res419_fn_type_annotation {
+mod res4087_generic_plus_inference {
     use super::*;
 
     fn check_ok(src: &str) {
@@ -17066,78 +16259,59 @@ mod res419_fn_type_annotation {
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    #[test]
-    fn fn_param_callable_as_function() {
-        // A higher-order function with a fn(int) -> int parameter must
-        // be callable inside the body without a type error.
-        check_ok(
-            r#"
-fn apply(int x, fn(int) -> int f) -> int { return f(x); }
-fn double(int x) -> int { return x * 2; }
-fn main(int _d) -> int { return apply(5, double); }
-"#,
-        );
-    }
-
-    #[test]
-    fn fn_param_zero_args_callable() {
-        check_ok(
-            r#"
-fn call_zero(fn() -> int f) -> int { return f(); }
-fn get_one() -> int { return 1; }
-fn main(int _d) -> int { return call_zero(get_one); }
-"#,
+    fn check_err(src: &str, fragment: &str) {
+        let (prog, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let e = TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected a type error but got Ok");
+        assert!(
+            e.contains(fragment),
+            "expected {:?} in error: {e}",
+            fragment
         );
     }
 
     #[test]
-    fn fn_param_multi_arg_callable() {
+    fn sum_of_generic_bound_method_calls_typechecks_as_int() {
         check_ok(
-            r#"
-fn apply2(int x, int y, fn(int, int) -> int f) -> int { return f(x, y); }
-fn add(int a, int b) -> int { return a + b; }
-fn main(int _d) -> int { return apply2(3, 4, add); }
-"#,
+            "trait Drawable { fn draw(self) -> int; }\n\
+             trait Sizable { fn size(self) -> int; }\n\
+             struct Circle { int radius }\n\
+             impl Drawable for Circle { fn draw(self) -> int { return self.radius; } }\n\
+             impl Sizable for Circle { fn size(self) -> int { return self.radius * 2; } }\n\
+             fn render<T: Drawable + Sizable>(T shape) -> int { return shape.draw() + shape.size(); }\n\
+             fn main(int _d) { println(render(new Circle { radius: 4 })); }\n\
+             main(0);\n",
         );
     }
 
     #[test]
-    fn fn_return_type_inferred_from_annotation() {
-        // When calling a fn-typed parameter the return type is the
-        // declared return type of the function annotation.
-        check_ok(
-            r#"
-fn transform(string s, fn(string) -> string f) -> string { return f(s); }
-fn shout(string x) -> string { return to_upper(x); }
-fn main(int _d) -> int {
-    let _r = transform("hello", shout);
-    return 0;
-}
-"#,
-        );
+    fn real_array_literal_concat_still_infers_array() {
+        // RES-4087 regression guard: the fix must not weaken the real
+        // array-concat case — both operands genuinely ARE arrays here,
+        // so `+` must still infer `Array` (and reject an `int` binding).
+        check_err("let x: int = [1, 2] + [3, 4];\n", "value has type array");
     }
 
     #[test]
-    fn fn_type_in_let_binding() {
-        // A let binding with a fn(...) -> ... annotation should work.
+    fn array_concat_with_one_dynamic_any_operand_still_infers_array() {
+        // A genuinely-array literal on one side, `Any` on the other
+        // (simulating a dynamic value): still array concat, since one
+        // side is a real array. Only BOTH-Any should fall through to
+        // numeric inference.
         check_ok(
-            r#"
-fn id(int x) -> int { return x; }
-fn main(int _d) -> int {
-    let f: fn(int) -> int = id;
-    return f(3);
-}
-"#,
+            "fn make_dynamic() -> array { return [1, 2]; }\n\
+             fn combine() -> array { return make_dynamic() + [3, 4]; }\n\
+             println(combine());\n",
         );
     }
 }
 
-// ============================================================
-// RES-420: for-in element type from builtin call
-// ============================================================
 #[cfg(test)]
-mod res420_forin_element_type {
+mod res2805_generic_return_type_inference {
     use super::*;
+    use crate::type_relations::substitute_with_bindings;
 
     fn check_ok(src: &str) {
         let (prog, errs) = crate::parse(src);
@@ -17147,72 +16321,84 @@ mod res420_forin_element_type {
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    #[test]
-    fn forin_array_range_element_is_int() {
-        // `array_range(lo, hi)` returns `Array`; element type is Int.
-        // The arithmetic `x + 1` must not produce a type error.
-        check_ok(
-            r#"
-fn main(int _d) -> int {
-    let s = 0;
-    for x in array_range(0, 5) {
-        let s = s + x;
-    }
-    return s;
-}
-"#,
+    fn check_err(src: &str, fragment: &str) {
+        let (prog, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let e = TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected a type error but got Ok");
+        assert!(
+            e.contains(fragment),
+            "expected {:?} in error: {e}",
+            fragment
         );
     }
 
     #[test]
-    fn forin_split_element_is_string() {
-        // `split(str, sep)` → Array of strings; element type is String.
+    fn identity_int_accepted() {
         check_ok(
-            r#"
-fn main(int _d) -> int {
-    for word in split("hello world", " ") {
-        let _len = len(word);
+            "fn identity<T>(T x) -> T { return x; }\n\
+             let y: int = identity(42)\n",
+        );
     }
-    return 0;
-}
-"#,
+
+    #[test]
+    fn identity_string_to_int_rejected() {
+        check_err(
+            "fn identity<T>(T x) -> T { return x; }\n\
+             let y: int = identity(\"hello\")\n",
+            "value has type string",
         );
     }
 
     #[test]
-    fn forin_string_split_element_is_string() {
+    fn identity_no_annotation_accepted() {
         check_ok(
-            r#"
-fn main(int _d) -> int {
-    for tok in string_split("a,b,c", ",") {
-        let _u = to_upper(tok);
-    }
-    return 0;
-}
-"#,
+            "fn identity<T>(T x) -> T { return x; }\n\
+             let y = identity(42)\n",
         );
     }
 
     #[test]
-    fn forin_range_syntax_still_int() {
-        // The original Node::Range { .. } case must still work.
+    fn multi_param_same_type_accepted() {
         check_ok(
-            r#"
-fn main(int _d) -> int {
-    let acc = 0;
-    for i in 0..5 { let acc = acc + i; }
-    return acc;
-}
-"#,
+            "fn first<T>(T a, T b) -> T { return a; }\n\
+             let x: int = first(1, 2)\n",
         );
     }
+
+    #[test]
+    fn substitute_with_bindings_replaces_struct() {
+        let tp = vec!["T".to_string()];
+        let mut bindings = std::collections::HashMap::new();
+        bindings.insert("T", Type::Int);
+        let ret = Type::Struct("T".to_string());
+        let result = substitute_with_bindings(&ret, &tp, &bindings);
+        assert_eq!(result, Type::Int);
+    }
+
+    #[test]
+    fn substitute_with_bindings_recurses_option() {
+        let tp = vec!["T".to_string()];
+        let mut bindings = std::collections::HashMap::new();
+        bindings.insert("T", Type::String);
+        let ret = Type::Option(Box::new(Type::Struct("T".to_string())));
+        let result = substitute_with_bindings(&ret, &tp, &bindings);
+        assert_eq!(result, Type::Option(Box::new(Type::String)));
+    }
+
+    #[test]
+    fn unbound_param_falls_back_to_any() {
+        let tp = vec!["T".to_string()];
+        let bindings = std::collections::HashMap::new();
+        let ret = Type::Struct("T".to_string());
+        let result = substitute_with_bindings(&ret, &tp, &bindings);
+        assert_eq!(result, Type::Any);
+    }
 }
 
-// ============================================================
-// RES-421: IfStatement type with diverging branch
-// ============================================================
 #[cfg(test)]
-mod res421_if_diverging_branch {
+mod res2703_result_exhaustiveness {
     use super::*;
 
     fn check_ok(src: &str) {
@@ -17223,78 +16409,87 @@ mod res421_if_diverging_branch {
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str) -> String {
+    fn check_err(src: &str, fragment: &str) {
         let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
+        let e = TypeChecker::new()
             .check_program(&prog)
-            .expect_err("expected type error")
+            .expect_err("expected a type error but got Ok");
+        assert!(
+            e.contains(fragment),
+            "expected {:?} in error: {e}",
+            fragment
+        );
     }
 
     #[test]
-    fn if_else_consequence_diverges_uses_alt_type() {
-        // consequence returns early; else branch is Int — whole expr is Int.
+    fn ok_and_err_arms_accepted_as_exhaustive() {
         check_ok(
-            r#"
-fn guard(bool bad) -> int {
-    let x = if bad { return 0; } else { 42 };
-    return x;
-}
-fn main(int _d) -> int { return guard(false); }
-"#,
+            "fn f() -> void {\n\
+             let r = Ok(5);\n\
+             match r {\n\
+               Ok(v) => println(to_string(v)),\n\
+               Err(e) => println(e),\n\
+             }\n\
+             }\n",
         );
     }
 
     #[test]
-    fn if_else_alternative_diverges_uses_cons_type() {
-        // alternative returns early; consequence is Int — whole expr is Int.
+    fn wildcard_arm_still_makes_result_match_exhaustive() {
         check_ok(
-            r#"
-fn guard2(bool bad) -> int {
-    let x = if bad { 42 } else { return 0; };
-    return x;
-}
-fn main(int _d) -> int { return guard2(false); }
-"#,
+            "fn f() -> void {\n\
+             let r = Ok(5);\n\
+             match r {\n\
+               Ok(v) => println(to_string(v)),\n\
+               _ => println(\"fallback\"),\n\
+             }\n\
+             }\n",
         );
     }
 
     #[test]
-    fn both_branches_non_diverging_must_match() {
-        // Neither diverges and types differ → type error (pre-existing behaviour).
-        let e = check_err(
-            r#"
-fn f(bool b) -> int {
-    let x = if b { 1 } else { "two" };
-    return x;
-}
-"#,
+    fn missing_err_arm_still_errors() {
+        check_err(
+            "fn f() -> void {\n\
+             let r = Ok(5);\n\
+             match r {\n\
+               Ok(v) => println(to_string(v)),\n\
+             }\n\
+             }\n",
+            "Non-exhaustive match on enum `Result`",
         );
-        assert!(
-            e.contains("incompatible") || e.contains("mismatch") || e.contains("type"),
-            "expected incompatible-types error; got: {e}"
+    }
+
+    #[test]
+    fn missing_ok_arm_still_errors() {
+        check_err(
+            "fn f() -> void {\n\
+             let r = Err(\"oops\");\n\
+             match r {\n\
+               Err(e) => println(e),\n\
+             }\n\
+             }\n",
+            "Non-exhaustive match on enum `Result`",
         );
     }
 
     #[test]
-    fn guard_clause_pattern_compiles() {
-        // Classic guard: early return in if-without-else is fine.
+    fn ok_and_err_arms_with_block_bodies_accepted() {
         check_ok(
-            r#"
-fn safe_sqrt(int n) -> float {
-    if n < 0 { return 0.0; }
-    return sqrt(n);
-}
-fn main(int _d) -> int { return 0; }
-"#,
+            "fn f() -> void {\n\
+             let r = Ok(5);\n\
+             match r {\n\
+               Ok(v) => { println(to_string(v)); },\n\
+               Err(e) => { println(e); },\n\
+             }\n\
+             }\n",
         );
     }
 }
 
-// ── RES-425: generic type parameter call-site fix ────────────────────────────
-
 #[cfg(test)]
-mod res425_generic_typecheck {
+mod res2705_array_type_annotation {
     use super::*;
 
     fn check_ok(src: &str) {
@@ -17302,61 +16497,69 @@ mod res425_generic_typecheck {
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
             .check_program(&prog)
-            .unwrap_or_else(|e| panic!("type error: {e}"));
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str) -> String {
+    fn check_err(src: &str, fragment: &str) {
         let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
+        let e = TypeChecker::new()
             .check_program(&prog)
-            .expect_err("expected type error")
+            .expect_err("expected a type error but got Ok");
+        // Case-insensitive: under RESILIENT_RICH_DIAG=1 the E0007
+        // rich format emits a lowercase "type mismatch" header
+        // instead of the legacy "Type mismatch" string.
+        assert!(
+            e.to_lowercase().contains(&fragment.to_lowercase()),
+            "expected {:?} in error: {e}",
+            fragment
+        );
     }
 
     #[test]
-    fn generic_id_with_int_no_false_positive() {
-        check_ok("fn id<T>(T x) -> T { return x; }\nfn f(int a) -> int { return id(a); }");
+    fn capitalised_array_param_accepts_array_literal() {
+        check_ok(
+            "fn sum(Array items) -> int { return 0; }\n\
+             sum([1, 2, 3]);\n",
+        );
     }
 
     #[test]
-    fn generic_id_with_string_no_false_positive() {
-        check_ok("fn id<T>(T x) -> T { return x; }\nfn f(string s) -> string { return id(s); }");
+    fn lowercase_array_param_still_accepted() {
+        check_ok(
+            "fn sum(array items) -> int { return 0; }\n\
+             sum([1, 2, 3]);\n",
+        );
     }
 
     #[test]
-    fn generic_two_params_no_false_positive() {
+    fn capitalised_array_struct_field_accepts_array_literal() {
         check_ok(
-            "fn first<A, B>(A a, B b) -> A { return a; }\n\
-             fn f(int x, string y) -> int { return first(x, y); }",
+            "struct Bag { Array items }\n\
+             let b = new Bag { items: [1, 2, 3] };\n",
         );
     }
 
     #[test]
-    fn generic_accepts_different_types_at_different_sites() {
+    fn capitalised_array_return_type_accepted() {
         check_ok(
-            "fn wrap<T>(T x) -> T { return x; }\n\
-             fn f(int a) -> int { return wrap(a); }\n\
-             fn g(string s) -> string { return wrap(s); }",
+            "fn make() -> Array { return [1, 2]; }\n\
+             let xs = make();\n",
         );
     }
 
     #[test]
-    fn non_generic_type_mismatch_still_caught() {
-        let e = check_err(
-            "fn takes_int(int x) -> int { return x; }\n\
-             fn f(string s) -> int { return takes_int(s); }",
-        );
-        assert!(
-            e.contains("mismatch") || e.contains("expected int"),
-            "expected type mismatch; got: {e}"
+    fn wrong_type_still_errors_for_array_param() {
+        check_err(
+            "fn sum(Array items) -> int { return 0; }\n\
+             sum(42);\n",
+            "Type mismatch",
         );
     }
 }
 
-// ── RES-426: tuple return type in function signatures ────────────────────────
-
 #[cfg(test)]
-mod res426_tuple_return_type {
+mod res2711_type_char {
     use super::*;
 
     fn check_ok(src: &str) {
@@ -17364,148 +16567,141 @@ mod res426_tuple_return_type {
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
             .check_program(&prog)
-            .unwrap_or_else(|e| panic!("type error: {e}"));
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    #[test]
-    fn tuple_return_type_parses_and_typechecks() {
-        check_ok(
-            "fn divmod(int a, int b) -> (int, int) { return (a / b, a % b); }\n\
-             fn f(int a) -> int { let r = divmod(a, 3); return 0; }",
+    fn check_err(src: &str, fragment: &str) {
+        let (prog, errs) = crate::parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let e = TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected a type error but got Ok");
+        assert!(
+            e.contains(fragment),
+            "expected {:?} in error: {e}",
+            fragment
         );
     }
 
     #[test]
-    fn tuple_return_single_element() {
-        check_ok("fn wrap(int x) -> (int) { return (x); }\nfn f(int a) -> int { return 0; }");
+    fn char_literal_is_typed_as_char() {
+        check_ok("let c: char = 'A';");
     }
 
     #[test]
-    fn tuple_return_three_elements() {
+    fn char_and_capitalised_char_annotation_both_resolve() {
+        check_ok("let c: char = 'x'; let d: Char = 'y';");
+    }
+
+    #[test]
+    fn function_returning_char_from_literal_accepted() {
         check_ok(
-            "fn triple(int x) -> (int, int, int) { return (x, x, x); }\n\
-             fn f(int a) -> int { return 0; }",
+            "fn get() -> char { return 'Z'; }\n\
+             let c: char = get();\n",
         );
     }
 
     #[test]
-    fn tuple_destructure_from_function() {
-        // let (a, b) = divmod(...) should parse and type-check
+    fn char_to_upper_lower_return_char() {
         check_ok(
-            "fn divmod(int a, int b) -> (int, int) { return (a / b, a % b); }\n\
-             fn f(int a) -> int {\n\
-                 let (q, r) = divmod(a, 3);\n\
-                 return q;\n\
-             }",
+            "let u: char = char_to_upper('a');\n\
+             let l: char = char_to_lower('A');\n",
         );
     }
+
+    #[test]
+    fn char_to_int_returns_int() {
+        check_ok("let n: int = char_to_int('A');");
+    }
+
+    #[test]
+    fn int_assigned_to_char_variable_is_type_error() {
+        check_err("let c: char = 42;", "has type int");
+    }
 }
 
-// RES-2693: struct implementing a trait must be accepted wherever the trait
-// type is expected — function parameters, let annotations, return statements.
 #[cfg(test)]
-mod res2693_trait_param_compat {
-    use super::*;
+mod res2713_pattern_literal_type_check {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
 
     fn check_ok(src: &str) {
-        let (prog, errs) = crate::parse(src);
+        let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
             .check_program(&prog)
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str, expected_fragment: &str) {
-        let (prog, errs) = crate::parse(src);
+    fn check_err(src: &str, fragment: &str) {
+        let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         let e = TypeChecker::new()
             .check_program(&prog)
             .expect_err("expected a type error but got Ok");
-        // Case-insensitive: under RESILIENT_RICH_DIAG=1 the E0007
-        // rich format emits a lowercase "type mismatch" header
-        // instead of the legacy "Type mismatch" string.
         assert!(
-            e.to_lowercase().contains(&expected_fragment.to_lowercase()),
-            "expected fragment {:?} not found in error: {e}",
-            expected_fragment
+            e.contains(fragment),
+            "expected {:?} in error: {e}",
+            fragment
         );
     }
 
     #[test]
-    fn struct_accepted_as_trait_fn_param() {
-        check_ok(
-            "trait Greet { fn greet(self) -> string; }\n\
-             struct Alice { int x }\n\
-             impl Greet for Alice {\n\
-               fn greet(self) -> string { return \"hi\"; }\n\
-             }\n\
-             fn say(Greet g) -> void { println(g.greet()); }\n\
-             let a = new Alice { x: 1 };\n\
-             say(a);\n",
+    fn char_pattern_on_int_scrutinee_is_error() {
+        check_err(
+            "let x: int = 5; match x { 'a' => println(\"x\"), _ => println(\"y\") }",
+            "incompatible",
         );
     }
 
     #[test]
-    fn struct_accepted_for_trait_let_annotation() {
-        check_ok(
-            "trait Greet { fn greet(self) -> string; }\n\
-             struct Bob { int y }\n\
-             impl Greet for Bob {\n\
-               fn greet(self) -> string { return \"hello\"; }\n\
-             }\n\
-             let b: Greet = new Bob { y: 2 };\n\
-             println(b.greet());\n",
+    fn int_pattern_on_char_scrutinee_is_error() {
+        check_err(
+            "let c: char = 'a'; match c { 1 => println(\"x\"), _ => println(\"y\") }",
+            "incompatible",
         );
     }
 
     #[test]
-    fn struct_accepted_as_trait_return_type() {
-        check_ok(
-            "trait Greet { fn greet(self) -> string; }\n\
-             struct Carol { int z }\n\
-             impl Greet for Carol {\n\
-               fn greet(self) -> string { return \"hey\"; }\n\
-             }\n\
-             fn make() -> Greet { return new Carol { z: 3 }; }\n",
+    fn bool_pattern_on_string_scrutinee_is_error() {
+        check_err(
+            r#"let s = "hello"; match s { true => println("x"), _ => println("y") }"#,
+            "incompatible",
         );
     }
 
     #[test]
-    fn non_implementing_struct_still_errors() {
-        check_err(
-            "trait Greet { fn greet(self) -> string; }\n\
-             struct Dave { int w }\n\
-             fn say(Greet g) -> void { println(g.greet()); }\n\
-             let d = new Dave { w: 4 };\n\
-             say(d);\n",
-            "Type mismatch",
-        );
+    fn correct_char_pattern_on_char_scrutinee_accepted() {
+        check_ok("let c: char = 'x'; match c { 'x' => println(\"x\"), _ => println(\"y\") }");
     }
 
     #[test]
-    fn multiple_impls_different_traits_independent() {
-        // struct implementing two traits can satisfy either
+    fn correct_int_pattern_on_int_scrutinee_accepted() {
+        check_ok("let n: int = 5; match n { 5 => println(\"five\"), _ => println(\"other\") }");
+    }
+
+    #[test]
+    fn any_scrutinee_accepts_any_literal_pattern() {
+        // When the scrutinee type is unknown (Any), all literal patterns pass.
         check_ok(
-            "trait Greet { fn greet(self) -> string; }\n\
-             trait Farewell { fn bye(self) -> string; }\n\
-             struct Eve { int n }\n\
-             impl Greet for Eve { fn greet(self) -> string { return \"hi\"; } }\n\
-             impl Farewell for Eve { fn bye(self) -> string { return \"bye\"; } }\n\
-             fn say_hi(Greet g) -> void { println(g.greet()); }\n\
-             fn say_bye(Farewell f) -> void { println(f.bye()); }\n\
-             let e = new Eve { n: 0 };\n\
-             say_hi(e);\n\
-             say_bye(e);\n",
+            "fn f(any x) -> void { match x { 'a' => println(\"a\"), 1 => println(\"1\"), _ => println(\"_\") } } f(0);",
         );
     }
+
+    #[test]
+    fn string_subscript_returns_char_type() {
+        // s[i] now produces Type::Char, so the result can be bound to a char variable.
+        check_ok(r#"let s = "hello"; let c: char = s[0];"#);
+    }
 }
 
 #[cfg(test)]
-mod res2701_generic_fn_type_params {
-    use super::*;
+mod res2715_try_op_option {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
 
     fn check_ok(src: &str) {
-        let (prog, errs) = crate::parse(src);
+        let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
             .check_program(&prog)
@@ -17513,89 +16709,77 @@ mod res2701_generic_fn_type_params {
     }
 
     fn check_err(src: &str, fragment: &str) {
-        let (prog, errs) = crate::parse(src);
+        let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         let e = TypeChecker::new()
             .check_program(&prog)
             .expect_err("expected a type error but got Ok");
-        // Case-insensitive: under RESILIENT_RICH_DIAG=1 the E0007
-        // rich format emits a lowercase "type mismatch" header
-        // instead of the legacy "Type mismatch" string.
         assert!(
-            e.to_lowercase().contains(&fragment.to_lowercase()),
+            e.contains(fragment),
             "expected {:?} in error: {e}",
             fragment
         );
     }
 
     #[test]
-    fn lambda_accepted_for_fn_t_to_t_param() {
+    fn try_on_option_accepted() {
         check_ok(
-            "fn apply_fn<T>(T x, fn(T) -> T f) -> T { return f(x); }\n\
-             let r = apply_fn(5, fn(int n) -> int { return n * 2; });\n",
+            "fn f() -> Option { return Some(1); }\n\
+             fn g() -> Option { let v = f()?; return Some(v); }\n\
+             g();\n",
         );
     }
 
     #[test]
-    fn named_fn_accepted_for_fn_t_to_t_param() {
+    fn try_on_result_still_accepted() {
         check_ok(
-            "fn double(int x) -> int { return x * 2; }\n\
-             fn apply_fn<T>(T x, fn(T) -> T f) -> T { return f(x); }\n\
-             let r = apply_fn(5, double);\n",
+            "fn ok_result() -> Result { return Ok(42); }\n\
+             fn use_it() -> Result { let v = ok_result()?; return Ok(v); }\n\
+             use_it();\n",
         );
     }
 
     #[test]
-    fn two_type_params_fn_arg_accepted() {
-        // A and B both appear in non-fn-type positions so inference can bind
-        // them; the fn-type param fn(A) -> A still uses a type variable.
-        check_ok(
-            "fn transform<A, B>(A x, B y, fn(A) -> A f) -> A { return f(x); }\n\
-             let r = transform(3, \"unused\", fn(int n) -> int { return n * 2; });\n",
+    fn try_on_int_rejected() {
+        check_err(
+            "fn f() -> int { let n = 5; let v = n?; return v; }",
+            "Result or Option",
         );
     }
 
     #[test]
-    fn wrong_concrete_fn_type_still_errors() {
+    fn try_on_string_rejected() {
         check_err(
-            "fn apply_int(int x, fn(int) -> int f) -> int { return f(x); }\n\
-             let r = apply_int(5, fn(string s) -> int { return 0; });\n",
-            "Type mismatch",
+            r#"fn f() -> string { let s = "hello"; let v = s?; return v; }"#,
+            "Result or Option",
         );
     }
 
     #[test]
-    fn substitute_type_params_helper_recurses() {
-        let tp = vec!["T".to_string()];
-        let fn_type = Type::Function {
-            params: vec![Type::Struct("T".to_string())],
-            return_type: Box::new(Type::Struct("T".to_string())),
-        };
-        let subst = substitute_type_params(&fn_type, &tp);
-        assert_eq!(
-            subst,
-            Type::Function {
-                params: vec![Type::Any],
-                return_type: Box::new(Type::Any),
-            }
+    fn try_propagates_option_inner_type() {
+        // When the option inner type is known, ? should unwrap to that type.
+        // Binding to a typed variable exercises the type propagation.
+        check_ok(
+            "fn safe_div(int a, int b) -> Option { \
+               if b == 0 { return None; } \
+               return Some(a / b); \
+             }\n\
+             fn compute() -> Option { \
+               let r = safe_div(10, 2)?; \
+               return Some(r * 2); \
+             }\n\
+             compute();\n",
         );
     }
 }
 
-/// RES-4087: `+` on a generic-`T` receiver's method-call results was
-/// misinferred as array concat. `shape.draw()` on `T: Drawable` types
-/// as `Any` (the checker can't look through the trait bound to see the
-/// method returns `int`), and the array-concat branch of `+` used to
-/// accept `Any` on both sides via the `compatible(_, Type::Array)`
-/// wildcard rule — so `Any + Any` was classified as array concat even
-/// though both operands are ints at runtime. `rz check` rejected the
-/// program while `rz run` executed it correctly (printing 12).
 #[cfg(test)]
-mod res4087_generic_plus_inference {
-    use super::*;
+mod res2717_null_coalescing {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
 
     fn check_ok(src: &str) {
-        let (prog, errs) = crate::parse(src);
+        let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
             .check_program(&prog)
@@ -17603,7 +16787,7 @@ mod res4087_generic_plus_inference {
     }
 
     fn check_err(src: &str, fragment: &str) {
-        let (prog, errs) = crate::parse(src);
+        let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         let e = TypeChecker::new()
             .check_program(&prog)
@@ -17616,355 +16800,236 @@ mod res4087_generic_plus_inference {
     }
 
     #[test]
-    fn sum_of_generic_bound_method_calls_typechecks_as_int() {
+    fn null_coalesce_on_option_accepted() {
         check_ok(
-            "trait Drawable { fn draw(self) -> int; }\n\
-             trait Sizable { fn size(self) -> int; }\n\
-             struct Circle { int radius }\n\
-             impl Drawable for Circle { fn draw(self) -> int { return self.radius; } }\n\
-             impl Sizable for Circle { fn size(self) -> int { return self.radius * 2; } }\n\
-             fn render<T: Drawable + Sizable>(T shape) -> int { return shape.draw() + shape.size(); }\n\
-             fn main(int _d) { println(render(new Circle { radius: 4 })); }\n\
-             main(0);\n",
+            "fn f() -> Option { return Some(42); }\n\
+             let x = f() ?? 0;\n\
+             println(to_string(x));\n",
         );
     }
 
     #[test]
-    fn real_array_literal_concat_still_infers_array() {
-        // RES-4087 regression guard: the fix must not weaken the real
-        // array-concat case — both operands genuinely ARE arrays here,
-        // so `+` must still infer `Array` (and reject an `int` binding).
-        check_err("let x: int = [1, 2] + [3, 4];\n", "value has type array");
+    fn null_coalesce_on_none_accepted() {
+        check_ok(
+            "let x = None ?? 99;\n\
+             println(to_string(x));\n",
+        );
     }
 
     #[test]
-    fn array_concat_with_one_dynamic_any_operand_still_infers_array() {
-        // A genuinely-array literal on one side, `Any` on the other
-        // (simulating a dynamic value): still array concat, since one
-        // side is a real array. Only BOTH-Any should fall through to
-        // numeric inference.
+    fn null_coalesce_on_non_option_rejected() {
+        check_err("let x = 42 ?? 0;", "requires an Option on the left");
+    }
+
+    #[test]
+    fn null_coalesce_on_string_rejected() {
+        check_err(
+            r#"let x = "hello" ?? "world";"#,
+            "requires an Option on the left",
+        );
+    }
+
+    #[test]
+    fn null_coalesce_any_lhs_accepted() {
         check_ok(
-            "fn make_dynamic() -> array { return [1, 2]; }\n\
-             fn combine() -> array { return make_dynamic() + [3, 4]; }\n\
-             println(combine());\n",
+            "fn any_fn() -> any { return None; }\n\
+             let x = any_fn() ?? 0;\n",
         );
     }
 }
 
 #[cfg(test)]
-mod res2805_generic_return_type_inference {
-    use super::*;
-    use crate::type_relations::substitute_with_bindings;
+mod res2719_type_name_aliases {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
 
     fn check_ok(src: &str) {
-        let (prog, errs) = crate::parse(src);
+        let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
             .check_program(&prog)
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str, fragment: &str) {
-        let (prog, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let e = TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected a type error but got Ok");
-        assert!(
-            e.contains(fragment),
-            "expected {:?} in error: {e}",
-            fragment
-        );
-    }
-
     #[test]
-    fn identity_int_accepted() {
-        check_ok(
-            "fn identity<T>(T x) -> T { return x; }\n\
-             let y: int = identity(42)\n",
-        );
+    fn str_alias_for_string() {
+        check_ok(r#"fn greet(str name) -> str { return name; }"#);
     }
 
     #[test]
-    fn identity_string_to_int_rejected() {
-        check_err(
-            "fn identity<T>(T x) -> T { return x; }\n\
-             let y: int = identity(\"hello\")\n",
-            "value has type string",
-        );
+    fn capital_string_alias_for_string() {
+        check_ok(r#"fn greet(String name) -> String { return name; }"#);
     }
 
     #[test]
-    fn identity_no_annotation_accepted() {
-        check_ok(
-            "fn identity<T>(T x) -> T { return x; }\n\
-             let y = identity(42)\n",
-        );
+    fn boolean_alias_for_bool() {
+        check_ok("fn pos(int x) -> boolean { return x > 0; }");
     }
 
     #[test]
-    fn multi_param_same_type_accepted() {
-        check_ok(
-            "fn first<T>(T a, T b) -> T { return a; }\n\
-             let x: int = first(1, 2)\n",
-        );
+    fn int32_alias_for_int32_type() {
+        check_ok("fn f(int32 x) -> int32 { return x; }");
     }
 
     #[test]
-    fn substitute_with_bindings_replaces_struct() {
-        let tp = vec!["T".to_string()];
-        let mut bindings = std::collections::HashMap::new();
-        bindings.insert("T", Type::Int);
-        let ret = Type::Struct("T".to_string());
-        let result = substitute_with_bindings(&ret, &tp, &bindings);
-        assert_eq!(result, Type::Int);
+    fn int8_alias_for_int8_type() {
+        check_ok("fn f(int8 x) -> int8 { return x; }");
     }
 
     #[test]
-    fn substitute_with_bindings_recurses_option() {
-        let tp = vec!["T".to_string()];
-        let mut bindings = std::collections::HashMap::new();
-        bindings.insert("T", Type::String);
-        let ret = Type::Option(Box::new(Type::Struct("T".to_string())));
-        let result = substitute_with_bindings(&ret, &tp, &bindings);
-        assert_eq!(result, Type::Option(Box::new(Type::String)));
+    fn uint8_alias_for_uint8_type() {
+        check_ok("fn f(uint8 x) -> uint8 { return x; }");
     }
 
     #[test]
-    fn unbound_param_falls_back_to_any() {
-        let tp = vec!["T".to_string()];
-        let bindings = std::collections::HashMap::new();
-        let ret = Type::Struct("T".to_string());
-        let result = substitute_with_bindings(&ret, &tp, &bindings);
-        assert_eq!(result, Type::Any);
-    }
-}
-
-#[cfg(test)]
-mod res2703_result_exhaustiveness {
-    use super::*;
-
-    fn check_ok(src: &str) {
-        let (prog, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program(&prog)
-            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
-    }
-
-    fn check_err(src: &str, fragment: &str) {
-        let (prog, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let e = TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected a type error but got Ok");
-        assert!(
-            e.contains(fragment),
-            "expected {:?} in error: {e}",
-            fragment
-        );
+    fn uint32_alias_for_uint32_type() {
+        check_ok("fn f(uint32 x) -> uint32 { return x; }");
     }
 
     #[test]
-    fn ok_and_err_arms_accepted_as_exhaustive() {
-        check_ok(
-            "fn f() -> void {\n\
-             let r = Ok(5);\n\
-             match r {\n\
-               Ok(v) => println(to_string(v)),\n\
-               Err(e) => println(e),\n\
-             }\n\
-             }\n",
-        );
+    fn byte_alias_for_uint8() {
+        check_ok("fn f(byte x) -> byte { return x; }");
     }
 
     #[test]
-    fn wildcard_arm_still_makes_result_match_exhaustive() {
-        check_ok(
-            "fn f() -> void {\n\
-             let r = Ok(5);\n\
-             match r {\n\
-               Ok(v) => println(to_string(v)),\n\
-               _ => println(\"fallback\"),\n\
-             }\n\
-             }\n",
-        );
+    fn double_alias_for_float() {
+        check_ok("fn f(double x) -> double { return x; }");
     }
 
     #[test]
-    fn missing_err_arm_still_errors() {
-        check_err(
-            "fn f() -> void {\n\
-             let r = Ok(5);\n\
-             match r {\n\
-               Ok(v) => println(to_string(v)),\n\
-             }\n\
-             }\n",
-            "Non-exhaustive match on enum `Result`",
-        );
+    fn long_alias_for_int() {
+        check_ok("fn f(long x) -> long { return x; }");
     }
 
     #[test]
-    fn missing_ok_arm_still_errors() {
-        check_err(
-            "fn f() -> void {\n\
-             let r = Err(\"oops\");\n\
-             match r {\n\
-               Err(e) => println(e),\n\
-             }\n\
-             }\n",
-            "Non-exhaustive match on enum `Result`",
-        );
+    fn str_param_can_concat_with_string_literal() {
+        check_ok(r#"fn greet(str name) -> string { return "hi " + name; }"#);
     }
 
     #[test]
-    fn ok_and_err_arms_with_block_bodies_accepted() {
-        check_ok(
-            "fn f() -> void {\n\
-             let r = Ok(5);\n\
-             match r {\n\
-               Ok(v) => { println(to_string(v)); },\n\
-               Err(e) => { println(e); },\n\
-             }\n\
-             }\n",
-        );
+    fn string_param_can_be_assigned_to_string_var() {
+        check_ok("fn greet(String name) -> string { let s: string = name; return s; }");
     }
 }
 
 #[cfg(test)]
-mod res2705_array_type_annotation {
-    use super::*;
+mod res2721_interp_string_scope {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
 
-    fn check_ok(src: &str) {
-        let (prog, errs) = crate::parse(src);
+    fn check_ok_no_warnings(src: &str) {
+        let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
             .check_program(&prog)
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str, fragment: &str) {
-        let (prog, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let e = TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected a type error but got Ok");
-        // Case-insensitive: under RESILIENT_RICH_DIAG=1 the E0007
-        // rich format emits a lowercase "type mismatch" header
-        // instead of the legacy "Type mismatch" string.
-        assert!(
-            e.to_lowercase().contains(&fragment.to_lowercase()),
-            "expected {:?} in error: {e}",
-            fragment
-        );
-    }
-
     #[test]
-    fn capitalised_array_param_accepts_array_literal() {
-        check_ok(
-            "fn sum(Array items) -> int { return 0; }\n\
-             sum([1, 2, 3]);\n",
-        );
+    fn let_variable_in_interp_no_false_positive() {
+        // Variables defined by let-bindings must be visible inside {expr}.
+        check_ok_no_warnings(r#"let x = 10; let s = "{x}";"#);
     }
 
     #[test]
-    fn lowercase_array_param_still_accepted() {
-        check_ok(
-            "fn sum(array items) -> int { return 0; }\n\
-             sum([1, 2, 3]);\n",
-        );
+    fn multi_variable_interp_no_false_positive() {
+        check_ok_no_warnings(r#"let a = 1; let b = 2; let s = "{a} and {b}";"#);
     }
 
     #[test]
-    fn capitalised_array_struct_field_accepts_array_literal() {
-        check_ok(
-            "struct Bag { Array items }\n\
-             let b = new Bag { items: [1, 2, 3] };\n",
-        );
+    fn expression_in_interp_no_false_positive() {
+        check_ok_no_warnings(r#"let x = 5; let y = 3; let s = "{x + y}";"#);
     }
 
     #[test]
-    fn capitalised_array_return_type_accepted() {
-        check_ok(
-            "fn make() -> Array { return [1, 2]; }\n\
-             let xs = make();\n",
+    fn function_return_in_interp_no_false_positive() {
+        check_ok_no_warnings(
+            "fn double(int n) -> int { return n * 2; }\n\
+             let s = \"result: {double(7)}\";\n",
         );
     }
 
     #[test]
-    fn wrong_type_still_errors_for_array_param() {
-        check_err(
-            "fn sum(Array items) -> int { return 0; }\n\
-             sum(42);\n",
-            "Type mismatch",
-        );
+    fn interp_string_is_typed_as_string() {
+        let (prog, errs) = parse(r#"let x = 1; let s: string = "{x}";"#);
+        assert!(errs.is_empty());
+        TypeChecker::new()
+            .check_program(&prog)
+            .expect("typed let with interp string should pass");
     }
 }
 
 #[cfg(test)]
-mod res2711_type_char {
-    use super::*;
+mod res2801_generic_struct_type_validation {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
 
     fn check_ok(src: &str) {
-        let (prog, errs) = crate::parse(src);
+        let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
             .check_program(&prog)
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str, fragment: &str) {
-        let (prog, errs) = crate::parse(src);
+    fn check_err(src: &str, expected_msg: &str) {
+        let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let e = TypeChecker::new()
+        let err = TypeChecker::new()
             .check_program(&prog)
-            .expect_err("expected a type error but got Ok");
+            .expect_err("expected a type error");
         assert!(
-            e.contains(fragment),
-            "expected {:?} in error: {e}",
-            fragment
+            err.contains(expected_msg),
+            "error should contain \"{expected_msg}\", got: {err}"
         );
     }
 
     #[test]
-    fn char_literal_is_typed_as_char() {
-        check_ok("let c: char = 'A';");
+    fn consistent_type_params_accepted() {
+        check_ok(
+            "struct Pair<T> { T first, T second }\n\
+             let p = new Pair { first: 1, second: 2 }\n",
+        );
     }
 
     #[test]
-    fn char_and_capitalised_char_annotation_both_resolve() {
-        check_ok("let c: char = 'x'; let d: Char = 'y';");
+    fn inconsistent_type_params_rejected() {
+        check_err(
+            "struct Pair<T> { T first, T second }\n\
+             let p = new Pair { first: 1, second: \"hello\" }\n",
+            "type parameter",
+        );
     }
 
     #[test]
-    fn function_returning_char_from_literal_accepted() {
+    fn different_type_params_independent() {
         check_ok(
-            "fn get() -> char { return 'Z'; }\n\
-             let c: char = get();\n",
+            "struct Pair<T, U> { T first, U second }\n\
+             let p = new Pair { first: 1, second: \"hello\" }\n",
         );
     }
 
     #[test]
-    fn char_to_upper_lower_return_char() {
+    fn non_generic_struct_unchanged() {
         check_ok(
-            "let u: char = char_to_upper('a');\n\
-             let l: char = char_to_lower('A');\n",
+            "struct Point { int x, int y }\n\
+             let p = new Point { x: 1, y: 2 }\n",
         );
     }
 
     #[test]
-    fn char_to_int_returns_int() {
-        check_ok("let n: int = char_to_int('A');");
+    fn compatible_tuple_types() {
+        check_ok("fn id(int x) -> int { return x; }\nlet r: int = id(42)\n");
     }
 
     #[test]
-    fn int_assigned_to_char_variable_is_type_error() {
-        check_err("let c: char = 42;", "has type int");
+    fn char_concat_with_string_accepted() {
+        check_ok("let c: char = 'x'\nlet s = \"hello\" + c\n");
     }
 }
 
 #[cfg(test)]
-mod res2713_pattern_literal_type_check {
+mod res2807_float32_gaps {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -17976,213 +17041,213 @@ mod res2713_pattern_literal_type_check {
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str, fragment: &str) {
+    #[test]
+    fn f32_string_concat_accepted() {
+        check_ok("let x: f32 = 3.14\nlet s = \"val: \" + x\n");
+    }
+
+    #[test]
+    fn f32_unary_minus_accepted() {
+        check_ok("let x: f32 = 3.14\nlet y: f32 = -x\n");
+    }
+}
+
+#[cfg(test)]
+mod res2810_missing_builtin_types {
+    use crate::cfg_attr::{CfgConfig, with_test_config};
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn with_std_feature<T>(f: impl FnOnce() -> T) -> T {
+        with_test_config(CfgConfig::new(["std".to_string()], None), f)
+    }
+
+    fn check_ok(src: &str) {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let e = TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected a type error but got Ok");
-        assert!(
-            e.contains(fragment),
-            "expected {:?} in error: {e}",
-            fragment
-        );
+        with_std_feature(|| {
+            TypeChecker::new()
+                .check_program(&prog)
+                .unwrap_or_else(|e| panic!("unexpected type error: {e}"))
+        });
     }
 
     #[test]
-    fn char_pattern_on_int_scrutinee_is_error() {
-        check_err(
-            "let x: int = 5; match x { 'a' => println(\"x\"), _ => println(\"y\") }",
-            "incompatible",
-        );
+    fn clock_now_accepted() {
+        check_ok("let t = clock_now()\nlet e = clock_elapsed(t)\n");
     }
 
     #[test]
-    fn int_pattern_on_char_scrutinee_is_error() {
-        check_err(
-            "let c: char = 'a'; match c { 1 => println(\"x\"), _ => println(\"y\") }",
-            "incompatible",
+    fn event_journal_accepted() {
+        check_ok(
+            "let t = tick_now()\ntick_advance(1)\nlet id = record_event(\"x\", 42)\nlet log = replay_events()\nclear_events()\nlet n = event_count()\n",
         );
     }
 
     #[test]
-    fn bool_pattern_on_string_scrutinee_is_error() {
-        check_err(
-            r#"let s = "hello"; match s { true => println("x"), _ => println("y") }"#,
-            "incompatible",
+    fn provenance_accepted() {
+        check_ok(
+            "let tagged = tag(42, \"sensor\")\nlet v = untag(tagged, \"sensor\")\nlet src = tag_of(tagged)\n",
         );
     }
 
     #[test]
-    fn correct_char_pattern_on_char_scrutinee_accepted() {
-        check_ok("let c: char = 'x'; match c { 'x' => println(\"x\"), _ => println(\"y\") }");
+    fn snapshot_store_accepted() {
+        check_ok(
+            "snapshot_save(\"key\", 100)\nlet v = snapshot_load(\"key\")\nlet keys = snapshot_keys()\nsnapshot_clear(\"key\")\n",
+        );
     }
 
     #[test]
-    fn correct_int_pattern_on_int_scrutinee_accepted() {
-        check_ok("let n: int = 5; match n { 5 => println(\"five\"), _ => println(\"other\") }");
+    fn quota_builtins_accepted() {
+        check_ok(
+            "quota_set(\"api\", 100)\nlet ok = quota_charge(\"api\", 1)\nlet r = quota_remaining(\"api\")\nquota_reset(\"api\")\nlet u = quota_used(\"api\")\nlet all = quotas()\n",
+        );
     }
 
     #[test]
-    fn any_scrutinee_accepts_any_literal_pattern() {
-        // When the scrutinee type is unknown (Any), all literal patterns pass.
+    fn capability_builtins_accepted() {
         check_ok(
-            "fn f(any x) -> void { match x { 'a' => println(\"a\"), 1 => println(\"1\"), _ => println(\"_\") } } f(0);",
+            "let tok = mint_cap(\"admin\")\nlet ok = check_cap(\"admin\", tok)\nrevoke_cap(\"admin\")\nlet all = caps()\n",
         );
     }
 
     #[test]
-    fn string_subscript_returns_char_type() {
-        // s[i] now produces Type::Char, so the result can be bound to a char variable.
-        check_ok(r#"let s = "hello"; let c: char = s[0];"#);
+    fn actor_builtins_accepted() {
+        check_ok("fn handler() { let msg = receive() }\nlet pid = spawn(handler)\nsend(pid, 42)\n");
     }
-}
-
-#[cfg(test)]
-mod res2715_try_op_option {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
 
-    fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program(&prog)
-            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+    #[test]
+    fn string_builder_accepted() {
+        check_ok("let sb = StringBuilder_new(256)\n");
+    }
+
+    #[test]
+    fn string_builder_zero_arg_constructor_accepted() {
+        check_ok("let sb = StringBuilder_new()\n");
     }
 
-    fn check_err(src: &str, fragment: &str) {
+    fn check_err(src: &str) -> String {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let e = TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected a type error but got Ok");
-        assert!(
-            e.contains(fragment),
-            "expected {:?} in error: {e}",
-            fragment
-        );
+        with_std_feature(|| {
+            TypeChecker::new()
+                .check_program(&prog)
+                .expect_err("expected a type error")
+        })
     }
 
     #[test]
-    fn try_on_option_accepted() {
+    fn vector_builtins_accepted() {
         check_ok(
-            "fn f() -> Option { return Some(1); }\n\
-             fn g() -> Option { let v = f()?; return Some(v); }\n\
-             g();\n",
+            "let a = [1.0, 2.0, 3.0]\nlet b = [4.0, 5.0, 6.0]\nlet s = vec_add(a, b)\nlet d = vec_sub(a, b)\nlet sc = vec_scale(a, 2.0)\nlet dot = vec_dot(a, b)\nlet n = vec_norm(a)\nlet c = vec_cross(a, b)\nlet l = vec_lerp(a, b, 0.5)\n",
         );
     }
 
     #[test]
-    fn try_on_result_still_accepted() {
+    fn matrix_builtins_accepted() {
         check_ok(
-            "fn ok_result() -> Result { return Ok(42); }\n\
-             fn use_it() -> Result { let v = ok_result()?; return Ok(v); }\n\
-             use_it();\n",
+            "let m = [[1.0, 2.0], [3.0, 4.0]]\nlet s = mat_add(m, m)\nlet p = mat_mul(m, m)\nlet sc = mat_scale(m, 2.0)\nlet i = mat_identity(3)\nlet t = mat_trace(m)\nlet d = mat_det(m)\nlet inv = mat_inv(m)\nlet lu = mat_lu(m)\nlet r = mat_rank(m)\nlet x = mat_solve(m, [1.0, 2.0])\n",
         );
     }
 
     #[test]
-    fn try_on_int_rejected() {
-        check_err(
-            "fn f() -> int { let n = 5; let v = n?; return v; }",
-            "Result or Option",
+    fn complex_builtins_accepted() {
+        check_ok(
+            "let z = complex(1.0, 2.0)\nlet w = complex(3.0, 4.0)\nlet re = complex_real(z)\nlet im = complex_imag(z)\nlet a = complex_add(z, w)\nlet s = complex_sub(z, w)\nlet m = complex_mul(z, w)\nlet d = complex_div(z, w)\nlet ab = complex_abs(z)\nlet ar = complex_arg(z)\nlet cj = complex_conj(z)\nlet e = complex_exp(z)\nlet ln = complex_ln(z)\nlet sq = complex_sqrt(z)\nlet si = complex_sin(z)\nlet co = complex_cos(z)\n",
         );
     }
 
     #[test]
-    fn try_on_string_rejected() {
-        check_err(
-            r#"fn f() -> string { let s = "hello"; let v = s?; return v; }"#,
-            "Result or Option",
+    fn data_util_builtins_accepted() {
+        check_ok(
+            "let a = arange(0, 10, 1)\nlet l = linspace(0.0, 1.0, 5)\nlet lg = logspace(0.0, 2.0, 3)\nlet rows = csv_parse(\"a,b\")\nlet tsv = csv_parse_tsv(\"a\")\nlet f = csv_format([[\"a\"]])\nlet ft = csv_format_tsv([[\"a\"]])\nlet tb = table_format([[\"a\"]])\nlet s = format_float(3.14159, 2)\nlet enc = rle_encode([1, 1, 2])\nlet dec = rle_decode(enc)\n",
         );
     }
 
     #[test]
-    fn try_propagates_option_inner_type() {
-        // When the option inner type is known, ? should unwrap to that type.
-        // Binding to a typed variable exercises the type propagation.
+    fn number_theory_builtins_accepted() {
         check_ok(
-            "fn safe_div(int a, int b) -> Option { \
-               if b == 0 { return None; } \
-               return Some(a / b); \
-             }\n\
-             fn compute() -> Option { \
-               let r = safe_div(10, 2)?; \
-               return Some(r * 2); \
-             }\n\
-             compute();\n",
+            "let a = count_digits(12345)\nlet b = digit_sum(12345)\nlet c = digital_root(12345)\nlet d = divisors(12)\nlet e = euler_totient(12)\nlet f = int_log(8, 2)\nlet g = int_to_digits(123)\nlet h = is_fibonacci(13)\nlet i = is_perfect(28)\nlet j = prime_factors(12)\nlet k = primes_up_to(20)\n",
         );
     }
-}
-
-#[cfg(test)]
-mod res2717_null_coalescing {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
-
-    fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program(&prog)
-            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
-    }
 
-    fn check_err(src: &str, fragment: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let e = TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected a type error but got Ok");
-        assert!(
-            e.contains(fragment),
-            "expected {:?} in error: {e}",
-            fragment
+    #[test]
+    fn stats_and_graph_builtins_accepted() {
+        check_ok(
+            "let q = stats_iqr([1.0, 2.0, 3.0, 4.0])\nlet m = stats_mode_int([1, 2, 2, 3])\nlet z = stats_zscore([1.0, 2.0, 3.0])\nlet bfs = graph_bfs([[1], [0]], 0)\nlet dfs = graph_dfs([[1], [0]], 0)\n",
         );
     }
 
     #[test]
-    fn null_coalesce_on_option_accepted() {
+    fn reflection_and_option_builtins_accepted() {
         check_ok(
-            "fn f() -> Option { return Some(42); }\n\
-             let x = f() ?? 0;\n\
-             println(to_string(x));\n",
+            "let n = struct_name(42)\nlet x = identity(5)\nlet t = is_tuple(5)\nlet u = option_unwrap(Some(5))\nlet o = option_unwrap_or(Some(5), 0)\n",
         );
     }
 
     #[test]
-    fn null_coalesce_on_none_accepted() {
+    fn volatile_builtins_type_check() {
+        // The MMIO intrinsics resolve to their registered signatures
+        // (reads -> int, writes -> void). The RES-406 capability gate that
+        // requires a safety block runs in a later, separate pass
+        // (crate::unsafe_check), so type checking alone accepts the calls.
         check_ok(
-            "let x = None ?? 99;\n\
-             println(to_string(x));\n",
+            "let v = volatile_read_u32(1024)\nvolatile_write_u32(1024, 42)\nlet b = volatile_read_u8(512)\nvolatile_write_u16(512, 7)\n",
         );
     }
 
     #[test]
-    fn null_coalesce_on_non_option_rejected() {
-        check_err("let x = 42 ?? 0;", "requires an Option on the left");
+    fn return_types_are_precise() {
+        // Each builtin's registered return type unifies with the matching
+        // annotation — proving the type is precise, not erased to `Any`.
+        check_ok("let d: float = vec_dot([1.0, 2.0], [3.0, 4.0])\n");
+        check_ok("let b: bool = is_perfect(6)\n");
+        check_ok("let n: int = count_digits(123)\n");
+        check_ok("let s: string = struct_name(5)\n");
+        check_ok("let a: array = divisors(12)\n");
+        // A mismatched annotation is rejected — vec_dot returns float, not
+        // string — confirming the return type is genuinely `Float`.
+        let err = check_err("let s: string = vec_dot([1.0], [1.0])\n");
+        assert!(
+            err.to_lowercase().contains("string") || err.to_lowercase().contains("float"),
+            "expected a float/string mismatch, got: {err}"
+        );
     }
 
     #[test]
-    fn null_coalesce_on_string_rejected() {
-        check_err(
-            r#"let x = "hello" ?? "world";"#,
-            "requires an Option on the left",
+    fn arity_is_enforced() {
+        // vec_dot takes exactly 2 arguments.
+        let err = check_err("let d = vec_dot([1.0])\n");
+        assert!(
+            err.contains("argument"),
+            "expected an arity error, got: {err}"
         );
     }
 
     #[test]
-    fn null_coalesce_any_lhs_accepted() {
+    fn pure_math_callable_from_pure_fn() {
+        // The math/data builtins are classified pure, so a `@pure` function
+        // may call them. Without the PURE_BUILTINS entries the purity pass
+        // would reject these as "calls unannotated fn".
         check_ok(
-            "fn any_fn() -> any { return None; }\n\
-             let x = any_fn() ?? 0;\n",
+            "@pure\nfn compute() -> float {\n    let v = vec_norm([3.0, 4.0])\n    let d = vec_dot([1.0, 2.0], [3.0, 4.0])\n    let p = is_perfect(28)\n    let n = count_digits(123)\n    return v + d\n}\n",
+        );
+    }
+
+    #[test]
+    fn impure_builtin_rejected_from_pure_fn() {
+        // Stateful builtins remain impure: a `@pure` fn must not call them.
+        let err = check_err("@pure\nfn t() -> int {\n    return clock_now()\n}\n");
+        assert!(
+            err.contains("impure") || err.contains("clock_now"),
+            "expected an impurity rejection, got: {err}"
         );
     }
 }
 
 #[cfg(test)]
-mod res2719_type_name_aliases {
+mod res2814_generic_enum_constructors {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -18194,73 +17259,60 @@ mod res2719_type_name_aliases {
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    #[test]
-    fn str_alias_for_string() {
-        check_ok(r#"fn greet(str name) -> str { return name; }"#);
-    }
-
-    #[test]
-    fn capital_string_alias_for_string() {
-        check_ok(r#"fn greet(String name) -> String { return name; }"#);
-    }
-
-    #[test]
-    fn boolean_alias_for_bool() {
-        check_ok("fn pos(int x) -> boolean { return x > 0; }");
-    }
-
-    #[test]
-    fn int32_alias_for_int32_type() {
-        check_ok("fn f(int32 x) -> int32 { return x; }");
-    }
-
-    #[test]
-    fn int8_alias_for_int8_type() {
-        check_ok("fn f(int8 x) -> int8 { return x; }");
-    }
-
-    #[test]
-    fn uint8_alias_for_uint8_type() {
-        check_ok("fn f(uint8 x) -> uint8 { return x; }");
-    }
-
-    #[test]
-    fn uint32_alias_for_uint32_type() {
-        check_ok("fn f(uint32 x) -> uint32 { return x; }");
+    fn check_err(src: &str) -> String {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected a type error")
     }
 
     #[test]
-    fn byte_alias_for_uint8() {
-        check_ok("fn f(byte x) -> byte { return x; }");
+    fn generic_enum_constructor_accepts_concrete_types() {
+        check_ok(
+            "enum Either<L, R> { Left(L), Right(R) }\nlet a = Either::Left(42)\nlet b = Either::Right(\"hello\")\n",
+        );
     }
 
     #[test]
-    fn double_alias_for_float() {
-        check_ok("fn f(double x) -> double { return x; }");
+    fn generic_enum_single_param() {
+        check_ok("enum Box<T> { Wrap(T) }\nlet x = Box::Wrap(true)\n");
     }
 
     #[test]
-    fn long_alias_for_int() {
-        check_ok("fn f(long x) -> long { return x; }");
+    fn generic_enum_match_binding_usable() {
+        check_ok(
+            "enum Box<T> { Wrap(T) }\nlet x = Box::Wrap(42)\nmatch x { Box::Wrap(v) => println(v) }\n",
+        );
     }
 
     #[test]
-    fn str_param_can_concat_with_string_literal() {
-        check_ok(r#"fn greet(str name) -> string { return "hi " + name; }"#);
+    fn non_generic_enum_still_validated() {
+        let err = check_err(
+            "enum Color { Red(int), Blue(string) }\nlet c = Color::Red(\"not-an-int\")\n",
+        );
+        assert!(
+            err.contains("argument has type string, expected int"),
+            "unexpected error: {err}"
+        );
     }
 
     #[test]
-    fn string_param_can_be_assigned_to_string_var() {
-        check_ok("fn greet(String name) -> string { let s: string = name; return s; }");
+    fn generic_enum_arity_still_enforced() {
+        let err = check_err("enum Box<T> { Wrap(T) }\nlet x = Box::Wrap(1, 2)\n");
+        assert!(
+            err.contains("expected 1 arg(s), got 2"),
+            "unexpected error: {err}"
+        );
     }
 }
 
 #[cfg(test)]
-mod res2721_interp_string_scope {
+mod res2816_operator_and_type_alias_fixes {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
-    fn check_ok_no_warnings(src: &str) {
+    fn check_ok(src: &str) {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
@@ -18269,41 +17321,28 @@ mod res2721_interp_string_scope {
     }
 
     #[test]
-    fn let_variable_in_interp_no_false_positive() {
-        // Variables defined by let-bindings must be visible inside {expr}.
-        check_ok_no_warnings(r#"let x = 10; let s = "{x}";"#);
-    }
-
-    #[test]
-    fn multi_variable_interp_no_false_positive() {
-        check_ok_no_warnings(r#"let a = 1; let b = 2; let s = "{a} and {b}";"#);
+    fn string_repeat_accepted() {
+        check_ok("let s = \"x\" * 3\n");
     }
 
     #[test]
-    fn expression_in_interp_no_false_positive() {
-        check_ok_no_warnings(r#"let x = 5; let y = 3; let s = "{x + y}";"#);
+    fn int_times_string_accepted() {
+        check_ok("let s = 3 * \"ab\"\n");
     }
 
     #[test]
-    fn function_return_in_interp_no_false_positive() {
-        check_ok_no_warnings(
-            "fn double(int n) -> int { return n * 2; }\n\
-             let s = \"result: {double(7)}\";\n",
-        );
+    fn bool_capitalised_alias_accepted() {
+        check_ok("struct R { Bool ok }\nlet r = new R { ok: true }\n");
     }
 
     #[test]
-    fn interp_string_is_typed_as_string() {
-        let (prog, errs) = parse(r#"let x = 1; let s: string = "{x}";"#);
-        assert!(errs.is_empty());
-        TypeChecker::new()
-            .check_program(&prog)
-            .expect("typed let with interp string should pass");
+    fn string_repeat_with_variable() {
+        check_ok("let n = 5\nlet s = \"-\" * n\n");
     }
 }
 
 #[cfg(test)]
-mod res2801_generic_struct_type_validation {
+mod res2818_option_match_exhaustive {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -18315,64 +17354,49 @@ mod res2801_generic_struct_type_validation {
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str, expected_msg: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let err = TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected a type error");
-        assert!(
-            err.contains(expected_msg),
-            "error should contain \"{expected_msg}\", got: {err}"
-        );
-    }
-
     #[test]
-    fn consistent_type_params_accepted() {
+    fn option_qualified_some_none_exhaustive() {
         check_ok(
-            "struct Pair<T> { T first, T second }\n\
-             let p = new Pair { first: 1, second: 2 }\n",
+            "fn describe(Option<int> x) -> string {\nmatch x {\nOption::Some(v) => \"got\",\nOption::None => \"nothing\",\n}\n}\n",
         );
     }
 
     #[test]
-    fn inconsistent_type_params_rejected() {
-        check_err(
-            "struct Pair<T> { T first, T second }\n\
-             let p = new Pair { first: 1, second: \"hello\" }\n",
-            "type parameter",
+    fn option_bare_some_none_still_works() {
+        check_ok(
+            "fn check(Option<int> x) -> string {\nmatch x {\nSome(v) => \"yes\",\nNone => \"no\",\n}\n}\n",
         );
     }
+}
 
-    #[test]
-    fn different_type_params_independent() {
-        check_ok(
-            "struct Pair<T, U> { T first, U second }\n\
-             let p = new Pair { first: 1, second: \"hello\" }\n",
-        );
+#[cfg(test)]
+mod res2820_return_arm_compatibility {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn check_ok(src: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program(&prog)
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
     #[test]
-    fn non_generic_struct_unchanged() {
+    fn if_let_with_return_accepted() {
         check_ok(
-            "struct Point { int x, int y }\n\
-             let p = new Point { x: 1, y: 2 }\n",
+            "fn classify(int n) -> string {\nif let 0 = n { return \"zero\" }\nreturn \"other\"\n}\n",
         );
     }
 
     #[test]
-    fn compatible_tuple_types() {
-        check_ok("fn id(int x) -> int { return x; }\nlet r: int = id(42)\n");
-    }
-
-    #[test]
-    fn char_concat_with_string_accepted() {
-        check_ok("let c: char = 'x'\nlet s = \"hello\" + c\n");
+    fn match_return_arm_compatible_with_value_arm() {
+        check_ok("fn f(int n) -> string {\nmatch n {\n0 => { return \"a\" },\n_ => \"b\",\n}\n}\n");
     }
 }
 
 #[cfg(test)]
-mod res2807_float32_gaps {
+mod res2822_never_type_return {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -18385,212 +17409,365 @@ mod res2807_float32_gaps {
     }
 
     #[test]
-    fn f32_string_concat_accepted() {
-        check_ok("let x: f32 = 3.14\nlet s = \"val: \" + x\n");
+    fn never_return_with_loop_accepted() {
+        check_ok("fn fail(string msg) -> ! {\nprintln(msg)\nloop { }\n}\n");
     }
 
     #[test]
-    fn f32_unary_minus_accepted() {
-        check_ok("let x: f32 = 3.14\nlet y: f32 = -x\n");
+    fn never_return_fn_callable() {
+        check_ok(
+            "fn fail(string msg) -> ! {\nloop { }\n}\nfn check(int x) -> int {\nif x == 0 { fail(\"zero\") }\nx\n}\n",
+        );
     }
 }
 
 #[cfg(test)]
-mod res2810_missing_builtin_types {
+mod std_only_builtin_gating_tests {
+    use super::*;
     use crate::cfg_attr::{CfgConfig, with_test_config};
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
-
-    fn with_std_feature<T>(f: impl FnOnce() -> T) -> T {
-        with_test_config(CfgConfig::new(["std".to_string()], None), f)
-    }
 
-    fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
+    fn check_with_cfg(src: &str, cfg: CfgConfig) -> Result<(), String> {
+        let (prog, errs) = crate::parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        with_std_feature(|| {
+        with_test_config(cfg, || {
             TypeChecker::new()
-                .check_program(&prog)
-                .unwrap_or_else(|e| panic!("unexpected type error: {e}"))
-        });
+                .check_program_with_source(&prog, "<t>")
+                .map(|_| ())
+        })
     }
 
     #[test]
-    fn clock_now_accepted() {
-        check_ok("let t = clock_now()\nlet e = clock_elapsed(t)\n");
+    fn std_only_builtins_require_feature_in_typechecker() {
+        let src = "fn f() { return clock_ms(); }\n";
+        let err = check_with_cfg(src, CfgConfig::default())
+            .expect_err("std-only builtin should be rejected when std is disabled");
+        assert!(
+            err.contains("builtin `clock_ms` is unavailable without `feature = \"std\"`"),
+            "unexpected error: {err}"
+        );
+
+        let ok = check_with_cfg(src, CfgConfig::new(["std".to_string()], None)).is_ok();
+        assert!(ok, "clock_ms should typecheck when std is enabled");
+
+        let src = "fn f() { return sin(1.0); }\n";
+        let err = check_with_cfg(src, CfgConfig::default())
+            .expect_err("trig builtin should be rejected when std is disabled");
+        assert!(
+            err.contains("builtin `sin` is unavailable without `feature = \"std\"`"),
+            "unexpected error: {err}"
+        );
     }
 
     #[test]
-    fn event_journal_accepted() {
-        check_ok(
-            "let t = tick_now()\ntick_advance(1)\nlet id = record_event(\"x\", 42)\nlet log = replay_events()\nclear_events()\nlet n = event_count()\n",
+    fn user_binding_shadows_std_only_builtin_name() {
+        let src = "fn clock_ms() { return 1; }\nfn f() { return clock_ms(); }\n";
+        let ok = check_with_cfg(src, CfgConfig::default()).is_ok();
+        assert!(
+            ok,
+            "user-defined `clock_ms` should shadow the std-only builtin when std is disabled"
         );
     }
+}
 
-    #[test]
-    fn provenance_accepted() {
-        check_ok(
-            "let tagged = tag(42, \"sensor\")\nlet v = untag(tagged, \"sensor\")\nlet src = tag_of(tagged)\n",
+/// RES-3923: array indexing must recover the element type instead of
+/// erasing it to `Any`. These tests pin the soundness fix — the two
+/// `#3923` repros (reject a wrong-typed binding, accept a correct one)
+/// plus the annotation / iteration / method paths that depend on it.
+#[cfg(test)]
+mod res3923_array_element_type {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn check_ok(src: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program(&prog)
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+    }
+
+    fn check_err(src: &str, needle: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let err = TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected a type error");
+        assert!(
+            err.contains(needle),
+            "error should contain \"{needle}\", got: {err}"
         );
     }
 
+    // The #3923 reject repro: an int array's element must not satisfy a
+    // `string` annotation. Before the fix `int_arr[0]` typed as `Any`
+    // and this was silently accepted.
     #[test]
-    fn snapshot_store_accepted() {
-        check_ok(
-            "snapshot_save(\"key\", 100)\nlet v = snapshot_load(\"key\")\nlet keys = snapshot_keys()\nsnapshot_clear(\"key\")\n",
+    fn index_of_int_array_rejects_string_binding() {
+        check_err(
+            "fn main() { let xs = [1, 2, 3]; let s: string = xs[0]; }\nmain();\n",
+            "value has type int",
         );
     }
 
+    // The #3923 accept repro: the correct element type still binds.
     #[test]
-    fn quota_builtins_accepted() {
-        check_ok(
-            "quota_set(\"api\", 100)\nlet ok = quota_charge(\"api\", 1)\nlet r = quota_remaining(\"api\")\nquota_reset(\"api\")\nlet u = quota_used(\"api\")\nlet all = quotas()\n",
+    fn index_of_int_array_accepts_int_binding() {
+        check_ok("fn main() { let xs = [1, 2, 3]; let i: int = xs[0]; }\nmain();\n");
+    }
+
+    #[test]
+    fn index_of_string_array_rejects_int_binding() {
+        check_err(
+            "fn main() { let xs = [\"a\", \"b\"]; let n: int = xs[0]; }\nmain();\n",
+            "value has type string",
         );
     }
 
     #[test]
-    fn capability_builtins_accepted() {
-        check_ok(
-            "let tok = mint_cap(\"admin\")\nlet ok = check_cap(\"admin\", tok)\nrevoke_cap(\"admin\")\nlet all = caps()\n",
+    fn bracket_annotation_tracks_element_type() {
+        check_err(
+            "fn main() { let xs: [int; 3] = [1, 2, 3]; let s: string = xs[0]; }\nmain();\n",
+            "value has type int",
         );
+        check_ok("fn main() { let xs: [int; 3] = [1, 2, 3]; let i: int = xs[0]; }\nmain();\n");
     }
 
     #[test]
-    fn actor_builtins_accepted() {
-        check_ok("fn handler() { let msg = receive() }\nlet pid = spawn(handler)\nsend(pid, 42)\n");
+    fn generic_annotation_tracks_element_type() {
+        check_err(
+            "fn main() { let xs: array<int> = [1, 2, 3]; let s: string = xs[0]; }\nmain();\n",
+            "value has type int",
+        );
     }
 
     #[test]
-    fn string_builder_accepted() {
-        check_ok("let sb = StringBuilder_new(256)\n");
+    fn bracket_annotation_rejects_wrong_element_literal() {
+        check_err(
+            "fn main() { let xs: [string; 3] = [1, 2, 3]; }\nmain();\n",
+            "value has type",
+        );
     }
 
     #[test]
-    fn string_builder_zero_arg_constructor_accepted() {
-        check_ok("let sb = StringBuilder_new()\n");
+    fn for_in_binds_element_type() {
+        check_err(
+            "fn main() { let xs = [1, 2, 3]; for n in xs { let s: string = n; } }\nmain();\n",
+            "value has type int",
+        );
     }
 
-    fn check_err(src: &str) -> String {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        with_std_feature(|| {
-            TypeChecker::new()
-                .check_program(&prog)
-                .expect_err("expected a type error")
-        })
+    // Untyped arrays (here from a builtin) stay permissive: no element
+    // type is known, so `arr[i]` yields `Any` and must not spuriously
+    // reject a binding of any type.
+    #[test]
+    fn untyped_builtin_array_index_stays_permissive() {
+        check_ok("fn main() { let xs = divisors(12); let s: string = xs[0]; }\nmain();\n");
     }
 
+    // Slice-syntax `xs[lo..hi]` preserves the element type, so indexing
+    // the slice recovers it and a wrong-typed binding is rejected.
     #[test]
-    fn vector_builtins_accepted() {
+    fn slice_syntax_preserves_element_type() {
+        check_err(
+            "fn main() { let xs = [1, 2, 3]; let s = xs[1..3]; let bad: string = s[0]; }\nmain();\n",
+            "value has type int",
+        );
         check_ok(
-            "let a = [1.0, 2.0, 3.0]\nlet b = [4.0, 5.0, 6.0]\nlet s = vec_add(a, b)\nlet d = vec_sub(a, b)\nlet sc = vec_scale(a, 2.0)\nlet dot = vec_dot(a, b)\nlet n = vec_norm(a)\nlet c = vec_cross(a, b)\nlet l = vec_lerp(a, b, 0.5)\n",
+            "fn main() { let xs = [1, 2, 3]; let s = xs[1..3]; let ok: int = s[0]; }\nmain();\n",
         );
     }
 
+    // Array methods must still resolve on element-tracked arrays.
     #[test]
-    fn matrix_builtins_accepted() {
-        check_ok(
-            "let m = [[1.0, 2.0], [3.0, 4.0]]\nlet s = mat_add(m, m)\nlet p = mat_mul(m, m)\nlet sc = mat_scale(m, 2.0)\nlet i = mat_identity(3)\nlet t = mat_trace(m)\nlet d = mat_det(m)\nlet inv = mat_inv(m)\nlet lu = mat_lu(m)\nlet r = mat_rank(m)\nlet x = mat_solve(m, [1.0, 2.0])\n",
-        );
+    fn methods_resolve_on_tracked_arrays() {
+        check_ok("fn main() { let xs = [1, 2, 3]; let n: int = xs.len(); }\nmain();\n");
+    }
+}
+
+/// RES-4012: end-to-end proof that `check_node`'s inline `Node::Match`
+/// exhaustiveness gate — which runs *before* the `enum_exhaustiveness.rs`
+/// / `struct_exhaustiveness.rs` extension passes and used to carry its
+/// own, separately-buggy or-pattern and bool-field-struct logic — now
+/// delegates to the same canonical helpers RES-3934 fixed
+/// (`enum_exhaustiveness::collect_variant_leaves` and
+/// `struct_exhaustiveness::bool_fields_exhaustively_covered_typed`), so
+/// the two programs #4013 documented as still-rejected end-to-end are
+/// accepted, while genuinely non-exhaustive variants of the same shapes
+/// still correctly error.
+#[cfg(test)]
+mod res4012_unify_exhaustiveness {
+    use crate::parse;
+    use crate::typechecker::TypeChecker;
+
+    fn check_ok(src: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        TypeChecker::new()
+            .check_program(&prog)
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    #[test]
-    fn complex_builtins_accepted() {
-        check_ok(
-            "let z = complex(1.0, 2.0)\nlet w = complex(3.0, 4.0)\nlet re = complex_real(z)\nlet im = complex_imag(z)\nlet a = complex_add(z, w)\nlet s = complex_sub(z, w)\nlet m = complex_mul(z, w)\nlet d = complex_div(z, w)\nlet ab = complex_abs(z)\nlet ar = complex_arg(z)\nlet cj = complex_conj(z)\nlet e = complex_exp(z)\nlet ln = complex_ln(z)\nlet sq = complex_sqrt(z)\nlet si = complex_sin(z)\nlet co = complex_cos(z)\n",
+    fn check_err(src: &str, fragment: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let e = TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected a type error but got Ok");
+        assert!(
+            e.contains(fragment),
+            "expected {:?} in error: {e}",
+            fragment
         );
     }
 
+    // The exact program from issue #4012 / #4013: an or-pattern that
+    // unions coverage of two enum variants, plus a third arm for the
+    // remaining variant, is genuinely exhaustive. The inline gate used
+    // to bail on the `Or` pattern (silently dropping it from the
+    // covered set) and reject this as non-exhaustive.
     #[test]
-    fn data_util_builtins_accepted() {
+    fn or_pattern_covering_two_variants_plus_remaining_arm_is_accepted() {
         check_ok(
-            "let a = arange(0, 10, 1)\nlet l = linspace(0.0, 1.0, 5)\nlet lg = logspace(0.0, 2.0, 3)\nlet rows = csv_parse(\"a,b\")\nlet tsv = csv_parse_tsv(\"a\")\nlet f = csv_format([[\"a\"]])\nlet ft = csv_format_tsv([[\"a\"]])\nlet tb = table_format([[\"a\"]])\nlet s = format_float(3.14159, 2)\nlet enc = rle_encode([1, 1, 2])\nlet dec = rle_decode(enc)\n",
+            "enum Color { Red, Green, Blue }\n\
+             fn describe(Color c) -> int {\n\
+             return match c {\n\
+             Color::Red | Color::Green => 0,\n\
+             Color::Blue => 1,\n\
+             };\n\
+             }\n",
         );
     }
 
+    // The mirror case: the or-pattern only covers two of the three
+    // variants and there is no further arm — this must still be
+    // rejected, naming the actually-missing variant.
     #[test]
-    fn number_theory_builtins_accepted() {
-        check_ok(
-            "let a = count_digits(12345)\nlet b = digit_sum(12345)\nlet c = digital_root(12345)\nlet d = divisors(12)\nlet e = euler_totient(12)\nlet f = int_log(8, 2)\nlet g = int_to_digits(123)\nlet h = is_fibonacci(13)\nlet i = is_perfect(28)\nlet j = prime_factors(12)\nlet k = primes_up_to(20)\n",
+    fn or_pattern_covering_two_variants_with_third_missing_still_errors() {
+        check_err(
+            "enum Color { Red, Green, Blue }\n\
+             fn describe(Color c) -> int {\n\
+             return match c {\n\
+             Color::Red | Color::Green => 0,\n\
+             };\n\
+             }\n",
+            "Color::Blue",
         );
     }
 
+    // The exact program from issue #4012 / #4013: a single bool field
+    // struct matched on both `true` and `false` across two arms is
+    // genuinely exhaustive via the bool truth table, even though
+    // neither arm alone is a catch-all. The inline gate used to hard
+    // -reject any struct match without a single-arm catch-all.
     #[test]
-    fn stats_and_graph_builtins_accepted() {
+    fn bool_field_struct_true_and_false_arms_is_accepted() {
         check_ok(
-            "let q = stats_iqr([1.0, 2.0, 3.0, 4.0])\nlet m = stats_mode_int([1, 2, 2, 3])\nlet z = stats_zscore([1.0, 2.0, 3.0])\nlet bfs = graph_bfs([[1], [0]], 0)\nlet dfs = graph_dfs([[1], [0]], 0)\n",
+            "struct Flag { bool on }\n\
+             fn f(Flag flag) -> int {\n\
+             return match flag {\n\
+             Flag { on: true } => 1,\n\
+             Flag { on: false } => 0,\n\
+             };\n\
+             }\n",
         );
     }
 
+    // Only `true` is covered — `false` is genuinely missing and must
+    // still be rejected.
     #[test]
-    fn reflection_and_option_builtins_accepted() {
-        check_ok(
-            "let n = struct_name(42)\nlet x = identity(5)\nlet t = is_tuple(5)\nlet u = option_unwrap(Some(5))\nlet o = option_unwrap_or(Some(5), 0)\n",
+    fn bool_field_struct_only_true_arm_still_errors() {
+        check_err(
+            "struct Flag { bool on }\n\
+             fn f(Flag flag) -> int {\n\
+             return match flag {\n\
+             Flag { on: true } => 1,\n\
+             };\n\
+             }\n",
+            "Non-exhaustive match on struct `Flag`",
         );
     }
 
+    // Two independent bool fields, all four combinations covered by
+    // four literal arms — exhaustive via the cartesian truth table even
+    // though no single arm is a catch-all.
     #[test]
-    fn volatile_builtins_type_check() {
-        // The MMIO intrinsics resolve to their registered signatures
-        // (reads -> int, writes -> void). The RES-406 capability gate that
-        // requires a safety block runs in a later, separate pass
-        // (crate::unsafe_check), so type checking alone accepts the calls.
+    fn two_bool_fields_full_cartesian_coverage_is_accepted() {
         check_ok(
-            "let v = volatile_read_u32(1024)\nvolatile_write_u32(1024, 42)\nlet b = volatile_read_u8(512)\nvolatile_write_u16(512, 7)\n",
-        );
-    }
-
-    #[test]
-    fn return_types_are_precise() {
-        // Each builtin's registered return type unifies with the matching
-        // annotation — proving the type is precise, not erased to `Any`.
-        check_ok("let d: float = vec_dot([1.0, 2.0], [3.0, 4.0])\n");
-        check_ok("let b: bool = is_perfect(6)\n");
-        check_ok("let n: int = count_digits(123)\n");
-        check_ok("let s: string = struct_name(5)\n");
-        check_ok("let a: array = divisors(12)\n");
-        // A mismatched annotation is rejected — vec_dot returns float, not
-        // string — confirming the return type is genuinely `Float`.
-        let err = check_err("let s: string = vec_dot([1.0], [1.0])\n");
-        assert!(
-            err.to_lowercase().contains("string") || err.to_lowercase().contains("float"),
-            "expected a float/string mismatch, got: {err}"
+            "struct Toggle { bool a, bool b }\n\
+             fn f(Toggle t) -> int {\n\
+             return match t {\n\
+             Toggle { a: true, b: true } => 0,\n\
+             Toggle { a: true, b: false } => 1,\n\
+             Toggle { a: false, b: true } => 2,\n\
+             Toggle { a: false, b: false } => 3,\n\
+             };\n\
+             }\n",
         );
     }
 
+    // One of the four (a, b) combinations is never covered — must
+    // still be rejected.
     #[test]
-    fn arity_is_enforced() {
-        // vec_dot takes exactly 2 arguments.
-        let err = check_err("let d = vec_dot([1.0])\n");
-        assert!(
-            err.contains("argument"),
-            "expected an arity error, got: {err}"
+    fn two_bool_fields_missing_one_combination_still_errors() {
+        check_err(
+            "struct Toggle { bool a, bool b }\n\
+             fn f(Toggle t) -> int {\n\
+             return match t {\n\
+             Toggle { a: true, b: true } => 0,\n\
+             Toggle { a: true, b: false } => 1,\n\
+             Toggle { a: false, b: true } => 2,\n\
+             };\n\
+             }\n",
+            "Non-exhaustive match on struct `Toggle`",
         );
     }
 
+    // A struct with a non-bool field can't be proven exhaustive from a
+    // finite set of literals — the catch-all requirement must still
+    // apply (unchanged fallback behavior).
     #[test]
-    fn pure_math_callable_from_pure_fn() {
-        // The math/data builtins are classified pure, so a `@pure` function
-        // may call them. Without the PURE_BUILTINS entries the purity pass
-        // would reject these as "calls unannotated fn".
-        check_ok(
-            "@pure\nfn compute() -> float {\n    let v = vec_norm([3.0, 4.0])\n    let d = vec_dot([1.0, 2.0], [3.0, 4.0])\n    let p = is_perfect(28)\n    let n = count_digits(123)\n    return v + d\n}\n",
+    fn mixed_bool_and_int_field_without_catch_all_still_errors() {
+        check_err(
+            "struct Reading { bool valid, int value }\n\
+             fn f(Reading r) -> int {\n\
+             return match r {\n\
+             Reading { valid: true, value: 0 } => 0,\n\
+             Reading { valid: false, value: 0 } => 1,\n\
+             };\n\
+             }\n",
+            "Non-exhaustive match on struct `Reading`",
         );
     }
 
+    // A guarded arm must never count toward bool coverage — a guard
+    // can fail at runtime, so relying solely on it for `on: false`
+    // leaves the match genuinely non-exhaustive.
     #[test]
-    fn impure_builtin_rejected_from_pure_fn() {
-        // Stateful builtins remain impure: a `@pure` fn must not call them.
-        let err = check_err("@pure\nfn t() -> int {\n    return clock_now()\n}\n");
-        assert!(
-            err.contains("impure") || err.contains("clock_now"),
-            "expected an impurity rejection, got: {err}"
+    fn guarded_bool_arm_does_not_count_toward_coverage() {
+        check_err(
+            "struct Flag { bool on }\n\
+             fn f(Flag flag, bool extra) -> int {\n\
+             return match flag {\n\
+             Flag { on: true } => 1,\n\
+             Flag { on: false } if extra => 0,\n\
+             };\n\
+             }\n",
+            "Non-exhaustive match on struct `Flag`",
         );
     }
 }
 
+/// RES-4011: end-to-end proof that nested/payload pattern exhaustiveness
+/// is now checked, not just a match's top-level shape. Before this fix,
+/// `crate::enum_exhaustiveness::check_nested` didn't exist and neither
+/// this module's inline `Node::Match` gate nor `enum_exhaustiveness.rs`
+/// looked past the top-level `Some(..)`/`None` (or bare enum variant)
+/// pattern, so a `Some(Shape::Circle(r))` arm silently accepted an
+/// incomplete inner `Shape` match — a soundness hole (RES-3935 / #4011),
+/// not merely a missing lint: the uncovered variant compiled and
+/// produced a wrong runtime value.
 #[cfg(test)]
-mod res2814_generic_enum_constructors {
+mod res4011_nested_pattern_exhaustiveness {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -18602,56 +17779,87 @@ mod res2814_generic_enum_constructors {
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    fn check_err(src: &str) -> String {
+    fn check_err(src: &str, fragment: &str) {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
+        let e = TypeChecker::new()
             .check_program(&prog)
-            .expect_err("expected a type error")
-    }
-
-    #[test]
-    fn generic_enum_constructor_accepts_concrete_types() {
-        check_ok(
-            "enum Either<L, R> { Left(L), Right(R) }\nlet a = Either::Left(42)\nlet b = Either::Right(\"hello\")\n",
+            .expect_err("expected a type error but got Ok");
+        assert!(
+            e.contains(fragment),
+            "expected {:?} in error: {e}",
+            fragment
         );
     }
 
+    // The exact program from issue #4011: `Shape` declares `Square` but
+    // only `Circle` is covered inside the `Some(..)` arm. Must now be
+    // rejected, naming the missing nested variant.
     #[test]
-    fn generic_enum_single_param() {
-        check_ok("enum Box<T> { Wrap(T) }\nlet x = Box::Wrap(true)\n");
+    fn missing_variant_nested_inside_option_some_is_rejected() {
+        check_err(
+            "enum Shape { Circle(int), Square(int) }\n\
+             fn f(Option<Shape> os) -> int {\n\
+             return match os {\n\
+             Some(Shape::Circle(r)) => r,\n\
+             None => 0,\n\
+             };\n\
+             }\n",
+            "Shape::Square",
+        );
     }
 
+    // The mirror case: every `Shape` variant is covered inside `Some(..)`
+    // — genuinely exhaustive, must be accepted.
     #[test]
-    fn generic_enum_match_binding_usable() {
+    fn fully_covered_variant_nested_inside_option_some_is_accepted() {
         check_ok(
-            "enum Box<T> { Wrap(T) }\nlet x = Box::Wrap(42)\nmatch x { Box::Wrap(v) => println(v) }\n",
+            "enum Shape { Circle(int), Square(int) }\n\
+             fn f(Option<Shape> os) -> int {\n\
+             return match os {\n\
+             Some(Shape::Circle(r)) => r,\n\
+             Some(Shape::Square(s)) => s,\n\
+             None => 0,\n\
+             };\n\
+             }\n",
         );
     }
 
+    // An identifier binding at the nested position covers every
+    // remaining `Shape` variant without naming them individually.
     #[test]
-    fn non_generic_enum_still_validated() {
-        let err = check_err(
-            "enum Color { Red(int), Blue(string) }\nlet c = Color::Red(\"not-an-int\")\n",
-        );
-        assert!(
-            err.contains("argument has type string, expected int"),
-            "unexpected error: {err}"
+    fn wildcard_at_nested_position_is_accepted() {
+        check_ok(
+            "enum Shape { Circle(int), Square(int) }\n\
+             fn f(Option<Shape> os) -> int {\n\
+             return match os {\n\
+             Some(s) => 0,\n\
+             None => 1,\n\
+             };\n\
+             }\n",
         );
     }
 
+    // Nesting directly inside a user-declared enum's own payload (no
+    // `Option` involved): `Wrap::Has(Shape::Circle(r))` leaves
+    // `Shape::Square` uncovered.
     #[test]
-    fn generic_enum_arity_still_enforced() {
-        let err = check_err("enum Box<T> { Wrap(T) }\nlet x = Box::Wrap(1, 2)\n");
-        assert!(
-            err.contains("expected 1 arg(s), got 2"),
-            "unexpected error: {err}"
+    fn missing_variant_nested_inside_enum_payload_is_rejected() {
+        check_err(
+            "enum Shape { Circle(int), Square(int) }\n\
+             enum Wrap { Has(Shape) }\n\
+             fn f(Wrap w) -> int {\n\
+             return match w {\n\
+             Wrap::Has(Shape::Circle(r)) => r,\n\
+             };\n\
+             }\n",
+            "Shape::Square",
         );
     }
 }
 
 #[cfg(test)]
-mod res2816_operator_and_type_alias_fixes {
+mod union_types {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
@@ -18663,540 +17871,653 @@ mod res2816_operator_and_type_alias_fixes {
             .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    #[test]
-    fn string_repeat_accepted() {
-        check_ok("let s = \"x\" * 3\n");
+    fn check_err(src: &str, fragment: &str) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let e = TypeChecker::new()
+            .check_program(&prog)
+            .expect_err("expected a type error but got Ok");
+        assert!(
+            e.contains(fragment),
+            "expected {:?} in error: {e}",
+            fragment
+        );
     }
 
     #[test]
-    fn int_times_string_accepted() {
-        check_ok("let s = 3 * \"ab\"\n");
+    fn member_widens_into_union_param_and_return() {
+        check_ok(
+            "fn read(int id) -> int | string {\n\
+             if id < 0 { return \"offline\"; }\n\
+             return id;\n\
+             }\n\
+             let r: int | string = read(1);\n\
+             let wider: int | string | bool = r;\n",
+        );
     }
 
     #[test]
-    fn bool_capitalised_alias_accepted() {
-        check_ok("struct R { Bool ok }\nlet r = new R { ok: true }\n");
+    fn non_member_is_rejected() {
+        check_err(
+            "fn f(int | string r) -> int { return 1; }\nlet x = f(true);\n",
+            "expected int | string, got bool",
+        );
     }
 
     #[test]
-    fn string_repeat_with_variable() {
-        check_ok("let n = 5\nlet s = \"-\" * n\n");
+    fn union_does_not_flow_into_member_without_narrowing() {
+        check_err(
+            "fn g(int n) -> int { return n; }\n\
+             fn f(int | string r) -> int { return g(r); }\n",
+            "expected int, got int | string",
+        );
+        check_err(
+            "fn f(int | string r) -> int { return r + 1; }\n",
+            "int | string",
+        );
     }
-}
-
-#[cfg(test)]
-mod res2818_option_match_exhaustive {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
 
-    fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program(&prog)
-            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+    #[test]
+    fn type_test_narrows_both_branches() {
+        check_ok(
+            "fn f(int | string r) -> int {\n\
+             if is_int(r) { return r + 1; } else { return len(r); }\n\
+             }\n",
+        );
     }
 
     #[test]
-    fn option_qualified_some_none_exhaustive() {
+    fn negated_type_test_swaps_branches() {
         check_ok(
-            "fn describe(Option<int> x) -> string {\nmatch x {\nOption::Some(v) => \"got\",\nOption::None => \"nothing\",\n}\n}\n",
+            "fn f(int | string r) -> string {\n\
+             if !is_string(r) { return to_string(r * 2); } else { return r; }\n\
+             }\n",
         );
     }
 
     #[test]
-    fn option_bare_some_none_still_works() {
+    fn narrowing_does_not_leak_past_the_if() {
+        check_err(
+            "fn f(int | string r) -> int {\n\
+             if is_int(r) { let a = r + 1; }\n\
+             return r;\n\
+             }\n",
+            "returning int | string",
+        );
+    }
+
+    #[test]
+    fn union_through_alias_and_literal_match() {
         check_ok(
-            "fn check(Option<int> x) -> string {\nmatch x {\nSome(v) => \"yes\",\nNone => \"no\",\n}\n}\n",
+            "type Reading = int | string;\n\
+             fn code(Reading r) -> int {\n\
+             return match r { 0 => 1, \"off\" => 2, _ => 3, };\n\
+             }\n",
         );
     }
 }
 
 #[cfg(test)]
-mod res2820_return_arm_compatibility {
+mod flow_narrowing {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
-    fn check_ok(src: &str) {
+    /// Check `src` and return `(discharged, left for runtime)` for
+    /// call-site `requires`.
+    fn callsite_stats(src: &str) -> (usize, usize) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let mut tc = TypeChecker::new();
+        tc.check_program(&prog)
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+        (
+            tc.stats.requires_discharged_at_compile,
+            tc.stats.requires_left_for_runtime,
+        )
+    }
+
+    fn check_strict(src: &str) -> Result<(), String> {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
         TypeChecker::new()
+            .with_strict_any(true)
             .check_program(&prog)
-            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+            .map(|_| ())
     }
 
+    const SQRTISH: &str = "fn sqrtish(int x) -> int requires x >= 0 { return x; }\n";
+
     #[test]
-    fn if_let_with_return_accepted() {
-        check_ok(
-            "fn classify(int n) -> string {\nif let 0 = n { return \"zero\" }\nreturn \"other\"\n}\n",
+    fn comparison_guard_discharges_requires() {
+        let src = format!(
+            "{SQRTISH}fn g(int y) -> int {{ if y >= 0 {{ return sqrtish(y); }} return 0; }}\n"
         );
+        assert_eq!(callsite_stats(&src), (1, 0));
     }
 
     #[test]
-    fn match_return_arm_compatible_with_value_arm() {
-        check_ok("fn f(int n) -> string {\nmatch n {\n0 => { return \"a\" },\n_ => \"b\",\n}\n}\n");
+    fn predicate_guard_is_inlined() {
+        let src = format!(
+            "{SQRTISH}fn is_valid_reading(int x) -> bool {{ return x >= 0 && x <= 4095; }}\n\
+             fn g(int y) -> int {{ if is_valid_reading(y) {{ return sqrtish(y); }} return 0; }}\n"
+        );
+        assert_eq!(callsite_stats(&src), (1, 0));
     }
-}
-
-#[cfg(test)]
-mod res2822_never_type_return {
-    use crate::parse;
-    use crate::typechecker::TypeChecker;
 
-    fn check_ok(src: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program(&prog)
-            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+    #[test]
+    fn early_return_guards_the_rest_of_the_block() {
+        let src = format!(
+            "{SQRTISH}fn g(int y) -> int {{ if y < 0 {{ return 0; }} return sqrtish(y); }}\n"
+        );
+        assert_eq!(callsite_stats(&src), (1, 0));
     }
 
     #[test]
-    fn never_return_with_loop_accepted() {
-        check_ok("fn fail(string msg) -> ! {\nprintln(msg)\nloop { }\n}\n");
+    fn reassignment_invalidates_the_fact() {
+        let src = format!(
+            "{SQRTISH}fn g(int y) -> int {{ if y >= 0 {{ y = y - 10; return sqrtish(y); }} return 0; }}\n"
+        );
+        assert_eq!(callsite_stats(&src), (0, 1));
     }
 
     #[test]
-    fn never_return_fn_callable() {
-        check_ok(
-            "fn fail(string msg) -> ! {\nloop { }\n}\nfn check(int x) -> int {\nif x == 0 { fail(\"zero\") }\nx\n}\n",
+    fn else_branch_gets_the_negation() {
+        let src = format!(
+            "{SQRTISH}fn g(int y) -> int {{ if y < 0 {{ return 0; }} else {{ return sqrtish(y); }} }}\n"
         );
+        assert_eq!(callsite_stats(&src), (1, 0));
     }
-}
-
-#[cfg(test)]
-mod std_only_builtin_gating_tests {
-    use super::*;
-    use crate::cfg_attr::{CfgConfig, with_test_config};
 
-    fn check_with_cfg(src: &str, cfg: CfgConfig) -> Result<(), String> {
-        let (prog, errs) = crate::parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        with_test_config(cfg, || {
-            TypeChecker::new()
-                .check_program_with_source(&prog, "<t>")
-                .map(|_| ())
-        })
+    #[test]
+    fn fact_does_not_leak_past_the_if() {
+        let src = format!(
+            "{SQRTISH}fn g(int y) -> int {{ if y >= 0 {{ let a = 1; }} return sqrtish(y); }}\n"
+        );
+        assert_eq!(callsite_stats(&src), (0, 1));
     }
 
     #[test]
-    fn std_only_builtins_require_feature_in_typechecker() {
-        let src = "fn f() { return clock_ms(); }\n";
-        let err = check_with_cfg(src, CfgConfig::default())
-            .expect_err("std-only builtin should be rejected when std is disabled");
-        assert!(
-            err.contains("builtin `clock_ms` is unavailable without `feature = \"std\"`"),
-            "unexpected error: {err}"
-        );
+    fn caller_requires_discharges_callee_requires() {
+        let src = format!("{SQRTISH}fn g(int y) -> int requires y > 3 {{ return sqrtish(y); }}\n");
+        assert_eq!(callsite_stats(&src), (1, 0));
+    }
 
-        let ok = check_with_cfg(src, CfgConfig::new(["std".to_string()], None)).is_ok();
-        assert!(ok, "clock_ms should typecheck when std is enabled");
+    #[test]
+    fn option_unwrap_under_none_check_is_typed() {
+        check_strict(
+            "fn f(Option<int> q) -> int {\n\
+             if q != None { let v = unwrap_option(q); return v + 1; }\n\
+             return 0;\n\
+             }\n",
+        )
+        .expect("unwrap under `!= None` is typed int");
+    }
 
-        let src = "fn f() { return sin(1.0); }\n";
-        let err = check_with_cfg(src, CfgConfig::default())
-            .expect_err("trig builtin should be rejected when std is disabled");
-        assert!(
-            err.contains("builtin `sin` is unavailable without `feature = \"std\"`"),
-            "unexpected error: {err}"
-        );
+    #[test]
+    fn option_unwrap_without_check_stays_any() {
+        let err =
+            check_strict("fn f(Option<int> q) -> int { let v = unwrap_option(q); return v; }\n")
+                .unwrap_err();
+        assert!(err.contains("`let v` has type `Any`"), "{err}");
     }
 
     #[test]
-    fn user_binding_shadows_std_only_builtin_name() {
-        let src = "fn clock_ms() { return 1; }\nfn f() { return clock_ms(); }\n";
-        let ok = check_with_cfg(src, CfgConfig::default()).is_ok();
-        assert!(
-            ok,
-            "user-defined `clock_ms` should shadow the std-only builtin when std is disabled"
+    fn early_exit_narrows_union() {
+        callsite_stats(
+            "fn f(int | string r) -> string {\n\
+             if is_string(r) { return r; }\n\
+             return to_string(r + 1);\n\
+             }\n",
         );
     }
 }
 
-/// RES-3923: array indexing must recover the element type instead of
-/// erasing it to `Any`. These tests pin the soundness fix — the two
-/// `#3923` repros (reject a wrong-typed binding, accept a correct one)
-/// plus the annotation / iteration / method paths that depend on it.
 #[cfg(test)]
-mod res3923_array_element_type {
+mod coercion_policy {
+    use crate::coercion::CoercionPolicy;
     use crate::parse;
-    use crate::typechecker::TypeChecker;
+    use crate::typechecker::{CheckDiagnostic, TypeChecker, collect_check_diagnostics};
 
-    fn check_ok(src: &str) {
+    fn check(src: &str, policy: CoercionPolicy) -> (Result<(), String>, Vec<CheckDiagnostic>) {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program(&prog)
-            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+        collect_check_diagnostics(|| {
+            TypeChecker::new()
+                .with_coercion(policy)
+                .check_program(&prog)
+                .map(|_| ())
+        })
     }
 
-    fn check_err(src: &str, needle: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let err = TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected a type error");
-        assert!(
-            err.contains(needle),
-            "error should contain \"{needle}\", got: {err}"
-        );
+    fn coercion_warnings(diags: &[CheckDiagnostic]) -> usize {
+        diags
+            .iter()
+            .filter(|d| d.code == "implicit-coercion")
+            .count()
     }
 
-    // The #3923 reject repro: an int array's element must not satisfy a
-    // `string` annotation. Before the fix `int_arr[0]` typed as `Any`
-    // and this was silently accepted.
     #[test]
-    fn index_of_int_array_rejects_string_binding() {
-        check_err(
-            "fn main() { let xs = [1, 2, 3]; let s: string = xs[0]; }\nmain();\n",
-            "value has type int",
-        );
+    fn standard_concatenates_silently_and_rejects_mixed_numerics() {
+        let (res, diags) = check("let s = \"n=\" + 3;\n", CoercionPolicy::Standard);
+        assert!(res.is_ok(), "{res:?}");
+        assert_eq!(coercion_warnings(&diags), 0);
+        let (res, _) = check("let x = 1 + 2.5;\n", CoercionPolicy::Standard);
+        assert!(res.unwrap_err().contains("does not implicitly coerce"));
     }
 
-    // The #3923 accept repro: the correct element type still binds.
     #[test]
-    fn index_of_int_array_accepts_int_binding() {
-        check_ok("fn main() { let xs = [1, 2, 3]; let i: int = xs[0]; }\nmain();\n");
+    fn strict_rejects_string_concat_of_non_strings() {
+        let (res, _) = check("let s = \"n=\" + 3;\n", CoercionPolicy::Strict);
+        let e = res.unwrap_err();
+        assert!(e.contains("strict coercion policy"), "{e}");
+        let (res, _) = check(
+            "let s = \"n=\" + to_string(3) + \"!\";\n",
+            CoercionPolicy::Strict,
+        );
+        assert!(res.is_ok(), "{res:?}");
     }
 
     #[test]
-    fn index_of_string_array_rejects_int_binding() {
-        check_err(
-            "fn main() { let xs = [\"a\", \"b\"]; let n: int = xs[0]; }\nmain();\n",
-            "value has type string",
+    fn permissive_widens_int_to_float_and_warns() {
+        let (res, diags) = check(
+            "let x: float = 1 + 2.5;\nlet b = 3 < 3.5;\nlet s = \"x=\" + x;\n",
+            CoercionPolicy::Permissive,
         );
+        assert!(res.is_ok(), "{res:?}");
+        assert_eq!(coercion_warnings(&diags), 3, "{diags:?}");
     }
 
     #[test]
-    fn bracket_annotation_tracks_element_type() {
-        check_err(
-            "fn main() { let xs: [int; 3] = [1, 2, 3]; let s: string = xs[0]; }\nmain();\n",
-            "value has type int",
+    fn permissive_leaves_same_type_operands_alone() {
+        let (res, diags) = check(
+            "let x = 1 + 2;\nlet y = 1.5 * 2.0;\nlet s = \"a\" + \"b\";\n",
+            CoercionPolicy::Permissive,
         );
-        check_ok("fn main() { let xs: [int; 3] = [1, 2, 3]; let i: int = xs[0]; }\nmain();\n");
+        assert!(res.is_ok(), "{res:?}");
+        assert_eq!(coercion_warnings(&diags), 0);
     }
+}
 
-    #[test]
-    fn generic_annotation_tracks_element_type() {
-        check_err(
-            "fn main() { let xs: array<int> = [1, 2, 3]; let s: string = xs[0]; }\nmain();\n",
-            "value has type int",
-        );
+#[cfg(test)]
+mod warning_categories {
+    use crate::parse;
+    use crate::typechecker::{CheckDiagnostic, TypeChecker, collect_check_diagnostics};
+    use crate::warnings::{WarningCategory, WarningConfig};
+
+    fn check(src: &str, config: WarningConfig) -> (Result<(), String>, Vec<CheckDiagnostic>) {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        collect_check_diagnostics(|| {
+            TypeChecker::new()
+                .with_warnings(config)
+                .check_program(&prog)
+                .map(|_| ())
+        })
     }
 
-    #[test]
-    fn bracket_annotation_rejects_wrong_element_literal() {
-        check_err(
-            "fn main() { let xs: [string; 3] = [1, 2, 3]; }\nmain();\n",
-            "value has type",
-        );
+    fn all_on() -> WarningConfig {
+        let mut config = WarningConfig::default();
+        for category in WarningCategory::ALL {
+            config.set(category, true);
+        }
+        config
     }
 
-    #[test]
-    fn for_in_binds_element_type() {
-        check_err(
-            "fn main() { let xs = [1, 2, 3]; for n in xs { let s: string = n; } }\nmain();\n",
-            "value has type int",
-        );
+    fn codes(diags: &[CheckDiagnostic], code: &str) -> Vec<(String, usize)> {
+        diags
+            .iter()
+            .filter(|d| d.code == code)
+            .map(|d| (d.severity.clone(), d.line))
+            .collect()
     }
 
-    // Untyped arrays (here from a builtin) stay permissive: no element
-    // type is known, so `arr[i]` yields `Any` and must not spuriously
-    // reject a binding of any type.
+    const SRC: &str = "fn f(x) -> int {\n\
+                       \x20   let n = x + 1;\n\
+                       \x20   if n > 0 {\n\
+                       \x20       let n = 2;\n\
+                       \x20       return n;\n\
+                       \x20   }\n\
+                       \x20   let spare = 0;\n\
+                       \x20   return n;\n\
+                       }\n\
+                       println(f(1));\n";
+
     #[test]
-    fn untyped_builtin_array_index_stays_permissive() {
-        check_ok("fn main() { let xs = divisors(12); let s: string = xs[0]; }\nmain();\n");
+    fn defaults_report_unused_but_not_shadowing_or_implicit_any() {
+        let (res, diags) = check(SRC, WarningConfig::default());
+        assert!(res.is_ok(), "{res:?}");
+        assert_eq!(codes(&diags, "unused"), [("warning".to_string(), 7)]);
+        assert!(codes(&diags, "shadowing").is_empty());
+        assert!(codes(&diags, "implicit-any").is_empty());
     }
 
-    // Slice-syntax `xs[lo..hi]` preserves the element type, so indexing
-    // the slice recovers it and a wrong-typed binding is rejected.
     #[test]
-    fn slice_syntax_preserves_element_type() {
-        check_err(
-            "fn main() { let xs = [1, 2, 3]; let s = xs[1..3]; let bad: string = s[0]; }\nmain();\n",
-            "value has type int",
-        );
-        check_ok(
-            "fn main() { let xs = [1, 2, 3]; let s = xs[1..3]; let ok: int = s[0]; }\nmain();\n",
-        );
+    fn enabled_categories_report_at_their_sites() {
+        let (res, diags) = check(SRC, all_on());
+        assert!(res.is_ok(), "{res:?}");
+        assert_eq!(codes(&diags, "shadowing"), [("warning".to_string(), 4)]);
+        assert_eq!(codes(&diags, "implicit-any"), [("warning".to_string(), 1)]);
     }
 
-    // Array methods must still resolve on element-tracked arrays.
     #[test]
-    fn methods_resolve_on_tracked_arrays() {
-        check_ok("fn main() { let xs = [1, 2, 3]; let n: int = xs.len(); }\nmain();\n");
+    fn disabling_a_category_silences_it() {
+        let mut config = WarningConfig::default();
+        config.set(WarningCategory::Unused, false);
+        let (_, diags) = check(SRC, config);
+        assert!(codes(&diags, "unused").is_empty());
     }
-}
 
-/// RES-4012: end-to-end proof that `check_node`'s inline `Node::Match`
-/// exhaustiveness gate — which runs *before* the `enum_exhaustiveness.rs`
-/// / `struct_exhaustiveness.rs` extension passes and used to carry its
-/// own, separately-buggy or-pattern and bool-field-struct logic — now
-/// delegates to the same canonical helpers RES-3934 fixed
-/// (`enum_exhaustiveness::collect_variant_leaves` and
-/// `struct_exhaustiveness::bool_fields_exhaustively_covered_typed`), so
-/// the two programs #4013 documented as still-rejected end-to-end are
-/// accepted, while genuinely non-exhaustive variants of the same shapes
-/// still correctly error.
+    #[test]
+    fn deny_reports_errors_and_fails_the_check() {
+        let mut config = all_on();
+        config.deny = true;
+        let (res, diags) = check(SRC, config);
+        assert_eq!(res.unwrap_err(), "3 warnings denied by --deny-warnings");
+        assert!(
+            diags
+                .iter()
+                .filter(|d| d.code == "shadowing" || d.code == "unused")
+                .all(|d| d.severity == "error"),
+            "{diags:?}"
+        );
+        let (res, _) = check("let x: int = 1;\nprintln(x);\n", config);
+        assert!(res.is_ok(), "a clean program passes: {res:?}");
+    }
+}
+
 #[cfg(test)]
-mod res4012_unify_exhaustiveness {
+mod overloading {
     use crate::parse;
     use crate::typechecker::TypeChecker;
 
-    fn check_ok(src: &str) {
+    fn check(src: &str) -> Result<(), String> {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program(&prog)
-            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+        TypeChecker::new().check_program(&prog).map(|_| ())
     }
 
-    fn check_err(src: &str, fragment: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let e = TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected a type error but got Ok");
-        assert!(
-            e.contains(fragment),
-            "expected {:?} in error: {e}",
-            fragment
-        );
+    const AREA: &str = "fn area(int w) -> int { return w * w; }\n\
+                        fn area(float w) -> float { return w * w; }\n";
+
+    #[test]
+    fn call_takes_the_selected_overloads_return_type() {
+        check(&format!(
+            "{AREA}let a: int = area(3);\nlet b: float = area(1.5);\n"
+        ))
+        .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+        let e = check(&format!("{AREA}let a: int = area(1.5);\n")).unwrap_err();
+        assert!(e.contains("let a: int"), "{e}");
     }
 
-    // The exact program from issue #4012 / #4013: an or-pattern that
-    // unions coverage of two enum variants, plus a third arm for the
-    // remaining variant, is genuinely exhaustive. The inline gate used
-    // to bail on the `Or` pattern (silently dropping it from the
-    // covered set) and reject this as non-exhaustive.
     #[test]
-    fn or_pattern_covering_two_variants_plus_remaining_arm_is_accepted() {
-        check_ok(
-            "enum Color { Red, Green, Blue }\n\
-             fn describe(Color c) -> int {\n\
-             return match c {\n\
-             Color::Red | Color::Green => 0,\n\
-             Color::Blue => 1,\n\
-             };\n\
-             }\n",
-        );
+    fn arity_selects_between_overloads() {
+        check(
+            "fn log(string m) {}\nfn log(string l, string m) {}\n\
+             log(\"a\");\nlog(\"b\", \"c\");\n",
+        )
+        .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    // The mirror case: the or-pattern only covers two of the three
-    // variants and there is no further arm — this must still be
-    // rejected, naming the actually-missing variant.
     #[test]
-    fn or_pattern_covering_two_variants_with_third_missing_still_errors() {
-        check_err(
-            "enum Color { Red, Green, Blue }\n\
-             fn describe(Color c) -> int {\n\
-             return match c {\n\
-             Color::Red | Color::Green => 0,\n\
-             };\n\
-             }\n",
-            "Color::Blue",
+    fn no_matching_overload_names_the_candidates() {
+        let e = check(&format!("{AREA}area(true);\n")).unwrap_err();
+        assert!(
+            e.contains("no overload of `area` accepts (bool); candidates: area(int), area(float)"),
+            "{e}"
         );
     }
 
-    // The exact program from issue #4012 / #4013: a single bool field
-    // struct matched on both `true` and `false` across two arms is
-    // genuinely exhaustive via the bool truth table, even though
-    // neither arm alone is a catch-all. The inline gate used to hard
-    // -reject any struct match without a single-arm catch-all.
     #[test]
-    fn bool_field_struct_true_and_false_arms_is_accepted() {
-        check_ok(
-            "struct Flag { bool on }\n\
-             fn f(Flag flag) -> int {\n\
-             return match flag {\n\
-             Flag { on: true } => 1,\n\
-             Flag { on: false } => 0,\n\
-             };\n\
-             }\n",
-        );
+    fn exact_type_beats_any_and_ties_are_ambiguous() {
+        check("fn f(int x) -> int { return x; }\nfn f(any x) -> string { return \"?\"; }\nlet n: int = f(1);\n")
+            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+        let e = check("fn g(any x) {}\nfn g(any x, int y) {}\nfn g(int x, any y) {}\ng(1, 2);\n")
+            .unwrap_err();
+        assert!(e.contains("ambiguous call to overloaded `g`"), "{e}");
     }
 
-    // Only `true` is covered — `false` is genuinely missing and must
-    // still be rejected.
     #[test]
-    fn bool_field_struct_only_true_arm_still_errors() {
-        check_err(
-            "struct Flag { bool on }\n\
-             fn f(Flag flag) -> int {\n\
-             return match flag {\n\
-             Flag { on: true } => 1,\n\
-             };\n\
-             }\n",
-            "Non-exhaustive match on struct `Flag`",
+    fn an_overload_set_is_not_a_value() {
+        let e = check(&format!("{AREA}let f = area;\n")).unwrap_err();
+        assert!(
+            e.contains("overloaded fn `area` cannot be used as a value"),
+            "{e}"
+        );
+        // A builtin of the same name does not stand in for the set.
+        let e = check("fn log(string m) {}\nfn log(string l, string m) {}\nlet f = log;\n")
+            .unwrap_err();
+        assert!(
+            e.contains("overloaded fn `log` cannot be used as a value"),
+            "{e}"
         );
+        check(&format!(
+            "{AREA}let f = fn(int w) -> int {{ return area(w); }};\nlet n: int = f(2);\n"
+        ))
+        .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
     }
 
-    // Two independent bool fields, all four combinations covered by
-    // four literal arms — exhaustive via the cartesian truth table even
-    // though no single arm is a catch-all.
     #[test]
-    fn two_bool_fields_full_cartesian_coverage_is_accepted() {
-        check_ok(
-            "struct Toggle { bool a, bool b }\n\
-             fn f(Toggle t) -> int {\n\
-             return match t {\n\
-             Toggle { a: true, b: true } => 0,\n\
-             Toggle { a: true, b: false } => 1,\n\
-             Toggle { a: false, b: true } => 2,\n\
-             Toggle { a: false, b: false } => 3,\n\
-             };\n\
-             }\n",
-        );
+    fn identical_signatures_are_still_duplicates() {
+        let e = check("fn h(int x) {}\nfn h(int y) {}\n").unwrap_err();
+        assert!(e.contains("duplicate function name `h`"), "{e}");
     }
+}
+
+#[cfg(test)]
+mod completion_query {
+    use crate::parse;
+    use crate::span::Pos;
+    use crate::typechecker::{Type, query_completions};
+
+    /// Name → type at `line:col` of `src`.
+    fn at(src: &str, line: usize, col: usize) -> Vec<(String, Type)> {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        query_completions(&prog, Pos::new(line, col, 0))
+            .into_iter()
+            .map(|c| (c.name, c.ty))
+            .collect()
+    }
+
+    fn ty_of<'a>(found: &'a [(String, Type)], name: &str) -> Option<&'a Type> {
+        found.iter().find(|(n, _)| n == name).map(|(_, t)| t)
+    }
+
+    const SRC: &str = "fn scale(int n, float k) -> float {\n\
+                       \x20   let a = 2;\n\
+                       \x20   if n > 0 {\n\
+                       \x20       let inner = \"x\";\n\
+                       \x20       return k;\n\
+                       \x20   }\n\
+                       \x20   let later = true;\n\
+                       \x20   return k;\n\
+                       }\n\
+                       let top = 1.5;\n";
 
-    // One of the four (a, b) combinations is never covered — must
-    // still be rejected.
     #[test]
-    fn two_bool_fields_missing_one_combination_still_errors() {
-        check_err(
-            "struct Toggle { bool a, bool b }\n\
-             fn f(Toggle t) -> int {\n\
-             return match t {\n\
-             Toggle { a: true, b: true } => 0,\n\
-             Toggle { a: true, b: false } => 1,\n\
-             Toggle { a: false, b: true } => 2,\n\
-             };\n\
-             }\n",
-            "Non-exhaustive match on struct `Toggle`",
-        );
+    fn parameters_and_earlier_locals_carry_their_types() {
+        let found = at(SRC, 3, 5);
+        assert_eq!(ty_of(&found, "n"), Some(&Type::Int));
+        assert_eq!(ty_of(&found, "k"), Some(&Type::Float));
+        assert_eq!(ty_of(&found, "a"), Some(&Type::Int));
+        assert!(ty_of(&found, "later").is_none(), "declared after line 3");
+        assert!(ty_of(&found, "scale").is_some());
+        assert!(ty_of(&found, "println").is_some(), "builtins are in scope");
     }
 
-    // A struct with a non-bool field can't be proven exhaustive from a
-    // finite set of literals — the catch-all requirement must still
-    // apply (unchanged fallback behavior).
     #[test]
-    fn mixed_bool_and_int_field_without_catch_all_still_errors() {
-        check_err(
-            "struct Reading { bool valid, int value }\n\
-             fn f(Reading r) -> int {\n\
-             return match r {\n\
-             Reading { valid: true, value: 0 } => 0,\n\
-             Reading { valid: false, value: 0 } => 1,\n\
-             };\n\
-             }\n",
-            "Non-exhaustive match on struct `Reading`",
-        );
+    fn inner_block_bindings_stay_inside_the_block() {
+        assert_eq!(ty_of(&at(SRC, 5, 9), "inner"), Some(&Type::String));
+        let after = at(SRC, 8, 5);
+        assert!(ty_of(&after, "inner").is_none());
+        assert_eq!(ty_of(&after, "later"), Some(&Type::Bool));
     }
 
-    // A guarded arm must never count toward bool coverage — a guard
-    // can fail at runtime, so relying solely on it for `on: false`
-    // leaves the match genuinely non-exhaustive.
     #[test]
-    fn guarded_bool_arm_does_not_count_toward_coverage() {
-        check_err(
-            "struct Flag { bool on }\n\
-             fn f(Flag flag, bool extra) -> int {\n\
-             return match flag {\n\
-             Flag { on: true } => 1,\n\
-             Flag { on: false } if extra => 0,\n\
-             };\n\
-             }\n",
-            "Non-exhaustive match on struct `Flag`",
-        );
+    fn top_level_lists_user_declarations_before_builtins() {
+        let found = at(SRC, 11, 1);
+        assert_eq!(ty_of(&found, "top"), Some(&Type::Float));
+        assert!(ty_of(&found, "n").is_none(), "parameters are local");
+        let scale = found.iter().position(|(n, _)| n == "scale").unwrap();
+        let println = found.iter().position(|(n, _)| n == "println").unwrap();
+        assert!(scale < println);
+    }
+
+    #[test]
+    fn overloads_are_listed_under_their_bare_name() {
+        let found = at("fn log(string m) {}\nfn log(string l, string m) {}\n", 3, 1);
+        let logs = found.iter().filter(|(n, _)| n == "log").count();
+        assert_eq!(logs, 2);
+        assert!(!found.iter().any(|(n, _)| n.contains('$')));
     }
 }
 
-/// RES-4011: end-to-end proof that nested/payload pattern exhaustiveness
-/// is now checked, not just a match's top-level shape. Before this fix,
-/// `crate::enum_exhaustiveness::check_nested` didn't exist and neither
-/// this module's inline `Node::Match` gate nor `enum_exhaustiveness.rs`
-/// looked past the top-level `Some(..)`/`None` (or bare enum variant)
-/// pattern, so a `Some(Shape::Circle(r))` arm silently accepted an
-/// incomplete inner `Shape` match — a soundness hole (RES-3935 / #4011),
-/// not merely a missing lint: the uncovered variant compiled and
-/// produced a wrong runtime value.
 #[cfg(test)]
-mod res4011_nested_pattern_exhaustiveness {
+mod deferred_body_tests {
     use crate::parse;
-    use crate::typechecker::TypeChecker;
+    use crate::typechecker::{CheckDiagnostic, TypeChecker, collect_check_diagnostics};
+    use crate::warnings::{WarningCategory, WarningConfig};
+
+    type Outcome = (Result<String, String>, Vec<CheckDiagnostic>);
+
+    /// `before`, then enough independent fns to take the deferred
+    /// path — each shadowing its parameter, so every body warns —
+    /// then `after`.
+    fn program(before: &str, after: &str) -> String {
+        let mut src = format!("let base = 1;\n{before}");
+        for i in 0..crate::parallel_check::MIN_BODIES + 4 {
+            src.push_str(&format!(
+                "fn f{i}(int x) -> int {{ let x = x + {i}; return x + base; }}\n"
+            ));
+        }
+        src.push_str(after);
+        src
+    }
 
-    fn check_ok(src: &str) {
+    fn check(src: &str, parallel: bool) -> Outcome {
         let (prog, errs) = parse(src);
         assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        TypeChecker::new()
-            .check_program(&prog)
-            .unwrap_or_else(|e| panic!("unexpected type error: {e}"));
+        let mut config = WarningConfig::default();
+        config.set(WarningCategory::Shadowing, true);
+        collect_check_diagnostics(|| {
+            TypeChecker::new()
+                .with_warnings(config)
+                .with_parallel_bodies(parallel)
+                .check_program(&prog)
+                .map(|ty| ty.to_string())
+        })
     }
 
-    fn check_err(src: &str, fragment: &str) {
-        let (prog, errs) = parse(src);
-        assert!(errs.is_empty(), "parse errors: {:?}", errs);
-        let e = TypeChecker::new()
-            .check_program(&prog)
-            .expect_err("expected a type error but got Ok");
+    fn both(src: &str) -> Outcome {
+        let sequential = check(src, false);
+        let deferred = check(src, true);
+        assert_eq!(deferred, sequential);
+        deferred
+    }
+
+    #[test]
+    fn deferred_bodies_report_what_the_sequential_walk_does() {
+        let last = crate::parallel_check::MIN_BODIES + 3;
+        let (result, diags) = both(&program("", &format!("let total = f0(1) + f{last}(2);\n")));
+        assert!(result.is_ok(), "{result:?}");
+        let lines: Vec<usize> = diags
+            .iter()
+            .filter(|d| d.code == "shadowing")
+            .map(|d| d.line)
+            .collect();
+        assert_eq!(lines.len(), crate::parallel_check::MIN_BODIES + 4);
+        assert!(lines.is_sorted(), "{lines:?}");
+    }
+
+    #[test]
+    fn first_failing_body_in_source_order_wins() {
+        let src = program(
+            "fn bad(int x) -> string { return x; }\n",
+            "let late: int = true;\n",
+        );
+        let (result, diags) = both(&src);
+        let err = result.unwrap_err();
         assert!(
-            e.contains(fragment),
-            "expected {:?} in error: {e}",
-            fragment
+            err.contains(":2:") && err.contains("declared string"),
+            "{err}"
         );
+        assert!(diags.is_empty(), "nothing after `bad` reports: {diags:?}");
     }
 
-    // The exact program from issue #4011: `Shape` declares `Square` but
-    // only `Circle` is covered inside the `Some(..)` arm. Must now be
-    // rejected, naming the missing nested variant.
     #[test]
-    fn missing_variant_nested_inside_option_some_is_rejected() {
-        check_err(
-            "enum Shape { Circle(int), Square(int) }\n\
-             fn f(Option<Shape> os) -> int {\n\
-             return match os {\n\
-             Some(Shape::Circle(r)) => r,\n\
-             None => 0,\n\
-             };\n\
-             }\n",
-            "Shape::Square",
+    fn a_failing_statement_stops_before_later_bodies() {
+        let src = program(
+            "let early: int = true;\n",
+            "fn bad(int x) -> string { return x; }\n",
         );
+        let (result, diags) = both(&src);
+        let err = result.unwrap_err();
+        assert!(err.contains(":2:"), "{err}");
+        assert!(diags.is_empty(), "{diags:?}");
+    }
+
+    /// Whether `need`'s runtime `requires` would be dropped as proven.
+    fn need_is_proven(src: &str, parallel: bool) -> bool {
+        let (prog, errs) = parse(src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        let mut tc = TypeChecker::new().with_parallel_bodies(parallel);
+        tc.check_program(&prog).unwrap();
+        tc.stats.fully_provable_fns().contains("need")
     }
 
-    // The mirror case: every `Shape` variant is covered inside `Some(..)`
-    // — genuinely exhaustive, must be accepted.
     #[test]
-    fn fully_covered_variant_nested_inside_option_some_is_accepted() {
-        check_ok(
-            "enum Shape { Circle(int), Square(int) }\n\
-             fn f(Option<Shape> os) -> int {\n\
-             return match os {\n\
-             Some(Shape::Circle(r)) => r,\n\
-             Some(Shape::Square(s)) => s,\n\
-             None => 0,\n\
-             };\n\
-             }\n",
+    fn an_outer_variable_a_fn_assigns_is_no_longer_constant() {
+        let src = "let x = 5;
+                   fn dec() { x = -1; }
+                   fn need(int v) -> int requires v >= 0 { return v; }
+                   dec();
+                   println(need(x));
+";
+        assert!(!need_is_proven(src, false));
+
+        let deferred = program(
+            "let x = 5;
+             fn dec() -> int { x = -1; return 0; }
+             fn need(int v) -> int requires v >= 0 { return v; }
+",
+            "let d = dec();
+let r = need(x);
+",
         );
+        assert!(!need_is_proven(&deferred, false));
+        assert!(!need_is_proven(&deferred, true));
     }
 
-    // An identifier binding at the nested position covers every
-    // remaining `Shape` variant without naming them individually.
     #[test]
-    fn wildcard_at_nested_position_is_accepted() {
-        check_ok(
-            "enum Shape { Circle(int), Square(int) }\n\
-             fn f(Option<Shape> os) -> int {\n\
-             return match os {\n\
-             Some(s) => 0,\n\
-             None => 1,\n\
-             };\n\
-             }\n",
+    fn a_refutation_in_a_deferred_body_stays_a_refutation() {
+        let src = program(
+            "fn need(int v) -> int requires v > 0 { return v; }\n\
+             fn bad() -> int { return need(0 - 1); }\n",
+            "",
         );
+        let (prog, errs) = parse(&src);
+        assert!(errs.is_empty(), "parse errors: {:?}", errs);
+        for parallel in [false, true] {
+            let err = TypeChecker::new()
+                .with_parallel_bodies(parallel)
+                .check_source(&prog, "<t>")
+                .unwrap_err();
+            assert!(err.refuted, "{err}");
+        }
+        let (prog, _) = parse(&program("fn bad(int x) -> string { return x; }\n", ""));
+        let err = TypeChecker::new()
+            .with_parallel_bodies(true)
+            .check_source(&prog, "<t>")
+            .unwrap_err();
+        assert!(!err.refuted, "{err}");
     }
 
-    // Nesting directly inside a user-declared enum's own payload (no
-    // `Option` involved): `Wrap::Has(Shape::Circle(r))` leaves
-    // `Shape::Square` uncovered.
     #[test]
-    fn missing_variant_nested_inside_enum_payload_is_rejected() {
-        check_err(
-            "enum Shape { Circle(int), Square(int) }\n\
-             enum Wrap { Has(Shape) }\n\
-             fn f(Wrap w) -> int {\n\
-             return match w {\n\
-             Wrap::Has(Shape::Circle(r)) => r,\n\
-             };\n\
-             }\n",
-            "Shape::Square",
-        );
+    fn a_fns_local_constants_do_not_reach_the_next_fn() {
+        let src = "fn a() -> int { let n = 0; return n; }\n\
+                   fn b(int n) -> int requires n > 0 { return n; }\n\
+                   fn c(int n) -> int { return b(n); }\n";
+        let (result, _) = check(src, false);
+        assert!(result.is_ok(), "{result:?}");
     }
 }
@@ -143,6 +143,7 @@ impl Substitution {
             },
             // RES-401: tuple element types may contain Var nodes.
             Type::Tuple(ts) => Type::Tuple(ts.iter().map(|t| self.apply(t)).collect()),
+            Type::Union(ms) => Type::Union(ms.iter().map(|m| self.apply(m)).collect()),
             Type::AnonymousStruct(fields) => Type::AnonymousStruct(
                 fields
                     .iter()
@@ -331,6 +332,7 @@ impl Substitution {
             } => params.iter().any(|p| self.occurs(v, p)) || self.occurs(v, return_type),
             // RES-401: check element types of tuples.
             Type::Tuple(ts) => ts.iter().any(|t| self.occurs(v, t)),
+            Type::Union(ms) => ms.iter().any(|m| self.occurs(v, m)),
             Type::AnonymousStruct(fields) => fields.iter().any(|(_, ty)| self.occurs(v, ty)),
             // RES-2651: check inner type of Option.
             Type::Option(inner) => self.occurs(v, inner),
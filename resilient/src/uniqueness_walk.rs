@@ -214,6 +214,8 @@ pub(crate) fn walk_children<'a>(node: &'a Node, f: &mut impl FnMut(&'a Node)) {
             }
         }
         Node::Quantifier { body, .. } => visit(body, f),
+        Node::UnsafeBlock { body, .. } => visit(body, f),
+        Node::DeferStatement { expr, .. } => visit(expr, f),
         // Leaf nodes and declarations without expression children.
         _ => {}
     }
@@ -497,7 +499,7 @@ mod tests {
     fn is_ident_returns_true_for_match() {
         use crate::span::Span;
         let node = Node::Identifier {
-            name: "myVar".to_string(),
+            name: "myVar".into(),
             span: Span::default(),
         };
         assert!(is_ident(&node, "myVar"));
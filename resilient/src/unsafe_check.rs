@@ -2,8 +2,9 @@
 //!
 //! Walks the program AST after typechecking and rejects any call to
 //! one of the eight volatile intrinsics
-//! (`volatile_read_u8/16/32/64`, `volatile_write_u8/16/32/64`) that
-//! is not lexically inside an `unsafe { … }` block.
+//! (`volatile_read_u8/16/32/64`, `volatile_write_u8/16/32/64`) or
+//! the register builtins (`mmio_read`, `mmio_write`) that is not
+//! lexically inside an `unsafe { … }` block.
 //!
 //! The pass runs once per compilation unit; it doesn't change the
 //! AST, only collects diagnostics. Errors are surfaced through the
@@ -23,9 +24,15 @@
 //!   rather than as a guard inside the call typecheck arm.
 
 use crate::Node;
+use crate::mmio::MMIO_BUILTIN_NAMES;
 use crate::uniqueness_walk::any_node;
 use crate::volatile::VOLATILE_INTRINSIC_NAMES;
 
+/// Whether `name` is a builtin only `unsafe` code may call.
+fn is_privileged(name: &str) -> bool {
+    VOLATILE_INTRINSIC_NAMES.contains(&name) || MMIO_BUILTIN_NAMES.contains(&name)
+}
+
 /// Walk `program`, returning a list of human-readable diagnostics
 /// for every privileged-builtin call that's not inside an `unsafe`
 /// block. Empty list means clean.
@@ -43,7 +50,7 @@ use crate::volatile::VOLATILE_INTRINSIC_NAMES;
 pub fn check_program(program: &Node) -> Vec<String> {
     let has_volatile_call = any_node(program, |n| match n {
         Node::CallExpression { function, .. } => match function.as_ref() {
-            Node::Identifier { name, .. } => VOLATILE_INTRINSIC_NAMES.contains(&name.as_str()),
+            Node::Identifier { name, .. } => is_privileged(name),
             _ => false,
         },
         _ => false,
@@ -65,7 +72,7 @@ fn walk(node: &Node, inside_unsafe: bool, errs: &mut Vec<String>) {
             ..
         } => {
             if let Node::Identifier { name, span, .. } = function.as_ref()
-                && VOLATILE_INTRINSIC_NAMES.contains(&name.as_str())
+                && is_privileged(name)
                 && !inside_unsafe
             {
                 errs.push(format!(
@@ -156,6 +163,13 @@ mod tests {
         assert!(e[0].contains("unsafe"));
     }
 
+    #[test]
+    fn mmio_builtins_are_gated_like_the_intrinsics() {
+        let e = errs("mmio_write(0x40021000, 1);\nunsafe { let r = mmio_read(0x40021000); }");
+        assert_eq!(e.len(), 1, "{:?}", e);
+        assert!(e[0].contains("`mmio_write` outside an `unsafe` block"));
+    }
+
     #[test]
     fn volatile_inside_unsafe_is_clean() {
         let e = errs(
@@ -100,9 +100,17 @@ pub(crate) fn check(program: &Node, source_path: &str) {
     // Warn for every candidate whose alias isn't referenced.
     for info in &candidates {
         if !used_namespaces.contains(info.alias) {
-            eprintln!(
-                "warning: {}:{}:{}: unused import: \"{}\" (alias `{}`)",
-                source_path, info.span.start.line, info.span.start.column, info.path, info.alias,
+            crate::typechecker::emit_check_warning_plain(
+                format!(
+                    "warning: {}:{}:{}: unused import: \"{}\" (alias `{}`)",
+                    source_path,
+                    info.span.start.line,
+                    info.span.start.column,
+                    info.path,
+                    info.alias,
+                ),
+                source_path,
+                "unused-import",
             );
         }
     }
@@ -144,10 +152,10 @@ fn collect_namespaces<'a>(node: &'a Node, out: &mut HashSet<&'a str>) {
             for d in defaults.iter().flatten() {
                 collect_namespaces(d, out);
             }
-            for r in requires {
+            for r in requires.iter() {
                 collect_namespaces(r, out);
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 collect_namespaces(e, out);
             }
             if let Some(rt) = recovers_to {
@@ -162,10 +170,10 @@ fn collect_namespaces<'a>(node: &'a Node, out: &mut HashSet<&'a str>) {
             ..
         } => {
             collect_namespaces(body, out);
-            for r in requires {
+            for r in requires.iter() {
                 collect_namespaces(r, out);
             }
-            for e in ensures {
+            for e in ensures.iter() {
                 collect_namespaces(e, out);
             }
             if let Some(rt) = recovers_to {
@@ -475,6 +483,7 @@ fn collect_namespaces<'a>(node: &'a Node, out: &mut HashSet<&'a str>) {
         Node::BreakWith { value, .. } => collect_namespaces(value, out),
         Node::DeferStatement { expr, .. } => collect_namespaces(expr, out),
         Node::BenchBlock { body, .. } => collect_namespaces(body, out),
+        Node::TestFn { function, .. } => collect_namespaces(function, out),
     }
 }
 
@@ -311,6 +311,19 @@ thread_local! {
         std::cell::RefCell::new(HashMap::new());
 }
 
+/// The registry `check` left on this thread, for a worker thread that
+/// checks part of the same program.
+pub(crate) fn registry() -> HashMap<String, VarianceMap> {
+    VARIANCE_REGISTRY.with(|cell| cell.borrow().clone())
+}
+
+/// Replace this thread's registry with one taken by [`registry`].
+pub(crate) fn install_registry(registry: HashMap<String, VarianceMap>) {
+    VARIANCE_REGISTRY.with(|cell| {
+        *cell.borrow_mut() = registry;
+    });
+}
+
 /// Query the variance of `tp_name` in function `fn_name`.
 /// Returns `Variance::Phantom` when the function or parameter is unknown
 /// (i.e., when `check` has not yet been called for this program).
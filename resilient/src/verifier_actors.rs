@@ -234,7 +234,7 @@ fn straight_line_post(body: &Node, state_name: &str) -> Option<Node> {
     // so the verifier translates it to a free Z3 Int constant
     // representing the pre-state value of the field.
     let mut current = Node::Identifier {
-        name: state_name.to_string(),
+        name: state_name.into(),
         span: Span::default(),
     };
 
@@ -404,7 +404,7 @@ fn render_clause(node: &Node) -> String {
         } => {
             format!("{}{}", operator, render_clause(right))
         }
-        Node::Identifier { name, .. } => name.clone(),
+        Node::Identifier { name, .. } => name.to_string(),
         Node::IntegerLiteral { value, .. } => value.to_string(),
         Node::BooleanLiteral { value, .. } => value.to_string(),
         Node::FieldAccess { target, field, .. } => {
@@ -441,7 +441,7 @@ mod tests {
 
     fn ident(name: &str) -> Node {
         Node::Identifier {
-            name: name.to_string(),
+            name: name.into(),
             span: Span::default(),
         }
     }
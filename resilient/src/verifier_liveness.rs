@@ -222,7 +222,7 @@ pub(crate) fn verify_actor_liveness(
 /// straight back through the existing Z3 translator.
 fn candidates(state_name: &str) -> Vec<Node> {
     let state = Node::Identifier {
-        name: state_name.to_string(),
+        name: state_name.into(),
         span: Span::default(),
     };
     vec![
@@ -485,7 +485,7 @@ fn render_clause(node: &Node) -> String {
         Node::PrefixExpression {
             operator, right, ..
         } => format!("{}{}", operator, render_clause(right)),
-        Node::Identifier { name, .. } => name.clone(),
+        Node::Identifier { name, .. } => name.to_string(),
         Node::IntegerLiteral { value, .. } => value.to_string(),
         Node::BooleanLiteral { value, .. } => value.to_string(),
         Node::FieldAccess { target, field, .. } => {
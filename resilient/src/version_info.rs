@@ -48,6 +48,7 @@ pub fn verbose() -> String {
         ("commit", env!("RESILIENT_BUILD_GIT_HASH")),
         ("built", env!("RESILIENT_BUILD_DATE")),
         ("target", env!("RESILIENT_BUILD_TARGET")),
+        ("host", crate::host_profile::HostProfile::current().name()),
         ("profile", env!("RESILIENT_BUILD_PROFILE")),
         ("rustc", env!("RESILIENT_BUILD_RUSTC_VERSION")),
         ("features", env!("RESILIENT_BUILD_FEATURES")),
@@ -107,6 +108,7 @@ mod tests {
                 v
             );
         }
+        assert!(v.contains("host: native"), "host line missing: {:?}", v);
         // Profile likewise — set by Cargo.
         let profile = env!("RESILIENT_BUILD_PROFILE");
         if profile != "unknown" {
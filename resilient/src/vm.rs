@@ -14,6 +14,7 @@
 
 use crate::Value;
 use crate::bytecode::{Chunk, Op, Program};
+use std::rc::Rc;
 
 /// Errors the VM can surface at runtime. Like `CompileError`, the
 /// `&'static str` payloads describe the offending op without
@@ -102,6 +103,13 @@ pub enum VmError {
     /// `recovers_to` final-state-counterexample wording) — see
     /// `Op::ContractViolation`.
     ContractViolation(String),
+    /// The run spent its `--max-steps` budget (see `fuel`). Carries
+    /// the interpreter's message so both runtimes report it alike.
+    ResourceExhausted(String),
+    /// A float operator produced `NaN` or an infinity under
+    /// `--float-strict` (see `float_mode`). Carries the interpreter's
+    /// message so both runtimes report it alike.
+    FloatFault(String),
 }
 
 impl VmError {
@@ -160,6 +168,8 @@ impl std::fmt::Display for VmError {
                 write!(f, "vm: checked failure: {}", variant)
             }
             VmError::ContractViolation(msg) => write!(f, "{}", msg),
+            VmError::ResourceExhausted(msg) => write!(f, "{}", msg),
+            VmError::FloatFault(msg) => write!(f, "{}", msg),
         }
     }
 }
@@ -841,6 +851,53 @@ fn run_inner(
 /// running a live block's body; `stack`/`locals`/`frames`/`try_stack`
 /// are shared by `&mut` reference with the caller so a retry can see
 /// (and roll back) whatever the failed attempt mutated.
+/// Pop `try_stack` down to the innermost frame with a `catch` arm
+/// for `variant`; returns it with that arm's handler pc. Used for
+/// typed builtin failures (`builtin_failure_variant`).
+fn pop_catching_frame(
+    program: &Program,
+    try_stack: &mut Vec<TryFrame>,
+    variant: &str,
+) -> Option<(TryFrame, usize)> {
+    while let Some(try_frame) = try_stack.pop() {
+        let handler_chunk = if try_frame.chunk_idx == usize::MAX {
+            &program.main
+        } else {
+            &program.functions[try_frame.chunk_idx].chunk
+        };
+        let entry = &handler_chunk.try_handlers[try_frame.handler_table_idx as usize];
+        if let Some(arm) = entry.arms.iter().find(|a| a.variant == variant) {
+            let handler_pc = arm.handler_pc;
+            return Some((try_frame, handler_pc));
+        }
+    }
+    None
+}
+
+/// `exit(code)` unwinds every live frame: run each one's pending
+/// `defer`s, innermost first, as its return would have. Their errors
+/// are dropped — the exit wins, as a failing body's error wins over
+/// its defers' in the tree walker.
+fn run_defers_for_exit(
+    program: &Program,
+    frames: &[CallFrame],
+    locals: &[Value],
+    overflow_mode: OverflowMode,
+) {
+    for frame in frames.iter().rev() {
+        for defer_fn_idx in frame.defers.iter().rev() {
+            let Some(func) = program.functions.get(*defer_fn_idx as usize) else {
+                continue;
+            };
+            let args: Vec<Value> = locals
+                .get(frame.locals_base..frame.locals_base + func.arity as usize)
+                .map(<[Value]>::to_vec)
+                .unwrap_or_default();
+            let _ = run_postcheck(program, *defer_fn_idx, args, overflow_mode);
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn run_dispatch_loop(
     program: &Program,
@@ -911,6 +968,7 @@ fn run_dispatch_loop(
             return Ok(LoopOutcome::ExitedNormally);
         }
         frames[frame_idx].pc += 1;
+        crate::fuel::charge().map_err(VmError::ResourceExhausted)?;
 
         match op {
             Op::Const(idx) => {
@@ -924,12 +982,13 @@ fn run_dispatch_loop(
             Op::Add => {
                 let b = stack.pop().ok_or(VmError::EmptyStack)?;
                 let a = stack.pop().ok_or(VmError::EmptyStack)?;
+                let (a, b) = vm_widen_operands(a, b);
                 match (a, b) {
                     (Value::Int(x), Value::Int(y)) => {
                         stack.push(Value::Int(overflow_mode.add(x, y, "Add")?));
                     }
                     (Value::Float(x), Value::Float(y)) => {
-                        stack.push(Value::Float(x + y));
+                        stack.push(vm_float_arith("+", x, y, x + y)?);
                     }
                     (Value::String(s1), Value::String(s2)) => {
                         stack.push(Value::String(s1 + &s2));
@@ -980,12 +1039,13 @@ fn run_dispatch_loop(
             Op::Sub => {
                 let b = stack.pop().ok_or(VmError::EmptyStack)?;
                 let a = stack.pop().ok_or(VmError::EmptyStack)?;
+                let (a, b) = vm_widen_operands(a, b);
                 match (a, b) {
                     (Value::Int(x), Value::Int(y)) => {
                         stack.push(Value::Int(overflow_mode.sub(x, y, "Sub")?));
                     }
                     (Value::Float(x), Value::Float(y)) => {
-                        stack.push(Value::Float(x - y));
+                        stack.push(vm_float_arith("-", x, y, x - y)?);
                     }
                     // RES-3994: `impl Sub for T` operator overload (see Add above).
                     (a, b) => {
@@ -1016,12 +1076,13 @@ fn run_dispatch_loop(
             Op::Mul => {
                 let b = stack.pop().ok_or(VmError::EmptyStack)?;
                 let a = stack.pop().ok_or(VmError::EmptyStack)?;
+                let (a, b) = vm_widen_operands(a, b);
                 match (a, b) {
                     (Value::Int(x), Value::Int(y)) => {
                         stack.push(Value::Int(overflow_mode.mul(x, y, "Mul")?));
                     }
                     (Value::Float(x), Value::Float(y)) => {
-                        stack.push(Value::Float(x * y));
+                        stack.push(vm_float_arith("*", x, y, x * y)?);
                     }
                     (Value::String(ref s), Value::Int(n))
                     | (Value::Int(n), Value::String(ref s)) => {
@@ -1069,12 +1130,13 @@ fn run_dispatch_loop(
             Op::Div => {
                 let b = stack.pop().ok_or(VmError::EmptyStack)?;
                 let a = stack.pop().ok_or(VmError::EmptyStack)?;
+                let (a, b) = vm_widen_operands(a, b);
                 match (a, b) {
                     (Value::Int(x), Value::Int(y)) => {
                         stack.push(Value::Int(overflow_mode.div(x, y)?));
                     }
                     (Value::Float(x), Value::Float(y)) => {
-                        stack.push(Value::Float(x / y));
+                        stack.push(vm_float_arith("/", x, y, x / y)?);
                     }
                     _ => return Err(VmError::TypeMismatch("Div")),
                 }
@@ -1082,12 +1144,13 @@ fn run_dispatch_loop(
             Op::Mod => {
                 let b = stack.pop().ok_or(VmError::EmptyStack)?;
                 let a = stack.pop().ok_or(VmError::EmptyStack)?;
+                let (a, b) = vm_widen_operands(a, b);
                 match (a, b) {
                     (Value::Int(x), Value::Int(y)) => {
                         stack.push(Value::Int(overflow_mode.rem(x, y)?));
                     }
                     (Value::Float(x), Value::Float(y)) => {
-                        stack.push(Value::Float(x % y));
+                        stack.push(vm_float_arith("%", x, y, x % y)?);
                     }
                     _ => return Err(VmError::TypeMismatch("Mod")),
                 }
@@ -1414,9 +1477,13 @@ fn run_dispatch_loop(
             Op::Lt => {
                 let b = stack.pop().ok_or(VmError::EmptyStack)?;
                 let a = stack.pop().ok_or(VmError::EmptyStack)?;
+                let (a, b) = vm_widen_operands(a, b);
                 match (a, b) {
                     (Value::Int(x), Value::Int(y)) => stack.push(Value::Bool(x < y)),
-                    (Value::Float(x), Value::Float(y)) => stack.push(Value::Bool(x < y)),
+                    (Value::Float(x), Value::Float(y)) => {
+                        vm_float_cmp("<", x, y)?;
+                        stack.push(Value::Bool(x < y))
+                    }
                     (Value::String(ref x), Value::String(ref y)) => stack.push(Value::Bool(x < y)),
                     // RES-2683: char ordering.
                     (Value::Char(x), Value::Char(y)) => stack.push(Value::Bool(x < y)),
@@ -1426,9 +1493,13 @@ fn run_dispatch_loop(
             Op::Le => {
                 let b = stack.pop().ok_or(VmError::EmptyStack)?;
                 let a = stack.pop().ok_or(VmError::EmptyStack)?;
+                let (a, b) = vm_widen_operands(a, b);
                 match (a, b) {
                     (Value::Int(x), Value::Int(y)) => stack.push(Value::Bool(x <= y)),
-                    (Value::Float(x), Value::Float(y)) => stack.push(Value::Bool(x <= y)),
+                    (Value::Float(x), Value::Float(y)) => {
+                        vm_float_cmp("<=", x, y)?;
+                        stack.push(Value::Bool(x <= y))
+                    }
                     (Value::String(ref x), Value::String(ref y)) => stack.push(Value::Bool(x <= y)),
                     // RES-2683: char ordering.
                     (Value::Char(x), Value::Char(y)) => stack.push(Value::Bool(x <= y)),
@@ -1438,9 +1509,13 @@ fn run_dispatch_loop(
             Op::Gt => {
                 let b = stack.pop().ok_or(VmError::EmptyStack)?;
                 let a = stack.pop().ok_or(VmError::EmptyStack)?;
+                let (a, b) = vm_widen_operands(a, b);
                 match (a, b) {
                     (Value::Int(x), Value::Int(y)) => stack.push(Value::Bool(x > y)),
-                    (Value::Float(x), Value::Float(y)) => stack.push(Value::Bool(x > y)),
+                    (Value::Float(x), Value::Float(y)) => {
+                        vm_float_cmp(">", x, y)?;
+                        stack.push(Value::Bool(x > y))
+                    }
                     (Value::String(ref x), Value::String(ref y)) => stack.push(Value::Bool(x > y)),
                     // RES-2683: char ordering.
                     (Value::Char(x), Value::Char(y)) => stack.push(Value::Bool(x > y)),
@@ -1450,9 +1525,13 @@ fn run_dispatch_loop(
             Op::Ge => {
                 let b = stack.pop().ok_or(VmError::EmptyStack)?;
                 let a = stack.pop().ok_or(VmError::EmptyStack)?;
+                let (a, b) = vm_widen_operands(a, b);
                 match (a, b) {
                     (Value::Int(x), Value::Int(y)) => stack.push(Value::Bool(x >= y)),
-                    (Value::Float(x), Value::Float(y)) => stack.push(Value::Bool(x >= y)),
+                    (Value::Float(x), Value::Float(y)) => {
+                        vm_float_cmp(">=", x, y)?;
+                        stack.push(Value::Bool(x >= y))
+                    }
                     (Value::String(ref x), Value::String(ref y)) => stack.push(Value::Bool(x >= y)),
                     // RES-2683: char ordering.
                     (Value::Char(x), Value::Char(y)) => stack.push(Value::Bool(x >= y)),
@@ -1801,13 +1880,48 @@ fn run_dispatch_loop(
                     // comment — reconstructs the call stack from
                     // `frames` itself rather than a dedicated tracking
                     // vec (the tree-walker's `Interpreter::call_stack`).
-                    Value::Array(vm_stacktrace_builtin(frames, program, source_path))
+                    Value::Array(vm_stacktrace_builtin(frames, program, source_path).into())
                 } else if let Some(func) = crate::lookup_builtin(name) {
-                    func(&args).map_err(VmError::BuiltinCallFailed)?
+                    match func(&args) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            // A typed failure (`NotFound`, ...) inside
+                            // `try` jumps to its `catch` arm, the same
+                            // unwind as a `fails` call above.
+                            if let Some(variant) = crate::builtin_failure_variant(&e)
+                                && let Some((try_frame, handler_pc)) =
+                                    pop_catching_frame(program, try_stack, variant)
+                            {
+                                while frames.len() > try_frame.call_depth {
+                                    let popped = frames.pop().ok_or(VmError::CallStackUnderflow)?;
+                                    locals.truncate(popped.locals_base);
+                                }
+                                stack.truncate(try_frame.stack_depth);
+                                let fi = frames.len() - 1;
+                                frames[fi].pc = handler_pc;
+                                if let Some(target_len) = target_frames_len
+                                    && frames.len() < target_len
+                                {
+                                    return Ok(LoopOutcome::ReturnedFromFrame(Value::Void));
+                                }
+                                continue;
+                            }
+                            if crate::exit_request().is_some() {
+                                run_defers_for_exit(program, frames, locals, overflow_mode);
+                            }
+                            return Err(VmError::BuiltinCallFailed(e));
+                        }
+                    }
                 } else if let Some(stdlib_result) =
                     crate::stdlib::call_by_qualified_name(name, &args)
                 {
-                    stdlib_result.map_err(VmError::BuiltinCallFailed)?
+                    // `os::exit` unwinds like `exit`.
+                    stdlib_result.map_err(|e| {
+                        if crate::exit_request().is_some() {
+                            run_defers_for_exit(program, frames, locals, overflow_mode);
+                        }
+                        VmError::BuiltinCallFailed(e)
+                    })?
                 } else {
                     return Err(VmError::UnknownBuiltin(name.to_string()));
                 };
@@ -1825,7 +1939,7 @@ fn run_dispatch_loop(
                 }
                 let split_at = stack.len() - n;
                 let items: Vec<Value> = stack.drain(split_at..).collect();
-                stack.push(Value::Array(items));
+                stack.push(Value::Array(items.into()));
             }
             // RES-401: same convention as MakeArray — drain `len` values.
             Op::MakeTuple { len } => {
@@ -2037,7 +2151,9 @@ fn run_dispatch_loop(
                 let locals_snapshot: Vec<Value> = locals[locals_base..].to_vec();
                 let stack_depth = stack.len();
                 let max_retries = entry.max_retries as usize;
-                let start_instant = entry.timeout_ns.map(|_| std::time::Instant::now());
+                let start_instant = entry
+                    .timeout_ns
+                    .map(|_| crate::host_clock::monotonic_nanos());
                 let mut retry_count: usize = 0;
                 loop {
                     frames[frame_idx].pc = entry.body_start_pc;
@@ -2068,6 +2184,11 @@ fn run_dispatch_loop(
                             break;
                         }
                         Ok(LoopOutcome::Halted(v)) => return Ok(LoopOutcome::Halted(v)),
+                        // A spent step budget stays spent; retrying
+                        // cannot succeed.
+                        Err(e) if matches!(e.kind(), VmError::ResourceExhausted(_)) => {
+                            return Err(e);
+                        }
                         Err(e) => {
                             // RES-359 + RES-141: same retry-count
                             // arithmetic as the tree-walker's
@@ -2083,7 +2204,8 @@ fn run_dispatch_loop(
                             }
                             let timed_out = match (start_instant, entry.timeout_ns) {
                                 (Some(t0), Some(budget)) => {
-                                    t0.elapsed().as_nanos() >= u128::from(budget)
+                                    crate::host_clock::monotonic_nanos().saturating_sub(t0)
+                                        >= u128::from(budget)
                                 }
                                 _ => false,
                             };
@@ -2267,7 +2389,10 @@ fn run_dispatch_loop(
                     };
                     fields.push((field_name, v));
                 }
-                stack.push(Value::Struct { name, fields });
+                stack.push(Value::Struct {
+                    name,
+                    fields: Rc::new(fields),
+                });
             }
             Op::MakeEnumTuple {
                 type_const,
@@ -2347,7 +2472,9 @@ fn run_dispatch_loop(
                 else {
                     return Err(VmError::TypeMismatch("SetField (non-struct target)"));
                 };
-                let slot = fields.iter_mut().find(|(k, _)| k.as_str() == field);
+                let slot = Rc::make_mut(&mut fields)
+                    .iter_mut()
+                    .find(|(k, _)| k.as_str() == field);
                 match slot {
                     Some((_, existing)) => *existing = v,
                     None => {
@@ -2481,16 +2608,33 @@ fn vm_can_stringify(v: &Value) -> bool {
     // RES-3889: `Value::Char` participates in `string + char` concatenation,
     // mirroring the interpreter's `can_stringify_for_concat`. String subscript
     // now yields a `Char`, so `"prefix" + s[i]` must stringify it.
+    // The strict coercion policy turns the conversion off entirely.
     matches!(
         v,
         Value::Int(_) | Value::Float(_) | Value::Bool(_) | Value::Char(_)
-    )
+    ) && crate::coercion::policy().allows_string_concat()
+}
+
+/// Arithmetic and ordering operands after the coercion policy: an
+/// `int` beside a `float` becomes a `float` under `--coercion
+/// permissive`; everything else passes through unchanged.
+#[inline]
+fn vm_widen_operands(a: Value, b: Value) -> (Value, Value) {
+    match (&a, &b) {
+        (Value::Int(_), Value::Float(_)) | (Value::Float(_), Value::Int(_)) => {
+            match crate::coercion::widen_pair(&a, &b) {
+                Some((x, y)) => (Value::Float(x), Value::Float(y)),
+                None => (a, b),
+            }
+        }
+        _ => (a, b),
+    }
 }
 
 fn vm_stringify(v: &Value) -> String {
     match v {
         Value::Int(i) => i.to_string(),
-        Value::Float(f) => f.to_string(),
+        Value::Float(f) => crate::float_mode::display(*f),
         Value::Bool(b) => b.to_string(),
         Value::String(s) => s.clone(),
         // RES-3889: bare character, matching the interpreter's `Display`.
@@ -2502,7 +2646,7 @@ fn vm_stringify(v: &Value) -> String {
 fn vm_push_stringified(buf: &mut String, v: &Value) {
     match v {
         Value::Int(i) => buf.push_str(&i.to_string()),
-        Value::Float(f) => buf.push_str(&f.to_string()),
+        Value::Float(f) => buf.push_str(&crate::float_mode::display(*f)),
         Value::Bool(b) => buf.push_str(&b.to_string()),
         // RES-3889: append the bare character, matching the interpreter.
         Value::Char(c) => buf.push(*c),
@@ -2743,7 +2887,7 @@ fn vm_call_closure_value(
 fn run_pending_actors(program: &Program, overflow_mode: OverflowMode) -> Result<(), VmError> {
     const MAX_ACTOR_STEPS: usize = 100_000;
     let mut steps = 0;
-    while let Some(pid) = crate::actor_runtime::next_runnable_actor() {
+    while let Some(pid) = crate::next_scheduled_actor() {
         if steps >= MAX_ACTOR_STEPS {
             return Err(VmError::BuiltinCallFailed(format!(
                 "actor scheduler exceeded step limit ({MAX_ACTOR_STEPS}); \
@@ -2774,10 +2918,7 @@ fn run_pending_actors(program: &Program, overflow_mode: OverflowMode) -> Result<
                     actor_pid: pid.0,
                     reason: crate::supervisor_runtime::CrashReason::UnhandledError,
                 };
-                let now_secs = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
+                let now_secs = crate::host_clock::wall_clock_since_epoch().as_secs();
 
                 if crate::supervisor_runtime::handle_crash_event(crash_event, now_secs) {
                     crate::actor_runtime::mark_runnable(pid);
@@ -2841,7 +2982,7 @@ fn vm_call_array_functional_method(
             for item in items {
                 out.push(call(program, &callback, vec![item])?);
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         })(),
         "filter" => (|| {
             if args.len() != 1 {
@@ -2864,7 +3005,7 @@ fn vm_call_array_functional_method(
                     }
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         })(),
         "reduce" => (|| match args.len() {
             1 => {
@@ -2908,7 +3049,7 @@ fn vm_call_array_functional_method(
                     other => out.push(other),
                 }
             }
-            Ok(Value::Array(out))
+            Ok(Value::Array(out.into()))
         })(),
         "for_each" => (|| {
             if args.len() != 1 {
@@ -3274,8 +3415,14 @@ fn vm_call_builtin_method(
             method,
             "map" | "filter" | "reduce" | "flat_map" | "for_each" | "find" | "any" | "all"
         ) {
-            return vm_call_array_functional_method(program, items, method, args, overflow_mode)
-                .expect("method name checked against the exact set this helper handles");
+            return vm_call_array_functional_method(
+                program,
+                items.into_inner(),
+                method,
+                args,
+                overflow_mode,
+            )
+            .expect("method name checked against the exact set this helper handles");
         }
         return vm_call_builtin_method_scalar(Value::Array(items), method, args);
     }
@@ -3351,6 +3498,20 @@ fn vm_call_builtin_method_scalar(
     func(&call_args).map_err(VmError::BuiltinCallFailed)
 }
 
+/// `x op y` for floats, checked against `--float-strict`.
+#[inline]
+fn vm_float_arith(op: &str, x: f64, y: f64, result: f64) -> Result<Value, VmError> {
+    crate::float_mode::check_arith(op, x, y, result)
+        .map(Value::Float)
+        .map_err(VmError::FloatFault)
+}
+
+/// Reject an ordering comparison with `NaN` under `--float-strict`.
+#[inline]
+fn vm_float_cmp(op: &str, x: f64, y: f64) -> Result<(), VmError> {
+    crate::float_mode::check_cmp(op, x, y).map_err(VmError::FloatFault)
+}
+
 fn vm_values_eq(a: &Value, b: &Value) -> bool {
     match (a, b) {
         (Value::Int(x), Value::Int(y)) => x == y,
@@ -3360,7 +3521,10 @@ fn vm_values_eq(a: &Value, b: &Value) -> bool {
         // RES-2683: char equality.
         (Value::Char(x), Value::Char(y)) => x == y,
         (Value::Void, Value::Void) => true,
-        (Value::Array(x), Value::Array(y)) | (Value::Tuple(x), Value::Tuple(y)) => {
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y.iter()).all(|(a, b)| vm_values_eq(a, b))
+        }
+        (Value::Tuple(x), Value::Tuple(y)) => {
             x.len() == y.len() && x.iter().zip(y.iter()).all(|(a, b)| vm_values_eq(a, b))
         }
         (
@@ -3466,6 +3630,8 @@ fn vm_enum_payload_eq(l: &crate::EnumValuePayload, r: &crate::EnumValuePayload)
 fn vm_values_eq_checked(a: &Value, b: &Value) -> Result<bool, VmError> {
     if std::mem::discriminant(a) == std::mem::discriminant(b) {
         Ok(vm_values_eq(a, b))
+    } else if let Some((x, y)) = crate::coercion::widen_pair(a, b) {
+        Ok(x == y)
     } else {
         Err(VmError::TypeMismatch("=="))
     }
@@ -3921,6 +4087,7 @@ fn h_const(state: &mut VmState<'_>, op: Op) -> Result<Step, VmError> {
 fn h_add(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
     let b = state.stack.pop().ok_or(VmError::EmptyStack)?;
     let a = state.stack.pop().ok_or(VmError::EmptyStack)?;
+    let (a, b) = vm_widen_operands(a, b);
     match (a, b) {
         (Value::Int(x), Value::Int(y)) => {
             state
@@ -3928,7 +4095,7 @@ fn h_add(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
                 .push(Value::Int(state.overflow_mode.add(x, y, "Add")?));
         }
         (Value::Float(x), Value::Float(y)) => {
-            state.stack.push(Value::Float(x + y));
+            state.stack.push(vm_float_arith("+", x, y, x + y)?);
         }
         (Value::String(s1), Value::String(s2)) => {
             state.stack.push(Value::String(s1 + &s2));
@@ -3982,6 +4149,7 @@ fn h_add(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
 fn h_sub(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
     let b = state.stack.pop().ok_or(VmError::EmptyStack)?;
     let a = state.stack.pop().ok_or(VmError::EmptyStack)?;
+    let (a, b) = vm_widen_operands(a, b);
     match (a, b) {
         (Value::Int(x), Value::Int(y)) => {
             state
@@ -3989,7 +4157,7 @@ fn h_sub(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
                 .push(Value::Int(state.overflow_mode.sub(x, y, "Sub")?));
         }
         (Value::Float(x), Value::Float(y)) => {
-            state.stack.push(Value::Float(x - y));
+            state.stack.push(vm_float_arith("-", x, y, x - y)?);
         }
         // RES-3994: `impl Sub for T` operator overload (see h_add above).
         (a, b) => {
@@ -4025,6 +4193,7 @@ fn h_sub(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
 fn h_mul(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
     let b = state.stack.pop().ok_or(VmError::EmptyStack)?;
     let a = state.stack.pop().ok_or(VmError::EmptyStack)?;
+    let (a, b) = vm_widen_operands(a, b);
     match (a, b) {
         (Value::Int(x), Value::Int(y)) => {
             state
@@ -4032,7 +4201,7 @@ fn h_mul(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
                 .push(Value::Int(state.overflow_mode.mul(x, y, "Mul")?));
         }
         (Value::Float(x), Value::Float(y)) => {
-            state.stack.push(Value::Float(x * y));
+            state.stack.push(vm_float_arith("*", x, y, x * y)?);
         }
         (Value::String(ref s), Value::Int(n)) | (Value::Int(n), Value::String(ref s)) => {
             if n < 0 {
@@ -4084,12 +4253,13 @@ fn h_mul(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
 fn h_div(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
     let b = state.stack.pop().ok_or(VmError::EmptyStack)?;
     let a = state.stack.pop().ok_or(VmError::EmptyStack)?;
+    let (a, b) = vm_widen_operands(a, b);
     match (a, b) {
         (Value::Int(x), Value::Int(y)) => {
             state.stack.push(Value::Int(state.overflow_mode.div(x, y)?));
         }
         (Value::Float(x), Value::Float(y)) => {
-            state.stack.push(Value::Float(x / y));
+            state.stack.push(vm_float_arith("/", x, y, x / y)?);
         }
         _ => return Err(VmError::TypeMismatch("Div")),
     }
@@ -4100,12 +4270,13 @@ fn h_div(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
 fn h_mod(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
     let b = state.stack.pop().ok_or(VmError::EmptyStack)?;
     let a = state.stack.pop().ok_or(VmError::EmptyStack)?;
+    let (a, b) = vm_widen_operands(a, b);
     match (a, b) {
         (Value::Int(x), Value::Int(y)) => {
             state.stack.push(Value::Int(state.overflow_mode.rem(x, y)?));
         }
         (Value::Float(x), Value::Float(y)) => {
-            state.stack.push(Value::Float(x % y));
+            state.stack.push(vm_float_arith("%", x, y, x % y)?);
         }
         _ => return Err(VmError::TypeMismatch("Mod")),
     }
@@ -4338,9 +4509,13 @@ fn h_neq(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
 fn h_lt(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
     let b = state.stack.pop().ok_or(VmError::EmptyStack)?;
     let a = state.stack.pop().ok_or(VmError::EmptyStack)?;
+    let (a, b) = vm_widen_operands(a, b);
     let result = match (a, b) {
         (Value::Int(x), Value::Int(y)) => x < y,
-        (Value::Float(x), Value::Float(y)) => x < y,
+        (Value::Float(x), Value::Float(y)) => {
+            vm_float_cmp("<", x, y)?;
+            x < y
+        }
         (Value::String(ref x), Value::String(ref y)) => x < y,
         (Value::Char(x), Value::Char(y)) => x < y,
         _ => return Err(VmError::TypeMismatch("Lt")),
@@ -4353,9 +4528,13 @@ fn h_lt(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
 fn h_le(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
     let b = state.stack.pop().ok_or(VmError::EmptyStack)?;
     let a = state.stack.pop().ok_or(VmError::EmptyStack)?;
+    let (a, b) = vm_widen_operands(a, b);
     let result = match (a, b) {
         (Value::Int(x), Value::Int(y)) => x <= y,
-        (Value::Float(x), Value::Float(y)) => x <= y,
+        (Value::Float(x), Value::Float(y)) => {
+            vm_float_cmp("<=", x, y)?;
+            x <= y
+        }
         (Value::String(ref x), Value::String(ref y)) => x <= y,
         (Value::Char(x), Value::Char(y)) => x <= y,
         _ => return Err(VmError::TypeMismatch("Le")),
@@ -4368,9 +4547,13 @@ fn h_le(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
 fn h_gt(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
     let b = state.stack.pop().ok_or(VmError::EmptyStack)?;
     let a = state.stack.pop().ok_or(VmError::EmptyStack)?;
+    let (a, b) = vm_widen_operands(a, b);
     let result = match (a, b) {
         (Value::Int(x), Value::Int(y)) => x > y,
-        (Value::Float(x), Value::Float(y)) => x > y,
+        (Value::Float(x), Value::Float(y)) => {
+            vm_float_cmp(">", x, y)?;
+            x > y
+        }
         (Value::String(ref x), Value::String(ref y)) => x > y,
         (Value::Char(x), Value::Char(y)) => x > y,
         _ => return Err(VmError::TypeMismatch("Gt")),
@@ -4383,9 +4566,13 @@ fn h_gt(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
 fn h_ge(state: &mut VmState<'_>, _op: Op) -> Result<Step, VmError> {
     let b = state.stack.pop().ok_or(VmError::EmptyStack)?;
     let a = state.stack.pop().ok_or(VmError::EmptyStack)?;
+    let (a, b) = vm_widen_operands(a, b);
     let result = match (a, b) {
         (Value::Int(x), Value::Int(y)) => x >= y,
-        (Value::Float(x), Value::Float(y)) => x >= y,
+        (Value::Float(x), Value::Float(y)) => {
+            vm_float_cmp(">=", x, y)?;
+            x >= y
+        }
         (Value::String(ref x), Value::String(ref y)) => x >= y,
         (Value::Char(x), Value::Char(y)) => x >= y,
         _ => return Err(VmError::TypeMismatch("Ge")),
@@ -4576,7 +4763,7 @@ fn h_make_array(state: &mut VmState<'_>, op: Op) -> Result<Step, VmError> {
     }
     let split_at = state.stack.len() - n;
     let items: Vec<Value> = state.stack.drain(split_at..).collect();
-    state.stack.push(Value::Array(items));
+    state.stack.push(Value::Array(items.into()));
     Ok(Step::Continue)
 }
 
@@ -4792,13 +4979,29 @@ fn h_call_builtin(state: &mut VmState<'_>, op: Op) -> Result<Step, VmError> {
     } else if name == "stacktrace" {
         // RES-4131: see the match-dispatch `Op::CallBuiltin` arm and
         // `vm_stacktrace_builtin`'s doc comment for the rationale.
-        Value::Array(vm_stacktrace_builtin(
-            &state.frames,
-            state.program,
-            state.source_path,
-        ))
+        Value::Array(vm_stacktrace_builtin(&state.frames, state.program, state.source_path).into())
     } else if let Some(func) = crate::lookup_builtin(name) {
-        func(&args).map_err(VmError::BuiltinCallFailed)?
+        match func(&args) {
+            Ok(v) => v,
+            Err(e) => {
+                // See the match-dispatch arm: a typed failure with a
+                // matching `catch` arm unwinds to it.
+                if let Some(variant) = crate::builtin_failure_variant(&e)
+                    && let Some((try_frame, handler_pc)) =
+                        pop_catching_frame(state.program, &mut state.try_stack, variant)
+                {
+                    while state.frames.len() > try_frame.call_depth {
+                        let popped = state.frames.pop().ok_or(VmError::CallStackUnderflow)?;
+                        state.locals.truncate(popped.locals_base);
+                    }
+                    state.stack.truncate(try_frame.stack_depth);
+                    let fi = state.frame_idx();
+                    state.frames[fi].pc = handler_pc;
+                    return Ok(Step::Continue);
+                }
+                return Err(VmError::BuiltinCallFailed(e));
+            }
+        }
     } else if let Some(stdlib_result) = crate::stdlib::call_by_qualified_name(name, &args) {
         stdlib_result.map_err(VmError::BuiltinCallFailed)?
     } else {
@@ -4836,7 +5039,10 @@ fn h_struct_literal(state: &mut VmState<'_>, op: Op) -> Result<Step, VmError> {
         };
         fields.push((field_name, v));
     }
-    state.stack.push(Value::Struct { name, fields });
+    state.stack.push(Value::Struct {
+        name,
+        fields: Rc::new(fields),
+    });
     Ok(Step::Continue)
 }
 
@@ -4924,7 +5130,9 @@ fn h_set_field(state: &mut VmState<'_>, op: Op) -> Result<Step, VmError> {
     else {
         return Err(VmError::TypeMismatch("SetField (non-struct target)"));
     };
-    let slot = fields.iter_mut().find(|(k, _)| k.as_str() == field);
+    let slot = Rc::make_mut(&mut fields)
+        .iter_mut()
+        .find(|(k, _)| k.as_str() == field);
     match slot {
         Some((_, existing)) => *existing = v,
         None => {
@@ -5203,7 +5411,7 @@ fn iter_prepare_closure_or_value(
                 }
             }
         }
-        return Ok(Value::Array(items));
+        return Ok(Value::Array(items.into()));
     }
     iter_prepare_value(v)
 }
@@ -5225,7 +5433,7 @@ fn iter_prepare_value(v: Value) -> Result<Value, VmError> {
             let mut keys: Vec<&crate::MapKey> = m.keys().collect();
             keys.sort_unstable_by(|a, b| crate::map_entries_merge::cmp_map_keys(a, b));
             let arr: Vec<Value> = keys.into_iter().map(|k| k.to_value()).collect();
-            Ok(Value::Array(arr))
+            Ok(Value::Array(arr.into()))
         }
         Value::Range {
             start,
@@ -5235,7 +5443,7 @@ fn iter_prepare_value(v: Value) -> Result<Value, VmError> {
             let arr: Vec<Value> = crate::ranges::iterate_range(start, end, inclusive)
                 .map(Value::Int)
                 .collect();
-            Ok(Value::Array(arr))
+            Ok(Value::Array(arr.into()))
         }
         _ => Err(VmError::TypeMismatch(
             "IterPrepare: expected Array, String, Map, or Range",
@@ -6059,6 +6267,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn self_call_inside_try_is_not_a_tail_call() {
+        // The `catch` must see a failure from the callee, so the call
+        // keeps its own frame.
+        let src = "fn probe(int n) -> int { try { return probe(n - 1); } catch NotFound { return n; } } probe(1);";
+        let (ast, _) = crate::parse(src);
+        let prog = crate::compiler::compile(&ast).unwrap();
+        let code = &prog.functions[0].chunk.code;
+        assert!(
+            !code
+                .iter()
+                .any(|op| matches!(op, crate::bytecode::Op::TailCall(_))),
+            "unexpected TailCall in try body: {:?}",
+            code
+        );
+    }
+
     #[test]
     fn res384_non_self_call_still_uses_regular_call() {
         // A call to a *different* function must not be promoted to TailCall.
@@ -7332,11 +7557,9 @@ mod tests {
         let result = run_with(&prog, OverflowMode::Wrap, Dispatch::Match).unwrap();
         assert_eq!(
             value_repr(&result),
-            value_repr(&Value::Array(vec![
-                Value::Int(1),
-                Value::Int(2),
-                Value::Int(3)
-            ]))
+            value_repr(&Value::Array(
+                vec![Value::Int(1), Value::Int(2), Value::Int(3)].into()
+            ))
         );
     }
 
@@ -8277,8 +8500,8 @@ mod tests {
     fn vm_eq_arrays_equal() {
         let prog = const_program(
             &[
-                Value::Array(vec![Value::Int(1), Value::Int(2)]),
-                Value::Array(vec![Value::Int(1), Value::Int(2)]),
+                Value::Array(vec![Value::Int(1), Value::Int(2)].into()),
+                Value::Array(vec![Value::Int(1), Value::Int(2)].into()),
             ],
             &[Op::Const(0), Op::Const(1), Op::Eq, Op::Return],
         );
@@ -8292,8 +8515,8 @@ mod tests {
     fn vm_neq_arrays_different() {
         let prog = const_program(
             &[
-                Value::Array(vec![Value::Int(1), Value::Int(2)]),
-                Value::Array(vec![Value::Int(1), Value::Int(3)]),
+                Value::Array(vec![Value::Int(1), Value::Int(2)].into()),
+                Value::Array(vec![Value::Int(1), Value::Int(3)].into()),
             ],
             &[Op::Const(0), Op::Const(1), Op::Neq, Op::Return],
         );
@@ -8307,8 +8530,8 @@ mod tests {
     fn vm_eq_arrays_different_length() {
         let prog = const_program(
             &[
-                Value::Array(vec![Value::Int(1)]),
-                Value::Array(vec![Value::Int(1), Value::Int(2)]),
+                Value::Array(vec![Value::Int(1)].into()),
+                Value::Array(vec![Value::Int(1), Value::Int(2)].into()),
             ],
             &[Op::Const(0), Op::Const(1), Op::Eq, Op::Return],
         );
@@ -8337,14 +8560,20 @@ mod tests {
     fn vm_eq_nested_arrays() {
         let prog = const_program(
             &[
-                Value::Array(vec![
-                    Value::Array(vec![Value::Int(1), Value::Int(2)]),
-                    Value::Int(3),
-                ]),
-                Value::Array(vec![
-                    Value::Array(vec![Value::Int(1), Value::Int(2)]),
-                    Value::Int(3),
-                ]),
+                Value::Array(
+                    vec![
+                        Value::Array(vec![Value::Int(1), Value::Int(2)].into()),
+                        Value::Int(3),
+                    ]
+                    .into(),
+                ),
+                Value::Array(
+                    vec![
+                        Value::Array(vec![Value::Int(1), Value::Int(2)].into()),
+                        Value::Int(3),
+                    ]
+                    .into(),
+                ),
             ],
             &[Op::Const(0), Op::Const(1), Op::Eq, Op::Return],
         );
@@ -8360,11 +8589,17 @@ mod tests {
             &[
                 Value::Struct {
                     name: "Point".into(),
-                    fields: vec![("x".into(), Value::Int(1)), ("y".into(), Value::Int(2))],
+                    fields: Rc::new(vec![
+                        ("x".into(), Value::Int(1)),
+                        ("y".into(), Value::Int(2)),
+                    ]),
                 },
                 Value::Struct {
                     name: "Point".into(),
-                    fields: vec![("x".into(), Value::Int(1)), ("y".into(), Value::Int(2))],
+                    fields: Rc::new(vec![
+                        ("x".into(), Value::Int(1)),
+                        ("y".into(), Value::Int(2)),
+                    ]),
                 },
             ],
             &[Op::Const(0), Op::Const(1), Op::Eq, Op::Return],
@@ -8381,11 +8616,11 @@ mod tests {
             &[
                 Value::Struct {
                     name: "Point".into(),
-                    fields: vec![("x".into(), Value::Int(1))],
+                    fields: Rc::new(vec![("x".into(), Value::Int(1))]),
                 },
                 Value::Struct {
                     name: "Vec2".into(),
-                    fields: vec![("x".into(), Value::Int(1))],
+                    fields: Rc::new(vec![("x".into(), Value::Int(1))]),
                 },
             ],
             &[Op::Const(0), Op::Const(1), Op::Eq, Op::Return],
@@ -8424,7 +8659,7 @@ mod tests {
         m2.insert(MapKey::Int(2), Value::Bool(true));
         m2.insert(MapKey::Str("a".into()), Value::Int(1));
         let prog = const_program(
-            &[Value::Map(m1), Value::Map(m2)],
+            &[Value::Map(m1.into()), Value::Map(m2.into())],
             &[Op::Const(0), Op::Const(1), Op::Eq, Op::Return],
         );
         match run(&prog).unwrap() {
@@ -8441,7 +8676,7 @@ mod tests {
         let mut m2 = HashMap::new();
         m2.insert(MapKey::Str("a".into()), Value::Int(2));
         let prog = const_program(
-            &[Value::Map(m1), Value::Map(m2)],
+            &[Value::Map(m1.into()), Value::Map(m2.into())],
             &[Op::Const(0), Op::Const(1), Op::Neq, Op::Return],
         );
         match run(&prog).unwrap() {
@@ -8724,7 +8959,7 @@ mod tests {
     fn vm_jump_if_false_array_is_truthy() {
         let prog = const_program(
             &[
-                Value::Array(vec![Value::Int(1)]),
+                Value::Array(vec![Value::Int(1)].into()),
                 Value::Int(1),
                 Value::Int(0),
             ],
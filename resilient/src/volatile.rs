@@ -16,6 +16,10 @@
 //! * Lowers to `core::ptr::read_volatile` / `write_volatile` in
 //!   the runtime path. The pointer cast is justified by the
 //!   `unsafe { … }` gate at the call site.
+//! * Touches only addresses the `mmio` capability and the
+//!   `--mmio` / `[sandbox] mmio` allowlist permit (`mmio.rs`);
+//!   anything else fails with `PermissionDenied` before the
+//!   pointer is formed.
 //!
 //! The intrinsics are tagged "non-modelable" by the verifier —
 //! same path FFI takes (per the [TLA+ V2.0 design lock-in's Q4](../../docs/superpowers/specs/2026-04-30-tla-v2-design-lock-in.md#q4-ffi-side-effects--choose-or-contract)).
@@ -23,12 +27,15 @@
 //! (`CHOOSE x \in T : true`); writes are no-ops at the Z3 level.
 //!
 //! Tests use a small allocated buffer and the address-of trick —
-//! we cast a `Vec<u8>` pointer back to `usize`, hand it to the
-//! intrinsic, and verify round-trip semantics. No real MMIO
-//! addresses are touched.
+//! we cast a `Vec<u8>` pointer back to `usize`, allow the whole
+//! address space, hand it to the intrinsic, and verify round-trip
+//! semantics. No real MMIO addresses are touched.
 
 use crate::{RResult, Value};
 
+/// Every variant the intrinsics fail with.
+pub(crate) const FAILURES: &[&str] = &["PermissionDenied"];
+
 /// Type-tag for the eight intrinsic variants. Used by the unified
 /// `read` / `write` paths to centralize bounds + alignment checks.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -132,6 +139,7 @@ fn builtin_volatile_read(name: &'static str, args: &[Value], width: Width) -> RR
             width.bytes()
         ));
     }
+    crate::mmio::check_access(name, addr as u64, width.bytes() as u64)?;
     Ok(Value::Int(read_at(addr, width) as i64))
 }
 
@@ -150,6 +158,7 @@ fn builtin_volatile_write(name: &'static str, args: &[Value], width: Width) -> R
             width.bytes()
         ));
     }
+    crate::mmio::check_access(name, addr as u64, width.bytes() as u64)?;
     let value = match &args[1] {
         Value::Int(n) => *n as u64,
         other => {
@@ -206,10 +215,18 @@ pub const VOLATILE_INTRINSIC_NAMES: &[&str] = &[
 mod tests {
     use super::*;
 
+    /// Allow the whole address space. Every test allows the same
+    /// ranges, so running them in parallel can't race on the
+    /// process-wide allowlist.
+    fn allow_all() {
+        crate::mmio::set_ranges(crate::mmio::parse_ranges("0..0xffff_ffff_ffff_ffff").unwrap());
+    }
     fn buf_addr(buf: &[u8]) -> i64 {
+        allow_all();
         buf.as_ptr() as i64
     }
     fn buf_addr_mut(buf: &mut [u8]) -> i64 {
+        allow_all();
         buf.as_mut_ptr() as i64
     }
 
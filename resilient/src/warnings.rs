@@ -0,0 +1,250 @@
+//! Warning categories for `rz` and `rz check`.
+//!
+//! The checker's advisory diagnostics fall into four categories, each
+//! switched on or off independently:
+//!
+//! - `unused` (on by default) — a top-level fn that is never called,
+//!   a local `let` that is never read;
+//! - `shadowing` — a `let` that rebinds a name already in scope;
+//! - `implicit-any` — a parameter without a type annotation, or an
+//!   unannotated `let` whose initializer types as `Any`;
+//! - `implicit-coercion` (on by default) — every implicit conversion
+//!   under `--coercion permissive`.
+//!
+//! `--warn <category>` / `--no-warn <category>` toggle one (`all`
+//! names every category), and `--deny-warnings` turns every enabled
+//! warning into an error so a CI run fails on it. A denied warning is
+//! printed as `error[<category>]` and the check fails once it has
+//! reported them all. One process-wide setting chosen by the driver —
+//! the same pattern as `coercion`; the checker snapshots it at
+//! construction and tests use `TypeChecker::with_warnings`.
+
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WarningCategory {
+    Unused,
+    Shadowing,
+    ImplicitAny,
+    ImplicitCoercion,
+}
+
+impl WarningCategory {
+    pub const ALL: [WarningCategory; 4] = [
+        WarningCategory::Unused,
+        WarningCategory::Shadowing,
+        WarningCategory::ImplicitAny,
+        WarningCategory::ImplicitCoercion,
+    ];
+
+    /// The category's flag spelling, also the diagnostic code.
+    pub fn name(self) -> &'static str {
+        match self {
+            WarningCategory::Unused => "unused",
+            WarningCategory::Shadowing => "shadowing",
+            WarningCategory::ImplicitAny => "implicit-any",
+            WarningCategory::ImplicitCoercion => "implicit-coercion",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Parse a `--warn` / `--no-warn` argument: one category, or `all`.
+pub fn parse_categories(s: &str) -> Option<Vec<WarningCategory>> {
+    let s = s.trim().to_ascii_lowercase();
+    if s == "all" {
+        return Some(WarningCategory::ALL.to_vec());
+    }
+    WarningCategory::ALL
+        .into_iter()
+        .find(|c| c.name() == s)
+        .map(|c| vec![c])
+}
+
+/// Apply the warning flag at `args[*i]`, advancing `*i` past a
+/// separate value. `None` when `args[*i]` is not a warning flag;
+/// `Some(Err(..))` for a missing or unknown category.
+pub fn apply_cli_flag(args: &[String], i: &mut usize) -> Option<Result<(), String>> {
+    let arg = args[*i].as_str();
+    if arg == "--deny-warnings" {
+        set_deny(true);
+        return Some(Ok(()));
+    }
+    let (flag, on) = if arg == "--warn" || arg.starts_with("--warn=") {
+        ("--warn", true)
+    } else if arg == "--no-warn" || arg.starts_with("--no-warn=") {
+        ("--no-warn", false)
+    } else {
+        return None;
+    };
+    let value = match arg.split_once('=') {
+        Some((_, v)) => v.to_string(),
+        None => {
+            *i += 1;
+            match args.get(*i) {
+                Some(v) => v.clone(),
+                None => {
+                    return Some(Err(format!(
+                        "{} requires a category: {}",
+                        flag, CATEGORY_LIST
+                    )));
+                }
+            }
+        }
+    };
+    match parse_categories(&value) {
+        Some(categories) => {
+            for category in categories {
+                set_enabled(category, on);
+            }
+            Some(Ok(()))
+        }
+        None => Some(Err(format!(
+            "unknown warning category `{}` (expected {})",
+            value, CATEGORY_LIST
+        ))),
+    }
+}
+
+pub(crate) const CATEGORY_LIST: &str = "unused, shadowing, implicit-any, implicit-coercion, or all";
+
+/// Which categories report, and whether they fail the check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarningConfig {
+    enabled: u8,
+    pub deny: bool,
+}
+
+impl Default for WarningConfig {
+    fn default() -> Self {
+        WarningConfig {
+            enabled: DEFAULT_ENABLED,
+            deny: false,
+        }
+    }
+}
+
+impl WarningConfig {
+    pub fn enabled(self, category: WarningCategory) -> bool {
+        self.enabled & category.bit() != 0
+    }
+
+    #[allow(dead_code)] // tests build configs with it
+    pub fn set(&mut self, category: WarningCategory, on: bool) {
+        if on {
+            self.enabled |= category.bit();
+        } else {
+            self.enabled &= !category.bit();
+        }
+    }
+
+    /// Report `message` at `file:line:col` under `category`. Returns
+    /// `true` when the report was denied (printed as an error), so the
+    /// caller can fail the check once everything is reported.
+    pub(crate) fn report(
+        self,
+        category: WarningCategory,
+        file: &str,
+        line: usize,
+        col: usize,
+        message: &str,
+    ) -> bool {
+        if !self.enabled(category) {
+            return false;
+        }
+        let file = if file.is_empty() { "<input>" } else { file };
+        let severity = if self.deny { "error" } else { "warning" };
+        crate::typechecker::emit_check_diagnostic_plain(
+            format!(
+                "{}:{}:{}: {}[{}]: {}",
+                file,
+                line,
+                col,
+                severity,
+                category.name(),
+                message
+            ),
+            severity,
+            file,
+            category.name(),
+        );
+        self.deny
+    }
+}
+
+/// The error a check fails with after reporting `count` denied
+/// warnings.
+pub(crate) fn denied_error(count: usize) -> String {
+    format!(
+        "{} warning{} denied by --deny-warnings",
+        count,
+        if count == 1 { "" } else { "s" }
+    )
+}
+
+const DEFAULT_ENABLED: u8 =
+    1 << WarningCategory::Unused as u8 | 1 << WarningCategory::ImplicitCoercion as u8;
+
+static ENABLED: AtomicU8 = AtomicU8::new(DEFAULT_ENABLED);
+static DENY: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable one category process-wide. Called from the CLI
+/// dispatchers.
+pub fn set_enabled(category: WarningCategory, on: bool) {
+    if on {
+        ENABLED.fetch_or(category.bit(), Ordering::Relaxed);
+    } else {
+        ENABLED.fetch_and(!category.bit(), Ordering::Relaxed);
+    }
+}
+
+/// Turn enabled warnings into errors process-wide (`--deny-warnings`).
+pub fn set_deny(on: bool) {
+    DENY.store(on, Ordering::Relaxed);
+}
+
+/// The process-wide configuration.
+pub fn config() -> WarningConfig {
+    WarningConfig {
+        enabled: ENABLED.load(Ordering::Relaxed),
+        deny: DENY.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categories_parse_by_name_and_all() {
+        assert_eq!(
+            parse_categories("implicit-any"),
+            Some(vec![WarningCategory::ImplicitAny])
+        );
+        assert_eq!(parse_categories("ALL").map(|v| v.len()), Some(4));
+        assert_eq!(parse_categories("unsued"), None);
+    }
+
+    #[test]
+    fn defaults_enable_unused_and_coercion_only() {
+        let cfg = WarningConfig::default();
+        assert!(cfg.enabled(WarningCategory::Unused));
+        assert!(cfg.enabled(WarningCategory::ImplicitCoercion));
+        assert!(!cfg.enabled(WarningCategory::Shadowing));
+        assert!(!cfg.enabled(WarningCategory::ImplicitAny));
+        assert!(!cfg.deny);
+    }
+
+    #[test]
+    fn set_toggles_a_single_category() {
+        let mut cfg = WarningConfig::default();
+        cfg.set(WarningCategory::Shadowing, true);
+        cfg.set(WarningCategory::Unused, false);
+        assert!(cfg.enabled(WarningCategory::Shadowing));
+        assert!(!cfg.enabled(WarningCategory::Unused));
+        assert!(cfg.enabled(WarningCategory::ImplicitCoercion));
+    }
+}
@@ -9,6 +9,9 @@
 // * Uses `notify` + `notify-debouncer-mini` for cross-platform file
 //   watching with a 200 ms debounce — fast enough for interactive use,
 //   long enough to coalesce multi-write saves.
+// * Each interactive cycle ends with a one-line summary on stderr
+//   (`[watch 12:00:01] type error in 4 ms: ...`) so the outcome is
+//   visible without scrolling back through the program's output.
 // * The watcher thread delivers `DebounceEventResult` via a plain
 //   `std::sync::mpsc` channel so no extra runtime dependency is needed.
 // * CI / pipe detection: when the environment variable `CI` is set
@@ -19,17 +22,31 @@
 
 use std::path::Path;
 use std::sync::mpsc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use notify_debouncer_mini::{DebounceEventResult, new_debouncer, notify::RecursiveMode};
 
+/// How one run of the watched file ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CycleOutcome {
+    /// The program ran to completion.
+    Ok,
+    /// It ran to completion after the checker reported this type
+    /// error (the default soft type-checking mode).
+    RanWithTypeError(String),
+    /// `exit(code)` or a `main` result ended it with a nonzero status.
+    Exited(i32),
+    /// The `execute_file` error that stopped it.
+    Failed(String),
+}
+
 /// Entry point called from the library CLI dispatcher when `--watch` is passed.
 ///
 /// Runs `file_path` immediately, then re-runs it on every save until
 /// the user presses Ctrl-C.  `execute_once` is a closure that
 /// encapsulates all the flags from the outer CLI parse — it mirrors the
 /// `execute_file(…)` call that would have happened without `--watch`.
-pub fn run_watch(file_path: &Path, execute_once: impl Fn() -> bool) {
+pub fn run_watch(file_path: &Path, execute_once: impl Fn() -> CycleOutcome) {
     // Silently fall through to a single run when we detect a
     // non-interactive environment so CI pipelines that happen to pass
     // `--watch` are not surprised by a blocking loop.
@@ -38,8 +55,22 @@ pub fn run_watch(file_path: &Path, execute_once: impl Fn() -> bool) {
         return;
     }
 
+    let run_cycle = || {
+        let started = Instant::now();
+        let outcome = execute_once();
+        eprintln!(
+            "[watch {}] {}",
+            timestamp_now(),
+            cycle_summary(&outcome, started.elapsed())
+        );
+    };
+
+    // Events also fire when a run merely reads the file, so a re-run
+    // needs the contents to have changed since the last one.
+    let mut last_contents = std::fs::read(file_path).ok();
+
     // First run before entering the watch loop.
-    execute_once();
+    run_cycle();
 
     let (tx, rx) = mpsc::channel::<DebounceEventResult>();
 
@@ -99,10 +130,16 @@ pub fn run_watch(file_path: &Path, execute_once: impl Fn() -> bool) {
                             .ok()
                             .or_else(|| Some(file_path.to_path_buf()))
                 });
-                if relevant {
-                    eprintln!("--- [re-run: {}] ---", timestamp_now());
-                    execute_once();
+                if !relevant {
+                    continue;
+                }
+                let contents = std::fs::read(file_path).ok();
+                if contents == last_contents {
+                    continue;
                 }
+                last_contents = contents;
+                eprintln!("--- [re-run: {}] ---", timestamp_now());
+                run_cycle();
             }
             Err(e) => {
                 eprintln!("watch error: {:?}", e);
@@ -111,6 +148,30 @@ pub fn run_watch(file_path: &Path, execute_once: impl Fn() -> bool) {
     }
 }
 
+/// `ok in 12 ms`, or what went wrong: the kind of failure (see
+/// `failure_exit_code`) and the first line of its message.
+fn cycle_summary(outcome: &CycleOutcome, elapsed: Duration) -> String {
+    let ms = elapsed.as_millis();
+    match outcome {
+        CycleOutcome::Ok => format!("ok in {} ms", ms),
+        CycleOutcome::RanWithTypeError(err) => format!(
+            "ran with a type error in {} ms: {}",
+            ms,
+            err.lines().next().unwrap_or("")
+        ),
+        CycleOutcome::Exited(code) => format!("exited with status {} in {} ms", code, ms),
+        CycleOutcome::Failed(err) => {
+            let kind = match crate::failure_exit_code(err) {
+                2 => "parse/type error",
+                3 => "verification failure",
+                _ => "runtime error",
+            };
+            let first_line = err.lines().next().unwrap_or("");
+            format!("{} in {} ms: {}", kind, ms, first_line)
+        }
+    }
+}
+
 /// Returns `true` when running in a known non-interactive context so
 /// that `--watch` gracefully degrades to a single run instead of
 /// blocking forever.
@@ -147,6 +208,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cycle_summary_names_the_kind_of_failure() {
+        let ms = Duration::from_millis(7);
+        assert_eq!(cycle_summary(&CycleOutcome::Ok, ms), "ok in 7 ms");
+        assert_eq!(
+            cycle_summary(&CycleOutcome::RanWithTypeError("a.rz:2:1: bad".into()), ms),
+            "ran with a type error in 7 ms: a.rz:2:1: bad"
+        );
+        assert_eq!(
+            cycle_summary(&CycleOutcome::Exited(4), ms),
+            "exited with status 4 in 7 ms"
+        );
+        let parse = CycleOutcome::Failed("Failed to parse program: 1 parser error(s)".into());
+        assert_eq!(
+            cycle_summary(&parse, ms),
+            "parse/type error in 7 ms: Failed to parse program: 1 parser error(s)"
+        );
+        let runtime = CycleOutcome::Failed("a.rz:1:5: Runtime error: boom\nstack".into());
+        assert_eq!(
+            cycle_summary(&runtime, ms),
+            "runtime error in 7 ms: a.rz:1:5: Runtime error: boom"
+        );
+    }
+
     #[test]
     fn is_non_interactive_returns_bool() {
         // Just verify it can be called without panic — the return value
@@ -77,7 +77,7 @@ pub(crate) fn merge_where_clause(
         // The subject of the bound: either `TypeParam` or `TypeParam::AssocType`.
         let subject = match &parser.current_token {
             Token::Identifier(n) => {
-                let n = n.clone();
+                let n = n.to_string();
                 parser.next_token(); // skip name
                 // Associated-type projection: `T::Item`
                 if parser.current_token == Token::DoubleColon {
@@ -87,10 +87,10 @@ pub(crate) fn merge_where_clause(
                         parser.next_token(); // skip assoc name
                         full
                     } else {
-                        n
+                        n.to_string()
                     }
                 } else {
-                    n
+                    n.to_string()
                 }
             }
             // Stop on anything that can't start a where clause entry.
@@ -113,9 +113,9 @@ pub(crate) fn merge_where_clause(
         loop {
             match &parser.current_token {
                 Token::Identifier(b) => {
-                    let b = b.clone();
+                    let b = b.to_string();
                     parser.next_token(); // skip trait name
-                    bounds.push(b);
+                    bounds.push(b.to_string());
                 }
                 other => {
                     let tok = other.clone();
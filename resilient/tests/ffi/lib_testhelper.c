@@ -54,3 +54,15 @@ int64_t rt_sum_7(int64_t a, int64_t b, int64_t c, int64_t d, int64_t e, int64_t
 int64_t rt_sum_8(int64_t a, int64_t b, int64_t c, int64_t d, int64_t e, int64_t f, int64_t g, int64_t h) {
     return a + b + c + d + e + f + g + h;
 }
+
+/* `extern "C"` declarations bound through `--ffi-lib`: an int register
+ * read and the `const char*` string shapes in both directions. */
+int64_t c_read_register(int64_t addr) { return 0x1000 + addr; }
+
+int64_t rt_str_len(const char *s) {
+    int64_t n = 0;
+    while (s[n] != '\0') n++;
+    return n;
+}
+
+const char *rt_greeting(void) { return "hello from C"; }
@@ -95,7 +95,7 @@ fn anonymous_struct_binding_rejects_missing_required_field() {
         .expect("spawn resilient check");
     assert_eq!(
         check.status.code(),
-        Some(1),
+        Some(2),
         "expected missing field to fail typecheck; stdout={} stderr={}",
         String::from_utf8_lossy(&check.stdout),
         String::from_utf8_lossy(&check.stderr)
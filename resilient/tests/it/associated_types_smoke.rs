@@ -33,8 +33,8 @@ fn check_rejects_unknown_associated_type_binding() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -59,8 +59,8 @@ fn check_rejects_self_assoc_return_type_mismatch() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -106,8 +106,8 @@ fn check_rejects_generic_projection_of_undeclared_assoc_type() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -0,0 +1,66 @@
+//! `resilient::ast`: the public `Visitor` / `Folder` traversal reaches
+//! every child, and a fold prints back as runnable source.
+
+use resilient::ast::{self, Folder, Node, Visitor};
+
+const PROGRAM: &str = "fn scale(int x) -> int\n    requires x >= 0\n    ensures result >= 0\n{\n    return match x {\n        0 => 0,\n        _ => x * 3,\n    };\n}\n\nprintln(scale(2));\n";
+
+/// Every integer literal the walk reaches, in order.
+struct Integers(Vec<i64>);
+
+impl Visitor for Integers {
+    fn visit_node(&mut self, node: &Node) {
+        if let Node::IntegerLiteral { value, .. } = node {
+            self.0.push(*value);
+        }
+        ast::walk_node(self, node);
+    }
+}
+
+struct Rename;
+
+impl Folder for Rename {
+    fn fold_node(&mut self, node: Node) -> Node {
+        let mut node = ast::fold_children(self, node);
+        match &mut node {
+            Node::Identifier { name, .. } if *name == "scale" => *name = "triple".into(),
+            Node::Function { name, .. } if name == "scale" => *name = "triple".to_string(),
+            _ => {}
+        }
+        node
+    }
+}
+
+#[test]
+fn visitor_reaches_contracts_and_pattern_literals() {
+    let program = ast::parse(PROGRAM).expect("parses");
+    let mut integers = Integers(Vec::new());
+    integers.visit_node(&program);
+    // requires, ensures, the `0` pattern, its arm, the other arm, the call.
+    assert_eq!(integers.0, [0, 0, 0, 0, 3, 2]);
+}
+
+#[test]
+fn folder_rewrites_and_prints_back() {
+    let program = ast::parse(PROGRAM).expect("parses");
+    let renamed = Rename.fold_node(program);
+    let Node::Program(stmts) = &renamed else {
+        panic!("expected a program");
+    };
+    let mut children = 0;
+    ast::for_each_child(&stmts[1].node, |_| children += 1);
+    assert_eq!(children, 1, "the println statement has one expression");
+
+    let source = ast::to_source(&renamed);
+    assert!(source.contains("fn triple(int x) -> int"), "{source}");
+    assert!(source.contains("println(triple(2));"), "{source}");
+    let result = resilient::run_program(&source);
+    assert!(result.ok, "{:?}", result.errors);
+    assert_eq!(result.stdout, "6\n");
+}
+
+#[test]
+fn parse_errors_are_returned() {
+    let errors = ast::parse("fn broken( {").unwrap_err();
+    assert!(!errors.is_empty());
+}
@@ -55,7 +55,7 @@ fn assert_atomic_call_error(source: &str, expected: &str, line: usize) {
     let (path, stdout, stderr, code) = check_source("invalid", source);
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "invalid atomic call should fail; stdout={stdout} stderr={stderr}"
     );
     let combined = format!("{stdout}{stderr}");
@@ -59,7 +59,7 @@ fn atomic_on_function_is_rejected() {
     );
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "atomic function shape must fail; stdout={stdout} stderr={stderr}"
     );
     let combined = format!("{stdout}{stderr}");
@@ -78,7 +78,7 @@ fn atomic_attribute_arguments_are_rejected() {
     );
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "atomic attributes with args must fail; stdout={stdout} stderr={stderr}"
     );
     let combined = format!("{stdout}{stderr}");
@@ -97,7 +97,7 @@ fn atomic_non_integer_initializer_is_rejected() {
     );
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "atomic non-integer initializer must fail; stdout={stdout} stderr={stderr}"
     );
     let combined = format!("{stdout}{stderr}");
@@ -120,7 +120,7 @@ fn res3394_duplicate_atomic_registration_is_rejected_end_to_end() {
     );
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "duplicate #[atomic] registration must fail; stdout={stdout} stderr={stderr}"
     );
     let combined = format!("{stdout}{stderr}");
@@ -0,0 +1,112 @@
+//! `--backend=interp|vm|jit` picks the execution engine.
+
+use std::process::{Command, Output};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn run(tag: &str, args: &[&str], src: &str) -> Output {
+    let path = std::env::temp_dir().join(format!(
+        "res_backend_flag_{}_{}.rz",
+        tag,
+        std::process::id()
+    ));
+    std::fs::write(&path, src).unwrap();
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+// `sum_to` is a hot-function candidate under `--backend=jit`;
+// `report` prints, so it always stays in the interpreter.
+const SUMS: &str = "\
+fn sum_to(int n) -> int {
+    let total = 0;
+    let i = 1;
+    while i <= n {
+        total = total + i;
+        i = i + 1;
+    }
+    return total;
+}
+fn report(int n) {
+    println(sum_to(n));
+}
+report(10);
+report(1000);
+";
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+#[test]
+fn interp_and_vm_backends_agree() {
+    for (tag, args) in [
+        ("interp", &["--backend=interp"][..]),
+        ("vm_eq", &["--backend=vm"][..]),
+        ("vm_space", &["--backend", "vm"][..]),
+    ] {
+        let out = run(tag, args, SUMS);
+        assert_eq!(out.status.code(), Some(0), "{tag}: {out:?}");
+        assert!(stdout(&out).starts_with("55\n500500\n"), "{tag}: {out:?}");
+    }
+}
+
+#[test]
+fn an_unknown_backend_is_a_usage_error() {
+    let out = run("unknown", &["--backend=llvm"], SUMS);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("unknown --backend `llvm` (expected interp, vm, or jit)"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn trace_rejects_the_jit_backend() {
+    let out = run("trace", &["--trace", "--backend=jit"], SUMS);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("drop --backend=jit"));
+}
+
+#[cfg(not(feature = "jit"))]
+#[test]
+fn jit_backend_requires_the_jit_feature() {
+    let out = run("jit_missing", &["--backend=jit"], SUMS);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(stdout(&out).is_empty());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("Backend-limited: --backend=jit requires the `jit` feature"),
+        "{stderr}"
+    );
+}
+
+#[cfg(feature = "jit")]
+#[test]
+fn jit_backend_matches_the_interpreter() {
+    let out = run("jit", &["--backend=jit"], SUMS);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    assert!(stdout(&out).starts_with("55\n500500\n"), "{out:?}");
+}
+
+#[cfg(feature = "jit")]
+#[test]
+fn a_native_fault_is_reported_by_the_interpreter() {
+    let src = "fn ratio(int a, int b) -> int {\n    return a / b;\n}\nprintln(ratio(6, 3));\nprintln(ratio(1, 0));\n";
+    let interp = run("div", &[], src);
+    let jit = run("div", &["--backend=jit"], src);
+    assert_eq!(stdout(&jit), stdout(&interp));
+    assert_eq!(jit.status.code(), interp.status.code());
+    assert_eq!(
+        String::from_utf8_lossy(&jit.stderr),
+        String::from_utf8_lossy(&interp.stderr)
+    );
+}
@@ -173,3 +173,46 @@ bench \"fib\" {\n\
 
     let _ = fs::remove_dir_all(&repo);
 }
+
+#[test]
+fn bench_fn_declarations_run_with_fixed_iterations() {
+    let dir = tmp_dir("bench_fn");
+    let src_path = dir.join("bench_fn.rz");
+    fs::write(
+        &src_path,
+        "fn square(int n) -> int {\n    return n * n;\n}\n\nbench fn squares() {\n    square(12);\n}\n\nbench \"block\" {\n    square(3);\n}\n",
+    )
+    .expect("write bench source");
+
+    let output = Command::new(bin())
+        .arg("bench")
+        .arg(&src_path)
+        .args(["--iters", "7", "--runs", "2", "--filter", "squares"])
+        .output()
+        .expect("spawn resilient bench");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "stdout={stdout} stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("(warmup: 1, runs: 2)"), "{stdout}");
+    let row = stdout
+        .lines()
+        .find(|l| l.starts_with("squares "))
+        .unwrap_or_else(|| panic!("no row for `squares`; stdout={stdout}"));
+    let cols: Vec<&str> = row.split_whitespace().collect();
+    assert_eq!(cols[6], "7", "iters column: {row}");
+    assert!(!stdout.contains("block"), "{stdout}");
+
+    let output = Command::new(bin())
+        .arg("bench")
+        .arg(&src_path)
+        .args(["--iters", "0"])
+        .output()
+        .expect("spawn resilient bench");
+    assert_eq!(output.status.code(), Some(2));
+
+    let _ = fs::remove_dir_all(&dir);
+}
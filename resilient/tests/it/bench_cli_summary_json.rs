@@ -63,7 +63,7 @@ fn bench_writes_machine_readable_summary_artifact() {
     assert_eq!(json["schema_version"], 1);
     assert_eq!(json["source"], "examples/bench_simple.rz");
     assert_eq!(json["warmup_iters"], 1);
-    assert_eq!(json["run_iters"], 3);
+    assert_eq!(json["run_iters"], 10);
     assert_eq!(json["benchmark_count"], 5);
 
     let benches = json["benchmarks"].as_array().expect("benchmarks array");
@@ -71,6 +71,10 @@ fn bench_writes_machine_readable_summary_artifact() {
     let first = &benches[0];
     assert_eq!(first["name"], "empty block");
     assert!(first.get("mean_ns").is_some(), "missing mean_ns: {first:?}");
+    assert!(
+        first["iters_per_sample"].as_u64().is_some_and(|n| n >= 1),
+        "missing iters_per_sample: {first:?}"
+    );
     assert!(
         first.get("baseline_mean_ns").is_some(),
         "missing baseline_mean_ns field: {first:?}"
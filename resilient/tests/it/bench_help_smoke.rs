@@ -22,7 +22,8 @@ fn assert_focused_bench_help(args: &[&str]) {
     let stdout = String::from_utf8_lossy(&output.stdout);
     for expected in [
         "Usage: rz bench <file> [--baseline <git-ref>] [--summary-json <path>] [--warmup N] [--runs N]",
-        "Discover and run `bench \"name\" { ... }` blocks.",
+        "Discover and run `bench \"name\" { ... }` blocks and `bench fn name() { ... }`",
+        "--iters <N>        Runs of the body per sample",
         "--summary-json <path>  Write a stable JSON summary artifact",
         "--filter <substr>  Only run benchmarks whose names contain <substr>",
     ] {
@@ -1164,3 +1164,30 @@ main();
         result.stdout
     );
 }
+
+#[test]
+fn test_array_sort_nan_is_caught_as_invalid_argument() {
+    let code = r#"
+let xs = [2.0, unwrap(parse_float("NaN")), 1.0];
+try {
+    println(sort(xs));
+} catch InvalidArgument {
+    println("unordered");
+}
+"#;
+    let result = resilient::run_program(code);
+    assert!(result.ok, "Failed: {:?}", result.errors);
+    assert_eq!(result.stdout, "unordered\n");
+
+    let result = resilient::run_program("array_sort_desc([unwrap(parse_float(\"NaN\"))]);");
+    assert!(!result.ok);
+    assert!(
+        result
+            .errors
+            .iter()
+            .any(|e| e
+                .contains("array_sort_desc: InvalidArgument: NaN at index 0 cannot be ordered")),
+        "Got: {:?}",
+        result.errors
+    );
+}
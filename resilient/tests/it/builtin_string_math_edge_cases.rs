@@ -745,9 +745,23 @@ fn math_sqrt_imperfect() {
 }
 
 #[test]
-fn math_sqrt_negative_returns_nan() {
-    let code = r#"print(is_nan(sqrt(-1.0)))"#;
-    assert_eq!(run_ok(code), "true");
+fn math_sqrt_negative_is_domain_error() {
+    assert!(run_err(r#"print(sqrt(-1.0))"#));
+    assert!(run_err(r#"print(sqrt(-4))"#));
+    assert_eq!(run_ok(r#"print(is_nan(sqrt(0.0 / 0.0)))"#), "true");
+}
+
+#[test]
+fn math_sqrt_domain_error_is_caught_by_variant() {
+    let code = r#"
+try {
+    let r = sqrt(-4.0);
+    print(r);
+} catch DomainError {
+    print("domain");
+}
+"#;
+    assert_eq!(run_ok(code), "domain");
 }
 
 #[test]
@@ -21,11 +21,11 @@ fn assert_focused_check_help(args: &[&str]) {
     );
     let stdout = String::from_utf8_lossy(&output.stdout);
     for expected in [
-        "rz check — type-check a file without running it",
-        "USAGE:\n    rz check <file> [FLAGS]",
-        "FLAGS:\n    -q, --quiet",
+        "Type-check files without running them",
+        "Usage: rz check [OPTIONS] [FILE|DIR]...",
+        "Options:\n  -q, --quiet",
         "--emit-diagnostics-json",
-        "--z3-theory MODE        Backend-limited; requires --features z3",
+        "--z3-theory <MODE>         SMT theory: bv, lia, or auto (requires --features z3)",
         "Run `rz --help` for global flags and other subcommands.",
     ] {
         assert!(
@@ -48,8 +48,3 @@ fn check_long_help_is_focused() {
 fn check_short_help_is_focused() {
     assert_focused_check_help(&["check", "-h"]);
 }
-
-#[test]
-fn check_help_word_is_focused() {
-    assert_focused_check_help(&["check", "help"]);
-}
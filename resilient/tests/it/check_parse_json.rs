@@ -29,8 +29,8 @@ fn check_emit_diagnostics_json_reports_parse_errors_as_json_only() {
 
     assert_eq!(
         out.status.code(),
-        Some(1),
-        "expected parse failure exit 1, got {:?}\nstdout: {}\nstderr: {}",
+        Some(2),
+        "expected parse failure exit 2, got {:?}\nstdout: {}\nstderr: {}",
         out.status,
         String::from_utf8_lossy(&out.stdout),
         String::from_utf8_lossy(&out.stderr),
@@ -2,7 +2,7 @@
 //!
 //! Verifies that `check` parses + type-checks without running:
 //! - exits 0 for valid source.
-//! - exits 1 for a file with a type error.
+//! - exits 2 for a file with a type error.
 //! - exits 2 when no path is given.
 //! - respects `--quiet` / `-q` (suppresses all output but keeps exit code).
 
@@ -47,7 +47,7 @@ fn check_valid_file_exits_zero() {
 }
 
 #[test]
-fn check_type_error_exits_one() {
+fn check_type_error_exits_two() {
     let dir = tmp_dir("type_err");
     let src_path = dir.join("bad.rz");
     // Assign a string to an int — type mismatch.
@@ -64,8 +64,8 @@ fn check_type_error_exits_one() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1 for type error; stderr={}",
+        Some(2),
+        "expected exit 2 for type error; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
 
@@ -132,8 +132,8 @@ fn check_rejects_nonexhaustive_struct_match() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1 for non-exhaustive struct match; stderr={}",
+        Some(2),
+        "expected exit 2 for non-exhaustive struct match; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -539,7 +539,7 @@ fn check_valid_program_exits_zero() {
 }
 
 #[test]
-fn check_type_error_exits_one() {
+fn check_type_error_exits_two() {
     let src = tmp_file(
         "check_type_error",
         "fn main(int _x) {\n    let y: int = \"bad\";\n    return 0;\n}\nmain(0);\n",
@@ -549,19 +549,19 @@ fn check_type_error_exits_one() {
         .arg(&src)
         .output()
         .expect("spawn check");
-    assert_eq!(output.status.code(), Some(1), "type error should exit 1");
+    assert_eq!(output.status.code(), Some(2), "type error should exit 2");
     let _ = fs::remove_file(&src);
 }
 
 #[test]
-fn check_parse_error_exits_one() {
+fn check_parse_error_exits_two() {
     let src = tmp_file("check_parse_error", "fn main() { let x = ; }\n");
     let output = Command::new(bin())
         .arg("check")
         .arg(&src)
         .output()
         .expect("spawn check");
-    assert_eq!(output.status.code(), Some(1), "parse error should exit 1");
+    assert_eq!(output.status.code(), Some(2), "parse error should exit 2");
     let _ = fs::remove_file(&src);
 }
 
@@ -914,7 +914,7 @@ fn check_stderr_contains_diagnostics() {
         .output()
         .expect("spawn check");
 
-    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(output.status.code(), Some(2));
     let stderr = String::from_utf8_lossy(&output.stderr);
     // Type error diagnostics should appear on stderr.
     assert!(
@@ -0,0 +1,102 @@
+//! Integration tests for `--coercion <policy>`: the checker, the
+//! tree-walker, and the VM agree on which implicit conversions run,
+//! and the permissive policy warns at each one.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(tag: &str, body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!(
+        "res_coercion_{}_{}_{}.rz",
+        tag,
+        std::process::id(),
+        n
+    ));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(tag: &str, body: &str, args: &[&str]) -> Output {
+    let path = tmp_file(tag, body);
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+const MIXED: &str = "let x = 1 + 2.5;\n\
+println(x);\n\
+println(3 < 3.5);\n\
+println(2 == 2.0);\n\
+println(\"n=\" + 3);\n";
+
+#[test]
+fn permissive_widens_and_warns_on_both_backends() {
+    for backend in [&[][..], &["--vm"][..]] {
+        let mut args = backend.to_vec();
+        args.extend(["--coercion", "permissive"]);
+        let out = run("permissive", MIXED, &args);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(out.status.success(), "{backend:?} stderr: {stderr}");
+        assert!(
+            stdout.contains("3.5\ntrue\ntrue\nn=3\n"),
+            "{backend:?} stdout: {stdout}"
+        );
+        assert_eq!(
+            stderr.matches("warning[implicit-coercion]").count(),
+            4,
+            "{backend:?} stderr: {stderr}"
+        );
+    }
+}
+
+#[test]
+fn strict_rejects_concat_in_check_and_at_runtime() {
+    let src = "println(\"n=\" + 3);\n";
+    let out = run("strict_check", src, &["check", "--coercion=strict"]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("strict coercion policy"),
+        "stderr: {stderr}"
+    );
+
+    for backend in [&[][..], &["--vm"][..]] {
+        let mut args = backend.to_vec();
+        args.extend(["--coercion", "strict", "--no-typecheck"]);
+        let out = run("strict_run", src, &args);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(!out.status.success(), "{backend:?} stdout: {stdout}");
+        assert!(!stdout.contains("n=3"), "{backend:?} stdout: {stdout}");
+    }
+}
+
+#[test]
+fn standard_is_the_default() {
+    let out = run("standard", "println(\"n=\" + 3);\n", &["check"]);
+    assert!(out.status.success());
+    let out = run("standard_mixed", "let x = 1 + 2.5;\n", &["check"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn unknown_policy_is_a_usage_error() {
+    let out = run("bogus", "println(1);\n", &["check", "--coercion", "loose"]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("unknown --coercion policy"),
+        "stderr: {stderr}"
+    );
+}
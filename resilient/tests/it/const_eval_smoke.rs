@@ -0,0 +1,85 @@
+//! Integration tests for compile-time constant evaluation: constants
+//! resolve in any order on both backends, enum discriminants read back
+//! through `as int`, and a cycle is rejected by `rz check` with its
+//! chain.
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(tag: &str, body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!(
+        "res_const_eval_{}_{}_{}.rz",
+        tag,
+        std::process::id(),
+        n
+    ));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+const CONSTS: &str = "const WORDS = BYTES / 8;\n\
+const BYTES = 4096;\n\
+enum Level { Off, Low = 10, Mid, High = Level::Mid as int * 2 }\n\
+const HIGH = Level::High as int;\n\
+static_assert(WORDS * 8 == BYTES, \"whole words\");\n\
+fn first([int; WORDS] words) -> int { return words[0]; }\n\
+println(WORDS);\n\
+println(HIGH);\n";
+
+#[test]
+fn constants_resolve_in_any_order_on_both_backends() {
+    for backend in [None, Some("--vm")] {
+        let path = tmp_file("order", CONSTS);
+        let mut cmd = Command::new(bin());
+        if let Some(flag) = backend {
+            cmd.arg(flag);
+        }
+        let out = cmd.arg(&path).output().expect("spawn rz");
+        let _ = std::fs::remove_file(&path);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(out.status.success(), "{backend:?} stderr: {stderr}");
+        assert!(stdout.contains("512\n22\n"), "{backend:?} stdout: {stdout}");
+    }
+}
+
+#[test]
+fn enum_variant_casts_to_its_discriminant() {
+    let path = tmp_file(
+        "cast",
+        "enum Level { Off, Low = 10, Mid }\nprintln(Level::Off as int);\nprintln(Level::Mid as int);\n",
+    );
+    let out = Command::new(bin()).arg(&path).output().expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(stdout.contains("0\n11\n"), "stdout: {stdout}");
+}
+
+#[test]
+fn check_rejects_a_constant_cycle() {
+    let path = tmp_file("cycle", "const A = B + 1;\nconst B = A;\n");
+    let out = Command::new(bin())
+        .arg("check")
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!out.status.success(), "a cycle must fail the check");
+    assert!(
+        stderr.contains("circular constant definition: 'A' -> 'B' -> 'A'"),
+        "stderr: {stderr}"
+    );
+}
@@ -0,0 +1,183 @@
+//! `rz debug <file>` runs the program under the console debugger,
+//! reading commands from stdin; `rz run --step <file>` walks through
+//! it one statement per Enter.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn debug(name: &str, args: &[&str], commands: &str) -> Output {
+    let path = std::env::temp_dir().join(format!("res_debug_{}_{}.rz", std::process::id(), name));
+    std::fs::write(&path, PROGRAM).unwrap();
+    let mut child = Command::new(bin())
+        .arg("debug")
+        .args(args)
+        .arg("--no-typecheck")
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn rz debug");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(commands.as_bytes())
+        .unwrap();
+    let out = child.wait_with_output().expect("wait for rz debug");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+fn stops(out: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&out.stderr)
+        .lines()
+        .filter_map(|l| l.split_once("[debug] stopped ").map(|(_, s)| s))
+        .map(|s| {
+            // Drop the temp file path, keep the reason, line and source.
+            let (reason, rest) = s.split_once(" at ").unwrap();
+            let line = rest.rsplit_once(".rz:").unwrap().1;
+            format!("{reason} at {line}")
+        })
+        .collect()
+}
+
+const PROGRAM: &str = "fn square(int n) -> int {\n    let r = n * n;\n    return r;\n}\n\nfn flaky(int x) -> int {\n    live retries(2) {\n        let d = live_retries();\n        return x / d;\n    }\n}\n\nlet a = 3;\nlet b = square(a);\nprintln(b);\nprintln(flaky(10));\n";
+
+#[test]
+fn step_enters_calls_and_shows_the_scope_chain() {
+    let out = debug("step", &[], "s\ns\n\np n\nvars\nbt\nc\n");
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        stops(&out),
+        [
+            "(step) at 13: let a = 3;",
+            "(step) at 14: let b = square(a);",
+            "(step) at 2: let r = n * n;",
+            "(step) at 3: return r;",
+        ]
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("(rdb) n = 3\n"), "print n: {stderr}");
+    assert!(
+        stderr.contains("scope 0:\n  r = 9\nscope 1:\n  n = 3\nglobals:\n  a = 3\n"),
+        "vars: {stderr}"
+    );
+    assert!(stderr.contains("#0 square at "), "backtrace: {stderr}");
+    assert!(stderr.contains("#1 <main> at "), "backtrace: {stderr}");
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        "9\n10\nProgram executed successfully\n"
+    );
+}
+
+#[test]
+fn breakpoints_and_next_stay_in_the_function() {
+    let out = debug("break", &["--break", "2"], "c\nn\nn\nn\nc\n");
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        stops(&out),
+        [
+            "(step) at 13: let a = 3;",
+            "(breakpoint) at 2: let r = n * n;",
+            "(step) at 3: return r;",
+            "(step) at 15: println(b);",
+            "(step) at 16: println(flaky(10));",
+        ]
+    );
+}
+
+#[test]
+fn live_stops_on_each_retry_with_the_error() {
+    let out = debug("live", &["--live"], "c\np x\nc\n");
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        stops(&out),
+        [
+            "(step) at 13: let a = 3;",
+            "(live retry 1/2 after: Division by zero) at 7: live retries(2) {",
+        ]
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("(rdb) x = 10\n"));
+}
+
+#[test]
+fn debug_needs_a_file_and_the_interpreter() {
+    let out = Command::new(bin()).arg("debug").output().unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    let out = debug("vm", &["--vm"], "");
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("tree-walking interpreter"));
+}
+
+/// `rz run --step` on `PROGRAM`, answering the pauses with `input`;
+/// returns the exit code and the step reports, with the temp path
+/// shortened to `prog.rz`.
+fn run_step(name: &str, input: &str) -> (Option<i32>, String) {
+    let path = std::env::temp_dir().join(format!("res_step_{}_{}.rz", std::process::id(), name));
+    std::fs::write(&path, PROGRAM).unwrap();
+    let mut child = Command::new(bin())
+        .args(["run", "--step", "--no-typecheck"])
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn rz run --step");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let out = child.wait_with_output().expect("wait for rz run --step");
+    let _ = std::fs::remove_file(&path);
+    let stderr = String::from_utf8_lossy(&out.stderr)
+        .replace("(step) ", "")
+        .replace(&path.display().to_string(), "prog.rz");
+    let steps: Vec<&str> = stderr
+        .lines()
+        .filter(|l| l.starts_with("[step ") || l.starts_with("  "))
+        .collect();
+    (out.status.code(), steps.join("\n"))
+}
+
+#[test]
+fn step_shows_each_statement_what_changed_and_the_live_retries() {
+    let (code, steps) = run_step("all", "\n\n\n\n\n");
+    assert_eq!(code, Some(0));
+    assert!(
+        steps.starts_with(
+            "[step 1] prog.rz:13: let a = 3;\n\
+             [step 2] prog.rz:14: let b = square(a);\n  + a = 3\n\
+             [step 3] prog.rz:2: let r = n * n;\n  + n = 3\n"
+        ),
+        "{steps}"
+    );
+    assert!(
+        steps.contains("[step 5] prog.rz:15: println(b);\n  + b = 9\n  - n\n  - r\n"),
+        "{steps}"
+    );
+    assert!(
+        steps.contains("  live: block at line 7, attempt 1/2 failed: Division by zero"),
+        "{steps}"
+    );
+}
+
+#[test]
+fn step_runs_on_at_c_and_stops_at_q() {
+    let (code, steps) = run_step("c", "c\n");
+    assert_eq!(code, Some(0));
+    assert!(steps.contains("[step 2] prog.rz:14"), "{steps}");
+
+    let (code, steps) = run_step("q", "\nq\n");
+    assert_eq!(code, Some(0));
+    assert!(
+        steps.ends_with("[step 2] prog.rz:14: let b = square(a);\n  + a = 3"),
+        "{steps}"
+    );
+}
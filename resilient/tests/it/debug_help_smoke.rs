@@ -22,10 +22,11 @@ fn assert_focused_debug_help(args: &[&str]) {
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     for expected in [
-        "rz debug — start the Debug Adapter Protocol server",
-        "USAGE:\n    rz debug <file>",
-        "Starts a DAP server on stdin/stdout for an editor or debugger client.",
-        "The file argument labels the session; the DAP launch request supplies the program path.",
+        "rz debug — run a file under the console debugger",
+        "USAGE:\n    rz debug <file> [--break [FILE:]LINE]... [--live]\n    rz debug --dap [<file>]",
+        "Commands are read from stdin at the `(rdb)` prompt:",
+        "--live also stops on each retry of a `live` block, with the error that caused it.",
+        "the DAP launch request supplies the program path.",
         "rz debug examples/hello.rz",
         "For direct adapter launches, clients may use `rz --dap`.",
         "Run `rz --help` for global flags and other subcommands.",
@@ -0,0 +1,66 @@
+//! `rz doc` writes API pages per module with doc comments, effects and
+//! contracts.
+
+use std::fs;
+use std::process::Command;
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+const GEOMETRY: &str = "//! Geometry helpers.\n\n/// Clamp `x` into `[lo, hi]`.\npure fn clamp(int x, int lo, int hi) -> int\n    requires lo <= hi\n{\n    if x < lo { return lo; }\n    if x > hi { return hi; }\n    return x;\n}\n\n/// A point on the grid.\nstruct Point {\n    int x,\n    int y,\n}\n";
+
+#[test]
+fn doc_writes_a_page_per_module_and_an_index() {
+    let dir = std::env::temp_dir().join(format!("res_doc_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src/util")).unwrap();
+    fs::write(dir.join("src/util/geometry.rz"), GEOMETRY).unwrap();
+    fs::write(dir.join("src/main.rz"), "fn main() {\n    println(1);\n}\n").unwrap();
+    let out_dir = dir.join("api");
+
+    for (format, ext) in [("html", "html"), ("markdown", "md")] {
+        let out = Command::new(bin())
+            .arg("doc")
+            .arg(dir.join("src"))
+            .args(["--format", format, "--out"])
+            .arg(&out_dir)
+            .output()
+            .expect("spawn rz doc");
+        assert_eq!(
+            out.status.code(),
+            Some(0),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        assert!(String::from_utf8_lossy(&out.stdout).contains("Documented 2 modules"));
+        let index = fs::read_to_string(out_dir.join(format!("index.{ext}"))).unwrap();
+        assert!(index.contains("util/geometry") && index.contains("Geometry helpers."));
+        assert!(out_dir.join(format!("main.{ext}")).exists());
+    }
+
+    let page = fs::read_to_string(out_dir.join("util/geometry.md")).unwrap();
+    for expected in [
+        "# Module `util/geometry`",
+        "[Index](../index.md)",
+        "```rust\npure fn clamp(int x, int lo, int hi) -> int\n```",
+        "Clamp `x` into `[lo, hi]`.",
+        "- **Effects:** `pure`",
+        "- **Requires:** `lo <= hi`",
+        "## struct `Point`",
+        "A point on the grid.",
+        "- **Field:** `int x`",
+    ] {
+        assert!(page.contains(expected), "missing {expected:?} in:\n{page}");
+    }
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn doc_rejects_an_unknown_format() {
+    let out = Command::new(bin())
+        .args(["doc", "--format", "pdf"])
+        .output()
+        .expect("spawn rz doc");
+    assert_eq!(out.status.code(), Some(2));
+}
@@ -5,7 +5,7 @@ fn lsp_docs_describe_current_diagnostic_ranges() {
     let docs = include_str!("../../../docs/lsp.md");
 
     for expected in [
-        "Every `did_open` or `did_change` event re-runs the full parse +",
+        "Every `did_open` or `did_change` event re-runs the parse +",
         "reported column for parser and typechecker errors",
         "lint diagnostics use",
         "the source positions recorded by the lint pass",
@@ -7,12 +7,13 @@ fn tooling_docs_describe_current_debug_and_test_subcommands() {
     for expected in [
         "## Debugger",
         "### `rz debug <file>`",
-        "Starts the Debug Adapter Protocol (DAP) server on stdin/stdout",
+        "Runs the file under a console debugger.",
+        "instead starts the Debug Adapter Protocol\n(DAP) server on stdin/stdout",
         "rz debug examples/hello.rz",
         "For direct adapter launches, clients may also use `rz --dap`.",
         "## Test framework",
         "### `rz test [<file|dir>] [--filter <substring>]`",
-        "Discovers and runs `fn test_*()` functions in `.rz` files.",
+        "Discovers and runs tests in `.rz` files: top-level `test fn`\ndeclarations and zero-parameter `fn test_*()` functions.",
         "rz test resilient/examples/test_runner_demo.rz",
         "Parallel execution and JUnit output are still future",
     ] {
@@ -5,10 +5,9 @@ fn tooling_docs_describe_formatter_comment_preservation_boundary() {
     let docs = include_str!("../../../docs/tooling.md");
 
     for expected in [
-        "The formatter is a structural round-trip,\nand the parser discards comments.",
-        "Comments are not preserved today.",
-        "Run `fmt` only on code you're willing to re-attach comments to by",
-        "hand; comment-preserving formatting is not available yet.",
+        "The parser discards comments, so the formatter reads\nthem from the source separately",
+        "A\ncomment on a line of its own stays above the statement that follows\nit",
+        "No comment is\never dropped, and formatting the output again changes nothing.",
     ] {
         assert!(
             docs.contains(expected),
@@ -16,6 +15,10 @@ fn tooling_docs_describe_formatter_comment_preservation_boundary() {
         );
     }
 
+    assert!(
+        !docs.contains("Comments are not preserved today."),
+        "tooling docs should not describe comments as dropped"
+    );
     assert!(
         !docs.contains("Comment-aware formatting is the next planned formatter\nimprovement."),
         "tooling docs should not use roadmap-ish comment-aware formatter wording"
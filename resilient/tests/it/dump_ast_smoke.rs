@@ -0,0 +1,80 @@
+//! Smoke tests for `--dump-ast[=json]`, both as a driver flag and on
+//! `rz check`.
+
+use std::process::{Command, Output};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn rz(args: &[&str]) -> Output {
+    Command::new(bin())
+        .args(args)
+        .output()
+        .expect("spawn rz --dump-ast")
+}
+
+#[test]
+fn dump_ast_prints_the_indented_tree() {
+    for args in [
+        &["--dump-ast", "examples/hello.rz"][..],
+        &["check", "--dump-ast", "examples/hello.rz"][..],
+    ] {
+        let output = rz(args);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(
+            output.status.code(),
+            Some(0),
+            "{args:?}: stderr={}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(stdout.starts_with("Program(\n"), "{args:?}: {stdout}");
+        assert!(
+            stdout.contains("        Function {\n"),
+            "{args:?}: {stdout}"
+        );
+        assert!(!stdout.contains("span:"), "{args:?}: {stdout}");
+    }
+}
+
+#[test]
+fn dump_ast_json_matches_the_dump_ast_json_flag() {
+    let reference = rz(&["--dump-ast-json", "examples/hello.rz"]);
+    for args in [
+        &["--dump-ast=json", "examples/hello.rz"][..],
+        &["check", "--dump-ast=json", "examples/hello.rz"][..],
+    ] {
+        let output = rz(args);
+        assert_eq!(output.status.code(), Some(0), "{args:?}");
+        assert_eq!(output.stdout, reference.stdout, "{args:?}");
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("--dump-ast=json prints JSON");
+        assert_eq!(json["type"], "Program");
+    }
+}
+
+#[test]
+fn check_dump_ast_reports_parse_errors() {
+    let path = std::env::temp_dir().join(format!("res_dump_ast_{}.rz", std::process::id()));
+    std::fs::write(&path, "let = ;\n").unwrap();
+    let output = rz(&["check", "--dump-ast", path.to_str().unwrap()]);
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Expected identifier after 'let'"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn dump_ast_rejects_unknown_formats() {
+    let output = rz(&["--dump-ast=xml", "examples/hello.rz"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--dump-ast must be `tree` or `json`"),
+        "{stderr}"
+    );
+}
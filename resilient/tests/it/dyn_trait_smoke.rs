@@ -30,8 +30,8 @@ fn check_rejects_unknown_trait_in_dyn_annotation() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -49,8 +49,8 @@ fn check_rejects_dyn_trait_coercion_when_struct_does_not_implement() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -73,8 +73,8 @@ fn check_rejects_dyn_trait_with_no_self_method() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -95,8 +95,8 @@ fn check_rejects_dyn_trait_with_self_returning_method() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -133,8 +133,8 @@ fn check_rejects_unknown_trait_in_array_dyn_annotation() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -152,8 +152,8 @@ fn check_rejects_not_object_safe_trait_in_array_dyn() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -174,8 +174,8 @@ fn check_rejects_array_dyn_coercion_when_element_does_not_implement() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -196,8 +196,8 @@ fn check_rejects_struct_field_coercion_when_value_does_not_implement() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -218,8 +218,8 @@ fn check_rejects_return_type_coercion_when_value_does_not_implement() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -255,8 +255,8 @@ fn check_rejects_dyn_trait_coercion_through_local_alias() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -274,8 +274,8 @@ fn check_rejects_dyn_trait_coercion_through_fn_return_type() {
         .expect("spawn resilient check");
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "expected exit 1; stderr={}",
+        Some(2),
+        "expected exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
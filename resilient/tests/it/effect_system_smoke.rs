@@ -7,7 +7,7 @@
 //! - A program where a `pure fn` only calls other `pure fn`s and
 //!   pure builtins — `check` must exit 0.
 //! - A program where a `pure fn` calls an `io fn` — `check` must
-//!   exit 1 and the diagnostic must read
+//!   exit 2 and the diagnostic must read
 //!   `cannot call io function ... from pure context`.
 //!
 //! These tests deliberately use the `resilient check` binary so
@@ -93,8 +93,8 @@ fn pure_calling_io_is_rejected() {
         .expect("spawn resilient check");
     assert_eq!(
         out.status.code(),
-        Some(1),
-        "expected exit 1 for pure→io violation; stderr={}",
+        Some(2),
+        "expected exit 2 for pure→io violation; stderr={}",
         String::from_utf8_lossy(&out.stderr)
     );
 
@@ -132,8 +132,8 @@ fn pure_calling_unannotated_is_rejected() {
         .expect("spawn resilient check");
     assert_eq!(
         out.status.code(),
-        Some(1),
-        "expected exit 1 — unannotated fns default to io; stderr={}",
+        Some(2),
+        "expected exit 2 — unannotated fns default to io; stderr={}",
         String::from_utf8_lossy(&out.stderr)
     );
 
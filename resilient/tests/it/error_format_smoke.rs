@@ -0,0 +1,109 @@
+//! `--error-format=json|sarif`: structured diagnostics on stderr.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(tag: &str, body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!(
+        "error_format_{}_{}_{}.rz",
+        tag,
+        std::process::id(),
+        n
+    ));
+    std::fs::write(&path, body).expect("write scratch");
+    path
+}
+
+fn run(args: &[&str], path: &PathBuf) -> Output {
+    Command::new(bin())
+        .args(args)
+        .arg(path)
+        .output()
+        .expect("spawn rz")
+}
+
+/// Every stderr line is one JSON diagnostic.
+fn json_lines(out: &Output) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(&out.stderr)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap_or_else(|e| panic!("not JSON ({e}): {l}")))
+        .collect()
+}
+
+#[test]
+fn json_reports_runtime_error_with_span_and_fix() {
+    let path = tmp_file(
+        "runtime",
+        "fn main(int _d) {\n    let x = array_revrese([1, 2, 3]);\n    return 0;\n}\nmain(0);\n",
+    );
+    let out = run(&["--error-format=json", "--no-typecheck"], &path);
+    assert_eq!(out.status.code(), Some(1));
+    let diags = json_lines(&out);
+    assert_eq!(diags.len(), 1, "{diags:?}");
+    let d = &diags[0];
+    assert_eq!(d["phase"], "runtime");
+    assert_eq!(d["severity"], "error");
+    assert_eq!(d["file"], path.to_string_lossy().as_ref());
+    assert!(d["span"]["line"].as_u64().is_some(), "{d}");
+    assert_eq!(d["fixes"][0]["replacement"], "array_reverse");
+}
+
+#[test]
+fn json_reports_parse_and_type_errors_by_phase() {
+    let parse = tmp_file("parse", "fn main(int _d) { let x 1; return 0; } main(0);\n");
+    let out = run(&["--error-format", "json"], &parse);
+    assert_eq!(out.status.code(), Some(2));
+    let diags = json_lines(&out);
+    assert_eq!(diags[0]["phase"], "parse");
+    assert_eq!(diags[0]["span"]["column"], 25);
+    assert_eq!(diags[0]["span"]["end_column"], 26);
+
+    let typed = tmp_file(
+        "type",
+        "fn main(int _d) {\n    let bad: int = \"hi\";\n    return 0;\n}\nmain(0);\n",
+    );
+    let out = run(&["check", "--error-format=json"], &typed);
+    assert_eq!(out.status.code(), Some(2));
+    let diags = json_lines(&out);
+    assert_eq!(diags.len(), 1, "{diags:?}");
+    assert_eq!(diags[0]["phase"], "typecheck");
+    assert_eq!(diags[0]["span"]["line"], 2);
+}
+
+#[test]
+fn sarif_prints_one_log_for_the_run() {
+    let path = tmp_file("sarif", "fn main(int _d) { let x 1; return 0; } main(0);\n");
+    let out = run(&["--error-format=sarif"], &path);
+    assert_eq!(out.status.code(), Some(2));
+    let log: serde_json::Value =
+        serde_json::from_slice(&out.stderr).expect("stderr is one SARIF document");
+    assert_eq!(log["version"], "2.1.0");
+    let results = log["runs"][0]["results"].as_array().expect("results");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+        1
+    );
+
+    let clean = tmp_file("clean", "println(1);\n");
+    let out = run(&["--error-format=sarif", "--seed", "1"], &clean);
+    assert_eq!(out.status.code(), Some(0));
+    let log: serde_json::Value = serde_json::from_slice(&out.stderr).expect("SARIF");
+    assert_eq!(log["runs"][0]["results"], serde_json::json!([]));
+}
+
+#[test]
+fn unknown_error_format_is_a_usage_error() {
+    let path = tmp_file("unknown", "println(1);\n");
+    let out = run(&["--error-format=xml"], &path);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("unknown --error-format `xml`"));
+}
@@ -0,0 +1,194 @@
+//! Integration tests for process exit codes: `exit(code)` unwinds
+//! through `defer`s and becomes the status of `rz`, the result of a
+//! `main` declared `-> int` does the same, runtime errors exit 1,
+//! parse and type errors exit 2, and verification failures exit 3.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("res_exit_code_{}_{}.rz", std::process::id(), n));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(body: &str, args: &[&str]) -> Output {
+    let path = tmp_file(body);
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+const EXIT_THROUGH_DEFERS: &str = "fn cleanup(string who) {\n\
+    println(\"cleanup \" + who);\n\
+}\n\
+fn inner() {\n\
+    defer cleanup(\"inner\");\n\
+    exit(3);\n\
+    println(\"unreachable\");\n\
+}\n\
+fn main(int _d) {\n\
+    defer cleanup(\"main\");\n\
+    inner();\n\
+    return 0;\n\
+}\n\
+main(0);\n";
+
+#[test]
+fn exit_runs_defers_and_sets_the_status_on_both_runtimes() {
+    for args in [&[][..], &["--vm"][..]] {
+        let out = run(EXIT_THROUGH_DEFERS, args);
+        assert_eq!(out.status.code(), Some(3), "{:?}", args);
+        let stdout = stdout(&out);
+        assert!(
+            stdout.contains("cleanup inner\ncleanup main\n"),
+            "{:?} stdout: {}",
+            args,
+            stdout
+        );
+        assert!(!stdout.contains("unreachable"), "{:?}", args);
+        assert!(
+            !stdout.contains("Program executed successfully"),
+            "{:?}",
+            args
+        );
+    }
+}
+
+#[test]
+fn exit_is_not_caught_or_retried() {
+    let src = "try {\n\
+    exit(5);\n\
+} catch NotFound {\n\
+    println(\"caught\");\n\
+}\n";
+    let out = run(src, &[]);
+    assert_eq!(out.status.code(), Some(5));
+    assert!(!stdout(&out).contains("caught"));
+
+    let src = "live {\n\
+    println(\"attempt\");\n\
+    exit(6);\n\
+}\n";
+    let out = run(src, &[]);
+    assert_eq!(out.status.code(), Some(6));
+    assert_eq!(stdout(&out).matches("attempt").count(), 1);
+}
+
+#[test]
+fn error_text_never_reads_as_an_exit() {
+    for src in [
+        "assert(false, \"exit(0)\");\n",
+        "let m = {\"a\" -> 1};\nprintln(m[\"exit(0)\"]);\n",
+    ] {
+        let out = run(src, &[]);
+        assert_eq!(out.status.code(), Some(1), "{src}");
+        assert!(
+            String::from_utf8_lossy(&out.stderr).contains("Error"),
+            "{src}"
+        );
+    }
+}
+
+#[test]
+fn exit_codes_outside_a_byte_are_invalid_arguments() {
+    for code in ["256", "0 - 1"] {
+        let out = run(&format!("exit({code});\n"), &[]);
+        assert_eq!(out.status.code(), Some(1), "{code}");
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(stderr.contains("exit: InvalidArgument"), "{stderr}");
+    }
+    let src = "try {\n\
+    exit(300);\n\
+} catch InvalidArgument {\n\
+    println(\"bad code\");\n\
+}\n";
+    let out = run(src, &[]);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(stdout(&out).contains("bad code"));
+
+    let out = run("fn main(int _d) -> int { return 256; }\nmain(0);\n", &[]);
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("main: InvalidArgument"), "{stderr}");
+}
+
+#[test]
+fn main_result_becomes_the_status_on_both_runtimes() {
+    let src = "fn main(int _d) -> int {\n\
+    println(\"hi\");\n\
+    return 4;\n\
+}\n\
+main(0);\n";
+    for args in [&[][..], &["--vm"][..]] {
+        let out = run(src, args);
+        assert_eq!(out.status.code(), Some(4), "{:?}", args);
+        assert!(stdout(&out).contains("hi"), "{:?}", args);
+    }
+    // Without `-> int` the result is just a value; the run succeeds.
+    let out = run("fn main(int _d) { return 4; }\nmain(0);\n", &[]);
+    assert_eq!(out.status.code(), Some(0));
+}
+
+#[test]
+fn zero_and_errors_keep_their_statuses() {
+    let out = run("fn main(int _d) { return 0; }\nmain(0);\n", &[]);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(stdout(&out).contains("Program executed successfully"));
+
+    let out = run("let x = 1 / 0;\n", &[]);
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn static_failures_exit_two_and_refuted_contracts_exit_three() {
+    let out = run("let = ;\n", &[]);
+    assert_eq!(out.status.code(), Some(2));
+
+    let type_error = "fn f(int x) -> int { return x; }\nlet y = f(\"a\");\n";
+    let out = run(type_error, &["--typecheck"]);
+    assert_eq!(out.status.code(), Some(2));
+    // Soft mode only reports the type error.
+    let out = run(type_error, &[]);
+    assert_eq!(out.status.code(), Some(0));
+
+    let refuted = "fn f(int x) requires x > 0 { return x; }\nprintln(f(0 - 1));\n";
+    let out = run(refuted, &["--typecheck"]);
+    assert_eq!(out.status.code(), Some(3));
+    let out = run(refuted, &["check"]);
+    assert_eq!(out.status.code(), Some(3));
+}
+
+#[test]
+fn type_errors_naming_verifier_words_still_exit_two() {
+    let src = "let a: int = counterexample;\n";
+    let out = run(src, &["--typecheck"]);
+    assert_eq!(out.status.code(), Some(2));
+    let out = run(src, &["check"]);
+    assert_eq!(out.status.code(), Some(2));
+    let out = run(src, &["check", "--error-format", "json"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("\"phase\":\"typecheck\""), "{stderr}");
+}
+
+#[test]
+fn quiet_drops_only_the_success_line() {
+    let out = run("println(\"hi\");\n", &["--quiet"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(stdout(&out), "hi\n");
+}
@@ -0,0 +1,100 @@
+//! `extern "C" fn` declarations bound through `--ffi-lib`, the string
+//! marshalling rules, and the `--ffi=none` sandbox gate.
+
+#![cfg(all(feature = "ffi", any(target_os = "linux", target_os = "macos")))]
+
+use std::process::{Command, Output};
+
+fn helper_path() -> &'static str {
+    env!("RESILIENT_FFI_TESTHELPER_PATH")
+}
+
+fn run(tag: &str, args: &[&str], src: &str) -> Output {
+    let path = std::env::temp_dir().join(format!(
+        "res_ffi_extern_c_{}_{}.rz",
+        tag,
+        std::process::id()
+    ));
+    std::fs::write(&path, src).unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_rz"))
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+fn stderr(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stderr).into_owned()
+}
+
+const REGISTERS: &str = "\
+extern \"C\" fn c_read_register(addr: int) -> int;
+extern \"C\" fn rt_str_len(s: string) -> int;
+extern \"C\" fn rt_greeting() -> string;
+println(c_read_register(4));
+println(rt_str_len(\"héllo\"));
+println(rt_greeting());
+";
+
+#[test]
+fn extern_c_binds_against_ffi_lib() {
+    let out = run("bind", &["--ffi-lib", helper_path()], REGISTERS);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    assert!(
+        stdout(&out).starts_with("4100\n6\nhello from C\n"),
+        "{out:?}"
+    );
+}
+
+#[test]
+fn extern_c_falls_back_to_the_running_process() {
+    let src = "extern \"C\" fn strlen(s: string) -> int;\nprintln(strlen(\"four\"));\n";
+    let out = run("process", &[], src);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    assert!(stdout(&out).starts_with("4\n"), "{out:?}");
+}
+
+#[test]
+fn a_missing_extern_c_symbol_names_the_libraries_searched() {
+    let src = "extern \"C\" fn rt_not_there(x: int) -> int;\nprintln(rt_not_there(1));\n";
+    let lib = format!("--ffi-lib={}", helper_path());
+    let out = run("missing", &[&lib], src);
+    assert_ne!(out.status.code(), Some(0));
+    let err = stderr(&out);
+    assert!(
+        err.contains("symbol `rt_not_there` not found in `")
+            && err.contains("or the running process"),
+        "{err}"
+    );
+}
+
+#[test]
+fn ffi_none_refuses_extern_declarations() {
+    let out = run(
+        "sandbox",
+        &["--ffi=none", "--ffi-lib", helper_path()],
+        REGISTERS,
+    );
+    assert_ne!(out.status.code(), Some(0));
+    assert!(stdout(&out).is_empty(), "{out:?}");
+    assert!(
+        stderr(&out).contains("native calls are disabled by the sandbox (--ffi=none)"),
+        "{out:?}"
+    );
+}
+
+#[test]
+fn an_unknown_ffi_policy_is_a_usage_error() {
+    let out = run("policy", &["--ffi=maybe"], REGISTERS);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(
+        stderr(&out).contains("unknown --ffi policy `maybe` (expected allow or none)"),
+        "{out:?}"
+    );
+}
@@ -0,0 +1,110 @@
+//! Integration tests for float special values: IEEE results by
+//! default, typed errors under `--float-strict` on both runtimes, and
+//! the shortest-round-trip / `--float-precision` printing.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("res_float_{}_{}.rz", std::process::id(), n));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(body: &str, args: &[&str]) -> Output {
+    let path = tmp_file(body);
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+const SPECIALS: &str = r#"let inf = 1.0 / 0.0;
+println(inf);
+println(0.0 / 0.0);
+println(is_nan(inf - inf));
+println(is_finite(inf));
+"#;
+
+#[test]
+fn special_values_follow_ieee_by_default() {
+    for runtime in [&[][..], &["--vm"][..]] {
+        let out = run(SPECIALS, runtime);
+        assert!(out.status.success(), "{:?}", out);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(
+            stdout.starts_with("inf\nNaN\ntrue\nfalse\n"),
+            "{runtime:?}: {stdout}"
+        );
+    }
+}
+
+#[test]
+fn float_strict_raises_typed_errors() {
+    let src = r#"fn ratio(float hits, float total) {
+    return hits / total;
+}
+try {
+    println(ratio(1.0, 0.0));
+} catch DivisionByZero {
+    println("no samples");
+}
+let nan = unwrap(parse_float("NaN"));
+println(nan != nan);
+try {
+    let b = nan < 1.0;
+} catch InvalidOperation {
+    println("unordered");
+}
+try {
+    let big = 1e308 * 10.0;
+} catch FloatOverflow {
+    println("overflow");
+}
+"#;
+    let out = run(src, &["--float-strict"]);
+    assert!(out.status.success(), "{:?}", out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.starts_with("no samples\ntrue\nunordered\noverflow\n"),
+        "{stdout}"
+    );
+
+    let out = run("println(2.0 / 0.0);\n", &["--vm", "--float-strict"]);
+    assert!(!out.status.success());
+    assert!(
+        String::from_utf8_lossy(&out.stderr)
+            .contains("DivisionByZero: 2.0 / 0.0 under --float-strict"),
+        "{:?}",
+        out
+    );
+}
+
+#[test]
+fn floats_print_shortest_or_with_fixed_precision() {
+    let src = "let x = 2.0 / 3.0;\nprintln(0.1 + 0.2);\nprintln(1e21 * 1.0);\nprintln(\"x=\" + x);\nprintln(to_string(4.0));\n";
+    let out = run(src, &[]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.starts_with("0.30000000000000004\n1e21\nx=0.6666666666666666\n4\n"),
+        "{stdout}"
+    );
+    let out = run(src, &["--float-precision", "2"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.starts_with("0.30\n1.00e21\nx=0.67\n4.00\n"),
+        "{stdout}"
+    );
+    let out = run(src, &["--float-precision=many"]);
+    assert_eq!(out.status.code(), Some(2));
+}
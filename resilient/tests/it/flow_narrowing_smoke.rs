@@ -0,0 +1,95 @@
+//! Integration tests for flow-sensitive narrowing: `--audit` counts
+//! call-site `requires` guarded by an `if` as discharged statically,
+//! and `--strict` accepts `unwrap_option` under a `!= None` check.
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(tag: &str, body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!(
+        "res_flow_narrowing_{}_{}_{}.rz",
+        tag,
+        std::process::id(),
+        n
+    ));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+const GUARDED: &str = "fn sqrtish(int x) -> int requires x >= 0 { return x; }\n\
+fn is_valid_reading(int x) -> bool { return x >= 0 && x <= 4095; }\n\
+fn g(int y) -> int {\n\
+    if is_valid_reading(y) { return sqrtish(y); }\n\
+    return 0;\n\
+}\n\
+fn h(int y) -> int {\n\
+    if y < 0 { return 0; }\n\
+    return sqrtish(y);\n\
+}\n\
+println(g(3) + h(4));\n";
+
+#[test]
+fn audit_counts_guarded_calls_as_discharged() {
+    let path = tmp_file("audit", GUARDED);
+    let out = Command::new(bin())
+        .arg("--audit")
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "stdout: {stdout}");
+    assert!(
+        stdout.contains("call-site requires discharged statically") && stdout.contains("2 / 2"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn strict_accepts_unwrap_under_none_check() {
+    let src = "fn first(Option<int> q) -> int {\n\
+        if q != None { let v = unwrap_option(q); return v + 1; }\n\
+        return 0;\n\
+    }\n\
+    println(first(Some(4)));\n";
+    let path = tmp_file("strict", src);
+    let out = Command::new(bin())
+        .arg("--strict")
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(out.status.success(), "stderr: {stderr}");
+    assert!(stdout.contains('5'), "stdout: {stdout}");
+}
+
+#[test]
+fn a_call_that_reassigns_a_global_keeps_the_runtime_check() {
+    // `mess()` invalidates the `y >= 0` the `if` established, so the
+    // call to `sqrtish` must still be checked at runtime.
+    let src = "let y = 5;\n\
+        fn mess() { y = 0 - 1; }\n\
+        fn sqrtish(int x) -> int requires x >= 0 { return x; }\n\
+        fn g() -> int { if y >= 0 { mess(); return sqrtish(y); } return 0; }\n\
+        println(g());\n";
+    let path = tmp_file("global", src);
+    let out = Command::new(bin()).arg(&path).output().expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!out.status.success(), "stdout: {stdout}");
+    assert!(
+        stderr.contains("Contract violation in fn sqrtish"),
+        "stderr: {stderr}"
+    );
+    assert!(!stdout.lines().any(|l| l == "-1"), "stdout: {stdout}");
+}
@@ -0,0 +1,153 @@
+//! Integration tests for the sandboxed file builtins: `read_file` /
+//! `write_file` / `append_file` fail with `NotFound` /
+//! `PermissionDenied`, `try ... catch` recovers from them on both
+//! runtimes, and `--fs read-only|none` narrows what a program may
+//! touch.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn scratch(tag: &str, ext: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "res_fs_sandbox_{}_{}_{}.{}",
+        tag,
+        std::process::id(),
+        n,
+        ext
+    ))
+}
+
+fn run(tag: &str, body: &str, args: &[&str]) -> Output {
+    let path = scratch(tag, "rz");
+    std::fs::write(&path, body).expect("write scratch file");
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+fn stderr(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stderr).into_owned()
+}
+
+#[test]
+fn write_append_read_round_trip() {
+    let data = scratch("round_trip", "txt");
+    let src = format!(
+        "write_file(\"{p}\", \"ab\");\n\
+         append_file(\"{p}\", \"cd\");\n\
+         println(read_file(\"{p}\"));\n\
+         println(file_exists(\"{p}\"));\n",
+        p = data.display()
+    );
+    let out = run("round_trip", &src, &[]);
+    let _ = std::fs::remove_file(&data);
+    assert!(out.status.success(), "stderr: {}", stderr(&out));
+    assert!(
+        stdout(&out).contains("abcd\ntrue"),
+        "stdout: {}",
+        stdout(&out)
+    );
+}
+
+#[test]
+fn missing_file_is_caught_as_not_found_on_both_runtimes() {
+    let missing = scratch("missing", "txt");
+    let src = format!(
+        "fn load(string p) {{\n\
+             return read_file(p);\n\
+         }}\n\
+         try {{\n\
+             println(load(\"{}\"));\n\
+         }} catch NotFound {{\n\
+             println(\"missing\");\n\
+         }}\n",
+        missing.display()
+    );
+    for args in [&[][..], &["--vm"][..]] {
+        let out = run("not_found", &src, args);
+        assert!(out.status.success(), "{:?} stderr: {}", args, stderr(&out));
+        assert!(
+            stdout(&out).contains("missing"),
+            "{:?} stdout: {}",
+            args,
+            stdout(&out)
+        );
+    }
+}
+
+#[test]
+fn read_only_policy_denies_writes_but_not_reads() {
+    let data = scratch("read_only", "txt");
+    std::fs::write(&data, "kept").unwrap();
+    let src = format!(
+        "try {{\n\
+             write_file(\"{p}\", \"clobbered\");\n\
+         }} catch PermissionDenied {{\n\
+             println(\"denied\");\n\
+         }}\n\
+         println(read_file(\"{p}\"));\n",
+        p = data.display()
+    );
+    let out = run("read_only", &src, &["--fs", "read-only"]);
+    let _ = std::fs::remove_file(&data);
+    assert!(out.status.success(), "stderr: {}", stderr(&out));
+    assert!(
+        stdout(&out).contains("denied\nkept"),
+        "stdout: {}",
+        stdout(&out)
+    );
+}
+
+#[test]
+fn none_policy_covers_the_older_builtins() {
+    let src = "println(file_read(\"/etc/hostname\"));\n";
+    let out = run("none", src, &["--fs=none"]);
+    assert!(!out.status.success());
+    assert!(
+        stderr(&out).contains("file_read: PermissionDenied: /etc/hostname: denied by --fs none"),
+        "stderr: {}",
+        stderr(&out)
+    );
+}
+
+#[test]
+fn live_block_retries_a_typed_failure() {
+    let missing = scratch("live", "txt");
+    let src = format!(
+        "live {{\n\
+             println(read_file(\"{}\"));\n\
+         }}\n",
+        missing.display()
+    );
+    let out = run("live", &src, &[]);
+    assert!(!out.status.success());
+    let err = stderr(&out);
+    assert!(err.contains("Live block failed after"), "stderr: {}", err);
+    assert!(err.contains("read_file: NotFound"), "stderr: {}", err);
+}
+
+#[test]
+fn unknown_policy_is_a_usage_error() {
+    let out = run("bad_policy", "println(1);\n", &["--fs", "everything"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(
+        stderr(&out).contains("unknown --fs policy `everything`"),
+        "stderr: {}",
+        stderr(&out)
+    );
+}
@@ -18,7 +18,7 @@ fn global_help_preserves_nested_indentation() {
     for expected in [
         "USAGE:\n    rz [FLAGS] [<file>]\n    rz                      # start REPL",
         "COMMON FLAGS:\n    -h, --help                   Show this help and exit",
-        "    -t, --typecheck              Run the static type checker in strict mode\n                                 (fail with exit 1 on any type error).",
+        "    -t, --typecheck              Run the static type checker in strict mode\n                                 (fail with exit 2 on any type error).",
         "STATUS:\n    stable             Supported for scripts and CI on the default build",
         "    backend-limited    Stable when the named backend/build feature is present;\n                       unavailable builds print a rebuild hint",
         "SUBCOMMANDS:\n    repl                 Start interactive REPL (alias for bare `rz`)",
@@ -41,9 +41,9 @@ fn repl_help_preserves_flags_and_examples_layout() {
     assert_eq!(output.status.code(), Some(0));
     let stdout = String::from_utf8_lossy(&output.stdout);
     for expected in [
-        "USAGE:\n    rz repl [--examples-dir DIR]\n    rz repl --help",
-        "FLAGS:\n    --help, -h            Show this help and exit\n    --examples-dir DIR    REPL examples directory",
-        "EXAMPLES:\n    rz repl                   # start REPL\n    rz repl --examples-dir .  # use the current directory for `examples`",
+        "Usage: rz repl [OPTIONS]",
+        "Options:\n      --examples-dir <DIR>  Directory the REPL's `examples` command lists\n  -h, --help                Print help",
+        "Examples:\n  rz repl                   # start REPL\n  rz repl --examples-dir .  # use the current directory for `examples`",
         "For bare REPL startup, run plain `rz`.",
     ] {
         assert!(
@@ -0,0 +1,86 @@
+//! `resilient::highlight`: classified, span-tagged tokens for editors.
+
+use resilient::highlight::{HighlightKind, HighlightToken, highlight};
+
+const PROGRAM: &str = "\
+struct Point { int x }
+// scale a point
+fn scale(Point p, int k) -> int
+    requires k > 0
+{
+    let s = \"not // a comment\";
+    return p.x * k;
+}
+println(scale(new Point { x: 2 }, 3));
+";
+
+/// `(text, kind, declaration)` for every token.
+fn classified(src: &str, tokens: &[HighlightToken]) -> Vec<(String, HighlightKind, bool)> {
+    let chars: Vec<char> = src.chars().collect();
+    tokens
+        .iter()
+        .map(|t| {
+            let text: String = chars[t.span.start.offset..t.span.end.offset]
+                .iter()
+                .collect();
+            (text, t.kind, t.declaration)
+        })
+        .collect()
+}
+
+#[test]
+fn highlight_classifies_names_from_context_and_declarations() {
+    let tokens = classified(PROGRAM, &highlight(PROGRAM));
+    let kind_of = |text: &str| -> Vec<(HighlightKind, bool)> {
+        tokens
+            .iter()
+            .filter(|(t, _, _)| t == text)
+            .map(|(_, k, d)| (*k, *d))
+            .collect()
+    };
+    use HighlightKind::*;
+    // Declared once, then used as a type in a parameter and after `new`.
+    assert_eq!(
+        kind_of("Point"),
+        [(Type, true), (Type, false), (Type, false)]
+    );
+    assert_eq!(kind_of("scale"), [(Function, true), (Function, false)]);
+    assert_eq!(kind_of("println"), [(Function, false)]);
+    assert_eq!(kind_of("int"), [(Type, false); 3]);
+    assert_eq!(kind_of("requires"), [(ContractKeyword, false)]);
+    assert_eq!(kind_of("fn"), [(Keyword, false)]);
+    assert_eq!(kind_of("s"), [(Variable, true)]);
+    assert_eq!(kind_of("k"), [(Variable, false); 3]);
+    assert_eq!(kind_of("\"not // a comment\""), [(String, false)]);
+    assert_eq!(kind_of("// scale a point"), [(Comment, false)]);
+    assert_eq!(kind_of("3"), [(Number, false)]);
+    assert_eq!(kind_of("*"), [(Operator, false)]);
+}
+
+#[test]
+fn highlight_spans_are_in_source_order_and_carry_positions() {
+    let tokens = highlight(PROGRAM);
+    assert!(
+        tokens
+            .windows(2)
+            .all(|w| w[0].span.end.offset <= w[1].span.start.offset),
+        "tokens overlap or are out of order"
+    );
+    let comment = tokens
+        .iter()
+        .find(|t| t.kind == HighlightKind::Comment)
+        .unwrap();
+    assert_eq!((comment.span.start.line, comment.span.start.column), (2, 1));
+
+    // Nested block comments close where the lexer closes them.
+    let src = "/* a /* b */ c */ let x = 1;";
+    let kinds: Vec<_> = classified(src, &highlight(src))
+        .into_iter()
+        .map(|(text, kind, _)| (text, kind))
+        .collect();
+    assert_eq!(
+        kinds[0],
+        ("/* a /* b */ c */".to_string(), HighlightKind::Comment)
+    );
+    assert_eq!(kinds[1], ("let".to_string(), HighlightKind::Keyword));
+}
@@ -0,0 +1,146 @@
+//! Integration tests for `http_get` / `http_post` against a local
+//! server: a response comes back as a `Response` struct, network
+//! failures are typed so `live` blocks retry them and `catch` names
+//! them, a `live ... within` budget caps the request timeout, and
+//! `--net none` denies every request.
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("res_http_{}_{}.rz", std::process::id(), n));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(body: &str, args: &[&str]) -> Output {
+    let path = tmp_file(body);
+    let out = Command::new(bin())
+        .args(["--feature", "std"])
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+/// Serve one connection per entry of `replies`, in order, after
+/// reading the request head. `None` hangs up without answering.
+fn serve(replies: Vec<Option<&'static str>>) -> (u16, thread::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = thread::spawn(move || {
+        for reply in replies {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !String::from_utf8_lossy(&buf).contains("\r\n\r\n") {
+                match stream.read(&mut chunk) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+            if let Some(reply) = reply {
+                let _ = stream.write_all(reply.as_bytes());
+            }
+        }
+    });
+    (port, handle)
+}
+
+const OK_REPLY: &str = "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello";
+
+#[test]
+fn live_block_retries_a_dropped_connection() {
+    let (port, server) = serve(vec![None, Some(OK_REPLY)]);
+    let src = format!(
+        "live {{\n    let r = http_get(\"http://127.0.0.1:{port}/\");\n    println(r.status);\n    println(r.body);\n}}\n"
+    );
+    let out = run(&src, &[]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(out.status.success(), "stderr: {stderr}");
+    assert!(stdout.contains("200\nhello\n"), "stdout: {stdout}");
+    assert!(
+        stderr.contains("http_get: ConnectionFailed"),
+        "stderr: {stderr}"
+    );
+    server.join().unwrap();
+}
+
+#[test]
+fn failures_are_caught_by_variant() {
+    let src = r#"try {
+    let r = http_get("http://127.0.0.1:1/");
+    println("unexpected");
+} catch ConnectionFailed {
+    println("refused");
+}
+"#;
+    let out = run(src, &[]);
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("refused"),
+        "{:?}",
+        out
+    );
+}
+
+#[test]
+fn live_within_budget_caps_the_request_timeout() {
+    // Accepts the connection and never answers: only a timeout ends
+    // the request, and the default one is 30 s.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (_stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(3));
+    });
+    let src = format!(
+        "fn main(int _d) {{\n    live within 200ms {{\n        let r = http_get(\"http://127.0.0.1:{port}/\");\n    }}\n}}\nmain(0);\n"
+    );
+    let start = Instant::now();
+    let out = run(&src, &[]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!out.status.success());
+    assert!(
+        stderr.contains("Live block timed out") && stderr.contains("http_get: Timeout"),
+        "stderr: {stderr}"
+    );
+    assert!(
+        start.elapsed() < Duration::from_secs(2),
+        "the block's budget should end the request, took {:?}",
+        start.elapsed()
+    );
+    server.join().unwrap();
+}
+
+#[test]
+fn net_none_denies_requests() {
+    let src = r#"try {
+    let r = http_post("http://127.0.0.1:1/", "{}");
+} catch PermissionDenied {
+    println("denied");
+}
+"#;
+    let out = run(src, &["--net", "none"]);
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("denied"),
+        "{:?}",
+        out
+    );
+    let out = run("println(1);\n", &["--net=sometimes"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("unknown --net policy"));
+}
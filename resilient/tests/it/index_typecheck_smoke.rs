@@ -36,7 +36,7 @@ fn index_int_target_is_rejected() {
     let (out, code) = check_src("let x = 5;\nlet z = x[0];\nprintln(z);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "indexing an int must fail typecheck; got:\n{out}"
     );
     assert!(
@@ -51,7 +51,7 @@ fn index_struct_target_is_rejected() {
         check_src("struct P { int a }\nlet p = new P { a: 1 };\nlet z = p[0];\nprintln(z);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "indexing a struct must fail typecheck; got:\n{out}"
     );
     assert!(
@@ -65,7 +65,7 @@ fn index_assignment_to_int_target_is_rejected() {
     let (out, code) = check_src("let x = 5;\nx[0] = 9;\nprintln(x);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "index-assignment to an int must fail typecheck; got:\n{out}"
     );
     assert!(
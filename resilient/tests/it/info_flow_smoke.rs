@@ -32,7 +32,7 @@ fn leak_example_is_rejected() {
     let (stdout, stderr, code) = run_check("info_flow_leak.rz");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "leak example must fail `rz check`; stdout={stdout} stderr={stderr}"
     );
     let combined = format!("{stdout}{stderr}");
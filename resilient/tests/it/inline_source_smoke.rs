@@ -0,0 +1,66 @@
+//! `rz -` runs a program read from stdin and `rz -e CODE` one given
+//! on the command line.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn rz_with_stdin(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(bin())
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn rz");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().expect("wait for rz")
+}
+
+#[test]
+fn dash_reads_the_program_from_stdin() {
+    for args in [&["-"][..], &["run", "-", "--vm"][..]] {
+        let out = rz_with_stdin(args, "let x = 6 * 7;\nprintln(x);\n");
+        assert_eq!(out.status.code(), Some(0), "{args:?}");
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "42\nProgram executed successfully\n",
+            "{args:?}"
+        );
+    }
+}
+
+#[test]
+fn eval_runs_its_argument_with_script_args() {
+    let out = Command::new(bin())
+        .args(["--no-typecheck", "-q", "-e", "println(args());", "--", "a"])
+        .output()
+        .expect("spawn rz -e");
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "[\"a\"]\n");
+}
+
+#[test]
+fn inline_programs_are_named_in_diagnostics() {
+    let out = Command::new(bin())
+        .args(["--eval=let xs = [1];\nprintln(xs[3]);"])
+        .output()
+        .expect("spawn rz --eval");
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("Error: <eval>:2:"), "{stderr}");
+
+    let out = rz_with_stdin(&["-"], "let = ;\n");
+    assert_eq!(out.status.code(), Some(2));
+
+    let out = Command::new(bin()).arg("-e").output().expect("spawn rz -e");
+    assert_eq!(out.status.code(), Some(2));
+}
@@ -0,0 +1,77 @@
+//! The embedding API: `lexer`, `parser`, `typechecker`, `interpreter`
+//! and `runtime` used from outside the crate.
+
+use resilient::Node;
+use resilient::lexer::{self, LexError, Token};
+use resilient::typechecker::TypeChecker;
+use resilient::{interpreter, parser, runtime};
+
+const PROGRAM: &str = "\
+fn fact(int n) -> int requires n >= 0 {
+    if n <= 1 { return 1; }
+    return n * fact(n - 1);
+}
+println(fact(5));
+";
+
+#[test]
+fn lexer_tokenizes_with_spans() {
+    let tokens = lexer::tokenize("fn f() {}\n`");
+    assert_eq!(tokens[0].0, Token::Function);
+    assert!(matches!(&tokens[1].0, Token::Identifier(name) if name.as_str() == "f"));
+    let (last, span) = tokens.last().unwrap();
+    assert_eq!(*last, Token::Error(LexError::UnexpectedChar('`')));
+    assert_eq!((span.start.line, span.start.column), (2, 1));
+}
+
+#[test]
+fn parse_check_and_run_pipeline() {
+    // The debug interpreter recurses deeply on `fact`; run it on an
+    // enlarged stack, as `main.rs` does for the CLI.
+    let handle = std::thread::Builder::new()
+        .stack_size(8 * 1024 * 1024)
+        .spawn(|| {
+            let program = parser::parse_source(PROGRAM).unwrap();
+            TypeChecker::new().check_program(&program).unwrap();
+            let result = interpreter::run(&program);
+            assert!(result.ok, "{:?}", result.errors);
+            assert_eq!(result.stdout, "120\n");
+
+            // Same program through the one-call entry point.
+            assert_eq!(interpreter::run_program(PROGRAM).stdout, "120\n");
+        })
+        .expect("thread spawn failed");
+    handle.join().unwrap();
+}
+
+#[test]
+fn parse_errors_are_diagnostics_with_a_recovered_tree() {
+    let src = "let a = 1;\nfn (;\n";
+    let errors = parser::parse_source(src).unwrap_err();
+    assert_eq!(errors[0].span.start.line, 2);
+
+    let (program, recovered_errors) = parser::parse_recovering(src);
+    assert_eq!(recovered_errors, errors);
+    let Node::Program(stmts) = program else {
+        panic!("expected a program");
+    };
+    assert!(
+        stmts
+            .iter()
+            .any(|s| matches!(&s.node, Node::LetStatement { name, .. } if name == "a"))
+    );
+}
+
+#[test]
+fn runtime_fuel_bounds_a_run() {
+    let program = parser::parse_source("let i = 0;\nwhile true { i = i + 1; }").unwrap();
+    runtime::fuel::set_max_steps(Some(5_000));
+    let result = interpreter::run(&program);
+    runtime::fuel::set_max_steps(None);
+    assert!(!result.ok);
+    assert!(
+        result.errors[0].contains("ResourceExhausted"),
+        "{:?}",
+        result.errors
+    );
+}
@@ -472,3 +472,40 @@ fn lint_emit_diagnostics_json_empty_for_clean_file() {
     );
     let _ = std::fs::remove_file(&src);
 }
+
+#[test]
+fn lint_reads_levels_from_the_project_manifest() {
+    let root = std::env::temp_dir().join(format!("res_lint_manifest_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(
+        root.join("resilient.toml"),
+        "[package]\nname = \"demo\"\n\n[lint]\nL0096 = \"deny\"\nL0014 = \"allow\"\n",
+    )
+    .unwrap();
+    let src = root.join("main.rz");
+    std::fs::write(
+        &src,
+        "fn f(int x) -> int {\n    live {\n        return x;\n    }\n}\n",
+    )
+    .unwrap();
+
+    let out = Command::new(bin())
+        .arg("lint")
+        .arg(&src)
+        .output()
+        .expect("spawn lint");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(out.status.code(), Some(2), "stdout: {stdout}");
+    assert!(stdout.contains("error[L0096]"), "stdout: {stdout}");
+    assert!(!stdout.contains("L0014"), "stdout: {stdout}");
+
+    // A flag wins over the manifest.
+    let out = Command::new(bin())
+        .args(["lint", "--allow", "L0096"])
+        .arg(&src)
+        .output()
+        .expect("spawn lint");
+    assert!(!String::from_utf8_lossy(&out.stdout).contains("L0096"));
+    let _ = std::fs::remove_dir_all(&root);
+}
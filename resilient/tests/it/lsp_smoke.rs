@@ -957,3 +957,87 @@ fn lsp_did_change_republishes_diagnostics() {
         }
     }
 }
+
+#[test]
+fn lsp_did_change_applies_incremental_range_edits() {
+    // Incremental sync: each change splices `text` into `range`, and
+    // diagnostics follow the edited buffer.
+    let mut child = Command::new(bin())
+        .arg("--lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn resilient --lsp");
+
+    let mut stdin = child.stdin.take().expect("piped stdin");
+    let mut stdout = child.stdout.take().expect("piped stdout");
+
+    let init = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{}}}"#;
+    stdin.write_all(frame(init).as_bytes()).unwrap();
+    stdin.flush().ok();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let body = read_one_message(&mut stdout, deadline).expect("read initialize response");
+    assert!(
+        body.contains(r#""textDocumentSync":2"#),
+        "expected incremental sync in: {body}"
+    );
+    let initialized = r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#;
+    stdin.write_all(frame(initialized).as_bytes()).unwrap();
+
+    let uri = "file:///tmp/lsp_incremental.rs";
+    let did_open = format!(
+        r#"{{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{{"textDocument":{{"uri":"{uri}","languageId":"resilient","version":1,"text":"println(1);\nprintln(2);\nprintln(3);\nlet x = 1;\nprintln(x);\n"}}}}}}"#
+    );
+    stdin.write_all(frame(&did_open).as_bytes()).unwrap();
+    stdin.flush().ok();
+    let is_diag = |body: &str| body.contains(r#""method":"textDocument/publishDiagnostics""#);
+    let body = read_until(
+        &mut stdout,
+        is_diag,
+        Instant::now() + Duration::from_secs(5),
+    )
+    .expect("read clean publishDiagnostics");
+    assert!(body.contains(r#""diagnostics":[]"#), "got:\n{body}");
+
+    // Turn line 4's `let x = 1;` into `let x: int = "hi";`: the
+    // typechecker must see the spliced text.
+    let did_change = format!(
+        r#"{{"jsonrpc":"2.0","method":"textDocument/didChange","params":{{"textDocument":{{"uri":"{uri}","version":2}},"contentChanges":[{{"range":{{"start":{{"line":3,"character":5}},"end":{{"line":3,"character":9}}}},"text":": int = \"hi\""}}]}}}}"#
+    );
+    stdin.write_all(frame(&did_change).as_bytes()).unwrap();
+    stdin.flush().ok();
+    let body = read_until(
+        &mut stdout,
+        is_diag,
+        Instant::now() + Duration::from_secs(5),
+    )
+    .expect("read buggy publishDiagnostics");
+    assert!(!body.contains(r#""diagnostics":[]"#), "got:\n{body}");
+    assert!(
+        body.contains(r#""line":3"#),
+        "expected a line-4 diagnostic in: {body}"
+    );
+
+    // Two blank lines inserted above it move the diagnostic down.
+    let did_change = format!(
+        r#"{{"jsonrpc":"2.0","method":"textDocument/didChange","params":{{"textDocument":{{"uri":"{uri}","version":3}},"contentChanges":[{{"range":{{"start":{{"line":0,"character":0}},"end":{{"line":0,"character":0}}}},"text":"\n\n"}}]}}}}"#
+    );
+    stdin.write_all(frame(&did_change).as_bytes()).unwrap();
+    stdin.flush().ok();
+    let body = read_until(
+        &mut stdout,
+        is_diag,
+        Instant::now() + Duration::from_secs(5),
+    )
+    .expect("read shifted publishDiagnostics");
+    assert!(
+        body.contains(r#""line":5"#),
+        "expected a line-6 diagnostic in: {body}"
+    );
+
+    let exit = r#"{"jsonrpc":"2.0","method":"exit"}"#;
+    let _ = stdin.write_all(frame(exit).as_bytes());
+    drop(stdin);
+    let _ = child.wait();
+}
@@ -11,9 +11,11 @@ mod agent_playbook_lib_allowlist_smoke;
 mod ai_threats_smoke;
 mod anonymous_structs_smoke;
 mod associated_types_smoke;
+mod ast_api_smoke;
 mod atomic_types_callsite_smoke;
 mod atomic_types_runtime_parity;
 mod atomic_types_smoke;
+mod backend_flag_smoke;
 mod backend_limited_diagnostics_smoke;
 mod bench_cli;
 mod bench_cli_summary_json;
@@ -36,17 +38,21 @@ mod cli_integration_edge_cases;
 mod cli_toggle_source_lib_split_smoke;
 mod cluster_invariant_smoke;
 mod codeless_diagnostic_lint_smoke;
+mod coercion_policy_smoke;
 mod compound_trait_bounds_golden;
 mod conformance;
+mod const_eval_smoke;
 mod const_generic_array_type_golden;
 mod const_generic_len_golden;
 mod contract_certificate_e2e_smoke;
 mod core_touchpoint_source_lib_split_smoke;
+mod debug_console_smoke;
 mod debug_help_smoke;
 mod derive_partial_ord_runtime;
 mod diagnostic_model_copy_smoke;
 mod diagnostics_snapshots;
 mod differential;
+mod doc_smoke;
 mod docs_certificates_manifest_schema_smoke;
 mod docs_certification_roadmap_copy_smoke;
 mod docs_certification_verify_all_command_smoke;
@@ -90,22 +96,29 @@ mod docs_tooling_fmt_copy_smoke;
 mod docs_tooling_pkg_subcommands_smoke;
 mod docs_tooling_semantic_token_path_smoke;
 mod docs_tutorial_verify_all_command_smoke;
+mod dump_ast_smoke;
 mod dump_chunks_smoke;
 mod dump_source_map_help_smoke;
 mod dump_tokens_smoke;
 mod dyn_trait_smoke;
 mod effect_system_smoke;
+mod error_format_smoke;
 mod examples_golden;
 mod examples_smoke;
+mod exit_code_smoke;
 mod explain_effects_cli;
 mod ffi_comment_copy_smoke;
 mod ffi_docs_callback_copy_smoke;
 mod ffi_docs_string_support_smoke;
+mod ffi_extern_c;
 mod ffi_integration;
 mod ffi_variadic_integration;
+mod float_semantics_smoke;
+mod flow_narrowing_smoke;
 mod fmt_check_smoke;
 mod fmt_help_smoke;
 mod free_vars_docs_copy_smoke;
+mod fs_sandbox_smoke;
 mod fuzz_docs_cli_names_smoke;
 mod fuzz_docs_library_surface_smoke;
 mod generic_bound_call_site_golden;
@@ -113,13 +126,17 @@ mod global_help_word_copy_smoke;
 mod global_subcommand_help_copy_smoke;
 mod help_layout_smoke;
 mod help_word_smoke;
+mod highlight_api_smoke;
+mod http_client_smoke;
 mod index_typecheck_smoke;
 mod info_flow_smoke;
+mod inline_source_smoke;
 mod issue_template_test_location_smoke;
 mod jit_backend_header_copy_smoke;
 mod jit_comment_copy_smoke;
 mod jit_run_path_copy_smoke;
 mod lexer_keyword_source_lib_split_smoke;
+mod library_api_smoke;
 mod linear_types;
 mod lint_help_smoke;
 mod lint_parse_json;
@@ -139,10 +156,17 @@ mod lsp_snapshots;
 mod lsp_source_lib_split_smoke;
 mod manifest_example_extension_comment_smoke;
 mod manifest_feature_command_copy_smoke;
+mod max_memory_smoke;
+mod max_steps_smoke;
 mod mcp_server_copy_smoke;
 mod mcp_syntax_resource_copy_smoke;
 mod mcp_tool_help_copy_smoke;
+mod metrics_smoke;
+mod mmio_smoke;
+mod mutate_smoke;
+mod new_project_smoke;
 mod noninterference_smoke;
+mod overloading_smoke;
 mod package_existence;
 mod panic_on_fault_smoke;
 mod parser_extension_source_lib_split_smoke;
@@ -151,6 +175,7 @@ mod parser_negative_statements;
 mod parser_negative_types_structs;
 mod parser_panic_smoke;
 mod pkg_add_help_smoke;
+mod pkg_add_smoke;
 mod pkg_help_copy_smoke;
 mod pkg_init_help_smoke;
 mod pkg_init_smoke;
@@ -162,23 +187,34 @@ mod playground_acceptance_rows_smoke;
 mod playground_banner_copy_smoke;
 mod playground_docs_tree_walker_smoke;
 mod playground_runtime_comment_copy_smoke;
+mod plugin_smoke;
+mod prelude_smoke;
+mod profile_smoke;
+mod project_config_smoke;
+mod project_smoke;
 mod projection_bounds_smoke;
 mod projection_where_clause_golden;
 mod projection_where_clause_smoke;
+mod read_stdin_smoke;
 mod readme_install_release_tag_smoke;
 mod readme_project_status_copy_smoke;
 mod readme_self_hosting_status_smoke;
 mod readme_workspace_copy_smoke;
+mod record_replay_smoke;
 mod recovers_to_smoke;
 mod recovers_to_z3_obligation;
+mod recursive_structs_smoke;
 mod repl_help_copy_smoke;
 mod repl_help_smoke;
 mod repl_smoke;
 mod rich_diag_smoke;
 mod roundtrip;
+mod run_subcommand_smoke;
 mod runtime_feature_gating_smoke;
 mod rzbc_build_roundtrip;
 mod safety_critical_smoke;
+mod sandbox_allow_smoke;
+mod script_args_smoke;
 mod self_host_extension_copy_smoke;
 mod self_host_parity;
 mod self_host_parity_help_smoke;
@@ -186,6 +222,7 @@ mod self_host_parity_report_cli;
 mod self_host_readme_input_copy_smoke;
 mod self_host_readme_lexer_criterion_smoke;
 mod self_host_readme_parser_status_smoke;
+mod serde_smoke;
 mod source_comment_lib_split_smoke;
 mod stability_help_smoke;
 mod stable_cli_surface_smoke;
@@ -194,27 +231,39 @@ mod stable_inventory_rz_test_smoke;
 mod stable_language_surface_backfill;
 mod stack_usage_help_smoke;
 mod stdlib_builtin_location_copy_smoke;
+mod strict_any_smoke;
 mod string_interning_comprehensive;
 mod string_interning_task1;
 mod string_interning_task3;
 mod string_interning_task4;
 mod string_interning_task5;
 mod string_interning_task6;
+mod structured_logging_smoke;
+mod tail_calls_smoke;
 mod target_profiles_rejection_smoke;
+mod telemetry_smoke;
 mod terminal_mode_usage_smoke;
 mod termination_smoke;
+mod test_coverage_smoke;
+mod test_fn_smoke;
 mod test_help_smoke;
+mod test_snapshot_smoke;
 mod tla_dispatch_smoke;
 mod tla_help_smoke;
+mod trace_smoke;
 mod try_catch_runtime;
 mod typecheck_negative_diagnostics;
 mod typecheck_strict_smoke;
+mod union_types_smoke;
 mod unknown_command_diagnostics_smoke;
 mod unknown_command_help_hint_smoke;
+mod value_bridge_smoke;
 mod verification_help_smoke;
 mod verify_all_smoke;
 mod verify_cert_smoke;
+mod verify_subcommand_smoke;
 mod vibe_gate;
 mod vscode_readme_roadmap_copy_smoke;
 mod vscode_readme_rz_defaults_smoke;
 mod vscode_release_sync_smoke;
+mod warning_categories_smoke;
@@ -0,0 +1,87 @@
+//! Integration tests for `--max-memory`: a program whose bound data
+//! keeps growing stops with a typed `OutOfMemory` error, and one that
+//! stays small is unaffected.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("res_max_memory_{}_{}.rz", std::process::id(), n));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(body: &str, args: &[&str]) -> Output {
+    let path = tmp_file(body);
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+const GROWING_ARRAY: &str = "fn main(int _d) {\n\
+    let xs = [];\n\
+    let i = 0;\n\
+    while i < 100000 {\n\
+        xs = push(xs, \"a reading that takes up some room\");\n\
+        i = i + 1;\n\
+    }\n\
+    println(len(xs));\n\
+}\n\
+main(0);\n";
+
+#[test]
+fn growing_array_stops_with_out_of_memory() {
+    let out = run(GROWING_ARRAY, &["--max-memory", "100000"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert_eq!(out.status.code(), Some(1), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("OutOfMemory") && stderr.contains("limit of 100000 bytes"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+#[test]
+fn small_program_runs_under_the_cap() {
+    let out = run(
+        "let xs = [1, 2, 3];\nprintln(len(xs));\n",
+        &["--max-memory=100000"],
+    );
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(String::from_utf8_lossy(&out.stdout).contains("3\n"));
+}
+
+#[test]
+fn max_memory_rejects_a_non_number() {
+    let out = run("println(1);\n", &["--max-memory", "lots"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--max-memory expects a byte count"));
+}
+
+#[test]
+fn max_memory_is_rejected_with_the_vm() {
+    let out = run("println(1);\n", &["--max-memory", "100000", "--vm"]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("--max-memory is counted by the tree-walking interpreter; drop --vm"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(out.stdout.is_empty(), "the program should not run");
+}
@@ -0,0 +1,86 @@
+//! Integration tests for `--max-steps`: a run that outlives its step
+//! budget stops with `ResourceExhausted` on both runtimes, a `live`
+//! block does not retry it, and a budget the program fits in changes
+//! nothing.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("res_max_steps_{}_{}.rz", std::process::id(), n));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(body: &str, args: &[&str]) -> Output {
+    let path = tmp_file(body);
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+/// Never finishes on its own: the loop guard is far above the
+/// budgets used here, and every attempt of the `live` body spins.
+const SPINNING_LIVE_BLOCK: &str = "fn main(int _d) {\n\
+    live {\n\
+        let i = 0;\n\
+        while true {\n\
+            i = i + 1;\n\
+        }\n\
+    }\n\
+}\n\
+main(0);\n";
+
+#[test]
+fn spinning_live_block_stops_with_resource_exhausted() {
+    for args in [
+        &["--max-steps", "5000"][..],
+        &["--vm", "--max-steps=5000"][..],
+    ] {
+        let out = run(SPINNING_LIVE_BLOCK, args);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert_eq!(out.status.code(), Some(1), "{:?} stderr: {}", args, stderr);
+        assert!(
+            stderr.contains("ResourceExhausted") && stderr.contains("limit of 5000 steps"),
+            "{:?} stderr: {}",
+            args,
+            stderr
+        );
+        assert!(
+            !stderr.contains("Live block failed after"),
+            "a spent budget must not be retried; {:?} stderr: {}",
+            args,
+            stderr
+        );
+    }
+}
+
+#[test]
+fn program_within_budget_runs_normally() {
+    let out = run("println(1 + 2);\n", &["--max-steps", "1000"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(stdout.contains("3\n"), "stdout: {}", stdout);
+}
+
+#[test]
+fn max_steps_rejects_a_non_number() {
+    let out = run("println(1);\n", &["--max-steps", "lots"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--max-steps expects a u64"));
+}
@@ -0,0 +1,102 @@
+//! Integration tests for `--metrics-port`: while the program runs,
+//! `GET /metrics` on the announced address returns its counters in
+//! Prometheus text format.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+/// Retries twice, then parks on `read_line` until stdin closes.
+const PROGRAM: &str = "let d = 0;\n\
+live retries(3) {\n\
+    if live_retries() < 2 { let z = 1 / d; }\n\
+}\n\
+let greeting = \"hello\";\n\
+println(\"ready\");\n\
+let line = read_line();\n";
+
+fn get(addr: &str, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).expect("connect to the metrics port");
+    write!(stream, "GET {path} HTTP/1.1\r\nHost: {addr}\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn serves_counters_while_the_program_runs() {
+    let path = std::env::temp_dir().join(format!("res_metrics_{}.rz", std::process::id()));
+    std::fs::write(&path, PROGRAM).unwrap();
+    let mut child = Command::new(bin())
+        .args(["--no-typecheck", "--metrics-port", "127.0.0.1:0"])
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn rz");
+
+    let mut stderr = BufReader::new(child.stderr.take().unwrap()).lines();
+    let addr = stderr
+        .by_ref()
+        .map_while(Result::ok)
+        .find_map(|l| {
+            l.strip_prefix("[metrics] serving http://")
+                .and_then(|rest| rest.strip_suffix("/metrics"))
+                .map(String::from)
+        })
+        .expect("rz should announce the metrics address");
+    // Keep draining stderr so the `[LIVE BLOCK]` lines don't hit a
+    // closed pipe.
+    let drain = std::thread::spawn(move || stderr.for_each(drop));
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut ready = String::new();
+    stdout.read_line(&mut ready).unwrap();
+    assert_eq!(ready, "ready\n");
+
+    let response = get(&addr, "/metrics");
+    let missing = get(&addr, "/other");
+    drop(child.stdin.take());
+    let _ = child.wait();
+    let _ = drain.join();
+    let _ = std::fs::remove_file(&path);
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+    assert!(response.contains("Content-Type: text/plain; version=0.0.4\r\n"));
+    let body = response.split_once("\r\n\r\n").unwrap().1;
+    let value = |name: &str| -> u64 {
+        body.lines()
+            .find_map(|l| l.strip_prefix(name)?.strip_prefix(' ')?.parse().ok())
+            .unwrap_or_else(|| panic!("no {name} in {body}"))
+    };
+    assert!(value("resilient_statements_total") >= 3, "{body}");
+    assert_eq!(value("resilient_live_retries_total"), 2, "{body}");
+    assert_eq!(value("resilient_live_exhaustions_total"), 0, "{body}");
+    assert!(value("resilient_memory_bytes") > 0, "{body}");
+    assert!(body.contains("# TYPE resilient_actor_mailbox_depth gauge\n"));
+    assert!(body.contains("resilient_breaker_state{line=\"2\",state=\"closed\"} 1\n"));
+    assert!(body.contains("resilient_breaker_state{line=\"2\",state=\"half_open\"} 0\n"));
+
+    assert!(missing.starts_with("HTTP/1.1 404"), "{missing}");
+}
+
+#[test]
+fn rejects_the_vm_and_a_bad_port() {
+    let out = Command::new(bin())
+        .args(["--vm", "--metrics-port", "9100", "-e", "println(1);"])
+        .output()
+        .expect("spawn rz");
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("drop --vm"));
+
+    let out = Command::new(bin())
+        .args(["--metrics-port", "metrics", "-e", "println(1);"])
+        .output()
+        .expect("spawn rz");
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("expects a port or HOST:PORT"));
+}
@@ -0,0 +1,157 @@
+//! `mmio_read` / `mmio_write`: only inside `unsafe`, only inside the
+//! `--mmio` / `[sandbox] mmio` allowlist, and only with the
+//! `unsafe-io` feature. A scratch file stands in for the register
+//! device.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn scratch(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("res_mmio_{}_{}_{}", std::process::id(), n, name))
+}
+
+fn run_in(dir: &std::path::Path, body: &str, args: &[&str]) -> Output {
+    let path = dir.join("main.rz");
+    std::fs::write(&path, body).expect("write scratch file");
+    Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz")
+}
+
+#[cfg(all(feature = "unsafe-io", unix))]
+const TOGGLE: &str = r#"fn main() {
+    unsafe {
+        mmio_write(4100, 3735928559);
+        println(mmio_read(4100));
+        try {
+            let r = mmio_read(4112);
+        } catch PermissionDenied {
+            println("outside");
+        }
+    }
+}
+main();
+"#;
+
+#[test]
+fn addresses_outside_the_allowlist_are_denied() {
+    let dir = scratch("deny");
+    std::fs::create_dir_all(&dir).unwrap();
+    let src = "unsafe {\n    try {\n        let r = mmio_read(4096);\n    } catch PermissionDenied {\n        println(\"denied\");\n    }\n}\n";
+    let out = run_in(&dir, src, &[]);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("denied"),
+        "{:?}",
+        out
+    );
+}
+
+#[test]
+fn volatile_intrinsics_outside_the_allowlist_are_denied() {
+    let dir = scratch("volatile");
+    std::fs::create_dir_all(&dir).unwrap();
+    let src = "unsafe {\n    try {\n        volatile_write_u32(8192, 1);\n    } catch PermissionDenied {\n        println(\"denied\");\n    }\n    let v = volatile_read_u64(4104);\n}\n";
+    let out = run_in(&dir, src, &["--mmio", "0x1000..0x1008"]);
+    let _ = std::fs::remove_dir_all(&dir);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert_eq!(out.status.code(), Some(1), "{:?}", out);
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("denied"),
+        "{:?}",
+        out
+    );
+    assert!(
+        stderr
+            .contains("volatile_read_u64: PermissionDenied: 0x1008 is outside every allowed range"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn calls_outside_unsafe_are_rejected_before_running() {
+    let dir = scratch("gate");
+    std::fs::create_dir_all(&dir).unwrap();
+    let out = run_in(&dir, "mmio_write(4096, 1);\n", &["--mmio", "4096+16"]);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(!out.status.success());
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("`mmio_write` outside an `unsafe` block"),
+        "{:?}",
+        out
+    );
+}
+
+#[cfg(not(feature = "unsafe-io"))]
+#[test]
+fn builds_without_the_feature_report_unsupported() {
+    let dir = scratch("unsupported");
+    std::fs::create_dir_all(&dir).unwrap();
+    let src = "unsafe {\n    try {\n        let r = mmio_read(4096);\n    } catch Unsupported {\n        println(\"no unsafe-io\");\n    }\n}\n";
+    let out = run_in(&dir, src, &["--mmio", "4096+16"]);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("no unsafe-io"),
+        "{:?}",
+        out
+    );
+}
+
+#[cfg(all(feature = "unsafe-io", unix))]
+#[test]
+fn registers_round_trip_through_the_device_flags() {
+    let dir = scratch("flags");
+    std::fs::create_dir_all(&dir).unwrap();
+    let device = dir.join("regs.bin");
+    std::fs::write(&device, vec![0u8; 8192]).unwrap();
+    let device_flag = format!("--mmio-device={}", device.display());
+    let out = run_in(&dir, TOGGLE, &["--mmio", "0x1000..0x1010", &device_flag]);
+    let regs = std::fs::read(&device).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(out.status.success(), "{:?}", out);
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("3735928559\noutside\n"),
+        "{:?}",
+        out
+    );
+    assert_eq!(regs[0x1004..0x1008], 0xDEAD_BEEFu32.to_ne_bytes());
+}
+
+#[cfg(all(feature = "unsafe-io", unix))]
+#[test]
+fn the_manifest_sandbox_section_sets_ranges_and_device() {
+    let dir = scratch("manifest");
+    std::fs::create_dir_all(&dir).unwrap();
+    let device = dir.join("regs.bin");
+    std::fs::write(&device, vec![0u8; 8192]).unwrap();
+    std::fs::write(
+        dir.join("resilient.toml"),
+        format!(
+            "[package]\nname = \"blink\"\n\n[sandbox]\nmmio = [\"0x1000+0x10\"]\nmmio_device = \"{}\"\n",
+            device.display()
+        ),
+    )
+    .unwrap();
+    let out = run_in(&dir, TOGGLE, &[]);
+    let missing = run_in(&dir, TOGGLE, &["--mmio-device", "/nonexistent/mem"]);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("3735928559\noutside\n"),
+        "{:?}",
+        out
+    );
+    assert!(
+        String::from_utf8_lossy(&missing.stderr).contains("mmio_write: NotFound: /nonexistent/mem"),
+        "{:?}",
+        missing
+    );
+}
@@ -0,0 +1,79 @@
+//! `rz mutate`: run a file's tests against mutants of its functions
+//! and report the survivors.
+
+use std::process::{Command, Output};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn run(name: &str, program: &str) -> Output {
+    let path = std::env::temp_dir().join(format!("res_mutate_{}_{}.rz", std::process::id(), name));
+    std::fs::write(&path, program).unwrap();
+    let out = Command::new(bin())
+        .arg("mutate")
+        .arg(&path)
+        .output()
+        .expect("spawn rz mutate");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+/// The mutant lines, without the file path.
+fn results(out: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter(|l| l.starts_with("mutant "))
+        .map(|l| l.split_once(".rz:").expect("a location").1.to_string())
+        .collect()
+}
+
+#[test]
+fn survivors_are_listed_and_exit_one() {
+    let program = "fn at_least(int x, int lo) -> int {\n    if x < lo {\n        return lo;\n    }\n    return x;\n}\n\nfn half(int n) -> int {\n    assert(n >= 0);\n    return n / 2;\n}\n\ntest fn raises_low() {\n    assert_eq(at_least(-5, 0), 0);\n}\n\ntest fn halves() {\n    assert_eq(half(8), 4);\n}\n";
+    let out = run("survivors", program);
+    assert_eq!(out.status.code(), Some(1));
+    let results = results(&out);
+    assert_eq!(
+        results[..2],
+        [
+            "2: at_least: `<` -> `<=` ... SURVIVED",
+            "2: at_least: `<` -> `>=` ... killed by raises_low",
+        ]
+    );
+    assert!(
+        results.contains(&"9: half: assert condition -> `true` ... SURVIVED".to_string()),
+        "{results:?}"
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("survived mutants:\n  "), "{stdout}");
+    assert!(stdout.contains(" mutants: "), "{stdout}");
+}
+
+#[test]
+fn all_killed_exits_zero_and_loops_time_out() {
+    let program = "fn steps_to(int n) -> int {\n    let i = 0;\n    let steps = 0;\n    while i != n {\n        i = i + 1;\n        steps = steps + 1;\n    }\n    return steps;\n}\n\ntest fn counts() {\n    assert_eq(steps_to(3), 3);\n}\n";
+    let out = run("killed", program);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(out.status.code(), Some(0), "{stdout}");
+    assert!(
+        results(&out).contains(&"4: steps_to: `!=` -> `==` ... killed by counts".to_string()),
+        "{stdout}"
+    );
+    assert!(stdout.contains("killed (timeout)"), "{stdout}");
+    assert!(stdout.contains("0 survived (100% killed)"), "{stdout}");
+}
+
+#[test]
+fn failing_tests_before_mutation_exit_two() {
+    let out = run(
+        "baseline",
+        "fn one() -> int { return 1; }\n\ntest fn wrong() {\n    assert_eq(one(), 2);\n}\n",
+    );
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("test wrong fails before mutation"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,71 @@
+//! `rz new <name>` creates a project that runs, checks and tests
+//! cleanly, and writes nothing outside the new directory.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn rz(args: &[&str], cwd: &Path) -> Output {
+    Command::new(bin())
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .expect("spawn rz")
+}
+
+#[test]
+fn the_new_project_runs_checks_and_passes_its_test() {
+    let parent = std::env::temp_dir().join(format!("res_new_smoke_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&parent);
+    std::fs::create_dir_all(&parent).unwrap();
+
+    let out = rz(&["new", "demo"], &parent);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    let entries: Vec<_> = std::fs::read_dir(&parent).unwrap().collect();
+    assert_eq!(entries.len(), 1, "only ./demo is created");
+    let root = parent.join("demo");
+    for file in [
+        "resilient.toml",
+        "src/main.rz",
+        "src/greet.rz",
+        "src/supervisor.rz",
+        "tests/greet_test.rz",
+        ".gitignore",
+    ] {
+        assert!(root.join(file).is_file(), "{file}");
+    }
+
+    let out = rz(&["-q", "."], &root);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        "Hello, world!\nHello, supervisor!\n"
+    );
+    let out = rz(&["check", "."], &root);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!stderr.contains("warning[unused]"), "{stderr}");
+    let out = rz(&["test"], &root);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    assert!(String::from_utf8_lossy(&out.stdout).contains("1 passed, 0 failed"));
+
+    // A second run refuses to touch the existing project.
+    let out = rz(&["new", "demo"], &parent);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("refusing to overwrite"));
+    let _ = std::fs::remove_dir_all(&parent);
+}
+
+#[test]
+fn new_needs_a_valid_name() {
+    let cwd = std::env::temp_dir();
+    let out = rz(&["new"], &cwd);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("requires a project name"));
+    let out = rz(&["new", "a/b"], &cwd);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("invalid project name"));
+}
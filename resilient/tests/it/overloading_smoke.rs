@@ -0,0 +1,116 @@
+//! Integration tests for function overloading: the tree-walker
+//! dispatches on arity and argument types, the VM on arity, neither
+//! lets a set be used as a value, and `rz fmt` prints overloads under
+//! their source names.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(tag: &str, body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!(
+        "res_overloading_{}_{}_{}.rz",
+        tag,
+        std::process::id(),
+        n
+    ));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(tag: &str, body: &str, args: &[&str]) -> Output {
+    let path = tmp_file(tag, body);
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+const LOG: &str = "fn log(string msg) { println(\"[info] \" + msg); }\n\
+fn log(string level, string msg) { println(\"[\" + level + \"] \" + msg); }\n\
+log(\"ready\");\n\
+log(\"warn\", \"hot\");\n";
+
+const AREA: &str = "fn area(int w) -> int { return w * w; }\n\
+fn area(float w) -> float { return w * w; }\n\
+println(area(3));\n\
+println(area(1.5));\n";
+
+#[test]
+fn arity_overloads_run_on_both_backends() {
+    for backend in [&[][..], &["--vm"][..]] {
+        let out = run("log", LOG, backend);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(out.status.success(), "{backend:?} stderr: {stderr}");
+        assert!(
+            stdout.contains("[info] ready\n[warn] hot\n"),
+            "{backend:?} stdout: {stdout}"
+        );
+    }
+}
+
+#[test]
+fn tree_walker_dispatches_on_argument_types() {
+    let out = run("area", AREA, &[]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("9\n2.25\n"), "stdout: {stdout}");
+}
+
+#[test]
+fn vm_rejects_a_call_only_types_could_resolve() {
+    let out = run("area_vm", AREA, &["--vm"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("ambiguous call to overloaded `area`"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn check_reports_a_call_no_overload_accepts() {
+    let src = format!("{AREA}area(true);\n");
+    let out = run("nomatch", &src, &["check"]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("no overload of `area` accepts (bool)"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn an_overload_set_used_as_a_value_is_rejected_by_both_backends() {
+    let src = format!("{LOG}let f = log;\nf(\"again\");\n");
+    for backend in [&["--no-typecheck"][..], &["--no-typecheck", "--vm"][..]] {
+        let out = run("value", &src, backend);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(!out.status.success(), "{backend:?} stdout: {stdout}");
+        assert!(
+            stderr.contains("overloaded fn `log` cannot be used as a value"),
+            "{backend:?} stderr: {stderr}"
+        );
+        assert!(!stdout.contains("again"), "{backend:?} stdout: {stdout}");
+    }
+}
+
+#[test]
+fn fmt_prints_source_names() {
+    let out = run("fmt", LOG, &["fmt"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("fn log(string msg)"), "stdout: {stdout}");
+    assert!(!stdout.contains('$'), "stdout: {stdout}");
+}
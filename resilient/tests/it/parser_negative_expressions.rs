@@ -48,7 +48,7 @@ fn check_src(src: &str) -> (String, Option<i32>) {
 #[test]
 fn binary_missing_right_operand_add() {
     let (out, code) = check_src("let x = 5 +;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -58,7 +58,7 @@ fn binary_missing_right_operand_add() {
 #[test]
 fn binary_missing_right_operand_multiply() {
     let (out, code) = check_src("let x = 3 * ;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -68,7 +68,7 @@ fn binary_missing_right_operand_multiply() {
 #[test]
 fn binary_missing_right_operand_divide() {
     let (out, code) = check_src("let x = 10 / ;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -78,7 +78,7 @@ fn binary_missing_right_operand_divide() {
 #[test]
 fn binary_missing_right_operand_modulo() {
     let (out, code) = check_src("let x = 7 % ;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -88,7 +88,7 @@ fn binary_missing_right_operand_modulo() {
 #[test]
 fn binary_missing_right_operand_comparison() {
     let (out, code) = check_src("let x = 5 == ;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -98,7 +98,7 @@ fn binary_missing_right_operand_comparison() {
 #[test]
 fn binary_missing_left_operand() {
     let (out, code) = check_src("let x = + 5;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") || out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -108,7 +108,7 @@ fn binary_missing_left_operand() {
 #[test]
 fn binary_both_operands_missing() {
     let (out, code) = check_src("let x = *;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") || out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -122,7 +122,7 @@ fn binary_both_operands_missing() {
 #[test]
 fn unbalanced_paren_missing_close() {
     let (out, code) = check_src("let x = (5 + 3;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains(")") && out.contains("1:"),
         "expected diagnostic mentioning ')'; got:\n{out}"
@@ -132,7 +132,7 @@ fn unbalanced_paren_missing_close() {
 #[test]
 fn unbalanced_paren_extra_close() {
     let (out, code) = check_src("let x = (5 + 3));\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("unexpected") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -142,7 +142,7 @@ fn unbalanced_paren_extra_close() {
 #[test]
 fn unbalanced_paren_nested_missing_close() {
     let (out, code) = check_src("let x = ((5 + 3);\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains(")") || out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -152,7 +152,7 @@ fn unbalanced_paren_nested_missing_close() {
 #[test]
 fn unbalanced_bracket_missing_close_in_array() {
     let (out, code) = check_src("let x = [1, 2, 3;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("]") && out.contains("1:"),
         "expected diagnostic mentioning ']'; got:\n{out}"
@@ -162,7 +162,7 @@ fn unbalanced_bracket_missing_close_in_array() {
 #[test]
 fn unbalanced_bracket_missing_close_in_index() {
     let (out, code) = check_src("let x = [1, 2, 3];\nlet y = x[0;\nprintln(y);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("]") && out.contains("2:"),
         "expected diagnostic mentioning ']'; got:\n{out}"
@@ -176,7 +176,7 @@ fn unbalanced_bracket_missing_close_in_index() {
 #[test]
 fn array_missing_closing_bracket() {
     let (out, code) = check_src("let x = [1, 2, 3\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("]") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -186,7 +186,7 @@ fn array_missing_closing_bracket() {
 #[test]
 fn array_consecutive_commas() {
     let (out, code) = check_src("let x = [1,,2];\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("]") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -203,7 +203,7 @@ fn array_trailing_comma_with_garbage() {
 #[test]
 fn array_leading_comma() {
     let (out, code) = check_src("let x = [, 1, 2];\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("]") || out.contains("Expected"),
         "expected diagnostic; got:\n{out}"
@@ -213,7 +213,7 @@ fn array_leading_comma() {
 #[test]
 fn array_semicolon_instead_of_comma() {
     let (out, code) = check_src("let x = [1; 2; 3];\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("]") || out.contains("Expected"),
         "expected diagnostic; got:\n{out}"
@@ -227,7 +227,7 @@ fn array_semicolon_instead_of_comma() {
 #[test]
 fn call_missing_closing_paren() {
     let (out, code) = check_src("let x = foo(1, 2;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains(")") && out.contains("1:"),
         "expected diagnostic mentioning ')'; got:\n{out}"
@@ -237,7 +237,7 @@ fn call_missing_closing_paren() {
 #[test]
 fn call_bad_argument_separator() {
     let (out, code) = check_src("let x = foo(1; 2);\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains(")") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -248,9 +248,9 @@ fn call_bad_argument_separator() {
 fn call_trailing_comma_before_paren() {
     let (out, code) = check_src("fn foo(int x) { return x; }\nlet x = foo(1,);\nprintln(x);\n");
     // Trailing comma may or may not be allowed; verify parse doesn't crash
-    // Accept both success (0) and parse error (1)
+    // Accept both success (0) and parse error (2)
     assert!(
-        code == Some(0) || code == Some(1),
+        code == Some(0) || code == Some(2),
         "unexpected code; got:\n{out}"
     );
 }
@@ -258,7 +258,7 @@ fn call_trailing_comma_before_paren() {
 #[test]
 fn call_leading_comma_in_args() {
     let (out, code) = check_src("let x = foo(, 1);\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -268,7 +268,7 @@ fn call_leading_comma_in_args() {
 #[test]
 fn call_consecutive_commas() {
     let (out, code) = check_src("let x = foo(1,, 2);\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -278,7 +278,7 @@ fn call_consecutive_commas() {
 #[test]
 fn call_empty_named_arg_label() {
     let (out, code) = check_src("let x = foo(:5);\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -288,7 +288,7 @@ fn call_empty_named_arg_label() {
 #[test]
 fn call_duplicate_named_args() {
     let (out, code) = check_src("let x = foo(a: 1, a: 2);\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Duplicate") && out.contains("1:"),
         "expected diagnostic mentioning Duplicate; got:\n{out}"
@@ -298,7 +298,7 @@ fn call_duplicate_named_args() {
 #[test]
 fn call_positional_after_named() {
     let (out, code) = check_src("let x = foo(a: 1, 2);\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("positional") || out.contains("named"),
         "expected diagnostic about arg order; got:\n{out}"
@@ -312,7 +312,7 @@ fn call_positional_after_named() {
 #[test]
 fn index_missing_expression() {
     let (out, code) = check_src("let x = [1, 2, 3];\nlet y = x[];\nprintln(y);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("2:"),
         "expected diagnostic; got:\n{out}"
@@ -322,7 +322,7 @@ fn index_missing_expression() {
 #[test]
 fn index_missing_closing_bracket() {
     let (out, code) = check_src("let x = [1, 2, 3];\nlet y = x[0;\nprintln(y);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("]") && out.contains("2:"),
         "expected diagnostic; got:\n{out}"
@@ -332,7 +332,7 @@ fn index_missing_closing_bracket() {
 #[test]
 fn index_comma_instead_of_close() {
     let (out, code) = check_src("let x = [1, 2, 3];\nlet y = x[0,];\nprintln(y);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("]") && out.contains("2:"),
         "expected diagnostic; got:\n{out}"
@@ -349,7 +349,7 @@ fn index_double_dot_open() {
 #[test]
 fn index_range_missing_closing() {
     let (out, code) = check_src("let x = [1, 2, 3];\nlet y = x[0..2\nprintln(y);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("]") && (out.contains("2:") || out.contains("3:")),
         "expected diagnostic; got:\n{out}"
@@ -364,7 +364,7 @@ fn index_range_missing_closing() {
 fn field_access_missing_name() {
     let (out, code) =
         check_src("struct P { int a }\nlet p = new P { a: 1 };\nlet x = p.;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected field name") && out.contains("3:"),
         "expected diagnostic; got:\n{out}"
@@ -375,7 +375,7 @@ fn field_access_missing_name() {
 fn field_access_illegal_start_char() {
     let (out, code) =
         check_src("struct P { int a }\nlet p = new P { a: 1 };\nlet x = p.@a;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("3:") || out.contains("Expected field"),
         "expected diagnostic; got:\n{out}"
@@ -387,7 +387,7 @@ fn field_access_numeric_out_of_range() {
     let (out, code) = check_src(
         "struct P { int a, int b }\nlet p = new P { a: 1, b: 2 };\nlet x = p.-1;\nprintln(x);\n",
     );
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("non-negative") || out.contains("3:"),
         "expected diagnostic; got:\n{out}"
@@ -401,7 +401,7 @@ fn field_access_numeric_out_of_range() {
 #[test]
 fn struct_literal_missing_name() {
     let (out, code) = check_src("let x = new { a: 1 };\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -410,7 +410,8 @@ fn struct_literal_missing_name() {
 
 #[test]
 fn struct_literal_missing_brace() {
-    let (out, code) = check_src("let x = new Point (1, 2);\nprintln(x);\n");
+    let (out, code) =
+        check_src("struct Point(int, int);\nlet x = new Point (1, 2);\nprintln(x);\n");
     // Tuple-struct constructor syntax parses cleanly.
     assert_eq!(code, Some(0), "tuple-struct ctor should parse; got:\n{out}");
 }
@@ -418,7 +419,7 @@ fn struct_literal_missing_brace() {
 #[test]
 fn struct_literal_unclosed_brace() {
     let (out, code) = check_src("let x = new Point { a: 1, b: 2\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("}") || out.contains("Expected"),
         "expected diagnostic; got:\n{out}"
@@ -428,7 +429,7 @@ fn struct_literal_unclosed_brace() {
 #[test]
 fn struct_literal_missing_field_name() {
     let (out, code) = check_src("let x = new Point { : 1 };\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected field name") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -438,7 +439,7 @@ fn struct_literal_missing_field_name() {
 #[test]
 fn struct_literal_missing_colon() {
     let (out, code) = check_src("let x = new Point { a 1 };\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected") || out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -448,7 +449,7 @@ fn struct_literal_missing_colon() {
 #[test]
 fn struct_literal_missing_value() {
     let (out, code) = check_src("let x = new Point { a: };\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("expected") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -458,7 +459,7 @@ fn struct_literal_missing_value() {
 #[test]
 fn struct_literal_bad_separator() {
     let (out, code) = check_src("let x = new Point { a: 1; b: 2 };\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("}") || out.contains("Expected"),
         "expected diagnostic; got:\n{out}"
@@ -469,7 +470,7 @@ fn struct_literal_bad_separator() {
 fn struct_literal_base_update_unclosed() {
     let (out, code) =
         check_src("let p = new Point { a: 1 };\nlet p2 = new Point { ..p, a: 2\nprintln(p2);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("}") || out.contains("Expected"),
         "expected diagnostic; got:\n{out}"
@@ -483,7 +484,7 @@ fn struct_literal_base_update_unclosed() {
 #[test]
 fn tuple_struct_missing_close_paren() {
     let (out, code) = check_src("let x = new Pair(1, 2;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains(")") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -493,7 +494,7 @@ fn tuple_struct_missing_close_paren() {
 #[test]
 fn tuple_struct_bad_arg_separator() {
     let (out, code) = check_src("let x = new Pair(1; 2);\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains(")") || out.contains("Expected"),
         "expected diagnostic; got:\n{out}"
@@ -507,7 +508,7 @@ fn tuple_struct_bad_arg_separator() {
 #[test]
 fn lexer_illegal_backtick() {
     let (out, code) = check_src("let x = `hello`;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:") || out.contains("Unexpected"),
         "expected diagnostic; got:\n{out}"
@@ -517,7 +518,7 @@ fn lexer_illegal_backtick() {
 #[test]
 fn lexer_illegal_dollar() {
     let (out, code) = check_src("let x = $y;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:") || out.contains("Unexpected"),
         "expected diagnostic; got:\n{out}"
@@ -527,7 +528,7 @@ fn lexer_illegal_dollar() {
 #[test]
 fn lexer_illegal_at_operator_standalone() {
     let (out, code) = check_src("let x = 5 @ 3;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:") || out.contains("Unexpected"),
         "expected diagnostic; got:\n{out}"
@@ -537,13 +538,27 @@ fn lexer_illegal_at_operator_standalone() {
 #[test]
 fn lexer_double_hash() {
     let (out, code) = check_src("let x = ##5;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:") || out.contains("Unexpected"),
         "expected diagnostic; got:\n{out}"
     );
 }
 
+#[test]
+fn lexer_errors_are_all_reported_together() {
+    let (out, code) = check_src("let a = 1;\n$ ~ `\nlet b = 99999999999999999999;\nprintln(a);\n");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
+    for diag in [
+        "2:1: Unexpected character '$'",
+        "2:3: Unexpected character '~'",
+        "2:5: Unexpected character '`'",
+        "3:9: integer literal `99999999999999999999` overflows i64",
+    ] {
+        assert!(out.contains(diag), "missing {diag:?}; got:\n{out}");
+    }
+}
+
 // ============================================================================
 // LEXER ERRORS: UNTERMINATED COMMENTS
 // ============================================================================
@@ -584,9 +599,11 @@ fn lexer_bad_escape_incomplete() {
 #[test]
 fn lexer_unterminated_string() {
     let (out, code) = check_src("let x = \"unclosed");
-    // Characterization: an unterminated string runs to EOF and the
-    // program is accepted.
-    assert_eq!(code, Some(0), "lenient EOF string; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
+    assert!(
+        out.contains("1:9: unterminated string literal"),
+        "expected diagnostic; got:\n{out}"
+    );
 }
 
 // ============================================================================
@@ -610,11 +627,9 @@ fn lexer_binary_literal_empty() {
 #[test]
 fn lexer_float_double_dot() {
     let (out, code) = check_src("let x = 1.2.3;\nprintln(x);\n");
-    // Characterization: the lexer reports the bad float literal as a
-    // diagnostic but `check` still exits 0.
-    assert_eq!(code, Some(0), "double-dot float exit; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
-        out.contains("float literal `1.2.3` is not a valid IEEE 754 value"),
+        out.contains("1:9: float literal `1.2.3` is not a valid IEEE 754 value"),
         "expected diagnostic; got:\n{out}"
     );
 }
@@ -622,7 +637,7 @@ fn lexer_float_double_dot() {
 #[test]
 fn lexer_scientific_notation_empty_exponent() {
     let (out, code) = check_src("let x = 1e;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:") || out.contains("Expected") || out.contains("exponent"),
         "expected diagnostic; got:\n{out}"
@@ -632,7 +647,7 @@ fn lexer_scientific_notation_empty_exponent() {
 #[test]
 fn lexer_scientific_notation_incomplete() {
     let (out, code) = check_src("let x = 1e+;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:") || out.contains("Expected") || out.contains("exponent"),
         "expected diagnostic; got:\n{out}"
@@ -646,7 +661,7 @@ fn lexer_scientific_notation_incomplete() {
 #[test]
 fn range_unclosed_in_array() {
     let (out, code) = check_src("let x = [1..;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("]") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -656,7 +671,7 @@ fn range_unclosed_in_array() {
 #[test]
 fn range_inclusive_unclosed() {
     let (out, code) = check_src("let x = [1..=;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") || out.contains("]"),
         "expected diagnostic; got:\n{out}"
@@ -677,7 +692,7 @@ fn range_double_dot_in_expression_context() {
 #[test]
 fn lambda_missing_pipe() {
     let (out, code) = check_src("let f = |x -> x + 1;\nprintln(f);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:") || out.contains("Expected"),
         "expected diagnostic; got:\n{out}"
@@ -688,7 +703,7 @@ fn lambda_missing_pipe() {
 fn lambda_unclosed() {
     let (out, code) = check_src("let f = |x| x + 1;\nprintln(f);\n");
     // Pipe-delimited lambda syntax is not supported in a let binding.
-    assert_eq!(code, Some(1), "pipe lambda must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "pipe lambda must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression after `=`") && out.contains("1:9"),
         "expected diagnostic; got:\n{out}"
@@ -757,7 +772,7 @@ fn deep_nesting_100_brackets_succeeds() {
 #[test]
 fn chained_comparison_missing_middle() {
     let (out, code) = check_src("let x = 1 < && 3 < 5;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -767,7 +782,7 @@ fn chained_comparison_missing_middle() {
 #[test]
 fn chained_comparison_missing_right() {
     let (out, code) = check_src("let x = 1 < 2 && ;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -781,7 +796,7 @@ fn chained_comparison_missing_right() {
 #[test]
 fn unexpected_eof_in_expression() {
     let (out, code) = check_src("let x = 5 +");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression") || out.contains("1:"),
         "expected diagnostic; got:\n{out}"
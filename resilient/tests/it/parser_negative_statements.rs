@@ -50,7 +50,7 @@ fn check_src(src: &str) -> (String, Option<i32>) {
 #[test]
 fn let_missing_identifier() {
     let (out, code) = check_src("let = 5;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected identifier after 'let'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -60,7 +60,7 @@ fn let_missing_identifier() {
 #[test]
 fn let_missing_equals() {
     let (out, code) = check_src("let x 5;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '=' after identifier") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -70,7 +70,7 @@ fn let_missing_equals() {
 #[test]
 fn let_missing_value_expression() {
     let (out, code) = check_src("let x = ;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected expression after `=`") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -80,7 +80,7 @@ fn let_missing_value_expression() {
 #[test]
 fn let_with_type_annotation_missing_type() {
     let (out, code) = check_src("let x: = 5;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -90,7 +90,7 @@ fn let_with_type_annotation_missing_type() {
 #[test]
 fn let_tuple_destructure_missing_closing_paren() {
     let (out, code) = check_src("let (a, b = 5;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -104,7 +104,7 @@ fn let_tuple_destructure_missing_closing_paren() {
 #[test]
 fn fn_missing_name() {
     let (out, code) = check_src("fn (int x) { return 0; }\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected identifier after 'fn'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -114,7 +114,7 @@ fn fn_missing_name() {
 #[test]
 fn fn_missing_open_paren() {
     let (out, code) = check_src("fn foo int x { return 0; }\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '('") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -124,7 +124,7 @@ fn fn_missing_open_paren() {
 #[test]
 fn fn_missing_close_paren() {
     let (out, code) = check_src("fn foo(int x { return 0; }\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -134,7 +134,7 @@ fn fn_missing_close_paren() {
 #[test]
 fn fn_missing_open_brace() {
     let (out, code) = check_src("fn foo(int x) return 0;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '{'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -144,7 +144,7 @@ fn fn_missing_open_brace() {
 #[test]
 fn fn_missing_close_brace() {
     let (out, code) = check_src("fn foo(int x) { return 0;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -152,9 +152,10 @@ fn fn_missing_close_brace() {
 }
 
 #[test]
-fn fn_param_missing_type() {
-    let (out, code) = check_src("fn foo(x) { return 0; }\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+fn fn_param_missing_name() {
+    // `fn foo(x)` is an untyped parameter; a lone builtin type is not.
+    let (out, code) = check_src("fn foo(int) { return 0; }\nprintln(1);\n");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -164,7 +165,7 @@ fn fn_param_missing_type() {
 #[test]
 fn fn_param_syntax_error() {
     let (out, code) = check_src("fn foo(int x int y) { return 0; }\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -178,7 +179,7 @@ fn fn_param_syntax_error() {
 #[test]
 fn if_missing_open_paren() {
     let (out, code) = check_src("if x > 0 { }\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -188,7 +189,7 @@ fn if_missing_open_paren() {
 #[test]
 fn if_missing_close_paren() {
     let (out, code) = check_src("if (x > 0 { }\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected ')' after if condition") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -198,7 +199,7 @@ fn if_missing_close_paren() {
 #[test]
 fn if_missing_open_brace() {
     let (out, code) = check_src("if (x > 0) return 0;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '{'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -208,7 +209,7 @@ fn if_missing_open_brace() {
 #[test]
 fn if_missing_close_brace() {
     let (out, code) = check_src("if (x > 0) { return 0;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -218,7 +219,7 @@ fn if_missing_close_brace() {
 #[test]
 fn if_else_missing_brace() {
     let (out, code) = check_src("if (x > 0) { } else return 0;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '{'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -232,7 +233,7 @@ fn if_else_missing_brace() {
 #[test]
 fn while_missing_close_paren() {
     let (out, code) = check_src("fn main(int x) { while (x > 0 { } }\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected ')' after while condition") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -242,7 +243,7 @@ fn while_missing_close_paren() {
 #[test]
 fn while_missing_open_brace() {
     let (out, code) = check_src("fn main(int x) { while (x > 0) x = x - 1; }\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '{'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -252,7 +253,7 @@ fn while_missing_open_brace() {
 #[test]
 fn while_with_missing_body() {
     let (out, code) = check_src("fn main(int x) { while (x > 0) }\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '{'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -266,7 +267,7 @@ fn while_with_missing_body() {
 #[test]
 fn for_missing_identifier() {
     let (out, code) = check_src("fn main(int x) { for in [1, 2] { } }\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected identifier after 'for'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -276,7 +277,7 @@ fn for_missing_identifier() {
 #[test]
 fn for_missing_in_keyword() {
     let (out, code) = check_src("fn main(int x) { for i [1, 2] { } }\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected 'in' after 'for") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -286,7 +287,7 @@ fn for_missing_in_keyword() {
 #[test]
 fn for_missing_iterable() {
     let (out, code) = check_src("fn main(int x) { for i in { } }\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -296,7 +297,7 @@ fn for_missing_iterable() {
 #[test]
 fn for_missing_open_brace() {
     let (out, code) = check_src("fn main(int x) { for i in [1, 2] x = i; }\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '{'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -306,7 +307,7 @@ fn for_missing_open_brace() {
 #[test]
 fn for_missing_body_brace() {
     let (out, code) = check_src("fn main(int x) { for i in [1, 2] }\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '{'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -320,7 +321,7 @@ fn for_missing_body_brace() {
 #[test]
 fn struct_missing_open_brace() {
     let (out, code) = check_src("struct Point let x = 5;\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -330,7 +331,7 @@ fn struct_missing_open_brace() {
 #[test]
 fn invalid_keyword_combo() {
     let (out, code) = check_src("const mut x = 5;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -344,7 +345,7 @@ fn invalid_keyword_combo() {
 #[test]
 fn type_alias_missing_name() {
     let (out, code) = check_src("type = int;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected alias name after 'type'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -354,7 +355,7 @@ fn type_alias_missing_name() {
 #[test]
 fn type_alias_missing_equals() {
     let (out, code) = check_src("type MyInt int;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '=' after 'type") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -368,7 +369,7 @@ fn type_alias_missing_equals() {
 #[test]
 fn unsafe_missing_brace() {
     let (out, code) = check_src("fn main(int x) { unsafe let y = 5; }\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '{' after 'unsafe'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -382,7 +383,7 @@ fn unsafe_missing_brace() {
 #[test]
 fn struct_field_missing_type() {
     let (out, code) = check_src("struct Point { x, y }\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -392,7 +393,7 @@ fn struct_field_missing_type() {
 #[test]
 fn invalid_hex_literal() {
     let (out, code) = check_src("let x = 0xG;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -406,7 +407,7 @@ fn invalid_hex_literal() {
 #[test]
 fn use_missing_path() {
     let (out, code) = check_src("use ;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -420,7 +421,7 @@ fn use_missing_path() {
 #[test]
 fn match_missing_fat_arrow() {
     let (out, code) = check_src("fn main(int x) { match x { _ 0 } }\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -434,7 +435,7 @@ fn match_missing_fat_arrow() {
 #[test]
 fn pub_without_valid_target() {
     let (out, code) = check_src("pub let x = 5;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         (out.contains("'pub' must be followed by") || out.contains("followed by"))
             && out.contains("1:"),
@@ -449,7 +450,7 @@ fn pub_without_valid_target() {
 #[test]
 fn impl_missing_name() {
     let (out, code) = check_src("impl { }\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected struct or trait name after 'impl'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -459,7 +460,7 @@ fn impl_missing_name() {
 #[test]
 fn impl_missing_open_brace() {
     let (out, code) = check_src("impl MyStruct }\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("Expected '{'") && out.contains("1:"),
         "expected diagnostic; got:\n{out}"
@@ -469,7 +470,7 @@ fn impl_missing_open_brace() {
 #[test]
 fn impl_missing_close_brace() {
     let (out, code) = check_src("impl MyStruct { fn foo(int x) { } \nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -483,7 +484,7 @@ fn impl_missing_close_brace() {
 #[test]
 fn field_access_on_literal() {
     let (out, code) = check_src("let x = 5.foo;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -493,7 +494,7 @@ fn field_access_on_literal() {
 #[test]
 fn invalid_method_call() {
     let (out, code) = check_src("let x = 5.();\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -507,7 +508,7 @@ fn invalid_method_call() {
 #[test]
 fn array_missing_closing_bracket() {
     let (out, code) = check_src("let x = [1, 2, 3;\nprintln(x);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -517,7 +518,7 @@ fn array_missing_closing_bracket() {
 #[test]
 fn return_outside_function() {
     let (out, code) = check_src("let x = return 5;\nprintln(1);\n");
-    assert_eq!(code, Some(1), "must fail; got:\n{out}");
+    assert_eq!(code, Some(2), "must fail; got:\n{out}");
     assert!(
         out.contains("1:"),
         "expected line position in diagnostic; got:\n{out}"
@@ -44,7 +44,7 @@ fn type_annot_missing_closing_angle_in_generic() {
     let (out, code) = check_src("fn foo<T(int a : T { return a; }\nfoo(5);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing closing angle must fail; got: {}",
         out
     );
@@ -60,7 +60,7 @@ fn type_annot_missing_closing_paren_in_fn_type() {
     let (out, code) = check_src("fn id(fn(int -> int x) { return x; }\nid(nil);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing closing paren in fn type must fail; got: {}",
         out
     );
@@ -76,7 +76,7 @@ fn type_annot_wrong_separator_in_generic_list() {
     let (out, code) = check_src("fn foo<T; U>(T a, U b) { return a; }\nfoo(1, 2);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "semicolon in type params must fail; got: {}",
         out
     );
@@ -92,7 +92,7 @@ fn type_annot_missing_comma_in_generic_list() {
     let (out, code) = check_src("fn foo<T U>(T a, U b) { return a; }\nfoo(1, 2);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing comma in type params must fail; got: {}",
         out
     );
@@ -108,7 +108,7 @@ fn type_annot_missing_bound_name_after_colon() {
     let (out, code) = check_src("fn foo<T : +U>(T a) { return a; }\nfoo(5);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing trait name after colon must fail; got: {}",
         out
     );
@@ -124,7 +124,7 @@ fn type_annot_missing_closing_bracket_in_array_type() {
     let (out, code) = check_src("let x: [int; 5 = [1, 2, 3, 4, 5];\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing bracket in array type must fail; got: {}",
         out
     );
@@ -140,7 +140,7 @@ fn type_annot_missing_semicolon_in_array_type() {
     let (out, code) = check_src("fn foo(int[5 10]) { return 0; }\nfoo(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing semicolon in array type must fail; got: {}",
         out
     );
@@ -156,7 +156,7 @@ fn type_annot_wrong_separator_in_fn_params() {
     let (out, code) = check_src("fn foo(fn(int; int -> int) { return 0; }\nfoo(nil);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "semicolon in fn type params must fail; got: {}",
         out
     );
@@ -175,7 +175,7 @@ fn struct_decl_missing_opening_brace() {
         check_src("struct Point int x int y\nlet p = new Point { x: 1, y: 2 };\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing opening brace in struct decl must fail; got: {}",
         out
     );
@@ -192,7 +192,7 @@ fn struct_decl_missing_closing_brace() {
         check_src("struct Point { int x, int y\nlet p = new Point { x: 1 };\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing closing brace in struct decl must fail; got: {}",
         out
     );
@@ -207,7 +207,7 @@ fn struct_decl_missing_closing_brace() {
 fn struct_decl_missing_field_type() {
     let (out, code) =
         check_src("struct Point { x int y }\nlet p = new Point { x: 1, y: 2 };\nprint(0);\n");
-    assert_eq!(code, Some(1), "missing field type must fail; got: {}", out);
+    assert_eq!(code, Some(2), "missing field type must fail; got: {}", out);
     assert!(
         out.contains("type") || out.contains("field"),
         "expected diagnostic about field type; got: {}",
@@ -219,7 +219,7 @@ fn struct_decl_missing_field_type() {
 fn struct_decl_missing_field_name() {
     let (out, code) =
         check_src("struct Point { int int y }\nlet p = new Point { y: 1 };\nprint(0);\n");
-    assert_eq!(code, Some(1), "missing field name must fail; got: {}", out);
+    assert_eq!(code, Some(2), "missing field name must fail; got: {}", out);
     assert!(
         out.contains("name") || out.contains("field"),
         "expected diagnostic about field name; got: {}",
@@ -233,7 +233,7 @@ fn struct_decl_duplicate_field_name() {
         check_src("struct Point { int x, int x }\nlet p = new Point { x: 1 };\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "duplicate field name must fail; got: {}",
         out
     );
@@ -250,7 +250,7 @@ fn struct_decl_wrong_separator() {
         check_src("struct Point { int x; int y }\nlet p = new Point { x: 1, y: 2 };\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "semicolon instead of comma in struct must fail; got: {}",
         out
     );
@@ -268,7 +268,7 @@ fn struct_decl_trailing_garbage() {
     );
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "trailing garbage in struct must fail; got: {}",
         out
     );
@@ -285,7 +285,7 @@ fn struct_decl_trailing_garbage() {
 fn struct_literal_missing_field_value() {
     let (out, code) =
         check_src("struct Point { int x, int y }\nlet p = new Point { x: , y: 2 };\nprint(0);\n");
-    assert_eq!(code, Some(1), "missing field value must fail; got: {}", out);
+    assert_eq!(code, Some(2), "missing field value must fail; got: {}", out);
     assert!(
         out.contains("value") || out.contains("Expected") || out.contains(","),
         "expected diagnostic about missing value; got: {}",
@@ -299,7 +299,7 @@ fn struct_literal_unknown_field() {
         check_src("struct Point { int x, int y }\nlet p = new Point { z: 5 };\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "unknown field in literal must fail; got: {}",
         out
     );
@@ -316,7 +316,7 @@ fn struct_literal_missing_closing_brace() {
         check_src("struct Point { int x, int y }\nlet p = new Point { x: 1, y: 2;\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing closing brace in struct literal must fail; got: {}",
         out
     );
@@ -335,7 +335,7 @@ fn impl_block_missing_type_name() {
         check_src("struct P { int x }\nimpl { fn foo(self int) { return 0; } }\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing type in impl must fail; got: {}",
         out
     );
@@ -352,7 +352,7 @@ fn impl_block_missing_method_fn_keyword() {
         check_src("struct P { int x }\nimpl P { foo(self int) { return 0; } }\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing 'fn' in impl method must fail; got: {}",
         out
     );
@@ -367,7 +367,7 @@ fn impl_block_missing_method_fn_keyword() {
 fn impl_block_missing_method_name() {
     let (out, code) =
         check_src("struct P { int x }\nimpl P { fn (self int) { return 0; } }\nprint(0);\n");
-    assert_eq!(code, Some(1), "missing method name must fail; got: {}", out);
+    assert_eq!(code, Some(2), "missing method name must fail; got: {}", out);
     assert!(
         out.contains("name") || out.contains("identifier"),
         "expected diagnostic about method name; got: {}",
@@ -382,7 +382,7 @@ fn match_missing_fat_arrow_after_pattern() {
     let (out, code) = check_src("fn f(int x) { return match x { 1 2 }; }\nf(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing fat arrow after pattern must fail; got: {}",
         out
     );
@@ -398,7 +398,7 @@ fn match_missing_closing_brace() {
     let (out, code) = check_src("fn f(int x) { return match x { 1 => 2; }; }\nf(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing closing brace in match must fail; got: {}",
         out
     );
@@ -412,7 +412,7 @@ fn match_missing_closing_brace() {
 #[test]
 fn match_eof_inside_arms() {
     let (out, code) = check_src("fn f(int x) { return match x { 1 => 2\n");
-    assert_eq!(code, Some(1), "EOF inside match must fail; got: {}", out);
+    assert_eq!(code, Some(2), "EOF inside match must fail; got: {}", out);
     assert!(
         out.contains("EOF") || out.contains("Unexpected"),
         "expected diagnostic about EOF; got: {}",
@@ -427,7 +427,7 @@ fn enum_decl_missing_variant_name() {
     let (out, code) = check_src("enum Color { (int), Green }\nlet c = Green;\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing variant name must fail; got: {}",
         out
     );
@@ -441,7 +441,7 @@ fn enum_decl_missing_variant_name() {
 #[test]
 fn enum_decl_duplicate_variant() {
     let (out, code) = check_src("enum Color { Red, Red }\nlet c = Red;\nprint(0);\n");
-    assert_eq!(code, Some(1), "duplicate variant must fail; got: {}", out);
+    assert_eq!(code, Some(2), "duplicate variant must fail; got: {}", out);
     assert!(
         out.contains("Duplicate") || out.contains("duplicate"),
         "expected diagnostic about duplicate; got: {}",
@@ -454,7 +454,7 @@ fn enum_decl_missing_closing_brace() {
     let (out, code) = check_src("enum Color { Red, Green\nlet c = Red;\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing closing brace in enum must fail; got: {}",
         out
     );
@@ -470,7 +470,7 @@ fn enum_decl_missing_opening_brace() {
     let (out, code) = check_src("enum Color Red, Green\nlet c = Red;\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing opening brace in enum must fail; got: {}",
         out
     );
@@ -486,7 +486,7 @@ fn enum_decl_missing_opening_brace() {
 #[test]
 fn trait_decl_missing_name() {
     let (out, code) = check_src("trait { fn foo(self int); }\nprint(0);\n");
-    assert_eq!(code, Some(1), "missing trait name must fail; got: {}", out);
+    assert_eq!(code, Some(2), "missing trait name must fail; got: {}", out);
     assert!(
         out.contains("identifier") || out.contains("trait"),
         "expected diagnostic about trait name; got: {}",
@@ -499,7 +499,7 @@ fn trait_decl_missing_closing_brace() {
     let (out, code) = check_src("trait Foo { fn bar(self int); let x = 5;\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing closing brace in trait must fail; got: {}",
         out
     );
@@ -517,7 +517,7 @@ fn generic_params_invalid_char_in_param() {
     let (out, code) = check_src("fn foo<123>(int x) { return x; }\nfoo(5);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "numeric literal in type params must fail; got: {}",
         out
     );
@@ -533,7 +533,7 @@ fn generic_params_missing_closing_angle() {
     let (out, code) = check_src("fn foo<T(int x) { return x; }\nfoo(5);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing closing angle in type params must fail; got: {}",
         out
     );
@@ -549,7 +549,7 @@ fn generic_params_extra_colon() {
     let (out, code) = check_src("fn foo<T::U>(T a) { return a; }\nfoo(5);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "extra separator in type params must fail; got: {}",
         out
     );
@@ -567,7 +567,7 @@ fn match_pattern_range_missing_hi() {
     let (out, code) = check_src("fn f(int x) { return match x { 1..a => 2 }; }\nf(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "invalid range pattern must fail; got: {}",
         out
     );
@@ -583,7 +583,7 @@ fn match_pattern_unsupported_start() {
     let (out, code) = check_src("fn f(int x) { return match x { @foo => 2 }; }\nf(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "unsupported pattern start must fail; got: {}",
         out
     );
@@ -601,7 +601,7 @@ fn tuple_literal_missing_comma() {
     let (out, code) = check_src("fn f() { let t = (1 2); return 0; }\nf();\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing comma in tuple literal must fail; got: {}",
         out
     );
@@ -617,7 +617,7 @@ fn tuple_literal_missing_closing_paren() {
     let (out, code) = check_src("fn f() { let t = (1, 2; return 0; }\nf();\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing closing paren in tuple literal must fail; got: {}",
         out
     );
@@ -635,7 +635,7 @@ fn ref_type_missing_close_bracket_in_region() {
     let (out, code) = check_src("fn foo(&[L int x) { return 0; }\nfoo(nil);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing bracket in region label must fail; got: {}",
         out
     );
@@ -649,7 +649,7 @@ fn ref_type_missing_close_bracket_in_region() {
 #[test]
 fn ref_type_missing_region_name() {
     let (out, code) = check_src("fn foo(&[] int x) { return 0; }\nfoo(nil);\n");
-    assert_eq!(code, Some(1), "missing region name must fail; got: {}", out);
+    assert_eq!(code, Some(2), "missing region name must fail; got: {}", out);
     assert!(
         out.contains("region") || out.contains("identifier"),
         "expected diagnostic about region name; got: {}",
@@ -664,7 +664,7 @@ fn array_type_missing_semicolon_before_size() {
     let (out, code) = check_src("fn foo([int 5] x) { return 0; }\nfoo(nil);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing semicolon in array type must fail; got: {}",
         out
     );
@@ -678,7 +678,7 @@ fn array_type_missing_semicolon_before_size() {
 #[test]
 fn array_type_invalid_size() {
     let (out, code) = check_src("fn foo([int; -5] x) { return 0; }\nfoo(nil);\n");
-    assert_eq!(code, Some(1), "negative array size must fail; got: {}", out);
+    assert_eq!(code, Some(2), "negative array size must fail; got: {}", out);
     assert!(
         out.contains("non-negative") || out.contains("integer"),
         "expected diagnostic about size; got: {}",
@@ -693,7 +693,7 @@ fn fn_type_missing_closing_paren() {
     let (out, code) = check_src("fn foo(fn(int, int -> int x) { return x; }\nfoo(nil);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing closing paren in fn type must fail; got: {}",
         out
     );
@@ -709,7 +709,7 @@ fn fn_type_missing_arrow() {
     let (out, code) = check_src("fn foo(fn(int, int int x) { return x; }\nfoo(nil);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "missing arrow in fn type must fail; got: {}",
         out
     );
@@ -727,7 +727,7 @@ fn struct_decl_missing_identifier() {
     let (out, code) = check_src("struct 123 { int x }\nprint(0);\n");
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "numeric literal as struct name must fail; got: {}",
         out
     );
@@ -741,7 +741,7 @@ fn struct_decl_missing_identifier() {
 #[test]
 fn new_struct_missing_name() {
     let (out, code) = check_src("fn f() { let p = new 123 { x: 1 }; return 0; }\nf();\n");
-    assert_eq!(code, Some(1), "numeric in new must fail; got: {}", out);
+    assert_eq!(code, Some(2), "numeric in new must fail; got: {}", out);
     assert!(
         out.contains("struct") || out.contains("name"),
         "expected diagnostic about struct name; got: {}",
@@ -0,0 +1,155 @@
+//! `rz add <name> --path/--git <source>` end-to-end: the dependency
+//! lands in `resilient.toml` and `resilient.lock`, and a program
+//! imports its modules with `use "<name>/<module>";`.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_parent(tag: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let p = std::env::temp_dir().join(format!("res_pkg_add_{}_{}_{}", tag, std::process::id(), n));
+    std::fs::create_dir_all(&p).expect("mkdir pkg add tmp");
+    p
+}
+
+/// An app with an empty `[dependencies]` and a library next to it
+/// whose `src/greet.rz` exports `greet()`.
+fn scaffold(parent: &Path) -> (PathBuf, PathBuf) {
+    let app = parent.join("app");
+    let lib = parent.join("greeter");
+    std::fs::create_dir_all(app.join("src")).expect("mkdir app src");
+    std::fs::create_dir_all(lib.join("src")).expect("mkdir lib src");
+    std::fs::write(
+        app.join("resilient.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\n",
+    )
+    .expect("write app manifest");
+    std::fs::write(
+        app.join("src/main.rz"),
+        "use \"greeter/greet\";\nprintln(greet());\n",
+    )
+    .expect("write app main");
+    std::fs::write(
+        lib.join("resilient.toml"),
+        "[package]\nname = \"greeter\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write lib manifest");
+    std::fs::write(
+        lib.join("src/greet.rz"),
+        "pub fn greet() -> string { return \"hello from greeter\"; }\n",
+    )
+    .expect("write lib module");
+    (app, lib)
+}
+
+fn run(args: &[&str], dir: &Path, home: &Path) -> Output {
+    Command::new(bin())
+        .args(args)
+        .current_dir(dir)
+        .env("HOME", home)
+        .output()
+        .expect("spawn rz")
+}
+
+fn assert_ok(out: &Output, what: &str) {
+    assert!(
+        out.status.success(),
+        "{what} failed; stdout={} stderr={}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn add_path_dependency_and_import_it_by_string_path() {
+    let parent = tmp_parent("path");
+    let (app, _) = scaffold(&parent);
+
+    let out = run(&["add", "greeter", "--path", "../greeter"], &app, &parent);
+    assert_ok(&out, "rz add --path");
+    let manifest = std::fs::read_to_string(app.join("resilient.toml")).expect("read manifest");
+    assert!(
+        manifest.contains("greeter = { path = \"../greeter\" }"),
+        "{manifest}"
+    );
+    let lock = std::fs::read_to_string(app.join("resilient.lock")).expect("read lockfile");
+    assert!(lock.contains("source = \"path:../greeter\""), "{lock}");
+
+    let out = run(&["src/main.rz"], &app, &parent);
+    assert_ok(&out, "running the app");
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("hello from greeter\n"),
+        "{}",
+        String::from_utf8_lossy(&out.stdout)
+    );
+
+    let _ = std::fs::remove_dir_all(&parent);
+}
+
+#[test]
+fn add_git_dependency_clones_into_the_home_cache() {
+    let parent = tmp_parent("git");
+    let (app, lib) = scaffold(&parent);
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(&lib)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    };
+    let committed = git(&["init", "-q"])
+        && git(&["add", "."])
+        && git(&[
+            "-c",
+            "user.name=t",
+            "-c",
+            "user.email=t@t",
+            "commit",
+            "-qm",
+            "init",
+        ]);
+    if !committed {
+        eprintln!("skipping: git is unavailable");
+        let _ = std::fs::remove_dir_all(&parent);
+        return;
+    }
+
+    let url = lib.to_string_lossy().to_string();
+    let out = run(&["pkg", "add", "greeter", "--git", &url], &app, &parent);
+    assert_ok(&out, "rz pkg add --git");
+    let lock = std::fs::read_to_string(app.join("resilient.lock")).expect("read lockfile");
+    assert!(lock.contains(&format!("source = \"git:{url}\"")), "{lock}");
+    let cache = parent.join(".resilient/cache/git");
+    assert!(
+        std::fs::read_dir(&cache).is_ok_and(|mut d| d.next().is_some()),
+        "expected a clone under {}",
+        cache.display()
+    );
+
+    let out = run(&["src/main.rz"], &app, &parent);
+    assert_ok(&out, "running the app");
+    assert!(String::from_utf8_lossy(&out.stdout).contains("hello from greeter\n"));
+
+    let _ = std::fs::remove_dir_all(&parent);
+}
+
+#[test]
+fn add_rejects_two_sources() {
+    let parent = tmp_parent("two_sources");
+    let (app, _) = scaffold(&parent);
+    let out = run(
+        &["add", "greeter", "path:../greeter", "--git", "https://x"],
+        &app,
+        &parent,
+    );
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("takes one source"));
+    let _ = std::fs::remove_dir_all(&parent);
+}
@@ -0,0 +1,86 @@
+//! Builtin packages: `resilient::plugin` and `rz --plugin`.
+
+use std::process::Command;
+
+use resilient::bridge::{FromResilient, IntoResilient};
+use resilient::interpreter;
+use resilient::parser;
+use resilient::plugin::{self, PluginError, Registry};
+use resilient::typechecker::TypeChecker;
+
+fn register(registry: &mut Registry) {
+    registry
+        .builtin("plugin_smoke_double", |args| {
+            let n = i64::from_resilient(args[0].clone()).map_err(|e| e.to_string())?;
+            Ok((n * 2).into_resilient())
+        })
+        .builtin("plugin_smoke_fail", |_| {
+            Err("plugin_smoke_fail: no device".to_string())
+        });
+}
+
+#[test]
+fn added_builtins_typecheck_and_run() {
+    plugin::add(register).unwrap();
+
+    let src = "let n = plugin_smoke_double(21);\nprintln(n);\n";
+    let program = parser::parse_source(src).unwrap();
+    TypeChecker::new().check_program(&program).unwrap();
+    let result = interpreter::run(&program);
+    assert!(result.ok, "{:?}", result.errors);
+    assert_eq!(result.stdout, "42\n");
+
+    let result = interpreter::run_program("plugin_smoke_fail();");
+    assert!(!result.ok);
+    assert!(
+        result.errors[0].contains("plugin_smoke_fail: no device"),
+        "{:?}",
+        result.errors
+    );
+}
+
+#[test]
+fn a_builtin_cannot_be_redefined() {
+    let err = plugin::add(|registry| {
+        registry.builtin("println", |_| Ok(().into_resilient()));
+    })
+    .unwrap_err();
+    assert_eq!(
+        err,
+        PluginError::Duplicate {
+            plugin: "<static>".to_string(),
+            name: "println".to_string(),
+        }
+    );
+}
+
+fn rz_with_plugin(path: &str) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_rz"))
+        .args(["--plugin", path, "-e", "println(1);"])
+        .output()
+        .expect("spawn rz")
+}
+
+#[cfg(feature = "plugins")]
+#[test]
+fn a_missing_plugin_is_a_usage_error() {
+    let out = rz_with_plugin("/nonexistent/libplugin_smoke.so");
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("plugin `/nonexistent/libplugin_smoke.so`: cannot open library"),
+        "{stderr}"
+    );
+}
+
+#[cfg(not(feature = "plugins"))]
+#[test]
+fn plugin_flag_requires_the_plugins_feature() {
+    let out = rz_with_plugin("libplugin_smoke.so");
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("Backend-limited: --plugin requires the `plugins` feature"),
+        "{stderr}"
+    );
+}
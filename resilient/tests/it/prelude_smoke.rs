@@ -0,0 +1,60 @@
+//! The prelude: functions from `prelude.rz` on every backend, a
+//! program's own declaration winning, and `--no-prelude`.
+
+use std::process::{Command, Output};
+
+fn rz(args: &[&str], src: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rz"))
+        .args(args)
+        .args(["-e", src])
+        .output()
+        .expect("spawn rz")
+}
+
+const PROGRAM: &str = "\
+let polls = 0;
+fn poll() -> Result {
+    polls = polls + 1;
+    if polls < 3 { return Err(\"busy\"); }
+    return Ok(polls * 10);
+}
+println(retry_or(poll, 5, -1));
+println(format_bytes(1536));
+println(format_duration_ms(125000));
+println(in_range(clamp_step(0, 100, 15), 10, 20));
+";
+
+#[test]
+fn prelude_functions_run_on_every_backend() {
+    for args in [&[][..], &["--vm"], &["-t"]] {
+        let out = rz(args, PROGRAM);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(out.status.success(), "{args:?}: {stdout}");
+        assert!(
+            stdout.contains("30\n1.5 KiB\n2m05s\ntrue\n"),
+            "{args:?}: {stdout}"
+        );
+    }
+}
+
+#[test]
+fn a_program_can_replace_a_prelude_function() {
+    let src = "fn format_bytes(int n) -> string { return \"mine\"; }\nprintln(format_bytes(1));";
+    for args in [&[][..], &["--vm"]] {
+        let out = rz(args, src);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(out.status.success(), "{args:?}: {stdout}");
+        assert!(stdout.contains("mine\n"), "{args:?}: {stdout}");
+    }
+}
+
+#[test]
+fn no_prelude_leaves_the_functions_out() {
+    let out = rz(&["--no-prelude"], "println(format_bytes(2048));");
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("Undefined variable 'format_bytes'"),
+        "{stderr}"
+    );
+}
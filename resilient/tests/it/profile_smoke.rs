@@ -0,0 +1,67 @@
+//! `--profile` prints calls, total and self time per user function.
+
+use std::process::{Command, Output};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn run(args: &[&str], src: &str) -> Output {
+    let path = std::env::temp_dir().join(format!(
+        "res_profile_{}_{}.rz",
+        std::process::id(),
+        args.join("_").replace(['-', '='], "")
+    ));
+    std::fs::write(&path, src).unwrap();
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+/// `(function, calls)` for each row of the table.
+fn rows(out: &Output) -> Vec<(String, u64)> {
+    String::from_utf8_lossy(&out.stderr)
+        .lines()
+        .filter_map(|l| l.strip_prefix("[profile] "))
+        .skip(1)
+        .map(|row| {
+            let cols: Vec<&str> = row.split_whitespace().collect();
+            (cols[0].to_string(), cols[1].parse().unwrap())
+        })
+        .collect()
+}
+
+const FIB: &str = "fn fib(int n) -> int {\n    if n < 2 { return n; }\n    return fib(n - 1) + fib(n - 2);\n}\nfn twice(int x) -> int {\n    return fib(x) * 2;\n}\nprintln(twice(10));\n";
+
+#[test]
+fn each_function_gets_a_row_with_its_call_count() {
+    let out = run(&["--profile", "--no-typecheck"], FIB);
+    assert_eq!(out.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("[profile] function   calls   total ms    self ms"),
+        "{stderr}"
+    );
+    let mut rows = rows(&out);
+    rows.sort();
+    assert_eq!(rows, [("fib".to_string(), 177), ("twice".to_string(), 1)]);
+}
+
+#[test]
+fn a_failed_run_still_prints_its_profile() {
+    let src = "fn boom(int n) -> int {\n    return 10 / n;\n}\nprintln(boom(0));\n";
+    let out = run(&["--profile", "--no-typecheck"], src);
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(rows(&out), [("boom".to_string(), 1)]);
+}
+
+#[test]
+fn profile_is_rejected_with_the_vm() {
+    let out = run(&["--profile", "--vm"], FIB);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--profile follows the tree-walking"));
+}
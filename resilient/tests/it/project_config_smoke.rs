@@ -0,0 +1,102 @@
+//! Settings shared through `resilient.toml` apply to `rz`, `rz check`
+//! and `rz fmt`, and command-line flags win over them.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn rz(args: &[&str], cwd: &Path) -> Output {
+    Command::new(bin())
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .expect("spawn rz")
+}
+
+/// A project under the temp dir with `settings` after `[package]`.
+fn project(label: &str, settings: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("res_cfg_{}_{}", label, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("resilient.toml"),
+        format!("[package]\nname = \"cfg\"\n\n{settings}"),
+    )
+    .unwrap();
+    root
+}
+
+#[test]
+fn strictness_makes_type_errors_fatal_unless_a_flag_says_otherwise() {
+    let root = project("strict", "[check]\nstrictness = \"fatal\"\n");
+    std::fs::write(
+        root.join("src/bad.rz"),
+        "let x: int = \"s\";\nprintln(1);\n",
+    )
+    .unwrap();
+
+    let out = rz(&["src/bad.rz"], &root);
+    assert_eq!(out.status.code(), Some(2), "{out:?}");
+    assert!(!String::from_utf8_lossy(&out.stdout).contains("executed successfully"));
+
+    let out = rz(&["--no-typecheck", "src/bad.rz"], &root);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        "1\nProgram executed successfully\n"
+    );
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn live_retries_set_the_default_budget() {
+    let root = project("live", "[live]\nretries = 1\n");
+    std::fs::write(
+        root.join("src/main.rz"),
+        "fn div(int a, int b) -> int {\n    return a / b;\n}\nlive {\n    println(div(10, live_retries()));\n}\n",
+    )
+    .unwrap();
+    let out = rz(&["src/main.rz"], &root);
+    assert_eq!(out.status.code(), Some(1), "{out:?}");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Live block failed after 1 attempts"));
+
+    // With the built-in three, the second attempt succeeds.
+    std::fs::write(root.join("resilient.toml"), "[package]\nname = \"cfg\"\n").unwrap();
+    let out = rz(&["src/main.rz"], &root);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn fmt_uses_the_project_indent_and_the_flag_overrides_it() {
+    let root = project("fmt", "[fmt]\nindent = 2\n");
+    std::fs::write(root.join("src/f.rz"), "fn f(int n) -> int { return n; }\n").unwrap();
+    let out = rz(&["fmt", "src/f.rz"], &root);
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        "fn f(int n) -> int {\n  return n;\n}\n"
+    );
+    let out = rz(&["fmt", "--indent", "4", "src/f.rz"], &root);
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        "fn f(int n) -> int {\n    return n;\n}\n"
+    );
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn a_bad_setting_is_a_warning_with_its_line() {
+    let root = project("bad", "[verify]\ntimeot_ms = 5\n");
+    std::fs::write(root.join("src/ok.rz"), "println(1);\n").unwrap();
+    let out = rz(&["check", "src/ok.rz"], &root);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    assert!(
+        String::from_utf8_lossy(&out.stderr)
+            .contains("resilient.toml:5: unknown setting `timeot_ms` in [verify]"),
+        "{out:?}"
+    );
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,103 @@
+//! Running and checking a project directory, several files at once,
+//! and type errors and warnings reported in the `use`d file they
+//! come from.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn rz(args: &[&str], cwd: &Path) -> Output {
+    Command::new(bin())
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .expect("spawn rz")
+}
+
+/// `<tmp>/<label>/proj` with a manifest, `src/main.rz` using
+/// `src/util.rz`, and `util_body` as the latter.
+fn project(label: &str, util_body: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("res_proj_{}_{}", label, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let proj = root.join("proj");
+    std::fs::create_dir_all(proj.join("src")).unwrap();
+    std::fs::write(proj.join("resilient.toml"), "[package]\nname = \"proj\"\n").unwrap();
+    std::fs::write(
+        proj.join("src/main.rz"),
+        "use \"util.rz\";\nprintln(two());\n",
+    )
+    .unwrap();
+    std::fs::write(proj.join("src/util.rz"), util_body).unwrap();
+    root
+}
+
+#[test]
+fn a_project_directory_runs_its_entry() {
+    let root = project("run", "fn two() -> int {\n    return 2;\n}\n");
+    let out = rz(&["proj"], &root);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        "2\nProgram executed successfully\n"
+    );
+
+    let out = rz(&["check", "-q", "proj"], &root);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+
+    let out = rz(&["proj/src"], &root);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("without a resilient.toml"));
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn a_type_error_is_reported_in_the_module_it_comes_from() {
+    let root = project("attr", "fn two() -> int {\n    return \"two\";\n}\n");
+    for args in [&["check", "proj"][..], &["--typecheck", "proj"][..]] {
+        let out = rz(args, &root);
+        assert_eq!(out.status.code(), Some(2), "{args:?}");
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(
+            stderr.contains("proj/src/util.rz:2:12: return type mismatch"),
+            "{args:?}: {stderr}"
+        );
+        assert!(stderr.contains("return \"two\";"), "{args:?}: {stderr}");
+        assert!(!stderr.contains("main.rz:2:12"), "{args:?}: {stderr}");
+    }
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn a_warning_is_reported_in_the_module_it_comes_from() {
+    let root = project(
+        "warn",
+        "fn two() -> int {\n    return 2;\n}\n\nfn three() -> int {\n    return 3;\n}\n",
+    );
+    for args in [&["check", "proj"][..], &["proj"][..]] {
+        let out = rz(args, &root);
+        assert_eq!(out.status.code(), Some(0), "{args:?}");
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(
+            stderr.contains("proj/src/util.rz:5:1: warning[unused]: function `three`"),
+            "{args:?}: {stderr}"
+        );
+        assert!(!stderr.contains("main.rz:5:1"), "{args:?}: {stderr}");
+    }
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn check_takes_several_files_and_exits_with_the_worst_status() {
+    let root = project("many", "fn two() -> int {\n    return 2;\n}\n");
+    std::fs::write(root.join("bad.rz"), "let x: int = \"no\";\n").unwrap();
+    let out = rz(&["check", "-q", "proj", "proj/src/util.rz"], &root);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+
+    let out = rz(&["check", "bad.rz", "proj"], &root);
+    assert_eq!(out.status.code(), Some(2), "{out:?}");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("bad.rz:1:"));
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -78,7 +78,7 @@ main(0);\n";
     let output = run_check("err", src);
     assert_eq!(
         output.status.code(),
-        Some(1),
+        Some(2),
         "expected typecheck failure; stdout={} stderr={}",
         String::from_utf8_lossy(&output.stdout),
         String::from_utf8_lossy(&output.stderr)
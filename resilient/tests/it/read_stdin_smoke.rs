@@ -0,0 +1,87 @@
+//! Integration tests for `read_line()` / `read_int()`: lines come from
+//! piped stdin, and end of input or a non-integer line fails with a
+//! catchable `EndOfInput` / `InvalidInput` on both runtimes.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("res_read_stdin_{}_{}.rz", std::process::id(), n));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run_with_stdin(body: &str, stdin: &str, args: &[&str]) -> Output {
+    let path = tmp_file(body);
+    let mut child = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn rz");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let out = child.wait_with_output().expect("wait for rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+const PROGRAM: &str = "let name = read_line();\n\
+let n = read_int();\n\
+println(name + \" \" + to_string(n * 2));\n\
+try {\n\
+    let m = read_int();\n\
+} catch InvalidInput {\n\
+    println(\"not a number\");\n\
+}\n\
+try {\n\
+    let rest = read_line();\n\
+} catch EndOfInput {\n\
+    println(\"done\");\n\
+}\n";
+
+#[test]
+fn reads_lines_and_catches_typed_failures_on_both_runtimes() {
+    for args in [&[][..], &["--vm"][..]] {
+        let out = run_with_stdin(PROGRAM, "ada\r\n 21 \nabc\n", args);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(
+            out.status.success(),
+            "{:?} stderr: {}",
+            args,
+            String::from_utf8_lossy(&out.stderr)
+        );
+        assert!(
+            stdout.contains("ada 42\nnot a number\ndone"),
+            "{:?} stdout: {}",
+            args,
+            stdout
+        );
+    }
+}
+
+#[test]
+fn uncaught_end_of_input_is_a_runtime_error() {
+    let out = run_with_stdin("let s = read_line();\nprintln(s);\n", "", &[]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("read_line: EndOfInput: stdin is closed"),
+        "stderr: {}",
+        stderr
+    );
+}
@@ -0,0 +1,108 @@
+//! Integration tests for `--record` / `--replay`: a replay reproduces
+//! the recorded run's random numbers, clock reads, and stdin without
+//! the original inputs, and warns when the program no longer follows
+//! the trace.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_path(ext: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "res_record_replay_{}_{}.{}",
+        std::process::id(),
+        n,
+        ext
+    ))
+}
+
+fn run(src: &Path, args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(bin())
+        .args(args)
+        .arg(src)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn rz");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(stdin.as_bytes())
+        .expect("write stdin");
+    child.wait_with_output().expect("wait rz")
+}
+
+const NONDETERMINISTIC: &str = "let name = read_line();\n\
+println(\"hello \" + name);\n\
+println(random_int(0, 1000000));\n\
+println(clock_ms());\n";
+
+#[test]
+fn replay_reproduces_the_recorded_run() {
+    let src = tmp_path("rz");
+    let trace = tmp_path("bin");
+    std::fs::write(&src, NONDETERMINISTIC).unwrap();
+    let trace_arg = trace.to_str().unwrap();
+
+    let recorded = run(&src, &["--record", trace_arg], "alice\n");
+    assert!(
+        recorded.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&recorded.stderr)
+    );
+    // No stdin this time: the line comes from the trace.
+    let replayed = run(&src, &["--replay", trace_arg], "");
+    let _ = std::fs::remove_file(&src);
+    let _ = std::fs::remove_file(&trace);
+
+    let stderr = String::from_utf8_lossy(&replayed.stderr);
+    assert!(replayed.status.success(), "stderr: {}", stderr);
+    assert!(!stderr.contains("diverged"), "stderr: {}", stderr);
+    assert_eq!(
+        String::from_utf8_lossy(&replayed.stdout),
+        String::from_utf8_lossy(&recorded.stdout)
+    );
+    assert!(String::from_utf8_lossy(&replayed.stdout).starts_with("hello alice\n"));
+}
+
+#[test]
+fn replay_warns_when_the_program_diverges() {
+    let src = tmp_path("rz");
+    let trace = tmp_path("bin");
+    let trace_arg = trace.to_str().unwrap();
+    std::fs::write(&src, "println(random_int(0, 10));\n").unwrap();
+    let recorded = run(&src, &["--record", trace_arg], "");
+    assert!(recorded.status.success());
+
+    // The edited program reads stdin the trace never saw.
+    std::fs::write(&src, "println(read_line());\n").unwrap();
+    let replayed = run(&src, &["--replay", trace_arg], "live\n");
+    let _ = std::fs::remove_file(&src);
+    let _ = std::fs::remove_file(&trace);
+
+    let stderr = String::from_utf8_lossy(&replayed.stderr);
+    assert!(stderr.contains("replay diverged"), "stderr: {}", stderr);
+    assert!(String::from_utf8_lossy(&replayed.stdout).contains("live"));
+}
+
+#[test]
+fn replay_rejects_a_file_that_is_not_a_trace() {
+    let src = tmp_path("rz");
+    let bogus = tmp_path("bin");
+    std::fs::write(&src, "println(1);\n").unwrap();
+    std::fs::write(&bogus, "not a trace").unwrap();
+    let out = run(&src, &["--replay", bogus.to_str().unwrap()], "");
+    let _ = std::fs::remove_file(&src);
+    let _ = std::fs::remove_file(&bogus);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("bad magic"));
+}
@@ -1,4 +1,5 @@
-//! RES-3205: REPL help documents the help-word usage form.
+//! RES-3205: `rz repl` takes no positional words, so `rz repl help`
+//! is a usage error that points at `rz repl --help`.
 
 use std::process::Command;
 
@@ -7,7 +8,7 @@ fn bin() -> &'static str {
 }
 
 #[test]
-fn repl_help_documents_help_word_usage() {
+fn repl_help_word_points_at_the_help_flag() {
     let output = Command::new(bin())
         .args(["repl", "help"])
         .output()
@@ -15,27 +16,24 @@ fn repl_help_documents_help_word_usage() {
 
     assert_eq!(
         output.status.code(),
-        Some(0),
-        "repl help should exit 0; stdout={} stderr={}",
+        Some(2),
+        "repl help word should be a usage error; stdout={} stderr={}",
         String::from_utf8_lossy(&output.stdout),
         String::from_utf8_lossy(&output.stderr)
     );
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
     for expected in [
-        "USAGE:\n    rz repl [--examples-dir DIR]",
-        "rz repl --help",
-        "rz repl help",
-        "FLAGS:",
-        "--examples-dir DIR",
+        "unexpected argument 'help'",
+        "Usage: rz repl [OPTIONS]",
+        "'--help'",
     ] {
         assert!(
-            stdout.contains(expected),
-            "repl help missing {expected:?}; got:\n{stdout}"
+            stderr.contains(expected),
+            "repl usage error missing {expected:?}; got:\n{stderr}"
         );
     }
-    let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(
         !stderr.contains("seed="),
-        "repl help copy path should not print seed banner; got:\n{stderr}"
+        "repl usage error should not print seed banner; got:\n{stderr}"
     );
 }
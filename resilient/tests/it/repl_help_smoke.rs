@@ -21,9 +21,9 @@ fn assert_repl_help_without_seed(args: &[&str]) {
     );
     let stdout = String::from_utf8_lossy(&output.stdout);
     for expected in [
-        "rz repl",
-        "USAGE:\n    rz repl [--examples-dir DIR]",
-        "FLAGS:\n    --help, -h",
+        "Start the interactive REPL",
+        "Usage: rz repl [OPTIONS]",
+        "Options:\n      --examples-dir <DIR>",
         "For bare REPL startup, run plain `rz`.",
     ] {
         assert!(
@@ -38,11 +38,6 @@ fn assert_repl_help_without_seed(args: &[&str]) {
     );
 }
 
-#[test]
-fn repl_help_word_has_no_seed_banner() {
-    assert_repl_help_without_seed(&["repl", "help"]);
-}
-
 #[test]
 fn repl_long_help_has_no_seed_banner() {
     assert_repl_help_without_seed(&["repl", "--help"]);
@@ -1,4 +1,5 @@
-use std::process::Command;
+use std::io::Write;
+use std::process::{Command, Stdio};
 
 fn bin() -> &'static str {
     env!("CARGO_BIN_EXE_rz")
@@ -40,3 +41,65 @@ fn help_includes_repl_subcommand() {
         "global help output missing repl alias entry: {stdout}"
     );
 }
+
+#[test]
+fn repl_reads_multi_line_input_until_it_closes() {
+    // A function body, a string and an array literal each span lines;
+    // every one is evaluated once, after its closing line.
+    let home = std::env::temp_dir().join(format!("res_repl_home_{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+    let mut child = Command::new(bin())
+        .arg("repl")
+        .env("HOME", &home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn rz repl");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"fn inc(int x) {\n    return x + 1;\n}\nprintln(inc(41));\nprintln(\"a\nb\");\nlet xs = [1,\n  2];\nprintln(xs);\n")
+        .unwrap();
+    let output = child.wait_with_output().expect("wait for rz repl");
+    let _ = std::fs::remove_dir_all(&home);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stdout.contains("42\na\nb\n[1, 2]\n"),
+        "stdout: {stdout}\nstderr: {stderr}"
+    );
+    assert!(!stderr.contains("Error"), "stderr: {stderr}");
+}
+
+#[test]
+fn repl_paste_mode_evaluates_the_block_at_end() {
+    // Line by line, `fn` with its brace on the next line would be
+    // evaluated (and rejected) before the body arrives.
+    let home = std::env::temp_dir().join(format!("res_repl_paste_home_{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+    let mut child = Command::new(bin())
+        .arg("repl")
+        .env("HOME", &home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn rz repl");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b":paste\nfn inc(int x) -> int\n{\n    return x + 1;\n}\nprintln(inc(41));\n:end\nprintln(inc(1));\n")
+        .unwrap();
+    let output = child.wait_with_output().expect("wait for rz repl");
+    let _ = std::fs::remove_dir_all(&home);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stdout.contains("42\n2\n"),
+        "stdout: {stdout}\nstderr: {stderr}"
+    );
+    assert!(!stderr.contains("error"), "stderr: {stderr}");
+}
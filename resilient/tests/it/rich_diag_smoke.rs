@@ -44,8 +44,8 @@ fn rich_diag_off_by_default_uses_legacy_format() {
 
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "type error must exit 1; stderr={}",
+        Some(2),
+        "type error must exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -76,8 +76,8 @@ fn rich_diag_env_var_enables_rustc_style_block() {
 
     assert_eq!(
         output.status.code(),
-        Some(1),
-        "type error must still exit 1; stderr={}",
+        Some(2),
+        "type error must still exit 2; stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     let stderr = String::from_utf8_lossy(&output.stderr);
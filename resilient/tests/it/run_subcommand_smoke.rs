@@ -0,0 +1,52 @@
+//! `rz run <file>` is the default file mode spelled out.
+
+use std::process::Command;
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+#[test]
+fn run_subcommand_runs_the_file_with_flags_and_program_args() {
+    let dir = std::env::temp_dir().join(format!("res_run_subcommand_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("main.rz");
+    std::fs::write(&file, "println(args());\n").unwrap();
+    let output = Command::new(bin())
+        .arg("run")
+        .arg("--no-typecheck")
+        .arg(&file)
+        .args(["--", "a", "b"])
+        .output()
+        .expect("spawn rz run");
+    let _ = std::fs::remove_dir_all(&dir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout={stdout}");
+    assert!(stdout.contains(r#"["a", "b"]"#), "stdout={stdout}");
+}
+
+#[test]
+fn run_subcommand_without_a_file_is_a_usage_error() {
+    let output = Command::new(bin())
+        .arg("run")
+        .output()
+        .expect("spawn rz run");
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("rz run <file>"), "stderr={stderr}");
+}
+
+#[test]
+fn run_subcommand_has_its_own_help() {
+    let output = Command::new(bin())
+        .args(["run", "--help"])
+        .output()
+        .expect("spawn rz run --help");
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Usage: rz run <file> [FLAGS] [-- ARGS...]"),
+        "stdout={stdout}"
+    );
+    assert!(!stdout.contains("COMMON FLAGS:"), "stdout={stdout}");
+}
@@ -76,7 +76,7 @@ fn check_rejects_assume_false_under_safety_critical() {
         .expect("spawn rz check --safety-critical");
     assert_eq!(
         out.status.code(),
-        Some(1),
+        Some(2),
         "safety-critical check mode should fail compilation; stdout={} stderr={}",
         String::from_utf8_lossy(&out.stdout),
         String::from_utf8_lossy(&out.stderr),
@@ -106,7 +106,7 @@ fn safety_critical_mode_ignores_allow_comment_for_l0006() {
         .expect("spawn rz check --safety-critical");
     assert_eq!(
         out.status.code(),
-        Some(1),
+        Some(2),
         "allow comment must not bypass safety-critical L0006; stdout={} stderr={}",
         String::from_utf8_lossy(&out.stdout),
         String::from_utf8_lossy(&out.stderr),
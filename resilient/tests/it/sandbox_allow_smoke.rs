@@ -0,0 +1,314 @@
+//! Integration tests for `--allow`: once a capability policy is set,
+//! every I/O builtin outside it fails with a typed `PermissionDenied`,
+//! whether the policy comes from the command line or from
+//! `[sandbox] allow` in `resilient.toml`.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn scratch_dir(tag: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join(format!(
+        "res_sandbox_allow_{}_{}_{}",
+        tag,
+        std::process::id(),
+        n
+    ));
+    std::fs::create_dir_all(&dir).expect("mkdir scratch dir");
+    dir
+}
+
+fn run(dir: &Path, body: &str, args: &[&str]) -> Output {
+    let path = dir.join("main.rz");
+    std::fs::write(&path, body).expect("write scratch file");
+    Command::new(bin())
+        .args(["--no-typecheck"])
+        .args(args)
+        .arg(&path)
+        .current_dir(dir)
+        .output()
+        .expect("spawn rz")
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+/// Reads a file, writes one, spawns a process and opens a socket,
+/// printing what each one did.
+fn probe(dir: &Path) -> String {
+    let data = dir.join("data.txt");
+    std::fs::write(&data, "hello").expect("write data file");
+    format!(
+        "try {{ println(read_file(\"{data}\")); }} catch PermissionDenied {{ println(\"read denied\"); }}\n\
+         try {{ write_file(\"{out}\", \"x\"); println(\"wrote\"); }} catch PermissionDenied {{ println(\"write denied\"); }}\n\
+         println(exec(\"true\", []));\n\
+         println(tcp_connect(\"127.0.0.1\", 9));\n",
+        data = data.display(),
+        out = dir.join("out.txt").display()
+    )
+}
+
+#[test]
+fn allow_grants_only_the_named_capabilities() {
+    let dir = scratch_dir("flag");
+    let out = run(&dir, &probe(&dir), &["--allow", "fs-read"]);
+    let stdout = stdout(&out);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(stdout.contains("hello\nwrite denied\n"), "{stdout}");
+    assert!(
+        stdout.contains("exec: PermissionDenied: true: the sandbox does not allow `exec`"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "tcp_connect: PermissionDenied: 127.0.0.1:9: the sandbox does not allow `net`"
+        ),
+        "{stdout}"
+    );
+    assert!(!dir.join("out.txt").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn manifest_policy_applies_and_the_flag_wins() {
+    let dir = scratch_dir("manifest");
+    std::fs::write(
+        dir.join("resilient.toml"),
+        "[package]\nname = \"untrusted\"\nversion = \"0.1.0\"\n\n[sandbox]\nallow = []\n",
+    )
+    .expect("write manifest");
+    let body = probe(&dir);
+
+    let out = run(&dir, &body, &[]);
+    assert!(
+        stdout(&out).starts_with("read denied\nwrite denied\n"),
+        "{}",
+        stdout(&out)
+    );
+
+    let out = run(&dir, &body, &["--allow=fs"]);
+    assert!(
+        stdout(&out).starts_with("hello\nwrote\n"),
+        "{}",
+        stdout(&out)
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn allow_none_denies_the_std_fs_and_include_builtins() {
+    let dir = scratch_dir("std_fs");
+    let secret = dir.join("secret.txt");
+    std::fs::write(&secret, "hunter2").expect("write secret");
+    let secret = secret.display().to_string();
+    let target = dir.join("pwned.txt").display().to_string();
+    let listing = dir.display().to_string();
+    let cases = [
+        (
+            "fs_read",
+            format!("fs_read(\"{secret}\")"),
+            &secret,
+            "fs-read",
+        ),
+        (
+            "fs_exists",
+            format!("fs_exists(\"{secret}\")"),
+            &secret,
+            "fs-read",
+        ),
+        (
+            "fs_list",
+            format!("fs_list(\"{listing}\")"),
+            &listing,
+            "fs-read",
+        ),
+        (
+            "fs_write",
+            format!("fs_write(\"{target}\", \"x\")"),
+            &target,
+            "fs-write",
+        ),
+        (
+            "fs_append",
+            format!("fs_append(\"{target}\", \"x\")"),
+            &target,
+            "fs-write",
+        ),
+        (
+            "fs_remove",
+            format!("fs_remove(\"{secret}\")"),
+            &secret,
+            "fs-write",
+        ),
+        (
+            "include_str",
+            format!("include_str(\"{secret}\")"),
+            &secret,
+            "fs-read",
+        ),
+        (
+            "include_bytes",
+            format!("include_bytes(\"{secret}\")"),
+            &secret,
+            "fs-read",
+        ),
+    ];
+    for (builtin, call, subject, capability) in cases {
+        let body = format!("use std::fs;\nprintln({call});\n");
+        let out = run(&dir, &body, &["--allow", "none"]);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert_eq!(out.status.code(), Some(1), "{builtin}: {stderr}");
+        assert!(
+            stderr.contains(&format!(
+                "{builtin}: PermissionDenied: {subject}: the sandbox does not allow `{capability}`"
+            )),
+            "{builtin}: {stderr}"
+        );
+        assert!(
+            !stdout(&out).contains("hunter2"),
+            "{builtin} leaked the file"
+        );
+
+        let caught = format!(
+            "use std::fs;\ntry {{ println({call}); }} catch PermissionDenied {{ println(\"denied\"); }}\n"
+        );
+        let out = run(&dir, &caught, &["--allow", "none"]);
+        assert!(
+            stdout(&out).starts_with("denied\n"),
+            "{builtin}: {}",
+            stdout(&out)
+        );
+    }
+    assert!(!dir.join("pwned.txt").exists());
+    assert!(dir.join("secret.txt").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn allow_none_denies_the_std_process_http_and_net_builtins() {
+    let dir = scratch_dir("std_io");
+    // Nothing may connect here; a denied call must not even try.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind listener");
+    listener
+        .set_nonblocking(true)
+        .expect("nonblocking listener");
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let touch = format!("touch {}", dir.join("pwned.txt").display());
+    let cases = [
+        (
+            "process",
+            "process_exec",
+            format!("process_exec(\"{touch}\")"),
+            touch.clone(),
+            "exec",
+        ),
+        (
+            "process",
+            "process_which",
+            "process_which(\"sh\")".to_string(),
+            "sh".to_string(),
+            "exec",
+        ),
+        (
+            "http",
+            "http_get",
+            format!("http_get(\"{url}\")"),
+            url.clone(),
+            "net",
+        ),
+        (
+            "http",
+            "http_post",
+            format!("http_post(\"{url}\", \"{{}}\")"),
+            url.clone(),
+            "net",
+        ),
+        (
+            "http",
+            "http_status",
+            format!("http_status(\"{url}\")"),
+            url.clone(),
+            "net",
+        ),
+        (
+            "http",
+            "http_headers",
+            format!("http_headers(\"{url}\")"),
+            url.clone(),
+            "net",
+        ),
+        (
+            "net",
+            "net_resolve",
+            "net_resolve(\"localhost\")".to_string(),
+            "localhost".to_string(),
+            "net",
+        ),
+    ];
+    for (module, builtin, call, subject, capability) in cases {
+        let body = format!("use std::{module};\nprintln({call});\n");
+        let out = run(&dir, &body, &["--allow", "none"]);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert_eq!(out.status.code(), Some(1), "{builtin}: {stderr}");
+        assert!(
+            stderr.contains(&format!(
+                "{builtin}: PermissionDenied: {subject}: the sandbox does not allow `{capability}`"
+            )),
+            "{builtin}: {stderr}"
+        );
+
+        let caught = format!(
+            "use std::{module};\ntry {{ println({call}); }} catch PermissionDenied {{ println(\"denied\"); }}\n"
+        );
+        let out = run(&dir, &caught, &["--allow", "none"]);
+        assert!(
+            stdout(&out).starts_with("denied\n"),
+            "{builtin}: {}",
+            stdout(&out)
+        );
+    }
+    assert!(!dir.join("pwned.txt").exists());
+    assert!(
+        listener.accept().is_err(),
+        "a denied std::http call connected"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn volatile_intrinsics_need_the_mmio_capability() {
+    let dir = scratch_dir("volatile");
+    let out = run(
+        &dir,
+        "unsafe {\n    let v = volatile_read_u8(8);\n    println(v);\n}\n",
+        &["--allow", "none", "--mmio", "0+16"],
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert_eq!(out.status.code(), Some(1), "{stderr}");
+    assert!(
+        stderr
+            .contains("volatile_read_u8: PermissionDenied: 0x8: the sandbox does not allow `mmio`"),
+        "{stderr}"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn unknown_capability_is_a_usage_error() {
+    let dir = scratch_dir("unknown");
+    let out = run(&dir, "println(1);\n", &["--allow", "fs-read,disk"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("unknown capability `disk`"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
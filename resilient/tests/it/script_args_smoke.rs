@@ -0,0 +1,85 @@
+//! Integration tests for program configuration: `args()` returns what
+//! follows `--` on the `rz` command line, and `env(name)` reads the
+//! process environment.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path =
+        std::env::temp_dir().join(format!("res_script_args_{}_{}.rz", std::process::id(), n));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(body: &str, before: &[&str], after: &[&str], envs: &[(&str, &str)]) -> Output {
+    let path = tmp_file(body);
+    let out = Command::new(bin())
+        .args(before)
+        .arg(&path)
+        .args(after)
+        .envs(envs.iter().copied())
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+const MONITOR: &str = "let a = args();\n\
+println(len(a));\n\
+match parse_int(a[0]) {\n\
+    Ok(threshold) => println(threshold + 1),\n\
+    Err(e) => println(e),\n\
+}\n\
+println(a[1]);\n";
+
+#[test]
+fn args_after_double_dash_reach_the_program_on_both_runtimes() {
+    for before in [&[][..], &["--vm"][..]] {
+        let out = run(MONITOR, before, &["--", "75", "--not-a-flag"], &[]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(
+            out.status.success(),
+            "{:?} stderr: {}",
+            before,
+            String::from_utf8_lossy(&out.stderr)
+        );
+        assert!(
+            stdout.contains("2\n76\n--not-a-flag"),
+            "{:?} stdout: {}",
+            before,
+            stdout
+        );
+    }
+}
+
+#[test]
+fn args_is_empty_without_double_dash() {
+    let out = run("println(len(args()));\n", &[], &[], &[]);
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).starts_with("0\n"));
+}
+
+#[test]
+fn env_reads_configuration_from_the_environment() {
+    let src = "match env(\"RES_SCRIPT_ARGS_ENDPOINT\") {\n\
+        Ok(v) => println(v),\n\
+        Err(e) => println(\"unset: \" + e),\n\
+    }\n";
+    let out = run(
+        src,
+        &[],
+        &[],
+        &[("RES_SCRIPT_ARGS_ENDPOINT", "http://host:9000")],
+    );
+    assert!(String::from_utf8_lossy(&out.stdout).contains("http://host:9000"));
+    let out = run(src, &[], &[], &[]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("unset: not set"));
+}
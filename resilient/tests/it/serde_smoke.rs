@@ -0,0 +1,40 @@
+//! `--features serde`: the AST and types survive a JSON round trip.
+
+#![cfg(feature = "serde")]
+
+use resilient::typechecker::{Type, TypeChecker};
+use resilient::{Node, interpreter, parser};
+
+const PROGRAM: &str = "\
+fn clamp(int x, int lo, int hi) -> int requires lo <= hi {
+    if x < lo { return lo; }
+    if x > hi { return hi; }
+    return x;
+}
+let names = [\"a\", \"b\"];
+for n in names { println(\"{n}: \" + to_string(clamp(-3 * 2, 0, 10))); }
+";
+
+#[test]
+fn a_program_round_trips_and_runs_the_same() {
+    let program = parser::parse_source(PROGRAM).unwrap();
+    let json = serde_json::to_string(&program).unwrap();
+    let restored: Node = serde_json::from_str(&json).unwrap();
+    assert_eq!(format!("{restored:?}"), format!("{program:?}"));
+
+    TypeChecker::new().check_program(&restored).unwrap();
+    let result = interpreter::run(&restored);
+    assert!(result.ok, "{:?}", result.errors);
+    assert_eq!(result.stdout, interpreter::run(&program).stdout);
+    assert_eq!(result.stdout, "a: 0\nb: 0\n");
+}
+
+#[test]
+fn types_round_trip() {
+    let ty = Type::Function {
+        params: vec![Type::Int, Type::Option(Box::new(Type::String))],
+        return_type: Box::new(Type::Tuple(vec![Type::Bool, Type::Struct("Point".into())])),
+    };
+    let json = serde_json::to_string(&ty).unwrap();
+    assert_eq!(serde_json::from_str::<Type>(&json).unwrap(), ty);
+}
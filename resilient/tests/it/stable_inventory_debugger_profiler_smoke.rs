@@ -19,7 +19,7 @@ fn stable_inventory_splits_debugger_from_future_profiler() {
     }
 
     for expected in [
-        "| `rz debug <file>` / `--dap` | `resilient/tests/debug_help_smoke.rs`, `resilient/src/dap_server.rs` | Covered | DAP server CLI entrypoints are documented and help-covered; breakpoints, stepping, and watch expressions remain maturing. |",
+        "| `rz debug <file>` / `--dap` | `resilient/tests/debug_help_smoke.rs`, `resilient/tests/debug_console_smoke.rs`, `resilient/src/dap_server.rs` | Covered | The console debugger (breakpoints, stepping, variables, `live` retry stops) and the DAP server entrypoints are documented and smoke-covered; DAP watch expressions remain maturing. |",
         "| Profiler path | `docs/tooling.md` documents the profiler as future; current timing data comes from `rz bench` and `--jit-cache-stats`. | Stabilize a profiler CLI and add direct smoke coverage before promoting it. |",
     ] {
         assert!(
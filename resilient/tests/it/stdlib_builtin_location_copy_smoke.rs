@@ -5,10 +5,9 @@ fn stdlib_docs_point_builtin_contributors_at_library_sources() {
     let docs = include_str!("../../../STDLIB.md");
 
     for expected in [
-        "Implementations live in `resilient/src/lib.rs` (table: `BUILTINS`);",
-        "signatures live in `resilient/src/typechecker.rs`",
-        "1. The `BUILTINS` table in `resilient/src/lib.rs`.",
-        "5. A focused Rust test in `resilient/src/lib.rs` or `resilient/tests/`.",
+        "the `BUILTINS` table in `resilient/src/builtin_registry.rs`.",
+        "1. A row in the `BUILTINS` table in `resilient/src/builtin_registry.rs`",
+        "4. A focused Rust test in `resilient/src/lib.rs` or `resilient/tests/`.",
     ] {
         assert!(
             docs.contains(expected),
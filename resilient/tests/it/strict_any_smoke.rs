@@ -0,0 +1,119 @@
+//! Integration tests for `--strict`: `Any` is rejected with a
+//! per-site diagnostic, on both `rz <file>` and `rz check`.
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(tag: &str, body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!(
+        "res_strict_any_{}_{}_{}.rz",
+        tag,
+        std::process::id(),
+        n
+    ));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+#[test]
+fn strict_rejects_any_parameter_and_exits_nonzero() {
+    let path = tmp_file(
+        "param",
+        "fn f(any x) -> int { return 1; }\nprintln(f(1));\n",
+    );
+    let output = Command::new(bin())
+        .arg("--strict")
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "stderr={stderr}");
+    assert!(
+        stderr.contains("parameter `x` of fn `f` is declared `Any`"),
+        "stderr={stderr}"
+    );
+}
+
+#[test]
+fn check_strict_explains_inferred_any() {
+    let path = tmp_file("let", "let d = identity(3);\n");
+    let output = Command::new(bin())
+        .args(["check", "--strict"])
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "stderr={stderr}");
+    assert!(
+        stderr.contains("`let d` has type `Any`, introduced by a call to `identity`"),
+        "stderr={stderr}"
+    );
+}
+
+#[test]
+fn without_strict_any_stays_permissive() {
+    let path = tmp_file("soft", "let d = identity(3);\nprintln(d);\n");
+    let output = Command::new(bin())
+        .arg("check")
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn strict_rejects_untyped_parameter() {
+    let path = tmp_file(
+        "untyped",
+        "fn f(x) -> int { return x + 1; }\nprintln(f(1));\n",
+    );
+    let output = Command::new(bin())
+        .arg("--strict")
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "stderr={stderr}");
+    assert!(
+        stderr.contains("parameter `x` of fn `f` has no type annotation"),
+        "stderr={stderr}"
+    );
+}
+
+#[test]
+fn quiet_strict_prints_no_status_lines() {
+    let path = tmp_file(
+        "quiet",
+        "fn f(int x) -> int { return x + 1; }\nprintln(f(1));\n",
+    );
+    let output = Command::new(bin())
+        .args(["-q", "--strict"])
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(0), "stdout={stdout}");
+    assert_eq!(stdout, "2\n");
+}
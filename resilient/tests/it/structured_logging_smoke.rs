@@ -0,0 +1,111 @@
+//! Integration tests for the `log_*` builtins: records go to stderr
+//! in text or JSON lines, `--log-level` and `set_log_level` filter
+//! them, and stdout stays the program's own.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("res_logging_{}_{}.rz", std::process::id(), n));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(body: &str, args: &[&str]) -> Output {
+    let path = tmp_file(body);
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+/// Log lines on stderr, without the seed banner and other diagnostics.
+fn log_lines(out: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&out.stderr)
+        .lines()
+        .filter(|l| l.starts_with('[') || l.starts_with('{'))
+        .map(str::to_string)
+        .collect()
+}
+
+const PROGRAM: &str = r#"log_debug("starting");
+log_info("request served", {"path" -> "/", "ms" -> 12});
+println("served");
+log_error("disk full", {"free" -> 0});
+"#;
+
+#[test]
+fn records_go_to_stderr_as_text_by_default() {
+    for args in [&[][..], &["--vm"][..]] {
+        let out = run(PROGRAM, args);
+        assert!(out.status.success(), "{:?}: {:?}", args, out);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout)
+                .matches("served")
+                .count(),
+            1
+        );
+        assert!(!String::from_utf8_lossy(&out.stdout).contains("[INFO]"));
+        assert_eq!(
+            log_lines(&out),
+            [
+                r#"[INFO] request served ms=12 path="/""#,
+                "[ERROR] disk full free=0",
+            ],
+            "{:?}",
+            args
+        );
+    }
+}
+
+#[test]
+fn json_format_writes_one_object_per_line() {
+    let out = run(PROGRAM, &["--log-format", "json", "--log-level=debug"]);
+    assert!(out.status.success(), "{:?}", out);
+    assert_eq!(
+        log_lines(&out),
+        [
+            r#"{"level": "debug", "msg": "starting", "fields": {}}"#,
+            r#"{"level": "info", "msg": "request served", "fields": {"ms": 12, "path": "/"}}"#,
+            r#"{"level": "error", "msg": "disk full", "fields": {"free": 0}}"#,
+        ]
+    );
+}
+
+#[test]
+fn level_filter_applies_from_the_flag_and_at_run_time() {
+    let out = run(PROGRAM, &["--log-level", "error"]);
+    assert_eq!(log_lines(&out), ["[ERROR] disk full free=0"]);
+
+    let out = run(PROGRAM, &["--log-level", "off"]);
+    assert!(log_lines(&out).is_empty(), "{:?}", out);
+
+    let src = r#"let prev = set_log_level("warn");
+log_info("dropped");
+log_warn("kept");
+set_log_level(prev);
+log_info("kept again");
+"#;
+    let out = run(src, &[]);
+    assert_eq!(log_lines(&out), ["[WARN] kept", "[INFO] kept again"]);
+}
+
+#[test]
+fn bad_log_flags_are_rejected() {
+    let out = run("println(1);\n", &["--log-level", "loud"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--log-level expects"));
+    let out = run("println(1);\n", &["--log-format", "xml"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--log-format expects text or json"));
+}
@@ -0,0 +1,133 @@
+//! Integration tests for self tail calls: `return f(args)` reuses the
+//! running frame on both runtimes, so recursive loops run far past the
+//! call-depth limit, while calls that are not in tail position — inside
+//! an expression or a `try` body — keep their own frame. Each reuse
+//! still gets fresh parameter bindings, so closures made along the way
+//! keep the values they captured.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("res_tail_calls_{}_{}.rz", std::process::id(), n));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(body: &str, args: &[&str]) -> Output {
+    let path = tmp_file(body);
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+fn stderr(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stderr).into_owned()
+}
+
+const COUNT: &str = "fn count(int n, int acc) -> int {\n\
+    if n == 0 {\n\
+        return acc;\n\
+    }\n\
+    return count(n - 1, acc + 1);\n\
+}\n\
+println(count(200000, 0));\n";
+
+#[test]
+fn tail_recursive_loop_runs_on_both_runtimes() {
+    for args in [&[][..], &["--vm"][..]] {
+        let out = run(COUNT, args);
+        assert!(out.status.success(), "{:?} stderr: {}", args, stderr(&out));
+        assert!(
+            stdout(&out).contains("200000\n"),
+            "{:?} stdout: {}",
+            args,
+            stdout(&out)
+        );
+    }
+}
+
+#[test]
+fn non_tail_recursion_still_hits_the_depth_limit() {
+    let src = "fn depth(int n) -> int {\n\
+    if n == 0 {\n\
+        return 0;\n\
+    }\n\
+    return depth(n - 1) + 1;\n\
+}\n\
+println(depth(200000));\n";
+    let out = run(src, &[]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(
+        stderr(&out).contains("maximum interpreter call depth exceeded at fn depth"),
+        "stderr: {}",
+        stderr(&out)
+    );
+}
+
+#[test]
+fn call_inside_try_keeps_its_frame_on_both_runtimes() {
+    // The failure at `n == 0` must reach the `catch` of the frame that
+    // called it (n == 1), not one whose `n` was rebound in place.
+    let src = "fn probe(int n) -> int {\n\
+    if n == 0 {\n\
+        return len(read_file(\"/nonexistent/res_tail_probe\"));\n\
+    }\n\
+    try {\n\
+        return probe(n - 1);\n\
+    } catch NotFound {\n\
+        return n;\n\
+    }\n\
+}\n\
+println(probe(3));\n";
+    for args in [&[][..], &["--vm"][..]] {
+        let out = run(src, args);
+        assert!(out.status.success(), "{:?} stderr: {}", args, stderr(&out));
+        assert!(
+            stdout(&out).starts_with("1\n") || stdout(&out).contains("\n1\n"),
+            "{:?} stdout: {}",
+            args,
+            stdout(&out)
+        );
+    }
+}
+
+#[test]
+fn closures_keep_the_parameters_of_their_iteration() {
+    let src = "fn collect(int n, fs) {\n\
+    if n == 0 {\n\
+        return fs;\n\
+    }\n\
+    let f = fn() { return n; };\n\
+    return collect(n - 1, push(fs, f));\n\
+}\n\
+let fs = collect(3, []);\n\
+println(fs[0]());\n\
+println(fs[1]());\n\
+println(fs[2]());\n";
+    for args in [&[][..], &["--vm"][..]] {
+        let out = run(src, args);
+        assert!(out.status.success(), "{:?} stderr: {}", args, stderr(&out));
+        assert!(
+            stdout(&out).contains("3\n2\n1\n"),
+            "{:?} stdout: {}",
+            args,
+            stdout(&out)
+        );
+    }
+}
@@ -29,7 +29,7 @@ fn assert_rejected(example: &str, needle: &str) {
     let (stdout, stderr, code) = run_check(example);
     assert_eq!(
         code,
-        Some(1),
+        Some(2),
         "`rz check` should reject {example}; stdout={stdout} stderr={stderr}"
     );
     assert!(
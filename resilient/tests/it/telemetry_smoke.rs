@@ -0,0 +1,253 @@
+//! Integration tests for the MQTT and serial-port builtins: messages
+//! round-trip through a local stand-in broker, a FIFO stands in for a
+//! serial line looped back on itself, failures are typed so `live`
+//! blocks retry them and `catch` names them, and a `live ... within`
+//! budget caps the call's timeout. Builds without the `telemetry`
+//! feature fail every call with `Unsupported`.
+
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn run(body: &str, args: &[&str]) -> Output {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("res_telemetry_{}_{}.rz", std::process::id(), n));
+    std::fs::write(&path, body).expect("write scratch file");
+    let out = Command::new(bin())
+        .args(["--feature", "std"])
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+#[test]
+fn net_none_denies_mqtt() {
+    let src = r#"try {
+    mqtt_publish("127.0.0.1:1", "plant/temp", "21.5");
+} catch PermissionDenied {
+    println("denied");
+}
+"#;
+    let out = run(src, &["--net", "none"]);
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("denied"),
+        "{:?}",
+        out
+    );
+}
+
+#[test]
+fn malformed_broker_is_invalid_url() {
+    let src = r#"try {
+    let m = mqtt_subscribe("http://broker/", "plant/#", 100);
+} catch InvalidUrl {
+    println("bad broker");
+}
+"#;
+    let out = run(src, &[]);
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("bad broker"),
+        "{:?}",
+        out
+    );
+}
+
+#[cfg(not(feature = "telemetry"))]
+#[test]
+fn builds_without_the_feature_report_unsupported() {
+    let src = r#"try {
+    let p = serial_open("/dev/ttyUSB0");
+} catch Unsupported {
+    println("no serial");
+}
+try {
+    mqtt_publish("127.0.0.1:1", "plant/temp", "21.5", 100);
+} catch Unsupported {
+    println("no mqtt");
+}
+"#;
+    let out = run(src, &[]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "{:?}", out);
+    assert!(stdout.contains("no serial\nno mqtt\n"), "stdout: {stdout}");
+}
+
+#[cfg(feature = "telemetry")]
+mod with_feature {
+    use super::run;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn read_packet(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+        let mut first = [0u8];
+        stream.read_exact(&mut first).unwrap();
+        let (mut len, mut shift) = (0usize, 0);
+        loop {
+            let mut byte = [0u8];
+            stream.read_exact(&mut byte).unwrap();
+            len |= ((byte[0] & 0x7F) as usize) << shift;
+            shift += 7;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+        let mut body = vec![0u8; len];
+        stream.read_exact(&mut body).unwrap();
+        (first[0], body)
+    }
+
+    /// Accept one session and answer its CONNECT with `code`.
+    fn accept(listener: &TcpListener, code: u8) -> TcpStream {
+        let (mut stream, _) = listener.accept().unwrap();
+        let (first, _) = read_packet(&mut stream);
+        assert_eq!(first, 0x10, "expected CONNECT");
+        stream.write_all(&[0x20, 2, 0, code]).unwrap();
+        stream
+    }
+
+    fn publish_packet(topic: &str, payload: &str) -> Vec<u8> {
+        let len = 2 + topic.len() + payload.len();
+        let mut out = vec![0x30, len as u8, 0, topic.len() as u8];
+        out.extend_from_slice(topic.as_bytes());
+        out.extend_from_slice(payload.as_bytes());
+        out
+    }
+
+    #[test]
+    fn publish_and_subscribe_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let broker = thread::spawn(move || {
+            let mut publisher = accept(&listener, 0);
+            let (first, body) = read_packet(&mut publisher);
+            assert_eq!(first, 0x30, "expected a QoS 0 PUBLISH");
+            assert_eq!(&body[2..12], b"plant/temp");
+            let payload = String::from_utf8(body[12..].to_vec()).unwrap();
+
+            let mut subscriber = accept(&listener, 0);
+            let (first, body) = read_packet(&mut subscriber);
+            assert_eq!(first, 0x82, "expected SUBSCRIBE");
+            assert_eq!(&body[4..11], b"plant/#");
+            subscriber.write_all(&[0x90, 3, 0, 1, 0]).unwrap();
+            subscriber
+                .write_all(&publish_packet("plant/temp", &payload))
+                .unwrap();
+            let mut rest = Vec::new();
+            let _ = subscriber.read_to_end(&mut rest);
+        });
+        let src = format!(
+            "mqtt_publish(\"mqtt://127.0.0.1:{port}\", \"plant/temp\", \"21.5\");\nlet m = mqtt_subscribe(\"127.0.0.1:{port}\", \"plant/#\", 2000);\nprintln(m.topic + \" \" + m.payload);\n"
+        );
+        let out = run(&src, &[]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(out.status.success(), "{:?}", out);
+        assert!(stdout.contains("plant/temp 21.5\n"), "stdout: {stdout}");
+        broker.join().unwrap();
+    }
+
+    #[test]
+    fn live_block_retries_a_refused_session() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let broker = thread::spawn(move || {
+            // 3: server unavailable.
+            drop(accept(&listener, 3));
+            let mut stream = accept(&listener, 0);
+            let (first, _) = read_packet(&mut stream);
+            assert_eq!(first, 0x30);
+        });
+        let src = format!(
+            "live {{\n    mqtt_publish(\"127.0.0.1:{port}\", \"plant/temp\", \"21.5\");\n    println(\"sent\");\n}}\n"
+        );
+        let out = run(&src, &[]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(out.status.success(), "stderr: {stderr}");
+        assert!(stdout.contains("sent\n"), "stdout: {stdout}");
+        assert!(
+            stderr.contains("mqtt_publish: ConnectionFailed") && stderr.contains("return code 3"),
+            "stderr: {stderr}"
+        );
+        broker.join().unwrap();
+    }
+
+    #[test]
+    fn live_within_budget_caps_a_subscription() {
+        // Grants the subscription and never publishes: only a timeout
+        // ends the call, and the default one is 30 s.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let broker = thread::spawn(move || {
+            let mut stream = accept(&listener, 0);
+            read_packet(&mut stream);
+            stream.write_all(&[0x90, 3, 0, 1, 0]).unwrap();
+            thread::sleep(Duration::from_secs(3));
+        });
+        let src = format!(
+            "fn main(int _d) {{\n    live within 200ms {{\n        let m = mqtt_subscribe(\"127.0.0.1:{port}\", \"plant/#\");\n    }}\n}}\nmain(0);\n"
+        );
+        let start = Instant::now();
+        let out = run(&src, &[]);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(!out.status.success());
+        assert!(
+            stderr.contains("Live block timed out") && stderr.contains("mqtt_subscribe: Timeout"),
+            "stderr: {stderr}"
+        );
+        assert!(
+            start.elapsed() < Duration::from_secs(2),
+            "the block's budget should end the call, took {:?}",
+            start.elapsed()
+        );
+        broker.join().unwrap();
+    }
+
+    /// A FIFO opened read-write reads back what is written to it, like
+    /// a serial line with a loopback plug.
+    #[cfg(unix)]
+    #[test]
+    fn serial_loopback_reads_writes_and_times_out() {
+        let path = std::env::temp_dir().join(format!("res_serial_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let made = std::process::Command::new("mkfifo")
+            .arg(&path)
+            .status()
+            .expect("run mkfifo");
+        assert!(made.success());
+        let src = format!(
+            r#"let p = serial_open("{path}");
+println(serial_write(p, "T=21.5\n"));
+print(serial_read(p, 64, 1000));
+try {{
+    serial_read(p, 64, 100);
+}} catch Timeout {{
+    println("quiet line");
+}}
+println(serial_close(p));
+try {{
+    serial_open("{path}.missing");
+}} catch NotFound {{
+    println("no device");
+}}
+"#,
+            path = path.display()
+        );
+        let out = run(&src, &[]);
+        let _ = std::fs::remove_file(&path);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(out.status.success(), "{:?}", out);
+        assert!(
+            stdout.contains("7\nT=21.5\nquiet line\ntrue\nno device\n"),
+            "stdout: {stdout}"
+        );
+    }
+}
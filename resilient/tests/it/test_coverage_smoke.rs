@@ -0,0 +1,55 @@
+//! `rz test --coverage` writes lcov and HTML reports over the test
+//! files and the files they `use`.
+
+use std::fs;
+use std::process::Command;
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+const LIB: &str = "fn sign(int x) -> int {\n    if x < 0 {\n        return -1;\n    }\n    assert(x >= 0, \"non-negative\");\n    return 1;\n}\n\nfn unused() -> int {\n    return 0;\n}\n";
+
+const TESTS: &str =
+    "use \"lib.rz\";\n\nfn test_sign() {\n    assert(sign(3) == 1, \"positive\");\n}\n";
+
+#[test]
+fn coverage_reports_lines_branches_and_asserts_of_used_files() {
+    let dir = std::env::temp_dir().join(format!("res_coverage_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("src/lib.rz"), LIB).unwrap();
+    fs::write(dir.join("src/lib_test.rz"), TESTS).unwrap();
+    let report = dir.join("cov");
+
+    let out = Command::new(bin())
+        .arg("test")
+        .arg(dir.join("src"))
+        .arg(format!("--coverage={}", report.display()))
+        .output()
+        .expect("spawn rz test");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(out.status.code(), Some(0), "{stdout}");
+    assert!(
+        stdout.contains("coverage: lines 4/6 (66.7%), branches 1/2 (50.0%), asserts 2/2 (100.0%)"),
+        "{stdout}"
+    );
+
+    let lcov = fs::read_to_string(report.join("lcov.info")).unwrap();
+    let records: Vec<&str> = lcov.split("end_of_record\n").collect();
+    let lib = records
+        .iter()
+        .find(|r| r.contains("lib.rz\n"))
+        .expect("a record for lib.rz");
+    assert!(lib.contains("BRDA:2,0,0,0\nBRDA:2,0,1,1\n"), "{lcov}");
+    assert!(
+        lib.contains("DA:3,0\n") && lib.contains("DA:10,0\n"),
+        "{lcov}"
+    );
+    assert!(lib.contains("LF:5\nLH:3\n"), "{lcov}");
+    assert!(lcov.contains("lib_test.rz\n"), "{lcov}");
+
+    let html = fs::read_to_string(report.join("index.html")).unwrap();
+    assert!(html.contains("condition never true"), "{html}");
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,79 @@
+//! `test fn` declarations: discovered by `rz test`, each run in its own
+//! interpreter, and skipped by a normal run.
+
+use std::process::{Command, Output};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+const PROGRAM: &str = "fn divide(int a, int b) -> int {\n    return a / b;\n}\n\nlet counter = 0;\n\ntest fn divides_safely() {\n    counter = counter + 1;\n    assert_eq(divide(10, 2), 5);\n    assert_eq(counter, 1);\n}\n\ntest fn globals_start_fresh() {\n    counter = counter + 1;\n    assert_eq(counter, 1);\n}\n\ntest fn divides_wrongly() {\n    assert_eq(divide(7, 2), 4);\n}\n\nfn test_legacy() {\n    assert_true(divide(4, 2) == 2);\n}\n\nprintln(divide(9, 3));\n";
+
+fn run(name: &str, args: &[&str]) -> Output {
+    let path = std::env::temp_dir().join(format!("res_test_fn_{}_{}.rz", std::process::id(), name));
+    std::fs::write(&path, PROGRAM).unwrap();
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+/// The result lines, without their timings.
+fn results(out: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter(|l| l.starts_with("test "))
+        .map(|l| l.rsplit_once(" (").expect("a timing").0.to_string())
+        .collect()
+}
+
+#[test]
+fn test_fns_run_isolated_and_failures_exit_nonzero() {
+    let out = run("all", &["test"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(
+        results(&out),
+        [
+            "test divides_safely ... ok",
+            "test globals_start_fresh ... ok",
+            "test divides_wrongly ... FAIL",
+            "test test_legacy ... ok",
+        ]
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("4 tests: 3 passed, 1 failed ("), "{stdout}");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains(": divides_wrongly: "), "{stderr}");
+}
+
+#[test]
+fn filter_selects_test_fns_by_name() {
+    let out = run("filter", &["test", "--filter", "divides_s"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(results(&out), ["test divides_safely ... ok"]);
+}
+
+#[test]
+fn a_normal_run_skips_test_fns() {
+    let out = run("skip", &["--no-typecheck"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        "3\nProgram executed successfully\n"
+    );
+}
+
+#[test]
+fn test_fns_take_no_parameters() {
+    let path = std::env::temp_dir().join(format!("res_test_fn_{}_params.rz", std::process::id()));
+    std::fs::write(&path, "test fn takes(int x) {\n    assert_eq(x, x);\n}\n").unwrap();
+    let out = Command::new(bin()).arg("test").arg(&path).output().unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_ne!(out.status.code(), Some(0));
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("test fn `takes` must not take parameters")
+    );
+}
@@ -21,10 +21,11 @@ fn assert_focused_test_help(args: &[&str]) {
     );
     let stdout = String::from_utf8_lossy(&output.stdout);
     for expected in [
-        "Usage: rz test [<file|dir>] [--filter <substring>]",
-        "Discover and run fn test_*() functions in .rz files.",
-        "(no argument)       Discover from the current directory",
-        "--filter <substr>   Only run tests whose name contains <substr>",
+        "Usage: rz test [OPTIONS] [FILE|DIR]",
+        "Discover and run `test fn` declarations and `fn test_*()` functions",
+        "[default: the current directory]",
+        "--filter <SUBSTR>   Only run tests whose name contains SUBSTR",
+        "--coverage[=<DIR>]  Write line, branch and assert coverage",
     ] {
         assert!(
             stdout.contains(expected),
@@ -46,8 +47,3 @@ fn test_long_help_is_focused() {
 fn test_short_help_is_focused() {
     assert_focused_test_help(&["test", "-h"]);
 }
-
-#[test]
-fn test_help_word_is_focused() {
-    assert_focused_test_help(&["test", "help"]);
-}
@@ -0,0 +1,81 @@
+//! `rz test --snapshot` checks program stdout against `.snap` files;
+//! `--update` writes them.
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn snapshot(dir: &Path, args: &[&str]) -> Output {
+    Command::new(bin())
+        .args(["test", "--snapshot"])
+        .args(args)
+        .arg(dir)
+        .output()
+        .expect("spawn rz test --snapshot")
+}
+
+#[test]
+fn snapshots_are_written_checked_and_diffed() {
+    let dir = std::env::temp_dir().join(format!("res_snapshot_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("greet.rz"),
+        "println(\"Hello, world\");\nprintln(1 + 1);\n",
+    )
+    .unwrap();
+    fs::write(dir.join("ask.rz"), "println(input());\n").unwrap();
+    fs::write(dir.join("ask.interactive"), "").unwrap();
+
+    let out = snapshot(&dir, &[]);
+    assert_eq!(out.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("greet.rz ... missing"), "{stdout}");
+    assert!(
+        stdout.contains("1 snapshot: 0 passed, 1 failed"),
+        "{stdout}"
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("rerun with --update"));
+
+    let out = snapshot(&dir, &["--update"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("greet.rz ... written"));
+    assert_eq!(
+        fs::read_to_string(dir.join("greet.snap")).unwrap(),
+        "Hello, world\n2\nProgram executed successfully\n"
+    );
+    assert!(!dir.join("ask.snap").exists());
+
+    let out = snapshot(&dir, &[]);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("1 snapshot: 1 passed, 0 failed"));
+
+    fs::write(
+        dir.join("greet.rz"),
+        "println(\"Hello, World\");\nprintln(1 + 1);\n",
+    )
+    .unwrap();
+    let out = snapshot(&dir, &[]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("greet.rz ... changed"));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("    @@ line 1\n    -Hello, world\n    +Hello, World\n"),
+        "{stderr}"
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn update_needs_snapshot() {
+    let out = Command::new(bin())
+        .args(["test", "--update"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--update only applies to --snapshot"));
+}
@@ -0,0 +1,84 @@
+//! `--trace` logs function calls and returns, indented by depth.
+
+use std::process::{Command, Output};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn run(args: &[&str], src: &str) -> Output {
+    let path = std::env::temp_dir().join(format!(
+        "res_trace_{}_{}.rz",
+        std::process::id(),
+        args.join("_").replace(['-', '=', ','], "")
+    ));
+    std::fs::write(&path, src).unwrap();
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+fn trace_lines(out: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&out.stderr)
+        .lines()
+        .filter(|l| l.starts_with("[trace]"))
+        .map(String::from)
+        .collect()
+}
+
+const FIB: &str = "fn fib(int n) -> int {\n    if n < 2 { return n; }\n    return fib(n - 1) + fib(n - 2);\n}\nfn twice(int x) -> int {\n    return fib(x) * 2;\n}\nprintln(twice(2));\n";
+
+#[test]
+fn calls_and_returns_form_an_indented_tree() {
+    let out = run(&["--trace", "--no-typecheck"], FIB);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        trace_lines(&out),
+        [
+            "[trace] → twice(x = 2)",
+            "[trace]   → fib(n = 2)",
+            "[trace]     → fib(n = 1)",
+            "[trace]     ← fib = 1",
+            "[trace]     → fib(n = 0)",
+            "[trace]     ← fib = 0",
+            "[trace]   ← fib = 1",
+            "[trace] ← twice = 2",
+        ]
+    );
+}
+
+#[test]
+fn a_filter_traces_only_the_named_functions() {
+    let out = run(&["--trace=twice", "--no-typecheck"], FIB);
+    assert_eq!(
+        trace_lines(&out),
+        ["[trace] → twice(x = 2)", "[trace] ← twice = 2"]
+    );
+}
+
+#[test]
+fn failed_attempts_of_a_live_block_are_traced() {
+    let src = "fn risky(int attempt) -> int {\n    return 10 / (attempt - 1);\n}\nlive {\n    println(risky(live_retries() + 1));\n}\n";
+    let out = run(&["--trace", "--no-typecheck"], src);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        trace_lines(&out),
+        [
+            "[trace] → risky(attempt = 1)",
+            "[trace] ✗ risky: Division by zero",
+            "[trace] → risky(attempt = 2)",
+            "[trace] ← risky = 10",
+        ]
+    );
+}
+
+#[test]
+fn trace_is_rejected_with_the_vm() {
+    let out = run(&["--trace", "--vm"], FIB);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--trace follows the tree-walking"));
+}
@@ -40,7 +40,7 @@ fn check_src(src: &str) -> (String, Option<i32>) {
 fn binop_string_plus_array() {
     let src = "fn main(int _d) { return \"hi\" + [1]; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1), "expected typecheck failure; got:\n{out}");
+    assert_eq!(code, Some(2), "expected typecheck failure; got:\n{out}");
     assert!(
         out.contains("cannot concatenate string"),
         "expected concatenate diagnostic; got:\n{out}"
@@ -51,7 +51,7 @@ fn binop_string_plus_array() {
 fn binop_int_plus_bool() {
     let src = "fn main(int _d) { return 1 + true; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Cannot apply") || out.contains("type"),
         "expected type error; got:\n{out}"
@@ -62,7 +62,7 @@ fn binop_int_plus_bool() {
 fn binop_float_divide_bool() {
     let src = "fn main(int _d) { return 3.14 / true; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Cannot apply") || out.contains("type"),
         "expected type mismatch; got:\n{out}"
@@ -73,7 +73,7 @@ fn binop_float_divide_bool() {
 fn binop_bool_minus_int() {
     let src = "fn main(int _d) { return true - 5; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Cannot") || out.contains("type"),
         "expected error; got:\n{out}"
@@ -84,7 +84,7 @@ fn binop_bool_minus_int() {
 fn binop_array_divide_int() {
     let src = "fn main(int _d) { let xs = [1, 2]; return xs / 2; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Cannot apply") || out.contains("type"),
         "expected type error; got:\n{out}"
@@ -95,7 +95,7 @@ fn binop_array_divide_int() {
 fn bitwise_and_bool_int() {
     let src = "fn main(int _d) { return true & 5; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Bitwise") || out.contains("requires int"),
         "expected bitwise error; got:\n{out}"
@@ -106,7 +106,7 @@ fn bitwise_and_bool_int() {
 fn logical_or_int_bool() {
     let src = "fn main(int _d) { return 42 || true; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Logical") || out.contains("requires bool"),
         "expected logical error; got:\n{out}"
@@ -117,7 +117,7 @@ fn logical_or_int_bool() {
 fn compare_array_int() {
     let src = "fn main(int _d) { return [1, 2] < 5; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Cannot compare"),
         "expected comparison error; got:\n{out}"
@@ -128,7 +128,7 @@ fn compare_array_int() {
 fn coalesce_non_option() {
     let src = "fn main(int _d) { return 5 ?? 10; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("requires an Option"),
         "expected option error; got:\n{out}"
@@ -141,7 +141,7 @@ fn coalesce_non_option() {
 fn unary_not_on_int() {
     let src = "fn main(int _d) { return !5; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Cannot apply '!'"),
         "expected unary ! error; got:\n{out}"
@@ -152,7 +152,7 @@ fn unary_not_on_int() {
 fn unary_minus_on_bool() {
     let src = "fn main(int _d) { return -true; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Cannot apply '-'"),
         "expected unary - error; got:\n{out}"
@@ -163,7 +163,7 @@ fn unary_minus_on_bool() {
 fn unary_minus_on_string() {
     let src = "fn main(int _d) { return -\"hi\"; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Cannot apply '-'"),
         "expected unary - error; got:\n{out}"
@@ -176,7 +176,7 @@ fn unary_minus_on_string() {
 fn if_condition_int() {
     let src = "fn main(int _d) { if 42 { return 1; } return 0; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("condition") || out.contains("Bool"),
         "expected condition error; got:\n{out}"
@@ -187,7 +187,7 @@ fn if_condition_int() {
 fn while_condition_string() {
     let src = "fn main(int _d) { while \"loop\" { return 0; } return 0; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("condition") || out.contains("Bool"),
         "expected condition error; got:\n{out}"
@@ -200,7 +200,7 @@ fn while_condition_string() {
 fn range_lower_bound_string() {
     let src = "fn main(int _d) { for i in \"lo\"..10 { return i; } return 0; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("range lower bound"),
         "expected range error; got:\n{out}"
@@ -211,7 +211,7 @@ fn range_lower_bound_string() {
 fn range_upper_bound_bool() {
     let src = "fn main(int _d) { for i in 0..true { return i; } return 0; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("range upper bound"),
         "expected range error; got:\n{out}"
@@ -224,7 +224,7 @@ fn range_upper_bound_bool() {
 fn assert_condition_int() {
     let src = "fn main(int _d) { assert(42); return 0; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Assert condition"),
         "expected assert error; got:\n{out}"
@@ -235,7 +235,7 @@ fn assert_condition_int() {
 fn assert_message_int() {
     let src = "fn main(int _d) { assert(true, 99); return 0; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Assert message") || out.contains("string"),
         "expected message error; got:\n{out}"
@@ -246,7 +246,7 @@ fn assert_message_int() {
 fn assume_condition_array() {
     let src = "fn main(int _d) { assume([1]); return 0; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Assume condition"),
         "expected assume error; got:\n{out}"
@@ -258,7 +258,7 @@ fn return_type_mismatch() {
     let src =
         "fn get_int() -> int { return true; } fn main(int _d) { return get_int(); } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("type") || out.contains("expected") || out.contains("return"),
         "expected return type error; got:\n{out}"
@@ -271,7 +271,7 @@ fn return_type_mismatch() {
 fn call_undefined_function() {
     let src = "fn main(int _d) { return frob(1); } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("Undefined") || out.contains("not found") || out.contains("variable"),
         "expected undefined error; got:\n{out}"
@@ -283,7 +283,7 @@ fn call_wrong_arg_count() {
     let src =
         "fn add(int a, int b) { return a + b; } fn main(int _d) { return add(1); } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("arg") || out.contains("parameter"),
         "expected arity error; got:\n{out}"
@@ -294,7 +294,7 @@ fn call_wrong_arg_count() {
 fn call_wrong_arg_type() {
     let src = "fn takes_bool(bool b) { if b { return 1; } return 0; } fn main(int _d) { return takes_bool(42); } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("type") || out.contains("expected"),
         "expected arg type error; got:\n{out}"
@@ -307,7 +307,7 @@ fn call_wrong_arg_type() {
 fn enum_constructor_arity_mismatch() {
     let src = "enum E { Some(int), None } fn main(int _d) { return E::Some(1, 2); } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("arg") || out.contains("expected"),
         "expected arg count error; got:\n{out}"
@@ -319,7 +319,7 @@ fn field_access_nonexistent_field() {
     let src =
         "struct P { int x } fn main(int _d) { let p = new P { x: 1 }; return p.y; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("has no field"),
         "expected field error; got:\n{out}"
@@ -330,7 +330,7 @@ fn field_access_nonexistent_field() {
 fn array_subscript_out_of_bounds_type() {
     let src = "fn main(int _d) { let xs = [1, 2]; return xs[true]; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("bool") || out.contains("int"),
         "expected type error; got:\n{out}"
@@ -343,7 +343,7 @@ fn array_subscript_out_of_bounds_type() {
 fn let_annotated_int_assigned_string() {
     let src = "fn main(int _d) { let x: int = \"hi\"; return x; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("type") || out.contains("expected"),
         "expected type mismatch; got:\n{out}"
@@ -354,7 +354,7 @@ fn let_annotated_int_assigned_string() {
 fn let_annotated_bool_assigned_int() {
     let src = "fn main(int _d) { let b: bool = 42; return if b { 1 } else { 0 }; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("type") || out.contains("expected"),
         "expected type mismatch; got:\n{out}"
@@ -367,7 +367,7 @@ fn let_annotated_bool_assigned_int() {
 fn match_arm_type_mismatch() {
     let src = "fn main(int _d) { return match 5 { 1 => true, _ => 42 }; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("type") || out.contains("bool") || out.contains("int"),
         "expected type mismatch; got:\n{out}"
@@ -380,7 +380,7 @@ fn match_arm_type_mismatch() {
 fn array_mixed_types() {
     let src = "fn main(int _d) { return [1, \"hi\", 3]; } main(0);\n";
     let (out, code) = check_src(src);
-    assert_eq!(code, Some(1));
+    assert_eq!(code, Some(2));
     assert!(
         out.contains("element") || out.contains("type") || out.contains("array"),
         "expected array type error; got:\n{out}"
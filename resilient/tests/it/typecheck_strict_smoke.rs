@@ -71,7 +71,7 @@ fn typecheck_strict_turns_type_errors_fatal() {
 
     assert_eq!(
         output.status.code(),
-        Some(1),
+        Some(2),
         "strict typecheck should fail fast on type errors; stdout={} stderr={}",
         String::from_utf8_lossy(&output.stdout),
         String::from_utf8_lossy(&output.stderr)
@@ -0,0 +1,75 @@
+//! Integration tests for union types: `int | string` annotations run
+//! end to end, and narrowing via `is_int` etc. matches the runtime.
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(tag: &str, body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!(
+        "res_union_types_{}_{}_{}.rz",
+        tag,
+        std::process::id(),
+        n
+    ));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+const SENSOR: &str = "fn read_sensor(int id) -> int | string {\n\
+    if id < 0 { return \"offline\"; }\n\
+    return id * 10;\n\
+}\n\
+fn show(int | string r) -> string {\n\
+    if is_int(r) { return \"reading \" + to_string(r + 1); } else { return \"error: \" + r; }\n\
+}\n\
+println(show(read_sensor(4)));\n\
+println(show(read_sensor(-1)));\n";
+
+#[test]
+fn narrowed_union_program_runs_on_both_backends() {
+    for backend in [None, Some("--vm")] {
+        let path = tmp_file("run", SENSOR);
+        let mut cmd = Command::new(bin());
+        if let Some(flag) = backend {
+            cmd.arg(flag);
+        }
+        let output = cmd.arg(&path).output().expect("spawn rz");
+        let _ = std::fs::remove_file(&path);
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{backend:?}: stderr={stderr}");
+        assert!(
+            stdout.contains("reading 41") && stdout.contains("error: offline"),
+            "{backend:?}: stdout={stdout}"
+        );
+    }
+}
+
+#[test]
+fn check_rejects_union_used_without_narrowing() {
+    let path = tmp_file(
+        "unnarrowed",
+        "fn f(int | string r) -> int { return r + 1; }\n",
+    );
+    let output = Command::new(bin())
+        .arg("check")
+        .arg(&path)
+        .output()
+        .expect("spawn rz check");
+    let _ = std::fs::remove_file(&path);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "stderr={stderr}");
+    assert!(
+        stderr.contains("Cannot apply '+' to int | string and int"),
+        "stderr={stderr}"
+    );
+}
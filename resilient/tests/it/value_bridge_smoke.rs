@@ -0,0 +1,124 @@
+//! `resilient::bridge`: Rust values in and out of a loaded program.
+
+use std::collections::HashMap;
+
+use resilient::bridge::{BridgeError, FromResilient, IntoResilient, ResilientValue};
+use resilient::interpreter::Session;
+
+#[derive(Debug, Clone, PartialEq, IntoResilient, FromResilient)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+#[derive(Debug, PartialEq, IntoResilient, FromResilient)]
+#[resilient(name = "Box")]
+struct Rect {
+    origin: Point,
+    tags: Vec<String>,
+}
+
+const PROGRAM: &str = "\
+struct Point { float x, float y }
+struct Box { Point origin, Array<string> tags }
+fn process(int n, float scale) -> float {
+    return to_float(n) * scale;
+}
+fn shift(Point p, float dx) -> Point {
+    return new Point { x: p.x + dx, y: p.y };
+}
+fn label(Box b) -> string {
+    return b.tags[0];
+}
+fn broken() -> Point {
+    return new Point { x: 1.0, y: \"up\" };
+}
+fn lookup(int k) -> Option<int> {
+    if k > 0 { return Some(k); }
+    return None;
+}
+fn pair(int a) -> (int, string) {
+    return (a, \"n\");
+}
+let answer = 42;
+";
+
+fn session() -> Session {
+    Session::load(PROGRAM).unwrap()
+}
+
+#[test]
+fn scalars_in_and_out() {
+    let mut s = session();
+    let total: f64 = s.call("process", (3, 7.5)).unwrap();
+    assert_eq!(total, 22.5);
+    assert_eq!(s.call::<Option<i64>>("lookup", (4,)).unwrap(), Some(4));
+    assert_eq!(s.call::<Option<i64>>("lookup", (0,)).unwrap(), None);
+    let pair: (i64, String) = s.call("pair", (9,)).unwrap();
+    assert_eq!(pair, (9, "n".to_string()));
+}
+
+#[test]
+fn derived_structs_round_trip() {
+    let mut s = session();
+    let p: Point = s.call("shift", (Point { x: 1.0, y: 2.0 }, 0.5)).unwrap();
+    assert_eq!(p, Point { x: 1.5, y: 2.0 });
+
+    let rect = Rect {
+        origin: p,
+        tags: vec!["door".to_string()],
+    };
+    let label: String = s.call("label", (rect,)).unwrap();
+    assert_eq!(label, "door");
+}
+
+#[test]
+fn mismatches_name_the_types_and_the_path() {
+    let mut s = session();
+    let err = s.call::<i64>("process", (3, 7.5)).unwrap_err();
+    assert_eq!(err.to_string(), "expected int, found float");
+
+    let err = s.call::<Point>("broken", ()).unwrap_err();
+    assert_eq!(err.to_string(), "expected float, found string at y");
+
+    let nested = Rect {
+        origin: Point { x: 0.0, y: 0.0 },
+        tags: vec![],
+    }
+    .into_resilient();
+    let err = Point::from_resilient(nested).unwrap_err();
+    assert_eq!(err.to_string(), "expected struct Point, found struct Box");
+
+    let map: HashMap<String, ResilientValue> =
+        HashMap::from([("origin".to_string(), vec![1_i64, 2].into_resilient())]);
+    let err = HashMap::<String, Vec<String>>::from_resilient(map.into_resilient()).unwrap_err();
+    assert_eq!(err.to_string(), "expected string, found int at origin[0]");
+
+    let err = u8::from_resilient(300_i64.into_resilient()).unwrap_err();
+    assert_eq!(err.to_string(), "expected int in u8 range, found 300");
+}
+
+#[test]
+fn call_errors() {
+    let mut s = session();
+    assert_eq!(
+        s.call::<()>("missing", ()).unwrap_err(),
+        BridgeError::UnknownFunction("missing".to_string())
+    );
+    assert_eq!(
+        s.call::<f64>("process", (3,)).unwrap_err(),
+        BridgeError::Arity {
+            name: "process".to_string(),
+            expected: 2,
+            found: 1,
+        }
+    );
+    assert!(matches!(
+        s.call::<()>("answer", ()).unwrap_err(),
+        BridgeError::NotAFunction { found, .. } if found == "int"
+    ));
+    assert!(matches!(
+        Session::load("fn (").err(),
+        Some(BridgeError::Load(errors)) if !errors.is_empty()
+    ));
+}
@@ -0,0 +1,78 @@
+//! `rz verify <file>` type-checks and verifies without running, and a
+//! misspelt subcommand is a usage error rather than a run.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(tag: &str, body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!(
+        "res_verify_subcommand_{}_{}_{}.rz",
+        tag,
+        std::process::id(),
+        n
+    ));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn rz(args: &[&str], body: &str) -> Output {
+    let path = tmp_file("prog", body);
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+const REFUTED: &str = "fn f(int x) -> int ensures false { return x; }\n\
+println(\"ran\");\n\
+println(f(1));\n";
+
+#[test]
+fn verify_exits_three_on_a_failing_ensures_without_running() {
+    let out = rz(&["verify"], REFUTED);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert_eq!(out.status.code(), Some(3), "stderr={stderr}");
+    assert!(
+        stderr.contains("fn f: contract can never hold"),
+        "stderr={stderr}"
+    );
+    assert!(!stdout.contains("ran"), "stdout={stdout}");
+}
+
+#[test]
+fn verify_passes_a_clean_file() {
+    let out = rz(
+        &["verify", "--quiet"],
+        "fn f(int x) -> int requires x > 0 { return x; }\nprintln(f(1));\n",
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn a_misspelt_subcommand_does_not_run_the_file() {
+    let out = rz(&["verfiy"], REFUTED);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert_eq!(out.status.code(), Some(2), "stderr={stderr}");
+    assert!(
+        stderr.contains("unknown command or file `verfiy`"),
+        "stderr={stderr}"
+    );
+    assert!(!stdout.contains("ran"), "stdout={stdout}");
+}
@@ -0,0 +1,109 @@
+//! Integration tests for warning categories: `--warn` / `--no-warn`
+//! pick which advisory diagnostics `rz check` and `rz` report, and
+//! `--deny-warnings` turns the enabled ones into a failing check.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bin() -> &'static str {
+    env!("CARGO_BIN_EXE_rz")
+}
+
+fn tmp_file(tag: &str, body: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!(
+        "res_warnings_{}_{}_{}.rz",
+        tag,
+        std::process::id(),
+        n
+    ));
+    std::fs::write(&path, body).expect("write scratch file");
+    path
+}
+
+fn run(tag: &str, body: &str, args: &[&str]) -> Output {
+    let path = tmp_file(tag, body);
+    let out = Command::new(bin())
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("spawn rz");
+    let _ = std::fs::remove_file(&path);
+    out
+}
+
+const SHADOWED: &str = "fn f(int n) -> int {\n\
+    let n = n + 1;\n\
+    return n;\n\
+}\n\
+println(f(1));\n";
+
+#[test]
+fn shadowing_reports_only_when_enabled() {
+    let out = run("off", SHADOWED, &["check"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(out.status.success(), "stderr: {stderr}");
+    assert!(!stderr.contains("warning[shadowing]"), "stderr: {stderr}");
+
+    let out = run("on", SHADOWED, &["check", "--warn", "shadowing"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(out.status.success(), "stderr: {stderr}");
+    assert!(
+        stderr.contains(":2:1: warning[shadowing]: `let n` shadows"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn deny_warnings_fails_check_and_run() {
+    let out = run(
+        "deny_check",
+        SHADOWED,
+        &["check", "--warn=all", "--deny-warnings"],
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert_eq!(out.status.code(), Some(2), "stderr: {stderr}");
+    assert!(stderr.contains("error[shadowing]"), "stderr: {stderr}");
+    assert!(
+        stderr.contains("denied by --deny-warnings"),
+        "stderr: {stderr}"
+    );
+
+    let out = run(
+        "deny_run",
+        SHADOWED,
+        &["--warn", "shadowing", "--deny-warnings"],
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(!out.status.success());
+    assert!(!stdout.contains('2'), "the program must not run: {stdout}");
+}
+
+#[test]
+fn no_warn_silences_a_default_category() {
+    let src = "fn f() -> int {\n    let spare = 1;\n    return 2;\n}\nprintln(f());\n";
+    let out = run("unused", src, &["check"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("warning[unused]"), "stderr: {stderr}");
+    let out = run(
+        "no_unused",
+        src,
+        &["check", "--no-warn", "unused", "--deny-warnings"],
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(out.status.success(), "stderr: {stderr}");
+    assert!(!stderr.contains("[unused]"), "stderr: {stderr}");
+}
+
+#[test]
+fn unknown_category_is_a_usage_error() {
+    let out = run("bogus", "println(1);\n", &["check", "--warn", "bogus"]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("unknown warning category `bogus`"),
+        "stderr: {stderr}"
+    );
+}